@@ -0,0 +1,46 @@
+use std::{env, io};
+
+use crate::{
+    cli::{parse_command, Command, HELP},
+    commands::{run_backlinks, run_doctor, run_export, run_index, run_new, run_search},
+    workspace::Workspace,
+};
+
+pub fn run() -> Result<(), String> {
+    let invocation = parse_command(env::args().skip(1).collect())?;
+    mdit_vault_indexing::set_vault_lock_owner("mdit CLI");
+    let json = invocation.options.json;
+    let mut stdout = io::stdout();
+
+    match invocation.command {
+        Command::Help => {
+            print!("{HELP}");
+            #[cfg(feature = "bench")]
+            print!("\n{}", crate::bench::BENCH_HELP);
+            Ok(())
+        }
+        Command::Version => {
+            println!("{}", env!("CARGO_PKG_VERSION"));
+            Ok(())
+        }
+        // Benchmarks run on a vault and database of their own.
+        #[cfg(feature = "bench")]
+        Command::Bench(command) => crate::bench::run_bench(&command, json, &mut stdout),
+        command => {
+            let workspace = Workspace::resolve(&invocation.options)?;
+            match command {
+                Command::Index(command) => run_index(&workspace, &command, json, &mut stdout),
+                Command::Search(command) => run_search(&workspace, &command, json, &mut stdout),
+                Command::New(command) => run_new(&workspace, &command, json, &mut stdout),
+                Command::Backlinks(command) => {
+                    run_backlinks(&workspace, &command, json, &mut stdout)
+                }
+                Command::Export(command) => run_export(&workspace, &command, &mut stdout),
+                Command::Doctor => run_doctor(&workspace, json, &mut stdout),
+                #[cfg(feature = "bench")]
+                Command::Bench(_) => unreachable!("handled above"),
+                Command::Help | Command::Version => unreachable!("handled above"),
+            }
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! Synthetic vaults and timed indexing and search runs, to compare releases
+//! on the same numbers. Built only with the `bench` feature.
+
+use std::{
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use mdit_vault_indexing::{
+    index_vault_documents_with_profiles, search_notes_for_query_with_profiles,
+};
+use serde::Serialize;
+
+use crate::commands::{write_json, write_line};
+
+pub const BENCH_HELP: &str = "\
+Benchmark commands (built with the `bench` feature):
+  bench generate <dir> [--notes <n>] [--words <n>] [--seed <n>]
+                                   Write a synthetic vault to <dir>.
+  bench run [--notes <n>] [--words <n>] [--queries <n>] [--seed <n>]
+                                   Index and search a fresh synthetic vault and
+                                   report the time each phase took.
+";
+
+const DEFAULT_NOTES: usize = 1000;
+const DEFAULT_WORDS: usize = 300;
+const DEFAULT_QUERIES: usize = 50;
+const DEFAULT_SEED: u64 = 42;
+const NOTES_PER_FOLDER: usize = 100;
+const LINKS_PER_NOTE: usize = 3;
+// One note in ten is edited before the incremental run.
+const EDITED_NOTE_STRIDE: usize = 10;
+
+const WORDS: &[&str] = &[
+    "archive", "budget", "canvas", "draft", "engine", "garden", "harbor", "index", "journal",
+    "kernel", "ledger", "meeting", "network", "orbit", "pattern", "quarter", "review", "signal",
+    "template", "update", "vector", "window", "yield", "zone", "album", "bridge", "compass",
+    "delta", "forest", "glacier", "horizon", "island", "lantern", "meadow", "nectar", "ocean",
+    "pepper", "quartz", "river", "summit", "thunder", "valley", "willow", "anchor", "beacon",
+    "cipher", "domain", "ember",
+];
+const TAGS: &[&str] = &["project", "idea", "reading", "meeting", "todo", "reference"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VaultShape {
+    pub notes: usize,
+    pub words: usize,
+    pub seed: u64,
+}
+
+impl Default for VaultShape {
+    fn default() -> Self {
+        Self {
+            notes: DEFAULT_NOTES,
+            words: DEFAULT_WORDS,
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BenchCommand {
+    Generate { dir: PathBuf, shape: VaultShape },
+    Run { shape: VaultShape, queries: usize },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchReport {
+    pub notes: usize,
+    pub words_per_note: usize,
+    pub phases: Vec<PhaseTiming>,
+    pub search: SearchTiming,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseTiming {
+    pub name: &'static str,
+    pub millis: f64,
+    /// Files the phase processed, where that applies.
+    pub files: Option<usize>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchTiming {
+    pub queries: usize,
+    pub mean_millis: f64,
+    pub p50_millis: f64,
+    pub p95_millis: f64,
+    pub max_millis: f64,
+}
+
+pub fn parse_bench_command(mut args: impl Iterator<Item = String>) -> Result<BenchCommand, String> {
+    let subcommand = args
+        .next()
+        .ok_or_else(|| "bench requires `generate` or `run`".to_string())?;
+    let mut dir = None;
+    let mut shape = VaultShape::default();
+    let mut queries = DEFAULT_QUERIES;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--notes" => shape.notes = count_flag(&mut args, "--notes")?,
+            "--words" => shape.words = count_flag(&mut args, "--words")?,
+            "--seed" => shape.seed = count_flag(&mut args, "--seed")? as u64,
+            "--queries" if subcommand == "run" => queries = count_flag(&mut args, "--queries")?,
+            other if other.starts_with("--") => {
+                return Err(format!("unknown bench flag `{other}`"))
+            }
+            _ if subcommand == "generate" && dir.is_none() => dir = Some(PathBuf::from(arg)),
+            other => return Err(format!("unexpected bench argument `{other}`")),
+        }
+    }
+
+    match subcommand.as_str() {
+        "generate" => Ok(BenchCommand::Generate {
+            dir: dir.ok_or_else(|| "bench generate requires a directory".to_string())?,
+            shape,
+        }),
+        "run" => Ok(BenchCommand::Run { shape, queries }),
+        other => Err(format!("unknown bench command `{other}`")),
+    }
+}
+
+fn count_flag(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<usize, String> {
+    let value = args
+        .next()
+        .ok_or_else(|| format!("missing value for `{flag}`"))?;
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|count| *count > 0)
+        .ok_or_else(|| format!("invalid value `{value}` for `{flag}`"))
+}
+
+pub fn run_bench(command: &BenchCommand, json: bool, out: &mut impl Write) -> Result<(), String> {
+    match command {
+        BenchCommand::Generate { dir, shape } => {
+            generate_vault(dir, *shape)?;
+            write_line(
+                out,
+                format!("Wrote {} notes to {}", shape.notes, dir.display()),
+            )
+        }
+        BenchCommand::Run { shape, queries } => {
+            let report = run_benchmark(*shape, *queries)?;
+            if json {
+                return write_json(out, &report);
+            }
+            write_line(
+                out,
+                format!("{} notes of {} words", report.notes, report.words_per_note),
+            )?;
+            for phase in &report.phases {
+                let files = phase
+                    .files
+                    .map(|files| format!(" ({files} files)"))
+                    .unwrap_or_default();
+                write_line(
+                    out,
+                    format!("{:<24}{:>10.1} ms{files}", phase.name, phase.millis),
+                )?;
+            }
+            let search = &report.search;
+            write_line(
+                out,
+                format!(
+                    "search ({} queries)      mean {:.2} ms, p50 {:.2} ms, p95 {:.2} ms, max {:.2} ms",
+                    search.queries,
+                    search.mean_millis,
+                    search.p50_millis,
+                    search.p95_millis,
+                    search.max_millis
+                ),
+            )
+        }
+    }
+}
+
+/// Generates a vault in a temporary directory with its own database, so runs
+/// never touch the desktop app's index, then times each indexing phase and
+/// the searches.
+pub fn run_benchmark(shape: VaultShape, queries: usize) -> Result<BenchReport, String> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    let dir = env::temp_dir().join(format!("mdit-bench-{nanos}"));
+    let report = run_benchmark_in(&dir, shape, queries);
+    let _ = fs::remove_dir_all(&dir);
+    report
+}
+
+fn run_benchmark_in(dir: &Path, shape: VaultShape, queries: usize) -> Result<BenchReport, String> {
+    let vault = dir.join("vault");
+    let db_path = dir.join("appdata.db");
+    let mut phases = Vec::new();
+
+    let started = Instant::now();
+    generate_vault(&vault, shape)?;
+    phases.push(phase("generate", started.elapsed(), None));
+    let vault = fs::canonicalize(&vault).map_err(|error| error.to_string())?;
+    app_storage::migrations::run_migrations_at(&db_path).map_err(|error| format!("{error:#}"))?;
+
+    let index = |name, force| -> Result<PhaseTiming, String> {
+        let started = Instant::now();
+        let summary = index_vault_documents_with_profiles(&vault, &db_path, &[], force)
+            .map_err(|error| format!("{error:#}"))?;
+        Ok(phase(
+            name,
+            started.elapsed(),
+            Some(summary.files_processed),
+        ))
+    };
+    phases.push(index("index (cold)", false)?);
+    phases.push(index("index (unchanged)", false)?);
+
+    let started = Instant::now();
+    for note in (0..shape.notes).step_by(EDITED_NOTE_STRIDE) {
+        let path = vault.join(note_rel_path(note));
+        let mut contents = fs::read_to_string(&path).map_err(|error| error.to_string())?;
+        contents.push_str("\nEdited for the incremental run.\n");
+        fs::write(&path, contents).map_err(|error| error.to_string())?;
+    }
+    phases.push(phase("edit", started.elapsed(), None));
+    phases.push(index("index (10% changed)", false)?);
+    phases.push(index("index (forced)", true)?);
+
+    let mut rng = Rng::new(shape.seed);
+    let mut durations = Vec::with_capacity(queries);
+    for _ in 0..queries {
+        let query = format!("{} {}", rng.pick(WORDS), rng.pick(WORDS));
+        let started = Instant::now();
+        search_notes_for_query_with_profiles(&vault, &db_path, &query, &[])
+            .map_err(|error| format!("{error:#}"))?;
+        durations.push(started.elapsed());
+    }
+
+    Ok(BenchReport {
+        notes: shape.notes,
+        words_per_note: shape.words,
+        phases,
+        search: search_timing(durations),
+    })
+}
+
+fn phase(name: &'static str, elapsed: Duration, files: Option<usize>) -> PhaseTiming {
+    PhaseTiming {
+        name,
+        millis: millis(elapsed),
+        files,
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn search_timing(mut durations: Vec<Duration>) -> SearchTiming {
+    if durations.is_empty() {
+        return SearchTiming::default();
+    }
+    durations.sort();
+    let percentile = |fraction: f64| {
+        let index = ((durations.len() - 1) as f64 * fraction).round() as usize;
+        millis(durations[index])
+    };
+    SearchTiming {
+        queries: durations.len(),
+        mean_millis: millis(durations.iter().sum::<Duration>()) / durations.len() as f64,
+        p50_millis: percentile(0.5),
+        p95_millis: percentile(0.95),
+        max_millis: percentile(1.0),
+    }
+}
+
+fn note_rel_path(note: usize) -> String {
+    format!("folder-{:03}/note-{note:05}.md", note / NOTES_PER_FOLDER)
+}
+
+/// Writes `shape.notes` notes with tags, headings, and wiki links to other
+/// notes. The same seed always gives the same vault.
+pub fn generate_vault(dir: &Path, shape: VaultShape) -> Result<(), String> {
+    let mut rng = Rng::new(shape.seed);
+    for note in 0..shape.notes {
+        let path = dir.join(note_rel_path(note));
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|error| format!("failed to create {}: {error}", parent.display()))?;
+        }
+
+        let mut contents = format!("---\ntags: [{}]\n---\n# Note {note}\n\n", rng.pick(TAGS));
+        for word in 0..shape.words {
+            contents.push_str(rng.pick(WORDS));
+            contents.push(if word % 60 == 59 { '\n' } else { ' ' });
+        }
+        contents.push('\n');
+        for _ in 0..LINKS_PER_NOTE {
+            let target = rng.below(shape.notes);
+            contents.push_str(&format!("\nSee [[note-{target:05}]].\n"));
+        }
+
+        fs::write(&path, contents)
+            .map_err(|error| format!("failed to write {}: {error}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// xorshift64*, enough to spread words and links without a dependency.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn benchmark_reports_every_phase() {
+        let shape = VaultShape {
+            notes: 25,
+            words: 40,
+            seed: 7,
+        };
+        let report = run_benchmark(shape, 5).unwrap();
+
+        let names = report
+            .phases
+            .iter()
+            .map(|phase| phase.name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "generate",
+                "index (cold)",
+                "index (unchanged)",
+                "edit",
+                "index (10% changed)",
+                "index (forced)",
+            ]
+        );
+        assert_eq!(report.phases[1].files, Some(25));
+        assert_eq!(report.search.queries, 5);
+        assert!(report.search.max_millis >= report.search.p50_millis);
+    }
+
+    #[test]
+    fn parse_bench_command_reads_the_vault_shape() {
+        let args = |values: &[&str]| {
+            values
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+        assert_eq!(
+            parse_bench_command(args(&["run", "--notes", "200", "--queries", "10"])),
+            Ok(BenchCommand::Run {
+                shape: VaultShape {
+                    notes: 200,
+                    ..VaultShape::default()
+                },
+                queries: 10,
+            })
+        );
+        assert_eq!(
+            parse_bench_command(args(&["generate", "--words", "0", "/tmp/vault"])),
+            Err("invalid value `0` for `--words`".to_string())
+        );
+        assert_eq!(
+            parse_bench_command(args(&["generate", "--queries", "3", "/tmp/vault"])),
+            Err("unknown bench flag `--queries`".to_string())
+        );
+    }
+}
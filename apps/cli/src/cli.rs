@@ -0,0 +1,321 @@
+use std::path::PathBuf;
+
+pub const HELP: &str = "\
+mdit
+
+Headless vault operations for Mdit. Works on a vault directory directly and
+shares the index with the desktop app.
+
+Usage:
+  mdit [options] <command> [args]
+
+Commands:
+  index [--force] [--break-lock]   Index the vault's Markdown files. --break-lock
+                                   removes a lock left by another writer first.
+  search <query> [--limit <n>]     Search indexed notes.
+  new <title> [--dir <path>] [--content <text>]
+                                   Create a note.
+  backlinks <note>                 List notes linking to <note>.
+  export [--output <file>]         Write notes, tags, aliases and links as JSON.
+  doctor                           Report problems with the vault layout and index.
+
+Options:
+  --vault <path>   Vault directory. Defaults to MDIT_VAULT or the current directory.
+  --db <path>      App database. Defaults to MDIT_DB_PATH or the desktop app's database.
+  --json           Print machine readable output.
+  -h, --help       Show this help.
+  -V, --version    Show the version.
+";
+
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GlobalOptions {
+    pub vault: Option<PathBuf>,
+    pub db: Option<PathBuf>,
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Version,
+    Index(IndexCommand),
+    Search(SearchCommand),
+    New(NewCommand),
+    Backlinks(BacklinksCommand),
+    Export(ExportCommand),
+    Doctor,
+    #[cfg(feature = "bench")]
+    Bench(crate::bench::BenchCommand),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexCommand {
+    pub force: bool,
+    pub break_lock: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchCommand {
+    pub query: String,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewCommand {
+    pub title: String,
+    pub directory: Option<String>,
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BacklinksCommand {
+    pub note: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportCommand {
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub options: GlobalOptions,
+    pub command: Command,
+}
+
+/// Parses global options, which may appear before or after the command, and
+/// leaves the remaining arguments to the command's own parser.
+pub fn parse_command(args: Vec<String>) -> Result<Invocation, String> {
+    let mut options = GlobalOptions::default();
+    let mut command_args = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--vault" => options.vault = Some(PathBuf::from(flag_value(&mut args, "--vault")?)),
+            "--db" => options.db = Some(PathBuf::from(flag_value(&mut args, "--db")?)),
+            "--json" => options.json = true,
+            _ => command_args.push(arg),
+        }
+    }
+
+    let mut command_args = command_args.into_iter();
+    let Some(name) = command_args.next() else {
+        return Ok(Invocation {
+            options,
+            command: Command::Help,
+        });
+    };
+
+    let command = match name.as_str() {
+        "-h" | "--help" | "help" => Command::Help,
+        "-V" | "--version" => Command::Version,
+        "index" => Command::Index(parse_index_command(command_args)?),
+        "search" => Command::Search(parse_search_command(command_args)?),
+        "new" => Command::New(parse_new_command(command_args)?),
+        "backlinks" => Command::Backlinks(parse_backlinks_command(command_args)?),
+        "export" => Command::Export(parse_export_command(command_args)?),
+        "doctor" => {
+            reject_extra_args("doctor", command_args)?;
+            Command::Doctor
+        }
+        #[cfg(feature = "bench")]
+        "bench" => Command::Bench(crate::bench::parse_bench_command(command_args)?),
+        other => return Err(format!("unknown command `{other}`")),
+    };
+
+    Ok(Invocation { options, command })
+}
+
+fn flag_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for `{flag}`"))
+}
+
+fn reject_extra_args(command: &str, mut args: impl Iterator<Item = String>) -> Result<(), String> {
+    match args.next() {
+        Some(arg) => Err(format!("unexpected {command} argument `{arg}`")),
+        None => Ok(()),
+    }
+}
+
+fn parse_index_command(args: impl Iterator<Item = String>) -> Result<IndexCommand, String> {
+    let mut command = IndexCommand::default();
+
+    for arg in args {
+        match arg.as_str() {
+            "--force" => command.force = true,
+            "--break-lock" => command.break_lock = true,
+            other => return Err(format!("unknown index flag `{other}`")),
+        }
+    }
+
+    Ok(command)
+}
+
+fn parse_search_command(args: impl Iterator<Item = String>) -> Result<SearchCommand, String> {
+    let mut terms = Vec::new();
+    let mut limit = DEFAULT_SEARCH_LIMIT;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--limit" => {
+                let value = flag_value(&mut args, "--limit")?;
+                limit = value
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|limit| *limit > 0)
+                    .ok_or_else(|| format!("invalid search limit `{value}`"))?;
+            }
+            other if other.starts_with("--") => {
+                return Err(format!("unknown search flag `{other}`"))
+            }
+            _ => terms.push(arg),
+        }
+    }
+
+    let query = terms.join(" ");
+    if query.trim().is_empty() {
+        return Err("search requires a query".to_string());
+    }
+
+    Ok(SearchCommand { query, limit })
+}
+
+fn parse_new_command(args: impl Iterator<Item = String>) -> Result<NewCommand, String> {
+    let mut title = None;
+    let mut directory = None;
+    let mut content = None;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dir" => directory = Some(flag_value(&mut args, "--dir")?),
+            "--content" => content = Some(flag_value(&mut args, "--content")?),
+            other if other.starts_with("--") => return Err(format!("unknown new flag `{other}`")),
+            _ if title.is_none() => title = Some(arg),
+            other => return Err(format!("unexpected new argument `{other}`")),
+        }
+    }
+
+    Ok(NewCommand {
+        title: title.ok_or_else(|| "new requires a title".to_string())?,
+        directory,
+        content,
+    })
+}
+
+fn parse_backlinks_command(
+    mut args: impl Iterator<Item = String>,
+) -> Result<BacklinksCommand, String> {
+    let note = args
+        .next()
+        .ok_or_else(|| "backlinks requires a note path".to_string())?;
+    reject_extra_args("backlinks", args)?;
+
+    Ok(BacklinksCommand {
+        note: PathBuf::from(note),
+    })
+}
+
+fn parse_export_command(args: impl Iterator<Item = String>) -> Result<ExportCommand, String> {
+    let mut command = ExportCommand::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" | "-o" => {
+                command.output = Some(PathBuf::from(flag_value(&mut args, "--output")?))
+            }
+            other => return Err(format!("unknown export flag `{other}`")),
+        }
+    }
+
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_command_reads_global_options_around_the_command() {
+        let invocation =
+            parse_command(args(&["--vault", "/notes", "index", "--force", "--json"])).unwrap();
+
+        assert_eq!(
+            invocation.options,
+            GlobalOptions {
+                vault: Some(PathBuf::from("/notes")),
+                db: None,
+                json: true,
+            }
+        );
+        assert_eq!(
+            invocation.command,
+            Command::Index(IndexCommand {
+                force: true,
+                break_lock: false,
+            })
+        );
+        assert_eq!(parse_command(vec![]).unwrap().command, Command::Help);
+    }
+
+    #[test]
+    fn parse_command_parses_command_arguments() {
+        assert_eq!(
+            parse_command(args(&["search", "rust", "notes", "--limit", "5"]))
+                .unwrap()
+                .command,
+            Command::Search(SearchCommand {
+                query: "rust notes".to_string(),
+                limit: 5,
+            })
+        );
+        assert_eq!(
+            parse_command(args(&["new", "Meeting", "--dir", "Work"]))
+                .unwrap()
+                .command,
+            Command::New(NewCommand {
+                title: "Meeting".to_string(),
+                directory: Some("Work".to_string()),
+                content: None,
+            })
+        );
+        assert_eq!(
+            parse_command(args(&["export", "-o", "index.json"]))
+                .unwrap()
+                .command,
+            Command::Export(ExportCommand {
+                output: Some(PathBuf::from("index.json")),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_command_rejects_invalid_input() {
+        assert_eq!(
+            parse_command(args(&["wat"])),
+            Err("unknown command `wat`".to_string())
+        );
+        assert_eq!(
+            parse_command(args(&["search", "--limit", "0", "rust"])),
+            Err("invalid search limit `0`".to_string())
+        );
+        assert_eq!(
+            parse_command(args(&["backlinks"])),
+            Err("backlinks requires a note path".to_string())
+        );
+        assert_eq!(
+            parse_command(args(&["--vault"])),
+            Err("missing value for `--vault`".to_string())
+        );
+    }
+}
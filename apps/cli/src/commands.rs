@@ -0,0 +1,420 @@
+use std::{collections::HashMap, fs, io::Write, path::Path};
+
+use mdit_local_api::{create_note, CreateNoteInput};
+use mdit_vault_indexing::{
+    break_vault_lock, get_backlinks, get_graph_view_data, get_indexing_meta, get_note_aliases,
+    get_note_tags, index_vault_documents_with_profiles, run_vault_diagnostics,
+    search_notes_for_query_with_profiles, VaultLockInfo,
+};
+use serde::Serialize;
+
+use crate::{
+    cli::{BacklinksCommand, ExportCommand, IndexCommand, NewCommand, SearchCommand},
+    workspace::Workspace,
+};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultExport {
+    pub notes: Vec<ExportedNote>,
+    pub links: Vec<ExportedLink>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedNote {
+    pub rel_path: String,
+    pub file_name: String,
+    pub tags: Vec<String>,
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedLink {
+    pub source: String,
+    pub target: String,
+    pub unresolved: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DoctorReport {
+    workspace_path: String,
+    db_path: String,
+    registered: bool,
+    indexed_doc_count: usize,
+    embedding_configured: bool,
+    vector_search: app_storage::sqlite_ext::VectorSupport,
+    /// Another process writing the index right now.
+    index_lock: Option<VaultLockInfo>,
+    #[serde(flatten)]
+    diagnostics: mdit_vault_indexing::VaultDiagnostics,
+}
+
+pub(crate) fn write_line(out: &mut impl Write, line: impl std::fmt::Display) -> Result<(), String> {
+    writeln!(out, "{line}").map_err(|error| format!("failed to write output: {error}"))
+}
+
+pub(crate) fn write_json(out: &mut impl Write, value: &impl Serialize) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|error| format!("failed to encode output: {error}"))?;
+    write_line(out, json)
+}
+
+pub fn run_index(
+    workspace: &Workspace,
+    command: &IndexCommand,
+    json: bool,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let profiles = workspace.embedding_profiles()?;
+    if command.break_lock {
+        let holder = break_vault_lock(&workspace.root).map_err(|error| format!("{error:#}"))?;
+        if let (Some(holder), false) = (holder, json) {
+            write_line(
+                out,
+                format!(
+                    "Removed index lock held by {} (pid {} on {})",
+                    holder.owner, holder.pid, holder.host
+                ),
+            )?;
+        }
+    }
+    let summary = index_vault_documents_with_profiles(
+        &workspace.root,
+        &workspace.db_path,
+        &profiles,
+        command.force,
+    )
+    .map_err(|error| format!("{error:#}"))?;
+
+    if json {
+        return write_json(out, &summary);
+    }
+
+    write_line(
+        out,
+        format!(
+            "Indexed {} of {} files ({} new, {} removed, {} embeddings, {} pending)",
+            summary.files_processed,
+            summary.files_discovered,
+            summary.docs_inserted,
+            summary.docs_deleted,
+            summary.embeddings_written,
+            summary.embeddings_pending,
+        ),
+    )?;
+    for skipped in &summary.skipped_files {
+        write_line(out, format!("skipped: {skipped}"))?;
+    }
+    Ok(())
+}
+
+pub fn run_search(
+    workspace: &Workspace,
+    command: &SearchCommand,
+    json: bool,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let profiles = workspace.embedding_profiles()?;
+    let results = search_notes_for_query_with_profiles(
+        &workspace.root,
+        &workspace.db_path,
+        command.query.trim(),
+        &profiles,
+    )
+    .map_err(|error| format!("{error:#}"))?
+    .into_iter()
+    .take(command.limit)
+    .collect::<Vec<_>>();
+
+    if json {
+        return write_json(out, &results);
+    }
+
+    for entry in &results {
+        let path = Path::new(&entry.path);
+        let rel_path = path.strip_prefix(&workspace.root).unwrap_or(path);
+        write_line(
+            out,
+            format!("{:.3}\t{}", entry.similarity, rel_path.display()),
+        )?;
+    }
+    Ok(())
+}
+
+pub fn run_new(
+    workspace: &Workspace,
+    command: &NewCommand,
+    json: bool,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let created = create_note(
+        &workspace.db_path,
+        CreateNoteInput {
+            vault_id: workspace.ensure_vault_id()?,
+            directory_rel_path: command.directory.clone(),
+            title: command.title.clone(),
+            content: command.content.clone(),
+        },
+    )
+    .map_err(|error| error.to_string())?;
+
+    if json {
+        return write_json(out, &created);
+    }
+    write_line(out, created.relative_path)
+}
+
+pub fn run_backlinks(
+    workspace: &Workspace,
+    command: &BacklinksCommand,
+    json: bool,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let note_path = workspace.note_path(&command.note)?;
+    let backlinks = get_backlinks(&workspace.root, &workspace.db_path, &note_path)
+        .map_err(|error| format!("{error:#}"))?;
+
+    if json {
+        return write_json(out, &backlinks);
+    }
+    for backlink in backlinks {
+        write_line(out, backlink.rel_path)?;
+    }
+    Ok(())
+}
+
+/// Builds the export from the index; run `index` first for an up to date view.
+pub fn build_vault_export(workspace: &Workspace) -> Result<VaultExport, String> {
+    let graph = get_graph_view_data(&workspace.root, &workspace.db_path)
+        .map_err(|error| format!("{error:#}"))?;
+    let rel_path_by_node_id = graph
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node.rel_path.as_str()))
+        .collect::<HashMap<_, _>>();
+
+    let note_paths = graph
+        .nodes
+        .iter()
+        .filter(|node| !node.unresolved)
+        .map(|node| node.rel_path.clone())
+        .collect::<Vec<_>>();
+    let mut tags = get_note_tags(&workspace.root, &workspace.db_path, &note_paths)
+        .map_err(|error| format!("{error:#}"))?;
+    let mut aliases = get_note_aliases(&workspace.root, &workspace.db_path)
+        .map_err(|error| format!("{error:#}"))?;
+
+    let links = graph
+        .edges
+        .iter()
+        .filter_map(|edge| {
+            Some(ExportedLink {
+                source: rel_path_by_node_id.get(edge.source.as_str())?.to_string(),
+                target: rel_path_by_node_id.get(edge.target.as_str())?.to_string(),
+                unresolved: edge.unresolved,
+            })
+        })
+        .collect();
+    let notes = graph
+        .nodes
+        .into_iter()
+        .filter(|node| !node.unresolved)
+        .map(|node| ExportedNote {
+            tags: tags.remove(&node.rel_path).unwrap_or_default(),
+            aliases: aliases.remove(&node.rel_path).unwrap_or_default(),
+            rel_path: node.rel_path,
+            file_name: node.file_name,
+        })
+        .collect();
+
+    Ok(VaultExport { notes, links })
+}
+
+pub fn run_export(
+    workspace: &Workspace,
+    command: &ExportCommand,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let export = build_vault_export(workspace)?;
+
+    match &command.output {
+        Some(output) => {
+            let json = serde_json::to_string_pretty(&export)
+                .map_err(|error| format!("failed to encode export: {error}"))?;
+            fs::write(output, json)
+                .map_err(|error| format!("failed to write {}: {error}", output.display()))
+        }
+        None => write_json(out, &export),
+    }
+}
+
+/// Prints the report and fails when the vault has layout problems, so the
+/// command can gate CI jobs.
+pub fn run_doctor(workspace: &Workspace, json: bool, out: &mut impl Write) -> Result<(), String> {
+    let diagnostics =
+        run_vault_diagnostics(&workspace.root).map_err(|error| format!("{error:#}"))?;
+    let meta = get_indexing_meta(&workspace.root, &workspace.db_path)
+        .map_err(|error| format!("{error:#}"))?;
+    let report = DoctorReport {
+        workspace_path: workspace.root.to_string_lossy().to_string(),
+        db_path: workspace.db_path.to_string_lossy().to_string(),
+        registered: workspace.find_vault_id()?.is_some(),
+        indexed_doc_count: meta.indexed_doc_count,
+        embedding_configured: !workspace.embedding_profiles()?.is_empty(),
+        vector_search: meta.vector_search,
+        index_lock: meta.index_lock,
+        diagnostics,
+    };
+    let problem_count =
+        report.diagnostics.case_conflicts.len() + report.diagnostics.duplicate_basenames.len();
+
+    if json {
+        write_json(out, &report)?;
+    } else {
+        write_line(out, format!("vault: {}", report.workspace_path))?;
+        write_line(out, format!("database: {}", report.db_path))?;
+        if !report.registered {
+            write_line(out, "index: not indexed yet, run `mdit index`")?;
+        } else {
+            write_line(
+                out,
+                format!("index: {} notes indexed", report.indexed_doc_count),
+            )?;
+        }
+        if !report.embedding_configured {
+            write_line(out, "embeddings: not configured, search uses keywords only")?;
+        }
+        if let Some(error) = &report.vector_search.error {
+            write_line(
+                out,
+                format!("vector search: unavailable, search uses keywords only ({error})"),
+            )?;
+        }
+        if let Some(holder) = &report.index_lock {
+            write_line(
+                out,
+                format!(
+                    "index lock: held by {} (pid {} on {}); use `mdit index --break-lock` if it is gone",
+                    holder.owner, holder.pid, holder.host
+                ),
+            )?;
+        }
+        for conflict in &report.diagnostics.case_conflicts {
+            write_line(
+                out,
+                format!("case conflict: {}", conflict.rel_paths.join(", ")),
+            )?;
+        }
+        for duplicate in &report.diagnostics.duplicate_basenames {
+            write_line(
+                out,
+                format!(
+                    "duplicate name `{}`: {}",
+                    duplicate.basename,
+                    duplicate.rel_paths.join(", ")
+                ),
+            )?;
+        }
+    }
+
+    if problem_count > 0 {
+        return Err(format!("found {problem_count} problem(s) in the vault"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        path::PathBuf,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::*;
+    use crate::cli::BacklinksCommand;
+
+    fn unique_test_dir(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be valid")
+            .as_nanos();
+        env::temp_dir().join(format!("mdit-cli-{name}-{nanos}"))
+    }
+
+    fn test_workspace(name: &str) -> Workspace {
+        let dir = unique_test_dir(name);
+        let vault = dir.join("vault");
+        fs::create_dir_all(&vault).expect("vault dir should be created");
+        let db_path = dir.join("appdata.db");
+        app_storage::migrations::run_migrations_at(&db_path).expect("migrations should run");
+
+        Workspace {
+            root: fs::canonicalize(&vault).expect("vault should canonicalize"),
+            db_path,
+        }
+    }
+
+    #[test]
+    fn index_backlinks_and_export_share_the_vault_index() {
+        let workspace = test_workspace("export");
+        fs::write(
+            workspace.root.join("a.md"),
+            "---\ntags: [project]\naliases: [Alpha]\n---\nSee [[b]] and [[missing]].\n",
+        )
+        .unwrap();
+        fs::write(workspace.root.join("b.md"), "# B\n").unwrap();
+
+        let mut out = Vec::new();
+        run_index(&workspace, &IndexCommand::default(), false, &mut out).unwrap();
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .starts_with("Indexed 2 of 2 files"));
+
+        let mut out = Vec::new();
+        run_backlinks(
+            &workspace,
+            &BacklinksCommand {
+                note: PathBuf::from("b.md"),
+            },
+            false,
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "a.md\n");
+
+        let export = build_vault_export(&workspace).unwrap();
+        let note_a = export
+            .notes
+            .iter()
+            .find(|note| note.rel_path == "a.md")
+            .expect("a.md should be exported");
+        assert_eq!(note_a.tags, vec!["project".to_string()]);
+        assert_eq!(note_a.aliases, vec!["Alpha".to_string()]);
+        assert_eq!(export.notes.len(), 2);
+        assert!(export
+            .links
+            .iter()
+            .any(|link| link.source == "a.md" && link.target == "b.md" && !link.unresolved));
+        assert!(export.links.iter().any(|link| link.unresolved));
+    }
+
+    #[test]
+    fn doctor_fails_on_duplicate_basenames() {
+        let workspace = test_workspace("doctor");
+        fs::create_dir_all(workspace.root.join("Work")).unwrap();
+        fs::write(workspace.root.join("Plan.md"), "").unwrap();
+        fs::write(workspace.root.join("Work/plan.md"), "").unwrap();
+
+        let mut out = Vec::new();
+        let result = run_doctor(&workspace, false, &mut out);
+
+        assert_eq!(result, Err("found 1 problem(s) in the vault".to_string()));
+        let report = String::from_utf8(out).unwrap();
+        assert!(report.contains("index: not indexed yet"));
+        assert!(report.contains("duplicate name `plan`: Plan.md, Work/plan.md"));
+    }
+}
@@ -0,0 +1,8 @@
+mod app;
+#[cfg(feature = "bench")]
+mod bench;
+mod cli;
+mod commands;
+mod workspace;
+
+pub use app::run;
@@ -0,0 +1,6 @@
+fn main() {
+    if let Err(error) = mdit_cli::run() {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,120 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use mdit_vault_indexing::EmbeddingProfile;
+
+use crate::cli::GlobalOptions;
+
+pub const VAULT_ENV: &str = "MDIT_VAULT";
+pub const DB_PATH_ENV: &str = "MDIT_DB_PATH";
+// Matches the desktop bundle identifier, so the CLI and the app share one index.
+const APP_IDENTIFIER: &str = "app.mdit";
+
+/// A vault directory paired with the migrated app database that indexes it.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub db_path: PathBuf,
+}
+
+impl Workspace {
+    pub fn resolve(options: &GlobalOptions) -> Result<Self, String> {
+        let root = resolve_vault_root(options)?;
+        let db_path = resolve_db_path(options)?;
+        Ok(Self { root, db_path })
+    }
+
+    /// Embedding profiles configured for this vault in the desktop app.
+    pub fn embedding_profiles(&self) -> Result<Vec<EmbeddingProfile>, String> {
+        let config = app_storage::vault::get_embedding_config(&self.db_path, &self.root)
+            .map_err(|error| format!("{error:#}"))?;
+
+        Ok(config
+            .map(|config| {
+                config
+                    .profiles()
+                    .into_iter()
+                    .map(|(provider, model)| EmbeddingProfile::new(provider, model))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Vault id used by the local API services, registering the vault if the
+    /// app has never seen it.
+    pub fn ensure_vault_id(&self) -> Result<i64, String> {
+        if let Some(vault_id) = self.find_vault_id()? {
+            return Ok(vault_id);
+        }
+
+        app_storage::vault::touch_workspace(&self.db_path, &self.root)
+            .map_err(|error| format!("{error:#}"))?;
+        self.find_vault_id()?
+            .ok_or_else(|| format!("failed to register vault {}", self.root.display()))
+    }
+
+    pub fn find_vault_id(&self) -> Result<Option<i64>, String> {
+        app_storage::vault::find_workspace_by_path(&self.db_path, &self.root)
+            .map(|workspace| workspace.map(|workspace| workspace.id))
+            .map_err(|error| format!("{error:#}"))
+    }
+
+    /// Absolute path of a note given relative to the vault or the current
+    /// directory.
+    pub fn note_path(&self, note: &Path) -> Result<PathBuf, String> {
+        let candidate = if note.is_absolute() || note.exists() {
+            note.to_path_buf()
+        } else {
+            self.root.join(note)
+        };
+
+        let note_path = fs::canonicalize(&candidate)
+            .map_err(|error| format!("note not found {}: {error}", candidate.display()))?;
+        if !note_path.starts_with(&self.root) {
+            return Err(format!(
+                "note {} is outside the vault {}",
+                note_path.display(),
+                self.root.display()
+            ));
+        }
+
+        Ok(note_path)
+    }
+}
+
+fn resolve_vault_root(options: &GlobalOptions) -> Result<PathBuf, String> {
+    let vault = match options.vault.clone().or_else(|| env_path(VAULT_ENV)) {
+        Some(vault) => vault,
+        None => env::current_dir()
+            .map_err(|error| format!("failed to resolve the current directory: {error}"))?,
+    };
+
+    let root = fs::canonicalize(&vault)
+        .map_err(|error| format!("vault not found {}: {error}", vault.display()))?;
+    if !root.is_dir() {
+        return Err(format!("vault is not a directory: {}", root.display()));
+    }
+
+    Ok(root)
+}
+
+fn resolve_db_path(options: &GlobalOptions) -> Result<PathBuf, String> {
+    if let Some(db_path) = options.db.clone().or_else(|| env_path(DB_PATH_ENV)) {
+        app_storage::migrations::run_migrations_at(&db_path)
+            .map_err(|error| format!("{error:#}"))?;
+        return Ok(db_path);
+    }
+
+    let app_data_dir = dirs::data_dir()
+        .ok_or_else(|| "failed to resolve the system data directory".to_string())?
+        .join(APP_IDENTIFIER);
+    app_storage::migrations::run_app_migrations(&app_data_dir).map_err(|error| format!("{error:#}"))
+}
+
+fn env_path(name: &str) -> Option<PathBuf> {
+    env::var_os(name)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
@@ -0,0 +1,228 @@
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use mdit_credentials::{get_app_secret, AppSecretKey};
+use serde::{Deserialize, Serialize};
+use tauri::{
+    menu::{Menu, MenuItem, PredefinedMenuItem},
+    tray::TrayIconBuilder,
+    Manager,
+};
+
+use crate::app::{quick_capture, window_lifecycle::show_and_focus_main_window};
+
+pub const MAIN_WINDOW_LABEL: &str = "main";
+const BACKGROUND_FLAG: &str = "--background";
+const BACKGROUND_SETTINGS_FILE: &str = "settings/background.json";
+const TRAY_ID: &str = "tray";
+// Leave the login session a moment before the first indexing pass.
+const DEFERRED_INIT_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackgroundModeSettings {
+    /// Launch without a window, as if started with `--background`.
+    pub start_in_background: bool,
+    /// Mirrors the local API toggle so the server can start without the UI.
+    pub run_local_api: bool,
+}
+
+fn settings_path<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>) -> Option<PathBuf> {
+    crate::persistence::app_data_dir(app_handle)
+        .ok()
+        .map(|dir| dir.join(BACKGROUND_SETTINGS_FILE))
+}
+
+pub fn load_background_settings<R: tauri::Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+) -> BackgroundModeSettings {
+    settings_path(app_handle)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_background_settings<R: tauri::Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    settings: &BackgroundModeSettings,
+) -> Result<(), String> {
+    let path = settings_path(app_handle)
+        .ok_or_else(|| "Failed to resolve app data directory".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|error| error.to_string())?;
+    fs::write(path, json).map_err(|error| error.to_string())
+}
+
+/// Records whether the local API should run, for the next background launch.
+pub fn remember_local_api_enabled<R: tauri::Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    enabled: bool,
+) {
+    let mut settings = load_background_settings(app_handle);
+    if settings.run_local_api == enabled {
+        return;
+    }
+    settings.run_local_api = enabled;
+    if let Err(error) = save_background_settings(app_handle, &settings) {
+        tracing::error!("Failed to save background mode settings: {error}");
+    }
+}
+
+pub fn is_background_launch(app_handle: &tauri::AppHandle) -> bool {
+    std::env::args().any(|arg| arg == BACKGROUND_FLAG)
+        || load_background_settings(app_handle).start_in_background
+}
+
+/// Returns the main window, creating it first when the app was launched in
+/// the background.
+pub fn ensure_main_window(app_handle: &tauri::AppHandle) -> Option<tauri::WebviewWindow> {
+    if let Some(window) = app_handle.get_webview_window(MAIN_WINDOW_LABEL) {
+        return Some(window);
+    }
+
+    let config = app_handle.config().app.windows.first()?.clone();
+    let builder = tauri::WebviewWindowBuilder::from_config(app_handle, &config).ok()?;
+    let window = crate::persistence::with_webview_data(builder, app_handle)
+        .build()
+        .map_err(|error| tracing::error!("Failed to create main window: {error}"))
+        .ok()?;
+
+    #[cfg(target_os = "macos")]
+    let _ = app_handle.set_activation_policy(tauri::ActivationPolicy::Regular);
+
+    Some(window)
+}
+
+pub fn open_main_window(app_handle: &tauri::AppHandle) {
+    if let Some(window) = ensure_main_window(app_handle) {
+        show_and_focus_main_window(window);
+    }
+}
+
+fn create_background_tray(app_handle: &tauri::AppHandle) -> tauri::Result<()> {
+    if app_handle.tray_by_id(TRAY_ID).is_some() {
+        return Ok(());
+    }
+
+    let open = MenuItem::with_id(app_handle, "open", "Open Mdit", true, None::<&str>)?;
+    let capture = MenuItem::with_id(
+        app_handle,
+        "quick-capture",
+        "Quick Capture",
+        true,
+        None::<&str>,
+    )?;
+    let separator = PredefinedMenuItem::separator(app_handle)?;
+    let quit = PredefinedMenuItem::quit(app_handle, None)?;
+    let menu = Menu::with_items(app_handle, &[&open, &capture, &separator, &quit])?;
+
+    let icon = app_handle
+        .path()
+        .resolve(
+            "icons/trayTemplate.png",
+            tauri::path::BaseDirectory::Resource,
+        )
+        .ok()
+        .and_then(|path| tauri::image::Image::from_path(path).ok())
+        .or_else(|| app_handle.default_window_icon().cloned());
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .icon_as_template(true)
+        .on_menu_event(|app_handle, event| match event.id().as_ref() {
+            "open" => open_main_window(app_handle),
+            "quick-capture" => {
+                if let Err(error) =
+                    quick_capture::toggle_quick_capture_window_command(app_handle.clone())
+                {
+                    tracing::error!("Failed to toggle quick capture window: {error}");
+                }
+            }
+            _ => {}
+        });
+    if let Some(icon) = icon {
+        builder = builder.icon(icon);
+    }
+    builder.build(app_handle)?;
+
+    Ok(())
+}
+
+/// Starts the local API and the vault watcher of the last opened vault
+/// without any window. Runs after a short delay so launching at login stays
+/// light.
+fn start_background_services(app_handle: tauri::AppHandle) {
+    thread::spawn(move || {
+        thread::sleep(DEFERRED_INIT_DELAY);
+
+        if load_background_settings(&app_handle).run_local_api {
+            let token = get_app_secret(
+                AppSecretKey::LocalApiToken,
+                &crate::commands::credentials::backend(&app_handle),
+            );
+            let started = match token {
+                Ok(Some(token)) => crate::local_api::set_local_api_auth_token(&app_handle, token)
+                    .and_then(|_| crate::local_api::start_local_api_server(&app_handle))
+                    .map_err(|error| error.to_string()),
+                Ok(None) => Err("no local API token is stored".to_string()),
+                Err(error) => Err(error.to_string()),
+            };
+            if let Err(error) = started {
+                tracing::error!("Failed to start local API in background mode: {error}");
+            }
+        }
+
+        let workspace = crate::persistence::run_app_migrations(&app_handle).and_then(|db_path| {
+            app_storage::vault::list_workspaces(&db_path).map_err(|error| error.to_string())
+        });
+        match workspace.map(|workspaces| workspaces.into_iter().next()) {
+            Ok(Some(workspace_path)) => {
+                if let Err(error) = crate::commands::vault_watch::start_background_vault_watch(
+                    &app_handle,
+                    workspace_path,
+                ) {
+                    tracing::error!("Failed to start background vault watcher: {error}");
+                }
+            }
+            Ok(None) => {}
+            Err(error) => tracing::error!("Failed to resolve vault for background mode: {error}"),
+        }
+    });
+}
+
+/// Called from setup. Creates the main window for a normal launch; for a
+/// background launch only the tray and the deferred services are started.
+pub fn initialize(app_handle: &tauri::AppHandle) -> tauri::Result<()> {
+    if !is_background_launch(app_handle) {
+        ensure_main_window(app_handle);
+        return Ok(());
+    }
+
+    #[cfg(target_os = "macos")]
+    let _ = app_handle.set_activation_policy(tauri::ActivationPolicy::Accessory);
+
+    create_background_tray(app_handle)?;
+    start_background_services(app_handle.clone());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_background_mode_settings_command(
+    app_handle: tauri::AppHandle,
+) -> BackgroundModeSettings {
+    load_background_settings(&app_handle)
+}
+
+#[tauri::command]
+pub fn set_start_in_background_command(
+    app_handle: tauri::AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = load_background_settings(&app_handle);
+    settings.start_in_background = enabled;
+    save_background_settings(&app_handle, &settings)
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use mdit_vault_indexing::run_consistency_sweep;
+use serde_json::Value as JsonValue;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::commands::vault_indexing::{index_run_guard, resolve_embedding_for_workspace};
+use crate::commands::vault_watch::VaultWatchRuntimeState;
+
+pub const CONSISTENCY_SWEEP_SETTING: &str = "consistencySweep";
+
+const TICK: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SweepSettings {
+    enabled: bool,
+    interval: Duration,
+}
+
+impl SweepSettings {
+    fn from_value(value: &JsonValue) -> Self {
+        Self {
+            enabled: value
+                .get("enabled")
+                .and_then(JsonValue::as_bool)
+                .unwrap_or(true),
+            interval: Duration::from_secs(
+                value
+                    .get("intervalMinutes")
+                    .and_then(JsonValue::as_u64)
+                    .unwrap_or(360)
+                    * 60,
+            ),
+        }
+    }
+
+    fn load(db_path: &Path, workspace_path: &str) -> Option<Self> {
+        app_storage::vault_settings::get_vault_setting(
+            db_path,
+            Path::new(workspace_path),
+            CONSISTENCY_SWEEP_SETTING,
+        )
+        .map(|setting| Self::from_value(&setting.value))
+        .map_err(|error| tracing::error!("Failed to load consistency sweep settings: {error}"))
+        .ok()
+    }
+}
+
+/// Starts the worker that checks the index of watched vaults against their
+/// files on the interval each vault sets, catching events the watcher missed.
+pub fn initialize<R: Runtime>(app_handle: &AppHandle<R>) {
+    let app_handle = app_handle.clone();
+    thread::spawn(move || {
+        // A vault's first sweep waits a full interval; opening it already
+        // ran a full index.
+        let mut last_swept = HashMap::new();
+        loop {
+            thread::sleep(TICK);
+            run_sweep_pass(&app_handle, &mut last_swept);
+        }
+    });
+}
+
+fn run_sweep_pass<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    last_swept: &mut HashMap<String, Instant>,
+) {
+    let Ok(db_path) = crate::persistence::run_app_migrations(app_handle) else {
+        return;
+    };
+    let run_guard = index_run_guard(app_handle);
+    let workspaces = app_handle
+        .state::<VaultWatchRuntimeState>()
+        .watched_workspaces();
+    last_swept.retain(|workspace_path, _| workspaces.contains(workspace_path));
+
+    for workspace_path in workspaces {
+        let Some(settings) = SweepSettings::load(&db_path, &workspace_path) else {
+            continue;
+        };
+        let swept = *last_swept
+            .entry(workspace_path.clone())
+            .or_insert_with(Instant::now);
+        if !settings.enabled || swept.elapsed() < settings.interval {
+            continue;
+        }
+        // Failures are retried at the next interval rather than every tick.
+        last_swept.insert(workspace_path.clone(), Instant::now());
+
+        let workspace_root = Path::new(&workspace_path);
+        let embedding_profiles = match resolve_embedding_for_workspace(&db_path, workspace_root) {
+            Ok(profiles) => profiles,
+            Err(error) => {
+                tracing::error!("Failed to resolve embedding for consistency sweep: {error}");
+                Vec::new()
+            }
+        };
+        let sweep = run_guard.run_exclusive(workspace_root, || {
+            run_consistency_sweep(workspace_root, &db_path, &embedding_profiles)
+        });
+        match sweep {
+            Ok(sweep) if !sweep.reindexed.is_empty() || !sweep.pruned.is_empty() => tracing::info!(
+                "Consistency sweep of {workspace_path} re-indexed {} and pruned {} documents",
+                sweep.reindexed.len(),
+                sweep.pruned.len()
+            ),
+            Ok(_) => {}
+            Err(error) => {
+                tracing::error!("Consistency sweep failed for {workspace_path}: {error:#}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::SweepSettings;
+
+    #[test]
+    fn settings_fall_back_to_defaults_for_missing_fields() {
+        assert_eq!(
+            SweepSettings::from_value(&json!({})),
+            SweepSettings {
+                enabled: true,
+                interval: Duration::from_secs(6 * 60 * 60),
+            }
+        );
+        assert_eq!(
+            SweepSettings::from_value(&json!({ "enabled": false, "intervalMinutes": 30 })),
+            SweepSettings {
+                enabled: false,
+                interval: Duration::from_secs(30 * 60),
+            }
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::PathBuf;
+
+use mdit_logging::CrashSummary;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::app::logging::log_dir;
+use crate::path_scope::PathScope;
+
+const CRASH_REPORTING_SETTINGS_FILE: &str = "settings/crash_reporting.json";
+// Enough of the log to show what led up to the most recent crash.
+const REPORT_LOG_LINES: usize = 1000;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CrashReportingSettings {
+    /// The user agreed to export crash reports; off until they opt in.
+    pub share_reports: bool,
+}
+
+fn settings_path<R: Runtime>(app_handle: &AppHandle<R>) -> Option<PathBuf> {
+    crate::persistence::app_data_dir(app_handle)
+        .ok()
+        .map(|dir| dir.join(CRASH_REPORTING_SETTINGS_FILE))
+}
+
+fn load_crash_reporting_settings<R: Runtime>(app_handle: &AppHandle<R>) -> CrashReportingSettings {
+    settings_path(app_handle)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_crash_reporting_settings<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    settings: &CrashReportingSettings,
+) -> Result<(), String> {
+    let path = settings_path(app_handle)
+        .ok_or_else(|| "Failed to resolve app data directory".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|error| error.to_string())?;
+    fs::write(path, json).map_err(|error| error.to_string())
+}
+
+/// Records panics from every thread, async tasks and the local API server
+/// included, in the crash log. Runs right after logging starts.
+pub fn initialize<R: Runtime>(app_handle: &AppHandle<R>) {
+    if let Some(log_dir) = log_dir(app_handle) {
+        mdit_logging::install_panic_hook(&log_dir);
+    }
+}
+
+/// Vault folders and the home folder, replaced in exported reports so they
+/// do not reveal the user's name or where their notes live.
+fn redactions<R: Runtime>(app_handle: &AppHandle<R>) -> Vec<(PathBuf, String)> {
+    let mut redactions = crate::persistence::run_app_migrations(app_handle)
+        .ok()
+        .and_then(|db_path| app_storage::vault::list_workspaces(&db_path).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|workspace| (PathBuf::from(workspace), "<vault>".to_string()))
+        .collect::<Vec<_>>();
+    if let Ok(home_dir) = app_handle.path().home_dir() {
+        redactions.push((home_dir, "~".to_string()));
+    }
+    redactions
+}
+
+#[tauri::command]
+pub fn get_crash_reporting_settings_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> CrashReportingSettings {
+    load_crash_reporting_settings(&app_handle)
+}
+
+#[tauri::command]
+pub fn set_crash_report_sharing_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut settings = load_crash_reporting_settings(&app_handle);
+    settings.share_reports = enabled;
+    save_crash_reporting_settings(&app_handle, &settings)
+}
+
+/// Recorded crashes grouped by where they happened, most recent first.
+#[tauri::command]
+pub fn get_crash_summaries_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<CrashSummary>, String> {
+    let log_dir =
+        log_dir(&app_handle).ok_or_else(|| "Failed to resolve app data directory".to_string())?;
+    mdit_logging::crash_summaries(&log_dir)
+        .map_err(|error| format!("Failed to read crash log: {error}"))
+}
+
+/// Writes the crash log and the recent app log, with paths redacted, to
+/// `output_path`. Refused until the user opts in to sharing reports.
+#[tauri::command]
+pub fn export_crash_report_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    output_path: String,
+) -> Result<(), String> {
+    if !load_crash_reporting_settings(&app_handle).share_reports {
+        return Err("Crash report sharing is turned off".to_string());
+    }
+    let output_path = PathScope::load(&app_handle)?.resolve(&output_path)?;
+    let log_dir =
+        log_dir(&app_handle).ok_or_else(|| "Failed to resolve app data directory".to_string())?;
+    let report = mdit_logging::crash_report(&log_dir, REPORT_LOG_LINES, &redactions(&app_handle))
+        .map_err(|error| format!("Failed to read crash log: {error}"))?;
+    fs::write(output_path, report).map_err(|error| error.to_string())
+}
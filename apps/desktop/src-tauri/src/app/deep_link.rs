@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use mdit_local_api::CreateNoteInput;
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::app::vault_windows;
+
+pub const DEEP_LINK_SCHEME: &str = "mdit";
+pub const DEEP_LINK_EVENT: &str = "deep-link";
+
+/// A parsed `mdit://` link.
+///
+/// `vault` is a vault folder path or folder name; without it the most
+/// recently opened vault is used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    /// `mdit://open?vault=...&file=...`
+    Open { vault: Option<String>, file: String },
+    /// `mdit://new?vault=...&title=...&content=...`
+    New {
+        vault: Option<String>,
+        title: String,
+        content: Option<String>,
+    },
+    /// `mdit://search?vault=...&q=...`
+    Search {
+        vault: Option<String>,
+        query: String,
+    },
+}
+
+/// Work handed to the window holding the target vault. Queued until that
+/// window asks for it, since a window opened for the link is not listening yet.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DeepLinkWindowAction {
+    #[serde(rename_all = "camelCase")]
+    OpenFile {
+        workspace_path: String,
+        file_path: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Search {
+        workspace_path: String,
+        query: String,
+    },
+    /// An image or PDF opened from the OS; not a link action.
+    #[serde(rename_all = "camelCase")]
+    OpenAttachment {
+        workspace_path: String,
+        file_path: String,
+    },
+}
+
+#[derive(Default)]
+pub struct DeepLinkState {
+    pending: Mutex<HashMap<String, Vec<DeepLinkWindowAction>>>,
+}
+
+impl DeepLinkState {
+    fn push(&self, label: &str, action: DeepLinkWindowAction) {
+        self.pending
+            .lock()
+            .unwrap()
+            .entry(label.to_string())
+            .or_default()
+            .push(action);
+    }
+
+    fn take(&self, label: &str) -> Vec<DeepLinkWindowAction> {
+        self.pending
+            .lock()
+            .unwrap()
+            .remove(label)
+            .unwrap_or_default()
+    }
+}
+
+pub fn parse_deep_link(url: &tauri::Url) -> Result<DeepLink, String> {
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return Err(format!("Unsupported link: {url}"));
+    }
+
+    let params: HashMap<String, String> = url.query_pairs().into_owned().collect();
+    let param = |name: &str| {
+        params
+            .get(name)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let required = |name: &str| param(name).ok_or_else(|| format!("Missing `{name}` in {url}"));
+    let vault = param("vault");
+
+    match url.host_str().unwrap_or_default() {
+        "open" => Ok(DeepLink::Open {
+            vault,
+            file: required("file")?,
+        }),
+        "new" => Ok(DeepLink::New {
+            vault,
+            title: required("title")?,
+            content: params.get("content").cloned(),
+        }),
+        "search" => Ok(DeepLink::Search {
+            vault,
+            query: required("q")?,
+        }),
+        action => Err(format!("Unknown link action `{action}` in {url}")),
+    }
+}
+
+/// Whether command line arguments carry a link, which the deep-link plugin
+/// forwards on its own.
+pub fn has_deep_link_arg(args: &[String]) -> bool {
+    let prefix = format!("{DEEP_LINK_SCHEME}://");
+    args.iter().any(|arg| arg.starts_with(&prefix))
+}
+
+/// Matches `vault` against the known vaults by path, then by folder name.
+fn resolve_vault(workspaces: &[String], vault: Option<&str>) -> Result<String, String> {
+    let Some(vault) = vault else {
+        return workspaces
+            .first()
+            .cloned()
+            .ok_or_else(|| "Open a vault before using mdit:// links".to_string());
+    };
+
+    let by_path = workspaces
+        .iter()
+        .find(|workspace| Path::new(workspace) == Path::new(vault));
+    let by_name = || {
+        workspaces.iter().find(|workspace| {
+            Path::new(workspace)
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(vault))
+        })
+    };
+
+    match by_path.or_else(by_name) {
+        Some(workspace) => Ok(workspace.clone()),
+        None if Path::new(vault).is_absolute() && Path::new(vault).is_dir() => {
+            Ok(vault.to_string())
+        }
+        None => Err(format!("Unknown vault: {vault}")),
+    }
+}
+
+/// Resolves a vault-relative file, adding `.md` when the link omits it.
+fn resolve_vault_file(workspace_path: &str, file: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(file.trim_start_matches(['/', '\\']));
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(format!("Invalid file in link: {file}"));
+    }
+
+    let file_path = Path::new(workspace_path).join(relative);
+    if file_path.is_file() {
+        return Ok(file_path);
+    }
+
+    let with_extension = Path::new(workspace_path).join(format!("{}.md", relative.display()));
+    if with_extension.is_file() {
+        return Ok(with_extension);
+    }
+
+    Err(format!("File not found in vault: {file}"))
+}
+
+pub(crate) fn dispatch_to_vault_window(
+    app_handle: &tauri::AppHandle,
+    workspace_path: String,
+    action: DeepLinkWindowAction,
+) -> Result<(), String> {
+    let label = vault_windows::open_window_for_vault(app_handle, workspace_path)?;
+    app_handle.state::<DeepLinkState>().push(&label, action);
+    let _ = app_handle.emit_to(label.as_str(), DEEP_LINK_EVENT, ());
+    Ok(())
+}
+
+fn handle_deep_link(app_handle: &tauri::AppHandle, link: DeepLink) -> Result<(), String> {
+    let db_path = crate::persistence::run_app_migrations(app_handle)?;
+    let workspaces =
+        app_storage::vault::list_workspaces(&db_path).map_err(|error| error.to_string())?;
+
+    match link {
+        DeepLink::Open { vault, file } => {
+            let workspace_path = resolve_vault(&workspaces, vault.as_deref())?;
+            let file_path = resolve_vault_file(&workspace_path, &file)?;
+            dispatch_to_vault_window(
+                app_handle,
+                workspace_path.clone(),
+                DeepLinkWindowAction::OpenFile {
+                    workspace_path,
+                    file_path: file_path.to_string_lossy().to_string(),
+                },
+            )
+        }
+        DeepLink::New {
+            vault,
+            title,
+            content,
+        } => {
+            let workspace_path = resolve_vault(&workspaces, vault.as_deref())?;
+            app_storage::vault::touch_workspace(&db_path, Path::new(&workspace_path))
+                .map_err(|error| error.to_string())?;
+            let workspace =
+                app_storage::vault::find_workspace_by_path(&db_path, Path::new(&workspace_path))
+                    .map_err(|error| error.to_string())?
+                    .ok_or_else(|| format!("Unknown vault: {workspace_path}"))?;
+
+            let created = mdit_local_api::create_note(
+                &db_path,
+                CreateNoteInput {
+                    vault_id: workspace.id,
+                    directory_rel_path: None,
+                    title,
+                    content,
+                },
+            )
+            .map_err(|error| error.to_string())?;
+            dispatch_to_vault_window(
+                app_handle,
+                workspace_path.clone(),
+                DeepLinkWindowAction::OpenFile {
+                    workspace_path,
+                    file_path: created.absolute_path,
+                },
+            )
+        }
+        DeepLink::Search { vault, query } => {
+            let workspace_path = resolve_vault(&workspaces, vault.as_deref())?;
+            dispatch_to_vault_window(
+                app_handle,
+                workspace_path.clone(),
+                DeepLinkWindowAction::Search {
+                    workspace_path,
+                    query,
+                },
+            )
+        }
+    }
+}
+
+pub fn handle_deep_link_urls(app_handle: &tauri::AppHandle, urls: Vec<tauri::Url>) {
+    for url in urls {
+        if let Err(error) =
+            parse_deep_link(&url).and_then(|link| handle_deep_link(app_handle, link))
+        {
+            tracing::error!("Failed to handle deep link: {error}");
+        }
+    }
+}
+
+/// Called from setup. Links arriving while the app runs come through
+/// `on_open_url`; on Windows and Linux the launch link is read separately.
+pub fn initialize(app_handle: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    app_handle.deep_link().register_all()?;
+
+    let handle = app_handle.clone();
+    app_handle.deep_link().on_open_url(move |event| {
+        handle_deep_link_urls(&handle, event.urls());
+    });
+
+    #[cfg(not(target_os = "macos"))]
+    if let Some(urls) = app_handle.deep_link().get_current()? {
+        handle_deep_link_urls(app_handle, urls);
+    }
+
+    Ok(())
+}
+
+/// Returns and clears the link actions queued for the calling window.
+#[tauri::command]
+pub fn take_pending_deep_links_command(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, DeepLinkState>,
+) -> Vec<DeepLinkWindowAction> {
+    state.take(window.label())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_deep_link, resolve_vault, DeepLink};
+
+    fn parse(url: &str) -> Result<DeepLink, String> {
+        parse_deep_link(&tauri::Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn parses_open_new_and_search_links() {
+        assert_eq!(
+            parse("mdit://open?vault=Work&file=Projects%2FPlan.md"),
+            Ok(DeepLink::Open {
+                vault: Some("Work".to_string()),
+                file: "Projects/Plan.md".to_string(),
+            })
+        );
+        assert_eq!(
+            parse("mdit://new?title=Call%20notes&content=-%20agenda"),
+            Ok(DeepLink::New {
+                vault: None,
+                title: "Call notes".to_string(),
+                content: Some("- agenda".to_string()),
+            })
+        );
+        assert_eq!(
+            parse("mdit://search?q=rust+async"),
+            Ok(DeepLink::Search {
+                vault: None,
+                query: "rust async".to_string(),
+            })
+        );
+        assert!(parse("mdit://open?vault=Work").is_err());
+        assert!(parse("mdit://delete?file=a.md").is_err());
+    }
+
+    #[test]
+    fn resolves_vaults_by_path_then_name() {
+        let workspaces = vec!["/vaults/work".to_string(), "/vaults/home".to_string()];
+
+        assert_eq!(resolve_vault(&workspaces, None), Ok("/vaults/work".into()));
+        assert_eq!(
+            resolve_vault(&workspaces, Some("/vaults/home")),
+            Ok("/vaults/home".into())
+        );
+        assert_eq!(
+            resolve_vault(&workspaces, Some("HOME")),
+            Ok("/vaults/home".into())
+        );
+        assert!(resolve_vault(&workspaces, Some("missing")).is_err());
+        assert!(resolve_vault(&[], None).is_err());
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::commands::feeds::{emit_feeds_refreshed, refresh_feeds};
+use crate::commands::vault_indexing::index_run_guard;
+use crate::commands::vault_watch::VaultWatchRuntimeState;
+
+const TICK: Duration = Duration::from_secs(5 * 60);
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Starts the worker that refreshes the feeds of watched vaults every hour.
+pub fn initialize<R: Runtime>(app_handle: &AppHandle<R>) {
+    let app_handle = app_handle.clone();
+    thread::spawn(move || {
+        let mut last_refreshed = HashMap::new();
+        loop {
+            thread::sleep(TICK);
+            run_refresh_pass(&app_handle, &mut last_refreshed);
+        }
+    });
+}
+
+fn run_refresh_pass<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    last_refreshed: &mut HashMap<String, Instant>,
+) {
+    let Ok(db_path) = crate::persistence::run_app_migrations(app_handle) else {
+        return;
+    };
+    let run_guard = index_run_guard(app_handle);
+    let workspaces = app_handle
+        .state::<VaultWatchRuntimeState>()
+        .watched_workspaces();
+
+    for workspace_path in workspaces {
+        let due = last_refreshed
+            .get(&workspace_path)
+            .is_none_or(|refreshed| refreshed.elapsed() >= REFRESH_INTERVAL);
+        if !due {
+            continue;
+        }
+        // Failures are retried at the next interval rather than every tick.
+        last_refreshed.insert(workspace_path.clone(), Instant::now());
+
+        match refresh_feeds(&run_guard, &db_path, Path::new(&workspace_path), None) {
+            Ok(refreshes) => emit_feeds_refreshed(app_handle, workspace_path, &refreshes),
+            Err(error) => tracing::error!("Feed refresh failed for {workspace_path}: {error}"),
+        }
+    }
+}
@@ -1,16 +1,70 @@
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use tauri::Manager;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons};
 
-#[cfg(not(target_os = "macos"))]
-use std::path::PathBuf;
+use crate::app::deep_link::{self, DeepLinkWindowAction};
+use crate::app::{background_mode, vault_windows};
+
+const ATTACHMENT_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "avif", "svg", "bmp", "pdf",
+];
+const ATTACHMENT_FOLDER_SETTING: &str = "attachmentFolder";
 
 #[derive(Default)]
 pub struct AppState {
     pub opened_files: Mutex<Vec<String>>,
+    /// Folders and attachments passed at launch, handled once the app is ready.
+    pub opened_other_paths: Mutex<Vec<PathBuf>>,
     pub suppress_next_main_show: Mutex<bool>,
     pub next_edit_window_id: Mutex<u64>,
 }
 
+/// What an opened or dropped path is, by how the app handles it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum OpenedPath {
+    Note(PathBuf),
+    Folder(PathBuf),
+    Attachment(PathBuf),
+}
+
+fn classify_opened_path(path: &Path) -> Option<OpenedPath> {
+    if path.is_dir() {
+        return Some(OpenedPath::Folder(path.to_path_buf()));
+    }
+    if !path.is_file() {
+        return None;
+    }
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())?;
+    if extension == "md" {
+        Some(OpenedPath::Note(path.to_path_buf()))
+    } else if ATTACHMENT_EXTENSIONS.contains(&extension.as_str()) {
+        Some(OpenedPath::Attachment(path.to_path_buf()))
+    } else {
+        None
+    }
+}
+
+/// Splits opened paths into notes, which open in editor windows, and the
+/// folders and attachments handled by [`open_other_paths`].
+fn partition_opened_paths(paths: &[PathBuf]) -> (Vec<String>, Vec<PathBuf>) {
+    let mut notes = Vec::new();
+    let mut others = Vec::new();
+
+    for opened in paths.iter().filter_map(|path| classify_opened_path(path)) {
+        match opened {
+            OpenedPath::Note(path) => notes.push(path.to_string_lossy().to_string()),
+            OpenedPath::Folder(path) | OpenedPath::Attachment(path) => others.push(path),
+        }
+    }
+
+    (notes, others)
+}
+
 impl AppState {
     pub fn mark_suppress_next_main_show(&self) {
         let mut suppress = self.suppress_next_main_show.lock().unwrap();
@@ -49,24 +103,19 @@ fn initialize_opened_files_with_paths(app_state: &AppState, file_paths: Vec<Stri
 
 /// Initializes opened_files when the app starts.
 pub fn initialize_opened_files(app_state: &AppState) {
-    let file_paths = get_opened_files_from_args();
+    let (file_paths, other_paths) = partition_opened_paths(&get_opened_paths_from_args());
     initialize_opened_files_with_paths(app_state, file_paths);
+    *app_state.opened_other_paths.lock().unwrap() = other_paths;
 }
 
 #[cfg(not(target_os = "macos"))]
 pub fn handle_single_instance_args(app_handle: &tauri::AppHandle, args: &[String]) -> bool {
-    let file_paths = get_opened_files_from_args_list(args.iter());
-    if file_paths.is_empty() {
+    let paths = get_opened_paths_from_args_list(args.iter());
+    if paths.is_empty() {
         return false;
     }
 
-    let state = app_handle.state::<AppState>();
-    let mut opened_files = state.opened_files.lock().unwrap();
-    *opened_files = file_paths.clone();
-    drop(opened_files);
-    state.mark_suppress_next_main_show();
-    drop(state);
-    open_edit_windows(app_handle, &file_paths);
+    open_paths(app_handle, &paths);
     true
 }
 
@@ -91,8 +140,8 @@ fn open_edit_window(app_handle: &tauri::AppHandle, file_path: &str) {
         config.transparent = false;
         config.window_effects = None;
 
-        tauri::WebviewWindowBuilder::from_config(app_handle, &config)
-            .ok()?
+        let builder = tauri::WebviewWindowBuilder::from_config(app_handle, &config).ok()?;
+        crate::persistence::with_webview_data(builder, app_handle)
             .build()
             .ok()
     })();
@@ -109,27 +158,256 @@ fn open_edit_windows(app_handle: &tauri::AppHandle, file_paths: &[String]) {
     }
 }
 
+/// Hands files inside an open vault to the window holding it and opens edit
+/// windows for the rest.
+fn open_files(app_handle: &tauri::AppHandle, file_paths: Vec<String>) {
+    let file_paths = vault_windows::route_files_to_vault_windows(app_handle, &file_paths);
+    if file_paths.is_empty() {
+        return;
+    }
+
+    let state = app_handle.state::<AppState>();
+    let mut opened_files = state.opened_files.lock().unwrap();
+    *opened_files = file_paths.clone();
+    drop(opened_files);
+    state.mark_suppress_next_main_show();
+    drop(state);
+    open_edit_windows(app_handle, &file_paths);
+}
+
+fn open_paths(app_handle: &tauri::AppHandle, paths: &[PathBuf]) {
+    let (file_paths, other_paths) = partition_opened_paths(paths);
+    if !file_paths.is_empty() {
+        open_files(app_handle, file_paths);
+    }
+    open_other_paths(app_handle, other_paths);
+}
+
+/// Handles folders and attachments passed at launch.
+pub fn open_pending_other_paths(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let other_paths = std::mem::take(&mut *state.opened_other_paths.lock().unwrap());
+    drop(state);
+    open_other_paths(app_handle, other_paths);
+}
+
+fn open_other_paths(app_handle: &tauri::AppHandle, paths: Vec<PathBuf>) {
+    let workspaces = crate::persistence::run_app_migrations(app_handle).and_then(|db_path| {
+        app_storage::vault::list_workspaces(&db_path).map_err(|error| error.to_string())
+    });
+    let workspaces = match workspaces {
+        Ok(workspaces) => workspaces,
+        Err(error) => {
+            tracing::error!("Failed to load vaults for opened paths: {error}");
+            return;
+        }
+    };
+
+    for path in paths {
+        let result = match classify_opened_path(&path) {
+            Some(OpenedPath::Folder(folder)) => {
+                open_folder(app_handle, &workspaces, folder);
+                Ok(())
+            }
+            Some(OpenedPath::Attachment(file)) => open_attachment(app_handle, &workspaces, file),
+            _ => Ok(()),
+        };
+        if let Err(error) = result {
+            tracing::error!("Failed to open {}: {error}", path.display());
+        }
+    }
+}
+
+fn open_vault(app_handle: &tauri::AppHandle, workspace_path: &Path) -> Result<(), String> {
+    let db_path = crate::persistence::run_app_migrations(app_handle)?;
+    app_storage::vault::touch_workspace(&db_path, workspace_path)
+        .map_err(|error| error.to_string())?;
+    vault_windows::open_window_for_vault(app_handle, workspace_path.to_string_lossy().to_string())
+        .map(|_| ())
+}
+
+/// Opens a known vault right away; any other folder is registered as a vault
+/// only after the user confirms.
+fn open_folder(app_handle: &tauri::AppHandle, workspaces: &[String], folder: PathBuf) {
+    if workspaces
+        .iter()
+        .any(|workspace| Path::new(workspace) == folder)
+    {
+        if let Err(error) = open_vault(app_handle, &folder) {
+            tracing::error!("Failed to open vault {}: {error}", folder.display());
+        }
+        return;
+    }
+
+    let name = folder
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| folder.to_string_lossy().to_string());
+    let handle = app_handle.clone();
+    app_handle
+        .dialog()
+        .message(format!("Open \"{name}\" as a vault in Mdit?"))
+        .title("Open Folder")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Open Vault".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |confirmed| {
+            if !confirmed {
+                return;
+            }
+            if let Err(error) = open_vault(&handle, &folder) {
+                tracing::error!("Failed to open vault {}: {error}", folder.display());
+            }
+        });
+}
+
+/// Innermost known vault containing `path`.
+fn containing_vault<'a>(workspaces: &'a [String], path: &Path) -> Option<&'a String> {
+    workspaces
+        .iter()
+        .filter(|workspace| path.starts_with(workspace))
+        .max_by_key(|workspace| workspace.len())
+}
+
+/// Shows an attachment in the vault containing it, or offers to copy it into
+/// the attachment folder of the current vault.
+fn open_attachment(
+    app_handle: &tauri::AppHandle,
+    workspaces: &[String],
+    file: PathBuf,
+) -> Result<(), String> {
+    if let Some(workspace_path) = containing_vault(workspaces, &file) {
+        return deep_link::dispatch_to_vault_window(
+            app_handle,
+            workspace_path.clone(),
+            DeepLinkWindowAction::OpenAttachment {
+                workspace_path: workspace_path.clone(),
+                file_path: file.to_string_lossy().to_string(),
+            },
+        );
+    }
+
+    let Some(workspace_path) = current_vault(app_handle, workspaces) else {
+        return Err("no vault to import the file into".to_string());
+    };
+    let vault_name = Path::new(&workspace_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| workspace_path.clone());
+    let file_name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let handle = app_handle.clone();
+    app_handle
+        .dialog()
+        .message(format!(
+            "\"{file_name}\" is not in a vault. Import a copy into \"{vault_name}\"?"
+        ))
+        .title("Import File")
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Import".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |confirmed| {
+            if !confirmed {
+                return;
+            }
+            let result = import_attachment(&handle, &workspace_path, &file).and_then(|imported| {
+                deep_link::dispatch_to_vault_window(
+                    &handle,
+                    workspace_path.clone(),
+                    DeepLinkWindowAction::OpenAttachment {
+                        workspace_path: workspace_path.clone(),
+                        file_path: imported.to_string_lossy().to_string(),
+                    },
+                )
+            });
+            if let Err(error) = result {
+                tracing::error!("Failed to import {}: {error}", file.display());
+            }
+        });
+
+    Ok(())
+}
+
+/// The vault shown in the main window, or else the most recently opened one.
+fn current_vault(app_handle: &tauri::AppHandle, workspaces: &[String]) -> Option<String> {
+    vault_windows::window_vault(app_handle, background_mode::MAIN_WINDOW_LABEL)
+        .or_else(|| workspaces.first().cloned())
+}
+
+fn import_attachment(
+    app_handle: &tauri::AppHandle,
+    workspace_path: &str,
+    file: &Path,
+) -> Result<PathBuf, String> {
+    let db_path = crate::persistence::run_app_migrations(app_handle)?;
+    let folder = attachment_folder(&db_path, Path::new(workspace_path))?;
+
+    let target_dir = Path::new(workspace_path).join(folder);
+    fs::create_dir_all(&target_dir).map_err(|error| error.to_string())?;
+    let file_name = file
+        .file_name()
+        .ok_or_else(|| format!("invalid file: {}", file.display()))?;
+    let destination = unique_destination(&target_dir, &file_name.to_string_lossy());
+    fs::copy(file, &destination).map_err(|error| error.to_string())?;
+    Ok(destination)
+}
+
+/// The vault's attachment folder setting, relative to the vault root.
+pub(crate) fn attachment_folder(db_path: &Path, workspace_path: &Path) -> Result<String, String> {
+    let setting = app_storage::vault_settings::get_vault_setting(
+        db_path,
+        workspace_path,
+        ATTACHMENT_FOLDER_SETTING,
+    )
+    .map_err(|error| error.to_string())?;
+    let folder = setting.value.as_str().unwrap_or_default().trim();
+    if Path::new(folder)
+        .components()
+        .any(|component| !matches!(component, std::path::Component::Normal(_)))
+    {
+        return Err(format!("invalid attachment folder: {folder}"));
+    }
+    Ok(folder.to_string())
+}
+
+/// `dir/name`, or `dir/name 1.ext`, `dir/name 2.ext`, ... when taken.
+fn unique_destination(dir: &Path, file_name: &str) -> PathBuf {
+    let candidate = dir.join(file_name);
+    if !candidate.exists() {
+        return candidate;
+    }
+
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..)
+        .map(|index| dir.join(format!("{stem} {index}{extension}")))
+        .find(|candidate| !candidate.exists())
+        .expect("an unused file name exists")
+}
+
 /// Handles the RunEvent::Opened event on macOS.
 #[cfg(target_os = "macos")]
 pub fn handle_opened_event(app_handle: &tauri::AppHandle, urls: Vec<tauri::Url>) {
-    let file_paths: Vec<String> = urls
-        .iter()
-        .filter_map(|u| u.to_file_path().ok())
-        .map(|p| p.to_string_lossy().to_string())
-        .collect();
+    let paths: Vec<PathBuf> = urls.iter().filter_map(|u| u.to_file_path().ok()).collect();
 
-    if file_paths.is_empty() {
+    if paths.is_empty() {
         return;
     }
 
-    {
-        let state = app_handle.state::<AppState>();
-        let mut opened_files = state.opened_files.lock().unwrap();
-        *opened_files = file_paths.clone();
-        drop(opened_files);
-        state.mark_suppress_next_main_show();
-        open_edit_windows(app_handle, &file_paths);
-    }
+    open_paths(app_handle, &paths);
 }
 
 /// Opens the edit window if there are files in opened_files (for non-macOS platforms).
@@ -143,38 +421,39 @@ pub fn open_edit_window_if_files_exist(app_handle: &tauri::AppHandle) {
     }
 }
 
-/// Collects files passed as command line arguments on non-macOS platforms (Windows, Linux, etc.).
+/// Collects paths passed as command line arguments on non-macOS platforms (Windows, Linux, etc.).
 #[cfg(not(target_os = "macos"))]
-fn get_opened_files_from_args() -> Vec<String> {
+fn get_opened_paths_from_args() -> Vec<PathBuf> {
     let args: Vec<String> = std::env::args().collect();
-    get_opened_files_from_args_list(args.iter().skip(1))
+    get_opened_paths_from_args_list(args.iter().skip(1))
 }
 
 #[cfg(not(target_os = "macos"))]
-fn get_opened_files_from_args_list<'a, I>(args: I) -> Vec<String>
+fn get_opened_paths_from_args_list<'a, I>(args: I) -> Vec<PathBuf>
 where
     I: Iterator<Item = &'a String>,
 {
-    args.filter_map(|arg| {
-        let path = PathBuf::from(arg);
-        if path.exists() && path.is_file() && path.extension().map_or(false, |ext| ext == "md") {
-            Some(path.to_string_lossy().to_string())
-        } else {
-            None
-        }
-    })
-    .collect()
+    crate::persistence::profile::without_profile_args(args)
+        .into_iter()
+        .map(PathBuf::from)
+        .filter(|path| classify_opened_path(path).is_some())
+        .collect()
 }
 
 /// Returns an empty vector on macOS (uses RunEvent::Opened instead).
 #[cfg(target_os = "macos")]
-fn get_opened_files_from_args() -> Vec<String> {
+fn get_opened_paths_from_args() -> Vec<PathBuf> {
     Vec::new()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{initialize_opened_files_with_paths, AppState};
+    use std::fs;
+
+    use super::{
+        classify_opened_path, initialize_opened_files_with_paths, unique_destination, AppState,
+        OpenedPath,
+    };
 
     #[test]
     fn suppress_flag_is_consumed_once() {
@@ -207,4 +486,40 @@ mod tests {
         assert_eq!(state.next_edit_window_label(), "edit-0");
         assert_eq!(state.next_edit_window_label(), "edit-1");
     }
+
+    #[test]
+    fn classifies_notes_folders_and_attachments() {
+        let dir = std::env::temp_dir().join(format!("mdit-opened-paths-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["note.md", "photo.PNG", "paper.pdf", "data.csv"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        assert_eq!(
+            classify_opened_path(&dir),
+            Some(OpenedPath::Folder(dir.clone()))
+        );
+        assert_eq!(
+            classify_opened_path(&dir.join("note.md")),
+            Some(OpenedPath::Note(dir.join("note.md")))
+        );
+        assert_eq!(
+            classify_opened_path(&dir.join("photo.PNG")),
+            Some(OpenedPath::Attachment(dir.join("photo.PNG")))
+        );
+        assert_eq!(
+            classify_opened_path(&dir.join("paper.pdf")),
+            Some(OpenedPath::Attachment(dir.join("paper.pdf")))
+        );
+        assert_eq!(classify_opened_path(&dir.join("data.csv")), None);
+        assert_eq!(classify_opened_path(&dir.join("missing.md")), None);
+
+        assert_eq!(unique_destination(&dir, "image.png"), dir.join("image.png"));
+        assert_eq!(
+            unique_destination(&dir, "photo.PNG"),
+            dir.join("photo 1.PNG")
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use mdit_vault_indexing::{GraphDelta, GraphTracker};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, WebviewWindow};
+
+use crate::commands::vault_indexing::{index_run_guard, run_blocking};
+
+pub const GRAPH_DELTA_EVENT: &str = "graph-delta";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphDeltaPayload {
+    workspace_path: String,
+    delta: GraphDelta,
+}
+
+/// Windows following a vault's graph, and the index write each vault's graph
+/// was last loaded after.
+#[derive(Default)]
+pub struct GraphUpdateState {
+    tracker: GraphTracker,
+    subscriptions: Mutex<HashMap<String, String>>,
+    loaded_generations: Mutex<HashMap<String, u64>>,
+}
+
+impl GraphUpdateState {
+    fn subscribe(&self, window_label: &str, workspace_path: &str) {
+        let previous = match self.subscriptions.lock() {
+            Ok(mut subscriptions) => {
+                subscriptions.insert(window_label.to_string(), workspace_path.to_string())
+            }
+            Err(_) => return,
+        };
+        if let Some(previous) = previous.filter(|previous| previous != workspace_path) {
+            self.forget_unfollowed(&previous);
+        }
+    }
+
+    fn unsubscribe(&self, window_label: &str) {
+        let removed = match self.subscriptions.lock() {
+            Ok(mut subscriptions) => subscriptions.remove(window_label),
+            Err(_) => return,
+        };
+        if let Some(workspace_path) = removed {
+            self.forget_unfollowed(&workspace_path);
+        }
+    }
+
+    /// Drops the tracked graph once no window follows the vault.
+    fn forget_unfollowed(&self, workspace_path: &str) {
+        let followed = self.subscriptions.lock().is_ok_and(|subscriptions| {
+            subscriptions
+                .values()
+                .any(|subscribed| subscribed == workspace_path)
+        });
+        if followed {
+            return;
+        }
+        self.tracker.forget(Path::new(workspace_path));
+        if let Ok(mut generations) = self.loaded_generations.lock() {
+            generations.remove(workspace_path);
+        }
+    }
+
+    /// Records `generation` and returns whether it differs from the last one.
+    fn note_generation(&self, workspace_path: &str, generation: u64) -> bool {
+        let Ok(mut generations) = self.loaded_generations.lock() else {
+            return false;
+        };
+        generations.insert(workspace_path.to_string(), generation) != Some(generation)
+    }
+
+    fn subscribers_by_workspace(&self) -> HashMap<String, Vec<String>> {
+        let mut by_workspace: HashMap<String, Vec<String>> = HashMap::new();
+        if let Ok(subscriptions) = self.subscriptions.lock() {
+            for (label, workspace_path) in subscriptions.iter() {
+                by_workspace
+                    .entry(workspace_path.clone())
+                    .or_default()
+                    .push(label.clone());
+            }
+        }
+        by_workspace
+    }
+}
+
+/// Returns what changed in the graph since `since_version`, or the whole graph
+/// when no version is given. The calling window then receives a
+/// `graph-delta` event whenever indexing changes the graph.
+#[tauri::command]
+pub async fn get_graph_delta_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+    state: State<'_, GraphUpdateState>,
+    workspace_path: String,
+    since_version: Option<u64>,
+) -> Result<GraphDelta, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    state.subscribe(window.label(), &workspace_path);
+    // Read before loading, so a write that lands during the load is picked
+    // up by the next poll.
+    let generation = index_run_guard(&app_handle).write_generation(Path::new(&workspace_path));
+    state.note_generation(&workspace_path, generation);
+
+    let tracker = state.tracker.clone();
+    let workspace_root = PathBuf::from(workspace_path);
+    run_blocking(move || tracker.delta_since(&workspace_root, &db_path, since_version)).await
+}
+
+#[tauri::command]
+pub fn unsubscribe_graph_deltas_command<R: Runtime>(
+    window: WebviewWindow<R>,
+    state: State<'_, GraphUpdateState>,
+) {
+    state.unsubscribe(window.label());
+}
+
+/// Stops graph updates for a destroyed window.
+pub fn unsubscribe_window<R: Runtime>(app_handle: &AppHandle<R>, window_label: &str) {
+    app_handle
+        .state::<GraphUpdateState>()
+        .unsubscribe(window_label);
+}
+
+/// Starts the worker that pushes graph deltas to subscribed windows after the
+/// index of their vault is written.
+pub fn initialize<R: Runtime>(app_handle: &AppHandle<R>) {
+    let app_handle = app_handle.clone();
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+        publish_changed_graphs(&app_handle);
+    });
+}
+
+fn publish_changed_graphs<R: Runtime>(app_handle: &AppHandle<R>) {
+    let state = app_handle.state::<GraphUpdateState>();
+    let subscribers = state.subscribers_by_workspace();
+    if subscribers.is_empty() {
+        return;
+    }
+    let Ok(db_path) = crate::persistence::run_app_migrations(app_handle) else {
+        return;
+    };
+    let run_guard = index_run_guard(app_handle);
+
+    for (workspace_path, labels) in subscribers {
+        let workspace_root = Path::new(&workspace_path);
+        if !state.note_generation(&workspace_path, run_guard.write_generation(workspace_root)) {
+            continue;
+        }
+
+        let delta = match state.tracker.refresh(workspace_root, &db_path) {
+            Ok(Some(delta)) => delta,
+            Ok(None) => continue,
+            Err(error) => {
+                tracing::error!("Failed to refresh graph for {workspace_path}: {error:#}");
+                continue;
+            }
+        };
+        let payload = GraphDeltaPayload {
+            workspace_path: workspace_path.clone(),
+            delta,
+        };
+        for label in labels {
+            let _ = app_handle.emit_to(label.as_str(), GRAPH_DELTA_EVENT, payload.clone());
+        }
+    }
+}
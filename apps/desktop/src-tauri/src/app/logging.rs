@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::PathBuf;
+
+use mdit_logging::LogLevel;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+const LOG_DIR: &str = "logs";
+const LOGGING_SETTINGS_FILE: &str = "settings/logging.json";
+const DEFAULT_RECENT_LINES: usize = 500;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LoggingSettings {
+    pub level: LogLevel,
+}
+
+fn app_data_path<R: Runtime>(app_handle: &AppHandle<R>, rel_path: &str) -> Option<PathBuf> {
+    crate::persistence::app_data_dir(app_handle)
+        .ok()
+        .map(|dir| dir.join(rel_path))
+}
+
+/// Where the app log and the crash log are written.
+pub(crate) fn log_dir<R: Runtime>(app_handle: &AppHandle<R>) -> Option<PathBuf> {
+    app_data_path(app_handle, LOG_DIR)
+}
+
+fn load_logging_settings<R: Runtime>(app_handle: &AppHandle<R>) -> LoggingSettings {
+    app_data_path(app_handle, LOGGING_SETTINGS_FILE)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_logging_settings<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    settings: &LoggingSettings,
+) -> Result<(), String> {
+    let path = app_data_path(app_handle, LOGGING_SETTINGS_FILE)
+        .ok_or_else(|| "Failed to resolve app data directory".to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(settings).map_err(|error| error.to_string())?;
+    fs::write(path, json).map_err(|error| error.to_string())
+}
+
+/// Sends `tracing` events to the rotating log in the app data directory at
+/// the saved level. Runs before the other workers so their start-up is logged.
+pub fn initialize<R: Runtime>(app_handle: &AppHandle<R>) {
+    let Some(log_dir) = log_dir(app_handle) else {
+        return;
+    };
+    let settings = load_logging_settings(app_handle);
+    if let Err(error) = mdit_logging::init(&log_dir, settings.level) {
+        eprintln!("Failed to start logging: {error}");
+    }
+}
+
+/// The last lines of the log, oldest first, for attaching to bug reports.
+#[tauri::command]
+pub fn get_recent_logs_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    max_lines: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let log_dir =
+        log_dir(&app_handle).ok_or_else(|| "Failed to resolve app data directory".to_string())?;
+    mdit_logging::recent_logs(&log_dir, max_lines.unwrap_or(DEFAULT_RECENT_LINES))
+        .map_err(|error| format!("Failed to read logs: {error}"))
+}
+
+#[tauri::command]
+pub fn get_log_level_command<R: Runtime>(app_handle: AppHandle<R>) -> LogLevel {
+    load_logging_settings(&app_handle).level
+}
+
+/// Saves the level and applies it right away.
+#[tauri::command]
+pub fn set_log_level_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    level: LogLevel,
+) -> Result<(), String> {
+    let mut settings = load_logging_settings(&app_handle);
+    settings.level = level;
+    save_logging_settings(&app_handle, &settings)?;
+    mdit_logging::set_level(level);
+    tracing::info!(?level, "log level changed");
+    Ok(())
+}
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use mdit_vault_indexing::{run_maintenance_batch, IndexRunGuard, MaintenanceTask};
+use serde_json::Value as JsonValue;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::commands::vault_indexing::{index_run_guard, resolve_embedding_for_workspace};
+use crate::commands::vault_watch::VaultWatchRuntimeState;
+
+pub const MAINTENANCE_SETTING: &str = "maintenance";
+
+const TICK: Duration = Duration::from_secs(30);
+const BATCH_SIZE: usize = 25;
+// After each batch the worker sleeps this many times as long as the batch
+// took, keeping maintenance to a quarter of one core.
+const THROTTLE_FACTOR: u32 = 3;
+
+/// Tracks when the user last did something and which tasks ran recently.
+pub struct MaintenanceState {
+    last_activity: Mutex<Instant>,
+    last_completed: Mutex<HashMap<(String, MaintenanceTask), Instant>>,
+}
+
+impl Default for MaintenanceState {
+    fn default() -> Self {
+        Self {
+            last_activity: Mutex::new(Instant::now()),
+            last_completed: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl MaintenanceState {
+    /// Called for every command the frontend invokes.
+    pub fn record_activity(&self) {
+        if let Ok(mut last_activity) = self.last_activity.lock() {
+            *last_activity = Instant::now();
+        }
+    }
+
+    fn is_idle(&self, idle: Duration) -> bool {
+        self.last_activity
+            .lock()
+            .map(|last_activity| last_activity.elapsed() >= idle)
+            .unwrap_or(false)
+    }
+
+    fn is_due(&self, workspace_path: &str, task: MaintenanceTask) -> bool {
+        let Ok(last_completed) = self.last_completed.lock() else {
+            return false;
+        };
+        last_completed
+            .get(&(workspace_path.to_string(), task))
+            .is_none_or(|completed| completed.elapsed() >= task_interval(task))
+    }
+
+    /// When a task last finished for the vault, if one has since the app started.
+    pub fn last_completed_at(&self, workspace_path: &str) -> Option<SystemTime> {
+        let last_completed = self.last_completed.lock().ok()?;
+        let elapsed = last_completed
+            .iter()
+            .filter(|((path, _), _)| path == workspace_path)
+            .map(|(_, completed)| completed.elapsed())
+            .min()?;
+        SystemTime::now().checked_sub(elapsed)
+    }
+
+    fn mark_completed(&self, workspace_path: &str, task: MaintenanceTask) {
+        if let Ok(mut last_completed) = self.last_completed.lock() {
+            last_completed.insert((workspace_path.to_string(), task), Instant::now());
+        }
+    }
+}
+
+fn task_interval(task: MaintenanceTask) -> Duration {
+    match task {
+        MaintenanceTask::Vacuum => Duration::from_secs(24 * 60 * 60),
+        _ => Duration::from_secs(60 * 60),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MaintenanceSettings {
+    enabled: bool,
+    only_on_ac_power: bool,
+    idle: Duration,
+}
+
+impl MaintenanceSettings {
+    fn from_value(value: &JsonValue) -> Self {
+        let flag = |name: &str| value.get(name).and_then(JsonValue::as_bool);
+        Self {
+            enabled: flag("enabled").unwrap_or(true),
+            only_on_ac_power: flag("onlyOnAcPower").unwrap_or(true),
+            idle: Duration::from_secs(
+                value
+                    .get("idleSeconds")
+                    .and_then(JsonValue::as_u64)
+                    .unwrap_or(300),
+            ),
+        }
+    }
+
+    fn load(db_path: &Path, workspace_path: &str) -> Option<Self> {
+        app_storage::vault_settings::get_vault_setting(
+            db_path,
+            Path::new(workspace_path),
+            MAINTENANCE_SETTING,
+        )
+        .map(|setting| Self::from_value(&setting.value))
+        .map_err(|error| tracing::error!("Failed to load maintenance settings: {error}"))
+        .ok()
+    }
+}
+
+/// Starts the worker that runs index maintenance for watched vaults once the
+/// app has been idle long enough.
+pub fn initialize<R: Runtime>(app_handle: &AppHandle<R>) {
+    let app_handle = app_handle.clone();
+    thread::spawn(move || loop {
+        thread::sleep(TICK);
+        run_idle_pass(&app_handle);
+    });
+}
+
+fn run_idle_pass<R: Runtime>(app_handle: &AppHandle<R>) {
+    let Ok(db_path) = crate::persistence::run_app_migrations(app_handle) else {
+        return;
+    };
+    let state = app_handle.state::<MaintenanceState>();
+    let run_guard = index_run_guard(app_handle);
+    let workspaces = app_handle
+        .state::<VaultWatchRuntimeState>()
+        .watched_workspaces();
+
+    for workspace_path in workspaces {
+        let Some(settings) = MaintenanceSettings::load(&db_path, &workspace_path) else {
+            continue;
+        };
+        if !settings.enabled
+            || !state.is_idle(settings.idle)
+            || (settings.only_on_ac_power && !on_ac_power())
+        {
+            continue;
+        }
+
+        let embedding_profiles =
+            match resolve_embedding_for_workspace(&db_path, Path::new(&workspace_path)) {
+                Ok(profiles) => profiles,
+                Err(error) => {
+                    tracing::error!("Failed to resolve embedding for maintenance: {error}");
+                    Vec::new()
+                }
+            };
+        run_vault_maintenance(
+            &state,
+            &run_guard,
+            &db_path,
+            &workspace_path,
+            &embedding_profiles,
+            settings.idle,
+        );
+    }
+}
+
+/// Works through the due tasks batch by batch, giving up the index between
+/// batches and stopping as soon as the user is active again.
+fn run_vault_maintenance(
+    state: &MaintenanceState,
+    run_guard: &IndexRunGuard,
+    db_path: &Path,
+    workspace_path: &str,
+    embedding_profiles: &[mdit_vault_indexing::EmbeddingProfile],
+    idle: Duration,
+) {
+    let workspace_root = Path::new(workspace_path);
+    for task in MaintenanceTask::ALL {
+        if !state.is_due(workspace_path, task) {
+            continue;
+        }
+
+        loop {
+            if !state.is_idle(idle) {
+                return;
+            }
+
+            let started = Instant::now();
+            let batch = run_guard.run_exclusive(workspace_root, || {
+                run_maintenance_batch(
+                    workspace_root,
+                    db_path,
+                    task,
+                    embedding_profiles,
+                    BATCH_SIZE,
+                )
+            });
+            match batch {
+                Ok(batch) if !batch.done => thread::sleep(started.elapsed() * THROTTLE_FACTOR),
+                Ok(_) => {
+                    state.mark_completed(workspace_path, task);
+                    break;
+                }
+                Err(error) => {
+                    // Retried at the next interval rather than on every tick.
+                    tracing::error!(
+                        "Index maintenance {task:?} failed for {workspace_path}: {error:#}"
+                    );
+                    state.mark_completed(workspace_path, task);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Whether the machine runs on mains power. Unknown states count as mains
+/// so desktops without a battery are never held back.
+#[cfg(target_os = "macos")]
+fn on_ac_power() -> bool {
+    std::process::Command::new("pmset")
+        .args(["-g", "ps"])
+        .output()
+        .map(|output| {
+            let output = String::from_utf8_lossy(&output.stdout);
+            !output.contains("Battery Power")
+        })
+        .unwrap_or(true)
+}
+
+#[cfg(target_os = "linux")]
+fn on_ac_power() -> bool {
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+        return true;
+    };
+    let read = |path: &Path, name: &str| {
+        std::fs::read_to_string(path.join(name))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mains = supplies
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| read(path, "type") == "Mains")
+        .collect::<Vec<_>>();
+    mains.is_empty() || mains.iter().any(|path| read(path, "online") == "1")
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn on_ac_power() -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::MaintenanceSettings;
+
+    #[test]
+    fn settings_fall_back_to_defaults_for_missing_fields() {
+        assert_eq!(
+            MaintenanceSettings::from_value(&json!({ "onlyOnAcPower": false })),
+            MaintenanceSettings {
+                enabled: true,
+                only_on_ac_power: false,
+                idle: Duration::from_secs(300),
+            }
+        );
+        assert_eq!(
+            MaintenanceSettings::from_value(&json!({ "enabled": false, "idleSeconds": 60 })),
+            MaintenanceSettings {
+                enabled: false,
+                only_on_ac_power: true,
+                idle: Duration::from_secs(60),
+            }
+        );
+    }
+}
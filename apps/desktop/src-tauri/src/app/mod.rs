@@ -1,2 +1,14 @@
+pub mod background_mode;
+pub mod consistency_sweep;
+pub mod crash_reporting;
+pub mod deep_link;
+pub mod feed_refresh;
 pub mod file_opening;
+pub mod graph_updates;
+pub mod logging;
+pub mod maintenance;
+pub mod quick_capture;
+pub mod spotlight;
+pub mod vault_windows;
+pub mod voice_transcription;
 pub mod window_lifecycle;
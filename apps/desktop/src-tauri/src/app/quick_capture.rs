@@ -0,0 +1,190 @@
+use std::{collections::BTreeMap, path::Path};
+
+use mdit_local_api::{
+    AppendNoteInput, AppendToInboxInput, AppendedNote, InboxEntry, INBOX_NOTE_REL_PATH,
+};
+use mdit_vault_indexing::format_daily_note_name;
+use serde::Deserialize;
+use tauri::Manager;
+
+pub const QUICK_CAPTURE_WINDOW_LABEL: &str = "quick_capture";
+const DAILY_NOTE_FORMAT_SETTING: &str = "dailyNoteFormat";
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuickCaptureTarget {
+    Inbox,
+    DailyNote,
+}
+
+fn build_quick_capture_window(
+    app_handle: &tauri::AppHandle,
+) -> tauri::Result<tauri::WebviewWindow> {
+    let builder = tauri::WebviewWindowBuilder::new(
+        app_handle,
+        QUICK_CAPTURE_WINDOW_LABEL,
+        tauri::WebviewUrl::App("/quick-capture".into()),
+    )
+    .title("Quick Capture")
+    .inner_size(560.0, 180.0)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .visible(false)
+    .center();
+    let window = crate::persistence::with_webview_data(builder, app_handle).build()?;
+
+    // Behave like a popover: clicking elsewhere dismisses it.
+    let hide_handle = window.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Focused(false) = event {
+            let _ = hide_handle.hide();
+        }
+    });
+
+    Ok(window)
+}
+
+fn hide_quick_capture_window(app_handle: &tauri::AppHandle) {
+    if let Some(window) = app_handle.get_webview_window(QUICK_CAPTURE_WINDOW_LABEL) {
+        let _ = window.hide();
+    }
+}
+
+/// Parses the `YYYY-MM-DD` local date sent by the capture window.
+fn parse_local_date(date: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = date.trim().splitn(3, '-').map(str::parse::<u32>);
+    let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) =
+        (parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+
+    ((1..=12).contains(&month) && (1..=31).contains(&day)).then_some((year, month, day))
+}
+
+fn daily_note_rel_path(
+    db_path: &Path,
+    workspace_root: &Path,
+    date: &str,
+) -> Result<String, String> {
+    let (year, month, day) =
+        parse_local_date(date).ok_or_else(|| format!("Invalid capture date: {date}"))?;
+    let format = daily_note_format(db_path, workspace_root)?;
+
+    Ok(format!(
+        "{}.md",
+        format_daily_note_name(&format, year, month, day)
+    ))
+}
+
+/// The vault's daily note format, such as `YYYY-MM-DD`.
+pub(crate) fn daily_note_format(db_path: &Path, workspace_root: &Path) -> Result<String, String> {
+    let setting = app_storage::vault_settings::get_vault_setting(
+        db_path,
+        workspace_root,
+        DAILY_NOTE_FORMAT_SETTING,
+    )
+    .map_err(|error| error.to_string())?;
+
+    Ok(setting.value.as_str().unwrap_or("YYYY-MM-DD").to_string())
+}
+
+#[tauri::command]
+pub fn toggle_quick_capture_window_command(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let window = match app_handle.get_webview_window(QUICK_CAPTURE_WINDOW_LABEL) {
+        Some(window) => window,
+        None => build_quick_capture_window(&app_handle).map_err(|error| error.to_string())?,
+    };
+
+    if window.is_visible().unwrap_or(false) {
+        window.hide().map_err(|error| error.to_string())
+    } else {
+        let _ = window.center();
+        window.show().map_err(|error| error.to_string())?;
+        window.set_focus().map_err(|error| error.to_string())
+    }
+}
+
+#[tauri::command]
+pub fn hide_quick_capture_window_command(app_handle: tauri::AppHandle) {
+    hide_quick_capture_window(&app_handle);
+}
+
+/// Appends `text` to the inbox or today's daily note of the most recently
+/// opened vault, then hides the capture window. The main window is left
+/// alone so focus returns to whatever the user was doing.
+#[tauri::command]
+pub fn quick_capture_command(
+    app_handle: tauri::AppHandle,
+    text: String,
+    target: QuickCaptureTarget,
+    date: String,
+) -> Result<AppendedNote, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace = most_recent_workspace(&db_path)?;
+
+    let relative_path = match target {
+        QuickCaptureTarget::Inbox => INBOX_NOTE_REL_PATH.to_string(),
+        QuickCaptureTarget::DailyNote => {
+            daily_note_rel_path(&db_path, Path::new(&workspace.workspace_root), &date)?
+        }
+    };
+
+    let appended = mdit_local_api::append_note(
+        &db_path,
+        AppendNoteInput {
+            vault_id: workspace.id,
+            relative_path,
+            content: text,
+        },
+    )
+    .map_err(|error| error.to_string())?;
+
+    hide_quick_capture_window(&app_handle);
+    Ok(appended)
+}
+
+/// Appends a timestamped entry to the inbox of the most recently opened
+/// vault. Entries written at the same time by other captures, agents or
+/// devices are kept.
+#[tauri::command]
+pub fn append_to_inbox_command(
+    app_handle: tauri::AppHandle,
+    text: String,
+    metadata: Option<BTreeMap<String, String>>,
+) -> Result<InboxEntry, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace = most_recent_workspace(&db_path)?;
+
+    mdit_local_api::append_to_inbox(
+        &db_path,
+        AppendToInboxInput {
+            vault_id: workspace.id,
+            text,
+            metadata: metadata.unwrap_or_default(),
+        },
+    )
+    .map_err(|error| error.to_string())
+}
+
+fn most_recent_workspace(db_path: &Path) -> Result<app_storage::vault::VaultWorkspace, String> {
+    app_storage::vault::list_workspaces_with_meta(db_path)
+        .map_err(|error| error.to_string())?
+        .into_iter()
+        .next()
+        .ok_or_else(|| "Open a vault before capturing notes".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_local_date;
+
+    #[test]
+    fn parses_only_valid_local_dates() {
+        assert_eq!(parse_local_date("2024-05-07"), Some((2024, 5, 7)));
+        assert_eq!(parse_local_date("2024-13-01"), None);
+        assert_eq!(parse_local_date("today"), None);
+    }
+}
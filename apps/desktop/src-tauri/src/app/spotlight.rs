@@ -0,0 +1,151 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread;
+
+use mdit_vault_watch::{VaultEntryKind, VaultEntryState, VaultWatchBatch, VaultWatchOp};
+use tauri::{AppHandle, Manager, Runtime};
+
+pub const SPOTLIGHT_METADATA_SETTING: &str = "spotlightMetadata";
+
+enum SpotlightJob {
+    /// Exports the notes at these vault-relative paths.
+    Notes {
+        root: PathBuf,
+        rel_paths: Vec<String>,
+    },
+    /// Exports every note under a folder.
+    Tree(PathBuf),
+    /// Removes the metadata from every note under a folder.
+    Clear(PathBuf),
+}
+
+/// Queue of the worker thread that writes Spotlight metadata. The thread is
+/// started on first use, so vaults that never opt in cost nothing.
+#[derive(Default)]
+pub struct SpotlightState {
+    sender: Mutex<Option<Sender<SpotlightJob>>>,
+}
+
+impl SpotlightState {
+    fn enqueue(&self, job: SpotlightJob) {
+        let Ok(mut sender) = self.sender.lock() else {
+            return;
+        };
+        let sender = sender.get_or_insert_with(|| {
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || {
+                for job in receiver {
+                    run_job(job);
+                }
+            });
+            sender
+        });
+        let _ = sender.send(job);
+    }
+}
+
+fn run_job(job: SpotlightJob) {
+    match job {
+        SpotlightJob::Notes { root, rel_paths } => {
+            for rel_path in rel_paths {
+                let path = root.join(&rel_path);
+                if let Err(error) = mdit_spotlight_export::export_note(&path) {
+                    tracing::error!("Failed to export Spotlight metadata: {error}");
+                }
+            }
+        }
+        SpotlightJob::Tree(dir) => {
+            mdit_spotlight_export::export_vault(&dir);
+        }
+        SpotlightJob::Clear(dir) => mdit_spotlight_export::clear_vault(&dir),
+    }
+}
+
+fn is_enabled<R: Runtime>(app_handle: &AppHandle<R>, workspace_path: &str) -> bool {
+    if !mdit_spotlight_export::SUPPORTED {
+        return false;
+    }
+
+    crate::persistence::run_app_migrations(app_handle)
+        .and_then(|db_path| {
+            app_storage::vault_settings::get_vault_setting(
+                &db_path,
+                Path::new(workspace_path),
+                SPOTLIGHT_METADATA_SETTING,
+            )
+            .map_err(|error| error.to_string())
+        })
+        .map(|setting| setting.value.as_bool().unwrap_or(false))
+        .unwrap_or(false)
+}
+
+/// Catches up on edits made while the vault was not watched. Called when a
+/// watcher starts.
+pub fn refresh_vault<R: Runtime>(app_handle: &AppHandle<R>, workspace_path: &str) {
+    if is_enabled(app_handle, workspace_path) {
+        app_handle
+            .state::<SpotlightState>()
+            .enqueue(SpotlightJob::Tree(PathBuf::from(workspace_path)));
+    }
+}
+
+/// Exports or removes the metadata of the whole vault after the setting
+/// changed.
+pub fn apply_setting<R: Runtime>(app_handle: &AppHandle<R>, workspace_path: &str, enabled: bool) {
+    if !mdit_spotlight_export::SUPPORTED {
+        return;
+    }
+
+    let root = PathBuf::from(workspace_path);
+    let job = if enabled {
+        SpotlightJob::Tree(root)
+    } else {
+        SpotlightJob::Clear(root)
+    };
+    app_handle.state::<SpotlightState>().enqueue(job);
+}
+
+/// Re-exports the notes touched by a watch batch.
+pub fn handle_watch_batch<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    workspace_path: &str,
+    batch: &VaultWatchBatch,
+) {
+    if batch.ops.is_empty() || !is_enabled(app_handle, workspace_path) {
+        return;
+    }
+
+    let state = app_handle.state::<SpotlightState>();
+    let root = PathBuf::from(workspace_path);
+    let mut rel_paths = Vec::new();
+    for op in &batch.ops {
+        match op {
+            VaultWatchOp::PathState {
+                rel_path,
+                after: VaultEntryState::File,
+                ..
+            } => rel_paths.push(rel_path.clone()),
+            VaultWatchOp::Move {
+                to_rel,
+                entry_kind: VaultEntryKind::File,
+                ..
+            } => rel_paths.push(to_rel.clone()),
+            VaultWatchOp::Move {
+                to_rel,
+                entry_kind: VaultEntryKind::Directory,
+                ..
+            } => state.enqueue(SpotlightJob::Tree(root.join(to_rel))),
+            VaultWatchOp::ScanTree { rel_prefix, .. } => {
+                state.enqueue(SpotlightJob::Tree(root.join(rel_prefix)))
+            }
+            VaultWatchOp::FullRescan { .. } => state.enqueue(SpotlightJob::Tree(root.clone())),
+            VaultWatchOp::PathState { .. } => {}
+        }
+    }
+
+    rel_paths.retain(|rel_path| mdit_spotlight_export::is_note_path(Path::new(rel_path)));
+    if !rel_paths.is_empty() {
+        state.enqueue(SpotlightJob::Notes { root, rel_paths });
+    }
+}
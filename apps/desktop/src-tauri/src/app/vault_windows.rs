@@ -0,0 +1,361 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+
+use crate::app::background_mode::{self, MAIN_WINDOW_LABEL};
+use crate::app::window_lifecycle::show_and_focus_main_window;
+
+pub const VAULT_WINDOW_LABEL_PREFIX: &str = "vault-";
+pub const VAULT_WINDOW_OPEN_FILES_EVENT: &str = "vault-window-open-files";
+const VAULT_WINDOWS_FILE_NAME: &str = "vault-windows.json";
+
+/// Which vault each webview window holds, keyed by window label.
+///
+/// The main window registers its vault from the frontend and is restored by
+/// the recent-workspace flow; extra `vault-*` windows are persisted so the
+/// whole set comes back on restart.
+#[derive(Default)]
+pub struct VaultWindowState {
+    owners: Mutex<HashMap<String, String>>,
+}
+
+impl VaultWindowState {
+    fn window_for_vault(&self, workspace_path: &str) -> Option<String> {
+        let owners = self.owners.lock().unwrap();
+        owners
+            .iter()
+            .find(|(_, owned)| same_path(owned, workspace_path))
+            .map(|(label, _)| label.clone())
+    }
+
+    fn vault_for_window(&self, label: &str) -> Option<String> {
+        self.owners.lock().unwrap().get(label).cloned()
+    }
+
+    fn set_owner(&self, label: &str, workspace_path: Option<String>) {
+        let mut owners = self.owners.lock().unwrap();
+        match workspace_path {
+            Some(workspace_path) => {
+                owners.insert(label.to_string(), workspace_path);
+            }
+            None => {
+                owners.remove(label);
+            }
+        }
+    }
+
+    fn next_vault_window_label(&self) -> String {
+        let owners = self.owners.lock().unwrap();
+        let next_id = owners
+            .keys()
+            .filter_map(|label| label.strip_prefix(VAULT_WINDOW_LABEL_PREFIX))
+            .filter_map(|id| id.parse::<u64>().ok())
+            .max()
+            .map_or(0, |id| id + 1);
+        format!("{VAULT_WINDOW_LABEL_PREFIX}{next_id}")
+    }
+
+    fn persisted_windows(&self) -> BTreeMap<String, String> {
+        self.owners
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(label, _)| label.starts_with(VAULT_WINDOW_LABEL_PREFIX))
+            .map(|(label, workspace_path)| (label.clone(), workspace_path.clone()))
+            .collect()
+    }
+
+    /// Groups `file_paths` by the window whose vault contains them. Files no
+    /// open vault contains are returned separately.
+    fn route_files(&self, file_paths: &[String]) -> (BTreeMap<String, Vec<String>>, Vec<String>) {
+        let owners = self.owners.lock().unwrap();
+        let mut routed: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut unrouted = Vec::new();
+
+        for file_path in file_paths {
+            // Nested vaults go to the innermost one.
+            let owner = owners
+                .iter()
+                .filter(|(_, workspace_path)| Path::new(file_path).starts_with(workspace_path))
+                .max_by_key(|(_, workspace_path)| workspace_path.len())
+                .map(|(label, _)| label.clone());
+
+            match owner {
+                Some(label) => routed.entry(label).or_default().push(file_path.clone()),
+                None => unrouted.push(file_path.clone()),
+            }
+        }
+
+        (routed, unrouted)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultWindowOpenFilesPayload {
+    file_paths: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PersistedVaultWindows {
+    /// Window label to workspace path.
+    windows: BTreeMap<String, String>,
+}
+
+fn same_path(left: &str, right: &str) -> bool {
+    Path::new(left) == Path::new(right)
+}
+
+fn vault_windows_file(app_handle: &tauri::AppHandle) -> Option<PathBuf> {
+    crate::persistence::app_data_dir(app_handle)
+        .ok()
+        .map(|dir| dir.join(VAULT_WINDOWS_FILE_NAME))
+}
+
+fn save_vault_windows(app_handle: &tauri::AppHandle) {
+    let Some(path) = vault_windows_file(app_handle) else {
+        return;
+    };
+    let state = app_handle.state::<VaultWindowState>();
+    let persisted = PersistedVaultWindows {
+        windows: state.persisted_windows(),
+    };
+
+    let result = serde_json::to_string_pretty(&persisted)
+        .map_err(|error| error.to_string())
+        .and_then(|json| {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+            }
+            fs::write(&path, json).map_err(|error| error.to_string())
+        });
+    if let Err(error) = result {
+        tracing::error!("Failed to save vault windows: {error}");
+    }
+}
+
+fn load_vault_windows(app_handle: &tauri::AppHandle) -> PersistedVaultWindows {
+    vault_windows_file(app_handle)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn build_vault_window(app_handle: &tauri::AppHandle, label: &str) -> Option<tauri::WebviewWindow> {
+    let mut config = app_handle.config().app.windows.first()?.clone();
+    config.label = label.to_string();
+    config.visible = true;
+    config.url = tauri::WebviewUrl::App("/".into());
+
+    let builder = tauri::WebviewWindowBuilder::from_config(app_handle, &config).ok()?;
+    crate::persistence::with_webview_data(builder, app_handle)
+        .build()
+        .ok()
+}
+
+/// Recreates the vault windows that were open when the app last quit. The
+/// window-state plugin restores their size and position by label.
+pub fn restore_vault_windows(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<VaultWindowState>();
+
+    for (label, workspace_path) in load_vault_windows(app_handle).windows {
+        if !label.starts_with(VAULT_WINDOW_LABEL_PREFIX) || !Path::new(&workspace_path).is_dir() {
+            continue;
+        }
+        if state.window_for_vault(&workspace_path).is_some() {
+            continue;
+        }
+
+        state.set_owner(&label, Some(workspace_path));
+        if build_vault_window(app_handle, &label).is_none() {
+            state.set_owner(&label, None);
+        }
+    }
+
+    save_vault_windows(app_handle);
+}
+
+/// Sends files to the windows already holding their vault and focuses those
+/// windows. Returns the files that no open vault contains.
+pub fn route_files_to_vault_windows(
+    app_handle: &tauri::AppHandle,
+    file_paths: &[String],
+) -> Vec<String> {
+    let state = app_handle.state::<VaultWindowState>();
+    let (routed, unrouted) = state.route_files(file_paths);
+
+    for (label, file_paths) in routed {
+        let Some(window) = app_handle.get_webview_window(&label) else {
+            continue;
+        };
+        let _ = app_handle.emit_to(
+            label.as_str(),
+            VAULT_WINDOW_OPEN_FILES_EVENT,
+            VaultWindowOpenFilesPayload { file_paths },
+        );
+        show_and_focus_main_window(window);
+    }
+
+    unrouted
+}
+
+/// Vault registered for the window with `label`, if any.
+pub fn window_vault(app_handle: &tauri::AppHandle, label: &str) -> Option<String> {
+    app_handle
+        .state::<VaultWindowState>()
+        .vault_for_window(label)
+}
+
+/// Forgets the vault of a destroyed window. The persisted set is left alone
+/// so windows closed by quitting the app are restored on the next launch.
+pub fn handle_window_destroyed(app_handle: &tauri::AppHandle, label: &str) {
+    app_handle
+        .state::<VaultWindowState>()
+        .set_owner(label, None);
+}
+
+/// Focuses the window already holding `workspace_path`, or opens a new one for
+/// it. Returns the window label.
+pub fn open_vault_window(
+    app_handle: &tauri::AppHandle,
+    workspace_path: String,
+) -> Result<String, String> {
+    let state = app_handle.state::<VaultWindowState>();
+    if let Some(label) = state.window_for_vault(&workspace_path) {
+        if let Some(window) = app_handle.get_webview_window(&label) {
+            show_and_focus_main_window(window);
+            return Ok(label);
+        }
+        state.set_owner(&label, None);
+    }
+
+    if !Path::new(&workspace_path).is_dir() {
+        return Err(format!("Folder does not exist: {workspace_path}"));
+    }
+
+    let label = state.next_vault_window_label();
+    state.set_owner(&label, Some(workspace_path));
+    let Some(window) = build_vault_window(app_handle, &label) else {
+        state.set_owner(&label, None);
+        return Err("Failed to create vault window".to_string());
+    };
+
+    save_vault_windows(app_handle);
+    show_and_focus_main_window(window);
+    Ok(label)
+}
+
+/// Like [`open_vault_window`], but hands the vault to the main window when it
+/// holds no vault yet (welcome screen, or not created in background mode).
+pub fn open_window_for_vault(
+    app_handle: &tauri::AppHandle,
+    workspace_path: String,
+) -> Result<String, String> {
+    let state = app_handle.state::<VaultWindowState>();
+    let main_is_idle = state.window_for_vault(&workspace_path).is_none()
+        && state.vault_for_window(MAIN_WINDOW_LABEL).is_none();
+    if !main_is_idle {
+        return open_vault_window(app_handle, workspace_path);
+    }
+
+    // Picked up by the main window's bootstrap if it has not loaded yet.
+    state.set_owner(MAIN_WINDOW_LABEL, Some(workspace_path));
+    background_mode::open_main_window(app_handle);
+    Ok(MAIN_WINDOW_LABEL.to_string())
+}
+
+#[tauri::command]
+pub fn open_vault_window_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+) -> Result<String, String> {
+    open_vault_window(&app_handle, workspace_path)
+}
+
+/// Records the vault the calling window switched to, or `None` when it closed
+/// its vault.
+#[tauri::command]
+pub fn register_window_vault_command(
+    app_handle: tauri::AppHandle,
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, VaultWindowState>,
+    workspace_path: Option<String>,
+) {
+    state.set_owner(window.label(), workspace_path);
+    if window.label().starts_with(VAULT_WINDOW_LABEL_PREFIX) {
+        save_vault_windows(&app_handle);
+    }
+}
+
+/// Vault assigned to the calling window when it was opened or restored.
+#[tauri::command]
+pub fn get_window_vault_command(
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, VaultWindowState>,
+) -> Option<String> {
+    state.vault_for_window(window.label())
+}
+
+/// Called before a vault window is closed by the user, so it is not restored.
+#[tauri::command]
+pub fn close_vault_window_command(
+    app_handle: tauri::AppHandle,
+    window: tauri::WebviewWindow,
+    state: tauri::State<'_, VaultWindowState>,
+) {
+    state.set_owner(window.label(), None);
+    save_vault_windows(&app_handle);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VaultWindowState;
+
+    fn state_with(owners: &[(&str, &str)]) -> VaultWindowState {
+        let state = VaultWindowState::default();
+        for (label, workspace_path) in owners {
+            state.set_owner(label, Some(workspace_path.to_string()));
+        }
+        state
+    }
+
+    #[test]
+    fn routes_files_to_innermost_owning_vault() {
+        let state = state_with(&[
+            ("main", "/vaults/work"),
+            ("vault-0", "/vaults/work/archive"),
+            ("vault-1", "/vaults/home"),
+        ]);
+
+        let (routed, unrouted) = state.route_files(&[
+            "/vaults/work/todo.md".to_string(),
+            "/vaults/work/archive/2023.md".to_string(),
+            "/vaults/homework/essay.md".to_string(),
+        ]);
+
+        assert_eq!(routed["main"], vec!["/vaults/work/todo.md".to_string()]);
+        assert_eq!(
+            routed["vault-0"],
+            vec!["/vaults/work/archive/2023.md".to_string()]
+        );
+        assert!(!routed.contains_key("vault-1"));
+        assert_eq!(unrouted, vec!["/vaults/homework/essay.md".to_string()]);
+    }
+
+    #[test]
+    fn vault_window_labels_continue_after_restored_windows() {
+        let state = state_with(&[("main", "/a"), ("vault-3", "/b")]);
+
+        assert_eq!(state.next_vault_window_label(), "vault-4");
+        assert_eq!(state.window_for_vault("/b/"), Some("vault-3".to_string()));
+
+        let persisted = state.persisted_windows();
+        assert_eq!(persisted.len(), 1);
+        assert_eq!(persisted["vault-3"], "/b");
+    }
+}
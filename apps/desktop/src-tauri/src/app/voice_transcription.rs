@@ -0,0 +1,50 @@
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::commands::transcription::{emit_transcriptions_written, transcribe_voice_folder};
+use crate::commands::vault_indexing::index_run_guard;
+use crate::commands::vault_watch::VaultWatchRuntimeState;
+
+const TICK: Duration = Duration::from_secs(5 * 60);
+
+/// Starts the worker that transcribes recordings dropped into the voice
+/// folders of watched vaults.
+pub fn initialize<R: Runtime>(app_handle: &AppHandle<R>) {
+    let app_handle = app_handle.clone();
+    thread::spawn(move || loop {
+        thread::sleep(TICK);
+        run_transcription_pass(&app_handle);
+    });
+}
+
+fn run_transcription_pass<R: Runtime>(app_handle: &AppHandle<R>) {
+    let Ok(db_path) = crate::persistence::run_app_migrations(app_handle) else {
+        return;
+    };
+    let run_guard = index_run_guard(app_handle);
+    let workspaces = app_handle
+        .state::<VaultWatchRuntimeState>()
+        .watched_workspaces();
+
+    for workspace_path in workspaces {
+        match transcribe_voice_folder(&run_guard, &db_path, Path::new(&workspace_path)) {
+            Ok(Some(run)) => {
+                for failure in &run.failures {
+                    tracing::error!(
+                        "Failed to transcribe {}: {}",
+                        failure.audio_path.display(),
+                        failure.error
+                    );
+                }
+                emit_transcriptions_written(app_handle, workspace_path, &run);
+            }
+            Ok(None) => {}
+            Err(error) => {
+                tracing::error!("Voice transcription failed for {workspace_path}: {error}")
+            }
+        }
+    }
+}
@@ -1,13 +1,13 @@
 use tauri::Manager;
 
-use crate::app::file_opening;
+use crate::app::{background_mode, file_opening, vault_windows};
 
 pub fn show_and_focus_main_window(window: tauri::WebviewWindow) {
     if let Err(error) = window.show() {
-        eprintln!("Failed to show window: {error}");
+        tracing::error!("Failed to show window: {error}");
     }
     if let Err(error) = window.set_focus() {
-        eprintln!("Failed to focus window: {error}");
+        tracing::error!("Failed to focus window: {error}");
     }
 }
 
@@ -30,7 +30,9 @@ pub fn show_main_window(
 pub fn handle_run_event(app_handle: &tauri::AppHandle, event: &tauri::RunEvent) {
     match event {
         tauri::RunEvent::Ready { .. } => {
-            if let Some(main_window) = app_handle.get_webview_window("main") {
+            // Absent when launched in background mode; created on demand.
+            let main_window = app_handle.get_webview_window(background_mode::MAIN_WINDOW_LABEL);
+            if let Some(main_window) = main_window.as_ref() {
                 let _ = main_window.hide();
             }
             #[cfg(not(target_os = "macos"))]
@@ -38,13 +40,26 @@ pub fn handle_run_event(app_handle: &tauri::AppHandle, event: &tauri::RunEvent)
                 // Open edit window if files were passed as command line arguments
                 file_opening::open_edit_window_if_files_exist(app_handle);
             }
+            // Restore after opening startup files: restored windows are not
+            // listening yet, so files must not be routed to them.
+            if main_window.is_some() {
+                vault_windows::restore_vault_windows(app_handle);
+            }
+            file_opening::open_pending_other_paths(app_handle);
+        }
+        tauri::RunEvent::WindowEvent {
+            label,
+            event: tauri::WindowEvent::Destroyed,
+            ..
+        } => {
+            vault_windows::handle_window_destroyed(app_handle, label);
+            crate::commands::vault_watch::stop_window_vault_watch(app_handle, label);
+            crate::app::graph_updates::unsubscribe_window(app_handle, label);
         }
         #[cfg(target_os = "macos")]
         tauri::RunEvent::Reopen { .. } => {
             // Show main window if it exists, otherwise create it
-            if let Some(main_window) = app_handle.get_webview_window("main") {
-                show_and_focus_main_window(main_window);
-            }
+            background_mode::open_main_window(app_handle);
         }
         #[cfg(target_os = "macos")]
         tauri::RunEvent::Opened { urls } => {
@@ -0,0 +1,71 @@
+use std::path::{Path, PathBuf};
+
+use mdit_vault_indexing::{
+    format_citation, get_citing_notes, search_citations, CitationEntry, CitationStyle,
+};
+
+use crate::commands::vault_indexing::run_blocking;
+
+const BIBLIOGRAPHY_PATH_SETTING: &str = "bibliographyPath";
+const DEFAULT_CITATION_SEARCH_LIMIT: usize = 20;
+
+fn bibliography_path(db_path: &Path, workspace_root: &Path) -> Result<String, String> {
+    let setting = app_storage::vault_settings::get_vault_setting(
+        db_path,
+        workspace_root,
+        BIBLIOGRAPHY_PATH_SETTING,
+    )
+    .map_err(|error| error.to_string())?;
+
+    Ok(setting.value.as_str().unwrap_or_default().to_string())
+}
+
+/// Bibliography entries matching `query`, read from the vault's configured
+/// `.bib` or CSL-JSON file. Empty while no bibliography is configured.
+#[tauri::command]
+pub async fn search_citations_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<CitationEntry>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+    let bibliography_path = bibliography_path(&db_path, &workspace_path)?;
+    let limit = limit.unwrap_or(DEFAULT_CITATION_SEARCH_LIMIT);
+
+    run_blocking(move || {
+        search_citations(&workspace_path, &db_path, &bibliography_path, &query, limit)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn format_citation_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    key: String,
+    style: CitationStyle,
+) -> Result<String, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+    let bibliography_path = bibliography_path(&db_path, &workspace_path)?;
+
+    run_blocking(move || {
+        format_citation(&workspace_path, &db_path, &bibliography_path, &key, style)
+    })
+    .await
+}
+
+/// Rel paths of the notes citing `key` with `[@key]`.
+#[tauri::command]
+pub async fn get_citing_notes_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    key: String,
+) -> Result<Vec<String>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+
+    run_blocking(move || get_citing_notes(&workspace_path, &db_path, &key)).await
+}
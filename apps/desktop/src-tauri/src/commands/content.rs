@@ -1,13 +1,380 @@
 use std::path::{Path, PathBuf};
 
+use mdit_vault_indexing::IndexRunGuard;
+use serde::Deserialize;
+use tauri::{AppHandle, Runtime};
+
+use crate::commands::vault_indexing::index_run_guard;
+use crate::path_scope::{resolve_path, PathScope};
+
 #[tauri::command]
-pub async fn get_file_frontmatter(path: String) -> Result<serde_json::Value, String> {
-    tauri::async_runtime::spawn_blocking(move || mdit_note::read_frontmatter(&PathBuf::from(path)))
+pub async fn get_file_frontmatter<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: String,
+) -> Result<mdit_note::Frontmatter, String> {
+    let path = resolve_path(&app_handle, &path)?;
+    tauri::async_runtime::spawn_blocking(move || mdit_note::read_frontmatter(&path))
         .await
         .map_err(|error| error.to_string())?
 }
 
+/// Sets one frontmatter property in place and returns the updated frontmatter.
+/// With a `workspace_path`, the note is reindexed so property filters see the change.
+#[tauri::command]
+pub async fn set_frontmatter_property_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: String,
+    key: String,
+    value: serde_json::Value,
+    workspace_path: Option<String>,
+) -> Result<mdit_note::Frontmatter, String> {
+    let (path, index) = resolve_note_and_index(&app_handle, &path, workspace_path)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        ensure_property_editable(&path, &key)?;
+        mdit_note::write_frontmatter_property(&path, &key, &value)?;
+        reindex_note(index, &path);
+        mdit_note::read_frontmatter(&path)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
 #[tauri::command]
-pub fn get_note_preview(path: String) -> Result<String, String> {
-    mdit_note::get_note_preview(Path::new(&path))
+pub async fn remove_frontmatter_property_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: String,
+    key: String,
+    workspace_path: Option<String>,
+) -> Result<mdit_note::Frontmatter, String> {
+    let (path, index) = resolve_note_and_index(&app_handle, &path, workspace_path)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        ensure_property_editable(&path, &key)?;
+        if mdit_note::delete_frontmatter_property(&path, &key)? {
+            reindex_note(index, &path);
+        }
+        mdit_note::read_frontmatter(&path)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Notes under `folder_path`, or the whole vault, grouped into columns by the
+/// value of their `property`.
+#[tauri::command]
+pub async fn get_kanban_board_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    property: String,
+    columns: Vec<String>,
+    folder_path: Option<String>,
+) -> Result<mdit_note::KanbanBoard, String> {
+    let scope = PathScope::load(&app_handle)?;
+    let folder = scope.resolve(folder_path.as_deref().unwrap_or(&workspace_path))?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        mdit_note::build_kanban_board(&folder, &property, &columns)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Moves a card by setting the note's board `property` to the target
+/// `column`, then reindexes it like any other frontmatter edit.
+#[tauri::command]
+pub async fn move_card_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    path: String,
+    property: String,
+    column: String,
+) -> Result<mdit_note::Frontmatter, String> {
+    let (path, index) = resolve_note_and_index(&app_handle, &path, Some(workspace_path))?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        ensure_property_editable(&path, &property)?;
+        let value = serde_json::Value::String(column.trim().to_string());
+        mdit_note::write_frontmatter_property(&path, property.trim(), &value)?;
+        reindex_note(index, &path);
+        mdit_note::read_frontmatter(&path)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// A locked note only lets its `locked` property change, so it can be unlocked.
+fn ensure_property_editable(path: &Path, key: &str) -> Result<(), String> {
+    if key == mdit_note::LOCKED_PROPERTY {
+        return Ok(());
+    }
+    mdit_note::ensure_note_unlocked(path)
+}
+
+struct NoteIndex {
+    workspace_root: PathBuf,
+    db_path: PathBuf,
+    run_guard: IndexRunGuard,
+}
+
+fn resolve_note_and_index<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    path: &str,
+    workspace_path: Option<String>,
+) -> Result<(PathBuf, Option<NoteIndex>), String> {
+    let scope = PathScope::load(app_handle)?;
+    let path = scope.resolve(path)?;
+    let index = match workspace_path {
+        Some(workspace_path) => Some(NoteIndex {
+            workspace_root: scope.resolve(&workspace_path)?,
+            db_path: crate::persistence::run_app_migrations(app_handle)?,
+            run_guard: index_run_guard(app_handle),
+        }),
+        None => None,
+    };
+    Ok((path, index))
+}
+
+/// The edit is already on disk, so indexing failures are only logged.
+fn reindex_note(index: Option<NoteIndex>, path: &Path) {
+    let Some(index) = index else {
+        return;
+    };
+    if let Err(error) = index.run_guard.run_exclusive(&index.workspace_root, || {
+        mdit_vault_indexing::index_note_with_profiles(
+            &index.workspace_root,
+            &index.db_path,
+            path,
+            &[],
+        )
+    }) {
+        tracing::error!(
+            "Failed to reindex {} after frontmatter edit: {error}",
+            path.display()
+        );
+    }
+}
+
+/// Notes a map of content lists.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum MocSource {
+    /// Every note under the folder, subfolders included.
+    Folder { path: String },
+    /// Every note with the tag or a tag nested under it.
+    Tag { tag: String },
+}
+
+/// Writes a map-of-content note at `moc_path` listing the notes of `source`.
+/// Only the block between the MOC markers is rewritten, so running it again
+/// refreshes the list and keeps anything written around it.
+#[tauri::command]
+pub async fn generate_moc_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    moc_path: String,
+    source: MocSource,
+    options: Option<mdit_note::MocOptions>,
+) -> Result<mdit_note::MocResult, String> {
+    let scope = PathScope::load(&app_handle)?;
+    let moc_path = scope.resolve(&moc_path)?;
+    let source = match source {
+        MocSource::Folder { path } => MocSource::Folder {
+            path: scope.resolve(&path)?.to_string_lossy().into_owned(),
+        },
+        tag => tag,
+    };
+    let index = NoteIndex {
+        workspace_root: scope.resolve(&workspace_path)?,
+        db_path: crate::persistence::run_app_migrations(&app_handle)?,
+        run_guard: index_run_guard(&app_handle),
+    };
+    let options = options.unwrap_or_default();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let notes = collect_moc_notes(&index, &source, options.group_by)?;
+        let result = mdit_note::generate_moc(&index.workspace_root, &moc_path, &notes, &options)?;
+        reindex_note(Some(index), &moc_path);
+        Ok(result)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+fn collect_moc_notes(
+    index: &NoteIndex,
+    source: &MocSource,
+    group_by: mdit_note::MocGroupBy,
+) -> Result<Vec<mdit_note::MocNote>, String> {
+    let (paths, group_root, tag_prefix) = match source {
+        MocSource::Folder { path } => {
+            let folder = PathBuf::from(path);
+            (mdit_note::collect_folder_notes(&folder)?, folder, None)
+        }
+        MocSource::Tag { tag } => {
+            let entries = mdit_vault_indexing::search_notes_by_tag(
+                &index.workspace_root,
+                &index.db_path,
+                tag,
+            )
+            .map_err(|error| error.to_string())?;
+            let tag = tag.trim().trim_start_matches('#').to_lowercase();
+            (
+                entries
+                    .into_iter()
+                    .map(|entry| PathBuf::from(entry.path))
+                    .collect(),
+                index.workspace_root.clone(),
+                Some(tag),
+            )
+        }
+    };
+
+    let mut tags_by_path = match group_by {
+        mdit_note::MocGroupBy::Folder => Default::default(),
+        mdit_note::MocGroupBy::Tag => {
+            let rel_paths = paths
+                .iter()
+                .filter_map(|path| {
+                    workspace_rel_path(&index.workspace_root, &path.to_string_lossy())
+                })
+                .collect::<Vec<_>>();
+            mdit_vault_indexing::get_note_tags(&index.workspace_root, &index.db_path, &rel_paths)
+                .map_err(|error| error.to_string())?
+        }
+    };
+
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let groups = match group_by {
+                mdit_note::MocGroupBy::Folder => Some(mdit_note::folder_group(&group_root, &path))
+                    .filter(|group| !group.is_empty())
+                    .into_iter()
+                    .collect(),
+                mdit_note::MocGroupBy::Tag => {
+                    workspace_rel_path(&index.workspace_root, &path.to_string_lossy())
+                        .and_then(|rel_path| tags_by_path.remove(&rel_path))
+                        .unwrap_or_default()
+                        .into_iter()
+                        // Under a tag source, only the tag and its children
+                        // become headings.
+                        .filter(|tag| {
+                            tag_prefix.as_ref().is_none_or(|prefix| {
+                                let tag = tag.to_lowercase();
+                                tag == *prefix || tag.starts_with(&format!("{prefix}/"))
+                            })
+                        })
+                        .collect()
+                }
+            };
+            mdit_note::MocNote { path, groups }
+        })
+        .collect())
+}
+
+/// Downloads the note at `path` when it is a cloud placeholder, so the editor
+/// does not stall reading it. Returns whether a download was needed.
+#[tauri::command]
+pub async fn materialize_cloud_file_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: String,
+) -> Result<bool, String> {
+    let path = resolve_path(&app_handle, &path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        mdit_vault_indexing::materialize_cloud_placeholder(&path).map_err(|error| error.to_string())
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+#[tauri::command]
+pub fn get_note_preview<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: String,
+    max_chars: Option<usize>,
+) -> Result<mdit_note::NotePreview, String> {
+    let path = resolve_path(&app_handle, &path)?;
+    mdit_note::get_note_preview(&path, max_chars.unwrap_or(mdit_note::DEFAULT_PREVIEW_CHARS))
+}
+
+/// Headings and fenced code blocks of the note at `path`, with byte offsets
+/// and whether each block is a renderable diagram.
+#[tauri::command]
+pub async fn get_note_outline_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: String,
+) -> Result<mdit_note::NoteOutline, String> {
+    let path = resolve_path(&app_handle, &path)?;
+    tauri::async_runtime::spawn_blocking(move || mdit_note::get_note_outline(&path))
+        .await
+        .map_err(|error| error.to_string())?
+}
+
+/// Sanitizes `title` and picks the first free note file name in `directory_path`.
+#[tauri::command]
+pub fn get_unique_note_file_name_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    directory_path: String,
+    title: String,
+) -> Result<mdit_note::UniqueNoteFileName, String> {
+    let directory = resolve_path(&app_handle, &directory_path)?;
+    mdit_note::unique_note_file_name(&directory, &title)
+}
+
+/// Lists `path` with metadata, note titles and child counts in one call. When
+/// `workspace_path` is given, markdown entries also carry their indexed tags.
+#[tauri::command]
+pub async fn list_directory_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: String,
+    options: Option<mdit_note::ListDirectoryOptions>,
+    workspace_path: Option<String>,
+) -> Result<Vec<mdit_note::DirectoryEntry>, String> {
+    let scope = PathScope::load(&app_handle)?;
+    let path = scope.resolve(&path)?;
+    let index = match workspace_path {
+        Some(workspace_path) => Some((
+            scope.resolve(&workspace_path)?,
+            crate::persistence::run_app_migrations(&app_handle)?,
+        )),
+        None => None,
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut entries = mdit_note::list_directory(&path, &options.unwrap_or_default())?;
+        if let Some((workspace_root, db_path)) = index {
+            attach_note_tags(&workspace_root, &db_path, &mut entries)?;
+        }
+        Ok(entries)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+fn attach_note_tags(
+    workspace_root: &Path,
+    db_path: &Path,
+    entries: &mut [mdit_note::DirectoryEntry],
+) -> Result<(), String> {
+    let rel_paths = entries
+        .iter()
+        .map(|entry| workspace_rel_path(workspace_root, &entry.path))
+        .collect::<Vec<_>>();
+    let lookup = rel_paths.iter().flatten().cloned().collect::<Vec<_>>();
+    let mut tags_by_path = mdit_vault_indexing::get_note_tags(workspace_root, db_path, &lookup)
+        .map_err(|error| error.to_string())?;
+
+    for (entry, rel_path) in entries.iter_mut().zip(rel_paths) {
+        if let Some(tags) = rel_path.and_then(|rel_path| tags_by_path.remove(&rel_path)) {
+            entry.tags = tags;
+        }
+    }
+
+    Ok(())
+}
+
+fn workspace_rel_path(workspace_root: &Path, entry_path: &str) -> Option<String> {
+    let rel_path = Path::new(entry_path).strip_prefix(workspace_root).ok()?;
+    Some(rel_path.to_string_lossy().replace('\\', "/"))
 }
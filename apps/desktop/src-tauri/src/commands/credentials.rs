@@ -7,7 +7,9 @@ use mdit_credentials::{
 use tauri::{AppHandle, Runtime};
 use tauri_plugin_keyring::KeyringExt;
 
-struct TauriKeyringBackend<'a, R: Runtime> {
+use crate::persistence::profile::keychain_service;
+
+pub(crate) struct TauriKeyringBackend<'a, R: Runtime> {
     app_handle: &'a AppHandle<R>,
 }
 
@@ -15,26 +17,26 @@ impl<R: Runtime> CredentialStoreBackend for TauriKeyringBackend<'_, R> {
     fn get_password(&self, service: &str, user: &str) -> Result<Option<String>, String> {
         self.app_handle
             .keyring()
-            .get_password(service, user)
+            .get_password(&keychain_service(service), user)
             .map_err(|error| error.to_string())
     }
 
     fn set_password(&self, service: &str, user: &str, password: &str) -> Result<(), String> {
         self.app_handle
             .keyring()
-            .set_password(service, user, password)
+            .set_password(&keychain_service(service), user, password)
             .map_err(|error| error.to_string())
     }
 
     fn delete_password(&self, service: &str, user: &str) -> Result<(), String> {
         self.app_handle
             .keyring()
-            .delete_password(service, user)
+            .delete_password(&keychain_service(service), user)
             .map_err(|error| error.to_string())
     }
 }
 
-fn backend<R: Runtime>(app_handle: &AppHandle<R>) -> TauriKeyringBackend<'_, R> {
+pub(crate) fn backend<R: Runtime>(app_handle: &AppHandle<R>) -> TauriKeyringBackend<'_, R> {
     TauriKeyringBackend { app_handle }
 }
 
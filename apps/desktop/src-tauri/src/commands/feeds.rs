@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use app_storage::feeds::FeedSubscription;
+use mdit_feeds::FeedRefresh;
+use mdit_vault_indexing::IndexRunGuard;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::commands::vault_indexing::{index_run_guard, index_written_notes};
+use crate::path_scope::PathScope;
+
+/// Broadcast when a refresh wrote notes, so open file trees pick them up.
+pub const FEEDS_REFRESHED_EVENT: &str = "feeds-refreshed";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FeedsRefreshedPayload {
+    workspace_path: String,
+    refreshes: Vec<FeedRefresh>,
+}
+
+#[tauri::command]
+pub fn list_feeds_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+) -> Result<Vec<FeedSubscription>, String> {
+    let workspace_root = PathScope::load(&app_handle)?.resolve(&workspace_path)?;
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::feeds::list_feed_subscriptions(&db_path, &workspace_root)
+        .map_err(|error| error.to_string())
+}
+
+/// Checks that `url` is a feed and subscribes the vault to it.
+#[tauri::command]
+pub async fn subscribe_feed_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    url: String,
+) -> Result<FeedSubscription, String> {
+    let workspace_root = PathScope::load(&app_handle)?.resolve(&workspace_path)?;
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        mdit_feeds::subscribe_feed(&db_path, &workspace_root, &url)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Fetches one feed, or all of the vault's when `feed_id` is missing, and
+/// writes notes for the new items.
+#[tauri::command]
+pub async fn refresh_feeds_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    feed_id: Option<i64>,
+) -> Result<Vec<FeedRefresh>, String> {
+    let workspace_root = PathScope::load(&app_handle)?.resolve(&workspace_path)?;
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let run_guard = index_run_guard(&app_handle);
+
+    let refreshes = tauri::async_runtime::spawn_blocking(move || {
+        refresh_feeds(&run_guard, &db_path, &workspace_root, feed_id)
+    })
+    .await
+    .map_err(|error| error.to_string())??;
+    emit_feeds_refreshed(&app_handle, workspace_path, &refreshes);
+    Ok(refreshes)
+}
+
+/// Stops following the feed. Notes already written stay in the vault.
+#[tauri::command]
+pub fn unsubscribe_feed_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    feed_id: i64,
+) -> Result<bool, String> {
+    let workspace_root = PathScope::load(&app_handle)?.resolve(&workspace_path)?;
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::feeds::remove_feed_subscription(&db_path, &workspace_root, feed_id)
+        .map_err(|error| error.to_string())
+}
+
+/// Refreshes the feeds and indexes the notes they wrote.
+pub(crate) fn refresh_feeds(
+    run_guard: &IndexRunGuard,
+    db_path: &Path,
+    workspace_root: &Path,
+    feed_id: Option<i64>,
+) -> Result<Vec<FeedRefresh>, String> {
+    let refreshes = match feed_id {
+        None => mdit_feeds::refresh_vault_feeds(db_path, workspace_root)?,
+        Some(feed_id) => {
+            let subscription = app_storage::feeds::list_feed_subscriptions(db_path, workspace_root)
+                .map_err(|error| error.to_string())?
+                .into_iter()
+                .find(|subscription| subscription.id == feed_id)
+                .ok_or_else(|| format!("No feed subscription with id {feed_id}"))?;
+            vec![mdit_feeds::refresh_feed(
+                db_path,
+                workspace_root,
+                &subscription,
+            )?]
+        }
+    };
+
+    let created = refreshes.iter().flat_map(|refresh| &refresh.created);
+    index_written_notes(run_guard, workspace_root, db_path, created);
+    Ok(refreshes)
+}
+
+pub(crate) fn emit_feeds_refreshed<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    workspace_path: String,
+    refreshes: &[FeedRefresh],
+) {
+    if refreshes.iter().all(|refresh| refresh.created.is_empty()) {
+        return;
+    }
+    let payload = FeedsRefreshedPayload {
+        workspace_path,
+        refreshes: refreshes.to_vec(),
+    };
+    let _ = app_handle.emit(FEEDS_REFRESHED_EVENT, payload);
+}
@@ -1,5 +1,17 @@
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use app_storage::trash::{RecordTrashedNoteInput, TrashedNoteRecord};
+use mdit_vault_indexing::IndexRunGuard;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+use crate::commands::vault_indexing::{index_run_guard, rename_indexed_entry};
+use crate::path_scope::{resolve_path, PathScope};
+
+// Used where the system trash cannot be enumerated, so restores stay possible.
+const VAULT_TRASH_DIR: &str = ".mdit/trash";
 
 fn delete_paths(paths: Vec<String>) -> Result<(), trash::Error> {
     #[cfg(target_os = "macos")]
@@ -40,23 +52,735 @@ fn copy_recursive(source: &Path, destination: &Path) -> Result<(), std::io::Erro
 }
 
 #[tauri::command]
-pub fn copy(source_path: String, destination_path: String) -> Result<(), String> {
-    let source = Path::new(&source_path);
-    let destination = Path::new(&destination_path);
+pub fn copy<R: Runtime>(
+    app_handle: AppHandle<R>,
+    source_path: String,
+    destination_path: String,
+) -> Result<(), String> {
+    let scope = PathScope::load(&app_handle)?;
+    let source = scope.resolve(&source_path)?;
+    let destination = scope.resolve(&destination_path)?;
+    if destination.exists() {
+        mdit_note::ensure_entry_unlocked(&destination)?;
+    }
+
+    copy_recursive(&source, &destination).map_err(|error| format!("Failed to copy: {}", error))
+}
+
+/// Writes a file for the editor. Locked notes refuse every write except the
+/// one that unlocks them.
+#[tauri::command]
+pub fn write_text_file_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: String,
+    contents: String,
+) -> Result<(), String> {
+    let path = resolve_path(&app_handle, &path)?;
+    mdit_note::ensure_note_writable(&path, &contents)?;
+    fs::write(&path, contents).map_err(|error| format!("Failed to write file: {}", error))
+}
+
+/// Renames a note or folder in place. Locked notes, and folders holding one,
+/// keep their name.
+#[tauri::command]
+pub fn rename_entry_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    source_path: String,
+    destination_path: String,
+) -> Result<(), String> {
+    let scope = PathScope::load(&app_handle)?;
+    let source = scope.resolve_entry(&source_path)?;
+    let destination = scope.resolve_entry(&destination_path)?;
+    mdit_note::ensure_entry_unlocked(&source)?;
+    if destination.exists() {
+        mdit_note::ensure_entry_unlocked(&destination)?;
+    }
+    fs::rename(&source, &destination).map_err(|error| format!("Failed to rename: {}", error))
+}
+
+/// The index a batch move keeps up to date.
+struct EntryIndex {
+    workspace_root: PathBuf,
+    db_path: PathBuf,
+    run_guard: IndexRunGuard,
+}
+
+/// What to do when a batch copy or move finds an entry with the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CollisionPolicy {
+    Fail,
+    Overwrite,
+    /// Pick the first free "name 2.md", "name 3.md", ... in the destination.
+    AutoRename,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferResult {
+    pub source_path: String,
+    /// Where the entry ended up; `None` when it was not transferred.
+    pub destination_path: Option<String>,
+    pub error: Option<String>,
+}
+
+impl TransferResult {
+    fn done(source: &Path, destination: &Path) -> Self {
+        Self {
+            source_path: source.to_string_lossy().into_owned(),
+            destination_path: Some(destination.to_string_lossy().into_owned()),
+            error: None,
+        }
+    }
+
+    fn failed(source: &Path, error: String) -> Self {
+        Self {
+            source_path: source.to_string_lossy().into_owned(),
+            destination_path: None,
+            error: Some(error),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn copy_entries_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    source_paths: Vec<String>,
+    destination_dir: String,
+    collision_policy: CollisionPolicy,
+) -> Result<Vec<TransferResult>, String> {
+    let scope = PathScope::load(&app_handle)?;
+    let destination_dir = scope.resolve(&destination_dir)?;
+
+    let results = source_paths
+        .iter()
+        .map(|source_path| {
+            // The copy is named after the entry but reads what it points to,
+            // so both have to be inside the roots.
+            let result = scope
+                .resolve(source_path)
+                .and_then(|_| scope.resolve_entry(source_path))
+                .and_then(|source| copy_entry(&source, &destination_dir, collision_policy));
+            match result {
+                Ok(destination) => TransferResult::done(Path::new(source_path), &destination),
+                Err(error) => TransferResult::failed(Path::new(source_path), error),
+            }
+        })
+        .collect();
+
+    Ok(results)
+}
+
+/// Moves entries into `destination_dir`. With a `workspace_path`, moved
+/// markdown notes keep their index rows under the new path.
+#[tauri::command]
+pub fn move_entries_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: Option<String>,
+    source_paths: Vec<String>,
+    destination_dir: String,
+    collision_policy: CollisionPolicy,
+) -> Result<Vec<TransferResult>, String> {
+    let scope = PathScope::load(&app_handle)?;
+    let index = match workspace_path {
+        Some(workspace_path) => Some(EntryIndex {
+            workspace_root: scope.resolve(&workspace_path)?,
+            db_path: crate::persistence::run_app_migrations(&app_handle)?,
+            run_guard: index_run_guard(&app_handle),
+        }),
+        None => None,
+    };
+    let destination_dir = scope.resolve(&destination_dir)?;
 
-    copy_recursive(source, destination).map_err(|error| format!("Failed to copy: {}", error))
+    let results = source_paths
+        .iter()
+        .map(|source_path| {
+            let result = scope.resolve_entry(source_path).and_then(|source| {
+                move_entry(&source, &destination_dir, collision_policy, index.as_ref())
+            });
+            match result {
+                Ok(destination) => TransferResult::done(Path::new(source_path), &destination),
+                Err(error) => TransferResult::failed(Path::new(source_path), error),
+            }
+        })
+        .collect();
+
+    Ok(results)
+}
+
+fn copy_entry(
+    source: &Path,
+    destination_dir: &Path,
+    policy: CollisionPolicy,
+) -> Result<PathBuf, String> {
+    let name = entry_name(source)?;
+    ensure_not_nested(source, destination_dir)?;
+
+    // Copying onto itself would destroy the source, so it always duplicates.
+    let policy = if destination_dir.join(name) == source {
+        CollisionPolicy::AutoRename
+    } else {
+        policy
+    };
+    let destination = resolve_destination(destination_dir, name, policy)?;
+    if destination.exists() {
+        mdit_note::ensure_entry_unlocked(&destination)?;
+    }
+    clear_destination(&destination, policy)?;
+
+    copy_recursive(source, &destination).map_err(|error| format!("Failed to copy: {}", error))?;
+    Ok(destination)
+}
+
+fn move_entry(
+    source: &Path,
+    destination_dir: &Path,
+    policy: CollisionPolicy,
+    index: Option<&EntryIndex>,
+) -> Result<PathBuf, String> {
+    let name = entry_name(source)?;
+    ensure_not_nested(source, destination_dir)?;
+
+    if destination_dir.join(name) == source {
+        return Ok(source.to_path_buf());
+    }
+
+    mdit_note::ensure_entry_unlocked(source)?;
+    let destination = resolve_destination(destination_dir, name, policy)?;
+    if destination.exists() {
+        mdit_note::ensure_entry_unlocked(&destination)?;
+        if let Some(index) = index {
+            forget_indexed_entry(index, &destination);
+        }
+    }
+    clear_destination(&destination, policy)?;
+
+    let is_directory = source.is_dir();
+    if fs::rename(source, &destination).is_err() {
+        // `rename` cannot cross filesystems; fall back to copy and delete.
+        copy_recursive(source, &destination)
+            .map_err(|error| format!("Failed to move: {}", error))?;
+        let removed = if is_directory {
+            fs::remove_dir_all(source)
+        } else {
+            fs::remove_file(source)
+        };
+        removed.map_err(|error| format!("Failed to remove moved source: {}", error))?;
+    }
+
+    if let Some(index) = index {
+        rename_indexed_entries(index, source, &destination, is_directory);
+    }
+
+    Ok(destination)
+}
+
+fn entry_name(source: &Path) -> Result<&std::ffi::OsStr, String> {
+    if !source.exists() {
+        return Err(format!("{} does not exist", source.display()));
+    }
+    source
+        .file_name()
+        .ok_or_else(|| format!("Cannot transfer {}", source.display()))
+}
+
+fn ensure_not_nested(source: &Path, destination_dir: &Path) -> Result<(), String> {
+    if source.is_dir() && destination_dir.starts_with(source) {
+        return Err(format!("Cannot place {} inside itself", source.display()));
+    }
+    Ok(())
+}
+
+fn resolve_destination(
+    destination_dir: &Path,
+    name: &std::ffi::OsStr,
+    policy: CollisionPolicy,
+) -> Result<PathBuf, String> {
+    let destination = destination_dir.join(name);
+    if !destination.exists() {
+        return Ok(destination);
+    }
+
+    match policy {
+        CollisionPolicy::Fail => Err(format!("{} already exists", destination.display())),
+        CollisionPolicy::Overwrite => Ok(destination),
+        CollisionPolicy::AutoRename => Ok(auto_renamed_destination(destination_dir, name)),
+    }
+}
+
+fn clear_destination(destination: &Path, policy: CollisionPolicy) -> Result<(), String> {
+    if policy != CollisionPolicy::Overwrite || !destination.exists() {
+        return Ok(());
+    }
+
+    let removed = if destination.is_dir() {
+        fs::remove_dir_all(destination)
+    } else {
+        fs::remove_file(destination)
+    };
+    removed.map_err(|error| format!("Failed to replace {}: {}", destination.display(), error))
+}
+
+/// First free "stem N.ext" in `destination_dir`, counting from 2.
+fn auto_renamed_destination(destination_dir: &Path, name: &std::ffi::OsStr) -> PathBuf {
+    let name = Path::new(name);
+    let stem = name
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = name
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+
+    (2..)
+        .map(|counter| destination_dir.join(format!("{stem} {counter}{extension}")))
+        .find(|candidate| !candidate.exists())
+        .expect("an unused name always exists")
+}
+
+fn forget_indexed_entry(index: &EntryIndex, destination: &Path) {
+    let result = index.run_guard.run_exclusive(&index.workspace_root, || {
+        if destination.is_dir() {
+            mdit_vault_indexing::delete_indexed_notes_by_prefix(
+                &index.workspace_root,
+                &index.db_path,
+                destination,
+            )
+            .map(|_| ())
+        } else if mdit_note::is_note_path(destination) {
+            mdit_vault_indexing::delete_indexed_note(
+                &index.workspace_root,
+                &index.db_path,
+                destination,
+            )
+            .map(|_| ())
+        } else {
+            Ok(())
+        }
+    });
+
+    if let Err(error) = result {
+        tracing::error!(
+            "Failed to drop index for {}: {error}",
+            destination.display()
+        );
+    }
+}
+
+/// Index failures are logged only: the next indexing run repairs them.
+fn rename_indexed_entries(
+    index: &EntryIndex,
+    source: &Path,
+    destination: &Path,
+    is_directory: bool,
+) {
+    let moved_notes = if is_directory {
+        collect_markdown_paths(destination)
+            .into_iter()
+            .filter_map(|new_path| {
+                let rel_path = new_path.strip_prefix(destination).ok()?;
+                Some((source.join(rel_path), new_path.clone()))
+            })
+            .collect::<Vec<_>>()
+    } else if mdit_note::is_note_path(destination) {
+        vec![(source.to_path_buf(), destination.to_path_buf())]
+    } else {
+        Vec::new()
+    };
+
+    if let Err(error) = rename_indexed_entry(
+        &index.run_guard,
+        &index.workspace_root,
+        &index.db_path,
+        source,
+        destination,
+        &moved_notes,
+    ) {
+        tracing::error!(
+            "Failed to update index for moved {}: {error}",
+            destination.display()
+        );
+    }
+}
+
+fn collect_markdown_paths(directory: &Path) -> Vec<PathBuf> {
+    let Ok(read_dir) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+
+    let mut paths = Vec::new();
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            paths.extend(collect_markdown_paths(&path));
+        } else if mdit_note::is_note_path(&path) {
+            paths.push(path);
+        }
+    }
+    paths
 }
 
 #[tauri::command]
-pub fn move_to_trash(path: String) -> Result<(), String> {
-    delete_paths(vec![path]).map_err(|error| format!("Failed to delete file: {}", error))
+pub fn move_to_trash<R: Runtime>(app_handle: AppHandle<R>, path: String) -> Result<(), String> {
+    let path = PathScope::load(&app_handle)?.resolve_entry(&path)?;
+    trash_paths(&app_handle, vec![path.to_string_lossy().into_owned()])
+        .map_err(|error| format!("Failed to delete file: {}", error))
 }
 
 #[tauri::command]
-pub fn move_many_to_trash(paths: Vec<String>) -> Result<(), String> {
+pub fn move_many_to_trash<R: Runtime>(
+    app_handle: AppHandle<R>,
+    paths: Vec<String>,
+) -> Result<(), String> {
     if paths.is_empty() {
         return Ok(());
     }
 
-    delete_paths(paths).map_err(|error| format!("Failed to delete files: {}", error))
+    let scope = PathScope::load(&app_handle)?;
+    let paths = paths
+        .iter()
+        .map(|path| {
+            scope
+                .resolve_entry(path)
+                .map(|path| path.to_string_lossy().into_owned())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    trash_paths(&app_handle, paths).map_err(|error| format!("Failed to delete files: {}", error))
+}
+
+#[tauri::command]
+pub fn list_trashed_notes_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+) -> Result<Vec<TrashedNoteRecord>, String> {
+    resolve_path(&app_handle, &workspace_path)?;
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_root = canonical_workspace_root(&workspace_path);
+    let records = app_storage::trash::list_trashed_notes(&db_path, &workspace_root)
+        .map_err(|error| error.to_string())?;
+
+    // Drop entries whose item was purged or restored outside mdit.
+    let system_items = system_trash::list_original_paths();
+    let (restorable, stale): (Vec<_>, Vec<_>) = records
+        .into_iter()
+        .partition(|record| is_restorable(&workspace_root, record, system_items.as_deref()));
+    let stale_ids = stale.iter().map(|record| record.id).collect::<Vec<_>>();
+    app_storage::trash::delete_trashed_notes(&db_path, &stale_ids)
+        .map_err(|error| error.to_string())?;
+
+    Ok(restorable)
+}
+
+/// Moves a trashed note back to its original path and returns that path.
+#[tauri::command]
+pub fn restore_trashed_note_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    trashed_id: i64,
+) -> Result<String, String> {
+    resolve_path(&app_handle, &workspace_path)?;
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_root = canonical_workspace_root(&workspace_path);
+    let record = app_storage::trash::get_trashed_note(&db_path, &workspace_root, trashed_id)
+        .map_err(|error| error.to_string())?
+        .ok_or_else(|| "Trashed note not found".to_string())?;
+
+    let destination = workspace_root.join(&record.original_path);
+    if destination.exists() {
+        return Err(format!(
+            "Cannot restore: {} already exists",
+            destination.display()
+        ));
+    }
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent).map_err(|error| format!("Failed to restore note: {}", error))?;
+    }
+
+    match record.trash_location.as_deref() {
+        Some(location) => restore_from_vault_trash(Path::new(location), &destination)?,
+        None => system_trash::restore(&destination)?,
+    }
+
+    app_storage::trash::delete_trashed_notes(&db_path, &[record.id])
+        .map_err(|error| error.to_string())?;
+
+    Ok(destination.to_string_lossy().into_owned())
+}
+
+fn trash_paths<R: Runtime>(app_handle: &AppHandle<R>, paths: Vec<String>) -> Result<(), String> {
+    for path in &paths {
+        mdit_note::ensure_entry_unlocked(Path::new(path))?;
+    }
+
+    // The ledger is best effort: trashing must keep working without appdata.
+    let db_path = crate::persistence::run_app_migrations(app_handle).ok();
+    let workspaces = db_path
+        .as_deref()
+        .and_then(|db_path| app_storage::vault::list_workspaces(db_path).ok())
+        .unwrap_or_default();
+
+    let mut untracked = Vec::new();
+    let mut tracked = Vec::new();
+    for path in paths {
+        match find_owning_workspace(&workspaces, Path::new(&path)) {
+            Some((workspace_root, rel_path)) => tracked.push((path, workspace_root, rel_path)),
+            None => untracked.push(path),
+        }
+    }
+
+    if !untracked.is_empty() {
+        delete_paths(untracked).map_err(|error| error.to_string())?;
+    }
+
+    for (path, workspace_root, rel_path) in tracked {
+        let trash_location = if system_trash::IS_LISTABLE {
+            delete_paths(vec![path]).map_err(|error| error.to_string())?;
+            None
+        } else {
+            let location = move_to_vault_trash(Path::new(&workspace_root), Path::new(&path))?;
+            Some(location.to_string_lossy().into_owned())
+        };
+
+        if let Some(db_path) = db_path.as_deref() {
+            let input = RecordTrashedNoteInput {
+                original_path: rel_path,
+                trash_location,
+            };
+            let _ = app_storage::trash::record_trashed_notes(
+                db_path,
+                Path::new(&workspace_root),
+                &[input],
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds the registered vault containing `path`, returning its root as
+/// registered and the `/`-separated path inside it. Both sides are compared
+/// canonicalized, so symlinked vaults and respelled roots still match; the
+/// entry itself is not resolved, as a trashed link is the link. The vault
+/// root itself is not tracked.
+fn find_owning_workspace(workspaces: &[String], path: &Path) -> Option<(String, String)> {
+    let canonical = fs::canonicalize(path.parent()?)
+        .ok()?
+        .join(path.file_name()?);
+
+    workspaces
+        .iter()
+        .filter_map(|root| {
+            let canonical_root = fs::canonicalize(root).ok()?;
+            let rel_path = canonical.strip_prefix(&canonical_root).ok()?;
+            let rel_path = rel_path.to_string_lossy().replace('\\', "/");
+            (!rel_path.is_empty()).then(|| (root.clone(), rel_path, canonical_root))
+        })
+        .max_by_key(|(_, _, canonical_root)| canonical_root.components().count())
+        .map(|(root, rel_path, _)| (root, rel_path))
+}
+
+// The system trash records canonical paths, so compare against the same form.
+fn canonical_workspace_root(workspace_path: &str) -> PathBuf {
+    fs::canonicalize(workspace_path).unwrap_or_else(|_| PathBuf::from(workspace_path))
+}
+
+fn move_to_vault_trash(workspace_root: &Path, path: &Path) -> Result<PathBuf, String> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("Cannot trash {}", path.display()))?;
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    // Each item gets its own folder so equal names never collide.
+    let bucket = workspace_root.join(VAULT_TRASH_DIR).join(stamp.to_string());
+    fs::create_dir_all(&bucket).map_err(|error| error.to_string())?;
+
+    let location = bucket.join(file_name);
+    fs::rename(path, &location).map_err(|error| error.to_string())?;
+    Ok(location)
+}
+
+fn restore_from_vault_trash(location: &Path, destination: &Path) -> Result<(), String> {
+    fs::rename(location, destination)
+        .map_err(|error| format!("Failed to restore note: {}", error))?;
+
+    if let Some(bucket) = location.parent() {
+        let _ = fs::remove_dir(bucket);
+    }
+
+    Ok(())
+}
+
+fn is_restorable(
+    workspace_root: &Path,
+    record: &TrashedNoteRecord,
+    system_items: Option<&[PathBuf]>,
+) -> bool {
+    match (record.trash_location.as_deref(), system_items) {
+        (Some(location), _) => Path::new(location).exists(),
+        (None, Some(items)) => {
+            let original = workspace_root.join(&record.original_path);
+            items.iter().any(|item| *item == original)
+        }
+        // Without a way to look inside the system trash, keep the entry.
+        (None, None) => true,
+    }
+}
+
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+mod system_trash {
+    use std::path::{Path, PathBuf};
+
+    pub(super) const IS_LISTABLE: bool = true;
+
+    /// Original paths of everything in the system trash.
+    pub(super) fn list_original_paths() -> Option<Vec<PathBuf>> {
+        let items = trash::os_limited::list().ok()?;
+        Some(items.iter().map(|item| item.original_path()).collect())
+    }
+
+    pub(super) fn restore(destination: &Path) -> Result<(), String> {
+        let items = trash::os_limited::list()
+            .map_err(|error| format!("Failed to read system trash: {}", error))?;
+        let item = items
+            .into_iter()
+            .filter(|item| item.original_path() == destination)
+            .max_by_key(|item| item.time_deleted)
+            .ok_or_else(|| "The note is no longer in the system trash".to_string())?;
+
+        trash::os_limited::restore_all([item])
+            .map_err(|error| format!("Failed to restore note: {}", error))
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+mod system_trash {
+    use std::path::{Path, PathBuf};
+
+    pub(super) const IS_LISTABLE: bool = false;
+
+    pub(super) fn list_original_paths() -> Option<Vec<PathBuf>> {
+        None
+    }
+
+    pub(super) fn restore(_destination: &Path) -> Result<(), String> {
+        Err("Restoring from the system trash is not supported on this platform".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{auto_renamed_destination, copy_entry, find_owning_workspace, CollisionPolicy};
+    use std::ffi::OsStr;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock error")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("{prefix}-{id}"));
+        fs::create_dir_all(&path).expect("failed to create temp dir");
+        path
+    }
+
+    #[test]
+    fn auto_rename_skips_taken_numbered_names() {
+        let dir = temp_dir("mdit-transfer-auto-rename");
+        fs::write(dir.join("note.md"), "").expect("write note");
+        fs::write(dir.join("note 2.md"), "").expect("write note 2");
+
+        let destination = auto_renamed_destination(&dir, OsStr::new("note.md"));
+        assert_eq!(destination, dir.join("note 3.md"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn copy_respects_collision_policy() {
+        let root = temp_dir("mdit-transfer-copy");
+        let target = root.join("target");
+        fs::create_dir_all(&target).expect("create target");
+        fs::write(root.join("note.md"), "new").expect("write source");
+        fs::write(target.join("note.md"), "old").expect("write existing");
+
+        let source = root.join("note.md");
+        assert!(copy_entry(&source, &target, CollisionPolicy::Fail).is_err());
+
+        let renamed = copy_entry(&source, &target, CollisionPolicy::AutoRename)
+            .expect("auto rename should succeed");
+        assert_eq!(renamed, target.join("note 2.md"));
+
+        copy_entry(&source, &target, CollisionPolicy::Overwrite).expect("overwrite succeeds");
+        assert_eq!(
+            fs::read_to_string(target.join("note.md")).expect("read overwritten"),
+            "new"
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn copy_does_not_overwrite_locked_notes() {
+        let root = temp_dir("mdit-transfer-copy-locked");
+        let target = root.join("target");
+        fs::create_dir_all(&target).expect("create target");
+        fs::write(root.join("note.md"), "new").expect("write source");
+        let locked = "---\nlocked: true\n---\nSigned.";
+        fs::write(target.join("note.md"), locked).expect("write locked");
+
+        let source = root.join("note.md");
+        assert!(copy_entry(&source, &target, CollisionPolicy::Overwrite).is_err());
+        assert_eq!(
+            fs::read_to_string(target.join("note.md")).expect("read locked"),
+            locked
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn finds_the_vault_of_a_note_through_a_symlinked_root() {
+        let root = temp_dir("mdit-trash-owner");
+        let vault = root.join("vault");
+        fs::create_dir_all(vault.join("notes")).expect("create vault");
+        fs::write(vault.join("notes/todo.md"), "").expect("write note");
+        let linked = root.join("linked-vault");
+        std::os::unix::fs::symlink(&vault, &linked).expect("link vault");
+        let workspaces = vec![linked.to_string_lossy().into_owned()];
+
+        let owner = find_owning_workspace(
+            &workspaces,
+            &fs::canonicalize(vault.join("notes/todo.md")).expect("canonical note"),
+        );
+        assert_eq!(
+            owner,
+            Some((workspaces[0].clone(), "notes/todo.md".to_string()))
+        );
+        assert_eq!(find_owning_workspace(&workspaces, &linked), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
 }
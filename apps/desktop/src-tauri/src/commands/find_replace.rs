@@ -0,0 +1,85 @@
+use mdit_vault_indexing::{
+    find_in_vault, replace_in_vault, search_in_note, undo_last_replace, FindOptions,
+    NoteSearchResult, UndoReplaceResult, VaultFindResult, VaultReplaceResult,
+};
+use tauri::{AppHandle, Runtime};
+
+use crate::commands::vault_indexing::{index_run_guard, index_written_notes, run_blocking};
+use crate::path_scope::PathScope;
+
+/// Matches of `pattern` across the vault's notes, with the lines they sit on.
+#[tauri::command]
+pub async fn find_in_vault_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    pattern: String,
+    options: Option<FindOptions>,
+) -> Result<VaultFindResult, String> {
+    let workspace_root = PathScope::load(&app_handle)?.resolve(&workspace_path)?;
+    let options = options.unwrap_or_default();
+
+    run_blocking(move || find_in_vault(&workspace_root, &pattern, &options)).await
+}
+
+/// Every match range of `query` in one note, for the editor's find bar.
+#[tauri::command]
+pub async fn search_in_note_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: String,
+    query: String,
+    options: Option<FindOptions>,
+) -> Result<NoteSearchResult, String> {
+    let path = PathScope::load(&app_handle)?.resolve(&path)?;
+    let options = options.unwrap_or_default();
+
+    run_blocking(move || search_in_note(&path, &query, &options)).await
+}
+
+/// Replaces every match of `pattern` across the vault and reindexes the
+/// changed notes. The replace can be reverted with `undo_last_replace_command`.
+#[tauri::command]
+pub async fn replace_in_vault_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    pattern: String,
+    replacement: String,
+    options: Option<FindOptions>,
+) -> Result<VaultReplaceResult, String> {
+    let workspace_root = PathScope::load(&app_handle)?.resolve(&workspace_path)?;
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let run_guard = index_run_guard(&app_handle);
+    let options = options.unwrap_or_default();
+
+    run_blocking(move || {
+        let result = replace_in_vault(&workspace_root, &db_path, &pattern, &replacement, &options)?;
+        let paths = result
+            .files
+            .iter()
+            .map(|file| workspace_root.join(&file.rel_path));
+        index_written_notes(&run_guard, &workspace_root, &db_path, paths);
+        Ok(result)
+    })
+    .await
+}
+
+/// Reverts the vault's most recent replace. Notes edited since are left alone.
+#[tauri::command]
+pub async fn undo_last_replace_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+) -> Result<UndoReplaceResult, String> {
+    let workspace_root = PathScope::load(&app_handle)?.resolve(&workspace_path)?;
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let run_guard = index_run_guard(&app_handle);
+
+    run_blocking(move || {
+        let result = undo_last_replace(&workspace_root, &db_path)?;
+        let paths = result
+            .restored
+            .iter()
+            .map(|rel_path| workspace_root.join(rel_path));
+        index_written_notes(&run_guard, &workspace_root, &db_path, paths);
+        Ok(result)
+    })
+    .await
+}
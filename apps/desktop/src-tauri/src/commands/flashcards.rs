@@ -0,0 +1,174 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use mdit_note::{FlashcardKind, FlashcardPatterns};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+use crate::path_scope::PathScope;
+
+/// Notes to take flashcards from.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum FlashcardScope {
+    /// A single note.
+    Note { path: String },
+    /// Every note under the folder, subfolders included.
+    Folder { path: String },
+    /// Every note with the tag or a tag nested under it.
+    Tag { tag: String },
+    /// Every note in the vault.
+    Vault,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FlashcardExportOptions {
+    pub patterns: FlashcardPatterns,
+    /// Anki deck the cards go to; Anki asks when it is missing.
+    pub deck: Option<String>,
+    /// Export cards again even when an earlier export had them.
+    pub include_exported: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlashcardExportReport {
+    /// Nothing is written when no card is new.
+    pub output_path: Option<PathBuf>,
+    pub exported: usize,
+    pub basic: usize,
+    pub cloze: usize,
+    /// Cards left out because an earlier export had them.
+    pub already_exported: usize,
+    /// Notes the exported cards came from.
+    pub notes: usize,
+}
+
+/// Writes the flashcards found in `scope` to an Anki import file at
+/// `output_path`. Each card is remembered per note, so exporting again only
+/// writes cards added or changed since.
+#[tauri::command]
+pub async fn export_flashcards_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    scope: FlashcardScope,
+    output_path: String,
+    options: Option<FlashcardExportOptions>,
+) -> Result<FlashcardExportReport, String> {
+    let path_scope = PathScope::load(&app_handle)?;
+    let workspace_root = path_scope.resolve(&workspace_path)?;
+    let output_path = path_scope.resolve(&output_path)?;
+    let scope = match scope {
+        FlashcardScope::Note { path } => FlashcardScope::Note {
+            path: path_scope.resolve(&path)?.to_string_lossy().into_owned(),
+        },
+        FlashcardScope::Folder { path } => FlashcardScope::Folder {
+            path: path_scope.resolve(&path)?.to_string_lossy().into_owned(),
+        },
+        scope => scope,
+    };
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let options = options.unwrap_or_default();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let notes = collect_scope_notes(&workspace_root, &db_path, &scope)?;
+        export_flashcards(&workspace_root, &db_path, &notes, &output_path, &options)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+fn collect_scope_notes(
+    workspace_root: &Path,
+    db_path: &Path,
+    scope: &FlashcardScope,
+) -> Result<Vec<PathBuf>, String> {
+    let mut notes = match scope {
+        FlashcardScope::Note { path } => vec![PathBuf::from(path)],
+        FlashcardScope::Folder { path } => mdit_note::collect_folder_notes(Path::new(path))?,
+        FlashcardScope::Tag { tag } => {
+            mdit_vault_indexing::search_notes_by_tag(workspace_root, db_path, tag)
+                .map_err(|error| error.to_string())?
+                .into_iter()
+                .map(|entry| PathBuf::from(entry.path))
+                .collect()
+        }
+        FlashcardScope::Vault => mdit_note::collect_folder_notes(workspace_root)?,
+    };
+    notes.sort();
+    notes.dedup();
+    Ok(notes)
+}
+
+fn export_flashcards(
+    workspace_root: &Path,
+    db_path: &Path,
+    notes: &[PathBuf],
+    output_path: &Path,
+    options: &FlashcardExportOptions,
+) -> Result<FlashcardExportReport, String> {
+    let exported_before =
+        app_storage::flashcard_exports::exported_flashcard_hashes(db_path, workspace_root)
+            .map_err(|error| error.to_string())?;
+
+    let mut cards = Vec::new();
+    let mut exported = Vec::new();
+    let mut already_exported = 0;
+    let mut note_count = 0;
+    for note in notes {
+        let rel_path = note
+            .strip_prefix(workspace_root)
+            .map_err(|_| format!("Note is outside the vault: {}", note.display()))?
+            .to_string_lossy()
+            .replace('\\', "/");
+        let markdown = fs::read_to_string(note)
+            .map_err(|error| format!("Failed to read {}: {error}", note.display()))?;
+        let before = exported_before.get(&rel_path);
+        let mut seen = HashSet::new();
+        let mut from_note = 0;
+        for card in mdit_note::extract_flashcards(&markdown, &options.patterns) {
+            let hash = card.hash();
+            if !seen.insert(hash.clone()) {
+                continue;
+            }
+            if !options.include_exported && before.is_some_and(|hashes| hashes.contains(&hash)) {
+                already_exported += 1;
+                continue;
+            }
+            cards.push((card, vec![mdit_note::anki_source_tag(&rel_path)]));
+            exported.push((note.as_path(), hash));
+            from_note += 1;
+        }
+        if from_note > 0 {
+            note_count += 1;
+        }
+    }
+
+    let count_kind = |kind| cards.iter().filter(|(card, _)| card.kind == kind).count();
+    let mut report = FlashcardExportReport {
+        output_path: None,
+        exported: cards.len(),
+        basic: count_kind(FlashcardKind::Basic),
+        cloze: count_kind(FlashcardKind::Cloze),
+        already_exported,
+        notes: note_count,
+    };
+    if cards.is_empty() {
+        return Ok(report);
+    }
+
+    let tsv = mdit_note::flashcards_to_anki_tsv(&cards, options.deck.as_deref());
+    fs::write(output_path, tsv)
+        .map_err(|error| format!("Failed to write {}: {error}", output_path.display()))?;
+    let exported = exported
+        .iter()
+        .map(|(note, hash)| (*note, hash.as_str()))
+        .collect::<Vec<_>>();
+    app_storage::flashcard_exports::record_exported_flashcards(db_path, workspace_root, &exported)
+        .map_err(|error| error.to_string())?;
+
+    report.output_path = Some(output_path.to_path_buf());
+    Ok(report)
+}
@@ -1,17 +1,30 @@
+use tauri::{AppHandle, Runtime};
+
+use crate::path_scope::{resolve_path, PathScope};
+
 #[tauri::command]
-pub fn get_image_properties(
+pub fn get_image_properties<R: Runtime>(
+    app_handle: AppHandle<R>,
     path: String,
 ) -> Result<mdit_image_processing::ImageProperties, String> {
-    mdit_image_processing::get_image_properties(&path)
+    let path = resolve_path(&app_handle, &path)?;
+    mdit_image_processing::get_image_properties(&path.to_string_lossy())
 }
 
 #[tauri::command]
-pub async fn edit_image(
+pub async fn edit_image<R: Runtime>(
+    app_handle: AppHandle<R>,
     input_path: String,
-    options: mdit_image_processing::ImageEditOptions,
+    mut options: mdit_image_processing::ImageEditOptions,
 ) -> Result<String, String> {
+    let scope = PathScope::load(&app_handle)?;
+    let input_path = scope.resolve(&input_path)?;
+    if let Some(output_path) = options.output_path.as_deref() {
+        options.output_path = Some(scope.resolve(output_path)?.to_string_lossy().into_owned());
+    }
+
     tauri::async_runtime::spawn_blocking(move || {
-        mdit_image_processing::edit_image(&input_path, options)
+        mdit_image_processing::edit_image(&input_path.to_string_lossy(), options)
     })
     .await
     .map_err(|error| error.to_string())?
@@ -1,10 +1,12 @@
 use tauri::AppHandle;
 
 #[tauri::command]
-pub fn start_local_api_server_command(app_handle: AppHandle, token: String) -> Result<(), String> {
+pub fn start_local_api_server_command(app_handle: AppHandle, token: String) -> Result<u16, String> {
     crate::local_api::set_local_api_auth_token(&app_handle, token)
         .map_err(|error| format!("{error:#}"))?;
-    crate::local_api::start_local_api_server(&app_handle).map_err(|error| format!("{error:#}"))
+    crate::local_api::start_local_api_server(&app_handle).map_err(|error| format!("{error:#}"))?;
+    crate::app::background_mode::remember_local_api_enabled(&app_handle, true);
+    Ok(crate::local_api::local_api_port(&app_handle))
 }
 
 #[tauri::command]
@@ -16,8 +18,16 @@ pub fn set_local_api_auth_token_command(
         .map_err(|error| format!("{error:#}"))
 }
 
+/// The port differs per profile and moves when taken, so clients are told
+/// which one to use.
+#[tauri::command]
+pub fn get_local_api_port_command(app_handle: AppHandle) -> u16 {
+    crate::local_api::local_api_port(&app_handle)
+}
+
 #[tauri::command]
 pub fn stop_local_api_server_command(app_handle: AppHandle) -> Result<(), String> {
     crate::local_api::shutdown_local_api_server(&app_handle);
+    crate::app::background_mode::remember_local_api_enabled(&app_handle, false);
     Ok(())
 }
@@ -1,9 +1,20 @@
+pub mod citations;
 pub mod content;
 pub mod credentials;
+pub mod feeds;
 pub mod filesystem;
+pub mod find_replace;
+pub mod flashcards;
 pub mod image;
 pub mod local_api;
+pub mod note_import;
 pub mod ollama;
+pub mod pinned_notes;
+pub mod search_history;
+pub mod search_vocabulary;
+pub mod spellcheck;
+pub mod transcription;
 pub mod vault_indexing;
+pub mod vault_settings;
 pub mod vault_watch;
 pub mod window;
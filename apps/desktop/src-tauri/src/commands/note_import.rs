@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+
+use mdit_note_import::{
+    fetch_ics, import_calendar, import_notes, CalendarImport, CalendarImportOptions, ImportOptions,
+    ImportReport, ImportSource, ImportedFileKind,
+};
+use mdit_vault_indexing::format_daily_note_name;
+
+use crate::app::file_opening::attachment_folder;
+use crate::app::quick_capture::daily_note_format;
+use crate::commands::vault_indexing::{index_run_guard, index_written_notes};
+
+const MEETING_NOTES_FOLDER_SETTING: &str = "meetingNotesFolder";
+
+/// Imports a Bear backup, an Apple Notes export, Word documents or Kindle
+/// and Readwise highlights into the vault and indexes the new and extended
+/// notes. Images go to the vault's attachment folder. With `dry_run`, only
+/// the planned files are reported.
+#[tauri::command]
+pub async fn import_notes_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    source: ImportSource,
+    input_path: String,
+    target_folder: Option<String>,
+    dry_run: bool,
+) -> Result<ImportReport, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+    let input_path = PathBuf::from(input_path);
+    let options = ImportOptions {
+        target_folder: target_folder.unwrap_or_default(),
+        attachment_folder: attachment_folder(&db_path, &workspace_path)?,
+        dry_run,
+    };
+    let run_guard = index_run_guard(&app_handle);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let report = import_notes(source, &input_path, &workspace_path, &options)?;
+        if !report.dry_run {
+            let notes = report
+                .files
+                .iter()
+                .filter(|file| file.kind == ImportedFileKind::Note)
+                .map(|file| &file.rel_path)
+                .chain(report.updated.iter().map(|note| &note.rel_path))
+                .map(|rel_path| workspace_path.join(rel_path));
+            index_written_notes(&run_guard, &workspace_path, &db_path, notes);
+        }
+        Ok(report)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
+
+/// Creates or updates meeting notes for the events from `start` to `end` of
+/// an `.ics` file or a calendar URL, in the vault's meeting notes folder.
+/// Each new note links to the daily note of its meeting.
+#[tauri::command]
+pub async fn import_ics_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    source: String,
+    start: String,
+    end: String,
+) -> Result<CalendarImport, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+    let meeting_folder = app_storage::vault_settings::get_vault_setting(
+        &db_path,
+        &workspace_path,
+        MEETING_NOTES_FOLDER_SETTING,
+    )
+    .map_err(|error| error.to_string())?;
+    let options = CalendarImportOptions {
+        meeting_folder: meeting_folder
+            .value
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        start,
+        end,
+    };
+    let daily_note_format = daily_note_format(&db_path, &workspace_path)?;
+    let run_guard = index_run_guard(&app_handle);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let is_url = ["http://", "https://", "webcal://"]
+            .iter()
+            .any(|scheme| source.trim().starts_with(scheme));
+        let ics = if is_url {
+            fetch_ics(&source)?
+        } else {
+            std::fs::read_to_string(&source)
+                .map_err(|error| format!("Failed to read {source}: {error}"))?
+        };
+        let import = import_calendar(&ics, &workspace_path, &options, &|year, month, day| {
+            format_daily_note_name(&daily_note_format, year, month, day)
+        })?;
+
+        let notes = import
+            .created
+            .iter()
+            .chain(&import.updated)
+            .map(|note| workspace_path.join(&note.rel_path));
+        index_written_notes(&run_guard, &workspace_path, &db_path, notes);
+        Ok(import)
+    })
+    .await
+    .map_err(|error| error.to_string())?
+}
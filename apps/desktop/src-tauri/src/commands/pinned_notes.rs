@@ -0,0 +1,81 @@
+use std::path::{Path, PathBuf};
+
+use app_storage::pinned_notes::PinnedNote;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Broadcast to every window so pins made in one window show up in the others.
+pub const PINNED_NOTES_CHANGED_EVENT: &str = "pinned-notes-changed";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PinnedNotesChangedPayload {
+    workspace_path: String,
+    pinned_notes: Vec<PinnedNote>,
+}
+
+fn emit_pinned_notes_changed<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    workspace_path: String,
+    pinned_notes: &[PinnedNote],
+) {
+    let payload = PinnedNotesChangedPayload {
+        workspace_path,
+        pinned_notes: pinned_notes.to_vec(),
+    };
+    let _ = app_handle.emit(PINNED_NOTES_CHANGED_EVENT, payload);
+}
+
+#[tauri::command]
+pub fn list_pinned_notes_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+) -> Result<Vec<PinnedNote>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::pinned_notes::list_pinned_notes(&db_path, Path::new(&workspace_path))
+        .map_err(|error| error.to_string())
+}
+
+/// Pins or unpins `note_path` and returns the updated pins.
+#[tauri::command]
+pub fn pin_note_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    note_path: String,
+    pinned: bool,
+) -> Result<Vec<PinnedNote>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let pinned_notes = app_storage::pinned_notes::pin_note(
+        &db_path,
+        Path::new(&workspace_path),
+        Path::new(&note_path),
+        pinned,
+    )
+    .map_err(|error| error.to_string())?;
+
+    emit_pinned_notes_changed(&app_handle, workspace_path, &pinned_notes);
+    Ok(pinned_notes)
+}
+
+/// Moves the given pins to the front, in order, and returns the updated pins.
+#[tauri::command]
+pub fn reorder_pinned_notes_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    note_paths: Vec<String>,
+) -> Result<Vec<PinnedNote>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let note_paths = note_paths
+        .into_iter()
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    let pinned_notes = app_storage::pinned_notes::reorder_pinned_notes(
+        &db_path,
+        Path::new(&workspace_path),
+        &note_paths,
+    )
+    .map_err(|error| error.to_string())?;
+
+    emit_pinned_notes_changed(&app_handle, workspace_path, &pinned_notes);
+    Ok(pinned_notes)
+}
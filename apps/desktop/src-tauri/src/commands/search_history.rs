@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use app_storage::search_history::{self, SearchHistoryEntry};
+use tauri::{AppHandle, Runtime};
+
+const DEFAULT_HISTORY_LIMIT: usize = 20;
+const DEFAULT_SUGGESTION_LIMIT: usize = 8;
+
+/// Remembers a query the user ran, such as one whose result they opened.
+/// Returns `false` when the vault's `searchHistory` setting is off.
+#[tauri::command]
+pub fn record_search_query_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    query: String,
+) -> Result<bool, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    search_history::record_search_query(&db_path, &PathBuf::from(workspace_path), &query)
+        .map_err(|error| error.to_string())
+}
+
+/// The vault's most recent queries, newest first.
+#[tauri::command]
+pub fn get_search_history_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHistoryEntry>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    search_history::get_search_history(
+        &db_path,
+        &PathBuf::from(workspace_path),
+        limit.unwrap_or(DEFAULT_HISTORY_LIMIT),
+    )
+    .map_err(|error| error.to_string())
+}
+
+/// Past queries completing `prefix`, most used first.
+#[tauri::command]
+pub fn suggest_queries_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    prefix: String,
+    limit: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    search_history::suggest_queries(
+        &db_path,
+        &PathBuf::from(workspace_path),
+        &prefix,
+        limit.unwrap_or(DEFAULT_SUGGESTION_LIMIT),
+    )
+    .map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub fn clear_search_history_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+) -> Result<(), String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    search_history::clear_search_history(&db_path, &PathBuf::from(workspace_path))
+        .map_err(|error| error.to_string())
+}
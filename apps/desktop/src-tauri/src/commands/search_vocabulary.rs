@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use app_storage::search_vocabulary::{self, SearchSynonym};
+use tauri::{AppHandle, Runtime};
+
+#[tauri::command]
+pub fn list_search_synonyms_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+) -> Result<Vec<SearchSynonym>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    search_vocabulary::list_search_synonyms(&db_path, &PathBuf::from(workspace_path))
+        .map_err(|error| error.to_string())
+}
+
+/// Pairs `term` with `synonym` for the vault's searches and returns the
+/// updated pairs.
+#[tauri::command]
+pub fn add_search_synonym_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    term: String,
+    synonym: String,
+) -> Result<Vec<SearchSynonym>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    search_vocabulary::add_search_synonym(&db_path, &PathBuf::from(workspace_path), &term, &synonym)
+        .map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub fn remove_search_synonym_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    term: String,
+    synonym: String,
+) -> Result<Vec<SearchSynonym>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    search_vocabulary::remove_search_synonym(
+        &db_path,
+        &PathBuf::from(workspace_path),
+        &term,
+        &synonym,
+    )
+    .map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub fn list_search_stopwords_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+) -> Result<Vec<String>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    search_vocabulary::list_search_stopwords(&db_path, &PathBuf::from(workspace_path))
+        .map_err(|error| error.to_string())
+}
+
+/// Drops `word` from the vault's queries from now on and returns the updated
+/// stopwords.
+#[tauri::command]
+pub fn add_search_stopword_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    word: String,
+) -> Result<Vec<String>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    search_vocabulary::add_search_stopword(&db_path, &PathBuf::from(workspace_path), &word)
+        .map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub fn remove_search_stopword_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    word: String,
+) -> Result<Vec<String>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    search_vocabulary::remove_search_stopword(&db_path, &PathBuf::from(workspace_path), &word)
+        .map_err(|error| error.to_string())
+}
@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use mdit_spellcheck::{check_text, list_dictionaries, DictionaryStore, Misspelling};
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::commands::vault_indexing::run_blocking;
+
+const DICTIONARIES_DIR: &str = "dictionaries";
+// Where Linux distributions install hunspell dictionaries.
+#[cfg(target_os = "linux")]
+const SYSTEM_DICTIONARY_DIRS: &[&str] = &["/usr/share/hunspell", "/usr/share/myspell/dicts"];
+#[cfg(not(target_os = "linux"))]
+const SYSTEM_DICTIONARY_DIRS: &[&str] = &[];
+
+/// Dictionaries stay loaded for the life of the app; a large one takes a
+/// moment to expand.
+#[derive(Default)]
+pub struct SpellcheckState {
+    store: Arc<DictionaryStore>,
+}
+
+/// Folders searched for `<lang>.aff`/`<lang>.dic` pairs, in priority order:
+/// dictionaries the user dropped into the app data folder, then bundled
+/// ones, then the system's.
+fn dictionary_dirs<R: Runtime>(app_handle: &AppHandle<R>) -> Vec<PathBuf> {
+    [
+        crate::persistence::app_data_dir(app_handle),
+        app_handle.path().resource_dir(),
+    ]
+    .into_iter()
+    .filter_map(Result::ok)
+    .map(|dir| dir.join(DICTIONARIES_DIR))
+    .chain(SYSTEM_DICTIONARY_DIRS.iter().map(PathBuf::from))
+    .collect()
+}
+
+/// Misspelled words of `text` for the `lang` dictionary, such as `en-US`.
+/// With a `workspace_path`, the vault's custom words are accepted too.
+#[tauri::command]
+pub async fn spellcheck_text_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    state: State<'_, SpellcheckState>,
+    text: String,
+    lang: String,
+    workspace_path: Option<String>,
+) -> Result<Vec<Misspelling>, String> {
+    let custom_words = match workspace_path {
+        Some(workspace_path) => {
+            let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+            app_storage::spellcheck_words::list_spellcheck_words(
+                &db_path,
+                &PathBuf::from(workspace_path),
+            )
+            .map_err(|error| error.to_string())?
+        }
+        None => Vec::new(),
+    };
+    let dirs = dictionary_dirs(&app_handle);
+    let store = state.store.clone();
+
+    run_blocking(move || {
+        let dictionary = store.get(&dirs, &lang)?;
+        Ok(check_text(&dictionary, &custom_words, &text))
+    })
+    .await
+}
+
+/// Languages with an installed dictionary, such as `en_US`.
+#[tauri::command]
+pub fn list_spellcheck_languages_command<R: Runtime>(app_handle: AppHandle<R>) -> Vec<String> {
+    list_dictionaries(&dictionary_dirs(&app_handle))
+}
+
+#[tauri::command]
+pub fn list_spellcheck_words_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+) -> Result<Vec<String>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::spellcheck_words::list_spellcheck_words(&db_path, &PathBuf::from(workspace_path))
+        .map_err(|error| error.to_string())
+}
+
+/// Adds `word` to the vault's dictionary and returns the updated word list.
+#[tauri::command]
+pub fn add_spellcheck_word_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    word: String,
+) -> Result<Vec<String>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::spellcheck_words::add_spellcheck_word(
+        &db_path,
+        &PathBuf::from(workspace_path),
+        &word,
+    )
+    .map_err(|error| error.to_string())
+}
+
+/// Removes `word` from the vault's dictionary and returns the updated word list.
+#[tauri::command]
+pub fn remove_spellcheck_word_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    word: String,
+) -> Result<Vec<String>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::spellcheck_words::remove_spellcheck_word(
+        &db_path,
+        &PathBuf::from(workspace_path),
+        &word,
+    )
+    .map_err(|error| error.to_string())
+}
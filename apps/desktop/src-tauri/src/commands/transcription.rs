@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use mdit_transcription::TranscriptionRun;
+use mdit_vault_indexing::IndexRunGuard;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::commands::vault_indexing::{index_run_guard, index_written_notes};
+use crate::path_scope::PathScope;
+
+/// Broadcast when a run wrote notes, so open editors and file trees reload.
+pub const TRANSCRIPTIONS_WRITTEN_EVENT: &str = "transcriptions-written";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TranscriptionsWrittenPayload {
+    workspace_path: String,
+    run: TranscriptionRun,
+}
+
+/// Transcribes the audio linked from `note_path`, or from every note of the
+/// vault and its voice folder when no note is given, and indexes the notes
+/// that changed.
+#[tauri::command]
+pub async fn transcribe_audio_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    note_path: Option<String>,
+) -> Result<TranscriptionRun, String> {
+    let scope = PathScope::load(&app_handle)?;
+    let workspace_root = scope.resolve(&workspace_path)?;
+    let note_path = note_path.map(|path| scope.resolve(&path)).transpose()?;
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let config = mdit_transcription::load_transcription_config(&db_path, &workspace_root)?
+        .ok_or("Transcription is turned off for this vault")?;
+    let run_guard = index_run_guard(&app_handle);
+
+    let run = tauri::async_runtime::spawn_blocking(move || {
+        let run = match note_path {
+            Some(note_path) => {
+                mdit_transcription::transcribe_note(&workspace_root, &config, &note_path)?
+            }
+            None => mdit_transcription::transcribe_vault(&workspace_root, &config)?,
+        };
+        let notes = run.created.iter().chain(&run.updated);
+        index_written_notes(&run_guard, &workspace_root, &db_path, notes);
+        Ok::<_, String>(run)
+    })
+    .await
+    .map_err(|error| error.to_string())??;
+    emit_transcriptions_written(&app_handle, workspace_path, &run);
+    Ok(run)
+}
+
+/// Gives new recordings in the voice folder a note and a transcript, and
+/// indexes the notes. `None` while the vault has transcription turned off.
+pub(crate) fn transcribe_voice_folder(
+    run_guard: &IndexRunGuard,
+    db_path: &Path,
+    workspace_root: &Path,
+) -> Result<Option<TranscriptionRun>, String> {
+    let Some(config) = mdit_transcription::load_transcription_config(db_path, workspace_root)?
+    else {
+        return Ok(None);
+    };
+    let run = mdit_transcription::transcribe_voice_folder(workspace_root, &config)?;
+    let notes = run.created.iter().chain(&run.updated);
+    index_written_notes(run_guard, workspace_root, db_path, notes);
+    Ok(Some(run))
+}
+
+pub(crate) fn emit_transcriptions_written<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    workspace_path: String,
+    run: &TranscriptionRun,
+) {
+    if run.created.is_empty() && run.updated.is_empty() {
+        return;
+    }
+    let payload = TranscriptionsWrittenPayload {
+        workspace_path,
+        run: run.clone(),
+    };
+    let _ = app_handle.emit(TRANSCRIPTIONS_WRITTEN_EVENT, payload);
+}
@@ -1,16 +1,41 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use app_storage::embedding_providers::EmbeddingProviderConfig;
+use app_storage::sqlite_ext::VectorSupport;
 use app_storage::vault::VaultEmbeddingConfig;
+use app_storage::vault_template::{CreatedVault, VaultTemplate};
+use mdit_note::{ArchiveResult, MovedPath};
 use mdit_vault_indexing::{
-    delete_indexed_note, get_backlinks, get_graph_view_data, get_indexing_meta, get_related_notes,
-    index_note, index_vault_documents, refresh_workspace_embeddings, rename_indexed_note,
-    resolve_wiki_link, search_notes_by_tag, search_notes_for_query, BacklinkEntry, GraphViewData,
-    IndexSummary, IndexingMeta, RelatedNoteEntry, ResolveWikiLinkRequest, ResolveWikiLinkResult,
-    SemanticNoteEntry, TagNoteEntry,
+    break_vault_lock, build_context, check_embedding_provider, compare_vaults, copy_vault_files,
+    delete_indexed_note, export_graph, find_query_dependents, get_activity_heatmap, get_backlinks,
+    get_calendar_data, get_embedding_coverage, get_graph_view_data, get_indexing_meta,
+    get_link_counts, get_note_aliases, get_note_index_status, get_notes_in_bounds, get_notes_near,
+    get_related_notes, get_vault_health, get_writing_stats, group_search_results,
+    index_note_with_profiles, index_vault_documents_with_profiles, query_notes,
+    refresh_workspace_embeddings_with_profiles, reindex_note_with_profiles, rename_indexed_note,
+    render_query_block, resolve_wiki_link_with_index, run_vault_diagnostics, search_notes_by_tag,
+    search_notes_for_query_with_options, search_notes_with_correction, search_segments,
+    suggest_person_links, ActivityHeatmap, BacklinkEntry, CalendarDay, ContextRequest,
+    CorrectedSearchResults, EmbeddingCoverage, EmbeddingProfile, EmbeddingProviderCheck, GeoBounds,
+    GeoNote, GraphExportFormat, GraphExportOptions, GraphExportReport, GraphViewData,
+    IndexRunGuard, IndexSummary, IndexingMeta, NoteContext, NoteIndexStatus, NoteQuery,
+    NoteQueryResult, PersonLinkSuggestion, RelatedNoteEntry, ResolveWikiLinkRequest,
+    ResolveWikiLinkResult, SearchMode, SearchOptions, SearchScope, SegmentMatch, SemanticNoteEntry,
+    TagNoteEntry, VaultComparison, VaultCopyReport, VaultDiagnostics, VaultHealth, VaultLockInfo,
+    WritingStats, DEFAULT_CONTENT_MAX_BYTES,
 };
-use tauri::{AppHandle, Runtime};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
 
-async fn run_blocking<F, T>(f: F) -> Result<T, String>
+use crate::app::maintenance::MaintenanceState;
+use crate::commands::vault_watch::VaultWatchRuntimeState;
+use crate::path_scope::PathScope;
+
+const PEOPLE_FOLDER_SETTING: &str = "peopleFolder";
+const DEFAULT_SEGMENT_SEARCH_LIMIT: usize = 20;
+
+pub(crate) async fn run_blocking<F, T>(f: F) -> Result<T, String>
 where
     F: FnOnce() -> anyhow::Result<T> + Send + 'static,
     T: Send + 'static,
@@ -21,16 +46,122 @@ where
         .map_err(|error| error.to_string())
 }
 
-fn resolve_embedding_for_workspace(
+/// Guard shared by every writer of the index, the vault watcher included.
+pub(crate) fn index_run_guard<R: Runtime>(app_handle: &AppHandle<R>) -> IndexRunGuard {
+    app_handle.state::<IndexRunGuard>().inner().clone()
+}
+
+/// Indexes notes a command has already written. The notes stay written
+/// either way, so indexing failures are only logged.
+pub(crate) fn index_written_notes<P: AsRef<Path>>(
+    run_guard: &IndexRunGuard,
+    workspace_root: &Path,
+    db_path: &Path,
+    paths: impl IntoIterator<Item = P>,
+) {
+    let mut paths = paths
+        .into_iter()
+        .map(|path| path.as_ref().to_path_buf())
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths.dedup();
+    if paths.is_empty() {
+        return;
+    }
+    if let Err(error) = run_guard.run_exclusive(workspace_root, || {
+        for path in &paths {
+            if let Err(error) = index_note_with_profiles(workspace_root, db_path, path, &[]) {
+                tracing::error!("Failed to index note {}: {error}", path.display());
+            }
+        }
+        Ok(())
+    }) {
+        tracing::error!("Failed to index written notes: {error}");
+    }
+}
+
+/// Points the index rows of `moved_notes` at their new paths under the run
+/// guard, then moves the pins, note ids and flashcard history kept for
+/// `old_path`, a note or a folder, to `new_path`. Only the index result is
+/// returned; the other records are repaired on their own and only logged.
+pub(crate) fn rename_indexed_entry(
+    run_guard: &IndexRunGuard,
+    workspace_root: &Path,
+    db_path: &Path,
+    old_path: &Path,
+    new_path: &Path,
+    moved_notes: &[(PathBuf, PathBuf)],
+) -> anyhow::Result<bool> {
+    let renamed = run_guard.run_exclusive(workspace_root, || {
+        // One failing note should not leave the rest of a folder behind.
+        let mut renamed = false;
+        let mut first_error = None;
+        for (old_note_path, new_note_path) in moved_notes {
+            match rename_indexed_note(workspace_root, db_path, old_note_path, new_note_path) {
+                Ok(changed) => renamed |= changed,
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+        first_error.map_or(Ok(renamed), Err)
+    });
+
+    if let Err(error) =
+        app_storage::pinned_notes::rename_pinned_notes(db_path, workspace_root, old_path, new_path)
+    {
+        tracing::error!("Failed to move pins for {}: {error}", new_path.display());
+    }
+    if let Err(error) = app_storage::note_identity::rename_note_identities(
+        db_path,
+        workspace_root,
+        old_path,
+        new_path,
+    ) {
+        tracing::error!(
+            "Failed to move note ids for {}: {error}",
+            new_path.display()
+        );
+    }
+    if let Err(error) = app_storage::flashcard_exports::rename_exported_flashcards(
+        db_path,
+        workspace_root,
+        old_path,
+        new_path,
+    ) {
+        tracing::error!(
+            "Failed to move flashcard history for {}: {error}",
+            new_path.display()
+        );
+    }
+
+    renamed
+}
+
+/// Probes sqlite-vec once at startup and keeps the result for status checks.
+/// Without it indexing and search carry on with keywords only.
+pub(crate) fn initialize_vector_support<R: Runtime>(app_handle: &AppHandle<R>) {
+    let support = app_storage::sqlite_ext::vector_support().clone();
+    if let Some(error) = support.error.as_deref() {
+        tracing::warn!("vector search unavailable, falling back to keyword search: {error}");
+    }
+    app_handle.manage(support);
+}
+
+pub(crate) fn resolve_embedding_for_workspace(
     db_path: &Path,
     workspace_path: &Path,
-) -> Result<(String, String), String> {
+) -> Result<Vec<EmbeddingProfile>, String> {
     let embedding_config = app_storage::vault::get_embedding_config(db_path, workspace_path)
         .map_err(|error| error.to_string())?;
 
     match embedding_config {
-        Some(config) => Ok((config.embedding_provider, config.embedding_model)),
-        None => Ok((String::new(), String::new())),
+        Some(config) => Ok(config
+            .profiles()
+            .into_iter()
+            .map(|(provider, model)| EmbeddingProfile::new(provider, model))
+            .collect()),
+        None => Ok(Vec::new()),
     }
 }
 
@@ -42,17 +173,18 @@ pub async fn index_vault_documents_command(
 ) -> Result<IndexSummary, String> {
     let db_path = crate::persistence::run_app_migrations(&app_handle)?;
     let workspace_path = PathBuf::from(workspace_path);
-    let (embedding_provider, embedding_model) =
-        resolve_embedding_for_workspace(&db_path, &workspace_path)?;
+    let embedding_profiles = resolve_embedding_for_workspace(&db_path, &workspace_path)?;
+    let run_guard = index_run_guard(&app_handle);
 
     run_blocking(move || {
-        index_vault_documents(
-            &workspace_path,
-            &db_path,
-            &embedding_provider,
-            &embedding_model,
-            force_reindex,
-        )
+        run_guard.run_workspace(&workspace_path, force_reindex, |force_reindex| {
+            index_vault_documents_with_profiles(
+                &workspace_path,
+                &db_path,
+                &embedding_profiles,
+                force_reindex,
+            )
+        })
     })
     .await
 }
@@ -63,29 +195,50 @@ pub async fn index_note_command(
     workspace_path: String,
     note_path: String,
     include_embeddings: Option<bool>,
+    force: Option<bool>,
 ) -> Result<IndexSummary, String> {
     let db_path = crate::persistence::run_app_migrations(&app_handle)?;
     let workspace_path = PathBuf::from(workspace_path);
     let note_path = PathBuf::from(note_path);
     let should_include_embeddings = include_embeddings.unwrap_or(true);
-    let (embedding_provider, embedding_model) = if should_include_embeddings {
+    let embedding_profiles = if should_include_embeddings {
         resolve_embedding_for_workspace(&db_path, &workspace_path)?
     } else {
-        (String::new(), String::new())
+        Vec::new()
     };
+    let force = force.unwrap_or(false);
+    let run_guard = index_run_guard(&app_handle);
 
     run_blocking(move || {
-        index_note(
-            &workspace_path,
-            &db_path,
-            &note_path,
-            &embedding_provider,
-            &embedding_model,
-        )
+        run_guard.run_exclusive(&workspace_path, || {
+            if force {
+                reindex_note_with_profiles(
+                    &workspace_path,
+                    &db_path,
+                    &note_path,
+                    &embedding_profiles,
+                )
+            } else {
+                index_note_with_profiles(&workspace_path, &db_path, &note_path, &embedding_profiles)
+            }
+        })
     })
     .await
 }
 
+#[tauri::command]
+pub async fn get_note_index_status_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    path: String,
+) -> Result<NoteIndexStatus, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+    let note_path = PathBuf::from(path);
+
+    run_blocking(move || get_note_index_status(&workspace_path, &db_path, &note_path)).await
+}
+
 #[tauri::command]
 pub async fn refresh_workspace_embeddings_command(
     app_handle: tauri::AppHandle,
@@ -93,16 +246,17 @@ pub async fn refresh_workspace_embeddings_command(
 ) -> Result<IndexSummary, String> {
     let db_path = crate::persistence::run_app_migrations(&app_handle)?;
     let workspace_path = PathBuf::from(workspace_path);
-    let (embedding_provider, embedding_model) =
-        resolve_embedding_for_workspace(&db_path, &workspace_path)?;
+    let embedding_profiles = resolve_embedding_for_workspace(&db_path, &workspace_path)?;
+    let run_guard = index_run_guard(&app_handle);
 
     run_blocking(move || {
-        refresh_workspace_embeddings(
-            &workspace_path,
-            &db_path,
-            &embedding_provider,
-            &embedding_model,
-        )
+        run_guard.run_exclusive(&workspace_path, || {
+            refresh_workspace_embeddings_with_profiles(
+                &workspace_path,
+                &db_path,
+                &embedding_profiles,
+            )
+        })
     })
     .await
 }
@@ -118,9 +272,19 @@ pub async fn rename_indexed_note_command(
     let workspace_path = PathBuf::from(workspace_path);
     let old_note_path = PathBuf::from(old_note_path);
     let new_note_path = PathBuf::from(new_note_path);
+    mdit_note::ensure_note_unlocked(&old_note_path)?;
+    mdit_note::ensure_note_unlocked(&new_note_path)?;
+    let run_guard = index_run_guard(&app_handle);
 
     run_blocking(move || {
-        rename_indexed_note(&workspace_path, &db_path, &old_note_path, &new_note_path)
+        rename_indexed_entry(
+            &run_guard,
+            &workspace_path,
+            &db_path,
+            &old_note_path,
+            &new_note_path,
+            &[(old_note_path.clone(), new_note_path.clone())],
+        )
     })
     .await
 }
@@ -134,8 +298,22 @@ pub async fn delete_indexed_note_command(
     let db_path = crate::persistence::run_app_migrations(&app_handle)?;
     let workspace_path = PathBuf::from(workspace_path);
     let note_path = PathBuf::from(note_path);
+    mdit_note::ensure_note_unlocked(&note_path)?;
+    let run_guard = index_run_guard(&app_handle);
+
+    run_blocking(move || {
+        run_guard.run_exclusive(&workspace_path, || {
+            delete_indexed_note(&workspace_path, &db_path, &note_path)
+        })
+    })
+    .await
+}
 
-    run_blocking(move || delete_indexed_note(&workspace_path, &db_path, &note_path)).await
+/// Removes a lock another process left on the vault's index, returning the
+/// holder it removed.
+#[tauri::command]
+pub fn break_vault_lock_command(workspace_path: String) -> Result<Option<VaultLockInfo>, String> {
+    break_vault_lock(Path::new(&workspace_path)).map_err(|error| error.to_string())
 }
 
 #[tauri::command]
@@ -144,32 +322,295 @@ pub fn get_indexing_meta_command(
     workspace_path: String,
 ) -> Result<IndexingMeta, String> {
     let db_path = crate::persistence::run_app_migrations(&app_handle)?;
-    get_indexing_meta(&PathBuf::from(workspace_path), &db_path).map_err(|error| error.to_string())
+    let workspace_path = PathBuf::from(workspace_path);
+    let mut meta =
+        get_indexing_meta(&workspace_path, &db_path).map_err(|error| error.to_string())?;
+    meta.run_status = index_run_guard(&app_handle).status(&workspace_path);
+    meta.vector_search = app_handle.state::<VectorSupport>().inner().clone();
+    Ok(meta)
+}
+
+const ARCHIVE_FOLDER_SETTING: &str = "archiveFolder";
+
+fn archive_folder_for_workspace(db_path: &Path, workspace_path: &Path) -> Result<String, String> {
+    let setting = app_storage::vault_settings::get_vault_setting(
+        db_path,
+        workspace_path,
+        ARCHIVE_FOLDER_SETTING,
+    )
+    .map_err(|error| error.to_string())?;
+    Ok(setting.value.as_str().unwrap_or("Archive").to_string())
+}
+
+/// Points the index at the note's new path, then re-indexes it so the
+/// `archived` flag follows the frontmatter.
+fn reindex_moved_note(
+    run_guard: &IndexRunGuard,
+    workspace_path: &Path,
+    db_path: &Path,
+    moved: &MovedPath,
+    embedding_profiles: &[EmbeddingProfile],
+) -> anyhow::Result<()> {
+    run_guard.run_exclusive(workspace_path, || {
+        rename_indexed_note(workspace_path, db_path, &moved.from, &moved.to)?;
+        index_note_with_profiles(workspace_path, db_path, &moved.to, embedding_profiles)?;
+        Ok(())
+    })
+}
+
+/// Moves a note, and optionally the attachments only it embeds, into the
+/// vault's archive folder.
+#[tauri::command]
+pub async fn archive_note_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    note_path: String,
+    include_attachments: Option<bool>,
+) -> Result<ArchiveResult, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let scope = PathScope::load(&app_handle)?;
+    let workspace_path = scope.resolve(&workspace_path)?;
+    let note_path = scope.resolve_entry(&note_path)?;
+    let archive_folder = archive_folder_for_workspace(&db_path, &workspace_path)?;
+    let embedding_profiles = resolve_embedding_for_workspace(&db_path, &workspace_path)?;
+
+    let result = mdit_note::archive_note(
+        &workspace_path,
+        &note_path,
+        &archive_folder,
+        include_attachments.unwrap_or(false),
+    )?;
+    let run_guard = index_run_guard(&app_handle);
+    run_blocking(move || {
+        reindex_moved_note(
+            &run_guard,
+            &workspace_path,
+            &db_path,
+            &result.note,
+            &embedding_profiles,
+        )?;
+        Ok(result)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn unarchive_note_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    note_path: String,
+) -> Result<ArchiveResult, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let scope = PathScope::load(&app_handle)?;
+    let workspace_path = scope.resolve(&workspace_path)?;
+    let note_path = scope.resolve_entry(&note_path)?;
+    let archive_folder = archive_folder_for_workspace(&db_path, &workspace_path)?;
+    let embedding_profiles = resolve_embedding_for_workspace(&db_path, &workspace_path)?;
+
+    let result = mdit_note::unarchive_note(&workspace_path, &note_path, &archive_folder)?;
+    let run_guard = index_run_guard(&app_handle);
+    run_blocking(move || {
+        reindex_moved_note(
+            &run_guard,
+            &workspace_path,
+            &db_path,
+            &result.note,
+            &embedding_profiles,
+        )?;
+        Ok(result)
+    })
+    .await
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn search_query_entries_command(
     app_handle: tauri::AppHandle,
     workspace_path: String,
     query: String,
+    code_only: Option<bool>,
+    include_archived: Option<bool>,
+    regex: Option<bool>,
+    sort: Option<SearchSort>,
+    include_content: Option<bool>,
+    content_max_bytes: Option<usize>,
+    collapse_similar: Option<bool>,
 ) -> Result<Vec<SemanticNoteEntry>, String> {
     let db_path = crate::persistence::run_app_migrations(&app_handle)?;
     let workspace_path = PathBuf::from(workspace_path);
-    let (embedding_provider, embedding_model) =
-        resolve_embedding_for_workspace(&db_path, &workspace_path)?;
+    let embedding_profiles = resolve_embedding_for_workspace(&db_path, &workspace_path)?;
+    let options = SearchOptions {
+        content_max_bytes: content_max_bytes_option(include_content, content_max_bytes),
+        ..search_options(code_only, include_archived, regex, sort, collapse_similar)
+    };
 
     run_blocking(move || {
-        search_notes_for_query(
+        search_notes_for_query_with_options(
             &workspace_path,
             &db_path,
             &query,
-            &embedding_provider,
-            &embedding_model,
+            &embedding_profiles,
+            options,
         )
     })
     .await
 }
 
+/// Same as `search_query_entries_command`, retried with misspelled words
+/// corrected when the query finds nothing.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn search_query_with_correction_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    query: String,
+    code_only: Option<bool>,
+    include_archived: Option<bool>,
+    regex: Option<bool>,
+    sort: Option<SearchSort>,
+    include_content: Option<bool>,
+    content_max_bytes: Option<usize>,
+    collapse_similar: Option<bool>,
+) -> Result<CorrectedSearchResults, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+    let embedding_profiles = resolve_embedding_for_workspace(&db_path, &workspace_path)?;
+    let options = SearchOptions {
+        content_max_bytes: content_max_bytes_option(include_content, content_max_bytes),
+        ..search_options(code_only, include_archived, regex, sort, collapse_similar)
+    };
+
+    run_blocking(move || {
+        search_notes_with_correction(
+            &workspace_path,
+            &db_path,
+            &query,
+            &embedding_profiles,
+            options,
+        )
+    })
+    .await
+}
+
+/// Same as `search_query_entries_command`, with the results bucketed by
+/// folder or tag.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn search_query_groups_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    query: String,
+    group_by: SearchGroupBy,
+    code_only: Option<bool>,
+    include_archived: Option<bool>,
+    regex: Option<bool>,
+    sort: Option<SearchSort>,
+    collapse_similar: Option<bool>,
+) -> Result<Vec<SearchResultGroup>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+    let embedding_profiles = resolve_embedding_for_workspace(&db_path, &workspace_path)?;
+    let options = search_options(code_only, include_archived, regex, sort, collapse_similar);
+
+    run_blocking(move || {
+        let entries = search_notes_for_query_with_options(
+            &workspace_path,
+            &db_path,
+            &query,
+            &embedding_profiles,
+            options,
+        )?;
+        group_search_results(&workspace_path, &db_path, entries, group_by)
+    })
+    .await
+}
+
+/// Matching segments ranked on their own, for passage-level retrieval.
+#[tauri::command]
+pub async fn search_segments_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SegmentMatch>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+    let embedding_profiles = resolve_embedding_for_workspace(&db_path, &workspace_path)?;
+    let limit = limit.unwrap_or(DEFAULT_SEGMENT_SEARCH_LIMIT);
+
+    run_blocking(move || {
+        search_segments(
+            &workspace_path,
+            &db_path,
+            &query,
+            &embedding_profiles,
+            limit,
+        )
+    })
+    .await
+}
+
+fn content_max_bytes_option(
+    include_content: Option<bool>,
+    content_max_bytes: Option<usize>,
+) -> Option<usize> {
+    include_content
+        .unwrap_or(false)
+        .then(|| content_max_bytes.unwrap_or(DEFAULT_CONTENT_MAX_BYTES))
+}
+
+fn search_options(
+    code_only: Option<bool>,
+    include_archived: Option<bool>,
+    regex: Option<bool>,
+    sort: Option<SearchSort>,
+    collapse_similar: Option<bool>,
+) -> SearchOptions {
+    SearchOptions {
+        scope: if code_only.unwrap_or(false) {
+            SearchScope::CodeOnly
+        } else {
+            SearchScope::All
+        },
+        include_archived: include_archived.unwrap_or(false),
+        mode: if regex.unwrap_or(false) {
+            SearchMode::Regex
+        } else {
+            SearchMode::Hybrid
+        },
+        sort: sort.unwrap_or_default(),
+        content_max_bytes: None,
+        collapse_similar: collapse_similar.unwrap_or(false),
+    }
+}
+
+/// Packs note passages for an AI client into `max_tokens`. Passages from
+/// `note_paths` come first, then the best passage of each note matching `query`.
+#[tauri::command]
+pub async fn build_context_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    query: Option<String>,
+    note_paths: Option<Vec<String>>,
+    max_tokens: usize,
+) -> Result<NoteContext, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+    let embedding_profiles = resolve_embedding_for_workspace(&db_path, &workspace_path)?;
+    let request = ContextRequest {
+        query,
+        note_paths: note_paths
+            .unwrap_or_default()
+            .into_iter()
+            .map(PathBuf::from)
+            .collect(),
+        max_tokens,
+    };
+
+    run_blocking(move || build_context(&workspace_path, &db_path, &request, &embedding_profiles))
+        .await
+}
+
 #[tauri::command]
 pub async fn search_tag_entries_command(
     app_handle: tauri::AppHandle,
@@ -184,6 +625,7 @@ pub async fn search_tag_entries_command(
 
 #[tauri::command]
 pub async fn resolve_wiki_link_command(
+    app_handle: tauri::AppHandle,
     workspace_path: String,
     current_note_path: Option<String>,
     raw_target: String,
@@ -195,8 +637,80 @@ pub async fn resolve_wiki_link_command(
         raw_target,
         workspace_rel_paths,
     };
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+
+    run_blocking(move || resolve_wiki_link_with_index(request, &db_path)).await
+}
+
+#[tauri::command]
+pub async fn get_note_aliases_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+
+    run_blocking(move || get_note_aliases(&workspace_path, &db_path)).await
+}
+
+#[tauri::command]
+pub async fn get_vault_diagnostics_command(
+    workspace_path: String,
+) -> Result<VaultDiagnostics, String> {
+    let workspace_path = PathBuf::from(workspace_path);
+
+    run_blocking(move || run_vault_diagnostics(&workspace_path)).await
+}
+
+/// Everything the settings health panel shows, in one call.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultHealthReport {
+    #[serde(flatten)]
+    pub index: VaultHealth,
+    /// A watcher keeps the index current as files change.
+    pub watching: bool,
+    /// Unix seconds when index maintenance last finished a task for the
+    /// vault, since the app started.
+    pub last_maintenance_at: Option<u64>,
+}
+
+#[tauri::command]
+pub async fn get_vault_health_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+) -> Result<VaultHealthReport, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let watching = app_handle
+        .state::<VaultWatchRuntimeState>()
+        .watched_workspaces()
+        .contains(&workspace_path);
+    let last_maintenance_at = app_handle
+        .state::<MaintenanceState>()
+        .last_completed_at(&workspace_path)
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_secs());
+    let workspace_path = PathBuf::from(workspace_path);
+
+    let index = run_blocking(move || get_vault_health(&workspace_path, &db_path)).await?;
+    Ok(VaultHealthReport {
+        index,
+        watching,
+        last_maintenance_at,
+    })
+}
+
+/// Whether semantic search results can be trusted right now: notes embedded
+/// with the current model, stale ones and segments past the model's window.
+#[tauri::command]
+pub async fn get_embedding_coverage_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+) -> Result<EmbeddingCoverage, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
 
-    run_blocking(move || resolve_wiki_link(request)).await
+    run_blocking(move || get_embedding_coverage(&workspace_path, &db_path)).await
 }
 
 #[tauri::command]
@@ -212,6 +726,20 @@ pub async fn get_backlinks_command(
     run_blocking(move || get_backlinks(&workspace_path, &db_path, &file_path)).await
 }
 
+/// Inbound link counts of the notes at `rel_paths`, keyed by path, for
+/// file tree badges without one backlink lookup per note.
+#[tauri::command]
+pub async fn get_link_counts_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    rel_paths: Vec<String>,
+) -> Result<HashMap<String, usize>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+
+    run_blocking(move || get_link_counts(&workspace_path, &db_path, &rel_paths)).await
+}
+
 #[tauri::command]
 pub async fn get_related_notes_command(
     app_handle: tauri::AppHandle,
@@ -238,6 +766,237 @@ pub async fn get_graph_view_data_command(
     run_blocking(move || get_graph_view_data(&workspace_path, &db_path)).await
 }
 
+/// Writes the vault's note graph to `output_path` as GraphML, DOT or
+/// D3-style JSON, for analysis in Gephi or scripts.
+#[tauri::command]
+pub async fn export_graph_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    output_path: String,
+    format: GraphExportFormat,
+    options: Option<GraphExportOptions>,
+) -> Result<GraphExportReport, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let scope = PathScope::load(&app_handle)?;
+    let workspace_path = scope.resolve(&workspace_path)?;
+    let output_path = scope.resolve(&output_path)?;
+    let options = options.unwrap_or_default();
+
+    run_blocking(move || export_graph(&workspace_path, &db_path, &output_path, format, &options))
+        .await
+}
+
+/// Classifies the files of two copies of a vault as identical, changed or
+/// present in only one of them.
+#[tauri::command]
+pub async fn compare_vaults_command(
+    app_handle: tauri::AppHandle,
+    path_a: String,
+    path_b: String,
+) -> Result<VaultComparison, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let scope = PathScope::load(&app_handle)?;
+    let path_a = scope.resolve(&path_a)?;
+    let path_b = scope.resolve(&path_b)?;
+
+    run_blocking(move || compare_vaults(&db_path, &path_a, &path_b)).await
+}
+
+/// Copies the files at `rel_paths` from one copy of a vault to the other,
+/// replacing what is there, and indexes the copied notes.
+#[tauri::command]
+pub async fn copy_vault_files_command(
+    app_handle: tauri::AppHandle,
+    source_path: String,
+    target_path: String,
+    rel_paths: Vec<String>,
+) -> Result<VaultCopyReport, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let scope = PathScope::load(&app_handle)?;
+    let source_path = scope.resolve(&source_path)?;
+    let target_path = scope.resolve(&target_path)?;
+    let embedding_profiles = resolve_embedding_for_workspace(&db_path, &target_path)?;
+    let run_guard = index_run_guard(&app_handle);
+
+    run_blocking(move || {
+        run_guard.run_exclusive(&target_path, || {
+            copy_vault_files(
+                &db_path,
+                &source_path,
+                &target_path,
+                &rel_paths,
+                &embedding_profiles,
+            )
+        })
+    })
+    .await
+}
+
+/// Mentions in the note of people with a page in the vault's people
+/// folder, each with the wiki link that would replace it.
+#[tauri::command]
+pub async fn suggest_person_links_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    note_path: String,
+) -> Result<Vec<PersonLinkSuggestion>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+    let note_path = PathBuf::from(note_path);
+    let people_folder = app_storage::vault_settings::get_vault_setting(
+        &db_path,
+        &workspace_path,
+        PEOPLE_FOLDER_SETTING,
+    )
+    .map_err(|error| error.to_string())?;
+    let people_folder = people_folder.value.as_str().unwrap_or_default().to_string();
+
+    run_blocking(move || {
+        suggest_person_links(&workspace_path, &db_path, &note_path, &people_folder)
+    })
+    .await
+}
+
+/// Notes whose frontmatter places them within `radius_km` of a point,
+/// nearest first.
+#[tauri::command]
+pub async fn get_notes_near_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    lat: f64,
+    lng: f64,
+    radius_km: f64,
+) -> Result<Vec<GeoNote>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+
+    run_blocking(move || get_notes_near(&workspace_path, &db_path, lat, lng, radius_km)).await
+}
+
+/// Located notes inside the visible area of a map.
+#[tauri::command]
+pub async fn get_notes_in_bounds_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    bounds: GeoBounds,
+) -> Result<Vec<GeoNote>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+
+    run_blocking(move || get_notes_in_bounds(&workspace_path, &db_path, bounds)).await
+}
+
+/// Inclusive `YYYY-MM-DD` day range shown by the calendar.
+#[derive(Debug, Deserialize)]
+pub struct CalendarRange {
+    start: String,
+    end: String,
+}
+
+#[tauri::command]
+pub async fn get_calendar_data_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    range: CalendarRange,
+) -> Result<Vec<CalendarDay>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+    let daily_note_format =
+        crate::app::quick_capture::daily_note_format(&db_path, &workspace_path)?;
+
+    run_blocking(move || {
+        get_calendar_data(
+            &workspace_path,
+            &db_path,
+            &range.start,
+            &range.end,
+            &daily_note_format,
+        )
+    })
+    .await
+}
+
+/// Runs a table query over the indexed notes: a folder or tag source, the
+/// fields to show, their sort and a row limit.
+#[tauri::command]
+pub async fn query_notes_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    query: NoteQuery,
+) -> Result<NoteQueryResult, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+
+    run_blocking(move || query_notes(&workspace_path, &db_path, &query)).await
+}
+
+/// Runs the body of an `mdit-query` block written in `note_path`.
+#[tauri::command]
+pub async fn render_query_block_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    note_path: String,
+    block_content: String,
+) -> Result<NoteQueryResult, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+    let note_path = PathBuf::from(note_path);
+
+    run_blocking(move || render_query_block(&workspace_path, &db_path, &note_path, &block_content))
+        .await
+}
+
+/// Vault-relative paths of the notes whose query blocks read from any of
+/// `rel_paths`, to refresh after those notes are saved and reindexed.
+#[tauri::command]
+pub async fn get_query_dependents_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    rel_paths: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+
+    run_blocking(move || find_query_dependents(&workspace_path, &db_path, &rel_paths)).await
+}
+
+#[tauri::command]
+pub async fn get_activity_heatmap_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    range: CalendarRange,
+) -> Result<ActivityHeatmap, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+
+    run_blocking(move || get_activity_heatmap(&workspace_path, &db_path, &range.start, &range.end))
+        .await
+}
+
+/// Words added and removed over the range, from the word delta stored for
+/// each indexed revision, optionally limited to one folder.
+#[tauri::command]
+pub async fn get_writing_stats_command(
+    app_handle: tauri::AppHandle,
+    workspace_path: String,
+    range: CalendarRange,
+    folder: Option<String>,
+) -> Result<WritingStats, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = PathBuf::from(workspace_path);
+
+    run_blocking(move || {
+        get_writing_stats(
+            &workspace_path,
+            &db_path,
+            &range.start,
+            &range.end,
+            folder.as_deref(),
+        )
+    })
+    .await
+}
+
 #[tauri::command]
 pub fn list_vault_workspaces_command<R: Runtime>(
     app_handle: AppHandle<R>,
@@ -251,11 +1010,29 @@ pub fn touch_vault_workspace_command<R: Runtime>(
     app_handle: AppHandle<R>,
     workspace_path: String,
 ) -> Result<(), String> {
+    crate::path_scope::ensure_registrable(&app_handle, &workspace_path)?;
     let db_path = crate::persistence::run_app_migrations(&app_handle)?;
     app_storage::vault::touch_workspace(&db_path, Path::new(&workspace_path))
         .map_err(|error| error.to_string())
 }
 
+/// Sets up a new vault folder from `template` and registers it.
+#[tauri::command]
+pub fn create_vault_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: String,
+    template: Option<VaultTemplate>,
+) -> Result<CreatedVault, String> {
+    crate::path_scope::ensure_registrable(&app_handle, &path)?;
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::vault_template::create_vault(
+        &db_path,
+        Path::new(&path),
+        template.unwrap_or_default(),
+    )
+    .map_err(|error| error.to_string())
+}
+
 #[tauri::command]
 pub fn remove_vault_workspace_command<R: Runtime>(
     app_handle: AppHandle<R>,
@@ -282,13 +1059,121 @@ pub fn set_vault_embedding_config_command<R: Runtime>(
     workspace_path: String,
     embedding_provider: String,
     embedding_model: String,
+    fallback_embedding_provider: Option<String>,
+    fallback_embedding_model: Option<String>,
 ) -> Result<(), String> {
     let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_path = Path::new(&workspace_path);
     app_storage::vault::set_embedding_config(
         &db_path,
-        Path::new(&workspace_path),
+        workspace_path,
         &embedding_provider,
         &embedding_model,
     )
+    .map_err(|error| error.to_string())?;
+
+    // Omitting both fallback arguments leaves the stored fallback untouched.
+    if fallback_embedding_provider.is_none() && fallback_embedding_model.is_none() {
+        return Ok(());
+    }
+
+    app_storage::vault::set_fallback_embedding_config(
+        &db_path,
+        workspace_path,
+        fallback_embedding_provider.as_deref().unwrap_or_default(),
+        fallback_embedding_model.as_deref().unwrap_or_default(),
+    )
     .map_err(|error| error.to_string())
 }
+
+#[tauri::command]
+pub fn list_embedding_providers_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<EmbeddingProviderConfig>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::embedding_providers::list_embedding_providers(&db_path)
+        .map_err(|error| error.to_string())
+}
+
+/// Adds or replaces an HTTP embedding provider. Vaults already using it pick
+/// up the change on their next indexing run.
+#[tauri::command]
+pub fn save_embedding_provider_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    config: EmbeddingProviderConfig,
+) -> Result<EmbeddingProviderConfig, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::embedding_providers::save_embedding_provider(&db_path, config)
+        .map_err(|error| error.to_string())
+}
+
+/// Probes a provider and model before indexing; the index is not touched.
+#[tauri::command]
+pub async fn check_embedding_provider_command(
+    app_handle: tauri::AppHandle,
+    provider: String,
+    model: String,
+) -> Result<EmbeddingProviderCheck, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    run_blocking(move || check_embedding_provider(&db_path, &provider, &model)).await
+}
+
+#[tauri::command]
+pub fn delete_embedding_provider_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    name: String,
+) -> Result<bool, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::embedding_providers::delete_embedding_provider(&db_path, &name)
+        .map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub fn get_embedding_cache_limit_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<usize, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::embedding_cache::get_embedding_cache_max_entries(&db_path)
+        .map_err(|error| error.to_string())
+}
+
+/// Zero turns the cache off and drops the cached vectors.
+#[tauri::command]
+pub fn set_embedding_cache_limit_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    max_entries: usize,
+) -> Result<(), String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::embedding_cache::set_embedding_cache_max_entries(&db_path, max_entries)
+        .map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub fn clear_embedding_cache_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<usize, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::embedding_cache::clear_embedding_cache(&db_path).map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub fn get_vault_excluded_frontmatter_keys_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+) -> Result<Vec<String>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::vault::get_excluded_frontmatter_keys(&db_path, Path::new(&workspace_path))
+        .map_err(|error| error.to_string())
+}
+
+// Already indexed notes pick up the new keys when they change or on a forced reindex.
+#[tauri::command]
+pub fn set_vault_excluded_frontmatter_keys_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    keys: Vec<String>,
+) -> Result<(), String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::vault::set_excluded_frontmatter_keys(&db_path, Path::new(&workspace_path), &keys)
+        .map_err(|error| error.to_string())
+}
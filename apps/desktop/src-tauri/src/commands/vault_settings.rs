@@ -0,0 +1,152 @@
+use std::path::{Path, PathBuf};
+
+use app_storage::vault_config::VaultConfigImport;
+use app_storage::vault_settings::{VaultSetting, VaultSettingKey};
+use mdit_vault_indexing::apply_fts_tokenizer;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::commands::vault_indexing::index_run_guard;
+
+/// Broadcast to every window so open windows of the same vault stay in sync.
+pub const VAULT_SETTING_CHANGED_EVENT: &str = "vault-setting-changed";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultSettingChangedPayload {
+    workspace_path: String,
+    #[serde(flatten)]
+    setting: VaultSetting,
+}
+
+fn emit_setting_changed<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    workspace_path: String,
+    setting: &VaultSetting,
+) {
+    let payload = VaultSettingChangedPayload {
+        workspace_path,
+        setting: setting.clone(),
+    };
+    let _ = app_handle.emit(VAULT_SETTING_CHANGED_EVENT, payload);
+}
+
+/// Carries a changed setting over to what depends on it outside the settings
+/// table.
+fn apply_setting<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    db_path: &Path,
+    workspace_path: &str,
+    setting: &VaultSetting,
+) {
+    if setting.key == crate::app::spotlight::SPOTLIGHT_METADATA_SETTING {
+        let enabled = setting.value.as_bool().unwrap_or(false);
+        crate::app::spotlight::apply_setting(app_handle, workspace_path, enabled);
+    } else if setting.key == VaultSettingKey::SearchTokenizer.as_str() {
+        // Rebuilding the full-text index reads every note, so it runs off the
+        // command thread and waits for any index run in progress.
+        let run_guard = index_run_guard(app_handle);
+        let db_path = db_path.to_path_buf();
+        let workspace_root = PathBuf::from(workspace_path);
+        tauri::async_runtime::spawn_blocking(move || {
+            let result = run_guard.run_exclusive(&workspace_root, || {
+                apply_fts_tokenizer(&workspace_root, &db_path)
+            });
+            if let Err(error) = result {
+                tracing::warn!("failed to rebuild full-text index: {error:#}");
+            }
+        });
+    }
+}
+
+#[tauri::command]
+pub fn list_vault_settings_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+) -> Result<Vec<VaultSetting>, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::vault_settings::list_vault_settings(&db_path, Path::new(&workspace_path))
+        .map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub fn get_vault_setting_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    key: String,
+) -> Result<VaultSetting, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::vault_settings::get_vault_setting(&db_path, Path::new(&workspace_path), &key)
+        .map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub fn set_vault_setting_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    key: String,
+    value: JsonValue,
+) -> Result<VaultSetting, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let setting = app_storage::vault_settings::set_vault_setting(
+        &db_path,
+        Path::new(&workspace_path),
+        &key,
+        &value,
+    )
+    .map_err(|error| error.to_string())?;
+
+    apply_setting(&app_handle, &db_path, &workspace_path, &setting);
+    emit_setting_changed(&app_handle, workspace_path, &setting);
+    Ok(setting)
+}
+
+#[tauri::command]
+pub fn reset_vault_setting_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+    key: String,
+) -> Result<VaultSetting, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let setting = app_storage::vault_settings::reset_vault_setting(
+        &db_path,
+        Path::new(&workspace_path),
+        &key,
+    )
+    .map_err(|error| error.to_string())?;
+
+    apply_setting(&app_handle, &db_path, &workspace_path, &setting);
+    emit_setting_changed(&app_handle, workspace_path, &setting);
+    Ok(setting)
+}
+
+#[tauri::command]
+pub fn export_vault_config_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+) -> Result<String, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    app_storage::vault_config::export_vault_config(&db_path, Path::new(&workspace_path))
+        .map(|path| path.to_string_lossy().into_owned())
+        .map_err(|error| error.to_string())
+}
+
+#[tauri::command]
+pub fn import_vault_config_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_path: String,
+) -> Result<VaultConfigImport, String> {
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace_root = Path::new(&workspace_path);
+    let result = app_storage::vault_config::import_vault_config(&db_path, workspace_root)
+        .map_err(|error| error.to_string())?;
+
+    for key in &result.applied_settings {
+        let setting = app_storage::vault_settings::get_vault_setting(&db_path, workspace_root, key)
+            .map_err(|error| error.to_string())?;
+        apply_setting(&app_handle, &db_path, &workspace_path, &setting);
+        emit_setting_changed(&app_handle, workspace_path.clone(), &setting);
+    }
+    Ok(result)
+}
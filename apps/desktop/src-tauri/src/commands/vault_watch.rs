@@ -1,21 +1,44 @@
+use std::collections::HashMap;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 
+use app_storage::vault::{migrate_workspace_path, VaultWorkspace};
 use mdit_vault_indexer::{start_vault_indexer, VaultIndexerConfig, VaultIndexerHandle};
 use mdit_vault_indexing::VaultIndexingRuntimeAdapter;
 use mdit_vault_watch::{VaultWatchBatchPayload, VAULT_WATCH_BATCH_EVENT};
-use tauri::{AppHandle, Emitter, Runtime, State};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State, WebviewWindow};
 
+// Session key of the watcher started without a window in background mode.
+const BACKGROUND_SESSION_KEY: &str = "background";
+
+/// One watcher per window, since each window can hold a different vault.
 #[derive(Default)]
 pub struct VaultWatchRuntimeState {
-    watcher: Mutex<Option<VaultWatchSession>>,
+    watchers: Mutex<HashMap<String, VaultWatchSession>>,
 }
 
 impl VaultWatchRuntimeState {
-    fn lock_watcher(&self) -> Result<std::sync::MutexGuard<'_, Option<VaultWatchSession>>, String> {
-        self.watcher
+    fn lock_watchers(
+        &self,
+    ) -> Result<std::sync::MutexGuard<'_, HashMap<String, VaultWatchSession>>, String> {
+        self.watchers
             .lock()
             .map_err(|error| format!("Failed to lock vault watch runtime state: {}", error))
     }
+
+    /// Vaults with a running watcher, each listed once.
+    pub fn watched_workspaces(&self) -> Vec<String> {
+        let Ok(watchers) = self.lock_watchers() else {
+            return Vec::new();
+        };
+        let mut workspaces = watchers
+            .values()
+            .map(|session| session.workspace_path.clone())
+            .collect::<Vec<_>>();
+        workspaces.sort();
+        workspaces.dedup();
+        workspaces
+    }
 }
 
 struct VaultWatchSession {
@@ -33,57 +56,72 @@ fn stop_session(session: VaultWatchSession, error_message: &str) -> Result<(), S
 #[tauri::command]
 pub fn start_vault_watch_command<R: Runtime>(
     app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
     state: State<'_, VaultWatchRuntimeState>,
     workspace_path: String,
 ) -> Result<(), String> {
-    let previous_session = {
-        let mut watcher = state.lock_watcher()?;
-        if let Some(active) = watcher.as_ref() {
+    let window_label = window.label().to_string();
+    let (previous_session, background_session) = {
+        let mut watchers = state.lock_watchers()?;
+        if let Some(active) = watchers.get(&window_label) {
             if active.workspace_path == workspace_path {
                 return Ok(());
             }
         }
-        watcher.take()
+        // A window takes over from the watcher started in background mode.
+        (
+            watchers.remove(&window_label),
+            watchers.remove(BACKGROUND_SESSION_KEY),
+        )
     };
 
     if let Some(active) = previous_session {
         stop_session(active, "Failed to stop existing vault watcher")?;
     }
 
+    if let Some(background) = background_session {
+        stop_session(background, "Failed to stop background vault watcher")?;
+    }
+
     let db_path = crate::persistence::run_app_migrations(&app_handle)?;
     let emit_workspace_path = workspace_path.clone();
     let emit_handle = app_handle.clone();
+    let emit_label = window_label.clone();
 
     let handle = start_vault_indexer(
         &workspace_path,
         &db_path,
-        Arc::new(VaultIndexingRuntimeAdapter),
+        Arc::new(VaultIndexingRuntimeAdapter::new(
+            crate::commands::vault_indexing::index_run_guard(&app_handle),
+        )),
         VaultIndexerConfig::default(),
         move |batch| {
+            crate::app::spotlight::handle_watch_batch(&emit_handle, &emit_workspace_path, &batch);
             let payload = VaultWatchBatchPayload {
                 workspace_path: emit_workspace_path.clone(),
                 batch,
             };
-            let _ = emit_handle.emit_to("main", VAULT_WATCH_BATCH_EVENT, payload);
+            let _ = emit_handle.emit_to(emit_label.as_str(), VAULT_WATCH_BATCH_EVENT, payload);
         },
     )
     .map_err(|error| format!("Failed to start vault indexer: {}", error))?;
+    crate::app::spotlight::refresh_vault(&app_handle, &workspace_path);
 
     let (replaced_session, redundant_session) = {
-        let mut watcher = state.lock_watcher()?;
+        let mut watchers = state.lock_watchers()?;
         let new_session = VaultWatchSession {
             workspace_path,
             handle,
         };
 
-        if let Some(active) = watcher.as_ref() {
+        if let Some(active) = watchers.get(&window_label) {
             if active.workspace_path == new_session.workspace_path {
                 (None, Some(new_session))
             } else {
-                (watcher.replace(new_session), None)
+                (watchers.insert(window_label, new_session), None)
             }
         } else {
-            (watcher.replace(new_session), None)
+            (watchers.insert(window_label, new_session), None)
         }
     };
 
@@ -100,13 +138,14 @@ pub fn start_vault_watch_command<R: Runtime>(
 }
 
 #[tauri::command]
-pub fn stop_vault_watch_command(
+pub fn stop_vault_watch_command<R: Runtime>(
+    window: WebviewWindow<R>,
     state: State<'_, VaultWatchRuntimeState>,
     workspace_path: Option<String>,
 ) -> Result<(), String> {
     let session_to_stop = {
-        let mut watcher = state.lock_watcher()?;
-        let should_stop = match (watcher.as_ref(), workspace_path.as_ref()) {
+        let mut watchers = state.lock_watchers()?;
+        let should_stop = match (watchers.get(window.label()), workspace_path.as_ref()) {
             (Some(active), Some(expected_workspace_path)) => {
                 &active.workspace_path == expected_workspace_path
             }
@@ -115,7 +154,7 @@ pub fn stop_vault_watch_command(
         };
 
         if should_stop {
-            watcher.take()
+            watchers.remove(window.label())
         } else {
             None
         }
@@ -127,3 +166,100 @@ pub fn stop_vault_watch_command(
 
     Ok(())
 }
+
+/// Moves the vault registered at `old_path` to `new_path` after its folder
+/// was moved on disk, keeping its index, and watches it there from this
+/// window. Watchers still pointed at the old path are stopped.
+#[tauri::command]
+pub fn migrate_vault_path_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+    state: State<'_, VaultWatchRuntimeState>,
+    old_path: String,
+    new_path: String,
+) -> Result<VaultWorkspace, String> {
+    crate::path_scope::ensure_registrable(&app_handle, &new_path)?;
+    let stale_sessions = {
+        let mut watchers = state.lock_watchers()?;
+        let stale_keys = watchers
+            .iter()
+            .filter(|(_, session)| session.workspace_path == old_path)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        stale_keys
+            .into_iter()
+            .filter_map(|key| watchers.remove(&key))
+            .collect::<Vec<_>>()
+    };
+    for session in stale_sessions {
+        stop_session(session, "Failed to stop vault watcher at the old path")?;
+    }
+
+    let db_path = crate::persistence::run_app_migrations(&app_handle)?;
+    let workspace = migrate_workspace_path(&db_path, &old_path, Path::new(&new_path))
+        .map_err(|error| error.to_string())?;
+
+    start_vault_watch_command(app_handle, window, state, new_path)?;
+    Ok(workspace)
+}
+
+/// Stops the watcher of a destroyed window.
+pub fn stop_window_vault_watch<R: Runtime>(app_handle: &AppHandle<R>, window_label: &str) {
+    let state = app_handle.state::<VaultWatchRuntimeState>();
+    let session = match state.lock_watchers() {
+        Ok(mut watchers) => watchers.remove(window_label),
+        Err(error) => {
+            tracing::error!("{error}");
+            return;
+        }
+    };
+
+    if let Some(active) = session {
+        if let Err(error) = stop_session(active, "Failed to stop vault watcher") {
+            tracing::error!("{error}");
+        }
+    }
+}
+
+/// Watches `workspace_path` without a window, for background mode. Batches
+/// are not emitted anywhere; the indexer and the Spotlight export still keep
+/// up with them.
+pub fn start_background_vault_watch<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    workspace_path: String,
+) -> Result<(), String> {
+    let state = app_handle.state::<VaultWatchRuntimeState>();
+    if state.lock_watchers()?.contains_key(BACKGROUND_SESSION_KEY) {
+        return Ok(());
+    }
+
+    let db_path = crate::persistence::run_app_migrations(app_handle)?;
+    let batch_handle = app_handle.clone();
+    let batch_workspace_path = workspace_path.clone();
+    let handle = start_vault_indexer(
+        &workspace_path,
+        &db_path,
+        Arc::new(VaultIndexingRuntimeAdapter::new(
+            crate::commands::vault_indexing::index_run_guard(app_handle),
+        )),
+        VaultIndexerConfig::default(),
+        move |batch| {
+            crate::app::spotlight::handle_watch_batch(&batch_handle, &batch_workspace_path, &batch);
+        },
+    )
+    .map_err(|error| format!("Failed to start vault indexer: {}", error))?;
+    crate::app::spotlight::refresh_vault(app_handle, &workspace_path);
+
+    let replaced = state.lock_watchers()?.insert(
+        BACKGROUND_SESSION_KEY.to_string(),
+        VaultWatchSession {
+            workspace_path,
+            handle,
+        },
+    );
+    if let Some(replaced) = replaced {
+        stop_session(replaced, "Failed to stop redundant vault watcher")?;
+    }
+
+    Ok(())
+}
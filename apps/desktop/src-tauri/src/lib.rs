@@ -1,6 +1,7 @@
 mod app;
 mod commands;
 mod local_api;
+mod path_scope;
 mod persistence;
 
 use tauri::Manager;
@@ -8,14 +9,194 @@ use tauri_plugin_window_state::Builder as WindowStateBuilder;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    if let Err(error) =
+        persistence::portable::initialize().and_then(|_| persistence::profile::initialize())
+    {
+        eprintln!("{error}");
+        std::process::exit(2);
+    }
+
     let app_state = app::file_opening::AppState::default();
     app::file_opening::initialize_opened_files(&app_state);
 
-    let app = tauri::Builder::default()
-        .plugin(tauri_plugin_http::init())
-        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+    let command_handler: fn(tauri::ipc::Invoke) -> bool = tauri::generate_handler![
+        app::window_lifecycle::show_main_window,
+        app::vault_windows::open_vault_window_command,
+        app::vault_windows::register_window_vault_command,
+        app::vault_windows::get_window_vault_command,
+        app::vault_windows::close_vault_window_command,
+        app::quick_capture::toggle_quick_capture_window_command,
+        app::quick_capture::hide_quick_capture_window_command,
+        app::quick_capture::quick_capture_command,
+        app::quick_capture::append_to_inbox_command,
+        app::background_mode::get_background_mode_settings_command,
+        app::background_mode::set_start_in_background_command,
+        app::deep_link::take_pending_deep_links_command,
+        app::logging::get_recent_logs_command,
+        app::logging::get_log_level_command,
+        app::logging::set_log_level_command,
+        app::crash_reporting::get_crash_reporting_settings_command,
+        app::crash_reporting::set_crash_report_sharing_command,
+        app::crash_reporting::get_crash_summaries_command,
+        app::crash_reporting::export_crash_report_command,
+        commands::citations::search_citations_command,
+        commands::citations::format_citation_command,
+        commands::citations::get_citing_notes_command,
+        commands::credentials::list_credential_providers_command,
+        commands::credentials::get_credential_command,
+        commands::credentials::set_api_key_credential_command,
+        commands::credentials::set_codex_credential_command,
+        commands::credentials::delete_credential_command,
+        commands::credentials::get_app_secret_command,
+        commands::credentials::set_app_secret_command,
+        commands::credentials::delete_app_secret_command,
+        commands::filesystem::copy,
+        commands::filesystem::write_text_file_command,
+        commands::filesystem::rename_entry_command,
+        commands::filesystem::copy_entries_command,
+        commands::filesystem::move_entries_command,
+        commands::content::get_file_frontmatter,
+        commands::content::set_frontmatter_property_command,
+        commands::content::remove_frontmatter_property_command,
+        commands::content::get_kanban_board_command,
+        commands::content::move_card_command,
+        commands::filesystem::move_to_trash,
+        commands::filesystem::move_many_to_trash,
+        commands::filesystem::list_trashed_notes_command,
+        commands::filesystem::restore_trashed_note_command,
+        commands::content::materialize_cloud_file_command,
+        commands::content::get_note_preview,
+        commands::content::get_note_outline_command,
+        commands::content::list_directory_command,
+        commands::content::get_unique_note_file_name_command,
+        commands::content::generate_moc_command,
+        persistence::apply_appdata_migrations,
+        path_scope::pick_allowed_folder_command,
+        path_scope::revoke_path_root_command,
+        commands::vault_indexing::index_vault_documents_command,
+        commands::vault_indexing::index_note_command,
+        commands::vault_indexing::get_note_index_status_command,
+        commands::vault_indexing::refresh_workspace_embeddings_command,
+        commands::vault_indexing::rename_indexed_note_command,
+        commands::vault_indexing::delete_indexed_note_command,
+        commands::vault_indexing::get_indexing_meta_command,
+        commands::vault_indexing::break_vault_lock_command,
+        commands::vault_indexing::search_query_entries_command,
+        commands::vault_indexing::search_query_with_correction_command,
+        commands::vault_indexing::search_query_groups_command,
+        commands::vault_indexing::search_segments_command,
+        commands::vault_indexing::archive_note_command,
+        commands::vault_indexing::unarchive_note_command,
+        commands::vault_indexing::build_context_command,
+        commands::vault_indexing::search_tag_entries_command,
+        commands::vault_indexing::resolve_wiki_link_command,
+        commands::vault_indexing::get_note_aliases_command,
+        commands::vault_indexing::get_vault_diagnostics_command,
+        commands::vault_indexing::get_vault_health_command,
+        commands::vault_indexing::get_embedding_coverage_command,
+        commands::vault_indexing::get_backlinks_command,
+        commands::vault_indexing::get_link_counts_command,
+        commands::vault_indexing::get_related_notes_command,
+        commands::vault_indexing::get_graph_view_data_command,
+        commands::vault_indexing::export_graph_command,
+        commands::vault_indexing::compare_vaults_command,
+        commands::vault_indexing::copy_vault_files_command,
+        commands::vault_indexing::get_calendar_data_command,
+        commands::vault_indexing::suggest_person_links_command,
+        commands::vault_indexing::get_notes_near_command,
+        commands::vault_indexing::get_notes_in_bounds_command,
+        commands::vault_indexing::query_notes_command,
+        commands::vault_indexing::render_query_block_command,
+        commands::vault_indexing::get_query_dependents_command,
+        commands::vault_indexing::get_activity_heatmap_command,
+        commands::vault_indexing::get_writing_stats_command,
+        app::graph_updates::get_graph_delta_command,
+        app::graph_updates::unsubscribe_graph_deltas_command,
+        commands::vault_indexing::list_vault_workspaces_command,
+        commands::vault_indexing::touch_vault_workspace_command,
+        commands::vault_indexing::create_vault_command,
+        commands::vault_indexing::remove_vault_workspace_command,
+        commands::vault_indexing::get_vault_embedding_config_command,
+        commands::vault_indexing::set_vault_embedding_config_command,
+        commands::vault_indexing::list_embedding_providers_command,
+        commands::vault_indexing::save_embedding_provider_command,
+        commands::vault_indexing::delete_embedding_provider_command,
+        commands::vault_indexing::check_embedding_provider_command,
+        commands::vault_indexing::get_embedding_cache_limit_command,
+        commands::vault_indexing::set_embedding_cache_limit_command,
+        commands::vault_indexing::clear_embedding_cache_command,
+        commands::vault_indexing::get_vault_excluded_frontmatter_keys_command,
+        commands::vault_indexing::set_vault_excluded_frontmatter_keys_command,
+        commands::search_history::record_search_query_command,
+        commands::search_history::get_search_history_command,
+        commands::search_history::suggest_queries_command,
+        commands::search_history::clear_search_history_command,
+        commands::search_vocabulary::list_search_synonyms_command,
+        commands::search_vocabulary::add_search_synonym_command,
+        commands::search_vocabulary::remove_search_synonym_command,
+        commands::search_vocabulary::list_search_stopwords_command,
+        commands::search_vocabulary::add_search_stopword_command,
+        commands::search_vocabulary::remove_search_stopword_command,
+        commands::pinned_notes::list_pinned_notes_command,
+        commands::pinned_notes::pin_note_command,
+        commands::pinned_notes::reorder_pinned_notes_command,
+        commands::spellcheck::spellcheck_text_command,
+        commands::spellcheck::list_spellcheck_languages_command,
+        commands::spellcheck::list_spellcheck_words_command,
+        commands::spellcheck::add_spellcheck_word_command,
+        commands::spellcheck::remove_spellcheck_word_command,
+        commands::transcription::transcribe_audio_command,
+        commands::find_replace::find_in_vault_command,
+        commands::find_replace::search_in_note_command,
+        commands::find_replace::replace_in_vault_command,
+        commands::find_replace::undo_last_replace_command,
+        commands::vault_settings::list_vault_settings_command,
+        commands::vault_settings::get_vault_setting_command,
+        commands::vault_settings::set_vault_setting_command,
+        commands::vault_settings::reset_vault_setting_command,
+        commands::vault_settings::export_vault_config_command,
+        commands::vault_settings::import_vault_config_command,
+        commands::vault_watch::start_vault_watch_command,
+        commands::vault_watch::stop_vault_watch_command,
+        commands::vault_watch::migrate_vault_path_command,
+        commands::local_api::start_local_api_server_command,
+        commands::local_api::set_local_api_auth_token_command,
+        commands::local_api::stop_local_api_server_command,
+        commands::local_api::get_local_api_port_command,
+        commands::note_import::import_notes_command,
+        commands::note_import::import_ics_command,
+        commands::flashcards::export_flashcards_command,
+        commands::feeds::list_feeds_command,
+        commands::feeds::subscribe_feed_command,
+        commands::feeds::refresh_feeds_command,
+        commands::feeds::unsubscribe_feed_command,
+        commands::ollama::list_ollama_models_command,
+        commands::image::get_image_properties,
+        commands::image::edit_image,
+        commands::window::set_macos_traffic_lights_hidden,
+        commands::window::set_macos_pinned_window_space_behavior
+    ];
+
+    // The capture window is always centered, so its position is not restored.
+    let mut window_state = WindowStateBuilder::default()
+        .with_denylist(&[app::quick_capture::QUICK_CAPTURE_WINDOW_LABEL]);
+    if let Some(file) = persistence::portable::window_state_file() {
+        window_state = window_state.with_filename(file);
+    }
+
+    let mut builder = tauri::Builder::default();
+    // The single-instance lock is keyed by the bundle identifier, which all
+    // profiles share; holding it would send a named profile's launch to
+    // whichever profile started first, so only the default profile takes it.
+    if persistence::profile::current_profile().is_none() {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            // The deep-link plugin receives links forwarded from the new instance.
+            if app::deep_link::has_deep_link_arg(&args) {
+                return;
+            }
+
             #[cfg(not(target_os = "macos"))]
-            if app::file_opening::handle_single_instance_args(app, &_args) {
+            if app::file_opening::handle_single_instance_args(app, &args) {
                 return;
             }
 
@@ -24,10 +205,13 @@ pub fn run() {
                 return;
             }
 
-            if let Some(main_window) = app.get_webview_window("main") {
-                app::window_lifecycle::show_and_focus_main_window(main_window);
-            }
-        }))
+            app::background_mode::open_main_window(app);
+        }));
+    }
+
+    let app = builder
+        .plugin(tauri_plugin_http::init())
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_os::init())
         .plugin(tauri_plugin_shell::init())
@@ -39,55 +223,42 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_clipboard::init())
-        .plugin(WindowStateBuilder::default().build())
+        .plugin(window_state.build())
         .manage(local_api::LocalApiRuntimeState::default())
         .manage(local_api::LocalApiAuthState::default())
         .manage(commands::vault_watch::VaultWatchRuntimeState::default())
-        .invoke_handler(tauri::generate_handler![
-            app::window_lifecycle::show_main_window,
-            commands::credentials::list_credential_providers_command,
-            commands::credentials::get_credential_command,
-            commands::credentials::set_api_key_credential_command,
-            commands::credentials::set_codex_credential_command,
-            commands::credentials::delete_credential_command,
-            commands::credentials::get_app_secret_command,
-            commands::credentials::set_app_secret_command,
-            commands::credentials::delete_app_secret_command,
-            commands::filesystem::copy,
-            commands::content::get_file_frontmatter,
-            commands::filesystem::move_to_trash,
-            commands::filesystem::move_many_to_trash,
-            commands::content::get_note_preview,
-            persistence::apply_appdata_migrations,
-            commands::vault_indexing::index_vault_documents_command,
-            commands::vault_indexing::index_note_command,
-            commands::vault_indexing::refresh_workspace_embeddings_command,
-            commands::vault_indexing::rename_indexed_note_command,
-            commands::vault_indexing::delete_indexed_note_command,
-            commands::vault_indexing::get_indexing_meta_command,
-            commands::vault_indexing::search_query_entries_command,
-            commands::vault_indexing::search_tag_entries_command,
-            commands::vault_indexing::resolve_wiki_link_command,
-            commands::vault_indexing::get_backlinks_command,
-            commands::vault_indexing::get_related_notes_command,
-            commands::vault_indexing::get_graph_view_data_command,
-            commands::vault_indexing::list_vault_workspaces_command,
-            commands::vault_indexing::touch_vault_workspace_command,
-            commands::vault_indexing::remove_vault_workspace_command,
-            commands::vault_indexing::get_vault_embedding_config_command,
-            commands::vault_indexing::set_vault_embedding_config_command,
-            commands::vault_watch::start_vault_watch_command,
-            commands::vault_watch::stop_vault_watch_command,
-            commands::local_api::start_local_api_server_command,
-            commands::local_api::set_local_api_auth_token_command,
-            commands::local_api::stop_local_api_server_command,
-            commands::ollama::list_ollama_models_command,
-            commands::image::get_image_properties,
-            commands::image::edit_image,
-            commands::window::set_macos_traffic_lights_hidden,
-            commands::window::set_macos_pinned_window_space_behavior
-        ])
+        .manage(path_scope::PathScopeState::default())
+        .manage(app::vault_windows::VaultWindowState::default())
+        .manage(app::deep_link::DeepLinkState::default())
+        .manage(app::spotlight::SpotlightState::default())
+        .manage(mdit_vault_indexing::IndexRunGuard::default())
+        .manage(app::maintenance::MaintenanceState::default())
+        .manage(app::graph_updates::GraphUpdateState::default())
+        .manage(commands::spellcheck::SpellcheckState::default())
+        .invoke_handler(move |invoke| {
+            // Any command from the UI counts as activity and holds off index maintenance.
+            invoke
+                .message
+                .webview_ref()
+                .state::<app::maintenance::MaintenanceState>()
+                .record_activity();
+            command_handler(invoke)
+        })
         .manage(app_state)
+        .setup(|app| {
+            app::logging::initialize(app.handle());
+            app::crash_reporting::initialize(app.handle());
+            commands::vault_indexing::initialize_vector_support(app.handle());
+            mdit_vault_indexing::set_vault_lock_owner("mdit desktop app");
+            app::background_mode::initialize(app.handle())?;
+            app::deep_link::initialize(app.handle())?;
+            app::maintenance::initialize(app.handle());
+            app::consistency_sweep::initialize(app.handle());
+            app::feed_refresh::initialize(app.handle());
+            app::graph_updates::initialize(app.handle());
+            app::voice_transcription::initialize(app.handle());
+            Ok(())
+        })
         .build(tauri::generate_context!())
         .expect("error while running tauri application");
 
@@ -1,8 +1,12 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
 
 use mdit_local_api::{
-    CreateNoteInput, LocalApiError, LocalApiErrorKind, SearchNoteEntry, SearchNotesInput,
+    AppendToInboxInput, BuildContextInput, CaseConflict, CreateNoteInput, DuplicateBasename,
+    GetNoteIdInput, LocalApiError, LocalApiErrorKind, NoteIdentity, ResolveNoteIdInput,
+    SearchNoteEntry, SearchNotesInput, SearchSegmentEntry, SearchSegmentsInput,
+    VaultDiagnosticsInput,
 };
+use mdit_vault_indexing::{ContextSource, MatchedPassage};
 use rmcp::schemars;
 use rmcp::{
     handler::server::{router::tool::ToolRouter, wrapper::Parameters},
@@ -51,7 +55,7 @@ impl MditMcpServer {
 
     #[tool(
         name = "create_note",
-        description = "Create a markdown note in a vault. Returns NOTE_ALREADY_EXISTS when a duplicate file name exists."
+        description = "Create a markdown note in a vault. Returns NOTE_ALREADY_EXISTS when a duplicate file name exists and PRIVATE_PATH for folders the vault keeps private."
     )]
     async fn create_note(
         &self,
@@ -78,6 +82,74 @@ impl MditMcpServer {
         }))
     }
 
+    #[tool(
+        name = "append_to_inbox",
+        description = "Append a timestamped entry to the vault's inbox note, creating it when missing. Concurrent captures never overwrite each other. Returns NOTE_LOCKED when the inbox is locked."
+    )]
+    async fn append_to_inbox(
+        &self,
+        Parameters(input): Parameters<AppendToInboxToolInput>,
+    ) -> Result<Json<AppendToInboxToolOutput>, McpError> {
+        let entry = mdit_local_api::append_to_inbox(
+            &self.db_path,
+            AppendToInboxInput {
+                vault_id: input.vault_id,
+                text: input.text,
+                metadata: input.metadata.unwrap_or_default(),
+            },
+        )
+        .map_err(local_api_error_to_mcp)?;
+
+        Ok(Json(AppendToInboxToolOutput {
+            entry: InboxEntryTool {
+                vault_id: entry.vault_id,
+                relative_path: entry.relative_path,
+                absolute_path: entry.absolute_path,
+                timestamp: entry.timestamp,
+            },
+        }))
+    }
+
+    #[tool(
+        name = "get_note_id",
+        description = "Get the stable id of a note, assigning one on first use. The id keeps resolving after the note is renamed or moved."
+    )]
+    async fn get_note_id(
+        &self,
+        Parameters(input): Parameters<GetNoteIdToolInput>,
+    ) -> Result<Json<NoteIdentityToolOutput>, McpError> {
+        let note = mdit_local_api::get_note_id(
+            &self.db_path,
+            GetNoteIdInput {
+                vault_id: input.vault_id,
+                relative_path: input.relative_path,
+            },
+        )
+        .map_err(local_api_error_to_mcp)?;
+
+        Ok(Json(NoteIdentityToolOutput { note: note.into() }))
+    }
+
+    #[tool(
+        name = "resolve_note_id",
+        description = "Find the current location of a note by its stable id. Returns NOTE_ID_NOT_FOUND when the note no longer exists."
+    )]
+    async fn resolve_note_id(
+        &self,
+        Parameters(input): Parameters<ResolveNoteIdToolInput>,
+    ) -> Result<Json<NoteIdentityToolOutput>, McpError> {
+        let note = mdit_local_api::resolve_note_id(
+            &self.db_path,
+            ResolveNoteIdInput {
+                vault_id: input.vault_id,
+                note_id: input.note_id,
+            },
+        )
+        .map_err(local_api_error_to_mcp)?;
+
+        Ok(Json(NoteIdentityToolOutput { note: note.into() }))
+    }
+
     #[tool(
         name = "search_notes",
         description = "Search markdown notes in a vault."
@@ -91,7 +163,75 @@ impl MditMcpServer {
 
         let results = output.results.into_iter().map(Into::into).collect();
 
-        Ok(Json(SearchNotesToolOutput { results }))
+        Ok(Json(SearchNotesToolOutput {
+            results,
+            redacted: output.redacted,
+        }))
+    }
+
+    #[tool(
+        name = "search_segments",
+        description = "Search passages of markdown notes in a vault, each with its text, location, and scores. Only notes indexed with embeddings have passages."
+    )]
+    async fn search_segments(
+        &self,
+        Parameters(input): Parameters<SearchSegmentsToolInput>,
+    ) -> Result<Json<SearchSegmentsToolOutput>, McpError> {
+        let output = mdit_local_api::search_segments(&self.db_path, input.into())
+            .map_err(local_api_error_to_mcp)?;
+
+        let results = output.results.into_iter().map(Into::into).collect();
+
+        Ok(Json(SearchSegmentsToolOutput {
+            results,
+            redacted: output.redacted,
+        }))
+    }
+
+    #[tool(
+        name = "build_context",
+        description = "Pack cited passages from the given notes and the notes best matching a query into a token budget, ready to paste into a prompt. Each passage is marked [n] after its source."
+    )]
+    async fn build_context(
+        &self,
+        Parameters(input): Parameters<BuildContextToolInput>,
+    ) -> Result<Json<BuildContextToolOutput>, McpError> {
+        let output = mdit_local_api::build_context(&self.db_path, input.into())
+            .map_err(local_api_error_to_mcp)?;
+
+        Ok(Json(BuildContextToolOutput {
+            context: output.context.context,
+            token_count: output.context.token_count,
+            sources: output.context.sources.into_iter().map(Into::into).collect(),
+            truncated: output.context.truncated,
+            redacted: output.redacted,
+        }))
+    }
+
+    #[tool(
+        name = "get_vault_diagnostics",
+        description = "List paths in a vault that differ only by letter case and notes sharing a file name, which make [[name]] links ambiguous."
+    )]
+    async fn get_vault_diagnostics(
+        &self,
+        Parameters(input): Parameters<VaultDiagnosticsToolInput>,
+    ) -> Result<Json<VaultDiagnosticsToolOutput>, McpError> {
+        let output = mdit_local_api::get_vault_diagnostics(
+            &self.db_path,
+            VaultDiagnosticsInput {
+                vault_id: input.vault_id,
+            },
+        )
+        .map_err(local_api_error_to_mcp)?;
+
+        Ok(Json(VaultDiagnosticsToolOutput {
+            case_conflicts: output.case_conflicts.into_iter().map(Into::into).collect(),
+            duplicate_basenames: output
+                .duplicate_basenames
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }))
     }
 }
 
@@ -100,7 +240,7 @@ impl ServerHandler for MditMcpServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             instructions: Some(
-                "Expose vault listing, markdown note creation, and note search for local automation."
+                "Expose vault listing, markdown note creation, inbox capture, stable note ids, note and passage search, and vault diagnostics for local automation."
                     .into(),
             ),
             capabilities: ServerCapabilities::builder().enable_tools().build(),
@@ -134,8 +274,12 @@ fn local_api_error_to_mcp(error: LocalApiError) -> McpError {
     match kind {
         LocalApiErrorKind::NotFound => McpError::resource_not_found(message, data),
         LocalApiErrorKind::InvalidInput => McpError::invalid_params(message, data),
-        LocalApiErrorKind::Conflict => McpError::invalid_request(message, data),
-        LocalApiErrorKind::Internal => McpError::internal_error(message, data),
+        LocalApiErrorKind::Conflict | LocalApiErrorKind::Forbidden => {
+            McpError::invalid_request(message, data)
+        }
+        LocalApiErrorKind::Upstream | LocalApiErrorKind::Internal => {
+            McpError::internal_error(message, data)
+        }
     }
 }
 
@@ -148,16 +292,63 @@ pub struct CreateNoteToolInput {
     pub content: Option<String>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AppendToInboxToolInput {
+    pub vault_id: i64,
+    pub text: String,
+    /// Listed under the entry's heading as `key: value` lines.
+    pub metadata: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNoteIdToolInput {
+    pub vault_id: i64,
+    pub relative_path: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveNoteIdToolInput {
+    pub vault_id: i64,
+    pub note_id: String,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchNotesToolInput {
     pub vault_id: i64,
     pub query: String,
     pub limit: Option<usize>,
+    /// Include the indexed text of the top results.
+    pub include_content: Option<bool>,
+    /// Bytes of text per result; defaults to 16 KiB.
+    pub content_max_bytes: Option<usize>,
 }
 
 impl From<SearchNotesToolInput> for SearchNotesInput {
     fn from(value: SearchNotesToolInput) -> Self {
+        Self {
+            vault_id: value.vault_id,
+            query: value.query,
+            limit: value.limit,
+            include_content: value.include_content,
+            content_max_bytes: value.content_max_bytes,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSegmentsToolInput {
+    pub vault_id: i64,
+    pub query: String,
+    pub limit: Option<usize>,
+}
+
+impl From<SearchSegmentsToolInput> for SearchSegmentsInput {
+    fn from(value: SearchSegmentsToolInput) -> Self {
         Self {
             vault_id: value.vault_id,
             query: value.query,
@@ -166,6 +357,34 @@ impl From<SearchNotesToolInput> for SearchNotesInput {
     }
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildContextToolInput {
+    pub vault_id: i64,
+    pub query: Option<String>,
+    /// Notes to draw on first, relative to the vault root.
+    #[serde(default)]
+    pub note_paths: Vec<String>,
+    pub max_tokens: Option<usize>,
+}
+
+impl From<BuildContextToolInput> for BuildContextInput {
+    fn from(value: BuildContextToolInput) -> Self {
+        Self {
+            vault_id: value.vault_id,
+            query: value.query,
+            note_paths: value.note_paths,
+            max_tokens: value.max_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDiagnosticsToolInput {
+    pub vault_id: i64,
+}
+
 #[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 struct ListVaultsToolOutput {
@@ -195,10 +414,56 @@ struct CreatedNoteTool {
     pub absolute_path: String,
 }
 
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct AppendToInboxToolOutput {
+    pub entry: InboxEntryTool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct InboxEntryTool {
+    pub vault_id: i64,
+    pub relative_path: String,
+    pub absolute_path: String,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct NoteIdentityToolOutput {
+    pub note: NoteIdentityTool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct NoteIdentityTool {
+    pub vault_id: i64,
+    pub note_id: String,
+    pub workspace_path: String,
+    pub relative_path: String,
+    pub absolute_path: String,
+}
+
+impl From<NoteIdentity> for NoteIdentityTool {
+    fn from(value: NoteIdentity) -> Self {
+        Self {
+            vault_id: value.vault_id,
+            note_id: value.note_id,
+            workspace_path: value.workspace_path,
+            relative_path: value.relative_path,
+            absolute_path: value.absolute_path,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 struct SearchNotesToolOutput {
     pub results: Vec<SearchResultToolEntry>,
+    /// Some content had secrets masked by the vault's redaction rules.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
 }
 
 #[derive(Debug, Serialize, JsonSchema)]
@@ -209,6 +474,10 @@ struct SearchResultToolEntry {
     pub created_at: Option<i64>,
     pub modified_at: Option<i64>,
     pub similarity: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub content_truncated: bool,
 }
 
 impl From<SearchNoteEntry> for SearchResultToolEntry {
@@ -219,6 +488,142 @@ impl From<SearchNoteEntry> for SearchResultToolEntry {
             created_at: value.created_at,
             modified_at: value.modified_at,
             similarity: value.similarity,
+            content: value.content,
+            content_truncated: value.content_truncated,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct SearchSegmentsToolOutput {
+    pub results: Vec<SegmentResultToolEntry>,
+    /// Some segment text had secrets masked by the vault's redaction rules.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct SegmentResultToolEntry {
+    pub path: String,
+    pub rel_path: String,
+    pub ordinal: i64,
+    pub text: String,
+    pub start_offset: usize,
+    pub end_offset: usize,
+    pub heading_path: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_language: Option<String>,
+    pub vector_score: Option<f32>,
+    pub bm25_score: Option<f32>,
+    pub score: f32,
+}
+
+impl From<SearchSegmentEntry> for SegmentResultToolEntry {
+    fn from(value: SearchSegmentEntry) -> Self {
+        Self {
+            path: value.path,
+            rel_path: value.rel_path,
+            ordinal: value.ordinal,
+            text: value.text,
+            start_offset: value.start_offset,
+            end_offset: value.end_offset,
+            heading_path: value.heading_path,
+            code_language: value.code_language,
+            vector_score: value.vector_score,
+            bm25_score: value.bm25_score,
+            score: value.score,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct BuildContextToolOutput {
+    pub context: String,
+    pub token_count: usize,
+    pub sources: Vec<ContextSourceToolEntry>,
+    /// Whether passages were left out to stay within the budget.
+    pub truncated: bool,
+    /// Some passage text had secrets masked by the vault's redaction rules.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct ContextSourceToolEntry {
+    pub marker: usize,
+    pub path: String,
+    pub rel_path: String,
+    pub passages: Vec<ContextPassageToolEntry>,
+}
+
+impl From<ContextSource> for ContextSourceToolEntry {
+    fn from(value: ContextSource) -> Self {
+        Self {
+            marker: value.marker,
+            path: value.path,
+            rel_path: value.rel_path,
+            passages: value.passages.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct ContextPassageToolEntry {
+    pub start_offset: usize,
+    pub end_offset: usize,
+    pub heading_path: Vec<String>,
+    pub code_language: Option<String>,
+}
+
+impl From<MatchedPassage> for ContextPassageToolEntry {
+    fn from(value: MatchedPassage) -> Self {
+        Self {
+            start_offset: value.start_offset,
+            end_offset: value.end_offset,
+            heading_path: value.heading_path,
+            code_language: value.code_language,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct VaultDiagnosticsToolOutput {
+    pub case_conflicts: Vec<CaseConflictToolEntry>,
+    pub duplicate_basenames: Vec<DuplicateBasenameToolEntry>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct CaseConflictToolEntry {
+    pub rel_paths: Vec<String>,
+}
+
+impl From<CaseConflict> for CaseConflictToolEntry {
+    fn from(value: CaseConflict) -> Self {
+        Self {
+            rel_paths: value.rel_paths,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct DuplicateBasenameToolEntry {
+    pub basename: String,
+    pub rel_paths: Vec<String>,
+}
+
+impl From<DuplicateBasename> for DuplicateBasenameToolEntry {
+    fn from(value: DuplicateBasename) -> Self {
+        Self {
+            basename: value.basename,
+            rel_paths: value.rel_paths,
         }
     }
 }
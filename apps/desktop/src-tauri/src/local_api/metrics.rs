@@ -0,0 +1,179 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs,
+    path::Path,
+    sync::{LazyLock, Mutex},
+};
+
+use mdit_vault_indexing::{IndexingMetrics, LatencySnapshot};
+
+/// Requests served since start, by method, route template, and status.
+static API_REQUESTS: LazyLock<Mutex<BTreeMap<(String, String, u16), u64>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+pub(super) fn record_request(method: &str, route: &str, status: u16) {
+    if let Ok(mut requests) = API_REQUESTS.lock() {
+        *requests
+            .entry((method.to_string(), route.to_string(), status))
+            .or_default() += 1;
+    }
+}
+
+/// Every metric in the Prometheus text exposition format.
+pub(super) fn render_metrics(db_path: &Path, indexing: &IndexingMetrics) -> String {
+    let mut out = String::new();
+
+    write_header(
+        &mut out,
+        "mdit_index_database_bytes",
+        "gauge",
+        "Size of the index database file.",
+    );
+    let database_bytes = fs::metadata(db_path)
+        .map(|metadata| metadata.len())
+        .unwrap_or(0);
+    let _ = writeln!(out, "mdit_index_database_bytes {database_bytes}");
+
+    write_header(
+        &mut out,
+        "mdit_index_documents",
+        "gauge",
+        "Documents in the index of each vault.",
+    );
+    for vault in &indexing.vaults {
+        let _ = writeln!(
+            out,
+            "mdit_index_documents{{vault=\"{}\"}} {}",
+            escape_label(&vault.workspace_root),
+            vault.documents
+        );
+    }
+    write_header(
+        &mut out,
+        "mdit_index_segments",
+        "gauge",
+        "Chunks in the index of each vault.",
+    );
+    for vault in &indexing.vaults {
+        let _ = writeln!(
+            out,
+            "mdit_index_segments{{vault=\"{}\"}} {}",
+            escape_label(&vault.workspace_root),
+            vault.segments
+        );
+    }
+
+    write_header(
+        &mut out,
+        "mdit_documents_indexed_total",
+        "counter",
+        "Documents chunked and written to the index.",
+    );
+    let _ = writeln!(
+        out,
+        "mdit_documents_indexed_total {}",
+        indexing.documents_indexed
+    );
+
+    write_histogram(
+        &mut out,
+        "mdit_embedding_duration_seconds",
+        "Time the embedding provider took per chunk.",
+        &indexing.embedding_latency,
+    );
+    write_histogram(
+        &mut out,
+        "mdit_search_duration_seconds",
+        "Time taken by note searches.",
+        &indexing.search_latency,
+    );
+
+    write_header(
+        &mut out,
+        "mdit_watch_queue_depth",
+        "gauge",
+        "File change batches waiting to be indexed.",
+    );
+    let _ = writeln!(
+        out,
+        "mdit_watch_queue_depth {}",
+        mdit_vault_indexer::queued_watch_batches()
+    );
+
+    write_header(
+        &mut out,
+        "mdit_api_requests_total",
+        "counter",
+        "Local API requests served.",
+    );
+    if let Ok(requests) = API_REQUESTS.lock() {
+        for ((method, route, status), count) in requests.iter() {
+            let _ = writeln!(
+                out,
+                "mdit_api_requests_total{{method=\"{method}\",route=\"{}\",status=\"{status}\"}} {count}",
+                escape_label(route)
+            );
+        }
+    }
+
+    out
+}
+
+fn write_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn write_histogram(out: &mut String, name: &str, help: &str, histogram: &LatencySnapshot) {
+    write_header(out, name, "histogram", help);
+    for (bound, count) in &histogram.buckets {
+        let _ = writeln!(out, "{name}_bucket{{le=\"{bound}\"}} {count}");
+    }
+    let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {}", histogram.count);
+    let _ = writeln!(out, "{name}_sum {}", histogram.sum_seconds);
+    let _ = writeln!(out, "{name}_count {}", histogram.count);
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use mdit_vault_indexing::{IndexingMetrics, LatencySnapshot, VaultIndexSize};
+
+    use super::render_metrics;
+
+    #[test]
+    fn renders_histograms_and_escapes_labels() {
+        let latency = LatencySnapshot {
+            buckets: vec![(0.1, 1), (1.0, 2)],
+            count: 3,
+            sum_seconds: 4.5,
+        };
+        let metrics = IndexingMetrics {
+            documents_indexed: 7,
+            embedding_latency: latency.clone(),
+            search_latency: latency,
+            vaults: vec![VaultIndexSize {
+                workspace_root: "C:\\Notes \"work\"".to_string(),
+                documents: 2,
+                segments: 5,
+            }],
+        };
+
+        let text = render_metrics(Path::new("/nonexistent/index.sqlite"), &metrics);
+        assert!(text.contains("mdit_index_documents{vault=\"C:\\\\Notes \\\"work\\\"\"} 2\n"));
+        assert!(text.contains("mdit_documents_indexed_total 7\n"));
+        assert!(text.contains("mdit_search_duration_seconds_bucket{le=\"0.1\"} 1\n"));
+        assert!(text.contains("mdit_search_duration_seconds_bucket{le=\"+Inf\"} 3\n"));
+        assert!(text.contains("mdit_embedding_duration_seconds_sum 4.5\n"));
+        assert!(text.contains("# TYPE mdit_watch_queue_depth gauge\n"));
+    }
+}
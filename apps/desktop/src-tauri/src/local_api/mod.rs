@@ -1,4 +1,5 @@
 mod mcp_sdk_server;
+mod metrics;
 mod router;
 
 #[cfg(test)]
@@ -10,8 +11,9 @@ mod tests_rest;
 
 use std::{
     error::Error as StdError,
-    io,
+    fs, io,
     net::{Ipv4Addr, SocketAddrV4, TcpListener},
+    path::PathBuf,
     sync::{Arc, Mutex, RwLock},
 };
 
@@ -19,6 +21,11 @@ use tauri::{AppHandle, Manager, Runtime};
 use tokio::sync::oneshot;
 
 const LOCAL_API_PORT: u16 = 39123;
+// Named profiles listen on one of the ports just above the default one.
+const PROFILE_PORT_RANGE: u64 = 1000;
+// How many ports past the preferred one are tried when it is taken.
+const PORT_PROBE_ATTEMPTS: u16 = 50;
+const LOCAL_API_PORT_FILE: &str = "settings/local-api-port";
 const LOCAL_API_AUTH_TOKEN_MIN_LENGTH: usize = 32;
 
 #[derive(Default)]
@@ -59,6 +66,7 @@ impl LocalApiAuthState {
 }
 
 pub struct LocalApiRuntime {
+    port: u16,
     shutdown_tx: Mutex<Option<oneshot::Sender<()>>>,
 }
 
@@ -82,6 +90,88 @@ impl Default for LocalApiRuntimeState {
     }
 }
 
+/// Port the profile would use if nothing else had claimed it yet.
+fn preferred_port() -> u16 {
+    match crate::persistence::profile::current_profile() {
+        Some(name) => {
+            let offset = crate::persistence::profile::profile_hash(name) % PROFILE_PORT_RANGE;
+            LOCAL_API_PORT + 1 + offset as u16
+        }
+        None => LOCAL_API_PORT,
+    }
+}
+
+fn port_file_path<R: Runtime>(app_handle: &AppHandle<R>) -> Option<PathBuf> {
+    crate::persistence::app_data_dir(app_handle)
+        .ok()
+        .map(|dir| dir.join(LOCAL_API_PORT_FILE))
+}
+
+fn saved_port<R: Runtime>(app_handle: &AppHandle<R>) -> Option<u16> {
+    port_file_path(app_handle)
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| contents.trim().parse().ok())
+}
+
+fn save_port<R: Runtime>(app_handle: &AppHandle<R>, port: u16) {
+    let Some(path) = port_file_path(app_handle) else {
+        return;
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, port.to_string()));
+    if let Err(error) = result {
+        tracing::warn!(
+            "Failed to save local API port to {}: {error}",
+            path.display()
+        );
+    }
+}
+
+/// Port the local API of the running profile listens on: the bound port
+/// while the server runs, otherwise the one it last settled on.
+pub fn local_api_port<R: Runtime>(app_handle: &AppHandle<R>) -> u16 {
+    let running_port = app_handle
+        .try_state::<LocalApiRuntimeState>()
+        .and_then(|state| {
+            state
+                .runtime
+                .lock()
+                .ok()
+                .and_then(|guard| guard.as_ref().map(|runtime| runtime.port))
+        });
+    running_port
+        .or_else(|| saved_port(app_handle))
+        .unwrap_or_else(preferred_port)
+}
+
+/// Binds the first free port from `start` on. Two profiles can hash to the
+/// same port, and other programs may hold it, so a taken port is skipped
+/// rather than treated as fatal.
+fn bind_first_free_port(start: u16) -> io::Result<TcpListener> {
+    let mut last_error = None;
+    for port in (start..=u16::MAX).take(PORT_PROBE_ATTEMPTS as usize) {
+        let bind_addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, port);
+        match TcpListener::bind(bind_addr) {
+            Ok(listener) => return Ok(listener),
+            Err(error) if error.kind() == io::ErrorKind::AddrInUse => {
+                tracing::debug!("Local API port {port} is in use, trying the next one");
+                last_error = Some(error);
+            }
+            Err(error) => {
+                return Err(io::Error::other(format!(
+                    "Failed to bind local API server on {bind_addr}: {error}"
+                )));
+            }
+        }
+    }
+    let error = last_error.map_or_else(|| "no ports left".to_string(), |e| e.to_string());
+    Err(io::Error::other(format!(
+        "Failed to find a free port for the local API server from {start}: {error}"
+    )))
+}
+
 fn create_local_api_runtime<R: Runtime>(
     app_handle: &AppHandle<R>,
 ) -> Result<LocalApiRuntime, Box<dyn StdError>> {
@@ -90,14 +180,17 @@ fn create_local_api_runtime<R: Runtime>(
     let router = router::build_router(router::LocalApiState {
         db_path,
         auth_token,
+        index_run_guard: crate::commands::vault_indexing::index_run_guard(app_handle),
     });
 
-    let bind_addr = SocketAddrV4::new(Ipv4Addr::LOCALHOST, LOCAL_API_PORT);
-    let std_listener = TcpListener::bind(bind_addr).map_err(|error| {
-        io::Error::other(format!(
-            "Failed to bind local API server on {bind_addr}: {error}"
-        ))
-    })?;
+    let start_port = saved_port(app_handle).unwrap_or_else(preferred_port);
+    let std_listener = bind_first_free_port(start_port)?;
+    let bind_addr = std_listener.local_addr()?;
+    let port = bind_addr.port();
+    if port != start_port {
+        tracing::warn!("Local API port {start_port} is in use, using {port} instead");
+    }
+    save_port(app_handle, port);
     std_listener.set_nonblocking(true).map_err(|error| {
         io::Error::other(format!(
             "Failed to configure local API socket on {bind_addr}: {error}"
@@ -110,7 +203,7 @@ fn create_local_api_runtime<R: Runtime>(
         let listener = match tokio::net::TcpListener::from_std(std_listener) {
             Ok(l) => l,
             Err(e) => {
-                eprintln!(
+                tracing::error!(
                     "Failed to create async listener for local API server on {bind_addr}: {e}"
                 );
                 return;
@@ -122,13 +215,14 @@ fn create_local_api_runtime<R: Runtime>(
         });
 
         if let Err(error) = server.await {
-            eprintln!("Local API server stopped with an error: {error}");
+            tracing::error!("Local API server stopped with an error: {error}");
         }
     });
 
-    eprintln!("Local API server started at http://127.0.0.1:{LOCAL_API_PORT}");
+    tracing::info!("Local API server started at http://{bind_addr}");
 
     Ok(LocalApiRuntime {
+        port,
         shutdown_tx: Mutex::new(Some(shutdown_tx)),
     })
 }
@@ -184,3 +278,20 @@ pub fn handle_run_event<R: Runtime>(app_handle: &AppHandle<R>, event: &tauri::Ru
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bind_skips_ports_that_are_taken() {
+        let taken = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let taken_port = taken.local_addr().unwrap().port();
+
+        let listener = bind_first_free_port(taken_port).unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert_ne!(port, taken_port);
+        assert!(port > taken_port);
+    }
+}
@@ -1,30 +1,43 @@
 use std::{
+    collections::BTreeMap,
     future::Future,
     path::PathBuf,
     pin::Pin,
     sync::{Arc, RwLock},
     task::{Context, Poll},
+    time::Instant,
 };
 
 use axum::{
-    extract::{Path, Request, State},
+    extract::{DefaultBodyLimit, MatchedPath, Path, Request, State},
     http::{header, HeaderMap, StatusCode, Uri},
     response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
-use mdit_local_api::{CreateNoteInput, LocalApiError, LocalApiErrorKind, SearchNotesInput};
+use mdit_local_api::{
+    AppendToInboxInput, BuildContextInput, ClipNoteInput, CreateNoteInput, GetNoteIdInput,
+    LocalApiError, LocalApiErrorKind, ResolveNoteIdInput, SearchNotesInput, SearchSegmentsInput,
+    VaultDiagnosticsInput,
+};
+use mdit_vault_indexing::IndexRunGuard;
 use serde::{Deserialize, Serialize};
 use tower::{Layer, Service};
+use tracing::Instrument;
 
 use super::mcp_sdk_server::build_mcp_service;
+use super::metrics;
 
 #[derive(Debug, Clone)]
 pub struct LocalApiState {
     pub db_path: PathBuf,
     pub auth_token: Arc<RwLock<String>>,
+    pub index_run_guard: IndexRunGuard,
 }
 
+/// Clips carry whole pages, which outgrow axum's default body limit.
+const MAX_CLIP_BODY_BYTES: usize = 16 * 1024 * 1024;
+
 pub fn build_router(state: LocalApiState) -> Router {
     let protected_routes =
         build_protected_routes(state.db_path.clone(), Arc::clone(&state.auth_token));
@@ -32,6 +45,7 @@ pub fn build_router(state: LocalApiState) -> Router {
     Router::new()
         .route("/healthz", get(healthz_handler))
         .merge(protected_routes)
+        .layer(RequestSpanLayer)
         .with_state(state)
 }
 
@@ -61,17 +75,94 @@ struct CreateNoteResponse {
     note: mdit_local_api::CreatedNote,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipNoteRequest {
+    pub directory_rel_path: Option<String>,
+    pub url: Option<String>,
+    pub html: Option<String>,
+    pub selection_html: Option<String>,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClipNoteResponse {
+    note: mdit_local_api::ClippedNote,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppendToInboxRequest {
+    pub text: String,
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppendToInboxResponse {
+    entry: mdit_local_api::InboxEntry,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNoteIdRequest {
+    pub relative_path: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NoteIdentityResponse {
+    note: mdit_local_api::NoteIdentity,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchNotesRequest {
     pub query: String,
     pub limit: Option<usize>,
+    pub include_content: Option<bool>,
+    pub content_max_bytes: Option<usize>,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct SearchNotesResponse {
     results: Vec<mdit_local_api::SearchNoteEntry>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    redacted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSegmentsRequest {
+    pub query: String,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchSegmentsResponse {
+    results: Vec<mdit_local_api::SearchSegmentEntry>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    redacted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildContextRequest {
+    pub query: Option<String>,
+    #[serde(default)]
+    pub note_paths: Vec<String>,
+    pub max_tokens: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultDiagnosticsResponse {
+    case_conflicts: Vec<mdit_local_api::CaseConflict>,
+    duplicate_basenames: Vec<mdit_local_api::DuplicateBasename>,
 }
 
 #[derive(Debug, Serialize)]
@@ -98,10 +189,39 @@ fn build_protected_routes(
     Router::new()
         .route("/api/v1/vaults", get(list_vaults_handler))
         .route("/api/v1/vaults/{vault_id}/notes", post(create_note_handler))
+        .route(
+            "/api/v1/vaults/{vault_id}/clip",
+            post(clip_note_handler).layer(DefaultBodyLimit::max(MAX_CLIP_BODY_BYTES)),
+        )
+        .route(
+            "/api/v1/vaults/{vault_id}/inbox",
+            post(append_to_inbox_handler),
+        )
+        .route(
+            "/api/v1/vaults/{vault_id}/note-ids",
+            post(get_note_id_handler),
+        )
+        .route(
+            "/api/v1/vaults/{vault_id}/note-ids/{note_id}",
+            get(resolve_note_id_handler),
+        )
         .route(
             "/api/v1/vaults/{vault_id}/search",
             post(search_notes_handler),
         )
+        .route(
+            "/api/v1/vaults/{vault_id}/search/segments",
+            post(search_segments_handler),
+        )
+        .route(
+            "/api/v1/vaults/{vault_id}/context",
+            post(build_context_handler),
+        )
+        .route(
+            "/api/v1/vaults/{vault_id}/diagnostics",
+            get(vault_diagnostics_handler),
+        )
+        .route("/metrics", get(metrics_handler))
         .nest_service("/mcp", mcp_service)
         .route_layer(AuthLayer::new(auth_token))
 }
@@ -110,6 +230,28 @@ async fn healthz_handler() -> Json<HealthResponse> {
     Json(HealthResponse { status: "ok" })
 }
 
+async fn metrics_handler(State(state): State<LocalApiState>) -> Response {
+    let rendered = tokio::task::spawn_blocking(move || {
+        mdit_vault_indexing::get_indexing_metrics(&state.db_path)
+            .map(|indexing| metrics::render_metrics(&state.db_path, &indexing))
+    })
+    .await;
+
+    match rendered {
+        Ok(Ok(text)) => {
+            ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], text).into_response()
+        }
+        Ok(Err(error)) => local_api_error_to_http(LocalApiError::Internal {
+            message: format!("{error:#}"),
+        })
+        .into_response(),
+        Err(error) => local_api_error_to_http(LocalApiError::Internal {
+            message: error.to_string(),
+        })
+        .into_response(),
+    }
+}
+
 async fn list_vaults_handler(State(state): State<LocalApiState>) -> ApiResult<ListVaultsResponse> {
     match mdit_local_api::list_vaults(&state.db_path) {
         Ok(vaults) => Ok(Json(ListVaultsResponse { vaults })),
@@ -135,6 +277,80 @@ async fn create_note_handler(
     }
 }
 
+async fn clip_note_handler(
+    Path(vault_id): Path<i64>,
+    State(state): State<LocalApiState>,
+    Json(request): Json<ClipNoteRequest>,
+) -> Result<(StatusCode, Json<ClipNoteResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let input = ClipNoteInput {
+        vault_id,
+        directory_rel_path: request.directory_rel_path,
+        url: request.url,
+        html: request.html,
+        selection_html: request.selection_html,
+        title: request.title,
+    };
+
+    // Fetching the page and its images blocks.
+    let clipped = tokio::task::spawn_blocking(move || {
+        mdit_local_api::clip_note(&state.db_path, &state.index_run_guard, input)
+    })
+    .await
+    .map_err(|error| LocalApiError::Internal {
+        message: error.to_string(),
+    })
+    .and_then(|result| result);
+
+    match clipped {
+        Ok(note) => Ok((StatusCode::CREATED, Json(ClipNoteResponse { note }))),
+        Err(error) => Err(local_api_error_to_http(error)),
+    }
+}
+
+async fn append_to_inbox_handler(
+    Path(vault_id): Path<i64>,
+    State(state): State<LocalApiState>,
+    Json(request): Json<AppendToInboxRequest>,
+) -> Result<(StatusCode, Json<AppendToInboxResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let input = AppendToInboxInput {
+        vault_id,
+        text: request.text,
+        metadata: request.metadata,
+    };
+
+    match mdit_local_api::append_to_inbox(&state.db_path, input) {
+        Ok(entry) => Ok((StatusCode::CREATED, Json(AppendToInboxResponse { entry }))),
+        Err(error) => Err(local_api_error_to_http(error)),
+    }
+}
+
+async fn get_note_id_handler(
+    Path(vault_id): Path<i64>,
+    State(state): State<LocalApiState>,
+    Json(request): Json<GetNoteIdRequest>,
+) -> ApiResult<NoteIdentityResponse> {
+    let input = GetNoteIdInput {
+        vault_id,
+        relative_path: request.relative_path,
+    };
+
+    match mdit_local_api::get_note_id(&state.db_path, input) {
+        Ok(note) => Ok(Json(NoteIdentityResponse { note })),
+        Err(error) => Err(local_api_error_to_http(error)),
+    }
+}
+
+async fn resolve_note_id_handler(
+    Path((vault_id, note_id)): Path<(i64, String)>,
+    State(state): State<LocalApiState>,
+) -> ApiResult<NoteIdentityResponse> {
+    match mdit_local_api::resolve_note_id(&state.db_path, ResolveNoteIdInput { vault_id, note_id })
+    {
+        Ok(note) => Ok(Json(NoteIdentityResponse { note })),
+        Err(error) => Err(local_api_error_to_http(error)),
+    }
+}
+
 async fn search_notes_handler(
     Path(vault_id): Path<i64>,
     State(state): State<LocalApiState>,
@@ -146,10 +362,13 @@ async fn search_notes_handler(
             vault_id,
             query: request.query,
             limit: request.limit,
+            include_content: request.include_content,
+            content_max_bytes: request.content_max_bytes,
         },
     ) {
         Ok(output) => Ok(Json(SearchNotesResponse {
             results: output.results,
+            redacted: output.redacted,
         })),
         Err(error) => Err(local_api_error_to_http_with_invalid_input_status(
             error,
@@ -158,6 +377,66 @@ async fn search_notes_handler(
     }
 }
 
+async fn search_segments_handler(
+    Path(vault_id): Path<i64>,
+    State(state): State<LocalApiState>,
+    Json(request): Json<SearchSegmentsRequest>,
+) -> ApiResult<SearchSegmentsResponse> {
+    match mdit_local_api::search_segments(
+        &state.db_path,
+        SearchSegmentsInput {
+            vault_id,
+            query: request.query,
+            limit: request.limit,
+        },
+    ) {
+        Ok(output) => Ok(Json(SearchSegmentsResponse {
+            results: output.results,
+            redacted: output.redacted,
+        })),
+        Err(error) => Err(local_api_error_to_http_with_invalid_input_status(
+            error,
+            StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+async fn build_context_handler(
+    Path(vault_id): Path<i64>,
+    State(state): State<LocalApiState>,
+    Json(request): Json<BuildContextRequest>,
+) -> ApiResult<mdit_local_api::BuildContextOutput> {
+    match mdit_local_api::build_context(
+        &state.db_path,
+        BuildContextInput {
+            vault_id,
+            query: request.query,
+            note_paths: request.note_paths,
+            max_tokens: request.max_tokens,
+        },
+    ) {
+        Ok(output) => Ok(Json(output)),
+        Err(error) => Err(local_api_error_to_http_with_invalid_input_status(
+            error,
+            StatusCode::BAD_REQUEST,
+        )),
+    }
+}
+
+async fn vault_diagnostics_handler(
+    Path(vault_id): Path<i64>,
+    State(state): State<LocalApiState>,
+) -> ApiResult<VaultDiagnosticsResponse> {
+    match mdit_local_api::get_vault_diagnostics(&state.db_path, VaultDiagnosticsInput { vault_id })
+    {
+        Ok(output) => Ok(Json(VaultDiagnosticsResponse {
+            case_conflicts: output.case_conflicts,
+            duplicate_basenames: output.duplicate_basenames,
+        })),
+        Err(error) => Err(local_api_error_to_http(error)),
+    }
+}
+
 #[derive(Clone)]
 struct AuthLayer {
     auth_token: Arc<RwLock<String>>,
@@ -221,6 +500,71 @@ where
     }
 }
 
+/// Runs each request inside an `http_request` span and logs how it ended,
+/// so indexing work a request triggers shows up under it in the log.
+#[derive(Clone)]
+struct RequestSpanLayer;
+
+impl<S> Layer<S> for RequestSpanLayer {
+    type Service = RequestSpanService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestSpanService { inner }
+    }
+}
+
+#[derive(Clone)]
+struct RequestSpanService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request> for RequestSpanService<S>
+where
+    S: Service<Request, Response = Response> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // The query is left out; MCP clients may pass the token in it.
+        let span = tracing::info_span!(
+            "http_request",
+            method = %request.method(),
+            path = %request.uri().path()
+        );
+        // Route templates keep note ids out of the request counts.
+        let method = request.method().to_string();
+        let route = request
+            .extensions()
+            .get::<MatchedPath>()
+            .map_or("unmatched", MatchedPath::as_str)
+            .to_string();
+        let started = Instant::now();
+        let future = span.in_scope(|| self.inner.call(request));
+        Box::pin(
+            async move {
+                let response = future.await?;
+                let status = response.status();
+                let elapsed_ms = started.elapsed().as_millis();
+                metrics::record_request(&method, &route, status.as_u16());
+                if status.is_server_error() {
+                    tracing::error!(status = status.as_u16(), elapsed_ms, "request failed");
+                } else {
+                    tracing::info!(status = status.as_u16(), elapsed_ms, "request finished");
+                }
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
+
 fn request_has_valid_token(request: &Request, configured_token: &str) -> bool {
     if configured_token.is_empty() {
         return false;
@@ -314,7 +658,9 @@ fn local_api_error_to_http_with_invalid_input_status(
     let status = match error.kind() {
         LocalApiErrorKind::NotFound => StatusCode::NOT_FOUND,
         LocalApiErrorKind::Conflict => StatusCode::CONFLICT,
+        LocalApiErrorKind::Forbidden => StatusCode::FORBIDDEN,
         LocalApiErrorKind::InvalidInput => invalid_input_status,
+        LocalApiErrorKind::Upstream => StatusCode::BAD_GATEWAY,
         LocalApiErrorKind::Internal => StatusCode::INTERNAL_SERVER_ERROR,
     };
 
@@ -31,6 +31,10 @@ async fn mcp_tools_list_and_create_note_conflict_flow() {
     assert!(tools.iter().any(|tool| tool.name == "list_vaults"));
     assert!(tools.iter().any(|tool| tool.name == "create_note"));
     assert!(tools.iter().any(|tool| tool.name == "search_notes"));
+    assert!(tools.iter().any(|tool| tool.name == "search_segments"));
+    assert!(tools.iter().any(|tool| tool.name == "build_context"));
+    assert!(tools.iter().any(|tool| tool.name == "get_note_id"));
+    assert!(tools.iter().any(|tool| tool.name == "resolve_note_id"));
 
     client
         .call_tool(CallToolRequestParams {
@@ -150,6 +154,7 @@ fn mcp_app(harness: &Harness) -> axum::Router {
     build_mcp_only_router(LocalApiState {
         db_path: harness.db_path.clone(),
         auth_token: Arc::new(RwLock::new(TEST_AUTH_TOKEN.to_string())),
+        index_run_guard: Default::default(),
     })
 }
 
@@ -101,6 +101,93 @@ async fn create_note_returns_conflict_when_file_already_exists() {
     );
 }
 
+#[tokio::test]
+async fn note_id_round_trips_and_reports_unknown_ids() {
+    let harness = Harness::new("local-api-rest-note-ids");
+    fs::write(harness.workspace_path.join("Plan.md"), "# Plan").expect("failed to write note");
+
+    let response = app(&harness)
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/vaults/{}/note-ids", harness.vault_id))
+                .method("POST")
+                .header(header::AUTHORIZATION, TEST_AUTH_HEADER)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({ "relativePath": "Plan.md" }).to_string()))
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let payload: Value = serde_json::from_slice(&body).expect("response should be json");
+    let note_id = payload
+        .get("note")
+        .and_then(|note| note.get("noteId"))
+        .and_then(Value::as_str)
+        .expect("response should carry a note id")
+        .to_string();
+
+    let response = app(&harness)
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/v1/vaults/{}/note-ids/{note_id}",
+                    harness.vault_id
+                ))
+                .method("GET")
+                .header(header::AUTHORIZATION, TEST_AUTH_HEADER)
+                .body(Body::empty())
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let payload: Value = serde_json::from_slice(&body).expect("response should be json");
+    assert_eq!(
+        payload
+            .get("note")
+            .and_then(|note| note.get("relativePath"))
+            .and_then(Value::as_str),
+        Some("Plan.md")
+    );
+
+    let response = app(&harness)
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/v1/vaults/{}/note-ids/unknown-id",
+                    harness.vault_id
+                ))
+                .method("GET")
+                .header(header::AUTHORIZATION, TEST_AUTH_HEADER)
+                .body(Body::empty())
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let payload: Value = serde_json::from_slice(&body).expect("response should be json");
+    assert_eq!(
+        payload
+            .get("error")
+            .and_then(|value| value.get("code"))
+            .and_then(Value::as_str),
+        Some("NOTE_ID_NOT_FOUND")
+    );
+}
+
 #[tokio::test]
 async fn search_notes_returns_results() {
     let harness = Harness::new("local-api-rest-search-success");
@@ -152,6 +239,90 @@ async fn search_notes_returns_results() {
         .is_some());
 }
 
+#[tokio::test]
+async fn clip_note_creates_note_from_page_html() {
+    let harness = Harness::new("local-api-rest-clip");
+
+    let response = app(&harness)
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/vaults/{}/clip", harness.vault_id))
+                .method("POST")
+                .header(header::AUTHORIZATION, TEST_AUTH_HEADER)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    json!({
+                        "url": "https://example.com/guide",
+                        "html": "<title>Guide</title><nav>Menu</nav><main><p>Step one</p></main>"
+                    })
+                    .to_string(),
+                ))
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let payload: Value = serde_json::from_slice(&body).expect("response should be json");
+
+    assert_eq!(
+        payload
+            .get("note")
+            .and_then(|note| note.get("relativePath"))
+            .and_then(Value::as_str),
+        Some("Guide.md")
+    );
+    let content = fs::read_to_string(harness.workspace_path.join("Guide.md"))
+        .expect("clipped note should exist");
+    assert!(content.contains("source: https://example.com/guide\n"));
+    assert!(content.ends_with("---\nStep one\n"));
+}
+
+#[tokio::test]
+async fn append_to_inbox_adds_entry_with_metadata() {
+    let harness = Harness::new("local-api-rest-inbox");
+
+    let response = app(&harness)
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/vaults/{}/inbox", harness.vault_id))
+                .method("POST")
+                .header(header::AUTHORIZATION, TEST_AUTH_HEADER)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    json!({
+                        "text": "Renew passport",
+                        "metadata": { "agent": "assistant" }
+                    })
+                    .to_string(),
+                ))
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let payload: Value = serde_json::from_slice(&body).expect("response should be json");
+
+    let timestamp = payload
+        .get("entry")
+        .and_then(|entry| entry.get("timestamp"))
+        .and_then(Value::as_str)
+        .expect("entry timestamp should exist");
+    let content = fs::read_to_string(harness.workspace_path.join("Inbox.md"))
+        .expect("inbox note should exist");
+    assert_eq!(
+        content,
+        format!("## {timestamp}\n\n- agent: assistant\n\nRenew passport\n")
+    );
+}
+
 #[tokio::test]
 async fn search_notes_returns_bad_request_for_empty_query() {
     let harness = Harness::new("local-api-rest-search-empty-query");
@@ -226,6 +397,128 @@ async fn search_notes_returns_not_found_for_unknown_vault() {
     );
 }
 
+#[tokio::test]
+async fn search_segments_returns_bad_request_for_empty_query() {
+    let harness = Harness::new("local-api-rest-search-segments-empty-query");
+
+    let response = app(&harness)
+        .oneshot(
+            Request::builder()
+                .uri(format!(
+                    "/api/v1/vaults/{}/search/segments",
+                    harness.vault_id
+                ))
+                .method("POST")
+                .header(header::AUTHORIZATION, TEST_AUTH_HEADER)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    json!({
+                        "query": "   "
+                    })
+                    .to_string(),
+                ))
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let payload: Value = serde_json::from_slice(&body).expect("response should be json");
+
+    assert_eq!(
+        payload
+            .get("error")
+            .and_then(|value| value.get("code"))
+            .and_then(Value::as_str),
+        Some("INVALID_SEARCH_QUERY")
+    );
+}
+
+#[tokio::test]
+async fn build_context_returns_bad_request_without_query_or_notes() {
+    let harness = Harness::new("local-api-rest-build-context-empty");
+
+    let response = app(&harness)
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/vaults/{}/context", harness.vault_id))
+                .method("POST")
+                .header(header::AUTHORIZATION, TEST_AUTH_HEADER)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    json!({
+                        "query": "   "
+                    })
+                    .to_string(),
+                ))
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let payload: Value = serde_json::from_slice(&body).expect("response should be json");
+
+    assert_eq!(
+        payload
+            .get("error")
+            .and_then(|value| value.get("code"))
+            .and_then(Value::as_str),
+        Some("MISSING_CONTEXT_SOURCE")
+    );
+}
+
+#[tokio::test]
+async fn vault_diagnostics_returns_duplicate_basenames() {
+    let harness = Harness::new("local-api-rest-diagnostics");
+    for dir in ["a", "b"] {
+        let dir_path = harness.workspace_path.join(dir);
+        fs::create_dir_all(&dir_path).expect("failed to create note directory");
+        fs::write(dir_path.join("Meeting.md"), "# Meeting").expect("failed to write note");
+    }
+
+    let response = app(&harness)
+        .oneshot(
+            Request::builder()
+                .uri(format!("/api/v1/vaults/{}/diagnostics", harness.vault_id))
+                .method("GET")
+                .header(header::AUTHORIZATION, TEST_AUTH_HEADER)
+                .body(Body::empty())
+                .expect("failed to build request"),
+        )
+        .await
+        .expect("request should succeed");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("failed to read response body");
+    let payload: Value = serde_json::from_slice(&body).expect("response should be json");
+
+    assert_eq!(
+        payload
+            .get("caseConflicts")
+            .and_then(Value::as_array)
+            .map(Vec::len),
+        Some(0)
+    );
+    assert_eq!(
+        payload.get("duplicateBasenames"),
+        Some(&json!([
+            {
+                "basename": "meeting",
+                "relPaths": ["a/Meeting.md", "b/Meeting.md"]
+            }
+        ]))
+    );
+}
+
 #[tokio::test]
 async fn get_vaults_returns_unauthorized_without_token() {
     let harness = Harness::new("local-api-rest-unauthorized");
@@ -264,5 +557,6 @@ fn app(harness: &Harness) -> axum::Router {
     build_router(LocalApiState {
         db_path: harness.db_path.clone(),
         auth_token: Arc::new(RwLock::new(TEST_AUTH_TOKEN.to_string())),
+        index_run_guard: Default::default(),
     })
 }
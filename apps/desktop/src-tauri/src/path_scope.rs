@@ -0,0 +1,301 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, Runtime, State};
+use tauri_plugin_dialog::DialogExt;
+
+/// Roots the webview may touch in addition to registered vaults, such as
+/// folders the user picked for export. Kept in memory only.
+#[derive(Default)]
+pub struct PathScopeState {
+    extra_roots: Mutex<Vec<PathBuf>>,
+}
+
+impl PathScopeState {
+    fn lock_roots(&self) -> Result<std::sync::MutexGuard<'_, Vec<PathBuf>>, String> {
+        self.extra_roots
+            .lock()
+            .map_err(|error| format!("Failed to lock path scope state: {}", error))
+    }
+}
+
+/// Canonical roots that path arguments from the webview must stay inside.
+pub struct PathScope {
+    roots: Vec<PathBuf>,
+}
+
+impl PathScope {
+    /// Collects registered vaults, extra allowed roots, and the folders of
+    /// files the app was opened with.
+    pub fn load<R: Runtime>(app_handle: &AppHandle<R>) -> Result<Self, String> {
+        let db_path = crate::persistence::run_app_migrations(app_handle)?;
+        let mut roots = app_storage::vault::list_workspaces(&db_path)
+            .map_err(|error| error.to_string())?
+            .into_iter()
+            .map(PathBuf::from)
+            .collect::<Vec<_>>();
+
+        if let Some(state) = app_handle.try_state::<PathScopeState>() {
+            roots.extend(state.lock_roots()?.iter().cloned());
+        }
+        if let Some(state) = app_handle.try_state::<crate::app::file_opening::AppState>() {
+            let opened_files = state
+                .opened_files
+                .lock()
+                .map_err(|error| format!("Failed to lock opened files: {}", error))?;
+            roots.extend(
+                opened_files
+                    .iter()
+                    .filter_map(|file| Path::new(file).parent().map(Path::to_path_buf)),
+            );
+        }
+
+        Ok(Self::new(roots))
+    }
+
+    fn new(roots: Vec<PathBuf>) -> Self {
+        // Roots that no longer exist cannot contain anything valid.
+        let roots = roots
+            .iter()
+            .filter_map(|root| fs::canonicalize(root).ok())
+            .collect();
+        Self { roots }
+    }
+
+    /// Resolved form of `path`, or an error when it leaves every root. The
+    /// path does not need to exist yet, so destinations can be validated too.
+    pub fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        let resolved = canonicalize_lenient(Path::new(path))?;
+        self.ensure_inside(resolved, path)
+    }
+
+    /// Resolved form of an entry that is trashed, moved or renamed. Only its
+    /// folder is resolved and the last segment is kept as given, so a symlink
+    /// is acted on itself rather than on what it points to.
+    pub fn resolve_entry(&self, path: &str) -> Result<PathBuf, String> {
+        let entry = Path::new(path);
+        ensure_plain_absolute(entry)?;
+        let (Some(parent), Some(name)) = (entry.parent(), entry.file_name()) else {
+            return Err(format!("Cannot act on {}", path));
+        };
+        let resolved = canonicalize_lenient(parent)?.join(name);
+        self.ensure_inside(resolved, path)
+    }
+
+    fn ensure_inside(&self, resolved: PathBuf, path: &str) -> Result<PathBuf, String> {
+        if self.roots.iter().any(|root| resolved.starts_with(root)) {
+            Ok(resolved)
+        } else {
+            Err(format!("Access denied outside of vaults: {}", path))
+        }
+    }
+}
+
+/// Checks a folder the webview wants to register as a vault. Registered
+/// vaults become roots, so only a folder picked in the native dialog or one
+/// already inside a root may be registered.
+pub fn ensure_registrable<R: Runtime>(app_handle: &AppHandle<R>, path: &str) -> Result<(), String> {
+    PathScope::load(app_handle)?.resolve(path).map(|_| ())
+}
+
+/// Loads the scope and resolves a single path argument.
+pub fn resolve_path<R: Runtime>(app_handle: &AppHandle<R>, path: &str) -> Result<PathBuf, String> {
+    PathScope::load(app_handle)?.resolve(path)
+}
+
+/// Canonicalizes `path` when it exists, so a symlink inside a root cannot
+/// point commands at a file outside it. Paths that are about to be created
+/// resolve their longest existing ancestor and append the rest.
+fn canonicalize_lenient(path: &Path) -> Result<PathBuf, String> {
+    ensure_plain_absolute(path)?;
+
+    if fs::symlink_metadata(path).is_ok() {
+        return fs::canonicalize(path)
+            .map_err(|error| format!("Cannot resolve path {}: {}", path.display(), error));
+    }
+
+    let parent = path.parent().unwrap_or(path);
+    for ancestor in parent.ancestors() {
+        let Ok(canonical) = fs::canonicalize(ancestor) else {
+            continue;
+        };
+        let rest = path
+            .strip_prefix(ancestor)
+            .map_err(|error| error.to_string())?;
+        return Ok(canonical.join(rest));
+    }
+
+    Err(format!("Cannot resolve path: {}", path.display()))
+}
+
+fn ensure_plain_absolute(path: &Path) -> Result<(), String> {
+    if !path.is_absolute() {
+        return Err(format!("Path must be absolute: {}", path.display()));
+    }
+    if path
+        .components()
+        .any(|component| component == Component::ParentDir)
+    {
+        return Err(format!(
+            "Path must not contain parent directory segments: {}",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Adds a root chosen through a backend flow. Never reachable with a path
+/// supplied by the webview.
+fn allow_root<R: Runtime>(app_handle: &AppHandle<R>, path: &Path) -> Result<PathBuf, String> {
+    let root = fs::canonicalize(path)
+        .map_err(|error| format!("Invalid root {}: {}", path.display(), error))?;
+    let state = app_handle.state::<PathScopeState>();
+    let mut roots = state.lock_roots()?;
+    if !roots.contains(&root) {
+        roots.push(root.clone());
+    }
+    Ok(root)
+}
+
+/// Lets the user pick a folder in the native dialog and allows it as a root.
+/// Returns `None` when the dialog is cancelled.
+#[tauri::command]
+pub async fn pick_allowed_folder_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    title: Option<String>,
+) -> Result<Option<String>, String> {
+    let picker = app_handle.clone();
+    let picked = tauri::async_runtime::spawn_blocking(move || {
+        let dialog = picker.dialog().file();
+        match title {
+            Some(title) => dialog.set_title(title),
+            None => dialog,
+        }
+        .blocking_pick_folder()
+    })
+    .await
+    .map_err(|error| format!("Folder picker failed: {}", error))?;
+
+    let Some(folder) = picked else {
+        return Ok(None);
+    };
+    let folder = folder
+        .into_path()
+        .map_err(|error| format!("Invalid folder: {}", error))?;
+    let root = allow_root(&app_handle, &folder)?;
+    Ok(Some(root.to_string_lossy().into_owned()))
+}
+
+#[tauri::command]
+pub fn revoke_path_root_command(
+    state: State<'_, PathScopeState>,
+    path: String,
+) -> Result<(), String> {
+    let root = fs::canonicalize(&path).unwrap_or_else(|_| PathBuf::from(&path));
+    state.lock_roots()?.retain(|allowed| allowed != &root);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::PathScope;
+
+    fn temp_dir(prefix: &str) -> PathBuf {
+        let id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock error")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("{prefix}-{id}"));
+        fs::create_dir_all(&path).expect("failed to create temp dir");
+        path
+    }
+
+    #[test]
+    fn resolves_existing_and_new_paths_inside_roots() {
+        let root = temp_dir("mdit-path-scope-inside");
+        fs::write(root.join("note.md"), "").expect("write note");
+        let scope = PathScope::new(vec![root.clone()]);
+        let canonical_root = fs::canonicalize(&root).expect("canonical root");
+
+        let existing = scope
+            .resolve(&root.join("note.md").to_string_lossy())
+            .expect("existing note is allowed");
+        assert_eq!(existing, canonical_root.join("note.md"));
+
+        let created = scope
+            .resolve(&root.join("new/child.md").to_string_lossy())
+            .expect("new note is allowed");
+        assert_eq!(created, canonical_root.join("new/child.md"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn rejects_traversal_and_paths_outside_roots() {
+        let root = temp_dir("mdit-path-scope-root");
+        let outside = temp_dir("mdit-path-scope-outside");
+        let scope = PathScope::new(vec![root.clone()]);
+
+        let escaped = root.join("../").join(outside.file_name().expect("name"));
+        assert!(scope.resolve(&escaped.to_string_lossy()).is_err());
+        assert!(scope.resolve(&outside.to_string_lossy()).is_err());
+        assert!(scope.resolve("relative/note.md").is_err());
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&outside);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_pointing_outside_roots() {
+        let root = temp_dir("mdit-path-scope-link-root");
+        let outside = temp_dir("mdit-path-scope-link-outside");
+        fs::write(outside.join("secret.md"), "").expect("write secret");
+        std::os::unix::fs::symlink(outside.join("secret.md"), root.join("note.md"))
+            .expect("create file link");
+        std::os::unix::fs::symlink(&outside, root.join("linked")).expect("create folder link");
+        let scope = PathScope::new(vec![root.clone()]);
+
+        assert!(scope
+            .resolve(&root.join("note.md").to_string_lossy())
+            .is_err());
+        assert!(scope
+            .resolve(&root.join("linked/secret.md").to_string_lossy())
+            .is_err());
+        assert!(scope
+            .resolve(&root.join("linked/new.md").to_string_lossy())
+            .is_err());
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&outside);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolves_entries_to_the_link_itself() {
+        let root = temp_dir("mdit-path-scope-entry-root");
+        let outside = temp_dir("mdit-path-scope-entry-outside");
+        fs::write(outside.join("secret.md"), "").expect("write secret");
+        std::os::unix::fs::symlink(outside.join("secret.md"), root.join("note.md"))
+            .expect("create file link");
+        std::os::unix::fs::symlink(&outside, root.join("linked")).expect("create folder link");
+        let scope = PathScope::new(vec![root.clone()]);
+        let canonical_root = fs::canonicalize(&root).expect("canonical root");
+
+        let link = scope
+            .resolve_entry(&root.join("note.md").to_string_lossy())
+            .expect("the link itself is inside the root");
+        assert_eq!(link, canonical_root.join("note.md"));
+        assert!(scope
+            .resolve_entry(&root.join("linked/secret.md").to_string_lossy())
+            .is_err());
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&outside);
+    }
+}
@@ -1,10 +1,62 @@
+pub mod portable;
+pub mod profile;
+
 use std::path::{Path, PathBuf};
 
 use anyhow::Context;
-use tauri::{AppHandle, Manager, Runtime};
+use tauri::{webview::WebviewWindowBuilder, AppHandle, Manager, Runtime};
+
+#[cfg(not(target_os = "macos"))]
+const WEBVIEW_DATA_DIR: &str = "webview";
+
+/// App data directory of the running profile. Everything the app stores
+/// outside a vault (the appdata database, settings files, logs) lives here;
+/// in portable mode that is the `data` folder beside the app.
+pub fn app_data_dir<R: Runtime>(app_handle: &AppHandle<R>) -> tauri::Result<PathBuf> {
+    let base = match portable::portable_data_dir() {
+        Some(dir) => dir.to_path_buf(),
+        None => app_handle.path().app_data_dir()?,
+    };
+    Ok(profile::profile_data_dir(base))
+}
+
+/// Gives windows their own webview storage, which holds the settings kept in
+/// local storage, when running a named profile or in portable mode. Other
+/// launches keep the platform's default store.
+#[cfg_attr(target_os = "macos", allow(unused_variables))]
+pub(crate) fn with_webview_data<'a, R: Runtime, M: Manager<R>>(
+    builder: WebviewWindowBuilder<'a, R, M>,
+    app_handle: &AppHandle<R>,
+) -> WebviewWindowBuilder<'a, R, M> {
+    // WKWebView stores cannot be moved to a folder, so portable mode leaves
+    // them where they are.
+    #[cfg(target_os = "macos")]
+    {
+        match profile::current_profile() {
+            Some(name) => {
+                builder.data_store_identifier(profile::webview_data_store_identifier(name))
+            }
+            None => builder,
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        if profile::current_profile().is_none() && portable::portable_data_dir().is_none() {
+            return builder;
+        }
+        match app_data_dir(app_handle) {
+            Ok(dir) => builder.data_directory(dir.join(WEBVIEW_DATA_DIR)),
+            Err(error) => {
+                tracing::error!("Failed to resolve webview data directory: {error}");
+                builder
+            }
+        }
+    }
+}
 
 pub fn run_app_migrations_anyhow<R: Runtime>(app_handle: &AppHandle<R>) -> anyhow::Result<PathBuf> {
-    let app_data_dir = app_handle.path().app_data_dir().map_err(|error| {
+    let app_data_dir = app_data_dir(app_handle).map_err(|error| {
         anyhow::anyhow!(
             "Failed to resolve app data directory for appdata database: {}",
             error
@@ -36,9 +88,10 @@ pub fn apply_appdata_migrations(
             if let Err(error) =
                 app_storage::migrations::cleanup_legacy_workspace_index_db(Path::new(trimmed))
             {
-                eprintln!(
+                tracing::error!(
                     "Failed to clean up legacy workspace DB at {}: {}",
-                    trimmed, error
+                    trimmed,
+                    error
                 );
             }
         }
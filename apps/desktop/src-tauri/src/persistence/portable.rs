@@ -0,0 +1,139 @@
+//! Portable mode: app data goes to a `data` folder beside the app instead of
+//! the platform's app data location, so mdit can run from a USB stick or a
+//! synced folder without leaving state behind on the machine.
+//!
+//! It is turned on by a `portable` file next to the executable, or by the
+//! `MDIT_PORTABLE` environment variable: `1` or `true` for the folder beside
+//! the app, a folder path (such as a vault) to keep `data` there instead, and
+//! `0` or `false` to ignore the marker file.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const PORTABLE_MARKER_FILE: &str = "portable";
+const PORTABLE_ENV_VAR: &str = "MDIT_PORTABLE";
+const PORTABLE_DATA_DIR: &str = "data";
+const WINDOW_STATE_FILE: &str = ".window-state.json";
+
+static PORTABLE_DATA: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Decides on portable mode and creates its data folder. Called once at
+/// startup, before anything resolves the app data directory.
+pub fn initialize() -> Result<(), String> {
+    let app_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| app_dir_of_executable(&exe));
+    let data_dir = portable_root(std::env::var_os(PORTABLE_ENV_VAR), app_dir)?
+        .map(|root| root.join(PORTABLE_DATA_DIR));
+
+    if let Some(data_dir) = &data_dir {
+        std::fs::create_dir_all(data_dir).map_err(|error| {
+            format!(
+                "Failed to create portable data folder {}: {error}",
+                data_dir.display()
+            )
+        })?;
+    }
+    let _ = PORTABLE_DATA.set(data_dir);
+    Ok(())
+}
+
+/// The `data` folder app data goes to, or `None` outside portable mode.
+pub fn portable_data_dir() -> Option<&'static Path> {
+    PORTABLE_DATA.get().and_then(|dir| dir.as_deref())
+}
+
+/// Where the window-state plugin saves window positions in portable mode.
+/// It otherwise writes to the platform's app config folder.
+pub(crate) fn window_state_file() -> Option<String> {
+    portable_data_dir().map(|dir| dir.join(WINDOW_STATE_FILE).to_string_lossy().into_owned())
+}
+
+fn portable_root(
+    env_value: Option<OsString>,
+    app_dir: Option<PathBuf>,
+) -> Result<Option<PathBuf>, String> {
+    let marker_root = || {
+        app_dir
+            .clone()
+            .filter(|dir| dir.join(PORTABLE_MARKER_FILE).is_file())
+    };
+    let Some(value) = env_value else {
+        return Ok(marker_root());
+    };
+
+    match value.to_string_lossy().trim() {
+        "" => Ok(marker_root()),
+        "0" | "false" => Ok(None),
+        "1" | "true" => app_dir.map(Some).ok_or_else(|| {
+            format!("{PORTABLE_ENV_VAR} is set but the app folder could not be found")
+        }),
+        _ => {
+            let root = PathBuf::from(value);
+            if root.is_dir() {
+                Ok(Some(root))
+            } else {
+                Err(format!(
+                    "{PORTABLE_ENV_VAR} must be 1, 0 or an existing folder: {}",
+                    root.display()
+                ))
+            }
+        }
+    }
+}
+
+/// The folder the app was started from. On macOS that is the folder holding
+/// `Mdit.app`, not the executable's folder inside the bundle.
+fn app_dir_of_executable(exe: &Path) -> Option<PathBuf> {
+    let exe_dir = exe.parent()?;
+    let bundle = exe_dir
+        .ancestors()
+        .find(|dir| dir.extension().is_some_and(|extension| extension == "app"));
+    match bundle {
+        Some(bundle) if cfg!(target_os = "macos") => bundle.parent().map(Path::to_path_buf),
+        _ => Some(exe_dir.to_path_buf()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{portable_root, PORTABLE_MARKER_FILE};
+
+    #[test]
+    fn marker_file_and_env_var_turn_portable_mode_on() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let app_dir = std::env::temp_dir().join(format!("mdit-portable-{nanos}"));
+        let vault = app_dir.join("vault");
+        fs::create_dir_all(&vault).expect("create folders");
+
+        assert_eq!(portable_root(None, Some(app_dir.clone())), Ok(None));
+        assert_eq!(
+            portable_root(Some("1".into()), Some(app_dir.clone())),
+            Ok(Some(app_dir.clone()))
+        );
+        assert_eq!(
+            portable_root(Some(vault.clone().into()), Some(app_dir.clone())),
+            Ok(Some(vault.clone()))
+        );
+        assert!(portable_root(Some(app_dir.join("missing").into()), None).is_err());
+
+        fs::write(app_dir.join(PORTABLE_MARKER_FILE), "").expect("write marker");
+        assert_eq!(
+            portable_root(None, Some(app_dir.clone())),
+            Ok(Some(app_dir.clone()))
+        );
+        assert_eq!(
+            portable_root(Some("0".into()), Some(app_dir.clone())),
+            Ok(None)
+        );
+
+        let _ = fs::remove_dir_all(&app_dir);
+    }
+}
@@ -0,0 +1,159 @@
+//! App profiles chosen with `--profile <name>` at launch.
+//!
+//! A named profile keeps its own app data (vault registry, settings, logs),
+//! keychain entries, webview storage and local API port, so a work and a
+//! personal profile can run side by side. Without the flag the app uses the
+//! default profile, whose data stays where it always was.
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+const PROFILE_FLAG: &str = "--profile";
+const PROFILES_DIR: &str = "profiles";
+const MAX_PROFILE_NAME_CHARS: usize = 64;
+
+static CURRENT_PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+/// Reads the profile from the command line. Called once at startup, before
+/// anything resolves the app data directory.
+pub fn initialize() -> Result<(), String> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let _ = CURRENT_PROFILE.set(profile_from_args(&args)?);
+    Ok(())
+}
+
+/// The named profile this instance runs, or `None` for the default one.
+pub fn current_profile() -> Option<&'static str> {
+    CURRENT_PROFILE.get().and_then(|profile| profile.as_deref())
+}
+
+/// `base` for the default profile, `base/profiles/<name>` for a named one.
+pub(crate) fn profile_data_dir(base: PathBuf) -> PathBuf {
+    match current_profile() {
+        Some(name) => base.join(PROFILES_DIR).join(name),
+        None => base,
+    }
+}
+
+/// Keychain service under which the running profile keeps its secrets.
+pub(crate) fn keychain_service(service: &str) -> String {
+    match current_profile() {
+        Some(name) => format!("{service}.profile.{name}"),
+        None => service.to_string(),
+    }
+}
+
+/// Stable number derived from the profile name, e.g. to pick its port.
+pub(crate) fn profile_hash(name: &str) -> u64 {
+    // FNV-1a; unlike `DefaultHasher` it stays the same across Rust releases.
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Data store of a named profile's webviews on macOS, where WKWebView picks
+/// stores by a UUID instead of a data directory.
+#[cfg(target_os = "macos")]
+pub(crate) fn webview_data_store_identifier(name: &str) -> [u8; 16] {
+    let mut identifier = [0; 16];
+    identifier[..8].copy_from_slice(&profile_hash(name).to_le_bytes());
+    identifier[8..].copy_from_slice(&profile_hash(&format!("{PROFILES_DIR}/{name}")).to_le_bytes());
+    identifier
+}
+
+/// Command line arguments other than `--profile` and its value.
+pub(crate) fn without_profile_args<'a, I>(args: I) -> Vec<&'a String>
+where
+    I: Iterator<Item = &'a String>,
+{
+    let mut skip_value = false;
+    args.filter(|arg| {
+        if std::mem::take(&mut skip_value) {
+            return false;
+        }
+        if arg.as_str() == PROFILE_FLAG {
+            skip_value = true;
+            return false;
+        }
+        !arg.starts_with(&format!("{PROFILE_FLAG}="))
+    })
+    .collect()
+}
+
+fn profile_from_args(args: &[String]) -> Result<Option<String>, String> {
+    let mut profile = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == PROFILE_FLAG {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("{PROFILE_FLAG} needs a profile name"))?;
+            profile = Some(value.as_str());
+        } else if let Some(value) = arg.strip_prefix(&format!("{PROFILE_FLAG}=")) {
+            profile = Some(value);
+        }
+    }
+
+    match profile.map(str::trim) {
+        // `--profile default` is the same as no flag.
+        None | Some("default") => Ok(None),
+        Some(name) => validate_profile_name(name).map(|_| Some(name.to_string())),
+    }
+}
+
+// The name becomes a folder and part of a keychain service name, so it is
+// kept to characters that are safe in both.
+fn validate_profile_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.chars().count() > MAX_PROFILE_NAME_CHARS {
+        return Err(format!(
+            "Profile name must be 1 to {MAX_PROFILE_NAME_CHARS} characters long"
+        ));
+    }
+    if !name
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+    {
+        return Err(format!(
+            "Profile name may only contain letters, digits, '-' and '_': {name}"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{profile_from_args, without_profile_args};
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn profile_is_read_from_either_flag_form() {
+        assert_eq!(profile_from_args(&args(&["notes.md"])), Ok(None));
+        assert_eq!(
+            profile_from_args(&args(&["--profile", "work"])),
+            Ok(Some("work".to_string()))
+        );
+        assert_eq!(
+            profile_from_args(&args(&["--background", "--profile=personal"])),
+            Ok(Some("personal".to_string()))
+        );
+        assert_eq!(
+            profile_from_args(&args(&["--profile", "default"])),
+            Ok(None)
+        );
+        assert!(profile_from_args(&args(&["--profile"])).is_err());
+        assert!(profile_from_args(&args(&["--profile", "../work"])).is_err());
+        assert!(profile_from_args(&args(&["--profile="])).is_err());
+    }
+
+    #[test]
+    fn profile_args_are_not_taken_for_paths() {
+        let args = args(&["--profile", "work", "notes.md", "--profile=home", "vault"]);
+        assert_eq!(
+            without_profile_args(args.iter()),
+            vec![&"notes.md".to_string(), &"vault".to_string()]
+        );
+    }
+}
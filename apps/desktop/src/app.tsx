@@ -14,26 +14,35 @@ import { SettingsDialog } from "./components/settings"
 import { Welcome } from "./components/welcome/welcome"
 import { ScreenCaptureProvider } from "./contexts/screen-capture-context"
 import { useAutoIndexing } from "./hooks/use-auto-indexing"
+import { useDeepLinks } from "./hooks/use-deep-links"
 import { useFontScale } from "./hooks/use-font-scale"
 import { useGitSync } from "./hooks/use-git-sync"
 import { useStoreRuntimeLifecycle } from "./hooks/use-store-runtime-lifecycle"
 import { useWorkspaceLifecycle } from "./hooks/use-workspace-lifecycle"
 import { startLocalApiServer, stopLocalApiServer } from "./lib/local-api"
 import { shouldRunLocalApiServer } from "./lib/local-api-runtime"
+import { closeVaultWindow, isVaultWindowLabel } from "./lib/vault-windows"
 import { isLinux, isWindows10 } from "./utils/platform"
 
 export function App() {
-	const { workspacePath, isLoading, localApiEnabled, setLocalApiError } =
-		useStore(
-			useShallow((s) => ({
-				workspacePath: s.workspacePath,
-				isLoading: s.isLoading,
-				localApiEnabled: s.localApiEnabled,
-				setLocalApiError: s.setLocalApiError,
-			})),
-		)
+	const {
+		workspacePath,
+		isLoading,
+		localApiEnabled,
+		setLocalApiError,
+		setLocalApiPort,
+	} = useStore(
+		useShallow((s) => ({
+			workspacePath: s.workspacePath,
+			isLoading: s.isLoading,
+			localApiEnabled: s.localApiEnabled,
+			setLocalApiError: s.setLocalApiError,
+			setLocalApiPort: s.setLocalApiPort,
+		})),
+	)
 	useFontScale()
 	useWorkspaceLifecycle()
+	useDeepLinks()
 	useStoreRuntimeLifecycle()
 	useAutoIndexing(workspacePath)
 	useGitSync(workspacePath)
@@ -45,6 +54,10 @@ export function App() {
 		const closeListener = appWindow.listen(
 			"tauri://close-requested",
 			async () => {
+				if (isVaultWindowLabel(appWindow.label)) {
+					await closeVaultWindow()
+					return
+				}
 				const isFullscreen = await appWindow.isFullscreen()
 				if (isFullscreen) {
 					await appWindow.setFullscreen(false)
@@ -72,7 +85,10 @@ export function App() {
 		const syncLocalApiServerState = async () => {
 			try {
 				if (shouldRunLocalApi) {
-					await startLocalApiServer()
+					const port = await startLocalApiServer()
+					if (isActive) {
+						setLocalApiPort(port)
+					}
 				} else {
 					await stopLocalApiServer()
 				}
@@ -92,7 +108,7 @@ export function App() {
 		return () => {
 			isActive = false
 		}
-	}, [localApiEnabled, setLocalApiError])
+	}, [localApiEnabled, setLocalApiError, setLocalApiPort])
 
 	if (isLoading) {
 		return <div className={`h-screen ${mutedBgClass}`} />
@@ -282,7 +282,7 @@ export function CollectionView() {
 									isSelected={isSelected}
 									onClick={handleClick}
 									onContextMenu={handleContextMenu}
-									previewText={getPreview(entry.path)}
+									previewText={getPreview(entry.path)?.excerpt}
 									setPreview={setPreview}
 									isRenaming={renamingEntryPath === entry.path}
 									onRenameSubmit={handleRenameSubmit}
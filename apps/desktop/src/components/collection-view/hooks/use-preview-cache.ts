@@ -1,8 +1,20 @@
 import { invoke } from "@tauri-apps/api/core"
 import { useCallback, useEffect, useRef, useState } from "react"
 
+export type NotePreview = {
+	title: string | null
+	excerpt: string
+	imagePath: string | null
+}
+
+const EMPTY_PREVIEW: NotePreview = {
+	title: null,
+	excerpt: "",
+	imagePath: null,
+}
+
 export function usePreviewCache(currentCollectionPath: string | null) {
-	const [previewTexts, setPreviewTexts] = useState<Map<string, string>>(
+	const [previews, setPreviews] = useState<Map<string, NotePreview>>(
 		new Map(),
 	)
 	const timeoutRef = useRef<number | null>(null)
@@ -17,12 +29,12 @@ export function usePreviewCache(currentCollectionPath: string | null) {
 		if (currentCollectionPath === null) {
 			// Delay cache clear when closing (to match transition delay)
 			timeoutRef.current = window.setTimeout(() => {
-				setPreviewTexts(new Map())
+				setPreviews(new Map())
 				timeoutRef.current = null
 			}, 100)
 		} else {
 			// Clear cache immediately when collection path changes
-			setPreviewTexts(new Map())
+			setPreviews(new Map())
 		}
 
 		return () => {
@@ -36,25 +48,25 @@ export function usePreviewCache(currentCollectionPath: string | null) {
 	// Stable cache access functions
 	const getPreview = useCallback(
 		(path: string) => {
-			return previewTexts.get(path)
+			return previews.get(path)
 		},
-		[previewTexts],
+		[previews],
 	)
 
 	const setPreview = useCallback(async (path: string) => {
 		try {
-			const preview = await invoke<string>("get_note_preview", {
+			const preview = await invoke<NotePreview>("get_note_preview", {
 				path,
 			})
-			setPreviewTexts((prev) => {
+			setPreviews((prev) => {
 				const next = new Map(prev)
 				next.set(path, preview)
 				return next
 			})
 		} catch (_e) {
-			setPreviewTexts((prev) => {
+			setPreviews((prev) => {
 				const next = new Map(prev)
-				next.set(path, "")
+				next.set(path, EMPTY_PREVIEW)
 				return next
 			})
 		}
@@ -62,10 +74,10 @@ export function usePreviewCache(currentCollectionPath: string | null) {
 
 	const invalidatePreview = useCallback(async (path: string) => {
 		try {
-			const preview = await invoke<string>("get_note_preview", {
+			const preview = await invoke<NotePreview>("get_note_preview", {
 				path,
 			})
-			setPreviewTexts((prev) => {
+			setPreviews((prev) => {
 				if (!prev.has(path)) {
 					return prev
 				}
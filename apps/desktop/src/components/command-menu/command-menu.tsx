@@ -1,5 +1,8 @@
 import {
 	type CommandMenuContentMatch,
+	type CommandMenuMatchedPassage,
+	type CommandMenuNoteAliases,
+	type CommandMenuPinnedNotes,
 	type CommandMenuSemanticResult,
 	type CommandMenuTagResult,
 	CommandMenu as SharedCommandMenu,
@@ -7,6 +10,7 @@ import {
 import { invoke } from "@tauri-apps/api/core"
 import { useCallback } from "react"
 import { useShallow } from "zustand/shallow"
+import { listPinnedNotes } from "@/lib/pinned-notes"
 import { useStore } from "@/store"
 import { searchMarkdownContent } from "./utils/note-content-search"
 
@@ -16,6 +20,7 @@ type QuerySearchEntry = {
 	createdAt?: number
 	modifiedAt?: number
 	similarity: number
+	passage: CommandMenuMatchedPassage | null
 }
 
 type QueryTagEntry = {
@@ -64,18 +69,21 @@ export function CommandMenu() {
 			query: string,
 			currentWorkspacePath: string,
 		): Promise<CommandMenuSemanticResult[]> => {
-			const results = await invoke<QuerySearchEntry[]>(
-				"search_query_entries_command",
+			// A misspelled query falls back to its correction rather than
+			// leaving the results empty.
+			const { entries } = await invoke<{ entries: QuerySearchEntry[] }>(
+				"search_query_with_correction_command",
 				{
 					workspacePath: currentWorkspacePath,
 					query,
 				},
 			)
 
-			return results.map((entry) => ({
+			return entries.map((entry) => ({
 				path: entry.path,
 				name: entry.name,
 				similarity: entry.similarity,
+				passage: entry.passage ?? undefined,
 				createdAt:
 					typeof entry.createdAt === "number"
 						? new Date(entry.createdAt)
@@ -114,6 +122,22 @@ export function CommandMenu() {
 		[],
 	)
 
+	const loadNoteAliases = useCallback(
+		(currentWorkspacePath: string): Promise<CommandMenuNoteAliases> =>
+			invoke<CommandMenuNoteAliases>("get_note_aliases_command", {
+				workspacePath: currentWorkspacePath,
+			}),
+		[],
+	)
+
+	const loadPinnedNotes = useCallback(
+		async (currentWorkspacePath: string): Promise<CommandMenuPinnedNotes> => {
+			const pinnedNotes = await listPinnedNotes(currentWorkspacePath)
+			return pinnedNotes.map((pinnedNote) => pinnedNote.relPath)
+		},
+		[],
+	)
+
 	return (
 		<SharedCommandMenu
 			open={isCommandMenuOpen}
@@ -125,6 +149,8 @@ export function CommandMenu() {
 			searchContent={searchContent}
 			searchSemantic={searchSemantic}
 			searchTags={searchTags}
+			loadNoteAliases={loadNoteAliases}
+			loadPinnedNotes={loadPinnedNotes}
 		/>
 	)
 }
@@ -5,6 +5,7 @@ import {
 	PopoverContent,
 	PopoverTrigger,
 } from "@mdit/ui/components/popover"
+import type { BacklinkEntry } from "@mdit/store/core"
 import { Separator } from "@mdit/ui/components/separator"
 import { invoke } from "@tauri-apps/api/core"
 import { ArrowRight, InfoIcon } from "lucide-react"
@@ -17,14 +18,13 @@ import { calculateReadingMinutes } from "../utils/reading-time"
 
 const WORD_SPLIT_REGEX = /\s+/
 
-type BacklinkEntry = {
-	relPath: string
-	fileName: string
-}
-
 type RelatedNoteEntry = {
 	relPath: string
 	fileName: string
+	// Cosine similarity to the open note, and the score the list is ordered
+	// by; lower than similarity when the vault decays older notes.
+	similarity: number
+	score: number
 }
 
 const RELATED_NOTES_LIMIT = 5
@@ -172,11 +172,18 @@ export function InfoButton() {
 										type="button"
 										key={entry.relPath}
 										onClick={() => handleNoteClick(entry.relPath)}
-										className="group flex w-full items-center justify-between gap-2 px-2 py-1.5 text-xs rounded text-muted-foreground hover:bg-muted hover:text-foreground transition-all text-left"
+										className="group flex w-full flex-col gap-0.5 px-2 py-1.5 text-xs rounded text-muted-foreground hover:bg-muted hover:text-foreground transition-all text-left"
 										title={entry.relPath}
 									>
-										<span className="truncate">{entry.fileName}</span>
-										<ArrowRight className="size-3.5 shrink-0 opacity-0 group-hover:opacity-100 transition-opacity" />
+										<span className="flex w-full items-center justify-between gap-2">
+											<span className="truncate">{entry.fileName}</span>
+											<ArrowRight className="size-3.5 shrink-0 opacity-0 group-hover:opacity-100 transition-opacity" />
+										</span>
+										{entry.mentions[0] && (
+											<span className="line-clamp-2 whitespace-pre-line text-[11px] opacity-70">
+												{entry.mentions[0].context.trim()}
+											</span>
+										)}
 									</button>
 								))}
 							</div>
@@ -195,7 +202,7 @@ export function InfoButton() {
 									<div
 										key={entry.relPath}
 										className="group flex items-center justify-between"
-										title={entry.relPath}
+										title={formatRelatedNoteTitle(entry)}
 									>
 										<button
 											type="button"
@@ -214,3 +221,11 @@ export function InfoButton() {
 		</Popover>
 	)
 }
+
+function formatRelatedNoteTitle(entry: RelatedNoteEntry) {
+	const similarity = `Similarity ${entry.similarity.toFixed(2)}`
+	if (entry.score === entry.similarity) {
+		return `${entry.relPath}\n${similarity}`
+	}
+	return `${entry.relPath}\n${similarity}, ${entry.score.toFixed(2)} after recency`
+}
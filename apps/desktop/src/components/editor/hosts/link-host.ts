@@ -15,6 +15,10 @@ import { useStore } from "@/store"
 type RelatedNoteEntry = {
 	relPath: string
 	fileName: string
+	// Cosine similarity to the open note, and the score the list is ordered
+	// by; lower than similarity when the vault decays older notes.
+	similarity: number
+	score: number
 }
 
 type DesktopLinkRuntimeDeps = {
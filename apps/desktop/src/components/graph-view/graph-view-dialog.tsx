@@ -5,10 +5,15 @@ import {
 } from "@mdit/graph-view"
 import { Button } from "@mdit/ui/components/button"
 import { Dialog, DialogContent, DialogTitle } from "@mdit/ui/components/dialog"
-import { invoke } from "@tauri-apps/api/core"
 import { relative, resolve } from "pathe"
 import { useCallback, useEffect, useRef, useState } from "react"
 import { useShallow } from "zustand/shallow"
+import {
+	applyGraphDelta,
+	getGraphDelta,
+	onGraphDelta,
+	unsubscribeGraphDeltas,
+} from "@/lib/graph-updates"
 import { useStore } from "@/store"
 
 const EMPTY_GRAPH_DATA: GraphViewData = {
@@ -41,9 +46,12 @@ export function GraphViewDialog() {
 	const [isLoading, setIsLoading] = useState(false)
 	const [errorMessage, setErrorMessage] = useState<string | null>(null)
 	const requestIdRef = useRef(0)
+	// Graph version the rendered data matches; null until the first load.
+	const versionRef = useRef<number | null>(null)
 
 	const fetchGraphData = useCallback(async () => {
 		if (!workspacePath) {
+			versionRef.current = null
 			setData(EMPTY_GRAPH_DATA)
 			setErrorMessage(null)
 			return
@@ -51,25 +59,25 @@ export function GraphViewDialog() {
 
 		const requestId = requestIdRef.current + 1
 		requestIdRef.current = requestId
-		setIsLoading(true)
+		const sinceVersion = versionRef.current ?? undefined
+		if (sinceVersion === undefined) {
+			setIsLoading(true)
+		}
 		setErrorMessage(null)
 
 		try {
-			const result = await invoke<GraphViewData>(
-				"get_graph_view_data_command",
-				{
-					workspacePath,
-				},
-			)
+			const delta = await getGraphDelta(workspacePath, sinceVersion)
 			if (requestIdRef.current !== requestId) {
 				return
 			}
-			setData(result)
+			versionRef.current = delta.version
+			setData((current) => applyGraphDelta(current, delta))
 		} catch (error) {
 			if (requestIdRef.current !== requestId) {
 				return
 			}
 
+			versionRef.current = null
 			setData(EMPTY_GRAPH_DATA)
 			setErrorMessage(
 				error instanceof Error
@@ -88,8 +96,30 @@ export function GraphViewDialog() {
 			return
 		}
 
+		versionRef.current = null
+		setData(EMPTY_GRAPH_DATA)
 		fetchGraphData()
-	}, [fetchGraphData, isGraphViewDialogOpen])
+
+		// Pushed deltas apply only on top of the version they were built from;
+		// on a gap, catch up from the version we have.
+		const unlistenPromise = onGraphDelta(({ workspacePath: path, delta }) => {
+			if (path !== workspacePath) {
+				return
+			}
+			if (delta.baseVersion !== versionRef.current) {
+				fetchGraphData()
+				return
+			}
+			versionRef.current = delta.version
+			setData((current) => applyGraphDelta(current, delta))
+		})
+
+		return () => {
+			requestIdRef.current += 1
+			void unlistenPromise.then((unlisten) => unlisten())
+			void unsubscribeGraphDeltas()
+		}
+	}, [fetchGraphData, isGraphViewDialogOpen, workspacePath])
 
 	const activeRelPath = (() => {
 		if (!workspacePath || !tabPath) {
@@ -2,6 +2,8 @@ import {
 	APP_HOTKEY_DEFINITIONS,
 	type AppHotkeyActionId,
 	FIXED_TAB_SHORTCUT_DIGITS,
+	type GlobalAppHotkeyActionId,
+	isGlobalAppHotkeyActionId,
 } from "@mdit/store/hotkeys"
 import { useHotkey } from "@tanstack/react-hotkeys"
 import { useCallback, useMemo } from "react"
@@ -113,7 +115,9 @@ export function Hotkeys() {
 		[activateTabById],
 	)
 
-	const actionHandlers = useMemo<Record<AppHotkeyActionId, () => void>>(
+	const actionHandlers = useMemo<
+		Record<Exclude<AppHotkeyActionId, GlobalAppHotkeyActionId>, () => void>
+	>(
 		() => ({
 			"create-note": () => {
 				void createAndOpenNote()
@@ -200,7 +204,8 @@ export function Hotkeys() {
 		<>
 			{APP_HOTKEY_DEFINITIONS.map((definition) => {
 				const binding = hotkeys[definition.id]
-				if (!binding) {
+				// Global shortcuts are registered with the OS by the system tray.
+				if (!binding || isGlobalAppHotkeyActionId(definition.id)) {
 					return null
 				}
 				return (
@@ -0,0 +1,102 @@
+import { Textarea } from "@mdit/ui/components/textarea"
+import { getCurrentWindow } from "@tauri-apps/api/window"
+import { useCallback, useEffect, useRef, useState } from "react"
+import {
+	captureNote,
+	hideQuickCaptureWindow,
+	type QuickCaptureTarget,
+} from "@/lib/quick-capture"
+
+const TARGET_LABELS: Record<QuickCaptureTarget, string> = {
+	inbox: "Inbox",
+	dailyNote: "Daily note",
+}
+
+export function QuickCapture() {
+	const [text, setText] = useState("")
+	const [target, setTarget] = useState<QuickCaptureTarget>("inbox")
+	const [error, setError] = useState<string | null>(null)
+	const [isSaving, setIsSaving] = useState(false)
+	const textareaRef = useRef<HTMLTextAreaElement>(null)
+
+	useEffect(() => {
+		// The window is reused, so focus the input every time it is shown.
+		const unlistenPromise = getCurrentWindow().onFocusChanged(
+			({ payload: focused }) => {
+				if (focused) {
+					textareaRef.current?.focus()
+				}
+			},
+		)
+
+		return () => {
+			void unlistenPromise.then((unlisten) => unlisten())
+		}
+	}, [])
+
+	const handleSubmit = useCallback(async () => {
+		if (!text.trim() || isSaving) {
+			return
+		}
+
+		setIsSaving(true)
+		try {
+			await captureNote(text, target)
+			setText("")
+			setError(null)
+		} catch (captureError) {
+			setError(String(captureError))
+		} finally {
+			setIsSaving(false)
+		}
+	}, [isSaving, target, text])
+
+	return (
+		<div
+			className="h-screen flex flex-col gap-2 p-3 bg-background"
+			data-tauri-drag-region=""
+		>
+			<Textarea
+				ref={textareaRef}
+				autoFocus
+				value={text}
+				placeholder="Capture a thought…"
+				className="flex-1 resize-none"
+				disabled={isSaving}
+				onChange={(event) => setText(event.target.value)}
+				onKeyDown={(event) => {
+					if (event.key === "Escape") {
+						event.preventDefault()
+						void hideQuickCaptureWindow()
+					} else if (event.key === "Enter" && !event.shiftKey) {
+						event.preventDefault()
+						void handleSubmit()
+					}
+				}}
+			/>
+			<div className="flex items-center justify-between text-xs text-muted-foreground">
+				<div className="flex gap-1">
+					{(Object.keys(TARGET_LABELS) as QuickCaptureTarget[]).map(
+						(option) => (
+							<button
+								key={option}
+								type="button"
+								className={
+									option === target
+										? "rounded px-2 py-0.5 bg-muted text-foreground"
+										: "rounded px-2 py-0.5"
+								}
+								onClick={() => setTarget(option)}
+							>
+								{TARGET_LABELS[option]}
+							</button>
+						),
+					)}
+				</div>
+				<span className={error ? "text-destructive truncate" : undefined}>
+					{error ?? "Enter to save · Shift+Enter for a new line"}
+				</span>
+			</div>
+		</div>
+	)
+}
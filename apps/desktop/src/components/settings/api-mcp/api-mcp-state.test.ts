@@ -13,4 +13,10 @@ describe("api-mcp toggle state", () => {
 	it("preserves the user toggle value", () => {
 		expect(getLocalApiToggleState(false).checked).toBe(false)
 	})
+
+	it("shows the port of the running profile", () => {
+		expect(getLocalApiToggleState(true, 39500).description).toBe(
+			"Base URL: http://127.0.0.1:39500",
+		)
+	})
 })
@@ -1,5 +1,10 @@
-export const getLocalApiToggleState = (localApiEnabled: boolean) => ({
-	description: "Base URL: http://127.0.0.1:39123",
+export const DEFAULT_LOCAL_API_PORT = 39123
+
+export const getLocalApiToggleState = (
+	localApiEnabled: boolean,
+	port: number = DEFAULT_LOCAL_API_PORT,
+) => ({
+	description: `Base URL: http://127.0.0.1:${port}`,
 	disabled: false,
 	checked: localApiEnabled,
 })
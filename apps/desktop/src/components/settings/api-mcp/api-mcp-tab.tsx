@@ -14,13 +14,13 @@ import { Check, Copy } from "lucide-react"
 import { useEffect, useState } from "react"
 import { toast } from "sonner"
 import { useShallow } from "zustand/shallow"
-import { setLocalApiAuthToken } from "@/lib/local-api"
+import { getLocalApiPort, setLocalApiAuthToken } from "@/lib/local-api"
 import {
 	ensureLocalApiAuthToken,
 	rotateLocalApiAuthToken,
 } from "@/lib/local-api-auth"
 import { useStore } from "@/store"
-import { getLocalApiToggleState } from "./api-mcp-state"
+import { DEFAULT_LOCAL_API_PORT, getLocalApiToggleState } from "./api-mcp-state"
 
 const REST_APIS = [
 	{
@@ -38,11 +38,21 @@ const REST_APIS = [
 		path: "/api/v1/vaults/{vault_id}/notes",
 		description: "Create markdown note",
 	},
+	{
+		method: "POST",
+		path: "/api/v1/vaults/{vault_id}/clip",
+		description: "Clip a web page into a note",
+	},
 	{
 		method: "POST",
 		path: "/api/v1/vaults/{vault_id}/search",
 		description: "Search notes",
 	},
+	{
+		method: "GET",
+		path: "/metrics",
+		description: "Prometheus metrics",
+	},
 ] as const
 
 const MCP_TOOLS = [
@@ -65,17 +75,17 @@ const CLIENT_GUIDES = [
 		name: "Claude Code",
 		description: "Add an MCP server using the Claude Code CLI.",
 		snippet:
-			'claude mcp add --transport http mdit "http://127.0.0.1:39123/mcp?token=<TOKEN>"',
+			'claude mcp add --transport http mdit "http://127.0.0.1:<PORT>/mcp?token=<TOKEN>"',
 	},
 	{
 		name: "Codex",
 		description: "Register the MCP server with Codex CLI (or use config.toml).",
 		snippet: `# CLI
-codex mcp add mdit --url "http://127.0.0.1:39123/mcp?token=<TOKEN>"
+codex mcp add mdit --url "http://127.0.0.1:<PORT>/mcp?token=<TOKEN>"
 
 # ~/.codex/config.toml
 [mcp_servers.mdit]
-url = "http://127.0.0.1:39123/mcp?token=<TOKEN>"`,
+url = "http://127.0.0.1:<PORT>/mcp?token=<TOKEN>"`,
 	},
 	{
 		name: "Cursor",
@@ -83,7 +93,7 @@ url = "http://127.0.0.1:39123/mcp?token=<TOKEN>"`,
 		snippet: `{
   "mcpServers": {
     "mdit": {
-      "url": "http://127.0.0.1:39123/mcp?token=<TOKEN>"
+      "url": "http://127.0.0.1:<PORT>/mcp?token=<TOKEN>"
     }
   }
 }`,
@@ -96,16 +106,40 @@ export function ApiMcpTab() {
 		setLocalApiEnabled,
 		localApiError,
 		setLocalApiError,
+		localApiPort,
 	} = useStore(
 		useShallow((state) => ({
 			localApiEnabled: state.localApiEnabled,
 			setLocalApiEnabled: state.setLocalApiEnabled,
 			localApiError: state.localApiError,
 			setLocalApiError: state.setLocalApiError,
+			localApiPort: state.localApiPort,
 		})),
 	)
 	const [token, setToken] = useState("")
 	const [tokenCopied, setTokenCopied] = useState(false)
+	// Each app profile serves the API on its own port, and the server moves to
+	// the next free one when another program already holds it. The bound port
+	// comes from the store once the server is up; until then the profile's
+	// remembered port is shown.
+	const [savedPort, setSavedPort] = useState(DEFAULT_LOCAL_API_PORT)
+	const port = localApiPort ?? savedPort
+
+	useEffect(() => {
+		let isActive = true
+		getLocalApiPort()
+			.then((profilePort) => {
+				if (isActive) {
+					setSavedPort(profilePort)
+				}
+			})
+			.catch((error) => {
+				console.error("Failed to load local API port:", error)
+			})
+		return () => {
+			isActive = false
+		}
+	}, [])
 
 	useEffect(() => {
 		let isActive = true
@@ -171,7 +205,11 @@ export function ApiMcpTab() {
 		}
 	}
 
-	const toggleState = getLocalApiToggleState(localApiEnabled)
+	const toggleState = getLocalApiToggleState(localApiEnabled, port)
+	const fillSnippet = (snippet: string) => {
+		const withPort = snippet.replace(/<PORT>/g, String(port))
+		return token ? withPort.replace(/<TOKEN>/g, token) : withPort
+	}
 
 	return (
 		<div className="flex-1 overflow-y-auto px-12 pt-12 pb-24 select-text **:select-text [&_button]:select-none **:[[role=switch]]:select-none">
@@ -302,9 +340,7 @@ export function ApiMcpTab() {
 							</FieldContent>
 							<div className="relative group/snippet">
 								<pre className="rounded-md border bg-muted px-3 py-2 text-xs whitespace-pre-wrap pr-10">
-									{token
-										? client.snippet.replace(/<TOKEN>/g, token)
-										: client.snippet}
+									{fillSnippet(client.snippet)}
 								</pre>
 								<Button
 									variant="ghost"
@@ -312,9 +348,7 @@ export function ApiMcpTab() {
 									className="absolute right-2 top-2 text-muted-foreground opacity-0 transition-opacity hover:text-foreground group-hover/snippet:opacity-100"
 									onClick={() =>
 										copyToClipboard(
-											token
-												? client.snippet.replace(/<TOKEN>/g, token)
-												: client.snippet,
+											fillSnippet(client.snippet),
 											"Snippet copied",
 										)
 									}
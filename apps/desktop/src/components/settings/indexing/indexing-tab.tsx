@@ -15,13 +15,21 @@ import {
 	SelectTrigger,
 	SelectValue,
 } from "@mdit/ui/components/select"
+import { Switch } from "@mdit/ui/components/switch"
 import { Loader2Icon, RefreshCcwIcon } from "lucide-react"
-import { useMemo } from "react"
+import { useEffect, useMemo, useState } from "react"
 import { useShallow } from "zustand/shallow"
 import type { WorkspaceEntry } from "@/store"
+import { breakVaultLock } from "@/lib/indexing"
+import { getVaultSetting, setVaultSetting } from "@/lib/vault-settings"
 import { calculateIndexingProgress, useStore } from "@/store"
+import { isMac } from "@/utils/platform"
 import { EmbeddingModelChangeDialog } from "./embedding-model-change-dialog"
 import { INDEXING_MODEL_CONTROL_STATE } from "./indexing-ui-state"
+import {
+	describeEmbeddingProviderCheck,
+	useEmbeddingProviderCheck,
+} from "./use-embedding-provider-check"
 import { useIndexingMetaPolling } from "./use-indexing-meta-polling"
 import { useIndexingModelChange } from "./use-indexing-model-change"
 
@@ -35,6 +43,10 @@ export function IndexingTab() {
 		config,
 		indexedDocCount,
 		isMetaLoading,
+		vectorSearchError,
+		indexLock,
+		skippedFiles,
+		retrySkippedFiles,
 	} = useStore(
 		useShallow((state) => ({
 			workspacePath: state.workspacePath,
@@ -46,6 +58,10 @@ export function IndexingTab() {
 			config: state.config,
 			indexedDocCount: state.indexedDocCount,
 			isMetaLoading: state.isMetaLoading,
+			vectorSearchError: state.vectorSearchError,
+			indexLock: state.indexLock,
+			skippedFiles: state.skippedFiles,
+			retrySkippedFiles: state.retrySkippedFiles,
 		})),
 	)
 
@@ -60,6 +76,8 @@ export function IndexingTab() {
 
 	const embeddingProvider = currentConfig?.embeddingProvider ?? ""
 	const embeddingModel = currentConfig?.embeddingModel ?? ""
+	const { check: providerCheck, isChecking: isCheckingProvider } =
+		useEmbeddingProviderCheck(embeddingProvider, embeddingModel)
 
 	const totalFiles = useMemo(() => countMarkdownFiles(entries), [entries])
 
@@ -96,8 +114,66 @@ export function IndexingTab() {
 		}
 	}
 
+	const retryFailedFiles = async () => {
+		if (!workspacePath) {
+			return
+		}
+
+		try {
+			await retrySkippedFiles(workspacePath)
+			await useStore.getState().loadIndexingMeta(workspacePath)
+		} catch (error) {
+			console.error("Failed to retry skipped files:", error)
+		}
+	}
+
+	const retryableSkippedCount = skippedFiles.filter(
+		(file) => file.retryable,
+	).length
+
+	const removeIndexLock = async () => {
+		if (!workspacePath) {
+			return
+		}
+
+		try {
+			await breakVaultLock(workspacePath)
+			await useStore.getState().loadIndexingMeta(workspacePath)
+		} catch (error) {
+			console.error("Failed to remove index lock:", error)
+		}
+	}
+
 	const progressLabel = `${indexedDocCount}/${totalFiles || 0} files indexed`
 
+	const [spotlightMetadata, setSpotlightMetadata] = useState(false)
+
+	useEffect(() => {
+		if (!workspacePath || !isMac()) {
+			return
+		}
+
+		getVaultSetting(workspacePath, "spotlightMetadata")
+			.then((setting) => setSpotlightMetadata(setting.value))
+			.catch((error) => {
+				console.error("Failed to load Spotlight setting:", error)
+			})
+	}, [workspacePath])
+
+	const handleSpotlightMetadataChange = (enabled: boolean) => {
+		if (!workspacePath) {
+			return
+		}
+
+		setSpotlightMetadata(enabled)
+		setVaultSetting(workspacePath, "spotlightMetadata", enabled).catch(
+			(error) => {
+				console.error("Failed to save Spotlight setting:", error)
+				setSpotlightMetadata(!enabled)
+			},
+		)
+	}
+
 	if (!workspacePath) {
 		return null
 	}
@@ -122,6 +198,25 @@ export function IndexingTab() {
 								<FieldDescription>
 									{modelControlState.description}
 								</FieldDescription>
+								{isCheckingProvider ? (
+									<FieldDescription>Checking model...</FieldDescription>
+								) : (
+									providerCheck && (
+										<FieldDescription
+											className={
+												providerCheck.reachable ? undefined : "text-destructive"
+											}
+										>
+											{describeEmbeddingProviderCheck(providerCheck)}
+										</FieldDescription>
+									)
+								)}
+								{vectorSearchError && (
+									<FieldDescription className="text-destructive">
+										Vector search is unavailable, so search uses keywords only:{" "}
+										{vectorSearchError}
+									</FieldDescription>
+								)}
 							</FieldContent>
 							<Select
 								value={selectedEmbeddingModel ?? undefined}
@@ -175,6 +270,21 @@ export function IndexingTab() {
 									Progress is estimated using the visible workspace files;
 									actual indexed content may differ slightly.
 								</p>
+								{skippedFiles.length > 0 && (
+									<p className="mt-2 text-xs text-destructive">
+										{skippedFiles.length} files could not be indexed
+										{retryableSkippedCount > 0 &&
+											`; ${retryableSkippedCount} may index on another try`}
+										.
+									</p>
+								)}
+								{indexLock && (
+									<p className="mt-2 text-xs text-destructive">
+										{indexLock.owner} on {indexLock.host} is writing this
+										vault's index, so indexing from here fails until it
+										finishes. Remove the lock only if it is no longer running.
+									</p>
+								)}
 							</div>
 							<div className="flex flex-wrap items-center justify-end gap-2 mt-4">
 								<Button
@@ -195,8 +305,38 @@ export function IndexingTab() {
 									<RefreshCcwIcon className="size-4" />
 									Force Rebuild
 								</Button>
+								{retryableSkippedCount > 0 && (
+									<Button
+										onClick={retryFailedFiles}
+										variant="outline"
+										disabled={isIndexing}
+									>
+										Retry Failed Files
+									</Button>
+								)}
+								{indexLock && (
+									<Button onClick={removeIndexLock} variant="outline">
+										Remove Lock
+									</Button>
+								)}
 							</div>
 						</Field>
+
+						{isMac() && (
+							<Field orientation="horizontal">
+								<FieldContent>
+									<FieldLabel>Show Notes in Spotlight</FieldLabel>
+									<FieldDescription>
+										Add note titles, tags and previews to macOS search. Turning
+										this off removes them again.
+									</FieldDescription>
+								</FieldContent>
+								<Switch
+									checked={spotlightMetadata}
+									onCheckedChange={handleSpotlightMetadataChange}
+								/>
+							</Field>
+						)}
 					</FieldGroup>
 				</FieldSet>
 			</div>
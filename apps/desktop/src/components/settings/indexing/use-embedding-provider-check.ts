@@ -0,0 +1,54 @@
+import { useEffect, useState } from "react"
+import {
+	checkEmbeddingProvider,
+	type EmbeddingProviderCheck,
+} from "@/lib/embedding-providers"
+
+export function useEmbeddingProviderCheck(provider: string, model: string) {
+	const [check, setCheck] = useState<EmbeddingProviderCheck | null>(null)
+	const [isChecking, setIsChecking] = useState(false)
+
+	useEffect(() => {
+		if (!provider || !model) {
+			setCheck(null)
+			return
+		}
+
+		let cancelled = false
+		setIsChecking(true)
+		checkEmbeddingProvider(provider, model)
+			.then((result) => {
+				if (!cancelled) {
+					setCheck(result)
+				}
+			})
+			.catch((error) => {
+				console.error("Failed to check embedding provider:", error)
+			})
+			.finally(() => {
+				if (!cancelled) {
+					setIsChecking(false)
+				}
+			})
+
+		return () => {
+			cancelled = true
+		}
+	}, [provider, model])
+
+	return { check, isChecking }
+}
+
+export const describeEmbeddingProviderCheck = (
+	check: EmbeddingProviderCheck,
+): string => {
+	if (!check.reachable) {
+		return check.error ?? "Provider is not reachable"
+	}
+
+	const details = [
+		check.dimension !== null ? `${check.dimension} dimensions` : null,
+		check.latencyMs !== null ? `${check.latencyMs} ms` : null,
+	].filter(Boolean)
+	return ["Ready", ...details].join(" · ")
+}
@@ -16,9 +16,14 @@ import {
 } from "@mdit/ui/components/select"
 import { Switch } from "@mdit/ui/components/switch"
 import { Monitor, Moon, Sun } from "lucide-react"
+import { useEffect, useState } from "react"
 import { useShallow } from "zustand/shallow"
 import { HotkeyKbd } from "@/components/hotkeys/hotkey-kbd"
 import { useTheme } from "@/contexts/theme-context"
+import {
+	getBackgroundModeSettings,
+	setStartInBackground,
+} from "@/lib/background-mode"
 import { useStore } from "@/store"
 
 export function PreferencesTab() {
@@ -35,6 +40,26 @@ export function PreferencesTab() {
 		})),
 	)
 
+	const [startInBackground, setStartInBackgroundState] = useState(false)
+
+	useEffect(() => {
+		getBackgroundModeSettings()
+			.then((settings) =>
+				setStartInBackgroundState(settings.startInBackground),
+			)
+			.catch((error) => {
+				console.error("Failed to load background mode settings:", error)
+			})
+	}, [])
+
+	const handleStartInBackgroundChange = (enabled: boolean) => {
+		setStartInBackgroundState(enabled)
+		setStartInBackground(enabled).catch((error) => {
+			console.error("Failed to save background mode setting:", error)
+			setStartInBackgroundState(!enabled)
+		})
+	}
+
 	const themeOptions: Array<{
 		value: "light" | "dark" | "system"
 		label: string
@@ -77,6 +102,19 @@ export function PreferencesTab() {
 							</SelectContent>
 						</Select>
 					</Field>
+					<Field orientation="horizontal">
+						<FieldContent>
+							<FieldLabel>Start in Background</FieldLabel>
+							<FieldDescription>
+								Launch without a window and keep the local API and vault
+								indexing running. Open Mdit from the tray when you need it.
+							</FieldDescription>
+						</FieldContent>
+						<Switch
+							checked={startInBackground}
+							onCheckedChange={handleStartInBackgroundChange}
+						/>
+					</Field>
 				</FieldGroup>
 			</FieldSet>
 
@@ -1,3 +1,4 @@
+import { hotkeyToMenuAccelerator } from "@mdit/store/hotkeys"
 import { LogicalPosition } from "@tauri-apps/api/dpi"
 import { Image } from "@tauri-apps/api/image"
 import { Menu, PredefinedMenuItem } from "@tauri-apps/api/menu"
@@ -7,6 +8,8 @@ import { WebviewWindow } from "@tauri-apps/api/webviewWindow"
 import { register, unregister } from "@tauri-apps/plugin-global-shortcut"
 import { join } from "pathe"
 import { useEffect } from "react"
+import { toggleQuickCaptureWindow } from "@/lib/quick-capture"
+import { useStore } from "@/store"
 
 const createQuickNoteWindow = () => {
 	// Generate a unique window label to allow multiple quick-note windows.
@@ -48,6 +51,13 @@ async function createSystemTray() {
 					},
 					accelerator: "CmdOrCtrl+Alt+N",
 				},
+				{
+					id: "Quick Capture",
+					text: "Quick Capture",
+					action: () => {
+						void toggleQuickCaptureWindow()
+					},
+				},
 				await PredefinedMenuItem.new({
 					item: "Separator",
 				}),
@@ -82,5 +92,27 @@ export function SystemTray() {
 			cleanup().catch(() => {})
 		}
 	}, [])
+
+	const quickCaptureHotkey = useStore((s) => s.hotkeys["quick-capture"])
+
+	useEffect(() => {
+		const shortcut = hotkeyToMenuAccelerator(quickCaptureHotkey)
+		if (!shortcut) {
+			return
+		}
+
+		const registration = register(shortcut, (event) => {
+			if (event.state === "Released") {
+				void toggleQuickCaptureWindow()
+			}
+		}).catch((error) => {
+			console.error("Failed to register quick capture shortcut:", error)
+		})
+
+		return () => {
+			void registration.then(() => unregister(shortcut).catch(() => {}))
+		}
+	}, [quickCaptureHotkey])
+
 	return null
 }
@@ -0,0 +1,70 @@
+import { useEffect } from "react"
+import { useShallow } from "zustand/shallow"
+import { onDeepLink, takePendingDeepLinks } from "@/lib/deep-links"
+import { useStore } from "@/store"
+
+const PREVIEWABLE_IMAGE_PATTERN = /\.(png|jpe?g|gif|webp|avif|svg|bmp)$/i
+
+export function useDeepLinks() {
+	const {
+		isLoading,
+		loadWorkspace,
+		openTab,
+		openCommandMenuWithQuery,
+		openImagePreview,
+		setSelectedEntryPaths,
+	} = useStore(
+		useShallow((state) => ({
+			isLoading: state.isLoading,
+			loadWorkspace: state.loadWorkspace,
+			openTab: state.openTab,
+			openCommandMenuWithQuery: state.openCommandMenuWithQuery,
+			openImagePreview: state.openImagePreview,
+			setSelectedEntryPaths: state.setSelectedEntryPaths,
+		})),
+	)
+
+	useEffect(() => {
+		// Wait for the bootstrap so a link does not race the initial vault load.
+		if (isLoading) {
+			return
+		}
+
+		const applyPendingDeepLinks = async () => {
+			for (const action of await takePendingDeepLinks()) {
+				if (useStore.getState().workspacePath !== action.workspacePath) {
+					await loadWorkspace(action.workspacePath)
+				}
+				if (action.type === "openFile") {
+					await openTab(action.filePath)
+				} else if (action.type === "search") {
+					openCommandMenuWithQuery(action.query)
+				} else if (PREVIEWABLE_IMAGE_PATTERN.test(action.filePath)) {
+					openImagePreview(action.filePath)
+				} else {
+					setSelectedEntryPaths(new Set([action.filePath]))
+				}
+			}
+		}
+
+		const handleDeepLink = () => {
+			applyPendingDeepLinks().catch((error) => {
+				console.error("Failed to handle deep link:", error)
+			})
+		}
+
+		handleDeepLink()
+		const unlistenPromise = onDeepLink(handleDeepLink)
+
+		return () => {
+			void unlistenPromise.then((unlisten) => unlisten())
+		}
+	}, [
+		isLoading,
+		loadWorkspace,
+		openCommandMenuWithQuery,
+		openImagePreview,
+		openTab,
+		setSelectedEntryPaths,
+	])
+}
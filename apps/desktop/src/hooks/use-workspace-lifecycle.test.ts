@@ -20,6 +20,23 @@ describe("bootstrapWorkspaceLifecycle", () => {
 		})
 	})
 
+	it("prefers the vault assigned to the window", async () => {
+		const syncRecentWorkspacePaths = vi.fn().mockResolvedValue(["/ws"])
+		const getWindowVault = vi.fn().mockResolvedValue("/other")
+		const loadWorkspace = vi.fn().mockResolvedValue(undefined)
+
+		await bootstrapWorkspaceLifecycle({
+			syncRecentWorkspacePaths,
+			getWindowVault,
+			loadWorkspace,
+		})
+
+		expect(loadWorkspace).toHaveBeenCalledWith("/other", {
+			recentWorkspacePaths: ["/ws"],
+			restoreLastOpenedFiles: true,
+		})
+	})
+
 	it("loads null when there is no recent workspace", async () => {
 		const syncRecentWorkspacePaths = vi.fn().mockResolvedValue([])
 		const loadWorkspace = vi.fn().mockResolvedValue(undefined)
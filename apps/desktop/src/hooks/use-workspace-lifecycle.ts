@@ -1,9 +1,16 @@
 import { useEffect, useRef } from "react"
 import { useShallow } from "zustand/shallow"
+import {
+	getWindowVault,
+	onVaultWindowOpenFiles,
+	registerWindowVault,
+} from "@/lib/vault-windows"
 import { useStore } from "@/store"
 
 type WorkspaceLifecycleBootstrapPorts = {
 	syncRecentWorkspacePaths: () => Promise<string[]>
+	// Vault the window was opened or restored for, if any.
+	getWindowVault?: () => Promise<string | null>
 	loadWorkspace: (
 		workspacePath: string | null,
 		options?: {
@@ -14,14 +21,19 @@ type WorkspaceLifecycleBootstrapPorts = {
 }
 
 export const bootstrapWorkspaceLifecycle = async (
-	{ syncRecentWorkspacePaths, loadWorkspace }: WorkspaceLifecycleBootstrapPorts,
+	{
+		syncRecentWorkspacePaths,
+		getWindowVault,
+		loadWorkspace,
+	}: WorkspaceLifecycleBootstrapPorts,
 	shouldCancel?: () => boolean,
 ): Promise<void> => {
 	const recentWorkspacePaths = await syncRecentWorkspacePaths()
+	const windowVault = (await getWindowVault?.()) ?? null
 	if (shouldCancel?.()) {
 		return
 	}
-	await loadWorkspace(recentWorkspacePaths[0] ?? null, {
+	await loadWorkspace(windowVault ?? recentWorkspacePaths[0] ?? null, {
 		recentWorkspacePaths,
 		restoreLastOpenedFiles: true,
 	})
@@ -34,6 +46,7 @@ export function useWorkspaceLifecycle() {
 		loadWorkspace,
 		watchWorkspace,
 		unwatchWorkspace,
+		openTab,
 	} = useStore(
 		useShallow((state) => ({
 			workspacePath: state.workspacePath,
@@ -41,6 +54,7 @@ export function useWorkspaceLifecycle() {
 			loadWorkspace: state.loadWorkspace,
 			watchWorkspace: state.watchWorkspace,
 			unwatchWorkspace: state.unwatchWorkspace,
+			openTab: state.openTab,
 		})),
 	)
 	const hasBootstrappedRef = useRef(false)
@@ -55,6 +69,7 @@ export function useWorkspaceLifecycle() {
 		void bootstrapWorkspaceLifecycle(
 			{
 				syncRecentWorkspacePaths,
+				getWindowVault,
 				loadWorkspace,
 			},
 			() => isCancelled,
@@ -94,4 +109,24 @@ export function useWorkspaceLifecycle() {
 			unwatchWorkspace()
 		}
 	}, [unwatchWorkspace, watchWorkspace, workspacePath])
+
+	useEffect(() => {
+		// Clearing before bootstrap would drop the vault assigned to this window.
+		if (!hasBootstrappedRef.current && !workspacePath) {
+			return
+		}
+		registerWindowVault(workspacePath ?? null).catch(console.error)
+	}, [workspacePath])
+
+	useEffect(() => {
+		const unlistenPromise = onVaultWindowOpenFiles((filePaths) => {
+			for (const filePath of filePaths) {
+				void openTab(filePath)
+			}
+		})
+
+		return () => {
+			void unlistenPromise.then((unlisten) => unlisten())
+		}
+	}, [openTab])
 }
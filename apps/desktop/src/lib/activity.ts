@@ -0,0 +1,46 @@
+import { invoke } from "@tauri-apps/api/core"
+import type { CalendarRange } from "./calendar"
+
+export type ActivityDay = {
+	date: string
+	notesTouched: number
+	// Estimated from how much notes grew while indexing.
+	wordsAdded: number
+}
+
+export type ActivityHeatmap = {
+	days: ActivityDay[]
+	// A quiet last day of the range does not end the current streak.
+	currentStreak: number
+	longestStreak: number
+}
+
+export const getActivityHeatmap = (
+	workspacePath: string,
+	range: CalendarRange,
+): Promise<ActivityHeatmap> =>
+	invoke("get_activity_heatmap_command", { workspacePath, range })
+
+export type NoteWritingStats = {
+	relPath: string
+	wordsAdded: number
+	wordsRemoved: number
+	// Words in the note after its last revision in the range.
+	wordCount: number
+}
+
+export type WritingStats = {
+	wordsAdded: number
+	wordsRemoved: number
+	// Most words added first.
+	notes: NoteWritingStats[]
+}
+
+// Words written over the range, counted per indexed revision. Pass a
+// vault-relative folder for one project's numbers.
+export const getWritingStats = (
+	workspacePath: string,
+	range: CalendarRange,
+	folder?: string,
+): Promise<WritingStats> =>
+	invoke("get_writing_stats_command", { workspacePath, range, folder })
@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type MovedPath = {
+	from: string
+	to: string
+}
+
+export type ArchiveResult = {
+	note: MovedPath
+	// Attachments moved along with the note.
+	attachments: MovedPath[]
+}
+
+// Moves the note into the vault's archiveFolder setting and marks it
+// `archived: true`. With includeAttachments, files only this note embeds
+// move with it.
+export const archiveNote = (
+	workspacePath: string,
+	notePath: string,
+	includeAttachments = false,
+): Promise<ArchiveResult> =>
+	invoke("archive_note_command", {
+		workspacePath,
+		notePath,
+		includeAttachments,
+	})
+
+export const unarchiveNote = (
+	workspacePath: string,
+	notePath: string,
+): Promise<ArchiveResult> =>
+	invoke("unarchive_note_command", { workspacePath, notePath })
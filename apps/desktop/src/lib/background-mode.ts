@@ -0,0 +1,12 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type BackgroundModeSettings = {
+	startInBackground: boolean
+	runLocalApi: boolean
+}
+
+export const getBackgroundModeSettings = (): Promise<BackgroundModeSettings> =>
+	invoke("get_background_mode_settings_command")
+
+export const setStartInBackground = (enabled: boolean): Promise<void> =>
+	invoke("set_start_in_background_command", { enabled })
@@ -0,0 +1,28 @@
+import { invoke } from "@tauri-apps/api/core"
+
+// Inclusive range of YYYY-MM-DD days, at most 400 of them.
+export type CalendarRange = {
+	start: string
+	end: string
+}
+
+export type CalendarNote = {
+	relPath: string
+	property: "date" | "due"
+}
+
+export type CalendarDay = {
+	date: string
+	dailyNote: string | null
+	// Created and modified counts use the local day of the file times.
+	createdCount: number
+	modifiedCount: number
+	datedNotes: CalendarNote[]
+}
+
+// One entry per day of the range, read from the index instead of the files.
+export const getCalendarData = (
+	workspacePath: string,
+	range: CalendarRange,
+): Promise<CalendarDay[]> =>
+	invoke("get_calendar_data_command", { workspacePath, range })
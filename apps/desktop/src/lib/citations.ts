@@ -0,0 +1,40 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type CitationAuthor = {
+	// Family name, or the whole name of an organization.
+	family: string
+	given: string | null
+}
+
+export type CitationEntry = {
+	key: string
+	entryType: string
+	title: string | null
+	authors: CitationAuthor[]
+	year: string | null
+	container: string | null
+}
+
+// pandoc inserts [@key]; the others are plain text for notes without Pandoc.
+export type CitationStyle = "pandoc" | "authorYear" | "reference"
+
+// Searches the bibliographyPath vault setting's file; empty while it is unset.
+export const searchCitations = (
+	workspacePath: string,
+	query: string,
+	limit?: number,
+): Promise<CitationEntry[]> =>
+	invoke("search_citations_command", { workspacePath, query, limit })
+
+export const formatCitation = (
+	workspacePath: string,
+	key: string,
+	style: CitationStyle,
+): Promise<string> =>
+	invoke("format_citation_command", { workspacePath, key, style })
+
+export const getCitingNotes = (
+	workspacePath: string,
+	key: string,
+): Promise<string[]> =>
+	invoke("get_citing_notes_command", { workspacePath, key })
@@ -0,0 +1,14 @@
+import { invoke } from "@tauri-apps/api/core"
+import { readTextFile } from "@tauri-apps/plugin-fs"
+
+// Downloads the file first when it is an iCloud, Dropbox or OneDrive
+// placeholder. Resolves to whether a download was needed.
+export const materializeCloudFile = (path: string): Promise<boolean> =>
+	invoke("materialize_cloud_file_command", { path })
+
+// Reads a note the user opens, fetching it from the cloud if only a
+// placeholder is on disk.
+export const readNoteText = async (path: string): Promise<string> => {
+	await materializeCloudFile(path)
+	return readTextFile(path)
+}
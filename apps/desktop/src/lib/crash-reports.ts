@@ -0,0 +1,28 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type CrashReportingSettings = {
+	shareReports: boolean
+}
+
+export type CrashSummary = {
+	location: string
+	message: string
+	count: number
+	firstSeen: string
+	lastSeen: string
+}
+
+export const getCrashReportingSettings = (): Promise<CrashReportingSettings> =>
+	invoke("get_crash_reporting_settings_command")
+
+export const setCrashReportSharing = (enabled: boolean): Promise<void> =>
+	invoke("set_crash_report_sharing_command", { enabled })
+
+// Recorded crashes grouped by where they happened, most recent first.
+export const getCrashSummaries = (): Promise<CrashSummary[]> =>
+	invoke("get_crash_summaries_command")
+
+// Writes a report with vault and home paths redacted. Fails until the user
+// opts in to sharing reports.
+export const exportCrashReport = (outputPath: string): Promise<void> =>
+	invoke("export_crash_report_command", { outputPath })
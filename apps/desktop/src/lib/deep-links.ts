@@ -0,0 +1,21 @@
+import { invoke } from "@tauri-apps/api/core"
+import type { UnlistenFn } from "@tauri-apps/api/event"
+import { getCurrentWindow } from "@tauri-apps/api/window"
+
+export const DEEP_LINK_EVENT = "deep-link"
+
+export type DeepLinkAction =
+	| { type: "openFile"; workspacePath: string; filePath: string }
+	| { type: "search"; workspacePath: string; query: string }
+	// Images and PDFs opened from the OS rather than through a link.
+	| { type: "openAttachment"; workspacePath: string; filePath: string }
+
+// Actions are queued per window in Rust, so a window opened for a link can
+// pick them up once it has loaded.
+export const takePendingDeepLinks = (): Promise<DeepLinkAction[]> =>
+	invoke("take_pending_deep_links_command")
+
+export const onDeepLink = (listener: () => void): Promise<UnlistenFn> =>
+	getCurrentWindow().listen(DEEP_LINK_EVENT, () => {
+		listener()
+	})
@@ -0,0 +1,54 @@
+import { invoke } from "@tauri-apps/api/core"
+
+// An HTTP embedding API described by configuration. Vault embedding settings
+// refer to it by name, like the built-in "ollama" provider.
+export type EmbeddingProviderConfig = {
+	name: string
+	baseUrl: string
+	// Name of the environment variable holding the API key.
+	apiKeyRef: string | null
+	// JSON body with {{model}}, {{input}} and {{dimension}} placeholders.
+	requestTemplate: Record<string, unknown>
+	// Dot separated path to the vector in the response, e.g. data.0.embedding.
+	responsePath: string
+	dimension: number | null
+}
+
+export const listEmbeddingProviders = (): Promise<EmbeddingProviderConfig[]> =>
+	invoke("list_embedding_providers_command")
+
+export const saveEmbeddingProvider = (
+	config: EmbeddingProviderConfig,
+): Promise<EmbeddingProviderConfig> =>
+	invoke("save_embedding_provider_command", { config })
+
+export const deleteEmbeddingProvider = (name: string): Promise<boolean> =>
+	invoke("delete_embedding_provider_command", { name })
+
+export type EmbeddingProviderCheck = {
+	provider: string
+	model: string
+	reachable: boolean
+	latencyMs: number | null
+	models: string[]
+	dimension: number | null
+	error: string | null
+}
+
+// Embeds a test string and lists models without touching the index.
+export const checkEmbeddingProvider = (
+	provider: string,
+	model: string,
+): Promise<EmbeddingProviderCheck> =>
+	invoke("check_embedding_provider_command", { provider, model })
+
+// Vectors are cached by chunk text so unchanged chunks are not re-embedded
+// after a reindex. The limit counts vectors; 0 turns the cache off.
+export const getEmbeddingCacheLimit = (): Promise<number> =>
+	invoke("get_embedding_cache_limit_command")
+
+export const setEmbeddingCacheLimit = (maxEntries: number): Promise<void> =>
+	invoke("set_embedding_cache_limit_command", { maxEntries })
+
+export const clearEmbeddingCache = (): Promise<number> =>
+	invoke("clear_embedding_cache_command")
@@ -0,0 +1,60 @@
+import { invoke } from "@tauri-apps/api/core"
+import { listen, type UnlistenFn } from "@tauri-apps/api/event"
+
+export const FEEDS_REFRESHED_EVENT = "feeds-refreshed"
+
+export type FeedSubscription = {
+	id: number
+	url: string
+	title: string
+	createdAt: string
+	lastFetchedAt: string | null
+	// Why the last fetch failed; null once a fetch succeeds.
+	lastError: string | null
+}
+
+export type FeedRefresh = {
+	feedId: number
+	title: string
+	// Absolute paths of the notes written for new items.
+	created: string[]
+	error: string | null
+}
+
+export type FeedsRefreshedPayload = {
+	workspacePath: string
+	refreshes: FeedRefresh[]
+}
+
+export const listFeeds = (workspacePath: string): Promise<FeedSubscription[]> =>
+	invoke("list_feeds_command", { workspacePath })
+
+// Fetches url to check it is an RSS or Atom feed, then subscribes to it.
+export const subscribeFeed = (
+	workspacePath: string,
+	url: string,
+): Promise<FeedSubscription> =>
+	invoke("subscribe_feed_command", { workspacePath, url })
+
+// Refreshes one feed, or every feed of the vault when feedId is missing.
+// New items become notes under Feeds/<feed title>/.
+export const refreshFeeds = (
+	workspacePath: string,
+	feedId?: number,
+): Promise<FeedRefresh[]> =>
+	invoke("refresh_feeds_command", { workspacePath, feedId })
+
+// Notes already written from the feed stay in the vault.
+export const unsubscribeFeed = (
+	workspacePath: string,
+	feedId: number,
+): Promise<boolean> =>
+	invoke("unsubscribe_feed_command", { workspacePath, feedId })
+
+// Fires after a manual or hourly background refresh that wrote notes.
+export const onFeedsRefreshed = (
+	listener: (payload: FeedsRefreshedPayload) => void,
+): Promise<UnlistenFn> =>
+	listen<FeedsRefreshedPayload>(FEEDS_REFRESHED_EVENT, (event) => {
+		listener(event.payload)
+	})
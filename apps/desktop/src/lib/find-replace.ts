@@ -0,0 +1,88 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type FindOptions = {
+	regex?: boolean
+	caseSensitive?: boolean
+	wholeWord?: boolean
+	// Glob over vault-relative paths such as "projects/**/*.md", or a plain
+	// folder or note path.
+	pathFilter?: string
+	maxResults?: number
+}
+
+export type VaultMatch = {
+	relPath: string
+	// Byte offsets into the note file.
+	start: number
+	end: number
+	line: number
+	context: string
+	contextStart: number
+}
+
+export type VaultFindResult = {
+	matches: VaultMatch[]
+	truncated: boolean
+}
+
+export type NoteMatch = {
+	// Byte offsets into the note file.
+	start: number
+	end: number
+	line: number
+	// UTF-16 column within the line.
+	column: number
+}
+
+export type NoteSearchResult = {
+	matches: NoteMatch[]
+	truncated: boolean
+}
+
+export type VaultReplaceResult = {
+	journalId: number | null
+	files: { relPath: string; replacements: number }[]
+	skipped: string[]
+	// Notes with `locked: true` in their frontmatter are never rewritten.
+	locked: string[]
+}
+
+export type UndoReplaceResult = {
+	journalId: number | null
+	restored: string[]
+	skipped: string[]
+}
+
+export const findInVault = (
+	workspacePath: string,
+	pattern: string,
+	options?: FindOptions,
+): Promise<VaultFindResult> =>
+	invoke("find_in_vault_command", { workspacePath, pattern, options })
+
+// pathFilter is ignored when searching a single note.
+export const searchInNote = (
+	path: string,
+	query: string,
+	options?: FindOptions,
+): Promise<NoteSearchResult> =>
+	invoke("search_in_note_command", { path, query, options })
+
+// With options.regex, the replacement may use $1 or ${name} groups.
+export const replaceInVault = (
+	workspacePath: string,
+	pattern: string,
+	replacement: string,
+	options?: FindOptions,
+): Promise<VaultReplaceResult> =>
+	invoke("replace_in_vault_command", {
+		workspacePath,
+		pattern,
+		replacement,
+		options,
+	})
+
+export const undoLastReplace = (
+	workspacePath: string,
+): Promise<UndoReplaceResult> =>
+	invoke("undo_last_replace_command", { workspacePath })
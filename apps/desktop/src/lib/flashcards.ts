@@ -0,0 +1,52 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type FlashcardScope =
+	| { type: "note"; path: string }
+	// Every note under the folder, subfolders included.
+	| { type: "folder"; path: string }
+	// Every note with the tag or a tag nested under it.
+	| { type: "tag"; tag: string }
+	| { type: "vault" }
+
+export type FlashcardPatterns = {
+	// "Q:" lines answered by the "A:" line after them.
+	questionAnswer?: boolean
+	// List items tagged with it, as "front :: back" or with nested answers.
+	// An empty tag turns them off; defaults to "flashcard".
+	tag?: string
+	// Lines with cloze deletions such as {{c1::answer}}.
+	cloze?: boolean
+}
+
+export type FlashcardExportOptions = {
+	patterns?: FlashcardPatterns
+	deck?: string
+	// Export cards again even when an earlier export had them.
+	includeExported?: boolean
+}
+
+export type FlashcardExportReport = {
+	// Null when no card was new, in which case nothing is written.
+	outputPath: string | null
+	exported: number
+	basic: number
+	cloze: number
+	alreadyExported: number
+	notes: number
+}
+
+// Writes the flashcards in scope to an Anki import file (tab-separated) at
+// outputPath. Cards are remembered per note, so later exports only contain
+// new or changed ones.
+export const exportFlashcards = (
+	workspacePath: string,
+	scope: FlashcardScope,
+	outputPath: string,
+	options?: FlashcardExportOptions,
+): Promise<FlashcardExportReport> =>
+	invoke("export_flashcards_command", {
+		workspacePath,
+		scope,
+		outputPath,
+		options,
+	})
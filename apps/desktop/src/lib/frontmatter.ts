@@ -1,6 +1,11 @@
-import { readTextFile, writeTextFile } from "@tauri-apps/plugin-fs"
+import { invoke } from "@tauri-apps/api/core"
+import { readTextFile } from "@tauri-apps/plugin-fs"
 import { parse, stringify } from "yaml"
 
+// The backend write refuses to edit locked notes, except to unlock them.
+const writeTextFile = (path: string, contents: string) =>
+	invoke<void>("write_text_file_command", { path, contents })
+
 const FRONTMATTER_REGEX = /^---\s*\r?\n([\s\S]*?)\r?\n---(?:\r?\n|$)/
 
 type ParsedFrontmatter = {
@@ -0,0 +1,36 @@
+import { invoke } from "@tauri-apps/api/core"
+
+// A note placed by its lat/lng or location frontmatter.
+export type GeoNote = {
+	relPath: string
+	lat: number
+	lng: number
+	// Place name from a text location property.
+	name: string | null
+	// Only set by getNotesNear.
+	distanceKm: number | null
+}
+
+// west greater than east spans the antimeridian.
+export type GeoBounds = {
+	south: number
+	west: number
+	north: number
+	east: number
+}
+
+// Located notes within radiusKm of the point, nearest first.
+export const getNotesNear = (
+	workspacePath: string,
+	lat: number,
+	lng: number,
+	radiusKm: number,
+): Promise<GeoNote[]> =>
+	invoke("get_notes_near_command", { workspacePath, lat, lng, radiusKm })
+
+// Located notes inside the visible map area, at most 5000.
+export const getNotesInBounds = (
+	workspacePath: string,
+	bounds: GeoBounds,
+): Promise<GeoNote[]> =>
+	invoke("get_notes_in_bounds_command", { workspacePath, bounds })
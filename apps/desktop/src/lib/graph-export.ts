@@ -0,0 +1,31 @@
+import { invoke } from "@tauri-apps/api/core"
+
+// graphml for Gephi and yEd, dot for Graphviz, json as D3 reads it:
+// { nodes: [{ id, label, kind, path }], links: [{ source, target, kind }] }.
+export type GraphExportFormat = "graphml" | "dot" | "json"
+
+export type GraphExportOptions = {
+	// A node per tag, linked from every note carrying it. Off by default.
+	includeTags?: boolean
+	// Link targets no note matches. On by default.
+	includeUnresolved?: boolean
+}
+
+export type GraphExportReport = {
+	nodeCount: number
+	edgeCount: number
+}
+
+// Writes the vault's note graph to outputPath, which must be inside a vault.
+export const exportGraph = (
+	workspacePath: string,
+	outputPath: string,
+	format: GraphExportFormat,
+	options?: GraphExportOptions,
+): Promise<GraphExportReport> =>
+	invoke("export_graph_command", {
+		workspacePath,
+		outputPath,
+		format,
+		options,
+	})
@@ -0,0 +1,74 @@
+import type { GraphEdge, GraphNode, GraphViewData } from "@mdit/graph-view"
+import { invoke } from "@tauri-apps/api/core"
+import type { UnlistenFn } from "@tauri-apps/api/event"
+import { getCurrentWindow } from "@tauri-apps/api/window"
+
+export const GRAPH_DELTA_EVENT = "graph-delta"
+
+export type GraphEdgeKey = {
+	source: string
+	target: string
+}
+
+export type GraphDelta = {
+	baseVersion: number
+	version: number
+	// Drop the current graph before applying; the upserts hold all of it.
+	reset: boolean
+	upsertedNodes: GraphNode[]
+	removedNodes: string[]
+	upsertedEdges: GraphEdge[]
+	removedEdges: GraphEdgeKey[]
+}
+
+export type GraphDeltaPayload = {
+	workspacePath: string
+	delta: GraphDelta
+}
+
+// Without sinceVersion the whole graph comes back as a reset. Either way the
+// window is subscribed to graph-delta events for the workspace afterwards.
+export const getGraphDelta = (
+	workspacePath: string,
+	sinceVersion?: number,
+): Promise<GraphDelta> =>
+	invoke("get_graph_delta_command", { workspacePath, sinceVersion })
+
+export const unsubscribeGraphDeltas = (): Promise<void> =>
+	invoke("unsubscribe_graph_deltas_command")
+
+export const onGraphDelta = (
+	listener: (payload: GraphDeltaPayload) => void,
+): Promise<UnlistenFn> =>
+	getCurrentWindow().listen<GraphDeltaPayload>(GRAPH_DELTA_EVENT, (event) => {
+		listener(event.payload)
+	})
+
+const edgeKey = (edge: GraphEdgeKey) => `${edge.source}\u0000${edge.target}`
+
+export const applyGraphDelta = (
+	data: GraphViewData,
+	delta: GraphDelta,
+): GraphViewData => {
+	const nodes = new Map(
+		delta.reset ? [] : data.nodes.map((node) => [node.id, node]),
+	)
+	for (const id of delta.removedNodes) {
+		nodes.delete(id)
+	}
+	for (const node of delta.upsertedNodes) {
+		nodes.set(node.id, node)
+	}
+
+	const edges = new Map(
+		delta.reset ? [] : data.edges.map((edge) => [edgeKey(edge), edge]),
+	)
+	for (const key of delta.removedEdges) {
+		edges.delete(edgeKey(key))
+	}
+	for (const edge of delta.upsertedEdges) {
+		edges.set(edgeKey(edge), edge)
+	}
+
+	return { nodes: [...nodes.values()], edges: [...edges.values()] }
+}
@@ -2,10 +2,36 @@ import type {
 	IndexingConfig,
 	IndexingMeta,
 	IndexingPort,
+	NoteIndexStatus,
+	VaultLockInfo,
 	WorkspaceIndexSummary,
 } from "@mdit/store/core"
 import { invoke } from "@tauri-apps/api/core"
 
+// Removes a lock another process left on the vault's index. Resolves to the
+// holder it removed, if any.
+export const breakVaultLock = (
+	workspacePath: string,
+): Promise<VaultLockInfo | null> =>
+	invoke("break_vault_lock_command", { workspacePath })
+
+// Reports how a single note stands in the index, for working out why search
+// does not find it.
+export const getNoteIndexStatus = (
+	workspacePath: string,
+	path: string,
+): Promise<NoteIndexStatus> =>
+	invoke("get_note_index_status_command", { workspacePath, path })
+
+// Indexes one note. With `force` the note is re-chunked, re-embedded and
+// re-linked even when its content has not changed.
+export const indexNote = (
+	workspacePath: string,
+	notePath: string,
+	force = false,
+): Promise<WorkspaceIndexSummary> =>
+	invoke("index_note_command", { workspacePath, notePath, force })
+
 export const createTauriIndexingPort = (
 	workspacePath: string,
 ): IndexingPort => ({
@@ -32,4 +58,5 @@ export const createTauriIndexingPort = (
 		invoke<WorkspaceIndexSummary>("refresh_workspace_embeddings_command", {
 			workspacePath,
 		}),
+	indexNote: (notePath: string) => indexNote(workspacePath, notePath),
 })
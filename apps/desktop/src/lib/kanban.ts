@@ -0,0 +1,50 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type KanbanCard = {
+	path: string
+	// First heading of the note, else its file name.
+	title: string
+	preview: string
+	modifiedAt: number | null
+}
+
+export type KanbanColumn = {
+	value: string
+	cards: KanbanCard[]
+}
+
+export type KanbanBoard = {
+	property: string
+	// The requested columns in order, then any other value found.
+	columns: KanbanColumn[]
+}
+
+// Groups the notes under folderPath, or the whole vault, by the value of
+// their frontmatter property. Notes without a scalar value are left out.
+export const getKanbanBoard = (
+	workspacePath: string,
+	property: string,
+	columns: string[],
+	folderPath?: string,
+): Promise<KanbanBoard> =>
+	invoke("get_kanban_board_command", {
+		workspacePath,
+		property,
+		columns,
+		folderPath,
+	})
+
+// Sets the note's board property to the target column and reindexes it.
+export const moveCard = async (
+	workspacePath: string,
+	path: string,
+	property: string,
+	column: string,
+): Promise<void> => {
+	await invoke("move_card_command", {
+		workspacePath,
+		path,
+		property,
+		column,
+	})
+}
@@ -0,0 +1,9 @@
+import { invoke } from "@tauri-apps/api/core"
+
+// Number of notes linking to each of relPaths, for backlink badges in the
+// file tree. Notes missing from the index are left out of the result.
+export const getLinkCounts = (
+	workspacePath: string,
+	relPaths: string[],
+): Promise<Record<string, number>> =>
+	invoke("get_link_counts_command", { workspacePath, relPaths })
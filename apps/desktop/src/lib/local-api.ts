@@ -1,9 +1,10 @@
 import { invoke } from "@tauri-apps/api/core"
 import { ensureLocalApiAuthToken } from "./local-api-auth"
 
-export async function startLocalApiServer(): Promise<void> {
+/** Starts the server and resolves to the port it bound. */
+export async function startLocalApiServer(): Promise<number> {
 	const token = await ensureLocalApiAuthToken()
-	await invoke("start_local_api_server_command", { token })
+	return invoke<number>("start_local_api_server_command", { token })
 }
 
 export async function stopLocalApiServer(): Promise<void> {
@@ -13,3 +14,7 @@ export async function stopLocalApiServer(): Promise<void> {
 export async function setLocalApiAuthToken(token: string): Promise<void> {
 	await invoke("set_local_api_auth_token_command", { token })
 }
+
+export async function getLocalApiPort(): Promise<number> {
+	return invoke<number>("get_local_api_port_command")
+}
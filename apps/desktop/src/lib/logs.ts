@@ -0,0 +1,13 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace"
+
+// The last lines of the app log, oldest first, for attaching to bug reports.
+export const getRecentLogs = (maxLines?: number): Promise<string[]> =>
+	invoke("get_recent_logs_command", { maxLines })
+
+export const getLogLevel = (): Promise<LogLevel> =>
+	invoke("get_log_level_command")
+
+export const setLogLevel = (level: LogLevel): Promise<void> =>
+	invoke("set_log_level_command", { level })
@@ -0,0 +1,35 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type MocSource =
+	// Every note under the folder, subfolders included.
+	| { type: "folder"; path: string }
+	// Every note with the tag or a tag nested under it.
+	| { type: "tag"; tag: string }
+
+export type MocOptions = {
+	groupBy?: "folder" | "tag"
+	// modifiedAt lists the most recently changed notes first.
+	sortBy?: "title" | "path" | "modifiedAt"
+	includePreviews?: boolean
+}
+
+export type MocResult = {
+	path: string
+	noteCount: number
+	created: boolean
+}
+
+// Writes the list between the MOC markers of the note at mocPath, creating
+// the note if needed. Text outside the markers is kept on regeneration.
+export const generateMoc = (
+	workspacePath: string,
+	mocPath: string,
+	source: MocSource,
+	options?: MocOptions,
+): Promise<MocResult> =>
+	invoke("generate_moc_command", {
+		workspacePath,
+		mocPath,
+		source,
+		options,
+	})
@@ -0,0 +1,38 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type ContextPassage = {
+	startOffset: number
+	endOffset: number
+	headingPath: string[]
+	codeLanguage: string | null
+}
+
+export type ContextSource = {
+	// Number cited as [n] in the context string.
+	marker: number
+	path: string
+	relPath: string
+	passages: ContextPassage[]
+}
+
+export type NoteContext = {
+	context: string
+	// cl100k tokens, never above the requested budget.
+	tokenCount: number
+	sources: ContextSource[]
+	// Whether passages were left out to stay within the budget.
+	truncated: boolean
+}
+
+// Passages from notePaths come first, then the best passage of each note
+// matching query.
+export const buildNoteContext = (
+	workspacePath: string,
+	request: { query?: string; notePaths?: string[]; maxTokens: number },
+): Promise<NoteContext> =>
+	invoke("build_context_command", {
+		workspacePath,
+		query: request.query ?? null,
+		notePaths: request.notePaths ?? null,
+		maxTokens: request.maxTokens,
+	})
@@ -0,0 +1,78 @@
+import { invoke } from "@tauri-apps/api/core"
+
+// bear: a .bear2bk backup, a folder of .textbundle notes, or one of them.
+// appleNotes: an .enex export, an exported .html note, or a folder of either.
+// docx: a Word document, a OneNote page exported as .docx, or a folder of them.
+// kindle: a My Clippings.txt file. readwise: a Readwise CSV export.
+export type ImportSource = "bear" | "appleNotes" | "docx" | "kindle" | "readwise"
+
+export type ImportedFile = {
+	// Relative to the vault root.
+	relPath: string
+	kind: "note" | "attachment"
+	size: number
+	// The note it came from in the source app.
+	origin: string
+}
+
+// A highlights note from an earlier import that new highlights were
+// appended to.
+export type UpdatedNote = {
+	relPath: string
+	origin: string
+	added: number
+}
+
+export type ImportReport = {
+	dryRun: boolean
+	// Notes first, then attachments. Names taken in the vault get a number.
+	files: ImportedFile[]
+	updated: UpdatedNote[]
+	skipped: { origin: string; reason: string }[]
+}
+
+// Imports notes into targetFolder, or the vault root, with images in the
+// vault's attachment folder, and indexes them. A dry run returns the same files without
+// writing any of them.
+export const importNotes = (
+	workspacePath: string,
+	source: ImportSource,
+	inputPath: string,
+	options: { targetFolder?: string; dryRun?: boolean } = {},
+): Promise<ImportReport> =>
+	invoke("import_notes_command", {
+		workspacePath,
+		source,
+		inputPath,
+		targetFolder: options.targetFolder,
+		dryRun: options.dryRun ?? false,
+	})
+
+export type MeetingNote = {
+	relPath: string
+	title: string
+	// YYYY-MM-DD
+	date: string
+}
+
+export type CalendarImport = {
+	created: MeetingNote[]
+	// Notes whose event changed; only their frontmatter is rewritten.
+	updated: MeetingNote[]
+	unchanged: number
+}
+
+// Creates or updates meeting notes, in the meetingNotesFolder vault setting,
+// for the events of an .ics file or an http(s)/webcal calendar URL between
+// start and end (YYYY-MM-DD, inclusive). New notes link to their daily note.
+export const importIcs = (
+	workspacePath: string,
+	source: string,
+	range: { start: string; end: string },
+): Promise<CalendarImport> =>
+	invoke("import_ics_command", {
+		workspacePath,
+		source,
+		start: range.start,
+		end: range.end,
+	})
@@ -0,0 +1,67 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type NoteQuerySource =
+	// Notes under the folder, relative to the vault root.
+	| { type: "folder"; path: string }
+	// Notes with the tag or a tag nested under it.
+	| { type: "tag"; tag: string }
+
+export type NoteQueryField =
+	// File name without the extension.
+	| { type: "title" }
+	| { type: "path" }
+	// Milliseconds since the epoch.
+	| { type: "createdAt" }
+	| { type: "modifiedAt" }
+	// Frontmatter property, matched regardless of case.
+	| { type: "property"; key: string }
+
+export type NoteQuery = {
+	source?: NoteQuerySource
+	select: NoteQueryField[]
+	// Notes missing a value sort last; ties fall back to the path.
+	sort?: { field: NoteQueryField; descending?: boolean }[]
+	// 100 when unset, at most 1000.
+	limit?: number
+}
+
+export type NoteQueryRow = {
+	relPath: string
+	// One value per selected field, null when the note has none.
+	values: unknown[]
+}
+
+export type NoteQueryResult = {
+	columns: NoteQueryField[]
+	rows: NoteQueryRow[]
+}
+
+// Evaluated against the index, so notes changed since the last indexing run
+// show their previous values.
+export const queryNotes = (
+	workspacePath: string,
+	query: NoteQuery,
+): Promise<NoteQueryResult> =>
+	invoke("query_notes_command", { workspacePath, query })
+
+// Runs the body of an ```mdit-query block written in notePath, e.g.
+// "from folder ./Tasks\nselect title, status\nsort due desc\nlimit 20".
+// Relative folders start at the note's folder.
+export const renderQueryBlock = (
+	workspacePath: string,
+	notePath: string,
+	blockContent: string,
+): Promise<NoteQueryResult> =>
+	invoke("render_query_block_command", {
+		workspacePath,
+		notePath,
+		blockContent,
+	})
+
+// Notes whose query blocks read from any of relPaths, to re-render once
+// those notes have been reindexed.
+export const getQueryDependents = (
+	workspacePath: string,
+	relPaths: string[],
+): Promise<string[]> =>
+	invoke("get_query_dependents_command", { workspacePath, relPaths })
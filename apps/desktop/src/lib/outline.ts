@@ -0,0 +1,30 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type OutlineHeading = {
+	level: number
+	text: string
+	// Byte offsets of the heading line in the note file.
+	start: number
+	end: number
+	line: number
+}
+
+export type OutlineCodeBlock = {
+	// First word of the fence info string, lowercased.
+	language: string | null
+	// Diagram languages such as mermaid and plantuml.
+	renderable: boolean
+	// Byte offsets of the block, fences included, in the note file.
+	start: number
+	end: number
+	startLine: number
+	endLine: number
+}
+
+export type NoteOutline = {
+	headings: OutlineHeading[]
+	codeBlocks: OutlineCodeBlock[]
+}
+
+export const getNoteOutline = (path: string): Promise<NoteOutline> =>
+	invoke("get_note_outline_command", { path })
@@ -0,0 +1,22 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type PersonLinkSuggestion = {
+	// Byte range of the mention in the note file.
+	start: number
+	end: number
+	line: number
+	// UTF-16 column, the way the editor indexes lines.
+	column: number
+	text: string
+	personRelPath: string
+	// Wiki link that replaces the mention, e.g. [[Ada Lovelace|Ada]].
+	link: string
+}
+
+// Capitalized mentions of people with a page in the peopleFolder vault
+// setting, by page name or alias. Code, links and frontmatter are skipped.
+export const suggestPersonLinks = (
+	workspacePath: string,
+	notePath: string,
+): Promise<PersonLinkSuggestion[]> =>
+	invoke("suggest_person_links_command", { workspacePath, notePath })
@@ -0,0 +1,40 @@
+import { invoke } from "@tauri-apps/api/core"
+import { listen, type UnlistenFn } from "@tauri-apps/api/event"
+
+export const PINNED_NOTES_CHANGED_EVENT = "pinned-notes-changed"
+
+export type PinnedNote = {
+	// Forward-slash path relative to the vault root.
+	relPath: string
+	pinnedAt: string
+}
+
+export type PinnedNotesChangedPayload = {
+	workspacePath: string
+	pinnedNotes: PinnedNote[]
+}
+
+export const listPinnedNotes = (workspacePath: string): Promise<PinnedNote[]> =>
+	invoke("list_pinned_notes_command", { workspacePath })
+
+export const pinNote = (
+	workspacePath: string,
+	notePath: string,
+	pinned: boolean,
+): Promise<PinnedNote[]> =>
+	invoke("pin_note_command", { workspacePath, notePath, pinned })
+
+// Moves notePaths to the front of the pins in the given order.
+export const reorderPinnedNotes = (
+	workspacePath: string,
+	notePaths: string[],
+): Promise<PinnedNote[]> =>
+	invoke("reorder_pinned_notes_command", { workspacePath, notePaths })
+
+// Fires in every window, including the one that made the change.
+export const onPinnedNotesChanged = (
+	listener: (payload: PinnedNotesChangedPayload) => void,
+): Promise<UnlistenFn> =>
+	listen<PinnedNotesChangedPayload>(PINNED_NOTES_CHANGED_EVENT, (event) => {
+		listener(event.payload)
+	})
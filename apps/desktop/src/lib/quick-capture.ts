@@ -0,0 +1,50 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type QuickCaptureTarget = "inbox" | "dailyNote"
+
+export type CapturedNote = {
+	vaultId: number
+	workspacePath: string
+	relativePath: string
+	absolutePath: string
+	created: boolean
+}
+
+export type InboxEntry = {
+	vaultId: number
+	workspacePath: string
+	relativePath: string
+	absolutePath: string
+	timestamp: string
+}
+
+const toLocalDateString = (date: Date): string => {
+	const month = String(date.getMonth() + 1).padStart(2, "0")
+	const day = String(date.getDate()).padStart(2, "0")
+	return `${date.getFullYear()}-${month}-${day}`
+}
+
+export const toggleQuickCaptureWindow = (): Promise<void> =>
+	invoke("toggle_quick_capture_window_command")
+
+export const hideQuickCaptureWindow = (): Promise<void> =>
+	invoke("hide_quick_capture_window_command")
+
+// The date is sent from here so the daily note follows the user's local day.
+export const captureNote = (
+	text: string,
+	target: QuickCaptureTarget,
+): Promise<CapturedNote> =>
+	invoke("quick_capture_command", {
+		text,
+		target,
+		date: toLocalDateString(new Date()),
+	})
+
+// Appends a timestamped entry to the inbox of the most recent vault without
+// touching the capture window. Metadata is listed under the entry's heading.
+export const appendToInbox = (
+	text: string,
+	metadata?: Record<string, string>,
+): Promise<InboxEntry> =>
+	invoke("append_to_inbox_command", { text, metadata: metadata ?? null })
@@ -0,0 +1,31 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type SearchHistoryEntry = {
+	query: string
+	searchCount: number
+	lastSearchedAt: string
+}
+
+// Resolves to false when the vault's searchHistory setting is off.
+export const recordSearchQuery = (
+	workspacePath: string,
+	query: string,
+): Promise<boolean> =>
+	invoke("record_search_query_command", { workspacePath, query })
+
+export const getSearchHistory = (
+	workspacePath: string,
+	limit?: number,
+): Promise<SearchHistoryEntry[]> =>
+	invoke("get_search_history_command", { workspacePath, limit })
+
+// Past queries completing prefix, most used first.
+export const suggestQueries = (
+	workspacePath: string,
+	prefix: string,
+	limit?: number,
+): Promise<string[]> =>
+	invoke("suggest_queries_command", { workspacePath, prefix, limit })
+
+export const clearSearchHistory = (workspacePath: string): Promise<void> =>
+	invoke("clear_search_history_command", { workspacePath })
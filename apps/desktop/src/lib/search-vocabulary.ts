@@ -0,0 +1,42 @@
+import { invoke } from "@tauri-apps/api/core"
+
+// The pair works in both directions; term is whichever sorts first.
+export type SearchSynonym = {
+	term: string
+	synonym: string
+}
+
+export const listSearchSynonyms = (
+	workspacePath: string,
+): Promise<SearchSynonym[]> =>
+	invoke("list_search_synonyms_command", { workspacePath })
+
+// Either side may be a few words, such as "ml" and "machine learning".
+export const addSearchSynonym = (
+	workspacePath: string,
+	term: string,
+	synonym: string,
+): Promise<SearchSynonym[]> =>
+	invoke("add_search_synonym_command", { workspacePath, term, synonym })
+
+export const removeSearchSynonym = (
+	workspacePath: string,
+	term: string,
+	synonym: string,
+): Promise<SearchSynonym[]> =>
+	invoke("remove_search_synonym_command", { workspacePath, term, synonym })
+
+export const listSearchStopwords = (workspacePath: string): Promise<string[]> =>
+	invoke("list_search_stopwords_command", { workspacePath })
+
+export const addSearchStopword = (
+	workspacePath: string,
+	word: string,
+): Promise<string[]> =>
+	invoke("add_search_stopword_command", { workspacePath, word })
+
+export const removeSearchStopword = (
+	workspacePath: string,
+	word: string,
+): Promise<string[]> =>
+	invoke("remove_search_stopword_command", { workspacePath, word })
@@ -0,0 +1,109 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type SearchSort = "score" | "modified" | "created" | "path"
+
+export type SearchGroupBy = "folder" | "tag"
+
+export type SearchEntry = {
+	path: string
+	name: string
+	createdAt?: number
+	modifiedAt?: number
+	similarity: number
+	passage: {
+		startOffset: number
+		endOffset: number
+		headingPath: string[]
+		codeLanguage: string | null
+	} | null
+	// Indexed text of the top results, when asked for with `includeContent`.
+	content: string | null
+	contentTruncated: boolean
+	// Near-duplicates folded into this result, with `collapseSimilar`.
+	similar: SearchEntry[]
+}
+
+export type SegmentMatch = {
+	path: string
+	relPath: string
+	ordinal: number
+	// The segment as embedded, headed by the note title and its headings.
+	text: string
+	startOffset: number
+	endOffset: number
+	headingPath: string[]
+	codeLanguage: string | null
+	vectorScore: number | null
+	bm25Score: number | null
+	score: number
+}
+
+export type CorrectedSearchResults = {
+	entries: SearchEntry[]
+	// The respelled query the entries were found with, when the query as
+	// typed matched nothing.
+	didYouMean: string | null
+}
+
+export type SearchResultGroup = {
+	// Folder relative to the vault root, or tag; empty for the root folder
+	// and for untagged notes.
+	key: string
+	entries: SearchEntry[]
+}
+
+export type SearchQueryOptions = {
+	codeOnly?: boolean
+	includeArchived?: boolean
+	regex?: boolean
+	sort?: SearchSort
+	includeContent?: boolean
+	// Per result; defaults to 16 KiB.
+	contentMaxBytes?: number
+	// Folds notes nearly identical to a better result into it, such as
+	// meeting minutes made from one template.
+	collapseSimilar?: boolean
+}
+
+export const searchQueryEntries = (
+	workspacePath: string,
+	query: string,
+	options: SearchQueryOptions = {},
+): Promise<SearchEntry[]> =>
+	invoke("search_query_entries_command", { workspacePath, query, ...options })
+
+// Retries with misspelled words replaced by indexed terms when the query
+// finds nothing; regex queries are never respelled.
+export const searchQueryWithCorrection = (
+	workspacePath: string,
+	query: string,
+	options: SearchQueryOptions = {},
+): Promise<CorrectedSearchResults> =>
+	invoke("search_query_with_correction_command", {
+		workspacePath,
+		query,
+		...options,
+	})
+
+// Groups come in the order of their best result; each keeps the sort order.
+export const searchQueryGroups = (
+	workspacePath: string,
+	query: string,
+	groupBy: SearchGroupBy,
+	options: SearchQueryOptions = {},
+): Promise<SearchResultGroup[]> =>
+	invoke("search_query_groups_command", {
+		workspacePath,
+		query,
+		groupBy,
+		...options,
+	})
+
+// Segments ranked on their own rather than folded into their notes. Vaults
+// indexed without embeddings have no segments.
+export const searchSegments = (
+	workspacePath: string,
+	query: string,
+	limit?: number,
+): Promise<SegmentMatch[]> =>
+	invoke("search_segments_command", { workspacePath, query, limit })
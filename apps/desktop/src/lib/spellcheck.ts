@@ -0,0 +1,38 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type Misspelling = {
+	word: string
+	// UTF-16 offsets into the checked text, usable with String.slice.
+	start: number
+	end: number
+	suggestions: string[]
+}
+
+// lang is a dictionary tag such as "en-US". With a workspacePath, the
+// vault's custom words are accepted too.
+export const spellcheckText = (
+	text: string,
+	lang: string,
+	workspacePath?: string,
+): Promise<Misspelling[]> =>
+	invoke("spellcheck_text_command", { text, lang, workspacePath })
+
+// Dictionaries are read from the app data "dictionaries" folder, the bundled
+// ones, and the system's hunspell folder.
+export const listSpellcheckLanguages = (): Promise<string[]> =>
+	invoke("list_spellcheck_languages_command")
+
+export const listSpellcheckWords = (workspacePath: string): Promise<string[]> =>
+	invoke("list_spellcheck_words_command", { workspacePath })
+
+export const addSpellcheckWord = (
+	workspacePath: string,
+	word: string,
+): Promise<string[]> =>
+	invoke("add_spellcheck_word_command", { workspacePath, word })
+
+export const removeSpellcheckWord = (
+	workspacePath: string,
+	word: string,
+): Promise<string[]> =>
+	invoke("remove_spellcheck_word_command", { workspacePath, word })
@@ -0,0 +1,40 @@
+import { invoke } from "@tauri-apps/api/core"
+import { listen, type UnlistenFn } from "@tauri-apps/api/event"
+
+export const TRANSCRIPTIONS_WRITTEN_EVENT = "transcriptions-written"
+
+export type TranscriptionFailure = {
+	audioPath: string
+	error: string
+}
+
+export type TranscriptionRun = {
+	// Notes made for new recordings in the voice folder.
+	created: string[]
+	// Notes that gained a "## Transcript: <file>" section.
+	updated: string[]
+	// Audio that could not be transcribed; the next run tries it again.
+	failures: TranscriptionFailure[]
+}
+
+export type TranscriptionsWrittenPayload = {
+	workspacePath: string
+	run: TranscriptionRun
+}
+
+// Transcribes the audio linked from notePath, or from every note and the
+// voice folder when notePath is missing. Rejects while the vault's
+// transcription setting is off.
+export const transcribeAudio = (
+	workspacePath: string,
+	notePath?: string,
+): Promise<TranscriptionRun> =>
+	invoke("transcribe_audio_command", { workspacePath, notePath })
+
+// Fires after a manual run or a background voice folder pass that wrote notes.
+export const onTranscriptionsWritten = (
+	listener: (payload: TranscriptionsWrittenPayload) => void,
+): Promise<UnlistenFn> =>
+	listen<TranscriptionsWrittenPayload>(TRANSCRIPTIONS_WRITTEN_EVENT, (event) => {
+		listener(event.payload)
+	})
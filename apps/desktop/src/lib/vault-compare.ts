@@ -0,0 +1,42 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type VaultFileStatus = "identical" | "changed" | "onlyInA" | "onlyInB"
+
+export type VaultFileComparison = {
+	relPath: string
+	status: VaultFileStatus
+	// Modification times in nanoseconds, to tell which side is newer.
+	modifiedNsA: number | null
+	modifiedNsB: number | null
+}
+
+export type VaultComparison = {
+	files: VaultFileComparison[]
+	identical: number
+	changed: number
+	onlyInA: number
+	onlyInB: number
+}
+
+export type VaultCopyReport = {
+	copied: string[]
+	// Copied notes and drawings indexed in the target vault.
+	indexed: number
+}
+
+// Compares two copies of a vault, such as a laptop and a desktop copy after
+// a failed sync, by relative path and file content.
+export const compareVaults = (
+	pathA: string,
+	pathB: string,
+): Promise<VaultComparison> =>
+	invoke("compare_vaults_command", { pathA, pathB })
+
+// Copies relPaths from sourcePath to targetPath, replacing the files there,
+// and indexes the copied notes in the target vault.
+export const copyVaultFiles = (
+	sourcePath: string,
+	targetPath: string,
+	relPaths: string[],
+): Promise<VaultCopyReport> =>
+	invoke("copy_vault_files_command", { sourcePath, targetPath, relPaths })
@@ -0,0 +1,51 @@
+import { invoke } from "@tauri-apps/api/core"
+
+export type OversizedNote = {
+	relPath: string
+	sizeBytes: number
+}
+
+export type VaultHealth = {
+	// Notes and drawings on disk.
+	sourceFileCount: number
+	// Files whose index entry matches what is on disk.
+	freshFileCount: number
+	freshnessPercent: number
+	indexedDocCount: number
+	embeddedDocCount: number
+	embeddingCoveragePercent: number
+	pendingEmbeddingCount: number
+	unresolvedLinkCount: number
+	// Files other than notes whose names appear in no note.
+	orphanAttachments: string[]
+	// Only the head of these notes is indexed.
+	oversizedNotes: OversizedNote[]
+	watching: boolean
+	// Unix seconds; null until maintenance has run since the app started.
+	lastMaintenanceAt: number | null
+}
+
+export const getVaultHealth = (workspacePath: string): Promise<VaultHealth> =>
+	invoke("get_vault_health_command", { workspacePath })
+
+export type EmbeddingCoverage = {
+	// The vault's primary embedding profile; null while embeddings are off.
+	provider: string | null
+	model: string | null
+	// Tokens the model reads per input; null for models the app does not know.
+	modelContextTokens: number | null
+	indexedDocCount: number
+	currentModelDocCount: number
+	// Vectors from a fallback or earlier model, which current queries miss.
+	otherModelDocCount: number
+	// Edited since their vectors were made.
+	staleDocCount: number
+	embeddedSegmentCount: number
+	// Cut off at the model's window when embedded.
+	oversizedSegmentCount: number
+}
+
+export const getEmbeddingCoverage = (
+	workspacePath: string,
+): Promise<EmbeddingCoverage> =>
+	invoke("get_embedding_coverage_command", { workspacePath })
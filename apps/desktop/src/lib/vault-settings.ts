@@ -0,0 +1,150 @@
+import { invoke } from "@tauri-apps/api/core"
+import { listen, type UnlistenFn } from "@tauri-apps/api/event"
+
+export const VAULT_SETTING_CHANGED_EVENT = "vault-setting-changed"
+
+export type VaultSettings = {
+	archiveFolder: string
+	attachmentFolder: string
+	// .bib or CSL-JSON file that [@key] citations refer to; empty when unset.
+	bibliographyPath: string
+	// Periodic check of the index against the files on disk.
+	consistencySweep: {
+		enabled?: boolean
+		intervalMinutes?: number
+	}
+	dailyNoteFormat: string
+	// Days a deleted note keeps its index identity for a restore; 0 disables.
+	deletedNoteRetentionDays: number
+	ignorePatterns: string[]
+	// Background index upkeep once the app has been idle for idleSeconds.
+	maintenance: {
+		enabled?: boolean
+		onlyOnAcPower?: boolean
+		idleSeconds?: number
+	}
+	// Notes larger than this are indexed by their head only.
+	maxIndexedFileMegabytes: number
+	// Where calendar imports write meeting notes.
+	meetingNotesFolder: string
+	// Person pages that name mentions are suggested as links to.
+	peopleFolder: string
+	// Folders the local API and MCP tools may not read or write, relative to
+	// the vault root. The app itself still opens them.
+	privatePaths: string[]
+	// Secrets masked in note text the local API and MCP tools return; patterns
+	// are regular expressions of the vault's own.
+	redaction: {
+		emails?: boolean
+		apiKeys?: boolean
+		ssns?: boolean
+		patterns?: string[]
+	}
+	// Rank related notes partly by how recently they changed: a note's
+	// similarity counts half as much every halfLifeDays since its last edit.
+	relatedNotesRecency: {
+		enabled?: boolean
+		halfLifeDays?: number
+	}
+	// Remember executed search queries for suggestions.
+	searchHistory: boolean
+	// How notes are split into words for keyword search: porter matches word
+	// stems, trigram any part of a word (and CJK text). tokenChars are kept
+	// inside words. Changing it rebuilds the vault's keyword index.
+	searchTokenizer: {
+		tokenizer?: "unicode61" | "porter" | "trigram"
+		tokenChars?: string
+	}
+	searchWeights: {
+		title?: number
+		content?: number
+		tags?: number
+		semantic?: number
+	}
+	// Export note titles, tags and previews for macOS Spotlight.
+	spotlightMetadata: boolean
+	// Audio transcription; whisperCpp runs a local binary and model, http
+	// posts to an OpenAI-compatible endpoint with the key from apiKeyRef's
+	// environment variable.
+	transcription: {
+		backend?: "off" | "whisperCpp" | "http"
+		whisperBinary?: string
+		whisperModel?: string
+		endpoint?: string
+		apiKeyRef?: string
+		model?: string
+		language?: string
+		// Recordings here get a note of their own.
+		voiceFolder?: string
+	}
+}
+
+export type VaultSettingKey = keyof VaultSettings
+
+export type VaultSetting<K extends VaultSettingKey = VaultSettingKey> = {
+	key: K
+	value: VaultSettings[K]
+	// null while the default value is in effect.
+	updatedAt: string | null
+}
+
+export type VaultSettingChangedPayload = VaultSetting & {
+	workspacePath: string
+}
+
+export const listVaultSettings = async (
+	workspacePath: string,
+): Promise<VaultSettings> => {
+	const settings = await invoke<VaultSetting[]>(
+		"list_vault_settings_command",
+		{ workspacePath },
+	)
+	return Object.fromEntries(
+		settings.map((setting) => [setting.key, setting.value]),
+	) as VaultSettings
+}
+
+export const getVaultSetting = <K extends VaultSettingKey>(
+	workspacePath: string,
+	key: K,
+): Promise<VaultSetting<K>> =>
+	invoke("get_vault_setting_command", { workspacePath, key })
+
+export const setVaultSetting = <K extends VaultSettingKey>(
+	workspacePath: string,
+	key: K,
+	value: VaultSettings[K],
+): Promise<VaultSetting<K>> =>
+	invoke("set_vault_setting_command", { workspacePath, key, value })
+
+export const resetVaultSetting = <K extends VaultSettingKey>(
+	workspacePath: string,
+	key: K,
+): Promise<VaultSetting<K>> =>
+	invoke("reset_vault_setting_command", { workspacePath, key })
+
+// Fires in every window, including the one that made the change.
+export const onVaultSettingChanged = (
+	listener: (payload: VaultSettingChangedPayload) => void,
+): Promise<UnlistenFn> =>
+	listen<VaultSettingChangedPayload>(VAULT_SETTING_CHANGED_EVENT, (event) => {
+		listener(event.payload)
+	})
+
+export type VaultConfigImport = {
+	appliedSettings: string[]
+	// "key: reason" for settings this build does not accept.
+	skippedSettings: string[]
+	addedSpellcheckWords: number
+	embeddingApplied: boolean
+}
+
+// Writes the vault's settings to .mdit/config.json inside the vault and
+// returns the file's path, so the configuration travels with synced vaults.
+export const exportVaultConfig = (workspacePath: string): Promise<string> =>
+	invoke("export_vault_config_command", { workspacePath })
+
+export const importVaultConfig = (
+	workspacePath: string,
+): Promise<VaultConfigImport> =>
+	invoke("import_vault_config_command", { workspacePath })
@@ -0,0 +1,40 @@
+import { invoke } from "@tauri-apps/api/core"
+import type { UnlistenFn } from "@tauri-apps/api/event"
+import { getCurrentWindow } from "@tauri-apps/api/window"
+
+export const VAULT_WINDOW_LABEL_PREFIX = "vault-"
+export const VAULT_WINDOW_OPEN_FILES_EVENT = "vault-window-open-files"
+
+type VaultWindowOpenFilesPayload = {
+	filePaths: string[]
+}
+
+export const isVaultWindowLabel = (label: string | null): boolean =>
+	label?.startsWith(VAULT_WINDOW_LABEL_PREFIX) ?? false
+
+// Focuses the window already holding the vault instead of opening a second one.
+export const openVaultWindow = (workspacePath: string): Promise<string> =>
+	invoke("open_vault_window_command", { workspacePath })
+
+export const registerWindowVault = (
+	workspacePath: string | null,
+): Promise<void> =>
+	invoke("register_window_vault_command", { workspacePath })
+
+export const getWindowVault = (): Promise<string | null> =>
+	invoke("get_window_vault_command")
+
+export const closeVaultWindow = async (): Promise<void> => {
+	await invoke("close_vault_window_command")
+	await getCurrentWindow().destroy()
+}
+
+export const onVaultWindowOpenFiles = (
+	listener: (filePaths: string[]) => void,
+): Promise<UnlistenFn> =>
+	getCurrentWindow().listen<VaultWindowOpenFilesPayload>(
+		VAULT_WINDOW_OPEN_FILES_EVENT,
+		(event) => {
+			listener(event.payload.filePaths)
+		},
+	)
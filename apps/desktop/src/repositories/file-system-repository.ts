@@ -4,11 +4,48 @@ import {
 	mkdir,
 	readDir,
 	readTextFile,
-	rename,
 	stat,
-	writeTextFile,
 } from "@tauri-apps/plugin-fs"
 
+export type DirectoryEntry = {
+	name: string
+	path: string
+	kind: "file" | "directory"
+	size: number | null
+	createdAt: number | null
+	modifiedAt: number | null
+	title: string | null
+	tags: string[]
+	childCount: number | null
+}
+
+export type ListDirectoryOptions = {
+	sortBy?: "name" | "createdAt" | "modifiedAt"
+	direction?: "asc" | "desc"
+	includeHidden?: boolean
+}
+
+export type UniqueNoteFileName = {
+	fileName: string
+	fullPath: string
+}
+
+export type TrashedNote = {
+	id: number
+	vaultId: number
+	originalPath: string
+	trashLocation: string | null
+	trashedAt: string
+}
+
+export type CollisionPolicy = "fail" | "overwrite" | "autoRename"
+
+export type TransferResult = {
+	sourcePath: string
+	destinationPath: string | null
+	error: string | null
+}
+
 export class FileSystemRepository {
 	exists(path: string): Promise<boolean> {
 		return exists(path)
@@ -22,12 +59,29 @@ export class FileSystemRepository {
 		return readDir(path)
 	}
 
+	listDirectory(
+		path: string,
+		options?: ListDirectoryOptions,
+		workspacePath?: string,
+	): Promise<DirectoryEntry[]> {
+		return invoke<DirectoryEntry[]>("list_directory_command", {
+			path,
+			options,
+			workspacePath,
+		})
+	}
+
 	readTextFile(path: string): Promise<string> {
 		return readTextFile(path)
 	}
 
+	// Renames and writes go through the backend, which refuses to change
+	// locked notes.
 	rename(sourcePath: string, destinationPath: string): Promise<void> {
-		return rename(sourcePath, destinationPath)
+		return invoke<void>("rename_entry_command", {
+			sourcePath,
+			destinationPath,
+		})
 	}
 
 	stat(path: string) {
@@ -35,7 +89,7 @@ export class FileSystemRepository {
 	}
 
 	writeTextFile(path: string, contents: string): Promise<void> {
-		return writeTextFile(path, contents)
+		return invoke<void>("write_text_file_command", { path, contents })
 	}
 
 	moveToTrash(path: string): Promise<void> {
@@ -46,10 +100,67 @@ export class FileSystemRepository {
 		return invoke<void>("move_many_to_trash", { paths })
 	}
 
+	listTrashedNotes(workspacePath: string): Promise<TrashedNote[]> {
+		return invoke<TrashedNote[]>("list_trashed_notes_command", {
+			workspacePath,
+		})
+	}
+
+	restoreTrashedNote(workspacePath: string, trashedId: number): Promise<string> {
+		return invoke<string>("restore_trashed_note_command", {
+			workspacePath,
+			trashedId,
+		})
+	}
+
 	copy(sourcePath: string, destinationPath: string): Promise<void> {
 		return invoke<void>("copy", { sourcePath, destinationPath })
 	}
 
+	copyEntries(
+		sourcePaths: string[],
+		destinationDir: string,
+		collisionPolicy: CollisionPolicy,
+	): Promise<TransferResult[]> {
+		return invoke<TransferResult[]>("copy_entries_command", {
+			sourcePaths,
+			destinationDir,
+			collisionPolicy,
+		})
+	}
+
+	moveEntries(
+		workspacePath: string | null,
+		sourcePaths: string[],
+		destinationDir: string,
+		collisionPolicy: CollisionPolicy,
+	): Promise<TransferResult[]> {
+		return invoke<TransferResult[]>("move_entries_command", {
+			workspacePath,
+			sourcePaths,
+			destinationDir,
+			collisionPolicy,
+		})
+	}
+
+	getUniqueNoteFileName(
+		directoryPath: string,
+		title: string,
+	): Promise<UniqueNoteFileName> {
+		return invoke<UniqueNoteFileName>("get_unique_note_file_name_command", {
+			directoryPath,
+			title,
+		})
+	}
+
+	pickAllowedFolder(title?: string): Promise<string | null> {
+		return invoke<string | null>("pick_allowed_folder_command", { title })
+	}
+
+	revokePathRoot(path: string): Promise<void> {
+		return invoke<void>("revoke_path_root_command", { path })
+	}
+
 	async isExistingDirectory(path: string): Promise<boolean> {
 		try {
 			const statResult = await this.stat(path)
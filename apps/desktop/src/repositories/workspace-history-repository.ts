@@ -1,5 +1,19 @@
 import { invoke } from "@tauri-apps/api/core"
 
+// "starter" adds Inbox, Daily, Templates and Attachments folders.
+export type VaultTemplate = "starter" | "empty"
+
+export type CreatedVault = {
+	workspaceRoot: string
+	created: string[]
+}
+
+export type VaultWorkspace = {
+	id: number
+	workspaceRoot: string
+	lastOpenedAt: string
+}
+
 export class WorkspaceHistoryRepository {
 	async listWorkspacePaths(): Promise<string[]> {
 		return invoke<string[]>("list_vault_workspaces_command")
@@ -11,6 +25,26 @@ export class WorkspaceHistoryRepository {
 		})
 	}
 
+	// The folder may exist but must be empty.
+	async createVault(
+		path: string,
+		template: VaultTemplate = "starter",
+	): Promise<CreatedVault> {
+		return invoke<CreatedVault>("create_vault_command", { path, template })
+	}
+
+	// For a vault folder moved on disk: keeps its index and watches it at
+	// newPath from this window. oldPath must no longer exist.
+	async migrateVaultPath(
+		oldPath: string,
+		newPath: string,
+	): Promise<VaultWorkspace> {
+		return invoke<VaultWorkspace>("migrate_vault_path_command", {
+			oldPath,
+			newPath,
+		})
+	}
+
 	async removeWorkspace(path: string): Promise<void> {
 		await invoke<void>("remove_vault_workspace_command", {
 			workspacePath: path,
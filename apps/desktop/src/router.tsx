@@ -8,6 +8,7 @@ import {
 	Router as WouterRouter,
 } from "wouter"
 import { App } from "@/app"
+import { QuickCapture } from "./components/quick-capture/quick-capture"
 import { EditNote } from "./components/quick-note/edit-note"
 import { QuickNote } from "./components/quick-note/quick-note"
 import { SystemTray } from "./components/system-tray/system-tray"
@@ -36,6 +37,9 @@ function AppRouter() {
 
 	return (
 		<Switch>
+			<Route path="/quick-capture">
+				<QuickCapture />
+			</Route>
 			<Route path="/quick-note">
 				<QuickNote />
 			</Route>
@@ -44,7 +48,7 @@ function AppRouter() {
 			</Route>
 			<Route path="/">
 				<App />
-				<SystemTray />
+				{label === "main" && <SystemTray />}
 			</Route>
 			<Route>Not Found</Route>
 		</Switch>
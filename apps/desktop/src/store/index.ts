@@ -11,8 +11,8 @@ import type {
 } from "@mdit/store/core"
 import { invoke } from "@tauri-apps/api/core"
 import { open } from "@tauri-apps/plugin-dialog"
-import { readTextFile, rename as renameFile } from "@tauri-apps/plugin-fs"
 import { toast } from "sonner"
+import { readNoteText } from "@/lib/cloud-files"
 import {
 	deleteCredential,
 	getCredential,
@@ -47,6 +47,8 @@ const browserStorage =
 			}
 		: globalThis.localStorage
 
+const fileSystemRepository = new FileSystemRepository()
+
 const frontmatterUtils: FrontmatterUtils = {
 	updateFileFrontmatter,
 	renameFileFrontmatterProperty,
@@ -78,18 +80,24 @@ export const useStore = createMditStore({
 		createIndexingPort: createTauriIndexingPort,
 	},
 	tab: {
-		readTextFile,
-		renameFile,
+		readTextFile: readNoteText,
+		renameFile: (oldPath, newPath) =>
+			fileSystemRepository.rename(oldPath, newPath),
 		saveSettings,
 	},
 	ui: {
 		preferences: new UserSettingsRepository(),
 	},
 	workspace: {
-		fileSystemRepository: new FileSystemRepository(),
+		fileSystemRepository,
 		settingsRepository: new WorkspaceSettingsRepository(),
 		historyRepository: new WorkspaceHistoryRepository(),
 		openDialog: async (options) => {
+			// Folders go through the backend picker, which is what lets the app
+			// register them as vaults.
+			if (options.directory) {
+				return fileSystemRepository.pickAllowedFolder(options.title)
+			}
 			const result = await open(options)
 			return typeof result === "string" ? result : null
 		},
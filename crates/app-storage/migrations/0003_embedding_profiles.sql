@@ -0,0 +1,5 @@
+ALTER TABLE `vault` ADD COLUMN `fallback_embedding_provider` text;
+--> statement-breakpoint
+ALTER TABLE `vault` ADD COLUMN `fallback_embedding_model` text;
+--> statement-breakpoint
+ALTER TABLE `doc` ADD COLUMN `last_embedding_provider` text;
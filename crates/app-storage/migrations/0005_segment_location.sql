@@ -0,0 +1,5 @@
+ALTER TABLE `segment` ADD COLUMN `start_offset` integer;
+--> statement-breakpoint
+ALTER TABLE `segment` ADD COLUMN `end_offset` integer;
+--> statement-breakpoint
+ALTER TABLE `segment` ADD COLUMN `heading_path` text;
@@ -0,0 +1 @@
+ALTER TABLE `doc` ADD COLUMN `language` text;
@@ -0,0 +1 @@
+ALTER TABLE `vault` ADD COLUMN `indexing_excluded_frontmatter_keys` text;
\ No newline at end of file
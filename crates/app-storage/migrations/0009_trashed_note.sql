@@ -0,0 +1,10 @@
+CREATE TABLE `trashed_note` (
+	`id` integer PRIMARY KEY AUTOINCREMENT NOT NULL,
+	`vault_id` integer NOT NULL,
+	`original_path` text NOT NULL,
+	`trash_location` text,
+	`trashed_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE INDEX `idx_trashed_note_vault` ON `trashed_note` (`vault_id`,`trashed_at`);
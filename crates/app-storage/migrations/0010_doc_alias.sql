@@ -0,0 +1,10 @@
+CREATE TABLE `doc_alias` (
+	`doc_id` integer NOT NULL,
+	`alias` text NOT NULL,
+	`normalized_alias` text NOT NULL,
+	FOREIGN KEY (`doc_id`) REFERENCES `doc`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `uniq_doc_alias_doc_normalized` ON `doc_alias` (`doc_id`,`normalized_alias`);
+--> statement-breakpoint
+CREATE INDEX `idx_doc_alias_normalized_doc` ON `doc_alias` (`normalized_alias`,`doc_id`);
@@ -0,0 +1,9 @@
+CREATE TABLE `embedding_provider` (
+	`name` text PRIMARY KEY NOT NULL,
+	`base_url` text NOT NULL,
+	`api_key_ref` text,
+	`request_template` text NOT NULL,
+	`response_path` text NOT NULL,
+	`dimension` integer,
+	`updated_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
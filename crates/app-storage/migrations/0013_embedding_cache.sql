@@ -0,0 +1,16 @@
+CREATE TABLE `embedding_cache` (
+	`content_hash` text NOT NULL,
+	`provider` text NOT NULL,
+	`model` text NOT NULL,
+	`dim` integer NOT NULL,
+	`vector` blob NOT NULL,
+	`last_used_at` integer NOT NULL,
+	PRIMARY KEY(`content_hash`, `provider`, `model`, `dim`)
+);
+--> statement-breakpoint
+CREATE INDEX `embedding_cache_last_used_at_idx` ON `embedding_cache` (`last_used_at`);
+--> statement-breakpoint
+CREATE TABLE `embedding_cache_config` (
+	`id` integer PRIMARY KEY NOT NULL CHECK (`id` = 1),
+	`max_entries` integer NOT NULL
+);
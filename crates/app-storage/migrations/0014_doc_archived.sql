@@ -0,0 +1 @@
+ALTER TABLE `doc` ADD COLUMN `archived` integer DEFAULT 0 NOT NULL;
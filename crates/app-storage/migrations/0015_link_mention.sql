@@ -0,0 +1,11 @@
+CREATE TABLE `link_mention` (
+	`link_id` integer NOT NULL,
+	`start_offset` integer NOT NULL,
+	`end_offset` integer NOT NULL,
+	`line` integer NOT NULL,
+	`context` text NOT NULL,
+	`context_start` integer NOT NULL,
+	FOREIGN KEY (`link_id`) REFERENCES `link`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE INDEX `idx_link_mention_link` ON `link_mention` (`link_id`,`start_offset`);
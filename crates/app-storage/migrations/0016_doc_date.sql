@@ -0,0 +1,12 @@
+ALTER TABLE `doc` ADD COLUMN `source_created_ns` integer;
+--> statement-breakpoint
+CREATE TABLE `doc_date` (
+	`doc_id` integer NOT NULL,
+	`property` text NOT NULL,
+	`date` text NOT NULL,
+	FOREIGN KEY (`doc_id`) REFERENCES `doc`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `uniq_doc_date_doc_property` ON `doc_date` (`doc_id`,`property`);
+--> statement-breakpoint
+CREATE INDEX `idx_doc_date_date_doc` ON `doc_date` (`date`,`doc_id`);
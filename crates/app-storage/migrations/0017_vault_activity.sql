@@ -0,0 +1,10 @@
+ALTER TABLE `doc` ADD COLUMN `last_activity_day` text;
+--> statement-breakpoint
+CREATE TABLE `vault_activity_day` (
+	`vault_id` integer NOT NULL,
+	`day` text NOT NULL,
+	`notes_touched` integer DEFAULT 0 NOT NULL,
+	`bytes_added` integer DEFAULT 0 NOT NULL,
+	PRIMARY KEY(`vault_id`, `day`),
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
@@ -0,0 +1,29 @@
+CREATE TABLE `vault_bibliography` (
+	`vault_id` integer PRIMARY KEY NOT NULL,
+	`rel_path` text NOT NULL,
+	`source_size` integer,
+	`source_mtime_ns` integer,
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE TABLE `citation_entry` (
+	`vault_id` integer NOT NULL,
+	`citation_key` text NOT NULL,
+	`entry_type` text NOT NULL,
+	`title` text,
+	`authors` text NOT NULL,
+	`year` text,
+	`container` text,
+	PRIMARY KEY(`vault_id`, `citation_key`),
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE TABLE `doc_citation` (
+	`doc_id` integer NOT NULL,
+	`citation_key` text NOT NULL,
+	FOREIGN KEY (`doc_id`) REFERENCES `doc`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `uniq_doc_citation_doc_key` ON `doc_citation` (`doc_id`,`citation_key`);
+--> statement-breakpoint
+CREATE INDEX `idx_doc_citation_key_doc` ON `doc_citation` (`citation_key`,`doc_id`);
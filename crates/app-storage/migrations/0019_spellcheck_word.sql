@@ -0,0 +1,7 @@
+CREATE TABLE `spellcheck_word` (
+	`vault_id` integer NOT NULL,
+	`word` text NOT NULL,
+	`created_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+	PRIMARY KEY(`vault_id`, `word`),
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
@@ -0,0 +1,20 @@
+CREATE TABLE `replace_journal` (
+	`id` integer PRIMARY KEY AUTOINCREMENT NOT NULL,
+	`vault_id` integer NOT NULL,
+	`pattern` text NOT NULL,
+	`replacement` text NOT NULL,
+	`created_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE INDEX `idx_replace_journal_vault` ON `replace_journal` (`vault_id`,`id`);
+--> statement-breakpoint
+CREATE TABLE `replace_journal_file` (
+	`journal_id` integer NOT NULL,
+	`rel_path` text NOT NULL,
+	`old_hash` text NOT NULL,
+	`new_hash` text NOT NULL,
+	`edits` text NOT NULL,
+	PRIMARY KEY(`journal_id`, `rel_path`),
+	FOREIGN KEY (`journal_id`) REFERENCES `replace_journal`(`id`) ON UPDATE no action ON DELETE cascade
+);
@@ -0,0 +1,10 @@
+CREATE TABLE `search_history` (
+	`vault_id` integer NOT NULL,
+	`query` text NOT NULL,
+	`search_count` integer DEFAULT 1 NOT NULL,
+	`last_searched_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+	PRIMARY KEY(`vault_id`, `query`),
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE INDEX `idx_search_history_recent` ON `search_history` (`vault_id`,`last_searched_at`);
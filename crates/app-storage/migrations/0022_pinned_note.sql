@@ -0,0 +1,8 @@
+CREATE TABLE `pinned_note` (
+	`vault_id` integer NOT NULL,
+	`rel_path` text NOT NULL,
+	`position` integer NOT NULL,
+	`pinned_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+	PRIMARY KEY(`vault_id`, `rel_path`),
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
@@ -0,0 +1,20 @@
+CREATE TABLE `doc_tombstone` (
+	`doc_id` integer PRIMARY KEY NOT NULL,
+	`vault_id` integer NOT NULL,
+	`rel_path` text NOT NULL,
+	`last_hash` text,
+	`deleted_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE INDEX `idx_doc_tombstone_vault_path` ON `doc_tombstone` (`vault_id`,`rel_path`);
+--> statement-breakpoint
+CREATE INDEX `idx_doc_tombstone_vault_hash` ON `doc_tombstone` (`vault_id`,`last_hash`);
+--> statement-breakpoint
+CREATE TABLE `doc_tombstone_link` (
+	`doc_id` integer NOT NULL,
+	`link_id` integer NOT NULL,
+	PRIMARY KEY(`doc_id`, `link_id`),
+	FOREIGN KEY (`doc_id`) REFERENCES `doc_tombstone`(`doc_id`) ON UPDATE no action ON DELETE cascade,
+	FOREIGN KEY (`link_id`) REFERENCES `link`(`id`) ON UPDATE no action ON DELETE cascade
+);
@@ -0,0 +1,10 @@
+CREATE TABLE `note_identity` (
+	`vault_id` integer NOT NULL,
+	`note_id` text NOT NULL,
+	`rel_path` text NOT NULL,
+	`created_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+	PRIMARY KEY(`vault_id`, `note_id`),
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `uniq_note_identity_vault_rel_path` ON `note_identity` (`vault_id`,`rel_path`);
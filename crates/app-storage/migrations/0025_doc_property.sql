@@ -0,0 +1,11 @@
+CREATE TABLE `doc_property` (
+	`doc_id` integer NOT NULL,
+	`key` text NOT NULL,
+	`value` text NOT NULL,
+	`sort_value` numeric,
+	FOREIGN KEY (`doc_id`) REFERENCES `doc`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `uniq_doc_property_doc_key` ON `doc_property` (`doc_id`,`key`);
+--> statement-breakpoint
+CREATE INDEX `idx_doc_property_key_doc` ON `doc_property` (`key`,`doc_id`);
@@ -0,0 +1,8 @@
+CREATE TABLE `doc_query_source` (
+	`doc_id` integer NOT NULL,
+	`source_kind` text NOT NULL,
+	`source_value` text NOT NULL,
+	FOREIGN KEY (`doc_id`) REFERENCES `doc`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `uniq_doc_query_source_doc_source` ON `doc_query_source` (`doc_id`,`source_kind`,`source_value`);
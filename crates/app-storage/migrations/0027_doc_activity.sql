@@ -0,0 +1,16 @@
+CREATE TABLE `doc_activity` (
+	`id` integer PRIMARY KEY AUTOINCREMENT NOT NULL,
+	`vault_id` integer NOT NULL,
+	`doc_id` integer,
+	`rel_path` text NOT NULL,
+	`day` text NOT NULL,
+	`words_added` integer DEFAULT 0 NOT NULL,
+	`words_removed` integer DEFAULT 0 NOT NULL,
+	`word_count` integer DEFAULT 0 NOT NULL,
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade,
+	FOREIGN KEY (`doc_id`) REFERENCES `doc`(`id`) ON UPDATE no action ON DELETE set null
+);
+--> statement-breakpoint
+CREATE INDEX `idx_doc_activity_vault_day` ON `doc_activity` (`vault_id`,`day`);
+--> statement-breakpoint
+CREATE INDEX `idx_doc_activity_doc` ON `doc_activity` (`doc_id`);
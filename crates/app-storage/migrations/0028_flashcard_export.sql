@@ -0,0 +1,8 @@
+CREATE TABLE `flashcard_export` (
+	`vault_id` integer NOT NULL,
+	`rel_path` text NOT NULL,
+	`card_hash` text NOT NULL,
+	`exported_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+	PRIMARY KEY(`vault_id`, `rel_path`, `card_hash`),
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
@@ -0,0 +1,20 @@
+CREATE TABLE `feed_subscription` (
+	`id` integer PRIMARY KEY AUTOINCREMENT NOT NULL,
+	`vault_id` integer NOT NULL,
+	`url` text NOT NULL,
+	`title` text NOT NULL,
+	`created_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+	`last_fetched_at` text,
+	`last_error` text,
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE UNIQUE INDEX `uniq_feed_subscription_vault_url` ON `feed_subscription` (`vault_id`,`url`);
+--> statement-breakpoint
+CREATE TABLE `feed_item` (
+	`feed_id` integer NOT NULL,
+	`guid` text NOT NULL,
+	`fetched_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+	PRIMARY KEY(`feed_id`, `guid`),
+	FOREIGN KEY (`feed_id`) REFERENCES `feed_subscription`(`id`) ON UPDATE no action ON DELETE cascade
+);
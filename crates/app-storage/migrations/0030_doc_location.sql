@@ -0,0 +1,9 @@
+CREATE TABLE `doc_location` (
+	`doc_id` integer PRIMARY KEY NOT NULL,
+	`lat` real NOT NULL,
+	`lng` real NOT NULL,
+	`name` text,
+	FOREIGN KEY (`doc_id`) REFERENCES `doc`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE INDEX `idx_doc_location_lat_lng` ON `doc_location` (`lat`,`lng`);
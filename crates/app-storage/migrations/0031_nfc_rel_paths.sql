@@ -0,0 +1,130 @@
+ALTER TABLE `vault` ADD COLUMN `workspace_key` text;
+--> statement-breakpoint
+UPDATE `vault` SET `workspace_key` = nfc(`workspace_root`);
+--> statement-breakpoint
+CREATE TABLE `vault_nfc_merge` (
+	`from_id` integer PRIMARY KEY NOT NULL,
+	`to_id` integer NOT NULL
+);
+--> statement-breakpoint
+INSERT INTO `vault_nfc_merge` (`from_id`, `to_id`)
+SELECT `vault`.`id`, (
+	SELECT `other`.`id` FROM `vault` AS `other`
+	WHERE `other`.`workspace_key` = `vault`.`workspace_key`
+	ORDER BY `other`.`workspace_root` = `other`.`workspace_key` DESC, `other`.`id`
+	LIMIT 1
+) FROM `vault`;
+--> statement-breakpoint
+DELETE FROM `vault_nfc_merge` WHERE `from_id` = `to_id`;
+--> statement-breakpoint
+UPDATE OR IGNORE `doc` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `doc`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `trashed_note` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `trashed_note`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `vault_setting` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `vault_setting`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `vault_activity_day` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `vault_activity_day`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `vault_bibliography` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `vault_bibliography`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `citation_entry` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `citation_entry`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `spellcheck_word` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `spellcheck_word`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `replace_journal` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `replace_journal`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `search_history` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `search_history`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `pinned_note` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `pinned_note`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `doc_tombstone` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `doc_tombstone`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `note_identity` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `note_identity`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `doc_activity` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `doc_activity`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `flashcard_export` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `flashcard_export`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+UPDATE OR IGNORE `feed_subscription` SET `vault_id` = (
+	SELECT `to_id` FROM `vault_nfc_merge` WHERE `from_id` = `feed_subscription`.`vault_id`
+) WHERE `vault_id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+DELETE FROM `vault` WHERE `id` IN (SELECT `from_id` FROM `vault_nfc_merge`);
+--> statement-breakpoint
+DROP TABLE `vault_nfc_merge`;
+--> statement-breakpoint
+DROP INDEX `vault_workspace_root_unique`;
+--> statement-breakpoint
+CREATE UNIQUE INDEX `vault_workspace_key_unique` ON `vault` (`workspace_key`);
+--> statement-breakpoint
+DELETE FROM `doc` WHERE `rel_path` <> nfc(`rel_path`) AND EXISTS (
+	SELECT 1 FROM `doc` AS `other`
+	WHERE `other`.`vault_id` = `doc`.`vault_id`
+		AND `other`.`id` <> `doc`.`id`
+		AND nfc(`other`.`rel_path`) = nfc(`doc`.`rel_path`)
+		AND (`other`.`rel_path` = nfc(`other`.`rel_path`) OR `other`.`id` < `doc`.`id`)
+);
+--> statement-breakpoint
+UPDATE `doc` SET `rel_path` = nfc(`rel_path`) WHERE `rel_path` <> nfc(`rel_path`);
+--> statement-breakpoint
+UPDATE OR IGNORE `link` SET `target_path` = nfc(`target_path`) WHERE `target_path` <> nfc(`target_path`);
+--> statement-breakpoint
+DELETE FROM `link` WHERE `target_path` <> nfc(`target_path`);
+--> statement-breakpoint
+UPDATE OR IGNORE `wiki_link_ref` SET `query_key` = nfc(`query_key`) WHERE `query_key` <> nfc(`query_key`);
+--> statement-breakpoint
+DELETE FROM `wiki_link_ref` WHERE `query_key` <> nfc(`query_key`);
+--> statement-breakpoint
+UPDATE `doc` SET `last_hash` = NULL WHERE `id` IN (
+	SELECT `source_doc_id` FROM `link` WHERE `target_doc_id` IS NULL
+);
+--> statement-breakpoint
+UPDATE OR IGNORE `pinned_note` SET `rel_path` = nfc(`rel_path`) WHERE `rel_path` <> nfc(`rel_path`);
+--> statement-breakpoint
+DELETE FROM `pinned_note` WHERE `rel_path` <> nfc(`rel_path`);
+--> statement-breakpoint
+UPDATE OR IGNORE `note_identity` SET `rel_path` = nfc(`rel_path`) WHERE `rel_path` <> nfc(`rel_path`);
+--> statement-breakpoint
+DELETE FROM `note_identity` WHERE `rel_path` <> nfc(`rel_path`);
+--> statement-breakpoint
+UPDATE OR IGNORE `flashcard_export` SET `rel_path` = nfc(`rel_path`) WHERE `rel_path` <> nfc(`rel_path`);
+--> statement-breakpoint
+DELETE FROM `flashcard_export` WHERE `rel_path` <> nfc(`rel_path`);
+--> statement-breakpoint
+UPDATE OR IGNORE `replace_journal_file` SET `rel_path` = nfc(`rel_path`) WHERE `rel_path` <> nfc(`rel_path`);
+--> statement-breakpoint
+DELETE FROM `replace_journal_file` WHERE `rel_path` <> nfc(`rel_path`);
+--> statement-breakpoint
+UPDATE `doc_tombstone` SET `rel_path` = nfc(`rel_path`) WHERE `rel_path` <> nfc(`rel_path`);
+--> statement-breakpoint
+UPDATE `doc_activity` SET `rel_path` = nfc(`rel_path`) WHERE `rel_path` <> nfc(`rel_path`);
+--> statement-breakpoint
+UPDATE `vault_bibliography` SET `rel_path` = nfc(`rel_path`) WHERE `rel_path` <> nfc(`rel_path`);
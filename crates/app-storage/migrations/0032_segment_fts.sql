@@ -0,0 +1,23 @@
+ALTER TABLE `segment` ADD `text` text DEFAULT '' NOT NULL;
+--> statement-breakpoint
+CREATE VIRTUAL TABLE `segment_fts` USING fts5(
+	`text`,
+	content='segment',
+	content_rowid='id',
+	tokenize='unicode61'
+);
+--> statement-breakpoint
+CREATE TRIGGER `segment_ai` AFTER INSERT ON `segment` BEGIN
+	INSERT INTO `segment_fts`(`rowid`,`text`) VALUES (new.`id`, new.`text`);
+END;
+--> statement-breakpoint
+CREATE TRIGGER `segment_ad` AFTER DELETE ON `segment` BEGIN
+	INSERT INTO `segment_fts`(`segment_fts`,`rowid`,`text`) VALUES ('delete', old.`id`, old.`text`);
+END;
+--> statement-breakpoint
+CREATE TRIGGER `segment_au` AFTER UPDATE OF `text` ON `segment` BEGIN
+	INSERT INTO `segment_fts`(`segment_fts`,`rowid`,`text`) VALUES ('delete', old.`id`, old.`text`);
+	INSERT INTO `segment_fts`(`rowid`,`text`) VALUES (new.`id`, new.`text`);
+END;
+--> statement-breakpoint
+UPDATE `doc` SET `last_hash` = NULL WHERE `id` IN (SELECT `doc_id` FROM `segment`);
@@ -0,0 +1,5 @@
+CREATE TABLE `vault_fts_tokenizer` (
+	`vault_id` integer PRIMARY KEY NOT NULL,
+	`tokenizer` text NOT NULL,
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
@@ -0,0 +1,16 @@
+CREATE TABLE `search_synonym` (
+	`vault_id` integer NOT NULL,
+	`term` text NOT NULL,
+	`synonym` text NOT NULL,
+	`created_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+	PRIMARY KEY(`vault_id`, `term`, `synonym`),
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
+--> statement-breakpoint
+CREATE TABLE `search_stopword` (
+	`vault_id` integer NOT NULL,
+	`word` text NOT NULL,
+	`created_at` text NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+	PRIMARY KEY(`vault_id`, `word`),
+	FOREIGN KEY (`vault_id`) REFERENCES `vault`(`id`) ON UPDATE no action ON DELETE cascade
+);
@@ -0,0 +1,3 @@
+ALTER TABLE `doc` ADD `minhash` blob;
+--> statement-breakpoint
+UPDATE `doc` SET `last_hash` = NULL;
@@ -0,0 +1,26 @@
+ALTER TABLE `doc` ADD `searchable` integer DEFAULT 1 NOT NULL;
+--> statement-breakpoint
+ALTER TABLE `doc` ADD `embeddable` integer DEFAULT 1 NOT NULL;
+--> statement-breakpoint
+DROP TRIGGER `doc_ai`;
+--> statement-breakpoint
+DROP TRIGGER `doc_ad`;
+--> statement-breakpoint
+DROP TRIGGER `doc_au`;
+--> statement-breakpoint
+CREATE TRIGGER `doc_ai` AFTER INSERT ON `doc` WHEN new.`searchable` = 1 BEGIN
+	INSERT INTO `doc_fts`(`rowid`,`content`) VALUES (new.`id`, new.`content`);
+END;
+--> statement-breakpoint
+CREATE TRIGGER `doc_ad` AFTER DELETE ON `doc` WHEN old.`searchable` = 1 BEGIN
+	INSERT INTO `doc_fts`(`doc_fts`,`rowid`,`content`) VALUES ('delete', old.`id`, old.`content`);
+END;
+--> statement-breakpoint
+CREATE TRIGGER `doc_au` AFTER UPDATE OF `content`, `searchable` ON `doc` BEGIN
+	INSERT INTO `doc_fts`(`doc_fts`,`rowid`,`content`) SELECT 'delete', old.`id`, old.`content` WHERE old.`searchable` = 1;
+	INSERT INTO `doc_fts`(`rowid`,`content`) SELECT new.`id`, new.`content` WHERE new.`searchable` = 1;
+END;
+--> statement-breakpoint
+DELETE FROM `vault_fts_tokenizer`;
+--> statement-breakpoint
+UPDATE `doc` SET `last_hash` = NULL;
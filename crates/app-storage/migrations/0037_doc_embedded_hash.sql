@@ -0,0 +1,3 @@
+ALTER TABLE `doc` ADD `last_embedded_hash` text;
+--> statement-breakpoint
+UPDATE `doc` SET `last_embedded_hash` = `last_hash` WHERE `last_embedding_model` IS NOT NULL AND `id` NOT IN (SELECT `doc_id` FROM `pending_embedding`);
@@ -0,0 +1,15 @@
+ALTER TABLE `doc` ADD `inbound_link_count` integer DEFAULT 0 NOT NULL;
+--> statement-breakpoint
+UPDATE `doc` SET `inbound_link_count` = (SELECT COUNT(DISTINCT `source_doc_id`) FROM `link` WHERE `target_doc_id` = `doc`.`id`);
+--> statement-breakpoint
+CREATE TRIGGER `link_inbound_ai` AFTER INSERT ON `link` WHEN new.`target_doc_id` IS NOT NULL BEGIN
+	UPDATE `doc` SET `inbound_link_count` = (SELECT COUNT(DISTINCT `source_doc_id`) FROM `link` WHERE `target_doc_id` = new.`target_doc_id`) WHERE `id` = new.`target_doc_id`;
+END;
+--> statement-breakpoint
+CREATE TRIGGER `link_inbound_ad` AFTER DELETE ON `link` WHEN old.`target_doc_id` IS NOT NULL BEGIN
+	UPDATE `doc` SET `inbound_link_count` = (SELECT COUNT(DISTINCT `source_doc_id`) FROM `link` WHERE `target_doc_id` = old.`target_doc_id`) WHERE `id` = old.`target_doc_id`;
+END;
+--> statement-breakpoint
+CREATE TRIGGER `link_inbound_au` AFTER UPDATE OF `target_doc_id` ON `link` WHEN old.`target_doc_id` IS NOT new.`target_doc_id` BEGIN
+	UPDATE `doc` SET `inbound_link_count` = (SELECT COUNT(DISTINCT `source_doc_id`) FROM `link` WHERE `target_doc_id` = `doc`.`id`) WHERE `id` IN (old.`target_doc_id`, new.`target_doc_id`);
+END;
@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::vault::open_vault_connection;
+
+/// Cached vectors kept while no limit has been configured. At 768 dimensions
+/// this is roughly 150 MB.
+pub const DEFAULT_EMBEDDING_CACHE_MAX_ENTRIES: usize = 50_000;
+
+pub fn get_embedding_cache_max_entries(db_path: &Path) -> Result<usize> {
+    let conn = open_vault_connection(db_path)?;
+    load_embedding_cache_max_entries(&conn)
+}
+
+/// Stores the limit and evicts the least recently used vectors above it.
+/// Zero turns the cache off.
+pub fn set_embedding_cache_max_entries(db_path: &Path, max_entries: usize) -> Result<()> {
+    let conn = open_vault_connection(db_path)?;
+    conn.execute(
+        "INSERT INTO embedding_cache_config (id, max_entries) VALUES (1, ?1)
+         ON CONFLICT(id) DO UPDATE SET max_entries = excluded.max_entries",
+        params![max_entries as i64],
+    )
+    .context("Failed to save embedding cache limit")?;
+
+    prune_embedding_cache(&conn, max_entries)?;
+    Ok(())
+}
+
+/// Returns the number of vectors removed.
+pub fn clear_embedding_cache(db_path: &Path) -> Result<usize> {
+    let conn = open_vault_connection(db_path)?;
+    conn.execute("DELETE FROM embedding_cache", [])
+        .context("Failed to clear embedding cache")
+}
+
+pub fn load_embedding_cache_max_entries(conn: &Connection) -> Result<usize> {
+    let max_entries = conn
+        .query_row(
+            "SELECT max_entries FROM embedding_cache_config WHERE id = 1",
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .optional()
+        .context("Failed to load embedding cache limit")?;
+
+    Ok(
+        max_entries.map_or(DEFAULT_EMBEDDING_CACHE_MAX_ENTRIES, |max_entries| {
+            max_entries.max(0) as usize
+        }),
+    )
+}
+
+/// Keeps the `max_entries` most recently used vectors and returns the number
+/// of vectors removed.
+pub fn prune_embedding_cache(conn: &Connection, max_entries: usize) -> Result<usize> {
+    conn.execute(
+        "DELETE FROM embedding_cache WHERE rowid IN (
+             SELECT rowid FROM embedding_cache
+             ORDER BY last_used_at DESC
+             LIMIT -1 OFFSET ?1
+         )",
+        params![max_entries as i64],
+    )
+    .context("Failed to prune embedding cache")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        clear_embedding_cache, get_embedding_cache_max_entries, set_embedding_cache_max_entries,
+        DEFAULT_EMBEDDING_CACHE_MAX_ENTRIES,
+    };
+    use crate::{migrations, vault::open_vault_connection};
+    use rusqlite::params;
+    use std::{
+        fs,
+        path::PathBuf,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    struct CacheHarness {
+        root: PathBuf,
+        db_path: PathBuf,
+    }
+
+    impl CacheHarness {
+        fn new(prefix: &str) -> Self {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock error")
+                .as_nanos();
+            let root = std::env::temp_dir().join(format!("{prefix}-{nanos}"));
+            let db_path = root.join("cache-test.sqlite");
+            migrations::run_migrations_at(&db_path).expect("failed to run test migrations");
+            Self { root, db_path }
+        }
+
+        fn insert(&self, content_hash: &str, last_used_at: i64) {
+            open_vault_connection(&self.db_path)
+                .unwrap()
+                .execute(
+                    "INSERT INTO embedding_cache
+                         (content_hash, provider, model, dim, vector, last_used_at)
+                     VALUES (?1, 'ollama', 'nomic-embed-text', 1, x'0000803f', ?2)",
+                    params![content_hash, last_used_at],
+                )
+                .expect("failed to insert cache entry");
+        }
+
+        fn hashes(&self) -> Vec<String> {
+            let conn = open_vault_connection(&self.db_path).unwrap();
+            let mut stmt = conn
+                .prepare("SELECT content_hash FROM embedding_cache ORDER BY content_hash")
+                .unwrap();
+            let rows = stmt.query_map([], |row| row.get(0)).unwrap();
+            rows.collect::<rusqlite::Result<_>>().unwrap()
+        }
+    }
+
+    impl Drop for CacheHarness {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn lowering_the_limit_evicts_least_recently_used_vectors() {
+        let harness = CacheHarness::new("mdit-embedding-cache-limit");
+        assert_eq!(
+            get_embedding_cache_max_entries(&harness.db_path).unwrap(),
+            DEFAULT_EMBEDDING_CACHE_MAX_ENTRIES
+        );
+
+        harness.insert("a", 30);
+        harness.insert("b", 10);
+        harness.insert("c", 20);
+        set_embedding_cache_max_entries(&harness.db_path, 2).expect("should save limit");
+
+        assert_eq!(
+            get_embedding_cache_max_entries(&harness.db_path).unwrap(),
+            2
+        );
+        assert_eq!(harness.hashes(), vec!["a".to_string(), "c".to_string()]);
+
+        assert_eq!(clear_embedding_cache(&harness.db_path).unwrap(), 2);
+        assert!(harness.hashes().is_empty());
+    }
+}
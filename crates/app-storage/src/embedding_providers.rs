@@ -0,0 +1,288 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+
+use crate::vault::open_vault_connection;
+
+/// Providers built into the indexer; configured providers cannot shadow them.
+pub const BUILTIN_EMBEDDING_PROVIDERS: &[&str] = &["ollama"];
+
+/// An HTTP embedding API described by data instead of code, so any service
+/// that takes JSON in and returns a vector can be used for indexing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingProviderConfig {
+    /// Lowercase name that vault embedding settings refer to.
+    pub name: String,
+    /// Endpoint the request is POSTed to.
+    pub base_url: String,
+    /// Name of the environment variable holding the API key, sent as a bearer
+    /// token. The key itself is never stored.
+    #[serde(default)]
+    pub api_key_ref: Option<String>,
+    /// JSON body with `{{model}}`, `{{input}}` and `{{dimension}}` placeholders.
+    #[serde(default = "default_request_template")]
+    pub request_template: JsonValue,
+    /// Dot separated path to the vector in the response, e.g. `data.0.embedding`.
+    #[serde(default = "default_response_path")]
+    pub response_path: String,
+    /// Vector size to request and expect. When unset it is probed from a test
+    /// embedding.
+    #[serde(default)]
+    pub dimension: Option<i32>,
+}
+
+/// The OpenAI embeddings request, which most hosted providers accept.
+pub fn default_request_template() -> JsonValue {
+    json!({ "model": "{{model}}", "input": "{{input}}" })
+}
+
+pub fn default_response_path() -> String {
+    "data.0.embedding".to_string()
+}
+
+impl EmbeddingProviderConfig {
+    fn normalized(mut self) -> Result<Self> {
+        self.name = self.name.trim().to_lowercase();
+        self.base_url = self.base_url.trim().to_string();
+        self.api_key_ref = self
+            .api_key_ref
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
+        self.response_path = self.response_path.trim().to_string();
+
+        if self.name.is_empty() {
+            return Err(anyhow!("Embedding provider name must be provided"));
+        }
+        if BUILTIN_EMBEDDING_PROVIDERS.contains(&self.name.as_str()) {
+            return Err(anyhow!(
+                "'{}' is a built-in embedding provider and cannot be configured",
+                self.name
+            ));
+        }
+        if !self.base_url.starts_with("http://") && !self.base_url.starts_with("https://") {
+            return Err(anyhow!(
+                "Embedding provider URL must start with http:// or https://"
+            ));
+        }
+        if !self.request_template.is_object() {
+            return Err(anyhow!("Embedding request template must be a JSON object"));
+        }
+        if self.response_path.is_empty() {
+            return Err(anyhow!("Embedding response path must be provided"));
+        }
+        if self.dimension.is_some_and(|dimension| dimension <= 0) {
+            return Err(anyhow!("Embedding dimension must be positive"));
+        }
+
+        Ok(self)
+    }
+}
+
+pub fn list_embedding_providers(db_path: &Path) -> Result<Vec<EmbeddingProviderConfig>> {
+    let conn = open_vault_connection(db_path)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT name, base_url, api_key_ref, request_template, response_path, dimension
+             FROM embedding_provider ORDER BY name",
+        )
+        .context("Failed to prepare embedding provider query")?;
+    let rows = stmt
+        .query_map([], read_provider_row)
+        .context("Failed to load embedding providers")?;
+
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read embedding providers")
+}
+
+pub fn get_embedding_provider(
+    db_path: &Path,
+    name: &str,
+) -> Result<Option<EmbeddingProviderConfig>> {
+    let conn = open_vault_connection(db_path)?;
+    load_embedding_provider(&conn, &name.trim().to_lowercase())
+}
+
+/// Validates and stores `config`, replacing any provider with the same name.
+pub fn save_embedding_provider(
+    db_path: &Path,
+    config: EmbeddingProviderConfig,
+) -> Result<EmbeddingProviderConfig> {
+    let config = config.normalized()?;
+    let conn = open_vault_connection(db_path)?;
+    conn.execute(
+        "INSERT INTO embedding_provider
+             (name, base_url, api_key_ref, request_template, response_path, dimension)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(name) DO UPDATE SET
+             base_url = excluded.base_url,
+             api_key_ref = excluded.api_key_ref,
+             request_template = excluded.request_template,
+             response_path = excluded.response_path,
+             dimension = excluded.dimension,
+             updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')",
+        params![
+            config.name,
+            config.base_url,
+            config.api_key_ref,
+            config.request_template.to_string(),
+            config.response_path,
+            config.dimension,
+        ],
+    )
+    .context("Failed to save embedding provider")?;
+
+    load_embedding_provider(&conn, &config.name)?
+        .ok_or_else(|| anyhow!("Embedding provider '{}' was not saved", config.name))
+}
+
+/// Returns whether a provider was removed.
+pub fn delete_embedding_provider(db_path: &Path, name: &str) -> Result<bool> {
+    let conn = open_vault_connection(db_path)?;
+    let deleted = conn
+        .execute(
+            "DELETE FROM embedding_provider WHERE name = ?1",
+            params![name.trim().to_lowercase()],
+        )
+        .context("Failed to delete embedding provider")?;
+    Ok(deleted > 0)
+}
+
+fn load_embedding_provider(
+    conn: &Connection,
+    name: &str,
+) -> Result<Option<EmbeddingProviderConfig>> {
+    conn.query_row(
+        "SELECT name, base_url, api_key_ref, request_template, response_path, dimension
+         FROM embedding_provider WHERE name = ?1",
+        params![name],
+        read_provider_row,
+    )
+    .optional()
+    .context("Failed to load embedding provider")
+}
+
+fn read_provider_row(row: &Row<'_>) -> rusqlite::Result<EmbeddingProviderConfig> {
+    let request_template = row.get::<_, String>(3)?;
+    Ok(EmbeddingProviderConfig {
+        name: row.get(0)?,
+        base_url: row.get(1)?,
+        api_key_ref: row.get(2)?,
+        request_template: serde_json::from_str(&request_template)
+            .unwrap_or_else(|_| default_request_template()),
+        response_path: row.get(4)?,
+        dimension: row.get(5)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        default_request_template, delete_embedding_provider, get_embedding_provider,
+        list_embedding_providers, save_embedding_provider, EmbeddingProviderConfig,
+    };
+    use crate::migrations;
+    use serde_json::json;
+    use std::{
+        fs,
+        path::PathBuf,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    struct ProviderHarness {
+        root: PathBuf,
+        db_path: PathBuf,
+    }
+
+    impl ProviderHarness {
+        fn new(prefix: &str) -> Self {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock error")
+                .as_nanos();
+            let root = std::env::temp_dir().join(format!("{prefix}-{nanos}"));
+            let db_path = root.join("providers-test.sqlite");
+            migrations::run_migrations_at(&db_path).expect("failed to run test migrations");
+            Self { root, db_path }
+        }
+    }
+
+    impl Drop for ProviderHarness {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    fn voyage() -> EmbeddingProviderConfig {
+        EmbeddingProviderConfig {
+            name: " Voyage ".to_string(),
+            base_url: "https://api.voyageai.com/v1/embeddings".to_string(),
+            api_key_ref: Some("VOYAGE_API_KEY".to_string()),
+            request_template: default_request_template(),
+            response_path: "data.0.embedding".to_string(),
+            dimension: None,
+        }
+    }
+
+    #[test]
+    fn providers_round_trip_by_normalized_name() {
+        let harness = ProviderHarness::new("mdit-embedding-providers-roundtrip");
+
+        let saved = save_embedding_provider(&harness.db_path, voyage()).expect("should save");
+        assert_eq!(saved.name, "voyage");
+
+        let updated = save_embedding_provider(
+            &harness.db_path,
+            EmbeddingProviderConfig {
+                request_template: json!({ "model": "{{model}}", "texts": ["{{input}}"] }),
+                response_path: "embeddings.0".to_string(),
+                dimension: Some(1024),
+                ..voyage()
+            },
+        )
+        .expect("should update");
+        assert_eq!(
+            list_embedding_providers(&harness.db_path).expect("should list"),
+            vec![updated.clone()]
+        );
+        assert_eq!(
+            get_embedding_provider(&harness.db_path, "VOYAGE").expect("should load"),
+            Some(updated)
+        );
+
+        assert!(delete_embedding_provider(&harness.db_path, "voyage").expect("should delete"));
+        assert!(!delete_embedding_provider(&harness.db_path, "voyage").expect("should delete"));
+    }
+
+    #[test]
+    fn save_embedding_provider_rejects_invalid_configs() {
+        let harness = ProviderHarness::new("mdit-embedding-providers-invalid");
+
+        for config in [
+            EmbeddingProviderConfig {
+                name: "Ollama".to_string(),
+                ..voyage()
+            },
+            EmbeddingProviderConfig {
+                base_url: "api.voyageai.com".to_string(),
+                ..voyage()
+            },
+            EmbeddingProviderConfig {
+                request_template: json!("{{input}}"),
+                ..voyage()
+            },
+            EmbeddingProviderConfig {
+                dimension: Some(0),
+                ..voyage()
+            },
+        ] {
+            assert!(save_embedding_provider(&harness.db_path, config).is_err());
+        }
+        assert!(list_embedding_providers(&harness.db_path)
+            .expect("should list")
+            .is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::vault::{ensure_workspace_exists, find_workspace_id, open_vault_connection};
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedSubscription {
+    pub id: i64,
+    pub url: String,
+    pub title: String,
+    pub created_at: String,
+    pub last_fetched_at: Option<String>,
+    /// Why the last fetch failed; cleared by the next one that succeeds.
+    pub last_error: Option<String>,
+}
+
+/// The vault's feeds, in the order they were subscribed.
+pub fn list_feed_subscriptions(
+    db_path: &Path,
+    workspace_root: &Path,
+) -> Result<Vec<FeedSubscription>> {
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, url, title, created_at, last_fetched_at, last_error
+             FROM feed_subscription WHERE vault_id = ?1 ORDER BY id",
+        )
+        .context("Failed to prepare feed subscription query")?;
+    let rows = stmt
+        .query_map(params![vault_id], read_subscription)
+        .context("Failed to load feed subscriptions")?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read feed subscription row")
+}
+
+/// Subscribes the vault to `url`. Subscribing again only updates the title.
+pub fn add_feed_subscription(
+    db_path: &Path,
+    workspace_root: &Path,
+    url: &str,
+    title: &str,
+) -> Result<FeedSubscription> {
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+
+    conn.execute(
+        "INSERT INTO feed_subscription (vault_id, url, title) VALUES (?1, ?2, ?3)
+         ON CONFLICT(vault_id, url) DO UPDATE SET title = excluded.title",
+        params![vault_id, url, title],
+    )
+    .context("Failed to save feed subscription")?;
+
+    conn.query_row(
+        "SELECT id, url, title, created_at, last_fetched_at, last_error
+         FROM feed_subscription WHERE vault_id = ?1 AND url = ?2",
+        params![vault_id, url],
+        read_subscription,
+    )
+    .context("Failed to load feed subscription")
+}
+
+/// Drops the subscription along with the record of the items it fetched.
+/// Notes made from those items stay. Returns whether it existed.
+pub fn remove_feed_subscription(
+    db_path: &Path,
+    workspace_root: &Path,
+    feed_id: i64,
+) -> Result<bool> {
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(false);
+    };
+
+    let removed = conn
+        .execute(
+            "DELETE FROM feed_subscription WHERE vault_id = ?1 AND id = ?2",
+            params![vault_id, feed_id],
+        )
+        .context("Failed to remove feed subscription")?;
+    Ok(removed > 0)
+}
+
+/// GUIDs of the items already turned into notes for the feed.
+pub fn seen_feed_item_guids(
+    db_path: &Path,
+    workspace_root: &Path,
+    feed_id: i64,
+) -> Result<HashSet<String>> {
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(HashSet::new());
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT item.guid FROM feed_item item
+             JOIN feed_subscription feed ON feed.id = item.feed_id
+             WHERE feed.vault_id = ?1 AND item.feed_id = ?2",
+        )
+        .context("Failed to prepare feed item query")?;
+    let rows = stmt
+        .query_map(params![vault_id, feed_id], |row| row.get::<_, String>(0))
+        .context("Failed to load feed items")?;
+    rows.collect::<rusqlite::Result<HashSet<_>>>()
+        .context("Failed to read feed item row")
+}
+
+/// Records a fetch of the feed: the GUIDs of the items it added and, when
+/// it failed, why.
+pub fn record_feed_fetch(
+    db_path: &Path,
+    workspace_root: &Path,
+    feed_id: i64,
+    new_guids: &[&str],
+    error: Option<&str>,
+) -> Result<()> {
+    let mut conn = open_vault_connection(db_path)?;
+    let vault_id = find_workspace_id(&conn, workspace_root)?
+        .ok_or_else(|| anyhow!("Vault is not registered: {}", workspace_root.display()))?;
+    ensure_feed_in_vault(&conn, vault_id, feed_id)?;
+
+    let tx = conn
+        .transaction()
+        .context("Failed to start feed fetch transaction")?;
+    tx.execute(
+        "UPDATE feed_subscription
+         SET last_fetched_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), last_error = ?2
+         WHERE id = ?1",
+        params![feed_id, error],
+    )
+    .context("Failed to update feed subscription")?;
+    {
+        let mut stmt = tx
+            .prepare("INSERT OR IGNORE INTO feed_item (feed_id, guid) VALUES (?1, ?2)")
+            .context("Failed to prepare feed item insert")?;
+        for guid in new_guids {
+            stmt.execute(params![feed_id, guid])
+                .context("Failed to record feed item")?;
+        }
+    }
+    tx.commit()
+        .context("Failed to commit feed fetch transaction")?;
+    Ok(())
+}
+
+fn ensure_feed_in_vault(conn: &Connection, vault_id: i64, feed_id: i64) -> Result<()> {
+    conn.query_row(
+        "SELECT 1 FROM feed_subscription WHERE vault_id = ?1 AND id = ?2",
+        params![vault_id, feed_id],
+        |_| Ok(()),
+    )
+    .optional()
+    .context("Failed to look up feed subscription")?
+    .ok_or_else(|| anyhow!("No feed subscription with id {feed_id}"))
+}
+
+fn read_subscription(row: &rusqlite::Row<'_>) -> rusqlite::Result<FeedSubscription> {
+    Ok(FeedSubscription {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        title: row.get(2)?,
+        created_at: row.get(3)?,
+        last_fetched_at: row.get(4)?,
+        last_error: row.get(5)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_feed_subscription, list_feed_subscriptions, record_feed_fetch,
+        remove_feed_subscription, seen_feed_item_guids,
+    };
+    use crate::migrations;
+    use std::{
+        collections::HashSet,
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn subscriptions_track_fetched_items_until_removed() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-feeds-{nanos}"));
+        let vault = root.join("vault");
+        let other_vault = root.join("other");
+        fs::create_dir_all(&vault).expect("create vault");
+        fs::create_dir_all(&other_vault).expect("create other vault");
+        let db_path = root.join("feeds.sqlite");
+        migrations::run_migrations_at(&db_path).expect("migrations run");
+
+        assert!(list_feed_subscriptions(&db_path, &vault)
+            .expect("no feeds")
+            .is_empty());
+
+        let feed = add_feed_subscription(&db_path, &vault, "https://a.example/rss", "Old")
+            .expect("subscribe");
+        let again = add_feed_subscription(&db_path, &vault, "https://a.example/rss", "A")
+            .expect("subscribe again");
+        assert_eq!(again.id, feed.id);
+        add_feed_subscription(&db_path, &other_vault, "https://b.example/atom", "B")
+            .expect("subscribe other vault");
+
+        record_feed_fetch(&db_path, &vault, feed.id, &["1", "2"], None).expect("fetch");
+        record_feed_fetch(&db_path, &vault, feed.id, &["2", "3"], Some("timed out"))
+            .expect("failed fetch");
+        assert!(record_feed_fetch(&db_path, &other_vault, feed.id, &["x"], None).is_err());
+
+        let feeds = list_feed_subscriptions(&db_path, &vault).expect("feeds");
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].title, "A");
+        assert_eq!(feeds[0].last_error.as_deref(), Some("timed out"));
+        assert!(feeds[0].last_fetched_at.is_some());
+        assert_eq!(
+            seen_feed_item_guids(&db_path, &vault, feed.id).expect("guids"),
+            HashSet::from(["1".to_string(), "2".to_string(), "3".to_string()])
+        );
+        assert!(seen_feed_item_guids(&db_path, &other_vault, feed.id)
+            .expect("other vault guids")
+            .is_empty());
+
+        assert!(!remove_feed_subscription(&db_path, &other_vault, feed.id).expect("wrong vault"));
+        assert!(remove_feed_subscription(&db_path, &vault, feed.id).expect("remove"));
+        assert!(list_feed_subscriptions(&db_path, &vault)
+            .expect("feeds after removal")
+            .is_empty());
+        assert!(seen_feed_item_guids(&db_path, &vault, feed.id)
+            .expect("guids after removal")
+            .is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::params;
+
+use crate::vault::{ensure_workspace_exists, find_workspace_id, open_vault_connection};
+
+/// Hashes of the flashcards already exported from each note, keyed by the
+/// note's forward-slash path relative to the vault root.
+pub fn exported_flashcard_hashes(
+    db_path: &Path,
+    workspace_root: &Path,
+) -> Result<HashMap<String, HashSet<String>>> {
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(HashMap::new());
+    };
+
+    let mut stmt = conn
+        .prepare("SELECT rel_path, card_hash FROM flashcard_export WHERE vault_id = ?1")
+        .context("Failed to prepare flashcard export query")?;
+    let rows = stmt
+        .query_map(params![vault_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .context("Failed to load exported flashcards")?;
+
+    let mut hashes: HashMap<String, HashSet<String>> = HashMap::new();
+    for row in rows {
+        let (rel_path, card_hash) = row.context("Failed to read exported flashcard row")?;
+        hashes.entry(rel_path).or_default().insert(card_hash);
+    }
+    Ok(hashes)
+}
+
+/// Remembers `(note path, card hash)` pairs as exported.
+pub fn record_exported_flashcards(
+    db_path: &Path,
+    workspace_root: &Path,
+    cards: &[(&Path, &str)],
+) -> Result<()> {
+    let mut conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+
+    let tx = conn
+        .transaction()
+        .context("Failed to start flashcard export transaction")?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT OR IGNORE INTO flashcard_export (vault_id, rel_path, card_hash)
+                 VALUES (?1, ?2, ?3)",
+            )
+            .context("Failed to prepare flashcard export insert")?;
+        for (note_path, card_hash) in cards {
+            let rel_path = note_rel_path(workspace_root, note_path)?;
+            stmt.execute(params![vault_id, rel_path, card_hash])
+                .with_context(|| format!("Failed to record flashcard of {rel_path}"))?;
+        }
+    }
+    tx.commit()
+        .context("Failed to commit flashcard export transaction")?;
+    Ok(())
+}
+
+/// Keeps the export history of notes that moved, so their cards are not
+/// exported again. Everything under `old_path` follows it.
+pub fn rename_exported_flashcards(
+    db_path: &Path,
+    workspace_root: &Path,
+    old_path: &Path,
+    new_path: &Path,
+) -> Result<()> {
+    let old_rel_path = note_rel_path(workspace_root, old_path)?;
+    let new_rel_path = note_rel_path(workspace_root, new_path)?;
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(());
+    };
+
+    conn.execute(
+        "UPDATE OR REPLACE flashcard_export
+         SET rel_path = ?3 || substr(rel_path, length(?2) + 1)
+         WHERE vault_id = ?1
+           AND (rel_path = ?2 OR substr(rel_path, 1, length(?2) + 1) = ?2 || '/')",
+        params![vault_id, old_rel_path, new_rel_path],
+    )
+    .context("Failed to update exported flashcards after move")?;
+    Ok(())
+}
+
+fn note_rel_path(workspace_root: &Path, note_path: &Path) -> Result<String> {
+    let rel_path = note_path
+        .strip_prefix(workspace_root)
+        .map_err(|_| anyhow!("Note is outside the vault: {}", note_path.display()))?;
+    let rel_path = rel_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    if rel_path.is_empty() {
+        return Err(anyhow!("The vault root is not a note"));
+    }
+    Ok(rel_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        exported_flashcard_hashes, record_exported_flashcards, rename_exported_flashcards,
+    };
+    use crate::migrations;
+    use std::{
+        collections::HashSet,
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn exported_cards_are_remembered_per_note_and_follow_moves() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-flashcard-exports-{nanos}"));
+        let vault = root.join("vault");
+        fs::create_dir_all(&vault).expect("create vault");
+        let db_path = root.join("flashcards.sqlite");
+        migrations::run_migrations_at(&db_path).expect("migrations run");
+
+        assert!(exported_flashcard_hashes(&db_path, &vault)
+            .expect("empty history")
+            .is_empty());
+
+        let cells = vault.join("biology/cells.md");
+        let atoms = vault.join("atoms.md");
+        record_exported_flashcards(
+            &db_path,
+            &vault,
+            &[
+                (&cells, "a1"),
+                (&cells, "b2"),
+                (&atoms, "c3"),
+                (&cells, "a1"),
+            ],
+        )
+        .expect("record cards");
+        assert!(record_exported_flashcards(&db_path, &vault, &[(&root, "x")]).is_err());
+
+        rename_exported_flashcards(&db_path, &vault, &vault.join("biology"), &vault.join("bio"))
+            .expect("rename folder");
+
+        let hashes = exported_flashcard_hashes(&db_path, &vault).expect("history");
+        assert_eq!(hashes.len(), 2);
+        assert_eq!(
+            hashes["bio/cells.md"],
+            HashSet::from(["a1".to_string(), "b2".to_string()])
+        );
+        assert_eq!(hashes["atoms.md"], HashSet::from(["c3".to_string()]));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,4 +1,18 @@
+pub mod embedding_cache;
+pub mod embedding_providers;
+pub mod feeds;
+pub mod flashcard_exports;
 pub mod migrations;
+pub mod note_identity;
+pub mod pinned_notes;
+pub mod search_history;
+pub mod search_vocabulary;
+pub mod spellcheck_words;
 pub mod sqlite_ext;
 pub mod sync_state;
+pub mod trash;
+pub mod unicode;
 pub mod vault;
+pub mod vault_config;
+pub mod vault_settings;
+pub mod vault_template;
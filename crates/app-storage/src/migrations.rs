@@ -9,7 +9,7 @@ use anyhow::{anyhow, Context, Result};
 use include_dir::{include_dir, Dir};
 use rusqlite::Connection;
 
-use crate::sqlite_ext;
+use crate::{sqlite_ext, unicode};
 
 // Keep SQL migrations embedded in the binary so desktop/test environments do not
 // depend on external migration files at runtime. Touch this file when adding a
@@ -19,7 +19,7 @@ static MIGRATIONS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/migrations");
 const RELEASE_DB_FILE_NAME: &str = "appdata.db";
 const DEV_DB_FILE_NAME: &str = "appdata.dev.db";
 const MIGRATIONS_TABLE: &str = "__migrations";
-const WORKSPACE_STATE_DIR_NAME: &str = ".mdit";
+pub(crate) const WORKSPACE_STATE_DIR_NAME: &str = ".mdit";
 const LEGACY_WORKSPACE_DB_FILE_NAME: &str = "db.sqlite";
 
 struct MigrationFile {
@@ -46,7 +46,9 @@ pub fn run_app_migrations(app_data_dir: &Path) -> Result<PathBuf> {
 }
 
 pub fn run_migrations_at(db_path: &Path) -> Result<()> {
-    sqlite_ext::register_auto_extension()?;
+    // The schema uses no vector functions; without sqlite-vec only vector
+    // search is lost.
+    sqlite_ext::vector_support();
 
     let db_dir = db_path.parent().ok_or_else(|| {
         anyhow!(
@@ -67,6 +69,7 @@ pub fn run_migrations_at(db_path: &Path) -> Result<()> {
 
     conn.pragma_update(None, "foreign_keys", 1)
         .context("Failed to enable foreign keys for appdata database")?;
+    unicode::register_nfc_function(&conn)?;
 
     ensure_migrations_table(&conn)?;
     let applied = load_applied_migrations(&conn)?;
@@ -217,9 +220,10 @@ pub fn cleanup_legacy_workspace_index_db(workspace_root: &Path) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::{
-        appdata_db_file_name, cleanup_legacy_workspace_index_db, DEV_DB_FILE_NAME,
-        RELEASE_DB_FILE_NAME,
+        appdata_db_file_name, cleanup_legacy_workspace_index_db, run_migrations_at,
+        DEV_DB_FILE_NAME, MIGRATIONS_TABLE, RELEASE_DB_FILE_NAME,
     };
+    use rusqlite::Connection;
     use std::{
         fs,
         path::{Path, PathBuf},
@@ -303,6 +307,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn given_decomposed_paths_when_nfc_migration_runs_then_duplicate_docs_and_vaults_are_merged() {
+        let workspace = TempWorkspace::new("mdit-migrations-nfc");
+        let db_path = workspace.root().join("appdata.db");
+        run_migrations_at(&db_path).expect("migrations should apply");
+
+        let conn = Connection::open(&db_path).expect("db should open");
+        conn.execute_batch(&format!(
+            "DROP INDEX vault_workspace_key_unique;
+             ALTER TABLE vault DROP COLUMN workspace_key;
+             CREATE UNIQUE INDEX vault_workspace_root_unique ON vault (workspace_root);
+             DELETE FROM {MIGRATIONS_TABLE} WHERE id = '0031_nfc_rel_paths';
+             INSERT INTO vault (id, workspace_root)
+               VALUES (1, '/notes'), (2, '/vaults/Cafe\u{301}'), (3, '/vaults/Café');
+             INSERT INTO doc (id, vault_id, rel_path, content, chunking_version, last_hash)
+               VALUES (5, 2, 'moved.md', '', 1, 'e');
+             INSERT INTO vault_setting (vault_id, key, value) VALUES (2, 'theme', '\"dark\"');
+             INSERT INTO doc (id, vault_id, rel_path, content, chunking_version, last_hash)
+               VALUES (1, 1, 'Cafe\u{301}.md', '', 1, 'a'),
+                      (2, 1, 'Café.md', '', 1, 'b'),
+                      (3, 1, 'Re\u{301}sume\u{301}.md', '', 1, 'c'),
+                      (4, 1, 'links.md', '', 1, 'd');
+             INSERT INTO link (source_doc_id, target_doc_id, target_path)
+               VALUES (4, NULL, 'Re\u{301}sume\u{301}.md');
+             INSERT INTO pinned_note (vault_id, rel_path, position)
+               VALUES (1, 'Cafe\u{301}.md', 0), (1, 'Café.md', 1);"
+        ))
+        .expect("fixture rows should insert");
+        drop(conn);
+
+        run_migrations_at(&db_path).expect("nfc migration should apply");
+
+        let conn = Connection::open(&db_path).expect("db should open");
+        let docs = conn
+            .prepare("SELECT id, rel_path FROM doc ORDER BY id")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                    .collect::<rusqlite::Result<Vec<(i64, String)>>>()
+            })
+            .expect("docs should load");
+        assert_eq!(
+            docs,
+            vec![
+                (2, "Café.md".to_string()),
+                (3, "Résumé.md".to_string()),
+                (4, "links.md".to_string()),
+                (5, "moved.md".to_string()),
+            ]
+        );
+
+        let vaults = conn
+            .prepare("SELECT id, workspace_root, workspace_key FROM vault ORDER BY id")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                    .collect::<rusqlite::Result<Vec<(i64, String, String)>>>()
+            })
+            .expect("vaults should load");
+        assert_eq!(
+            vaults,
+            vec![
+                (1, "/notes".to_string(), "/notes".to_string()),
+                (3, "/vaults/Café".to_string(), "/vaults/Café".to_string()),
+            ]
+        );
+        let merged: (i64, i64) = conn
+            .query_row(
+                "SELECT (SELECT vault_id FROM doc WHERE id = 5), \
+                        (SELECT vault_id FROM vault_setting WHERE key = 'theme')",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("merged rows should load");
+        assert_eq!(merged, (3, 3));
+
+        let (target_path, source_hash): (String, Option<String>) = conn
+            .query_row(
+                "SELECT l.target_path, d.last_hash FROM link l JOIN doc d ON d.id = l.source_doc_id",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("link should load");
+        assert_eq!(target_path, "Résumé.md");
+        assert_eq!(source_hash, None);
+
+        let pinned: i64 = conn
+            .query_row("SELECT COUNT(*) FROM pinned_note", [], |row| row.get(0))
+            .expect("pinned notes should count");
+        assert_eq!(pinned, 1);
+    }
+
     fn unique_id() -> u128 {
         use std::time::{SystemTime, UNIX_EPOCH};
 
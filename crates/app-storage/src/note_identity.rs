@@ -0,0 +1,167 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::vault::{ensure_workspace_exists, find_workspace_id, open_vault_connection};
+
+/// Returns the stable id of `note_path`, assigning a new one the first time
+/// the note is asked for. The id survives renames and moves made through
+/// [`rename_note_identities`], so external tools can hold on to it.
+pub fn ensure_note_id(db_path: &Path, workspace_root: &Path, note_path: &Path) -> Result<String> {
+    let rel_path = note_rel_path(workspace_root, note_path)?;
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+
+    if let Some(note_id) = load_note_id(&conn, vault_id, &rel_path)? {
+        return Ok(note_id);
+    }
+
+    let note_id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO note_identity (vault_id, note_id, rel_path) VALUES (?1, ?2, ?3)",
+        params![vault_id, note_id, rel_path],
+    )
+    .with_context(|| format!("Failed to assign id to note {rel_path}"))?;
+    Ok(note_id)
+}
+
+/// The forward-slash path, relative to the vault root, last recorded for
+/// `note_id`. The note itself may no longer exist there.
+pub fn find_note_by_id(
+    db_path: &Path,
+    workspace_root: &Path,
+    note_id: &str,
+) -> Result<Option<String>> {
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(None);
+    };
+
+    conn.query_row(
+        "SELECT rel_path FROM note_identity WHERE vault_id = ?1 AND note_id = ?2",
+        params![vault_id, note_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .context("Failed to resolve note id")
+}
+
+/// Moves the ids of every note under `old_path` along with it, so this also
+/// covers renamed folders. A note moved over another one keeps its own id.
+pub fn rename_note_identities(
+    db_path: &Path,
+    workspace_root: &Path,
+    old_path: &Path,
+    new_path: &Path,
+) -> Result<()> {
+    let old_rel_path = note_rel_path(workspace_root, old_path)?;
+    let new_rel_path = note_rel_path(workspace_root, new_path)?;
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(());
+    };
+
+    conn.execute(
+        "UPDATE OR REPLACE note_identity
+         SET rel_path = ?3 || substr(rel_path, length(?2) + 1)
+         WHERE vault_id = ?1
+           AND (rel_path = ?2 OR substr(rel_path, 1, length(?2) + 1) = ?2 || '/')",
+        params![vault_id, old_rel_path, new_rel_path],
+    )
+    .context("Failed to update note ids after move")?;
+    Ok(())
+}
+
+fn load_note_id(conn: &Connection, vault_id: i64, rel_path: &str) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT note_id FROM note_identity WHERE vault_id = ?1 AND rel_path = ?2",
+        params![vault_id, rel_path],
+        |row| row.get(0),
+    )
+    .optional()
+    .context("Failed to load note id")
+}
+
+fn note_rel_path(workspace_root: &Path, note_path: &Path) -> Result<String> {
+    let rel_path = note_path
+        .strip_prefix(workspace_root)
+        .map_err(|_| anyhow!("Note is outside the vault: {}", note_path.display()))?;
+    let rel_path = rel_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    if rel_path.is_empty() {
+        return Err(anyhow!("The vault root is not a note"));
+    }
+    Ok(rel_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ensure_note_id, find_note_by_id, rename_note_identities};
+    use crate::migrations;
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn note_ids_are_stable_and_follow_moved_notes() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-note-identity-{nanos}"));
+        let vault = root.join("vault");
+        fs::create_dir_all(&vault).expect("create vault");
+        let db_path = root.join("ids.sqlite");
+        migrations::run_migrations_at(&db_path).expect("migrations run");
+
+        let plan_id =
+            ensure_note_id(&db_path, &vault, &vault.join("projects/plan.md")).expect("assign id");
+        let inbox_id =
+            ensure_note_id(&db_path, &vault, &vault.join("inbox.md")).expect("assign id");
+        assert_ne!(plan_id, inbox_id);
+        assert_eq!(
+            ensure_note_id(&db_path, &vault, &vault.join("projects/plan.md")).expect("reuse id"),
+            plan_id
+        );
+        assert!(ensure_note_id(&db_path, &vault, &root.join("outside.md")).is_err());
+
+        rename_note_identities(
+            &db_path,
+            &vault,
+            &vault.join("projects"),
+            &vault.join("archive"),
+        )
+        .expect("rename folder");
+        assert_eq!(
+            find_note_by_id(&db_path, &vault, &plan_id).expect("resolve id"),
+            Some("archive/plan.md".to_string())
+        );
+        assert_eq!(
+            ensure_note_id(&db_path, &vault, &vault.join("archive/plan.md")).expect("reuse id"),
+            plan_id
+        );
+
+        rename_note_identities(
+            &db_path,
+            &vault,
+            &vault.join("inbox.md"),
+            &vault.join("archive/plan.md"),
+        )
+        .expect("move over note");
+        assert_eq!(
+            find_note_by_id(&db_path, &vault, &inbox_id).expect("resolve id"),
+            Some("archive/plan.md".to_string())
+        );
+        assert_eq!(
+            find_note_by_id(&db_path, &vault, &plan_id).expect("resolve id"),
+            None
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
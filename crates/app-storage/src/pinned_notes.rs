@@ -0,0 +1,211 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::vault::{ensure_workspace_exists, find_workspace_id, open_vault_connection};
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PinnedNote {
+    /// Forward-slash path relative to the vault root.
+    pub rel_path: String,
+    pub pinned_at: String,
+}
+
+/// The vault's pinned notes in the order the user arranged them.
+pub fn list_pinned_notes(db_path: &Path, workspace_root: &Path) -> Result<Vec<PinnedNote>> {
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+    load_pinned_notes(&conn, vault_id)
+}
+
+/// Pins `note_path` after the existing pins, or unpins it, and returns the
+/// updated list. Pinning a pinned note keeps its place.
+pub fn pin_note(
+    db_path: &Path,
+    workspace_root: &Path,
+    note_path: &Path,
+    pinned: bool,
+) -> Result<Vec<PinnedNote>> {
+    let rel_path = pinned_rel_path(workspace_root, note_path)?;
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+
+    if pinned {
+        conn.execute(
+            "INSERT OR IGNORE INTO pinned_note (vault_id, rel_path, position)
+             SELECT ?1, ?2, COALESCE(MAX(position), -1) + 1
+             FROM pinned_note WHERE vault_id = ?1",
+            params![vault_id, rel_path],
+        )
+        .context("Failed to pin note")?;
+    } else {
+        conn.execute(
+            "DELETE FROM pinned_note WHERE vault_id = ?1 AND rel_path = ?2",
+            params![vault_id, rel_path],
+        )
+        .context("Failed to unpin note")?;
+    }
+
+    load_pinned_notes(&conn, vault_id)
+}
+
+/// Puts the pins listed in `note_paths` first, in that order. Pins left out
+/// keep their relative order after them; paths that are not pinned are
+/// ignored.
+pub fn reorder_pinned_notes(
+    db_path: &Path,
+    workspace_root: &Path,
+    note_paths: &[PathBuf],
+) -> Result<Vec<PinnedNote>> {
+    let mut conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut ordered = note_paths
+        .iter()
+        .map(|note_path| pinned_rel_path(workspace_root, note_path))
+        .collect::<Result<Vec<_>>>()?;
+    for pinned in load_pinned_notes(&conn, vault_id)? {
+        if !ordered.contains(&pinned.rel_path) {
+            ordered.push(pinned.rel_path);
+        }
+    }
+
+    let tx = conn
+        .transaction()
+        .context("Failed to start pinned note transaction")?;
+    {
+        let mut stmt = tx
+            .prepare("UPDATE pinned_note SET position = ?3 WHERE vault_id = ?1 AND rel_path = ?2")
+            .context("Failed to prepare pinned note reorder")?;
+        for (position, rel_path) in ordered.iter().enumerate() {
+            stmt.execute(params![vault_id, rel_path, position as i64])
+                .with_context(|| format!("Failed to move pinned note {rel_path}"))?;
+        }
+    }
+    tx.commit()
+        .context("Failed to commit pinned note transaction")?;
+
+    load_pinned_notes(&conn, vault_id)
+}
+
+/// Keeps pins on notes that moved. Every pin under `old_path` follows it, so
+/// this also covers renamed folders.
+pub fn rename_pinned_notes(
+    db_path: &Path,
+    workspace_root: &Path,
+    old_path: &Path,
+    new_path: &Path,
+) -> Result<()> {
+    let old_rel_path = pinned_rel_path(workspace_root, old_path)?;
+    let new_rel_path = pinned_rel_path(workspace_root, new_path)?;
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(());
+    };
+
+    conn.execute(
+        "UPDATE OR REPLACE pinned_note
+         SET rel_path = ?3 || substr(rel_path, length(?2) + 1)
+         WHERE vault_id = ?1
+           AND (rel_path = ?2 OR substr(rel_path, 1, length(?2) + 1) = ?2 || '/')",
+        params![vault_id, old_rel_path, new_rel_path],
+    )
+    .context("Failed to update pinned notes after move")?;
+    Ok(())
+}
+
+fn load_pinned_notes(conn: &Connection, vault_id: i64) -> Result<Vec<PinnedNote>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT rel_path, pinned_at FROM pinned_note
+             WHERE vault_id = ?1
+             ORDER BY position, rel_path",
+        )
+        .context("Failed to prepare pinned note query")?;
+    let notes = stmt
+        .query_map(params![vault_id], |row| {
+            Ok(PinnedNote {
+                rel_path: row.get(0)?,
+                pinned_at: row.get(1)?,
+            })
+        })
+        .context("Failed to load pinned notes")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read pinned note rows")?;
+    Ok(notes)
+}
+
+fn pinned_rel_path(workspace_root: &Path, note_path: &Path) -> Result<String> {
+    let rel_path = note_path
+        .strip_prefix(workspace_root)
+        .map_err(|_| anyhow!("Note is outside the vault: {}", note_path.display()))?;
+    let rel_path = rel_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    if rel_path.is_empty() {
+        return Err(anyhow!("The vault root cannot be pinned"));
+    }
+    Ok(rel_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        list_pinned_notes, pin_note, rename_pinned_notes, reorder_pinned_notes, PinnedNote,
+    };
+    use crate::migrations;
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn pins_keep_their_order_and_follow_moved_notes() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-pinned-notes-{nanos}"));
+        let vault = root.join("vault");
+        fs::create_dir_all(&vault).expect("create vault");
+        let db_path = root.join("pins.sqlite");
+        migrations::run_migrations_at(&db_path).expect("migrations run");
+
+        pin_note(&db_path, &vault, &vault.join("b.md"), true).expect("pin b");
+        pin_note(&db_path, &vault, &vault.join("projects/a.md"), true).expect("pin a");
+        pin_note(&db_path, &vault, &vault.join("c.md"), true).expect("pin c");
+        let pins = pin_note(&db_path, &vault, &vault.join("b.md"), true).expect("pin b again");
+        let paths =
+            |pins: Vec<PinnedNote>| pins.into_iter().map(|pin| pin.rel_path).collect::<Vec<_>>();
+        assert_eq!(paths(pins), vec!["b.md", "projects/a.md", "c.md"]);
+        assert!(pin_note(&db_path, &vault, &root.join("outside.md"), true).is_err());
+
+        let pins = reorder_pinned_notes(&db_path, &vault, &[vault.join("c.md")]).expect("reorder");
+        assert_eq!(paths(pins), vec!["c.md", "b.md", "projects/a.md"]);
+
+        rename_pinned_notes(
+            &db_path,
+            &vault,
+            &vault.join("projects"),
+            &vault.join("archive"),
+        )
+        .expect("rename folder");
+        let pins = pin_note(&db_path, &vault, &vault.join("b.md"), false).expect("unpin b");
+        assert_eq!(paths(pins), vec!["c.md", "archive/a.md"]);
+        assert_eq!(
+            paths(list_pinned_notes(&db_path, &vault).expect("list pins")),
+            vec!["c.md", "archive/a.md"]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,219 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::vault::{ensure_workspace_exists, find_workspace_id, open_vault_connection};
+use crate::vault_settings::{get_vault_setting, VaultSettingKey};
+
+const MAX_QUERY_CHARS: usize = 500;
+// Oldest queries beyond this are dropped when a new one is recorded.
+const MAX_HISTORY_ENTRIES: i64 = 200;
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHistoryEntry {
+    pub query: String,
+    pub search_count: i64,
+    pub last_searched_at: String,
+}
+
+/// Remembers `query` as searched now, unless the vault turned the
+/// `searchHistory` setting off. Returns whether it was recorded.
+pub fn record_search_query(db_path: &Path, workspace_root: &Path, query: &str) -> Result<bool> {
+    let query = query.trim();
+    if query.is_empty() || query.chars().count() > MAX_QUERY_CHARS {
+        return Ok(false);
+    }
+    let enabled = get_vault_setting(
+        db_path,
+        workspace_root,
+        VaultSettingKey::SearchHistory.as_str(),
+    )?
+    .value
+    .as_bool()
+    .unwrap_or(true);
+    if !enabled {
+        return Ok(false);
+    }
+
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+    conn.execute(
+        "INSERT INTO search_history (vault_id, query) VALUES (?1, ?2)
+         ON CONFLICT(vault_id, query) DO UPDATE SET
+           search_count = search_count + 1,
+           last_searched_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')",
+        params![vault_id, query],
+    )
+    .context("Failed to record search query")?;
+    conn.execute(
+        "DELETE FROM search_history
+         WHERE vault_id = ?1
+           AND query NOT IN (
+             SELECT query FROM search_history
+             WHERE vault_id = ?1
+             ORDER BY last_searched_at DESC
+             LIMIT ?2
+           )",
+        params![vault_id, MAX_HISTORY_ENTRIES],
+    )
+    .context("Failed to prune search history")?;
+
+    Ok(true)
+}
+
+/// The vault's most recent queries, newest first.
+pub fn get_search_history(
+    db_path: &Path,
+    workspace_root: &Path,
+    limit: usize,
+) -> Result<Vec<SearchHistoryEntry>> {
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+
+    load_entries(
+        &conn,
+        "WHERE vault_id = ?1 ORDER BY last_searched_at DESC, query LIMIT ?2",
+        params![vault_id, limit as i64],
+    )
+}
+
+/// Past queries starting with `prefix`, ignoring case, that would complete
+/// it. Frequent queries come first, then recent ones.
+pub fn suggest_queries(
+    db_path: &Path,
+    workspace_root: &Path,
+    prefix: &str,
+    limit: usize,
+) -> Result<Vec<String>> {
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+    let prefix = prefix.trim_start();
+    let like_pattern = format!("{}%", escape_like_pattern(prefix));
+
+    let entries = load_entries(
+        &conn,
+        "WHERE vault_id = ?1 AND query LIKE ?2 ESCAPE '\\' AND lower(query) <> lower(?3)
+         ORDER BY search_count DESC, last_searched_at DESC, query
+         LIMIT ?4",
+        params![vault_id, like_pattern, prefix, limit as i64],
+    )?;
+    Ok(entries.into_iter().map(|entry| entry.query).collect())
+}
+
+pub fn clear_search_history(db_path: &Path, workspace_root: &Path) -> Result<()> {
+    let conn = open_vault_connection(db_path)?;
+    if let Some(vault_id) = find_workspace_id(&conn, workspace_root)? {
+        conn.execute(
+            "DELETE FROM search_history WHERE vault_id = ?1",
+            params![vault_id],
+        )
+        .context("Failed to clear search history")?;
+    }
+    Ok(())
+}
+
+fn load_entries(
+    conn: &Connection,
+    filter: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<SearchHistoryEntry>> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT query, search_count, last_searched_at FROM search_history {filter}"
+        ))
+        .context("Failed to prepare search history query")?;
+    let entries = stmt
+        .query_map(params, |row| {
+            Ok(SearchHistoryEntry {
+                query: row.get(0)?,
+                search_count: row.get(1)?,
+                last_searched_at: row.get(2)?,
+            })
+        })
+        .context("Failed to load search history")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read search history rows")?;
+    Ok(entries)
+}
+
+fn escape_like_pattern(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    for ch in input.chars() {
+        if matches!(ch, '\\' | '%' | '_') {
+            output.push('\\');
+        }
+        output.push(ch);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clear_search_history, get_search_history, record_search_query, suggest_queries};
+    use crate::{migrations, vault_settings::set_vault_setting};
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn queries_are_counted_suggested_by_prefix_and_skipped_when_disabled() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-search-history-{nanos}"));
+        let vault = root.join("vault");
+        fs::create_dir_all(&vault).expect("create vault");
+        let db_path = root.join("history.sqlite");
+        migrations::run_migrations_at(&db_path).expect("migrations run");
+
+        for query in ["roadmap", "Road trip", "roadmap", "100%_done", "  "] {
+            record_search_query(&db_path, &vault, query).expect("record query");
+        }
+
+        let history = get_search_history(&db_path, &vault, 10).expect("history loads");
+        assert_eq!(history.len(), 3);
+        let roadmap = history
+            .iter()
+            .find(|entry| entry.query == "roadmap")
+            .expect("roadmap recorded");
+        assert_eq!(roadmap.search_count, 2);
+
+        let suggestions = suggest_queries(&db_path, &vault, "ROAD", 10).expect("suggest");
+        assert_eq!(suggestions, vec!["roadmap", "Road trip"]);
+        assert!(suggest_queries(&db_path, &vault, "roadmap", 10)
+            .expect("suggest")
+            .is_empty());
+        assert_eq!(
+            suggest_queries(&db_path, &vault, "100%_", 10).expect("suggest"),
+            vec!["100%_done"]
+        );
+        assert!(suggest_queries(&db_path, &vault, "1_0", 10)
+            .expect("suggest")
+            .is_empty());
+
+        set_vault_setting(
+            &db_path,
+            &vault,
+            "searchHistory",
+            &serde_json::Value::Bool(false),
+        )
+        .expect("disable history");
+        assert!(!record_search_query(&db_path, &vault, "secret").expect("record query"));
+
+        clear_search_history(&db_path, &vault).expect("clear history");
+        assert!(get_search_history(&db_path, &vault, 10)
+            .expect("history loads")
+            .is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,296 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use crate::vault::{ensure_workspace_exists, find_workspace_id, open_vault_connection};
+
+const MAX_TERM_CHARS: usize = 100;
+
+/// Two spellings search treats as the same, such as "k8s" and "kubernetes".
+/// The pair works in both directions; `term` is whichever sorts first.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSynonym {
+    pub term: String,
+    pub synonym: String,
+}
+
+/// A vault's synonyms and stopwords, loaded once per search.
+#[derive(Debug, Default, Clone)]
+pub struct SearchVocabulary {
+    /// Each term, lowercased, mapped to every term it is paired with.
+    pub synonyms: HashMap<String, Vec<String>>,
+    pub stopwords: HashSet<String>,
+}
+
+impl SearchVocabulary {
+    pub fn is_empty(&self) -> bool {
+        self.synonyms.is_empty() && self.stopwords.is_empty()
+    }
+}
+
+pub fn load_search_vocabulary(conn: &Connection, vault_id: i64) -> Result<SearchVocabulary> {
+    let mut vocabulary = SearchVocabulary::default();
+
+    let mut stmt = conn
+        .prepare("SELECT term, synonym FROM search_synonym WHERE vault_id = ?1")
+        .context("Failed to prepare search synonym query")?;
+    let pairs = stmt
+        .query_map(params![vault_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .context("Failed to load search synonyms")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read search synonym rows")?;
+    for (term, synonym) in pairs {
+        vocabulary
+            .synonyms
+            .entry(term.clone())
+            .or_default()
+            .push(synonym.clone());
+        vocabulary.synonyms.entry(synonym).or_default().push(term);
+    }
+
+    let mut stmt = conn
+        .prepare("SELECT word FROM search_stopword WHERE vault_id = ?1")
+        .context("Failed to prepare search stopword query")?;
+    vocabulary.stopwords = stmt
+        .query_map(params![vault_id], |row| row.get(0))
+        .context("Failed to load search stopwords")?
+        .collect::<rusqlite::Result<HashSet<String>>>()
+        .context("Failed to read search stopword rows")?;
+
+    Ok(vocabulary)
+}
+
+/// Synonym pairs for this vault, sorted by term.
+pub fn list_search_synonyms(db_path: &Path, workspace_root: &Path) -> Result<Vec<SearchSynonym>> {
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT term, synonym FROM search_synonym
+             WHERE vault_id = ?1
+             ORDER BY term, synonym",
+        )
+        .context("Failed to prepare search synonym query")?;
+    let synonyms = stmt
+        .query_map(params![vault_id], |row| {
+            Ok(SearchSynonym {
+                term: row.get(0)?,
+                synonym: row.get(1)?,
+            })
+        })
+        .context("Failed to load search synonyms")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read search synonym rows")?;
+
+    Ok(synonyms)
+}
+
+/// Pairs `term` with `synonym` and returns the updated list. Either may be a
+/// few words; adding a pair twice, in either order, is a no-op.
+pub fn add_search_synonym(
+    db_path: &Path,
+    workspace_root: &Path,
+    term: &str,
+    synonym: &str,
+) -> Result<Vec<SearchSynonym>> {
+    let (term, synonym) = ordered_pair(normalize_term(term)?, normalize_term(synonym)?);
+    if term == synonym {
+        return Err(anyhow!("A search synonym must differ from its term"));
+    }
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+    conn.execute(
+        "INSERT OR IGNORE INTO search_synonym (vault_id, term, synonym) VALUES (?1, ?2, ?3)",
+        params![vault_id, term, synonym],
+    )
+    .context("Failed to add search synonym")?;
+
+    list_search_synonyms(db_path, workspace_root)
+}
+
+/// Unpairs `term` and `synonym`, given in either order, and returns the
+/// updated list.
+pub fn remove_search_synonym(
+    db_path: &Path,
+    workspace_root: &Path,
+    term: &str,
+    synonym: &str,
+) -> Result<Vec<SearchSynonym>> {
+    let conn = open_vault_connection(db_path)?;
+    if let Some(vault_id) = find_workspace_id(&conn, workspace_root)? {
+        let (term, synonym) = ordered_pair(collapse(term), collapse(synonym));
+        conn.execute(
+            "DELETE FROM search_synonym WHERE vault_id = ?1 AND term = ?2 AND synonym = ?3",
+            params![vault_id, term, synonym],
+        )
+        .context("Failed to remove search synonym")?;
+    }
+
+    list_search_synonyms(db_path, workspace_root)
+}
+
+/// Words dropped from this vault's queries, sorted.
+pub fn list_search_stopwords(db_path: &Path, workspace_root: &Path) -> Result<Vec<String>> {
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut stmt = conn
+        .prepare("SELECT word FROM search_stopword WHERE vault_id = ?1 ORDER BY word")
+        .context("Failed to prepare search stopword query")?;
+    let words = stmt
+        .query_map(params![vault_id], |row| row.get(0))
+        .context("Failed to load search stopwords")?
+        .collect::<rusqlite::Result<Vec<String>>>()
+        .context("Failed to read search stopword rows")?;
+
+    Ok(words)
+}
+
+/// Adds `word` to the vault's stopwords and returns the updated list.
+pub fn add_search_stopword(
+    db_path: &Path,
+    workspace_root: &Path,
+    word: &str,
+) -> Result<Vec<String>> {
+    let word = normalize_term(word)?;
+    if word.contains(' ') {
+        return Err(anyhow!("Search stopword must be a single word: {}", word));
+    }
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+    conn.execute(
+        "INSERT OR IGNORE INTO search_stopword (vault_id, word) VALUES (?1, ?2)",
+        params![vault_id, word],
+    )
+    .context("Failed to add search stopword")?;
+
+    list_search_stopwords(db_path, workspace_root)
+}
+
+/// Removes `word` from the vault's stopwords and returns the updated list.
+pub fn remove_search_stopword(
+    db_path: &Path,
+    workspace_root: &Path,
+    word: &str,
+) -> Result<Vec<String>> {
+    let conn = open_vault_connection(db_path)?;
+    if let Some(vault_id) = find_workspace_id(&conn, workspace_root)? {
+        conn.execute(
+            "DELETE FROM search_stopword WHERE vault_id = ?1 AND word = ?2",
+            params![vault_id, collapse(word)],
+        )
+        .context("Failed to remove search stopword")?;
+    }
+
+    list_search_stopwords(db_path, workspace_root)
+}
+
+/// Lowercases and joins the words with single spaces, the form queries are
+/// matched in.
+fn collapse(term: &str) -> String {
+    term.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn normalize_term(term: &str) -> Result<String> {
+    let term = collapse(term);
+    if term.is_empty() {
+        return Err(anyhow!("Search term must not be empty"));
+    }
+    if term.chars().count() > MAX_TERM_CHARS {
+        return Err(anyhow!(
+            "Search term must be at most {} characters",
+            MAX_TERM_CHARS
+        ));
+    }
+    Ok(term)
+}
+
+fn ordered_pair(a: String, b: String) -> (String, String) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_search_stopword, add_search_synonym, list_search_synonyms, load_search_vocabulary,
+        remove_search_stopword, remove_search_synonym, SearchSynonym,
+    };
+    use crate::{
+        migrations,
+        vault::{find_workspace_id, open_vault_connection},
+    };
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn synonyms_and_stopwords_are_kept_per_vault() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-search-vocabulary-{nanos}"));
+        let vault_a = root.join("a");
+        let vault_b = root.join("b");
+        fs::create_dir_all(&vault_a).expect("create vault a");
+        fs::create_dir_all(&vault_b).expect("create vault b");
+        let db_path = root.join("vocabulary.sqlite");
+        migrations::run_migrations_at(&db_path).expect("migrations run");
+
+        add_search_synonym(&db_path, &vault_a, "Kubernetes", " k8s ").expect("add synonym");
+        let synonyms =
+            add_search_synonym(&db_path, &vault_a, "k8s", "kubernetes").expect("add reversed");
+        assert_eq!(
+            synonyms,
+            vec![SearchSynonym {
+                term: "k8s".to_string(),
+                synonym: "kubernetes".to_string(),
+            }]
+        );
+        assert!(add_search_synonym(&db_path, &vault_a, "same", "SAME").is_err());
+        assert!(list_search_synonyms(&db_path, &vault_b)
+            .expect("list other vault")
+            .is_empty());
+
+        add_search_stopword(&db_path, &vault_a, "The").expect("add stopword");
+        assert!(add_search_stopword(&db_path, &vault_a, "of the").is_err());
+
+        let conn = open_vault_connection(&db_path).expect("open db");
+        let vault_id = find_workspace_id(&conn, &vault_a)
+            .expect("find vault")
+            .expect("vault exists");
+        let vocabulary = load_search_vocabulary(&conn, vault_id).expect("load vocabulary");
+        assert_eq!(vocabulary.synonyms["kubernetes"], vec!["k8s"]);
+        assert_eq!(vocabulary.synonyms["k8s"], vec!["kubernetes"]);
+        assert!(vocabulary.stopwords.contains("the"));
+
+        let synonyms =
+            remove_search_synonym(&db_path, &vault_a, "kubernetes", "k8s").expect("remove pair");
+        assert!(synonyms.is_empty());
+        let stopwords = remove_search_stopword(&db_path, &vault_a, "the").expect("remove stopword");
+        assert!(stopwords.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,124 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::params;
+
+use crate::vault::{ensure_workspace_exists, find_workspace_id, open_vault_connection};
+
+const MAX_WORD_CHARS: usize = 100;
+
+/// Words the spellchecker accepts in this vault, sorted case-insensitively.
+pub fn list_spellcheck_words(db_path: &Path, workspace_root: &Path) -> Result<Vec<String>> {
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT word FROM spellcheck_word
+             WHERE vault_id = ?1
+             ORDER BY word COLLATE NOCASE, word",
+        )
+        .context("Failed to prepare spellcheck word query")?;
+    let words = stmt
+        .query_map(params![vault_id], |row| row.get(0))
+        .context("Failed to load spellcheck words")?
+        .collect::<rusqlite::Result<Vec<String>>>()
+        .context("Failed to read spellcheck word rows")?;
+
+    Ok(words)
+}
+
+/// Adds `word` to the vault dictionary and returns the updated list.
+/// Adding a word twice is a no-op.
+pub fn add_spellcheck_word(
+    db_path: &Path,
+    workspace_root: &Path,
+    word: &str,
+) -> Result<Vec<String>> {
+    let word = normalize_word(word)?;
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+    conn.execute(
+        "INSERT OR IGNORE INTO spellcheck_word (vault_id, word) VALUES (?1, ?2)",
+        params![vault_id, word],
+    )
+    .context("Failed to add spellcheck word")?;
+
+    list_spellcheck_words(db_path, workspace_root)
+}
+
+/// Removes `word` from the vault dictionary and returns the updated list.
+pub fn remove_spellcheck_word(
+    db_path: &Path,
+    workspace_root: &Path,
+    word: &str,
+) -> Result<Vec<String>> {
+    let conn = open_vault_connection(db_path)?;
+    if let Some(vault_id) = find_workspace_id(&conn, workspace_root)? {
+        conn.execute(
+            "DELETE FROM spellcheck_word WHERE vault_id = ?1 AND word = ?2",
+            params![vault_id, word.trim()],
+        )
+        .context("Failed to remove spellcheck word")?;
+    }
+
+    list_spellcheck_words(db_path, workspace_root)
+}
+
+fn normalize_word(word: &str) -> Result<String> {
+    let word = word.trim();
+    if word.is_empty() {
+        return Err(anyhow!("Spellcheck word must not be empty"));
+    }
+    if word.chars().any(char::is_whitespace) {
+        return Err(anyhow!("Spellcheck word must be a single word: {}", word));
+    }
+    if word.chars().count() > MAX_WORD_CHARS {
+        return Err(anyhow!(
+            "Spellcheck word must be at most {} characters",
+            MAX_WORD_CHARS
+        ));
+    }
+    Ok(word.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_spellcheck_word, list_spellcheck_words, remove_spellcheck_word};
+    use crate::migrations;
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn words_are_kept_per_vault_without_duplicates() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-spellcheck-words-{nanos}"));
+        let vault_a = root.join("a");
+        let vault_b = root.join("b");
+        fs::create_dir_all(&vault_a).expect("create vault a");
+        fs::create_dir_all(&vault_b).expect("create vault b");
+        let db_path = root.join("words.sqlite");
+        migrations::run_migrations_at(&db_path).expect("migrations run");
+
+        add_spellcheck_word(&db_path, &vault_a, " mdit ").expect("add word");
+        add_spellcheck_word(&db_path, &vault_a, "Zettelkasten").expect("add word");
+        let words = add_spellcheck_word(&db_path, &vault_a, "mdit").expect("add twice");
+        assert_eq!(words, vec!["mdit", "Zettelkasten"]);
+        assert!(add_spellcheck_word(&db_path, &vault_a, "two words").is_err());
+        assert!(list_spellcheck_words(&db_path, &vault_b)
+            .expect("list other vault")
+            .is_empty());
+
+        let words = remove_spellcheck_word(&db_path, &vault_a, "mdit").expect("remove word");
+        assert_eq!(words, vec!["Zettelkasten"]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
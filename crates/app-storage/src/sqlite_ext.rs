@@ -1,9 +1,24 @@
 use std::sync::OnceLock;
 
 use anyhow::{anyhow, Result};
-use rusqlite::ffi::{sqlite3_auto_extension, SQLITE_OK};
+use rusqlite::{
+    ffi::{sqlite3_auto_extension, SQLITE_OK},
+    Connection,
+};
+use serde::Serialize;
 use sqlite_vec::sqlite3_vec_init;
 
+/// Whether sqlite-vec works in this process. Without it vector search and
+/// embedding storage are skipped and search runs on keywords alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VectorSupport {
+    pub available: bool,
+    pub version: Option<String>,
+    /// Why the probe failed, for showing next to the search settings.
+    pub error: Option<String>,
+}
+
 pub fn register_auto_extension() -> Result<()> {
     static INIT: OnceLock<Result<(), String>> = OnceLock::new();
 
@@ -24,3 +39,45 @@ pub fn register_auto_extension() -> Result<()> {
         Err(message) => Err(anyhow!(message.clone())),
     }
 }
+
+/// Registers sqlite-vec and checks that a fresh connection can call into it.
+/// Probed once; registration can fail on unsupported architectures, and a
+/// hardened runtime can block the extension even after it registers.
+pub fn vector_support() -> &'static VectorSupport {
+    static PROBE: OnceLock<VectorSupport> = OnceLock::new();
+
+    PROBE.get_or_init(|| {
+        let version = register_auto_extension().and_then(|()| {
+            let conn = Connection::open_in_memory()?;
+            let version = conn.query_row("SELECT vec_version()", [], |row| row.get(0))?;
+            Ok(version)
+        });
+        match version {
+            Ok(version) => VectorSupport {
+                available: true,
+                version: Some(version),
+                error: None,
+            },
+            Err(error) => VectorSupport {
+                available: false,
+                version: None,
+                error: Some(format!("{error:#}")),
+            },
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::vector_support;
+
+    #[test]
+    fn probe_reports_the_bundled_extension() {
+        let support = vector_support();
+        assert!(support.available, "{:?}", support.error);
+        assert!(support
+            .version
+            .as_deref()
+            .is_some_and(|version| version.starts_with('v')));
+    }
+}
@@ -0,0 +1,257 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::vault::{ensure_workspace_exists, open_vault_connection};
+
+/// A note mdit moved to the trash, kept so it can be listed and restored.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedNoteRecord {
+    pub id: i64,
+    pub vault_id: i64,
+    /// Path relative to the workspace root, with `/` separators.
+    pub original_path: String,
+    /// Where the note now lives when mdit knows it, such as the in-vault
+    /// `.mdit/trash` folder. `None` for items handed to the system trash.
+    pub trash_location: Option<String>,
+    pub trashed_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordTrashedNoteInput {
+    pub original_path: String,
+    pub trash_location: Option<String>,
+}
+
+fn map_trashed_note_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<TrashedNoteRecord> {
+    Ok(TrashedNoteRecord {
+        id: row.get(0)?,
+        vault_id: row.get(1)?,
+        original_path: row.get(2)?,
+        trash_location: row.get(3)?,
+        trashed_at: row.get(4)?,
+    })
+}
+
+fn insert_trashed_note(
+    conn: &Connection,
+    vault_id: i64,
+    input: &RecordTrashedNoteInput,
+) -> Result<TrashedNoteRecord> {
+    conn.execute(
+        "INSERT INTO trashed_note (vault_id, original_path, trash_location)
+         VALUES (?1, ?2, ?3)",
+        params![vault_id, input.original_path, input.trash_location],
+    )
+    .context("Failed to record trashed note")?;
+
+    let trashed_id = conn.last_insert_rowid();
+    conn.query_row(
+        "SELECT id, vault_id, original_path, trash_location, trashed_at
+         FROM trashed_note
+         WHERE id = ?1",
+        params![trashed_id],
+        map_trashed_note_row,
+    )
+    .context("Failed to reload trashed note after insert")
+}
+
+pub fn record_trashed_notes(
+    db_path: &Path,
+    workspace_root: &Path,
+    inputs: &[RecordTrashedNoteInput],
+) -> Result<Vec<TrashedNoteRecord>> {
+    let mut conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+
+    let tx = conn
+        .transaction()
+        .context("Failed to start trashed note transaction")?;
+    let records = inputs
+        .iter()
+        .map(|input| insert_trashed_note(&tx, vault_id, input))
+        .collect::<Result<Vec<_>>>()?;
+    tx.commit()
+        .context("Failed to commit trashed note transaction")?;
+
+    Ok(records)
+}
+
+/// Trashed notes of the workspace, most recently trashed first.
+pub fn list_trashed_notes(db_path: &Path, workspace_root: &Path) -> Result<Vec<TrashedNoteRecord>> {
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, vault_id, original_path, trash_location, trashed_at
+             FROM trashed_note
+             WHERE vault_id = ?1
+             ORDER BY trashed_at DESC, id DESC",
+        )
+        .context("Failed to prepare trashed note list query")?;
+
+    let records = stmt
+        .query_map(params![vault_id], map_trashed_note_row)
+        .context("Failed to load trashed notes")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read trashed note rows")?;
+
+    Ok(records)
+}
+
+pub fn get_trashed_note(
+    db_path: &Path,
+    workspace_root: &Path,
+    trashed_id: i64,
+) -> Result<Option<TrashedNoteRecord>> {
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+
+    conn.query_row(
+        "SELECT id, vault_id, original_path, trash_location, trashed_at
+         FROM trashed_note
+         WHERE id = ?1 AND vault_id = ?2",
+        params![trashed_id, vault_id],
+        map_trashed_note_row,
+    )
+    .optional()
+    .context("Failed to load trashed note")
+}
+
+/// Forget ledger entries once notes are restored or gone from the trash.
+pub fn delete_trashed_notes(db_path: &Path, trashed_ids: &[i64]) -> Result<()> {
+    if trashed_ids.is_empty() {
+        return Ok(());
+    }
+
+    let conn = open_vault_connection(db_path)?;
+    let mut stmt = conn
+        .prepare("DELETE FROM trashed_note WHERE id = ?1")
+        .context("Failed to prepare trashed note delete")?;
+    for trashed_id in trashed_ids {
+        stmt.execute(params![trashed_id])
+            .context("Failed to delete trashed note")?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        delete_trashed_notes, get_trashed_note, list_trashed_notes, record_trashed_notes,
+        RecordTrashedNoteInput,
+    };
+    use crate::migrations;
+    use std::{
+        fs,
+        path::PathBuf,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    struct TrashHarness {
+        root: PathBuf,
+        db_path: PathBuf,
+    }
+
+    impl TrashHarness {
+        fn new(prefix: &str) -> Self {
+            let mut root = std::env::temp_dir();
+            root.push(format!("{prefix}-{}", unique_id()));
+            fs::create_dir_all(&root).expect("failed to create temp root");
+
+            let db_path = root.join("trash-test.sqlite");
+            migrations::run_migrations_at(&db_path).expect("failed to run test migrations");
+
+            Self { root, db_path }
+        }
+
+        fn create_workspace(&self, name: &str) -> PathBuf {
+            let path = self.root.join(name);
+            fs::create_dir_all(&path).expect("failed to create workspace");
+            path
+        }
+    }
+
+    impl Drop for TrashHarness {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn trashed_notes_are_listed_per_workspace_newest_first() {
+        let harness = TrashHarness::new("mdit-trash-ledger-list");
+        let workspace_a = harness.create_workspace("a");
+        let workspace_b = harness.create_workspace("b");
+
+        let recorded = record_trashed_notes(
+            &harness.db_path,
+            &workspace_a,
+            &[
+                RecordTrashedNoteInput {
+                    original_path: "notes/first.md".to_string(),
+                    trash_location: None,
+                },
+                RecordTrashedNoteInput {
+                    original_path: "second.md".to_string(),
+                    trash_location: Some("/vault/.mdit/trash/1/second.md".to_string()),
+                },
+            ],
+        )
+        .expect("record should succeed");
+        assert_eq!(recorded.len(), 2);
+
+        let listed =
+            list_trashed_notes(&harness.db_path, &workspace_a).expect("list should succeed");
+        let paths = listed
+            .iter()
+            .map(|record| record.original_path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, vec!["second.md", "notes/first.md"]);
+
+        assert!(list_trashed_notes(&harness.db_path, &workspace_b)
+            .expect("list should succeed")
+            .is_empty());
+        assert!(
+            get_trashed_note(&harness.db_path, &workspace_b, recorded[0].id)
+                .expect("get should succeed")
+                .is_none(),
+            "ledger entries must not leak across workspaces"
+        );
+    }
+
+    #[test]
+    fn deleted_trashed_notes_are_forgotten() {
+        let harness = TrashHarness::new("mdit-trash-ledger-delete");
+        let workspace = harness.create_workspace("ws");
+
+        let recorded = record_trashed_notes(
+            &harness.db_path,
+            &workspace,
+            &[RecordTrashedNoteInput {
+                original_path: "note.md".to_string(),
+                trash_location: None,
+            }],
+        )
+        .expect("record should succeed");
+
+        delete_trashed_notes(&harness.db_path, &[recorded[0].id]).expect("delete should succeed");
+
+        assert!(
+            get_trashed_note(&harness.db_path, &workspace, recorded[0].id)
+                .expect("get should succeed")
+                .is_none()
+        );
+    }
+
+    fn unique_id() -> u128 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock error")
+            .as_nanos()
+    }
+}
@@ -0,0 +1,38 @@
+use std::borrow::Cow;
+
+use anyhow::{Context, Result};
+use icu_normalizer::ComposingNormalizerBorrowed;
+use rusqlite::{functions::FunctionFlags, Connection};
+
+/// `value` in Unicode NFC. macOS hands out file names in decomposed form
+/// while most editors and link text use composed form, so every path key
+/// the index stores goes through here to compare equal either way.
+pub fn nfc(value: &str) -> Cow<'_, str> {
+    ComposingNormalizerBorrowed::new_nfc().normalize(value)
+}
+
+/// Makes `nfc(text)` available to SQL on `conn`, for migrations that rewrite
+/// stored paths.
+pub(crate) fn register_nfc_function(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "nfc",
+        1,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let value = ctx.get::<Option<String>>(0)?;
+            Ok(value.map(|value| nfc(&value).into_owned()))
+        },
+    )
+    .context("Failed to register nfc SQL function")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::nfc;
+
+    #[test]
+    fn composes_decomposed_text() {
+        assert_eq!(nfc("Cafe\u{301}/ha\u{308}user.md"), "Café/häuser.md");
+        assert_eq!(nfc("Café.md"), "Café.md");
+    }
+}
@@ -8,11 +8,37 @@ use anyhow::{anyhow, Context, Result};
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::Serialize;
 
+use crate::{migrations::WORKSPACE_STATE_DIR_NAME, unicode::nfc};
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VaultEmbeddingConfig {
     pub embedding_provider: String,
     pub embedding_model: String,
+    pub fallback_embedding_provider: Option<String>,
+    pub fallback_embedding_model: Option<String>,
+}
+
+impl VaultEmbeddingConfig {
+    /// Configured `(provider, model)` pairs in the order they should be tried.
+    pub fn profiles(&self) -> Vec<(String, String)> {
+        let mut profiles = vec![(
+            self.embedding_provider.clone(),
+            self.embedding_model.clone(),
+        )];
+
+        if let (Some(provider), Some(model)) = (
+            self.fallback_embedding_provider.as_ref(),
+            self.fallback_embedding_model.as_ref(),
+        ) {
+            let fallback = (provider.clone(), model.clone());
+            if !profiles.contains(&fallback) {
+                profiles.push(fallback);
+            }
+        }
+
+        profiles
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -57,13 +83,27 @@ fn canonicalize_workspace_root(workspace_root: &Path) -> Result<PathBuf> {
     })
 }
 
-fn normalize_workspace_path(path: &Path) -> String {
+/// The vault path as stored and handed back for file access.
+fn workspace_path_string(path: &Path) -> String {
     path.to_string_lossy().replace('\\', "/")
 }
 
-fn normalized_workspace_key(workspace_root: &Path) -> Result<String> {
+/// The key vaults are matched by: the path in Unicode NFC, so a folder
+/// named in decomposed form on macOS finds the same vault.
+fn workspace_key(path: &str) -> String {
+    nfc(path).into_owned()
+}
+
+/// The canonical on-disk path of `workspace_root` and its lookup key.
+fn canonical_workspace(workspace_root: &Path) -> Result<(String, String)> {
     let canonical_root = canonicalize_workspace_root(workspace_root)?;
-    Ok(normalize_workspace_path(&canonical_root))
+    let path = workspace_path_string(&canonical_root);
+    let key = workspace_key(&path);
+    Ok((path, key))
+}
+
+fn normalized_workspace_key(workspace_root: &Path) -> Result<String> {
+    Ok(canonical_workspace(workspace_root)?.1)
 }
 
 fn normalized_workspace_key_from_input(workspace_path: &str) -> Option<String> {
@@ -71,14 +111,14 @@ fn normalized_workspace_key_from_input(workspace_path: &str) -> Option<String> {
     if trimmed.is_empty() {
         return None;
     }
-    Some(trimmed.replace('\\', "/"))
+    Some(workspace_key(&trimmed.replace('\\', "/")))
 }
 
 pub fn find_workspace_id(conn: &Connection, workspace_root: &Path) -> Result<Option<i64>> {
     let workspace_key = normalized_workspace_key(workspace_root)?;
 
     conn.query_row(
-        "SELECT id FROM vault WHERE workspace_root = ?1",
+        "SELECT id FROM vault WHERE workspace_key = ?1",
         params![workspace_key],
         |row| row.get::<_, i64>(0),
     )
@@ -87,16 +127,16 @@ pub fn find_workspace_id(conn: &Connection, workspace_root: &Path) -> Result<Opt
 }
 
 pub fn ensure_workspace_exists(conn: &Connection, workspace_root: &Path) -> Result<i64> {
-    let workspace_key = normalized_workspace_key(workspace_root)?;
+    let (workspace_path, workspace_key) = canonical_workspace(workspace_root)?;
 
     conn.execute(
-        "INSERT OR IGNORE INTO vault (workspace_root) VALUES (?1)",
-        params![workspace_key],
+        "INSERT OR IGNORE INTO vault (workspace_root, workspace_key) VALUES (?1, ?2)",
+        params![workspace_path, workspace_key],
     )
     .context("Failed to ensure vault row exists")?;
 
     conn.query_row(
-        "SELECT id FROM vault WHERE workspace_root = ?1",
+        "SELECT id FROM vault WHERE workspace_key = ?1",
         params![workspace_key],
         |row| row.get::<_, i64>(0),
     )
@@ -111,7 +151,7 @@ pub fn find_workspace_by_path(
     let conn = open_vault_connection(db_path)?;
 
     conn.query_row(
-        "SELECT id, workspace_root, last_opened_at FROM vault WHERE workspace_root = ?1",
+        "SELECT id, workspace_root, last_opened_at FROM vault WHERE workspace_key = ?1",
         params![workspace_key],
         map_vault_workspace_row,
     )
@@ -138,22 +178,61 @@ pub fn get_embedding_config(
     let workspace_key = normalized_workspace_key(workspace_root)?;
     let conn = open_vault_connection(db_path)?;
 
-    let row: Option<(Option<String>, Option<String>)> = conn
+    let row: Option<EmbeddingConfigRow> = conn
         .query_row(
-            "SELECT embedding_provider, embedding_model FROM vault WHERE workspace_root = ?1",
+            "SELECT embedding_provider, embedding_model, \
+                    fallback_embedding_provider, fallback_embedding_model \
+             FROM vault WHERE workspace_key = ?1",
             params![workspace_key],
-            |db_row| Ok((db_row.get(0)?, db_row.get(1)?)),
+            |db_row| {
+                Ok((
+                    db_row.get(0)?,
+                    db_row.get(1)?,
+                    db_row.get(2)?,
+                    db_row.get(3)?,
+                ))
+            },
         )
         .optional()
         .context("Failed to load vault embedding config")?;
 
-    let Some((provider, model)) = row else {
+    let Some((provider, model, fallback_provider, fallback_model)) = row else {
+        return Ok(None);
+    };
+
+    let Some((embedding_provider, embedding_model)) = normalize_embedding_profile(provider, model)
+    else {
         return Ok(None);
     };
 
+    let fallback = normalize_embedding_profile(fallback_provider, fallback_model);
+    let (fallback_embedding_provider, fallback_embedding_model) = match fallback {
+        Some((provider, model)) => (Some(provider), Some(model)),
+        None => (None, None),
+    };
+
+    Ok(Some(VaultEmbeddingConfig {
+        embedding_provider,
+        embedding_model,
+        fallback_embedding_provider,
+        fallback_embedding_model,
+    }))
+}
+
+type EmbeddingConfigRow = (
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+fn normalize_embedding_profile(
+    provider: Option<String>,
+    model: Option<String>,
+) -> Option<(String, String)> {
     let normalized_model = model.unwrap_or_default().trim().to_string();
     if normalized_model.is_empty() {
-        return Ok(None);
+        return None;
     }
 
     let normalized_provider = provider.unwrap_or_default().trim().to_string();
@@ -163,10 +242,7 @@ pub fn get_embedding_config(
         normalized_provider
     };
 
-    Ok(Some(VaultEmbeddingConfig {
-        embedding_provider,
-        embedding_model: normalized_model,
-    }))
+    Some((embedding_provider, normalized_model))
 }
 
 pub fn set_embedding_config(
@@ -204,14 +280,110 @@ pub fn set_embedding_config(
     Ok(())
 }
 
+/// Saves the secondary embedding profile used when the primary provider is unavailable.
+/// An empty model clears the fallback.
+pub fn set_fallback_embedding_config(
+    db_path: &Path,
+    workspace_root: &Path,
+    embedding_provider: &str,
+    embedding_model: &str,
+) -> Result<()> {
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+    let normalized_model = embedding_model.trim();
+
+    if normalized_model.is_empty() {
+        conn.execute(
+            "UPDATE vault \
+             SET fallback_embedding_provider = NULL, fallback_embedding_model = NULL \
+             WHERE id = ?1",
+            params![vault_id],
+        )
+        .context("Failed to clear vault fallback embedding config")?;
+        return Ok(());
+    }
+
+    let normalized_provider = embedding_provider.trim();
+    let provider_to_store = if normalized_provider.is_empty() {
+        "ollama"
+    } else {
+        normalized_provider
+    };
+
+    conn.execute(
+        "UPDATE vault \
+         SET fallback_embedding_provider = ?1, fallback_embedding_model = ?2 \
+         WHERE id = ?3",
+        params![provider_to_store, normalized_model, vault_id],
+    )
+    .context("Failed to save vault fallback embedding config")?;
+
+    Ok(())
+}
+
+/// Frontmatter keys whose values are left out of the search index for this vault.
+pub fn get_excluded_frontmatter_keys(db_path: &Path, workspace_root: &Path) -> Result<Vec<String>> {
+    let conn = open_vault_connection(db_path)?;
+    let Some(vault_id) = find_workspace_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+
+    load_excluded_frontmatter_keys(&conn, vault_id)
+}
+
+pub fn load_excluded_frontmatter_keys(conn: &Connection, vault_id: i64) -> Result<Vec<String>> {
+    let stored: Option<String> = conn
+        .query_row(
+            "SELECT indexing_excluded_frontmatter_keys FROM vault WHERE id = ?1",
+            params![vault_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .context("Failed to load excluded frontmatter keys")?
+        .flatten();
+
+    Ok(stored
+        .map(|value| normalize_frontmatter_keys(value.lines()))
+        .unwrap_or_default())
+}
+
+/// Saves the frontmatter keys excluded from indexing. An empty list clears the setting.
+pub fn set_excluded_frontmatter_keys(
+    db_path: &Path,
+    workspace_root: &Path,
+    keys: &[String],
+) -> Result<()> {
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+    let normalized = normalize_frontmatter_keys(keys.iter().map(String::as_str));
+    let stored = (!normalized.is_empty()).then(|| normalized.join("\n"));
+
+    conn.execute(
+        "UPDATE vault SET indexing_excluded_frontmatter_keys = ?1 WHERE id = ?2",
+        params![stored, vault_id],
+    )
+    .context("Failed to save excluded frontmatter keys")?;
+
+    Ok(())
+}
+
+fn normalize_frontmatter_keys<'a>(keys: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    keys.map(str::trim)
+        .filter(|key| !key.is_empty())
+        .filter(|key| seen.insert(key.to_lowercase()))
+        .map(str::to_string)
+        .collect()
+}
+
 pub fn touch_workspace(db_path: &Path, workspace_root: &Path) -> Result<()> {
-    let workspace_key = normalized_workspace_key(workspace_root)?;
+    let (workspace_path, workspace_key) = canonical_workspace(workspace_root)?;
     let conn = open_vault_connection(db_path)?;
 
     conn.execute(
-        "INSERT INTO vault (workspace_root, last_opened_at) VALUES (?1, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
-         ON CONFLICT(workspace_root) DO UPDATE SET last_opened_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')",
-        params![workspace_key],
+        "INSERT INTO vault (workspace_root, workspace_key, last_opened_at) VALUES (?1, ?2, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+         ON CONFLICT(workspace_key) DO UPDATE SET last_opened_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')",
+        params![workspace_path, workspace_key],
     )
     .context("Failed to touch vault row")?;
 
@@ -264,7 +436,7 @@ pub fn remove_workspace(db_path: &Path, workspace_path: &str) -> Result<()> {
     let conn = open_vault_connection(db_path)?;
     for candidate in candidates {
         conn.execute(
-            "DELETE FROM vault WHERE workspace_root = ?1",
+            "DELETE FROM vault WHERE workspace_key = ?1",
             params![candidate],
         )
         .context("Failed to remove vault row")?;
@@ -273,12 +445,90 @@ pub fn remove_workspace(db_path: &Path, workspace_path: &str) -> Result<()> {
     Ok(())
 }
 
+/// Points the vault registered at `old_path` at `new_root` after its folder
+/// was moved on disk, keeping its index, settings and history. The vault
+/// keeps its id, so nothing needs reindexing.
+///
+/// `old_path` must be gone, since a folder still there was copied rather
+/// than moved. Opening the moved folder first registers it as a new, empty
+/// vault; that row gives way, but one with indexed notes does not.
+pub fn migrate_workspace_path(
+    db_path: &Path,
+    old_path: &str,
+    new_root: &Path,
+) -> Result<VaultWorkspace> {
+    let old_key = normalized_workspace_key_from_input(old_path)
+        .ok_or_else(|| anyhow!("Old workspace path must not be empty"))?;
+    if Path::new(old_path).is_dir() {
+        return Err(anyhow!(
+            "Workspace path still exists: {}; move the folder before migrating its vault",
+            old_path
+        ));
+    }
+    let (new_path, new_key) = canonical_workspace(new_root)?;
+    let state_dir = new_root.join(WORKSPACE_STATE_DIR_NAME);
+    if state_dir.exists() && !state_dir.is_dir() {
+        return Err(anyhow!(
+            "Vault state path is not a folder: {}",
+            state_dir.display()
+        ));
+    }
+
+    let mut conn = open_vault_connection(db_path)?;
+    let tx = conn
+        .transaction()
+        .context("Failed to start vault path migration")?;
+    let find_id = |key: &str| {
+        tx.query_row(
+            "SELECT id FROM vault WHERE workspace_key = ?1",
+            params![key],
+            |row| row.get::<_, i64>(0),
+        )
+        .optional()
+        .context("Failed to resolve vault id")
+    };
+    let vault_id =
+        find_id(&old_key)?.ok_or_else(|| anyhow!("No vault is registered at {}", old_path))?;
+    if let Some(existing_id) = find_id(&new_key)?.filter(|id| *id != vault_id) {
+        let indexed_docs: i64 = tx
+            .query_row(
+                "SELECT COUNT(*) FROM doc WHERE vault_id = ?1",
+                params![existing_id],
+                |row| row.get(0),
+            )
+            .context("Failed to count notes of the vault at the new path")?;
+        if indexed_docs > 0 {
+            return Err(anyhow!(
+                "Another indexed vault is already registered at {}",
+                new_path
+            ));
+        }
+        tx.execute("DELETE FROM vault WHERE id = ?1", params![existing_id])
+            .context("Failed to remove the empty vault at the new path")?;
+    }
+
+    tx.execute(
+        "UPDATE vault \
+         SET workspace_root = ?1, workspace_key = ?2, \
+             last_opened_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') \
+         WHERE id = ?3",
+        params![new_path, new_key, vault_id],
+    )
+    .context("Failed to update vault path")?;
+    tx.commit()
+        .context("Failed to commit vault path migration")?;
+
+    get_workspace_by_id(db_path, vault_id)?
+        .ok_or_else(|| anyhow!("Vault {} disappeared during migration", vault_id))
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        ensure_workspace_exists, find_workspace_by_path, get_embedding_config, get_workspace_by_id,
-        list_workspaces, list_workspaces_with_meta, remove_workspace, set_embedding_config,
-        touch_workspace,
+        ensure_workspace_exists, find_workspace_by_path, get_embedding_config,
+        get_excluded_frontmatter_keys, get_workspace_by_id, list_workspaces,
+        list_workspaces_with_meta, migrate_workspace_path, remove_workspace, set_embedding_config,
+        set_excluded_frontmatter_keys, set_fallback_embedding_config, touch_workspace,
     };
     use crate::migrations;
     use rusqlite::{params, Connection, OptionalExtension};
@@ -429,6 +679,25 @@ mod tests {
         assert_eq!(before, after);
     }
 
+    #[test]
+    fn given_decomposed_workspace_name_when_touching_then_on_disk_path_is_listed() {
+        let harness = VaultHarness::new("mdit-vault-nfd");
+        let workspace = harness.create_workspace("Cafe\u{301}");
+        let on_disk = VaultHarness::workspace_key(&workspace);
+
+        touch_workspace(&harness.db_path, &workspace).expect("touch should succeed");
+        touch_workspace(&harness.db_path, &workspace).expect("touch should succeed");
+
+        let workspaces = list_workspaces(&harness.db_path).expect("listing should succeed");
+        assert_eq!(workspaces, vec![on_disk.clone()]);
+        assert!(Path::new(&workspaces[0]).is_dir());
+        let stored_key: String = harness
+            .open_connection()
+            .query_row("SELECT workspace_key FROM vault", [], |row| row.get(0))
+            .expect("key should load");
+        assert_eq!(stored_key, crate::unicode::nfc(&on_disk));
+    }
+
     #[test]
     fn given_removed_workspace_candidates_when_removing_then_rows_are_deleted() {
         let harness = VaultHarness::new("mdit-vault-remove");
@@ -439,7 +708,7 @@ mod tests {
         let raw_missing = format!("{}/missing", harness.root.to_string_lossy());
         let conn = harness.open_connection();
         conn.execute(
-            "INSERT INTO vault (workspace_root) VALUES (?1)",
+            "INSERT INTO vault (workspace_root, workspace_key) VALUES (?1, ?1)",
             params![raw_missing.clone()],
         )
         .expect("failed to insert raw missing row");
@@ -470,6 +739,52 @@ mod tests {
         assert!(removed_workspace.is_none());
     }
 
+    #[test]
+    fn given_moved_workspace_when_migrating_path_then_vault_keeps_its_id_and_notes() {
+        let harness = VaultHarness::new("mdit-vault-migrate-path");
+        let before = harness.create_workspace("before");
+        let before_key = VaultHarness::workspace_key(&before);
+        let conn = harness.open_connection();
+        let vault_id = ensure_workspace_exists(&conn, &before).expect("ensure should succeed");
+        conn.execute(
+            "INSERT INTO doc (vault_id, rel_path, content, chunking_version) \
+             VALUES (?1, 'note.md', 'body', 1)",
+            params![vault_id],
+        )
+        .expect("failed to insert doc");
+
+        let after = harness.root.join("after");
+        fs::rename(&before, &after).expect("failed to move workspace");
+        // Opening the moved folder registers it as a new vault first.
+        touch_workspace(&harness.db_path, &after).expect("touch should succeed");
+
+        let migrated = migrate_workspace_path(&harness.db_path, &before_key, &after)
+            .expect("migration should succeed");
+        assert_eq!(migrated.id, vault_id);
+        assert_eq!(migrated.workspace_root, VaultHarness::workspace_key(&after));
+        assert_eq!(
+            list_workspaces(&harness.db_path).expect("list should succeed"),
+            vec![migrated.workspace_root.clone()]
+        );
+        let doc_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM doc WHERE vault_id = ?1",
+                params![vault_id],
+                |row| row.get(0),
+            )
+            .expect("failed to count docs");
+        assert_eq!(doc_count, 1);
+
+        assert!(migrate_workspace_path(&harness.db_path, &before_key, &after).is_err());
+        let copy = harness.create_workspace("copy");
+        touch_workspace(&harness.db_path, &copy).expect("touch should succeed");
+        assert!(
+            migrate_workspace_path(&harness.db_path, copy.to_string_lossy().as_ref(), &after)
+                .is_err(),
+            "a folder still in place was not moved"
+        );
+    }
+
     #[test]
     fn given_saved_embedding_config_when_loading_then_it_roundtrips() {
         let harness = VaultHarness::new("mdit-vault-embedding-roundtrip");
@@ -566,6 +881,96 @@ mod tests {
         assert_eq!(config_b.embedding_model, "text-embedding-3-small");
     }
 
+    #[test]
+    fn given_fallback_embedding_config_when_loading_then_profiles_are_ordered() {
+        let harness = VaultHarness::new("mdit-vault-embedding-fallback");
+        let workspace = harness.create_workspace("ws");
+
+        set_embedding_config(&harness.db_path, &workspace, "ollama", "model-a")
+            .expect("set primary config should succeed");
+        set_fallback_embedding_config(
+            &harness.db_path,
+            &workspace,
+            "openai",
+            "text-embedding-3-small",
+        )
+        .expect("set fallback config should succeed");
+
+        let config = get_embedding_config(&harness.db_path, &workspace)
+            .expect("get config should succeed")
+            .expect("config should exist");
+
+        assert_eq!(
+            config.fallback_embedding_provider.as_deref(),
+            Some("openai")
+        );
+        assert_eq!(
+            config.fallback_embedding_model.as_deref(),
+            Some("text-embedding-3-small")
+        );
+        assert_eq!(
+            config.profiles(),
+            vec![
+                ("ollama".to_string(), "model-a".to_string()),
+                ("openai".to_string(), "text-embedding-3-small".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn given_cleared_fallback_when_loading_then_only_primary_profile_remains() {
+        let harness = VaultHarness::new("mdit-vault-embedding-fallback-clear");
+        let workspace = harness.create_workspace("ws");
+
+        set_embedding_config(&harness.db_path, &workspace, "ollama", "model-a")
+            .expect("set primary config should succeed");
+        set_fallback_embedding_config(&harness.db_path, &workspace, "ollama", "model-b")
+            .expect("set fallback config should succeed");
+        set_fallback_embedding_config(&harness.db_path, &workspace, "ollama", "")
+            .expect("clear fallback config should succeed");
+
+        let config = get_embedding_config(&harness.db_path, &workspace)
+            .expect("get config should succeed")
+            .expect("config should exist");
+
+        assert!(config.fallback_embedding_provider.is_none());
+        assert!(config.fallback_embedding_model.is_none());
+        assert_eq!(
+            config.profiles(),
+            vec![("ollama".to_string(), "model-a".to_string())]
+        );
+    }
+
+    #[test]
+    fn given_excluded_frontmatter_keys_when_saving_then_they_roundtrip_trimmed_and_deduplicated() {
+        let harness = VaultHarness::new("mdit-vault-excluded-keys");
+        let workspace = harness.create_workspace("ws");
+
+        assert!(get_excluded_frontmatter_keys(&harness.db_path, &workspace)
+            .expect("get keys should succeed")
+            .is_empty());
+
+        let keys = vec![
+            " cssclasses ".to_string(),
+            "".to_string(),
+            "Banner".to_string(),
+            "banner".to_string(),
+        ];
+        set_excluded_frontmatter_keys(&harness.db_path, &workspace, &keys)
+            .expect("set keys should succeed");
+        assert_eq!(
+            get_excluded_frontmatter_keys(&harness.db_path, &workspace)
+                .expect("get keys should succeed"),
+            vec!["cssclasses".to_string(), "Banner".to_string()]
+        );
+
+        set_excluded_frontmatter_keys(&harness.db_path, &workspace, &[])
+            .expect("clear keys should succeed");
+        assert!(get_excluded_frontmatter_keys(&harness.db_path, &workspace)
+            .expect("get keys should succeed")
+            .is_empty());
+    }
+
     fn unique_id() -> u128 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::spellcheck_words::{add_spellcheck_word, list_spellcheck_words};
+use crate::vault::{
+    get_embedding_config, get_excluded_frontmatter_keys, set_embedding_config,
+    set_excluded_frontmatter_keys, set_fallback_embedding_config,
+};
+use crate::vault_settings::{list_vault_settings, set_vault_setting};
+
+/// Where the exported configuration lives, relative to the vault root. The
+/// dot folder keeps it out of the note tree and the index.
+pub const VAULT_CONFIG_PATH: &str = ".mdit/config.json";
+const VAULT_CONFIG_VERSION: u32 = 1;
+
+/// The vault's mdit configuration that does not depend on the index, in the
+/// shape it is written to [`VAULT_CONFIG_PATH`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VaultConfig {
+    pub version: u32,
+    /// Settings changed from their defaults, by key.
+    pub settings: BTreeMap<String, JsonValue>,
+    pub excluded_frontmatter_keys: Vec<String>,
+    pub spellcheck_words: Vec<String>,
+    pub embedding: Option<VaultConfigEmbedding>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultConfigEmbedding {
+    pub provider: String,
+    pub model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_model: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultConfigImport {
+    pub applied_settings: Vec<String>,
+    /// Settings that are unknown to this build or whose value no longer
+    /// fits, with the reason.
+    pub skipped_settings: Vec<String>,
+    pub added_spellcheck_words: usize,
+    pub embedding_applied: bool,
+}
+
+/// Writes the vault's configuration to [`VAULT_CONFIG_PATH`] and returns the
+/// file's path.
+pub fn export_vault_config(db_path: &Path, workspace_root: &Path) -> Result<PathBuf> {
+    let config = load_vault_config(db_path, workspace_root)?;
+    let path = workspace_root.join(VAULT_CONFIG_PATH);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let contents =
+        serde_json::to_string_pretty(&config).context("Failed to serialize vault config")?;
+    fs::write(&path, format!("{contents}\n"))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    Ok(path)
+}
+
+/// Applies the configuration in [`VAULT_CONFIG_PATH`] on top of the vault's
+/// current one. Settings and embedding models in the file win; spellcheck
+/// words and excluded keys are merged.
+pub fn import_vault_config(db_path: &Path, workspace_root: &Path) -> Result<VaultConfigImport> {
+    let path = workspace_root.join(VAULT_CONFIG_PATH);
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let config = serde_json::from_str::<VaultConfig>(&contents)
+        .with_context(|| format!("Invalid vault config in {}", path.display()))?;
+    if config.version > VAULT_CONFIG_VERSION {
+        return Err(anyhow!(
+            "Vault config version {} is newer than this app supports",
+            config.version
+        ));
+    }
+
+    let mut result = VaultConfigImport::default();
+    for (key, value) in &config.settings {
+        match set_vault_setting(db_path, workspace_root, key, value) {
+            Ok(_) => result.applied_settings.push(key.clone()),
+            Err(error) => result.skipped_settings.push(format!("{key}: {error}")),
+        }
+    }
+
+    if !config.excluded_frontmatter_keys.is_empty() {
+        let mut keys = get_excluded_frontmatter_keys(db_path, workspace_root)?;
+        keys.extend(config.excluded_frontmatter_keys.iter().cloned());
+        set_excluded_frontmatter_keys(db_path, workspace_root, &keys)?;
+    }
+
+    let known_words = list_spellcheck_words(db_path, workspace_root)?;
+    for word in &config.spellcheck_words {
+        if known_words.contains(word) {
+            continue;
+        }
+        // A word the current rules reject is left out rather than failing
+        // the whole import.
+        if add_spellcheck_word(db_path, workspace_root, word).is_ok() {
+            result.added_spellcheck_words += 1;
+        }
+    }
+
+    if let Some(embedding) = &config.embedding {
+        set_embedding_config(
+            db_path,
+            workspace_root,
+            &embedding.provider,
+            &embedding.model,
+        )?;
+        set_fallback_embedding_config(
+            db_path,
+            workspace_root,
+            embedding.fallback_provider.as_deref().unwrap_or_default(),
+            embedding.fallback_model.as_deref().unwrap_or_default(),
+        )?;
+        result.embedding_applied = true;
+    }
+
+    Ok(result)
+}
+
+fn load_vault_config(db_path: &Path, workspace_root: &Path) -> Result<VaultConfig> {
+    let settings = list_vault_settings(db_path, workspace_root)?
+        .into_iter()
+        .filter(|setting| setting.updated_at.is_some())
+        .map(|setting| (setting.key, setting.value))
+        .collect();
+    let embedding =
+        get_embedding_config(db_path, workspace_root)?.map(|config| VaultConfigEmbedding {
+            provider: config.embedding_provider,
+            model: config.embedding_model,
+            fallback_provider: config.fallback_embedding_provider,
+            fallback_model: config.fallback_embedding_model,
+        });
+
+    Ok(VaultConfig {
+        version: VAULT_CONFIG_VERSION,
+        settings,
+        excluded_frontmatter_keys: get_excluded_frontmatter_keys(db_path, workspace_root)?,
+        spellcheck_words: list_spellcheck_words(db_path, workspace_root)?,
+        embedding,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_vault_config, import_vault_config, VAULT_CONFIG_PATH};
+    use crate::{
+        migrations,
+        spellcheck_words::{add_spellcheck_word, list_spellcheck_words},
+        vault::{get_embedding_config, set_embedding_config, touch_workspace},
+        vault_settings::{get_vault_setting, set_vault_setting},
+    };
+    use serde_json::json;
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn exported_config_applies_to_the_same_vault_on_another_machine() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-vault-config-{nanos}"));
+        let vault = root.join("vault");
+        fs::create_dir_all(&vault).expect("create vault");
+        let source_db = root.join("source.sqlite");
+        let target_db = root.join("target.sqlite");
+        migrations::run_migrations_at(&source_db).expect("migrations run");
+        migrations::run_migrations_at(&target_db).expect("migrations run");
+
+        set_vault_setting(&source_db, &vault, "archiveFolder", &json!("Old")).expect("set");
+        add_spellcheck_word(&source_db, &vault, "mdit").expect("add word");
+        set_embedding_config(&source_db, &vault, "ollama", "nomic-embed-text").expect("embed");
+        let path = export_vault_config(&source_db, &vault).expect("export");
+        assert_eq!(path, vault.join(VAULT_CONFIG_PATH));
+
+        let mut exported: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).expect("read export")).expect("json");
+        assert_eq!(exported["settings"], json!({ "archiveFolder": "Old" }));
+        exported["settings"]["retiredSetting"] = json!(true);
+        fs::write(&path, exported.to_string()).expect("write export");
+
+        touch_workspace(&target_db, &vault).expect("register vault");
+        let imported = import_vault_config(&target_db, &vault).expect("import");
+
+        assert_eq!(imported.applied_settings, vec!["archiveFolder"]);
+        assert_eq!(imported.skipped_settings.len(), 1);
+        assert_eq!(imported.added_spellcheck_words, 1);
+        assert_eq!(
+            get_vault_setting(&target_db, &vault, "archiveFolder")
+                .expect("setting loads")
+                .value,
+            json!("Old")
+        );
+        assert_eq!(
+            list_spellcheck_words(&target_db, &vault).expect("words load"),
+            vec!["mdit"]
+        );
+        assert_eq!(
+            get_embedding_config(&target_db, &vault)
+                .expect("embedding loads")
+                .map(|config| config.embedding_model),
+            Some("nomic-embed-text".to_string())
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,633 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+
+use crate::vault::{ensure_workspace_exists, find_workspace_id, open_vault_connection};
+
+/// Settings a vault can override. Each key carries the JSON Schema its value
+/// must satisfy and the value used while it is unset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultSettingKey {
+    /// Folder, relative to the vault root, that archived notes are moved to.
+    ArchiveFolder,
+    /// Folder, relative to the vault root, that new attachments are saved to.
+    AttachmentFolder,
+    /// BibTeX or CSL-JSON file, relative to the vault root, that citations
+    /// are looked up in. Empty while none is configured.
+    BibliographyPath,
+    /// How often the index is checked against the files on disk, to catch
+    /// changes the file watcher missed.
+    ConsistencySweep,
+    /// Date format used for daily note file names.
+    DailyNoteFormat,
+    /// Days the index remembers a deleted note, so restoring it keeps its
+    /// identity and backlinks. Zero forgets deleted notes right away.
+    DeletedNoteRetentionDays,
+    /// Gitignore-style patterns for paths the app should leave alone.
+    IgnorePatterns,
+    /// When background index maintenance may run.
+    Maintenance,
+    /// Size in megabytes above which a note is indexed by its head only.
+    MaxIndexedFileMegabytes,
+    /// Folder, relative to the vault root, that calendar imports write
+    /// meeting notes to.
+    MeetingNotesFolder,
+    /// Folder, relative to the vault root, holding a page per person that
+    /// name mentions are suggested as links to.
+    PeopleFolder,
+    /// Folders, relative to the vault root, that the local API and MCP tools
+    /// may not read or write. The app itself still opens them.
+    PrivatePaths,
+    /// Secrets masked in note text the local API and MCP tools return:
+    /// built-in patterns for emails, API keys and US social security
+    /// numbers, plus the vault's own regular expressions.
+    Redaction,
+    /// Whether related notes favour recently modified ones, and how many days
+    /// it takes a note's similarity to count half as much.
+    RelatedNotesRecency,
+    /// Whether executed search queries are remembered for suggestions.
+    SearchHistory,
+    /// How note text is split into words for keyword search. Changing it
+    /// rebuilds the vault's full-text index on the next index run.
+    SearchTokenizer,
+    /// Relative weight of each signal when ranking search results.
+    SearchWeights,
+    /// Whether note metadata is exported for macOS Spotlight.
+    SpotlightMetadata,
+    /// Backend that transcribes audio linked from notes, and the folder whose
+    /// recordings get a note each. Off until a backend is chosen.
+    Transcription,
+}
+
+impl VaultSettingKey {
+    pub const ALL: [Self; 19] = [
+        Self::ArchiveFolder,
+        Self::AttachmentFolder,
+        Self::BibliographyPath,
+        Self::ConsistencySweep,
+        Self::DailyNoteFormat,
+        Self::DeletedNoteRetentionDays,
+        Self::IgnorePatterns,
+        Self::Maintenance,
+        Self::MaxIndexedFileMegabytes,
+        Self::MeetingNotesFolder,
+        Self::PeopleFolder,
+        Self::PrivatePaths,
+        Self::Redaction,
+        Self::RelatedNotesRecency,
+        Self::SearchHistory,
+        Self::SearchTokenizer,
+        Self::SearchWeights,
+        Self::SpotlightMetadata,
+        Self::Transcription,
+    ];
+
+    pub fn parse(key: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.as_str() == key)
+            .ok_or_else(|| anyhow!("Unknown vault setting: {}", key))
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ArchiveFolder => "archiveFolder",
+            Self::AttachmentFolder => "attachmentFolder",
+            Self::BibliographyPath => "bibliographyPath",
+            Self::ConsistencySweep => "consistencySweep",
+            Self::DailyNoteFormat => "dailyNoteFormat",
+            Self::DeletedNoteRetentionDays => "deletedNoteRetentionDays",
+            Self::IgnorePatterns => "ignorePatterns",
+            Self::Maintenance => "maintenance",
+            Self::MaxIndexedFileMegabytes => "maxIndexedFileMegabytes",
+            Self::MeetingNotesFolder => "meetingNotesFolder",
+            Self::PeopleFolder => "peopleFolder",
+            Self::PrivatePaths => "privatePaths",
+            Self::Redaction => "redaction",
+            Self::RelatedNotesRecency => "relatedNotesRecency",
+            Self::SearchHistory => "searchHistory",
+            Self::SearchTokenizer => "searchTokenizer",
+            Self::SearchWeights => "searchWeights",
+            Self::SpotlightMetadata => "spotlightMetadata",
+            Self::Transcription => "transcription",
+        }
+    }
+
+    pub fn schema(self) -> JsonValue {
+        match self {
+            Self::ArchiveFolder => json!({ "type": "string", "minLength": 1, "maxLength": 255 }),
+            Self::AttachmentFolder => json!({ "type": "string", "maxLength": 255 }),
+            Self::BibliographyPath => json!({ "type": "string", "maxLength": 255 }),
+            Self::ConsistencySweep => json!({
+                "type": "object",
+                "properties": {
+                    "enabled": { "type": "boolean" },
+                    "intervalMinutes": { "type": "integer", "minimum": 5, "maximum": 10080 }
+                },
+                "additionalProperties": false
+            }),
+            Self::DailyNoteFormat => json!({ "type": "string", "minLength": 1, "maxLength": 64 }),
+            Self::DeletedNoteRetentionDays => {
+                json!({ "type": "integer", "minimum": 0, "maximum": 365 })
+            }
+            Self::IgnorePatterns => json!({
+                "type": "array",
+                "maxItems": 200,
+                "items": { "type": "string", "minLength": 1, "maxLength": 255 }
+            }),
+            Self::Maintenance => json!({
+                "type": "object",
+                "properties": {
+                    "enabled": { "type": "boolean" },
+                    "onlyOnAcPower": { "type": "boolean" },
+                    "idleSeconds": { "type": "integer", "minimum": 30, "maximum": 86400 }
+                },
+                "additionalProperties": false
+            }),
+            Self::MaxIndexedFileMegabytes => {
+                json!({ "type": "integer", "minimum": 1, "maximum": 1024 })
+            }
+            Self::MeetingNotesFolder => {
+                json!({ "type": "string", "minLength": 1, "maxLength": 255 })
+            }
+            Self::PeopleFolder => json!({ "type": "string", "minLength": 1, "maxLength": 255 }),
+            Self::PrivatePaths => json!({
+                "type": "array",
+                "maxItems": 200,
+                "items": { "type": "string", "minLength": 1, "maxLength": 255 }
+            }),
+            Self::Redaction => json!({
+                "type": "object",
+                "properties": {
+                    "emails": { "type": "boolean" },
+                    "apiKeys": { "type": "boolean" },
+                    "ssns": { "type": "boolean" },
+                    "patterns": {
+                        "type": "array",
+                        "maxItems": 50,
+                        "items": { "type": "string", "minLength": 1, "maxLength": 500 }
+                    }
+                },
+                "additionalProperties": false
+            }),
+            Self::RelatedNotesRecency => json!({
+                "type": "object",
+                "properties": {
+                    "enabled": { "type": "boolean" },
+                    "halfLifeDays": { "type": "number", "minimum": 1, "maximum": 3650 }
+                },
+                "additionalProperties": false
+            }),
+            Self::SearchHistory => json!({ "type": "boolean" }),
+            Self::SearchTokenizer => json!({
+                "type": "object",
+                "properties": {
+                    "tokenizer": { "type": "string", "enum": ["unicode61", "porter", "trigram"] },
+                    "tokenChars": { "type": "string", "maxLength": 32 }
+                },
+                "additionalProperties": false
+            }),
+            Self::SearchWeights => {
+                let weight = json!({ "type": "number", "minimum": 0, "maximum": 10 });
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "title": weight,
+                        "content": weight,
+                        "tags": weight,
+                        "semantic": weight
+                    },
+                    "additionalProperties": false
+                })
+            }
+            Self::SpotlightMetadata => json!({ "type": "boolean" }),
+            Self::Transcription => {
+                let path = json!({ "type": "string", "maxLength": 1024 });
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "backend": { "type": "string", "enum": ["off", "whisperCpp", "http"] },
+                        "whisperBinary": path,
+                        "whisperModel": path,
+                        "endpoint": path,
+                        "apiKeyRef": { "type": "string", "maxLength": 255 },
+                        "model": { "type": "string", "maxLength": 255 },
+                        "language": { "type": "string", "maxLength": 16 },
+                        "voiceFolder": { "type": "string", "minLength": 1, "maxLength": 255 }
+                    },
+                    "additionalProperties": false
+                })
+            }
+        }
+    }
+
+    pub fn default_value(self) -> JsonValue {
+        match self {
+            Self::ArchiveFolder => json!("Archive"),
+            Self::AttachmentFolder => json!(""),
+            Self::BibliographyPath => json!(""),
+            Self::ConsistencySweep => json!({ "enabled": true, "intervalMinutes": 360 }),
+            Self::DailyNoteFormat => json!("YYYY-MM-DD"),
+            Self::DeletedNoteRetentionDays => json!(30),
+            Self::IgnorePatterns => json!([]),
+            Self::Maintenance => json!({
+                "enabled": true,
+                "onlyOnAcPower": true,
+                "idleSeconds": 300
+            }),
+            Self::MaxIndexedFileMegabytes => json!(8),
+            Self::MeetingNotesFolder => json!("Meetings"),
+            Self::PeopleFolder => json!("People"),
+            Self::PrivatePaths => json!([]),
+            Self::Redaction => json!({
+                "emails": false,
+                "apiKeys": false,
+                "ssns": false,
+                "patterns": []
+            }),
+            Self::RelatedNotesRecency => json!({ "enabled": false, "halfLifeDays": 180 }),
+            Self::SearchHistory => json!(true),
+            Self::SearchTokenizer => json!({ "tokenizer": "unicode61", "tokenChars": "" }),
+            Self::SearchWeights => json!({
+                "title": 1,
+                "content": 1,
+                "tags": 1,
+                "semantic": 1
+            }),
+            Self::SpotlightMetadata => json!(false),
+            Self::Transcription => json!({
+                "backend": "off",
+                "whisperBinary": "",
+                "whisperModel": "",
+                "endpoint": "",
+                "apiKeyRef": "",
+                "model": "whisper-1",
+                "language": "",
+                "voiceFolder": "Voice"
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultSetting {
+    pub key: String,
+    pub value: JsonValue,
+    /// `None` while the default is in effect.
+    pub updated_at: Option<String>,
+}
+
+impl VaultSetting {
+    fn default_for(key: VaultSettingKey) -> Self {
+        Self {
+            key: key.as_str().to_string(),
+            value: key.default_value(),
+            updated_at: None,
+        }
+    }
+}
+
+/// Every known setting of the workspace, defaults filled in.
+pub fn list_vault_settings(db_path: &Path, workspace_root: &Path) -> Result<Vec<VaultSetting>> {
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = find_workspace_id(&conn, workspace_root)?;
+
+    VaultSettingKey::ALL
+        .into_iter()
+        .map(|key| match vault_id {
+            Some(vault_id) => load_vault_setting(&conn, vault_id, key),
+            None => Ok(VaultSetting::default_for(key)),
+        })
+        .collect()
+}
+
+pub fn get_vault_setting(db_path: &Path, workspace_root: &Path, key: &str) -> Result<VaultSetting> {
+    let key = VaultSettingKey::parse(key)?;
+    let conn = open_vault_connection(db_path)?;
+    match find_workspace_id(&conn, workspace_root)? {
+        Some(vault_id) => load_vault_setting(&conn, vault_id, key),
+        None => Ok(VaultSetting::default_for(key)),
+    }
+}
+
+/// Validates `value` against the key's schema and stores it.
+pub fn set_vault_setting(
+    db_path: &Path,
+    workspace_root: &Path,
+    key: &str,
+    value: &JsonValue,
+) -> Result<VaultSetting> {
+    let key = VaultSettingKey::parse(key)?;
+    validate_json_schema(&key.schema(), value)
+        .map_err(|error| anyhow!("Invalid value for {}: {}", key.as_str(), error))?;
+
+    let conn = open_vault_connection(db_path)?;
+    let vault_id = ensure_workspace_exists(&conn, workspace_root)?;
+    conn.execute(
+        "INSERT INTO vault_setting (vault_id, key, value) VALUES (?1, ?2, ?3)
+         ON CONFLICT(vault_id, key) DO UPDATE SET
+             value = excluded.value,
+             updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')",
+        params![vault_id, key.as_str(), value.to_string()],
+    )
+    .context("Failed to save vault setting")?;
+
+    load_vault_setting(&conn, vault_id, key)
+}
+
+/// Drops the stored value so the default applies again.
+pub fn reset_vault_setting(
+    db_path: &Path,
+    workspace_root: &Path,
+    key: &str,
+) -> Result<VaultSetting> {
+    let key = VaultSettingKey::parse(key)?;
+    let conn = open_vault_connection(db_path)?;
+    if let Some(vault_id) = find_workspace_id(&conn, workspace_root)? {
+        conn.execute(
+            "DELETE FROM vault_setting WHERE vault_id = ?1 AND key = ?2",
+            params![vault_id, key.as_str()],
+        )
+        .context("Failed to reset vault setting")?;
+    }
+
+    Ok(VaultSetting::default_for(key))
+}
+
+/// Loads `key` on an open connection, for callers that already hold one.
+pub fn load_vault_setting(
+    conn: &Connection,
+    vault_id: i64,
+    key: VaultSettingKey,
+) -> Result<VaultSetting> {
+    let stored = conn
+        .query_row(
+            "SELECT value, updated_at FROM vault_setting WHERE vault_id = ?1 AND key = ?2",
+            params![vault_id, key.as_str()],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)),
+        )
+        .optional()
+        .context("Failed to load vault setting")?;
+
+    let Some((raw_value, updated_at)) = stored else {
+        return Ok(VaultSetting::default_for(key));
+    };
+
+    // A value written by an older build may no longer fit the schema.
+    let value = serde_json::from_str::<JsonValue>(&raw_value)
+        .ok()
+        .filter(|value| validate_json_schema(&key.schema(), value).is_ok());
+    Ok(match value {
+        Some(value) => VaultSetting {
+            key: key.as_str().to_string(),
+            value,
+            updated_at: Some(updated_at),
+        },
+        None => VaultSetting::default_for(key),
+    })
+}
+
+/// Checks `value` against the subset of JSON Schema the setting schemas use:
+/// `type`, `enum`, `minLength`/`maxLength`, `minimum`/`maximum`,
+/// `items`/`maxItems`, `properties`, `required` and
+/// `additionalProperties: false`.
+pub fn validate_json_schema(schema: &JsonValue, value: &JsonValue) -> Result<(), String> {
+    validate_at(schema, value, "")
+}
+
+fn validate_at(schema: &JsonValue, value: &JsonValue, path: &str) -> Result<(), String> {
+    let location = if path.is_empty() { "/" } else { path };
+
+    if let Some(expected) = schema.get("type").and_then(JsonValue::as_str) {
+        let matches = match expected {
+            "string" => value.is_string(),
+            "number" => value.is_number(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "boolean" => value.is_boolean(),
+            "array" => value.is_array(),
+            "object" => value.is_object(),
+            "null" => value.is_null(),
+            _ => true,
+        };
+        if !matches {
+            return Err(format!("{location} must be of type {expected}"));
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(JsonValue::as_array) {
+        if !allowed.contains(value) {
+            let allowed = allowed
+                .iter()
+                .map(JsonValue::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(format!("{location} must be one of {allowed}"));
+        }
+    }
+
+    if let Some(text) = value.as_str() {
+        let length = text.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(JsonValue::as_u64) {
+            if length < min {
+                return Err(format!("{location} must be at least {min} characters"));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(JsonValue::as_u64) {
+            if length > max {
+                return Err(format!("{location} must be at most {max} characters"));
+            }
+        }
+    }
+
+    if let Some(number) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(JsonValue::as_f64) {
+            if number < min {
+                return Err(format!("{location} must be at least {min}"));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(JsonValue::as_f64) {
+            if number > max {
+                return Err(format!("{location} must be at most {max}"));
+            }
+        }
+    }
+
+    if let Some(items) = value.as_array() {
+        if let Some(max) = schema.get("maxItems").and_then(JsonValue::as_u64) {
+            if items.len() as u64 > max {
+                return Err(format!("{location} must have at most {max} items"));
+            }
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (index, item) in items.iter().enumerate() {
+                validate_at(item_schema, item, &format!("{path}/{index}"))?;
+            }
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(JsonValue::as_object);
+        if let Some(required) = schema.get("required").and_then(JsonValue::as_array) {
+            for name in required.iter().filter_map(JsonValue::as_str) {
+                if !object.contains_key(name) {
+                    return Err(format!("{location} is missing required property {name}"));
+                }
+            }
+        }
+        for (name, property) in object {
+            let property_path = format!("{path}/{name}");
+            match properties.and_then(|properties| properties.get(name)) {
+                Some(property_schema) => validate_at(property_schema, property, &property_path)?,
+                None if schema.get("additionalProperties") == Some(&JsonValue::Bool(false)) => {
+                    return Err(format!("{property_path} is not allowed"));
+                }
+                None => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        get_vault_setting, list_vault_settings, reset_vault_setting, set_vault_setting,
+        validate_json_schema, VaultSettingKey,
+    };
+    use crate::migrations;
+    use serde_json::json;
+    use std::{
+        fs,
+        path::PathBuf,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    struct SettingsHarness {
+        root: PathBuf,
+        db_path: PathBuf,
+        workspace: PathBuf,
+    }
+
+    impl SettingsHarness {
+        fn new(prefix: &str) -> Self {
+            let mut root = std::env::temp_dir();
+            root.push(format!("{prefix}-{}", unique_id()));
+            let workspace = root.join("vault");
+            fs::create_dir_all(&workspace).expect("failed to create workspace");
+
+            let db_path = root.join("settings-test.sqlite");
+            migrations::run_migrations_at(&db_path).expect("failed to run test migrations");
+
+            Self {
+                root,
+                db_path,
+                workspace,
+            }
+        }
+    }
+
+    impl Drop for SettingsHarness {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    fn unique_id() -> u128 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock error")
+            .as_nanos()
+    }
+
+    #[test]
+    fn settings_fall_back_to_defaults_until_set_and_after_reset() {
+        let harness = SettingsHarness::new("mdit-vault-settings-roundtrip");
+
+        let initial = get_vault_setting(&harness.db_path, &harness.workspace, "dailyNoteFormat")
+            .expect("default should load");
+        assert_eq!(initial.value, json!("YYYY-MM-DD"));
+        assert_eq!(initial.updated_at, None);
+
+        let saved = set_vault_setting(
+            &harness.db_path,
+            &harness.workspace,
+            "ignorePatterns",
+            &json!(["drafts/**", "*.tmp"]),
+        )
+        .expect("valid value should save");
+        assert_eq!(saved.value, json!(["drafts/**", "*.tmp"]));
+        assert!(saved.updated_at.is_some());
+
+        let listed = list_vault_settings(&harness.db_path, &harness.workspace)
+            .expect("settings should list");
+        assert_eq!(listed.len(), VaultSettingKey::ALL.len());
+        assert!(listed.contains(&saved));
+
+        let reset = reset_vault_setting(&harness.db_path, &harness.workspace, "ignorePatterns")
+            .expect("reset should succeed");
+        assert_eq!(reset.value, json!([]));
+        let reloaded = get_vault_setting(&harness.db_path, &harness.workspace, "ignorePatterns")
+            .expect("setting should load");
+        assert_eq!(reloaded, reset);
+    }
+
+    #[test]
+    fn set_vault_setting_rejects_unknown_keys_and_invalid_values() {
+        let harness = SettingsHarness::new("mdit-vault-settings-invalid");
+
+        assert!(set_vault_setting(
+            &harness.db_path,
+            &harness.workspace,
+            "theme",
+            &json!("dark")
+        )
+        .is_err());
+
+        let error = set_vault_setting(
+            &harness.db_path,
+            &harness.workspace,
+            "searchWeights",
+            &json!({ "title": 20 }),
+        )
+        .expect_err("out of range weight should fail");
+        assert!(error.to_string().contains("/title must be at most 10"));
+
+        let unchanged = get_vault_setting(&harness.db_path, &harness.workspace, "searchWeights")
+            .expect("setting should load");
+        assert_eq!(unchanged.updated_at, None);
+    }
+
+    #[test]
+    fn validate_json_schema_checks_nested_items_and_extra_properties() {
+        let schema = VaultSettingKey::SearchWeights.schema();
+        assert!(validate_json_schema(&schema, &json!({ "title": 2, "tags": 0.5 })).is_ok());
+        assert_eq!(
+            validate_json_schema(&schema, &json!({ "author": 1 })),
+            Err("/author is not allowed".to_string())
+        );
+
+        let schema = VaultSettingKey::IgnorePatterns.schema();
+        assert_eq!(
+            validate_json_schema(&schema, &json!(["ok", ""])),
+            Err("/1 must be at least 1 characters".to_string())
+        );
+        assert_eq!(
+            validate_json_schema(&schema, &json!("*.tmp")),
+            Err("/ must be of type array".to_string())
+        );
+
+        let schema = VaultSettingKey::Transcription.schema();
+        assert!(
+            validate_json_schema(&schema, &VaultSettingKey::Transcription.default_value()).is_ok()
+        );
+        assert_eq!(
+            validate_json_schema(&schema, &json!({ "backend": "cloud" })),
+            Err(r#"/backend must be one of "off", "whisperCpp", "http""#.to_string())
+        );
+    }
+}
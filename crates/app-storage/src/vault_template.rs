@@ -0,0 +1,158 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::vault::touch_workspace;
+use crate::vault_settings::{set_vault_setting, VaultSettingKey};
+
+pub const MDITIGNORE_FILE: &str = ".mditignore";
+
+const MDITIGNORE_CONTENTS: &str = "\
+# Paths mdit leaves alone, one gitignore-style pattern per line.
+node_modules/
+.DS_Store
+Thumbs.db
+";
+
+const INBOX_FOLDER: &str = "Inbox";
+const DAILY_FOLDER: &str = "Daily";
+const TEMPLATES_FOLDER: &str = "Templates";
+const ATTACHMENTS_FOLDER: &str = "Attachments";
+
+/// Layout a new vault starts with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VaultTemplate {
+    /// Inbox, daily notes, templates and attachments folders, with
+    /// attachments saved to the attachments folder.
+    #[default]
+    Starter,
+    /// Only the ignore file; settings stay at their defaults.
+    Empty,
+}
+
+impl VaultTemplate {
+    fn folders(self) -> &'static [&'static str] {
+        match self {
+            Self::Starter => &[
+                INBOX_FOLDER,
+                DAILY_FOLDER,
+                TEMPLATES_FOLDER,
+                ATTACHMENTS_FOLDER,
+            ],
+            Self::Empty => &[],
+        }
+    }
+
+    fn settings(self) -> Vec<(VaultSettingKey, serde_json::Value)> {
+        match self {
+            Self::Starter => vec![(VaultSettingKey::AttachmentFolder, json!(ATTACHMENTS_FOLDER))],
+            Self::Empty => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedVault {
+    pub workspace_root: String,
+    /// Files and folders the template created, relative to the vault root.
+    pub created: Vec<String>,
+}
+
+/// Creates a vault at `workspace_root` from `template` and registers it as
+/// the most recently opened workspace. The folder may exist but must be
+/// empty, so an existing vault is never overwritten.
+pub fn create_vault(
+    db_path: &Path,
+    workspace_root: &Path,
+    template: VaultTemplate,
+) -> Result<CreatedVault> {
+    if workspace_root.is_file() {
+        return Err(anyhow!(
+            "Vault path is a file: {}",
+            workspace_root.display()
+        ));
+    }
+    if workspace_root.is_dir() {
+        let mut entries = fs::read_dir(workspace_root)
+            .with_context(|| format!("Failed to read {}", workspace_root.display()))?;
+        if entries.next().is_some() {
+            return Err(anyhow!(
+                "Vault folder is not empty: {}",
+                workspace_root.display()
+            ));
+        }
+    }
+    fs::create_dir_all(workspace_root)
+        .with_context(|| format!("Failed to create vault at {}", workspace_root.display()))?;
+
+    let mut created = Vec::new();
+    for folder in template.folders() {
+        fs::create_dir(workspace_root.join(folder))
+            .with_context(|| format!("Failed to create vault folder {folder}"))?;
+        created.push(folder.to_string());
+    }
+    fs::write(workspace_root.join(MDITIGNORE_FILE), MDITIGNORE_CONTENTS)
+        .with_context(|| format!("Failed to write {MDITIGNORE_FILE}"))?;
+    created.push(MDITIGNORE_FILE.to_string());
+
+    touch_workspace(db_path, workspace_root)?;
+    for (key, value) in template.settings() {
+        set_vault_setting(db_path, workspace_root, key.as_str(), &value)?;
+    }
+
+    Ok(CreatedVault {
+        workspace_root: workspace_root.to_string_lossy().into_owned(),
+        created,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{create_vault, VaultTemplate, MDITIGNORE_FILE};
+    use crate::{migrations, vault::list_workspaces, vault_settings::get_vault_setting};
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    #[test]
+    fn starter_vault_gets_folders_settings_and_registration() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-vault-template-{nanos}"));
+        fs::create_dir_all(&root).expect("create temp root");
+        let db_path = root.join("appdata.sqlite");
+        migrations::run_migrations_at(&db_path).expect("migrations run");
+        let vault = root.join("Notes");
+
+        let created = create_vault(&db_path, &vault, VaultTemplate::Starter).expect("create vault");
+
+        assert_eq!(
+            created.created,
+            vec![
+                "Inbox",
+                "Daily",
+                "Templates",
+                "Attachments",
+                MDITIGNORE_FILE
+            ]
+        );
+        assert!(vault.join("Daily").is_dir());
+        assert!(vault.join(MDITIGNORE_FILE).is_file());
+        let attachment_folder =
+            get_vault_setting(&db_path, &vault, "attachmentFolder").expect("setting loads");
+        assert_eq!(attachment_folder.value, "Attachments");
+        assert_eq!(list_workspaces(&db_path).expect("workspaces load").len(), 1);
+
+        assert!(create_vault(&db_path, &vault, VaultTemplate::Empty).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
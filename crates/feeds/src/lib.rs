@@ -0,0 +1,244 @@
+//! RSS and Atom subscriptions. Each vault keeps its own feeds; refreshing one
+//! turns the items not seen before into notes under `Feeds/<feed title>/`,
+//! so articles are read and annotated like any other note.
+
+mod parse;
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use app_storage::feeds::FeedSubscription;
+use reqwest::blocking::Client;
+use serde::Serialize;
+use serde_json::json;
+use url::Url;
+
+pub use parse::{parse_feed, Feed, FeedItem};
+
+pub const FEEDS_FOLDER: &str = "Feeds";
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_FEED_BYTES: u64 = 10 * 1024 * 1024;
+const USER_AGENT: &str = concat!("mdit-feeds/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedRefresh {
+    pub feed_id: i64,
+    pub title: String,
+    /// Notes written for the items new since the last refresh.
+    pub created: Vec<PathBuf>,
+    pub error: Option<String>,
+}
+
+/// Fetches the feed at `url` to check it and learn its title, then
+/// subscribes the vault to it. Items are fetched by the first refresh.
+pub fn subscribe_feed(
+    db_path: &Path,
+    workspace_root: &Path,
+    url: &str,
+) -> Result<FeedSubscription, String> {
+    let url = parse_feed_url(url)?;
+    let feed = fetch_feed(&url)?;
+    let title = Some(feed.title.trim())
+        .filter(|title| !title.is_empty())
+        .or(url.host_str())
+        .unwrap_or(url.as_str());
+    app_storage::feeds::add_feed_subscription(db_path, workspace_root, url.as_str(), title)
+        .map_err(|error| error.to_string())
+}
+
+/// Refreshes every feed of the vault. A feed that fails does not stop the
+/// others; its error is in its [`FeedRefresh`] and on the subscription.
+pub fn refresh_vault_feeds(
+    db_path: &Path,
+    workspace_root: &Path,
+) -> Result<Vec<FeedRefresh>, String> {
+    let subscriptions = app_storage::feeds::list_feed_subscriptions(db_path, workspace_root)
+        .map_err(|error| error.to_string())?;
+    subscriptions
+        .iter()
+        .map(|subscription| refresh_feed(db_path, workspace_root, subscription))
+        .collect()
+}
+
+/// Writes a note for every item of the feed not seen before and records the
+/// fetch. Items are recognised by GUID, so edited or moved notes are never
+/// written again.
+pub fn refresh_feed(
+    db_path: &Path,
+    workspace_root: &Path,
+    subscription: &FeedSubscription,
+) -> Result<FeedRefresh, String> {
+    let mut refresh = FeedRefresh {
+        feed_id: subscription.id,
+        title: subscription.title.clone(),
+        created: Vec::new(),
+        error: None,
+    };
+    let mut new_guids = Vec::new();
+    let fetched = parse_feed_url(&subscription.url).and_then(|url| fetch_feed(&url));
+    match fetched {
+        Ok(feed) => {
+            let seen =
+                app_storage::feeds::seen_feed_item_guids(db_path, workspace_root, subscription.id)
+                    .map_err(|error| error.to_string())?;
+            let folder = feed_folder(workspace_root, &subscription.title);
+            let mut written = HashSet::new();
+            for item in feed.items {
+                if seen.contains(&item.guid) || !written.insert(item.guid.clone()) {
+                    continue;
+                }
+                match write_item_note(&folder, &subscription.title, &item) {
+                    Ok(path) => {
+                        refresh.created.push(path);
+                        new_guids.push(item.guid);
+                    }
+                    Err(error) => {
+                        refresh.error = Some(error);
+                        break;
+                    }
+                }
+            }
+        }
+        Err(error) => refresh.error = Some(error),
+    }
+
+    let new_guids = new_guids.iter().map(String::as_str).collect::<Vec<_>>();
+    app_storage::feeds::record_feed_fetch(
+        db_path,
+        workspace_root,
+        subscription.id,
+        &new_guids,
+        refresh.error.as_deref(),
+    )
+    .map_err(|error| error.to_string())?;
+    Ok(refresh)
+}
+
+fn parse_feed_url(url: &str) -> Result<Url, String> {
+    Url::parse(url.trim())
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .ok_or_else(|| format!("Feed URL must be an http or https address: {url}"))
+}
+
+fn fetch_feed(url: &Url) -> Result<Feed, String> {
+    let fetch_failed = |error: String| format!("Failed to fetch {url}: {error}");
+    let response = Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(USER_AGENT)
+        .build()
+        .and_then(|client| client.get(url.clone()).send())
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| fetch_failed(error.to_string()))?;
+
+    let mut bytes = Vec::new();
+    response
+        .take(MAX_FEED_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|error| fetch_failed(error.to_string()))?;
+    if bytes.len() as u64 > MAX_FEED_BYTES {
+        return Err(fetch_failed("feed is too large".to_string()));
+    }
+    parse_feed(&String::from_utf8_lossy(&bytes)).map_err(|error| format!("{url}: {error}"))
+}
+
+fn feed_folder(workspace_root: &Path, feed_title: &str) -> PathBuf {
+    let name = note::sanitize_note_title(feed_title);
+    let name = if name.is_empty() { "Feed" } else { &name };
+    workspace_root.join(FEEDS_FOLDER).join(name)
+}
+
+fn write_item_note(folder: &Path, feed_title: &str, item: &FeedItem) -> Result<PathBuf, String> {
+    fs::create_dir_all(folder)
+        .map_err(|error| format!("Failed to create {}: {error}", folder.display()))?;
+    let title = Some(item.title.trim())
+        .filter(|title| !note::sanitize_note_title(title).is_empty())
+        .unwrap_or("Untitled");
+    let path = PathBuf::from(note::unique_note_file_name(folder, title)?.full_path);
+    fs::write(&path, item_note(feed_title, title, item))
+        .map_err(|error| format!("Failed to write {}: {error}", path.display()))?;
+    Ok(path)
+}
+
+/// The item as a note: its content as Markdown under frontmatter naming the
+/// feed, the article and when it was published.
+fn item_note(feed_title: &str, title: &str, item: &FeedItem) -> String {
+    let markdown = note_import::html_fragment_to_markdown(&item.content_html, item.link.as_deref());
+    let mut properties = vec![("title", json!(title)), ("feed", json!(feed_title))];
+    if let Some(link) = &item.link {
+        properties.push(("source", json!(link)));
+    }
+    if let Some(published) = &item.published {
+        properties.push(("published", json!(published_date(published))));
+    }
+    if let Some(author) = &item.author {
+        properties.push(("author", json!(author)));
+    }
+
+    let body = format!("{}\n", markdown.trim_end());
+    properties.into_iter().fold(body, |content, (key, value)| {
+        note::set_frontmatter_property(&content, key, &value).unwrap_or(content)
+    })
+}
+
+/// `YYYY-MM-DD` for the RFC 2822 and RFC 3339 dates feeds use; anything
+/// else is kept as written.
+fn published_date(published: &str) -> String {
+    chrono::DateTime::parse_from_rfc2822(published)
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(published))
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| published.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{feed_folder, item_note, write_item_note, FeedItem};
+
+    #[test]
+    fn items_become_notes_in_the_feed_folder() {
+        let item = FeedItem {
+            guid: "post-7".to_string(),
+            title: "Rye: a guide".to_string(),
+            link: Some("https://kitchen.example/posts/rye".to_string()),
+            published: Some("Tue, 06 Oct 2026 08:00:00 +0000".to_string()),
+            author: None,
+            content_html: "<p>See <a href=\"spelt\">spelt</a>.</p>".to_string(),
+        };
+        assert_eq!(
+            item_note("Kitchen", "Rye: a guide", &item),
+            [
+                "---",
+                "title: 'Rye: a guide'",
+                "feed: Kitchen",
+                "source: https://kitchen.example/posts/rye",
+                "published: 2026-10-06",
+                "---",
+                "See [spelt](https://kitchen.example/posts/spelt).",
+                "",
+            ]
+            .join("\n")
+        );
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let vault = std::env::temp_dir().join(format!("mdit-feeds-notes-{nanos}"));
+        let folder = feed_folder(&vault, "Kitchen / Notes");
+        let first = write_item_note(&folder, "Kitchen", &item).expect("first note");
+        let second = write_item_note(&folder, "Kitchen", &item).expect("second note");
+        assert_eq!(first.parent(), Some(folder.as_path()));
+        assert!(folder.starts_with(vault.join("Feeds")));
+        assert_ne!(first, second);
+
+        let _ = fs::remove_dir_all(&vault);
+    }
+}
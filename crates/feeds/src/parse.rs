@@ -0,0 +1,237 @@
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// A parsed RSS 2.0, RSS 1.0 (RDF) or Atom feed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Feed {
+    pub title: String,
+    pub items: Vec<FeedItem>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedItem {
+    /// The item's `guid` or Atom `id`, else its link or title.
+    pub guid: String,
+    pub title: String,
+    pub link: Option<String>,
+    /// As the feed wrote it: RFC 2822 for RSS, RFC 3339 for Atom.
+    pub published: Option<String>,
+    pub author: Option<String>,
+    /// The full content when the feed has it, else the summary.
+    pub content_html: String,
+}
+
+#[derive(Default)]
+struct ItemFields {
+    item: FeedItem,
+    summary: String,
+    content: String,
+    updated: Option<String>,
+}
+
+impl ItemFields {
+    fn finish(self) -> Option<FeedItem> {
+        let ItemFields {
+            mut item,
+            summary,
+            content,
+            updated,
+        } = self;
+        item.published = item.published.or(updated);
+        item.content_html = if content.trim().is_empty() {
+            summary
+        } else {
+            content
+        };
+        if item.guid.is_empty() {
+            item.guid = item.link.clone().unwrap_or_else(|| item.title.clone());
+        }
+        (!item.guid.is_empty()).then_some(item)
+    }
+}
+
+/// Parses a feed document. Fields are matched by local name, so namespaced
+/// extensions such as `content:encoded` and `dc:creator` are picked up.
+pub fn parse_feed(xml: &str) -> Result<Feed, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut feed = Feed::default();
+    let mut found_root = false;
+    let mut path: Vec<String> = Vec::new();
+    let mut item: Option<ItemFields> = None;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event().map_err(|error| error.to_string())? {
+            Event::Start(element) => {
+                let name = local_name(&element);
+                found_root |= matches!(name.as_str(), "rss" | "RDF" | "feed");
+                if matches!(name.as_str(), "item" | "entry") {
+                    item = Some(ItemFields::default());
+                }
+                if let Some(item) = &mut item {
+                    read_atom_link(&element, &name, item);
+                }
+                path.push(name);
+                text.clear();
+            }
+            Event::Empty(element) => {
+                let name = local_name(&element);
+                if let Some(item) = &mut item {
+                    read_atom_link(&element, &name, item);
+                }
+            }
+            Event::Text(content) => {
+                text.push_str(&content.decode().map_err(|error| error.to_string())?);
+            }
+            Event::CData(content) => {
+                text.push_str(&String::from_utf8_lossy(&content.into_inner()));
+            }
+            Event::GeneralRef(reference) => {
+                let name = reference.decode().map_err(|error| error.to_string())?;
+                match reference.resolve_char_ref() {
+                    Ok(Some(ch)) => text.push(ch),
+                    _ => text.push_str(
+                        quick_xml::escape::resolve_predefined_entity(&name).unwrap_or_default(),
+                    ),
+                }
+            }
+            Event::End(_) => {
+                let Some(name) = path.pop() else {
+                    continue;
+                };
+                let value = std::mem::take(&mut text);
+                let value = value.trim();
+                match &mut item {
+                    Some(_) if matches!(name.as_str(), "item" | "entry") => {
+                        feed.items.extend(item.take().and_then(ItemFields::finish));
+                    }
+                    Some(item) => read_item_field(item, &name, path.last(), value),
+                    None if name == "title"
+                        && feed.title.is_empty()
+                        && path
+                            .last()
+                            .is_some_and(|parent| parent == "channel" || parent == "feed") =>
+                    {
+                        feed.title = value.to_string();
+                    }
+                    None => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !found_root {
+        return Err("Not an RSS or Atom feed".to_string());
+    }
+    Ok(feed)
+}
+
+fn read_item_field(item: &mut ItemFields, name: &str, parent: Option<&String>, value: &str) {
+    if value.is_empty() {
+        return;
+    }
+    let value = value.to_string();
+    match name {
+        "title" => item.item.title = value,
+        "link" if item.item.link.is_none() => item.item.link = Some(value),
+        "guid" | "id" => item.item.guid = value,
+        "pubDate" | "published" | "date" | "issued" => item.item.published = Some(value),
+        "updated" | "modified" => item.updated = Some(value),
+        "creator" => item.item.author = Some(value),
+        "name" if parent.is_some_and(|parent| parent == "author") => item.item.author = Some(value),
+        "author" if item.item.author.is_none() => item.item.author = Some(value),
+        "description" | "summary" => item.summary = value,
+        "encoded" | "content" => item.content = value,
+        _ => {}
+    }
+}
+
+/// Atom links live in attributes; the alternate one points at the article.
+fn read_atom_link(element: &BytesStart, name: &str, item: &mut ItemFields) {
+    if name != "link" {
+        return;
+    }
+    let attribute = |key: &[u8]| {
+        element
+            .try_get_attribute(key)
+            .ok()
+            .flatten()
+            .and_then(|attribute| attribute.unescape_value().ok())
+            .map(|value| value.into_owned())
+    };
+    let Some(href) = attribute(b"href") else {
+        return;
+    };
+    if attribute(b"rel").is_none_or(|rel| rel == "alternate") {
+        item.item.link = Some(href);
+    }
+}
+
+fn local_name(element: &BytesStart) -> String {
+    String::from_utf8_lossy(element.local_name().as_ref()).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_feed;
+
+    #[test]
+    fn reads_rss_and_atom_items() {
+        let rss = r#"<?xml version="1.0"?>
+            <rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/"
+                 xmlns:dc="http://purl.org/dc/elements/1.1/">
+              <channel>
+                <title>Kitchen notes</title>
+                <link>https://kitchen.example</link>
+                <item>
+                  <title>Rye &amp; spelt</title>
+                  <link>https://kitchen.example/rye</link>
+                  <guid isPermaLink="false">post-7</guid>
+                  <pubDate>Tue, 06 Oct 2026 08:00:00 +0000</pubDate>
+                  <dc:creator>Sam</dc:creator>
+                  <description>Short</description>
+                  <content:encoded><![CDATA[<p>Long <b>form</b></p>]]></content:encoded>
+                </item>
+                <item>
+                  <title>No guid</title>
+                  <link>https://kitchen.example/no-guid</link>
+                  <description>&lt;p&gt;Escaped&lt;/p&gt;</description>
+                </item>
+              </channel>
+            </rss>"#;
+        let feed = parse_feed(rss).expect("rss parses");
+        assert_eq!(feed.title, "Kitchen notes");
+        assert_eq!(feed.items.len(), 2);
+        assert_eq!(feed.items[0].guid, "post-7");
+        assert_eq!(feed.items[0].title, "Rye & spelt");
+        assert_eq!(feed.items[0].author.as_deref(), Some("Sam"));
+        assert_eq!(feed.items[0].content_html, "<p>Long <b>form</b></p>");
+        assert_eq!(feed.items[1].guid, "https://kitchen.example/no-guid");
+        assert_eq!(feed.items[1].content_html, "<p>Escaped</p>");
+
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+              <title>Field log</title>
+              <entry>
+                <title>Day one</title>
+                <id>urn:uuid:1</id>
+                <link rel="self" href="https://log.example/1.atom"/>
+                <link href="https://log.example/1"/>
+                <updated>2026-10-01T10:00:00Z</updated>
+                <author><name>Ada</name></author>
+                <summary type="html">&lt;em&gt;Hi&lt;/em&gt;</summary>
+              </entry>
+            </feed>"#;
+        let feed = parse_feed(atom).expect("atom parses");
+        assert_eq!(feed.title, "Field log");
+        let entry = &feed.items[0];
+        assert_eq!(entry.guid, "urn:uuid:1");
+        assert_eq!(entry.link.as_deref(), Some("https://log.example/1"));
+        assert_eq!(entry.published.as_deref(), Some("2026-10-01T10:00:00Z"));
+        assert_eq!(entry.author.as_deref(), Some("Ada"));
+        assert_eq!(entry.content_html, "<em>Hi</em>");
+
+        assert!(parse_feed("<html><body>nope</body></html>").is_err());
+    }
+}
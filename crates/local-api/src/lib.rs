@@ -1,10 +1,26 @@
 pub mod services;
 
+pub use services::append_note::{append_note, AppendNoteInput, AppendedNote};
+pub use services::append_to_inbox::{
+    append_to_inbox, AppendToInboxInput, InboxEntry, INBOX_NOTE_REL_PATH,
+};
+pub use services::build_context::{build_context, BuildContextInput, BuildContextOutput};
+pub use services::clip_note::{clip_note, ClipNoteInput, ClippedNote};
 pub use services::create_note::{create_note, CreateNoteInput, CreatedNote};
 pub use services::list_vaults::{list_vaults, VaultSummary};
+pub use services::note_identity::{
+    get_note_id, resolve_note_id, GetNoteIdInput, NoteIdentity, ResolveNoteIdInput,
+};
 pub use services::search_notes::{
     search_notes, SearchNoteEntry, SearchNotesInput, SearchNotesOutput,
 };
+pub use services::search_segments::{
+    search_segments, SearchSegmentEntry, SearchSegmentsInput, SearchSegmentsOutput,
+};
+pub use services::vault_diagnostics::{
+    get_vault_diagnostics, CaseConflict, DuplicateBasename, VaultDiagnosticsInput,
+    VaultDiagnosticsOutput,
+};
 
 use thiserror::Error;
 
@@ -13,6 +29,10 @@ pub enum LocalApiErrorKind {
     NotFound,
     Conflict,
     InvalidInput,
+    /// The vault keeps the path away from API callers.
+    Forbidden,
+    /// A server the request depends on failed.
+    Upstream,
     Internal,
 }
 
@@ -42,6 +62,42 @@ pub enum LocalApiError {
     #[error("note already exists: {relative_path}")]
     NoteAlreadyExists { relative_path: String },
 
+    #[error("note not found: {relative_path}")]
+    NoteNotFound { relative_path: String },
+
+    #[error("no note with id: {note_id}")]
+    NoteIdNotFound { note_id: String },
+
+    #[error("note is locked: {relative_path}")]
+    NoteLocked { relative_path: String },
+
+    #[error("path is private to the app: {relative_path}")]
+    PrivatePath { relative_path: String },
+
+    #[error("note path is invalid: {relative_path}")]
+    InvalidNotePath { relative_path: String },
+
+    #[error("note content is empty")]
+    EmptyNoteContent,
+
+    #[error("clip needs the page html or its url")]
+    MissingClipSource,
+
+    #[error("context needs a query or note paths")]
+    MissingContextSource,
+
+    #[error("token budget must be between 1 and 100000: {max_tokens}")]
+    InvalidTokenBudget { max_tokens: usize },
+
+    #[error("url must be an http or https address: {url}")]
+    InvalidClipUrl { url: String },
+
+    #[error("failed to fetch {url}: {message}")]
+    ClipFetchFailed { url: String, message: String },
+
+    #[error("redaction pattern {pattern} is invalid: {message}")]
+    InvalidRedactionPattern { pattern: String, message: String },
+
     #[error("internal error: {message}")]
     Internal { message: String },
 }
@@ -51,13 +107,25 @@ impl LocalApiError {
         match self {
             Self::VaultNotFound { .. }
             | Self::VaultWorkspaceUnavailable { .. }
-            | Self::DirectoryNotFound { .. } => LocalApiErrorKind::NotFound,
-            Self::NoteAlreadyExists { .. } => LocalApiErrorKind::Conflict,
+            | Self::DirectoryNotFound { .. }
+            | Self::NoteNotFound { .. }
+            | Self::NoteIdNotFound { .. } => LocalApiErrorKind::NotFound,
+            Self::NoteAlreadyExists { .. } | Self::NoteLocked { .. } => LocalApiErrorKind::Conflict,
             Self::InvalidTitle
             | Self::InvalidSearchQuery
             | Self::InvalidSearchLimit { .. }
-            | Self::InvalidDirectoryPath { .. } => LocalApiErrorKind::InvalidInput,
-            Self::Internal { .. } => LocalApiErrorKind::Internal,
+            | Self::InvalidDirectoryPath { .. }
+            | Self::InvalidNotePath { .. }
+            | Self::EmptyNoteContent
+            | Self::MissingClipSource
+            | Self::MissingContextSource
+            | Self::InvalidTokenBudget { .. }
+            | Self::InvalidClipUrl { .. } => LocalApiErrorKind::InvalidInput,
+            Self::PrivatePath { .. } => LocalApiErrorKind::Forbidden,
+            Self::ClipFetchFailed { .. } => LocalApiErrorKind::Upstream,
+            Self::InvalidRedactionPattern { .. } | Self::Internal { .. } => {
+                LocalApiErrorKind::Internal
+            }
         }
     }
 
@@ -71,6 +139,18 @@ impl LocalApiError {
             Self::InvalidDirectoryPath { .. } => "INVALID_DIRECTORY_REL_PATH",
             Self::DirectoryNotFound { .. } => "DIRECTORY_NOT_FOUND",
             Self::NoteAlreadyExists { .. } => "NOTE_ALREADY_EXISTS",
+            Self::NoteNotFound { .. } => "NOTE_NOT_FOUND",
+            Self::NoteIdNotFound { .. } => "NOTE_ID_NOT_FOUND",
+            Self::NoteLocked { .. } => "NOTE_LOCKED",
+            Self::PrivatePath { .. } => "PRIVATE_PATH",
+            Self::InvalidNotePath { .. } => "INVALID_NOTE_PATH",
+            Self::EmptyNoteContent => "EMPTY_NOTE_CONTENT",
+            Self::MissingClipSource => "MISSING_CLIP_SOURCE",
+            Self::MissingContextSource => "MISSING_CONTEXT_SOURCE",
+            Self::InvalidTokenBudget { .. } => "INVALID_TOKEN_BUDGET",
+            Self::InvalidClipUrl { .. } => "INVALID_CLIP_URL",
+            Self::ClipFetchFailed { .. } => "CLIP_FETCH_FAILED",
+            Self::InvalidRedactionPattern { .. } => "INVALID_REDACTION_PATTERN",
             Self::Internal { .. } => "INTERNAL_ERROR",
         }
     }
@@ -0,0 +1,222 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::LocalApiError;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppendNoteInput {
+    pub vault_id: i64,
+    /// Markdown file relative to the vault root. Missing folders are created.
+    pub relative_path: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppendedNote {
+    pub vault_id: i64,
+    pub workspace_path: String,
+    pub relative_path: String,
+    pub absolute_path: String,
+    /// Whether the note did not exist before this append.
+    pub created: bool,
+}
+
+/// Appends `content` as a new paragraph at the end of a note, creating the
+/// note when it does not exist yet.
+pub fn append_note(db_path: &Path, input: AppendNoteInput) -> Result<AppendedNote, LocalApiError> {
+    let AppendNoteInput {
+        vault_id,
+        relative_path,
+        content,
+    } = input;
+    let content = content.trim_end();
+    if content.trim().is_empty() {
+        return Err(LocalApiError::EmptyNoteContent);
+    }
+
+    let workspace = resolve_workspace(db_path, vault_id)?;
+    let workspace_path = PathBuf::from(&workspace.workspace_root);
+    let relative_path = normalize_note_rel_path(&relative_path)?;
+    let note_path = resolve_note_path(&workspace_path, &relative_path)?;
+
+    let existing = match fs::read_to_string(&note_path) {
+        Ok(existing) => Some(existing),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+        Err(error) => return Err(error.into()),
+    };
+    if existing.as_deref().is_some_and(note::is_locked_source) {
+        return Err(LocalApiError::NoteLocked { relative_path });
+    }
+    let separator = paragraph_separator(existing.as_deref().unwrap_or_default());
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&note_path)?;
+    file.write_all(format!("{separator}{content}\n").as_bytes())?;
+    touch_workspace_best_effort(db_path, &workspace_path);
+
+    Ok(AppendedNote {
+        vault_id: workspace.id,
+        workspace_path: normalize_path_separators(&workspace_path),
+        relative_path,
+        absolute_path: normalize_path_separators(&note_path),
+        created: existing.is_none(),
+    })
+}
+
+fn resolve_workspace(
+    db_path: &Path,
+    vault_id: i64,
+) -> Result<app_storage::vault::VaultWorkspace, LocalApiError> {
+    let workspace = app_storage::vault::get_workspace_by_id(db_path, vault_id)?
+        .ok_or(LocalApiError::VaultNotFound { vault_id })?;
+    let workspace_path = PathBuf::from(&workspace.workspace_root);
+
+    if !workspace_path.is_dir() {
+        return Err(LocalApiError::VaultWorkspaceUnavailable {
+            workspace_path: workspace.workspace_root,
+        });
+    }
+
+    Ok(workspace)
+}
+
+pub(crate) fn normalize_note_rel_path(relative_path: &str) -> Result<String, LocalApiError> {
+    let normalized = relative_path.trim().replace('\\', "/");
+    let invalid = || LocalApiError::InvalidNotePath {
+        relative_path: relative_path.to_string(),
+    };
+
+    if !normalized.to_lowercase().ends_with(".md") || Path::new(&normalized).is_absolute() {
+        return Err(invalid());
+    }
+
+    let mut parts = Vec::new();
+    for component in Path::new(&normalized).components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            _ => return Err(invalid()),
+        }
+    }
+
+    if parts.is_empty() {
+        return Err(invalid());
+    }
+    Ok(parts.join("/"))
+}
+
+pub(super) fn resolve_note_path(
+    workspace_path: &Path,
+    relative_path: &str,
+) -> Result<PathBuf, LocalApiError> {
+    let note_path = workspace_path.join(relative_path);
+    let parent = note_path.parent().unwrap_or(workspace_path);
+    fs::create_dir_all(parent)?;
+
+    // Symlinked folders must not lead the write outside the vault.
+    let canonical_workspace = fs::canonicalize(workspace_path)?;
+    let canonical_parent = fs::canonicalize(parent)?;
+    if !canonical_parent.starts_with(&canonical_workspace) || note_path.is_dir() {
+        return Err(LocalApiError::InvalidNotePath {
+            relative_path: relative_path.to_string(),
+        });
+    }
+
+    Ok(note_path)
+}
+
+/// Text that puts appended content on a paragraph of its own.
+fn paragraph_separator(existing: &str) -> &'static str {
+    if existing.trim().is_empty() || existing.ends_with("\n\n") {
+        ""
+    } else if existing.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    }
+}
+
+fn touch_workspace_best_effort(db_path: &Path, workspace_path: &Path) {
+    if let Err(error) = app_storage::vault::touch_workspace(db_path, workspace_path) {
+        tracing::error!(
+            "Failed to update vault last_opened_at after note append for '{}': {error}",
+            workspace_path.display()
+        );
+    }
+}
+
+fn normalize_path_separators(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::{append_note, AppendNoteInput};
+    use crate::{services::test_support::Harness, LocalApiError};
+
+    fn input(harness: &Harness, relative_path: &str, content: &str) -> AppendNoteInput {
+        AppendNoteInput {
+            vault_id: harness.vault_id,
+            relative_path: relative_path.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn append_note_creates_missing_note_and_separates_paragraphs() {
+        let harness = Harness::new("local-api-append-note");
+        let db_path = Path::new(&harness.db_path);
+
+        let first = append_note(db_path, input(&harness, "Daily/2024-05-01.md", "First"))
+            .expect("append should create the note");
+        assert!(first.created);
+        assert_eq!(first.relative_path, "Daily/2024-05-01.md");
+
+        let second = append_note(
+            db_path,
+            input(&harness, "./Daily\\2024-05-01.md", "Second\n"),
+        )
+        .expect("append should extend the note");
+        assert!(!second.created);
+
+        let note = fs::read_to_string(harness.workspace_path.join("Daily/2024-05-01.md"))
+            .expect("note should exist");
+        assert_eq!(note, "First\n\nSecond\n");
+    }
+
+    #[test]
+    fn append_note_rejects_paths_outside_vault_and_empty_content() {
+        let harness = Harness::new("local-api-append-invalid");
+        let db_path = Path::new(&harness.db_path);
+
+        let result = append_note(db_path, input(&harness, "../Inbox.md", "text"));
+        assert!(matches!(result, Err(LocalApiError::InvalidNotePath { .. })));
+
+        let result = append_note(db_path, input(&harness, "Inbox.txt", "text"));
+        assert!(matches!(result, Err(LocalApiError::InvalidNotePath { .. })));
+
+        let result = append_note(db_path, input(&harness, "Inbox.md", " \n "));
+        assert!(matches!(result, Err(LocalApiError::EmptyNoteContent)));
+        assert!(!harness.workspace_path.join("Inbox.md").exists());
+
+        let contract = "---\nlocked: true\n---\nSigned.\n";
+        fs::write(harness.workspace_path.join("Contract.md"), contract).expect("write note");
+        let result = append_note(db_path, input(&harness, "Contract.md", "text"));
+        assert!(matches!(result, Err(LocalApiError::NoteLocked { .. })));
+        assert_eq!(
+            fs::read_to_string(harness.workspace_path.join("Contract.md")).expect("read note"),
+            contract
+        );
+    }
+}
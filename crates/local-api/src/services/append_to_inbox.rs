@@ -0,0 +1,232 @@
+//! Captures appended to the vault's inbox note. Agents and devices capture
+//! at the same time, so each entry goes out in a single write on a handle
+//! opened for appending: the system puts every write at the current end of
+//! the file, and no capture is lost to another one written in between.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::append_note::resolve_note_path;
+use super::create_note::{
+    normalize_path_separators, resolve_workspace, touch_workspace_best_effort,
+};
+use super::private_paths::PrivatePaths;
+use crate::LocalApiError;
+
+/// The inbox note, relative to the vault root.
+pub const INBOX_NOTE_REL_PATH: &str = "Inbox.md";
+
+const OPEN_ATTEMPTS: u32 = 5;
+const OPEN_RETRY_DELAY: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppendToInboxInput {
+    pub vault_id: i64,
+    pub text: String,
+    /// Listed under the entry's heading as `key: value` lines, such as the
+    /// capturing agent or the page a capture came from.
+    #[serde(default)]
+    pub metadata: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxEntry {
+    pub vault_id: i64,
+    pub workspace_path: String,
+    pub relative_path: String,
+    pub absolute_path: String,
+    /// The entry's heading, the local time of the capture.
+    pub timestamp: String,
+}
+
+/// Appends `text` to the vault's inbox note as an entry headed by the time
+/// of the capture, creating the note when it does not exist yet.
+pub fn append_to_inbox(
+    db_path: &Path,
+    input: AppendToInboxInput,
+) -> Result<InboxEntry, LocalApiError> {
+    let AppendToInboxInput {
+        vault_id,
+        text,
+        metadata,
+    } = input;
+    if text.trim().is_empty() {
+        return Err(LocalApiError::EmptyNoteContent);
+    }
+
+    let workspace = resolve_workspace(db_path, vault_id)?;
+    let workspace_path = PathBuf::from(&workspace.workspace_root);
+    let relative_path = INBOX_NOTE_REL_PATH.to_string();
+    let note_path = resolve_note_path(&workspace_path, &relative_path)?;
+    PrivatePaths::load(db_path, &workspace_path)?.ensure_accessible(
+        &workspace_path,
+        &note_path,
+        &relative_path,
+    )?;
+    match fs::read_to_string(&note_path) {
+        Ok(existing) if note::is_locked_source(&existing) => {
+            return Err(LocalApiError::NoteLocked { relative_path });
+        }
+        Ok(_) => {}
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(error) => return Err(error.into()),
+    }
+
+    let timestamp = chrono::Local::now()
+        .format("%Y-%m-%d %H:%M:%S %:z")
+        .to_string();
+    let mut file = open_for_append(&note_path)?;
+    // The length only decides whether a blank line leads the entry. Another
+    // capture landing first changes no more than that.
+    let leading = if file.metadata()?.len() == 0 {
+        ""
+    } else {
+        "\n"
+    };
+    let entry = format!("{leading}{}", format_entry(&timestamp, &metadata, &text));
+    file.write_all(entry.as_bytes())?;
+    touch_workspace_best_effort(db_path, &workspace_path);
+
+    Ok(InboxEntry {
+        vault_id: workspace.id,
+        workspace_path: normalize_path_separators(&workspace_path),
+        relative_path,
+        absolute_path: normalize_path_separators(&note_path),
+        timestamp,
+    })
+}
+
+/// Opens `path` for appending, retrying while another process holds it in a
+/// way that refuses the open, as sync clients and editors on Windows do.
+fn open_for_append(path: &Path) -> io::Result<File> {
+    let mut attempt = 1;
+    loop {
+        match OpenOptions::new().create(true).append(true).open(path) {
+            Err(error) if attempt < OPEN_ATTEMPTS && is_transient(&error) => {
+                thread::sleep(OPEN_RETRY_DELAY * attempt);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn is_transient(error: &io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION.
+    const WINDOWS_SHARING_ERRORS: [i32; 2] = [32, 33];
+    matches!(
+        error.kind(),
+        io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock
+    ) || (cfg!(windows)
+        && error
+            .raw_os_error()
+            .is_some_and(|code| WINDOWS_SHARING_ERRORS.contains(&code)))
+}
+
+fn format_entry(timestamp: &str, metadata: &BTreeMap<String, String>, text: &str) -> String {
+    let mut entry = format!("## {timestamp}\n\n");
+    // Line breaks would let a value run into the entry's text.
+    let single_line = |value: &str| value.split_whitespace().collect::<Vec<_>>().join(" ");
+    let metadata = metadata
+        .iter()
+        .map(|(key, value)| (single_line(key), single_line(value)))
+        .filter(|(key, _)| !key.is_empty())
+        .collect::<Vec<_>>();
+    if !metadata.is_empty() {
+        for (key, value) in metadata {
+            entry.push_str(&format!("- {key}: {value}\n"));
+        }
+        entry.push('\n');
+    }
+    entry.push_str(text.trim_end());
+    entry.push('\n');
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, fs, path::Path, thread};
+
+    use super::{append_to_inbox, AppendToInboxInput, INBOX_NOTE_REL_PATH};
+    use crate::{services::test_support::Harness, LocalApiError};
+
+    fn input(harness: &Harness, text: &str) -> AppendToInboxInput {
+        AppendToInboxInput {
+            vault_id: harness.vault_id,
+            text: text.to_string(),
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn entries_carry_timestamp_and_metadata() {
+        let harness = Harness::new("local-api-inbox-entry");
+        let db_path = Path::new(&harness.db_path);
+
+        let mut first = input(&harness, "Call the plumber\n");
+        first
+            .metadata
+            .insert("source".to_string(), "phone\nagent".to_string());
+        let entry = append_to_inbox(db_path, first).expect("capture should succeed");
+        assert_eq!(entry.relative_path, INBOX_NOTE_REL_PATH);
+        append_to_inbox(db_path, input(&harness, "Buy milk")).expect("capture should succeed");
+
+        let inbox = fs::read_to_string(harness.workspace_path.join(INBOX_NOTE_REL_PATH))
+            .expect("inbox should exist");
+        let expected_start = format!(
+            "## {}\n\n- source: phone agent\n\nCall the plumber\n\n## ",
+            entry.timestamp
+        );
+        assert!(inbox.starts_with(&expected_start), "{inbox}");
+        assert!(inbox.ends_with("\n\nBuy milk\n"), "{inbox}");
+
+        let result = append_to_inbox(db_path, input(&harness, " \n "));
+        assert!(matches!(result, Err(LocalApiError::EmptyNoteContent)));
+    }
+
+    #[test]
+    fn concurrent_captures_all_land_intact() {
+        let harness = Harness::new("local-api-inbox-concurrent");
+        let db_path = harness.db_path.clone();
+        let vault_id = harness.vault_id;
+
+        let writers = (0..8)
+            .map(|writer| {
+                let db_path = db_path.clone();
+                thread::spawn(move || {
+                    for capture in 0..10 {
+                        let text = format!("capture {writer}-{capture}");
+                        let input = AppendToInboxInput {
+                            vault_id,
+                            text,
+                            metadata: BTreeMap::new(),
+                        };
+                        append_to_inbox(&db_path, input).expect("capture should succeed");
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for writer in writers {
+            writer.join().expect("writer should not panic");
+        }
+
+        let inbox = fs::read_to_string(harness.workspace_path.join(INBOX_NOTE_REL_PATH))
+            .expect("inbox should exist");
+        assert_eq!(inbox.matches("## ").count(), 80);
+        for writer in 0..8 {
+            for capture in 0..10 {
+                assert!(inbox.contains(&format!("\n\ncapture {writer}-{capture}\n")));
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::append_note::normalize_note_rel_path;
+use super::private_paths::PrivatePaths;
+use super::redaction::Redactor;
+use super::search_notes::resolve_workspace;
+use crate::LocalApiError;
+
+const DEFAULT_MAX_TOKENS: usize = 4_000;
+const MAX_TOKENS: usize = 100_000;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildContextInput {
+    pub vault_id: i64,
+    pub query: Option<String>,
+    /// Notes to draw on first, relative to the vault root.
+    #[serde(default)]
+    pub note_paths: Vec<String>,
+    /// Budget for the whole context, in cl100k tokens.
+    pub max_tokens: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildContextOutput {
+    #[serde(flatten)]
+    pub context: vault_indexing::NoteContext,
+    /// Some passage text had secrets masked by the vault's redaction rules.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
+}
+
+/// Packs cited note passages into a token budget, so clients do not each
+/// have to search, dedupe and trim. Private folders are left out and the
+/// vault's redaction rules apply before passages are counted.
+pub fn build_context(
+    db_path: &Path,
+    input: BuildContextInput,
+) -> Result<BuildContextOutput, LocalApiError> {
+    let BuildContextInput {
+        vault_id,
+        query,
+        note_paths,
+        max_tokens,
+    } = input;
+    let workspace = resolve_workspace(db_path, vault_id)?;
+    let workspace_path = PathBuf::from(&workspace.workspace_root);
+    let query = query
+        .map(|query| query.trim().to_string())
+        .filter(|query| !query.is_empty());
+    if query.is_none() && note_paths.is_empty() {
+        return Err(LocalApiError::MissingContextSource);
+    }
+    let max_tokens = max_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+    if !(1..=MAX_TOKENS).contains(&max_tokens) {
+        return Err(LocalApiError::InvalidTokenBudget { max_tokens });
+    }
+
+    let private_paths = PrivatePaths::load(db_path, &workspace_path)?;
+    let note_paths = note_paths
+        .iter()
+        .map(|note_path| {
+            let relative_path = normalize_note_rel_path(note_path)?;
+            // Checked before the file, so callers cannot probe what a private
+            // folder holds.
+            if private_paths.contains(&relative_path) {
+                return Err(LocalApiError::PrivatePath { relative_path });
+            }
+            let absolute_path = workspace_path.join(&relative_path);
+            if !absolute_path.is_file() {
+                return Err(LocalApiError::NoteNotFound { relative_path });
+            }
+            private_paths.ensure_accessible(&workspace_path, &absolute_path, &relative_path)?;
+            Ok(PathBuf::from(relative_path))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let redactor = Redactor::load(db_path, &workspace_path)?;
+    let profiles = app_storage::vault::get_embedding_config(db_path, &workspace_path)?
+        .map(|config| config.profiles())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(provider, model)| vault_indexing::EmbeddingProfile::new(provider, model))
+        .collect::<Vec<_>>();
+    let request = vault_indexing::ContextRequest {
+        query,
+        note_paths,
+        max_tokens,
+    };
+    let mut redacted = false;
+    let context = vault_indexing::build_context_where(
+        &workspace_path,
+        db_path,
+        &request,
+        &profiles,
+        |path| !private_paths.contains_path(&workspace_path, path),
+        |text| redacted |= redactor.redact(text),
+    )?;
+
+    Ok(BuildContextOutput { context, redacted })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::{build_context, BuildContextInput};
+    use crate::{services::test_support::Harness, LocalApiError};
+
+    #[test]
+    fn build_context_validates_sources_and_budget() {
+        let harness = Harness::new("local-api-build-context-invalid");
+        let input = |query: &str, max_tokens| BuildContextInput {
+            vault_id: harness.vault_id,
+            query: Some(query.to_string()),
+            note_paths: Vec::new(),
+            max_tokens,
+        };
+
+        assert!(matches!(
+            build_context(Path::new(&harness.db_path), input(" ", None)),
+            Err(LocalApiError::MissingContextSource)
+        ));
+        assert!(matches!(
+            build_context(Path::new(&harness.db_path), input("nebula", Some(0))),
+            Err(LocalApiError::InvalidTokenBudget { max_tokens: 0 })
+        ));
+    }
+
+    #[test]
+    fn build_context_refuses_notes_in_private_folders() {
+        let harness = Harness::new("local-api-build-context-private");
+        fs::create_dir_all(harness.workspace_path.join("Journal")).expect("create folder");
+        fs::write(harness.workspace_path.join("Journal/May.md"), "# May\n").expect("write note");
+        app_storage::vault_settings::set_vault_setting(
+            &harness.db_path,
+            &harness.workspace_path,
+            "privatePaths",
+            &serde_json::json!(["Journal"]),
+        )
+        .expect("save private paths");
+
+        let result = build_context(
+            Path::new(&harness.db_path),
+            BuildContextInput {
+                vault_id: harness.vault_id,
+                query: None,
+                note_paths: vec!["Journal/May.md".to_string()],
+                max_tokens: None,
+            },
+        );
+
+        assert!(matches!(result, Err(LocalApiError::PrivatePath { .. })));
+    }
+}
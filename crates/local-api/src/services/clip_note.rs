@@ -0,0 +1,656 @@
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{Read, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, ToSocketAddrs},
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use base64::Engine;
+use reqwest::{
+    blocking::{Client, Response},
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use url::Url;
+use vault_indexing::IndexRunGuard;
+
+use super::create_note::{
+    normalize_directory_rel_path, normalize_path_separators, resolve_target_directory,
+    resolve_workspace, touch_workspace_best_effort, validate_relative_directory, write_note_file,
+};
+use super::private_paths::PrivatePaths;
+use crate::LocalApiError;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+const MAX_PAGE_BYTES: u64 = 10 * 1024 * 1024;
+const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+const MAX_IMAGE_DOWNLOADS: usize = 100;
+const MAX_REDIRECTS: usize = 10;
+const USER_AGENT: &str = concat!("mdit-clipper/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipNoteInput {
+    pub vault_id: i64,
+    pub directory_rel_path: Option<String>,
+    /// The clipped page. It is fetched when `html` is missing.
+    pub url: Option<String>,
+    /// The page as the browser has it, so pages behind a login clip too.
+    pub html: Option<String>,
+    /// Clips only this part of the page instead of its main content.
+    pub selection_html: Option<String>,
+    /// Defaults to the page title.
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClippedNote {
+    pub vault_id: i64,
+    pub workspace_path: String,
+    pub relative_path: String,
+    pub absolute_path: String,
+    /// Vault-relative paths of the images saved for the note.
+    pub attachments: Vec<String>,
+}
+
+/// Turns a web page into a note: the page's main content, or the selection
+/// made on it, becomes Markdown, its images are saved to the vault's
+/// attachment folder, and the note records where it came from.
+pub fn clip_note(
+    db_path: &Path,
+    run_guard: &IndexRunGuard,
+    input: ClipNoteInput,
+) -> Result<ClippedNote, LocalApiError> {
+    let ClipNoteInput {
+        vault_id,
+        directory_rel_path,
+        url,
+        html,
+        selection_html,
+        title,
+    } = input;
+    let source_url = url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(parse_web_url)
+        .transpose()?;
+    let workspace = resolve_workspace(db_path, vault_id)?;
+    let workspace_path = PathBuf::from(&workspace.workspace_root);
+    let directory_rel_path = normalize_directory_rel_path(directory_rel_path);
+    validate_relative_directory(&directory_rel_path)?;
+    let target_directory = resolve_target_directory(&workspace_path, &directory_rel_path)?;
+    let attachment_folder = attachment_folder(db_path, &workspace_path)?;
+    let private_paths = PrivatePaths::load(db_path, &workspace_path)?;
+    private_paths.ensure_accessible(&workspace_path, &target_directory, &directory_rel_path)?;
+    // Images are saved only once the page is fetched, so their folder is
+    // checked up front.
+    if private_paths.contains(&attachment_folder) {
+        return Err(LocalApiError::PrivatePath {
+            relative_path: attachment_folder,
+        });
+    }
+
+    let mut http = Http::default();
+    let (html, page_url) = match (html, &source_url) {
+        (Some(html), _) => (html, source_url.clone()),
+        (None, Some(source_url)) => {
+            let (html, page_url) = fetch_page(&mut http, source_url)?;
+            (html, Some(page_url))
+        }
+        (None, None) => return Err(LocalApiError::MissingClipSource),
+    };
+    let selection_html = selection_html.filter(|selection| !selection.trim().is_empty());
+
+    let mut images = ImageSaver {
+        http,
+        workspace_path: &workspace_path,
+        folder: attachment_folder,
+        embeds: HashMap::new(),
+        saved: Vec::new(),
+        downloads: 0,
+    };
+    let clip = note_import::clip_html(
+        &html,
+        selection_html.as_deref(),
+        page_url.as_ref().map(Url::as_str),
+        &mut |src, _alt| images.save(src),
+    );
+
+    let title = title
+        .or(clip.title)
+        .map(|title| title.trim().to_string())
+        .filter(|title| !title.is_empty())
+        .or_else(|| {
+            source_url
+                .as_ref()
+                .and_then(Url::host_str)
+                .map(str::to_string)
+        })
+        .ok_or(LocalApiError::InvalidTitle)?;
+    if note::sanitize_note_title(&title).is_empty() {
+        return Err(LocalApiError::InvalidTitle);
+    }
+    let note_path = note::unique_note_file_name(&target_directory, &title)
+        .map(|file_name| PathBuf::from(file_name.full_path))
+        .map_err(|message| LocalApiError::Internal { message })?;
+    let relative_path = normalize_path_separators(
+        note_path
+            .strip_prefix(fs::canonicalize(&workspace_path)?)
+            .unwrap_or(note_path.as_path()),
+    );
+
+    let content = clip_frontmatter(
+        &format!("{}\n", clip.markdown.trim_end()),
+        &title,
+        source_url.as_ref(),
+    )?;
+    write_note_file(&note_path, &relative_path, Some(content))?;
+    if let Err(error) = run_guard.run_exclusive(&workspace_path, || {
+        vault_indexing::index_note_with_profiles(&workspace_path, db_path, &note_path, &[])
+    }) {
+        tracing::error!(
+            "Failed to index clipped note '{}': {error}",
+            note_path.display()
+        );
+    }
+    touch_workspace_best_effort(db_path, &workspace_path);
+
+    Ok(ClippedNote {
+        vault_id: workspace.id,
+        workspace_path: normalize_path_separators(&workspace_path),
+        relative_path,
+        absolute_path: normalize_path_separators(&note_path),
+        attachments: images.saved,
+    })
+}
+
+fn parse_web_url(url: &str) -> Result<Url, LocalApiError> {
+    Url::parse(url)
+        .ok()
+        .filter(|parsed| ensure_public_url(parsed).is_ok())
+        .ok_or_else(|| LocalApiError::InvalidClipUrl {
+            url: url.to_string(),
+        })
+}
+
+/// Refuses URLs that are not plain web addresses or that name this machine
+/// or the local network outright. Host names are checked again once they
+/// resolve, by [`PublicResolver`].
+fn ensure_public_url(url: &Url) -> Result<(), String> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("{url} is not a web address"));
+    }
+    let is_public = match url.host() {
+        Some(url::Host::Ipv4(ip)) => is_public_ip(IpAddr::V4(ip)),
+        Some(url::Host::Ipv6(ip)) => is_public_ip(IpAddr::V6(ip)),
+        Some(url::Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain != "localhost" && !domain.ends_with(".localhost")
+        }
+        None => false,
+    };
+    if !is_public {
+        return Err(format!("{url} is not a public address"));
+    }
+    Ok(())
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    let shared = first == 100 && (64..128).contains(&second);
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || shared
+        || first == 0)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    let unique_local = first & 0xfe00 == 0xfc00;
+    let link_local = first & 0xffc0 == 0xfe80;
+    !(ip.is_unspecified() || ip.is_loopback() || ip.is_multicast() || unique_local || link_local)
+}
+
+/// Resolves hosts with the system resolver and refuses any that point at
+/// this machine or the local network. Checking at connect time covers
+/// redirects and answers that change between the URL check and the request.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = (host.as_str(), 0).to_socket_addrs()?.collect::<Vec<_>>();
+            if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+                return Err(format!("{host} resolves to non-public address {}", addr.ip()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+fn attachment_folder(db_path: &Path, workspace_path: &Path) -> Result<String, LocalApiError> {
+    let setting = app_storage::vault_settings::get_vault_setting(
+        db_path,
+        workspace_path,
+        app_storage::vault_settings::VaultSettingKey::AttachmentFolder.as_str(),
+    )?;
+    let folder = setting
+        .value
+        .as_str()
+        .unwrap_or_default()
+        .trim()
+        .trim_matches('/');
+    if Path::new(folder)
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(LocalApiError::Internal {
+            message: format!("invalid attachment folder: {folder}"),
+        });
+    }
+    Ok(folder.to_string())
+}
+
+fn clip_frontmatter(
+    markdown: &str,
+    title: &str,
+    source_url: Option<&Url>,
+) -> Result<String, LocalApiError> {
+    let mut properties = vec![("title", json!(title))];
+    if let Some(source_url) = source_url {
+        properties.push(("source", json!(source_url.as_str())));
+    }
+    properties.push((
+        "clipped",
+        json!(chrono::Local::now().format("%Y-%m-%d").to_string()),
+    ));
+
+    properties
+        .into_iter()
+        .try_fold(markdown.to_string(), |content, (key, value)| {
+            note::set_frontmatter_property(&content, key, &value)
+        })
+        .map_err(|message| LocalApiError::Internal { message })
+}
+
+/// Builds the HTTP client on first use; clips sent with their HTML and
+/// inline images never need one.
+#[derive(Default)]
+struct Http {
+    client: Option<Client>,
+}
+
+impl Http {
+    fn get(&mut self, url: &str) -> Result<Response, String> {
+        let url = Url::parse(url).map_err(|error| error.to_string())?;
+        ensure_public_url(&url)?;
+        if self.client.is_none() {
+            let client = Client::builder()
+                .timeout(FETCH_TIMEOUT)
+                .user_agent(USER_AGENT)
+                .redirect(redirect::Policy::custom(|attempt| {
+                    if attempt.previous().len() >= MAX_REDIRECTS {
+                        return attempt.error("too many redirects");
+                    }
+                    match ensure_public_url(attempt.url()) {
+                        Ok(()) => attempt.follow(),
+                        Err(message) => attempt.error(message),
+                    }
+                }))
+                .dns_resolver(Arc::new(PublicResolver))
+                // A proxy would resolve hosts itself, past the checks above.
+                .no_proxy()
+                .build()
+                .map_err(|error| error.to_string())?;
+            self.client = Some(client);
+        }
+        self.client
+            .as_ref()
+            .expect("client is built above")
+            .get(url)
+            .send()
+            .and_then(Response::error_for_status)
+            .map_err(|error| error.to_string())
+    }
+}
+
+/// Fetches the page, returning it with the URL it ended up at after
+/// redirects, which relative links resolve against.
+fn fetch_page(http: &mut Http, url: &Url) -> Result<(String, Url), LocalApiError> {
+    let fetch_failed = |message| LocalApiError::ClipFetchFailed {
+        url: url.to_string(),
+        message,
+    };
+    let response = http.get(url.as_str()).map_err(fetch_failed)?;
+    let page_url = response.url().clone();
+    let bytes = read_limited(response, MAX_PAGE_BYTES).map_err(fetch_failed)?;
+    Ok((String::from_utf8_lossy(&bytes).into_owned(), page_url))
+}
+
+fn read_limited(response: Response, limit: u64) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    response
+        .take(limit + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|error| error.to_string())?;
+    if bytes.len() as u64 > limit {
+        return Err(format!("larger than {} MB", limit / (1024 * 1024)));
+    }
+    Ok(bytes)
+}
+
+struct ImageSaver<'a> {
+    http: Http,
+    workspace_path: &'a Path,
+    folder: String,
+    /// Embeds of the images saved so far, by source, so repeats share a file.
+    embeds: HashMap<String, String>,
+    saved: Vec<String>,
+    /// Remote images fetched so far, capped at [`MAX_IMAGE_DOWNLOADS`].
+    downloads: usize,
+}
+
+impl ImageSaver<'_> {
+    /// Saves the image at `src` and returns its embed. An image that cannot
+    /// be saved stays a link to the remote file.
+    fn save(&mut self, src: &str) -> Option<String> {
+        if let Some(embed) = self.embeds.get(src) {
+            return Some(embed.clone());
+        }
+        let saved = self
+            .load(src)
+            .and_then(|(stem, extension, bytes)| self.write(&stem, extension, &bytes));
+        match saved {
+            Ok(rel_path) => {
+                let embed = format!("![[{rel_path}]]");
+                self.embeds.insert(src.to_string(), embed.clone());
+                self.saved.push(rel_path);
+                Some(embed)
+            }
+            Err(error) => {
+                if !src.starts_with("data:") {
+                    tracing::error!("Failed to save clipped image '{src}': {error}");
+                }
+                None
+            }
+        }
+    }
+
+    fn load(&mut self, src: &str) -> Result<(String, &'static str, Vec<u8>), String> {
+        if let Some(data) = src.strip_prefix("data:") {
+            let (media_type, payload) = data.split_once(',').ok_or("malformed data URI")?;
+            let media_type = media_type
+                .strip_suffix(";base64")
+                .ok_or("data URI is not base64")?;
+            let extension = image_extension(media_type).ok_or("not an image")?;
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(payload.trim())
+                .map_err(|error| error.to_string())?;
+            if bytes.len() as u64 > MAX_IMAGE_BYTES {
+                return Err("image is too large".to_string());
+            }
+            return Ok(("image".to_string(), extension, bytes));
+        }
+
+        if self.downloads >= MAX_IMAGE_DOWNLOADS {
+            return Err(format!(
+                "more than {MAX_IMAGE_DOWNLOADS} images to download"
+            ));
+        }
+        self.downloads += 1;
+        let response = self.http.get(src)?;
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        let media_type = content_type.split(';').next().unwrap_or_default().trim();
+        let extension = image_extension(&media_type.to_ascii_lowercase()).ok_or("not an image")?;
+        let stem = Url::parse(src)
+            .ok()
+            .and_then(|url| {
+                let name = url.path_segments()?.next_back()?.to_string();
+                let stem = name
+                    .rsplit_once('.')
+                    .map_or(name.as_str(), |(stem, _)| stem);
+                Some(note::sanitize_note_title(stem))
+            })
+            .filter(|stem| !stem.is_empty())
+            .unwrap_or_else(|| "image".to_string());
+        let bytes = read_limited(response, MAX_IMAGE_BYTES)?;
+        Ok((stem, extension, bytes))
+    }
+
+    /// Writes the image under a name not yet taken in the attachment folder.
+    fn write(&self, stem: &str, extension: &str, bytes: &[u8]) -> Result<String, String> {
+        let directory = self.workspace_path.join(&self.folder);
+        fs::create_dir_all(&directory).map_err(|error| error.to_string())?;
+
+        for attempt in 0..1000 {
+            let file_name = if attempt == 0 {
+                format!("{stem}.{extension}")
+            } else {
+                format!("{stem} {attempt}.{extension}")
+            };
+            let mut file = match OpenOptions::new()
+                .create_new(true)
+                .write(true)
+                .open(directory.join(&file_name))
+            {
+                Ok(file) => file,
+                Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(error) => return Err(error.to_string()),
+            };
+            file.write_all(bytes).map_err(|error| error.to_string())?;
+            return Ok(if self.folder.is_empty() {
+                file_name
+            } else {
+                format!("{}/{file_name}", self.folder)
+            });
+        }
+        Err(format!("no free file name for {stem}.{extension}"))
+    }
+}
+
+fn image_extension(media_type: &str) -> Option<&'static str> {
+    Some(match media_type {
+        "image/png" => "png",
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/avif" => "avif",
+        "image/svg+xml" => "svg",
+        "image/bmp" => "bmp",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs, net::TcpListener, path::Path};
+
+    use vault_indexing::IndexRunGuard;
+
+    use super::{clip_note, parse_web_url, ClipNoteInput, Http, ImageSaver, MAX_IMAGE_DOWNLOADS};
+    use crate::{services::test_support::Harness, LocalApiError};
+
+    const PIXEL: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAQAAAC1HAwCAAAAC0lEQVR42mNkYAAAAAYAAjCB0C8AAAAASUVORK5CYII=";
+
+    fn input(vault_id: i64) -> ClipNoteInput {
+        ClipNoteInput {
+            vault_id,
+            directory_rel_path: None,
+            url: Some("https://example.com/posts/bread".to_string()),
+            html: None,
+            selection_html: None,
+            title: None,
+        }
+    }
+
+    #[test]
+    fn clip_note_writes_the_article_with_its_source_and_images() {
+        let harness = Harness::new("local-api-clip");
+        app_storage::vault_settings::set_vault_setting(
+            &harness.db_path,
+            &harness.workspace_path,
+            "attachmentFolder",
+            &serde_json::json!("attachments"),
+        )
+        .expect("set attachment folder");
+        fs::write(harness.workspace_path.join("Bread.md"), "taken").expect("existing note");
+
+        let html = format!(
+            "<html><head><title>Bread</title></head><body><nav>Menu</nav>\
+             <article><p>See <a href=\"/posts/rye\">rye</a>.</p>\
+             <img src=\"data:image/png;base64,{PIXEL}\" alt=\"crumb\">\
+             <img src=\"data:image/png;base64,{PIXEL}\"></article></body></html>"
+        );
+        let clipped = clip_note(
+            Path::new(&harness.db_path),
+            &IndexRunGuard::default(),
+            ClipNoteInput {
+                html: Some(html),
+                ..input(harness.vault_id)
+            },
+        )
+        .expect("clip succeeds");
+
+        assert_eq!(clipped.relative_path, "Bread 1.md");
+        assert_eq!(clipped.attachments, vec!["attachments/image.png"]);
+        assert!(harness
+            .workspace_path
+            .join("attachments/image.png")
+            .is_file());
+
+        let content = fs::read_to_string(&clipped.absolute_path).expect("note written");
+        let (frontmatter, body) = content
+            .strip_prefix("---\n")
+            .and_then(|content| content.split_once("---\n"))
+            .expect("frontmatter");
+        assert!(frontmatter
+            .starts_with("title: Bread\nsource: https://example.com/posts/bread\nclipped: "));
+        assert_eq!(
+            body,
+            "See [rye](https://example.com/posts/rye).\n\n![[attachments/image.png]]![[attachments/image.png]]\n"
+        );
+    }
+
+    #[test]
+    fn clip_note_rejects_missing_or_non_web_sources() {
+        let harness = Harness::new("local-api-clip-source");
+
+        let result = clip_note(
+            Path::new(&harness.db_path),
+            &IndexRunGuard::default(),
+            ClipNoteInput {
+                url: None,
+                ..input(harness.vault_id)
+            },
+        );
+        assert!(matches!(result, Err(LocalApiError::MissingClipSource)));
+
+        let result = clip_note(
+            Path::new(&harness.db_path),
+            &IndexRunGuard::default(),
+            ClipNoteInput {
+                url: Some("file:///etc/passwd".to_string()),
+                ..input(harness.vault_id)
+            },
+        );
+        match result {
+            Err(LocalApiError::InvalidClipUrl { url }) => assert_eq!(url, "file:///etc/passwd"),
+            other => panic!("expected invalid clip url error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn clip_urls_must_point_at_public_hosts() {
+        for url in [
+            "http://127.0.0.1/",
+            "http://localhost:8080/",
+            "http://app.localhost/",
+            "http://10.0.0.5/",
+            "http://192.168.1.1/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://[::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://[fd00::1]/",
+            "http://0.0.0.0/",
+        ] {
+            assert!(parse_web_url(url).is_err(), "{url} should be refused");
+        }
+        assert!(parse_web_url("https://example.com/posts/bread").is_ok());
+        assert!(parse_web_url("http://93.184.215.14/").is_ok());
+    }
+
+    #[test]
+    fn clip_note_does_not_fetch_images_from_this_machine() {
+        let harness = Harness::new("local-api-clip-loopback");
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind listener");
+        listener
+            .set_nonblocking(true)
+            .expect("nonblocking listener");
+        let port = listener.local_addr().expect("listener address").port();
+
+        let html = format!(
+            "<article><p>Bread</p><img src=\"http://127.0.0.1:{port}/a.png\">\
+             <img src=\"http://localhost:{port}/b.png\"></article>"
+        );
+        let clipped = clip_note(
+            Path::new(&harness.db_path),
+            &IndexRunGuard::default(),
+            ClipNoteInput {
+                html: Some(html),
+                ..input(harness.vault_id)
+            },
+        )
+        .expect("clip succeeds");
+
+        assert!(clipped.attachments.is_empty());
+        assert_eq!(
+            listener.accept().map(|_| ()).map_err(|error| error.kind()),
+            Err(std::io::ErrorKind::WouldBlock)
+        );
+    }
+
+    #[test]
+    fn image_saver_stops_downloading_past_the_cap() {
+        let harness = Harness::new("local-api-clip-image-cap");
+        let mut images = ImageSaver {
+            http: Http::default(),
+            workspace_path: &harness.workspace_path,
+            folder: String::new(),
+            embeds: HashMap::new(),
+            saved: Vec::new(),
+            downloads: MAX_IMAGE_DOWNLOADS,
+        };
+
+        let error = images
+            .load("https://example.com/crumb.png")
+            .expect_err("cap reached");
+        assert!(error.contains("images to download"));
+        assert!(images.http.client.is_none());
+    }
+}
@@ -6,6 +6,7 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
+use super::private_paths::PrivatePaths;
 use crate::LocalApiError;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -42,6 +43,14 @@ pub fn create_note(db_path: &Path, input: CreateNoteInput) -> Result<CreatedNote
     let workspace = resolve_workspace(db_path, vault_id)?;
     let workspace_path = PathBuf::from(&workspace.workspace_root);
     let resolved_note_path = resolve_note_path(&workspace_path, directory_rel_path, &title)?;
+    PrivatePaths::load(db_path, &workspace_path)?.ensure_accessible(
+        &workspace_path,
+        resolved_note_path
+            .note_path
+            .parent()
+            .unwrap_or(&workspace_path),
+        &resolved_note_path.relative_path,
+    )?;
     write_note_file(
         &resolved_note_path.note_path,
         &resolved_note_path.relative_path,
@@ -57,7 +66,7 @@ pub fn create_note(db_path: &Path, input: CreateNoteInput) -> Result<CreatedNote
     })
 }
 
-fn resolve_workspace(
+pub(super) fn resolve_workspace(
     db_path: &Path,
     vault_id: i64,
 ) -> Result<app_storage::vault::VaultWorkspace, LocalApiError> {
@@ -98,15 +107,15 @@ fn resolve_note_path(
 }
 
 fn resolve_note_file_name(title: &str) -> Result<String, LocalApiError> {
-    let sanitized_title = sanitize_note_title(title);
+    let sanitized_title = note::sanitize_note_title(title);
     if sanitized_title.is_empty() {
         return Err(LocalApiError::InvalidTitle);
     }
 
-    Ok(ensure_md_extension(sanitized_title))
+    Ok(format!("{sanitized_title}.md"))
 }
 
-fn write_note_file(
+pub(super) fn write_note_file(
     note_path: &Path,
     relative_path: &str,
     content: Option<String>,
@@ -132,33 +141,16 @@ fn write_note_file(
     Ok(())
 }
 
-fn touch_workspace_best_effort(db_path: &Path, workspace_path: &Path) {
+pub(super) fn touch_workspace_best_effort(db_path: &Path, workspace_path: &Path) {
     if let Err(error) = app_storage::vault::touch_workspace(db_path, workspace_path) {
-        eprintln!(
+        tracing::error!(
             "Failed to update vault last_opened_at after note creation for '{}': {error}",
             workspace_path.display()
         );
     }
 }
 
-fn sanitize_note_title(title: &str) -> String {
-    title
-        .chars()
-        .filter(|c| *c != '/' && *c != '\\')
-        .collect::<String>()
-        .trim()
-        .to_string()
-}
-
-fn ensure_md_extension(title: String) -> String {
-    if title.to_ascii_lowercase().ends_with(".md") {
-        title
-    } else {
-        format!("{title}.md")
-    }
-}
-
-fn normalize_directory_rel_path(directory_rel_path: Option<String>) -> String {
+pub(super) fn normalize_directory_rel_path(directory_rel_path: Option<String>) -> String {
     let value = directory_rel_path
         .unwrap_or_else(|| ".".to_string())
         .trim()
@@ -171,7 +163,7 @@ fn normalize_directory_rel_path(directory_rel_path: Option<String>) -> String {
     }
 }
 
-fn validate_relative_directory(directory_rel_path: &str) -> Result<(), LocalApiError> {
+pub(super) fn validate_relative_directory(directory_rel_path: &str) -> Result<(), LocalApiError> {
     let path = Path::new(directory_rel_path);
 
     if path.is_absolute() {
@@ -194,7 +186,7 @@ fn validate_relative_directory(directory_rel_path: &str) -> Result<(), LocalApiE
     Ok(())
 }
 
-fn resolve_target_directory(
+pub(super) fn resolve_target_directory(
     workspace_path: &Path,
     directory_rel_path: &str,
 ) -> Result<PathBuf, LocalApiError> {
@@ -222,7 +214,7 @@ fn resolve_target_directory(
     Ok(canonical_target)
 }
 
-fn normalize_path_separators(path: &Path) -> String {
+pub(super) fn normalize_path_separators(path: &Path) -> String {
     path.to_string_lossy().replace('\\', "/")
 }
 
@@ -295,4 +287,38 @@ mod tests {
             other => panic!("expected invalid directory path error, got {other:?}"),
         }
     }
+
+    #[test]
+    fn create_note_refuses_private_folders() {
+        let harness = Harness::new("local-api-private-folder");
+        fs::create_dir_all(harness.workspace_path.join("Journal/2024")).expect("create folder");
+        app_storage::vault_settings::set_vault_setting(
+            &harness.db_path,
+            &harness.workspace_path,
+            "privatePaths",
+            &serde_json::json!(["journal"]),
+        )
+        .expect("save private paths");
+
+        let result = create_note(
+            Path::new(&harness.db_path),
+            CreateNoteInput {
+                vault_id: harness.vault_id,
+                directory_rel_path: Some("Journal/2024".to_string()),
+                title: "Entry".to_string(),
+                content: None,
+            },
+        );
+
+        match result {
+            Err(LocalApiError::PrivatePath { relative_path }) => {
+                assert_eq!(relative_path, "Journal/2024/Entry.md")
+            }
+            other => panic!("expected private path error, got {other:?}"),
+        }
+        assert!(!harness
+            .workspace_path
+            .join("Journal/2024/Entry.md")
+            .exists());
+    }
 }
@@ -1,6 +1,15 @@
+pub mod append_note;
+pub mod append_to_inbox;
+pub mod build_context;
+pub mod clip_note;
 pub mod create_note;
 pub mod list_vaults;
+pub mod note_identity;
+pub(crate) mod private_paths;
+pub(crate) mod redaction;
 pub mod search_notes;
+pub mod search_segments;
+pub mod vault_diagnostics;
 
 #[cfg(test)]
 pub(crate) mod test_support;
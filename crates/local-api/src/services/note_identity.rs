@@ -0,0 +1,222 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::append_note::normalize_note_rel_path;
+use crate::services::private_paths::PrivatePaths;
+use crate::LocalApiError;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNoteIdInput {
+    pub vault_id: i64,
+    pub relative_path: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveNoteIdInput {
+    pub vault_id: i64,
+    pub note_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteIdentity {
+    pub vault_id: i64,
+    pub note_id: String,
+    pub workspace_path: String,
+    pub relative_path: String,
+    pub absolute_path: String,
+}
+
+/// Returns the stable id of an existing note, assigning one on first use.
+pub fn get_note_id(db_path: &Path, input: GetNoteIdInput) -> Result<NoteIdentity, LocalApiError> {
+    let workspace = resolve_workspace(db_path, input.vault_id)?;
+    let workspace_path = PathBuf::from(&workspace.workspace_root);
+    let relative_path = normalize_note_rel_path(&input.relative_path)?;
+    let note_path = workspace_path.join(&relative_path);
+    let private_paths = PrivatePaths::load(db_path, &workspace_path)?;
+    // Checked before the file, so callers cannot probe what a private
+    // folder holds.
+    if private_paths.contains(&relative_path) {
+        return Err(LocalApiError::PrivatePath { relative_path });
+    }
+    if !note_path.is_file() {
+        return Err(LocalApiError::NoteNotFound { relative_path });
+    }
+    private_paths.ensure_accessible(&workspace_path, &note_path, &relative_path)?;
+
+    let note_id = app_storage::note_identity::ensure_note_id(db_path, &workspace_path, &note_path)?;
+
+    Ok(NoteIdentity {
+        vault_id: workspace.id,
+        note_id,
+        workspace_path: normalize_path_separators(&workspace_path),
+        relative_path,
+        absolute_path: normalize_path_separators(&note_path),
+    })
+}
+
+/// Finds where the note with `note_id` lives now.
+pub fn resolve_note_id(
+    db_path: &Path,
+    input: ResolveNoteIdInput,
+) -> Result<NoteIdentity, LocalApiError> {
+    let workspace = resolve_workspace(db_path, input.vault_id)?;
+    let workspace_path = PathBuf::from(&workspace.workspace_root);
+    let note_id = input.note_id.trim().to_string();
+
+    let relative_path =
+        app_storage::note_identity::find_note_by_id(db_path, &workspace_path, &note_id)?
+            .ok_or_else(|| LocalApiError::NoteIdNotFound {
+                note_id: note_id.clone(),
+            })?;
+    let note_path = workspace_path.join(&relative_path);
+    // The id outlives a deleted note; only report it while the file is there.
+    // Notes in private folders are reported as missing.
+    if !note_path.is_file()
+        || PrivatePaths::load(db_path, &workspace_path)?.contains_path(&workspace_path, &note_path)
+    {
+        return Err(LocalApiError::NoteIdNotFound { note_id });
+    }
+
+    Ok(NoteIdentity {
+        vault_id: workspace.id,
+        note_id,
+        workspace_path: normalize_path_separators(&workspace_path),
+        relative_path,
+        absolute_path: normalize_path_separators(&note_path),
+    })
+}
+
+fn resolve_workspace(
+    db_path: &Path,
+    vault_id: i64,
+) -> Result<app_storage::vault::VaultWorkspace, LocalApiError> {
+    let workspace = app_storage::vault::get_workspace_by_id(db_path, vault_id)?
+        .ok_or(LocalApiError::VaultNotFound { vault_id })?;
+    let workspace_path = PathBuf::from(&workspace.workspace_root);
+
+    if !workspace_path.is_dir() {
+        return Err(LocalApiError::VaultWorkspaceUnavailable {
+            workspace_path: workspace.workspace_root,
+        });
+    }
+
+    Ok(workspace)
+}
+
+fn normalize_path_separators(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::{get_note_id, resolve_note_id, GetNoteIdInput, ResolveNoteIdInput};
+    use crate::{services::test_support::Harness, LocalApiError};
+
+    #[test]
+    fn note_id_resolves_after_the_note_moves() {
+        let harness = Harness::new("local-api-note-identity");
+        let db_path = Path::new(&harness.db_path);
+        fs::create_dir_all(harness.workspace_path.join("Projects")).expect("create folder");
+        fs::write(harness.workspace_path.join("Projects/Plan.md"), "# Plan").expect("write note");
+
+        let identity = get_note_id(
+            db_path,
+            GetNoteIdInput {
+                vault_id: harness.vault_id,
+                relative_path: "Projects/Plan.md".to_string(),
+            },
+        )
+        .expect("note id should be assigned");
+
+        fs::rename(
+            harness.workspace_path.join("Projects"),
+            harness.workspace_path.join("Archive"),
+        )
+        .expect("move folder");
+        app_storage::note_identity::rename_note_identities(
+            db_path,
+            &harness.workspace_path,
+            &harness.workspace_path.join("Projects"),
+            &harness.workspace_path.join("Archive"),
+        )
+        .expect("ids should follow the folder");
+
+        let resolved = resolve_note_id(
+            db_path,
+            ResolveNoteIdInput {
+                vault_id: harness.vault_id,
+                note_id: identity.note_id.clone(),
+            },
+        )
+        .expect("note id should resolve");
+        assert_eq!(resolved.relative_path, "Archive/Plan.md");
+
+        fs::remove_file(harness.workspace_path.join("Archive/Plan.md")).expect("delete note");
+        let result = resolve_note_id(
+            db_path,
+            ResolveNoteIdInput {
+                vault_id: harness.vault_id,
+                note_id: identity.note_id,
+            },
+        );
+        assert!(matches!(result, Err(LocalApiError::NoteIdNotFound { .. })));
+
+        let result = get_note_id(
+            db_path,
+            GetNoteIdInput {
+                vault_id: harness.vault_id,
+                relative_path: "Missing.md".to_string(),
+            },
+        );
+        assert!(matches!(result, Err(LocalApiError::NoteNotFound { .. })));
+    }
+
+    #[test]
+    fn notes_in_private_folders_have_no_reachable_id() {
+        let harness = Harness::new("local-api-note-identity-private");
+        let db_path = Path::new(&harness.db_path);
+        fs::create_dir_all(harness.workspace_path.join("Journal")).expect("create folder");
+        fs::write(harness.workspace_path.join("Journal/May.md"), "# May").expect("write note");
+
+        let identity = get_note_id(
+            db_path,
+            GetNoteIdInput {
+                vault_id: harness.vault_id,
+                relative_path: "Journal/May.md".to_string(),
+            },
+        )
+        .expect("note id should be assigned");
+        app_storage::vault_settings::set_vault_setting(
+            db_path,
+            &harness.workspace_path,
+            "privatePaths",
+            &serde_json::json!(["Journal"]),
+        )
+        .expect("save private paths");
+
+        for relative_path in ["Journal/May.md", "journal/Missing.md"] {
+            let result = get_note_id(
+                db_path,
+                GetNoteIdInput {
+                    vault_id: harness.vault_id,
+                    relative_path: relative_path.to_string(),
+                },
+            );
+            assert!(matches!(result, Err(LocalApiError::PrivatePath { .. })));
+        }
+        let result = resolve_note_id(
+            db_path,
+            ResolveNoteIdInput {
+                vault_id: harness.vault_id,
+                note_id: identity.note_id,
+            },
+        );
+        assert!(matches!(result, Err(LocalApiError::NoteIdNotFound { .. })));
+    }
+}
@@ -0,0 +1,120 @@
+//! Folders a vault keeps out of reach of the local API and MCP tools, listed
+//! in its `privatePaths` setting. The services refuse to read or write notes
+//! under them and leave them out of results, whatever token the caller holds;
+//! the app itself opens them as usual.
+
+use std::{fs, path::Path};
+
+use app_storage::vault_settings::{get_vault_setting, VaultSettingKey};
+
+use crate::LocalApiError;
+
+#[derive(Debug, Default)]
+pub(crate) struct PrivatePaths {
+    // Lowercased, slash-separated and relative to the vault root. Paths are
+    // compared without case, as the default macOS and Windows file systems
+    // would open them.
+    folders: Vec<String>,
+}
+
+impl PrivatePaths {
+    pub(crate) fn load(db_path: &Path, workspace_path: &Path) -> Result<Self, LocalApiError> {
+        let setting = get_vault_setting(
+            db_path,
+            workspace_path,
+            VaultSettingKey::PrivatePaths.as_str(),
+        )?;
+        let folders = setting
+            .value
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|folder| folder.as_str());
+        Ok(Self::new(folders))
+    }
+
+    fn new<'a>(folders: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            folders: folders.into_iter().filter_map(normalize).collect(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.folders.is_empty()
+    }
+
+    /// Whether `relative_path` is a private folder or lies under one.
+    pub(crate) fn contains(&self, relative_path: &str) -> bool {
+        let Some(path) = normalize(relative_path) else {
+            return false;
+        };
+        self.folders.iter().any(|folder| {
+            path.strip_prefix(folder.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+
+    /// [`Self::contains`] for a path in the vault, also checked with symlinks
+    /// resolved so a linked folder does not lead around a private one.
+    pub(crate) fn contains_path(&self, workspace_path: &Path, path: &Path) -> bool {
+        if self.folders.is_empty() {
+            return false;
+        }
+        let relative = |path: &Path, root: &Path| {
+            path.strip_prefix(root)
+                .ok()
+                .is_some_and(|relative| self.contains(&relative.to_string_lossy()))
+        };
+        if relative(path, workspace_path) {
+            return true;
+        }
+        match (fs::canonicalize(workspace_path), fs::canonicalize(path)) {
+            (Ok(workspace_path), Ok(path)) => relative(&path, &workspace_path),
+            _ => false,
+        }
+    }
+
+    /// Fails with [`LocalApiError::PrivatePath`] when `relative_path`, or
+    /// `existing_path` holding it, is under a private folder.
+    pub(crate) fn ensure_accessible(
+        &self,
+        workspace_path: &Path,
+        existing_path: &Path,
+        relative_path: &str,
+    ) -> Result<(), LocalApiError> {
+        if self.contains(relative_path) || self.contains_path(workspace_path, existing_path) {
+            return Err(LocalApiError::PrivatePath {
+                relative_path: relative_path.to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+fn normalize(path: &str) -> Option<String> {
+    let parts = path
+        .trim()
+        .split(['/', '\\'])
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect::<Vec<_>>();
+    (!parts.is_empty()).then(|| parts.join("/").to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PrivatePaths;
+
+    #[test]
+    fn folders_cover_their_contents_without_case_or_separator_differences() {
+        let private = PrivatePaths::new(["Journal/", "./Work\\HR", " "]);
+
+        assert!(private.contains("Journal"));
+        assert!(private.contains("journal/2024/May.md"));
+        assert!(private.contains("Work/hr/Reviews.md"));
+        assert!(private.contains("./Work\\HR"));
+        assert!(!private.contains("Journaling.md"));
+        assert!(!private.contains("Work/Plan.md"));
+        assert!(!private.contains("."));
+        assert!(!PrivatePaths::default().contains("Journal/May.md"));
+    }
+}
@@ -0,0 +1,134 @@
+//! Masks secrets in note text before the local API and MCP tools return it,
+//! following the vault's `redaction` setting. Text shown in the app itself is
+//! never touched.
+
+use std::{borrow::Cow, path::Path};
+
+use app_storage::vault_settings::{get_vault_setting, VaultSettingKey};
+use regex::{Regex, RegexBuilder};
+use serde_json::Value as JsonValue;
+
+use crate::LocalApiError;
+
+pub(crate) const REDACTED: &str = "[REDACTED]";
+// Caps the compiled size of a vault's own patterns.
+const PATTERN_SIZE_LIMIT: usize = 1 << 20;
+
+const EMAIL_PATTERN: &str = r"(?i)\b[a-z0-9._%+-]+@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,}\b";
+// Keys with a recognizable prefix: Stripe, OpenAI and Anthropic, AWS access
+// key ids, GitHub, Slack and Google.
+const API_KEY_PATTERN: &str = concat!(
+    r"\b(?:",
+    r"[spr]k_(?:live|test)_[A-Za-z0-9]{16,}",
+    r"|sk-[A-Za-z0-9_-]{20,}",
+    r"|(?:AKIA|ASIA)[0-9A-Z]{16}",
+    r"|gh[pousr]_[A-Za-z0-9]{36,}",
+    r"|github_pat_[A-Za-z0-9_]{22,}",
+    r"|xox[abpr]-[A-Za-z0-9-]{10,}",
+    r"|AIza[0-9A-Za-z_-]{35}",
+    r")\b"
+);
+const SSN_PATTERN: &str = r"\b\d{3}-\d{2}-\d{4}\b";
+
+#[derive(Debug)]
+pub(crate) struct Redactor {
+    patterns: Vec<Regex>,
+}
+
+impl Redactor {
+    pub(crate) fn load(db_path: &Path, workspace_path: &Path) -> Result<Self, LocalApiError> {
+        let setting =
+            get_vault_setting(db_path, workspace_path, VaultSettingKey::Redaction.as_str())?;
+        Self::from_setting(&setting.value)
+    }
+
+    fn from_setting(setting: &JsonValue) -> Result<Self, LocalApiError> {
+        let enabled = |name: &str| setting.get(name).and_then(JsonValue::as_bool) == Some(true);
+        let built_in = [
+            ("emails", EMAIL_PATTERN),
+            ("apiKeys", API_KEY_PATTERN),
+            ("ssns", SSN_PATTERN),
+        ]
+        .into_iter()
+        .filter(|(name, _)| enabled(name))
+        .map(|(_, pattern)| pattern);
+        let custom = setting
+            .get("patterns")
+            .and_then(JsonValue::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(JsonValue::as_str);
+
+        // A pattern that does not compile fails the request rather than let
+        // the text it was meant to hide through.
+        let patterns = built_in
+            .chain(custom)
+            .map(|pattern| {
+                RegexBuilder::new(pattern)
+                    .size_limit(PATTERN_SIZE_LIMIT)
+                    .build()
+                    .map_err(|error| LocalApiError::InvalidRedactionPattern {
+                        pattern: pattern.to_string(),
+                        message: error.to_string(),
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { patterns })
+    }
+
+    /// Replaces every match in `text` with [`REDACTED`]. Returns whether
+    /// anything was.
+    pub(crate) fn redact(&self, text: &mut String) -> bool {
+        let mut redacted = false;
+        for pattern in &self.patterns {
+            if let Cow::Owned(replaced) = pattern.replace_all(text, REDACTED) {
+                *text = replaced;
+                redacted = true;
+            }
+        }
+        redacted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{Redactor, REDACTED};
+    use crate::LocalApiError;
+
+    #[test]
+    fn enabled_patterns_mask_their_matches_only() {
+        let redactor = Redactor::from_setting(&json!({
+            "emails": true,
+            "apiKeys": true,
+            "ssns": false,
+            "patterns": ["(?i)pin: \\d{4}"]
+        }))
+        .expect("patterns should compile");
+
+        let mut text = "Mail ana@example.co.uk, key sk-proj-abcdefghijklmnopqrstuv, \
+                        SSN 123-45-6789, PIN: 1234."
+            .to_string();
+        assert!(redactor.redact(&mut text));
+        assert_eq!(
+            text,
+            format!("Mail {REDACTED}, key {REDACTED}, SSN 123-45-6789, {REDACTED}.")
+        );
+
+        let mut clean = "Nothing to hide here.".to_string();
+        assert!(!redactor.redact(&mut clean));
+        assert_eq!(clean, "Nothing to hide here.");
+    }
+
+    #[test]
+    fn invalid_patterns_fail_instead_of_leaking() {
+        let result = Redactor::from_setting(&json!({ "patterns": ["(unclosed"] }));
+        match result {
+            Err(LocalApiError::InvalidRedactionPattern { pattern, .. }) => {
+                assert_eq!(pattern, "(unclosed")
+            }
+            other => panic!("expected invalid pattern error, got {other:?}"),
+        }
+    }
+}
@@ -2,10 +2,13 @@ use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use super::private_paths::PrivatePaths;
+use super::redaction::Redactor;
 use crate::LocalApiError;
 
 const DEFAULT_LIMIT: usize = 20;
 const MAX_LIMIT: usize = 100;
+const MAX_CONTENT_BYTES: usize = 1024 * 1024;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -13,12 +16,24 @@ pub struct SearchNotesInput {
     pub vault_id: i64,
     pub query: String,
     pub limit: Option<usize>,
+    /// Inlines the indexed text of the top results.
+    pub include_content: Option<bool>,
+    /// Cap per result, up to 1 MiB; defaults to 16 KiB.
+    pub content_max_bytes: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SearchNotesOutput {
     pub results: Vec<SearchNoteEntry>,
+    /// The respelled query the results were found with, when the query as
+    /// sent matched nothing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub did_you_mean: Option<String>,
+    /// Some inlined content had secrets masked by the vault's redaction
+    /// rules.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -29,6 +44,10 @@ pub struct SearchNoteEntry {
     pub created_at: Option<i64>,
     pub modified_at: Option<i64>,
     pub similarity: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub content_truncated: bool,
 }
 
 pub fn search_notes(
@@ -39,6 +58,8 @@ pub fn search_notes(
         vault_id,
         query,
         limit,
+        include_content,
+        content_max_bytes,
     } = input;
     let workspace = resolve_workspace(db_path, vault_id)?;
     let workspace_path = PathBuf::from(&workspace.workspace_root);
@@ -48,23 +69,59 @@ pub fn search_notes(
     }
 
     let limit = resolve_limit(limit)?;
-    let results =
-        vault_indexing::search_notes_for_query(&workspace_path, db_path, trimmed_query, "", "")?
-            .into_iter()
-            .take(limit)
-            .map(|entry| SearchNoteEntry {
+    let private_paths = PrivatePaths::load(db_path, &workspace_path)?;
+    let redactor = Redactor::load(db_path, &workspace_path)?;
+    let options = vault_indexing::SearchOptions {
+        content_max_bytes: include_content.unwrap_or(false).then(|| {
+            content_max_bytes
+                .unwrap_or(vault_indexing::DEFAULT_CONTENT_MAX_BYTES)
+                .min(MAX_CONTENT_BYTES)
+        }),
+        ..Default::default()
+    };
+    let vault_indexing::CorrectedSearchResults {
+        entries,
+        did_you_mean,
+    } = vault_indexing::search_notes_with_correction(
+        &workspace_path,
+        db_path,
+        trimmed_query,
+        &[vault_indexing::EmbeddingProfile::new("", "")],
+        options,
+    )?;
+    let mut redacted = false;
+    let results = entries
+        .into_iter()
+        .filter(|entry| !private_paths.contains_path(&workspace_path, Path::new(&entry.path)))
+        .take(limit)
+        .map(|entry| {
+            let mut content = entry.content;
+            if let Some(content) = &mut content {
+                redacted |= redactor.redact(content);
+            }
+            SearchNoteEntry {
                 path: entry.path,
                 name: entry.name,
                 created_at: entry.created_at,
                 modified_at: entry.modified_at,
                 similarity: entry.similarity,
-            })
-            .collect();
-
-    Ok(SearchNotesOutput { results })
+                content,
+                content_truncated: entry.content_truncated,
+            }
+        })
+        .collect::<Vec<_>>();
+    // Respellings come from the vocabulary of every indexed note, so with
+    // private folders set they could leak words only private notes contain.
+    let did_you_mean = did_you_mean.filter(|_| private_paths.is_empty() && !results.is_empty());
+
+    Ok(SearchNotesOutput {
+        results,
+        did_you_mean,
+        redacted,
+    })
 }
 
-fn resolve_workspace(
+pub(crate) fn resolve_workspace(
     db_path: &Path,
     vault_id: i64,
 ) -> Result<app_storage::vault::VaultWorkspace, LocalApiError> {
@@ -81,7 +138,7 @@ fn resolve_workspace(
     Ok(workspace)
 }
 
-fn resolve_limit(limit: Option<usize>) -> Result<usize, LocalApiError> {
+pub(crate) fn resolve_limit(limit: Option<usize>) -> Result<usize, LocalApiError> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT);
     if !(1..=MAX_LIMIT).contains(&limit) {
         return Err(LocalApiError::InvalidSearchLimit { limit });
@@ -107,6 +164,8 @@ mod tests {
                 vault_id: harness.vault_id,
                 query: "   ".to_string(),
                 limit: None,
+                include_content: None,
+                content_max_bytes: None,
             },
         );
 
@@ -124,6 +183,8 @@ mod tests {
                     vault_id: harness.vault_id,
                     query: "query".to_string(),
                     limit: Some(limit),
+                    include_content: None,
+                    content_max_bytes: None,
                 },
             );
 
@@ -148,6 +209,8 @@ mod tests {
                 vault_id: harness.vault_id + 100,
                 query: "query".to_string(),
                 limit: None,
+                include_content: None,
+                content_max_bytes: None,
             },
         );
 
@@ -183,6 +246,8 @@ mod tests {
                 vault_id: harness.vault_id,
                 query: "nebula".to_string(),
                 limit: None,
+                include_content: None,
+                content_max_bytes: None,
             },
         )
         .expect("search should succeed");
@@ -194,6 +259,8 @@ mod tests {
                 vault_id: harness.vault_id,
                 query: "nebula".to_string(),
                 limit: Some(1),
+                include_content: None,
+                content_max_bytes: None,
             },
         )
         .expect("limited search should succeed");
@@ -205,6 +272,177 @@ mod tests {
             .any(|result| result.path == limited.results[0].path));
         assert!(limited.results[0].path.ends_with(".md"));
         assert!(!limited.results[0].name.is_empty());
+        assert_eq!(limited.results[0].content, None);
+    }
+
+    #[test]
+    fn search_notes_inlines_indexed_content_when_asked() {
+        let harness = Harness::new("local-api-search-content");
+        fs::write(
+            harness.workspace_path.join("Alpha.md"),
+            build_content("nebula"),
+        )
+        .expect("failed to write Alpha.md");
+        vault_indexing::index_vault_documents(
+            Path::new(&harness.workspace_path),
+            Path::new(&harness.db_path),
+            "",
+            "",
+            false,
+        )
+        .expect("failed to index workspace");
+
+        let output = search_notes(
+            Path::new(&harness.db_path),
+            SearchNotesInput {
+                vault_id: harness.vault_id,
+                query: "nebula".to_string(),
+                limit: None,
+                include_content: Some(true),
+                content_max_bytes: Some(12),
+            },
+        )
+        .expect("search should succeed");
+
+        let result = &output.results[0];
+        assert_eq!(result.content.as_deref().map(str::len), Some(12));
+        assert!(result.content_truncated);
+    }
+
+    #[test]
+    fn search_notes_redacts_inlined_content() {
+        let harness = Harness::new("local-api-search-redaction");
+        fs::write(
+            harness.workspace_path.join("Alpha.md"),
+            build_content("nebula billing goes to ana@example.com"),
+        )
+        .expect("failed to write Alpha.md");
+        vault_indexing::index_vault_documents(
+            Path::new(&harness.workspace_path),
+            Path::new(&harness.db_path),
+            "",
+            "",
+            false,
+        )
+        .expect("failed to index workspace");
+        app_storage::vault_settings::set_vault_setting(
+            &harness.db_path,
+            &harness.workspace_path,
+            "redaction",
+            &serde_json::json!({ "emails": true }),
+        )
+        .expect("save redaction rules");
+
+        let search = |include_content| {
+            search_notes(
+                Path::new(&harness.db_path),
+                SearchNotesInput {
+                    vault_id: harness.vault_id,
+                    query: "nebula".to_string(),
+                    limit: None,
+                    include_content: Some(include_content),
+                    content_max_bytes: None,
+                },
+            )
+            .expect("search should succeed")
+        };
+
+        let output = search(true);
+        let content = output.results[0].content.as_deref().expect("content");
+        assert!(content.contains("goes to [REDACTED]"));
+        assert!(!content.contains("ana@example.com"));
+        assert!(output.redacted);
+        assert!(!search(false).redacted);
+    }
+
+    #[test]
+    fn search_notes_suggests_a_spelling_when_nothing_matches() {
+        let harness = Harness::new("local-api-search-did-you-mean");
+        fs::write(
+            harness.workspace_path.join("Alpha.md"),
+            build_content("nebula"),
+        )
+        .expect("failed to write Alpha.md");
+        vault_indexing::index_vault_documents(
+            Path::new(&harness.workspace_path),
+            Path::new(&harness.db_path),
+            "",
+            "",
+            false,
+        )
+        .expect("failed to index workspace");
+
+        let output = search_notes(
+            Path::new(&harness.db_path),
+            SearchNotesInput {
+                vault_id: harness.vault_id,
+                query: "nebulla".to_string(),
+                limit: None,
+                include_content: None,
+                content_max_bytes: None,
+            },
+        )
+        .expect("search should succeed");
+
+        assert_eq!(output.did_you_mean.as_deref(), Some("nebula"));
+        assert_eq!(output.results.len(), 1);
+    }
+
+    #[test]
+    fn search_notes_leaves_out_private_folders() {
+        let harness = Harness::new("local-api-search-private");
+        fs::create_dir_all(harness.workspace_path.join("Journal")).expect("create folder");
+        fs::write(
+            harness.workspace_path.join("Alpha.md"),
+            build_content("nebula"),
+        )
+        .expect("failed to write Alpha.md");
+        fs::write(
+            harness.workspace_path.join("Journal/Beta.md"),
+            build_content("nebula quasar"),
+        )
+        .expect("failed to write Journal/Beta.md");
+        vault_indexing::index_vault_documents(
+            Path::new(&harness.workspace_path),
+            Path::new(&harness.db_path),
+            "",
+            "",
+            false,
+        )
+        .expect("failed to index workspace");
+        app_storage::vault_settings::set_vault_setting(
+            &harness.db_path,
+            &harness.workspace_path,
+            "privatePaths",
+            &serde_json::json!(["Journal"]),
+        )
+        .expect("save private paths");
+
+        let search = |query: &str| {
+            search_notes(
+                Path::new(&harness.db_path),
+                SearchNotesInput {
+                    vault_id: harness.vault_id,
+                    query: query.to_string(),
+                    limit: None,
+                    include_content: None,
+                    content_max_bytes: None,
+                },
+            )
+            .expect("search should succeed")
+        };
+
+        let output = search("nebula");
+        assert_eq!(output.results.len(), 1);
+        assert!(output.results[0].path.ends_with("Alpha.md"));
+
+        let output = search("quasr");
+        assert!(output.results.is_empty());
+        assert_eq!(output.did_you_mean, None);
+
+        let output = search("nebulla");
+        assert_eq!(output.results.len(), 1);
+        assert_eq!(output.did_you_mean, None);
     }
 
     fn build_content(query: &str) -> String {
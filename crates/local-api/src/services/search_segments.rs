@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::private_paths::PrivatePaths;
+use super::redaction::Redactor;
+use super::search_notes::{resolve_limit, resolve_workspace};
+use crate::LocalApiError;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSegmentsInput {
+    pub vault_id: i64,
+    pub query: String,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSegmentsOutput {
+    pub results: Vec<SearchSegmentEntry>,
+    /// Some segment text had secrets masked by the vault's redaction rules.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub redacted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSegmentEntry {
+    pub path: String,
+    pub rel_path: String,
+    pub ordinal: i64,
+    pub text: String,
+    /// Byte offsets of the segment in the note.
+    pub start_offset: usize,
+    pub end_offset: usize,
+    pub heading_path: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code_language: Option<String>,
+    pub vector_score: Option<f32>,
+    pub bm25_score: Option<f32>,
+    pub score: f32,
+}
+
+/// Matches individual segments rather than notes, with the vault's own
+/// embedding profiles. Vaults indexed without embeddings have no segments.
+pub fn search_segments(
+    db_path: &Path,
+    input: SearchSegmentsInput,
+) -> Result<SearchSegmentsOutput, LocalApiError> {
+    let SearchSegmentsInput {
+        vault_id,
+        query,
+        limit,
+    } = input;
+    let workspace = resolve_workspace(db_path, vault_id)?;
+    let workspace_path = PathBuf::from(&workspace.workspace_root);
+    let trimmed_query = query.trim();
+    if trimmed_query.is_empty() {
+        return Err(LocalApiError::InvalidSearchQuery);
+    }
+
+    let limit = resolve_limit(limit)?;
+    let private_paths = PrivatePaths::load(db_path, &workspace_path)?;
+    let redactor = Redactor::load(db_path, &workspace_path)?;
+    let profiles = app_storage::vault::get_embedding_config(db_path, &workspace_path)?
+        .map(|config| config.profiles())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(provider, model)| vault_indexing::EmbeddingProfile::new(provider, model))
+        .collect::<Vec<_>>();
+    let mut redacted = false;
+    // Private notes are dropped inside the search so they do not take up
+    // slots of the limit.
+    let results = vault_indexing::search_segments_where(
+        &workspace_path,
+        db_path,
+        trimmed_query,
+        &profiles,
+        limit,
+        |segment| !private_paths.contains_path(&workspace_path, Path::new(&segment.path)),
+    )?
+    .into_iter()
+    .map(|segment| {
+        let mut text = segment.text;
+        let mut heading_path = segment.passage.heading_path;
+        redacted |= redactor.redact(&mut text);
+        for heading in &mut heading_path {
+            redacted |= redactor.redact(heading);
+        }
+        SearchSegmentEntry {
+            path: segment.path,
+            rel_path: segment.rel_path,
+            ordinal: segment.ordinal,
+            text,
+            start_offset: segment.passage.start_offset,
+            end_offset: segment.passage.end_offset,
+            heading_path,
+            code_language: segment.passage.code_language,
+            vector_score: segment.vector_score,
+            bm25_score: segment.bm25_score,
+            score: segment.score,
+        }
+    })
+    .collect();
+
+    Ok(SearchSegmentsOutput { results, redacted })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::{search_segments, SearchSegmentsInput};
+    use crate::{services::test_support::Harness, LocalApiError};
+
+    #[test]
+    fn search_segments_validates_query_and_limit() {
+        let harness = Harness::new("local-api-search-segments-invalid");
+
+        let empty = search_segments(
+            Path::new(&harness.db_path),
+            SearchSegmentsInput {
+                vault_id: harness.vault_id,
+                query: "  ".to_string(),
+                limit: None,
+            },
+        );
+        assert!(matches!(empty, Err(LocalApiError::InvalidSearchQuery)));
+
+        let too_many = search_segments(
+            Path::new(&harness.db_path),
+            SearchSegmentsInput {
+                vault_id: harness.vault_id,
+                query: "nebula".to_string(),
+                limit: Some(101),
+            },
+        );
+        assert!(matches!(
+            too_many,
+            Err(LocalApiError::InvalidSearchLimit { limit: 101 })
+        ));
+    }
+
+    #[test]
+    fn search_segments_returns_nothing_for_vault_without_embeddings() {
+        let harness = Harness::new("local-api-search-segments-keyword-only");
+        fs::write(
+            harness.workspace_path.join("Alpha.md"),
+            format!("# Title\n\nnebula\n\n{}\n", "lorem ipsum ".repeat(40)),
+        )
+        .expect("failed to write Alpha.md");
+        vault_indexing::index_vault_documents(
+            Path::new(&harness.workspace_path),
+            Path::new(&harness.db_path),
+            "",
+            "",
+            false,
+        )
+        .expect("failed to index workspace");
+
+        let output = search_segments(
+            Path::new(&harness.db_path),
+            SearchSegmentsInput {
+                vault_id: harness.vault_id,
+                query: "nebula".to_string(),
+                limit: None,
+            },
+        )
+        .expect("search should succeed");
+
+        assert!(output.results.is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::private_paths::PrivatePaths;
+use crate::LocalApiError;
+
+pub use vault_indexing::{CaseConflict, DuplicateBasename};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDiagnosticsInput {
+    pub vault_id: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDiagnosticsOutput {
+    pub case_conflicts: Vec<CaseConflict>,
+    pub duplicate_basenames: Vec<DuplicateBasename>,
+}
+
+pub fn get_vault_diagnostics(
+    db_path: &Path,
+    input: VaultDiagnosticsInput,
+) -> Result<VaultDiagnosticsOutput, LocalApiError> {
+    let workspace = resolve_workspace(db_path, input.vault_id)?;
+    let workspace_path = Path::new(&workspace.workspace_root);
+    let diagnostics = vault_indexing::run_vault_diagnostics(workspace_path)?;
+    let private_paths = PrivatePaths::load(db_path, workspace_path)?;
+    let public_paths = |rel_paths: Vec<String>| {
+        let rel_paths = rel_paths
+            .into_iter()
+            .filter(|rel_path| !private_paths.contains(rel_path))
+            .collect::<Vec<_>>();
+        // A single path left conflicts with nothing the caller can see.
+        (rel_paths.len() > 1).then_some(rel_paths)
+    };
+
+    Ok(VaultDiagnosticsOutput {
+        case_conflicts: diagnostics
+            .case_conflicts
+            .into_iter()
+            .filter_map(|conflict| {
+                public_paths(conflict.rel_paths).map(|rel_paths| CaseConflict { rel_paths })
+            })
+            .collect(),
+        duplicate_basenames: diagnostics
+            .duplicate_basenames
+            .into_iter()
+            .filter_map(|duplicate| {
+                public_paths(duplicate.rel_paths).map(|rel_paths| DuplicateBasename {
+                    basename: duplicate.basename,
+                    rel_paths,
+                })
+            })
+            .collect(),
+    })
+}
+
+fn resolve_workspace(
+    db_path: &Path,
+    vault_id: i64,
+) -> Result<app_storage::vault::VaultWorkspace, LocalApiError> {
+    let workspace = app_storage::vault::get_workspace_by_id(db_path, vault_id)?
+        .ok_or(LocalApiError::VaultNotFound { vault_id })?;
+    let workspace_path = PathBuf::from(&workspace.workspace_root);
+
+    if !workspace_path.is_dir() {
+        return Err(LocalApiError::VaultWorkspaceUnavailable {
+            workspace_path: workspace.workspace_root,
+        });
+    }
+
+    Ok(workspace)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::{get_vault_diagnostics, VaultDiagnosticsInput};
+    use crate::{services::test_support::Harness, LocalApiError};
+
+    #[test]
+    fn get_vault_diagnostics_reports_duplicate_basenames() {
+        let harness = Harness::new("local-api-diagnostics-duplicates");
+        for dir in ["a", "b"] {
+            let dir_path = harness.workspace_path.join(dir);
+            fs::create_dir_all(&dir_path).expect("failed to create note directory");
+            fs::write(dir_path.join("Meeting.md"), "# Meeting").expect("failed to write note");
+        }
+
+        let output = get_vault_diagnostics(
+            Path::new(&harness.db_path),
+            VaultDiagnosticsInput {
+                vault_id: harness.vault_id,
+            },
+        )
+        .expect("diagnostics should succeed");
+
+        assert!(output.case_conflicts.is_empty());
+        assert_eq!(output.duplicate_basenames.len(), 1);
+        assert_eq!(
+            output.duplicate_basenames[0].rel_paths,
+            vec!["a/Meeting.md".to_string(), "b/Meeting.md".to_string()]
+        );
+    }
+
+    #[test]
+    fn get_vault_diagnostics_leaves_out_private_folders() {
+        let harness = Harness::new("local-api-diagnostics-private");
+        for dir in ["a", "b", "Journal"] {
+            let dir_path = harness.workspace_path.join(dir);
+            fs::create_dir_all(&dir_path).expect("failed to create note directory");
+            fs::write(dir_path.join("Meeting.md"), "# Meeting").expect("failed to write note");
+        }
+        fs::write(harness.workspace_path.join("Journal/May.md"), "# May").expect("write note");
+        fs::write(harness.workspace_path.join("a/May.md"), "# May").expect("write note");
+        app_storage::vault_settings::set_vault_setting(
+            &harness.db_path,
+            &harness.workspace_path,
+            "privatePaths",
+            &serde_json::json!(["Journal"]),
+        )
+        .expect("save private paths");
+
+        let output = get_vault_diagnostics(
+            Path::new(&harness.db_path),
+            VaultDiagnosticsInput {
+                vault_id: harness.vault_id,
+            },
+        )
+        .expect("diagnostics should succeed");
+
+        assert_eq!(output.duplicate_basenames.len(), 1);
+        assert_eq!(
+            output.duplicate_basenames[0].rel_paths,
+            vec!["a/Meeting.md".to_string(), "b/Meeting.md".to_string()]
+        );
+    }
+
+    #[test]
+    fn get_vault_diagnostics_returns_error_when_vault_is_missing() {
+        let harness = Harness::new("local-api-diagnostics-missing-vault");
+
+        let result = get_vault_diagnostics(
+            Path::new(&harness.db_path),
+            VaultDiagnosticsInput {
+                vault_id: harness.vault_id + 100,
+            },
+        );
+
+        assert!(matches!(result, Err(LocalApiError::VaultNotFound { .. })));
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    backtrace::Backtrace,
+    collections::HashMap,
+    fmt::Write as _,
+    fs, io,
+    panic::{self, Location},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+
+pub const CRASH_LOG_FILE_NAME: &str = "crashes.log";
+/// Older crashes are dropped once the log holds this many.
+const KEPT_CRASHES: usize = 50;
+
+// Panicking threads take turns rewriting the crash log.
+static CRASH_LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// One panic, as written to the crash log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashRecord {
+    pub time: String,
+    pub thread: String,
+    pub location: String,
+    pub message: String,
+    pub backtrace: String,
+}
+
+/// Crashes with the same message at the same place, counted together.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashSummary {
+    pub location: String,
+    pub message: String,
+    pub count: usize,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+/// Writes every panic, on any thread, to the crash log in `dir` with its
+/// backtrace and reports it to the app log before the default hook runs.
+/// Panics inside spawned tasks otherwise only surface as a failed join.
+pub fn install_panic_hook(dir: &Path) {
+    let dir = dir.to_path_buf();
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| (*message).to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let record = CrashRecord {
+            time: chrono::Utc::now()
+                .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+                .to_string(),
+            thread: thread::current().name().unwrap_or("<unnamed>").to_string(),
+            location: info.location().map(Location::to_string).unwrap_or_default(),
+            message,
+            backtrace: Backtrace::force_capture().to_string(),
+        };
+        tracing::error!(
+            thread = %record.thread,
+            location = %record.location,
+            "panic: {}",
+            record.message
+        );
+        // Nowhere left to report a failing crash log.
+        let _ = record_crash(&dir, record);
+        previous(info);
+    }));
+}
+
+fn record_crash(dir: &Path, record: CrashRecord) -> io::Result<()> {
+    let _guard = CRASH_LOG_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut crashes = read_crashes(dir)?;
+    crashes.push(record);
+    let kept = &crashes[crashes.len().saturating_sub(KEPT_CRASHES)..];
+
+    let mut contents = String::new();
+    for crash in kept {
+        let line = serde_json::to_string(crash).map_err(io::Error::other)?;
+        contents.push_str(&line);
+        contents.push('\n');
+    }
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(CRASH_LOG_FILE_NAME), contents)
+}
+
+/// Crashes recorded in `dir`, oldest first. Lines that fail to parse are
+/// skipped.
+pub fn read_crashes(dir: &Path) -> io::Result<Vec<CrashRecord>> {
+    let contents = match fs::read_to_string(dir.join(CRASH_LOG_FILE_NAME)) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// The recorded crashes grouped by place and message, most recent first.
+pub fn crash_summaries(dir: &Path) -> io::Result<Vec<CrashSummary>> {
+    let mut groups: HashMap<(String, String), CrashSummary> = HashMap::new();
+    for crash in read_crashes(dir)? {
+        groups
+            .entry((crash.location.clone(), crash.message.clone()))
+            .and_modify(|summary| {
+                summary.count += 1;
+                summary.last_seen = crash.time.clone();
+            })
+            .or_insert_with(|| CrashSummary {
+                location: crash.location,
+                message: crash.message,
+                count: 1,
+                first_seen: crash.time.clone(),
+                last_seen: crash.time,
+            });
+    }
+    let mut summaries = groups.into_values().collect::<Vec<_>>();
+    summaries.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+    Ok(summaries)
+}
+
+/// Replaces each path in `text` with its placeholder. Longer paths go first,
+/// so a vault inside the home folder keeps its own placeholder.
+pub fn redact(text: &str, replacements: &[(PathBuf, String)]) -> String {
+    let mut replacements = replacements
+        .iter()
+        .map(|(path, placeholder)| (path.to_string_lossy(), placeholder))
+        .filter(|(path, _)| !path.is_empty())
+        .collect::<Vec<_>>();
+    replacements.sort_by_key(|(path, _)| std::cmp::Reverse(path.len()));
+
+    replacements
+        .into_iter()
+        .fold(text.to_string(), |text, (path, placeholder)| {
+            text.replace(path.as_ref(), placeholder)
+        })
+}
+
+/// A plain-text report of the recorded crashes and the last `log_lines` of
+/// the app log, with `replacements` applied throughout.
+pub fn crash_report(
+    dir: &Path,
+    log_lines: usize,
+    replacements: &[(PathBuf, String)],
+) -> io::Result<String> {
+    let mut report = format!(
+        "# mdit crash report\n\nOS: {} {}\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let crashes = read_crashes(dir)?;
+    let _ = writeln!(report, "\n## Crashes ({})", crashes.len());
+    for crash in &crashes {
+        let _ = write!(
+            report,
+            "\n{} on {} at {}: {}\n{}\n",
+            crash.time, crash.thread, crash.location, crash.message, crash.backtrace
+        );
+    }
+    let _ = writeln!(report, "\n## Recent log");
+    for line in crate::recent_logs(dir, log_lines)? {
+        report.push_str(&line);
+        report.push('\n');
+    }
+    Ok(redact(&report, replacements))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::PathBuf,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::{crash_summaries, record_crash, redact, CrashRecord};
+
+    fn crash(time: &str, location: &str) -> CrashRecord {
+        CrashRecord {
+            time: time.to_string(),
+            thread: "tokio-runtime-worker".to_string(),
+            location: location.to_string(),
+            message: "index out of bounds".to_string(),
+            backtrace: String::new(),
+        }
+    }
+
+    #[test]
+    fn groups_crashes_by_place_and_message_and_redacts_paths() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should move forward")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("mdit-logging-crashes-{nanos}"));
+        record_crash(&dir, crash("2026-01-01T00:00:00.000Z", "src/a.rs:1:1")).expect("write");
+        record_crash(&dir, crash("2026-01-02T00:00:00.000Z", "src/b.rs:2:2")).expect("write");
+        record_crash(&dir, crash("2026-01-03T00:00:00.000Z", "src/a.rs:1:1")).expect("write");
+
+        let summaries = crash_summaries(&dir).expect("crash log should be readable");
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].location, "src/a.rs:1:1");
+        assert_eq!(summaries[0].count, 2);
+        assert_eq!(summaries[0].first_seen, "2026-01-01T00:00:00.000Z");
+        assert_eq!(summaries[0].last_seen, "2026-01-03T00:00:00.000Z");
+        assert_eq!(summaries[1].count, 1);
+
+        let replacements = [
+            (PathBuf::from("/home/ada"), "~".to_string()),
+            (PathBuf::from("/home/ada/Notes"), "<vault>".to_string()),
+        ];
+        assert_eq!(
+            redact(
+                "failed to index /home/ada/Notes/a.md from /home/ada/.config",
+                &replacements
+            ),
+            "failed to index <vault>/a.md from ~/.config"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
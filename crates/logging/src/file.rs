@@ -0,0 +1,96 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+/// Log files are named `mdit.<date>.log`, one per day.
+const LOG_FILE_PREFIX: &str = "mdit";
+const LOG_FILE_SUFFIX: &str = "log";
+/// Days of logs kept, the live one included.
+const KEPT_LOG_FILES: usize = 4;
+
+/// The daily log file in `dir`, pruned down to [`KEPT_LOG_FILES`].
+pub(crate) fn log_appender(dir: &Path) -> Result<RollingFileAppender, String> {
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(KEPT_LOG_FILES)
+        .build(dir)
+        .map_err(|error| error.to_string())
+}
+
+/// The log files in `dir`, newest first. Their dated names sort in order.
+pub(crate) fn log_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let is_log = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| {
+                name.starts_with(&format!("{LOG_FILE_PREFIX}."))
+                    && name.ends_with(&format!(".{LOG_FILE_SUFFIX}"))
+            });
+        if is_log {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files.reverse();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::log_files;
+
+    #[test]
+    fn lists_dated_log_files_newest_first() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should move forward")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("mdit-logging-files-{nanos}"));
+        fs::create_dir_all(&dir).expect("dir should be created");
+        for name in [
+            "mdit.2026-01-02.log",
+            "mdit.2026-01-03.log",
+            "mdit.2026-01-01.log",
+            "crashes.log",
+        ] {
+            fs::write(dir.join(name), "").expect("file should be written");
+        }
+
+        let names = log_files(&dir)
+            .expect("files should be listed")
+            .into_iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "mdit.2026-01-03.log",
+                "mdit.2026-01-02.log",
+                "mdit.2026-01-01.log"
+            ]
+        );
+        assert!(log_files(&dir.join("missing"))
+            .expect("missing dir")
+            .is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
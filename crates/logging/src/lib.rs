@@ -0,0 +1,150 @@
+//! The app's log: `tracing` events, with the spans they happened in, written
+//! to daily rotated files that users can attach to bug reports, and panics with
+//! their backtraces to a crash log.
+
+mod crash;
+mod file;
+
+use std::{fs, io, path::Path, sync::OnceLock};
+
+use serde::{Deserialize, Serialize};
+use tracing::{level_filters::LevelFilter, Subscriber};
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, Registry};
+
+pub use crash::{
+    crash_report, crash_summaries, install_panic_hook, read_crashes, redact, CrashRecord,
+    CrashSummary, CRASH_LOG_FILE_NAME,
+};
+use file::{log_appender, log_files};
+
+static INSTALLED_LEVEL: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+/// The most verbose events that are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn as_filter(self) -> LevelFilter {
+        match self {
+            Self::Error => LevelFilter::ERROR,
+            Self::Warn => LevelFilter::WARN,
+            Self::Info => LevelFilter::INFO,
+            Self::Debug => LevelFilter::DEBUG,
+            Self::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// Installs the file log in `dir` as the process-wide `tracing` subscriber.
+/// Fails when another subscriber is already installed.
+pub fn init(dir: &Path, level: LogLevel) -> Result<(), String> {
+    let (subscriber, handle) = file_subscriber(dir, level)?;
+    tracing::subscriber::set_global_default(subscriber).map_err(|error| error.to_string())?;
+    let _ = INSTALLED_LEVEL.set(handle);
+    Ok(())
+}
+
+/// Changes the level of the installed log; a no-op before [`init`].
+pub fn set_level(level: LogLevel) {
+    if let Some(installed) = INSTALLED_LEVEL.get() {
+        let _ = installed.reload(level.as_filter());
+    }
+}
+
+/// Events at `level` and above, with the spans they happened in, written to
+/// the daily log file in `dir` and, in debug builds, to stderr.
+fn file_subscriber(
+    dir: &Path,
+    level: LogLevel,
+) -> Result<
+    (
+        impl Subscriber + Send + Sync,
+        reload::Handle<LevelFilter, Registry>,
+    ),
+    String,
+> {
+    let (filter, handle) = reload::Layer::new(level.as_filter());
+    let file = fmt::layer()
+        .with_ansi(false)
+        .with_writer(log_appender(dir)?);
+    let stderr = cfg!(debug_assertions).then(|| fmt::layer().with_writer(io::stderr));
+    let subscriber = Registry::default().with(filter).with(file).with(stderr);
+    Ok((subscriber, handle))
+}
+
+/// The last `max_lines` lines logged in `dir`, oldest first, reaching into
+/// earlier days when today's file is shorter.
+pub fn recent_logs(dir: &Path, max_lines: usize) -> io::Result<Vec<String>> {
+    let mut newest_first = Vec::new();
+    for path in log_files(dir)? {
+        if newest_first.len() >= max_lines {
+            break;
+        }
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error),
+        };
+        newest_first.extend(
+            contents
+                .lines()
+                .rev()
+                .take(max_lines - newest_first.len())
+                .map(str::to_string),
+        );
+    }
+    newest_first.reverse();
+    Ok(newest_first)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::{file_subscriber, recent_logs, LogLevel};
+
+    #[test]
+    fn writes_events_inside_their_spans_at_the_chosen_level() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should move forward")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("mdit-logging-events-{nanos}"));
+        let (subscriber, level) =
+            file_subscriber(&dir, LogLevel::Info).expect("subscriber should be built");
+
+        tracing::subscriber::with_default(subscriber, || {
+            let run = tracing::info_span!("index_run", vault = "Notes");
+            let _entered = run.enter();
+            tracing::info!(files = 3, "indexed");
+            tracing::debug!("hidden at info");
+            level
+                .reload(LogLevel::Debug.as_filter())
+                .expect("level should change");
+            tracing::debug!(path = "a.md", "shown at debug");
+        });
+
+        let lines = recent_logs(&dir, 10).expect("logs should be readable");
+        assert_eq!(lines.len(), 2);
+        assert!(
+            lines[0].ends_with(" INFO index_run{vault=\"Notes\"}: logging::tests: indexed files=3")
+        );
+        assert!(lines[1].ends_with(
+            "DEBUG index_run{vault=\"Notes\"}: logging::tests: shown at debug path=\"a.md\""
+        ));
+        assert_eq!(recent_logs(&dir, 1).expect("tail").len(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
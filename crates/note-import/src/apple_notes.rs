@@ -0,0 +1,441 @@
+//! Apple Notes exports: `.enex` files, or folders of `.html` notes with their
+//! images next to them. Subfolders of an export folder become vault folders.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use base64::Engine;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use walkdir::WalkDir;
+
+use crate::hashtags::to_mdit_tag;
+use crate::html::{html_title, html_to_segments, HtmlReference};
+use crate::source::{
+    folders_between, has_extension, percent_decode, Segment, SourceAttachment, SourceNote,
+    SourceSkip,
+};
+
+pub(crate) fn read_apple_notes(input: &Path) -> Result<(Vec<SourceNote>, Vec<SourceSkip>), String> {
+    let mut notes = Vec::new();
+    let mut skipped = Vec::new();
+
+    if !input.is_dir() {
+        if has_extension(input, &["enex"]) {
+            notes.extend(read_enex(input, Vec::new(), &mut skipped)?);
+        } else if has_extension(input, &["html", "htm"]) {
+            let base = input.parent().unwrap_or(input);
+            notes.extend(read_html_notes(base, &[input.to_path_buf()], &mut skipped));
+        } else {
+            return Err(format!(
+                "Expected an .enex or .html export: {}",
+                input.display()
+            ));
+        }
+        return Ok((notes, skipped));
+    }
+
+    let mut html_files = Vec::new();
+    for entry in WalkDir::new(input)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if has_extension(path, &["enex"]) {
+            let folders = folders_between(input, path);
+            notes.extend(read_enex(path, folders, &mut skipped)?);
+        } else if has_extension(path, &["html", "htm"]) {
+            html_files.push(path.to_path_buf());
+        }
+    }
+    notes.extend(read_html_notes(input, &html_files, &mut skipped));
+    Ok((notes, skipped))
+}
+
+fn read_html_notes(
+    base: &Path,
+    files: &[PathBuf],
+    skipped: &mut Vec<SourceSkip>,
+) -> Vec<SourceNote> {
+    let mut sources = Vec::new();
+    for path in files {
+        match fs::read(path) {
+            Ok(bytes) => sources.push((path, String::from_utf8_lossy(&bytes).into_owned())),
+            Err(error) => skipped.push(SourceSkip {
+                origin: path.display().to_string(),
+                reason: error.to_string(),
+            }),
+        }
+    }
+
+    // Titles first, so links between the exported notes can use them.
+    let titles = sources
+        .iter()
+        .map(|(path, html)| {
+            let title = html_title(html).unwrap_or_else(|| {
+                path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            });
+            (normalize_path(path), title)
+        })
+        .collect::<HashMap<_, _>>();
+
+    let export_root = normalize_path(base);
+    let mut notes = Vec::new();
+    for (path, html) in &sources {
+        let folder = path.parent().unwrap_or(base);
+        let mut attachments = Vec::new();
+        let body = html_to_segments(html, &mut |reference| match reference {
+            HtmlReference::Link { href, label } => {
+                if href.contains(':') {
+                    return None;
+                }
+                let (target, heading) = match href.split_once('#') {
+                    Some((target, heading)) => (target, Some(percent_decode(heading))),
+                    None => (href, None),
+                };
+                let title = titles.get(&normalize_path(&folder.join(percent_decode(target))))?;
+                Some(Segment::NoteLink {
+                    title: title.clone(),
+                    heading,
+                    label: Some(label.to_string()).filter(|label| label != title),
+                })
+            }
+            HtmlReference::Image { src, alt } => {
+                let attachment = if let Some(data) = src.strip_prefix("data:") {
+                    decode_data_uri(data, alt, attachments.len())?
+                } else if src.contains(':') {
+                    return None;
+                } else {
+                    // Only files inside the export are imported, so a crafted
+                    // `src` cannot pull in arbitrary local files.
+                    let file = normalize_path(&folder.join(percent_decode(src)));
+                    if !file.starts_with(&export_root) {
+                        return None;
+                    }
+                    SourceAttachment {
+                        file_name: file.file_name()?.to_string_lossy().into_owned(),
+                        bytes: fs::read(&file).ok()?,
+                    }
+                };
+                attachments.push(attachment);
+                Some(Segment::Attachment {
+                    index: attachments.len() - 1,
+                    embed: true,
+                })
+            }
+            HtmlReference::Media { .. } => None,
+        });
+
+        notes.push(SourceNote {
+            origin: path.display().to_string(),
+            title: titles[&normalize_path(path)].clone(),
+            folders: folders_between(base, path),
+            tags: Vec::new(),
+            body,
+            attachments,
+        });
+    }
+    notes
+}
+
+/// `path` with `.` and `..` worked out, to compare link targets.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// The attachment in a `data:` URI, given without its `data:` prefix.
+fn decode_data_uri(data: &str, alt: &str, index: usize) -> Option<SourceAttachment> {
+    let (header, payload) = data.split_once(',')?;
+    let media_type = header.strip_suffix(";base64")?;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(payload.trim())
+        .ok()?;
+    let extension = media_type
+        .split_once('/')
+        .map(|(_, subtype)| subtype.split(['+', ';']).next().unwrap_or(subtype))
+        .filter(|subtype| !subtype.is_empty())
+        .unwrap_or("bin");
+    let stem = Some(alt.trim())
+        .filter(|alt| !alt.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("image {}", index + 1));
+    Some(SourceAttachment {
+        file_name: format!("{stem}.{extension}"),
+        bytes,
+    })
+}
+
+#[derive(Default)]
+struct EnexNote {
+    title: String,
+    content: String,
+    tags: Vec<String>,
+    resources: Vec<EnexResource>,
+}
+
+#[derive(Default)]
+struct EnexResource {
+    data: String,
+    mime: String,
+    file_name: String,
+}
+
+fn read_enex(
+    path: &Path,
+    folders: Vec<String>,
+    skipped: &mut Vec<SourceSkip>,
+) -> Result<Vec<SourceNote>, String> {
+    let xml = fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+    let origin = path.display().to_string();
+
+    let mut reader = Reader::from_str(&xml);
+    let mut elements: Vec<String> = Vec::new();
+    let mut notes = Vec::new();
+    let mut note: Option<EnexNote> = None;
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|error| format!("Invalid ENEX file {}: {error}", path.display()))?;
+        let text = match event {
+            Event::Start(start) => {
+                let name = String::from_utf8_lossy(start.local_name().as_ref()).into_owned();
+                match (name.as_str(), note.as_mut()) {
+                    ("note", _) => note = Some(EnexNote::default()),
+                    ("resource", Some(note)) => note.resources.push(EnexResource::default()),
+                    _ => {}
+                }
+                elements.push(name);
+                continue;
+            }
+            Event::End(_) => {
+                if elements.pop().as_deref() == Some("note") {
+                    if let Some(note) = note.take() {
+                        notes.push(convert_enex_note(note, &origin, &folders, skipped));
+                    }
+                }
+                continue;
+            }
+            Event::Text(text) => text
+                .decode()
+                .map_err(|error| format!("Invalid ENEX file {}: {error}", path.display()))?
+                .into_owned(),
+            Event::CData(data) => data
+                .decode()
+                .map_err(|error| format!("Invalid ENEX file {}: {error}", path.display()))?
+                .into_owned(),
+            Event::GeneralRef(reference) => {
+                let name = reference
+                    .decode()
+                    .map_err(|error| format!("Invalid ENEX file {}: {error}", path.display()))?;
+                match reference.resolve_char_ref() {
+                    Ok(Some(ch)) => ch.to_string(),
+                    _ => quick_xml::escape::resolve_predefined_entity(&name)
+                        .unwrap_or_default()
+                        .to_string(),
+                }
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+
+        let Some(note) = note.as_mut() else {
+            continue;
+        };
+        let in_resource = elements.iter().any(|element| element == "resource");
+        match (elements.last().map(String::as_str), in_resource) {
+            (Some("title"), false) => note.title.push_str(&text),
+            (Some("content"), false) => note.content.push_str(&text),
+            (Some("tag"), false) => note.tags.push(text),
+            (Some("data"), true) => push_resource(note, |resource| &mut resource.data, &text),
+            (Some("mime"), true) => push_resource(note, |resource| &mut resource.mime, &text),
+            (Some("file-name"), true) => {
+                push_resource(note, |resource| &mut resource.file_name, &text)
+            }
+            _ => {}
+        }
+    }
+    Ok(notes)
+}
+
+fn push_resource(note: &mut EnexNote, field: fn(&mut EnexResource) -> &mut String, text: &str) {
+    if let Some(resource) = note.resources.last_mut() {
+        field(resource).push_str(text);
+    }
+}
+
+fn convert_enex_note(
+    note: EnexNote,
+    origin: &str,
+    folders: &[String],
+    skipped: &mut Vec<SourceSkip>,
+) -> SourceNote {
+    let title = note.title.trim().to_string();
+    let mut attachments = Vec::new();
+    let mut media = HashMap::new();
+    for (index, resource) in note.resources.into_iter().enumerate() {
+        let data = resource
+            .data
+            .chars()
+            .filter(|ch| !ch.is_whitespace())
+            .collect::<String>();
+        let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(data) else {
+            skipped.push(SourceSkip {
+                origin: format!("{origin}: {title}"),
+                reason: format!("Attachment {} is not valid base64", index + 1),
+            });
+            continue;
+        };
+        let hash = format!("{:x}", md5::compute(&bytes));
+        let file_name = Some(resource.file_name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| {
+                let extension = resource.mime.rsplit('/').next().unwrap_or("bin");
+                format!("attachment {}.{extension}", index + 1)
+            });
+        media.insert(hash, attachments.len());
+        attachments.push(SourceAttachment { file_name, bytes });
+    }
+
+    let body = html_to_segments(&note.content, &mut |reference| match reference {
+        HtmlReference::Media { hash } => media
+            .get(hash)
+            .map(|&index| Segment::Attachment { index, embed: true }),
+        // Evernote note links carry ids this export cannot resolve; the
+        // label is usually the note's title.
+        HtmlReference::Link { href, label } if href.starts_with("evernote:") => {
+            Some(Segment::NoteLink {
+                title: label.to_string(),
+                heading: None,
+                label: None,
+            })
+        }
+        _ => None,
+    });
+
+    SourceNote {
+        origin: format!("{origin}: {title}"),
+        title,
+        folders: folders.to_vec(),
+        tags: note
+            .tags
+            .iter()
+            .filter_map(|tag| to_mdit_tag(tag))
+            .collect(),
+        body,
+        attachments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::read_apple_notes;
+    use crate::source::Segment;
+
+    #[test]
+    fn reads_enex_notes_with_tags_and_media() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-note-import-enex-{nanos}"));
+        fs::create_dir_all(root.join("Work")).unwrap();
+        // base64 of "png", whose MD5 is bff139fa05ac583f685a523ab3d110a0.
+        let enex = concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<en-export><note><title>Q3 &amp; Q4</title>",
+            "<content><![CDATA[<en-note><div>Plan <en-media hash=\"",
+            "bff139fa05ac583f685a523ab3d110a0\" type=\"image/png\"/></div>",
+            "<div>See <a href=\"evernote:///view/1/s1/abc/abc/\">Budget</a></div></en-note>]]></content>",
+            "<tag>Road map</tag>",
+            "<resource><data encoding=\"base64\">cG5n\n</data><mime>image/png</mime>",
+            "<resource-attributes><file-name>chart.png</file-name></resource-attributes></resource>",
+            "</note></en-export>"
+        );
+        fs::write(root.join("Work/Export.enex"), enex).unwrap();
+
+        let (notes, skipped) = read_apple_notes(&root).unwrap();
+
+        assert!(skipped.is_empty());
+        assert_eq!(notes.len(), 1);
+        let note = &notes[0];
+        assert_eq!(note.title, "Q3 & Q4");
+        assert_eq!(note.folders, vec!["Work".to_string()]);
+        assert_eq!(note.tags, vec!["Road-map".to_string()]);
+        assert_eq!(note.attachments[0].file_name, "chart.png");
+        assert_eq!(
+            note.body,
+            vec![
+                Segment::Markdown("Plan ".to_string()),
+                Segment::Attachment {
+                    index: 0,
+                    embed: true
+                },
+                Segment::Markdown("\n\nSee ".to_string()),
+                Segment::NoteLink {
+                    title: "Budget".to_string(),
+                    heading: None,
+                    label: None,
+                },
+            ]
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn skips_html_images_outside_the_export_folder() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-note-import-html-{nanos}"));
+        let export = root.join("Export");
+        fs::create_dir_all(&export).unwrap();
+        fs::write(root.join("secret.txt"), "secret").unwrap();
+        fs::write(export.join("photo.png"), "png").unwrap();
+        let outside = root.join("secret.txt");
+        let html = format!(
+            concat!(
+                "<html><head><title>Trip</title></head><body>",
+                "<img src=\"photo.png\"><img src=\"../secret.txt\">",
+                "<img src=\"{}\"></body></html>"
+            ),
+            outside.display()
+        );
+        fs::write(export.join("Trip.html"), html).unwrap();
+
+        let (notes, skipped) = read_apple_notes(&export).unwrap();
+
+        assert!(skipped.is_empty());
+        assert_eq!(notes.len(), 1);
+        let names = notes[0]
+            .attachments
+            .iter()
+            .map(|attachment| attachment.file_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["photo.png"]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,320 @@
+//! Bear backups: a `.bear2bk` archive of TextBundles, a folder of
+//! `.textbundle` folders, or a single one.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use walkdir::WalkDir;
+
+use crate::hashtags::rewrite_hashtags;
+use crate::source::{
+    percent_decode, Segment, SegmentWriter, SourceAttachment, SourceNote, SourceSkip,
+};
+
+const BUNDLE_EXTENSION: &str = ".textbundle";
+const TEXT_FILES: [&str; 3] = ["text.markdown", "text.md", "text.txt"];
+const ASSETS_PREFIX: &str = "assets/";
+
+#[derive(Default)]
+struct Bundle {
+    /// Files by their path inside the bundle.
+    files: HashMap<String, Vec<u8>>,
+}
+
+pub(crate) fn read_bear(input: &Path) -> Result<(Vec<SourceNote>, Vec<SourceSkip>), String> {
+    let bundles = if input.is_dir() {
+        read_bundle_folder(input)?
+    } else {
+        read_bundle_archive(input)?
+    };
+
+    let mut notes = Vec::new();
+    let mut skipped = Vec::new();
+    for (name, bundle) in bundles {
+        let origin = format!("{name}{BUNDLE_EXTENSION}");
+        if is_trashed(&bundle) {
+            skipped.push(SourceSkip {
+                origin,
+                reason: "In Bear's trash".to_string(),
+            });
+            continue;
+        }
+        let Some(text) = TEXT_FILES
+            .iter()
+            .find_map(|file| bundle.files.get(*file))
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+        else {
+            skipped.push(SourceSkip {
+                origin,
+                reason: "No text file in the bundle".to_string(),
+            });
+            continue;
+        };
+
+        let mut attachments = Vec::new();
+        let body = bear_segments(&rewrite_hashtags(&text), &bundle, &mut attachments);
+        notes.push(SourceNote {
+            origin,
+            title: name,
+            folders: Vec::new(),
+            tags: Vec::new(),
+            body,
+            attachments,
+        });
+    }
+    Ok((notes, skipped))
+}
+
+fn read_bundle_archive(path: &Path) -> Result<BTreeMap<String, Bundle>, String> {
+    let file = fs::File::open(path)
+        .map_err(|error| format!("Failed to open {}: {error}", path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|error| format!("Not a Bear backup: {}: {error}", path.display()))?;
+
+    let mut bundles = BTreeMap::new();
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|error| format!("Failed to read the Bear backup: {error}"))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(entry_path) = entry.enclosed_name() else {
+            continue;
+        };
+        let entry_path = entry_path.to_string_lossy().replace('\\', "/");
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|error| format!("Failed to read {entry_path}: {error}"))?;
+        add_bundle_file(&mut bundles, &entry_path, bytes);
+    }
+    Ok(bundles)
+}
+
+fn read_bundle_folder(path: &Path) -> Result<BTreeMap<String, Bundle>, String> {
+    // Relative to the parent, so a single bundle keeps its own name.
+    let base = path.parent().unwrap_or(path);
+    let mut bundles = BTreeMap::new();
+    for entry in WalkDir::new(path).into_iter().filter_map(Result::ok) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(base) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let bytes = fs::read(entry.path())
+            .map_err(|error| format!("Failed to read {}: {error}", entry.path().display()))?;
+        add_bundle_file(&mut bundles, &relative, bytes);
+    }
+    Ok(bundles)
+}
+
+/// Files outside any `.textbundle` folder are ignored.
+fn add_bundle_file(bundles: &mut BTreeMap<String, Bundle>, path: &str, bytes: Vec<u8>) {
+    let mut components = path.split('/');
+    for component in components.by_ref() {
+        let Some(name) = component.strip_suffix(BUNDLE_EXTENSION) else {
+            continue;
+        };
+        let inner = components.collect::<Vec<_>>().join("/");
+        bundles
+            .entry(name.to_string())
+            .or_default()
+            .files
+            .insert(inner, bytes);
+        return;
+    }
+}
+
+fn is_trashed(bundle: &Bundle) -> bool {
+    bundle
+        .files
+        .get("info.json")
+        .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(bytes).ok())
+        .and_then(|info| info.get("net.shinyfrog.bear")?.get("trashed")?.as_i64())
+        .is_some_and(|trashed| trashed != 0)
+}
+
+/// Splits Bear Markdown into text, `[[wiki links]]` and links to the
+/// bundle's assets, which become attachments.
+fn bear_segments(
+    markdown: &str,
+    bundle: &Bundle,
+    attachments: &mut Vec<SourceAttachment>,
+) -> Vec<Segment> {
+    let mut out = SegmentWriter::default();
+    let mut in_fence = false;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if in_fence || trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            out.push_str(line);
+            continue;
+        }
+
+        let mut rest = line;
+        while let Some(start) = rest.find('[') {
+            out.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let image = out.tail().ends_with('!');
+            match wiki_link(rest).or_else(|| asset_link(rest, image, bundle, attachments)) {
+                Some((len, segment)) => {
+                    if matches!(segment, Segment::Attachment { embed: true, .. }) {
+                        let without_bang = out.tail().len() - 1;
+                        out.truncate_tail(without_bang);
+                    }
+                    out.push(segment);
+                    rest = &rest[len..];
+                }
+                None => {
+                    out.push_str("[");
+                    rest = &rest[1..];
+                }
+            }
+        }
+        out.push_str(rest);
+    }
+    out.finish()
+}
+
+fn wiki_link(text: &str) -> Option<(usize, Segment)> {
+    let inner = text.strip_prefix("[[")?;
+    let end = inner.find("]]")?;
+    let target = &inner[..end];
+    if target.trim().is_empty() || target.contains('\n') {
+        return None;
+    }
+    let (title, label) = match target.split_once('|') {
+        Some((title, label)) => (title, Some(label.trim().to_string())),
+        None => (target, None),
+    };
+    let (title, heading) = match title.split_once('#') {
+        Some((title, heading)) => (title, Some(heading.trim().to_string())),
+        None => (title, None),
+    };
+    Some((
+        end + 4,
+        Segment::NoteLink {
+            title: title.trim().to_string(),
+            heading,
+            label,
+        },
+    ))
+}
+
+/// `[label](assets/file)` at the start of `text`, when the bundle has the
+/// file. `image` says whether a `!` came before.
+fn asset_link(
+    text: &str,
+    image: bool,
+    bundle: &Bundle,
+    attachments: &mut Vec<SourceAttachment>,
+) -> Option<(usize, Segment)> {
+    let label_end = text.find("](")?;
+    if text[1..label_end].contains(['\n', '[', ']']) {
+        return None;
+    }
+    let url_start = label_end + 2;
+    let url_end = url_start + text[url_start..].find(')')?;
+    let url = text[url_start..url_end]
+        .trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>');
+    let asset_path = percent_decode(url);
+    if !asset_path.starts_with(ASSETS_PREFIX) {
+        return None;
+    }
+    let bytes = bundle.files.get(&asset_path)?;
+    let file_name = asset_path.rsplit('/').next().unwrap_or(&asset_path);
+
+    attachments.push(SourceAttachment {
+        file_name: file_name.to_string(),
+        bytes: bytes.clone(),
+    });
+    Some((
+        url_end + 1,
+        Segment::Attachment {
+            index: attachments.len() - 1,
+            embed: image,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use zip::write::SimpleFileOptions;
+
+    use super::read_bear;
+    use crate::source::{Segment, SourceAttachment};
+
+    #[test]
+    fn reads_bundles_from_a_bear_backup() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-note-import-bear-{nanos}"));
+        fs::create_dir_all(&root).unwrap();
+        let backup = root.join("Backup.bear2bk");
+
+        let mut zip = zip::ZipWriter::new(fs::File::create(&backup).unwrap());
+        let mut add = |path: &str, bytes: &[u8]| {
+            zip.start_file(path, SimpleFileOptions::default()).unwrap();
+            zip.write_all(bytes).unwrap();
+        };
+        add(
+            "Trip.textbundle/text.markdown",
+            b"# Trip\n#travel plans#\nSee [[Packing]] and ![](assets/map%201.png)\n",
+        );
+        add("Trip.textbundle/assets/map 1.png", b"png");
+        add(
+            "Old.textbundle/info.json",
+            br#"{"net.shinyfrog.bear": {"trashed": 1}}"#,
+        );
+        add("Old.textbundle/text.markdown", b"Gone");
+        zip.finish().unwrap();
+
+        let (notes, skipped) = read_bear(&backup).unwrap();
+
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].origin, "Old.textbundle");
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].title, "Trip");
+        assert_eq!(
+            notes[0].body,
+            vec![
+                Segment::Markdown("# Trip\n#travel-plans\nSee ".to_string()),
+                Segment::NoteLink {
+                    title: "Packing".to_string(),
+                    heading: None,
+                    label: None,
+                },
+                Segment::Markdown(" and ".to_string()),
+                Segment::Attachment {
+                    index: 0,
+                    embed: true,
+                },
+                Segment::Markdown("\n".to_string()),
+            ]
+        );
+        assert_eq!(
+            notes[0].attachments,
+            vec![SourceAttachment {
+                file_name: "map 1.png".to_string(),
+                bytes: b"png".to_vec(),
+            }]
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
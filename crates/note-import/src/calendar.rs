@@ -0,0 +1,875 @@
+//! Meeting notes from iCalendar (`.ics`) files and calendar subscription
+//! URLs. Every event in the requested range gets a note with its time,
+//! place and attendees in frontmatter and an agenda skeleton in the body.
+//! Notes are recognised by the event's UID, so importing the calendar again
+//! updates their frontmatter and never touches what was written below it.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use note::{
+    parse_frontmatter, remove_frontmatter_property, sanitize_note_title, set_frontmatter_property,
+    FrontmatterValue,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use walkdir::WalkDir;
+
+use crate::plan::{vault_folder, UNTITLED};
+
+/// Frontmatter property holding the event's UID. Occurrences of a recurring
+/// event add their date, as in `uid/2024-05-07`.
+const UID_PROPERTY: &str = "uid";
+/// Longest range imported at once, so recurring events stay bounded.
+const MAX_RANGE_DAYS: i64 = 400;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_CALENDAR_BYTES: u64 = 20 * 1024 * 1024;
+const USER_AGENT: &str = concat!("mdit-note-import/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CalendarImportOptions {
+    /// Vault-relative folder for the meeting notes.
+    pub meeting_folder: String,
+    /// First day of events to import, `YYYY-MM-DD`.
+    pub start: String,
+    /// Last day of events to import, `YYYY-MM-DD` and inclusive.
+    pub end: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarImport {
+    pub created: Vec<MeetingNote>,
+    /// Meeting notes whose event changed since the last import.
+    pub updated: Vec<MeetingNote>,
+    /// Meeting notes already up to date.
+    pub unchanged: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingNote {
+    /// Forward-slash path relative to the vault root.
+    pub rel_path: String,
+    pub title: String,
+    /// `YYYY-MM-DD`.
+    pub date: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Event {
+    uid: String,
+    summary: String,
+    start: EventTime,
+    end: Option<EventTime>,
+    /// Used when the event has no end.
+    duration: Option<chrono::Duration>,
+    location: Option<String>,
+    description: Option<String>,
+    attendees: Vec<String>,
+    cancelled: bool,
+    rule: Option<String>,
+    excluded: Vec<NaiveDate>,
+    /// Set on the edited occurrence of a recurring event.
+    recurrence_id: Option<NaiveDate>,
+}
+
+/// Times are local wall-clock times: UTC times are converted, times with a
+/// `TZID` are kept as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EventTime {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl EventTime {
+    fn date(self) -> NaiveDate {
+        match self {
+            Self::Date(date) => date,
+            Self::DateTime(date_time) => date_time.date(),
+        }
+    }
+
+    fn time(self) -> Option<NaiveTime> {
+        match self {
+            Self::Date(_) => None,
+            Self::DateTime(date_time) => Some(date_time.time()),
+        }
+    }
+
+    /// The same time of day on `date`.
+    fn on(self, date: NaiveDate) -> Self {
+        match self {
+            Self::Date(_) => Self::Date(date),
+            Self::DateTime(date_time) => Self::DateTime(date.and_time(date_time.time())),
+        }
+    }
+}
+
+/// One meeting a note is written for.
+struct Occurrence<'a> {
+    id: String,
+    event: &'a Event,
+    start: EventTime,
+    end: Option<EventTime>,
+}
+
+/// Downloads the calendar at `url`. `webcal://` subscription links are
+/// fetched over https.
+pub fn fetch_ics(url: &str) -> Result<String, String> {
+    let url = url.trim();
+    let url = match url.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{rest}"),
+        None => url.to_string(),
+    };
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(format!(
+            "Calendar URL must be an http, https or webcal address: {url}"
+        ));
+    }
+
+    let fetch_failed = |error: String| format!("Failed to fetch {url}: {error}");
+    let response = reqwest::blocking::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(USER_AGENT)
+        .build()
+        .and_then(|client| client.get(&url).send())
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| fetch_failed(error.to_string()))?;
+    let mut bytes = Vec::new();
+    response
+        .take(MAX_CALENDAR_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|error| fetch_failed(error.to_string()))?;
+    if bytes.len() as u64 > MAX_CALENDAR_BYTES {
+        return Err(fetch_failed("calendar is too large".to_string()));
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Creates or updates a meeting note for every event of `ics` in the range.
+/// `daily_note` names the daily note of a year, month and day, which each
+/// new meeting note links to.
+pub fn import_calendar(
+    ics: &str,
+    vault_root: &Path,
+    options: &CalendarImportOptions,
+    daily_note: &dyn Fn(u32, u32, u32) -> String,
+) -> Result<CalendarImport, String> {
+    let parse_date = |date: &str| {
+        NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date: {date}"))
+    };
+    let (start, end) = (parse_date(&options.start)?, parse_date(&options.end)?);
+    if end < start {
+        return Err(format!(
+            "Import range ends before it starts: {}..{}",
+            options.start, options.end
+        ));
+    }
+    if (end - start).num_days() >= MAX_RANGE_DAYS {
+        return Err(format!(
+            "Import range is longer than {MAX_RANGE_DAYS} days: {}..{}",
+            options.start, options.end
+        ));
+    }
+
+    let events = parse_events(ics);
+    if events.is_empty() && !ics.contains("BEGIN:VCALENDAR") {
+        return Err("Not an iCalendar file".to_string());
+    }
+    let folder = vault_folder(&options.meeting_folder)?.join("/");
+    let folder_path = vault_root.join(&folder);
+    let mut existing = existing_meeting_notes(&folder_path)?;
+
+    let mut import = CalendarImport::default();
+    for occurrence in occurrences(&events, start, end) {
+        let meeting = MeetingNote {
+            rel_path: String::new(),
+            title: occurrence.title(),
+            date: occurrence.start.date().format("%Y-%m-%d").to_string(),
+        };
+        if let Some(path) = existing.remove(&occurrence.id) {
+            let content = fs::read_to_string(&path)
+                .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+            let updated = occurrence.properties().into_iter().fold(
+                content.clone(),
+                |content, (key, value)| match value {
+                    Some(value) => {
+                        set_frontmatter_property(&content, key, &value).unwrap_or(content)
+                    }
+                    None => remove_frontmatter_property(&content, key).unwrap_or(content),
+                },
+            );
+            if updated == content {
+                import.unchanged += 1;
+                continue;
+            }
+            fs::write(&path, updated)
+                .map_err(|error| format!("Failed to write {}: {error}", path.display()))?;
+            import.updated.push(MeetingNote {
+                rel_path: rel_path(vault_root, &path),
+                ..meeting
+            });
+            continue;
+        }
+
+        fs::create_dir_all(&folder_path)
+            .map_err(|error| format!("Failed to create {}: {error}", folder_path.display()))?;
+        let name = format!("{} {}", meeting.date, meeting.title);
+        let path = note::unique_note_file_name(&folder_path, &name)?.full_path;
+        let date = occurrence.start.date();
+        let content = occurrence.note(&daily_note(date.year() as u32, date.month(), date.day()));
+        fs::write(&path, content).map_err(|error| format!("Failed to write {path}: {error}"))?;
+        import.created.push(MeetingNote {
+            rel_path: rel_path(vault_root, Path::new(&path)),
+            ..meeting
+        });
+    }
+    Ok(import)
+}
+
+impl Occurrence<'_> {
+    fn title(&self) -> String {
+        Some(self.event.summary.trim())
+            .filter(|summary| !sanitize_note_title(summary).is_empty())
+            .unwrap_or(UNTITLED)
+            .to_string()
+    }
+
+    /// Frontmatter the calendar owns; `None` removes a property the event
+    /// no longer has.
+    fn properties(&self) -> Vec<(&'static str, Option<JsonValue>)> {
+        let time = |time: Option<EventTime>| {
+            time.and_then(EventTime::time)
+                .map(|time| json!(time.format("%H:%M").to_string()))
+        };
+        // An end on another day is kept as a date rather than a time.
+        let end = match self.end {
+            Some(end) if end.date() != self.start.date() && self.start.time().is_some() => {
+                Some(json!(end.date().format("%Y-%m-%d").to_string()))
+            }
+            end => time(end),
+        };
+        vec![
+            ("title", Some(json!(self.title()))),
+            (
+                "date",
+                Some(json!(self.start.date().format("%Y-%m-%d").to_string())),
+            ),
+            ("start", time(Some(self.start))),
+            ("end", end),
+            (
+                "location",
+                self.event.location.as_ref().map(|location| json!(location)),
+            ),
+            (
+                "attendees",
+                Some(&self.event.attendees)
+                    .filter(|attendees| !attendees.is_empty())
+                    .map(|attendees| json!(attendees)),
+            ),
+            (UID_PROPERTY, Some(json!(self.id))),
+        ]
+    }
+
+    fn note(&self, daily_note: &str) -> String {
+        let mut body = format!("\n[[{daily_note}]]\n\n## Agenda\n\n");
+        if let Some(description) = &self.event.description {
+            body.push_str(description.trim());
+            body.push_str("\n\n");
+        }
+        body.push_str("## Notes\n\n## Action items\n");
+
+        self.properties()
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value?)))
+            .fold(body, |content, (key, value)| {
+                set_frontmatter_property(&content, key, &value).unwrap_or(content)
+            })
+    }
+}
+
+/// Meeting notes from earlier imports, by the UID in their frontmatter.
+fn existing_meeting_notes(folder: &Path) -> Result<HashMap<String, std::path::PathBuf>, String> {
+    let mut notes = HashMap::new();
+    if !folder.is_dir() {
+        return Ok(notes);
+    }
+    for entry in WalkDir::new(folder).into_iter().filter_map(Result::ok) {
+        let path = entry.path();
+        if !entry.file_type().is_file() || !note::is_note_path(path) {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        let uid = parse_frontmatter(&content)
+            .properties
+            .into_iter()
+            .find(|property| property.key == UID_PROPERTY)
+            .and_then(|property| match property.value {
+                FrontmatterValue::String(uid) => Some(uid),
+                _ => None,
+            });
+        if let Some(uid) = uid {
+            notes.insert(uid, path.to_path_buf());
+        }
+    }
+    Ok(notes)
+}
+
+fn rel_path(vault_root: &Path, path: &Path) -> String {
+    path.strip_prefix(vault_root)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The meetings from `start` to `end`, with recurring events expanded and
+/// their edited occurrences in place of the originals.
+fn occurrences(events: &[Event], start: NaiveDate, end: NaiveDate) -> Vec<Occurrence<'_>> {
+    let overrides = events
+        .iter()
+        .filter_map(|event| Some(((event.uid.as_str(), event.recurrence_id?), event)))
+        .collect::<HashMap<_, _>>();
+
+    let mut occurrences = Vec::new();
+    for event in events {
+        if event.recurrence_id.is_some() {
+            continue;
+        }
+        let Some(rule) = &event.rule else {
+            let date = event.start.date();
+            if !event.cancelled && start <= date && date <= end {
+                occurrences.push(Occurrence {
+                    id: event.uid.clone(),
+                    event,
+                    start: event.start,
+                    end: event.end_time(event.start),
+                });
+            }
+            continue;
+        };
+        for date in recurrence_dates(event.start.date(), rule, end) {
+            if date < start || event.excluded.contains(&date) {
+                continue;
+            }
+            let id = format!("{}/{}", event.uid, date.format("%Y-%m-%d"));
+            let occurrence = match overrides.get(&(event.uid.as_str(), date)) {
+                Some(edited) => Occurrence {
+                    id,
+                    event: edited,
+                    start: edited.start,
+                    end: edited.end_time(edited.start),
+                },
+                None => {
+                    let start = event.start.on(date);
+                    Occurrence {
+                        id,
+                        event,
+                        start,
+                        end: event.end_time(start),
+                    }
+                }
+            };
+            if !occurrence.event.cancelled {
+                occurrences.push(occurrence);
+            }
+        }
+    }
+    occurrences.sort_by_key(|occurrence| match occurrence.start {
+        EventTime::Date(date) => date.and_time(NaiveTime::MIN),
+        EventTime::DateTime(date_time) => date_time,
+    });
+    occurrences
+}
+
+impl Event {
+    /// When the occurrence starting at `start` ends.
+    fn end_time(&self, start: EventTime) -> Option<EventTime> {
+        let length = match (self.start, self.end) {
+            (EventTime::DateTime(from), Some(EventTime::DateTime(to))) => to - from,
+            (EventTime::Date(from), Some(EventTime::Date(to))) => {
+                // All-day ends are exclusive.
+                return Some(EventTime::Date(
+                    start.date() + (to - from) - chrono::Duration::days(1),
+                ))
+                .filter(|end| *end != start);
+            }
+            _ => self.duration?,
+        };
+        match start {
+            EventTime::DateTime(start) => Some(EventTime::DateTime(start + length)),
+            EventTime::Date(_) => None,
+        }
+    }
+}
+
+/// Dates of a recurring event up to `until`, for the `FREQ`, `INTERVAL`,
+/// `COUNT`, `UNTIL` and weekly `BYDAY` parts of its rule. Other rules yield
+/// the first date only.
+fn recurrence_dates(first: NaiveDate, rule: &str, until: NaiveDate) -> Vec<NaiveDate> {
+    let parts = rule
+        .split(';')
+        .filter_map(|part| part.split_once('='))
+        .collect::<HashMap<_, _>>();
+    let interval = parts
+        .get("INTERVAL")
+        .and_then(|interval| interval.parse::<u32>().ok())
+        .filter(|interval| *interval > 0)
+        .unwrap_or(1);
+    let count = parts
+        .get("COUNT")
+        .and_then(|count| count.parse::<usize>().ok());
+    let until = parts
+        .get("UNTIL")
+        .and_then(|value| parse_time(value, None))
+        .map_or(until, |last| last.date().min(until));
+    let weekdays = parts
+        .get("BYDAY")
+        .map(|days| days.split(',').filter_map(weekday).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let mut dates = Vec::new();
+    let push = |date: NaiveDate, dates: &mut Vec<NaiveDate>| {
+        if count.is_some_and(|count| dates.len() >= count) || date > until {
+            return false;
+        }
+        dates.push(date);
+        true
+    };
+    match parts.get("FREQ").copied() {
+        Some("DAILY") => {
+            let mut date = first;
+            while push(date, &mut dates) {
+                date += chrono::Duration::days(i64::from(interval));
+            }
+        }
+        Some("WEEKLY") if !weekdays.is_empty() => {
+            let mut week =
+                first - chrono::Duration::days(i64::from(first.weekday().num_days_from_monday()));
+            'weeks: while week <= until {
+                let days = (0..7)
+                    .map(|offset| week + chrono::Duration::days(offset))
+                    .filter(|day| *day >= first && weekdays.contains(&day.weekday()));
+                for day in days {
+                    if !push(day, &mut dates) {
+                        break 'weeks;
+                    }
+                }
+                week += chrono::Duration::weeks(i64::from(interval));
+            }
+        }
+        Some("WEEKLY") => {
+            let mut date = first;
+            while push(date, &mut dates) {
+                date += chrono::Duration::weeks(i64::from(interval));
+            }
+        }
+        Some(freq @ ("MONTHLY" | "YEARLY")) => {
+            let step = if freq == "MONTHLY" {
+                interval
+            } else {
+                interval * 12
+            };
+            let mut months = 0;
+            while let Some(date) = first.checked_add_months(chrono::Months::new(months)) {
+                // Months without the day are skipped, as the rule says.
+                if date.day() == first.day() && !push(date, &mut dates) {
+                    break;
+                }
+                if date > until {
+                    break;
+                }
+                months += step;
+            }
+        }
+        _ => {
+            push(first, &mut dates);
+        }
+    }
+    dates
+}
+
+/// A `BYDAY` entry such as `MO`. Numbered entries like `2TU` are ignored.
+fn weekday(day: &str) -> Option<chrono::Weekday> {
+    use chrono::Weekday::*;
+    Some(match day.trim() {
+        "MO" => Mon,
+        "TU" => Tue,
+        "WE" => Wed,
+        "TH" => Thu,
+        "FR" => Fri,
+        "SA" => Sat,
+        "SU" => Sun,
+        _ => return None,
+    })
+}
+
+fn parse_events(ics: &str) -> Vec<Event> {
+    let mut events = Vec::new();
+    let mut current: Option<Vec<ContentLine>> = None;
+    for line in unfold(ics) {
+        let Some(line) = ContentLine::parse(&line) else {
+            continue;
+        };
+        match (line.name.as_str(), line.value.as_str()) {
+            ("BEGIN", "VEVENT") => current = Some(Vec::new()),
+            ("END", "VEVENT") => {
+                if let Some(event) = current.take().and_then(|lines| build_event(&lines)) {
+                    events.push(event);
+                }
+            }
+            _ => {
+                if let Some(lines) = current.as_mut() {
+                    lines.push(line);
+                }
+            }
+        }
+    }
+    events
+}
+
+fn build_event(lines: &[ContentLine]) -> Option<Event> {
+    let find = |name: &str| lines.iter().find(|line| line.name == name);
+    let text = |name: &str| {
+        find(name)
+            .map(|line| unescape(&line.value))
+            .filter(|value| !value.trim().is_empty())
+    };
+    let time = |line: &ContentLine| parse_time(&line.value, line.param("VALUE"));
+    let start = time(find("DTSTART")?)?;
+
+    Some(Event {
+        uid: text("UID").unwrap_or_else(|| {
+            format!(
+                "{}@{}",
+                text("SUMMARY").unwrap_or_default(),
+                find("DTSTART")
+                    .map(|line| line.value.as_str())
+                    .unwrap_or_default()
+            )
+        }),
+        summary: text("SUMMARY").unwrap_or_default(),
+        start,
+        end: find("DTEND").and_then(time),
+        duration: find("DURATION").and_then(|line| parse_duration(&line.value)),
+        location: text("LOCATION"),
+        description: text("DESCRIPTION"),
+        attendees: lines
+            .iter()
+            .filter(|line| line.name == "ATTENDEE")
+            .filter_map(|line| {
+                let address = line.value.trim();
+                let address = address
+                    .strip_prefix("mailto:")
+                    .or_else(|| address.strip_prefix("MAILTO:"))
+                    .unwrap_or(address);
+                Some(line.param("CN").unwrap_or(address).trim().to_string())
+                    .filter(|attendee| !attendee.is_empty())
+            })
+            .collect(),
+        cancelled: text("STATUS").is_some_and(|status| status.eq_ignore_ascii_case("CANCELLED")),
+        rule: find("RRULE").map(|line| line.value.to_ascii_uppercase()),
+        excluded: lines
+            .iter()
+            .filter(|line| line.name == "EXDATE")
+            .flat_map(|line| {
+                line.value
+                    .split(',')
+                    .filter_map(|value| parse_time(value, line.param("VALUE")))
+                    .map(EventTime::date)
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+        recurrence_id: find("RECURRENCE-ID").and_then(time).map(EventTime::date),
+    })
+}
+
+/// `20240507`, `20240507T100000` or `20240507T080000Z`.
+fn parse_time(value: &str, kind: Option<&str>) -> Option<EventTime> {
+    let value = value.trim();
+    if kind == Some("DATE") || value.len() == 8 {
+        return NaiveDate::parse_from_str(value, "%Y%m%d")
+            .ok()
+            .map(EventTime::Date);
+    }
+    let (value, utc) = match value.strip_suffix('Z') {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    let date_time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let date_time = if utc {
+        Utc.from_utc_datetime(&date_time)
+            .with_timezone(&Local)
+            .naive_local()
+    } else {
+        date_time
+    };
+    Some(EventTime::DateTime(date_time))
+}
+
+/// `PT1H30M`, `P1D` or `P2W`.
+fn parse_duration(value: &str) -> Option<chrono::Duration> {
+    let value = value.trim().strip_prefix('P')?;
+    let mut seconds = 0i64;
+    let mut number = String::new();
+    for ch in value.chars() {
+        match ch {
+            '0'..='9' => number.push(ch),
+            'T' => {}
+            unit => {
+                let amount = number.parse::<i64>().ok()?;
+                number.clear();
+                seconds += amount
+                    * match unit {
+                        'W' => 7 * 24 * 3600,
+                        'D' => 24 * 3600,
+                        'H' => 3600,
+                        'M' => 60,
+                        'S' => 1,
+                        _ => return None,
+                    };
+            }
+        }
+    }
+    Some(chrono::Duration::seconds(seconds))
+}
+
+/// A `NAME;PARAM=value:VALUE` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ContentLine {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl ContentLine {
+    fn parse(line: &str) -> Option<Self> {
+        // The value starts at the first colon outside a quoted parameter.
+        let mut quoted = false;
+        let colon = line.char_indices().find_map(|(index, ch)| match ch {
+            '"' => {
+                quoted = !quoted;
+                None
+            }
+            ':' if !quoted => Some(index),
+            _ => None,
+        })?;
+        let mut head = line[..colon].split(';');
+        let name = head.next()?.trim().to_ascii_uppercase();
+        let params = head
+            .filter_map(|param| param.split_once('='))
+            .map(|(key, value)| {
+                (
+                    key.trim().to_ascii_uppercase(),
+                    value.trim_matches('"').to_string(),
+                )
+            })
+            .collect();
+        Some(Self {
+            name,
+            params,
+            value: line[colon + 1..].to_string(),
+        })
+    }
+
+    fn param(&self, key: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Lines with the folded continuations (lines starting with a space or tab)
+/// joined back on.
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.trim_start_matches('\u{feff}').lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape(value: &str) -> String {
+    let mut text = String::new();
+    let mut chars = value.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            text.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => text.push('\n'),
+            Some(escaped) => text.push(escaped),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use chrono::NaiveDate;
+
+    use super::{import_calendar, recurrence_dates, CalendarImportOptions};
+
+    const CALENDAR: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+UID:planning-1\r
+SUMMARY:Sprint planning\r
+DTSTART;TZID=Europe/Berlin:20240507T100000\r
+DTEND;TZID=Europe/Berlin:20240507T110000\r
+LOCATION:Room 4\\, second floor\r
+DESCRIPTION:Review the backlog\\nPick goals\r
+ATTENDEE;CN=\"Kim, Ada\":mailto:ada@example.com\r
+ATTENDEE:mailto:bo@example.com\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:standup\r
+SUMMARY:Standup\r
+DTSTART:20240506T091500\r
+DURATION:PT15M\r
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE;COUNT=4\r
+EXDATE:20240508T091500\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:standup\r
+RECURRENCE-ID:20240513T091500\r
+SUMMARY:Standup (moved)\r
+DTSTART:20240513T093000\r
+DTEND:20240513T094500\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:offsite\r
+SUMMARY:Offsite\r
+DTSTART;VALUE=DATE:20240601\r
+DTEND;VALUE=DATE:20240602\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn creates_meeting_notes_and_updates_them_on_reimport() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let vault = std::env::temp_dir().join(format!("mdit-calendar-{nanos}"));
+        let options = CalendarImportOptions {
+            meeting_folder: "Meetings".to_string(),
+            start: "2024-05-01".to_string(),
+            end: "2024-05-31".to_string(),
+        };
+        let daily_note = |year, month, day| format!("Daily/{day:02}.{month:02}.{year}");
+
+        let import = import_calendar(CALENDAR, &vault, &options, &daily_note).unwrap();
+        assert_eq!(
+            import
+                .created
+                .iter()
+                .map(|note| note.rel_path.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "Meetings/2024-05-06 Standup.md",
+                "Meetings/2024-05-07 Sprint planning.md",
+                "Meetings/2024-05-13 Standup (moved).md",
+                "Meetings/2024-05-15 Standup.md",
+            ]
+        );
+        let planning = vault.join("Meetings/2024-05-07 Sprint planning.md");
+        assert_eq!(
+            fs::read_to_string(&planning).unwrap(),
+            [
+                "---",
+                "title: Sprint planning",
+                "date: 2024-05-07",
+                "start: 10:00",
+                "end: 11:00",
+                "location: Room 4, second floor",
+                "attendees:",
+                "  - Kim, Ada",
+                "  - bo@example.com",
+                "uid: planning-1",
+                "---",
+                "",
+                "[[Daily/07.05.2024]]",
+                "",
+                "## Agenda",
+                "",
+                "Review the backlog",
+                "Pick goals",
+                "",
+                "## Notes",
+                "",
+                "## Action items",
+                "",
+            ]
+            .join("\n")
+        );
+        assert!(
+            fs::read_to_string(vault.join("Meetings/2024-05-06 Standup.md"))
+                .unwrap()
+                .contains("end: 09:30\nuid: standup/2024-05-06\n")
+        );
+
+        let notes = fs::read_to_string(&planning).unwrap() + "Decided to ship.\n";
+        fs::write(&planning, &notes).unwrap();
+        let moved = CALENDAR.replace(
+            "DTEND;TZID=Europe/Berlin:20240507T110000",
+            "DTEND;TZID=Europe/Berlin:20240507T113000",
+        );
+        let import = import_calendar(&moved, &vault, &options, &daily_note).unwrap();
+        assert!(import.created.is_empty());
+        assert_eq!(import.updated.len(), 1);
+        assert_eq!(import.unchanged, 3);
+        assert_eq!(
+            fs::read_to_string(&planning).unwrap(),
+            notes.replace("end: 11:00", "end: 11:30")
+        );
+
+        fs::remove_dir_all(vault).unwrap();
+    }
+
+    #[test]
+    fn expands_recurrence_rules() {
+        let date = |raw: &str| NaiveDate::parse_from_str(raw, "%Y-%m-%d").unwrap();
+        let until = date("2025-12-31");
+        assert_eq!(
+            recurrence_dates(date("2024-01-31"), "FREQ=MONTHLY;COUNT=3", until),
+            vec![date("2024-01-31"), date("2024-03-31"), date("2024-05-31")]
+        );
+        assert_eq!(
+            recurrence_dates(
+                date("2024-05-01"),
+                "FREQ=DAILY;INTERVAL=2;UNTIL=20240506T000000Z",
+                until
+            ),
+            vec![date("2024-05-01"), date("2024-05-03"), date("2024-05-05")]
+        );
+        assert_eq!(
+            recurrence_dates(
+                date("2024-05-01"),
+                "FREQ=WEEKLY;BYDAY=FR,MO",
+                date("2024-05-10")
+            ),
+            vec![date("2024-05-03"), date("2024-05-06"), date("2024-05-10")]
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Web pages clipped from a browser. Only the main content of a page is kept,
+//! the way reader modes do it, unless the user clipped a selection.
+
+use url::Url;
+
+use crate::html::{html_title, is_web_url, tokens_to_segments, HtmlReference, Token, Tokenizer};
+use crate::source::Segment;
+
+/// Elements that hold a page's main content when it marks it up.
+const CONTENT_ELEMENTS: [&str; 2] = ["article", "main"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebClip {
+    /// The page's `<title>`, if any.
+    pub title: Option<String>,
+    pub markdown: String,
+}
+
+/// Converts a web page, or the `selection_html` made on it, to Markdown.
+/// Links and images resolve against `page_url`. `embed_image` gets the
+/// absolute URL and alt text of each image and returns the Markdown to put
+/// in its place; `None` keeps a link to the remote image.
+pub fn clip_html(
+    html: &str,
+    selection_html: Option<&str>,
+    page_url: Option<&str>,
+    embed_image: &mut dyn FnMut(&str, &str) -> Option<String>,
+) -> WebClip {
+    let markdown = match selection_html {
+        Some(selection) => convert(Tokenizer::new(selection), false, page_url, embed_image),
+        None => convert(main_content(html), true, page_url, embed_image),
+    };
+    WebClip {
+        title: html_title(html),
+        markdown,
+    }
+}
+
+/// Converts an HTML fragment, such as a feed item's content, to Markdown as
+/// a whole. Images stay links to the remote files.
+pub fn html_fragment_to_markdown(html: &str, base_url: Option<&str>) -> String {
+    convert(Tokenizer::new(html), false, base_url, &mut |_, _| None)
+}
+
+fn convert<'a>(
+    tokens: impl IntoIterator<Item = Token<'a>>,
+    skip_page_chrome: bool,
+    base_url: Option<&str>,
+    embed_image: &mut dyn FnMut(&str, &str) -> Option<String>,
+) -> String {
+    let base = base_url.and_then(|url| Url::parse(url).ok());
+    let absolute = |reference: &str| {
+        let url = match &base {
+            Some(base) => base.join(reference.trim()),
+            None => Url::parse(reference.trim()),
+        };
+        url.ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https" | "mailto" | "data"))
+            .map(String::from)
+    };
+
+    let mut resolve = |reference: HtmlReference<'_>| match reference {
+        HtmlReference::Image { src, alt } => {
+            let src = absolute(src)?;
+            if let Some(markdown) = embed_image(&src, alt) {
+                return Some(Segment::Markdown(markdown));
+            }
+            is_web_url(&src).then(|| Segment::Markdown(format!("![{alt}]({src})")))
+        }
+        HtmlReference::Link { href, label } => {
+            if href.starts_with('#') {
+                return None;
+            }
+            let href_url = absolute(href)?;
+            if href_url == href || href_url.starts_with("data:") {
+                // Absolute already; the converter writes it.
+                return None;
+            }
+            Some(Segment::Markdown(
+                if label.is_empty() || label == href_url {
+                    format!("<{href_url}>")
+                } else {
+                    format!("[{label}]({})", href_url.replace(' ', "%20"))
+                },
+            ))
+        }
+        HtmlReference::Media { .. } => None,
+    };
+
+    tokens_to_segments(tokens, &mut resolve, skip_page_chrome)
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Markdown(markdown) => Some(markdown),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The tokens of the `<article>` or `<main>` with the most text, else of the
+/// body, else the whole page.
+fn main_content(html: &str) -> Vec<Token<'_>> {
+    let tokens = Tokenizer::new(html).collect::<Vec<_>>();
+
+    let mut best: Option<(usize, usize, usize)> = None;
+    for (start, token) in tokens.iter().enumerate() {
+        let Token::Start {
+            name,
+            attributes,
+            self_closing: false,
+        } = token
+        else {
+            continue;
+        };
+        let is_content = CONTENT_ELEMENTS.contains(&name.as_str())
+            || attributes.get("role").is_some_and(|role| role == "main");
+        if !is_content {
+            continue;
+        }
+        let (end, text_len) = element_extent(&tokens, start, name);
+        if text_len > best.map_or(0, |(_, _, best_len)| best_len) {
+            best = Some((start, end, text_len));
+        }
+    }
+
+    let (start, end) = match best {
+        Some((start, end, _)) => (start, end),
+        None => {
+            let body = tokens
+                .iter()
+                .position(|token| matches!(token, Token::Start { name, .. } if name == "body"));
+            (body.unwrap_or(0), tokens.len())
+        }
+    };
+    tokens.into_iter().take(end).skip(start).collect::<Vec<_>>()
+}
+
+/// The index just past the element opened at `tokens[start]`, and the
+/// length of its text.
+fn element_extent(tokens: &[Token<'_>], start: usize, name: &str) -> (usize, usize) {
+    let mut depth = 0;
+    let mut text_len = 0;
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Start {
+                name: open,
+                self_closing: false,
+                ..
+            } if open == name => depth += 1,
+            Token::End(close) if close == name => {
+                depth -= 1;
+                if depth == 0 {
+                    return (index + 1, text_len);
+                }
+            }
+            Token::Text(text) => text_len += text.trim().len(),
+            _ => {}
+        }
+    }
+    (tokens.len(), text_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::clip_html;
+
+    #[test]
+    fn keeps_the_main_article_and_resolves_links_and_images() {
+        let html = concat!(
+            "<html><head><title>Bread basics</title></head><body>",
+            "<nav><a href=\"/\">Home</a></nav>",
+            "<article><header><h1>Bread basics</h1></header>",
+            "<p>Knead the <a href=\"dough.html\">dough</a> for <b>ten</b> minutes.</p>",
+            "<img src=\"/img/loaf.jpg\" alt=\"loaf\"><img src=\"https://cdn.example/crumb.png\">",
+            "<form><button>Subscribe</button></form></article>",
+            "<aside><article>Related</article></aside>",
+            "<footer>Copyright</footer></body></html>"
+        );
+
+        let mut images = Vec::new();
+        let clip = clip_html(
+            html,
+            None,
+            Some("https://example.com/recipes/bread"),
+            &mut |src, alt| {
+                images.push((src.to_string(), alt.to_string()));
+                (alt == "loaf").then(|| "![[attachments/loaf.jpg]]".to_string())
+            },
+        );
+
+        assert_eq!(clip.title.as_deref(), Some("Bread basics"));
+        assert_eq!(
+            clip.markdown,
+            [
+                "# Bread basics",
+                "",
+                "Knead the [dough](https://example.com/recipes/dough.html) for **ten** minutes.",
+                "",
+                "![[attachments/loaf.jpg]]![](https://cdn.example/crumb.png)",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            images,
+            vec![
+                (
+                    "https://example.com/img/loaf.jpg".to_string(),
+                    "loaf".to_string()
+                ),
+                ("https://cdn.example/crumb.png".to_string(), String::new()),
+            ]
+        );
+
+        let selection = clip_html(html, Some("<p>Only <i>this</i></p>"), None, &mut |_, _| {
+            None
+        });
+        assert_eq!(selection.markdown, "Only *this*");
+    }
+}
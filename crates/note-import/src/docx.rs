@@ -0,0 +1,816 @@
+//! Word documents, OneNote pages exported as `.docx` included. Headings,
+//! lists, tables, emphasis and links are kept, and images become
+//! attachments. Subfolders of an export folder become vault folders.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Seek};
+use std::mem;
+use std::path::Path;
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+use crate::source::{
+    folders_between, has_extension, Segment, SegmentWriter, SourceAttachment, SourceNote,
+    SourceSkip,
+};
+
+const IMAGE_RELATIONSHIP: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/image";
+
+pub(crate) fn read_docx(input: &Path) -> Result<(Vec<SourceNote>, Vec<SourceSkip>), String> {
+    if !input.is_dir() {
+        if !has_extension(input, &["docx"]) {
+            return Err(format!("Expected a .docx document: {}", input.display()));
+        }
+        return Ok((vec![read_document(input, Vec::new())?], Vec::new()));
+    }
+
+    let mut notes = Vec::new();
+    let mut skipped = Vec::new();
+    for entry in WalkDir::new(input)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        // Word keeps `~$name.docx` lock files beside open documents.
+        let lock_file = entry.file_name().to_string_lossy().starts_with("~$");
+        if !entry.file_type().is_file() || lock_file || !has_extension(path, &["docx"]) {
+            continue;
+        }
+        match read_document(path, folders_between(input, path)) {
+            Ok(note) => notes.push(note),
+            Err(reason) => skipped.push(SourceSkip {
+                origin: path.display().to_string(),
+                reason,
+            }),
+        }
+    }
+    Ok((notes, skipped))
+}
+
+fn read_document(path: &Path, folders: Vec<String>) -> Result<SourceNote, String> {
+    let invalid = |error: String| format!("Invalid .docx file {}: {error}", path.display());
+    let file = fs::File::open(path)
+        .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+    let mut archive = ZipArchive::new(file).map_err(|error| invalid(error.to_string()))?;
+    let document = read_part(&mut archive, "word/document.xml")
+        .map_err(&invalid)?
+        .ok_or_else(|| invalid("word/document.xml is missing".to_string()))?;
+    let title = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut links = HashMap::new();
+    let mut images = HashMap::new();
+    let mut attachments = Vec::new();
+    if let Some(xml) = read_part(&mut archive, "word/_rels/document.xml.rels").map_err(&invalid)? {
+        for relationship in parse_relationships(&xml).map_err(&invalid)? {
+            if relationship.external {
+                links.insert(relationship.id, relationship.target);
+            } else if relationship.kind == IMAGE_RELATIONSHIP {
+                let part = part_name(&relationship.target);
+                let Some(bytes) = read_bytes(&mut archive, &part).map_err(&invalid)? else {
+                    continue;
+                };
+                let name = part.rsplit('/').next().unwrap_or(&part);
+                images.insert(relationship.id, attachments.len());
+                attachments.push(SourceAttachment {
+                    file_name: format!("{title} {name}"),
+                    bytes,
+                });
+            }
+        }
+    }
+    let headings = match read_part(&mut archive, "word/styles.xml").map_err(&invalid)? {
+        Some(xml) => parse_heading_styles(&xml).map_err(&invalid)?,
+        None => HashMap::new(),
+    };
+    let numbering = match read_part(&mut archive, "word/numbering.xml").map_err(&invalid)? {
+        Some(xml) => parse_numbering(&xml).map_err(&invalid)?,
+        None => Numbering::default(),
+    };
+
+    let mut converter = Converter::new(&headings, &numbering, &links, &images);
+    converter.convert(&document).map_err(&invalid)?;
+
+    Ok(SourceNote {
+        origin: path.display().to_string(),
+        title,
+        folders,
+        tags: Vec::new(),
+        body: converter.body.finish(),
+        attachments,
+    })
+}
+
+fn read_bytes<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, String> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(error) => return Err(error.to_string()),
+    };
+    let mut bytes = Vec::new();
+    entry
+        .read_to_end(&mut bytes)
+        .map_err(|error| format!("{name}: {error}"))?;
+    Ok(Some(bytes))
+}
+
+fn read_part<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<Option<String>, String> {
+    Ok(read_bytes(archive, name)?.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+/// The zip entry a relationship target of `word/document.xml` points at.
+fn part_name(target: &str) -> String {
+    match target.strip_prefix('/') {
+        Some(absolute) => absolute.to_string(),
+        None => format!("word/{}", target.trim_start_matches("./")),
+    }
+}
+
+struct Relationship {
+    id: String,
+    kind: String,
+    target: String,
+    external: bool,
+}
+
+fn parse_relationships(xml: &str) -> Result<Vec<Relationship>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut relationships = Vec::new();
+    loop {
+        match reader.read_event().map_err(|error| error.to_string())? {
+            Event::Start(element) | Event::Empty(element)
+                if element.local_name().as_ref() == b"Relationship" =>
+            {
+                let (Some(id), Some(target)) =
+                    (attribute(&element, b"Id"), attribute(&element, b"Target"))
+                else {
+                    continue;
+                };
+                relationships.push(Relationship {
+                    id,
+                    kind: attribute(&element, b"Type").unwrap_or_default(),
+                    target,
+                    external: attribute(&element, b"TargetMode").as_deref() == Some("External"),
+                });
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(relationships)
+}
+
+/// Heading levels by paragraph style id. Style ids are localized, so the
+/// level comes from the style's built-in name or its outline level.
+fn parse_heading_styles(xml: &str) -> Result<HashMap<String, usize>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut headings = HashMap::new();
+    let mut style: Option<(String, Option<usize>)> = None;
+    loop {
+        match reader.read_event().map_err(|error| error.to_string())? {
+            Event::Start(element) | Event::Empty(element) => match element.name().as_ref() {
+                b"w:style" => style = attribute(&element, b"w:styleId").map(|id| (id, None)),
+                b"w:name" => {
+                    if let (Some((_, level)), Some(name)) =
+                        (style.as_mut(), attribute(&element, b"w:val"))
+                    {
+                        *level = level.or(heading_level(&name));
+                    }
+                }
+                b"w:outlineLvl" => {
+                    let outline = attribute(&element, b"w:val").and_then(|val| val.parse().ok());
+                    if let (Some((_, level)), Some(outline)) = (style.as_mut(), outline) {
+                        *level = level.or(outline_level(outline));
+                    }
+                }
+                _ => {}
+            },
+            Event::End(element) if element.name().as_ref() == b"w:style" => {
+                if let Some((id, Some(level))) = style.take() {
+                    headings.insert(id, level);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(headings)
+}
+
+/// The level of a style named like `heading 2`, `Heading2` or `Title`.
+fn heading_level(name: &str) -> Option<usize> {
+    let name = name.replace(' ', "").to_lowercase();
+    if name == "title" {
+        return Some(1);
+    }
+    let level = name.strip_prefix("heading")?.parse::<usize>().ok()?;
+    (level > 0).then(|| level.min(6))
+}
+
+/// Word outline levels count from 0, and 9 is body text.
+fn outline_level(outline: usize) -> Option<usize> {
+    (outline < 9).then(|| (outline + 1).min(6))
+}
+
+/// Which list levels are numbered rather than bulleted.
+#[derive(Default)]
+struct Numbering {
+    abstract_by_num: HashMap<String, String>,
+    ordered: HashMap<(String, String), bool>,
+}
+
+impl Numbering {
+    fn is_ordered(&self, num_id: &str, level: &str) -> bool {
+        self.abstract_by_num
+            .get(num_id)
+            .and_then(|abstract_id| self.ordered.get(&(abstract_id.clone(), level.to_string())))
+            .copied()
+            .unwrap_or(false)
+    }
+}
+
+fn parse_numbering(xml: &str) -> Result<Numbering, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut numbering = Numbering::default();
+    let mut abstract_id = None;
+    let mut level = None;
+    let mut num_id = None;
+    loop {
+        match reader.read_event().map_err(|error| error.to_string())? {
+            Event::Start(element) | Event::Empty(element) => match element.name().as_ref() {
+                b"w:abstractNum" => abstract_id = attribute(&element, b"w:abstractNumId"),
+                b"w:lvl" => level = attribute(&element, b"w:ilvl"),
+                b"w:numFmt" => {
+                    if let (Some(abstract_id), Some(level), Some(format)) = (
+                        abstract_id.clone(),
+                        level.clone(),
+                        attribute(&element, b"w:val"),
+                    ) {
+                        let ordered = format != "bullet" && format != "none";
+                        numbering.ordered.insert((abstract_id, level), ordered);
+                    }
+                }
+                b"w:num" => num_id = attribute(&element, b"w:numId"),
+                b"w:abstractNumId" => {
+                    if let (Some(num_id), Some(abstract_id)) =
+                        (num_id.clone(), attribute(&element, b"w:val"))
+                    {
+                        numbering.abstract_by_num.insert(num_id, abstract_id);
+                    }
+                }
+                _ => {}
+            },
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(numbering)
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key.as_ref() == name)
+        .and_then(|attribute| attribute.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
+
+/// Whether a `w:b`-like toggle is on; it is when it has no value.
+fn toggle(element: &BytesStart) -> bool {
+    !matches!(
+        attribute(element, b"w:val").as_deref(),
+        Some("0" | "false" | "off" | "none")
+    )
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Format {
+    bold: bool,
+    italic: bool,
+    strike: bool,
+}
+
+impl Format {
+    fn markers(self) -> Vec<&'static str> {
+        [(self.strike, "~~"), (self.bold, "**"), (self.italic, "*")]
+            .into_iter()
+            .filter_map(|(on, marker)| on.then_some(marker))
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct Paragraph {
+    heading: Option<usize>,
+    list_id: Option<String>,
+    list_level: Option<String>,
+    content: SegmentWriter,
+    /// Emphasis markers written and not yet closed.
+    open: Format,
+    link: Option<String>,
+}
+
+impl Paragraph {
+    fn push_text(&mut self, text: &str, format: Format) {
+        // Markers around whitespace alone would not parse as emphasis.
+        if format != self.open && !text.trim().is_empty() {
+            self.close_format();
+            let rest = text.trim_start();
+            self.content.push_str(&text[..text.len() - rest.len()]);
+            for marker in format.markers() {
+                self.content.push_str(marker);
+            }
+            self.open = format;
+            self.content.push_str(&escape(rest));
+        } else {
+            self.content.push_str(&escape(text));
+        }
+    }
+
+    /// Closes open emphasis, keeping trailing spaces outside the markers.
+    fn close_format(&mut self) {
+        if self.open == Format::default() {
+            return;
+        }
+        let tail = self.content.tail();
+        let kept = tail.trim_end().len();
+        let trailing = tail[kept..].to_string();
+        self.content.truncate_tail(kept);
+        for marker in self.open.markers().into_iter().rev() {
+            self.content.push_str(marker);
+        }
+        self.content.push_str(&trailing);
+        self.open = Format::default();
+    }
+
+    fn embed(&mut self, segment: Segment) {
+        self.close_format();
+        self.content.push(segment);
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '*' | '`' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+#[derive(Default)]
+struct Table {
+    rows: Vec<Vec<Vec<Segment>>>,
+    row: Vec<Vec<Segment>>,
+    cell: SegmentWriter,
+}
+
+struct Converter<'a> {
+    headings: &'a HashMap<String, usize>,
+    numbering: &'a Numbering,
+    links: &'a HashMap<String, String>,
+    images: &'a HashMap<String, usize>,
+    body: SegmentWriter,
+    last_was_list: bool,
+    paragraph: Paragraph,
+    /// Paragraphs open, counting those in text boxes inside a paragraph.
+    paragraph_depth: usize,
+    /// The outermost table; nested tables are flattened into its cells.
+    table: Option<Table>,
+    table_depth: usize,
+    run: Option<Format>,
+    in_text: bool,
+    /// Depth inside `mc:Fallback`, which repeats the content before it.
+    fallback_depth: usize,
+}
+
+impl<'a> Converter<'a> {
+    fn new(
+        headings: &'a HashMap<String, usize>,
+        numbering: &'a Numbering,
+        links: &'a HashMap<String, String>,
+        images: &'a HashMap<String, usize>,
+    ) -> Self {
+        Self {
+            headings,
+            numbering,
+            links,
+            images,
+            body: SegmentWriter::default(),
+            last_was_list: false,
+            paragraph: Paragraph::default(),
+            paragraph_depth: 0,
+            table: None,
+            table_depth: 0,
+            run: None,
+            in_text: false,
+            fallback_depth: 0,
+        }
+    }
+
+    fn convert(&mut self, xml: &str) -> Result<(), String> {
+        let mut reader = Reader::from_str(xml);
+        loop {
+            match reader.read_event().map_err(|error| error.to_string())? {
+                Event::Start(element) => self.start(&element),
+                Event::Empty(element) => {
+                    self.start(&element);
+                    self.end(element.name().as_ref());
+                }
+                Event::End(element) => self.end(element.name().as_ref()),
+                Event::Text(text) if self.in_text && self.fallback_depth == 0 => {
+                    let text = text.decode().map_err(|error| error.to_string())?;
+                    self.text(&text);
+                }
+                Event::GeneralRef(reference) if self.in_text && self.fallback_depth == 0 => {
+                    let name = reference.decode().map_err(|error| error.to_string())?;
+                    let text = match reference.resolve_char_ref() {
+                        Ok(Some(ch)) => ch.to_string(),
+                        _ => quick_xml::escape::resolve_predefined_entity(&name)
+                            .unwrap_or_default()
+                            .to_string(),
+                    };
+                    self.text(&text);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn start(&mut self, element: &BytesStart) {
+        let name = element.name();
+        if name.as_ref() == b"mc:Fallback" {
+            self.fallback_depth += 1;
+        }
+        if self.fallback_depth > 0 {
+            return;
+        }
+
+        match name.as_ref() {
+            b"w:p" => {
+                self.paragraph_depth += 1;
+                if self.paragraph_depth == 1 {
+                    self.paragraph = Paragraph::default();
+                }
+            }
+            b"w:pStyle" if self.paragraph_depth == 1 => {
+                if let Some(style) = attribute(element, b"w:val") {
+                    self.paragraph.heading = self
+                        .headings
+                        .get(&style)
+                        .copied()
+                        .or_else(|| heading_level(&style));
+                }
+            }
+            b"w:outlineLvl" if self.paragraph_depth == 1 => {
+                if let Some(level) = attribute(element, b"w:val")
+                    .and_then(|val| val.parse().ok())
+                    .and_then(outline_level)
+                {
+                    self.paragraph.heading = Some(level);
+                }
+            }
+            b"w:numId" if self.paragraph_depth == 1 => {
+                // Id 0 removes the numbering a style would add.
+                self.paragraph.list_id = attribute(element, b"w:val").filter(|id| id != "0");
+            }
+            b"w:ilvl" if self.paragraph_depth == 1 => {
+                self.paragraph.list_level = attribute(element, b"w:val");
+            }
+            b"w:r" => self.run = Some(Format::default()),
+            b"w:b" => self.set_format(|format| &mut format.bold, element),
+            b"w:i" => self.set_format(|format| &mut format.italic, element),
+            b"w:strike" | b"w:dstrike" => self.set_format(|format| &mut format.strike, element),
+            b"w:t" => self.in_text = self.paragraph_depth > 0,
+            b"w:tab" if self.run.is_some() => self.text(" "),
+            // Page and column breaks have no Markdown form.
+            b"w:br" | b"w:cr"
+                if self.run.is_some()
+                    && attribute(element, b"w:type").is_none_or(|kind| kind == "textWrapping") =>
+            {
+                self.paragraph.content.push_str("\n");
+            }
+            b"w:hyperlink" if self.paragraph_depth == 1 => {
+                let url = attribute(element, b"r:id")
+                    .and_then(|id| self.links.get(&id))
+                    .cloned();
+                if let Some(url) = url {
+                    self.paragraph.close_format();
+                    self.paragraph.content.push_str("[");
+                    self.paragraph.link = Some(url);
+                }
+            }
+            b"a:blip" | b"v:imagedata" if self.paragraph_depth > 0 => {
+                let id = attribute(element, b"r:embed").or_else(|| attribute(element, b"r:id"));
+                if let Some(&index) = id.and_then(|id| self.images.get(&id)) {
+                    self.paragraph
+                        .embed(Segment::Attachment { index, embed: true });
+                }
+            }
+            b"w:tbl" => {
+                self.table_depth += 1;
+                if self.table_depth == 1 {
+                    self.table = Some(Table::default());
+                }
+            }
+            b"w:tr" if self.table_depth == 1 => {
+                if let Some(table) = self.table.as_mut() {
+                    table.row = Vec::new();
+                }
+            }
+            b"w:tc" if self.table_depth == 1 => {
+                if let Some(table) = self.table.as_mut() {
+                    table.cell = SegmentWriter::default();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &[u8]) {
+        if self.fallback_depth > 0 {
+            if name == b"mc:Fallback" {
+                self.fallback_depth -= 1;
+            }
+            return;
+        }
+
+        match name {
+            b"w:p" => {
+                self.paragraph_depth = self.paragraph_depth.saturating_sub(1);
+                if self.paragraph_depth == 0 {
+                    self.finish_paragraph();
+                }
+            }
+            b"w:r" => self.run = None,
+            b"w:t" => self.in_text = false,
+            b"w:hyperlink" if self.paragraph_depth == 1 => {
+                if let Some(url) = self.paragraph.link.take() {
+                    self.paragraph.close_format();
+                    self.paragraph
+                        .content
+                        .push_str(&format!("]({})", url.replace(' ', "%20")));
+                }
+            }
+            b"w:tc" if self.table_depth == 1 => {
+                if let Some(table) = self.table.as_mut() {
+                    let cell = mem::take(&mut table.cell).finish();
+                    table.row.push(cell);
+                }
+            }
+            b"w:tr" if self.table_depth == 1 => {
+                if let Some(table) = self.table.as_mut() {
+                    let row = mem::take(&mut table.row);
+                    table.rows.push(row);
+                }
+            }
+            b"w:tbl" => {
+                self.table_depth = self.table_depth.saturating_sub(1);
+                if self.table_depth == 0 {
+                    if let Some(table) = self.table.take() {
+                        self.finish_table(table);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn set_format(&mut self, field: fn(&mut Format) -> &mut bool, element: &BytesStart) {
+        // Paragraph mark formatting also uses these, outside any run.
+        if let Some(format) = self.run.as_mut() {
+            *field(format) = toggle(element);
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        // Heading styles are usually bold already.
+        let format = match self.paragraph.heading {
+            Some(_) => Format::default(),
+            None => self.run.unwrap_or_default(),
+        };
+        self.paragraph.push_text(text, format);
+    }
+
+    fn finish_paragraph(&mut self) {
+        let mut paragraph = mem::take(&mut self.paragraph);
+        paragraph.close_format();
+        let content = trim_segments(paragraph.content.finish());
+        if content.is_empty() {
+            return;
+        }
+
+        if let Some(table) = self.table.as_mut() {
+            if !table.cell.is_empty() {
+                table.cell.push_str("<br>");
+            }
+            for segment in content {
+                table.cell.push(match segment {
+                    Segment::Markdown(text) => {
+                        Segment::Markdown(text.replace('|', "\\|").replace('\n', "<br>"))
+                    }
+                    segment => segment,
+                });
+            }
+            return;
+        }
+
+        let is_list = paragraph.heading.is_none() && paragraph.list_id.is_some();
+        if !self.body.is_empty() {
+            let separator = if is_list && self.last_was_list {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            self.body.push_str(separator);
+        }
+        if let Some(level) = paragraph.heading {
+            self.body.push_str(&format!("{} ", "#".repeat(level)));
+        } else if let Some(list_id) = paragraph.list_id.as_deref() {
+            let level = paragraph.list_level.as_deref().unwrap_or("0");
+            let indent = "  ".repeat(level.parse::<usize>().unwrap_or(0));
+            let marker = if self.numbering.is_ordered(list_id, level) {
+                "1. "
+            } else {
+                "- "
+            };
+            self.body.push_str(&indent);
+            self.body.push_str(marker);
+        }
+        for segment in content {
+            self.body.push(match segment {
+                Segment::Markdown(text) => Segment::Markdown(text.replace('\n', "\\\n")),
+                segment => segment,
+            });
+        }
+        self.last_was_list = is_list;
+    }
+
+    /// Writes a pipe table with the first row as its header.
+    fn finish_table(&mut self, table: Table) {
+        let columns = table.rows.iter().map(Vec::len).max().unwrap_or(0);
+        if columns == 0 {
+            return;
+        }
+        if !self.body.is_empty() {
+            self.body.push_str("\n\n");
+        }
+        for (index, row) in table.rows.into_iter().enumerate() {
+            if index == 1 {
+                self.body.push_str(&"| --- ".repeat(columns));
+                self.body.push_str("|\n");
+            }
+            let mut cells = row.into_iter();
+            for _ in 0..columns {
+                self.body.push_str("| ");
+                for segment in cells.next().unwrap_or_default() {
+                    self.body.push(segment);
+                }
+                self.body.push_str(" ");
+            }
+            self.body.push_str("|\n");
+        }
+        let kept = self.body.tail().trim_end().len();
+        self.body.truncate_tail(kept);
+        self.last_was_list = false;
+    }
+}
+
+/// `segments` without whitespace at either end.
+fn trim_segments(mut segments: Vec<Segment>) -> Vec<Segment> {
+    if let Some(Segment::Markdown(text)) = segments.first_mut() {
+        *text = text.trim_start().to_string();
+        if text.is_empty() {
+            segments.remove(0);
+        }
+    }
+    if let Some(Segment::Markdown(text)) = segments.last_mut() {
+        *text = text.trim_end().to_string();
+        if text.is_empty() {
+            segments.pop();
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    use super::read_docx;
+    use crate::source::Segment;
+
+    #[test]
+    fn converts_headings_lists_tables_links_and_images() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-note-import-docx-{nanos}"));
+        fs::create_dir_all(root.join("Team")).unwrap();
+
+        let document = concat!(
+            "<w:document><w:body>",
+            "<w:p><w:pPr><w:pStyle w:val=\"Berschrift1\"/></w:pPr><w:r><w:t>Plan</w:t></w:r></w:p>",
+            "<w:p><w:r><w:t xml:space=\"preserve\">Ship </w:t></w:r>",
+            "<w:r><w:rPr><w:b/></w:rPr><w:t xml:space=\"preserve\">v2 </w:t></w:r>",
+            "<w:r><w:t>via </w:t></w:r><w:hyperlink r:id=\"rId2\"><w:r><w:t>the board</w:t></w:r></w:hyperlink>",
+            "<w:r><w:t xml:space=\"preserve\"> &amp; [notes]</w:t></w:r></w:p>",
+            "<w:p><w:pPr><w:numPr><w:ilvl w:val=\"0\"/><w:numId w:val=\"1\"/></w:numPr></w:pPr><w:r><w:t>One</w:t></w:r></w:p>",
+            "<w:p><w:pPr><w:numPr><w:ilvl w:val=\"1\"/><w:numId w:val=\"1\"/></w:numPr></w:pPr><w:r><w:t>Two</w:t></w:r></w:p>",
+            "<w:tbl><w:tr><w:tc><w:p><w:r><w:t>Name</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Owner</w:t></w:r></w:p></w:tc></w:tr>",
+            "<w:tr><w:tc><w:p><w:r><w:t>a|b</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Kim</w:t></w:r></w:p></w:tc></w:tr></w:tbl>",
+            "<w:p><w:r><w:drawing><a:blip r:embed=\"rId3\"/></w:drawing></w:r></w:p>",
+            "<w:p/></w:body></w:document>"
+        );
+        let relationships = concat!(
+            "<Relationships>",
+            "<Relationship Id=\"rId2\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink\" Target=\"https://example.com/board\" TargetMode=\"External\"/>",
+            "<Relationship Id=\"rId3\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/image\" Target=\"media/image1.png\"/>",
+            "</Relationships>"
+        );
+        let styles = "<w:styles><w:style w:styleId=\"Berschrift1\"><w:name w:val=\"heading 1\"/></w:style></w:styles>";
+        let numbering = concat!(
+            "<w:numbering><w:abstractNum w:abstractNumId=\"0\">",
+            "<w:lvl w:ilvl=\"0\"><w:numFmt w:val=\"decimal\"/></w:lvl>",
+            "<w:lvl w:ilvl=\"1\"><w:numFmt w:val=\"bullet\"/></w:lvl>",
+            "</w:abstractNum><w:num w:numId=\"1\"><w:abstractNumId w:val=\"0\"/></w:num></w:numbering>"
+        );
+
+        let mut zip = ZipWriter::new(fs::File::create(root.join("Team/Roadmap.docx")).unwrap());
+        for (name, bytes) in [
+            ("word/document.xml", document.as_bytes()),
+            ("word/_rels/document.xml.rels", relationships.as_bytes()),
+            ("word/styles.xml", styles.as_bytes()),
+            ("word/numbering.xml", numbering.as_bytes()),
+            ("word/media/image1.png", b"png".as_slice()),
+        ] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(bytes).unwrap();
+        }
+        zip.finish().unwrap();
+        fs::write(root.join("Team/~$Roadmap.docx"), b"lock").unwrap();
+
+        let (notes, skipped) = read_docx(&root).unwrap();
+
+        assert!(skipped.is_empty());
+        assert_eq!(notes.len(), 1);
+        let note = &notes[0];
+        assert_eq!(note.title, "Roadmap");
+        assert_eq!(note.folders, vec!["Team".to_string()]);
+        assert_eq!(note.attachments[0].file_name, "Roadmap image1.png");
+        assert_eq!(note.attachments[0].bytes, b"png");
+        assert_eq!(
+            note.body,
+            vec![
+                Segment::Markdown(
+                    [
+                        "# Plan",
+                        "",
+                        "Ship **v2** via [the board](https://example.com/board) & \\[notes\\]",
+                        "",
+                        "1. One",
+                        "  - Two",
+                        "",
+                        "| Name | Owner |",
+                        "| --- | --- |",
+                        "| a\\|b | Kim |",
+                        "",
+                        "",
+                    ]
+                    .join("\n")
+                ),
+                Segment::Attachment {
+                    index: 0,
+                    embed: true
+                },
+            ]
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+/// The mdit form of a tag from another app: words joined by `-`, nesting
+/// kept as `/`, and characters a tag cannot hold dropped.
+pub(crate) fn to_mdit_tag(raw: &str) -> Option<String> {
+    let segments = raw
+        .trim()
+        .trim_matches('#')
+        .split('/')
+        .filter_map(|segment| {
+            let mut converted = String::new();
+            for ch in segment.trim().chars() {
+                if ch.is_alphanumeric() || ch == '_' {
+                    converted.push(ch);
+                } else if (ch == '-' || ch.is_whitespace()) && !converted.ends_with('-') {
+                    converted.push('-');
+                }
+            }
+            let converted = converted.trim_matches('-');
+            (!converted.is_empty()).then(|| converted.to_string())
+        })
+        .collect::<Vec<_>>();
+    (!segments.is_empty()).then(|| segments.join("/"))
+}
+
+/// Rewrites Bear hashtags outside code into mdit tags: `#tag` and
+/// `#nested/tag` as they are, `#multi word tag#` as `#multi-word-tag`.
+pub(crate) fn rewrite_hashtags(markdown: &str) -> String {
+    let mut output = String::with_capacity(markdown.len());
+    let mut in_fence = false;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            output.push_str(line);
+            continue;
+        }
+        if in_fence {
+            output.push_str(line);
+            continue;
+        }
+        rewrite_line(line, &mut output);
+    }
+    output
+}
+
+fn rewrite_line(line: &str, output: &mut String) {
+    let mut in_code = false;
+    let mut index = 0;
+    while index < line.len() {
+        let rest = &line[index..];
+        let ch = rest.chars().next().expect("index is on a char boundary");
+        if ch == '`' {
+            in_code = !in_code;
+        }
+        let at_boundary = line[..index]
+            .chars()
+            .next_back()
+            .is_none_or(char::is_whitespace);
+        if ch == '#' && !in_code && at_boundary {
+            if let Some((raw_len, tag)) = parse_hashtag(rest) {
+                output.push('#');
+                output.push_str(&tag);
+                index += raw_len;
+                continue;
+            }
+        }
+        output.push(ch);
+        index += ch.len_utf8();
+    }
+}
+
+/// The length of the Bear hashtag starting `text`, and its mdit form.
+fn parse_hashtag(text: &str) -> Option<(usize, String)> {
+    let body = &text[1..];
+    let first = body.chars().next()?;
+    if first.is_whitespace() || first == '#' {
+        return None;
+    }
+
+    // `#multi word#` closes on the same line, after no space.
+    let line_end = body.find(['\n', '\r']).unwrap_or(body.len());
+    if let Some(close) = body[..line_end].find('#') {
+        let inner = &body[..close];
+        let after = body[close + 1..].chars().next();
+        if inner.contains(char::is_whitespace)
+            && !inner.ends_with(char::is_whitespace)
+            && after.is_none_or(|ch| ch.is_whitespace() || ch.is_ascii_punctuation())
+        {
+            return to_mdit_tag(inner).map(|tag| (close + 2, tag));
+        }
+    }
+
+    let word_end = body.find(char::is_whitespace).unwrap_or(body.len());
+    let word = body[..word_end].trim_end_matches(|ch: char| ch.is_ascii_punctuation());
+    let tag = to_mdit_tag(word)?;
+    Some((1 + word.len(), tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rewrite_hashtags, to_mdit_tag};
+
+    #[test]
+    fn rewrites_bear_hashtags_outside_code() {
+        let markdown = [
+            "# Title",
+            "Filed under #work/Client A and #reading list#, #done.",
+            "Not a tag: issue#4 or `#code`",
+            "```",
+            "#include <stdio.h>",
+            "```",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            rewrite_hashtags(&markdown),
+            [
+                "# Title",
+                "Filed under #work/Client A and #reading-list, #done.",
+                "Not a tag: issue#4 or `#code`",
+                "```",
+                "#include <stdio.h>",
+                "```",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            to_mdit_tag("#Books / To read 📚#").as_deref(),
+            Some("Books/To-read")
+        );
+        assert_eq!(to_mdit_tag("🎉"), None);
+    }
+}
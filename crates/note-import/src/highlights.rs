@@ -0,0 +1,640 @@
+//! Kindle `My Clippings.txt` files and Readwise CSV exports. Highlights are
+//! grouped into one note per book. Importing again appends only the
+//! highlights the book's note does not contain yet, so the same export can
+//! be imported whenever it grows.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use note::{parse_frontmatter, sanitize_note_title, set_frontmatter_property};
+use serde_json::json;
+
+use crate::plan::{vault_folder, UNTITLED};
+use crate::{
+    ImportOptions, ImportReport, ImportSource, ImportedFile, ImportedFileKind, SkippedNote,
+    UpdatedNote,
+};
+
+/// Frontmatter property that marks a note as a book's highlights, naming
+/// where they came from. Only such notes are appended to.
+const HIGHLIGHTS_PROPERTY: &str = "highlights";
+const KINDLE_SEPARATOR: &str = "==========";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Book {
+    title: String,
+    author: Option<String>,
+    highlights: Vec<Highlight>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Highlight {
+    /// Empty for a note the reader left without highlighting anything.
+    text: String,
+    note: Option<String>,
+    /// Page and location, as the export gives them.
+    location: Option<String>,
+    added: Option<String>,
+}
+
+/// Writes or extends one note per book found in the export at `input`.
+pub(crate) fn import_highlights(
+    source: ImportSource,
+    input: &Path,
+    vault_root: &Path,
+    options: &ImportOptions,
+) -> Result<ImportReport, String> {
+    let export = fs::read_to_string(input)
+        .map_err(|error| format!("Failed to read {}: {error}", input.display()))?;
+    let (books, source_name) = match source {
+        ImportSource::Kindle => (read_kindle_clippings(&export), "kindle"),
+        ImportSource::Readwise => (read_readwise_csv(&export)?, "readwise"),
+        _ => unreachable!("not a highlights source"),
+    };
+    let folder = vault_folder(&options.target_folder)?
+        .iter()
+        .map(|name| format!("{name}/"))
+        .collect::<String>();
+
+    let mut report = ImportReport {
+        dry_run: options.dry_run,
+        files: Vec::new(),
+        updated: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let mut taken = HashSet::new();
+    for book in &books {
+        let stem = Some(sanitize_note_title(&book.title))
+            .filter(|stem| !stem.is_empty())
+            .unwrap_or_else(|| UNTITLED.to_string());
+        let target = book_note(vault_root, &folder, &stem, &mut taken)?;
+
+        match target {
+            BookNote::New(rel_path) => {
+                let content = new_book_note(book, source_name);
+                if !options.dry_run {
+                    write_new(&vault_root.join(&rel_path), &content)?;
+                }
+                report.files.push(ImportedFile {
+                    rel_path,
+                    kind: ImportedFileKind::Note,
+                    size: content.len() as u64,
+                    origin: book.title.clone(),
+                });
+            }
+            BookNote::Existing(rel_path, content) => {
+                let present = quoted_text(&content);
+                let new = book
+                    .highlights
+                    .iter()
+                    .filter(|highlight| !present.contains(&normalize(highlight.key())))
+                    .collect::<Vec<_>>();
+                if new.is_empty() {
+                    report.skipped.push(SkippedNote {
+                        origin: book.title.clone(),
+                        reason: "No new highlights".to_string(),
+                    });
+                    continue;
+                }
+                if !options.dry_run {
+                    let separator = if content.ends_with('\n') {
+                        "\n"
+                    } else {
+                        "\n\n"
+                    };
+                    let addition = new
+                        .iter()
+                        .map(|highlight| render_highlight(highlight))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    append(
+                        &vault_root.join(&rel_path),
+                        &format!("{separator}{addition}"),
+                    )?;
+                }
+                report.updated.push(UpdatedNote {
+                    rel_path,
+                    origin: book.title.clone(),
+                    added: new.len(),
+                });
+            }
+        }
+    }
+    Ok(report)
+}
+
+enum BookNote {
+    New(String),
+    /// A highlights note from an earlier import, with its content.
+    Existing(String, String),
+}
+
+/// The first of `stem.md`, `stem 1.md`, ... that is a highlights note or
+/// free. Other notes with the book's name are left alone.
+fn book_note(
+    vault_root: &Path,
+    folder: &str,
+    stem: &str,
+    taken: &mut HashSet<String>,
+) -> Result<BookNote, String> {
+    for attempt in 0.. {
+        let rel_path = if attempt == 0 {
+            format!("{folder}{stem}.md")
+        } else {
+            format!("{folder}{stem} {attempt}.md")
+        };
+        if !taken.insert(rel_path.to_lowercase()) {
+            continue;
+        }
+        let path = vault_root.join(&rel_path);
+        if !path.exists() {
+            return Ok(BookNote::New(rel_path));
+        }
+        let content = fs::read_to_string(&path)
+            .map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+        let is_highlights = parse_frontmatter(&content)
+            .properties
+            .iter()
+            .any(|property| property.key == HIGHLIGHTS_PROPERTY);
+        if is_highlights {
+            return Ok(BookNote::Existing(rel_path, content));
+        }
+    }
+    unreachable!("an unused name exists")
+}
+
+fn new_book_note(book: &Book, source_name: &str) -> String {
+    let body = book
+        .highlights
+        .iter()
+        .map(render_highlight)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut properties = vec![("title", json!(book.title))];
+    if let Some(author) = &book.author {
+        properties.push(("author", json!(author)));
+    }
+    properties.push((HIGHLIGHTS_PROPERTY, json!(source_name)));
+
+    properties
+        .into_iter()
+        .fold(format!("\n{body}"), |content, (key, value)| {
+            set_frontmatter_property(&content, key, &value).unwrap_or(content)
+        })
+}
+
+fn render_highlight(highlight: &Highlight) -> String {
+    let mut markdown = String::new();
+    for line in highlight.text.lines() {
+        markdown.push_str(if line.trim().is_empty() { ">" } else { "> " });
+        markdown.push_str(line.trim_end());
+        markdown.push('\n');
+    }
+    let meta = [
+        highlight.location.clone(),
+        highlight
+            .added
+            .as_ref()
+            .map(|added| format!("Added {added}")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" · ");
+    if !meta.is_empty() {
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+        markdown.push_str(&format!("*{meta}*\n"));
+    }
+    if let Some(note) = &highlight.note {
+        markdown.push_str(&format!("\n**Note:** {note}\n"));
+    }
+    markdown
+}
+
+impl Highlight {
+    /// What identifies the highlight in a note it was written to.
+    fn key(&self) -> &str {
+        if self.text.is_empty() {
+            self.note.as_deref().unwrap_or_default()
+        } else {
+            &self.text
+        }
+    }
+}
+
+/// The note's text with quote markers dropped and whitespace collapsed, so
+/// highlights can be found in it however they were wrapped.
+fn quoted_text(content: &str) -> String {
+    normalize(
+        &content
+            .lines()
+            .map(|line| line.trim_start().trim_start_matches('>'))
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn write_new(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed to create {}: {error}", parent.display()))?;
+    }
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|error| format!("Failed to write {}: {error}", path.display()))
+}
+
+fn append(path: &Path, content: &str) -> Result<(), String> {
+    fs::OpenOptions::new()
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|error| format!("Failed to write {}: {error}", path.display()))
+}
+
+/// Adds a highlight to its book, keeping books in the order they first
+/// appear.
+fn add_highlight(books: &mut Vec<Book>, title: &str, author: Option<String>, highlight: Highlight) {
+    let index = match books
+        .iter()
+        .position(|book| book.title == title && book.author == author)
+    {
+        Some(index) => index,
+        None => {
+            books.push(Book {
+                title: title.to_string(),
+                author,
+                highlights: Vec::new(),
+            });
+            books.len() - 1
+        }
+    };
+    let highlights = &mut books[index].highlights;
+    if !highlights.iter().any(|existing| existing == &highlight) {
+        highlights.push(highlight);
+    }
+}
+
+fn read_kindle_clippings(export: &str) -> Vec<Book> {
+    let mut books = Vec::new();
+    for entry in export.split(KINDLE_SEPARATOR) {
+        let mut lines = entry
+            .lines()
+            .map(|line| line.trim_start_matches('\u{feff}').trim_end())
+            .skip_while(|line| line.trim().is_empty());
+        let (Some(heading), Some(meta)) = (lines.next(), lines.next()) else {
+            continue;
+        };
+        let text = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+        if text.is_empty() {
+            // Bookmarks carry no text.
+            continue;
+        }
+
+        let (title, author) = split_kindle_heading(heading.trim());
+        let mut location = Vec::new();
+        let mut added = None;
+        for part in meta.trim().trim_start_matches('-').split('|') {
+            let part = part.trim();
+            if let Some(date) = part.strip_prefix("Added on ") {
+                added = Some(date.to_string());
+            } else {
+                let part = ["Your Highlight on ", "Your Note on ", "Your Highlight at "]
+                    .iter()
+                    .find_map(|prefix| part.strip_prefix(prefix))
+                    .unwrap_or(part);
+                location.push(capitalize(part.trim()));
+            }
+        }
+        let location = Some(location.join(" · ")).filter(|location| !location.is_empty());
+
+        if meta.contains("Your Note") {
+            let note_location = location
+                .as_deref()
+                .and_then(location_range)
+                .map(|(_, end)| end);
+            let highlight = books
+                .iter_mut()
+                .find(|book: &&mut Book| book.title == title && book.author == author)
+                .and_then(|book| {
+                    book.highlights.iter_mut().rev().find(|highlight| {
+                        highlight.note.is_none()
+                            && highlight
+                                .location
+                                .as_deref()
+                                .and_then(location_range)
+                                .zip(note_location)
+                                .is_some_and(|((start, end), at)| start <= at && at <= end)
+                    })
+                });
+            match highlight {
+                Some(highlight) => highlight.note = Some(text),
+                None => add_highlight(
+                    &mut books,
+                    &title,
+                    author,
+                    Highlight {
+                        note: Some(text),
+                        location,
+                        added,
+                        ..Highlight::default()
+                    },
+                ),
+            }
+            continue;
+        }
+
+        // Extending a highlight on the Kindle adds a new clipping and keeps
+        // the old one; only the longer one is kept.
+        let start = location
+            .as_deref()
+            .and_then(location_range)
+            .map(|(start, _)| start);
+        let extended = books
+            .iter_mut()
+            .filter(|book| book.title == title && book.author == author)
+            .flat_map(|book| book.highlights.iter_mut())
+            .find(|existing| {
+                start.is_some()
+                    && existing
+                        .location
+                        .as_deref()
+                        .and_then(location_range)
+                        .map(|(start, _)| start)
+                        == start
+            });
+        if let Some(existing) = extended {
+            if text.contains(existing.text.as_str()) {
+                existing.text = text;
+                existing.location = location;
+                existing.added = added;
+            }
+            continue;
+        }
+        add_highlight(
+            &mut books,
+            &title,
+            author,
+            Highlight {
+                text,
+                note: None,
+                location,
+                added,
+            },
+        );
+    }
+    books
+}
+
+/// `Title (Author)` as the Kindle writes it.
+fn split_kindle_heading(heading: &str) -> (String, Option<String>) {
+    if let Some(rest) = heading.strip_suffix(')') {
+        if let Some((title, author)) = rest.rsplit_once(" (") {
+            return (title.trim().to_string(), Some(author.trim().to_string()));
+        }
+    }
+    (heading.to_string(), None)
+}
+
+/// The start and end of the `Location 180-182` part of a clipping.
+fn location_range(location: &str) -> Option<(u64, u64)> {
+    let range = location
+        .split(" · ")
+        .find_map(|part| part.strip_prefix("Location "))?;
+    let (start, end) = range.split_once('-').unwrap_or((range, range));
+    let start = start.trim().parse().ok()?;
+    Some((start, end.trim().parse().unwrap_or(start)))
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn read_readwise_csv(export: &str) -> Result<Vec<Book>, String> {
+    let mut rows = parse_csv(export.trim_start_matches('\u{feff}')).into_iter();
+    let header = rows.next().unwrap_or_default();
+    let column = |name: &str| header.iter().position(|column| column.trim() == name);
+    let (Some(text_column), Some(title_column)) = (column("Highlight"), column("Book Title"))
+    else {
+        return Err(
+            "Not a Readwise export: Highlight and Book Title columns are missing".to_string(),
+        );
+    };
+    let author_column = column("Book Author");
+    let note_column = column("Note");
+    let location_type_column = column("Location Type");
+    let location_column = column("Location");
+    let added_column = column("Highlighted at");
+
+    let mut books = Vec::new();
+    for row in rows {
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|column| row.get(column))
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let (Some(text), Some(title)) = (field(Some(text_column)), field(Some(title_column)))
+        else {
+            continue;
+        };
+        let location = field(location_column).map(|location| {
+            match field(location_type_column).filter(|kind| kind != "order") {
+                Some(kind) => format!("{} {location}", capitalize(&kind)),
+                None => location,
+            }
+        });
+        let added = field(added_column).map(|added| {
+            // `2024-03-04 21:15:03+00:00` becomes the date.
+            match added.get(..10) {
+                Some(date) if date.as_bytes().get(4) == Some(&b'-') => date.to_string(),
+                _ => added,
+            }
+        });
+        add_highlight(
+            &mut books,
+            &title,
+            field(author_column),
+            Highlight {
+                text,
+                note: field(note_column),
+                location,
+                added,
+            },
+        );
+    }
+    Ok(books)
+}
+
+/// Rows of RFC 4180 CSV: quoted fields may hold commas, newlines and `""`.
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{read_readwise_csv, Highlight};
+    use crate::{import_notes, ImportOptions, ImportSource};
+
+    const CLIPPINGS: &str = "\u{feff}Dune (Frank Herbert)
+- Your Highlight on page 8 | Location 120-121 | Added on Monday, March 4, 2024 9:15:03 PM
+
+Fear is the mind-killer.
+==========
+Dune (Frank Herbert)
+- Your Highlight on page 8 | Location 120-122 | Added on Monday, March 4, 2024 9:16:00 PM
+
+Fear is the mind-killer. Fear is the little-death.
+==========
+Dune (Frank Herbert)
+- Your Note on page 8 | Location 122 | Added on Monday, March 4, 2024 9:17:00 PM
+
+Litany
+==========
+Dune (Frank Herbert)
+- Your Bookmark on page 9 | Location 130 | Added on Monday, March 4, 2024 9:18:00 PM
+
+
+==========
+";
+
+    #[test]
+    fn kindle_reimport_appends_only_new_highlights() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-highlights-{nanos}"));
+        let vault = root.join("vault");
+        fs::create_dir_all(vault.join("Books")).unwrap();
+        fs::write(vault.join("Books/Dune.md"), "My own review\n").unwrap();
+        let clippings = root.join("My Clippings.txt");
+        fs::write(&clippings, CLIPPINGS).unwrap();
+        let options = ImportOptions {
+            target_folder: "Books".to_string(),
+            ..ImportOptions::default()
+        };
+
+        let report = import_notes(ImportSource::Kindle, &clippings, &vault, &options).unwrap();
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(report.files[0].rel_path, "Books/Dune 1.md");
+        let note = fs::read_to_string(vault.join("Books/Dune 1.md")).unwrap();
+        assert_eq!(
+            note,
+            [
+                "---",
+                "title: Dune",
+                "author: Frank Herbert",
+                "highlights: kindle",
+                "---",
+                "",
+                "> Fear is the mind-killer. Fear is the little-death.",
+                "",
+                "*Page 8 · Location 120-122 · Added Monday, March 4, 2024 9:16:00 PM*",
+                "",
+                "**Note:** Litany",
+                "",
+            ]
+            .join("\n")
+        );
+
+        fs::write(
+            &clippings,
+            format!(
+                "{CLIPPINGS}Dune (Frank Herbert)\n- Your Highlight on page 20 | Location 300-301 | Added on Tuesday, March 5, 2024 8:00:00 AM\n\nThe spice must flow.\n==========\n"
+            ),
+        )
+        .unwrap();
+        let report = import_notes(ImportSource::Kindle, &clippings, &vault, &options).unwrap();
+        assert!(report.files.is_empty());
+        assert_eq!(report.updated.len(), 1);
+        assert_eq!(report.updated[0].rel_path, "Books/Dune 1.md");
+        assert_eq!(report.updated[0].added, 1);
+        let appended = fs::read_to_string(vault.join("Books/Dune 1.md")).unwrap();
+        assert_eq!(
+            &appended[note.len()..],
+            "\n> The spice must flow.\n\n*Page 20 · Location 300-301 · Added Tuesday, March 5, 2024 8:00:00 AM*\n"
+        );
+        assert_eq!(
+            fs::read_to_string(vault.join("Books/Dune.md")).unwrap(),
+            "My own review\n"
+        );
+
+        let report = import_notes(ImportSource::Kindle, &clippings, &vault, &options).unwrap();
+        assert!(report.files.is_empty() && report.updated.is_empty());
+        assert_eq!(report.skipped[0].reason, "No new highlights");
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn reads_readwise_csv_exports() {
+        let csv = "Highlight,Book Title,Book Author,Amazon Book ID,Note,Color,Tags,Location Type,Location,Highlighted at\r\n\
+                   \"He said, \"\"go\"\"\nnow\",Walden,Henry David Thoreau,,,yellow,,location,42,2024-03-04 21:15:03+00:00\r\n\
+                   Simplify,Walden,Henry David Thoreau,,Key idea,,,order,7,\r\n";
+        let books = read_readwise_csv(csv).unwrap();
+        assert_eq!(books.len(), 1);
+        assert_eq!(books[0].author.as_deref(), Some("Henry David Thoreau"));
+        assert_eq!(
+            books[0].highlights,
+            vec![
+                Highlight {
+                    text: "He said, \"go\"\nnow".to_string(),
+                    note: None,
+                    location: Some("Location 42".to_string()),
+                    added: Some("2024-03-04".to_string()),
+                },
+                Highlight {
+                    text: "Simplify".to_string(),
+                    note: Some("Key idea".to_string()),
+                    location: Some("7".to_string()),
+                    added: None,
+                },
+            ]
+        );
+        assert!(read_readwise_csv("a,b\n1,2\n").is_err());
+    }
+}
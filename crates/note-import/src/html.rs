@@ -0,0 +1,754 @@
+//! A forgiving HTML to Markdown converter for the markup note apps export:
+//! Apple Notes HTML and Evernote's ENML. It reads tags loosely, without
+//! building a DOM, and leaves out anything it does not know.
+
+use std::collections::HashMap;
+
+use crate::source::{Segment, SegmentWriter};
+
+/// Something the HTML points at, for the caller to turn into a link or an
+/// embed. Returning `None` keeps a plain Markdown link, or drops the image.
+pub(crate) enum HtmlReference<'a> {
+    Link {
+        href: &'a str,
+        label: &'a str,
+    },
+    Image {
+        src: &'a str,
+        alt: &'a str,
+    },
+    /// Evernote `<en-media>`, by the MD5 hash of the resource.
+    Media {
+        hash: &'a str,
+    },
+}
+
+pub(crate) type Resolver<'r> = dyn FnMut(HtmlReference<'_>) -> Option<Segment> + 'r;
+
+pub(crate) fn html_to_segments(html: &str, resolve: &mut Resolver<'_>) -> Vec<Segment> {
+    tokens_to_segments(Tokenizer::new(html), resolve, false)
+}
+
+/// Converts already tokenized HTML. With `skip_page_chrome`, navigation,
+/// forms and similar parts of web pages are left out too.
+pub(crate) fn tokens_to_segments<'a>(
+    tokens: impl IntoIterator<Item = Token<'a>>,
+    resolve: &mut Resolver<'_>,
+    skip_page_chrome: bool,
+) -> Vec<Segment> {
+    let mut converter = Converter::new(resolve);
+    converter.skip_page_chrome = skip_page_chrome;
+    for token in tokens {
+        match token {
+            Token::Text(text) => converter.text(&decode_entities(text)),
+            Token::Start {
+                name,
+                attributes,
+                self_closing,
+            } => converter.start(&name, &attributes, self_closing),
+            Token::End(name) => converter.end(&name),
+        }
+    }
+    converter.finish()
+}
+
+/// Text of the document's `<title>`, if any.
+pub(crate) fn html_title(html: &str) -> Option<String> {
+    let mut in_title = false;
+    let mut title = String::new();
+    for token in Tokenizer::new(html) {
+        match token {
+            Token::Start { name, .. } if name == "title" => in_title = true,
+            Token::End(name) if name == "title" => break,
+            Token::Text(text) if in_title => title.push_str(&decode_entities(text)),
+            _ => {}
+        }
+    }
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    Some(title).filter(|title| !title.is_empty())
+}
+
+struct Converter<'r, 'a> {
+    out: SegmentWriter,
+    resolve: &'r mut Resolver<'a>,
+    skip_page_chrome: bool,
+    skip_depth: usize,
+    pre_depth: usize,
+    code_depth: usize,
+    quote_depth: usize,
+    lists: Vec<List>,
+    /// Emphasis markers still open, with the tail length right after each.
+    emphasis: Vec<(&'static str, usize)>,
+    link: Option<OpenLink>,
+    table: Option<Table>,
+    at_line_start: bool,
+    pending_space: bool,
+    pending_break: bool,
+}
+
+struct List {
+    ordered: bool,
+    next: usize,
+}
+
+struct OpenLink {
+    href: String,
+    start: usize,
+}
+
+#[derive(Default)]
+struct Table {
+    rows: usize,
+    cells: usize,
+}
+
+impl<'r, 'a> Converter<'r, 'a> {
+    fn new(resolve: &'r mut Resolver<'a>) -> Self {
+        Self {
+            out: SegmentWriter::default(),
+            resolve,
+            skip_page_chrome: false,
+            skip_depth: 0,
+            pre_depth: 0,
+            code_depth: 0,
+            quote_depth: 0,
+            lists: Vec::new(),
+            emphasis: Vec::new(),
+            link: None,
+            table: None,
+            at_line_start: true,
+            pending_space: false,
+            pending_break: false,
+        }
+    }
+
+    fn finish(mut self) -> Vec<Segment> {
+        let trimmed = self.out.tail().trim_end().len();
+        self.out.truncate_tail(trimmed);
+        self.out.finish()
+    }
+
+    fn start(&mut self, name: &str, attributes: &HashMap<String, String>, self_closing: bool) {
+        if self.is_skipped(name) {
+            if !self_closing {
+                self.skip_depth += 1;
+            }
+            return;
+        }
+        if self.skip_depth > 0 {
+            return;
+        }
+
+        let attribute = |key: &str| attributes.get(key).map(String::as_str).unwrap_or("");
+        match name {
+            "p" | "div" | "section" | "article" | "en-note" | "body" => self.block(true),
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block(true);
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                self.inline(&format!("{} ", "#".repeat(level)));
+            }
+            "br" => {
+                if self.at_line_start {
+                    self.block(true);
+                } else {
+                    self.pending_break = true;
+                }
+            }
+            "hr" => {
+                self.block(true);
+                self.inline("---");
+                self.block(true);
+            }
+            "blockquote" => {
+                self.block(true);
+                self.quote_depth += 1;
+            }
+            "ul" | "ol" => {
+                self.block(self.lists.is_empty());
+                self.lists.push(List {
+                    ordered: name == "ol",
+                    next: 1,
+                });
+            }
+            "li" => {
+                self.block(false);
+                let depth = self.lists.len().max(1);
+                let marker = match self.lists.last_mut() {
+                    Some(List {
+                        ordered: true,
+                        next,
+                    }) => {
+                        *next += 1;
+                        format!("{}. ", *next - 1)
+                    }
+                    _ => "- ".to_string(),
+                };
+                self.line_prefix(depth - 1);
+                self.out.push_str(&marker);
+                self.at_line_start = false;
+            }
+            "pre" => {
+                self.block(true);
+                self.inline("```\n");
+                self.pre_depth += 1;
+            }
+            "code" | "tt" if self.pre_depth == 0 => {
+                self.inline("`");
+                self.code_depth += 1;
+            }
+            "b" | "strong" => self.open_emphasis("**"),
+            "i" | "em" => self.open_emphasis("*"),
+            "s" | "strike" | "del" => self.open_emphasis("~~"),
+            "a" => {
+                self.flush_space();
+                self.link = Some(OpenLink {
+                    href: attribute("href").to_string(),
+                    start: self.out.tail().len(),
+                });
+            }
+            "img" => {
+                let segment = (self.resolve)(HtmlReference::Image {
+                    src: attribute("src"),
+                    alt: attribute("alt"),
+                });
+                match segment {
+                    Some(segment) => self.embed(segment),
+                    None if is_web_url(attribute("src")) => {
+                        let image = format!("![{}]({})", attribute("alt"), attribute("src"));
+                        self.inline(&image);
+                    }
+                    None => {}
+                }
+            }
+            "en-media" => {
+                if let Some(segment) = (self.resolve)(HtmlReference::Media {
+                    hash: attribute("hash"),
+                }) {
+                    self.embed(segment);
+                }
+            }
+            "en-todo" => self.checkbox(attribute("checked") == "true"),
+            "input" if attribute("type") == "checkbox" => {
+                self.checkbox(attributes.contains_key("checked"))
+            }
+            "table" => {
+                self.block(true);
+                self.table = Some(Table::default());
+            }
+            "tr" => {
+                self.block(false);
+                self.inline("|");
+                if let Some(table) = self.table.as_mut() {
+                    table.cells = 0;
+                }
+            }
+            "td" | "th" => {
+                self.pending_space = true;
+                if let Some(table) = self.table.as_mut() {
+                    table.cells += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        if self.is_skipped(name) {
+            self.skip_depth = self.skip_depth.saturating_sub(1);
+            return;
+        }
+        if self.skip_depth > 0 {
+            return;
+        }
+
+        match name {
+            "p" | "div" | "section" | "article" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block(true)
+            }
+            "blockquote" => {
+                self.block(true);
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+            }
+            "ul" | "ol" => {
+                self.lists.pop();
+                self.block(self.lists.is_empty());
+            }
+            "li" => self.block(false),
+            "pre" if self.pre_depth > 0 => {
+                self.pre_depth -= 1;
+                if !self.out.tail().ends_with('\n') {
+                    self.out.push_str("\n");
+                }
+                self.out.push_str("```");
+                self.block(true);
+            }
+            "code" | "tt" if self.code_depth > 0 => {
+                self.code_depth -= 1;
+                self.out.push_str("`");
+            }
+            "b" | "strong" => self.close_emphasis("**"),
+            "i" | "em" => self.close_emphasis("*"),
+            "s" | "strike" | "del" => self.close_emphasis("~~"),
+            "a" => self.close_link(),
+            "td" | "th" => {
+                self.pending_space = false;
+                self.pending_break = false;
+                self.out.push_str(" |");
+            }
+            "tr" => {
+                let Some(table) = self.table.as_mut() else {
+                    return;
+                };
+                table.rows += 1;
+                if table.rows == 1 {
+                    let separator = format!("\n|{}", " --- |".repeat(table.cells.max(1)));
+                    self.out.push_str(&separator);
+                }
+                self.block(false);
+            }
+            "table" => {
+                self.table = None;
+                self.block(true);
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.skip_depth > 0 {
+            return;
+        }
+        if self.pre_depth > 0 {
+            self.out.push_str(text);
+            return;
+        }
+
+        for ch in text.chars() {
+            if ch.is_whitespace() {
+                self.pending_space = !self.at_line_start;
+                continue;
+            }
+            self.flush_space();
+            let escape = self.code_depth == 0 && matches!(ch, '\\' | '*' | '`' | '[' | ']');
+            if escape {
+                self.out.push_str("\\");
+            }
+            let mut buffer = [0; 4];
+            self.out.push_str(ch.encode_utf8(&mut buffer));
+        }
+    }
+
+    /// Writes Markdown syntax where text would go.
+    fn inline(&mut self, markdown: &str) {
+        self.flush_space();
+        self.out.push_str(markdown);
+    }
+
+    fn embed(&mut self, segment: Segment) {
+        self.flush_space();
+        self.out.push(segment);
+    }
+
+    fn checkbox(&mut self, checked: bool) {
+        let box_ = if checked { "[x] " } else { "[ ] " };
+        if self.at_line_start && self.lists.is_empty() {
+            self.inline("- ");
+        }
+        self.inline(box_);
+        self.pending_space = false;
+    }
+
+    fn open_emphasis(&mut self, marker: &'static str) {
+        self.inline(marker);
+        self.emphasis.push((marker, self.out.tail().len()));
+    }
+
+    fn close_emphasis(&mut self, marker: &'static str) {
+        let Some(index) = self.emphasis.iter().rposition(|(open, _)| *open == marker) else {
+            return;
+        };
+        let (_, opened_at) = self.emphasis.remove(index);
+        let tail = self.out.tail();
+        if tail.len() == opened_at && tail.ends_with(marker) {
+            // Nothing between the markers.
+            self.out.truncate_tail(opened_at - marker.len());
+            return;
+        }
+        self.out.push_str(marker);
+    }
+
+    fn close_link(&mut self) {
+        let Some(link) = self.link.take() else {
+            return;
+        };
+        let tail = self.out.tail();
+        let Some(label) = tail.get(link.start..).map(str::to_string) else {
+            return;
+        };
+        if link.href.is_empty() {
+            return;
+        }
+
+        let resolved = (self.resolve)(HtmlReference::Link {
+            href: &link.href,
+            label: label.trim(),
+        });
+        match resolved {
+            Some(segment) => {
+                self.out.truncate_tail(link.start);
+                self.out.push(segment);
+            }
+            None if is_web_url(&link.href) || link.href.starts_with("mailto:") => {
+                self.out.truncate_tail(link.start);
+                let markdown = if label.trim().is_empty() || label.trim() == link.href {
+                    format!("<{}>", link.href)
+                } else {
+                    format!("[{}]({})", label.trim(), link.href.replace(' ', "%20"))
+                };
+                self.out.push_str(&markdown);
+            }
+            None => {}
+        }
+    }
+
+    /// Elements whose content is left out.
+    fn is_skipped(&self, name: &str) -> bool {
+        matches!(name, "head" | "style" | "script" | "title")
+            || (self.skip_page_chrome
+                && matches!(
+                    name,
+                    "nav"
+                        | "footer"
+                        | "aside"
+                        | "form"
+                        | "noscript"
+                        | "svg"
+                        | "button"
+                        | "iframe"
+                        | "template"
+                ))
+    }
+
+    /// Ends the current line, and the paragraph too when `blank`.
+    fn block(&mut self, blank: bool) {
+        self.pending_space = false;
+        self.pending_break = false;
+        if self.out.is_empty() {
+            return;
+        }
+        let blank = blank && self.lists.is_empty() && self.table.is_none();
+        let wanted = if blank { 2 } else { 1 };
+        let tail = self.out.tail();
+        let trimmed = tail.trim_end_matches([' ', '\t']).len();
+        self.out.truncate_tail(trimmed);
+        let existing = self
+            .out
+            .tail()
+            .chars()
+            .rev()
+            .take_while(|ch| *ch == '\n')
+            .count();
+        let has_text = !self.out.is_empty();
+        if has_text && existing < wanted {
+            self.out.push_str(&"\n".repeat(wanted - existing));
+        }
+        self.at_line_start = true;
+    }
+
+    fn flush_space(&mut self) {
+        if self.pending_break {
+            self.pending_break = false;
+            self.pending_space = false;
+            self.out.push_str("\\\n");
+            self.at_line_start = true;
+        }
+        if self.at_line_start {
+            self.line_prefix(self.lists.len());
+            self.at_line_start = false;
+            self.pending_space = false;
+        } else if self.pending_space {
+            self.out.push_str(" ");
+            self.pending_space = false;
+        }
+    }
+
+    fn line_prefix(&mut self, list_depth: usize) {
+        let prefix = format!(
+            "{}{}",
+            "> ".repeat(self.quote_depth),
+            "  ".repeat(list_depth)
+        );
+        self.out.push_str(&prefix);
+        self.at_line_start = false;
+    }
+}
+
+pub(crate) fn is_web_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+pub(crate) enum Token<'a> {
+    Text(&'a str),
+    Start {
+        name: String,
+        attributes: HashMap<String, String>,
+        self_closing: bool,
+    },
+    End(String),
+}
+
+pub(crate) struct Tokenizer<'a> {
+    html: &'a str,
+    position: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    pub(crate) fn new(html: &'a str) -> Self {
+        Self { html, position: 0 }
+    }
+
+    fn skip_past(&mut self, terminator: &str) {
+        self.position = match self.html[self.position..].find(terminator) {
+            Some(offset) => self.position + offset + terminator.len(),
+            None => self.html.len(),
+        };
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        loop {
+            let rest = &self.html[self.position..];
+            if rest.is_empty() {
+                return None;
+            }
+            if !rest.starts_with('<') {
+                let end = rest.find('<').unwrap_or(rest.len());
+                self.position += end;
+                return Some(Token::Text(&rest[..end]));
+            }
+            if rest.starts_with("<!--") {
+                self.skip_past("-->");
+                continue;
+            }
+            if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let end = cdata.find("]]>").unwrap_or(cdata.len());
+                self.skip_past("]]>");
+                return Some(Token::Text(&cdata[..end]));
+            }
+            if rest.starts_with("<!") || rest.starts_with("<?") {
+                self.skip_past(">");
+                continue;
+            }
+
+            let is_end = rest.starts_with("</");
+            let name_start = if is_end { 2 } else { 1 };
+            let name_len = rest[name_start..]
+                .find(|ch: char| ch.is_whitespace() || ch == '/' || ch == '>')
+                .unwrap_or(rest.len() - name_start);
+            if name_len == 0 || !rest[name_start..].starts_with(|ch: char| ch.is_alphabetic()) {
+                // A stray `<` in text.
+                self.position += 1;
+                return Some(Token::Text("<"));
+            }
+            let Some(tag_end) = find_tag_end(rest) else {
+                // An unclosed tag at the end is left as text.
+                self.position = self.html.len();
+                return Some(Token::Text(rest));
+            };
+            let name = rest[name_start..name_start + name_len].to_lowercase();
+            let inner = &rest[name_start + name_len..tag_end - 1];
+            self.position += tag_end;
+
+            if is_end {
+                return Some(Token::End(name));
+            }
+            let self_closing = inner.trim_end().ends_with('/');
+            return Some(Token::Start {
+                name,
+                attributes: parse_attributes(inner.trim_end().trim_end_matches('/')),
+                self_closing,
+            });
+        }
+    }
+}
+
+/// Index just past the `>` closing the tag at the start of `rest`, skipping
+/// quoted attribute values.
+fn find_tag_end(rest: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, ch) in rest.char_indices() {
+        match (quote, ch) {
+            (Some(open), _) if ch == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(ch),
+            (None, '>') => return Some(index + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_attributes(raw: &str) -> HashMap<String, String> {
+    let mut attributes = HashMap::new();
+    let mut rest = raw.trim_start();
+    while !rest.is_empty() {
+        let name_len = rest
+            .find(|ch: char| ch.is_whitespace() || ch == '=')
+            .unwrap_or(rest.len());
+        let name = rest[..name_len].to_lowercase();
+        rest = rest[name_len..].trim_start();
+
+        let mut value = String::new();
+        if let Some(after_equals) = rest.strip_prefix('=') {
+            let after_equals = after_equals.trim_start();
+            let (raw_value, remaining) = match after_equals.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let body = &after_equals[1..];
+                    let end = body.find(quote).unwrap_or(body.len());
+                    (&body[..end], body.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after_equals
+                        .find(char::is_whitespace)
+                        .unwrap_or(after_equals.len());
+                    (&after_equals[..end], &after_equals[end..])
+                }
+            };
+            value = decode_entities(raw_value);
+            rest = remaining.trim_start();
+        }
+        if !name.is_empty() {
+            attributes.entry(name).or_insert(value);
+        } else {
+            // Skip a character that starts no attribute.
+            let skip = rest.chars().next().map_or(0, char::len_utf8);
+            rest = &rest[skip..];
+        }
+    }
+    attributes
+}
+
+pub(crate) fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        });
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{html_title, html_to_segments, HtmlReference};
+    use crate::source::Segment;
+
+    fn markdown(html: &str) -> String {
+        let segments = html_to_segments(html, &mut |reference| match reference {
+            HtmlReference::Link { href, label } if href.ends_with(".html") => {
+                Some(Segment::NoteLink {
+                    title: href.trim_end_matches(".html").to_string(),
+                    heading: None,
+                    label: Some(label.to_string()),
+                })
+            }
+            _ => None,
+        });
+        segments
+            .into_iter()
+            .map(|segment| match segment {
+                Segment::Markdown(markdown) => markdown,
+                Segment::NoteLink { title, .. } => format!("[[{title}]]"),
+                Segment::Attachment { index, .. } => format!("![[{index}]]"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn converts_apple_notes_markup() {
+        let html = concat!(
+            "<html><head><title>Trip &amp; Plans</title><style>p{}</style></head><body>",
+            "<div><h1>Trip</h1></div><div>Pack <b>light</b> and <i></i>early.</div>",
+            "<div><br></div><div>Line one<br>line two</div>",
+            "<ul><li>Tickets<ul><li>Train</li></ul></li><li>See <a href=\"Hotel.html\">hotel</a></li></ul>",
+            "<div>Read <a href=\"https://example.com\">this</a> [soon]</div>",
+            "<pre>let x = 1;\n</pre>",
+            "</body></html>"
+        );
+
+        assert_eq!(html_title(html).as_deref(), Some("Trip & Plans"));
+        assert_eq!(
+            markdown(html),
+            [
+                "# Trip",
+                "",
+                "Pack **light** and early.",
+                "",
+                "Line one\\",
+                "line two",
+                "",
+                "- Tickets",
+                "  - Train",
+                "- See [[Hotel]]",
+                "",
+                "Read [this](https://example.com) \\[soon\\]",
+                "",
+                "```",
+                "let x = 1;",
+                "```",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn converts_enml_todos_and_tables() {
+        let html = concat!(
+            "<?xml version=\"1.0\"?><!DOCTYPE en-note SYSTEM \"x\"><en-note>",
+            "<div><en-todo checked=\"true\"/>Done</div><div><en-todo/>Open</div>",
+            "<table><tr><td>A</td><td>B</td></tr><tr><td>1</td><td>2</td></tr></table>",
+            "</en-note>"
+        );
+
+        assert_eq!(
+            markdown(html),
+            "- [x] Done\n\n- [ ] Open\n\n| A | B |\n| --- | --- |\n| 1 | 2 |"
+        );
+    }
+}
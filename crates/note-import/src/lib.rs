@@ -0,0 +1,212 @@
+//! Imports notes from other apps into a vault. Hashtags become mdit tags,
+//! images and files become vault attachments, and links between the
+//! imported notes become wiki links. Every file is planned before anything
+//! is written, so a dry run reports the same file tree a real import makes.
+//! Web pages clipped from a browser go through the same HTML conversion.
+//! Kindle and Readwise highlights become one note per book, which later
+//! imports of the same export extend. Calendar events become meeting
+//! notes that later imports keep up to date.
+
+mod apple_notes;
+mod bear;
+mod calendar;
+mod clip;
+mod docx;
+mod hashtags;
+mod highlights;
+mod html;
+mod plan;
+mod source;
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+pub use calendar::{
+    fetch_ics, import_calendar, CalendarImport, CalendarImportOptions, MeetingNote,
+};
+pub use clip::{clip_html, html_fragment_to_markdown, WebClip};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportSource {
+    /// A `.bear2bk` backup, a folder of `.textbundle`s, or a single one.
+    Bear,
+    /// An `.enex` export, an exported `.html` note, or a folder of either.
+    AppleNotes,
+    /// A `.docx` document, a OneNote page exported as one, or a folder of
+    /// them.
+    Docx,
+    /// A Kindle `My Clippings.txt` file.
+    Kindle,
+    /// A Readwise CSV export.
+    Readwise,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ImportOptions {
+    /// Vault-relative folder for the notes; the vault root when empty.
+    pub target_folder: String,
+    /// Vault-relative folder for images and other files.
+    pub attachment_folder: String,
+    /// Only report what would be written.
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+    pub dry_run: bool,
+    /// Notes first, then attachments, in the order they are written.
+    pub files: Vec<ImportedFile>,
+    /// Existing notes the import appended to.
+    pub updated: Vec<UpdatedNote>,
+    pub skipped: Vec<SkippedNote>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedFile {
+    /// Forward-slash path relative to the vault root.
+    pub rel_path: String,
+    pub kind: ImportedFileKind,
+    pub size: u64,
+    /// The note it came from in the source app.
+    pub origin: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportedFileKind {
+    Note,
+    Attachment,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatedNote {
+    /// Forward-slash path relative to the vault root.
+    pub rel_path: String,
+    pub origin: String,
+    /// How many highlights were appended.
+    pub added: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedNote {
+    pub origin: String,
+    pub reason: String,
+}
+
+/// Imports the export at `input` into the vault at `vault_root`. Existing
+/// files are never overwritten; clashing names get a number instead. The
+/// only exception is a highlights note, which new highlights are appended to.
+pub fn import_notes(
+    source: ImportSource,
+    input: &Path,
+    vault_root: &Path,
+    options: &ImportOptions,
+) -> Result<ImportReport, String> {
+    if !input.exists() {
+        return Err(format!("Import source not found: {}", input.display()));
+    }
+
+    let (notes, skipped) = match source {
+        ImportSource::Bear => bear::read_bear(input)?,
+        ImportSource::AppleNotes => apple_notes::read_apple_notes(input)?,
+        ImportSource::Docx => docx::read_docx(input)?,
+        ImportSource::Kindle | ImportSource::Readwise => {
+            return highlights::import_highlights(source, input, vault_root, options);
+        }
+    };
+    let files = plan::plan_files(&notes, vault_root, options)?;
+    if !options.dry_run {
+        plan::write_files(vault_root, &files)?;
+    }
+
+    Ok(ImportReport {
+        dry_run: options.dry_run,
+        files: files.into_iter().map(|planned| planned.file).collect(),
+        updated: Vec::new(),
+        skipped: skipped
+            .into_iter()
+            .map(|skip| SkippedNote {
+                origin: skip.origin,
+                reason: skip.reason,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{import_notes, ImportOptions, ImportSource, ImportedFileKind};
+
+    #[test]
+    fn dry_run_reports_the_files_a_real_import_writes() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-note-import-{nanos}"));
+        let export = root.join("export");
+        let vault = root.join("vault");
+        fs::create_dir_all(export.join("Recipes")).unwrap();
+        fs::create_dir_all(&vault).unwrap();
+        fs::write(
+            export.join("Recipes/Bread.html"),
+            "<html><head><title>Bread</title></head><body><div>Flour, see <a href=\"../Shopping.html\">list</a></div><img src=\"bread%20loaf.jpg\"></body></html>",
+        )
+        .unwrap();
+        fs::write(export.join("Recipes/bread loaf.jpg"), b"jpg").unwrap();
+        fs::write(
+            export.join("Shopping.html"),
+            "<body><h1>Shopping</h1><div>#groceries</div></body>",
+        )
+        .unwrap();
+
+        let mut options = ImportOptions {
+            target_folder: "Apple Notes".to_string(),
+            attachment_folder: "attachments".to_string(),
+            dry_run: true,
+        };
+        let planned = import_notes(ImportSource::AppleNotes, &export, &vault, &options).unwrap();
+        assert!(planned.dry_run);
+        assert_eq!(
+            planned
+                .files
+                .iter()
+                .map(|file| (file.rel_path.as_str(), file.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Apple Notes/Recipes/Bread.md", ImportedFileKind::Note),
+                ("Apple Notes/Shopping.md", ImportedFileKind::Note),
+                ("attachments/bread loaf.jpg", ImportedFileKind::Attachment),
+            ]
+        );
+        assert!(!vault.join("Apple Notes").exists());
+
+        options.dry_run = false;
+        let written = import_notes(ImportSource::AppleNotes, &export, &vault, &options).unwrap();
+        assert_eq!(written.files, planned.files);
+        assert_eq!(
+            fs::read_to_string(vault.join("Apple Notes/Recipes/Bread.md")).unwrap(),
+            "Flour, see [[Shopping|list]]\n\n![[attachments/bread loaf.jpg]]\n"
+        );
+        assert_eq!(
+            fs::read_to_string(vault.join("Apple Notes/Shopping.md")).unwrap(),
+            "# Shopping\n\n#groceries\n"
+        );
+        assert_eq!(
+            fs::read(vault.join("attachments/bread loaf.jpg")).unwrap(),
+            b"jpg"
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path};
+
+use note::sanitize_note_title;
+
+use crate::source::{Segment, SourceNote};
+use crate::{ImportOptions, ImportedFile, ImportedFileKind};
+
+pub(crate) const UNTITLED: &str = "Untitled";
+
+/// A file the import will write, at a path free in the vault.
+pub(crate) struct PlannedFile {
+    pub(crate) file: ImportedFile,
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// Places every note and attachment in the vault without touching it.
+/// Paths already taken get a numbered name, and links between the notes
+/// follow the names they end up with.
+pub(crate) fn plan_files(
+    notes: &[SourceNote],
+    vault_root: &Path,
+    options: &ImportOptions,
+) -> Result<Vec<PlannedFile>, String> {
+    let target_folder = vault_folder(&options.target_folder)?;
+    let attachment_folder = vault_folder(&options.attachment_folder)?;
+    let mut names = NameAllocator::new(vault_root);
+
+    let mut note_paths = Vec::with_capacity(notes.len());
+    let mut stems_by_title = HashMap::new();
+    for note in notes {
+        let mut folder = target_folder.clone();
+        for name in &note.folders {
+            let name = sanitize_note_title(name);
+            if !name.is_empty() {
+                folder.push(name);
+            }
+        }
+        let title = Some(sanitize_note_title(&note.title))
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| UNTITLED.to_string());
+        let (rel_path, stem) = names.allocate(&folder, &title, ".md");
+        stems_by_title
+            .entry(note.title.trim().to_lowercase())
+            .or_insert(stem);
+        note_paths.push(rel_path);
+    }
+
+    let mut attachments = Vec::new();
+    let mut attachment_paths: HashMap<(String, md5::Digest), String> = HashMap::new();
+    let mut notes_out = Vec::with_capacity(notes.len());
+    for (note, rel_path) in notes.iter().zip(note_paths) {
+        let mut paths = Vec::with_capacity(note.attachments.len());
+        for attachment in &note.attachments {
+            let key = (
+                attachment.file_name.clone(),
+                md5::compute(&attachment.bytes),
+            );
+            if let Some(path) = attachment_paths.get(&key) {
+                paths.push(path.clone());
+                continue;
+            }
+            let (stem, extension) = split_extension(&attachment.file_name);
+            let stem = Some(sanitize_note_title(stem))
+                .filter(|stem| !stem.is_empty())
+                .unwrap_or_else(|| "attachment".to_string());
+            let (path, _) = names.allocate(&attachment_folder, &stem, &extension);
+            attachments.push(PlannedFile {
+                file: ImportedFile {
+                    rel_path: path.clone(),
+                    kind: ImportedFileKind::Attachment,
+                    size: attachment.bytes.len() as u64,
+                    origin: note.origin.clone(),
+                },
+                bytes: attachment.bytes.clone(),
+            });
+            attachment_paths.insert(key, path.clone());
+            paths.push(path);
+        }
+
+        let markdown = render_note(note, &paths, &stems_by_title);
+        notes_out.push(PlannedFile {
+            file: ImportedFile {
+                rel_path,
+                kind: ImportedFileKind::Note,
+                size: markdown.len() as u64,
+                origin: note.origin.clone(),
+            },
+            bytes: markdown.into_bytes(),
+        });
+    }
+
+    notes_out.extend(attachments);
+    Ok(notes_out)
+}
+
+/// Writes the planned files, never over an existing one.
+pub(crate) fn write_files(vault_root: &Path, files: &[PlannedFile]) -> Result<(), String> {
+    for planned in files {
+        let path = vault_root.join(&planned.file.rel_path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|error| format!("Failed to create {}: {error}", parent.display()))?;
+        }
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(&planned.bytes))
+            .map_err(|error| format!("Failed to write {}: {error}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// The components of a vault-relative folder, which may not leave the vault.
+pub(crate) fn vault_folder(folder: &str) -> Result<Vec<String>, String> {
+    let folder = folder.trim().replace('\\', "/");
+    let mut components = Vec::new();
+    for component in Path::new(&folder).components() {
+        match component {
+            Component::Normal(name) => components.push(name.to_string_lossy().into_owned()),
+            Component::CurDir => {}
+            _ => return Err(format!("Invalid vault folder: {folder}")),
+        }
+    }
+    Ok(components)
+}
+
+fn split_extension(file_name: &str) -> (&str, String) {
+    match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() && !extension.contains(' ') => {
+            (stem, format!(".{extension}"))
+        }
+        _ => (file_name, String::new()),
+    }
+}
+
+struct NameAllocator<'a> {
+    vault_root: &'a Path,
+    /// Planned paths, lowercased since macOS and Windows ignore case.
+    taken: HashSet<String>,
+}
+
+impl<'a> NameAllocator<'a> {
+    fn new(vault_root: &'a Path) -> Self {
+        Self {
+            vault_root,
+            taken: HashSet::new(),
+        }
+    }
+
+    /// The first free `folder/stem.ext`, `folder/stem 1.ext`, ..., and the
+    /// stem it got.
+    fn allocate(&mut self, folder: &[String], stem: &str, extension: &str) -> (String, String) {
+        let prefix = folder
+            .iter()
+            .map(|name| format!("{name}/"))
+            .collect::<String>();
+        (0..)
+            .map(|attempt| {
+                if attempt == 0 {
+                    stem.to_string()
+                } else {
+                    format!("{stem} {attempt}")
+                }
+            })
+            .map(|stem| (format!("{prefix}{stem}{extension}"), stem))
+            .find(|(rel_path, _)| {
+                !self.taken.contains(&rel_path.to_lowercase())
+                    && !self.vault_root.join(rel_path).exists()
+            })
+            .inspect(|(rel_path, _)| {
+                self.taken.insert(rel_path.to_lowercase());
+            })
+            .expect("an unused name exists")
+    }
+}
+
+fn render_note(
+    note: &SourceNote,
+    attachment_paths: &[String],
+    stems_by_title: &HashMap<String, String>,
+) -> String {
+    let mut markdown = String::new();
+    if !note.tags.is_empty() {
+        markdown.push_str("---\ntags:\n");
+        for tag in &note.tags {
+            markdown.push_str(&format!("  - \"{tag}\"\n"));
+        }
+        markdown.push_str("---\n\n");
+    }
+
+    for segment in &note.body {
+        match segment {
+            Segment::Markdown(text) => markdown.push_str(text),
+            Segment::NoteLink {
+                title,
+                heading,
+                label,
+            } => {
+                let (target, heading) = resolve_link(title, heading.as_deref(), stems_by_title);
+                markdown.push_str("[[");
+                markdown.push_str(&target);
+                if let Some(heading) = heading.filter(|heading| !heading.is_empty()) {
+                    markdown.push('#');
+                    markdown.push_str(&heading);
+                }
+                if let Some(label) = label.as_deref().filter(|label| !label.is_empty()) {
+                    markdown.push('|');
+                    markdown.push_str(label);
+                }
+                markdown.push_str("]]");
+            }
+            Segment::Attachment { index, embed } => {
+                let Some(path) = attachment_paths.get(*index) else {
+                    continue;
+                };
+                if *embed {
+                    markdown.push('!');
+                }
+                markdown.push_str(&format!("[[{path}]]"));
+            }
+        }
+    }
+
+    let mut markdown = markdown.trim_end().to_string();
+    markdown.push('\n');
+    markdown
+}
+
+/// The note name a link should use. Bear writes `[[Title/Heading]]`, so an
+/// unknown title is also tried without its last `/` part.
+fn resolve_link(
+    title: &str,
+    heading: Option<&str>,
+    stems_by_title: &HashMap<String, String>,
+) -> (String, Option<String>) {
+    if let Some(stem) = stems_by_title.get(&title.trim().to_lowercase()) {
+        return (stem.clone(), heading.map(str::to_string));
+    }
+    if heading.is_none() {
+        if let Some((parent, section)) = title.rsplit_once('/') {
+            if let Some(stem) = stems_by_title.get(&parent.trim().to_lowercase()) {
+                return (stem.clone(), Some(section.trim().to_string()));
+            }
+        }
+    }
+    (title.trim().to_string(), heading.map(str::to_string))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::plan_files;
+    use crate::source::{Segment, SourceAttachment, SourceNote};
+    use crate::ImportOptions;
+
+    #[test]
+    fn places_notes_and_attachments_beside_existing_files() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-note-import-plan-{nanos}"));
+        fs::create_dir_all(root.join("Imported")).unwrap();
+        fs::write(root.join("Imported/Trip.md"), "Existing").unwrap();
+
+        let image = SourceAttachment {
+            file_name: "map.png".to_string(),
+            bytes: b"png".to_vec(),
+        };
+        let notes = vec![
+            SourceNote {
+                origin: "a".to_string(),
+                title: "Trip".to_string(),
+                tags: vec!["travel".to_string()],
+                body: vec![
+                    Segment::Markdown("See ".to_string()),
+                    Segment::NoteLink {
+                        title: "Packing list/Shoes".to_string(),
+                        heading: None,
+                        label: None,
+                    },
+                    Segment::Markdown(" ".to_string()),
+                    Segment::Attachment {
+                        index: 0,
+                        embed: true,
+                    },
+                ],
+                attachments: vec![image.clone()],
+                ..SourceNote::default()
+            },
+            SourceNote {
+                origin: "b".to_string(),
+                title: "Packing list".to_string(),
+                folders: vec!["Lists".to_string()],
+                body: vec![Segment::Attachment {
+                    index: 0,
+                    embed: false,
+                }],
+                attachments: vec![image],
+                ..SourceNote::default()
+            },
+        ];
+        let options = ImportOptions {
+            target_folder: "Imported".to_string(),
+            attachment_folder: "attachments".to_string(),
+            dry_run: true,
+        };
+
+        let files = plan_files(&notes, &root, &options).unwrap();
+
+        let paths = files
+            .iter()
+            .map(|planned| planned.file.rel_path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "Imported/Trip 1.md",
+                "Imported/Lists/Packing list.md",
+                "attachments/map.png"
+            ]
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&files[0].bytes),
+            "---\ntags:\n  - \"travel\"\n---\n\nSee [[Packing list#Shoes]] ![[attachments/map.png]]\n"
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&files[1].bytes),
+            "[[attachments/map.png]]\n"
+        );
+        assert!(plan_files(
+            &notes,
+            &root,
+            &ImportOptions {
+                target_folder: "../outside".to_string(),
+                ..ImportOptions::default()
+            }
+        )
+        .is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,141 @@
+use std::path::Path;
+
+/// A note read from another app, before it has a place in the vault.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SourceNote {
+    /// Where it came from, for the report.
+    pub(crate) origin: String,
+    pub(crate) title: String,
+    /// Folder names below the import folder, outermost first.
+    pub(crate) folders: Vec<String>,
+    /// Written to the `tags` frontmatter property.
+    pub(crate) tags: Vec<String>,
+    pub(crate) body: Vec<Segment>,
+    pub(crate) attachments: Vec<SourceAttachment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Segment {
+    Markdown(String),
+    /// A link to another imported note, by its title in the source app.
+    NoteLink {
+        title: String,
+        heading: Option<String>,
+        label: Option<String>,
+    },
+    /// `SourceNote::attachments[index]`, embedded or as a plain link.
+    Attachment {
+        index: usize,
+        embed: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SourceAttachment {
+    pub(crate) file_name: String,
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// A note skipped while reading, with why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SourceSkip {
+    pub(crate) origin: String,
+    pub(crate) reason: String,
+}
+
+/// Builds a segment list, merging adjacent Markdown.
+#[derive(Debug, Default)]
+pub(crate) struct SegmentWriter {
+    segments: Vec<Segment>,
+}
+
+impl SegmentWriter {
+    pub(crate) fn push_str(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        match self.segments.last_mut() {
+            Some(Segment::Markdown(markdown)) => markdown.push_str(text),
+            _ => self.segments.push(Segment::Markdown(text.to_string())),
+        }
+    }
+
+    pub(crate) fn push(&mut self, segment: Segment) {
+        match segment {
+            Segment::Markdown(text) => self.push_str(&text),
+            segment => self.segments.push(segment),
+        }
+    }
+
+    /// The Markdown written since the last link or embed.
+    pub(crate) fn tail(&self) -> &str {
+        match self.segments.last() {
+            Some(Segment::Markdown(markdown)) => markdown,
+            _ => "",
+        }
+    }
+
+    pub(crate) fn truncate_tail(&mut self, len: usize) {
+        if let Some(Segment::Markdown(markdown)) = self.segments.last_mut() {
+            markdown.truncate(len);
+            if markdown.is_empty() {
+                self.segments.pop();
+            }
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub(crate) fn finish(self) -> Vec<Segment> {
+        self.segments
+    }
+}
+
+/// Decodes `%XX` escapes, as in relative URLs to exported files.
+pub(crate) fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| text.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+pub(crate) fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extensions
+                .iter()
+                .any(|candidate| extension.eq_ignore_ascii_case(candidate))
+        })
+}
+
+/// Names of the folders from `base` down to the folder holding `path`.
+pub(crate) fn folders_between(base: &Path, path: &Path) -> Vec<String> {
+    path.parent()
+        .and_then(|parent| parent.strip_prefix(base).ok())
+        .map(|relative| {
+            relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
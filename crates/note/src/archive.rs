@@ -0,0 +1,379 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use crate::frontmatter::locate_frontmatter;
+use crate::frontmatter_edit::{remove_frontmatter_property, set_frontmatter_property};
+use crate::lock::ensure_note_unlocked;
+use crate::note_path::is_note_path;
+
+/// Frontmatter property marking a note as archived.
+pub const ARCHIVED_PROPERTY: &str = "archived";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovedPath {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveResult {
+    pub note: MovedPath,
+    /// Attachments moved along with the note.
+    pub attachments: Vec<MovedPath>,
+}
+
+/// Moves `note_path` into `archive_folder`, keeping its path relative to the
+/// vault so relative links keep working, and marks it `archived: true`.
+///
+/// With `include_attachments`, files the note embeds that no other note
+/// mentions move with it.
+pub fn archive_note(
+    vault_root: &Path,
+    note_path: &Path,
+    archive_folder: &str,
+    include_attachments: bool,
+) -> Result<ArchiveResult, String> {
+    ensure_note_unlocked(note_path)?;
+    let archive_root = archive_root(vault_root, archive_folder)?;
+    let rel_path = vault_rel_path(vault_root, note_path)?;
+    if note_path.starts_with(&archive_root) {
+        return Err(format!("Note is already archived: {}", rel_path.display()));
+    }
+
+    let destination = archive_root.join(&rel_path);
+    let attachments = if include_attachments {
+        unique_attachments(vault_root, note_path)?
+            .into_iter()
+            .filter(|attachment| !attachment.starts_with(&archive_root))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let source = read_note(note_path)?;
+    let updated = set_frontmatter_property(&source, ARCHIVED_PROPERTY, &JsonValue::Bool(true))?;
+    let note = move_note(note_path, &destination, &updated)?;
+    let attachments = move_attachments(attachments, |attachment| {
+        vault_rel_path(vault_root, attachment).map(|rel_path| archive_root.join(rel_path))
+    });
+
+    Ok(ArchiveResult { note, attachments })
+}
+
+/// Moves an archived note back to where it was archived from and removes the
+/// `archived` property. Attachments archived with it move back too.
+pub fn unarchive_note(
+    vault_root: &Path,
+    note_path: &Path,
+    archive_folder: &str,
+) -> Result<ArchiveResult, String> {
+    ensure_note_unlocked(note_path)?;
+    let archive_root = archive_root(vault_root, archive_folder)?;
+    let rel_path = note_path
+        .strip_prefix(&archive_root)
+        .map_err(|_| format!("Note is not archived: {}", note_path.display()))?;
+
+    let destination = vault_root.join(rel_path);
+    let attachments = unique_attachments(vault_root, note_path)?
+        .into_iter()
+        .filter(|attachment| attachment.starts_with(&archive_root))
+        .collect();
+
+    let source = read_note(note_path)?;
+    let updated = remove_archived_property(source);
+    let note = move_note(note_path, &destination, &updated)?;
+    let attachments = move_attachments(attachments, |attachment| {
+        attachment
+            .strip_prefix(&archive_root)
+            .map(|rel_path| vault_root.join(rel_path))
+            .map_err(|error| error.to_string())
+    });
+
+    Ok(ArchiveResult { note, attachments })
+}
+
+/// Drops the block archiving added when `archived` was its only property.
+fn remove_archived_property(source: String) -> String {
+    let Some(updated) = remove_frontmatter_property(&source, ARCHIVED_PROPERTY) else {
+        return source;
+    };
+    match locate_frontmatter(&updated) {
+        Some(block) if updated[block.yaml.clone()].trim().is_empty() => {
+            format!(
+                "{}{}",
+                &updated[..block.block.start],
+                &updated[block.block.end..]
+            )
+        }
+        _ => updated,
+    }
+}
+
+fn archive_root(vault_root: &Path, archive_folder: &str) -> Result<PathBuf, String> {
+    let folder = Path::new(archive_folder.trim().trim_matches(['/', '\\']));
+    let is_relative = folder
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if folder.as_os_str().is_empty() || !is_relative {
+        return Err(format!("Invalid archive folder: {archive_folder}"));
+    }
+    Ok(vault_root.join(folder))
+}
+
+fn vault_rel_path(vault_root: &Path, path: &Path) -> Result<PathBuf, String> {
+    path.strip_prefix(vault_root)
+        .map(Path::to_path_buf)
+        .map_err(|_| format!("Path is outside the vault: {}", path.display()))
+}
+
+fn move_note(source: &Path, destination: &Path, contents: &str) -> Result<MovedPath, String> {
+    if destination.exists() {
+        return Err(format!(
+            "A file already exists at {}",
+            destination.display()
+        ));
+    }
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed to create folder: {}", error))?;
+    }
+
+    fs::write(source, contents).map_err(|error| format!("Failed to write file: {}", error))?;
+    fs::rename(source, destination).map_err(|error| format!("Failed to move note: {}", error))?;
+
+    Ok(MovedPath {
+        from: source.to_path_buf(),
+        to: destination.to_path_buf(),
+    })
+}
+
+/// Attachments are best effort: one that cannot move stays where it is and
+/// the note's link to it keeps working.
+fn move_attachments(
+    attachments: Vec<PathBuf>,
+    destination_for: impl Fn(&Path) -> Result<PathBuf, String>,
+) -> Vec<MovedPath> {
+    attachments
+        .into_iter()
+        .filter_map(|attachment| {
+            let destination = destination_for(&attachment).ok()?;
+            if destination.exists() {
+                return None;
+            }
+            fs::create_dir_all(destination.parent()?).ok()?;
+            fs::rename(&attachment, &destination).ok()?;
+            Some(MovedPath {
+                from: attachment,
+                to: destination,
+            })
+        })
+        .collect()
+}
+
+fn read_note(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|error| format!("Failed to read file: {}", error))
+}
+
+/// Files embedded by the note whose names appear in no other note.
+fn unique_attachments(vault_root: &Path, note_path: &Path) -> Result<Vec<PathBuf>, String> {
+    let source = read_note(note_path)?;
+    let note_dir = note_path.parent().unwrap_or(vault_root);
+    let mut files = Vec::new();
+    collect_files(vault_root, &mut files);
+
+    let mut attachments: Vec<PathBuf> = Vec::new();
+    for target in embedded_targets(&source) {
+        let resolved = if target.is_wiki {
+            resolve_wiki_embed(vault_root, &files, &target.path)
+        } else {
+            resolve_markdown_embed(vault_root, note_dir, &target.path)
+        };
+        if let Some(path) = resolved {
+            if !attachments.contains(&path) {
+                attachments.push(path);
+            }
+        }
+    }
+
+    let other_notes = files
+        .iter()
+        .filter(|path| is_note_path(path) && path.as_path() != note_path)
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .collect::<Vec<_>>();
+    attachments.retain(|attachment| {
+        let Some(name) = attachment.file_name().and_then(|name| name.to_str()) else {
+            return false;
+        };
+        !other_notes.iter().any(|contents| contents.contains(name))
+    });
+
+    Ok(attachments)
+}
+
+struct EmbedTarget {
+    path: String,
+    is_wiki: bool,
+}
+
+/// `![[file]]` and `![alt](path)` targets that point at non-note files.
+fn embedded_targets(source: &str) -> Vec<EmbedTarget> {
+    let mut targets = Vec::new();
+    let mut rest = source;
+
+    while let Some(start) = rest.find("![") {
+        rest = &rest[start + 2..];
+        if let Some(inner) = rest.strip_prefix('[') {
+            if let Some(end) = inner.find("]]") {
+                let target = inner[..end].split(['|', '#']).next().unwrap_or_default();
+                targets.push(EmbedTarget {
+                    path: target.trim().to_string(),
+                    is_wiki: true,
+                });
+                rest = &inner[end + 2..];
+            }
+            continue;
+        }
+
+        let Some(close) = rest.find("](") else {
+            continue;
+        };
+        let destination = &rest[close + 2..];
+        let Some(end) = destination.find(')') else {
+            continue;
+        };
+        let target = destination[..end]
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .trim_matches(['<', '>']);
+        targets.push(EmbedTarget {
+            path: target.replace("%20", " "),
+            is_wiki: false,
+        });
+        rest = &destination[end + 1..];
+    }
+
+    targets.retain(|target| {
+        let path = Path::new(&target.path);
+        !target.path.is_empty()
+            && !target.path.contains("://")
+            && path.extension().is_some()
+            && !is_note_path(path)
+    });
+    targets
+}
+
+fn resolve_markdown_embed(vault_root: &Path, note_dir: &Path, target: &str) -> Option<PathBuf> {
+    let candidate = match target.strip_prefix('/') {
+        Some(from_root) => vault_root.join(from_root),
+        None => note_dir.join(target),
+    };
+    let normalized = normalize_path(&candidate)?;
+    (normalized.starts_with(vault_root) && normalized.is_file()).then_some(normalized)
+}
+
+fn resolve_wiki_embed(vault_root: &Path, files: &[PathBuf], target: &str) -> Option<PathBuf> {
+    if target.contains('/') {
+        return resolve_markdown_embed(vault_root, vault_root, target);
+    }
+    files
+        .iter()
+        .find(|path| path.file_name().is_some_and(|name| name == target))
+        .cloned()
+}
+
+fn normalize_path(path: &Path) -> Option<PathBuf> {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    Some(normalized)
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{archive_note, unarchive_note};
+
+    fn temp_vault(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-note-archive-{name}-{nanos}"));
+        fs::create_dir_all(root.join("Projects/assets")).unwrap();
+        root
+    }
+
+    #[test]
+    fn archives_note_with_unique_attachments_and_restores_it() {
+        let root = temp_vault("roundtrip");
+        let note = root.join("Projects/Plan.md");
+        fs::write(
+            &note,
+            "# Plan\n\n![diagram](assets/diagram.png)\n![[shared.png]]\n",
+        )
+        .unwrap();
+        fs::write(root.join("Projects/assets/diagram.png"), "png").unwrap();
+        fs::write(root.join("Projects/assets/shared.png"), "png").unwrap();
+        fs::write(root.join("Other.md"), "![[shared.png]]\n").unwrap();
+
+        let archived = archive_note(&root, &note, "Archive", true).unwrap();
+
+        let archived_note = root.join("Archive/Projects/Plan.md");
+        assert_eq!(archived.note.to, archived_note);
+        assert!(!note.exists());
+        assert!(fs::read_to_string(&archived_note)
+            .unwrap()
+            .starts_with("---\narchived: true\n---\n# Plan"));
+        assert_eq!(archived.attachments.len(), 1);
+        assert!(root.join("Archive/Projects/assets/diagram.png").is_file());
+        assert!(root.join("Projects/assets/shared.png").is_file());
+        assert!(archive_note(&root, &archived_note, "Archive", true).is_err());
+
+        let restored = unarchive_note(&root, &archived_note, "Archive").unwrap();
+
+        assert_eq!(restored.note.to, note);
+        assert_eq!(restored.attachments.len(), 1);
+        assert!(root.join("Projects/assets/diagram.png").is_file());
+        assert_eq!(
+            fs::read_to_string(&note).unwrap(),
+            "# Plan\n\n![diagram](assets/diagram.png)\n![[shared.png]]\n"
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,289 @@
+use std::cmp::Ordering;
+use std::fs::{self, Metadata};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::note_path::is_note_path;
+use crate::preview::read_note_title;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DirectoryEntryKind {
+    File,
+    Directory,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryEntry {
+    pub name: String,
+    pub path: String,
+    pub kind: DirectoryEntryKind,
+    /// File size in bytes; `None` for directories.
+    pub size: Option<u64>,
+    pub created_at: Option<i64>,
+    pub modified_at: Option<i64>,
+    /// First heading of markdown notes.
+    pub title: Option<String>,
+    /// Indexed tags of markdown notes. Left empty here and filled in by callers
+    /// that have access to the vault index.
+    pub tags: Vec<String>,
+    /// Number of visible children of a directory; `None` for files.
+    pub child_count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DirectorySortKey {
+    #[default]
+    Name,
+    CreatedAt,
+    ModifiedAt,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ListDirectoryOptions {
+    pub sort_by: DirectorySortKey,
+    pub direction: SortDirection,
+    /// Include dot-prefixed entries such as `.mdit`.
+    pub include_hidden: bool,
+}
+
+/// List the direct children of `path` with their metadata, sorted by `options`.
+/// Entries whose metadata cannot be read are skipped.
+pub fn list_directory(
+    path: &Path,
+    options: &ListDirectoryOptions,
+) -> Result<Vec<DirectoryEntry>, String> {
+    let read_dir = fs::read_dir(path).map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    let mut entries = Vec::new();
+    for dir_entry in read_dir.flatten() {
+        let name = dir_entry.file_name().to_string_lossy().into_owned();
+        if !options.include_hidden && is_hidden(&name) {
+            continue;
+        }
+
+        // Follow symlinks so linked folders list like regular ones.
+        let entry_path = dir_entry.path();
+        let Ok(metadata) = fs::metadata(&entry_path) else {
+            continue;
+        };
+
+        entries.push(build_entry(name, &entry_path, &metadata, options));
+    }
+
+    sort_entries(&mut entries, options);
+    Ok(entries)
+}
+
+fn build_entry(
+    name: String,
+    entry_path: &Path,
+    metadata: &Metadata,
+    options: &ListDirectoryOptions,
+) -> DirectoryEntry {
+    let is_directory = metadata.is_dir();
+    let title = if !is_directory && is_note_path(&name) {
+        read_note_title(entry_path).ok().flatten()
+    } else {
+        None
+    };
+    let child_count = is_directory.then(|| count_children(entry_path, options.include_hidden));
+
+    DirectoryEntry {
+        path: entry_path.to_string_lossy().into_owned(),
+        kind: if is_directory {
+            DirectoryEntryKind::Directory
+        } else {
+            DirectoryEntryKind::File
+        },
+        size: (!is_directory).then_some(metadata.len()),
+        created_at: metadata.created().ok().and_then(system_time_to_millis),
+        modified_at: metadata.modified().ok().and_then(system_time_to_millis),
+        title,
+        tags: Vec::new(),
+        child_count,
+        name,
+    }
+}
+
+fn count_children(path: &Path, include_hidden: bool) -> usize {
+    let Ok(read_dir) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    read_dir
+        .flatten()
+        .filter(|entry| include_hidden || !is_hidden(&entry.file_name().to_string_lossy()))
+        .count()
+}
+
+fn sort_entries(entries: &mut [DirectoryEntry], options: &ListDirectoryOptions) {
+    entries.sort_by(|a, b| {
+        let name_order = compare_names(&a.name, &b.name);
+        let order = match options.sort_by {
+            DirectorySortKey::Name => name_order,
+            DirectorySortKey::CreatedAt => compare_times(a.created_at, b.created_at, name_order),
+            DirectorySortKey::ModifiedAt => compare_times(a.modified_at, b.modified_at, name_order),
+        };
+
+        match options.direction {
+            SortDirection::Asc => order,
+            SortDirection::Desc => order.reverse(),
+        }
+    });
+}
+
+/// Entries without a timestamp sort last, ties fall back to the name.
+fn compare_times(a: Option<i64>, b: Option<i64>, name_order: Ordering) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b).then(name_order),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => name_order,
+    }
+}
+
+/// Case-insensitive comparison that orders digit runs by value, so
+/// "note 2" sorts before "note 10".
+pub(crate) fn compare_names(a: &str, b: &str) -> Ordering {
+    let mut left = a.chars().peekable();
+    let mut right = b.chars().peekable();
+
+    loop {
+        match (left.peek().copied(), right.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(l), Some(r)) if l.is_ascii_digit() && r.is_ascii_digit() => {
+                let left_digits = take_digits(&mut left);
+                let right_digits = take_digits(&mut right);
+                let order = left_digits
+                    .len()
+                    .cmp(&right_digits.len())
+                    .then_with(|| left_digits.cmp(&right_digits));
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+            (Some(l), Some(r)) => {
+                let order = l.to_lowercase().cmp(r.to_lowercase());
+                if order != Ordering::Equal {
+                    return order;
+                }
+                left.next();
+                right.next();
+            }
+        }
+    }
+}
+
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> String {
+    let mut digits = String::new();
+    while let Some(ch) = chars.next_if(char::is_ascii_digit) {
+        digits.push(ch);
+    }
+
+    let trimmed = digits.trim_start_matches('0');
+    trimmed.to_string()
+}
+
+fn is_hidden(name: &str) -> bool {
+    name.starts_with('.')
+}
+
+pub(crate) fn system_time_to_millis(time: SystemTime) -> Option<i64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .and_then(|duration| i64::try_from(duration.as_millis()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{
+        list_directory, DirectoryEntryKind, DirectorySortKey, ListDirectoryOptions, SortDirection,
+    };
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(prefix: &str) -> Self {
+            let id = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system clock error")
+                .as_nanos();
+            let path = std::env::temp_dir().join(format!("{prefix}-{id}"));
+            fs::create_dir_all(&path).expect("failed to create temp dir");
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn lists_entries_with_metadata_in_natural_name_order() {
+        let dir = TempDir::new("mdit-note-list-directory");
+        fs::write(dir.0.join("note 10.md"), "# Ten\n\nbody").expect("write note 10");
+        fs::write(dir.0.join("Note 2.md"), "---\ntitle: x\n---\nplain").expect("write note 2");
+        fs::create_dir_all(dir.0.join("folder/.hidden")).expect("create folder");
+        fs::write(dir.0.join("folder/child.md"), "").expect("write child");
+        fs::write(dir.0.join(".secret.md"), "").expect("write hidden note");
+
+        let entries =
+            list_directory(&dir.0, &ListDirectoryOptions::default()).expect("listing succeeds");
+        let names = entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["folder", "Note 2.md", "note 10.md"]);
+
+        let folder = &entries[0];
+        assert_eq!(folder.kind, DirectoryEntryKind::Directory);
+        assert_eq!(folder.child_count, Some(1));
+        assert_eq!(folder.size, None);
+
+        assert_eq!(entries[1].title, None);
+        assert_eq!(entries[2].title.as_deref(), Some("Ten"));
+        assert_eq!(entries[2].size, Some(11));
+        assert!(entries[2].modified_at.is_some());
+    }
+
+    #[test]
+    fn sorts_descending_by_requested_key() {
+        let dir = TempDir::new("mdit-note-list-directory-desc");
+        fs::write(dir.0.join("a.md"), "").expect("write a");
+        fs::write(dir.0.join("b.md"), "").expect("write b");
+
+        let options = ListDirectoryOptions {
+            sort_by: DirectorySortKey::Name,
+            direction: SortDirection::Desc,
+            include_hidden: false,
+        };
+        let entries = list_directory(&dir.0, &options).expect("listing succeeds");
+        let names = entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["b.md", "a.md"]);
+    }
+}
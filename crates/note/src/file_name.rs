@@ -0,0 +1,141 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+const NOTE_EXTENSION: &str = ".md";
+const INVALID_FILE_NAME_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+const WINDOWS_RESERVED_NAMES: &[&str] = &["con", "prn", "aux", "nul"];
+const WINDOWS_NUMBERED_RESERVED_PREFIXES: &[&str] = &["com", "lpt"];
+// Same bound as the frontend helpers, so a crowded folder fails instead of spinning.
+const MAX_UNIQUE_NAME_ATTEMPTS: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UniqueNoteFileName {
+    pub file_name: String,
+    pub full_path: String,
+}
+
+/// Turn a note title into a file stem that is valid on every platform.
+/// Characters Windows rejects become spaces, trailing dots and spaces are
+/// dropped, and reserved device names such as `CON` get a `_` suffix.
+/// Returns an empty string when nothing usable is left.
+pub fn sanitize_note_title(title: &str) -> String {
+    let title = strip_note_extension(title.trim());
+    let replaced = title
+        .chars()
+        .map(|ch| {
+            if INVALID_FILE_NAME_CHARS.contains(&ch) || ch.is_control() {
+                ' '
+            } else {
+                ch
+            }
+        })
+        .collect::<String>();
+    let trimmed = replaced.trim().trim_end_matches(['.', ' ']);
+
+    escape_reserved_name(trimmed)
+}
+
+/// First free `Title.md`, `Title 1.md`, `Title 2.md`, ... in `directory`.
+pub fn unique_note_file_name(directory: &Path, title: &str) -> Result<UniqueNoteFileName, String> {
+    let stem = sanitize_note_title(title);
+    if stem.is_empty() {
+        return Err("Note name is empty after sanitization.".to_string());
+    }
+
+    (0..=MAX_UNIQUE_NAME_ATTEMPTS)
+        .map(|attempt| {
+            if attempt == 0 {
+                format!("{stem}{NOTE_EXTENSION}")
+            } else {
+                format!("{stem} {attempt}{NOTE_EXTENSION}")
+            }
+        })
+        .map(|file_name| (directory.join(&file_name), file_name))
+        .find(|(full_path, _)| !path_exists(full_path))
+        .map(|(full_path, file_name)| UniqueNoteFileName {
+            file_name,
+            full_path: full_path.to_string_lossy().into_owned(),
+        })
+        .ok_or_else(|| {
+            format!("Unable to generate unique filename after {MAX_UNIQUE_NAME_ATTEMPTS} attempts")
+        })
+}
+
+fn strip_note_extension(title: &str) -> &str {
+    let split = title.len().saturating_sub(NOTE_EXTENSION.len());
+    match (title.get(..split), title.get(split..)) {
+        (Some(stem), Some(extension)) if extension.eq_ignore_ascii_case(NOTE_EXTENSION) => stem,
+        _ => title,
+    }
+}
+
+/// Windows reserves device names regardless of extension, so `con.backup`
+/// is as unusable as `con`.
+fn escape_reserved_name(stem: &str) -> String {
+    let (head, rest) = match stem.find('.') {
+        Some(dot) => stem.split_at(dot),
+        None => (stem, ""),
+    };
+
+    if is_reserved_name(head) {
+        format!("{head}_{rest}")
+    } else {
+        stem.to_string()
+    }
+}
+
+fn is_reserved_name(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    if WINDOWS_RESERVED_NAMES.contains(&lower.as_str()) {
+        return true;
+    }
+
+    WINDOWS_NUMBERED_RESERVED_PREFIXES.iter().any(|prefix| {
+        lower
+            .strip_prefix(prefix)
+            .is_some_and(|digit| matches!(digit.as_bytes(), [b'1'..=b'9']))
+    })
+}
+
+fn path_exists(path: &Path) -> bool {
+    // Broken symlinks still occupy the name.
+    path.symlink_metadata().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{sanitize_note_title, unique_note_file_name};
+
+    #[test]
+    fn sanitizes_invalid_characters_and_reserved_names() {
+        assert_eq!(sanitize_note_title("  a/b:c?.md "), "a b c");
+        assert_eq!(sanitize_note_title("Draft..."), "Draft");
+        assert_eq!(sanitize_note_title("CON"), "CON_");
+        assert_eq!(sanitize_note_title("lpt1.notes"), "lpt1_.notes");
+        assert_eq!(sanitize_note_title("com10"), "com10");
+        assert_eq!(sanitize_note_title(" / \\ "), "");
+    }
+
+    #[test]
+    fn numbers_titles_that_already_exist() {
+        let id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock error")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("mdit-note-unique-name-{id}"));
+        fs::create_dir_all(&dir).expect("failed to create temp dir");
+        fs::write(dir.join("Title.md"), "").expect("write title");
+        fs::write(dir.join("Title 1.md"), "").expect("write title 1");
+
+        let unique = unique_note_file_name(&dir, "Title").expect("name is available");
+        assert_eq!(unique.file_name, "Title 2.md");
+        assert!(unique_note_file_name(&dir, "...").is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
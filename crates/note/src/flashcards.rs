@@ -0,0 +1,410 @@
+use serde::{Deserialize, Serialize};
+
+use crate::frontmatter::locate_frontmatter;
+
+/// Which note syntax becomes flashcards.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FlashcardPatterns {
+    /// A `Q:` line answered by the `A:` line after it.
+    pub question_answer: bool,
+    /// List items carrying `#tag`, as `front :: back` or with the answer in
+    /// nested items. Empty turns them off.
+    pub tag: String,
+    /// Lines with Anki cloze deletions such as `{{c1::mitochondria}}`.
+    pub cloze: bool,
+}
+
+impl Default for FlashcardPatterns {
+    fn default() -> Self {
+        Self {
+            question_answer: true,
+            tag: "flashcard".to_string(),
+            cloze: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FlashcardKind {
+    Basic,
+    Cloze,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Flashcard {
+    pub kind: FlashcardKind,
+    pub front: String,
+    /// Empty for cloze cards.
+    pub back: String,
+    /// 1-based line the card starts on.
+    pub line: usize,
+}
+
+impl Flashcard {
+    /// Identifies the card by its content, wherever it sits in the note.
+    pub fn hash(&self) -> String {
+        let kind = match self.kind {
+            FlashcardKind::Basic => "basic",
+            FlashcardKind::Cloze => "cloze",
+        };
+        let mut hasher = blake3::Hasher::new();
+        for part in [kind, &self.front, &self.back] {
+            hasher.update(part.as_bytes());
+            hasher.update(&[0]);
+        }
+        hasher.finalize().to_hex().to_string()
+    }
+}
+
+/// The flashcards in `markdown`, in note order. Frontmatter and fenced code
+/// are skipped, and a line belongs to one card at most.
+pub fn extract_flashcards(markdown: &str, patterns: &FlashcardPatterns) -> Vec<Flashcard> {
+    let body_start = locate_frontmatter(markdown)
+        .map(|block| block.block.end)
+        .unwrap_or(0);
+    let line_offset = markdown[..body_start].matches('\n').count() + 1;
+    let lines = markdown[body_start..].lines().collect::<Vec<_>>();
+    let tag = patterns.tag.trim().trim_start_matches('#');
+
+    let mut cards = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut index = 0;
+    while index < lines.len() {
+        let trimmed = lines[index].trim();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            index += 1;
+            continue;
+        }
+        if let Some(marker) = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker))
+        {
+            fence = Some(marker);
+            index += 1;
+            continue;
+        }
+
+        let line = index + line_offset;
+        if patterns.question_answer {
+            if let Some((card, next)) = question_answer(&lines, index) {
+                cards.push(Flashcard { line, ..card });
+                index = next;
+                continue;
+            }
+        }
+        if !tag.is_empty() {
+            if let Some((card, next)) = tagged_item(&lines, index, tag) {
+                cards.push(Flashcard { line, ..card });
+                index = next;
+                continue;
+            }
+        }
+        if patterns.cloze && has_cloze(trimmed) {
+            let (_, text) = list_item(lines[index]).unwrap_or((0, trimmed));
+            cards.push(Flashcard {
+                kind: FlashcardKind::Cloze,
+                front: text.trim().to_string(),
+                back: String::new(),
+                line,
+            });
+        }
+        index += 1;
+    }
+    cards
+}
+
+/// A `Q:` card starting at `lines[start]` and the line after it. Both sides
+/// run on until a blank line or the next label.
+fn question_answer(lines: &[&str], start: usize) -> Option<(Flashcard, usize)> {
+    let question = strip_label(lines[start], 'q')?;
+    let mut front = vec![question];
+    let mut index = start + 1;
+    while index < lines.len() && !is_block_end(lines[index]) {
+        front.push(lines[index].trim());
+        index += 1;
+    }
+    let answer = strip_label(lines.get(index)?, 'a')?;
+    let mut back = vec![answer];
+    index += 1;
+    while index < lines.len() && !is_block_end(lines[index]) {
+        back.push(lines[index].trim());
+        index += 1;
+    }
+    Some((
+        Flashcard {
+            kind: FlashcardKind::Basic,
+            front: front.join("\n").trim().to_string(),
+            back: back.join("\n").trim().to_string(),
+            line: 0,
+        },
+        index,
+    ))
+}
+
+fn is_block_end(line: &str) -> bool {
+    line.trim().is_empty() || strip_label(line, 'q').is_some() || strip_label(line, 'a').is_some()
+}
+
+/// The text after a `Q:` or `A:` label, either case.
+fn strip_label(line: &str, label: char) -> Option<&str> {
+    let mut chars = line.trim_start().char_indices();
+    let (_, first) = chars.next()?;
+    let (colon, second) = chars.next()?;
+    (first.eq_ignore_ascii_case(&label) && second == ':')
+        .then(|| line.trim_start()[colon + 1..].trim())
+}
+
+/// A list item tagged `#tag` at `lines[start]` and the line after it. The
+/// answer follows `::` on the same line or is the nested items below it.
+fn tagged_item(lines: &[&str], start: usize, tag: &str) -> Option<(Flashcard, usize)> {
+    let (indent, text) = list_item(lines[start])?;
+    let mut tagged = false;
+    let words = text
+        .split_whitespace()
+        .filter(|word| {
+            let is_tag = word
+                .trim_end_matches(|ch: char| ch.is_ascii_punctuation() && ch != '/')
+                .strip_prefix('#')
+                .is_some_and(|word| word.eq_ignore_ascii_case(tag));
+            tagged |= is_tag;
+            !is_tag
+        })
+        .collect::<Vec<_>>();
+    if !tagged {
+        return None;
+    }
+    let text = words.join(" ");
+
+    let mut index = start + 1;
+    let (front, back) = match text.split_once("::").filter(|_| !has_cloze(&text)) {
+        Some((front, back)) => (front.trim().to_string(), back.trim().to_string()),
+        None => {
+            let mut answer = Vec::new();
+            while let Some(child) = lines.get(index) {
+                let child_indent = child.len() - child.trim_start().len();
+                if child.trim().is_empty() || child_indent <= indent {
+                    break;
+                }
+                let (_, child_text) = list_item(child).unwrap_or((child_indent, child.trim()));
+                answer.push(child_text.trim());
+                index += 1;
+            }
+            (text, answer.join("\n"))
+        }
+    };
+    if front.is_empty() || back.is_empty() {
+        return None;
+    }
+    Some((
+        Flashcard {
+            kind: FlashcardKind::Basic,
+            front,
+            back,
+            line: 0,
+        },
+        index,
+    ))
+}
+
+/// The indent and text of a `-`, `*`, `+` or `1.` list item line.
+fn list_item(line: &str) -> Option<(usize, &str)> {
+    let rest = line.trim_start();
+    let indent = line.len() - rest.len();
+    let text = if let Some(text) = rest
+        .strip_prefix("- ")
+        .or_else(|| rest.strip_prefix("* "))
+        .or_else(|| rest.strip_prefix("+ "))
+    {
+        text
+    } else {
+        let digits = rest.len()
+            - rest
+                .trim_start_matches(|ch: char| ch.is_ascii_digit())
+                .len();
+        if digits == 0 {
+            return None;
+        }
+        rest[digits..]
+            .strip_prefix(". ")
+            .or_else(|| rest[digits..].strip_prefix(") "))?
+    };
+    Some((indent, text))
+}
+
+/// Whether `text` holds a `{{c<n>::...}}` deletion.
+fn has_cloze(text: &str) -> bool {
+    text.match_indices("{{c").any(|(start, _)| {
+        let rest = &text[start + 3..];
+        let digits = rest.len()
+            - rest
+                .trim_start_matches(|ch: char| ch.is_ascii_digit())
+                .len();
+        digits > 0 && rest[digits..].starts_with("::") && rest[digits..].contains("}}")
+    })
+}
+
+/// The Anki tag for cards from the note at `rel_path`: its path without the
+/// extension, with folders nested under `::`.
+pub fn anki_source_tag(rel_path: &str) -> String {
+    let without_extension = rel_path
+        .rsplit_once('.')
+        .filter(|(_, extension)| !extension.contains('/'))
+        .map_or(rel_path, |(stem, _)| stem);
+    without_extension
+        .split('/')
+        .map(|part| part.split_whitespace().collect::<Vec<_>>().join("_"))
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// A tab-separated file Anki imports without asking for settings. Each card
+/// is written with its Anki tags; the note type is picked per row.
+pub fn flashcards_to_anki_tsv(cards: &[(Flashcard, Vec<String>)], deck: Option<&str>) -> String {
+    let mut tsv = String::from("#separator:tab\n#html:true\n#notetype column:1\n");
+    if let Some(deck) = deck.map(str::trim).filter(|deck| !deck.is_empty()) {
+        tsv.push_str(&format!("#deck:{deck}\n"));
+    }
+    tsv.push_str("#tags column:4\n");
+    for (card, tags) in cards {
+        let notetype = match card.kind {
+            FlashcardKind::Basic => "Basic",
+            FlashcardKind::Cloze => "Cloze",
+        };
+        tsv.push_str(&format!(
+            "{notetype}\t{}\t{}\t{}\n",
+            anki_field(&card.front),
+            anki_field(&card.back),
+            tags.join(" ")
+        ));
+    }
+    tsv
+}
+
+/// `text` as an HTML field on one line.
+fn anki_field(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\t', "    ")
+        .replace("\r\n", "\n")
+        .replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        anki_source_tag, extract_flashcards, flashcards_to_anki_tsv, Flashcard, FlashcardKind,
+        FlashcardPatterns,
+    };
+
+    fn card(kind: FlashcardKind, front: &str, back: &str, line: usize) -> Flashcard {
+        Flashcard {
+            kind,
+            front: front.to_string(),
+            back: back.to_string(),
+            line,
+        }
+    }
+
+    #[test]
+    fn extracts_question_tagged_and_cloze_cards() {
+        let markdown = [
+            "---",
+            "tags: [biology]",
+            "---",
+            "Q: What is the powerhouse",
+            "of the cell?",
+            "A: The mitochondria",
+            "",
+            "- Osmosis :: water moving across a membrane #flashcard",
+            "- Parts of a cell #Flashcard",
+            "  - nucleus",
+            "  - ribosomes",
+            "- Not a card #flashcard",
+            "The {{c1::nucleus}} holds DNA.",
+            "```",
+            "Q: inside code",
+            "A: skipped",
+            "```",
+            "Q: Unanswered",
+        ]
+        .join("\n");
+
+        let cards = extract_flashcards(&markdown, &FlashcardPatterns::default());
+
+        assert_eq!(
+            cards,
+            vec![
+                card(
+                    FlashcardKind::Basic,
+                    "What is the powerhouse\nof the cell?",
+                    "The mitochondria",
+                    4
+                ),
+                card(
+                    FlashcardKind::Basic,
+                    "Osmosis",
+                    "water moving across a membrane",
+                    8
+                ),
+                card(
+                    FlashcardKind::Basic,
+                    "Parts of a cell",
+                    "nucleus\nribosomes",
+                    9
+                ),
+                card(
+                    FlashcardKind::Cloze,
+                    "The {{c1::nucleus}} holds DNA.",
+                    "",
+                    13
+                ),
+            ]
+        );
+        assert_eq!(cards[0].hash(), cards[0].clone().hash());
+        assert_ne!(cards[0].hash(), cards[1].hash());
+
+        let only_cloze = FlashcardPatterns {
+            question_answer: false,
+            tag: String::new(),
+            cloze: true,
+        };
+        assert_eq!(extract_flashcards(&markdown, &only_cloze).len(), 1);
+    }
+
+    #[test]
+    fn writes_anki_tsv_with_headers_and_escaped_fields() {
+        let cards = vec![
+            (
+                card(FlashcardKind::Basic, "a < b?", "yes\n\"always\"", 1),
+                vec![anki_source_tag("Math/Basic facts.md")],
+            ),
+            (
+                card(FlashcardKind::Cloze, "{{c1::2}} + 2", "", 2),
+                Vec::new(),
+            ),
+        ];
+
+        assert_eq!(
+            flashcards_to_anki_tsv(&cards, Some("Math")),
+            [
+                "#separator:tab",
+                "#html:true",
+                "#notetype column:1",
+                "#deck:Math",
+                "#tags column:4",
+                "Basic\ta &lt; b?\tyes<br>&quot;always&quot;\tMath::Basic_facts",
+                "Cloze\t{{c1::2}} + 2\t\t",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
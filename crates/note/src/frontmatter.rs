@@ -1,28 +1,89 @@
-use serde_json::{Map, Value as JsonValue};
+use serde::Serialize;
 use serde_yaml::Value as YamlValue;
 use std::fs;
+use std::ops::Range;
 use std::path::Path;
 
-fn extract_frontmatter(source: &str) -> Option<String> {
+/// Parsed frontmatter of a note together with where it sits in the file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Frontmatter {
+    /// Top-level properties in the order they appear in the YAML.
+    pub properties: Vec<FrontmatterProperty>,
+    /// Byte range of the whole block, both `---` lines and the newline after
+    /// the closing one included. `None` when the note has no frontmatter.
+    pub span: Option<FrontmatterSpan>,
+    /// Byte range of the YAML between the delimiters.
+    pub yaml_span: Option<FrontmatterSpan>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrontmatterProperty {
+    pub key: String,
+    pub value: FrontmatterValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum FrontmatterValue {
+    Null,
+    String(String),
+    Number(serde_json::Number),
+    Boolean(bool),
+    /// ISO 8601 date or date-time, kept as written.
+    Date(String),
+    List(Vec<FrontmatterValue>),
+    Object(Vec<FrontmatterProperty>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FrontmatterSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl From<Range<usize>> for FrontmatterSpan {
+    fn from(range: Range<usize>) -> Self {
+        Self {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+/// Byte ranges of a frontmatter block and of its YAML content in `source`.
+pub(crate) struct FrontmatterBlock {
+    pub(crate) block: Range<usize>,
+    pub(crate) yaml: Range<usize>,
+}
+
+pub(crate) fn locate_frontmatter(source: &str) -> Option<FrontmatterBlock> {
     let trimmed = source
         .trim_start_matches(['\u{FEFF}', '\u{200B}'])
         .trim_start();
     if !trimmed.starts_with("---") {
         return None;
     }
+    let block_start = source.len() - trimmed.len();
 
-    let mut lines = trimmed.lines();
+    let mut offset = block_start;
+    let mut lines = trimmed.split_inclusive('\n');
     let first = lines.next()?;
     if !is_frontmatter_delimiter(first) {
         return None;
     }
+    offset += first.len();
+    let yaml_start = offset;
 
-    let mut yaml_lines: Vec<&str> = Vec::new();
     for line in lines {
         if is_frontmatter_delimiter(line) {
-            return Some(yaml_lines.join("\n"));
+            return Some(FrontmatterBlock {
+                block: block_start..offset + line.len(),
+                yaml: yaml_start..offset,
+            });
         }
-        yaml_lines.push(line);
+        offset += line.len();
     }
 
     None
@@ -32,43 +93,72 @@ fn is_frontmatter_delimiter(line: &str) -> bool {
     line.trim() == "---"
 }
 
-fn yaml_to_json(value: YamlValue) -> JsonValue {
+fn yaml_to_value(value: YamlValue) -> FrontmatterValue {
     match value {
-        YamlValue::Null => JsonValue::Null,
-        YamlValue::Bool(v) => JsonValue::Bool(v),
-        YamlValue::Number(num) => yaml_number_to_json(num),
-        YamlValue::String(v) => JsonValue::String(v),
+        YamlValue::Null => FrontmatterValue::Null,
+        YamlValue::Bool(v) => FrontmatterValue::Boolean(v),
+        YamlValue::Number(num) => yaml_number_to_value(num),
+        YamlValue::String(v) if is_iso_date(&v) => FrontmatterValue::Date(v),
+        YamlValue::String(v) => FrontmatterValue::String(v),
         YamlValue::Sequence(items) => {
-            JsonValue::Array(items.into_iter().map(yaml_to_json).collect())
-        }
-        YamlValue::Mapping(map) => {
-            let mut object = Map::new();
-            for (key, val) in map {
-                let key_string = yaml_key_to_string(key);
-                object.insert(key_string, yaml_to_json(val));
-            }
-            JsonValue::Object(object)
+            FrontmatterValue::List(items.into_iter().map(yaml_to_value).collect())
         }
+        YamlValue::Mapping(map) => FrontmatterValue::Object(
+            map.into_iter()
+                .map(|(key, val)| FrontmatterProperty {
+                    key: yaml_key_to_string(key),
+                    value: yaml_to_value(val),
+                })
+                .collect(),
+        ),
         YamlValue::Tagged(tagged) => {
             let tagged_value = *tagged;
-            yaml_to_json(tagged_value.value)
+            yaml_to_value(tagged_value.value)
         }
     }
 }
 
-fn yaml_number_to_json(num: serde_yaml::Number) -> JsonValue {
+fn yaml_number_to_value(num: serde_yaml::Number) -> FrontmatterValue {
     if let Some(value) = num.as_i64() {
-        return JsonValue::Number(value.into());
+        return FrontmatterValue::Number(value.into());
     }
     if let Some(value) = num.as_u64() {
-        return JsonValue::Number(value.into());
+        return FrontmatterValue::Number(value.into());
     }
     if let Some(value) = num.as_f64() {
         if let Some(number) = serde_json::Number::from_f64(value) {
-            return JsonValue::Number(number);
+            return FrontmatterValue::Number(number);
         }
     }
-    JsonValue::Null
+    FrontmatterValue::Null
+}
+
+/// `YYYY-MM-DD`, optionally followed by a `T` or space and a time. YAML has
+/// no date type of its own in serde_yaml, so dates arrive as strings.
+fn is_iso_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    if bytes.len() < 10 {
+        return false;
+    }
+
+    let date_shape = bytes[..10]
+        .iter()
+        .enumerate()
+        .all(|(index, byte)| match index {
+            4 | 7 => *byte == b'-',
+            _ => byte.is_ascii_digit(),
+        });
+    if !date_shape {
+        return false;
+    }
+
+    match &bytes[10..] {
+        [] => true,
+        [b'T' | b' ', time @ ..] => {
+            time.len() >= 5 && time[..2].iter().all(u8::is_ascii_digit) && time[2] == b':'
+        }
+        _ => false,
+    }
 }
 
 fn yaml_key_to_string(value: YamlValue) -> String {
@@ -84,27 +174,88 @@ fn yaml_key_to_string(value: YamlValue) -> String {
     }
 }
 
-fn parse_frontmatter(source: &str) -> JsonValue {
-    let Some(yaml_source) = extract_frontmatter(source) else {
-        return JsonValue::Object(Map::new());
+pub fn parse_frontmatter(source: &str) -> Frontmatter {
+    let Some(block) = locate_frontmatter(source) else {
+        return Frontmatter::default();
+    };
+    let mut frontmatter = Frontmatter {
+        properties: Vec::new(),
+        span: Some(block.block.into()),
+        yaml_span: Some(block.yaml.clone().into()),
     };
 
-    let parsed: YamlValue = match serde_yaml::from_str(&yaml_source) {
+    let parsed: YamlValue = match serde_yaml::from_str(&source[block.yaml]) {
         Ok(value) => value,
         Err(e) => {
-            eprintln!("Failed to parse frontmatter YAML: {}", e);
-            return JsonValue::Object(Map::new());
+            tracing::warn!("Failed to parse frontmatter YAML: {}", e);
+            return frontmatter;
         }
     };
 
-    match parsed {
-        YamlValue::Mapping(_) => yaml_to_json(parsed),
-        _ => JsonValue::Object(Map::new()),
+    if let FrontmatterValue::Object(properties) = yaml_to_value(parsed) {
+        frontmatter.properties = properties;
     }
+    frontmatter
 }
 
-pub fn read_frontmatter(path: &Path) -> Result<JsonValue, String> {
+/// Reads the frontmatter of the note at `path`. Spans are byte offsets into
+/// the file, so callers can patch it without rewriting the rest.
+pub fn read_frontmatter(path: &Path) -> Result<Frontmatter, String> {
     let contents = fs::read(path).map_err(|error| format!("Failed to read file: {}", error))?;
     let contents = String::from_utf8_lossy(&contents);
     Ok(parse_frontmatter(contents.as_ref()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_frontmatter, FrontmatterValue};
+
+    #[test]
+    fn parses_typed_values_in_source_order() {
+        let source = "---\ntitle: Trip\nrating: 4\ndraft: false\ncreated: 2024-05-01\ntags:\n  - travel\n  - seoul\n---\nBody";
+        let frontmatter = parse_frontmatter(source);
+
+        let keys = frontmatter
+            .properties
+            .iter()
+            .map(|property| property.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["title", "rating", "draft", "created", "tags"]);
+
+        let values = frontmatter
+            .properties
+            .into_iter()
+            .map(|property| property.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values[0], FrontmatterValue::String("Trip".to_string()));
+        assert_eq!(values[1], FrontmatterValue::Number(4.into()));
+        assert_eq!(values[2], FrontmatterValue::Boolean(false));
+        assert_eq!(values[3], FrontmatterValue::Date("2024-05-01".to_string()));
+        assert_eq!(
+            values[4],
+            FrontmatterValue::List(vec![
+                FrontmatterValue::String("travel".to_string()),
+                FrontmatterValue::String("seoul".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn reports_byte_spans_of_block_and_yaml() {
+        let source = "\u{FEFF}---\r\na: 1\r\n---\r\nBody";
+        let frontmatter = parse_frontmatter(source);
+
+        let span = frontmatter.span.expect("block span");
+        let yaml_span = frontmatter.yaml_span.expect("yaml span");
+        assert_eq!(&source[span.start..span.end], "---\r\na: 1\r\n---\r\n");
+        assert_eq!(&source[yaml_span.start..yaml_span.end], "a: 1\r\n");
+        assert_eq!(&source[span.end..], "Body");
+    }
+
+    #[test]
+    fn returns_empty_frontmatter_without_block() {
+        let frontmatter = parse_frontmatter("# Title\n---\n");
+        assert!(frontmatter.properties.is_empty());
+        assert_eq!(frontmatter.span, None);
+    }
+}
@@ -0,0 +1,247 @@
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use serde_json::Value as JsonValue;
+
+use crate::frontmatter::locate_frontmatter;
+
+const PROPERTY_INDENT: &str = "  ";
+
+/// Set `key` to `value` in the frontmatter of `source`, creating the block
+/// when there is none. Only the lines of that property change; everything
+/// else, comments included, is kept byte for byte.
+pub fn set_frontmatter_property(
+    source: &str,
+    key: &str,
+    value: &JsonValue,
+) -> Result<String, String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("Property name cannot be empty".to_string());
+    }
+
+    let Some(block) = locate_frontmatter(source) else {
+        let newline = detect_newline(source);
+        let insert_at = leading_marker_len(source);
+        let property = render_property(key, value, newline)?;
+        return Ok(format!(
+            "{}---{newline}{property}---{newline}{}",
+            &source[..insert_at],
+            &source[insert_at..]
+        ));
+    };
+
+    let newline = detect_newline(&source[block.block.clone()]);
+    let property = render_property(key, value, newline)?;
+    let (range, property) = match find_property_lines(source, block.yaml.clone(), key) {
+        Some(range) => (range, property),
+        None => {
+            // Append after the last property, keeping a missing final newline intact.
+            let end = block.yaml.end;
+            let needs_newline = end > block.yaml.start && !source[..end].ends_with('\n');
+            let property = if needs_newline {
+                format!("{newline}{property}")
+            } else {
+                property
+            };
+            (end..end, property)
+        }
+    };
+
+    Ok(splice(source, range, &property))
+}
+
+/// Remove `key` from the frontmatter of `source`. Returns `None` when the
+/// property does not exist.
+pub fn remove_frontmatter_property(source: &str, key: &str) -> Option<String> {
+    let block = locate_frontmatter(source)?;
+    let range = find_property_lines(source, block.yaml, key.trim())?;
+    Some(splice(source, range, ""))
+}
+
+pub fn write_frontmatter_property(path: &Path, key: &str, value: &JsonValue) -> Result<(), String> {
+    let source = read_note(path)?;
+    let updated = set_frontmatter_property(&source, key, value)?;
+    write_note(path, &source, &updated)
+}
+
+/// Returns whether the property existed.
+pub fn delete_frontmatter_property(path: &Path, key: &str) -> Result<bool, String> {
+    let source = read_note(path)?;
+    match remove_frontmatter_property(&source, key) {
+        Some(updated) => write_note(path, &source, &updated).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+fn read_note(path: &Path) -> Result<String, String> {
+    // Lossy decoding would rewrite unrelated bytes, so invalid UTF-8 is an error.
+    fs::read_to_string(path).map_err(|error| format!("Failed to read file: {}", error))
+}
+
+fn write_note(path: &Path, original: &str, updated: &str) -> Result<(), String> {
+    if original == updated {
+        return Ok(());
+    }
+    fs::write(path, updated).map_err(|error| format!("Failed to write file: {}", error))
+}
+
+fn splice(source: &str, range: Range<usize>, replacement: &str) -> String {
+    let mut output = String::with_capacity(source.len() + replacement.len());
+    output.push_str(&source[..range.start]);
+    output.push_str(replacement);
+    output.push_str(&source[range.end..]);
+    output
+}
+
+/// Byte range of the lines holding top-level `key` inside `yaml`: the key line
+/// plus its indented or `- ` continuation lines.
+fn find_property_lines(source: &str, yaml: Range<usize>, key: &str) -> Option<Range<usize>> {
+    let mut offset = yaml.start;
+    let mut found: Option<Range<usize>> = None;
+
+    for line in source[yaml].split_inclusive('\n') {
+        let line_range = offset..offset + line.len();
+        offset = line_range.end;
+
+        if let Some(range) = found.as_mut() {
+            if is_continuation_line(line) {
+                range.end = line_range.end;
+                continue;
+            }
+            break;
+        }
+
+        if top_level_key(line).is_some_and(|line_key| line_key == key) {
+            found = Some(line_range);
+        }
+    }
+
+    found
+}
+
+fn is_continuation_line(line: &str) -> bool {
+    (line.starts_with([' ', '\t']) && !line.trim().is_empty()) || line.starts_with('-')
+}
+
+/// Key of a `key: value` line at column zero, unquoted.
+fn top_level_key(line: &str) -> Option<String> {
+    let line = line.trim_end();
+    let first = line.chars().next()?;
+    if first.is_whitespace() || matches!(first, '#' | '-') {
+        return None;
+    }
+
+    if matches!(first, '"' | '\'') {
+        let closing = line[1..].find(first)? + 1;
+        return line[closing + 1..]
+            .starts_with(':')
+            .then(|| line[1..closing].to_string());
+    }
+
+    let colon = line
+        .match_indices(':')
+        .map(|(index, _)| index)
+        .find(|index| line[index + 1..].is_empty() || line[index + 1..].starts_with([' ', '\t']))?;
+    Some(line[..colon].trim().to_string())
+}
+
+fn render_property(key: &str, value: &JsonValue, newline: &str) -> Result<String, String> {
+    let key = render_key(key)?;
+    let yaml = serde_yaml::to_string(value)
+        .map_err(|error| format!("Failed to serialize property: {}", error))?;
+    let yaml = yaml.trim_end_matches('\n');
+
+    let is_block = match value {
+        JsonValue::Array(items) => !items.is_empty(),
+        JsonValue::Object(map) => !map.is_empty(),
+        _ => false,
+    };
+
+    let mut output = String::new();
+    if is_block {
+        output.push_str(&format!("{key}:{newline}"));
+        for line in yaml.lines() {
+            output.push_str(&format!("{PROPERTY_INDENT}{line}{newline}"));
+        }
+    } else {
+        // Multi-line strings come back as an indented block scalar.
+        let mut lines = yaml.lines();
+        output.push_str(&format!(
+            "{key}: {}{newline}",
+            lines.next().unwrap_or_default()
+        ));
+        for line in lines {
+            output.push_str(&format!("{line}{newline}"));
+        }
+    }
+
+    Ok(output)
+}
+
+fn render_key(key: &str) -> Result<String, String> {
+    let needs_quotes = key.starts_with(['"', '\'', '#', '-', '[', '{', '&', '*', '!', '|', '>'])
+        || key.contains(": ")
+        || key.ends_with(':')
+        || key.contains(" #");
+    if !needs_quotes {
+        return Ok(key.to_string());
+    }
+
+    serde_yaml::to_string(key)
+        .map(|quoted| quoted.trim_end().to_string())
+        .map_err(|error| format!("Failed to serialize property name: {}", error))
+}
+
+fn detect_newline(text: &str) -> &'static str {
+    if text.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+fn leading_marker_len(source: &str) -> usize {
+    source.len() - source.trim_start_matches('\u{FEFF}').len()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{remove_frontmatter_property, set_frontmatter_property};
+
+    #[test]
+    fn replaces_value_and_keeps_other_lines_and_comments() {
+        let source =
+            "---\n# reviewed\ntitle: Old\ntags:\n  - a\n  - b\nrating: 3 # out of 5\n---\nBody\n";
+
+        let updated = set_frontmatter_property(source, "tags", &json!(["x"])).expect("set tags");
+        assert_eq!(
+            updated,
+            "---\n# reviewed\ntitle: Old\ntags:\n  - x\nrating: 3 # out of 5\n---\nBody\n"
+        );
+
+        let updated = set_frontmatter_property(&updated, "title", &json!("true")).expect("set");
+        assert!(updated.contains("title: 'true'\n"));
+    }
+
+    #[test]
+    fn appends_new_properties_and_creates_missing_block() {
+        let source = "---\r\ntitle: A\r\n---\r\nBody";
+        let updated = set_frontmatter_property(source, "draft", &json!(true)).expect("append");
+        assert_eq!(updated, "---\r\ntitle: A\r\ndraft: true\r\n---\r\nBody");
+
+        let created = set_frontmatter_property("Body", "rating", &json!(4)).expect("create");
+        assert_eq!(created, "---\nrating: 4\n---\nBody");
+    }
+
+    #[test]
+    fn removes_property_with_unindented_list_items() {
+        let source = "---\naliases:\n- One\n- Two\ntitle: A\n---\n";
+        let updated = remove_frontmatter_property(source, "aliases").expect("removed");
+        assert_eq!(updated, "---\ntitle: A\n---\n");
+        assert!(remove_frontmatter_property(source, "missing").is_none());
+    }
+}
@@ -0,0 +1,211 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::directory::{compare_names, system_time_to_millis};
+use crate::frontmatter::{parse_frontmatter, FrontmatterValue};
+use crate::moc::collect_folder_notes;
+use crate::preview::{build_note_preview, DEFAULT_PREVIEW_CHARS};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KanbanBoard {
+    pub property: String,
+    /// The requested columns in order, then any other value found, in
+    /// natural order.
+    pub columns: Vec<KanbanColumn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KanbanColumn {
+    pub value: String,
+    pub cards: Vec<KanbanCard>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KanbanCard {
+    pub path: PathBuf,
+    /// First heading of the note, else its file name.
+    pub title: String,
+    /// Body excerpt without the heading.
+    pub preview: String,
+    pub modified_at: Option<i64>,
+}
+
+/// Groups the notes under `folder` by the value of their `property`. Values
+/// match `columns` case-insensitively; notes without the property, or with a
+/// list or object value, are left off the board.
+pub fn build_kanban_board(
+    folder: &Path,
+    property: &str,
+    columns: &[String],
+) -> Result<KanbanBoard, String> {
+    let property = property.trim();
+    if property.is_empty() {
+        return Err("Board property is empty".to_string());
+    }
+
+    let mut board_columns: Vec<KanbanColumn> = Vec::new();
+    for value in columns.iter().map(|value| value.trim()) {
+        let lowered = value.to_lowercase();
+        if value.is_empty()
+            || board_columns
+                .iter()
+                .any(|column| column.value.to_lowercase() == lowered)
+        {
+            continue;
+        }
+        board_columns.push(KanbanColumn {
+            value: value.to_string(),
+            cards: Vec::new(),
+        });
+    }
+    let requested = board_columns.len();
+
+    for path in collect_folder_notes(folder)? {
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let Some(value) = property_value(&source, property) else {
+            continue;
+        };
+        let card = build_card(path, &source);
+        let lowered = value.to_lowercase();
+        match board_columns
+            .iter_mut()
+            .find(|column| column.value.to_lowercase() == lowered)
+        {
+            Some(column) => column.cards.push(card),
+            None => board_columns.push(KanbanColumn {
+                value,
+                cards: vec![card],
+            }),
+        }
+    }
+
+    board_columns[requested..].sort_by(|a, b| compare_names(&a.value, &b.value));
+    for column in &mut board_columns {
+        column.cards.sort_by(compare_cards);
+    }
+
+    Ok(KanbanBoard {
+        property: property.to_string(),
+        columns: board_columns,
+    })
+}
+
+/// The property as a column label, for scalar values only.
+fn property_value(source: &str, property: &str) -> Option<String> {
+    let value = parse_frontmatter(source)
+        .properties
+        .into_iter()
+        .find(|candidate| candidate.key == property)?
+        .value;
+    let label = match value {
+        FrontmatterValue::String(text) | FrontmatterValue::Date(text) => text,
+        FrontmatterValue::Number(number) => number.to_string(),
+        FrontmatterValue::Boolean(flag) => flag.to_string(),
+        FrontmatterValue::Null | FrontmatterValue::List(_) | FrontmatterValue::Object(_) => {
+            return None;
+        }
+    };
+    Some(label.trim().to_string()).filter(|label| !label.is_empty())
+}
+
+fn build_card(path: PathBuf, source: &str) -> KanbanCard {
+    let preview = build_note_preview(source, DEFAULT_PREVIEW_CHARS);
+    // The excerpt starts with the heading, which is already the title.
+    let excerpt = match &preview.title {
+        Some(heading) => preview
+            .excerpt
+            .strip_prefix(heading.as_str())
+            .map_or(preview.excerpt.as_str(), str::trim_start)
+            .to_string(),
+        None => preview.excerpt,
+    };
+    let title = preview.title.unwrap_or_else(|| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    let modified_at = fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(system_time_to_millis);
+
+    KanbanCard {
+        path,
+        title,
+        preview: excerpt,
+        modified_at,
+    }
+}
+
+fn compare_cards(a: &KanbanCard, b: &KanbanCard) -> Ordering {
+    compare_names(&a.title, &b.title).then_with(|| a.path.cmp(&b.path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::build_kanban_board;
+
+    #[test]
+    fn groups_notes_into_requested_columns_then_found_values() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-note-kanban-{nanos}"));
+        fs::create_dir_all(root.join("Tasks")).unwrap();
+        fs::write(
+            root.join("Tasks/write.md"),
+            "---\nstatus: Doing\n---\n# Write draft\n\nFirst pass.",
+        )
+        .unwrap();
+        fs::write(
+            root.join("Tasks/review.md"),
+            "---\nstatus: doing\n---\nReview.",
+        )
+        .unwrap();
+        fs::write(root.join("Tasks/ship.md"), "---\nstatus: blocked\n---\n").unwrap();
+        fs::write(root.join("Tasks/tags.md"), "---\nstatus: [a, b]\n---\n").unwrap();
+        fs::write(root.join("Tasks/plain.md"), "No frontmatter.").unwrap();
+
+        let board = build_kanban_board(&root, "status", &["Todo".to_string(), "Doing".to_string()])
+            .unwrap();
+
+        let columns = board
+            .columns
+            .iter()
+            .map(|column| {
+                (
+                    column.value.as_str(),
+                    column
+                        .cards
+                        .iter()
+                        .map(|card| card.title.as_str())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![
+                ("Todo", vec![]),
+                ("Doing", vec!["review", "Write draft"]),
+                ("blocked", vec!["ship"]),
+            ]
+        );
+        assert_eq!(board.columns[1].cards[1].preview, "First pass.");
+        assert!(build_kanban_board(&root, " ", &[]).is_err());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,7 +1,55 @@
+mod archive;
+mod directory;
+mod file_name;
+mod flashcards;
 mod frontmatter;
+mod frontmatter_edit;
+mod kanban;
+mod lock;
 mod markdown_text;
+mod math;
+mod moc;
+mod note_path;
+mod outline;
 mod preview;
+mod sanitize;
 
-pub use frontmatter::read_frontmatter;
-pub use markdown_text::{format_indexing_text, format_preview_text};
-pub use preview::get_note_preview;
+pub use archive::{archive_note, unarchive_note, ArchiveResult, MovedPath, ARCHIVED_PROPERTY};
+pub use directory::{
+    list_directory, DirectoryEntry, DirectoryEntryKind, DirectorySortKey, ListDirectoryOptions,
+    SortDirection,
+};
+pub use file_name::{sanitize_note_title, unique_note_file_name, UniqueNoteFileName};
+pub use flashcards::{
+    anki_source_tag, extract_flashcards, flashcards_to_anki_tsv, Flashcard, FlashcardKind,
+    FlashcardPatterns,
+};
+pub use frontmatter::{
+    parse_frontmatter, read_frontmatter, Frontmatter, FrontmatterProperty, FrontmatterSpan,
+    FrontmatterValue,
+};
+pub use frontmatter_edit::{
+    delete_frontmatter_property, remove_frontmatter_property, set_frontmatter_property,
+    write_frontmatter_property,
+};
+pub use kanban::{build_kanban_board, KanbanBoard, KanbanCard, KanbanColumn};
+pub use lock::{
+    ensure_entry_unlocked, ensure_note_unlocked, ensure_note_writable, is_locked_source,
+    is_note_locked, LOCKED_PROPERTY,
+};
+pub use markdown_text::{
+    format_indexing_text, format_indexing_text_with_options, format_preview_text,
+    mask_indexing_noise, IndexingTextOptions,
+};
+pub use moc::{
+    collect_folder_notes, folder_group, generate_moc, MocGroupBy, MocNote, MocOptions, MocResult,
+    MocSortKey, MOC_BLOCK_END, MOC_BLOCK_START,
+};
+pub use note_path::{
+    is_drawing_path, is_indexed_path, is_note_path, DRAWING_EXTENSIONS, NOTE_EXTENSIONS,
+};
+pub use outline::{
+    build_note_outline, get_note_outline, NoteOutline, OutlineCodeBlock, OutlineHeading,
+    DIAGRAM_LANGUAGES,
+};
+pub use preview::{build_note_preview, get_note_preview, NotePreview, DEFAULT_PREVIEW_CHARS};
@@ -0,0 +1,141 @@
+use std::fs;
+use std::path::Path;
+
+use crate::frontmatter::{parse_frontmatter, FrontmatterValue};
+use crate::note_path::is_note_path;
+
+/// Frontmatter property marking a note as read-only.
+pub const LOCKED_PROPERTY: &str = "locked";
+
+/// Whether `contents` carry `locked: true` in their frontmatter.
+pub fn is_locked_source(contents: &str) -> bool {
+    parse_frontmatter(contents)
+        .properties
+        .iter()
+        .any(|property| {
+            property.key == LOCKED_PROPERTY && property.value == FrontmatterValue::Boolean(true)
+        })
+}
+
+/// Whether the note at `path` is locked. Folders, other files and notes that
+/// cannot be read count as unlocked.
+pub fn is_note_locked(path: &Path) -> bool {
+    if !is_note_path(path) {
+        return false;
+    }
+    match fs::read(path) {
+        Ok(contents) => is_locked_source(&String::from_utf8_lossy(&contents)),
+        Err(_) => false,
+    }
+}
+
+/// Fails for a locked note so writers can refuse before touching it.
+pub fn ensure_note_unlocked(path: &Path) -> Result<(), String> {
+    if is_note_locked(path) {
+        return Err(format!("Note is locked: {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Fails when writing `contents` over `path` would edit a locked note. The
+/// one write a locked note takes is the one unlocking it, which leaves its
+/// body and every other property as they were.
+pub fn ensure_note_writable(path: &Path, contents: &str) -> Result<(), String> {
+    if !is_note_locked(path) {
+        return Ok(());
+    }
+    let current = fs::read(path).map_err(|error| format!("Failed to read note: {}", error))?;
+    if is_unlocking_edit(&String::from_utf8_lossy(&current), contents) {
+        return Ok(());
+    }
+    Err(format!("Note is locked: {}", path.display()))
+}
+
+fn is_unlocking_edit(current: &str, next: &str) -> bool {
+    if is_locked_source(next) {
+        return false;
+    }
+    let without_lock = |source: &str| {
+        let frontmatter = parse_frontmatter(source);
+        let body = frontmatter.span.map_or(source, |span| &source[span.end..]);
+        let properties = frontmatter
+            .properties
+            .into_iter()
+            .filter(|property| property.key != LOCKED_PROPERTY)
+            .collect::<Vec<_>>();
+        (
+            properties,
+            body.trim_start_matches(['\r', '\n']).to_string(),
+        )
+    };
+    without_lock(current) == without_lock(next)
+}
+
+/// Fails when `path` is a locked note or a folder holding one, so moving or
+/// deleting the folder cannot take a locked note with it.
+pub fn ensure_entry_unlocked(path: &Path) -> Result<(), String> {
+    if !path.is_dir() {
+        return ensure_note_unlocked(path);
+    }
+    let entries =
+        fs::read_dir(path).map_err(|error| format!("Failed to read directory: {}", error))?;
+    for entry in entries.flatten() {
+        ensure_entry_unlocked(&entry.path())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ensure_entry_unlocked, ensure_note_writable, is_locked_source, is_note_locked};
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn only_a_true_locked_property_locks_a_note() {
+        assert!(is_locked_source("---\nlocked: true\n---\nSigned."));
+        assert!(!is_locked_source("---\nlocked: false\n---\n"));
+        assert!(!is_locked_source("---\nlocked: \"yes\"\n---\n"));
+        assert!(!is_locked_source("locked: true\n"));
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-note-lock-{nanos}"));
+        fs::create_dir_all(root.join("Contracts")).expect("create folder");
+        fs::write(root.join("Contracts/lease.md"), "---\nlocked: true\n---\n").unwrap();
+        fs::write(root.join("draft.md"), "Draft").unwrap();
+
+        assert!(is_note_locked(&root.join("Contracts/lease.md")));
+        assert!(!is_note_locked(&root.join("draft.md")));
+        assert!(ensure_entry_unlocked(&root.join("Contracts")).is_err());
+        assert!(ensure_entry_unlocked(&root.join("draft.md")).is_ok());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn a_locked_note_only_takes_the_write_that_unlocks_it() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-note-lock-write-{nanos}"));
+        fs::create_dir_all(&root).expect("create folder");
+        let lease = root.join("lease.md");
+        fs::write(&lease, "---\ntitle: Lease\nlocked: true\n---\nSigned.").unwrap();
+
+        assert!(
+            ensure_note_writable(&lease, "---\ntitle: Lease\nlocked: true\n---\nEdited.").is_err()
+        );
+        assert!(ensure_note_writable(&lease, "---\ntitle: Lease\n---\nEdited.").is_err());
+        assert!(
+            ensure_note_writable(&lease, "---\ntitle: Lease\nlocked: false\n---\nSigned.").is_ok()
+        );
+        assert!(ensure_note_writable(&lease, "---\ntitle: Lease\n---\nSigned.").is_ok());
+        assert!(ensure_note_writable(&root.join("new.md"), "Anything").is_ok());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,6 +1,12 @@
+use std::ops::Range;
+
 use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
 use serde_yaml::Value;
 
+use crate::math::{mask_math, replace_math_for_indexing, strip_math};
+use crate::outline::is_diagram_language;
+use crate::sanitize::{blank_ranges, merge_ranges, noise_ranges, remove_noise};
+
 const BOM: char = '\u{FEFF}';
 const ZERO_WIDTH_SPACE: char = '\u{200B}';
 
@@ -11,8 +17,10 @@ pub fn format_preview_text(raw: &str) -> String {
 
     let cleaned = strip_hidden_chars(raw);
     let cleaned = strip_frontmatter(&cleaned);
+    let cleaned = strip_diagram_containers(&cleaned);
     let cleaned = strip_html_block_lines(&cleaned);
     let cleaned = strip_markdown_tables(&cleaned);
+    let cleaned = strip_math(&cleaned);
     if cleaned.trim().is_empty() {
         return String::new();
     }
@@ -98,7 +106,32 @@ pub fn format_preview_text(raw: &str) -> String {
     strip_reference_links(&collapsed)
 }
 
+/// Controls what [`format_indexing_text_with_options`] keeps from a note.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexingTextOptions {
+    /// Top-level frontmatter keys whose values are left out of indexed text,
+    /// matched case-insensitively.
+    pub excluded_frontmatter_keys: Vec<String>,
+}
+
+impl IndexingTextOptions {
+    fn excludes_key(&self, key: &str) -> bool {
+        let key = key.trim();
+        self.excluded_frontmatter_keys
+            .iter()
+            .any(|excluded| excluded.trim().eq_ignore_ascii_case(key))
+    }
+}
+
 pub fn format_indexing_text(raw: &str) -> String {
+    format_indexing_text_with_options(raw, &IndexingTextOptions::default())
+}
+
+/// Text stored for full-text search: frontmatter values followed by the body,
+/// without data URIs, the tails of extremely long tokens, or excluded keys.
+/// Math in the body keeps only its `\text{}` prose, and display math leaves
+/// a placeholder.
+pub fn format_indexing_text_with_options(raw: &str, options: &IndexingTextOptions) -> String {
     if raw.is_empty() {
         return String::new();
     }
@@ -108,12 +141,17 @@ pub fn format_indexing_text(raw: &str) -> String {
     let mut parts = Vec::new();
 
     if let Some(frontmatter) = frontmatter {
-        let values = extract_frontmatter_values(frontmatter);
+        let values = extract_frontmatter_values(frontmatter, options)
+            .into_iter()
+            .map(|value| remove_noise(&value))
+            .filter(|value| !value.trim().is_empty())
+            .collect::<Vec<_>>();
         if !values.is_empty() {
             parts.push(values.join("\n"));
         }
     }
 
+    let body = remove_noise(&replace_math_for_indexing(body.trim()));
     let body = body.trim();
     if !body.is_empty() {
         parts.push(body.to_string());
@@ -122,12 +160,125 @@ pub fn format_indexing_text(raw: &str) -> String {
     parts.join("\n\n")
 }
 
-fn strip_hidden_chars(raw: &str) -> String {
+/// Blank out the same noise [`format_indexing_text_with_options`] drops, but
+/// with spaces so byte offsets into `raw` stay valid for chunking. Math is
+/// overwritten with the same text the index keeps for it.
+pub fn mask_indexing_noise(raw: &str, options: &IndexingTextOptions) -> String {
+    let mut ranges = noise_ranges(raw);
+    ranges.extend(excluded_frontmatter_ranges(raw, options));
+    let masked = blank_ranges(raw, &merge_ranges(ranges));
+
+    let (_, body) = split_frontmatter(raw);
+    let body_start = raw.len() - body.len();
+    format!(
+        "{}{}",
+        &masked[..body_start],
+        mask_math(&masked[body_start..])
+    )
+}
+
+fn excluded_frontmatter_ranges(raw: &str, options: &IndexingTextOptions) -> Vec<Range<usize>> {
+    if options.excluded_frontmatter_keys.is_empty() {
+        return Vec::new();
+    }
+
+    let (Some(frontmatter), _) = split_frontmatter(raw) else {
+        return Vec::new();
+    };
+    let frontmatter_start = raw.len() - raw.trim_start().len();
+
+    let mut ranges = Vec::new();
+    let mut excluded_start: Option<usize> = None;
+    let mut line_start = frontmatter_start;
+    for line in frontmatter.split_inclusive('\n') {
+        let top_level_key = top_level_frontmatter_key(line);
+        if top_level_key.is_some() || is_frontmatter_delimiter(line) {
+            if let Some(start) = excluded_start.take() {
+                ranges.push(start..line_start);
+            }
+        }
+        if top_level_key.is_some_and(|key| options.excludes_key(key)) {
+            excluded_start = Some(line_start);
+        }
+        line_start += line.len();
+    }
+    if let Some(start) = excluded_start {
+        ranges.push(start..line_start);
+    }
+
+    ranges
+}
+
+fn top_level_frontmatter_key(line: &str) -> Option<&str> {
+    if line.starts_with(char::is_whitespace) || line.starts_with(['-', '#']) {
+        return None;
+    }
+
+    let (key, _) = line.split_once(':')?;
+    Some(key.trim().trim_matches(['"', '\'']))
+}
+
+pub(crate) fn strip_hidden_chars(raw: &str) -> String {
     raw.chars()
         .filter(|ch| *ch != BOM && *ch != ZERO_WIDTH_SPACE)
         .collect()
 }
 
+/// Drops diagram sources that are not fenced code, such as `:::mermaid`
+/// containers and `<pre class="mermaid">` blocks. Fenced diagrams are already
+/// skipped with other code blocks.
+fn strip_diagram_containers(raw: &str) -> String {
+    let mut kept = Vec::new();
+    let mut closer: Option<&str> = None;
+
+    for line in raw.lines() {
+        let trimmed = line.trim();
+        if let Some(end) = closer {
+            if trimmed.contains(end) {
+                closer = None;
+            }
+            continue;
+        }
+
+        if let Some(info) = trimmed.strip_prefix(":::") {
+            if info
+                .split_whitespace()
+                .next()
+                .is_some_and(is_diagram_language)
+            {
+                closer = Some(":::");
+                continue;
+            }
+        }
+        if let Some(end) = diagram_html_closer(trimmed) {
+            if !trimmed.contains(end) {
+                closer = Some(end);
+            }
+            continue;
+        }
+        kept.push(line);
+    }
+
+    kept.join("\n")
+}
+
+fn diagram_html_closer(line: &str) -> Option<&'static str> {
+    let end = if line.starts_with("<pre") {
+        "</pre>"
+    } else if line.starts_with("<div") {
+        "</div>"
+    } else {
+        return None;
+    };
+    let tag = &line[..line.find('>')?];
+    let class = tag.split("class=").nth(1)?.trim_matches(['"', '\'']);
+    class
+        .split(['"', '\'', ' '])
+        .take_while(|name| !name.is_empty())
+        .any(is_diagram_language)
+        .then_some(end)
+}
+
 fn strip_html_block_lines(raw: &str) -> String {
     raw.lines()
         .filter(|line| !line.trim_start().starts_with('<'))
@@ -184,7 +335,7 @@ fn split_frontmatter(raw: &str) -> (Option<&str>, &str) {
     (Some(frontmatter), body)
 }
 
-fn strip_frontmatter(raw: &str) -> String {
+pub(crate) fn strip_frontmatter(raw: &str) -> String {
     let (_, body) = split_frontmatter(raw);
     body.to_string()
 }
@@ -230,16 +381,19 @@ fn is_frontmatter_delimiter(line: &str) -> bool {
     line.trim() == "---"
 }
 
-fn extract_frontmatter_values(frontmatter: &str) -> Vec<String> {
+fn extract_frontmatter_values(frontmatter: &str, options: &IndexingTextOptions) -> Vec<String> {
     let payload = frontmatter_payload(frontmatter);
-    match serde_yaml::from_str::<Value>(&payload) {
-        Ok(value) => {
-            let mut scalars = Vec::new();
-            collect_yaml_scalar_values(&value, &mut scalars);
-            scalars
-        }
-        Err(_) => Vec::new(),
+    let Ok(mut value) = serde_yaml::from_str::<Value>(&payload) else {
+        return Vec::new();
+    };
+
+    if let Value::Mapping(map) = &mut value {
+        map.retain(|key, _| !key.as_str().is_some_and(|key| options.excludes_key(key)));
     }
+
+    let mut scalars = Vec::new();
+    collect_yaml_scalar_values(&value, &mut scalars);
+    scalars
 }
 
 fn frontmatter_payload(frontmatter: &str) -> String {
@@ -348,7 +502,10 @@ struct ListState {
 
 #[cfg(test)]
 mod tests {
-    use super::{format_indexing_text, format_preview_text};
+    use super::{
+        format_indexing_text, format_indexing_text_with_options, format_preview_text,
+        mask_indexing_noise, IndexingTextOptions,
+    };
 
     #[test]
     fn strips_heading_hashes_and_appends_next_line_as_body() {
@@ -441,6 +598,27 @@ mod tests {
         assert_eq!(format_preview_text(&raw), "Title key: value Body");
     }
 
+    #[test]
+    fn drops_diagram_sources_outside_fenced_code() {
+        let raw = [
+            "Intro",
+            ":::mermaid",
+            "graph TD",
+            "  A-->B",
+            ":::",
+            "<pre class=\"mermaid\">",
+            "sequenceDiagram",
+            "</pre>",
+            "<div class='note'>kept</div>",
+            "```mermaid",
+            "graph LR",
+            "```",
+            "After",
+        ]
+        .join("\n");
+        assert_eq!(format_preview_text(&raw), "Intro After");
+    }
+
     #[test]
     fn given_frontmatter_values_when_formatting_for_indexing_then_keeps_only_values() {
         let raw = [
@@ -490,4 +668,104 @@ mod tests {
 
         assert_eq!(indexed, raw);
     }
+
+    #[test]
+    fn given_excluded_keys_when_formatting_for_indexing_then_their_values_are_dropped() {
+        let raw = [
+            "---",
+            "title: Weekly Review",
+            "Template: daily-note-v2",
+            "cssclass:",
+            "  - wide",
+            "---",
+            "Body text",
+        ]
+        .join("\n");
+        let options = IndexingTextOptions {
+            excluded_frontmatter_keys: vec!["template".to_string(), "cssclass".to_string()],
+        };
+
+        let indexed = format_indexing_text_with_options(&raw, &options);
+
+        assert_eq!(indexed, "Weekly Review\n\nBody text");
+    }
+
+    #[test]
+    fn given_data_uri_in_body_when_formatting_for_indexing_then_payload_is_dropped() {
+        let raw = "Diagram ![d](data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAUA) below";
+
+        let indexed = format_indexing_text(raw);
+
+        assert_eq!(indexed, "Diagram ![d]() below");
+    }
+
+    #[test]
+    fn given_noise_when_masking_then_offsets_are_preserved() {
+        let raw = [
+            "---",
+            "title: Keep",
+            "template: drop-me",
+            "---",
+            "![d](data:image/png;base64,AAAA) tail",
+        ]
+        .join("\n");
+        let options = IndexingTextOptions {
+            excluded_frontmatter_keys: vec!["template".to_string()],
+        };
+
+        let masked = mask_indexing_noise(&raw, &options);
+
+        assert_eq!(masked.len(), raw.len());
+        assert!(masked.contains("title: Keep"));
+        assert!(!masked.contains("drop-me"));
+        assert!(!masked.contains("base64"));
+        assert_eq!(masked.find("tail"), raw.find("tail"));
+    }
+
+    const MATH_NOTE: &str = concat!(
+        "Energy $E = mc^2$ and \\(\\alpha + \\beta\\) matter.\n",
+        "\n",
+        "$$\n",
+        "\\int_0^1 f(x)\\,dx \\quad \\text{for all } x\n",
+        "$$\n",
+        "\n",
+        "Costs $5 and $10, see `$code$`.",
+    );
+
+    #[test]
+    fn given_math_when_formatting_preview_then_all_forms_are_dropped() {
+        assert_eq!(
+            format_preview_text(MATH_NOTE),
+            "Energy and matter. Costs $5 and $10, see $code$."
+        );
+        assert_eq!(
+            format_preview_text("Display \\[x^2\\] gone"),
+            "Display gone"
+        );
+    }
+
+    #[test]
+    fn given_math_when_formatting_for_indexing_then_only_placeholder_and_text_remain() {
+        assert_eq!(
+            format_indexing_text(MATH_NOTE),
+            "Energy  and  matter.\n\n[math] for all\n\nCosts $5 and $10, see `$code$`."
+        );
+        assert_eq!(
+            format_indexing_text("Then \\[\\sum_i \\textbf{weights}\\]."),
+            "Then [math] weights."
+        );
+    }
+
+    #[test]
+    fn given_math_when_masking_then_index_text_is_written_in_place() {
+        let masked = mask_indexing_noise(MATH_NOTE, &IndexingTextOptions::default());
+
+        assert_eq!(masked.len(), MATH_NOTE.len());
+        assert!(!masked.contains("mc^2"));
+        assert!(!masked.contains("alpha"));
+        assert!(!masked.contains("\\int"));
+        assert_eq!(masked.find("[math] for all"), MATH_NOTE.find("$$\n"));
+        assert_eq!(masked.find("Costs $5"), MATH_NOTE.find("Costs $5"));
+        assert!(masked.contains("`$code$`"));
+    }
 }
@@ -0,0 +1,339 @@
+use std::ops::Range;
+
+/// Stands in for display math in indexed text so searches can still tell a
+/// note has formulas.
+pub(crate) const MATH_PLACEHOLDER: &str = "[math]";
+
+// Commands whose argument is prose rather than TeX.
+const TEXT_COMMANDS: &[&str] = &["text", "textrm", "textit", "textbf", "textsf", "mbox"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MathKind {
+    /// `$...$` or `\(...\)`.
+    Inline,
+    /// `$$...$$` or `\[...\]`.
+    Display,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MathSpan {
+    /// Byte range of the formula, delimiters included.
+    pub range: Range<usize>,
+    pub kind: MathKind,
+}
+
+/// Formulas in `text`, outside code. A `$` only opens inline math when
+/// followed by a non-space, and only closes it when preceded by a non-space
+/// and not followed by a digit, so prices like `$5 and $10` stay text.
+/// Formulas never cross a blank line. Spans are sorted and disjoint.
+pub(crate) fn math_spans(text: &str) -> Vec<MathSpan> {
+    let bytes = text.as_bytes();
+    let fences = fenced_code_ranges(text);
+    let mut fences = fences.iter().peekable();
+    let mut spans = Vec::new();
+    let mut index = 0usize;
+
+    while index < bytes.len() {
+        while fences.next_if(|fence| fence.end <= index).is_some() {}
+        if let Some(fence) = fences.peek().filter(|fence| fence.start <= index) {
+            index = fence.end;
+            continue;
+        }
+
+        let next_fence = fences.peek().map(|fence| fence.start);
+        let limit = || block_end(text, index, next_fence);
+        let found =
+            match bytes[index] {
+                b'\\' => match bytes.get(index + 1) {
+                    Some(b'(') => find_after(text, index + 2, limit(), "\\)")
+                        .map(|end| (end, MathKind::Inline)),
+                    Some(b'[') => find_after(text, index + 2, limit(), "\\]")
+                        .map(|end| (end, MathKind::Display)),
+                    _ => {
+                        index += 2;
+                        continue;
+                    }
+                },
+                b'`' => {
+                    index = code_span_end(bytes, index);
+                    continue;
+                }
+                b'$' if bytes.get(index + 1) == Some(&b'$') => {
+                    match find_after(text, index + 2, limit(), "$$") {
+                        Some(end) => Some((end, MathKind::Display)),
+                        None => {
+                            index += 2;
+                            continue;
+                        }
+                    }
+                }
+                b'$' => inline_dollar_end(bytes, index, limit()).map(|end| (end, MathKind::Inline)),
+                _ => None,
+            };
+
+        match found {
+            Some((end, kind)) => {
+                spans.push(MathSpan {
+                    range: index..end,
+                    kind,
+                });
+                index = end;
+            }
+            None => index += 1,
+        }
+    }
+
+    spans
+}
+
+/// Drops every formula, for previews.
+pub(crate) fn strip_math(text: &str) -> String {
+    replace_math(text, |_, _| String::new())
+}
+
+/// Swaps every formula for its [`math_index_text`].
+pub(crate) fn replace_math_for_indexing(text: &str) -> String {
+    replace_math(text, math_index_text)
+}
+
+/// Like [`replace_math_for_indexing`], but written over the formula and
+/// padded with spaces so byte offsets stay valid for chunking. Words that
+/// do not fit in the formula's own length are left out.
+pub(crate) fn mask_math(text: &str) -> String {
+    let spans = math_spans(text);
+    if spans.is_empty() {
+        return text.to_string();
+    }
+
+    let mut bytes = text.as_bytes().to_vec();
+    for span in spans {
+        let replacement = math_index_text(&text[span.range.clone()], span.kind);
+        let fitted = fit_words(&replacement, span.range.len());
+        let target = &mut bytes[span.range];
+        target.fill(b' ');
+        target[..fitted.len()].copy_from_slice(fitted.as_bytes());
+    }
+    // Spans start and end on ASCII delimiters and the replacement is whole
+    // words, so the result stays valid UTF-8.
+    String::from_utf8(bytes).unwrap_or_else(|_| text.to_string())
+}
+
+/// What the index keeps of a formula: [`MATH_PLACEHOLDER`] for display
+/// math, then the prose inside `\text{}` and similar commands. TeX commands
+/// themselves are dropped.
+fn math_index_text(source: &str, kind: MathKind) -> String {
+    let mut words = Vec::new();
+    if kind == MathKind::Display {
+        words.push(MATH_PLACEHOLDER.to_string());
+    }
+    words.extend(text_command_contents(source));
+    words.join(" ")
+}
+
+fn replace_math(text: &str, replacement: impl Fn(&str, MathKind) -> String) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut cursor = 0usize;
+    for span in math_spans(text) {
+        output.push_str(&text[cursor..span.range.start]);
+        output.push_str(&replacement(&text[span.range.clone()], span.kind));
+        cursor = span.range.end;
+    }
+    output.push_str(&text[cursor..]);
+    output
+}
+
+fn text_command_contents(source: &str) -> Vec<String> {
+    let mut contents = Vec::new();
+    let mut rest = source;
+    while let Some(slash) = rest.find('\\') {
+        let after = &rest[slash + 1..];
+        let name_len = after
+            .find(|ch: char| !ch.is_ascii_alphabetic())
+            .unwrap_or(after.len());
+        let argument = after[name_len..].trim_start();
+        if TEXT_COMMANDS.contains(&&after[..name_len]) && argument.starts_with('{') {
+            let inner = braced_argument(argument);
+            let words = inner.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !words.is_empty() {
+                contents.push(words);
+            }
+            rest = &argument[1 + inner.len()..];
+        } else {
+            let skipped = match name_len {
+                0 => after.chars().next().map_or(0, char::len_utf8),
+                _ => name_len,
+            };
+            rest = &after[skipped..];
+        }
+    }
+    contents
+}
+
+/// The inside of a `{...}` group at the start of `text`, up to its matching
+/// brace or the end of the text.
+fn braced_argument(text: &str) -> &str {
+    let mut depth = 0usize;
+    for (index, ch) in text.char_indices().skip(1) {
+        match ch {
+            '{' => depth += 1,
+            '}' if depth == 0 => return &text[1..index],
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    &text[1..]
+}
+
+fn fit_words(text: &str, max_len: usize) -> &str {
+    if text.len() <= max_len {
+        return text;
+    }
+    let mut end = 0usize;
+    for (index, _) in text.match_indices(' ') {
+        if index > max_len {
+            break;
+        }
+        end = index;
+    }
+    &text[..end]
+}
+
+/// End of `delimiter` searched from `from`, if it closes before `limit`.
+fn find_after(text: &str, from: usize, limit: usize, delimiter: &str) -> Option<usize> {
+    let found = text.get(from..limit)?.find(delimiter)?;
+    Some(from + found + delimiter.len())
+}
+
+fn inline_dollar_end(bytes: &[u8], open: usize, limit: usize) -> Option<usize> {
+    if bytes.get(open + 1).is_none_or(u8::is_ascii_whitespace) {
+        return None;
+    }
+
+    let mut index = open + 1;
+    while index < limit {
+        match bytes[index] {
+            b'\\' => index += 2,
+            // Code spans win over math that would close inside them.
+            b'`' => return None,
+            b'$' if !bytes[index - 1].is_ascii_whitespace()
+                && !bytes.get(index + 1).is_some_and(u8::is_ascii_digit) =>
+            {
+                return Some(index + 1);
+            }
+            _ => index += 1,
+        }
+    }
+    None
+}
+
+/// Position after the inline code span opened at `start`, or after its
+/// backticks when the span is never closed.
+fn code_span_end(bytes: &[u8], start: usize) -> usize {
+    let run = bytes[start..]
+        .iter()
+        .take_while(|byte| **byte == b'`')
+        .count();
+    let mut index = start + run;
+    while index < bytes.len() {
+        if bytes[index] != b'`' {
+            index += 1;
+            continue;
+        }
+        let closing = bytes[index..]
+            .iter()
+            .take_while(|byte| **byte == b'`')
+            .count();
+        if closing == run {
+            return index + closing;
+        }
+        index += closing;
+    }
+    start + run
+}
+
+/// Start of the first blank line after `from`, or of the next code fence.
+fn block_end(text: &str, from: usize, next_fence: Option<usize>) -> usize {
+    let limit = next_fence.unwrap_or(text.len());
+    let mut offset = from;
+    for line in text[from..limit].split_inclusive('\n') {
+        if offset > from && line.trim().is_empty() {
+            return offset;
+        }
+        offset += line.len();
+    }
+    limit
+}
+
+fn fenced_code_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    // Start, fence character and fence length of the open block.
+    let mut open: Option<(usize, u8, usize)> = None;
+    let mut offset = 0usize;
+
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+        let marker = trimmed
+            .bytes()
+            .next()
+            .filter(|byte| *byte == b'`' || *byte == b'~');
+        let run = marker.map_or(0, |marker| {
+            trimmed.bytes().take_while(|byte| *byte == marker).count()
+        });
+
+        match (open, marker) {
+            (None, Some(marker)) if indent <= 3 && run >= 3 => {
+                open = Some((offset, marker, run));
+            }
+            (Some((start, fence, fence_len)), Some(marker))
+                if indent <= 3
+                    && marker == fence
+                    && run >= fence_len
+                    && trimmed[run..].trim().is_empty() =>
+            {
+                ranges.push(start..offset + line.len());
+                open = None;
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+    if let Some((start, _, _)) = open {
+        ranges.push(start..text.len());
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{math_spans, MathKind};
+
+    fn kinds_and_sources(text: &str) -> Vec<(MathKind, &str)> {
+        math_spans(text)
+            .into_iter()
+            .map(|span| (span.kind, &text[span.range]))
+            .collect()
+    }
+
+    #[test]
+    fn leaves_prices_escapes_and_code_alone() {
+        let raw = [
+            "Costs $5 and $10, or between $5-$10. Escaped \\$x\\$ too.",
+            "Inline `$a$` code.",
+            "```",
+            "$$b$$",
+            "```",
+            "Real $c$ here.",
+        ]
+        .join("\n");
+
+        assert_eq!(kinds_and_sources(&raw), vec![(MathKind::Inline, "$c$")]);
+    }
+
+    #[test]
+    fn formulas_do_not_cross_blank_lines() {
+        let raw = "Open $$ here\n\nand $$ there, then $x\n\ny$.";
+        assert!(math_spans(raw).is_empty());
+    }
+}
@@ -0,0 +1,383 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::directory::{compare_names, system_time_to_millis};
+use crate::lock::ensure_note_unlocked;
+use crate::note_path::is_note_path;
+use crate::preview::{get_note_preview, read_note_title, DEFAULT_PREVIEW_CHARS};
+
+/// Markers around the generated part of a map-of-content note. Everything
+/// outside them is left alone when the list is regenerated.
+pub const MOC_BLOCK_START: &str = "<!-- mdit:moc:start -->";
+pub const MOC_BLOCK_END: &str = "<!-- mdit:moc:end -->";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MocGroupBy {
+    #[default]
+    Folder,
+    Tag,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MocSortKey {
+    #[default]
+    Title,
+    Path,
+    /// Most recently modified first.
+    ModifiedAt,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MocOptions {
+    pub group_by: MocGroupBy,
+    pub sort_by: MocSortKey,
+    /// Follow each link with a short excerpt of the note.
+    pub include_previews: bool,
+}
+
+/// A note to list and the headings it appears under. Notes without a group
+/// are listed before the first heading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MocNote {
+    pub path: PathBuf,
+    pub groups: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MocResult {
+    pub path: PathBuf,
+    pub note_count: usize,
+    /// The note did not exist before.
+    pub created: bool,
+}
+
+#[derive(Clone)]
+struct MocItem {
+    link_target: String,
+    title: String,
+    stem: String,
+    modified_at: Option<i64>,
+    excerpt: Option<String>,
+}
+
+/// Markdown notes under `folder`, hidden entries excluded.
+pub fn collect_folder_notes(folder: &Path) -> Result<Vec<PathBuf>, String> {
+    if !folder.is_dir() {
+        return Err(format!("Folder does not exist: {}", folder.display()));
+    }
+    let mut notes = Vec::new();
+    collect_markdown(folder, &mut notes);
+    Ok(notes)
+}
+
+/// Subfolder of `note` relative to `folder`, or an empty group at the top.
+pub fn folder_group(folder: &Path, note: &Path) -> String {
+    note.parent()
+        .and_then(|parent| parent.strip_prefix(folder).ok())
+        .map(|rel_path| rel_path.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_default()
+}
+
+/// Writes the list of `notes` into the managed block of `moc_path`, creating
+/// the note when it does not exist yet. The note never lists itself.
+pub fn generate_moc(
+    vault_root: &Path,
+    moc_path: &Path,
+    notes: &[MocNote],
+    options: &MocOptions,
+) -> Result<MocResult, String> {
+    ensure_note_unlocked(moc_path)?;
+    let mut groups: BTreeMap<String, Vec<MocItem>> = BTreeMap::new();
+    let mut note_count = 0;
+    for note in notes.iter().filter(|note| note.path != moc_path) {
+        let Some(item) = load_item(vault_root, &note.path, options.include_previews) else {
+            continue;
+        };
+        note_count += 1;
+        if note.groups.is_empty() {
+            groups.entry(String::new()).or_default().push(item);
+            continue;
+        }
+        for group in &note.groups {
+            groups.entry(group.clone()).or_default().push(item.clone());
+        }
+    }
+
+    let body = render_groups(groups, options.sort_by);
+    let existing = match fs::read_to_string(moc_path) {
+        Ok(source) => Some(source),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+        Err(error) => return Err(format!("Failed to read file: {}", error)),
+    };
+    let created = existing.is_none();
+    let source = existing.unwrap_or_else(|| {
+        let title = moc_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        format!("# {title}\n")
+    });
+
+    if let Some(parent) = moc_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("Failed to create folder: {}", error))?;
+    }
+    fs::write(moc_path, replace_managed_block(&source, &body))
+        .map_err(|error| format!("Failed to write file: {}", error))?;
+
+    Ok(MocResult {
+        path: moc_path.to_path_buf(),
+        note_count,
+        created,
+    })
+}
+
+fn load_item(vault_root: &Path, path: &Path, include_preview: bool) -> Option<MocItem> {
+    let rel_path = path.strip_prefix(vault_root).ok()?;
+    let stem = path.file_stem()?.to_string_lossy().into_owned();
+    let link_target = rel_path
+        .with_extension("")
+        .to_string_lossy()
+        .replace('\\', "/");
+    let (heading, excerpt) = if include_preview {
+        let preview = get_note_preview(path, DEFAULT_PREVIEW_CHARS).ok()?;
+        // The excerpt starts with the heading, which is already the link text.
+        let excerpt = match &preview.title {
+            Some(heading) => preview
+                .excerpt
+                .strip_prefix(heading.as_str())
+                .map_or(preview.excerpt.as_str(), str::trim_start)
+                .to_string(),
+            None => preview.excerpt,
+        };
+        (
+            preview.title,
+            Some(excerpt).filter(|excerpt| !excerpt.is_empty()),
+        )
+    } else {
+        (read_note_title(path).ok().flatten(), None)
+    };
+    let title = heading.unwrap_or_else(|| stem.clone());
+    let modified_at = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(system_time_to_millis);
+
+    Some(MocItem {
+        link_target,
+        title,
+        stem,
+        modified_at,
+        excerpt,
+    })
+}
+
+fn render_groups(groups: BTreeMap<String, Vec<MocItem>>, sort_by: MocSortKey) -> String {
+    let mut groups = groups.into_iter().collect::<Vec<_>>();
+    // The ungrouped list leads, the headings follow in natural order.
+    groups.sort_by(|(a, _), (b, _)| match (a.is_empty(), b.is_empty()) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        _ => compare_names(a, b),
+    });
+
+    let mut sections = Vec::new();
+    for (group, mut items) in groups {
+        items.sort_by(|a, b| compare_items(a, b, sort_by));
+        let mut section = String::new();
+        if !group.is_empty() {
+            section.push_str(&format!("## {group}\n\n"));
+        }
+        for item in items {
+            section.push_str(&render_item(&item));
+        }
+        sections.push(section);
+    }
+    sections.join("\n")
+}
+
+fn compare_items(a: &MocItem, b: &MocItem, sort_by: MocSortKey) -> Ordering {
+    let title_order = compare_names(&a.title, &b.title);
+    match sort_by {
+        MocSortKey::Title => {
+            title_order.then_with(|| compare_names(&a.link_target, &b.link_target))
+        }
+        MocSortKey::Path => compare_names(&a.link_target, &b.link_target),
+        MocSortKey::ModifiedAt => match (a.modified_at, b.modified_at) {
+            (Some(a), Some(b)) => b.cmp(&a).then(title_order),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => title_order,
+        },
+    }
+}
+
+fn render_item(item: &MocItem) -> String {
+    let link = if item.title == item.stem {
+        format!("[[{}]]", item.link_target)
+    } else {
+        format!(
+            "[[{}|{}]]",
+            item.link_target,
+            item.title.replace(['[', ']', '|'], "")
+        )
+    };
+    match &item.excerpt {
+        Some(excerpt) => format!("- {link} — {}\n", excerpt.replace('\n', " ")),
+        None => format!("- {link}\n"),
+    }
+}
+
+/// Replaces the text between the markers with `body`, or appends a new block
+/// when the note has none.
+fn replace_managed_block(source: &str, body: &str) -> String {
+    let block = format!("{MOC_BLOCK_START}\n{body}{MOC_BLOCK_END}");
+    if let Some(start) = source.find(MOC_BLOCK_START) {
+        if let Some(end) = source[start..].find(MOC_BLOCK_END) {
+            let end = start + end + MOC_BLOCK_END.len();
+            return format!("{}{}{}", &source[..start], block, &source[end..]);
+        }
+    }
+
+    let mut updated = source.trim_end().to_string();
+    if !updated.is_empty() {
+        updated.push_str("\n\n");
+    }
+    updated.push_str(&block);
+    updated.push('\n');
+    updated
+}
+
+fn collect_markdown(dir: &Path, notes: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            collect_markdown(&path, notes);
+        } else if is_note_path(&path) {
+            notes.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{
+        collect_folder_notes, folder_group, generate_moc, MocNote, MocOptions, MOC_BLOCK_END,
+        MOC_BLOCK_START,
+    };
+
+    fn temp_vault(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-note-moc-{name}-{nanos}"));
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn folder_notes(root: &Path, folder: &str) -> Vec<MocNote> {
+        let folder = root.join(folder);
+        let mut notes = collect_folder_notes(&folder)
+            .unwrap()
+            .into_iter()
+            .map(|path| MocNote {
+                groups: vec![folder_group(&folder, &path)]
+                    .into_iter()
+                    .filter(|group| !group.is_empty())
+                    .collect(),
+                path,
+            })
+            .collect::<Vec<_>>();
+        notes.sort_by(|a, b| a.path.cmp(&b.path));
+        notes
+    }
+
+    #[test]
+    fn lists_notes_grouped_by_subfolder_with_previews() {
+        let root = temp_vault("folder");
+        fs::create_dir_all(root.join("Projects/Active")).unwrap();
+        fs::write(root.join("Projects/beta.md"), "# Beta\n\nSecond project.").unwrap();
+        fs::write(root.join("Projects/alpha.md"), "First project.").unwrap();
+        fs::write(root.join("Projects/Active/gamma.md"), "# Gamma\n").unwrap();
+        let moc_path = root.join("Projects/Projects MOC.md");
+
+        let result = generate_moc(
+            &root,
+            &moc_path,
+            &folder_notes(&root, "Projects"),
+            &MocOptions {
+                include_previews: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(result.created);
+        assert_eq!(result.note_count, 3);
+        assert_eq!(
+            fs::read_to_string(&moc_path).unwrap(),
+            format!(
+                "# Projects MOC\n\n{MOC_BLOCK_START}\n\
+                 - [[Projects/alpha]] — First project.\n\
+                 - [[Projects/beta|Beta]] — Second project.\n\
+                 \n## Active\n\n\
+                 - [[Projects/Active/gamma|Gamma]]\n\
+                 {MOC_BLOCK_END}\n"
+            )
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn regenerating_keeps_edits_outside_the_markers() {
+        let root = temp_vault("regenerate");
+        fs::create_dir_all(root.join("Notes")).unwrap();
+        fs::write(root.join("Notes/one.md"), "one").unwrap();
+        let moc_path = root.join("Index.md");
+        fs::write(
+            &moc_path,
+            format!(
+                "# Index\n\nIntro.\n\n{MOC_BLOCK_START}\n- stale\n{MOC_BLOCK_END}\n\nFooter.\n"
+            ),
+        )
+        .unwrap();
+        fs::write(root.join("Notes/two.md"), "two").unwrap();
+
+        let result = generate_moc(
+            &root,
+            &moc_path,
+            &folder_notes(&root, "Notes"),
+            &MocOptions::default(),
+        )
+        .unwrap();
+
+        assert!(!result.created);
+        assert_eq!(
+            fs::read_to_string(&moc_path).unwrap(),
+            format!(
+                "# Index\n\nIntro.\n\n{MOC_BLOCK_START}\n- [[Notes/one]]\n- [[Notes/two]]\n\
+                 {MOC_BLOCK_END}\n\nFooter.\n"
+            )
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,69 @@
+use std::path::Path;
+
+/// Extensions of the files treated as notes, lowercase.
+pub const NOTE_EXTENSIONS: [&str; 2] = ["md", "mdx"];
+
+/// Whether `path` names a note file, `.md` or `.mdx` in any letter case.
+/// Only the extension is checked; the file need not exist.
+pub fn is_note_path(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            NOTE_EXTENSIONS
+                .iter()
+                .any(|note_extension| extension.eq_ignore_ascii_case(note_extension))
+        })
+}
+
+/// Extensions of the drawing files whose text is indexed alongside notes,
+/// lowercase.
+pub const DRAWING_EXTENSIONS: [&str; 2] = ["excalidraw", "tldraw"];
+
+/// Whether `path` names an Excalidraw or tldraw drawing, by extension in any
+/// letter case.
+pub fn is_drawing_path(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            DRAWING_EXTENSIONS
+                .iter()
+                .any(|drawing_extension| extension.eq_ignore_ascii_case(drawing_extension))
+        })
+}
+
+/// Whether `path` names a file the vault index keeps a document for: a note
+/// or a drawing.
+pub fn is_indexed_path(path: impl AsRef<Path>) -> bool {
+    is_note_path(&path) || is_drawing_path(&path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{is_drawing_path, is_indexed_path, is_note_path};
+
+    #[test]
+    fn matches_markdown_extensions_in_any_case() {
+        for path in ["a.md", "dir/B.MD", "c.mdx", "d.Mdx", "e.f.md"] {
+            assert!(is_note_path(path), "{path}");
+        }
+        for path in ["a.markdown", "b.txt", "md", ".md/c", "d.md.bak"] {
+            assert!(!is_note_path(path), "{path}");
+        }
+        assert!(is_note_path(Path::new("/vault/Note.MDX")));
+    }
+
+    #[test]
+    fn matches_drawing_extensions_in_any_case() {
+        for path in ["Board.excalidraw", "sketches/Flow.TLDRAW"] {
+            assert!(is_drawing_path(path), "{path}");
+            assert!(is_indexed_path(path), "{path}");
+        }
+        assert!(!is_drawing_path("Board.excalidraw.md"));
+        assert!(is_indexed_path("Board.excalidraw.md"));
+        assert!(!is_indexed_path("Board.png"));
+    }
+}
@@ -0,0 +1,206 @@
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use serde::Serialize;
+
+/// Fence languages the editor draws as diagrams instead of showing as code.
+pub const DIAGRAM_LANGUAGES: &[&str] = &["mermaid", "plantuml", "puml"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteOutline {
+    pub headings: Vec<OutlineHeading>,
+    /// Fenced code blocks in document order. Indented code has no language
+    /// and is left out.
+    pub code_blocks: Vec<OutlineCodeBlock>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineHeading {
+    pub level: u8,
+    pub text: String,
+    /// Byte offsets of the heading line in the source file.
+    pub start: usize,
+    pub end: usize,
+    /// 1-based line of the heading.
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineCodeBlock {
+    /// First word of the info string, lowercased, such as `rust` for
+    /// ` ```rust title="main.rs" `.
+    pub language: Option<String>,
+    /// Whether the language is one of [`DIAGRAM_LANGUAGES`].
+    pub renderable: bool,
+    /// Byte offsets of the whole block, fences included, in the source file.
+    pub start: usize,
+    pub end: usize,
+    /// 1-based lines of the opening and closing fences.
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+pub fn get_note_outline(path: &Path) -> Result<NoteOutline, String> {
+    let source = fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    Ok(build_note_outline(&source))
+}
+
+/// Headings and fenced code blocks of `raw`, with offsets into `raw` so the
+/// editor can decide lazily which blocks to render.
+pub fn build_note_outline(raw: &str) -> NoteOutline {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
+
+    let lines = LineIndex::new(raw);
+    let mut outline = NoteOutline::default();
+    let mut heading: Option<(u8, String, Range<usize>)> = None;
+
+    for (event, range) in Parser::new_ext(raw, options).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                heading = Some((level as u8, String::new(), range));
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((level, text, range)) = heading.take() {
+                    let range = trim_trailing_newline(raw, range);
+                    outline.headings.push(OutlineHeading {
+                        level,
+                        text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+                        line: lines.line_of(range.start),
+                        start: range.start,
+                        end: range.end,
+                    });
+                }
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, heading_text, _)) = heading.as_mut() {
+                    heading_text.push_str(&text);
+                }
+            }
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+                let language = fence_language(&info);
+                let range = trim_trailing_newline(raw, range);
+                outline.code_blocks.push(OutlineCodeBlock {
+                    renderable: language.as_deref().is_some_and(is_diagram_language),
+                    language,
+                    start_line: lines.line_of(range.start),
+                    end_line: lines.line_of(range.end.saturating_sub(1).max(range.start)),
+                    start: range.start,
+                    end: range.end,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    outline
+}
+
+pub(crate) fn is_diagram_language(language: &str) -> bool {
+    DIAGRAM_LANGUAGES
+        .iter()
+        .any(|diagram| language.eq_ignore_ascii_case(diagram))
+}
+
+/// `rust` from `rust title="a.rs"`, and `python` from `{.python}`.
+fn fence_language(info: &str) -> Option<String> {
+    let word = info.split_whitespace().next()?;
+    let word = word.trim_matches(['{', '}']).trim_start_matches('.');
+    (!word.is_empty()).then(|| word.to_lowercase())
+}
+
+fn trim_trailing_newline(raw: &str, range: Range<usize>) -> Range<usize> {
+    let trimmed = raw[range.clone()].trim_end_matches(['\n', '\r']);
+    range.start..range.start + trimmed.len()
+}
+
+struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    fn new(text: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self { line_starts }
+    }
+
+    fn line_of(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|start| *start <= offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_note_outline, OutlineCodeBlock, OutlineHeading};
+
+    #[test]
+    fn reports_headings_and_fenced_block_languages_with_offsets() {
+        let raw = [
+            "---",
+            "title: ignored",
+            "---",
+            "# Design `v2`",
+            "",
+            "```mermaid",
+            "graph TD",
+            "```",
+            "",
+            "~~~{.Python}",
+            "print(1)",
+            "~~~",
+            "```",
+            "plain",
+            "```",
+        ]
+        .join("\n");
+
+        let outline = build_note_outline(&raw);
+
+        let heading_start = raw.find("# Design").expect("heading");
+        assert_eq!(
+            outline.headings,
+            vec![OutlineHeading {
+                level: 1,
+                text: "Design v2".to_string(),
+                start: heading_start,
+                end: heading_start + "# Design `v2`".len(),
+                line: 4,
+            }]
+        );
+
+        let mermaid_start = raw.find("```mermaid").expect("mermaid");
+        assert_eq!(
+            outline.code_blocks[0],
+            OutlineCodeBlock {
+                language: Some("mermaid".to_string()),
+                renderable: true,
+                start: mermaid_start,
+                end: mermaid_start + "```mermaid\ngraph TD\n```".len(),
+                start_line: 6,
+                end_line: 8,
+            }
+        );
+        let languages = outline
+            .code_blocks
+            .iter()
+            .map(|block| (block.language.as_deref(), block.renderable))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            languages,
+            vec![
+                (Some("mermaid"), true),
+                (Some("python"), false),
+                (None, false)
+            ]
+        );
+        assert_eq!(outline.code_blocks[2].end, raw.len());
+    }
+}
@@ -2,25 +2,174 @@ use std::fs::File;
 use std::io::Read;
 use std::path::Path;
 
-const PREVIEW_BYTES: usize = 500;
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag, TagEnd};
+use serde::Serialize;
 
-pub fn get_note_preview(path: &Path) -> Result<String, String> {
-    let mut file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
-    let mut buffer = vec![0u8; PREVIEW_BYTES];
+use crate::markdown_text::{format_preview_text, strip_frontmatter, strip_hidden_chars};
 
-    match file.read(&mut buffer) {
-        Ok(bytes_read) => {
-            if bytes_read == 0 {
-                return Ok(String::new());
+pub const DEFAULT_PREVIEW_CHARS: usize = 200;
+// Enough to get past long frontmatter blocks without reading whole notes.
+const PREVIEW_READ_BYTES: u64 = 16 * 1024;
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp", "avif"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotePreview {
+    /// Text of the first heading, if the note has one.
+    pub title: Option<String>,
+    /// Plain text excerpt of at most the requested number of characters.
+    pub excerpt: String,
+    /// Destination of the first image, as written in the note.
+    pub image_path: Option<String>,
+}
+
+pub fn get_note_preview(path: &Path, max_chars: usize) -> Result<NotePreview, String> {
+    let source = read_preview_source(path)?;
+    Ok(build_note_preview(&source, max_chars))
+}
+
+/// Text of the first heading of the note at `path`, without building an excerpt.
+pub(crate) fn read_note_title(path: &Path) -> Result<Option<String>, String> {
+    let source = read_preview_source(path)?;
+    let body = strip_frontmatter(&strip_hidden_chars(&source));
+    Ok(first_heading_and_image(&body).0)
+}
+
+fn read_preview_source(path: &Path) -> Result<String, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let mut buffer = Vec::new();
+    file.take(PREVIEW_READ_BYTES)
+        .read_to_end(&mut buffer)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    Ok(valid_utf8_prefix(&buffer).to_string())
+}
+
+pub fn build_note_preview(raw: &str, max_chars: usize) -> NotePreview {
+    let body = strip_frontmatter(&strip_hidden_chars(raw));
+    let (title, image_path) = first_heading_and_image(&body);
+
+    NotePreview {
+        title,
+        excerpt: truncate_chars(&format_preview_text(raw), max_chars),
+        image_path,
+    }
+}
+
+/// Longest prefix of `bytes` that is valid UTF-8. The read limit can split a
+/// multibyte character, which must not turn into a replacement character.
+fn valid_utf8_prefix(bytes: &[u8]) -> &str {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(error) => {
+            let valid = &bytes[..error.valid_up_to()];
+            std::str::from_utf8(valid).unwrap_or_default()
+        }
+    }
+}
+
+fn first_heading_and_image(body: &str) -> (Option<String>, Option<String>) {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_WIKILINKS);
+
+    let mut title: Option<String> = None;
+    let mut heading_text: Option<String> = None;
+    let mut image_path: Option<String> = None;
+
+    for event in Parser::new_ext(body, options) {
+        match event {
+            Event::Start(Tag::Heading { .. }) if title.is_none() => {
+                heading_text = Some(String::new());
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some(text) = heading_text.take() {
+                    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if !text.is_empty() {
+                        title = Some(text);
+                    }
+                }
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = heading_text.as_mut() {
+                    heading.push_str(&text);
+                }
             }
-            buffer.truncate(bytes_read);
-            let preview = String::from_utf8_lossy(&buffer);
-            Ok(format_preview_text(preview.as_ref()))
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                ..
+            }) if image_path.is_none() && is_previewable_image(link_type, &dest_url) => {
+                image_path = Some(dest_url.to_string());
+            }
+            _ => {}
+        }
+
+        if title.is_some() && image_path.is_some() {
+            break;
         }
-        Err(e) => Err(format!("Failed to read file: {}", e)),
+    }
+
+    (title, image_path)
+}
+
+fn is_previewable_image(link_type: LinkType, dest_url: &str) -> bool {
+    if dest_url.is_empty() || dest_url.starts_with("data:") {
+        return false;
+    }
+
+    // `![[...]]` also embeds notes, so only image files count.
+    if matches!(link_type, LinkType::WikiLink { .. }) {
+        return Path::new(dest_url)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                IMAGE_EXTENSIONS
+                    .iter()
+                    .any(|image| extension.eq_ignore_ascii_case(image))
+            });
+    }
+
+    true
+}
+
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((cut, _)) => format!("{}…", text[..cut].trim_end()),
+        None => text.to_string(),
     }
 }
 
-pub fn format_preview_text(raw: &str) -> String {
-    super::markdown_text::format_preview_text(raw)
+#[cfg(test)]
+mod tests {
+    use super::{build_note_preview, valid_utf8_prefix};
+
+    #[test]
+    fn skips_frontmatter_and_reads_first_heading_and_image() {
+        let raw = "---\ntitle: ignored\ntags: [a]\n---\nIntro line\n\n## Trip to Seoul\n\n![cover](assets/cover.png)\n";
+        let preview = build_note_preview(raw, 200);
+
+        assert_eq!(preview.title.as_deref(), Some("Trip to Seoul"));
+        assert_eq!(preview.image_path.as_deref(), Some("assets/cover.png"));
+        assert_eq!(preview.excerpt, "Intro line Trip to Seoul");
+    }
+
+    #[test]
+    fn truncates_excerpt_by_characters() {
+        let preview = build_note_preview("한국어 문장입니다", 3);
+        assert_eq!(preview.excerpt, "한국어…");
+    }
+
+    #[test]
+    fn only_counts_wiki_embeds_of_image_files() {
+        let preview = build_note_preview("![[Other note]]\n\n![[photos/Beach.JPG|200]]", 200);
+        assert_eq!(preview.image_path.as_deref(), Some("photos/Beach.JPG"));
+        assert_eq!(preview.title, None);
+    }
+
+    #[test]
+    fn drops_multibyte_character_split_by_read_limit() {
+        let bytes = "가나".as_bytes();
+        assert_eq!(valid_utf8_prefix(&bytes[..4]), "가");
+    }
 }
@@ -0,0 +1,127 @@
+use std::ops::Range;
+
+// Unbroken runs longer than this are hashes, minified code, or encoded payloads
+// rather than words, so only their head is kept for indexing.
+const MAX_INDEXED_TOKEN_CHARS: usize = 128;
+const DATA_URI_SCHEME: &str = "data:";
+// Longest media type plus parameters accepted before the comma of a data URI.
+const MAX_DATA_URI_HEADER_LEN: usize = 128;
+
+/// Byte ranges of `text` that should not reach the index: data URIs and the
+/// tail of extremely long unbroken tokens. Ranges are sorted and disjoint.
+pub(crate) fn noise_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+
+    for (token_start, token) in whitespace_tokens(text) {
+        let token_end = token_start + token.len();
+        let mut search_from = 0usize;
+        while let Some(found) = token[search_from..].find(DATA_URI_SCHEME) {
+            let uri_start = search_from + found;
+            search_from = uri_start + DATA_URI_SCHEME.len();
+            if let Some(uri_len) = data_uri_len(token, uri_start) {
+                ranges.push(token_start + uri_start..token_start + uri_start + uri_len);
+                search_from = uri_start + uri_len;
+            }
+        }
+
+        if let Some((cut, _)) = token.char_indices().nth(MAX_INDEXED_TOKEN_CHARS) {
+            ranges.push(token_start + cut..token_end);
+        }
+    }
+
+    merge_ranges(ranges)
+}
+
+/// Drop noise from `text` for full-text indexing.
+pub(crate) fn remove_noise(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut cursor = 0usize;
+    for range in noise_ranges(text) {
+        output.push_str(&text[cursor..range.start]);
+        cursor = range.end;
+    }
+    output.push_str(&text[cursor..]);
+    output
+}
+
+/// Overwrite `ranges` of `text` with spaces, keeping every byte offset intact.
+pub(crate) fn blank_ranges(text: &str, ranges: &[Range<usize>]) -> String {
+    let mut bytes = text.as_bytes().to_vec();
+    for range in ranges {
+        bytes[range.clone()].fill(b' ');
+    }
+    // Ranges always cover whole characters, so the result stays valid UTF-8.
+    String::from_utf8(bytes).unwrap_or_else(|_| text.to_string())
+}
+
+pub(crate) fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+fn whitespace_tokens(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(char::is_whitespace)
+        .filter(|token| !token.is_empty())
+        .map(move |token| (token.as_ptr() as usize - text.as_ptr() as usize, token))
+}
+
+/// Length of the data URI starting at `start` in `token`, if there is one.
+fn data_uri_len(token: &str, start: usize) -> Option<usize> {
+    // Reject words that merely end in "data", such as "metadata:".
+    let preceded_by_word = token[..start]
+        .chars()
+        .next_back()
+        .is_some_and(|ch| ch.is_alphanumeric());
+    if preceded_by_word {
+        return None;
+    }
+
+    let rest = &token[start + DATA_URI_SCHEME.len()..];
+    let comma = rest.find(',')?;
+    let header = &rest[..comma];
+    let header_is_valid = comma <= MAX_DATA_URI_HEADER_LEN
+        && header
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "/+-.;=".contains(ch));
+    if !header_is_valid {
+        return None;
+    }
+
+    let payload = &rest[comma + 1..];
+    let payload_len = payload
+        .find([')', '"', '\'', '>', ']'])
+        .unwrap_or(payload.len());
+
+    Some(DATA_URI_SCHEME.len() + comma + 1 + payload_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{noise_ranges, remove_noise};
+
+    #[test]
+    fn removes_data_uris_but_keeps_surrounding_markdown() {
+        let raw = "See ![chart](data:image/png;base64,iVBORw0KGgoAAAANSUhEUg==) here";
+        assert_eq!(remove_noise(raw), "See ![chart]() here");
+    }
+
+    #[test]
+    fn ignores_words_that_only_end_in_data() {
+        let raw = "metadata:value and data without a scheme";
+        assert!(noise_ranges(raw).is_empty());
+    }
+
+    #[test]
+    fn truncates_extremely_long_tokens() {
+        let token = "a".repeat(300);
+        let cleaned = remove_noise(&format!("start {token} end"));
+        assert_eq!(cleaned, format!("start {} end", "a".repeat(128)));
+    }
+}
@@ -94,6 +94,13 @@ impl BlockingOllamaEmbeddingClient {
 
         Ok(embedding)
     }
+
+    /// Local models that report the embedding capability.
+    pub fn list_embedding_models(&self) -> Result<Vec<String>> {
+        self.runtime
+            .block_on(list_model_catalog())
+            .map(|catalog| catalog.embedding_models)
+    }
 }
 
 fn build_catalog_from_inspections(
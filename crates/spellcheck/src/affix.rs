@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Context, Result};
+
+/// A flag as hunspell stores it: one character, two characters packed
+/// together with `FLAG long`, or a number with `FLAG num`.
+pub(crate) type Flag = u32;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum FlagMode {
+    #[default]
+    Char,
+    Long,
+    Num,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AffixRule {
+    pub kind: AffixKind,
+    pub flag: Flag,
+    /// Whether the rule combines with rules of the other kind.
+    pub cross_product: bool,
+    pub strip: String,
+    pub add: String,
+    /// Flags of the affixed word, for rules that allow a second suffix.
+    pub continuation: Vec<Flag>,
+    condition: Vec<ConditionItem>,
+}
+
+impl AffixRule {
+    /// Applies the rule to `stem` if the stem meets its condition.
+    pub fn apply(&self, stem: &str) -> Option<String> {
+        match self.kind {
+            AffixKind::Suffix => {
+                let base = stem.strip_suffix(self.strip.as_str())?;
+                if !condition_matches(&self.condition, stem.chars().rev(), true) {
+                    return None;
+                }
+                Some(format!("{base}{}", self.add))
+            }
+            AffixKind::Prefix => {
+                let base = stem.strip_prefix(self.strip.as_str())?;
+                if !condition_matches(&self.condition, stem.chars(), false) {
+                    return None;
+                }
+                Some(format!("{}{base}", self.add))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ConditionItem {
+    Any,
+    Char(char),
+    Set { negated: bool, chars: Vec<char> },
+}
+
+impl ConditionItem {
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            ConditionItem::Any => true,
+            ConditionItem::Char(expected) => *expected == ch,
+            ConditionItem::Set { negated, chars } => chars.contains(&ch) != *negated,
+        }
+    }
+}
+
+/// The parts of an `.aff` file the checker uses. Compounding rules and
+/// morphology are not supported and are skipped.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AffixFile {
+    flag_mode: FlagMode,
+    /// `AF` flag aliases, numbered from 1 in the `.dic` file.
+    aliases: Vec<Vec<Flag>>,
+    pub rules: Vec<AffixRule>,
+    /// Characters tried, most common first, when suggesting corrections.
+    pub try_chars: Vec<char>,
+    /// `REP` pairs of common misspellings and their fixes.
+    pub replacements: Vec<(String, String)>,
+    pub need_affix: Option<Flag>,
+    pub forbidden: Option<Flag>,
+    pub no_suggest: Option<Flag>,
+}
+
+impl AffixFile {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut affix = AffixFile::default();
+        // Rule headers announce how many rule lines follow.
+        let mut rule_headers: HashMap<(AffixKind, Flag), bool> = HashMap::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let mut fields = line.split_whitespace();
+            let Some(keyword) = fields.next() else {
+                continue;
+            };
+            let fields = fields.collect::<Vec<_>>();
+            let line_no = index + 1;
+
+            match keyword {
+                "FLAG" => {
+                    affix.flag_mode = match fields.first().copied() {
+                        Some("long") => FlagMode::Long,
+                        Some("num") => FlagMode::Num,
+                        _ => FlagMode::Char,
+                    }
+                }
+                "AF" if fields.len() == 1 && fields[0].parse::<usize>().is_ok() => {}
+                "AF" => {
+                    let flags = fields.first().copied().unwrap_or_default();
+                    let parsed = affix.parse_flags(flags);
+                    affix.aliases.push(parsed);
+                }
+                "TRY" => {
+                    affix.try_chars = fields
+                        .first()
+                        .map_or_else(Vec::new, |chars| chars.chars().collect());
+                }
+                "REP" if fields.len() >= 2 => {
+                    affix
+                        .replacements
+                        .push((fields[0].replace('_', " "), fields[1].replace('_', " ")));
+                }
+                "NEEDAFFIX" | "PSEUDOROOT" => affix.need_affix = affix.single_flag(&fields),
+                "FORBIDDENWORD" => affix.forbidden = affix.single_flag(&fields),
+                "NOSUGGEST" => affix.no_suggest = affix.single_flag(&fields),
+                "PFX" | "SFX" => {
+                    let kind = if keyword == "PFX" {
+                        AffixKind::Prefix
+                    } else {
+                        AffixKind::Suffix
+                    };
+                    let flag = affix
+                        .single_flag(&fields)
+                        .ok_or_else(|| anyhow!("Affix line {line_no} has no flag"))?;
+
+                    if let Some(&cross_product) = rule_headers.get(&(kind, flag)) {
+                        if fields.len() < 4 {
+                            continue;
+                        }
+                        let rule = affix
+                            .parse_rule(kind, flag, cross_product, &fields)
+                            .with_context(|| format!("Invalid affix rule on line {line_no}"))?;
+                        affix.rules.push(rule);
+                    } else {
+                        rule_headers.insert((kind, flag), fields.get(1) == Some(&"Y"));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(affix)
+    }
+
+    /// Flags of a `.dic` entry, resolving `AF` aliases.
+    pub fn entry_flags(&self, raw: &str) -> Vec<Flag> {
+        if !self.aliases.is_empty() {
+            if let Ok(alias) = raw.parse::<usize>() {
+                return alias
+                    .checked_sub(1)
+                    .and_then(|index| self.aliases.get(index))
+                    .cloned()
+                    .unwrap_or_default();
+            }
+        }
+        self.parse_flags(raw)
+    }
+
+    fn parse_flags(&self, raw: &str) -> Vec<Flag> {
+        match self.flag_mode {
+            FlagMode::Char => raw.chars().map(Flag::from).collect(),
+            FlagMode::Long => raw
+                .chars()
+                .collect::<Vec<_>>()
+                .chunks(2)
+                .map(|pair| {
+                    pair.iter()
+                        .fold(0, |flag, ch| (flag << 16) | Flag::from(*ch))
+                })
+                .collect(),
+            FlagMode::Num => raw
+                .split(',')
+                .filter_map(|number| number.trim().parse().ok())
+                .collect(),
+        }
+    }
+
+    fn single_flag(&self, fields: &[&str]) -> Option<Flag> {
+        self.parse_flags(fields.first()?).first().copied()
+    }
+
+    fn parse_rule(
+        &self,
+        kind: AffixKind,
+        flag: Flag,
+        cross_product: bool,
+        fields: &[&str],
+    ) -> Result<AffixRule> {
+        let strip = zero_as_empty(fields[1]);
+        let (add, continuation) = match fields[2].split_once('/') {
+            Some((add, flags)) => (zero_as_empty(add), self.entry_flags(flags)),
+            None => (zero_as_empty(fields[2]), Vec::new()),
+        };
+        let condition = parse_condition(fields[3])?;
+
+        Ok(AffixRule {
+            kind,
+            flag,
+            cross_product,
+            strip,
+            add,
+            continuation,
+            condition,
+        })
+    }
+}
+
+fn zero_as_empty(field: &str) -> String {
+    if field == "0" {
+        String::new()
+    } else {
+        field.to_string()
+    }
+}
+
+fn parse_condition(raw: &str) -> Result<Vec<ConditionItem>> {
+    if raw == "." {
+        return Ok(Vec::new());
+    }
+
+    let mut items = Vec::new();
+    let mut chars = raw.chars();
+    while let Some(ch) = chars.next() {
+        let item = match ch {
+            '.' => ConditionItem::Any,
+            '[' => {
+                let mut set = Vec::new();
+                let mut closed = false;
+                for ch in chars.by_ref() {
+                    if ch == ']' {
+                        closed = true;
+                        break;
+                    }
+                    set.push(ch);
+                }
+                if !closed {
+                    return Err(anyhow!("Unclosed `[` in condition `{raw}`"));
+                }
+                let negated = set.first() == Some(&'^');
+                if negated {
+                    set.remove(0);
+                }
+                ConditionItem::Set {
+                    negated,
+                    chars: set,
+                }
+            }
+            _ => ConditionItem::Char(ch),
+        };
+        items.push(item);
+    }
+    Ok(items)
+}
+
+/// Matches the condition against the start of the stem, or against its end
+/// when `from_end` is set and `chars` runs backwards.
+fn condition_matches(
+    condition: &[ConditionItem],
+    mut chars: impl Iterator<Item = char>,
+    from_end: bool,
+) -> bool {
+    let mut check = |item: &ConditionItem| chars.next().is_some_and(|ch| item.matches(ch));
+    if from_end {
+        condition.iter().rev().all(&mut check)
+    } else {
+        condition.iter().all(&mut check)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AffixFile, AffixKind};
+
+    #[test]
+    fn parses_rules_conditions_and_long_flags() {
+        let source = "\
+SET UTF-8
+FLAG long
+TRY esiarn
+REP 2
+REP f ph
+REP alot a_lot
+
+SFX Dd Y 2
+SFX Dd y ied [^aeiou]y
+SFX Dd 0 ed [^y]
+PFX Re N 1
+PFX Re 0 re/Dd .
+";
+        let affix = AffixFile::parse(source).expect("valid affix file");
+
+        assert_eq!(affix.try_chars, vec!['e', 's', 'i', 'a', 'r', 'n']);
+        assert_eq!(
+            affix.replacements[1],
+            ("alot".to_string(), "a lot".to_string())
+        );
+        assert_eq!(affix.rules.len(), 3);
+
+        let [ied, ed, re] = [&affix.rules[0], &affix.rules[1], &affix.rules[2]];
+        assert_eq!(ied.apply("cry").as_deref(), Some("cried"));
+        assert_eq!(ied.apply("play"), None);
+        assert_eq!(ed.apply("walk").as_deref(), Some("walked"));
+        assert_eq!(re.kind, AffixKind::Prefix);
+        assert_eq!(re.apply("play").as_deref(), Some("replay"));
+        assert_eq!(re.continuation, vec![ied.flag]);
+        assert_eq!(affix.entry_flags("DdRe"), vec![ied.flag, re.flag]);
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::affix::{AffixFile, AffixKind, AffixRule, Flag};
+
+/// A hunspell dictionary with every affixed form expanded up front, so
+/// checking a word is a set lookup.
+#[derive(Debug, Clone, Default)]
+pub struct Dictionary {
+    words: HashSet<String>,
+    forbidden: HashSet<String>,
+    /// Correct words that are never offered as suggestions.
+    no_suggest: HashSet<String>,
+    pub(crate) try_chars: Vec<char>,
+    pub(crate) replacements: Vec<(String, String)>,
+}
+
+impl Dictionary {
+    /// Loads an `.aff`/`.dic` pair. Files that are not UTF-8 are read as
+    /// ISO-8859-1, the other encoding common among hunspell dictionaries.
+    pub fn load(aff_path: &Path, dic_path: &Path) -> Result<Self> {
+        let aff = read_dictionary_file(aff_path)?;
+        let dic = read_dictionary_file(dic_path)?;
+        Self::from_hunspell(&aff, &dic)
+            .with_context(|| format!("Failed to load dictionary {}", dic_path.display()))
+    }
+
+    pub fn from_hunspell(aff: &str, dic: &str) -> Result<Self> {
+        let affix = AffixFile::parse(aff)?;
+        let mut rules_by_flag: HashMap<Flag, Vec<&AffixRule>> = HashMap::new();
+        for rule in &affix.rules {
+            rules_by_flag.entry(rule.flag).or_default().push(rule);
+        }
+
+        let mut expander = Expander {
+            affix: &affix,
+            rules_by_flag,
+            dictionary: Dictionary {
+                try_chars: affix.try_chars.clone(),
+                replacements: affix.replacements.clone(),
+                ..Dictionary::default()
+            },
+        };
+
+        // The first line holds the approximate entry count.
+        for line in dic.lines().skip(1) {
+            let Some(entry) = line.split_whitespace().next() else {
+                continue;
+            };
+            let (stem, flags) = split_entry(entry);
+            let flags = flags
+                .map(|flags| affix.entry_flags(flags))
+                .unwrap_or_default();
+            expander.expand(&stem, &flags);
+        }
+
+        let mut dictionary = expander.dictionary;
+        dictionary
+            .words
+            .retain(|word| !dictionary.forbidden.contains(word));
+        Ok(dictionary)
+    }
+
+    /// Whether `word` is spelled correctly. Like hunspell, a capitalized or
+    /// all-caps word is accepted when its lowercase form is.
+    pub fn check(&self, word: &str) -> bool {
+        let word = normalize_apostrophes(word);
+        if self.forbidden.contains(&word) {
+            return false;
+        }
+        if self.words.contains(&word) {
+            return true;
+        }
+
+        let lower = word.to_lowercase();
+        match Casing::of(&word) {
+            Casing::Capitalized => self.words.contains(&lower),
+            Casing::Upper => {
+                self.words.contains(&lower) || self.words.contains(&capitalize(&lower))
+            }
+            Casing::Lower | Casing::Mixed => false,
+        }
+    }
+
+    pub(crate) fn can_suggest(&self, word: &str) -> bool {
+        !self.no_suggest.contains(word) && self.check(word)
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.words.len()
+    }
+}
+
+struct Expander<'a> {
+    affix: &'a AffixFile,
+    rules_by_flag: HashMap<Flag, Vec<&'a AffixRule>>,
+    dictionary: Dictionary,
+}
+
+impl<'a> Expander<'a> {
+    fn expand(&mut self, stem: &str, flags: &[Flag]) {
+        let has = |flag: Option<Flag>| flag.is_some_and(|flag| flags.contains(&flag));
+        if has(self.affix.forbidden) {
+            self.dictionary.forbidden.insert(stem.to_string());
+            return;
+        }
+        if !has(self.affix.need_affix) {
+            self.insert(stem.to_string(), has(self.affix.no_suggest));
+        }
+
+        let mut cross_suffixed = Vec::new();
+        for rule in self.rules(flags, AffixKind::Suffix) {
+            let Some(word) = rule.apply(stem) else {
+                continue;
+            };
+            // A suffix may allow a second suffix on top of it.
+            for second in self.rules(&rule.continuation, AffixKind::Suffix) {
+                if let Some(twice) = second.apply(&word) {
+                    self.insert(twice, false);
+                }
+            }
+            if rule.cross_product {
+                cross_suffixed.push(word.clone());
+            }
+            let needs_more = self
+                .affix
+                .need_affix
+                .is_some_and(|flag| rule.continuation.contains(&flag));
+            if !needs_more {
+                self.insert(word, false);
+            }
+        }
+
+        for rule in self.rules(flags, AffixKind::Prefix) {
+            let Some(word) = rule.apply(stem) else {
+                continue;
+            };
+            if rule.cross_product {
+                for suffixed in &cross_suffixed {
+                    if let Some(both) = rule.apply(suffixed) {
+                        self.insert(both, false);
+                    }
+                }
+            }
+            for suffix in self.rules(&rule.continuation, AffixKind::Suffix) {
+                if let Some(both) = suffix.apply(&word) {
+                    self.insert(both, false);
+                }
+            }
+            self.insert(word, false);
+        }
+    }
+
+    fn rules(&self, flags: &[Flag], kind: AffixKind) -> Vec<&'a AffixRule> {
+        flags
+            .iter()
+            .filter_map(|flag| self.rules_by_flag.get(flag))
+            .flatten()
+            .filter(|rule| rule.kind == kind)
+            .copied()
+            .collect()
+    }
+
+    fn insert(&mut self, word: String, no_suggest: bool) {
+        if no_suggest {
+            self.dictionary.no_suggest.insert(word.clone());
+        }
+        self.dictionary.words.insert(word);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Casing {
+    Lower,
+    Capitalized,
+    Upper,
+    Mixed,
+}
+
+impl Casing {
+    pub fn of(word: &str) -> Self {
+        let mut letters = word.chars().filter(|ch| ch.is_alphabetic());
+        let Some(first) = letters.next() else {
+            return Casing::Lower;
+        };
+        let rest = letters.collect::<Vec<_>>();
+        let rest_lower = rest.iter().all(|ch| !ch.is_uppercase());
+        let rest_upper = rest.iter().all(|ch| !ch.is_lowercase());
+
+        match (first.is_uppercase(), rest_lower, rest_upper) {
+            (false, true, _) => Casing::Lower,
+            (true, _, true) if !rest.is_empty() => Casing::Upper,
+            (true, true, _) => Casing::Capitalized,
+            _ => Casing::Mixed,
+        }
+    }
+
+    /// Writes `word`, given in lowercase, in this casing.
+    pub fn apply(self, word: &str) -> String {
+        match self {
+            Casing::Upper => word.to_uppercase(),
+            Casing::Capitalized => capitalize(word),
+            Casing::Lower | Casing::Mixed => word.to_string(),
+        }
+    }
+}
+
+pub(crate) fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Typographic apostrophes are looked up as the ASCII ones dictionaries use.
+pub(crate) fn normalize_apostrophes(word: &str) -> String {
+    word.replace('\u{2019}', "'")
+}
+
+/// `word/flags`, where `\/` is a literal slash in the word.
+fn split_entry(entry: &str) -> (String, Option<&str>) {
+    let mut stem = String::new();
+    let mut chars = entry.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            '\\' if chars.peek().is_some_and(|(_, next)| *next == '/') => {
+                stem.push('/');
+                chars.next();
+            }
+            '/' => return (stem, Some(&entry[index + 1..])),
+            _ => stem.push(ch),
+        }
+    }
+    (stem, None)
+}
+
+fn read_dictionary_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(error) => error.into_bytes().into_iter().map(char::from).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Casing, Dictionary};
+
+    const AFF: &str = "\
+TRY esianrtolcdugmphbyfvkwz'
+NEEDAFFIX !
+FORBIDDENWORD *
+SFX S Y 2
+SFX S y ies [^aeiou]y
+SFX S 0 s [^y]
+SFX D Y 1
+SFX D 0 ed .
+PFX U Y 1
+PFX U 0 un .
+PFX R N 1
+PFX R 0 re/D .
+";
+
+    const DIC: &str = "\
+6
+cat/S
+city/S
+lock/UD
+play/R
+colour/!S
+irregardless/*
+";
+
+    #[test]
+    fn expands_affixes_and_applies_casing_rules() {
+        let dictionary = Dictionary::from_hunspell(AFF, DIC).expect("valid dictionary");
+
+        for word in [
+            "cat", "cats", "cities", "lock", "locked", "unlock", "unlocked", "replay", "replayed",
+            "colours", "Cats", "CITIES",
+        ] {
+            assert!(dictionary.check(word), "{word}");
+        }
+        for word in [
+            "citys",
+            "replaied",
+            "colour",
+            "irregardless",
+            "cAts",
+            "played",
+        ] {
+            assert!(!dictionary.check(word), "{word}");
+        }
+    }
+
+    #[test]
+    fn classifies_casing() {
+        assert_eq!(Casing::of("word"), Casing::Lower);
+        assert_eq!(Casing::of("Word"), Casing::Capitalized);
+        assert_eq!(Casing::of("WORD"), Casing::Upper);
+        assert_eq!(Casing::of("I"), Casing::Capitalized);
+        assert_eq!(Casing::of("iPhone"), Casing::Mixed);
+    }
+}
@@ -0,0 +1,141 @@
+mod affix;
+mod dictionary;
+mod store;
+mod suggest;
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+pub use dictionary::Dictionary;
+pub use store::{list_dictionaries, DictionaryStore};
+
+use dictionary::normalize_apostrophes;
+
+pub const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Misspelling {
+    pub word: String,
+    /// UTF-16 offsets into the checked text, the way JavaScript indexes
+    /// strings, so the editor can mark the range directly.
+    pub start: usize,
+    pub end: usize,
+    pub suggestions: Vec<String>,
+}
+
+/// Misspelled words of `text`. Words in `custom_words` are accepted in any
+/// casing. Words with digits, URLs and email addresses are not checked.
+pub fn check_text(
+    dictionary: &Dictionary,
+    custom_words: &[String],
+    text: &str,
+) -> Vec<Misspelling> {
+    let custom_words = custom_words
+        .iter()
+        .map(|word| normalize_apostrophes(word).to_lowercase())
+        .collect::<HashSet<_>>();
+
+    words(text)
+        .filter(|word| {
+            !custom_words.contains(&normalize_apostrophes(word.text).to_lowercase())
+                && !dictionary.check(word.text)
+        })
+        .map(|word| Misspelling {
+            word: word.text.to_string(),
+            start: word.start,
+            end: word.end,
+            suggestions: suggest::suggest(dictionary, word.text, MAX_SUGGESTIONS),
+        })
+        .collect()
+}
+
+struct Word<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+/// Runs of letters, with apostrophes inside words kept as in `don't`.
+fn words(text: &str) -> impl Iterator<Item = Word<'_>> {
+    let mut words = Vec::new();
+    let mut utf16_offset = 0usize;
+
+    for chunk in text.split_inclusive(char::is_whitespace) {
+        let chunk_offset = utf16_offset;
+        utf16_offset += chunk.encode_utf16().count();
+        if is_address(chunk) {
+            continue;
+        }
+
+        let mut word_start: Option<(usize, usize)> = None;
+        let mut chunk_utf16 = chunk_offset;
+        let mut chars = chunk.char_indices().peekable();
+        while let Some((index, ch)) = chars.next() {
+            let next_is_letter = chars.peek().is_some_and(|(_, next)| next.is_alphabetic());
+            let in_word = ch.is_alphanumeric()
+                || (is_apostrophe(ch) && word_start.is_some() && next_is_letter);
+            match (in_word, word_start) {
+                (true, None) => word_start = Some((index, chunk_utf16)),
+                (false, Some((start, start_utf16))) => {
+                    words.push((&chunk[start..index], start_utf16, chunk_utf16));
+                    word_start = None;
+                }
+                _ => {}
+            }
+            chunk_utf16 += ch.len_utf16();
+        }
+        if let Some((start, start_utf16)) = word_start {
+            words.push((&chunk[start..], start_utf16, chunk_utf16));
+        }
+    }
+
+    words
+        .into_iter()
+        .filter(|(text, _, _)| !text.chars().any(|ch| ch.is_numeric()))
+        .map(|(text, start, end)| Word { text, start, end })
+}
+
+fn is_apostrophe(ch: char) -> bool {
+    ch == '\'' || ch == '\u{2019}'
+}
+
+fn is_address(chunk: &str) -> bool {
+    let chunk = chunk.trim();
+    chunk.contains("://") || chunk.starts_with("www.") || chunk.contains('@')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_text, Dictionary, Misspelling};
+
+    #[test]
+    fn reports_misspellings_with_utf16_ranges_and_skips_custom_words() {
+        let dictionary =
+            Dictionary::from_hunspell("TRY eaostn\n", "7\nthe\nnote\nsays\ndon't\nsee\nat\nor\n")
+                .expect("valid dictionary");
+        let custom_words = vec!["Mdit".to_string()];
+        let text = "😀 The nots says: don’t see mdit at https://x.io or v2 \"teh\"";
+
+        let misspellings = check_text(&dictionary, &custom_words, text);
+
+        assert_eq!(
+            misspellings,
+            vec![
+                Misspelling {
+                    word: "nots".to_string(),
+                    start: 7,
+                    end: 11,
+                    suggestions: vec!["note".to_string()],
+                },
+                Misspelling {
+                    word: "teh".to_string(),
+                    start: 56,
+                    end: 59,
+                    suggestions: vec!["the".to_string()],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+
+use crate::Dictionary;
+
+/// Loads dictionaries from a list of folders holding hunspell `.aff`/`.dic`
+/// pairs named after their language, such as `en_US.aff`, and keeps them
+/// loaded. Earlier folders win when several have the same language.
+#[derive(Debug, Default)]
+pub struct DictionaryStore {
+    loaded: Mutex<HashMap<PathBuf, Arc<Dictionary>>>,
+}
+
+impl DictionaryStore {
+    /// The dictionary for `lang`, such as `en-US` or `en_US`. A bare `en`
+    /// falls back to the first regional dictionary of that language.
+    pub fn get(&self, dirs: &[PathBuf], lang: &str) -> Result<Arc<Dictionary>> {
+        let (aff_path, dic_path) = find_dictionary(dirs, lang)
+            .ok_or_else(|| anyhow!("No dictionary installed for `{lang}`"))?;
+
+        if let Some(dictionary) = self.lock().get(&dic_path) {
+            return Ok(dictionary.clone());
+        }
+        // Loading takes a while, so it happens outside the lock. Two callers
+        // may both load the same dictionary once; the second result wins.
+        let dictionary = Arc::new(Dictionary::load(&aff_path, &dic_path)?);
+        self.lock().insert(dic_path, dictionary.clone());
+        Ok(dictionary)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, Arc<Dictionary>>> {
+        self.loaded
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Languages with a complete `.aff`/`.dic` pair in any of `dirs`, sorted.
+pub fn list_dictionaries(dirs: &[PathBuf]) -> Vec<String> {
+    dirs.iter()
+        .flat_map(|dir| dictionary_names(dir))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+fn find_dictionary(dirs: &[PathBuf], lang: &str) -> Option<(PathBuf, PathBuf)> {
+    let wanted = lang.trim().replace('-', "_");
+    if wanted.is_empty() {
+        return None;
+    }
+
+    let exact = dirs.iter().find_map(|dir| {
+        dictionary_names(dir)
+            .into_iter()
+            .find(|name| name.eq_ignore_ascii_case(&wanted))
+            .map(|name| dictionary_paths(dir, &name))
+    });
+    exact.or_else(|| {
+        let language = wanted.split('_').next().unwrap_or(&wanted);
+        dirs.iter().find_map(|dir| {
+            dictionary_names(dir)
+                .into_iter()
+                .find(|name| {
+                    name.split('_')
+                        .next()
+                        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(language))
+                })
+                .map(|name| dictionary_paths(dir, &name))
+        })
+    })
+}
+
+fn dictionary_paths(dir: &Path, name: &str) -> (PathBuf, PathBuf) {
+    (
+        dir.join(format!("{name}.aff")),
+        dir.join(format!("{name}.dic")),
+    )
+}
+
+/// Names of the dictionaries in `dir` with both files present, sorted.
+fn dictionary_names(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let is_dic = path
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("dic"));
+            if !is_dic || !path.with_extension("aff").is_file() {
+                return None;
+            }
+            path.file_stem()?.to_str().map(str::to_string)
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::PathBuf,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::{list_dictionaries, DictionaryStore};
+
+    struct TempDirs(Vec<PathBuf>);
+
+    impl Drop for TempDirs {
+        fn drop(&mut self) {
+            for dir in &self.0 {
+                let _ = fs::remove_dir_all(dir);
+            }
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("mdit-spellcheck-{name}-{nanos}"));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    #[test]
+    fn finds_dictionaries_by_tag_and_prefers_earlier_folders() {
+        let user = temp_dir("user");
+        let bundled = temp_dir("bundled");
+        let dirs = TempDirs(vec![user.clone(), bundled.clone()]);
+        fs::write(user.join("en_GB.aff"), "").expect("write aff");
+        fs::write(user.join("en_GB.dic"), "1\ncolour\n").expect("write dic");
+        fs::write(bundled.join("en_US.aff"), "").expect("write aff");
+        fs::write(bundled.join("en_US.dic"), "1\ncolor\n").expect("write dic");
+        fs::write(bundled.join("de_DE.dic"), "1\nFarbe\n").expect("write dic");
+
+        assert_eq!(list_dictionaries(&dirs.0), vec!["en_GB", "en_US"]);
+
+        let store = DictionaryStore::default();
+        let us = store.get(&dirs.0, "en-us").expect("en_US loads");
+        assert!(us.check("color") && !us.check("colour"));
+        assert!(store
+            .get(&dirs.0, "en")
+            .expect("en falls back")
+            .check("colour"));
+        assert!(store.get(&dirs.0, "de").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+
+use crate::dictionary::{Casing, Dictionary};
+
+// Second-order edits grow quadratically with length, so long words only get
+// first-order ones.
+const MAX_SECOND_EDIT_CHARS: usize = 12;
+const FALLBACK_TRY_CHARS: &str = "esianrtolcdugmphbyfvkwzxjq";
+
+/// Corrections for a misspelled `word`, best first, written in its casing.
+/// Common misspellings from the dictionary's `REP` table come first, then
+/// single edits and a split into two words, then double edits.
+pub(crate) fn suggest(dictionary: &Dictionary, word: &str, limit: usize) -> Vec<String> {
+    let casing = Casing::of(word);
+    let lower = word.to_lowercase();
+    let try_chars = if dictionary.try_chars.is_empty() {
+        FALLBACK_TRY_CHARS.chars().collect()
+    } else {
+        dictionary.try_chars.clone()
+    };
+
+    let mut found = Suggestions {
+        dictionary,
+        casing,
+        seen: HashSet::from([lower.clone()]),
+        output: Vec::new(),
+        limit,
+    };
+
+    for (from, to) in &dictionary.replacements {
+        for (index, _) in lower.match_indices(from.as_str()) {
+            found.offer(format!(
+                "{}{to}{}",
+                &lower[..index],
+                &lower[index + from.len()..]
+            ));
+        }
+    }
+
+    let mut first_edits = single_edits(&lower, &try_chars);
+    rank_by_similarity(&lower, &mut first_edits);
+    for candidate in &first_edits {
+        found.offer(candidate.clone());
+    }
+    for (head, tail) in two_word_splits(&lower) {
+        if dictionary.check(head) && dictionary.check(tail) {
+            found.offer(format!("{head} {tail}"));
+        }
+    }
+
+    if found.output.is_empty() && lower.chars().count() <= MAX_SECOND_EDIT_CHARS {
+        let mut second_edits = first_edits
+            .iter()
+            .flat_map(|edit| single_edits(edit, &try_chars))
+            .filter(|candidate| dictionary.can_suggest(candidate))
+            .collect::<Vec<_>>();
+        rank_by_similarity(&lower, &mut second_edits);
+        for candidate in second_edits {
+            found.offer(candidate);
+        }
+    }
+
+    found.output
+}
+
+struct Suggestions<'a> {
+    dictionary: &'a Dictionary,
+    casing: Casing,
+    seen: HashSet<String>,
+    output: Vec<String>,
+    limit: usize,
+}
+
+impl Suggestions<'_> {
+    fn offer(&mut self, candidate: String) {
+        if self.output.len() >= self.limit || !self.seen.insert(candidate.clone()) {
+            return;
+        }
+        if candidate.contains(' ') || self.dictionary.can_suggest(&candidate) {
+            self.output.push(self.casing.apply(&candidate));
+        }
+    }
+}
+
+/// Deletions, transpositions, substitutions and insertions of one character.
+fn single_edits(word: &str, try_chars: &[char]) -> Vec<String> {
+    let chars = word.chars().collect::<Vec<_>>();
+    let joined = |parts: &[&[char]]| {
+        parts
+            .iter()
+            .flat_map(|part| part.iter())
+            .collect::<String>()
+    };
+    let mut edits = Vec::new();
+
+    for index in 0..chars.len() {
+        edits.push(joined(&[&chars[..index], &chars[index + 1..]]));
+        if index + 1 < chars.len() {
+            let mut swapped = chars.clone();
+            swapped.swap(index, index + 1);
+            edits.push(swapped.into_iter().collect());
+        }
+        for ch in try_chars {
+            if *ch != chars[index] {
+                edits.push(joined(&[&chars[..index], &[*ch], &chars[index + 1..]]));
+            }
+        }
+    }
+    for index in 0..=chars.len() {
+        for ch in try_chars {
+            edits.push(joined(&[&chars[..index], &[*ch], &chars[index..]]));
+        }
+    }
+
+    edits
+}
+
+fn two_word_splits(word: &str) -> impl Iterator<Item = (&str, &str)> {
+    word.char_indices()
+        .skip(1)
+        .map(move |(index, _)| (&word[..index], &word[index..]))
+}
+
+/// Keeps the first letter and longer shared prefixes and suffixes ahead,
+/// since typos tend to sit in the middle of words. Ties keep edit order.
+fn rank_by_similarity(word: &str, candidates: &mut [String]) {
+    let shared = |candidate: &String| {
+        let prefix = word
+            .chars()
+            .zip(candidate.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let suffix = word
+            .chars()
+            .rev()
+            .zip(candidate.chars().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        (prefix > 0, prefix + suffix)
+    };
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(shared(candidate)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::suggest;
+    use crate::Dictionary;
+
+    #[test]
+    fn suggests_replacements_edits_and_splits_in_the_word_casing() {
+        let aff = "TRY eitaonr\nREP 1\nREP f ph\n";
+        let dic = "6\nphone\nreceive\nnote\nnotes\nanother\nnot\n";
+        let dictionary = Dictionary::from_hunspell(aff, dic).expect("valid dictionary");
+
+        assert_eq!(suggest(&dictionary, "fone", 3), vec!["phone"]);
+        assert_eq!(suggest(&dictionary, "Recieve", 1), vec!["Receive"]);
+        assert_eq!(suggest(&dictionary, "NOTNOTE", 2), vec!["NOT NOTE"]);
+        assert_eq!(suggest(&dictionary, "anotehr", 5), vec!["another"]);
+        assert_eq!(suggest(&dictionary, "notse", 5), vec!["note", "notes"]);
+    }
+}
@@ -0,0 +1,132 @@
+//! Writes note metadata into `com.apple.metadata:` extended attributes, which
+//! Spotlight indexes alongside the file. Mdit owns the Markdown file type, so
+//! results open in the app. Everything is a no-op on other platforms.
+
+mod metadata;
+mod plist;
+
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+pub use metadata::NoteMetadata;
+
+/// Whether exported metadata reaches a system search index on this platform.
+pub const SUPPORTED: bool = cfg!(target_os = "macos");
+
+const TITLE_ATTRIBUTE: &str = "com.apple.metadata:kMDItemTitle";
+const KEYWORDS_ATTRIBUTE: &str = "com.apple.metadata:kMDItemKeywords";
+const DESCRIPTION_ATTRIBUTE: &str = "com.apple.metadata:kMDItemDescription";
+const ATTRIBUTES: [&str; 3] = [TITLE_ATTRIBUTE, KEYWORDS_ATTRIBUTE, DESCRIPTION_ATTRIBUTE];
+const NOTE_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+pub fn is_note_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            NOTE_EXTENSIONS
+                .iter()
+                .any(|note_extension| extension.eq_ignore_ascii_case(note_extension))
+        })
+}
+
+/// Exports the metadata of one note and returns whether any attribute
+/// changed. Unchanged attributes are not rewritten, so the export does not
+/// wake the vault watcher again.
+pub fn export_note(path: &Path) -> Result<bool, String> {
+    if !SUPPORTED {
+        return Ok(false);
+    }
+
+    let metadata = NoteMetadata::read(path)?;
+    let values = [
+        Some(plist::encode_string(&metadata.title)),
+        Some(&metadata.keywords)
+            .filter(|keywords| !keywords.is_empty())
+            .map(|keywords| plist::encode_string_array(keywords)),
+        Some(&metadata.description)
+            .filter(|description| !description.is_empty())
+            .map(|description| plist::encode_string(description)),
+    ];
+
+    let mut changed = false;
+    for (name, value) in ATTRIBUTES.into_iter().zip(values) {
+        changed |= sys::set_attribute(path, name, value.as_deref())?;
+    }
+    Ok(changed)
+}
+
+pub fn clear_note(path: &Path) -> Result<(), String> {
+    if !SUPPORTED {
+        return Ok(());
+    }
+
+    for name in ATTRIBUTES {
+        sys::set_attribute(path, name, None)?;
+    }
+    Ok(())
+}
+
+/// Exports every note under `root` and returns how many changed. Failures
+/// on single notes are skipped so one unreadable file does not stop the run.
+pub fn export_vault(root: &Path) -> usize {
+    vault_notes(root)
+        .filter(|path| export_note(path).unwrap_or(false))
+        .count()
+}
+
+pub fn clear_vault(root: &Path) {
+    for path in vault_notes(root) {
+        let _ = clear_note(&path);
+    }
+}
+
+/// Notes under `root`, skipping hidden files and folders such as `.git`.
+fn vault_notes(root: &Path) -> impl Iterator<Item = PathBuf> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file() && is_note_path(entry.path()))
+        .map(|entry| entry.into_path())
+}
+
+#[cfg(target_os = "macos")]
+mod sys {
+    use std::path::Path;
+
+    /// Sets or removes one attribute, returning whether it changed.
+    pub(crate) fn set_attribute(
+        path: &Path,
+        name: &str,
+        value: Option<&[u8]>,
+    ) -> Result<bool, String> {
+        let current = xattr::get(path, name)
+            .map_err(|error| format!("Failed to read {name} of {}: {error}", path.display()))?;
+        if current.as_deref() == value {
+            return Ok(false);
+        }
+
+        match value {
+            Some(value) => xattr::set(path, name, value),
+            None => xattr::remove(path, name),
+        }
+        .map_err(|error| format!("Failed to write {name} of {}: {error}", path.display()))?;
+        Ok(true)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod sys {
+    use std::path::Path;
+
+    pub(crate) fn set_attribute(
+        _path: &Path,
+        _name: &str,
+        _value: Option<&[u8]>,
+    ) -> Result<bool, String> {
+        Ok(false)
+    }
+}
@@ -0,0 +1,121 @@
+use std::fs;
+use std::path::Path;
+
+use note::{build_note_preview, parse_frontmatter, FrontmatterValue, DEFAULT_PREVIEW_CHARS};
+
+/// What Spotlight shows for a note: its title, keywords taken from the tags
+/// and aliases in the frontmatter, and a plain text preview.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NoteMetadata {
+    pub title: String,
+    pub keywords: Vec<String>,
+    pub description: String,
+}
+
+impl NoteMetadata {
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let contents = fs::read(path).map_err(|error| format!("Failed to read file: {}", error))?;
+        let file_stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(Self::from_source(
+            &file_stem,
+            &String::from_utf8_lossy(&contents),
+        ))
+    }
+
+    /// The title is the `title` property, then the first heading, then the
+    /// file name.
+    pub fn from_source(file_stem: &str, source: &str) -> Self {
+        let frontmatter = parse_frontmatter(source);
+        let preview = build_note_preview(source, DEFAULT_PREVIEW_CHARS);
+
+        let mut title = None;
+        let mut keywords: Vec<String> = Vec::new();
+        for property in frontmatter.properties {
+            match property.key.as_str() {
+                "title" => {
+                    if let FrontmatterValue::String(value) = property.value {
+                        title = Some(value.trim().to_string()).filter(|value| !value.is_empty());
+                    }
+                }
+                "tags" | "aliases" => {
+                    for keyword in property_keywords(&property.value, property.key == "tags") {
+                        if !keywords.contains(&keyword) {
+                            keywords.push(keyword);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            title: title
+                .or(preview.title)
+                .unwrap_or_else(|| file_stem.to_string()),
+            keywords,
+            description: preview.excerpt,
+        }
+    }
+}
+
+/// Tags may be written as a list or as one comma or space separated string,
+/// with or without `#`. Aliases keep their spaces.
+fn property_keywords(value: &FrontmatterValue, is_tags: bool) -> Vec<String> {
+    let values = match value {
+        FrontmatterValue::String(value) if is_tags => value
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(str::to_string)
+            .collect(),
+        FrontmatterValue::String(value) => vec![value.clone()],
+        FrontmatterValue::List(items) => items
+            .iter()
+            .filter_map(|item| match item {
+                FrontmatterValue::String(value) => Some(value.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    values
+        .into_iter()
+        .map(|value| {
+            let value = value.trim();
+            if is_tags {
+                value.trim_start_matches('#').to_string()
+            } else {
+                value.to_string()
+            }
+        })
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NoteMetadata;
+
+    #[test]
+    fn takes_title_keywords_and_preview_from_the_note() {
+        let metadata = NoteMetadata::from_source(
+            "plan",
+            "---\ntitle: Launch plan\ntags: [work, \"#q3\"]\naliases:\n  - Roadmap 2024\n  - work\n---\n# Plan\nShip the **beta** first.\n",
+        );
+
+        assert_eq!(metadata.title, "Launch plan");
+        assert_eq!(metadata.keywords, vec!["work", "q3", "Roadmap 2024"]);
+        assert!(metadata.description.contains("Ship the beta first."));
+    }
+
+    #[test]
+    fn falls_back_to_the_heading_then_the_file_name() {
+        let metadata = NoteMetadata::from_source("plan", "---\ntags: \"a, #b\"\n---\n# Plan\n");
+        assert_eq!(metadata.title, "Plan");
+        assert_eq!(metadata.keywords, vec!["a", "b"]);
+
+        assert_eq!(NoteMetadata::from_source("plan", "Just text").title, "plan");
+    }
+}
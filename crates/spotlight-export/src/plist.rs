@@ -0,0 +1,136 @@
+//! Minimal binary property list writer for the values Spotlight reads from
+//! `com.apple.metadata:` extended attributes: a string or an array of strings.
+
+const HEADER: &[u8] = b"bplist00";
+const INT_MARKER: u8 = 0x1;
+const ASCII_STRING_MARKER: u8 = 0x5;
+const UTF16_STRING_MARKER: u8 = 0x6;
+const ARRAY_MARKER: u8 = 0xA;
+
+pub(crate) fn encode_string(value: &str) -> Vec<u8> {
+    let mut object = Vec::new();
+    push_string(&mut object, value);
+    encode_objects(vec![object])
+}
+
+pub(crate) fn encode_string_array(values: &[String]) -> Vec<u8> {
+    let ref_size = byte_width(values.len() as u64);
+    let mut array = Vec::new();
+    push_marker(&mut array, ARRAY_MARKER, values.len());
+    for index in 1..=values.len() {
+        push_sized(&mut array, index as u64, ref_size);
+    }
+
+    let mut objects = vec![array];
+    objects.extend(values.iter().map(|value| {
+        let mut object = Vec::new();
+        push_string(&mut object, value);
+        object
+    }));
+    encode_objects(objects)
+}
+
+/// Lays out the objects after the header, then the offset table and trailer.
+/// The first object is the root.
+fn encode_objects(objects: Vec<Vec<u8>>) -> Vec<u8> {
+    let ref_size = byte_width(objects.len().saturating_sub(1) as u64);
+    let mut out = HEADER.to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for object in &objects {
+        offsets.push(out.len() as u64);
+        out.extend_from_slice(object);
+    }
+
+    let offset_table_offset = out.len() as u64;
+    let offset_size = byte_width(offset_table_offset);
+    for offset in offsets {
+        push_sized(&mut out, offset, offset_size);
+    }
+
+    out.extend_from_slice(&[0; 6]);
+    out.push(offset_size as u8);
+    out.push(ref_size as u8);
+    out.extend_from_slice(&(objects.len() as u64).to_be_bytes());
+    out.extend_from_slice(&0u64.to_be_bytes());
+    out.extend_from_slice(&offset_table_offset.to_be_bytes());
+    out
+}
+
+fn push_string(out: &mut Vec<u8>, value: &str) {
+    if value.is_ascii() {
+        push_marker(out, ASCII_STRING_MARKER, value.len());
+        out.extend_from_slice(value.as_bytes());
+        return;
+    }
+
+    let units = value.encode_utf16().collect::<Vec<_>>();
+    push_marker(out, UTF16_STRING_MARKER, units.len());
+    for unit in units {
+        out.extend_from_slice(&unit.to_be_bytes());
+    }
+}
+
+/// Object marker with its length in the low nibble, or followed by an integer
+/// object when the length does not fit.
+fn push_marker(out: &mut Vec<u8>, kind: u8, len: usize) {
+    if len < 0xF {
+        out.push(kind << 4 | len as u8);
+        return;
+    }
+
+    out.push(kind << 4 | 0xF);
+    let width = byte_width(len as u64);
+    out.push(INT_MARKER << 4 | width.trailing_zeros() as u8);
+    push_sized(out, len as u64, width);
+}
+
+fn push_sized(out: &mut Vec<u8>, value: u64, width: usize) {
+    out.extend_from_slice(&value.to_be_bytes()[8 - width..]);
+}
+
+fn byte_width(value: u64) -> usize {
+    match value {
+        0..=0xFF => 1,
+        0x100..=0xFFFF => 2,
+        0x1_0000..=0xFFFF_FFFF => 4,
+        _ => 8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_string, encode_string_array};
+
+    fn trailer(offset_size: u8, ref_size: u8, objects: u64, table_offset: u64) -> Vec<u8> {
+        let mut trailer = vec![0; 6];
+        trailer.push(offset_size);
+        trailer.push(ref_size);
+        trailer.extend_from_slice(&objects.to_be_bytes());
+        trailer.extend_from_slice(&0u64.to_be_bytes());
+        trailer.extend_from_slice(&table_offset.to_be_bytes());
+        trailer
+    }
+
+    #[test]
+    fn encodes_ascii_and_utf16_strings() {
+        let mut expected = b"bplist00\x54Plan\x08".to_vec();
+        expected.extend(trailer(1, 1, 1, 13));
+        assert_eq!(encode_string("Plan"), expected);
+
+        let mut expected = b"bplist00\x62\x00\x63\x00\xe9\x08".to_vec();
+        expected.extend(trailer(1, 1, 1, 13));
+        assert_eq!(encode_string("cé"), expected);
+    }
+
+    #[test]
+    fn encodes_string_arrays_with_long_lengths() {
+        let long = "x".repeat(20);
+        let encoded = encode_string_array(&["a".to_string(), long.clone()]);
+
+        let mut expected = b"bplist00\xa2\x01\x02\x51a\x5f\x10\x14".to_vec();
+        expected.extend_from_slice(long.as_bytes());
+        expected.extend_from_slice(&[0x08, 0x0b, 0x0d]);
+        expected.extend(trailer(1, 1, 3, 36));
+        assert_eq!(encoded, expected);
+    }
+}
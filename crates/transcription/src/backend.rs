@@ -0,0 +1,298 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::blocking::Client;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+/// Long recordings take a while to upload and transcribe.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// A stretch of speech and when it was said, in milliseconds from the start
+/// of the recording.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// What turns audio into text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptionBackend {
+    /// The whisper.cpp command line program with a local model. Formats other
+    /// than 16 kHz WAV need a whisper.cpp built with ffmpeg support.
+    WhisperCpp {
+        binary: PathBuf,
+        model: PathBuf,
+        language: Option<String>,
+    },
+    /// An OpenAI-compatible `audio/transcriptions` endpoint.
+    Http {
+        url: String,
+        /// Name of the environment variable holding the API key, sent as a
+        /// bearer token. The key itself is never stored.
+        api_key_ref: Option<String>,
+        model: String,
+        language: Option<String>,
+    },
+}
+
+impl TranscriptionBackend {
+    pub fn transcribe(&self, audio: &Path) -> Result<Vec<TranscriptSegment>, String> {
+        match self {
+            Self::WhisperCpp {
+                binary,
+                model,
+                language,
+            } => run_whisper_cpp(binary, model, language.as_deref(), audio),
+            Self::Http {
+                url,
+                api_key_ref,
+                model,
+                language,
+            } => post_audio(
+                url,
+                api_key_ref.as_deref(),
+                model,
+                language.as_deref(),
+                audio,
+            ),
+        }
+    }
+}
+
+fn run_whisper_cpp(
+    binary: &Path,
+    model: &Path,
+    language: Option<&str>,
+    audio: &Path,
+) -> Result<Vec<TranscriptSegment>, String> {
+    let mut command = Command::new(binary);
+    command.arg("-m").arg(model).arg("-f").arg(audio).arg("-np");
+    if let Some(language) = language {
+        command.arg("-l").arg(language);
+    }
+    let output = command
+        .output()
+        .map_err(|error| format!("Failed to run {}: {error}", binary.display()))?;
+    if !output.status.success() {
+        let detail = String::from_utf8_lossy(&output.stderr);
+        let detail = detail.lines().rev().find(|line| !line.trim().is_empty());
+        return Err(format!(
+            "whisper.cpp failed on {}: {}",
+            audio.display(),
+            detail.unwrap_or("no output").trim()
+        ));
+    }
+    Ok(parse_whisper_output(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Segments from whisper.cpp's default output, one per line:
+/// `[00:00:01.240 --> 00:00:04.800]   Text`.
+pub(crate) fn parse_whisper_output(output: &str) -> Vec<TranscriptSegment> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim_start().strip_prefix('[')?;
+            let (range, text) = rest.split_once(']')?;
+            let (start, end) = range.split_once("-->")?;
+            let text = text.trim();
+            // Silence comes out as markers such as `[BLANK_AUDIO]`.
+            if text.is_empty() || (text.starts_with('[') && text.ends_with(']')) {
+                return None;
+            }
+            Some(TranscriptSegment {
+                start_ms: parse_clock(start.trim())?,
+                end_ms: parse_clock(end.trim())?,
+                text: text.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Milliseconds in a `HH:MM:SS.mmm` timestamp.
+fn parse_clock(clock: &str) -> Option<u64> {
+    let (clock, millis) = clock.split_once('.').unwrap_or((clock, "0"));
+    let seconds = clock.split(':').try_fold(0u64, |total, part| {
+        part.parse::<u64>().ok().map(|part| total * 60 + part)
+    })?;
+    Some(seconds * 1000 + millis.parse::<u64>().ok()?)
+}
+
+fn post_audio(
+    url: &str,
+    api_key_ref: Option<&str>,
+    model: &str,
+    language: Option<&str>,
+    audio: &Path,
+) -> Result<Vec<TranscriptSegment>, String> {
+    let api_key = match api_key_ref {
+        Some(variable) => Some(env::var(variable).map_err(|_| {
+            format!("Environment variable {variable} for the transcription endpoint is not set")
+        })?),
+        None => None,
+    };
+    let bytes =
+        fs::read(audio).map_err(|error| format!("Failed to read {}: {error}", audio.display()))?;
+    let file_name = audio
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "audio".to_string());
+
+    let mut fields = vec![
+        ("model", model),
+        ("response_format", "verbose_json"),
+        ("timestamp_granularities[]", "segment"),
+    ];
+    if let Some(language) = language {
+        fields.push(("language", language));
+    }
+    let boundary = format!(
+        "mdit-transcription-{}",
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default()
+    );
+    let body = multipart_body(&boundary, &fields, &file_name, &bytes);
+
+    let mut request = Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .map_err(|error| error.to_string())?
+        .post(url)
+        .header(
+            "Content-Type",
+            format!("multipart/form-data; boundary={boundary}"),
+        )
+        .body(body);
+    if let Some(api_key) = api_key {
+        request = request.bearer_auth(api_key);
+    }
+    let response = request
+        .send()
+        .map_err(|error| format!("Failed to reach the transcription endpoint: {error}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        let detail = response.text().unwrap_or_default();
+        return Err(format!(
+            "Transcription endpoint returned {status}: {}",
+            detail.trim()
+        ));
+    }
+    let payload = response
+        .json::<JsonValue>()
+        .map_err(|error| format!("Transcription endpoint returned invalid JSON: {error}"))?;
+    Ok(segments_from_response(&payload))
+}
+
+fn multipart_body(
+    boundary: &str,
+    fields: &[(&str, &str)],
+    file_name: &str,
+    bytes: &[u8],
+) -> Vec<u8> {
+    let mut body = Vec::with_capacity(bytes.len() + 1024);
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+            )
+            .as_bytes(),
+        );
+    }
+    let file_name = file_name.replace('"', "'");
+    body.extend_from_slice(
+        format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(bytes);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    body
+}
+
+/// `segments` with `start`/`end` in seconds, or the whole `text` as one
+/// segment from endpoints that return no timings.
+pub(crate) fn segments_from_response(payload: &JsonValue) -> Vec<TranscriptSegment> {
+    let seconds = |value: Option<&JsonValue>| {
+        value
+            .and_then(JsonValue::as_f64)
+            .map(|seconds| (seconds.max(0.0) * 1000.0).round() as u64)
+    };
+    if let Some(segments) = payload.get("segments").and_then(JsonValue::as_array) {
+        return segments
+            .iter()
+            .filter_map(|segment| {
+                let text = segment.get("text")?.as_str()?.trim();
+                (!text.is_empty()).then(|| TranscriptSegment {
+                    start_ms: seconds(segment.get("start")).unwrap_or_default(),
+                    end_ms: seconds(segment.get("end")).unwrap_or_default(),
+                    text: text.to_string(),
+                })
+            })
+            .collect();
+    }
+    payload
+        .get("text")
+        .and_then(JsonValue::as_str)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(|text| TranscriptSegment {
+            start_ms: 0,
+            end_ms: 0,
+            text: text.to_string(),
+        })
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{parse_whisper_output, segments_from_response, TranscriptSegment};
+
+    fn segment(start_ms: u64, end_ms: u64, text: &str) -> TranscriptSegment {
+        TranscriptSegment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn reads_segments_from_whisper_cpp_and_http_responses() {
+        let output = "\n[00:00:00.000 --> 00:00:03.520]   Morning, everyone.\n\
+                      [00:00:03.520 --> 00:00:05.000]   [BLANK_AUDIO]\n\
+                      [01:00:05.250 --> 01:00:09.000]   That's all.\n";
+        assert_eq!(
+            parse_whisper_output(output),
+            [
+                segment(0, 3_520, "Morning, everyone."),
+                segment(3_605_250, 3_609_000, "That's all.")
+            ]
+        );
+
+        let verbose = json!({
+            "text": "Morning, everyone.",
+            "segments": [{ "start": 0.0, "end": 3.52, "text": " Morning, everyone." }]
+        });
+        assert_eq!(
+            segments_from_response(&verbose),
+            [segment(0, 3_520, "Morning, everyone.")]
+        );
+        assert_eq!(
+            segments_from_response(&json!({ "text": "Hi." })),
+            [segment(0, 0, "Hi.")]
+        );
+    }
+}
@@ -0,0 +1,419 @@
+//! Transcribes voice recordings into notes. Audio linked from a note gets a
+//! timestamped transcript section in that note, and recordings dropped into
+//! the vault's voice folder get a note of their own first. Transcription is
+//! off until the vault's `transcription` setting picks a backend.
+
+mod backend;
+mod section;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+pub use backend::{TranscriptSegment, TranscriptionBackend};
+pub use section::{has_transcript_section, render_transcript_section, transcript_heading};
+
+/// Vault setting holding the backend and the voice folder.
+pub const TRANSCRIPTION_SETTING: &str = "transcription";
+
+/// Extensions of the files treated as audio, lowercase.
+pub const AUDIO_EXTENSIONS: [&str; 9] = [
+    "aac", "flac", "m4a", "mp3", "oga", "ogg", "opus", "wav", "webm",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptionConfig {
+    pub backend: TranscriptionBackend,
+    /// Folder, relative to the vault root, whose recordings get a note each.
+    pub voice_folder: String,
+}
+
+/// The `transcription` vault setting as stored.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct TranscriptionSetting {
+    backend: String,
+    whisper_binary: String,
+    whisper_model: String,
+    endpoint: String,
+    api_key_ref: String,
+    model: String,
+    language: String,
+    voice_folder: String,
+}
+
+/// Notes a transcription run wrote.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionRun {
+    /// Notes made for new recordings in the voice folder.
+    pub created: Vec<PathBuf>,
+    /// Notes that gained a transcript section, including created ones.
+    pub updated: Vec<PathBuf>,
+    pub failures: Vec<TranscriptionFailure>,
+}
+
+/// Audio that could not be transcribed. It is tried again by the next run.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionFailure {
+    pub audio_path: PathBuf,
+    pub error: String,
+}
+
+/// The vault's transcription setup, or `None` while transcription is off.
+pub fn load_transcription_config(
+    db_path: &Path,
+    workspace_root: &Path,
+) -> Result<Option<TranscriptionConfig>, String> {
+    let setting = app_storage::vault_settings::get_vault_setting(
+        db_path,
+        workspace_root,
+        TRANSCRIPTION_SETTING,
+    )
+    .map_err(|error| error.to_string())?;
+    let setting = serde_json::from_value::<TranscriptionSetting>(setting.value)
+        .map_err(|error| format!("Invalid transcription setting: {error}"))?;
+    parse_setting(setting)
+}
+
+fn parse_setting(setting: TranscriptionSetting) -> Result<Option<TranscriptionConfig>, String> {
+    let filled = |value: String| Some(value.trim().to_string()).filter(|value| !value.is_empty());
+    let language = filled(setting.language);
+    let backend = match setting.backend.as_str() {
+        "" | "off" => return Ok(None),
+        "whisperCpp" => TranscriptionBackend::WhisperCpp {
+            binary: filled(setting.whisper_binary)
+                .map(PathBuf::from)
+                .ok_or("Set the whisper.cpp program to transcribe with")?,
+            model: filled(setting.whisper_model)
+                .map(PathBuf::from)
+                .ok_or("Set the whisper.cpp model to transcribe with")?,
+            language,
+        },
+        "http" => TranscriptionBackend::Http {
+            url: filled(setting.endpoint)
+                .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+                .ok_or("Transcription endpoint must start with http:// or https://")?,
+            api_key_ref: filled(setting.api_key_ref),
+            model: filled(setting.model).unwrap_or_else(|| "whisper-1".to_string()),
+            language,
+        },
+        other => return Err(format!("Unknown transcription backend: {other}")),
+    };
+    Ok(Some(TranscriptionConfig {
+        backend,
+        voice_folder: filled(setting.voice_folder).unwrap_or_else(|| "Voice".to_string()),
+    }))
+}
+
+/// Whether `path` names an audio file, by extension in any letter case.
+pub fn is_audio_path(path: impl AsRef<Path>) -> bool {
+    path.as_ref()
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            AUDIO_EXTENSIONS
+                .iter()
+                .any(|audio_extension| extension.eq_ignore_ascii_case(audio_extension))
+        })
+}
+
+/// Transcribes the audio linked from one note that it has no transcript of
+/// yet.
+pub fn transcribe_note(
+    workspace_root: &Path,
+    config: &TranscriptionConfig,
+    note_path: &Path,
+) -> Result<TranscriptionRun, String> {
+    let mut run = TranscriptionRun::default();
+    let mut transcriber = Transcriber::new(workspace_root, config);
+    transcriber.transcribe_note(note_path, &mut run)?;
+    Ok(run)
+}
+
+/// Gives each new recording in the voice folder a note and transcribes the
+/// notes there.
+pub fn transcribe_voice_folder(
+    workspace_root: &Path,
+    config: &TranscriptionConfig,
+) -> Result<TranscriptionRun, String> {
+    let mut run = TranscriptionRun::default();
+    Transcriber::new(workspace_root, config).transcribe_voice_folder(&mut run)?;
+    Ok(run)
+}
+
+/// [`transcribe_voice_folder`], then every other note of the vault.
+pub fn transcribe_vault(
+    workspace_root: &Path,
+    config: &TranscriptionConfig,
+) -> Result<TranscriptionRun, String> {
+    let mut run = TranscriptionRun::default();
+    let mut transcriber = Transcriber::new(workspace_root, config);
+    let voice_notes = transcriber.transcribe_voice_folder(&mut run)?;
+    for note_path in vault_files(workspace_root) {
+        if note::is_note_path(&note_path) && !voice_notes.contains(&note_path) {
+            transcriber.transcribe_note(&note_path, &mut run)?;
+        }
+    }
+    Ok(run)
+}
+
+struct Transcriber<'a> {
+    workspace_root: &'a Path,
+    config: &'a TranscriptionConfig,
+    /// Audio files of the vault by lowercase file name, for wiki links that
+    /// give only a name.
+    audio_by_name: Option<HashMap<String, Vec<PathBuf>>>,
+    /// Transcripts made this run, so audio linked from several notes is sent
+    /// once and a failure is reported once.
+    transcripts: HashMap<PathBuf, Option<Vec<TranscriptSegment>>>,
+}
+
+impl<'a> Transcriber<'a> {
+    fn new(workspace_root: &'a Path, config: &'a TranscriptionConfig) -> Self {
+        Self {
+            workspace_root,
+            config,
+            audio_by_name: None,
+            transcripts: HashMap::new(),
+        }
+    }
+
+    /// Writes a note embedding each recording in the voice folder that has
+    /// none next to it, then transcribes those notes and returns them.
+    fn transcribe_voice_folder(
+        &mut self,
+        run: &mut TranscriptionRun,
+    ) -> Result<Vec<PathBuf>, String> {
+        let folder = self
+            .workspace_root
+            .join(self.config.voice_folder.trim_matches('/'));
+        let mut notes = Vec::new();
+        for audio in vault_files(&folder).filter(|path| is_audio_path(path)) {
+            let note_path = audio.with_extension("md");
+            if !note_path.exists() {
+                let rel_path = audio
+                    .strip_prefix(self.workspace_root)
+                    .unwrap_or(&audio)
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                fs::write(&note_path, format!("![[{rel_path}]]\n"))
+                    .map_err(|error| format!("Failed to write {}: {error}", note_path.display()))?;
+                run.created.push(note_path.clone());
+            }
+            notes.push(note_path);
+        }
+        for note_path in &notes {
+            self.transcribe_note(note_path, run)?;
+        }
+        Ok(notes)
+    }
+
+    fn transcribe_note(
+        &mut self,
+        note_path: &Path,
+        run: &mut TranscriptionRun,
+    ) -> Result<(), String> {
+        let original = fs::read_to_string(note_path)
+            .map_err(|error| format!("Failed to read {}: {error}", note_path.display()))?;
+        if note::is_locked_source(&original) {
+            return Ok(());
+        }
+
+        let mut contents = original.clone();
+        for target in section::audio_link_targets(&original, |target| is_audio_path(target)) {
+            let Some(audio) = self.resolve_audio(note_path, &target) else {
+                continue;
+            };
+            let name = audio
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if has_transcript_section(&contents, &name) {
+                continue;
+            }
+            let backend = &self.config.backend;
+            let transcript = self.transcripts.entry(audio.clone()).or_insert_with(|| {
+                backend
+                    .transcribe(&audio)
+                    .map_err(|error| {
+                        run.failures.push(TranscriptionFailure {
+                            audio_path: audio.clone(),
+                            error,
+                        })
+                    })
+                    .ok()
+            });
+            if let Some(segments) = transcript {
+                let transcript = render_transcript_section(&name, segments);
+                contents = section::append_section(&contents, &transcript);
+            }
+        }
+
+        if contents != original {
+            fs::write(note_path, contents)
+                .map_err(|error| format!("Failed to write {}: {error}", note_path.display()))?;
+            run.updated.push(note_path.to_path_buf());
+        }
+        Ok(())
+    }
+
+    /// The vault file a link target points to. Targets with a folder are
+    /// relative to the note (Markdown links) or the vault root (wiki links
+    /// and root-anchored paths); bare names are looked up across the vault,
+    /// nearest the note first.
+    fn resolve_audio(&mut self, note_path: &Path, target: &str) -> Option<PathBuf> {
+        let target = percent_encoding::percent_decode_str(target)
+            .decode_utf8_lossy()
+            .into_owned();
+        let note_dir = note_path.parent().unwrap_or(self.workspace_root);
+        let candidates = match target.strip_prefix('/') {
+            Some(rooted) => vec![self.workspace_root.join(rooted)],
+            None => vec![note_dir.join(&target), self.workspace_root.join(&target)],
+        };
+        if let Some(found) = candidates.into_iter().find(|path| path.is_file()) {
+            let root = self.workspace_root.canonicalize().ok()?;
+            let found = found.canonicalize().ok()?;
+            return found.starts_with(&root).then_some(found);
+        }
+        if target.contains('/') {
+            return None;
+        }
+
+        let workspace_root = self.workspace_root;
+        let audio_by_name = self.audio_by_name.get_or_insert_with(|| {
+            let mut by_name: HashMap<String, Vec<PathBuf>> = HashMap::new();
+            for path in vault_files(workspace_root).filter(|path| is_audio_path(path)) {
+                if let Some(name) = path.file_name() {
+                    by_name
+                        .entry(name.to_string_lossy().to_lowercase())
+                        .or_default()
+                        .push(path);
+                }
+            }
+            by_name
+        });
+        let matches = audio_by_name.get(&target.to_lowercase())?;
+        matches
+            .iter()
+            .find(|path| path.parent() == Some(note_dir))
+            .or_else(|| matches.first())?
+            .canonicalize()
+            .ok()
+    }
+}
+
+/// Files under `root`, skipping hidden entries, in path order.
+fn vault_files(root: &Path) -> impl Iterator<Item = PathBuf> {
+    WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{
+        transcribe_vault, Transcriber, TranscriptionBackend, TranscriptionConfig, TranscriptionRun,
+    };
+
+    fn temp_vault(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        let vault = std::env::temp_dir().join(format!("mdit-transcription-{name}-{nanos}"));
+        fs::create_dir_all(vault.join("Voice")).expect("vault");
+        vault
+    }
+
+    #[test]
+    fn voice_recordings_get_a_note_and_failed_audio_is_reported() {
+        let vault = temp_vault("voice");
+        fs::write(vault.join("Voice/idea.m4a"), b"audio").expect("recording");
+        fs::write(vault.join("Journal.md"), "Listen to [[idea.m4a]] later.\n").expect("note");
+        let config = TranscriptionConfig {
+            backend: TranscriptionBackend::WhisperCpp {
+                binary: vault.join("missing-whisper"),
+                model: vault.join("model.bin"),
+                language: None,
+            },
+            voice_folder: "Voice".to_string(),
+        };
+
+        let run = transcribe_vault(&vault, &config).expect("run");
+        assert_eq!(run.created, [vault.join("Voice/idea.md")]);
+        assert_eq!(
+            fs::read_to_string(vault.join("Voice/idea.md")).expect("voice note"),
+            "![[Voice/idea.m4a]]\n"
+        );
+        assert!(run.updated.is_empty());
+        // Linked from both notes but tried once.
+        assert_eq!(run.failures.len(), 1);
+        assert!(run.failures[0].error.contains("missing-whisper"));
+
+        // A second run leaves the existing voice note alone.
+        let again = transcribe_vault(&vault, &config).expect("second run");
+        assert!(again.created.is_empty());
+
+        let _ = fs::remove_dir_all(&vault);
+    }
+
+    #[test]
+    fn resolves_links_by_path_or_by_name_across_the_vault() {
+        let vault = temp_vault("resolve");
+        fs::create_dir_all(vault.join("Projects/Calls")).expect("folders");
+        fs::write(vault.join("Voice/memo.mp3"), b"audio").expect("memo");
+        fs::write(vault.join("Projects/Calls/call.wav"), b"audio").expect("call");
+        let note = vault.join("Projects/Plan.md");
+        let config = TranscriptionConfig {
+            backend: TranscriptionBackend::Http {
+                url: "http://127.0.0.1:9/v1/audio/transcriptions".to_string(),
+                api_key_ref: None,
+                model: "whisper-1".to_string(),
+                language: None,
+            },
+            voice_folder: "Voice".to_string(),
+        };
+        let mut transcriber = Transcriber::new(&vault, &config);
+        let canonical = |path: PathBuf| path.canonicalize().expect("canonical");
+
+        assert_eq!(
+            transcriber.resolve_audio(&note, "Calls/call.wav"),
+            Some(canonical(vault.join("Projects/Calls/call.wav")))
+        );
+        assert_eq!(
+            transcriber.resolve_audio(&note, "/Voice/memo.mp3"),
+            Some(canonical(vault.join("Voice/memo.mp3")))
+        );
+        assert_eq!(
+            transcriber.resolve_audio(&note, "Memo.MP3"),
+            Some(canonical(vault.join("Voice/memo.mp3")))
+        );
+        assert_eq!(transcriber.resolve_audio(&note, "../../outside.mp3"), None);
+        assert_eq!(transcriber.resolve_audio(&note, "gone.mp3"), None);
+
+        let mut run = TranscriptionRun::default();
+        fs::write(&note, "Call: ![](Calls/call.wav)\n").expect("note");
+        transcriber
+            .transcribe_note(&note, &mut run)
+            .expect("note run");
+        assert_eq!(run.failures.len(), 1);
+        assert!(run.updated.is_empty());
+
+        let _ = fs::remove_dir_all(&vault);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::TranscriptSegment;
+
+const SECTION_PREFIX: &str = "## Transcript: ";
+
+/// The heading the transcript of `audio_name` goes under. A note with this
+/// heading already holds the transcript, so the audio is not sent again.
+pub fn transcript_heading(audio_name: &str) -> String {
+    format!("{SECTION_PREFIX}{audio_name}")
+}
+
+pub fn has_transcript_section(contents: &str, audio_name: &str) -> bool {
+    let heading = transcript_heading(audio_name);
+    contents.lines().any(|line| line.trim_end() == heading)
+}
+
+/// The transcript as a section: a list item per segment, led by the time it
+/// starts at.
+pub fn render_transcript_section(audio_name: &str, segments: &[TranscriptSegment]) -> String {
+    let mut section = format!("{}\n\n", transcript_heading(audio_name));
+    if segments.is_empty() {
+        section.push_str("_No speech detected._\n");
+    }
+    for segment in segments {
+        let text = segment
+            .text
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        section.push_str(&format!(
+            "- [{}] {text}\n",
+            format_timestamp(segment.start_ms)
+        ));
+    }
+    section
+}
+
+/// `contents` with `section` added at the end, a blank line apart.
+pub(crate) fn append_section(contents: &str, section: &str) -> String {
+    let body = contents.trim_end();
+    if body.is_empty() {
+        return section.to_string();
+    }
+    format!("{body}\n\n{section}")
+}
+
+/// `MM:SS`, or `H:MM:SS` from the first hour on.
+fn format_timestamp(ms: u64) -> String {
+    let seconds = ms / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes:02}:{seconds:02}")
+    }
+}
+
+/// Audio link targets in `contents`, as written: wiki links and embeds
+/// first, then Markdown links, each once.
+pub(crate) fn audio_link_targets(contents: &str, is_audio: impl Fn(&str) -> bool) -> Vec<String> {
+    let mut targets: Vec<String> = Vec::new();
+    let mut push = |target: &str| {
+        let target = target.trim();
+        if is_audio(target) && !targets.iter().any(|known| known == target) {
+            targets.push(target.to_string());
+        }
+    };
+
+    let mut rest = contents;
+    while let Some(start) = rest.find("[[") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("]]") else {
+            break;
+        };
+        let inner = &after[..end];
+        let target = inner.split('|').next().unwrap_or(inner);
+        push(target.split('#').next().unwrap_or(target));
+        rest = &after[end + 2..];
+    }
+
+    let mut rest = contents;
+    while let Some(start) = rest.find("](") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find(')') else {
+            break;
+        };
+        let destination = after[..end].trim();
+        // Drops a link title, as in `(memo.m4a "Standup")`.
+        let destination = match destination.strip_prefix('<') {
+            Some(inner) => inner.split_once('>').map_or(inner, |(path, _)| path),
+            None => destination
+                .split_once(" \"")
+                .map_or(destination, |(path, _)| path),
+        };
+        if !destination.contains("://") {
+            push(destination);
+        }
+        rest = &after[end + 1..];
+    }
+    targets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        append_section, audio_link_targets, has_transcript_section, render_transcript_section,
+    };
+    use crate::TranscriptSegment;
+
+    #[test]
+    fn renders_and_recognises_transcript_sections() {
+        let segments = [
+            TranscriptSegment {
+                start_ms: 1_200,
+                end_ms: 4_000,
+                text: " Let's start\n with the budget.".to_string(),
+            },
+            TranscriptSegment {
+                start_ms: 3_725_000,
+                end_ms: 3_730_000,
+                text: "Thanks all.".to_string(),
+            },
+        ];
+        let section = render_transcript_section("standup.m4a", &segments);
+        assert_eq!(
+            section,
+            "## Transcript: standup.m4a\n\n- [00:01] Let's start with the budget.\n- [1:02:05] Thanks all.\n"
+        );
+
+        let note = append_section("# Standup\n\n![[standup.m4a]]\n\n", &section);
+        assert!(note.starts_with("# Standup\n\n![[standup.m4a]]\n\n## Transcript: standup.m4a\n"));
+        assert!(has_transcript_section(&note, "standup.m4a"));
+        assert!(!has_transcript_section(&note, "retro.m4a"));
+    }
+
+    #[test]
+    fn finds_audio_in_wiki_and_markdown_links() {
+        let is_audio = |target: &str| target.ends_with(".m4a") || target.ends_with(".mp3");
+        let contents = "![[Voice/a.m4a]] and [[a.m4a|the memo]] [[Notes]]\n\
+                        [call](<recordings/call 1.mp3> \"Call\") [site](https://x.example/b.mp3) \
+                        ![again](Voice/a.m4a)";
+        assert_eq!(
+            audio_link_targets(contents, is_audio),
+            ["Voice/a.m4a", "a.m4a", "recordings/call 1.mp3"]
+        );
+    }
+}
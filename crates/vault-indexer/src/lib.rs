@@ -1,4 +1,7 @@
 mod rewrite;
 mod runtime;
 
-pub use runtime::{start_vault_indexer, VaultIndexerConfig, VaultIndexerError, VaultIndexerHandle};
+pub use runtime::{
+    queued_watch_batches, start_vault_indexer, VaultIndexerConfig, VaultIndexerError,
+    VaultIndexerHandle,
+};
@@ -2,10 +2,12 @@ use std::{
     collections::{BTreeSet, HashMap},
     path::{Path, PathBuf},
     sync::{
-        mpsc::{self, Receiver, Sender},
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
         Arc,
     },
     thread::{self, JoinHandle},
+    time::{Duration, Instant},
 };
 
 use anyhow::{Context, Result};
@@ -22,10 +24,21 @@ use crate::rewrite::{
     split_wiki_target_suffix, to_wiki_target_from_abs_path, with_preserved_surrounding_whitespace,
 };
 
+// Watch batches sent to any worker and not yet picked up.
+static QUEUED_WATCH_BATCHES: AtomicUsize = AtomicUsize::new(0);
+
+/// Watch batches waiting for their worker, across every running indexer.
+pub fn queued_watch_batches() -> usize {
+    QUEUED_WATCH_BATCHES.load(Ordering::Relaxed)
+}
+
 #[derive(Debug, Clone)]
 pub struct VaultIndexerConfig {
     pub watch_config: WatchConfig,
     pub startup_catchup: bool,
+    /// How often the worker retries embeddings that were queued while the
+    /// provider was unreachable. `None` disables the background retry.
+    pub pending_embedding_retry_interval: Option<Duration>,
 }
 
 impl Default for VaultIndexerConfig {
@@ -33,6 +46,7 @@ impl Default for VaultIndexerConfig {
         Self {
             watch_config: WatchConfig::default(),
             startup_catchup: true,
+            pending_embedding_retry_interval: Some(Duration::from_secs(60)),
         }
     }
 }
@@ -123,6 +137,7 @@ pub fn start_vault_indexer(
         canonical_workspace.clone(),
         db_path,
         indexing_runtime,
+        config.pending_embedding_retry_interval,
         worker_rx,
     );
 
@@ -132,8 +147,10 @@ pub fn start_vault_indexer(
         config.watch_config,
         move |batch| {
             on_batch(batch.clone());
+            QUEUED_WATCH_BATCHES.fetch_add(1, Ordering::Relaxed);
             if callback_tx.send(WorkerMessage::Batch(batch)).is_err() {
-                eprintln!("vault-indexer: failed to enqueue watch batch");
+                QUEUED_WATCH_BATCHES.fetch_sub(1, Ordering::Relaxed);
+                tracing::error!("failed to enqueue watch batch");
             }
         },
     )?;
@@ -162,28 +179,61 @@ fn spawn_worker(
     workspace_path: PathBuf,
     db_path: PathBuf,
     indexing_runtime: Arc<dyn VaultIndexingRuntime>,
+    pending_embedding_retry_interval: Option<Duration>,
     rx: Receiver<WorkerMessage>,
 ) -> JoinHandle<()> {
     thread::spawn(move || {
-        while let Ok(message) = rx.recv() {
+        let mut last_retry_at = Instant::now();
+        loop {
+            let message = match pending_embedding_retry_interval {
+                Some(interval) => {
+                    let wait = interval.saturating_sub(last_retry_at.elapsed());
+                    match rx.recv_timeout(wait) {
+                        Ok(message) => Some(message),
+                        Err(RecvTimeoutError::Timeout) => None,
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                None => match rx.recv() {
+                    Ok(message) => Some(message),
+                    Err(_) => break,
+                },
+            };
+
+            let Some(message) = message else {
+                // Provider errors are expected while it stays offline; the
+                // queue is kept and the next interval tries again.
+                let _ = indexing_runtime.drain_pending_embeddings(&workspace_path, &db_path);
+                last_retry_at = Instant::now();
+                continue;
+            };
+
             match message {
                 WorkerMessage::StartupCatchup => {
                     if let Err(error) =
                         index_vault_documents(indexing_runtime.as_ref(), &workspace_path, &db_path)
                     {
-                        eprintln!("vault-indexer: startup catch-up failed: {error:#}");
+                        tracing::error!("startup catch-up failed: {error:#}");
                     }
                 }
                 WorkerMessage::Batch(batch) => {
+                    QUEUED_WATCH_BATCHES.fetch_sub(1, Ordering::Relaxed);
                     if let Err(error) =
                         process_batch(indexing_runtime.as_ref(), &workspace_path, &db_path, batch)
                     {
-                        eprintln!("vault-indexer: failed to process batch: {error:#}");
+                        tracing::error!("failed to process batch: {error:#}");
                     }
                 }
                 WorkerMessage::Stop => break,
             }
         }
+
+        // Batches left behind by a stop are dropped with the channel.
+        let dropped = rx
+            .try_iter()
+            .filter(|message| matches!(message, WorkerMessage::Batch(_)))
+            .count();
+        QUEUED_WATCH_BATCHES.fetch_sub(dropped, Ordering::Relaxed);
     })
 }
 
@@ -193,6 +243,12 @@ fn process_batch(
     db_path: &Path,
     batch: VaultWatchBatch,
 ) -> Result<()> {
+    let _span = tracing::info_span!(
+        "watch_batch",
+        seq = batch.seq_in_stream,
+        ops = batch.ops.len()
+    )
+    .entered();
     let plan = build_indexing_plan(batch, workspace_path);
     if matches!(plan, IndexingPlan::FullReindex) {
         index_vault_documents(indexing_runtime, workspace_path, db_path)?;
@@ -223,8 +279,8 @@ fn process_batch(
         if let Err(error) =
             indexing_runtime.delete_indexed_note(workspace_path, db_path, &note_path)
         {
-            eprintln!(
-                "vault-indexer: failed to delete indexed note {}: {error:#}",
+            tracing::error!(
+                "failed to delete indexed note {}: {error:#}",
                 note_path.display()
             );
         }
@@ -236,8 +292,8 @@ fn process_batch(
             indexing_runtime.delete_indexed_notes_by_prefix(workspace_path, db_path, &path_prefix)
         {
             prefix_delete_failed = true;
-            eprintln!(
-                "vault-indexer: failed to delete indexed notes by prefix {}: {error:#}",
+            tracing::error!(
+                "failed to delete indexed notes by prefix {}: {error:#}",
                 path_prefix.display()
             );
         }
@@ -250,10 +306,7 @@ fn process_batch(
 
     for note_path in index_targets {
         if let Err(error) = indexing_runtime.index_note(workspace_path, db_path, &note_path) {
-            eprintln!(
-                "vault-indexer: failed to index note {}: {error:#}",
-                note_path.display()
-            );
+            tracing::error!("failed to index note {}: {error:#}", note_path.display());
         }
     }
 
@@ -288,12 +341,12 @@ fn build_indexing_plan(batch: VaultWatchBatch, workspace_path: &Path) -> Indexin
             } => match (before, after) {
                 (VaultEntryState::Missing, VaultEntryState::File)
                 | (VaultEntryState::File, VaultEntryState::File) => {
-                    if is_markdown_note_path(&rel_path) {
+                    if note::is_indexed_path(&rel_path) {
                         index_targets.insert(workspace_path.join(rel_path));
                     }
                 }
                 (VaultEntryState::File, VaultEntryState::Missing) => {
-                    if is_markdown_note_path(&rel_path) {
+                    if note::is_indexed_path(&rel_path) {
                         delete_targets.insert(workspace_path.join(rel_path));
                     }
                 }
@@ -309,17 +362,20 @@ fn build_indexing_plan(batch: VaultWatchBatch, workspace_path: &Path) -> Indexin
                 from_rel,
                 to_rel,
                 entry_kind: VaultEntryKind::File,
-            } => {
-                match (
-                    is_markdown_note_path(&from_rel),
-                    is_markdown_note_path(&to_rel),
-                ) {
-                    (true, true) => {
-                        markdown_moves.push((from_rel, to_rel));
-                    }
-                    _ => return IndexingPlan::FullReindex,
+            } => match (note::is_note_path(&from_rel), note::is_note_path(&to_rel)) {
+                (true, true) => {
+                    markdown_moves.push((from_rel, to_rel));
                 }
-            }
+                // Links to drawings are not rewritten, so a moved drawing is
+                // indexed afresh at its new path.
+                (false, false)
+                    if note::is_drawing_path(&from_rel) && note::is_drawing_path(&to_rel) =>
+                {
+                    delete_targets.insert(workspace_path.join(from_rel));
+                    index_targets.insert(workspace_path.join(to_rel));
+                }
+                _ => return IndexingPlan::FullReindex,
+            },
             VaultWatchOp::Move {
                 from_rel,
                 to_rel,
@@ -375,8 +431,8 @@ fn sync_backlinks_and_link_index(
         Ok(entries) => entries,
         Err(error) => {
             warnings.push("load-backlinks".to_string());
-            eprintln!(
-                "vault-indexer: failed to load backlinks before rename {} -> {}: {error:#}",
+            tracing::error!(
+                "failed to load backlinks before rename {} -> {}: {error:#}",
                 old_note_path.display(),
                 new_note_path.display()
             );
@@ -389,8 +445,8 @@ fn sync_backlinks_and_link_index(
             Ok(entries) => entries,
             Err(error) => {
                 warnings.push("load-new-backlinks".to_string());
-                eprintln!(
-                    "vault-indexer: failed to load unresolved backlinks for {}: {error:#}",
+                tracing::error!(
+                    "failed to load unresolved backlinks for {}: {error:#}",
                     new_note_path.display()
                 );
                 Vec::new()
@@ -403,7 +459,7 @@ fn sync_backlinks_and_link_index(
     for backlink in backlinks {
         let source_path =
             resolve_source_path(workspace_path, &backlink, old_note_path, new_note_path);
-        if !is_markdown_note_abs_path(&source_path) {
+        if !note::is_note_path(&source_path) {
             continue;
         }
 
@@ -423,8 +479,8 @@ fn sync_backlinks_and_link_index(
                 "rewrite:{}",
                 normalize_slashes(&source_path.to_string_lossy())
             ));
-            eprintln!(
-                "vault-indexer: failed to rewrite backlink document {}: {error:#}",
+            tracing::error!(
+                "failed to rewrite backlink document {}: {error:#}",
                 source_path.display()
             );
         }
@@ -433,7 +489,7 @@ fn sync_backlinks_and_link_index(
     for backlink in backlinks_to_new_target {
         let source_path =
             resolve_source_path(workspace_path, &backlink, old_note_path, new_note_path);
-        if !is_markdown_note_abs_path(&source_path) {
+        if !note::is_note_path(&source_path) {
             continue;
         }
         index_targets.insert(source_path);
@@ -443,8 +499,8 @@ fn sync_backlinks_and_link_index(
         indexing_runtime.rename_indexed_note(workspace_path, db_path, old_note_path, new_note_path)
     {
         warnings.push("rename-indexed-note".to_string());
-        eprintln!(
-            "vault-indexer: failed to rename indexed note {} -> {}: {error:#}",
+        tracing::error!(
+            "failed to rename indexed note {} -> {}: {error:#}",
             old_note_path.display(),
             new_note_path.display()
         );
@@ -456,16 +512,16 @@ fn sync_backlinks_and_link_index(
                 "index:{}",
                 normalize_slashes(&note_path.to_string_lossy())
             ));
-            eprintln!(
-                "vault-indexer: failed to refresh indexed note {}: {error:#}",
+            tracing::error!(
+                "failed to refresh indexed note {}: {error:#}",
                 note_path.display()
             );
         }
     }
 
     if !warnings.is_empty() {
-        eprintln!(
-            "vault-indexer: rename completed with warnings {} -> {}: {:?}",
+        tracing::warn!(
+            "rename completed with warnings {} -> {}: {:?}",
             old_note_path.display(),
             new_note_path.display(),
             warnings
@@ -492,10 +548,7 @@ fn rewrite_backlink_document(
         )
     })?;
     if metadata.file_type().is_symlink() {
-        eprintln!(
-            "vault-indexer: skipping symlink backlink source {}",
-            source_path.display()
-        );
+        tracing::warn!("skipping symlink backlink source {}", source_path.display());
         return Ok(false);
     }
 
@@ -530,8 +583,8 @@ fn rewrite_backlink_document(
             let resolved = match resolved {
                 Ok(value) => value,
                 Err(error) => {
-                    eprintln!(
-                        "vault-indexer: failed to resolve wiki target '{}' in {}: {error:#}",
+                    tracing::error!(
+                        "failed to resolve wiki target '{}' in {}: {error:#}",
                         trimmed_target,
                         source_path.display()
                     );
@@ -627,14 +680,6 @@ fn to_workspace_rel_path(workspace_path: &Path, note_path: &Path) -> Result<Stri
         })
 }
 
-fn is_markdown_note_abs_path(path: &Path) -> bool {
-    path.to_string_lossy().to_ascii_lowercase().ends_with(".md")
-}
-
-fn is_markdown_note_path(path: &str) -> bool {
-    path.to_ascii_lowercase().ends_with(".md")
-}
-
 #[cfg(test)]
 mod tests {
     #[cfg(unix)]
@@ -658,6 +703,7 @@ mod tests {
         RenameIndexedNote { old_path: String, new_path: String },
         GetBacklinks(String),
         ResolveWikiLink(String),
+        DrainPendingEmbeddings,
     }
 
     #[derive(Default)]
@@ -777,6 +823,14 @@ mod tests {
                 .unwrap_or_default())
         }
 
+        fn drain_pending_embeddings(&self, _workspace_root: &Path, _db_path: &Path) -> Result<()> {
+            self.calls
+                .lock()
+                .expect("calls lock poisoned")
+                .push(RuntimeCall::DrainPendingEmbeddings);
+            Ok(())
+        }
+
         fn resolve_wiki_link(
             &self,
             request: ResolveWikiLinkRequest,
@@ -791,6 +845,7 @@ mod tests {
                 match_count: 0,
                 disambiguated: false,
                 unresolved: true,
+                resolved_via_alias: false,
             })
         }
     }
@@ -822,6 +877,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn idle_worker_retries_pending_embeddings_on_interval() {
+        let runtime = Arc::new(FakeVaultIndexingRuntime::default());
+        let workspace = test_workspace_path();
+        let db_path = workspace.join("index.db");
+        let (tx, rx) = mpsc::channel();
+
+        let worker = spawn_worker(
+            workspace.clone(),
+            db_path,
+            runtime.clone(),
+            Some(Duration::from_millis(10)),
+            rx,
+        );
+        thread::sleep(Duration::from_millis(60));
+        tx.send(WorkerMessage::Stop).expect("failed to stop worker");
+        worker.join().expect("worker should exit cleanly");
+
+        assert!(runtime
+            .calls()
+            .contains(&RuntimeCall::DrainPendingEmbeddings));
+    }
+
     #[test]
     fn rescan_batch_runs_workspace_index_only() {
         let runtime = FakeVaultIndexingRuntime::default();
@@ -928,6 +1006,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn drawing_changes_and_moves_reindex_the_drawing() {
+        let runtime = FakeVaultIndexingRuntime::default();
+        let workspace = test_workspace_path();
+        let db_path = workspace.join("index.db");
+
+        let mut batch = empty_batch();
+        batch.ops = vec![
+            VaultWatchOp::PathState {
+                rel_path: "board.excalidraw".to_string(),
+                before: VaultEntryState::File,
+                after: VaultEntryState::File,
+            },
+            VaultWatchOp::Move {
+                from_rel: "flow.tldraw".to_string(),
+                to_rel: "archive/flow.tldraw".to_string(),
+                entry_kind: VaultEntryKind::File,
+            },
+        ];
+
+        process_batch(&runtime, &workspace, &db_path, batch)
+            .expect("batch processing should succeed");
+
+        assert_eq!(
+            runtime.calls(),
+            vec![
+                RuntimeCall::DeleteIndexedNote(normalize_path(&workspace.join("flow.tldraw"))),
+                RuntimeCall::IndexNote(normalize_path(&workspace.join("archive/flow.tldraw"))),
+                RuntimeCall::IndexNote(normalize_path(&workspace.join("board.excalidraw"))),
+            ]
+        );
+    }
+
     #[test]
     fn rename_with_mixed_file_types_triggers_workspace_rescan() {
         let runtime = FakeVaultIndexingRuntime::default();
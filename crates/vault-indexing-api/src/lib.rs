@@ -20,6 +20,10 @@ pub struct ResolveWikiLinkResult {
     pub match_count: usize,
     pub disambiguated: bool,
     pub unresolved: bool,
+    /// Set when the target matched a note through a frontmatter alias rather
+    /// than its file name.
+    #[serde(default)]
+    pub resolved_via_alias: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -27,6 +31,23 @@ pub struct ResolveWikiLinkResult {
 pub struct BacklinkEntry {
     pub rel_path: String,
     pub file_name: String,
+    /// Where the source mentions the note, in source order.
+    #[serde(default)]
+    pub mentions: Vec<BacklinkMention>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BacklinkMention {
+    /// Byte range of the link in the source file.
+    pub start: usize,
+    pub end: usize,
+    /// Zero-based line of `start`.
+    pub line: usize,
+    /// The mention's line with one line of context on either side.
+    pub context: String,
+    /// Byte offset of `context` in the source file.
+    pub context_start: usize,
 }
 
 pub trait VaultIndexingRuntime: Send + Sync {
@@ -57,5 +78,6 @@ pub trait VaultIndexingRuntime: Send + Sync {
         db_path: &Path,
         file_path: &Path,
     ) -> Result<Vec<BacklinkEntry>>;
+    fn drain_pending_embeddings(&self, workspace_root: &Path, db_path: &Path) -> Result<()>;
     fn resolve_wiki_link(&self, request: ResolveWikiLinkRequest) -> Result<ResolveWikiLinkResult>;
 }
@@ -0,0 +1,240 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{Context, Result};
+use rusqlite::params;
+use serde::Serialize;
+
+use super::{
+    calendar::dates_in_range, find_vault_id, open_indexing_connection, search::escape_like_pattern,
+};
+
+/// Rough Markdown bytes per word, spaces and punctuation included.
+const BYTES_PER_WORD: i64 = 6;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityHeatmap {
+    pub days: Vec<ActivityDay>,
+    /// Active days in a row up to the end of the range. A quiet last day
+    /// does not break the streak yet; the day may not be over.
+    pub current_streak: usize,
+    pub longest_streak: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityDay {
+    /// `YYYY-MM-DD`.
+    pub date: String,
+    /// Notes whose content changed that day.
+    pub notes_touched: usize,
+    /// Estimated from how much the notes grew; shrinking counts as zero.
+    pub words_added: usize,
+}
+
+impl ActivityDay {
+    fn is_active(&self) -> bool {
+        self.notes_touched > 0
+    }
+}
+
+/// Writing activity for every day from `start` to `end`, both `YYYY-MM-DD`
+/// and inclusive, as recorded by past indexing runs.
+pub fn get_activity_heatmap(
+    workspace_root: &Path,
+    db_path: &Path,
+    start: &str,
+    end: &str,
+) -> Result<ActivityHeatmap> {
+    let mut days = dates_in_range(start, end)?
+        .into_iter()
+        .map(|date| ActivityDay {
+            date: date.to_string(),
+            notes_touched: 0,
+            words_added: 0,
+        })
+        .collect::<Vec<_>>();
+
+    let conn = open_indexing_connection(db_path)?;
+    if let Some(vault_id) = find_vault_id(&conn, workspace_root)? {
+        let day_index = days
+            .iter()
+            .enumerate()
+            .map(|(index, day)| (day.date.clone(), index))
+            .collect::<HashMap<_, _>>();
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT day, notes_touched, bytes_added \
+                 FROM vault_activity_day \
+                 WHERE vault_id = ?1 AND day BETWEEN ?2 AND ?3",
+            )
+            .context("Failed to prepare activity query")?;
+        let rows = stmt
+            .query_map(params![vault_id, start, end], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })
+            .context("Failed to query activity")?;
+        for row in rows {
+            let (date, notes_touched, bytes_added) = row?;
+            if let Some(&index) = day_index.get(&date) {
+                days[index].notes_touched = notes_touched.max(0) as usize;
+                days[index].words_added = (bytes_added.max(0) / BYTES_PER_WORD) as usize;
+            }
+        }
+    }
+
+    let (current_streak, longest_streak) = streaks(&days);
+    Ok(ActivityHeatmap {
+        days,
+        current_streak,
+        longest_streak,
+    })
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WritingStats {
+    pub words_added: usize,
+    pub words_removed: usize,
+    /// Per note, most words added first. Notes deleted since keep the path
+    /// they were written under.
+    pub notes: Vec<NoteWritingStats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteWritingStats {
+    pub rel_path: String,
+    pub words_added: usize,
+    pub words_removed: usize,
+    /// Words in the note after its last revision in the range.
+    pub word_count: usize,
+}
+
+/// Words written from `start` to `end`, both `YYYY-MM-DD` and inclusive,
+/// summed from the word delta of each indexed revision. `folder` limits it to
+/// the notes under that vault-relative folder.
+pub fn get_writing_stats(
+    workspace_root: &Path,
+    db_path: &Path,
+    start: &str,
+    end: &str,
+    folder: Option<&str>,
+) -> Result<WritingStats> {
+    dates_in_range(start, end)?;
+    let mut stats = WritingStats::default();
+    let conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(stats);
+    };
+
+    let folder_pattern = folder
+        .map(|folder| folder.trim().replace('\\', "/"))
+        .map(|folder| folder.trim_matches('/').to_string())
+        .filter(|folder| !folder.is_empty())
+        .map(|folder| format!("{}/%", escape_like_pattern(&folder)));
+
+    // Revisions of notes still indexed follow them through renames.
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(d.rel_path, a.rel_path) AS path, \
+                    a.words_added, a.words_removed, a.word_count \
+             FROM doc_activity a \
+             LEFT JOIN doc d ON d.id = a.doc_id \
+             WHERE a.vault_id = ?1 AND a.day BETWEEN ?2 AND ?3 \
+               AND (?4 IS NULL OR COALESCE(d.rel_path, a.rel_path) LIKE ?4 ESCAPE '\\') \
+             ORDER BY a.id",
+        )
+        .context("Failed to prepare writing stats query")?;
+    let rows = stmt
+        .query_map(params![vault_id, start, end, folder_pattern], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })
+        .context("Failed to query writing stats")?;
+
+    let mut notes: HashMap<String, NoteWritingStats> = HashMap::new();
+    for row in rows {
+        let (rel_path, added, removed, word_count) =
+            row.context("Failed to read writing stats row")?;
+        let (added, removed) = (added.max(0) as usize, removed.max(0) as usize);
+        stats.words_added += added;
+        stats.words_removed += removed;
+        let note = notes
+            .entry(rel_path.clone())
+            .or_insert_with(|| NoteWritingStats {
+                rel_path,
+                words_added: 0,
+                words_removed: 0,
+                word_count: 0,
+            });
+        note.words_added += added;
+        note.words_removed += removed;
+        note.word_count = word_count.max(0) as usize;
+    }
+    stats.notes = notes.into_values().collect();
+    stats.notes.sort_by(|a, b| {
+        b.words_added
+            .cmp(&a.words_added)
+            .then_with(|| a.rel_path.cmp(&b.rel_path))
+    });
+
+    Ok(stats)
+}
+
+fn streaks(days: &[ActivityDay]) -> (usize, usize) {
+    let mut longest = 0;
+    let mut run = 0;
+    for day in days {
+        run = if day.is_active() { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+
+    let finished_days = match days.last() {
+        Some(last) if !last.is_active() => &days[..days.len() - 1],
+        _ => days,
+    };
+    let current = finished_days
+        .iter()
+        .rev()
+        .take_while(|day| day.is_active())
+        .count();
+
+    (current, longest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{streaks, ActivityDay};
+
+    fn days(active: &[bool]) -> Vec<ActivityDay> {
+        active
+            .iter()
+            .enumerate()
+            .map(|(index, active)| ActivityDay {
+                date: format!("2024-05-{:02}", index + 1),
+                notes_touched: usize::from(*active),
+                words_added: 0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn current_streak_survives_a_quiet_last_day() {
+        assert_eq!(
+            streaks(&days(&[true, true, true, false, true, true, false])),
+            (2, 3)
+        );
+        assert_eq!(streaks(&days(&[true, false, false])), (0, 1));
+        assert_eq!(streaks(&[]), (0, 0));
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use serde_yaml::Value;
+
+use super::{
+    links::wiki_query_dependency_key,
+    tags::{frontmatter_payload, lookup_mapping_value, split_frontmatter, strip_hidden_chars},
+};
+
+// `alias` is the singular form older Obsidian vaults still use.
+const ALIAS_KEYS: &[&str] = &["aliases", "alias"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NoteAlias {
+    pub(crate) alias: String,
+    /// Normalized like wiki link query keys, so `[[Alias]]` can be looked up directly.
+    pub(crate) normalized_alias: String,
+}
+
+/// Aliases declared in the frontmatter `aliases:` (or `alias:`) property.
+pub(crate) fn extract_note_aliases(source: &str) -> Vec<NoteAlias> {
+    if source.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let cleaned = strip_hidden_chars(source);
+    let (Some(frontmatter), _) = split_frontmatter(&cleaned) else {
+        return Vec::new();
+    };
+    let Ok(value) = serde_yaml::from_str::<Value>(&frontmatter_payload(frontmatter)) else {
+        return Vec::new();
+    };
+
+    let mut seen = HashSet::new();
+    let mut aliases = Vec::new();
+    for key in ALIAS_KEYS {
+        match lookup_mapping_value(&value, key) {
+            Some(Value::String(alias)) => push_alias(alias, &mut seen, &mut aliases),
+            Some(Value::Sequence(items)) => {
+                for item in items {
+                    if let Value::String(alias) = item {
+                        push_alias(alias, &mut seen, &mut aliases);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    aliases
+}
+
+fn push_alias(raw: &str, seen: &mut HashSet<String>, output: &mut Vec<NoteAlias>) {
+    let Some(normalized_alias) = wiki_query_dependency_key(raw) else {
+        return;
+    };
+
+    if seen.insert(normalized_alias.clone()) {
+        output.push(NoteAlias {
+            alias: raw.trim().to_string(),
+            normalized_alias,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract_note_aliases;
+
+    #[test]
+    fn extracts_list_and_single_aliases_case_insensitively() {
+        let raw = [
+            "---",
+            "Aliases:",
+            "  - Project Alpha",
+            "  - project alpha",
+            "  - ' PA '",
+            "alias: Alpha",
+            "---",
+            "Body",
+        ]
+        .join("\n");
+
+        let aliases = extract_note_aliases(&raw);
+
+        assert_eq!(
+            aliases
+                .into_iter()
+                .map(|alias| (alias.alias, alias.normalized_alias))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Project Alpha".to_string(), "project alpha".to_string()),
+                ("PA".to_string(), "pa".to_string()),
+                ("Alpha".to_string(), "alpha".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_notes_without_frontmatter_aliases() {
+        assert!(extract_note_aliases("# Title\n\naliases: not frontmatter").is_empty());
+        assert!(extract_note_aliases("---\ntags: [a]\n---\n").is_empty());
+    }
+}
@@ -0,0 +1,308 @@
+use std::{collections::HashMap, fmt, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use super::{find_vault_id, open_indexing_connection};
+
+/// Longest range served at once; enough for a year view with some slack.
+const MAX_CALENDAR_DAYS: usize = 400;
+
+/// What the calendar shows for one day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarDay {
+    /// `YYYY-MM-DD`.
+    pub date: String,
+    /// Rel path of the day's daily note, when it exists.
+    pub daily_note: Option<String>,
+    /// Notes whose file was created that day, in local time.
+    pub created_count: usize,
+    /// Notes last modified that day, in local time.
+    pub modified_count: usize,
+    /// Notes with a frontmatter `date` or `due` on that day.
+    pub dated_notes: Vec<CalendarNote>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarNote {
+    pub rel_path: String,
+    /// Frontmatter property holding the date, `date` or `due`.
+    pub property: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct CalendarDate {
+    year: u32,
+    month: u32,
+    day: u32,
+}
+
+impl CalendarDate {
+    /// Parses a `YYYY-MM-DD` date, rejecting days the month does not have.
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        let bytes = raw.as_bytes();
+        if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+            return None;
+        }
+        let year = raw[..4].parse().ok()?;
+        let month = raw[5..7].parse().ok()?;
+        let day = raw[8..].parse().ok()?;
+
+        ((1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day))
+            .then_some(Self { year, month, day })
+    }
+
+    fn next(self) -> Self {
+        if self.day < days_in_month(self.year, self.month) {
+            Self {
+                day: self.day + 1,
+                ..self
+            }
+        } else if self.month < 12 {
+            Self {
+                month: self.month + 1,
+                day: 1,
+                ..self
+            }
+        } else {
+            Self {
+                year: self.year + 1,
+                month: 1,
+                day: 1,
+            }
+        }
+    }
+}
+
+impl fmt::Display for CalendarDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Replaces `YYYY`, `YY`, `MM`, `M`, `DD` and `D` in a daily note format.
+/// Text inside `[...]` is kept as is, as in moment.js formats.
+pub fn format_daily_note_name(format: &str, year: u32, month: u32, day: u32) -> String {
+    const TOKENS: &[&str] = &["YYYY", "YY", "MM", "M", "DD", "D"];
+
+    let mut output = String::new();
+    let mut rest = format;
+    while !rest.is_empty() {
+        if let Some(escaped) = rest.strip_prefix('[') {
+            if let Some(end) = escaped.find(']') {
+                output.push_str(&escaped[..end]);
+                rest = &escaped[end + 1..];
+                continue;
+            }
+        }
+
+        let Some(token) = TOKENS.iter().find(|token| rest.starts_with(**token)) else {
+            let next = rest.chars().next().map_or(1, char::len_utf8);
+            output.push_str(&rest[..next]);
+            rest = &rest[next..];
+            continue;
+        };
+
+        let value = match *token {
+            "YYYY" => format!("{year:04}"),
+            "YY" => format!("{:02}", year % 100),
+            "MM" => format!("{month:02}"),
+            "M" => month.to_string(),
+            "DD" => format!("{day:02}"),
+            _ => day.to_string(),
+        };
+        output.push_str(&value);
+        rest = &rest[token.len()..];
+    }
+
+    output
+}
+
+/// Calendar entries for every day from `start` to `end`, both `YYYY-MM-DD`
+/// and inclusive. Daily notes are the notes named by `daily_note_format`.
+pub fn get_calendar_data(
+    workspace_root: &Path,
+    db_path: &Path,
+    start: &str,
+    end: &str,
+    daily_note_format: &str,
+) -> Result<Vec<CalendarDay>> {
+    let dates = dates_in_range(start, end)?;
+    let mut days = dates
+        .iter()
+        .map(|date| CalendarDay {
+            date: date.to_string(),
+            daily_note: None,
+            created_count: 0,
+            modified_count: 0,
+            dated_notes: Vec::new(),
+        })
+        .collect::<Vec<_>>();
+
+    let conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(days);
+    };
+
+    let mut daily_note_stmt = conn
+        .prepare("SELECT rel_path FROM doc WHERE vault_id = ?1 AND rel_path = ?2")
+        .context("Failed to prepare daily note query")?;
+    for (day, date) in days.iter_mut().zip(&dates) {
+        let rel_path = format!(
+            "{}.md",
+            format_daily_note_name(daily_note_format, date.year, date.month, date.day)
+        );
+        day.daily_note = daily_note_stmt
+            .query_row(params![vault_id, rel_path], |row| row.get::<_, String>(0))
+            .optional()
+            .context("Failed to query daily note")?;
+    }
+
+    let day_index = days
+        .iter()
+        .enumerate()
+        .map(|(index, day)| (day.date.clone(), index))
+        .collect::<HashMap<_, _>>();
+
+    for (date, count) in count_docs_by_local_day(&conn, vault_id, "source_created_ns", start, end)?
+    {
+        if let Some(&index) = day_index.get(&date) {
+            days[index].created_count = count;
+        }
+    }
+    for (date, count) in
+        count_docs_by_local_day(&conn, vault_id, "last_source_mtime_ns", start, end)?
+    {
+        if let Some(&index) = day_index.get(&date) {
+            days[index].modified_count = count;
+        }
+    }
+
+    let mut dated_stmt = conn
+        .prepare(
+            "SELECT dd.date, d.rel_path, dd.property \
+             FROM doc_date dd \
+             JOIN doc d ON d.id = dd.doc_id \
+             WHERE d.vault_id = ?1 AND dd.date BETWEEN ?2 AND ?3 \
+             ORDER BY dd.date, d.rel_path, dd.property",
+        )
+        .context("Failed to prepare dated note query")?;
+    let dated_rows = dated_stmt
+        .query_map(params![vault_id, start, end], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .context("Failed to query dated notes")?;
+    for row in dated_rows {
+        let (date, rel_path, property) = row?;
+        if let Some(&index) = day_index.get(&date) {
+            days[index]
+                .dated_notes
+                .push(CalendarNote { rel_path, property });
+        }
+    }
+
+    Ok(days)
+}
+
+/// Every date from `start` to `end`, both `YYYY-MM-DD` and inclusive.
+pub(crate) fn dates_in_range(start: &str, end: &str) -> Result<Vec<CalendarDate>> {
+    let start_date =
+        CalendarDate::parse(start).ok_or_else(|| anyhow!("Invalid calendar start: {start}"))?;
+    let end_date =
+        CalendarDate::parse(end).ok_or_else(|| anyhow!("Invalid calendar end: {end}"))?;
+    if end_date < start_date {
+        return Err(anyhow!(
+            "Calendar range ends before it starts: {start}..{end}"
+        ));
+    }
+
+    let mut dates = vec![start_date];
+    while let Some(&last) = dates.last().filter(|last| **last < end_date) {
+        if dates.len() == MAX_CALENDAR_DAYS {
+            return Err(anyhow!(
+                "Calendar range is longer than {MAX_CALENDAR_DAYS} days: {start}..{end}"
+            ));
+        }
+        dates.push(last.next());
+    }
+
+    Ok(dates)
+}
+
+/// Indexed notes per local day of the nanosecond timestamp in `column`.
+fn count_docs_by_local_day(
+    conn: &Connection,
+    vault_id: i64,
+    column: &str,
+    start: &str,
+    end: &str,
+) -> Result<Vec<(String, usize)>> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT date({column} / 1000000000, 'unixepoch', 'localtime') AS day, COUNT(*) \
+             FROM doc \
+             WHERE vault_id = ?1 AND last_hash IS NOT NULL AND {column} IS NOT NULL \
+             GROUP BY day \
+             HAVING day BETWEEN ?2 AND ?3"
+        ))
+        .with_context(|| format!("Failed to prepare {column} count query"))?;
+    let rows = stmt
+        .query_map(params![vault_id, start, end], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+        })
+        .with_context(|| format!("Failed to count notes by {column}"))?;
+
+    Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_daily_note_name, CalendarDate};
+
+    #[test]
+    fn formats_daily_note_names_from_tokens() {
+        assert_eq!(
+            format_daily_note_name("YYYY-MM-DD", 2024, 5, 7),
+            "2024-05-07"
+        );
+        assert_eq!(
+            format_daily_note_name("[Daily]/D.M.YY", 2024, 5, 7),
+            "Daily/7.5.24"
+        );
+    }
+
+    #[test]
+    fn parses_dates_and_steps_over_month_and_year_ends() {
+        assert_eq!(CalendarDate::parse("2023-02-29"), None);
+        assert_eq!(CalendarDate::parse("2024-5-07"), None);
+
+        let leap_day = CalendarDate::parse("2024-02-28")
+            .expect("valid date")
+            .next();
+        assert_eq!(leap_day.to_string(), "2024-02-29");
+        assert_eq!(leap_day.next().to_string(), "2024-03-01");
+
+        let new_year = CalendarDate::parse("2024-12-31")
+            .expect("valid date")
+            .next();
+        assert_eq!(new_year.to_string(), "2025-01-01");
+    }
+}
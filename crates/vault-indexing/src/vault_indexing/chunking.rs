@@ -1,19 +1,82 @@
+use std::io::{self, Read};
 use std::sync::OnceLock;
 
-use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 use tiktoken_rs::{cl100k_base, CoreBPE};
 
+use super::script::detect_language;
+
 // Default to a conservative chunk size until we can detect the embedding model's
 // context window dynamically.
 const MAX_TOKENS_PER_CHUNK_V1: usize = 1024;
 // Enforce a floor to avoid generating noisy embeddings with ultra-short chunks.
 const MIN_TOKENS_PER_CHUNK_V1: usize = 128;
+// Leave room for the breadcrumb v3 prepends so embedded text stays within the
+// v1 ceiling.
+const BREADCRUMB_TOKEN_RESERVE_V3: usize = 64;
+const BREADCRUMB_SEPARATOR: &str = " > ";
+// cl100k splits CJK text very differently from the multilingual tokenizers
+// embedding models use, so from v5 on CJK-dominant text is budgeted by
+// characters instead, charging each character this many tokens.
+const CJK_TOKENS_PER_CHAR: usize = 2;
+const CJK_BUDGET_CHUNKING_VERSION: i64 = 5;
+// Unindented lines starting with these usually open a new top-level definition,
+// which makes them good places to cut an oversized code fence.
+const CODE_DEFINITION_PREFIXES: &[&str] = &[
+    "fn ",
+    "pub fn ",
+    "pub(crate) fn ",
+    "async fn ",
+    "pub async fn ",
+    "impl ",
+    "impl<",
+    "def ",
+    "async def ",
+    "class ",
+    "function ",
+    "async function ",
+    "export ",
+    "func ",
+    "interface ",
+    "struct ",
+    "pub struct ",
+    "enum ",
+    "pub enum ",
+];
+
+/// A chunk ready for embedding plus where its raw text lives in the note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DocumentChunk {
+    /// Text sent to the embedder; v3 prefixes it with a breadcrumb line.
+    pub(crate) text: String,
+    /// Byte offset of the first raw character in the note.
+    pub(crate) start_offset: usize,
+    /// Byte offset one past the last raw character in the note.
+    pub(crate) end_offset: usize,
+    /// Headings enclosing the chunk start, outermost first.
+    pub(crate) heading_path: Vec<String>,
+    /// Language of the first fenced code block the chunk overlaps. Fences
+    /// without an info string report an empty language.
+    pub(crate) code_language: Option<String>,
+}
 
-/// Dispatch to the correct chunker for the requested version.
+/// Chunk texts only, for callers that do not need offsets.
+#[cfg(test)]
 pub(crate) fn chunk_document(contents: &str, chunking_version: i64) -> Vec<String> {
+    chunk_note(contents, "", chunking_version)
+        .into_iter()
+        .map(|chunk| chunk.text)
+        .collect()
+}
+
+/// Dispatch to the correct chunker for the requested version, keeping the raw
+/// offsets and heading context of every chunk. `title` becomes the first breadcrumb segment for versions that add one.
+pub(crate) fn chunk_note(contents: &str, title: &str, chunking_version: i64) -> Vec<DocumentChunk> {
     match chunking_version {
-        1 => chunk_markdown_v1(contents),
-        _ => chunk_markdown_v1(contents),
+        3 => chunk_markdown_v3(contents, title, false, chunking_version),
+        // v5 only changes how CJK text is budgeted, which lives in `count_tokens`.
+        4 | 5 => chunk_markdown_v3(contents, title, true, chunking_version),
+        _ => locate_chunks(contents, chunk_markdown_v1(contents)),
     }
 }
 
@@ -21,8 +84,61 @@ pub(crate) fn hash_content(contents: &str) -> String {
     blake3::hash(contents.as_bytes()).to_hex().to_string()
 }
 
+/// Same hash as [`hash_content`] over everything `reader` yields, without
+/// holding it in memory.
+pub(crate) fn hash_reader(reader: impl Read) -> io::Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update_reader(reader)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
 /// Chunk Markdown by major headings and enforce a token ceiling per chunk.
 fn chunk_markdown_v1(contents: &str) -> Vec<String> {
+    chunk_markdown_with_limits(
+        contents,
+        MIN_TOKENS_PER_CHUNK_V1,
+        MAX_TOKENS_PER_CHUNK_V1,
+        false,
+        1,
+    )
+}
+
+/// Same boundaries as v1, but each chunk is prefixed with a
+/// "Note Title > H1 > H2" breadcrumb so its embedding keeps document context.
+/// v4 additionally splits oversized code fences on blank lines and definitions.
+fn chunk_markdown_v3(
+    contents: &str,
+    title: &str,
+    code_aware: bool,
+    chunking_version: i64,
+) -> Vec<DocumentChunk> {
+    let raw_chunks = chunk_markdown_with_limits(
+        contents,
+        MIN_TOKENS_PER_CHUNK_V1,
+        MAX_TOKENS_PER_CHUNK_V1 - BREADCRUMB_TOKEN_RESERVE_V3,
+        code_aware,
+        chunking_version,
+    );
+
+    locate_chunks(contents, raw_chunks)
+        .into_iter()
+        .map(|mut chunk| {
+            let breadcrumb = format_breadcrumb(title, &chunk.heading_path);
+            if !breadcrumb.is_empty() {
+                chunk.text = format!("{breadcrumb}\n\n{}", chunk.text);
+            }
+            chunk
+        })
+        .collect()
+}
+
+fn chunk_markdown_with_limits(
+    contents: &str,
+    min_tokens: usize,
+    max_tokens: usize,
+    code_aware: bool,
+    chunking_version: i64,
+) -> Vec<String> {
     let sections = split_major_sections(contents);
     let mut chunks = Vec::new();
 
@@ -32,36 +148,205 @@ fn chunk_markdown_v1(contents: &str) -> Vec<String> {
             continue;
         }
 
-        if count_tokens(section) <= MAX_TOKENS_PER_CHUNK_V1 {
+        if count_tokens(section, chunking_version) <= max_tokens {
             chunks.push(section.to_string());
+        } else if code_aware && looks_like_code_block(section) {
+            chunks.extend(split_code_block(section, max_tokens, chunking_version));
         } else {
-            chunks.extend(split_section_by_tokens(section, MAX_TOKENS_PER_CHUNK_V1));
+            chunks.extend(split_section_by_tokens(
+                section,
+                max_tokens,
+                chunking_version,
+            ));
         }
     }
 
     if chunks.is_empty() && !contents.trim().is_empty() {
-        if count_tokens(contents) <= MAX_TOKENS_PER_CHUNK_V1 {
+        if count_tokens(contents, chunking_version) <= max_tokens {
             chunks.push(contents.trim().to_string());
         } else {
-            chunks.extend(split_section_by_tokens(contents, MAX_TOKENS_PER_CHUNK_V1));
+            chunks.extend(split_section_by_tokens(
+                contents,
+                max_tokens,
+                chunking_version,
+            ));
         }
     }
 
-    enforce_min_chunk_tokens(chunks, MIN_TOKENS_PER_CHUNK_V1, MAX_TOKENS_PER_CHUNK_V1)
+    enforce_min_chunk_tokens(chunks, min_tokens, max_tokens, chunking_version)
 }
 
-fn split_major_sections(contents: &str) -> Vec<String> {
-    let trimmed = contents.trim();
-    if trimmed.is_empty() {
-        return Vec::new();
+fn format_breadcrumb(title: &str, heading_path: &[String]) -> String {
+    let title = title.trim();
+    let mut segments = Vec::with_capacity(heading_path.len() + 1);
+    if !title.is_empty() {
+        segments.push(title);
+    }
+    segments.extend(heading_path.iter().map(String::as_str));
+    segments.join(BREADCRUMB_SEPARATOR)
+}
+
+/// Map chunk texts back to byte ranges in `contents` and attach heading paths.
+///
+/// Chunks come out in document order, so each lookup starts where the previous
+/// chunk began. A chunk that cannot be found gets an empty range at the cursor
+/// instead of failing the whole note.
+fn locate_chunks(contents: &str, chunks: Vec<String>) -> Vec<DocumentChunk> {
+    let headings = collect_headings(contents);
+    let fences = collect_code_fences(contents);
+    let mut cursor = 0usize;
+    let mut located = Vec::with_capacity(chunks.len());
+
+    for text in chunks {
+        let (start_offset, end_offset) = locate_chunk(contents, &text, cursor);
+        cursor = start_offset;
+        located.push(DocumentChunk {
+            heading_path: heading_path_at(&headings, start_offset),
+            code_language: code_language_in(&fences, start_offset, end_offset),
+            text,
+            start_offset,
+            end_offset,
+        });
+    }
+
+    located
+}
+
+fn locate_chunk(contents: &str, chunk: &str, cursor: usize) -> (usize, usize) {
+    let first_line = chunk.lines().next().unwrap_or_default().trim();
+    let Some(start) = contents[cursor..]
+        .find(first_line)
+        .map(|index| cursor + index)
+    else {
+        return (cursor, cursor);
+    };
+
+    if contents[start..].starts_with(chunk) {
+        return (start, start + chunk.len());
+    }
+
+    // Paragraph joins collapse blank lines, so the raw span is at least as long
+    // as the chunk text; look for the last line from there.
+    let last_line = chunk.lines().last().unwrap_or_default().trim();
+    let tail_from = floor_char_boundary(contents, start + chunk.len() - last_line.len());
+    let end = contents[tail_from..]
+        .find(last_line)
+        .map(|index| tail_from + index + last_line.len())
+        .unwrap_or(start + first_line.len());
+
+    (start, end)
+}
+
+fn floor_char_boundary(contents: &str, index: usize) -> usize {
+    let mut index = index.min(contents.len());
+    while !contents.is_char_boundary(index) {
+        index -= 1;
     }
+    index
+}
+
+struct HeadingMarker {
+    offset: usize,
+    level: HeadingLevel,
+    text: String,
+}
 
+fn collect_headings(contents: &str) -> Vec<HeadingMarker> {
+    let mut headings = Vec::new();
+    let mut current: Option<HeadingMarker> = None;
+
+    for (event, range) in Parser::new_ext(contents, markdown_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                current = Some(HeadingMarker {
+                    offset: range.start,
+                    level,
+                    text: String::new(),
+                });
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = current.as_mut() {
+                    heading.text.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some(mut heading) = current.take() {
+                    heading.text = heading.text.trim().to_string();
+                    if !heading.text.is_empty() {
+                        headings.push(heading);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    headings
+}
+
+fn heading_path_at(headings: &[HeadingMarker], offset: usize) -> Vec<String> {
+    let mut path: Vec<&HeadingMarker> = Vec::new();
+    for heading in headings
+        .iter()
+        .take_while(|heading| heading.offset <= offset)
+    {
+        while path.last().is_some_and(|last| last.level >= heading.level) {
+            path.pop();
+        }
+        path.push(heading);
+    }
+
+    path.into_iter()
+        .map(|heading| heading.text.clone())
+        .collect()
+}
+
+struct CodeFence {
+    start: usize,
+    end: usize,
+    language: String,
+}
+
+fn collect_code_fences(contents: &str) -> Vec<CodeFence> {
+    Parser::new_ext(contents, markdown_options())
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => Some(CodeFence {
+                start: range.start,
+                end: range.end,
+                language: info
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or_default()
+                    .to_lowercase(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn code_language_in(fences: &[CodeFence], start: usize, end: usize) -> Option<String> {
+    fences
+        .iter()
+        .find(|fence| fence.start < end.max(start + 1) && start < fence.end)
+        .map(|fence| fence.language.clone())
+}
+
+fn markdown_options() -> Options {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_TASKLISTS);
     options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
+    options
+}
 
-    let parser = Parser::new_ext(contents, options).into_offset_iter();
+fn split_major_sections(contents: &str) -> Vec<String> {
+    let trimmed = contents.trim();
+    if trimmed.is_empty() {
+        return Vec::new();
+    }
+
+    let parser = Parser::new_ext(contents, markdown_options()).into_offset_iter();
     let mut sections = Vec::new();
     let mut current_start = 0usize;
     let mut in_code_block = false;
@@ -119,7 +404,7 @@ fn is_major_heading(level: HeadingLevel) -> bool {
     matches!(level, HeadingLevel::H1 | HeadingLevel::H2)
 }
 
-fn split_section_by_tokens(section: &str, max_tokens: usize) -> Vec<String> {
+fn split_section_by_tokens(section: &str, max_tokens: usize, chunking_version: i64) -> Vec<String> {
     if section.trim().is_empty() || max_tokens == 0 {
         return Vec::new();
     }
@@ -129,12 +414,16 @@ fn split_section_by_tokens(section: &str, max_tokens: usize) -> Vec<String> {
     let mut current_chunk = String::new();
 
     for paragraph in paragraphs {
-        if count_tokens(&paragraph) > max_tokens {
+        if count_tokens(&paragraph, chunking_version) > max_tokens {
             if !current_chunk.trim().is_empty() {
                 chunks.push(current_chunk.trim().to_string());
                 current_chunk.clear();
             }
-            chunks.extend(split_text_strict_by_tokens(&paragraph, max_tokens));
+            chunks.extend(split_text_strict_by_tokens(
+                &paragraph,
+                max_tokens,
+                chunking_version,
+            ));
             continue;
         }
 
@@ -144,7 +433,7 @@ fn split_section_by_tokens(section: &str, max_tokens: usize) -> Vec<String> {
         }
 
         let candidate = format!("{}\n\n{}", current_chunk, paragraph);
-        if count_tokens(&candidate) <= max_tokens {
+        if count_tokens(&candidate, chunking_version) <= max_tokens {
             current_chunk = candidate;
         } else {
             chunks.push(current_chunk.trim().to_string());
@@ -157,17 +446,86 @@ fn split_section_by_tokens(section: &str, max_tokens: usize) -> Vec<String> {
     }
 
     if chunks.is_empty() {
-        split_text_strict_by_tokens(section, max_tokens)
+        split_text_strict_by_tokens(section, max_tokens, chunking_version)
     } else {
         chunks
     }
 }
 
-fn split_text_strict_by_tokens(text: &str, max_tokens: usize) -> Vec<String> {
+/// Split an oversized fenced block at blank lines or top-level definitions so a
+/// function is not cut in half. Pieces stay raw slices of the fence (the first
+/// keeps the opening fence, the last the closing one) so offsets still resolve.
+fn split_code_block(section: &str, max_tokens: usize, chunking_version: i64) -> Vec<String> {
+    let lines = section.lines().collect::<Vec<_>>();
+    let mut groups: Vec<Vec<&str>> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let starts_group = index > 1
+            && index + 1 < lines.len()
+            && (lines[index - 1].trim().is_empty() || is_code_definition_line(line));
+        match groups.last_mut() {
+            Some(group) if !starts_group => group.push(line),
+            _ => groups.push(vec![line]),
+        }
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for group in groups {
+        let group = group.join("\n");
+        if count_tokens(&group, chunking_version) > max_tokens {
+            if !current.trim().is_empty() {
+                chunks.push(current.trim().to_string());
+                current.clear();
+            }
+            chunks.extend(split_text_strict_by_tokens(
+                &group,
+                max_tokens,
+                chunking_version,
+            ));
+            continue;
+        }
+
+        if current.is_empty() {
+            current = group;
+            continue;
+        }
+
+        let candidate = format!("{current}\n{group}");
+        if count_tokens(&candidate, chunking_version) <= max_tokens {
+            current = candidate;
+        } else {
+            chunks.push(current.trim().to_string());
+            current = group;
+        }
+    }
+
+    if !current.trim().is_empty() {
+        chunks.push(current.trim().to_string());
+    }
+
+    chunks
+}
+
+fn is_code_definition_line(line: &str) -> bool {
+    !line.starts_with(char::is_whitespace)
+        && CODE_DEFINITION_PREFIXES
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+}
+
+fn split_text_strict_by_tokens(
+    text: &str,
+    max_tokens: usize,
+    chunking_version: i64,
+) -> Vec<String> {
     if text.trim().is_empty() || max_tokens == 0 {
         return Vec::new();
     }
 
+    if budgets_cjk_by_chars(text, chunking_version) {
+        return split_text_strict_by_chars(text, max_tokens / CJK_TOKENS_PER_CHAR);
+    }
+
     let tokenizer = tokenizer();
     let tokens = tokenizer.encode_ordinary(text);
     if tokens.is_empty() {
@@ -210,6 +568,15 @@ fn split_text_strict_by_tokens(text: &str, max_tokens: usize) -> Vec<String> {
     chunks
 }
 
+fn split_text_strict_by_chars(text: &str, max_chars: usize) -> Vec<String> {
+    let chars = text.chars().collect::<Vec<_>>();
+    chars
+        .chunks(max_chars.max(1))
+        .map(|window| window.iter().collect::<String>().trim().to_string())
+        .filter(|chunk| !chunk.is_empty())
+        .collect()
+}
+
 fn split_paragraphs(section: &str) -> Vec<String> {
     if is_atomic_block(section) {
         return vec![section.to_string()];
@@ -304,6 +671,7 @@ fn enforce_min_chunk_tokens(
     mut chunks: Vec<String>,
     min_tokens: usize,
     max_tokens: usize,
+    chunking_version: i64,
 ) -> Vec<String> {
     if chunks.len() < 2 || min_tokens == 0 {
         return chunks;
@@ -311,7 +679,7 @@ fn enforce_min_chunk_tokens(
 
     let mut index = 0;
     while index < chunks.len() {
-        if count_tokens(&chunks[index]) >= min_tokens {
+        if count_tokens(&chunks[index], chunking_version) >= min_tokens {
             index += 1;
             continue;
         }
@@ -322,10 +690,10 @@ fn enforce_min_chunk_tokens(
 
         let mut merged_any = false;
 
-        while count_tokens(&chunks[index]) < min_tokens && chunks.len() > 1 {
+        while count_tokens(&chunks[index], chunking_version) < min_tokens && chunks.len() > 1 {
             let previous_candidate = if index > 0 {
                 let merged = merge_chunk_pair(&chunks[index - 1], &chunks[index]);
-                let token_count = count_tokens(&merged);
+                let token_count = count_tokens(&merged, chunking_version);
                 if token_count <= max_tokens {
                     Some((MergeDirection::Previous, token_count, merged))
                 } else {
@@ -337,7 +705,7 @@ fn enforce_min_chunk_tokens(
 
             let next_candidate = if index + 1 < chunks.len() {
                 let merged = merge_chunk_pair(&chunks[index], &chunks[index + 1]);
-                let token_count = count_tokens(&merged);
+                let token_count = count_tokens(&merged, chunking_version);
                 if token_count <= max_tokens {
                     Some((MergeDirection::Next, token_count, merged))
                 } else {
@@ -409,20 +777,32 @@ fn merge_chunk_pair(left: &str, right: &str) -> String {
     format!("{left}\n\n{right}")
 }
 
-fn count_tokens(text: &str) -> usize {
+/// Budget cost of `text`: cl100k tokens, or from v5 on a per-character
+/// charge when the text is dominated by CJK scripts. Older versions keep
+/// counting tokens so their chunks come out as they were indexed.
+pub(crate) fn count_tokens(text: &str, chunking_version: i64) -> usize {
+    if budgets_cjk_by_chars(text, chunking_version) {
+        return text.chars().filter(|ch| !ch.is_whitespace()).count() * CJK_TOKENS_PER_CHAR;
+    }
+
     tokenizer().encode_ordinary(text).len()
 }
 
-fn tokenizer() -> &'static CoreBPE {
+fn budgets_cjk_by_chars(text: &str, chunking_version: i64) -> bool {
+    chunking_version >= CJK_BUDGET_CHUNKING_VERSION && detect_language(text).is_cjk()
+}
+
+pub(crate) fn tokenizer() -> &'static CoreBPE {
     static TOKENIZER: OnceLock<CoreBPE> = OnceLock::new();
     TOKENIZER.get_or_init(|| cl100k_base().expect("failed to initialize cl100k tokenizer"))
 }
 
 #[cfg(test)]
 mod unit_tests {
+    use super::super::TARGET_CHUNKING_VERSION as VERSION;
     use super::{
-        count_tokens, enforce_min_chunk_tokens, split_major_sections, split_section_by_tokens,
-        split_text_strict_by_tokens,
+        count_tokens, enforce_min_chunk_tokens, split_code_block, split_major_sections,
+        split_section_by_tokens, split_text_strict_by_tokens,
     };
 
     const GFM_MARKDOWN: &str = r#"---
@@ -520,7 +900,7 @@ Closing thoughts
     #[test]
     fn splits_long_sections_by_paragraph_before_tokens() {
         let section = "alpha beta gamma delta\n\nepsilon zeta eta theta\n\niota kappa lambda mu";
-        let chunks = split_section_by_tokens(section, 7);
+        let chunks = split_section_by_tokens(section, 7, VERSION);
 
         assert_eq!(chunks.len(), 3, "paragraph boundaries should be respected");
         assert_eq!(chunks[0], "alpha beta gamma delta");
@@ -531,7 +911,7 @@ Closing thoughts
     #[test]
     fn falls_back_to_token_split_for_single_large_paragraph() {
         let section = "Rep".repeat(60);
-        let chunks = split_section_by_tokens(&section, 10);
+        let chunks = split_section_by_tokens(&section, 10, VERSION);
 
         assert!(
             chunks.len() > 1,
@@ -542,7 +922,7 @@ Closing thoughts
     #[test]
     fn token_split_preserves_utf8_without_replacement_chars() {
         let section = "한글🙂테스트".repeat(120);
-        let chunks = split_text_strict_by_tokens(&section, 11);
+        let chunks = split_text_strict_by_tokens(&section, 11, VERSION);
 
         assert!(
             chunks.len() > 1,
@@ -558,7 +938,7 @@ Closing thoughts
     #[test]
     fn keeps_tables_together_even_with_blank_lines() {
         let section = "| Column | Type |\n| ------ | ---- |\n\n| id | number |\n| title | text |";
-        let chunks = split_section_by_tokens(section, 200);
+        let chunks = split_section_by_tokens(section, 200, VERSION);
 
         assert_eq!(chunks.len(), 1, "tables should remain atomic");
         assert!(chunks[0].contains("| Column | Type |"));
@@ -572,11 +952,12 @@ Closing thoughts
         let right =
             "## Details\nThis paragraph adds enough body to satisfy the minimum requirements.";
 
-        let min_tokens = count_tokens(&format!("{left}\n\n{short}")) + 1;
+        let min_tokens = count_tokens(&format!("{left}\n\n{short}"), VERSION) + 1;
         let merged = enforce_min_chunk_tokens(
             vec![left.to_string(), short.to_string(), right.to_string()],
             min_tokens,
             10_000,
+            VERSION,
         );
 
         assert_eq!(
@@ -598,7 +979,7 @@ Closing thoughts
             "Adding this chunk should push us past the threshold so the first entry can stand.";
         let trailing = "Final chunk that should remain unchanged";
 
-        let min_tokens = count_tokens(&format!("{first}\n\n{middle}")) + 1;
+        let min_tokens = count_tokens(&format!("{first}\n\n{middle}"), VERSION) + 1;
         let merged = enforce_min_chunk_tokens(
             vec![
                 first.to_string(),
@@ -608,6 +989,7 @@ Closing thoughts
             ],
             min_tokens,
             10_000,
+            VERSION,
         );
 
         assert_eq!(
@@ -627,11 +1009,12 @@ Closing thoughts
         let middle = "Second chunk that is still too small after one merge.";
         let short = "tiny";
 
-        let min_tokens = count_tokens(&format!("{middle}\n\n{short}")) + 1;
+        let min_tokens = count_tokens(&format!("{middle}\n\n{short}"), VERSION) + 1;
         let merged = enforce_min_chunk_tokens(
             vec![intro.to_string(), middle.to_string(), short.to_string()],
             min_tokens,
             10_000,
+            VERSION,
         );
 
         assert_eq!(
@@ -651,12 +1034,13 @@ Closing thoughts
         let short = "tiny";
         let right = "beta ".repeat(180);
 
-        let min_tokens = count_tokens(short) + 1;
-        let max_tokens = count_tokens(left.trim());
+        let min_tokens = count_tokens(short, VERSION) + 1;
+        let max_tokens = count_tokens(left.trim(), VERSION);
         let chunks = enforce_min_chunk_tokens(
             vec![left.clone(), short.to_string(), right.clone()],
             min_tokens,
             max_tokens,
+            VERSION,
         );
 
         assert_eq!(
@@ -666,4 +1050,19 @@ Closing thoughts
         );
         assert_eq!(chunks[1], short);
     }
+
+    #[test]
+    fn splits_oversized_code_fence_on_definitions() {
+        let body = "    let value = compute();\n".repeat(12);
+        let section = format!("```rust\nfn first() {{\n{body}}}\nfn second() {{\n{body}}}\n```");
+        let max_tokens = count_tokens(&section, VERSION) * 2 / 3;
+        let chunks = split_code_block(&section, max_tokens, VERSION);
+
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].starts_with("```rust\nfn first() {"));
+        assert!(chunks[0].ends_with('}'));
+        assert!(chunks[1].starts_with("fn second() {"));
+        assert!(chunks[1].ends_with("```"));
+        assert_eq!(chunks.join("\n"), section);
+    }
 }
@@ -0,0 +1,375 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    escape_sql_like_pattern, files::SourceFileStat, find_vault_id, open_indexing_connection,
+    sanitize_workspace_rel_path,
+};
+
+mod bibtex;
+mod csl_json;
+mod note_keys;
+
+pub(crate) use note_keys::extract_citation_keys;
+
+/// One reference of the vault bibliography.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CitationEntry {
+    /// Key cited as `[@key]`.
+    pub key: String,
+    /// BibTeX entry type or CSL item type, such as `article`.
+    pub entry_type: String,
+    pub title: Option<String>,
+    /// Authors, or editors when an entry has no authors.
+    pub authors: Vec<CitationAuthor>,
+    pub year: Option<String>,
+    /// Journal, book or publisher the reference appeared in.
+    pub container: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CitationAuthor {
+    /// Family name, or the whole name of an organization.
+    pub family: String,
+    pub given: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CitationStyle {
+    /// `[@key]`, resolved by Pandoc and other Markdown tools.
+    Pandoc,
+    /// `(Doe & Smith, 2021)`.
+    AuthorYear,
+    /// A full APA-like reference for a bibliography list.
+    Reference,
+}
+
+/// Entries whose key, title or author names contain `query`, keys starting
+/// with it first. An empty query lists entries by key.
+pub fn search_citations(
+    workspace_root: &Path,
+    db_path: &Path,
+    bibliography_path: &str,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<CitationEntry>> {
+    if bibliography_path.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+    refresh_citation_entries(&mut conn, workspace_root, vault_id, bibliography_path)?;
+
+    let escaped = escape_sql_like_pattern(query.trim());
+    let mut stmt = conn
+        .prepare(
+            "SELECT citation_key, entry_type, title, authors, year, container \
+             FROM citation_entry \
+             WHERE vault_id = ?1 \
+               AND (citation_key LIKE ?2 ESCAPE '\\' \
+                    OR title LIKE ?2 ESCAPE '\\' \
+                    OR authors LIKE ?2 ESCAPE '\\') \
+             ORDER BY citation_key LIKE ?3 ESCAPE '\\' DESC, citation_key \
+             LIMIT ?4",
+        )
+        .context("Failed to prepare citation search")?;
+    let rows = stmt
+        .query_map(
+            params![
+                vault_id,
+                format!("%{escaped}%"),
+                format!("{escaped}%"),
+                limit as i64
+            ],
+            citation_entry_from_row,
+        )
+        .context("Failed to search citations")?;
+
+    Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+}
+
+/// Text to insert for citing `key` in `style`.
+pub fn format_citation(
+    workspace_root: &Path,
+    db_path: &Path,
+    bibliography_path: &str,
+    key: &str,
+    style: CitationStyle,
+) -> Result<String> {
+    if bibliography_path.trim().is_empty() {
+        return Err(anyhow!("No bibliography is configured for this vault"));
+    }
+
+    let mut conn = open_indexing_connection(db_path)?;
+    let vault_id = find_vault_id(&conn, workspace_root)?
+        .ok_or_else(|| anyhow!("Vault is not indexed: {}", workspace_root.display()))?;
+    refresh_citation_entries(&mut conn, workspace_root, vault_id, bibliography_path)?;
+
+    let entry = conn
+        .query_row(
+            "SELECT citation_key, entry_type, title, authors, year, container \
+             FROM citation_entry \
+             WHERE vault_id = ?1 AND citation_key = ?2",
+            params![vault_id, key],
+            citation_entry_from_row,
+        )
+        .optional()
+        .context("Failed to load citation")?
+        .ok_or_else(|| anyhow!("Unknown citation key: {key}"))?;
+
+    Ok(match style {
+        CitationStyle::Pandoc => format!("[@{}]", entry.key),
+        CitationStyle::AuthorYear => format_author_year(&entry),
+        CitationStyle::Reference => format_reference(&entry),
+    })
+}
+
+/// Rel paths of the notes citing `key`.
+pub fn get_citing_notes(workspace_root: &Path, db_path: &Path, key: &str) -> Result<Vec<String>> {
+    let conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT d.rel_path \
+             FROM doc_citation dc \
+             JOIN doc d ON d.id = dc.doc_id \
+             WHERE d.vault_id = ?1 AND dc.citation_key = ?2 \
+             ORDER BY d.rel_path",
+        )
+        .context("Failed to prepare citing notes query")?;
+    let rows = stmt
+        .query_map(params![vault_id, key], |row| row.get::<_, String>(0))
+        .context("Failed to query citing notes")?;
+
+    Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+}
+
+/// Re-reads the bibliography into `citation_entry` when the file or the
+/// configured path changed since it was last read.
+fn refresh_citation_entries(
+    conn: &mut Connection,
+    workspace_root: &Path,
+    vault_id: i64,
+    bibliography_path: &str,
+) -> Result<()> {
+    let bibliography_path = sanitize_workspace_rel_path(bibliography_path)
+        .ok_or_else(|| anyhow!("Bibliography must be inside the vault: {bibliography_path}"))?;
+    let abs_path = workspace_root.join(&bibliography_path);
+    let stat = SourceFileStat::from_path(&abs_path);
+    let stored = conn
+        .query_row(
+            "SELECT rel_path, source_size, source_mtime_ns \
+             FROM vault_bibliography WHERE vault_id = ?1",
+            params![vault_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<i64>>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                ))
+            },
+        )
+        .optional()
+        .context("Failed to load bibliography state")?;
+    let current = (
+        bibliography_path.clone(),
+        stat.last_source_size,
+        stat.last_source_mtime_ns,
+    );
+    if stat.last_source_mtime_ns.is_some() && stored.as_ref() == Some(&current) {
+        return Ok(());
+    }
+
+    let source = fs::read_to_string(&abs_path)
+        .with_context(|| format!("Failed to read bibliography {}", abs_path.display()))?;
+    let is_csl_json = abs_path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    let entries = if is_csl_json {
+        csl_json::parse_csl_json(&source)?
+    } else {
+        bibtex::parse_bibtex(&source)
+    };
+
+    let tx = conn
+        .transaction()
+        .context("Failed to start bibliography transaction")?;
+    tx.execute(
+        "DELETE FROM citation_entry WHERE vault_id = ?1",
+        params![vault_id],
+    )
+    .context("Failed to clear citation entries")?;
+    {
+        // A key repeated in the file keeps its last entry.
+        let mut stmt = tx
+            .prepare(
+                "INSERT OR REPLACE INTO citation_entry \
+                 (vault_id, citation_key, entry_type, title, authors, year, container) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )
+            .context("Failed to prepare citation entry insert")?;
+        for entry in &entries {
+            stmt.execute(params![
+                vault_id,
+                entry.key,
+                entry.entry_type,
+                entry.title,
+                serde_json::to_string(&entry.authors)?,
+                entry.year,
+                entry.container
+            ])
+            .with_context(|| format!("Failed to insert citation entry {}", entry.key))?;
+        }
+    }
+    tx.execute(
+        "INSERT INTO vault_bibliography (vault_id, rel_path, source_size, source_mtime_ns) \
+         VALUES (?1, ?2, ?3, ?4) \
+         ON CONFLICT(vault_id) DO UPDATE SET \
+             rel_path = excluded.rel_path, \
+             source_size = excluded.source_size, \
+             source_mtime_ns = excluded.source_mtime_ns",
+        params![
+            vault_id,
+            bibliography_path,
+            stat.last_source_size,
+            stat.last_source_mtime_ns
+        ],
+    )
+    .context("Failed to save bibliography state")?;
+    tx.commit()
+        .context("Failed to commit bibliography entries")?;
+
+    Ok(())
+}
+
+fn citation_entry_from_row(row: &Row<'_>) -> rusqlite::Result<CitationEntry> {
+    let authors = row.get::<_, String>(3)?;
+    Ok(CitationEntry {
+        key: row.get(0)?,
+        entry_type: row.get(1)?,
+        title: row.get(2)?,
+        authors: serde_json::from_str(&authors).unwrap_or_default(),
+        year: row.get(4)?,
+        container: row.get(5)?,
+    })
+}
+
+fn format_author_year(entry: &CitationEntry) -> String {
+    let names = match entry.authors.as_slice() {
+        [] => entry.title.clone().unwrap_or_else(|| entry.key.clone()),
+        [only] => only.family.clone(),
+        [first, second] => format!("{} & {}", first.family, second.family),
+        [first, ..] => format!("{} et al.", first.family),
+    };
+    format!("({}, {})", names, entry.year.as_deref().unwrap_or("n.d."))
+}
+
+fn format_reference(entry: &CitationEntry) -> String {
+    let year = entry.year.as_deref().unwrap_or("n.d.");
+    let title = entry.title.as_deref().unwrap_or(&entry.key);
+
+    let mut reference = if entry.authors.is_empty() {
+        format!("{title}. ({year}).")
+    } else {
+        let names = entry.authors.iter().map(reference_name).collect::<Vec<_>>();
+        let authors = match names.as_slice() {
+            [only] => only.clone(),
+            [rest @ .., last] => format!("{}, & {}", rest.join(", "), last),
+            [] => String::new(),
+        };
+        format!("{authors} ({year}). {title}.")
+    };
+    if let Some(container) = &entry.container {
+        reference.push_str(&format!(" *{container}*."));
+    }
+    reference
+}
+
+/// `Doe, J. P.` for `Jane Patricia Doe`.
+fn reference_name(author: &CitationAuthor) -> String {
+    let initials = author
+        .given
+        .iter()
+        .flat_map(|given| given.split([' ', '-']))
+        .filter_map(|part| part.chars().next())
+        .filter(|initial| initial.is_uppercase())
+        .map(|initial| format!("{initial}."))
+        .collect::<Vec<_>>();
+
+    if initials.is_empty() {
+        author.family.clone()
+    } else {
+        format!("{}, {}", author.family, initials.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_author_year, format_reference, CitationAuthor, CitationEntry};
+
+    fn author(family: &str, given: &str) -> CitationAuthor {
+        CitationAuthor {
+            family: family.to_string(),
+            given: Some(given.to_string()),
+        }
+    }
+
+    fn entry(authors: Vec<CitationAuthor>) -> CitationEntry {
+        CitationEntry {
+            key: "doe2021".to_string(),
+            entry_type: "article".to_string(),
+            title: Some("Notes on Notes".to_string()),
+            authors,
+            year: Some("2021".to_string()),
+            container: Some("Journal of Notes".to_string()),
+        }
+    }
+
+    #[test]
+    fn formats_author_year_citations_by_author_count() {
+        assert_eq!(
+            format_author_year(&entry(vec![author("Doe", "Jane")])),
+            "(Doe, 2021)"
+        );
+        assert_eq!(
+            format_author_year(&entry(vec![author("Doe", "Jane"), author("Roe", "Max")])),
+            "(Doe & Roe, 2021)"
+        );
+        assert_eq!(
+            format_author_year(&entry(vec![
+                author("Doe", "Jane"),
+                author("Roe", "Max"),
+                author("Poe", "Ann"),
+            ])),
+            "(Doe et al., 2021)"
+        );
+        assert_eq!(
+            format_author_year(&entry(Vec::new())),
+            "(Notes on Notes, 2021)"
+        );
+    }
+
+    #[test]
+    fn formats_references_with_initials_and_container() {
+        assert_eq!(
+            format_reference(&entry(vec![
+                author("Doe", "Jane Patricia"),
+                author("Roe", "Jean-Max"),
+                author("Poe", "ann"),
+            ])),
+            "Doe, J. P., Roe, J. M., & Poe (2021). Notes on Notes. *Journal of Notes*."
+        );
+    }
+}
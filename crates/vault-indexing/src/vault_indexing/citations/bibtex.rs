@@ -0,0 +1,369 @@
+use std::collections::HashMap;
+
+use super::{CitationAuthor, CitationEntry};
+
+/// Entries of a BibTeX file. `@string` macros are expanded, `@comment` and
+/// `@preamble` blocks are skipped, and malformed entries are dropped.
+pub(super) fn parse_bibtex(source: &str) -> Vec<CitationEntry> {
+    let mut reader = Reader {
+        chars: source.chars().collect(),
+        pos: 0,
+    };
+    let mut macros = HashMap::new();
+    let mut entries = Vec::new();
+
+    while reader.skip_to('@') {
+        reader.pos += 1;
+        let entry_type = reader.read_identifier().to_lowercase();
+        reader.skip_whitespace();
+        let Some(close) = reader.open_block() else {
+            continue;
+        };
+
+        match entry_type.as_str() {
+            "comment" | "preamble" => reader.skip_block(close),
+            "string" => {
+                if let Some((name, value)) = reader.read_field(close, &macros) {
+                    macros.insert(name, value);
+                }
+                reader.skip_block(close);
+            }
+            _ => {
+                if let Some(entry) = reader.read_entry(entry_type, close, &macros) {
+                    entries.push(entry);
+                }
+            }
+        }
+    }
+
+    entries
+}
+
+struct Reader {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Reader {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_to(&mut self, target: char) -> bool {
+        while let Some(ch) = self.peek() {
+            if ch == target {
+                return true;
+            }
+            self.pos += 1;
+        }
+        false
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn read_identifier(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|ch| ch.is_alphanumeric() || "_-:.".contains(ch))
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Consumes `{` or `(` and returns the character closing the block.
+    fn open_block(&mut self) -> Option<char> {
+        let close = match self.peek()? {
+            '{' => '}',
+            '(' => ')',
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(close)
+    }
+
+    /// Moves past the end of the block closed by `close`.
+    fn skip_block(&mut self, close: char) {
+        let mut depth = 0usize;
+        while let Some(ch) = self.peek() {
+            self.pos += 1;
+            match ch {
+                '{' => depth += 1,
+                '}' if depth > 0 => depth -= 1,
+                _ if ch == close && depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    fn read_entry(
+        &mut self,
+        entry_type: String,
+        close: char,
+        macros: &HashMap<String, String>,
+    ) -> Option<CitationEntry> {
+        self.skip_whitespace();
+        let key_start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|ch| ch != ',' && ch != close && !ch.is_whitespace())
+        {
+            self.pos += 1;
+        }
+        let key: String = self.chars[key_start..self.pos].iter().collect();
+
+        let mut fields = HashMap::new();
+        while let Some((name, value)) = self.read_field(close, macros) {
+            fields.insert(name, value);
+        }
+        self.skip_block(close);
+
+        if key.is_empty() {
+            return None;
+        }
+
+        let authors = fields
+            .get("author")
+            .or_else(|| fields.get("editor"))
+            .map(|raw| parse_names(raw))
+            .unwrap_or_default();
+        let year = fields.get("year").cloned().or_else(|| {
+            fields
+                .get("date")
+                .and_then(|date| date.get(..4))
+                .map(str::to_string)
+        });
+        let container = ["journal", "journaltitle", "booktitle", "publisher"]
+            .iter()
+            .find_map(|name| fields.get(*name))
+            .map(|raw| clean_value(raw));
+
+        Some(CitationEntry {
+            key,
+            entry_type,
+            title: fields.get("title").map(|raw| clean_value(raw)),
+            authors,
+            year: year.map(|raw| clean_value(&raw)),
+            container,
+        })
+    }
+
+    /// Reads `name = value` up to the next comma. Values keep their braces so
+    /// names can tell corporate authors apart.
+    fn read_field(
+        &mut self,
+        close: char,
+        macros: &HashMap<String, String>,
+    ) -> Option<(String, String)> {
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                ',' => self.pos += 1,
+                ch if ch == close => return None,
+                _ => break,
+            }
+        }
+
+        let name = self.read_identifier().to_lowercase();
+        self.skip_whitespace();
+        if name.is_empty() || self.peek() != Some('=') {
+            return None;
+        }
+        self.pos += 1;
+
+        let mut value = String::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                '{' => value.push_str(&self.read_delimited('}')),
+                '"' => value.push_str(&self.read_delimited('"')),
+                _ => {
+                    let token = self.read_identifier();
+                    if token.is_empty() {
+                        return None;
+                    }
+                    let expanded = macros.get(&token.to_lowercase()).cloned().unwrap_or(token);
+                    value.push_str(&expanded);
+                }
+            }
+            self.skip_whitespace();
+            if self.peek() == Some('#') {
+                self.pos += 1;
+                continue;
+            }
+            break;
+        }
+
+        Some((name, value))
+    }
+
+    /// Reads a `{...}` or `"..."` value, returning its inside with nested
+    /// braces left in place.
+    fn read_delimited(&mut self, close: char) -> String {
+        self.pos += 1;
+        let start = self.pos;
+        let mut depth = 0usize;
+        while let Some(ch) = self.peek() {
+            if ch == close && depth == 0 {
+                break;
+            }
+            match ch {
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        let inner = self.chars[start..self.pos].iter().collect();
+        self.pos += 1;
+        inner
+    }
+}
+
+/// Removes braces and the few LaTeX escapes common in titles.
+fn clean_value(raw: &str) -> String {
+    let unescaped = raw
+        .replace("\\&", "&")
+        .replace("\\%", "%")
+        .replace("\\_", "_")
+        .replace("\\$", "$")
+        .replace("---", "\u{2014}")
+        .replace("--", "\u{2013}")
+        .replace('~', " ");
+    unescaped
+        .chars()
+        .filter(|ch| *ch != '{' && *ch != '}')
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits a BibTeX name list on ` and ` outside braces.
+fn parse_names(raw: &str) -> Vec<CitationAuthor> {
+    let mut names = Vec::new();
+    let bytes = raw.as_bytes();
+    let mut depth = 0usize;
+    let mut start = 0usize;
+    let mut index = 0usize;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            _ if depth == 0
+                && bytes[index..]
+                    .get(..5)
+                    .is_some_and(|word| word.eq_ignore_ascii_case(b" and ")) =>
+            {
+                names.push(&raw[start..index]);
+                index += " and ".len();
+                start = index;
+                continue;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    names.push(&raw[start..]);
+
+    names.into_iter().filter_map(parse_name).collect()
+}
+
+fn parse_name(raw: &str) -> Option<CitationAuthor> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+
+    // `{World Health Organization}` is one name, not a given and family name.
+    if raw.starts_with('{') && raw.ends_with('}') && !raw[1..raw.len() - 1].contains('{') {
+        return Some(CitationAuthor {
+            family: clean_value(raw),
+            given: None,
+        });
+    }
+
+    let cleaned = clean_value(raw);
+    if let Some((family, given)) = cleaned.split_once(',') {
+        // `von Last, Jr, First` keeps the first name after the last comma.
+        let given = given.rsplit(',').next().unwrap_or(given).trim();
+        return Some(CitationAuthor {
+            family: family.trim().to_string(),
+            given: (!given.is_empty()).then(|| given.to_string()),
+        });
+    }
+
+    match cleaned.rsplit_once(' ') {
+        Some((given, family)) => Some(CitationAuthor {
+            family: family.to_string(),
+            given: Some(given.to_string()),
+        }),
+        None => Some(CitationAuthor {
+            family: cleaned,
+            given: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_bibtex;
+    use crate::vault_indexing::citations::{CitationAuthor, CitationEntry};
+
+    #[test]
+    fn parses_entries_with_macros_names_and_nested_braces() {
+        let source = r#"
+@string{ jml = "Journal of Machine Learning" }
+@comment{ ignored @article{nope, title = {No}} }
+
+@article{smith2020,
+  author = {Smith, John and Jane {van der} Berg and {World Health Organization}},
+  title = {Learning {BibTeX} \& Friends},
+  journal = jml # " Research",
+  year = 2020,
+}
+
+@book(doe-book, title = "A {"}quoted{"} book", date = {2019-03-01})
+"#;
+
+        assert_eq!(
+            parse_bibtex(source),
+            vec![
+                CitationEntry {
+                    key: "smith2020".to_string(),
+                    entry_type: "article".to_string(),
+                    title: Some("Learning BibTeX & Friends".to_string()),
+                    authors: vec![
+                        CitationAuthor {
+                            family: "Smith".to_string(),
+                            given: Some("John".to_string()),
+                        },
+                        CitationAuthor {
+                            family: "Berg".to_string(),
+                            given: Some("Jane van der".to_string()),
+                        },
+                        CitationAuthor {
+                            family: "World Health Organization".to_string(),
+                            given: None,
+                        },
+                    ],
+                    year: Some("2020".to_string()),
+                    container: Some("Journal of Machine Learning Research".to_string()),
+                },
+                CitationEntry {
+                    key: "doe-book".to_string(),
+                    entry_type: "book".to_string(),
+                    title: Some("A \"quoted\" book".to_string()),
+                    authors: Vec::new(),
+                    year: Some("2019".to_string()),
+                    container: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,140 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+
+use super::{CitationAuthor, CitationEntry};
+
+/// Entries of a CSL-JSON array, as exported by Zotero and other reference
+/// managers. Items without an `id` are dropped.
+pub(super) fn parse_csl_json(source: &str) -> Result<Vec<CitationEntry>> {
+    let value: Value = serde_json::from_str(source).context("Failed to parse CSL-JSON")?;
+    let Value::Array(items) = value else {
+        return Err(anyhow!("CSL-JSON bibliography must be an array of items"));
+    };
+
+    Ok(items.iter().filter_map(parse_item).collect())
+}
+
+fn parse_item(item: &Value) -> Option<CitationEntry> {
+    let key = match item.get("id")? {
+        Value::String(id) => id.clone(),
+        Value::Number(id) => id.to_string(),
+        _ => return None,
+    };
+
+    let authors = ["author", "editor"]
+        .iter()
+        .filter_map(|field| item.get(*field).and_then(Value::as_array))
+        .map(|names| names.iter().filter_map(parse_name).collect::<Vec<_>>())
+        .find(|names| !names.is_empty())
+        .unwrap_or_default();
+
+    Some(CitationEntry {
+        key,
+        entry_type: string_field(item, "type").unwrap_or_else(|| "document".to_string()),
+        title: string_field(item, "title"),
+        authors,
+        year: item.get("issued").and_then(issued_year),
+        container: string_field(item, "container-title")
+            .or_else(|| string_field(item, "publisher")),
+    })
+}
+
+fn string_field(item: &Value, field: &str) -> Option<String> {
+    item.get(field)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn parse_name(name: &Value) -> Option<CitationAuthor> {
+    if let Some(literal) = string_field(name, "literal") {
+        return Some(CitationAuthor {
+            family: literal,
+            given: None,
+        });
+    }
+
+    Some(CitationAuthor {
+        family: string_field(name, "family")?,
+        given: string_field(name, "given"),
+    })
+}
+
+/// `{"date-parts": [[2020, 5]]}`, or a `raw`/`literal` date starting with the year.
+fn issued_year(issued: &Value) -> Option<String> {
+    if let Some(year) = issued
+        .get("date-parts")
+        .and_then(|parts| parts.get(0))
+        .and_then(|first| first.get(0))
+    {
+        return match year {
+            Value::Number(year) => Some(year.to_string()),
+            Value::String(year) => Some(year.clone()),
+            _ => None,
+        };
+    }
+
+    ["raw", "literal"]
+        .iter()
+        .filter_map(|field| string_field(issued, field))
+        .find_map(|date| {
+            let year = date.get(..4)?;
+            year.chars()
+                .all(|ch| ch.is_ascii_digit())
+                .then(|| year.to_string())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_csl_json;
+    use crate::vault_indexing::citations::{CitationAuthor, CitationEntry};
+
+    #[test]
+    fn parses_items_with_literal_names_and_date_parts() {
+        let source = r#"[
+            {
+                "id": "doe2021",
+                "type": "article-journal",
+                "title": "Notes on Notes",
+                "author": [{ "family": "Doe", "given": "Jane" }, { "literal": "The Lab" }],
+                "issued": { "date-parts": [[2021, 4]] },
+                "container-title": "Journal of Notes"
+            },
+            { "title": "No id" },
+            { "id": 42, "issued": { "raw": "1999-12" } }
+        ]"#;
+
+        assert_eq!(
+            parse_csl_json(source).expect("valid CSL-JSON"),
+            vec![
+                CitationEntry {
+                    key: "doe2021".to_string(),
+                    entry_type: "article-journal".to_string(),
+                    title: Some("Notes on Notes".to_string()),
+                    authors: vec![
+                        CitationAuthor {
+                            family: "Doe".to_string(),
+                            given: Some("Jane".to_string()),
+                        },
+                        CitationAuthor {
+                            family: "The Lab".to_string(),
+                            given: None,
+                        },
+                    ],
+                    year: Some("2021".to_string()),
+                    container: Some("Journal of Notes".to_string()),
+                },
+                CitationEntry {
+                    key: "42".to_string(),
+                    entry_type: "document".to_string(),
+                    title: None,
+                    authors: Vec::new(),
+                    year: Some("1999".to_string()),
+                    container: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+use crate::vault_indexing::tags::{split_frontmatter, strip_hidden_chars};
+
+/// Keys cited with Pandoc's bracketed syntax, such as `[@doe2021]` or
+/// `[see @doe2021, p. 4; -@smith2020]`, in order of first use. Code and
+/// link text are skipped.
+pub(crate) fn extract_citation_keys(source: &str) -> Vec<String> {
+    if !source.contains('@') {
+        return Vec::new();
+    }
+
+    let cleaned = strip_hidden_chars(source);
+    let (_, body) = split_frontmatter(&cleaned);
+
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut seen = HashSet::new();
+    let mut keys = Vec::new();
+    let mut skip_depth = 0usize;
+    // Brackets are split over several text events, so runs are joined first.
+    let mut text_run = String::new();
+
+    for event in Parser::new_ext(body, options) {
+        match event {
+            Event::Text(text) if skip_depth == 0 => {
+                text_run.push_str(&text);
+                continue;
+            }
+            Event::SoftBreak if skip_depth == 0 => {
+                text_run.push(' ');
+                continue;
+            }
+            Event::Start(Tag::CodeBlock(_) | Tag::Link { .. } | Tag::Image { .. }) => {
+                skip_depth = skip_depth.saturating_add(1);
+            }
+            Event::End(TagEnd::CodeBlock | TagEnd::Link | TagEnd::Image) => {
+                skip_depth = skip_depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+        collect_bracketed_keys(&text_run, &mut seen, &mut keys);
+        text_run.clear();
+    }
+    collect_bracketed_keys(&text_run, &mut seen, &mut keys);
+
+    keys
+}
+
+fn collect_bracketed_keys(text: &str, seen: &mut HashSet<String>, output: &mut Vec<String>) {
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        let after_open = &rest[open + 1..];
+        let Some(close) = after_open.find(']') else {
+            break;
+        };
+        let group = &after_open[..close];
+        // A nested `[` starts the group over.
+        if let Some(nested) = group.rfind('[') {
+            rest = &after_open[nested..];
+            continue;
+        }
+
+        for item in group.split(';') {
+            if let Some(key) = citation_key_in_item(item) {
+                if seen.insert(key.to_string()) {
+                    output.push(key.to_string());
+                }
+            }
+        }
+        rest = &after_open[close + 1..];
+    }
+}
+
+/// The `@key` of one citation in a group, after an optional prefix.
+fn citation_key_in_item(item: &str) -> Option<&str> {
+    let mut search_from = 0usize;
+    while let Some(relative_at) = item[search_from..].find('@') {
+        let at = search_from + relative_at;
+        let prev = item[..at].chars().next_back();
+        if prev.is_none_or(|ch| ch.is_whitespace() || ch == '-') {
+            let key = parse_key(&item[at + 1..]);
+            if !key.is_empty() {
+                return Some(key);
+            }
+        }
+        search_from = at + 1;
+    }
+    None
+}
+
+/// Pandoc keys start with a letter, digit or `_` and may contain inner
+/// punctuation, which must be followed by a key character.
+fn parse_key(text: &str) -> &str {
+    const INNER_PUNCTUATION: &str = ":.#$%&-+?<>~/";
+
+    let mut end = 0usize;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, ch)) = chars.next() {
+        if ch.is_alphanumeric() || ch == '_' {
+            end = index + ch.len_utf8();
+            continue;
+        }
+        let continues = end > 0
+            && INNER_PUNCTUATION.contains(ch)
+            && chars
+                .peek()
+                .is_some_and(|(_, next)| next.is_alphanumeric() || *next == '_');
+        if !continues {
+            break;
+        }
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract_citation_keys;
+
+    #[test]
+    fn extracts_bracketed_keys_outside_code_and_links() {
+        let raw = [
+            "---",
+            "title: '[@frontmatter]'",
+            "---",
+            "As shown [see @doe2021, p. 4; -@smith:2020.b] and again [@doe2021].",
+            "Mail me@example.com or [me@example.com], not `[@code]`.",
+            "[@linked](https://example.com) and [@wrapped",
+            "across-lines].",
+            "```",
+            "[@fenced]",
+            "```",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            extract_citation_keys(&raw),
+            vec!["doe2021", "smith:2020.b", "wrapped"]
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Placeholders left by iCloud Drive, Dropbox, OneDrive and other sync
+//! clients for files whose contents stay in the cloud until read. Reading one
+//! blocks on a download, so indexing leaves them alone until they are local.
+
+use std::{
+    fs::{self, File},
+    io,
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+
+/// Skip reason recorded in `skipped_files` for a placeholder. The note keeps
+/// its previous index entry and is picked up again once it has downloaded.
+pub const CLOUD_PLACEHOLDER_SKIP_REASON: &str =
+    "Cloud placeholder not downloaded yet; indexing deferred";
+
+#[cfg(target_os = "macos")]
+fn metadata_is_placeholder(metadata: &fs::Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt;
+
+    // SF_DATALESS from <sys/stat.h>, set by File Provider sync clients.
+    const SF_DATALESS: u32 = 0x4000_0000;
+    metadata.st_flags() & SF_DATALESS != 0
+}
+
+#[cfg(windows)]
+fn metadata_is_placeholder(metadata: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x0000_1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x0004_0000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x0040_0000;
+    metadata.file_attributes()
+        & (FILE_ATTRIBUTE_OFFLINE
+            | FILE_ATTRIBUTE_RECALL_ON_OPEN
+            | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+        != 0
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn metadata_is_placeholder(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+/// Whether `path` is a placeholder whose contents are not on this device.
+/// Reading the metadata never starts a download.
+pub fn is_cloud_placeholder(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|metadata| metadata_is_placeholder(&metadata))
+}
+
+/// Downloads a placeholder by reading it through, so opening the note does
+/// not stall on the first read. Returns whether `path` was a placeholder.
+pub fn materialize_cloud_placeholder(path: &Path) -> Result<bool> {
+    if !is_cloud_placeholder(path) {
+        return Ok(false);
+    }
+
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    io::copy(&mut file, &mut io::sink())
+        .with_context(|| format!("Failed to download {}", path.display()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::{is_cloud_placeholder, materialize_cloud_placeholder};
+
+    #[test]
+    fn local_files_are_not_placeholders() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should move forward")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("mdit-cloud-files-{nanos}.md"));
+        fs::write(&path, "# Local").expect("note should be written");
+
+        assert!(!is_cloud_placeholder(&path));
+        assert!(!materialize_cloud_placeholder(&path).expect("local note should read"));
+        assert!(!is_cloud_placeholder(&path.with_extension("missing")));
+
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use super::{
+    canonicalize_workspace_root,
+    files::{collect_markdown_files, MarkdownFile},
+    EmbeddingProfile,
+};
+
+/// What a consistency sweep found and fixed.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencySweep {
+    pub files_checked: usize,
+    /// Files with no row, or whose size or mtime differs from their row.
+    pub reindexed: Vec<String>,
+    /// Rows whose file is gone.
+    pub pruned: Vec<String>,
+}
+
+/// Compares the file tree against the indexed rows by size and mtime only,
+/// re-indexes the files that diverge and prunes rows whose file is gone.
+/// Catches up on watcher events missed over a long uptime without reading
+/// unchanged files.
+pub fn run_consistency_sweep(
+    workspace_root: &Path,
+    db_path: &Path,
+    embedding_profiles: &[EmbeddingProfile],
+) -> Result<ConsistencySweep> {
+    let _ = canonicalize_workspace_root(workspace_root)?;
+    let files = collect_markdown_files(workspace_root)?;
+    let mut sweep = ConsistencySweep {
+        files_checked: files.len(),
+        ..Default::default()
+    };
+
+    let conn = super::open_indexing_connection(db_path)?;
+    let Some(vault_id) = super::find_vault_id(&conn, workspace_root)? else {
+        // Never indexed; the first full index run covers every file.
+        return Ok(sweep);
+    };
+    let mut rows = load_source_stats(&conn, vault_id)?;
+
+    let mut divergent = Vec::new();
+    let mut seen = HashSet::with_capacity(files.len());
+    for file in files {
+        seen.insert(file.rel_path.clone());
+        if !rows
+            .get(&file.rel_path)
+            .is_some_and(|row| row.matches(&file))
+        {
+            divergent.push(file);
+        }
+    }
+
+    rows.retain(|rel_path, _| !seen.contains(rel_path));
+    let mut ghosts = rows.into_iter().collect::<Vec<_>>();
+    ghosts.sort_by(|(left, _), (right, _)| left.cmp(right));
+    let ghost_ids = ghosts.iter().map(|(_, row)| row.doc_id).collect::<Vec<_>>();
+    super::tombstones::delete_docs(&conn, vault_id, &ghost_ids)
+        .context("Failed to prune documents whose files are gone")?;
+    sweep.pruned = ghosts.into_iter().map(|(rel_path, _)| rel_path).collect();
+    drop(conn);
+
+    if !divergent.is_empty() {
+        sweep.reindexed = divergent.iter().map(|file| file.rel_path.clone()).collect();
+        super::run_indexing_for_files(
+            workspace_root,
+            db_path,
+            embedding_profiles,
+            divergent,
+            false,
+            false,
+        )?;
+    }
+
+    Ok(sweep)
+}
+
+struct SourceStatRow {
+    doc_id: i64,
+    size: Option<i64>,
+    mtime_ns: Option<i64>,
+}
+
+impl SourceStatRow {
+    fn matches(&self, file: &MarkdownFile) -> bool {
+        self.size.is_some()
+            && self.mtime_ns.is_some()
+            && self.size == file.last_source_size
+            && self.mtime_ns == file.last_source_mtime_ns
+    }
+}
+
+fn load_source_stats(conn: &Connection, vault_id: i64) -> Result<HashMap<String, SourceStatRow>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, rel_path, last_source_size, last_source_mtime_ns FROM doc WHERE vault_id = ?1",
+    )?;
+    let rows = stmt
+        .query_map(params![vault_id], |row| {
+            Ok((
+                row.get::<_, String>(1)?,
+                SourceStatRow {
+                    doc_id: row.get(0)?,
+                    size: row.get(2)?,
+                    mtime_ns: row.get(3)?,
+                },
+            ))
+        })?
+        .collect::<rusqlite::Result<HashMap<_, _>>>()
+        .context("Failed to load indexed source stats")?;
+    Ok(rows)
+}
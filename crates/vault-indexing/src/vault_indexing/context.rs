@@ -0,0 +1,280 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use super::{
+    chunking::{hash_content, tokenizer, DocumentChunk},
+    files::{disk_path, MarkdownFile},
+    search::{search_notes_for_query_with_profiles, MatchedPassage},
+    sync::{load_indexing_options, IndexingOptions, PreparedDocument},
+    EmbeddingProfile,
+};
+
+const BLOCK_SEPARATOR: &str = "\n\n";
+
+/// What to build a context from. Explicit notes come first, followed by the
+/// best passage of each note the query matches.
+#[derive(Debug, Clone, Default)]
+pub struct ContextRequest {
+    pub query: Option<String>,
+    /// Absolute or vault-relative note paths.
+    pub note_paths: Vec<PathBuf>,
+    /// Budget for the whole context string, in cl100k tokens.
+    pub max_tokens: usize,
+}
+
+/// Note passages packed for an AI client, each cited as `[n]`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteContext {
+    pub context: String,
+    pub token_count: usize,
+    pub sources: Vec<ContextSource>,
+    /// Whether passages were left out to stay within the budget.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextSource {
+    pub marker: usize,
+    pub path: String,
+    pub rel_path: String,
+    pub passages: Vec<MatchedPassage>,
+}
+
+struct Candidate {
+    rel_path: String,
+    chunk: DocumentChunk,
+    body: String,
+}
+
+/// Selects passages in priority order, drops repeated text and packs them
+/// into `max_tokens`, grouped by note in the order notes were first picked.
+pub fn build_context(
+    workspace_root: &Path,
+    db_path: &Path,
+    request: &ContextRequest,
+    embedding_profiles: &[EmbeddingProfile],
+) -> Result<NoteContext> {
+    build_context_where(
+        workspace_root,
+        db_path,
+        request,
+        embedding_profiles,
+        |_| true,
+        |_| {},
+    )
+}
+
+/// [`build_context`] drawing only on notes whose file `keep` accepts, with
+/// passage text and headings passed through `redact` before they are
+/// counted, so left-out notes and masked text never take up the budget.
+pub fn build_context_where(
+    workspace_root: &Path,
+    db_path: &Path,
+    request: &ContextRequest,
+    embedding_profiles: &[EmbeddingProfile],
+    mut keep: impl FnMut(&Path) -> bool,
+    mut redact: impl FnMut(&mut String),
+) -> Result<NoteContext> {
+    let query = request
+        .query
+        .as_deref()
+        .map(str::trim)
+        .filter(|query| !query.is_empty());
+    if query.is_none() && request.note_paths.is_empty() {
+        return Err(anyhow!("A query or note paths must be provided"));
+    }
+    if request.max_tokens == 0 {
+        return Err(anyhow!("Token budget must be positive"));
+    }
+
+    let options = load_options(workspace_root, db_path)?;
+    let mut candidates = Vec::new();
+
+    for note_path in &request.note_paths {
+        let rel_path = super::to_workspace_rel_markdown_path(workspace_root, note_path)?;
+        let document = load_document(workspace_root, &rel_path, &options)?;
+        for chunk in document.chunks() {
+            candidates.push(Candidate {
+                body: document.chunk_body(&chunk),
+                rel_path: rel_path.clone(),
+                chunk,
+            });
+        }
+    }
+
+    if let Some(query) = query {
+        for entry in search_notes_for_query_with_profiles(
+            workspace_root,
+            db_path,
+            query,
+            embedding_profiles,
+        )? {
+            let rel_path =
+                super::to_workspace_rel_markdown_path(workspace_root, Path::new(&entry.path))?;
+            let document = load_document(workspace_root, &rel_path, &options)?;
+            let chunks = document.chunks();
+            // Keyword-only matches carry no passage; their opening chunk stands in.
+            let best = entry
+                .passage
+                .as_ref()
+                .and_then(|passage| {
+                    chunks
+                        .iter()
+                        .find(|chunk| chunk.start_offset == passage.start_offset)
+                })
+                .or_else(|| chunks.first());
+            if let Some(chunk) = best {
+                candidates.push(Candidate {
+                    body: document.chunk_body(chunk),
+                    rel_path,
+                    chunk: chunk.clone(),
+                });
+            }
+        }
+    }
+
+    candidates.retain(|candidate| keep(&disk_path(workspace_root, &candidate.rel_path)));
+    for candidate in &mut candidates {
+        redact(&mut candidate.body);
+        for heading in &mut candidate.chunk.heading_path {
+            redact(heading);
+        }
+    }
+
+    Ok(pack_candidates(
+        workspace_root,
+        candidates,
+        request.max_tokens,
+    ))
+}
+
+fn load_options(workspace_root: &Path, db_path: &Path) -> Result<IndexingOptions> {
+    let conn = super::open_indexing_connection(db_path)?;
+    match super::find_vault_id(&conn, workspace_root)? {
+        Some(vault_id) => load_indexing_options(&conn, vault_id),
+        None => Ok(IndexingOptions::default()),
+    }
+}
+
+fn load_document(
+    workspace_root: &Path,
+    rel_path: &str,
+    options: &IndexingOptions,
+) -> Result<PreparedDocument> {
+    let file =
+        MarkdownFile::from_abs_and_rel(disk_path(workspace_root, rel_path), rel_path.to_string());
+    PreparedDocument::load(file, options)
+}
+
+fn pack_candidates(
+    workspace_root: &Path,
+    candidates: Vec<Candidate>,
+    max_tokens: usize,
+) -> NoteContext {
+    let mut seen_text = HashSet::new();
+    let mut selected: Vec<Candidate> = Vec::new();
+    let mut used_tokens = 0;
+    let mut truncated = false;
+
+    for candidate in candidates {
+        if candidate.body.is_empty() || !seen_text.insert(hash_content(&candidate.body)) {
+            continue;
+        }
+
+        let cost = count_tokens(&block_header(0, &candidate))
+            + count_tokens(&candidate.body)
+            + count_tokens(BLOCK_SEPARATOR);
+        if used_tokens + cost > max_tokens {
+            truncated = true;
+            continue;
+        }
+
+        used_tokens += cost;
+        selected.push(candidate);
+    }
+
+    // Token counts of separate pieces can differ from the joined string, so
+    // the rendered context is checked and trimmed from the lowest priority end.
+    loop {
+        let (context, sources) = render(workspace_root, &selected);
+        let token_count = count_tokens(&context);
+        if token_count <= max_tokens || selected.is_empty() {
+            return NoteContext {
+                context,
+                token_count,
+                sources,
+                truncated,
+            };
+        }
+
+        selected.pop();
+        truncated = true;
+    }
+}
+
+fn render(workspace_root: &Path, selected: &[Candidate]) -> (String, Vec<ContextSource>) {
+    let mut sources: Vec<(ContextSource, Vec<&Candidate>)> = Vec::new();
+    for candidate in selected {
+        match sources
+            .iter_mut()
+            .find(|(source, _)| source.rel_path == candidate.rel_path)
+        {
+            Some((_, passages)) => passages.push(candidate),
+            None => sources.push((
+                ContextSource {
+                    marker: sources.len() + 1,
+                    path: disk_path(workspace_root, &candidate.rel_path)
+                        .to_string_lossy()
+                        .to_string(),
+                    rel_path: candidate.rel_path.clone(),
+                    passages: Vec::new(),
+                },
+                vec![candidate],
+            )),
+        }
+    }
+
+    let mut blocks = Vec::new();
+    let sources = sources
+        .into_iter()
+        .map(|(mut source, mut candidates)| {
+            candidates.sort_by_key(|candidate| candidate.chunk.start_offset);
+            for candidate in candidates {
+                blocks.push(format!(
+                    "{}\n{}",
+                    block_header(source.marker, candidate),
+                    candidate.body
+                ));
+                source.passages.push(MatchedPassage {
+                    start_offset: candidate.chunk.start_offset,
+                    end_offset: candidate.chunk.end_offset,
+                    heading_path: candidate.chunk.heading_path.clone(),
+                    code_language: candidate.chunk.code_language.clone(),
+                });
+            }
+            source
+        })
+        .collect();
+
+    (blocks.join(BLOCK_SEPARATOR), sources)
+}
+
+fn block_header(marker: usize, candidate: &Candidate) -> String {
+    let mut header = format!("[{}] {}", marker, candidate.rel_path);
+    for heading in &candidate.chunk.heading_path {
+        header.push_str(" > ");
+        header.push_str(heading);
+    }
+    header
+}
+
+fn count_tokens(text: &str) -> usize {
+    tokenizer().encode_ordinary(text).len()
+}
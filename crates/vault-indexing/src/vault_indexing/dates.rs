@@ -0,0 +1,72 @@
+use serde_yaml::Value;
+
+use super::{
+    calendar::CalendarDate,
+    tags::{frontmatter_payload, lookup_mapping_value, split_frontmatter, strip_hidden_chars},
+};
+
+/// Frontmatter properties placed on the calendar.
+const DATE_KEYS: &[&str] = &["date", "due"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NoteDate {
+    pub(crate) property: String,
+    /// `YYYY-MM-DD`, so dates compare as text.
+    pub(crate) date: String,
+}
+
+/// Dates declared in the frontmatter `date:` and `due:` properties. A time
+/// after the date, as in `2024-05-07T09:30`, is dropped.
+pub(crate) fn extract_note_dates(source: &str) -> Vec<NoteDate> {
+    if source.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let cleaned = strip_hidden_chars(source);
+    let (Some(frontmatter), _) = split_frontmatter(&cleaned) else {
+        return Vec::new();
+    };
+    let Ok(value) = serde_yaml::from_str::<Value>(&frontmatter_payload(frontmatter)) else {
+        return Vec::new();
+    };
+
+    DATE_KEYS
+        .iter()
+        .filter_map(|key| {
+            let Some(Value::String(raw)) = lookup_mapping_value(&value, key) else {
+                return None;
+            };
+            let date = CalendarDate::parse(raw.trim().get(..10)?)?;
+            Some(NoteDate {
+                property: key.to_string(),
+                date: date.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_note_dates, NoteDate};
+
+    #[test]
+    fn extracts_date_and_due_ignoring_times_and_invalid_values() {
+        let raw = [
+            "---",
+            "Date: 2024-05-07T09:30",
+            "due: '2024-02-30'",
+            "created: 2024-01-01",
+            "---",
+            "Body",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            extract_note_dates(&raw),
+            vec![NoteDate {
+                property: "date".to_string(),
+                date: "2024-05-07".to_string(),
+            }]
+        );
+    }
+}
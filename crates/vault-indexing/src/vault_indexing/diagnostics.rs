@@ -0,0 +1,164 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    path::Path,
+};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::{
+    files::{collect_workspace_entries, WorkspaceEntry},
+    links::wiki_basename_key,
+};
+
+/// Problems in the layout of a vault that the index cannot fix by itself.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDiagnostics {
+    /// Files or folders whose paths only differ by letter case. Such vaults
+    /// break when synced to a case-insensitive file system.
+    pub case_conflicts: Vec<CaseConflict>,
+    /// Notes sharing a file name, which makes a bare `[[name]]` ambiguous.
+    pub duplicate_basenames: Vec<DuplicateBasename>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaseConflict {
+    pub rel_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateBasename {
+    /// Lowercased name without extension, as matched by wiki links.
+    pub basename: String,
+    pub rel_paths: Vec<String>,
+}
+
+pub fn run_vault_diagnostics(workspace_root: &Path) -> Result<VaultDiagnostics> {
+    let entries = collect_workspace_entries(workspace_root)?;
+
+    Ok(VaultDiagnostics {
+        case_conflicts: find_case_conflicts(&entries),
+        duplicate_basenames: find_duplicate_basenames(&entries),
+    })
+}
+
+fn find_case_conflicts(entries: &[WorkspaceEntry]) -> Vec<CaseConflict> {
+    let mut spellings: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+    for entry in entries {
+        spellings
+            .entry(entry.rel_path.to_lowercase())
+            .or_default()
+            .insert(entry.rel_path.as_str());
+    }
+
+    let conflicting = spellings
+        .iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(lower, _)| lower.as_str())
+        .collect::<HashSet<_>>();
+
+    // Children of a conflicting folder collide as well; reporting the folder is enough.
+    spellings
+        .iter()
+        .filter(|(lower, _)| conflicting.contains(lower.as_str()))
+        .filter(|(lower, _)| {
+            !lower
+                .match_indices('/')
+                .any(|(index, _)| conflicting.contains(&lower[..index]))
+        })
+        .map(|(_, paths)| CaseConflict {
+            rel_paths: paths.iter().map(|path| path.to_string()).collect(),
+        })
+        .collect()
+}
+
+fn find_duplicate_basenames(entries: &[WorkspaceEntry]) -> Vec<DuplicateBasename> {
+    let mut notes_by_basename: BTreeMap<String, BTreeMap<String, &str>> = BTreeMap::new();
+    for entry in entries.iter().filter(|entry| !entry.is_dir) {
+        let Some(basename) = wiki_basename_key(&entry.rel_path) else {
+            continue;
+        };
+        // Case-only duplicates of one path are already a case conflict.
+        notes_by_basename
+            .entry(basename)
+            .or_default()
+            .entry(entry.rel_path.to_lowercase())
+            .or_insert(entry.rel_path.as_str());
+    }
+
+    notes_by_basename
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(basename, paths)| DuplicateBasename {
+            basename,
+            rel_paths: paths.into_values().map(str::to_string).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_case_conflicts, find_duplicate_basenames};
+    use crate::vault_indexing::files::WorkspaceEntry;
+
+    fn entries(paths: &[(&str, bool)]) -> Vec<WorkspaceEntry> {
+        paths
+            .iter()
+            .map(|(rel_path, is_dir)| WorkspaceEntry {
+                abs_path: rel_path.into(),
+                rel_path: rel_path.to_string(),
+                is_dir: *is_dir,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_case_conflicts_once_per_top_most_folder() {
+        let entries = entries(&[
+            ("Notes", true),
+            ("notes", true),
+            ("Notes/a.md", false),
+            ("notes/a.md", false),
+            ("Todo.md", false),
+            ("todo.md", false),
+            ("other.md", false),
+        ]);
+
+        let conflicts = find_case_conflicts(&entries)
+            .into_iter()
+            .map(|conflict| conflict.rel_paths)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            conflicts,
+            vec![
+                vec!["Notes".to_string(), "notes".to_string()],
+                vec!["Todo.md".to_string(), "todo.md".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_notes_sharing_a_basename_in_different_folders() {
+        let entries = entries(&[
+            ("a/Meeting.md", false),
+            ("b/meeting.md", false),
+            ("Todo.md", false),
+            ("todo.md", false),
+            ("c/meeting.md", true),
+            ("image.png", false),
+        ]);
+
+        let duplicates = find_duplicate_basenames(&entries);
+
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].basename, "meeting");
+        assert_eq!(
+            duplicates[0].rel_paths,
+            vec!["a/Meeting.md".to_string(), "b/meeting.md".to_string()]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::Value;
+
+/// The text typed into an Excalidraw or tldraw drawing, one paragraph per
+/// text element in drawing order. Wiki links typed into the drawing or set
+/// as an element's link stay as written, so they are resolved like links in
+/// notes.
+pub(crate) fn extract_drawing_text(source: &str) -> Result<String> {
+    if source.trim().is_empty() {
+        return Ok(String::new());
+    }
+    let drawing = serde_json::from_str::<Value>(source).context("Drawing is not valid JSON")?;
+
+    let mut paragraphs = Vec::new();
+    if let Some(elements) = drawing.get("elements").and_then(Value::as_array) {
+        collect_excalidraw_text(elements, &mut paragraphs);
+    } else if let Some(records) = drawing.get("records").and_then(Value::as_array) {
+        collect_tldraw_text(records, &mut paragraphs);
+    } else {
+        return Err(anyhow!(
+            "Drawing has neither Excalidraw elements nor tldraw records"
+        ));
+    }
+
+    Ok(paragraphs
+        .iter()
+        .map(|paragraph| paragraph.trim())
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n"))
+}
+
+fn collect_excalidraw_text(elements: &[Value], paragraphs: &mut Vec<String>) {
+    for element in elements {
+        if element.get("isDeleted").and_then(Value::as_bool) == Some(true) {
+            continue;
+        }
+        // `originalText` is what was typed; `text` has the line breaks added
+        // to wrap it inside its container.
+        let text = string_field(element, "originalText").or_else(|| string_field(element, "text"));
+        paragraphs.extend(text.map(str::to_string));
+        if element.get("type").and_then(Value::as_str) == Some("frame") {
+            paragraphs.extend(string_field(element, "name").map(str::to_string));
+        }
+        paragraphs.extend(string_field(element, "link").map(str::to_string));
+    }
+}
+
+fn collect_tldraw_text(records: &[Value], paragraphs: &mut Vec<String>) {
+    for record in records {
+        if record.get("typeName").and_then(Value::as_str) != Some("shape") {
+            continue;
+        }
+        let Some(props) = record.get("props") else {
+            continue;
+        };
+        if let Some(text) = string_field(props, "text") {
+            paragraphs.push(text.to_string());
+        } else if let Some(rich_text) = props.get("richText") {
+            // Newer tldraw keeps shape text as a rich text document of
+            // paragraphs holding text runs.
+            let mut lines = Vec::new();
+            collect_rich_text_lines(rich_text, &mut lines);
+            paragraphs.push(lines.join("\n"));
+        }
+        paragraphs.extend(string_field(props, "name").map(str::to_string));
+        paragraphs.extend(string_field(props, "url").map(str::to_string));
+    }
+}
+
+fn collect_rich_text_lines(node: &Value, lines: &mut Vec<String>) {
+    if node.get("type").and_then(Value::as_str) == Some("paragraph") {
+        let mut line = String::new();
+        collect_rich_text_runs(node, &mut line);
+        lines.push(line);
+        return;
+    }
+    for child in node
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        collect_rich_text_lines(child, lines);
+    }
+}
+
+fn collect_rich_text_runs(node: &Value, line: &mut String) {
+    if let Some(text) = string_field(node, "text") {
+        line.push_str(text);
+    }
+    for child in node
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        collect_rich_text_runs(child, line);
+    }
+}
+
+fn string_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|text| !text.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::extract_drawing_text;
+
+    #[test]
+    fn extracts_text_elements_and_links_from_excalidraw() {
+        let drawing = json!({
+            "type": "excalidraw",
+            "version": 2,
+            "elements": [
+                { "type": "rectangle", "link": "[[Roadmap]]" },
+                {
+                    "type": "text",
+                    "text": "Ship the\nbeta",
+                    "originalText": "Ship the beta",
+                    "isDeleted": false
+                },
+                { "type": "text", "text": "Scrapped idea", "isDeleted": true },
+                { "type": "frame", "name": "Q3" },
+                { "type": "text", "text": "See [[Launch plan]] #launch" }
+            ]
+        });
+        assert_eq!(
+            extract_drawing_text(&drawing.to_string()).expect("text"),
+            "[[Roadmap]]\n\nShip the beta\n\nQ3\n\nSee [[Launch plan]] #launch"
+        );
+    }
+
+    #[test]
+    fn extracts_plain_and_rich_text_shapes_from_tldraw() {
+        let drawing = json!({
+            "tldrawFileFormatVersion": 1,
+            "records": [
+                { "typeName": "page", "name": "Page 1" },
+                { "typeName": "shape", "type": "note", "props": { "text": "Call [[Ada]]" } },
+                {
+                    "typeName": "shape",
+                    "type": "geo",
+                    "props": {
+                        "richText": {
+                            "type": "doc",
+                            "content": [
+                                {
+                                    "type": "paragraph",
+                                    "content": [
+                                        { "type": "text", "text": "Budget " },
+                                        { "type": "text", "text": "review", "marks": [{ "type": "bold" }] }
+                                    ]
+                                },
+                                { "type": "paragraph", "content": [{ "type": "text", "text": "Friday" }] }
+                            ]
+                        }
+                    }
+                },
+                { "typeName": "shape", "type": "frame", "props": { "name": "Week 42" } }
+            ]
+        });
+        assert_eq!(
+            extract_drawing_text(&drawing.to_string()).expect("text"),
+            "Call [[Ada]]\n\nBudget review\nFriday\n\nWeek 42"
+        );
+        assert!(extract_drawing_text("{\"shapes\": []}").is_err());
+        assert!(extract_drawing_text("not json").is_err());
+    }
+}
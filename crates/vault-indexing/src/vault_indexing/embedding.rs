@@ -3,91 +3,146 @@ use std::convert::TryFrom;
 use anyhow::{anyhow, Context, Result};
 use ollama_client::BlockingOllamaEmbeddingClient;
 
+use super::embedding_providers::EmbeddingProviderRegistry;
+
 #[derive(Debug)]
 pub(crate) struct EmbeddingVector {
     pub(crate) dim: i32,
     pub(crate) bytes: Vec<u8>,
 }
 
-/// Supported providers that can generate embedding vectors.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum EmbeddingProvider {
-    Ollama,
-    #[cfg(test)]
-    Test,
+/// A provider/model pair that can produce embedding vectors for a vault.
+///
+/// Callers pass profiles in priority order; indexing and search use the first
+/// one that is reachable and record which profile produced each vector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddingProfile {
+    pub provider: String,
+    pub model: String,
 }
 
-impl EmbeddingProvider {
-    /// Parse human input (e.g., CLI argument) into a provider enum.
-    fn from_str(value: &str) -> Result<Self> {
-        match value.trim().to_lowercase().as_str() {
-            "ollama" => Ok(Self::Ollama),
-            #[cfg(test)]
-            "test" => Ok(Self::Test),
-            provider => Err(anyhow!(
-                "Unsupported embedding provider '{}'. Only 'ollama' is currently supported.",
-                provider
-            )),
+impl EmbeddingProfile {
+    pub fn new(provider: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            provider: provider.into(),
+            model: model.into(),
         }
     }
+
+    pub(crate) fn is_configured(&self) -> bool {
+        !self.provider.trim().is_empty() && !self.model.trim().is_empty()
+    }
+
+    pub(crate) fn normalized_provider(&self) -> String {
+        self.provider.trim().to_lowercase()
+    }
+}
+
+/// Produces raw, unnormalized vectors for a model. There is one implementation
+/// per kind of provider; [`EmbeddingProviderRegistry`] picks it by name.
+pub(crate) trait EmbeddingBackend {
+    fn embed(&self, model: &str, text: &str) -> Result<Vec<f32>>;
+
+    /// Models the provider offers, for pickers and health checks.
+    fn list_models(&self) -> Result<Vec<String>>;
+
+    /// Vector size the provider is configured to return, if it declares one.
+    fn dimension(&self) -> Option<i32> {
+        None
+    }
+}
+
+pub(crate) struct OllamaEmbeddingBackend(BlockingOllamaEmbeddingClient);
+
+impl OllamaEmbeddingBackend {
+    pub(crate) fn new() -> Result<Self> {
+        BlockingOllamaEmbeddingClient::new()
+            .map(Self)
+            .context("Failed to initialize Ollama embedding client")
+    }
 }
 
-enum EmbeddingBackend {
-    Ollama(BlockingOllamaEmbeddingClient),
-    #[cfg(test)]
-    Test,
+impl EmbeddingBackend for OllamaEmbeddingBackend {
+    fn embed(&self, model: &str, text: &str) -> Result<Vec<f32>> {
+        self.0
+            .generate_embedding(model, text)
+            .context("Failed to generate embeddings with Ollama")
+    }
+
+    fn list_models(&self) -> Result<Vec<String>> {
+        self.0
+            .list_embedding_models()
+            .context("Failed to list Ollama models")
+    }
+}
+
+#[cfg(test)]
+pub(crate) struct TestEmbeddingBackend;
+
+#[cfg(test)]
+impl EmbeddingBackend for TestEmbeddingBackend {
+    fn embed(&self, _model: &str, text: &str) -> Result<Vec<f32>> {
+        Ok(vec![
+            text.len().max(1) as f32,
+            text.bytes().map(f32::from).sum::<f32>().max(1.0),
+            1.0,
+        ])
+    }
+
+    fn list_models(&self) -> Result<Vec<String>> {
+        Ok(vec!["test-embedding".to_string()])
+    }
 }
 
 pub(crate) struct EmbeddingClient {
+    provider: String,
     model: String,
-    backend: EmbeddingBackend,
+    backend: Box<dyn EmbeddingBackend>,
 }
 
 impl EmbeddingClient {
-    /// Instantiate a concrete backend client for the requested provider.
-    pub(crate) fn new(provider: &str, model: &str) -> Result<Self> {
+    /// Instantiate the backend registered for the requested provider.
+    pub(crate) fn new(
+        registry: &EmbeddingProviderRegistry,
+        provider: &str,
+        model: &str,
+    ) -> Result<Self> {
         if model.trim().is_empty() {
             return Err(anyhow!("Embedding model must be provided"));
         }
 
-        let provider = EmbeddingProvider::from_str(provider)?;
-        let backend = match provider {
-            EmbeddingProvider::Ollama => {
-                let client = BlockingOllamaEmbeddingClient::new()
-                    .context("Failed to initialize Ollama embedding client")?;
-                EmbeddingBackend::Ollama(client)
-            }
-            #[cfg(test)]
-            EmbeddingProvider::Test => EmbeddingBackend::Test,
-        };
-
+        let backend = registry.create_backend(provider)?;
         Ok(Self {
+            provider: provider.trim().to_lowercase(),
             model: model.to_string(),
             backend,
         })
     }
 
+    pub(crate) fn provider_name(&self) -> &str {
+        &self.provider
+    }
+
     pub(crate) fn model_name(&self) -> &str {
         &self.model
     }
 
-    /// Generate an embedding vector for the supplied chunk using the selected backend.
+    /// Generate a normalized embedding vector for the supplied chunk.
     pub(crate) fn generate(&self, text: &str) -> Result<EmbeddingVector> {
-        match &self.backend {
-            EmbeddingBackend::Ollama(client) => self.generate_with_ollama(client, text),
-            #[cfg(test)]
-            EmbeddingBackend::Test => self.generate_with_test(text),
+        let mut vector = {
+            let _timer = super::metrics::EMBEDDING_LATENCY.start_timer();
+            self.backend.embed(&self.model, text)?
+        };
+        if let Some(expected) = self.backend.dimension() {
+            if usize::try_from(expected).ok() != Some(vector.len()) {
+                return Err(anyhow!(
+                    "Embedding provider '{}' returned {} dimensions, expected {}",
+                    self.provider,
+                    vector.len(),
+                    expected
+                ));
+            }
         }
-    }
-
-    fn generate_with_ollama(
-        &self,
-        client: &BlockingOllamaEmbeddingClient,
-        text: &str,
-    ) -> Result<EmbeddingVector> {
-        let mut vector = client
-            .generate_embedding(&self.model, text)
-            .context("Failed to generate embeddings with Ollama")?;
 
         l2_normalize(&mut vector).with_context(|| {
             format!(
@@ -104,36 +159,15 @@ impl EmbeddingClient {
             bytes: f32_slice_to_le_bytes(&vector),
         })
     }
+}
 
-    #[cfg(test)]
-    fn generate_with_test(&self, text: &str) -> Result<EmbeddingVector> {
-        let mut vector = vec![
-            text.len().max(1) as f32,
-            text.bytes().map(f32::from).sum::<f32>().max(1.0),
-            1.0,
-        ];
-
-        l2_normalize(&mut vector).with_context(|| {
-            format!(
-                "Embedding vector for model '{}' contained invalid values",
-                self.model
-            )
-        })?;
-
-        let dim = i32::try_from(vector.len())
-            .map_err(|_| anyhow!("Embedding dimension {} exceeds i32::MAX", vector.len()))?;
-
-        Ok(EmbeddingVector {
-            dim,
-            bytes: f32_slice_to_le_bytes(&vector),
-        })
+/// Resolve the embedding dimension of a client, generating a test embedding
+/// unless the provider declares its dimension.
+pub(crate) fn resolve_embedding_dimension(embedder: &EmbeddingClient) -> Result<i32> {
+    if let Some(dimension) = embedder.backend.dimension() {
+        return Ok(dimension);
     }
-}
 
-/// Resolve the embedding dimension for a given provider and model by generating
-/// a test embedding and extracting its dimension.
-pub(crate) fn resolve_embedding_dimension(provider: &str, model: &str) -> Result<i32> {
-    let embedder = EmbeddingClient::new(provider, model)?;
     let test_embedding = embedder.generate("test")?;
     Ok(test_embedding.dim)
 }
@@ -0,0 +1,187 @@
+//! How much of a vault semantic search can trust right now: notes with
+//! vectors from the vault's current model, notes edited since they were
+//! embedded, and segments longer than the model reads.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use super::{
+    canonicalize_workspace_root, chunking::count_tokens, find_vault_id, open_indexing_connection,
+    segment_vec_table_exists,
+};
+
+// Input windows as the providers document them, in tokens. Models missing
+// here report no window and no oversized segments.
+const MODEL_CONTEXT_TOKENS: &[(&str, usize)] = &[
+    ("all-minilm", 512),
+    ("bge-large", 512),
+    ("bge-m3", 8192),
+    ("embeddinggemma", 2048),
+    ("granite-embedding", 512),
+    ("mxbai-embed-large", 512),
+    ("nomic-embed-text", 2048),
+    ("paraphrase-multilingual", 512),
+    ("qwen3-embedding", 32768),
+    ("snowflake-arctic-embed", 512),
+    ("snowflake-arctic-embed2", 8192),
+    ("text-embedding-3-large", 8191),
+    ("text-embedding-3-small", 8191),
+    ("text-embedding-ada-002", 8191),
+];
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingCoverage {
+    /// The vault's primary embedding profile; `None` while embeddings are off.
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    /// Tokens the model reads per input, when known. Text past it is cut off
+    /// before embedding.
+    pub model_context_tokens: Option<usize>,
+    /// Indexed notes meant to be embedded, leaving out `embed: false` ones.
+    pub indexed_doc_count: usize,
+    /// Notes with vectors from the current model.
+    pub current_model_doc_count: usize,
+    /// Notes whose vectors come from another model, such as the fallback or
+    /// one used before the vault switched. Search does not compare them with
+    /// queries embedded by the current model.
+    pub other_model_doc_count: usize,
+    /// Embedded notes whose content changed since their vectors were made.
+    pub stale_doc_count: usize,
+    pub embedded_segment_count: usize,
+    /// Embedded segments longer than `model_context_tokens`, counted with the
+    /// chunker's tokenizer, which only approximates the model's own.
+    pub oversized_segment_count: usize,
+}
+
+pub fn get_embedding_coverage(workspace_root: &Path, db_path: &Path) -> Result<EmbeddingCoverage> {
+    let _ = canonicalize_workspace_root(workspace_root)?;
+    let profile = app_storage::vault::get_embedding_config(db_path, workspace_root)?
+        .map(|config| {
+            (
+                config.embedding_provider.trim().to_lowercase(),
+                config.embedding_model.trim().to_string(),
+            )
+        })
+        .filter(|(provider, model)| !provider.is_empty() && !model.is_empty());
+    let mut coverage = EmbeddingCoverage {
+        model_context_tokens: profile
+            .as_ref()
+            .and_then(|(_, model)| model_context_tokens(model)),
+        ..Default::default()
+    };
+
+    let conn = open_indexing_connection(db_path)?;
+    if let Some(vault_id) = find_vault_id(&conn, workspace_root)? {
+        coverage.indexed_doc_count = count(
+            &conn,
+            "SELECT COUNT(*) FROM doc \
+             WHERE vault_id = ?1 AND last_hash IS NOT NULL AND embeddable = 1",
+            vault_id,
+        )?;
+        if segment_vec_table_exists(&conn)? {
+            count_embedded(&conn, vault_id, profile.as_ref(), &mut coverage)?;
+        }
+    }
+    (coverage.provider, coverage.model) = profile.unzip();
+
+    Ok(coverage)
+}
+
+fn count_embedded(
+    conn: &Connection,
+    vault_id: i64,
+    profile: Option<&(String, String)>,
+    coverage: &mut EmbeddingCoverage,
+) -> Result<()> {
+    // Rows embedded before providers were recorded match on the model alone,
+    // as indexing itself treats them.
+    let (provider, model) = profile.map_or(("", ""), |(provider, model)| (provider, model));
+    let mut stmt = conn
+        .prepare(
+            "SELECT \
+                 (d.last_embedding_provider IS NULL OR d.last_embedding_provider = ?2) \
+                     AND d.last_embedding_model IS ?3, \
+                 d.last_embedded_hash IS NOT d.last_hash \
+             FROM doc d \
+             WHERE d.vault_id = ?1 AND d.last_hash IS NOT NULL AND EXISTS ( \
+                 SELECT 1 FROM segment s JOIN segment_vec v ON v.rowid = s.id \
+                 WHERE s.doc_id = d.id \
+             )",
+        )
+        .context("Failed to prepare embedded doc query")?;
+    let rows = stmt
+        .query_map(params![vault_id, provider, model], |row| {
+            Ok((row.get::<_, bool>(0)?, row.get::<_, bool>(1)?))
+        })
+        .context("Failed to load embedded docs")?;
+    for row in rows {
+        let (current_model, stale) = row.context("Failed to read embedded doc")?;
+        if current_model {
+            coverage.current_model_doc_count += 1;
+        } else {
+            coverage.other_model_doc_count += 1;
+        }
+        if stale {
+            coverage.stale_doc_count += 1;
+        }
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.text, d.chunking_version FROM segment s \
+             JOIN segment_vec v ON v.rowid = s.id \
+             JOIN doc d ON d.id = s.doc_id \
+             WHERE d.vault_id = ?1",
+        )
+        .context("Failed to prepare embedded segment query")?;
+    let texts = stmt
+        .query_map(params![vault_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .context("Failed to load embedded segments")?;
+    for text in texts {
+        let (text, chunking_version) = text.context("Failed to read embedded segment")?;
+        coverage.embedded_segment_count += 1;
+        if coverage
+            .model_context_tokens
+            .is_some_and(|limit| count_tokens(&text, chunking_version) > limit)
+        {
+            coverage.oversized_segment_count += 1;
+        }
+    }
+
+    Ok(())
+}
+
+/// Input window of `model`, matched without its `:tag` or `org/` prefix.
+fn model_context_tokens(model: &str) -> Option<usize> {
+    let name = model.rsplit('/').next().unwrap_or(model);
+    let name = name.split(':').next().unwrap_or(name).to_lowercase();
+    MODEL_CONTEXT_TOKENS
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, tokens)| *tokens)
+}
+
+fn count(conn: &Connection, sql: &str, vault_id: i64) -> Result<usize> {
+    conn.query_row(sql, params![vault_id], |row| row.get::<_, i64>(0))
+        .map(|count| count as usize)
+        .context("Failed to count embedding coverage rows")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::model_context_tokens;
+
+    #[test]
+    fn model_windows_ignore_tags_and_namespaces() {
+        assert_eq!(model_context_tokens("nomic-embed-text:latest"), Some(2048));
+        assert_eq!(model_context_tokens("library/mxbai-embed-large"), Some(512));
+        assert_eq!(model_context_tokens("Snowflake-Arctic-Embed2"), Some(8192));
+        assert_eq!(model_context_tokens("model-a"), None);
+    }
+}
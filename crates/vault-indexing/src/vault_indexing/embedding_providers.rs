@@ -0,0 +1,529 @@
+//! Registry of embedding providers keyed by name.
+//!
+//! Ollama is built in. Any other provider is an HTTP API described by an
+//! [`EmbeddingProviderConfig`] stored in app-storage, so services such as
+//! Cohere, Voyage or Jina only need a config entry, not new code.
+
+use std::{
+    collections::BTreeMap,
+    env,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Result};
+use app_storage::embedding_providers::{
+    list_embedding_providers, EmbeddingProviderConfig, BUILTIN_EMBEDDING_PROVIDERS,
+};
+use serde::Serialize;
+use serde_json::{Map, Value as JsonValue};
+
+use super::embedding::{EmbeddingBackend, EmbeddingClient, OllamaEmbeddingBackend};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(60);
+const MODEL_PLACEHOLDER: &str = "{{model}}";
+const INPUT_PLACEHOLDER: &str = "{{input}}";
+const DIMENSION_PLACEHOLDER: &str = "{{dimension}}";
+
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingProviderRegistry {
+    http_providers: BTreeMap<String, EmbeddingProviderConfig>,
+}
+
+impl EmbeddingProviderRegistry {
+    /// Built-in providers plus the HTTP providers configured in the app database.
+    pub fn load(db_path: &Path) -> Result<Self> {
+        let mut registry = Self::default();
+        for config in list_embedding_providers(db_path)? {
+            registry.register(config);
+        }
+        Ok(registry)
+    }
+
+    pub fn register(&mut self, config: EmbeddingProviderConfig) {
+        self.http_providers
+            .insert(config.name.trim().to_lowercase(), config);
+    }
+
+    /// Every provider name a vault embedding setting can refer to.
+    pub fn provider_names(&self) -> Vec<String> {
+        BUILTIN_EMBEDDING_PROVIDERS
+            .iter()
+            .map(|name| name.to_string())
+            .chain(self.http_providers.keys().cloned())
+            .collect()
+    }
+
+    pub(crate) fn create_backend(&self, provider: &str) -> Result<Box<dyn EmbeddingBackend>> {
+        let name = provider.trim().to_lowercase();
+        match name.as_str() {
+            "ollama" => return Ok(Box::new(OllamaEmbeddingBackend::new()?)),
+            #[cfg(test)]
+            "test" => return Ok(Box::new(super::embedding::TestEmbeddingBackend)),
+            _ => {}
+        }
+
+        let config = self.http_providers.get(&name).ok_or_else(|| {
+            anyhow!(
+                "Unsupported embedding provider '{}'. Configure it as an HTTP embedding provider first.",
+                name
+            )
+        })?;
+        Ok(Box::new(HttpEmbeddingBackend::new(config.clone())?))
+    }
+}
+
+struct HttpEmbeddingBackend {
+    config: EmbeddingProviderConfig,
+    api_key: Option<String>,
+    client: reqwest::blocking::Client,
+}
+
+impl HttpEmbeddingBackend {
+    fn new(config: EmbeddingProviderConfig) -> Result<Self> {
+        let api_key = match config.api_key_ref.as_deref() {
+            Some(variable) => Some(env::var(variable).map_err(|_| {
+                anyhow!(
+                    "Environment variable {} for embedding provider '{}' is not set",
+                    variable,
+                    config.name
+                )
+            })?),
+            None => None,
+        };
+        let client = reqwest::blocking::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .build()
+            .context("Failed to initialize HTTP embedding client")?;
+
+        Ok(Self {
+            config,
+            api_key,
+            client,
+        })
+    }
+}
+
+impl EmbeddingBackend for HttpEmbeddingBackend {
+    fn embed(&self, model: &str, text: &str) -> Result<Vec<f32>> {
+        let body = render_request_template(
+            &self.config.request_template,
+            model,
+            text,
+            self.config.dimension,
+        );
+        let mut request = self.client.post(&self.config.base_url).json(&body);
+        if let Some(api_key) = self.api_key.as_deref() {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().with_context(|| {
+            format!("Failed to reach embedding provider '{}'", self.config.name)
+        })?;
+        let status = response.status();
+        if !status.is_success() {
+            let detail = response.text().unwrap_or_default();
+            return Err(anyhow!(
+                "Embedding provider '{}' returned {}: {}",
+                self.config.name,
+                status,
+                detail.trim()
+            ));
+        }
+
+        let payload = response.json::<JsonValue>().with_context(|| {
+            format!(
+                "Embedding provider '{}' returned invalid JSON",
+                self.config.name
+            )
+        })?;
+        extract_vector(&payload, &self.config.response_path)
+            .with_context(|| format!("Unexpected response from '{}'", self.config.name))
+    }
+
+    /// Lists models from the OpenAI style `models` endpoint next to the
+    /// embeddings endpoint. Providers without one report an error here, which
+    /// does not affect embedding.
+    fn list_models(&self) -> Result<Vec<String>> {
+        let mut request = self.client.get(models_url(&self.config.base_url));
+        if let Some(api_key) = self.api_key.as_deref() {
+            request = request.bearer_auth(api_key);
+        }
+
+        let payload = request
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json::<JsonValue>())
+            .with_context(|| format!("Failed to list models of '{}'", self.config.name))?;
+        Ok(model_names(&payload))
+    }
+
+    fn dimension(&self) -> Option<i32> {
+        self.config.dimension
+    }
+}
+
+/// Result of probing a provider and model without touching the index.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingProviderCheck {
+    pub provider: String,
+    pub model: String,
+    /// Whether the provider answered; with a model, whether it embedded text.
+    pub reachable: bool,
+    /// Round trip of the test embedding, or of the model listing without a model.
+    pub latency_ms: Option<u64>,
+    pub models: Vec<String>,
+    pub dimension: Option<i32>,
+    pub error: Option<String>,
+}
+
+/// Embeds a short test string with `provider` and `model` and lists the
+/// provider's models. Failures are reported in the result, not as errors.
+pub fn check_embedding_provider(
+    db_path: &Path,
+    provider: &str,
+    model: &str,
+) -> Result<EmbeddingProviderCheck> {
+    let registry = EmbeddingProviderRegistry::load(db_path)?;
+    let mut check = EmbeddingProviderCheck {
+        provider: provider.trim().to_lowercase(),
+        model: model.trim().to_string(),
+        ..Default::default()
+    };
+
+    let backend = match registry.create_backend(provider) {
+        Ok(backend) => backend,
+        Err(error) => {
+            check.error = Some(format!("{error:#}"));
+            return Ok(check);
+        }
+    };
+
+    let started = Instant::now();
+    let listed = backend.list_models();
+    let listing_latency = elapsed_ms(started);
+    let listing_error = match listed {
+        Ok(models) => {
+            check.models = models;
+            check.reachable = true;
+            check.latency_ms = Some(listing_latency);
+            None
+        }
+        Err(error) => Some(format!("{error:#}")),
+    };
+
+    if check.model.is_empty() {
+        check.error = listing_error;
+        return Ok(check);
+    }
+
+    let embedded = EmbeddingClient::new(&registry, provider, model).and_then(|embedder| {
+        let started = Instant::now();
+        let embedding = embedder.generate("test")?;
+        Ok((embedding.dim, elapsed_ms(started)))
+    });
+    match embedded {
+        Ok((dimension, latency_ms)) => {
+            check.reachable = true;
+            check.latency_ms = Some(latency_ms);
+            check.dimension = Some(dimension);
+        }
+        Err(error) => {
+            check.reachable = false;
+            check.error = Some(format!("{error:#}"));
+        }
+    }
+
+    Ok(check)
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+/// `https://host/v1/embeddings` becomes `https://host/v1/models`.
+fn models_url(base_url: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    match base_url.rsplit_once('/') {
+        Some((prefix, _)) if prefix.contains("://") && !prefix.ends_with('/') => {
+            format!("{prefix}/models")
+        }
+        _ => format!("{base_url}/models"),
+    }
+}
+
+/// Model ids from `{"data": [{"id": ...}]}` or `{"models": [{"name": ...}]}`.
+fn model_names(payload: &JsonValue) -> Vec<String> {
+    let items = payload
+        .get("data")
+        .or_else(|| payload.get("models"))
+        .unwrap_or(payload);
+
+    items
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    item.as_str()
+                        .or_else(|| item.get("id").and_then(JsonValue::as_str))
+                        .or_else(|| item.get("name").and_then(JsonValue::as_str))
+                })
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fills the placeholders in every string of the template. A value that is
+/// exactly `{{dimension}}` becomes a number, or is dropped when no dimension
+/// is configured.
+fn render_request_template(
+    template: &JsonValue,
+    model: &str,
+    input: &str,
+    dimension: Option<i32>,
+) -> JsonValue {
+    match template {
+        JsonValue::String(value) if value == DIMENSION_PLACEHOLDER => {
+            dimension.map(JsonValue::from).unwrap_or(JsonValue::Null)
+        }
+        JsonValue::String(value) => JsonValue::String(
+            value
+                .replace(MODEL_PLACEHOLDER, model)
+                .replace(INPUT_PLACEHOLDER, input),
+        ),
+        JsonValue::Array(items) => JsonValue::Array(
+            items
+                .iter()
+                .map(|item| render_request_template(item, model, input, dimension))
+                .collect(),
+        ),
+        JsonValue::Object(fields) => JsonValue::Object(
+            fields
+                .iter()
+                .filter(|(_, value)| {
+                    dimension.is_some() || value.as_str() != Some(DIMENSION_PLACEHOLDER)
+                })
+                .map(|(key, value)| {
+                    (
+                        key.clone(),
+                        render_request_template(value, model, input, dimension),
+                    )
+                })
+                .collect::<Map<_, _>>(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Follows a dot separated path such as `data.0.embedding`, where numeric
+/// segments index into arrays.
+fn extract_vector(payload: &JsonValue, response_path: &str) -> Result<Vec<f32>> {
+    let mut current = payload;
+    for segment in response_path
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+    {
+        current = match current {
+            JsonValue::Array(items) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get(index)),
+            JsonValue::Object(fields) => fields.get(segment),
+            _ => None,
+        }
+        .ok_or_else(|| anyhow!("Response has no value at '{}'", response_path))?;
+    }
+
+    let values = current
+        .as_array()
+        .ok_or_else(|| anyhow!("Value at '{}' is not an array", response_path))?;
+    if values.is_empty() {
+        return Err(anyhow!("Value at '{}' is an empty vector", response_path));
+    }
+
+    values
+        .iter()
+        .map(|value| {
+            value
+                .as_f64()
+                .map(|value| value as f32)
+                .ok_or_else(|| anyhow!("Value at '{}' is not a number vector", response_path))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use app_storage::embedding_providers::{default_request_template, EmbeddingProviderConfig};
+    use serde_json::json;
+
+    use super::{
+        check_embedding_provider, extract_vector, model_names, models_url, render_request_template,
+        EmbeddingProviderRegistry,
+    };
+    use crate::vault_indexing::embedding::{resolve_embedding_dimension, EmbeddingClient};
+
+    #[test]
+    fn renders_placeholders_and_drops_unset_dimension() {
+        let template = json!({
+            "model": "{{model}}",
+            "texts": ["{{input}}"],
+            "output_dimension": "{{dimension}}",
+            "truncate": true
+        });
+
+        assert_eq!(
+            render_request_template(&template, "embed-v4", "hello", None),
+            json!({ "model": "embed-v4", "texts": ["hello"], "truncate": true })
+        );
+        assert_eq!(
+            render_request_template(&template, "embed-v4", "hello", Some(256)),
+            json!({
+                "model": "embed-v4",
+                "texts": ["hello"],
+                "output_dimension": 256,
+                "truncate": true
+            })
+        );
+    }
+
+    #[test]
+    fn extracts_vectors_along_the_response_path() {
+        let openai = json!({ "data": [{ "embedding": [0.5, 1] }] });
+        assert_eq!(
+            extract_vector(&openai, "data.0.embedding").unwrap(),
+            vec![0.5, 1.0]
+        );
+
+        let cohere = json!({ "embeddings": { "float": [[2, 3]] } });
+        assert_eq!(
+            extract_vector(&cohere, "embeddings.float.0").unwrap(),
+            vec![2.0, 3.0]
+        );
+
+        assert!(extract_vector(&openai, "data.1.embedding").is_err());
+        assert!(extract_vector(&json!({ "data": [] }), "data").is_err());
+    }
+
+    #[test]
+    fn lists_models_next_to_the_embeddings_endpoint() {
+        assert_eq!(
+            models_url("https://api.openai.com/v1/embeddings"),
+            "https://api.openai.com/v1/models"
+        );
+        assert_eq!(
+            models_url("http://localhost:8080/"),
+            "http://localhost:8080/models"
+        );
+
+        assert_eq!(
+            model_names(&json!({ "data": [{ "id": "text-embedding-3-small" }] })),
+            vec!["text-embedding-3-small"]
+        );
+        assert_eq!(
+            model_names(&json!({ "models": [{ "name": "embed-v4.0" }, "legacy"] })),
+            vec!["embed-v4.0", "legacy"]
+        );
+        assert!(model_names(&json!({ "object": "list" })).is_empty());
+    }
+
+    #[test]
+    fn check_reports_dimension_and_errors_without_failing() {
+        let dir = std::env::temp_dir().join(format!(
+            "mdit-embedding-check-{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let db_path = dir.join("check.sqlite");
+        app_storage::migrations::run_migrations_at(&db_path).unwrap();
+
+        let check = check_embedding_provider(&db_path, "Test", "model-a").unwrap();
+        assert!(check.reachable);
+        assert_eq!(check.provider, "test");
+        assert_eq!(check.dimension, Some(3));
+        assert_eq!(check.models, vec!["test-embedding"]);
+        assert!(check.latency_ms.is_some());
+        assert_eq!(check.error, None);
+
+        let check = check_embedding_provider(&db_path, "cohere", "embed-v4").unwrap();
+        assert!(!check.reachable);
+        assert!(check
+            .error
+            .unwrap()
+            .contains("Unsupported embedding provider"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn unknown_providers_are_rejected() {
+        let registry = EmbeddingProviderRegistry::default();
+        assert!(EmbeddingClient::new(&registry, "cohere", "embed-v4").is_err());
+        assert_eq!(registry.provider_names(), vec!["ollama".to_string()]);
+    }
+
+    #[test]
+    fn configured_http_provider_embeds_through_the_registry() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+        let base_url = format!("http://{}/v1/embeddings", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("request should arrive");
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            while !String::from_utf8_lossy(&request).contains("\"input\"") {
+                let read = stream.read(&mut buffer).expect("request should read");
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            let body = r#"{"data":[{"embedding":[3.0,4.0]}]}"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .expect("response should write");
+            String::from_utf8_lossy(&request).to_string()
+        });
+
+        let mut registry = EmbeddingProviderRegistry::default();
+        registry.register(EmbeddingProviderConfig {
+            name: "Local".to_string(),
+            base_url,
+            api_key_ref: None,
+            request_template: default_request_template(),
+            response_path: "data.0.embedding".to_string(),
+            dimension: Some(2),
+        });
+
+        let embedder = EmbeddingClient::new(&registry, "local", "mini").unwrap();
+        assert_eq!(resolve_embedding_dimension(&embedder).unwrap(), 2);
+        let embedding = embedder.generate("hello").unwrap();
+        assert_eq!(embedding.dim, 2);
+        assert_eq!(
+            embedding.bytes,
+            [0.6f32, 0.8f32]
+                .iter()
+                .flat_map(|value| value.to_le_bytes())
+                .collect::<Vec<_>>()
+        );
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /v1/embeddings"));
+        assert!(request.contains(r#""model":"mini""#));
+    }
+}
@@ -1,19 +1,30 @@
 use std::{
     ffi::OsStr,
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom},
     path::{Component, Path, PathBuf},
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
+use app_storage::unicode::nfc;
 use walkdir::{DirEntry, WalkDir};
 
-/// Convenience holder for absolute + relative path of a Markdown source file.
+use super::{
+    chunking::{hash_content, hash_reader},
+    cloud_files::{is_cloud_placeholder, CLOUD_PLACEHOLDER_SKIP_REASON},
+};
+
+/// Convenience holder for absolute + relative path of an indexed source file,
+/// a Markdown note or a drawing.
 #[derive(Debug)]
 pub(crate) struct MarkdownFile {
     pub(crate) abs_path: PathBuf,
     pub(crate) rel_path: String,
     pub(crate) last_source_size: Option<i64>,
     pub(crate) last_source_mtime_ns: Option<i64>,
+    /// Birth time of the file, where the file system records one.
+    pub(crate) source_created_ns: Option<i64>,
 }
 
 impl MarkdownFile {
@@ -37,6 +48,7 @@ impl MarkdownFile {
             rel_path,
             last_source_size: source_stat.last_source_size,
             last_source_mtime_ns: source_stat.last_source_mtime_ns,
+            source_created_ns: source_stat.source_created_ns,
         }
     }
 }
@@ -45,6 +57,7 @@ impl MarkdownFile {
 pub(crate) struct SourceFileStat {
     pub(crate) last_source_size: Option<i64>,
     pub(crate) last_source_mtime_ns: Option<i64>,
+    pub(crate) source_created_ns: Option<i64>,
 }
 
 impl SourceFileStat {
@@ -56,10 +69,96 @@ impl SourceFileStat {
         Self {
             last_source_size: i64::try_from(metadata.len()).ok(),
             last_source_mtime_ns: metadata.modified().ok().and_then(system_time_to_nanos),
+            source_created_ns: metadata.created().ok().and_then(system_time_to_nanos),
         }
     }
 }
 
+/// Files above this many bytes are indexed by their head only, unless the
+/// vault sets another cap.
+pub(crate) const DEFAULT_MAX_SOURCE_BYTES: u64 = 8 * 1024 * 1024;
+
+/// The text a source file is indexed by and the hash it is tracked by.
+pub(crate) struct IndexedSource {
+    pub(crate) contents: String,
+    /// Hash of the whole file, also when `contents` holds only its head.
+    pub(crate) hash: String,
+    /// The file was larger than the cap and only its head was read.
+    pub(crate) truncated: bool,
+}
+
+/// Reads a note as written, or the text typed into a drawing. A note larger
+/// than `max_bytes` is read up to the last line break within the cap and
+/// hashed by streaming the rest, so an accidental log file in the vault
+/// never has to fit in memory. Cloud placeholders are refused rather than
+/// downloaded.
+pub(crate) fn read_indexed_source(abs_path: &Path, max_bytes: u64) -> Result<IndexedSource> {
+    if is_cloud_placeholder(abs_path) {
+        return Err(anyhow!(CLOUD_PLACEHOLDER_SKIP_REASON));
+    }
+
+    let mut file = File::open(abs_path)
+        .with_context(|| format!("Failed to read file {}", abs_path.display()))?;
+    let size = file
+        .metadata()
+        .with_context(|| format!("Failed to read file {}", abs_path.display()))?
+        .len();
+
+    if size <= max_bytes {
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .with_context(|| format!("Failed to read file {}", abs_path.display()))?;
+        if note::is_drawing_path(abs_path) {
+            contents = super::drawings::extract_drawing_text(&contents)
+                .with_context(|| format!("Failed to read drawing {}", abs_path.display()))?;
+        }
+        return Ok(IndexedSource {
+            hash: hash_content(&contents),
+            contents,
+            truncated: false,
+        });
+    }
+
+    // A drawing's text can only be found by parsing all of its JSON.
+    if note::is_drawing_path(abs_path) {
+        return Err(io::Error::new(
+            io::ErrorKind::FileTooLarge,
+            format!(
+                "Drawing {} is larger than the {max_bytes} byte indexing cap",
+                abs_path.display()
+            ),
+        )
+        .into());
+    }
+
+    let mut head = Vec::new();
+    (&mut file)
+        .take(max_bytes)
+        .read_to_end(&mut head)
+        .with_context(|| format!("Failed to read file {}", abs_path.display()))?;
+    if let Some(line_end) = head.iter().rposition(|byte| *byte == b'\n') {
+        head.truncate(line_end + 1);
+    }
+    let contents = match String::from_utf8(head) {
+        Ok(contents) => contents,
+        Err(error) => {
+            let valid_up_to = error.utf8_error().valid_up_to();
+            let mut head = error.into_bytes();
+            head.truncate(valid_up_to);
+            String::from_utf8(head).expect("prefix is valid UTF-8")
+        }
+    };
+
+    file.seek(SeekFrom::Start(0))
+        .and_then(|_| hash_reader(&mut file))
+        .map(|hash| IndexedSource {
+            contents,
+            hash,
+            truncated: true,
+        })
+        .with_context(|| format!("Failed to hash file {}", abs_path.display()))
+}
+
 pub(crate) fn collect_markdown_files(workspace_root: &Path) -> Result<Vec<MarkdownFile>> {
     // Walk the tree lazily, skipping hidden dot-paths entirely.
     let walker = WalkDir::new(workspace_root)
@@ -74,7 +173,7 @@ pub(crate) fn collect_markdown_files(workspace_root: &Path) -> Result<Vec<Markdo
             continue;
         }
 
-        if !is_markdown(entry.path()) {
+        if !note::is_indexed_path(entry.path()) {
             continue;
         }
 
@@ -87,6 +186,40 @@ pub(crate) fn collect_markdown_files(workspace_root: &Path) -> Result<Vec<Markdo
     Ok(files)
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct WorkspaceEntry {
+    pub(crate) abs_path: PathBuf,
+    pub(crate) rel_path: String,
+    pub(crate) is_dir: bool,
+}
+
+/// Every visible file and folder below `workspace_root`.
+pub(crate) fn collect_workspace_entries(workspace_root: &Path) -> Result<Vec<WorkspaceEntry>> {
+    let walker = WalkDir::new(workspace_root)
+        .min_depth(1)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| should_descend(entry, workspace_root));
+
+    let mut entries = Vec::new();
+    for entry in walker {
+        let entry = entry.with_context(|| "Failed to traverse workspace")?;
+        let rel = entry.path().strip_prefix(workspace_root).with_context(|| {
+            format!(
+                "Failed to compute relative path for {}",
+                entry.path().display()
+            )
+        })?;
+        entries.push(WorkspaceEntry {
+            abs_path: entry.path().to_path_buf(),
+            rel_path: normalize_rel_path(rel),
+            is_dir: entry.file_type().is_dir(),
+        });
+    }
+
+    Ok(entries)
+}
+
 fn should_descend(entry: &DirEntry, workspace_root: &Path) -> bool {
     if entry.path() == workspace_root {
         return true;
@@ -101,7 +234,7 @@ fn is_hidden_workspace_path(path: &Path, workspace_root: &Path) -> bool {
         .is_some_and(|rel| rel.components().any(is_hidden_component))
 }
 
-fn is_hidden_component(component: Component<'_>) -> bool {
+pub(crate) fn is_hidden_component(component: Component<'_>) -> bool {
     matches!(component, Component::Normal(value) if is_hidden_name(value))
 }
 
@@ -110,15 +243,42 @@ fn is_hidden_name(value: &OsStr) -> bool {
     value.starts_with('.') && value != "." && value != ".."
 }
 
-fn is_markdown(path: &Path) -> bool {
-    matches!(path.extension().and_then(OsStr::to_str), Some(ext) if ext.eq_ignore_ascii_case("md"))
+/// The key a note is stored under: forward slashes, Unicode NFC.
+pub(crate) fn normalize_rel_path(path: &Path) -> String {
+    nfc(&path.to_string_lossy()).replace('\\', "/")
 }
 
-pub(crate) fn normalize_rel_path(path: &Path) -> String {
-    path.to_string_lossy().replace('\\', "/")
+/// Where the file stored under the key `rel_path` lives on disk. Keys are
+/// NFC while names on disk may be decomposed, so a segment missing as written
+/// is matched against the names in its folder. Falls back to the plain join
+/// when nothing matches, e.g. for files that were deleted.
+pub(crate) fn disk_path(workspace_root: &Path, rel_path: &str) -> PathBuf {
+    let direct = workspace_root.join(rel_path);
+    if direct.exists() {
+        return direct;
+    }
+
+    let mut resolved = workspace_root.to_path_buf();
+    for segment in rel_path.split('/').filter(|segment| !segment.is_empty()) {
+        let candidate = resolved.join(segment);
+        if candidate.exists() {
+            resolved = candidate;
+            continue;
+        }
+        let matched = fs::read_dir(&resolved).ok().and_then(|entries| {
+            entries
+                .flatten()
+                .find(|entry| nfc(&entry.file_name().to_string_lossy()) == nfc(segment))
+        });
+        match matched {
+            Some(entry) => resolved = entry.path(),
+            None => return direct,
+        }
+    }
+    resolved
 }
 
-fn system_time_to_nanos(time: SystemTime) -> Option<i64> {
+pub(crate) fn system_time_to_nanos(time: SystemTime) -> Option<i64> {
     time.duration_since(UNIX_EPOCH)
         .ok()
         .and_then(|duration| i64::try_from(duration.as_nanos()).ok())
@@ -126,7 +286,7 @@ fn system_time_to_nanos(time: SystemTime) -> Option<i64> {
 
 #[cfg(test)]
 mod tests {
-    use super::collect_markdown_files;
+    use super::{collect_markdown_files, disk_path};
     use std::{
         path::{Path, PathBuf},
         time::{SystemTime, UNIX_EPOCH},
@@ -172,4 +332,23 @@ mod tests {
 
         let _ = std::fs::remove_dir_all(&root);
     }
+
+    #[test]
+    fn disk_path_finds_decomposed_names_from_composed_keys() {
+        let root = temp_workspace();
+        let on_disk = root.join("Cafe\u{301}/Re\u{301}sume\u{301}.md");
+        write_file(&on_disk);
+
+        let files = collect_markdown_files(&root).expect("markdown files should be collected");
+        assert_eq!(files[0].rel_path, "Café/Résumé.md");
+        assert_eq!(files[0].abs_path, on_disk);
+
+        assert_eq!(disk_path(&root, "Café/Résumé.md"), on_disk);
+        assert_eq!(
+            disk_path(&root, "Café/missing.md"),
+            root.join("Café/missing.md")
+        );
+
+        let _ = std::fs::remove_dir_all(root);
+    }
 }
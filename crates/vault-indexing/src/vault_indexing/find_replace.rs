@@ -0,0 +1,542 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+mod journal;
+
+use super::{
+    chunking::hash_content,
+    files::{collect_markdown_files, disk_path, MarkdownFile},
+    open_indexing_connection,
+};
+use journal::{JournalEdit, JournalFile};
+
+const DEFAULT_MAX_FIND_RESULTS: usize = 1000;
+const DEFAULT_MAX_NOTE_RESULTS: usize = 10_000;
+// Undo only ever goes back one replace at a time, so older entries are
+// pruned instead of growing the database forever.
+const MAX_JOURNAL_ENTRIES: i64 = 20;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FindOptions {
+    /// Treat the pattern as a regular expression instead of literal text.
+    /// Replacements may then refer to groups as `$1` or `${name}`.
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// Glob over vault-relative paths, such as `projects/**/*.md`. A path
+    /// without wildcards matches that note or everything in that folder.
+    pub path_filter: Option<String>,
+    /// Most matches a find returns, 1000 by default across the vault and
+    /// 10000 within one note. Replacing is not limited.
+    pub max_results: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultFindResult {
+    pub matches: Vec<VaultMatch>,
+    /// Whether matches past `max_results` were left out.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultMatch {
+    pub rel_path: String,
+    /// Byte range of the match in the source file.
+    pub start: usize,
+    pub end: usize,
+    /// Zero-based line of `start`.
+    pub line: usize,
+    /// The lines the match spans.
+    pub context: String,
+    /// Byte offset of `context` in the source file.
+    pub context_start: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteSearchResult {
+    pub matches: Vec<NoteMatch>,
+    /// Whether matches past `max_results` were left out.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteMatch {
+    /// Byte range of the match in the note file.
+    pub start: usize,
+    pub end: usize,
+    /// Zero-based line of `start`.
+    pub line: usize,
+    /// Zero-based column of `start` in UTF-16 code units, the way the
+    /// editor indexes lines.
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultReplaceResult {
+    /// Undo journal entry of this replace; `None` when no note changed.
+    pub journal_id: Option<i64>,
+    pub files: Vec<ReplacedFile>,
+    /// Notes that changed on disk while the replace ran, left untouched.
+    pub skipped: Vec<String>,
+    /// Locked notes with matches, left untouched.
+    pub locked: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacedFile {
+    pub rel_path: String,
+    pub replacements: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoReplaceResult {
+    /// Journal entry that was undone; `None` when there was nothing to undo.
+    pub journal_id: Option<i64>,
+    /// Notes put back as they were before the replace.
+    pub restored: Vec<String>,
+    /// Notes edited or locked since the replace, left as they are.
+    pub skipped: Vec<String>,
+}
+
+/// Every match of `pattern` in the vault's notes, in path order.
+pub fn find_in_vault(
+    workspace_root: &Path,
+    pattern: &str,
+    options: &FindOptions,
+) -> Result<VaultFindResult> {
+    let matcher = build_matcher(pattern, options)?;
+    let max_results = options.max_results.unwrap_or(DEFAULT_MAX_FIND_RESULTS);
+    let mut result = VaultFindResult {
+        matches: Vec::new(),
+        truncated: false,
+    };
+
+    for file in candidate_files(workspace_root, options)? {
+        let Ok(contents) = fs::read_to_string(&file.abs_path) else {
+            continue;
+        };
+        let mut lines = LineCounter::default();
+        for found in matcher
+            .find_iter(&contents)
+            .filter(|found| !found.is_empty())
+        {
+            if result.matches.len() == max_results {
+                result.truncated = true;
+                return Ok(result);
+            }
+            let (context_start, context_end) = line_bounds(&contents, found.start(), found.end());
+            result.matches.push(VaultMatch {
+                rel_path: file.rel_path.clone(),
+                start: found.start(),
+                end: found.end(),
+                line: lines.line_of(&contents, found.start()),
+                context: contents[context_start..context_end].to_string(),
+                context_start,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+/// Every match of `pattern` in one note, so a find bar can step through a
+/// large note without loading it. `options.path_filter` is ignored.
+pub fn search_in_note(
+    note_path: &Path,
+    pattern: &str,
+    options: &FindOptions,
+) -> Result<NoteSearchResult> {
+    let matcher = build_matcher(pattern, options)?;
+    let max_results = options.max_results.unwrap_or(DEFAULT_MAX_NOTE_RESULTS);
+    let contents = fs::read_to_string(note_path)
+        .with_context(|| format!("Failed to read note {}", note_path.display()))?;
+    let mut result = NoteSearchResult {
+        matches: Vec::new(),
+        truncated: false,
+    };
+
+    let mut lines = LineCounter::default();
+    for found in matcher
+        .find_iter(&contents)
+        .filter(|found| !found.is_empty())
+    {
+        if result.matches.len() == max_results {
+            result.truncated = true;
+            break;
+        }
+        let line = lines.line_of(&contents, found.start());
+        result.matches.push(NoteMatch {
+            start: found.start(),
+            end: found.end(),
+            line,
+            column: lines.column(&contents),
+        });
+    }
+
+    Ok(result)
+}
+
+/// Replaces every match of `pattern` in the vault's notes. Each note is
+/// written in one step through a temporary file, and the edits are recorded
+/// so [`undo_last_replace`] can put them back.
+pub fn replace_in_vault(
+    workspace_root: &Path,
+    db_path: &Path,
+    pattern: &str,
+    replacement: &str,
+    options: &FindOptions,
+) -> Result<VaultReplaceResult> {
+    let matcher = build_matcher(pattern, options)?;
+    let mut result = VaultReplaceResult {
+        journal_id: None,
+        files: Vec::new(),
+        skipped: Vec::new(),
+        locked: Vec::new(),
+    };
+    let mut journal_files = Vec::new();
+
+    for file in candidate_files(workspace_root, options)? {
+        let Ok(contents) = fs::read_to_string(&file.abs_path) else {
+            continue;
+        };
+        let Some((updated, edits)) = replace_contents(&matcher, &contents, replacement, options)
+        else {
+            continue;
+        };
+        if note::is_locked_source(&contents) {
+            result.locked.push(file.rel_path);
+            continue;
+        }
+
+        let old_hash = hash_content(&contents);
+        if !write_if_unchanged(&file.abs_path, &old_hash, &updated)? {
+            result.skipped.push(file.rel_path);
+            continue;
+        }
+        result.files.push(ReplacedFile {
+            rel_path: file.rel_path.clone(),
+            replacements: edits.len(),
+        });
+        journal_files.push(JournalFile {
+            rel_path: file.rel_path,
+            old_hash,
+            new_hash: hash_content(&updated),
+            edits,
+        });
+    }
+
+    if !journal_files.is_empty() {
+        let mut conn = open_indexing_connection(db_path)?;
+        let vault_id = app_storage::vault::ensure_workspace_exists(&conn, workspace_root)?;
+        let journal_id = journal::record_journal(
+            &mut conn,
+            vault_id,
+            pattern,
+            replacement,
+            &journal_files,
+            MAX_JOURNAL_ENTRIES,
+        )?;
+        result.journal_id = Some(journal_id);
+    }
+
+    Ok(result)
+}
+
+/// Reverts the vault's most recent replace. Notes edited since then are left
+/// alone and reported as skipped. The journal entry is dropped either way.
+pub fn undo_last_replace(workspace_root: &Path, db_path: &Path) -> Result<UndoReplaceResult> {
+    let conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = app_storage::vault::find_workspace_id(&conn, workspace_root)? else {
+        return Ok(UndoReplaceResult::default());
+    };
+    let Some((journal_id, files)) = journal::last_journal(&conn, vault_id)? else {
+        return Ok(UndoReplaceResult::default());
+    };
+
+    let mut result = UndoReplaceResult {
+        journal_id: Some(journal_id),
+        ..UndoReplaceResult::default()
+    };
+    for file in files {
+        let abs_path = disk_path(workspace_root, &file.rel_path);
+        let restored = fs::read_to_string(&abs_path)
+            .ok()
+            .filter(|contents| hash_content(contents) == file.new_hash)
+            .filter(|contents| !note::is_locked_source(contents))
+            .and_then(|contents| revert_edits(&contents, &file.edits))
+            .filter(|original| hash_content(original) == file.old_hash);
+        match restored {
+            Some(original) if write_if_unchanged(&abs_path, &file.new_hash, &original)? => {
+                result.restored.push(file.rel_path);
+            }
+            _ => result.skipped.push(file.rel_path),
+        }
+    }
+
+    journal::delete_journal(&conn, journal_id)?;
+    Ok(result)
+}
+
+fn build_matcher(pattern: &str, options: &FindOptions) -> Result<Regex> {
+    if pattern.is_empty() {
+        return Err(anyhow!("Search pattern must not be empty"));
+    }
+
+    let source = if options.regex {
+        pattern.to_string()
+    } else {
+        regex::escape(pattern)
+    };
+    let source = if options.whole_word {
+        format!(r"\b(?:{source})\b")
+    } else {
+        source
+    };
+
+    RegexBuilder::new(&source)
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .build()
+        .with_context(|| format!("Invalid search pattern: {pattern}"))
+}
+
+fn candidate_files(workspace_root: &Path, options: &FindOptions) -> Result<Vec<MarkdownFile>> {
+    let path_filter = options
+        .path_filter
+        .as_deref()
+        .map(str::trim)
+        .filter(|filter| !filter.is_empty())
+        .map(path_filter_regex)
+        .transpose()?;
+
+    // Drawings are indexed too, but their text lives inside JSON.
+    let mut files = collect_markdown_files(workspace_root)?
+        .into_iter()
+        .filter(|file| note::is_note_path(&file.rel_path))
+        .filter(|file| {
+            path_filter
+                .as_ref()
+                .is_none_or(|filter| filter.is_match(&file.rel_path))
+        })
+        .collect::<Vec<_>>();
+    files.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    Ok(files)
+}
+
+/// `*` and `?` stay within one path segment and `**` spans any number of
+/// them. A filter without wildcards matches a note or a folder's contents.
+fn path_filter_regex(filter: &str) -> Result<Regex> {
+    let filter = filter.replace('\\', "/");
+    let filter = filter.trim_matches('/');
+    if !filter.contains(['*', '?']) {
+        return Regex::new(&format!("^{}(?:/.*)?$", regex::escape(filter)))
+            .context("Invalid path filter");
+    }
+
+    let mut source = String::from("^");
+    let mut chars = filter.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    source.push_str("(?:.*/)?");
+                } else {
+                    source.push_str(".*");
+                }
+            }
+            '*' => source.push_str("[^/]*"),
+            '?' => source.push_str("[^/]"),
+            _ => source.push_str(&regex::escape(&ch.to_string())),
+        }
+    }
+    source.push('$');
+
+    Regex::new(&source).with_context(|| format!("Invalid path filter: {filter}"))
+}
+
+/// The replaced contents and the edits made, with offsets into the new
+/// contents. `None` when nothing changed.
+fn replace_contents(
+    matcher: &Regex,
+    contents: &str,
+    replacement: &str,
+    options: &FindOptions,
+) -> Option<(String, Vec<JournalEdit>)> {
+    let mut updated = String::with_capacity(contents.len());
+    let mut edits = Vec::new();
+    let mut cursor = 0usize;
+
+    for captures in matcher.captures_iter(contents) {
+        let Some(found) = captures.get(0).filter(|found| !found.is_empty()) else {
+            continue;
+        };
+        let mut replaced = String::new();
+        if options.regex {
+            captures.expand(replacement, &mut replaced);
+        } else {
+            replaced.push_str(replacement);
+        }
+        if replaced == found.as_str() {
+            continue;
+        }
+
+        updated.push_str(&contents[cursor..found.start()]);
+        edits.push(JournalEdit {
+            start: updated.len(),
+            old_text: found.as_str().to_string(),
+            new_text: replaced.clone(),
+        });
+        updated.push_str(&replaced);
+        cursor = found.end();
+    }
+
+    if edits.is_empty() {
+        return None;
+    }
+    updated.push_str(&contents[cursor..]);
+    Some((updated, edits))
+}
+
+fn revert_edits(contents: &str, edits: &[JournalEdit]) -> Option<String> {
+    let mut reverted = contents.to_string();
+    for edit in edits.iter().rev() {
+        let end = edit.start + edit.new_text.len();
+        if reverted.get(edit.start..end) != Some(edit.new_text.as_str()) {
+            return None;
+        }
+        reverted.replace_range(edit.start..end, &edit.old_text);
+    }
+    Some(reverted)
+}
+
+/// Writes `contents` over `path` through a temporary file in the same folder,
+/// unless the file no longer hashes to `expected_hash`. Returns whether it
+/// wrote.
+fn write_if_unchanged(path: &Path, expected_hash: &str, contents: &str) -> Result<bool> {
+    let current = fs::read_to_string(path).ok();
+    if current.as_deref().map(hash_content).as_deref() != Some(expected_hash) {
+        return Ok(false);
+    }
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid note path {}", path.display()))?;
+    // Dot-prefixed so the watcher and indexer ignore it.
+    let temp_path = path.with_file_name(format!(".{}.mdit-replace", file_name.to_string_lossy()));
+    fs::write(&temp_path, contents)
+        .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    if let Ok(metadata) = fs::metadata(path) {
+        let _ = fs::set_permissions(&temp_path, metadata.permissions());
+    }
+    if let Err(error) = fs::rename(&temp_path, path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(error).with_context(|| format!("Failed to replace {}", path.display()));
+    }
+
+    Ok(true)
+}
+
+/// Start and end of the lines spanning `start..end`, without line breaks.
+fn line_bounds(contents: &str, start: usize, end: usize) -> (usize, usize) {
+    let line_start = contents[..start].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = contents[end..]
+        .find('\n')
+        .map_or(contents.len(), |index| end + index);
+    let line_end = if contents[..line_end].ends_with('\r') {
+        line_end - 1
+    } else {
+        line_end
+    };
+    (line_start, line_end.max(line_start))
+}
+
+/// Counts lines incrementally, since matches come in order.
+#[derive(Default)]
+struct LineCounter {
+    offset: usize,
+    line: usize,
+    line_start: usize,
+}
+
+impl LineCounter {
+    fn line_of(&mut self, contents: &str, offset: usize) -> usize {
+        let skipped = &contents[self.offset..offset];
+        if let Some(last_break) = skipped.rfind('\n') {
+            self.line += skipped.matches('\n').count();
+            self.line_start = self.offset + last_break + 1;
+        }
+        self.offset = offset;
+        self.line
+    }
+
+    /// UTF-16 column of the offset last passed to [`LineCounter::line_of`].
+    fn column(&self, contents: &str) -> usize {
+        contents[self.line_start..self.offset]
+            .encode_utf16()
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_matcher, path_filter_regex, replace_contents, revert_edits, FindOptions};
+
+    #[test]
+    fn path_filters_match_globs_and_folders() {
+        let cases = [
+            ("projects", "projects/a.md", true),
+            ("projects/", "projects/deep/b.md", true),
+            ("projects", "projects-old/a.md", false),
+            ("projects/*.md", "projects/a.md", true),
+            ("projects/*.md", "projects/deep/b.md", false),
+            ("projects/**/*.md", "projects/a.md", true),
+            ("projects/**/*.md", "projects/deep/b.md", true),
+            ("**/todo?.md", "x/y/todo1.md", true),
+        ];
+        for (filter, path, expected) in cases {
+            let regex = path_filter_regex(filter).expect("valid filter");
+            assert_eq!(regex.is_match(path), expected, "{filter} vs {path}");
+        }
+    }
+
+    #[test]
+    fn regex_replacements_expand_groups_and_revert_exactly() {
+        let options = FindOptions {
+            regex: true,
+            whole_word: true,
+            ..FindOptions::default()
+        };
+        let matcher = build_matcher(r"(\w+)@(\w+)", &options).expect("valid regex");
+        let contents = "Mail jane@home and JOE@work, not me@@x.";
+
+        let (updated, edits) =
+            replace_contents(&matcher, contents, "$2:$1", &options).expect("replaced");
+
+        assert_eq!(updated, "Mail home:jane and work:JOE, not me@@x.");
+        assert_eq!(edits.len(), 2);
+        assert_eq!(revert_edits(&updated, &edits).as_deref(), Some(contents));
+    }
+
+    #[test]
+    fn literal_patterns_are_escaped_and_dollars_kept() {
+        let options = FindOptions::default();
+        let matcher = build_matcher("a.b", &options).expect("valid pattern");
+
+        let (updated, _) = replace_contents(&matcher, "A.B axb", "$1", &options).expect("replaced");
+
+        assert_eq!(updated, "$1 axb");
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// One replaced span. `start` is the byte offset in the replaced contents,
+/// so undo can check `new_text` is still there before restoring `old_text`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct JournalEdit {
+    pub(super) start: usize,
+    pub(super) old_text: String,
+    pub(super) new_text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct JournalFile {
+    pub(super) rel_path: String,
+    pub(super) old_hash: String,
+    pub(super) new_hash: String,
+    pub(super) edits: Vec<JournalEdit>,
+}
+
+/// Records a replace and drops all but the newest `keep` entries of the
+/// vault. Returns the new entry's id.
+pub(super) fn record_journal(
+    conn: &mut Connection,
+    vault_id: i64,
+    pattern: &str,
+    replacement: &str,
+    files: &[JournalFile],
+    keep: i64,
+) -> Result<i64> {
+    let tx = conn
+        .transaction()
+        .context("Failed to start replace journal transaction")?;
+
+    tx.execute(
+        "INSERT INTO replace_journal (vault_id, pattern, replacement) VALUES (?1, ?2, ?3)",
+        params![vault_id, pattern, replacement],
+    )
+    .context("Failed to insert replace journal entry")?;
+    let journal_id = tx.last_insert_rowid();
+
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO replace_journal_file \
+                 (journal_id, rel_path, old_hash, new_hash, edits) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+            )
+            .context("Failed to prepare replace journal file insert")?;
+        for file in files {
+            let edits = serde_json::to_string(&file.edits)
+                .context("Failed to serialize replace journal edits")?;
+            stmt.execute(params![
+                journal_id,
+                file.rel_path,
+                file.old_hash,
+                file.new_hash,
+                edits
+            ])
+            .with_context(|| format!("Failed to record replace of {}", file.rel_path))?;
+        }
+    }
+
+    tx.execute(
+        "DELETE FROM replace_journal
+         WHERE vault_id = ?1
+           AND id NOT IN (
+             SELECT id FROM replace_journal
+             WHERE vault_id = ?1
+             ORDER BY id DESC
+             LIMIT ?2
+           )",
+        params![vault_id, keep],
+    )
+    .context("Failed to prune replace journal")?;
+
+    tx.commit()
+        .context("Failed to commit replace journal transaction")?;
+    Ok(journal_id)
+}
+
+/// The vault's newest journal entry and its files.
+pub(super) fn last_journal(
+    conn: &Connection,
+    vault_id: i64,
+) -> Result<Option<(i64, Vec<JournalFile>)>> {
+    let Some(journal_id) = conn
+        .query_row(
+            "SELECT id FROM replace_journal WHERE vault_id = ?1 ORDER BY id DESC LIMIT 1",
+            params![vault_id],
+            |row| row.get::<_, i64>(0),
+        )
+        .optional()
+        .context("Failed to load last replace journal entry")?
+    else {
+        return Ok(None);
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT rel_path, old_hash, new_hash, edits FROM replace_journal_file
+             WHERE journal_id = ?1
+             ORDER BY rel_path",
+        )
+        .context("Failed to prepare replace journal file query")?;
+    let rows = stmt
+        .query_map(params![journal_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .context("Failed to load replace journal files")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read replace journal file rows")?;
+
+    let files = rows
+        .into_iter()
+        .map(|(rel_path, old_hash, new_hash, edits)| {
+            let edits = serde_json::from_str(&edits)
+                .with_context(|| format!("Invalid replace journal edits for {rel_path}"))?;
+            Ok(JournalFile {
+                rel_path,
+                old_hash,
+                new_hash,
+                edits,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Some((journal_id, files)))
+}
+
+pub(super) fn delete_journal(conn: &Connection, journal_id: i64) -> Result<()> {
+    conn.execute(
+        "DELETE FROM replace_journal WHERE id = ?1",
+        params![journal_id],
+    )
+    .with_context(|| format!("Failed to delete replace journal entry {journal_id}"))?;
+    Ok(())
+}
@@ -0,0 +1,151 @@
+//! Per-vault choice of FTS5 tokenizer.
+//!
+//! `doc_fts` indexes every vault with the default `unicode61` tokenizer. A
+//! vault that picks another one in its `searchTokenizer` setting gets a table
+//! of its own, `doc_fts_vault_<id>`, kept in step with `doc` by triggers that
+//! only fire for that vault's rows. Keyword search reads whichever table was
+//! last built, recorded in `vault_fts_tokenizer`, so a changed setting takes
+//! effect once the table has been rebuilt.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use app_storage::vault_settings::{load_vault_setting, VaultSettingKey};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::{find_vault_id, open_indexing_connection, vault_lock::acquire_vault_lock};
+
+pub(crate) const SHARED_FTS_TABLE: &str = "doc_fts";
+const DEFAULT_TOKENIZER: &str = "unicode61";
+
+/// The FTS5 table holding `vault_id`'s notes.
+pub(crate) fn vault_fts_table(conn: &Connection, vault_id: i64) -> Result<String> {
+    Ok(if built_tokenizer(conn, vault_id)? == DEFAULT_TOKENIZER {
+        SHARED_FTS_TABLE.to_string()
+    } else {
+        vault_table_name(vault_id)
+    })
+}
+
+/// The FTS5 `tokenize` argument the vault's table was last built with.
+pub(crate) fn built_tokenizer(conn: &Connection, vault_id: i64) -> Result<String> {
+    Ok(conn
+        .query_row(
+            "SELECT tokenizer FROM vault_fts_tokenizer WHERE vault_id = ?1",
+            params![vault_id],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .context("Failed to load vault tokenizer")?
+        .unwrap_or_else(|| DEFAULT_TOKENIZER.to_string()))
+}
+
+/// Rebuilds the vault's full-text index if its tokenizer setting changed
+/// since it was built. Returns whether it was rebuilt.
+pub fn apply_fts_tokenizer(workspace_root: &Path, db_path: &Path) -> Result<bool> {
+    let _vault_lock = acquire_vault_lock(workspace_root)?;
+    let mut conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(false);
+    };
+    sync_fts_tokenizer(&mut conn, vault_id)
+}
+
+/// Same as [`apply_fts_tokenizer`], for callers already holding the vault
+/// lock.
+pub(crate) fn sync_fts_tokenizer(conn: &mut Connection, vault_id: i64) -> Result<bool> {
+    let desired = configured_tokenizer(conn, vault_id)?;
+    if built_tokenizer(conn, vault_id)? == desired {
+        return Ok(false);
+    }
+
+    let table = vault_table_name(vault_id);
+    let tx = conn
+        .transaction()
+        .context("Failed to start tokenizer rebuild")?;
+    tx.execute_batch(&format!(
+        "DROP TRIGGER IF EXISTS {table}_ai; \
+         DROP TRIGGER IF EXISTS {table}_ad; \
+         DROP TRIGGER IF EXISTS {table}_au; \
+         DROP TABLE IF EXISTS {table};"
+    ))
+    .with_context(|| format!("Failed to drop {table}"))?;
+
+    if desired != DEFAULT_TOKENIZER {
+        let tokenize = desired.replace('"', "\"\"");
+        tx.execute_batch(&format!(
+            "CREATE VIRTUAL TABLE {table} USING fts5( \
+                 content, content='doc', content_rowid='id', tokenize=\"{tokenize}\" \
+             ); \
+             CREATE TRIGGER {table}_ai AFTER INSERT ON doc \
+             WHEN new.vault_id = {vault_id} AND new.searchable = 1 BEGIN \
+                 INSERT INTO {table}(rowid, content) VALUES (new.id, new.content); \
+             END; \
+             CREATE TRIGGER {table}_ad AFTER DELETE ON doc \
+             WHEN old.vault_id = {vault_id} AND old.searchable = 1 BEGIN \
+                 INSERT INTO {table}({table}, rowid, content) VALUES ('delete', old.id, old.content); \
+             END; \
+             CREATE TRIGGER {table}_au AFTER UPDATE OF content, searchable ON doc \
+             WHEN old.vault_id = {vault_id} BEGIN \
+                 INSERT INTO {table}({table}, rowid, content) \
+                 SELECT 'delete', old.id, old.content WHERE old.searchable = 1; \
+                 INSERT INTO {table}(rowid, content) \
+                 SELECT new.id, new.content WHERE new.searchable = 1; \
+             END;"
+        ))
+        .with_context(|| format!("Failed to create {table} with tokenizer {desired}"))?;
+        // A 'rebuild' would read every vault's rows from `doc`, so only this
+        // vault's searchable notes are inserted.
+        tx.execute(
+            &format!(
+                "INSERT INTO {table}(rowid, content) \
+                 SELECT id, content FROM doc WHERE vault_id = ?1 AND searchable = 1"
+            ),
+            params![vault_id],
+        )
+        .with_context(|| format!("Failed to fill {table}"))?;
+    }
+
+    tx.execute(
+        "INSERT INTO vault_fts_tokenizer (vault_id, tokenizer) VALUES (?1, ?2) \
+         ON CONFLICT(vault_id) DO UPDATE SET tokenizer = excluded.tokenizer",
+        params![vault_id, desired],
+    )
+    .context("Failed to record vault tokenizer")?;
+    tx.commit().context("Failed to commit tokenizer rebuild")?;
+
+    tracing::info!(vault_id, tokenizer = %desired, "rebuilt full-text index");
+    Ok(true)
+}
+
+/// The FTS5 `tokenize` argument the vault's setting asks for.
+fn configured_tokenizer(conn: &Connection, vault_id: i64) -> Result<String> {
+    let setting = load_vault_setting(conn, vault_id, VaultSettingKey::SearchTokenizer)?.value;
+    let token_chars = setting
+        .get("tokenChars")
+        .and_then(|value| value.as_str())
+        .unwrap_or_default();
+    let token_chars = if token_chars.is_empty() {
+        String::new()
+    } else {
+        format!(" tokenchars '{}'", token_chars.replace('\'', "''"))
+    };
+
+    Ok(
+        match setting
+            .get("tokenizer")
+            .and_then(|value| value.as_str())
+            .unwrap_or(DEFAULT_TOKENIZER)
+        {
+            // Trigram takes no word characters; it matches any substring of
+            // three or more characters, which also covers CJK text.
+            "trigram" => "trigram".to_string(),
+            "porter" => format!("porter unicode61{token_chars}"),
+            _ => format!("{DEFAULT_TOKENIZER}{token_chars}"),
+        },
+    )
+}
+
+fn vault_table_name(vault_id: i64) -> String {
+    format!("doc_fts_vault_{vault_id}")
+}
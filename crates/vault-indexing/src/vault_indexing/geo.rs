@@ -0,0 +1,291 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+
+use super::{
+    find_vault_id, open_indexing_connection,
+    tags::{frontmatter_payload, lookup_mapping_value, split_frontmatter, strip_hidden_chars},
+};
+
+const LOCATION_KEYS: &[&str] = &["location", "coordinates"];
+const LAT_KEYS: &[&str] = &["lat", "latitude"];
+const LNG_KEYS: &[&str] = &["lng", "lon", "long", "longitude"];
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+/// Most notes a geo query returns, enough for a busy map view.
+const MAX_GEO_RESULTS: usize = 5000;
+
+/// Where a note is about, from its frontmatter.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NoteLocation {
+    pub(crate) lat: f64,
+    pub(crate) lng: f64,
+    /// A place name given next to the coordinates, as in `location: Kyoto`.
+    pub(crate) name: Option<String>,
+}
+
+/// A located note.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeoNote {
+    pub rel_path: String,
+    pub lat: f64,
+    pub lng: f64,
+    pub name: Option<String>,
+    /// Great-circle distance from the query point; absent for box queries.
+    pub distance_km: Option<f64>,
+}
+
+/// A latitude/longitude box. `west` greater than `east` spans the
+/// antimeridian.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct GeoBounds {
+    pub south: f64,
+    pub west: f64,
+    pub north: f64,
+    pub east: f64,
+}
+
+/// Coordinates from `lat`/`lng` (or `latitude`/`longitude`) properties, or a
+/// `location` given as `[lat, lng]`, `"lat, lng"` or `{ lat, lng }`. A
+/// `location` that is plain text names the place.
+pub(crate) fn extract_note_location(source: &str) -> Option<NoteLocation> {
+    if source.trim().is_empty() {
+        return None;
+    }
+
+    let cleaned = strip_hidden_chars(source);
+    let (Some(frontmatter), _) = split_frontmatter(&cleaned) else {
+        return None;
+    };
+    let value = serde_yaml::from_str::<Value>(&frontmatter_payload(frontmatter)).ok()?;
+
+    let location = LOCATION_KEYS
+        .iter()
+        .find_map(|key| lookup_mapping_value(&value, key));
+    let name = match location {
+        Some(Value::String(text)) if parse_coordinate_pair(text).is_none() => {
+            Some(text.trim().to_string()).filter(|name| !name.is_empty())
+        }
+        _ => None,
+    };
+    let (lat, lng) = location
+        .and_then(coordinates)
+        .or_else(|| coordinates(&value))?;
+
+    Some(NoteLocation { lat, lng, name })
+}
+
+/// `[lat, lng]`, `"lat, lng"`, or a mapping with latitude and longitude keys.
+fn coordinates(value: &Value) -> Option<(f64, f64)> {
+    let (lat, lng) = match value {
+        Value::Sequence(items) if items.len() == 2 => (number(&items[0])?, number(&items[1])?),
+        Value::String(text) => parse_coordinate_pair(text)?,
+        Value::Mapping(_) => {
+            let find = |keys: &[&str]| {
+                keys.iter()
+                    .find_map(|key| lookup_mapping_value(value, key))
+                    .and_then(number)
+            };
+            (find(LAT_KEYS)?, find(LNG_KEYS)?)
+        }
+        _ => return None,
+    };
+    ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng)).then_some((lat, lng))
+}
+
+fn parse_coordinate_pair(text: &str) -> Option<(f64, f64)> {
+    let (lat, lng) = text.split_once(',')?;
+    Some((lat.trim().parse().ok()?, lng.trim().parse().ok()?))
+}
+
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+    .filter(|number: &f64| number.is_finite())
+}
+
+/// Located notes within `radius_km` of a point, nearest first.
+pub fn get_notes_near(
+    workspace_root: &Path,
+    db_path: &Path,
+    lat: f64,
+    lng: f64,
+    radius_km: f64,
+) -> Result<Vec<GeoNote>> {
+    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+        return Err(anyhow!("Invalid coordinates: {lat}, {lng}"));
+    }
+    if !radius_km.is_finite() || radius_km <= 0.0 {
+        return Err(anyhow!("Radius must be a positive number of kilometres"));
+    }
+
+    // A box around the circle narrows the rows through the index; the exact
+    // distance check follows.
+    let lat_delta = (radius_km / EARTH_RADIUS_KM).to_degrees();
+    let lng_delta = if lat.abs() + lat_delta >= 90.0 {
+        180.0
+    } else {
+        (lat_delta / lat.to_radians().cos()).min(180.0)
+    };
+    let bounds = GeoBounds {
+        south: (lat - lat_delta).max(-90.0),
+        north: (lat + lat_delta).min(90.0),
+        west: wrap_longitude(lng - lng_delta),
+        east: wrap_longitude(lng + lng_delta),
+    };
+    let bounds = if lng_delta >= 180.0 {
+        GeoBounds {
+            west: -180.0,
+            east: 180.0,
+            ..bounds
+        }
+    } else {
+        bounds
+    };
+
+    let mut notes = load_notes_in_bounds(workspace_root, db_path, bounds, usize::MAX)?
+        .into_iter()
+        .filter_map(|mut note| {
+            let distance = haversine_km(lat, lng, note.lat, note.lng);
+            note.distance_km = Some(distance);
+            (distance <= radius_km).then_some(note)
+        })
+        .collect::<Vec<_>>();
+    notes.sort_by(|left, right| {
+        let distance = |note: &GeoNote| note.distance_km.unwrap_or_default();
+        distance(left).total_cmp(&distance(right))
+    });
+    notes.truncate(MAX_GEO_RESULTS);
+    Ok(notes)
+}
+
+/// Located notes inside `bounds`, for the visible area of a map.
+pub fn get_notes_in_bounds(
+    workspace_root: &Path,
+    db_path: &Path,
+    bounds: GeoBounds,
+) -> Result<Vec<GeoNote>> {
+    if bounds.south > bounds.north {
+        return Err(anyhow!("Bounds end south of where they start"));
+    }
+    load_notes_in_bounds(workspace_root, db_path, bounds, MAX_GEO_RESULTS)
+}
+
+fn load_notes_in_bounds(
+    workspace_root: &Path,
+    db_path: &Path,
+    bounds: GeoBounds,
+    limit: usize,
+) -> Result<Vec<GeoNote>> {
+    let conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+
+    let longitude_clause = if bounds.west <= bounds.east {
+        "l.lng BETWEEN ?4 AND ?5"
+    } else {
+        "(l.lng >= ?4 OR l.lng <= ?5)"
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT d.rel_path, l.lat, l.lng, l.name \
+             FROM doc_location l \
+             JOIN doc d ON d.id = l.doc_id \
+             WHERE d.vault_id = ?1 AND l.lat BETWEEN ?2 AND ?3 AND {longitude_clause} \
+             ORDER BY d.rel_path \
+             LIMIT ?6"
+        ))
+        .context("Failed to prepare location query")?;
+    let rows = stmt
+        .query_map(
+            params![
+                vault_id,
+                bounds.south,
+                bounds.north,
+                bounds.west,
+                bounds.east,
+                i64::try_from(limit).unwrap_or(i64::MAX)
+            ],
+            |row| {
+                Ok(GeoNote {
+                    rel_path: row.get(0)?,
+                    lat: row.get(1)?,
+                    lng: row.get(2)?,
+                    name: row.get(3)?,
+                    distance_km: None,
+                })
+            },
+        )
+        .context("Failed to query located notes")?;
+
+    Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+}
+
+fn wrap_longitude(lng: f64) -> f64 {
+    if lng > 180.0 {
+        lng - 360.0
+    } else if lng < -180.0 {
+        lng + 360.0
+    } else {
+        lng
+    }
+}
+
+fn haversine_km(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let half_lat = (lat2 - lat1) / 2.0;
+    let half_lng = (lng2 - lng1).to_radians() / 2.0;
+    let a = half_lat.sin().powi(2) + lat1.cos() * lat2.cos() * half_lng.sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_note_location, haversine_km, NoteLocation};
+
+    #[test]
+    fn extracts_coordinates_from_pairs_lists_and_separate_keys() {
+        let location = |lines: &[&str]| {
+            extract_note_location(&format!("---\n{}\n---\nBody", lines.join("\n")))
+        };
+
+        assert_eq!(
+            location(&["location: Kyoto", "Lat: 35.0116", "lng: '135.7681'"]),
+            Some(NoteLocation {
+                lat: 35.0116,
+                lng: 135.7681,
+                name: Some("Kyoto".to_string()),
+            })
+        );
+        assert_eq!(
+            location(&["location: [-33.86, 151.21]"]).map(|found| (found.lat, found.lng)),
+            Some((-33.86, 151.21))
+        );
+        assert_eq!(
+            location(&["coordinates: '64.14, -21.94'"])
+                .map(|found| (found.lat, found.lng, found.name)),
+            Some((64.14, -21.94, None))
+        );
+        assert_eq!(
+            location(&["location:", "  latitude: 1.5", "  longitude: 2.5"])
+                .map(|found| (found.lat, found.lng)),
+            Some((1.5, 2.5))
+        );
+        assert_eq!(location(&["location: Kyoto"]), None);
+        assert_eq!(location(&["lat: 95", "lng: 10"]), None);
+    }
+
+    #[test]
+    fn measures_great_circle_distances() {
+        let paris_to_london = haversine_km(48.8566, 2.3522, 51.5074, -0.1278);
+        assert!((paris_to_london - 343.5).abs() < 1.0, "{paris_to_london}");
+        assert!(haversine_km(10.0, 179.9, 10.0, -179.9) < 25.0);
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use super::{get_graph_view_data, GraphEdge, GraphNode};
+
+/// Deltas kept per workspace for clients that fell a few versions behind.
+const DELTA_HISTORY_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphEdgeKey {
+    pub source: String,
+    pub target: String,
+}
+
+impl GraphEdgeKey {
+    fn of(edge: &GraphEdge) -> Self {
+        Self {
+            source: edge.source.clone(),
+            target: edge.target.clone(),
+        }
+    }
+}
+
+/// Changes that turn the graph at `base_version` into the graph at `version`.
+///
+/// Upserted nodes and edges replace any existing entry with the same id or
+/// endpoints. With `reset`, the client drops its graph first and the upserts
+/// hold the whole graph.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphDelta {
+    pub base_version: u64,
+    pub version: u64,
+    pub reset: bool,
+    pub upserted_nodes: Vec<GraphNode>,
+    pub removed_nodes: Vec<String>,
+    pub upserted_edges: Vec<GraphEdge>,
+    pub removed_edges: Vec<GraphEdgeKey>,
+}
+
+impl GraphDelta {
+    pub fn is_empty(&self) -> bool {
+        !self.reset
+            && self.upserted_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.upserted_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+struct TrackedGraph {
+    version: u64,
+    nodes: HashMap<String, GraphNode>,
+    edges: HashMap<GraphEdgeKey, GraphEdge>,
+    history: VecDeque<GraphDelta>,
+}
+
+/// Last graph sent for each workspace, so later calls return what changed
+/// instead of the whole graph.
+///
+/// Versions start from the clock when a workspace is first loaded, so a
+/// version held over from an earlier process does not match by accident.
+#[derive(Default, Clone)]
+pub struct GraphTracker {
+    graphs: Arc<Mutex<HashMap<PathBuf, TrackedGraph>>>,
+}
+
+impl GraphTracker {
+    /// Reloads the graph from the index and records the difference as a new
+    /// version. Returns `None` when nothing changed.
+    pub fn refresh(&self, workspace_root: &Path, db_path: &Path) -> Result<Option<GraphDelta>> {
+        // Held across the load so concurrent refreshes cannot record an older
+        // graph after a newer one.
+        let mut graphs = self.lock()?;
+        refresh_graph(&mut graphs, workspace_root, db_path)
+    }
+
+    /// Everything that changed after `since_version`, or the whole graph as a
+    /// reset when that version is unknown or too old.
+    pub fn delta_since(
+        &self,
+        workspace_root: &Path,
+        db_path: &Path,
+        since_version: Option<u64>,
+    ) -> Result<GraphDelta> {
+        let mut graphs = self.lock()?;
+        refresh_graph(&mut graphs, workspace_root, db_path)?;
+        let tracked = graphs
+            .get(workspace_root)
+            .ok_or_else(|| anyhow!("Graph for {} was not loaded", workspace_root.display()))?;
+
+        let merged = since_version.and_then(|since| merge_history(tracked, since));
+        Ok(merged.unwrap_or_else(|| GraphDelta {
+            base_version: 0,
+            version: tracked.version,
+            reset: true,
+            upserted_nodes: sorted_values(&tracked.nodes, |node| node.id.clone()),
+            removed_nodes: Vec::new(),
+            upserted_edges: sorted_values(&tracked.edges, |edge| {
+                (edge.source.clone(), edge.target.clone())
+            }),
+            removed_edges: Vec::new(),
+        }))
+    }
+
+    /// Forgets a workspace once no client follows its graph.
+    pub fn forget(&self, workspace_root: &Path) {
+        if let Ok(mut graphs) = self.lock() {
+            graphs.remove(workspace_root);
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<PathBuf, TrackedGraph>>> {
+        self.graphs
+            .lock()
+            .map_err(|_| anyhow!("Graph tracker is poisoned"))
+    }
+}
+
+fn refresh_graph(
+    graphs: &mut HashMap<PathBuf, TrackedGraph>,
+    workspace_root: &Path,
+    db_path: &Path,
+) -> Result<Option<GraphDelta>> {
+    let data = get_graph_view_data(workspace_root, db_path)?;
+    let nodes = data
+        .nodes
+        .into_iter()
+        .map(|node| (node.id.clone(), node))
+        .collect::<HashMap<_, _>>();
+    let edges = data
+        .edges
+        .into_iter()
+        .map(|edge| (GraphEdgeKey::of(&edge), edge))
+        .collect::<HashMap<_, _>>();
+
+    let Some(tracked) = graphs.get_mut(workspace_root) else {
+        graphs.insert(
+            workspace_root.to_path_buf(),
+            TrackedGraph {
+                version: initial_version(),
+                nodes,
+                edges,
+                history: VecDeque::new(),
+            },
+        );
+        return Ok(None);
+    };
+
+    let delta = GraphDelta {
+        base_version: tracked.version,
+        version: tracked.version + 1,
+        reset: false,
+        upserted_nodes: changed_entries(&tracked.nodes, &nodes),
+        removed_nodes: removed_keys(&tracked.nodes, &nodes),
+        upserted_edges: changed_entries(&tracked.edges, &edges),
+        removed_edges: removed_keys(&tracked.edges, &edges),
+    };
+    if delta.is_empty() {
+        return Ok(None);
+    }
+
+    tracked.version = delta.version;
+    tracked.nodes = nodes;
+    tracked.edges = edges;
+    if tracked.history.len() == DELTA_HISTORY_LEN {
+        tracked.history.pop_front();
+    }
+    tracked.history.push_back(delta.clone());
+    Ok(Some(delta))
+}
+
+fn initial_version() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(1, |elapsed| elapsed.as_millis() as u64)
+}
+
+fn changed_entries<K, V>(previous: &HashMap<K, V>, current: &HashMap<K, V>) -> Vec<V>
+where
+    K: Eq + std::hash::Hash,
+    V: Clone + PartialEq,
+{
+    current
+        .iter()
+        .filter(|(key, value)| previous.get(key) != Some(value))
+        .map(|(_, value)| value.clone())
+        .collect()
+}
+
+fn removed_keys<K, V>(previous: &HashMap<K, V>, current: &HashMap<K, V>) -> Vec<K>
+where
+    K: Eq + std::hash::Hash + Clone,
+{
+    previous
+        .keys()
+        .filter(|key| !current.contains_key(key))
+        .cloned()
+        .collect()
+}
+
+fn sorted_values<K, V, S>(entries: &HashMap<K, V>, sort_key: impl Fn(&V) -> S) -> Vec<V>
+where
+    V: Clone,
+    S: Ord,
+{
+    let mut values = entries.values().cloned().collect::<Vec<_>>();
+    values.sort_by_key(sort_key);
+    values
+}
+
+/// Folds the recorded deltas after `since` into one, or `None` when the
+/// history no longer reaches back that far.
+fn merge_history(tracked: &TrackedGraph, since: u64) -> Option<GraphDelta> {
+    if since == tracked.version {
+        return Some(GraphDelta {
+            base_version: since,
+            version: since,
+            ..Default::default()
+        });
+    }
+
+    let start = tracked
+        .history
+        .iter()
+        .position(|delta| delta.base_version == since)?;
+
+    let mut upserted_nodes: HashMap<String, GraphNode> = HashMap::new();
+    let mut removed_nodes: HashSet<String> = HashSet::new();
+    let mut upserted_edges: HashMap<GraphEdgeKey, GraphEdge> = HashMap::new();
+    let mut removed_edges: HashSet<GraphEdgeKey> = HashSet::new();
+    for delta in tracked.history.iter().skip(start) {
+        for node in &delta.upserted_nodes {
+            removed_nodes.remove(&node.id);
+            upserted_nodes.insert(node.id.clone(), node.clone());
+        }
+        for id in &delta.removed_nodes {
+            upserted_nodes.remove(id);
+            removed_nodes.insert(id.clone());
+        }
+        for edge in &delta.upserted_edges {
+            let key = GraphEdgeKey::of(edge);
+            removed_edges.remove(&key);
+            upserted_edges.insert(key, edge.clone());
+        }
+        for key in &delta.removed_edges {
+            upserted_edges.remove(key);
+            removed_edges.insert(key.clone());
+        }
+    }
+
+    Some(GraphDelta {
+        base_version: since,
+        version: tracked.version,
+        reset: false,
+        upserted_nodes: upserted_nodes.into_values().collect(),
+        removed_nodes: removed_nodes.into_iter().collect(),
+        upserted_edges: upserted_edges.into_values().collect(),
+        removed_edges: removed_edges.into_iter().collect(),
+    })
+}
@@ -0,0 +1,332 @@
+//! The note graph written out for tools outside the app: GraphML for Gephi
+//! and yEd, DOT for Graphviz, and D3-style JSON for scripts.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{Context, Result};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::{find_vault_id, get_graph_view_data, open_indexing_connection};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphExportFormat {
+    Graphml,
+    Dot,
+    /// `{ "nodes": [...], "links": [...] }`, as D3's force layout reads it.
+    Json,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GraphExportOptions {
+    /// Add a node per tag, with an edge from every note carrying it.
+    pub include_tags: bool,
+    /// Keep link targets that no note matches.
+    pub include_unresolved: bool,
+}
+
+impl Default for GraphExportOptions {
+    fn default() -> Self {
+        Self {
+            include_tags: false,
+            include_unresolved: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphExportReport {
+    pub node_count: usize,
+    pub edge_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ExportNode {
+    id: String,
+    label: String,
+    kind: &'static str,
+    /// Vault-relative path of a note, or the target text of an unresolved
+    /// link; `None` for tags.
+    path: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ExportEdge {
+    source: String,
+    target: String,
+    kind: &'static str,
+}
+
+/// Writes the vault's note graph to `output_path` in `format`.
+pub fn export_graph(
+    workspace_root: &Path,
+    db_path: &Path,
+    output_path: &Path,
+    format: GraphExportFormat,
+    options: &GraphExportOptions,
+) -> Result<GraphExportReport> {
+    let (nodes, edges) = collect_graph(workspace_root, db_path, options)?;
+    let content = match format {
+        GraphExportFormat::Graphml => render_graphml(&nodes, &edges),
+        GraphExportFormat::Dot => render_dot(&nodes, &edges),
+        GraphExportFormat::Json => render_json(&nodes, &edges)?,
+    };
+    fs::write(output_path, content)
+        .with_context(|| format!("Failed to write {}", output_path.display()))?;
+
+    Ok(GraphExportReport {
+        node_count: nodes.len(),
+        edge_count: edges.len(),
+    })
+}
+
+fn collect_graph(
+    workspace_root: &Path,
+    db_path: &Path,
+    options: &GraphExportOptions,
+) -> Result<(Vec<ExportNode>, Vec<ExportEdge>)> {
+    let graph = get_graph_view_data(workspace_root, db_path)?;
+    let mut nodes = graph
+        .nodes
+        .into_iter()
+        .filter(|node| options.include_unresolved || !node.unresolved)
+        .map(|node| ExportNode {
+            id: node.id,
+            label: node.file_name,
+            kind: if node.unresolved {
+                "unresolved"
+            } else {
+                "note"
+            },
+            path: Some(node.rel_path),
+        })
+        .collect::<Vec<_>>();
+    let mut edges = graph
+        .edges
+        .into_iter()
+        .filter(|edge| options.include_unresolved || !edge.unresolved)
+        .map(|edge| ExportEdge {
+            source: edge.source,
+            target: edge.target,
+            kind: "link",
+        })
+        .collect::<Vec<_>>();
+
+    if options.include_tags {
+        collect_tags(workspace_root, db_path, &mut nodes, &mut edges)?;
+    }
+    Ok((nodes, edges))
+}
+
+fn collect_tags(
+    workspace_root: &Path,
+    db_path: &Path,
+    nodes: &mut Vec<ExportNode>,
+    edges: &mut Vec<ExportEdge>,
+) -> Result<()> {
+    let conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(());
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT dt.doc_id, dt.tag, dt.normalized_tag \
+             FROM doc_tag dt \
+             JOIN doc d ON d.id = dt.doc_id \
+             WHERE d.vault_id = ?1 AND d.last_hash IS NOT NULL \
+             ORDER BY dt.normalized_tag, d.rel_path",
+        )
+        .context("Failed to prepare graph tag query")?;
+    let rows = stmt
+        .query_map(params![vault_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .context("Failed to execute graph tag query")?;
+
+    let mut tag_node_ids: HashMap<String, String> = HashMap::new();
+    for row in rows {
+        let (doc_id, tag, normalized_tag) = row?;
+        let tag_node_id = tag_node_ids
+            .entry(normalized_tag.clone())
+            .or_insert_with(|| {
+                let id = format!("tag:{normalized_tag}");
+                nodes.push(ExportNode {
+                    id: id.clone(),
+                    label: format!("#{tag}"),
+                    kind: "tag",
+                    path: None,
+                });
+                id
+            })
+            .clone();
+        // Matches the note ids of the graph view data.
+        edges.push(ExportEdge {
+            source: format!("doc:{doc_id}"),
+            target: tag_node_id,
+            kind: "tag",
+        });
+    }
+    Ok(())
+}
+
+fn render_graphml(nodes: &[ExportNode], edges: &[ExportEdge]) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+         <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n  \
+         <key id=\"kind\" for=\"node\" attr.name=\"kind\" attr.type=\"string\"/>\n  \
+         <key id=\"path\" for=\"node\" attr.name=\"path\" attr.type=\"string\"/>\n  \
+         <key id=\"edgeKind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n  \
+         <graph id=\"vault\" edgedefault=\"directed\">\n",
+    );
+    for node in nodes {
+        out.push_str(&format!("    <node id=\"{}\">\n", xml_escape(&node.id)));
+        out.push_str(&format!(
+            "      <data key=\"label\">{}</data>\n",
+            xml_escape(&node.label)
+        ));
+        out.push_str(&format!("      <data key=\"kind\">{}</data>\n", node.kind));
+        if let Some(path) = &node.path {
+            out.push_str(&format!(
+                "      <data key=\"path\">{}</data>\n",
+                xml_escape(path)
+            ));
+        }
+        out.push_str("    </node>\n");
+    }
+    for edge in edges {
+        out.push_str(&format!(
+            "    <edge source=\"{}\" target=\"{}\">\n      \
+             <data key=\"edgeKind\">{}</data>\n    </edge>\n",
+            xml_escape(&edge.source),
+            xml_escape(&edge.target),
+            edge.kind
+        ));
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+fn render_dot(nodes: &[ExportNode], edges: &[ExportEdge]) -> String {
+    let mut out = String::from("digraph vault {\n");
+    for node in nodes {
+        let path = node
+            .path
+            .as_deref()
+            .map(|path| format!(", path={}", dot_quote(path)))
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "  {} [label={}, kind={}{path}];\n",
+            dot_quote(&node.id),
+            dot_quote(&node.label),
+            dot_quote(node.kind)
+        ));
+    }
+    for edge in edges {
+        out.push_str(&format!(
+            "  {} -> {} [kind={}];\n",
+            dot_quote(&edge.source),
+            dot_quote(&edge.target),
+            dot_quote(edge.kind)
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn render_json(nodes: &[ExportNode], edges: &[ExportEdge]) -> Result<String> {
+    let nodes = nodes
+        .iter()
+        .map(|node| {
+            json!({
+                "id": node.id,
+                "label": node.label,
+                "kind": node.kind,
+                "path": node.path,
+            })
+        })
+        .collect::<Vec<_>>();
+    let links = edges
+        .iter()
+        .map(|edge| {
+            json!({
+                "source": edge.source,
+                "target": edge.target,
+                "kind": edge.kind,
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string_pretty(&json!({ "nodes": nodes, "links": links }))
+        .context("Failed to serialize graph")
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn dot_quote(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_dot, render_graphml, ExportEdge, ExportNode};
+
+    fn sample() -> (Vec<ExportNode>, Vec<ExportEdge>) {
+        (
+            vec![
+                ExportNode {
+                    id: "doc:1".to_string(),
+                    label: "Q&A \"draft\"".to_string(),
+                    kind: "note",
+                    path: Some("Q&A \"draft\".md".to_string()),
+                },
+                ExportNode {
+                    id: "tag:idea".to_string(),
+                    label: "#idea".to_string(),
+                    kind: "tag",
+                    path: None,
+                },
+            ],
+            vec![ExportEdge {
+                source: "doc:1".to_string(),
+                target: "tag:idea".to_string(),
+                kind: "tag",
+            }],
+        )
+    }
+
+    #[test]
+    fn graphml_and_dot_escape_names() {
+        let (nodes, edges) = sample();
+
+        let graphml = render_graphml(&nodes, &edges);
+        assert!(graphml.contains("<data key=\"label\">Q&amp;A &quot;draft&quot;</data>"));
+        assert!(graphml.contains("<edge source=\"doc:1\" target=\"tag:idea\">"));
+        assert!(!graphml.contains("<data key=\"path\">#idea"));
+
+        let dot = render_dot(&nodes, &edges);
+        assert!(dot.contains(
+            "  \"doc:1\" [label=\"Q&A \\\"draft\\\"\", kind=\"note\", path=\"Q&A \\\"draft\\\".md\"];"
+        ));
+        assert!(dot.contains("  \"tag:idea\" [label=\"#idea\", kind=\"tag\"];"));
+        assert!(dot.contains("  \"doc:1\" -> \"tag:idea\" [kind=\"tag\"];"));
+    }
+}
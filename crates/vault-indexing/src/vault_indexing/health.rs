@@ -0,0 +1,154 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use super::{
+    canonicalize_workspace_root,
+    cloud_files::is_cloud_placeholder,
+    files::{collect_markdown_files, collect_workspace_entries},
+    find_vault_id, open_indexing_connection,
+    sync::{count_current_files, count_pending_embeddings, load_indexing_options},
+};
+
+/// How well the index covers a vault, gathered in one pass for the health
+/// panel in settings.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultHealth {
+    /// Notes and drawings on disk.
+    pub source_file_count: usize,
+    /// Files whose index entry matches what is on disk.
+    pub fresh_file_count: usize,
+    /// `fresh_file_count` out of `source_file_count`, from 0 to 100.
+    pub freshness_percent: f64,
+    pub indexed_doc_count: usize,
+    /// Indexed notes with stored embeddings.
+    pub embedded_doc_count: usize,
+    /// `embedded_doc_count` out of `indexed_doc_count`, from 0 to 100.
+    pub embedding_coverage_percent: f64,
+    pub pending_embedding_count: usize,
+    pub unresolved_link_count: usize,
+    /// Files other than notes whose names appear in no note.
+    pub orphan_attachments: Vec<String>,
+    /// Notes over the vault's size cap, of which only the head is indexed.
+    pub oversized_notes: Vec<OversizedNote>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OversizedNote {
+    pub rel_path: String,
+    pub size_bytes: u64,
+}
+
+pub fn get_vault_health(workspace_root: &Path, db_path: &Path) -> Result<VaultHealth> {
+    let _ = canonicalize_workspace_root(workspace_root)?;
+    let files = collect_markdown_files(workspace_root)?;
+    let conn = open_indexing_connection(db_path)?;
+    let vault_id = find_vault_id(&conn, workspace_root)?;
+
+    let options = match vault_id {
+        Some(vault_id) => load_indexing_options(&conn, vault_id)?,
+        None => Default::default(),
+    };
+    let mut oversized_notes = files
+        .iter()
+        .filter_map(|file| {
+            let size_bytes = u64::try_from(file.last_source_size?).ok()?;
+            (size_bytes > options.max_source_bytes).then(|| OversizedNote {
+                rel_path: file.rel_path.clone(),
+                size_bytes,
+            })
+        })
+        .collect::<Vec<_>>();
+    oversized_notes.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+
+    let mut health = VaultHealth {
+        source_file_count: files.len(),
+        orphan_attachments: find_orphan_attachments(workspace_root, db_path)?,
+        oversized_notes,
+        ..Default::default()
+    };
+
+    if let Some(vault_id) = vault_id {
+        health.fresh_file_count = count_current_files(&conn, vault_id, &files)?;
+        health.indexed_doc_count = count(
+            &conn,
+            "SELECT COUNT(*) FROM doc WHERE vault_id = ?1 AND last_hash IS NOT NULL",
+            vault_id,
+        )?;
+        health.embedded_doc_count = count(
+            &conn,
+            "SELECT COUNT(*) FROM doc \
+             WHERE vault_id = ?1 AND last_hash IS NOT NULL AND last_embedding_model IS NOT NULL",
+            vault_id,
+        )?;
+        health.pending_embedding_count = count_pending_embeddings(&conn, vault_id)?;
+        health.unresolved_link_count = count(
+            &conn,
+            "SELECT COUNT(*) FROM link l JOIN doc s ON s.id = l.source_doc_id \
+             WHERE s.vault_id = ?1 AND l.target_doc_id IS NULL",
+            vault_id,
+        )?;
+    }
+    health.freshness_percent = percent(health.fresh_file_count, health.source_file_count);
+    health.embedding_coverage_percent =
+        percent(health.embedded_doc_count, health.indexed_doc_count);
+
+    Ok(health)
+}
+
+/// Matches embeds by file name, as archiving does when it decides which
+/// attachments travel with a note.
+fn find_orphan_attachments(workspace_root: &Path, db_path: &Path) -> Result<Vec<String>> {
+    // The index database and its journals may sit inside the vault.
+    let db_path = db_path.to_string_lossy();
+    let entries = collect_workspace_entries(workspace_root)?;
+    let (notes, attachments): (Vec<_>, Vec<_>) = entries
+        .into_iter()
+        .filter(|entry| !entry.is_dir)
+        .partition(|entry| note::is_indexed_path(&entry.rel_path));
+
+    // Reading a placeholder would download it, so its embeds go unseen.
+    let sources = notes
+        .iter()
+        .map(|entry| entry.abs_path.as_path())
+        .filter(|path| note::is_note_path(path) && !is_cloud_placeholder(path))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .collect::<Vec<_>>();
+
+    let mut orphans = attachments
+        .into_iter()
+        .map(|entry| entry.rel_path)
+        .filter(|rel_path| {
+            !workspace_root
+                .join(rel_path)
+                .to_string_lossy()
+                .starts_with(db_path.as_ref())
+        })
+        .filter(|rel_path| {
+            let name = rel_path.rsplit('/').next().unwrap_or(rel_path);
+            let encoded = name.replace(' ', "%20");
+            !sources
+                .iter()
+                .any(|source| source.contains(name) || source.contains(&encoded))
+        })
+        .collect::<Vec<_>>();
+    orphans.sort();
+    Ok(orphans)
+}
+
+fn percent(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        return 100.0;
+    }
+    (part as f64 * 1000.0 / whole as f64).round() / 10.0
+}
+
+fn count(conn: &Connection, sql: &str, vault_id: i64) -> Result<usize> {
+    conn.query_row(sql, params![vault_id], |row| row.get::<_, i64>(0))
+        .map(|count| count as usize)
+        .context("Failed to count vault health rows")
+}
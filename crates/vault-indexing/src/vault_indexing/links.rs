@@ -1,8 +1,10 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map::Entry, HashMap, HashSet},
+    ops::Range,
     path::{Component, Path, PathBuf},
 };
 
+use app_storage::unicode::nfc;
 use pulldown_cmark::{Event, Parser, Tag, TagEnd};
 
 use super::files::MarkdownFile;
@@ -11,6 +13,23 @@ use super::files::MarkdownFile;
 pub(crate) struct ResolvedLink {
     pub(crate) target_doc_id: Option<i64>,
     pub(crate) target_path: String,
+    /// Every place the source mentions this target, in source order.
+    pub(crate) mentions: Vec<LinkMention>,
+}
+
+/// One link occurrence in the source note, with the surrounding lines so
+/// backlink lists can show it without reading the source again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LinkMention {
+    /// Byte range of the whole link syntax in the source file.
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    /// Zero-based line of `start`.
+    pub(crate) line: usize,
+    /// The mention's line plus one line on either side.
+    pub(crate) context: String,
+    /// Byte offset of `context` in the source file.
+    pub(crate) context_start: usize,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -26,6 +45,7 @@ pub(crate) struct ResolvedWikiLinkTarget {
     pub(crate) match_count: usize,
     pub(crate) disambiguated: bool,
     pub(crate) unresolved: bool,
+    pub(crate) resolved_via_alias: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -48,6 +68,7 @@ enum LinkKind {
 struct LinkCandidate {
     kind: LinkKind,
     raw_target: String,
+    span: Range<usize>,
 }
 
 #[derive(Debug)]
@@ -56,6 +77,7 @@ pub(crate) struct LinkResolver {
     docs_by_path: HashMap<String, i64>,
     wiki_docs: Vec<WikiDocEntry>,
     basename_index: HashMap<String, Vec<usize>>,
+    alias_index: HashMap<String, Vec<usize>>,
 }
 
 impl LinkResolver {
@@ -67,9 +89,17 @@ impl LinkResolver {
             docs_by_path,
             wiki_docs,
             basename_index,
+            alias_index: HashMap::new(),
         }
     }
 
+    /// Lets wiki links reach notes through their frontmatter aliases, keyed
+    /// by normalized alias. Filename matches still take precedence.
+    pub(crate) fn with_aliases(mut self, note_aliases: &HashMap<String, Vec<String>>) -> Self {
+        self.alias_index = build_alias_index(&self.wiki_docs, note_aliases);
+        self
+    }
+
     #[allow(dead_code)]
     pub(crate) fn resolve_links(&self, source: &MarkdownFile, contents: &str) -> Vec<ResolvedLink> {
         self.resolve_links_with_dependencies(source, contents).links
@@ -82,9 +112,10 @@ impl LinkResolver {
     ) -> LinkResolution {
         let mut candidates = extract_markdown_candidates(contents);
         candidates.extend(extract_wiki_candidates(contents));
+        candidates.sort_by_key(|candidate| candidate.span.start);
 
-        let mut results = Vec::new();
-        let mut seen: HashSet<LinkKey> = HashSet::new();
+        let mut results: Vec<ResolvedLink> = Vec::new();
+        let mut seen: HashMap<LinkKey, usize> = HashMap::new();
         let mut wiki_query_keys: HashSet<String> = HashSet::new();
 
         for candidate in candidates {
@@ -93,15 +124,22 @@ impl LinkResolver {
                     wiki_query_keys.insert(key);
                 }
             }
+            let mention = link_mention(contents, candidate.span.clone());
             let resolved = match candidate.kind {
                 LinkKind::Wiki => self.resolve_wiki_candidate(source, candidate),
                 LinkKind::Markdown => self.resolve_markdown_candidate(source, candidate),
             };
 
-            if let Some(link) = resolved {
-                let key = LinkKey::from(&link);
-                if seen.insert(key) {
-                    results.push(link);
+            if let Some(mut link) = resolved {
+                match seen.entry(LinkKey::from(&link)) {
+                    Entry::Occupied(entry) => {
+                        results[*entry.get()].mentions.push(mention);
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(results.len());
+                        link.mentions.push(mention);
+                        results.push(link);
+                    }
                 }
             }
         }
@@ -136,12 +174,15 @@ impl LinkResolver {
         }
 
         let query_lower = normalized_query.to_lowercase();
-        let matches = find_wiki_candidates(
+        let mut matches = find_wiki_candidates(
             &self.wiki_docs,
             &self.basename_index,
             &query_lower,
             query_lower.contains('/'),
         );
+        if matches.is_empty() {
+            matches = find_alias_candidates(&self.wiki_docs, &self.alias_index, &query_lower);
+        }
 
         if let Some(selected) = choose_preferred_doc(matches, Some(source.rel_path.as_str()), None)
         {
@@ -149,12 +190,14 @@ impl LinkResolver {
             return Some(ResolvedLink {
                 target_doc_id,
                 target_path: selected.rel_path.clone(),
+                mentions: Vec::new(),
             });
         }
 
         Some(ResolvedLink {
             target_doc_id: None,
             target_path: unresolved_wiki_target_path(&normalized_query, path_part),
+            mentions: Vec::new(),
         })
     }
 
@@ -196,6 +239,7 @@ impl LinkResolver {
         Some(ResolvedLink {
             target_doc_id,
             target_path: rel_path,
+            mentions: Vec::new(),
         })
     }
 }
@@ -205,13 +249,16 @@ pub(crate) fn resolve_wiki_link_target(
     current_note_path: Option<&str>,
     raw_target: &str,
     workspace_rel_paths: &[String],
+    note_aliases: &HashMap<String, Vec<String>>,
 ) -> ResolvedWikiLinkTarget {
     let (wiki_docs, basename_index) = build_wiki_doc_indexes(workspace_rel_paths.iter());
+    let alias_index = build_alias_index(&wiki_docs, note_aliases);
 
     resolve_wiki_target_internal(
         raw_target,
         &wiki_docs,
         &basename_index,
+        &alias_index,
         current_note_path,
         Some(workspace_root),
     )
@@ -221,6 +268,7 @@ fn resolve_wiki_target_internal(
     raw_target: &str,
     wiki_docs: &[WikiDocEntry],
     basename_index: &HashMap<String, Vec<usize>>,
+    alias_index: &HashMap<String, Vec<usize>>,
     current_note_path: Option<&str>,
     workspace_root: Option<&Path>,
 ) -> ResolvedWikiLinkTarget {
@@ -246,20 +294,31 @@ fn resolve_wiki_target_internal(
 
     let query_lower = normalized_query.to_lowercase();
     let has_separator = query_lower.contains('/');
-    let matches = find_wiki_candidates(wiki_docs, basename_index, &query_lower, has_separator);
+    let mut matches = find_wiki_candidates(wiki_docs, basename_index, &query_lower, has_separator);
+    let resolved_via_alias = matches.is_empty();
+    if resolved_via_alias {
+        matches = find_alias_candidates(wiki_docs, alias_index, &query_lower);
+    }
     let match_count = matches.len();
 
     let Some(selected) = choose_preferred_doc(matches, current_note_path, workspace_root) else {
         return unresolved_wiki_target_result(append_wiki_suffix(&normalized_query, suffix));
     };
 
-    let canonical_base = shortest_unique_wiki_suffix(selected, wiki_docs);
+    // An alias is what the author chose to show, so it is kept instead of
+    // being rewritten to the file name.
+    let canonical_base = if resolved_via_alias {
+        normalized_query
+    } else {
+        shortest_unique_wiki_suffix(selected, wiki_docs)
+    };
     ResolvedWikiLinkTarget {
         canonical_target: append_wiki_suffix(&canonical_base, suffix),
         resolved_rel_path: Some(selected.rel_path.clone()),
         match_count,
         disambiguated: match_count > 1,
         unresolved: false,
+        resolved_via_alias,
     }
 }
 
@@ -270,6 +329,7 @@ fn unresolved_wiki_target_result(canonical_target: String) -> ResolvedWikiLinkTa
         match_count: 0,
         disambiguated: false,
         unresolved: true,
+        resolved_via_alias: false,
     }
 }
 
@@ -305,6 +365,13 @@ where
     (wiki_docs, basename_index)
 }
 
+/// Lowercased file name without extension, the key a bare `[[name]]` link
+/// is matched against. Drawings keep their extension. `None` for other
+/// files.
+pub(crate) fn wiki_basename_key(rel_path: &str) -> Option<String> {
+    build_wiki_doc_entry(rel_path).map(|entry| entry.basename_lower)
+}
+
 fn build_wiki_doc_entry(rel_path: &str) -> Option<WikiDocEntry> {
     let normalized_rel_path = normalize_path_separators(rel_path.trim());
     if normalized_rel_path.is_empty() {
@@ -317,11 +384,14 @@ fn build_wiki_doc_entry(rel_path: &str) -> Option<WikiDocEntry> {
         return None;
     }
 
-    if !has_markdown_extension(&normalized_rel_path) {
+    // Drawings keep their extension, so `[[Board.excalidraw]]` links one.
+    let no_ext = if has_markdown_extension(&normalized_rel_path) {
+        strip_markdown_extension(&normalized_rel_path).to_string()
+    } else if note::is_drawing_path(&normalized_rel_path) {
+        normalized_rel_path.clone()
+    } else {
         return None;
-    }
-
-    let no_ext = strip_markdown_extension(&normalized_rel_path).to_string();
+    };
     if no_ext.is_empty() {
         return None;
     }
@@ -346,6 +416,48 @@ fn build_wiki_doc_entry(rel_path: &str) -> Option<WikiDocEntry> {
     })
 }
 
+fn build_alias_index(
+    wiki_docs: &[WikiDocEntry],
+    note_aliases: &HashMap<String, Vec<String>>,
+) -> HashMap<String, Vec<usize>> {
+    if note_aliases.is_empty() {
+        return HashMap::new();
+    }
+
+    let doc_index_by_path = wiki_docs
+        .iter()
+        .enumerate()
+        .map(|(index, doc)| (doc.rel_path.as_str(), index))
+        .collect::<HashMap<_, _>>();
+
+    note_aliases
+        .iter()
+        .filter_map(|(alias, rel_paths)| {
+            let indices = rel_paths
+                .iter()
+                .filter_map(|rel_path| doc_index_by_path.get(rel_path.as_str()).copied())
+                .collect::<Vec<_>>();
+            (!indices.is_empty()).then(|| (alias.clone(), indices))
+        })
+        .collect()
+}
+
+fn find_alias_candidates<'a>(
+    wiki_docs: &'a [WikiDocEntry],
+    alias_index: &HashMap<String, Vec<usize>>,
+    query_lower: &str,
+) -> Vec<&'a WikiDocEntry> {
+    alias_index
+        .get(query_lower)
+        .map(|indices| {
+            indices
+                .iter()
+                .filter_map(|index| wiki_docs.get(*index))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 fn find_wiki_candidates<'a>(
     wiki_docs: &'a [WikiDocEntry],
     basename_index: &HashMap<String, Vec<usize>>,
@@ -482,22 +594,53 @@ impl From<&ResolvedLink> for LinkKey {
     }
 }
 
+/// Builds the mention for `span`, widening to whole lines one line before and
+/// after it.
+fn link_mention(contents: &str, span: Range<usize>) -> LinkMention {
+    let line_start = |offset: usize| contents[..offset].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = |offset: usize| {
+        contents[offset..]
+            .find('\n')
+            .map_or(contents.len(), |index| offset + index)
+    };
+
+    let mention_line_start = line_start(span.start);
+    let context_start = mention_line_start.checked_sub(1).map_or(0, line_start);
+    let mention_line_end = line_end(span.end);
+    let context_end = if mention_line_end < contents.len() {
+        line_end(mention_line_end + 1)
+    } else {
+        mention_line_end
+    };
+
+    LinkMention {
+        line: contents[..span.start].matches('\n').count(),
+        start: span.start,
+        end: span.end,
+        context: contents[context_start..context_end]
+            .trim_end_matches(['\r', '\n'])
+            .to_string(),
+        context_start,
+    }
+}
+
 fn extract_markdown_candidates(contents: &str) -> Vec<LinkCandidate> {
-    let parser = Parser::new(contents);
+    let parser = Parser::new(contents).into_offset_iter();
     let mut candidates = Vec::new();
-    let mut active_dest_url: Option<String> = None;
+    let mut active_link: Option<(String, Range<usize>)> = None;
 
-    for event in parser {
+    for (event, range) in parser {
         match event {
             Event::Start(Tag::Link { dest_url, .. }) => {
-                active_dest_url = Some(dest_url.to_string());
+                active_link = Some((dest_url.to_string(), range));
             }
             Event::End(TagEnd::Link) => {
-                if let Some(dest_url) = active_dest_url.take() {
+                if let Some((dest_url, span)) = active_link.take() {
                     if !dest_url.trim().is_empty() {
                         candidates.push(LinkCandidate {
                             kind: LinkKind::Markdown,
                             raw_target: dest_url,
+                            span,
                         });
                     }
                 }
@@ -509,13 +652,28 @@ fn extract_markdown_candidates(contents: &str) -> Vec<LinkCandidate> {
     candidates
 }
 
+/// Byte ranges of the wiki links outside fenced code.
+pub(crate) fn wiki_link_spans(contents: &str) -> Vec<Range<usize>> {
+    extract_wiki_candidates(contents)
+        .into_iter()
+        .map(|candidate| candidate.span)
+        .collect()
+}
+
 fn extract_wiki_candidates(contents: &str) -> Vec<LinkCandidate> {
     let mut candidates = Vec::new();
     let mut in_fence = false;
     let mut fence_char = '\0';
     let mut fence_len = 0usize;
+    let mut line_offset = 0usize;
+
+    for raw_line in contents.split_inclusive('\n') {
+        let line = raw_line
+            .strip_suffix('\n')
+            .map_or(raw_line, |line| line.strip_suffix('\r').unwrap_or(line));
+        let offset = line_offset;
+        line_offset += raw_line.len();
 
-    for line in contents.lines() {
         let trimmed = line.trim_start();
         if let Some((char, len)) = detect_fence(trimmed) {
             if !in_fence {
@@ -534,7 +692,7 @@ fn extract_wiki_candidates(contents: &str) -> Vec<LinkCandidate> {
             continue;
         }
 
-        extract_wiki_candidates_from_line(line, &mut candidates);
+        extract_wiki_candidates_from_line(line, offset, &mut candidates);
     }
 
     candidates
@@ -555,7 +713,11 @@ fn detect_fence(line: &str) -> Option<(char, usize)> {
     }
 }
 
-fn extract_wiki_candidates_from_line(line: &str, candidates: &mut Vec<LinkCandidate>) {
+fn extract_wiki_candidates_from_line(
+    line: &str,
+    line_offset: usize,
+    candidates: &mut Vec<LinkCandidate>,
+) {
     let bytes = line.as_bytes();
     let mut i = 0usize;
     let mut in_code = false;
@@ -598,6 +760,7 @@ fn extract_wiki_candidates_from_line(line: &str, candidates: &mut Vec<LinkCandid
                             candidates.push(LinkCandidate {
                                 kind: LinkKind::Wiki,
                                 raw_target: target.to_string(),
+                                span: line_offset + i..line_offset + end + 2,
                             });
                         }
                     }
@@ -664,7 +827,7 @@ fn strip_markdown_anchor(raw: &str) -> &str {
     }
 }
 
-fn wiki_query_dependency_key(raw_target: &str) -> Option<String> {
+pub(crate) fn wiki_query_dependency_key(raw_target: &str) -> Option<String> {
     let trimmed = raw_target.trim();
     if trimmed.is_empty() || is_external_wiki_target(trimmed) {
         return None;
@@ -689,7 +852,7 @@ fn normalize_wiki_query_path(path: &str) -> String {
         return String::new();
     }
 
-    let normalized = normalize_path_separators(trimmed);
+    let normalized = normalize_path_separators(&nfc(trimmed));
     let normalized = strip_current_dir_prefix_owned(normalized);
     let normalized = strip_leading_slashes_owned(normalized);
     if normalized.is_empty() {
@@ -787,7 +950,7 @@ fn has_markdown_extension(value: &str) -> bool {
 }
 
 fn normalize_rel_path(path: &Path) -> String {
-    path.to_string_lossy().replace('\\', "/")
+    nfc(&path.to_string_lossy()).replace('\\', "/")
 }
 
 fn resolve_relative_path(base: &Path, rel: &str) -> PathBuf {
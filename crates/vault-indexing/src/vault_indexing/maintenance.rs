@@ -0,0 +1,243 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use super::{
+    files::{disk_path, MarkdownFile},
+    fts_tokenizer::{vault_fts_table, SHARED_FTS_TABLE},
+    sync::{load_indexing_options, sync_embeddings_for_prepared, PreparedDocument},
+    EmbeddingProfile, IndexSummary, TARGET_CHUNKING_VERSION,
+};
+
+/// Low-priority upkeep run while the app is idle. Each task advances in
+/// batches so a scheduler can throttle it and stop between batches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MaintenanceTask {
+    /// Drops rows of notes deleted while nothing was watching the vault.
+    PruneOrphans,
+    /// Binds links whose target note was created after the link was indexed.
+    RefreshUnresolvedLinks,
+    /// Re-embeds notes indexed with another model or chunking version.
+    ReembedDrift,
+    /// Compacts the full-text index and the database file.
+    Vacuum,
+}
+
+impl MaintenanceTask {
+    /// Pruning goes first so later tasks skip dead rows; vacuum goes last to
+    /// reclaim what the others freed.
+    pub const ALL: [Self; 4] = [
+        Self::PruneOrphans,
+        Self::RefreshUnresolvedLinks,
+        Self::ReembedDrift,
+        Self::Vacuum,
+    ];
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceBatch {
+    pub processed: usize,
+    /// No work is left for the task.
+    pub done: bool,
+}
+
+impl MaintenanceBatch {
+    fn finished(processed: usize) -> Self {
+        Self {
+            processed,
+            done: true,
+        }
+    }
+
+    fn from_batch(processed: usize, batch_size: usize) -> Self {
+        Self {
+            processed,
+            done: processed < batch_size,
+        }
+    }
+}
+
+/// Runs one batch of `task` over at most `batch_size` notes.
+pub fn run_maintenance_batch(
+    workspace_root: &Path,
+    db_path: &Path,
+    task: MaintenanceTask,
+    embedding_profiles: &[EmbeddingProfile],
+    batch_size: usize,
+) -> Result<MaintenanceBatch> {
+    let batch_size = batch_size.max(1);
+    let mut conn = super::open_indexing_connection(db_path)?;
+    let Some(vault_id) = super::find_vault_id(&conn, workspace_root)? else {
+        return Ok(MaintenanceBatch::finished(0));
+    };
+
+    match task {
+        MaintenanceTask::PruneOrphans => prune_orphans(&conn, workspace_root, vault_id, batch_size),
+        MaintenanceTask::RefreshUnresolvedLinks => {
+            refresh_unresolved_links(&conn, vault_id, batch_size)
+        }
+        MaintenanceTask::ReembedDrift => reembed_drift(
+            &mut conn,
+            workspace_root,
+            db_path,
+            vault_id,
+            embedding_profiles,
+            batch_size,
+        ),
+        MaintenanceTask::Vacuum => vacuum(&conn, vault_id),
+    }
+}
+
+fn prune_orphans(
+    conn: &Connection,
+    workspace_root: &Path,
+    vault_id: i64,
+    batch_size: usize,
+) -> Result<MaintenanceBatch> {
+    let mut stmt = conn
+        .prepare("SELECT id, rel_path FROM doc WHERE vault_id = ?1")
+        .context("Failed to prepare orphan scan")?;
+    let orphan_ids = stmt
+        .query_map(params![vault_id], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .context("Failed to scan documents for orphans")?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|(_, rel_path)| !disk_path(workspace_root, rel_path).is_file())
+        .map(|(doc_id, _)| doc_id)
+        .take(batch_size)
+        .collect::<Vec<_>>();
+
+    super::tombstones::delete_docs(conn, vault_id, &orphan_ids)
+        .context("Failed to prune orphaned docs")?;
+
+    // The vector table is virtual, so deleted segments leave their rows behind.
+    if super::segment_vec_table_exists(conn)? {
+        conn.execute(
+            "DELETE FROM segment_vec WHERE rowid NOT IN (SELECT id FROM segment)",
+            [],
+        )
+        .context("Failed to prune orphaned segment vectors")?;
+    }
+
+    Ok(MaintenanceBatch::from_batch(orphan_ids.len(), batch_size))
+}
+
+fn refresh_unresolved_links(
+    conn: &Connection,
+    vault_id: i64,
+    batch_size: usize,
+) -> Result<MaintenanceBatch> {
+    let bound = conn
+        .execute(
+            "UPDATE link SET target_doc_id = ( \
+                 SELECT t.id FROM doc t WHERE t.vault_id = ?1 AND t.rel_path = link.target_path \
+             ) \
+             WHERE id IN ( \
+                 SELECT l.id FROM link l \
+                 JOIN doc s ON s.id = l.source_doc_id \
+                 JOIN doc t ON t.vault_id = s.vault_id AND t.rel_path = l.target_path \
+                 WHERE s.vault_id = ?1 AND l.target_doc_id IS NULL \
+                 LIMIT ?2 \
+             )",
+            params![vault_id, batch_size as i64],
+        )
+        .context("Failed to refresh unresolved links")?;
+
+    Ok(MaintenanceBatch::from_batch(bound, batch_size))
+}
+
+fn reembed_drift(
+    conn: &mut Connection,
+    workspace_root: &Path,
+    db_path: &Path,
+    vault_id: i64,
+    embedding_profiles: &[EmbeddingProfile],
+    batch_size: usize,
+) -> Result<MaintenanceBatch> {
+    let Some(embedding) = super::create_embedding_context(db_path, embedding_profiles)? else {
+        return Ok(MaintenanceBatch::finished(0));
+    };
+
+    // Queued documents are left to the pending-embedding retry.
+    let drifted = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT rel_path FROM doc \
+                 WHERE vault_id = ?1 AND last_hash IS NOT NULL \
+                   AND id NOT IN (SELECT doc_id FROM pending_embedding) \
+                   AND (chunking_version != ?2 \
+                        OR last_embedding_provider IS NOT ?3 \
+                        OR last_embedding_model IS NOT ?4 \
+                        OR last_embedding_dim IS NOT ?5) \
+                 ORDER BY id \
+                 LIMIT ?6",
+            )
+            .context("Failed to prepare drift scan")?;
+        let rows = stmt
+            .query_map(
+                params![
+                    vault_id,
+                    TARGET_CHUNKING_VERSION,
+                    embedding.embedder.provider_name(),
+                    embedding.embedder.model_name(),
+                    embedding.target_dim,
+                    batch_size as i64
+                ],
+                |row| row.get::<_, String>(0),
+            )
+            .context("Failed to scan documents for embedding drift")?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()?
+    };
+
+    let options = load_indexing_options(conn, vault_id)?;
+    let prepared_documents = drifted
+        .iter()
+        .filter_map(|rel_path| {
+            let file = MarkdownFile::from_abs_and_rel(
+                disk_path(workspace_root, rel_path),
+                rel_path.clone(),
+            );
+            PreparedDocument::load(file, &options).ok()
+        })
+        .collect::<Vec<_>>();
+
+    let mut summary = IndexSummary::default();
+    sync_embeddings_for_prepared(
+        conn,
+        vault_id,
+        &prepared_documents,
+        &embedding,
+        &mut summary,
+        true,
+    )?;
+
+    // Unreadable files stay drifted until pruned, so a batch that could load
+    // nothing ends the task instead of retrying the same rows.
+    Ok(MaintenanceBatch {
+        processed: summary.files_processed,
+        done: drifted.len() < batch_size || prepared_documents.is_empty(),
+    })
+}
+
+fn vacuum(conn: &Connection, vault_id: i64) -> Result<MaintenanceBatch> {
+    let vault_fts_table = vault_fts_table(conn, vault_id)?;
+    if vault_fts_table != SHARED_FTS_TABLE {
+        conn.execute_batch(&format!(
+            "INSERT INTO {vault_fts_table}({vault_fts_table}) VALUES('optimize');"
+        ))
+        .context("Failed to optimize vault full-text index")?;
+    }
+    conn.execute_batch(
+        "INSERT INTO doc_fts(doc_fts) VALUES('optimize'); \
+         VACUUM; \
+         PRAGMA optimize;",
+    )
+    .context("Failed to vacuum indexing database")?;
+    Ok(MaintenanceBatch::finished(1))
+}
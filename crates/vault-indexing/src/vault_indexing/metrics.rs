@@ -0,0 +1,173 @@
+//! Process-wide counters for the indexing pipeline, read by the local API's
+//! metrics endpoint.
+
+use std::{
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::open_indexing_connection;
+
+/// Upper bounds of the latency buckets, in seconds. Remote embedding
+/// providers land in the upper half, searches and local models in the lower.
+pub const LATENCY_BUCKETS_SECONDS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+pub(crate) static EMBEDDING_LATENCY: LatencyHistogram = LatencyHistogram::new();
+pub(crate) static SEARCH_LATENCY: LatencyHistogram = LatencyHistogram::new();
+static DOCUMENTS_INDEXED: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) struct LatencyHistogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS_SECONDS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl LatencyHistogram {
+    const fn new() -> Self {
+        Self {
+            buckets: [const { AtomicU64::new(0) }; LATENCY_BUCKETS_SECONDS.len()],
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn observe(&self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        if let Some(bucket) = LATENCY_BUCKETS_SECONDS
+            .iter()
+            .position(|bound| seconds <= *bound)
+        {
+            self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add(
+            u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Observes the time until the returned timer drops.
+    pub(crate) fn start_timer(&'static self) -> LatencyTimer {
+        LatencyTimer {
+            histogram: self,
+            started: Instant::now(),
+        }
+    }
+
+    fn snapshot(&self) -> LatencySnapshot {
+        let mut cumulative = 0;
+        let buckets = LATENCY_BUCKETS_SECONDS
+            .iter()
+            .zip(&self.buckets)
+            .map(|(bound, count)| {
+                cumulative += count.load(Ordering::Relaxed);
+                (*bound, cumulative)
+            })
+            .collect();
+        LatencySnapshot {
+            buckets,
+            count: self.count.load(Ordering::Relaxed),
+            sum_seconds: self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0,
+        }
+    }
+}
+
+pub(crate) struct LatencyTimer {
+    histogram: &'static LatencyHistogram,
+    started: Instant,
+}
+
+impl Drop for LatencyTimer {
+    fn drop(&mut self) {
+        self.histogram.observe(self.started.elapsed());
+    }
+}
+
+pub(crate) fn record_documents_indexed(count: usize) {
+    DOCUMENTS_INDEXED.fetch_add(count as u64, Ordering::Relaxed);
+}
+
+/// A latency histogram as Prometheus exposes it: bucket counts are
+/// cumulative, and observations past the last bound only show in `count`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencySnapshot {
+    pub buckets: Vec<(f64, u64)>,
+    pub count: u64,
+    pub sum_seconds: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultIndexSize {
+    pub workspace_root: String,
+    pub documents: u64,
+    pub segments: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexingMetrics {
+    /// Documents chunked and written since the process started.
+    pub documents_indexed: u64,
+    pub embedding_latency: LatencySnapshot,
+    pub search_latency: LatencySnapshot,
+    pub vaults: Vec<VaultIndexSize>,
+}
+
+/// The counters so far, with the indexed size of every vault in `db_path`.
+pub fn get_indexing_metrics(db_path: &Path) -> Result<IndexingMetrics> {
+    let conn = open_indexing_connection(db_path)?;
+    let mut stmt = conn.prepare(
+        "SELECT v.workspace_root,
+                (SELECT COUNT(*) FROM doc d WHERE d.vault_id = v.id),
+                (SELECT COUNT(*) FROM segment s JOIN doc d ON d.id = s.doc_id
+                  WHERE d.vault_id = v.id)
+         FROM vault v
+         ORDER BY v.workspace_root",
+    )?;
+    let vaults = stmt
+        .query_map([], |row| {
+            Ok(VaultIndexSize {
+                workspace_root: row.get(0)?,
+                documents: row.get(1)?,
+                segments: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(IndexingMetrics {
+        documents_indexed: DOCUMENTS_INDEXED.load(Ordering::Relaxed),
+        embedding_latency: EMBEDDING_LATENCY.snapshot(),
+        search_latency: SEARCH_LATENCY.snapshot(),
+        vaults,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::LatencyHistogram;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let histogram = LatencyHistogram::new();
+        histogram.observe(Duration::from_millis(3));
+        histogram.observe(Duration::from_millis(40));
+        histogram.observe(Duration::from_secs(30));
+
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.count, 3);
+        assert_eq!(snapshot.buckets[0], (0.005, 1));
+        assert_eq!(snapshot.buckets[3], (0.05, 2));
+        assert_eq!(snapshot.buckets.last(), Some(&(10.0, 2)));
+        assert!((snapshot.sum_seconds - 30.043).abs() < 1e-9);
+    }
+}
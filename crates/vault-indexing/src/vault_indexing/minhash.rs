@@ -0,0 +1,78 @@
+//! MinHash signatures of indexed note text, for spotting near-duplicate notes
+//! such as meeting minutes filled in from the same template.
+//!
+//! A note's text is split into overlapping runs of words (shingles); the
+//! fraction of equal lanes in two signatures estimates the Jaccard similarity
+//! of their shingle sets.
+
+const SIGNATURE_LANES: usize = 64;
+const SHINGLE_WORDS: usize = 5;
+
+/// Signature of `text` as stored in `doc.minhash`, or `None` for text
+/// without words.
+pub(crate) fn minhash_signature(text: &str) -> Option<Vec<u8>> {
+    let words = text
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    if words.is_empty() {
+        return None;
+    }
+
+    let mut lanes = [u32::MAX; SIGNATURE_LANES];
+    for shingle in words.windows(SHINGLE_WORDS.min(words.len())) {
+        let digest = blake3::hash(shingle.join(" ").as_bytes());
+        let base = u64::from_le_bytes(digest.as_bytes()[..8].try_into().unwrap());
+        for (lane, min) in lanes.iter_mut().enumerate() {
+            *min = (*min).min(mix(base ^ lane as u64) as u32);
+        }
+    }
+
+    Some(lanes.iter().flat_map(|lane| lane.to_le_bytes()).collect())
+}
+
+/// Estimated Jaccard similarity of the texts behind two signatures, from 0
+/// to 1. Signatures of another length compare as unrelated.
+pub(crate) fn signature_similarity(left: &[u8], right: &[u8]) -> f32 {
+    if left.len() != SIGNATURE_LANES * 4 || right.len() != left.len() {
+        return 0.0;
+    }
+    let equal = left
+        .chunks_exact(4)
+        .zip(right.chunks_exact(4))
+        .filter(|(left, right)| left == right)
+        .count();
+    equal as f32 / SIGNATURE_LANES as f32
+}
+
+// SplitMix64 finalizer: one hash per lane from a single shingle digest.
+fn mix(mut value: u64) -> u64 {
+    value = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{minhash_signature, signature_similarity};
+
+    #[test]
+    fn near_identical_texts_score_higher_than_unrelated_ones() {
+        let minutes = "Attendees: Ana, Ben. Agenda: review the roadmap, plan the release, \
+                       assign owners for the migration and agree on the next check-in date.";
+        let edited = minutes.replace("Ana, Ben", "Ana, Ben, Cleo");
+        let unrelated = "Sourdough needs a lively starter, a long cold proof and a very hot oven.";
+
+        let minutes = minhash_signature(minutes).expect("signature");
+        let edited = minhash_signature(&edited).expect("signature");
+        let unrelated = minhash_signature(unrelated).expect("signature");
+
+        assert_eq!(signature_similarity(&minutes, &minutes), 1.0);
+        assert!(signature_similarity(&minutes, &edited) > 0.6);
+        assert!(signature_similarity(&minutes, &unrelated) < 0.2);
+        assert_eq!(minhash_signature(" -- "), None);
+        assert_eq!(signature_similarity(&minutes, &[]), 0.0);
+    }
+}
@@ -10,35 +10,135 @@
 
 use std::{
     collections::{HashMap, HashSet},
-    ffi::OsStr,
     fs,
     path::{Component, Path, PathBuf},
 };
 
 use anyhow::{anyhow, Context, Result};
+use app_storage::sqlite_ext::{vector_support, VectorSupport};
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::Serialize;
 use vault_indexing_api::VaultIndexingRuntime;
 use walkdir::WalkDir;
 
+mod activity;
+mod aliases;
+mod calendar;
 mod chunking;
+mod citations;
+mod cloud_files;
+mod consistency;
+mod context;
+mod dates;
+mod diagnostics;
+mod drawings;
 mod embedding;
+mod embedding_coverage;
+mod embedding_providers;
 mod files;
+mod find_replace;
+mod fts_tokenizer;
+mod geo;
+mod graph_delta;
+mod graph_export;
+mod health;
 mod links;
+mod maintenance;
+mod metrics;
+mod minhash;
+mod note_status;
+mod people;
+mod properties;
+mod query;
+mod query_block;
+mod related_ranking;
+mod run_guard;
+mod script;
 mod search;
+mod skipped_files;
 mod sync;
 mod tags;
+mod tombstones;
+mod vault_compare;
+mod vault_lock;
 
+pub use activity::{
+    get_activity_heatmap, get_writing_stats, ActivityDay, ActivityHeatmap, NoteWritingStats,
+    WritingStats,
+};
+pub use calendar::{format_daily_note_name, get_calendar_data, CalendarDay, CalendarNote};
+pub use citations::{
+    format_citation, get_citing_notes, search_citations, CitationAuthor, CitationEntry,
+    CitationStyle,
+};
+pub use cloud_files::{
+    is_cloud_placeholder, materialize_cloud_placeholder, CLOUD_PLACEHOLDER_SKIP_REASON,
+};
+pub use consistency::{run_consistency_sweep, ConsistencySweep};
+pub use context::{build_context, build_context_where, ContextRequest, ContextSource, NoteContext};
+pub use diagnostics::{run_vault_diagnostics, CaseConflict, DuplicateBasename, VaultDiagnostics};
+pub use embedding::EmbeddingProfile;
 use embedding::{resolve_embedding_dimension, EmbeddingClient};
-use files::collect_markdown_files;
+pub use embedding_coverage::{get_embedding_coverage, EmbeddingCoverage};
+pub use embedding_providers::{
+    check_embedding_provider, EmbeddingProviderCheck, EmbeddingProviderRegistry,
+};
+use files::{collect_markdown_files, disk_path};
+pub use find_replace::{
+    find_in_vault, replace_in_vault, search_in_note, undo_last_replace, FindOptions, NoteMatch,
+    NoteSearchResult, ReplacedFile, UndoReplaceResult, VaultFindResult, VaultMatch,
+    VaultReplaceResult,
+};
+pub use fts_tokenizer::apply_fts_tokenizer;
+pub use geo::{get_notes_in_bounds, get_notes_near, GeoBounds, GeoNote};
+pub use graph_delta::{GraphDelta, GraphEdgeKey, GraphTracker};
+pub use graph_export::{export_graph, GraphExportFormat, GraphExportOptions, GraphExportReport};
+pub use health::{get_vault_health, OversizedNote, VaultHealth};
 use links::resolve_wiki_link_target;
-pub use search::{search_notes_by_tag, search_notes_for_query, SemanticNoteEntry, TagNoteEntry};
+pub use maintenance::{run_maintenance_batch, MaintenanceBatch, MaintenanceTask};
+pub use metrics::{
+    get_indexing_metrics, IndexingMetrics, LatencySnapshot, VaultIndexSize, LATENCY_BUCKETS_SECONDS,
+};
+pub use note_status::{get_note_index_status, NoteIndexStatus};
+pub use people::{suggest_person_links, PersonLinkSuggestion};
+pub use query::{
+    query_notes, NoteQuery, NoteQueryField, NoteQueryResult, NoteQueryRow, NoteQuerySort,
+    NoteQuerySource,
+};
+pub use query_block::{
+    find_query_dependents, parse_query_block, render_query_block, QUERY_BLOCK_LANGUAGE,
+};
+use related_ranking::RelatedNoteRanking;
+pub use run_guard::{IndexRunGuard, IndexRunStatus};
+pub use search::{
+    get_note_aliases, get_note_tags, group_search_results, search_notes_by_tag,
+    search_notes_for_query, search_notes_for_query_in_scope, search_notes_for_query_with_options,
+    search_notes_for_query_with_profiles, search_notes_with_correction, search_segments,
+    search_segments_where, CorrectedSearchResults, MatchedPassage, SearchGroupBy, SearchMode,
+    SearchOptions, SearchResultGroup, SearchScope, SearchSort, SegmentMatch, SemanticNoteEntry,
+    TagNoteEntry, DEFAULT_CONTENT_MAX_BYTES, REGEX_QUERY_PREFIX,
+};
+pub use skipped_files::{SkipErrorCode, SkippedFile};
 use sync::{
-    clear_segment_vectors_for_vault, sync_documents_with_prune, sync_embeddings_for_prepared,
+    clear_pending_embeddings, clear_segment_vectors_for_vault, count_pending_embeddings,
+    load_indexing_options, load_note_aliases, load_pending_embedding_rel_paths, plan_warm_start,
+    queue_pending_embeddings_for_prepared, sync_documents_with_prune, sync_embeddings_for_prepared,
+    WarmStart,
+};
+pub use vault_compare::{
+    compare_vaults, copy_vault_files, VaultComparison, VaultCopyReport, VaultFileComparison,
+    VaultFileStatus,
+};
+pub use vault_indexing_api::{
+    BacklinkEntry, BacklinkMention, ResolveWikiLinkRequest, ResolveWikiLinkResult,
+};
+use vault_lock::{acquire_vault_lock, live_foreign_vault_lock};
+pub use vault_lock::{
+    break_vault_lock, get_vault_lock, set_vault_lock_owner, VaultLockInfo, VaultLockedError,
+    VAULT_LOCK_PATH, VAULT_LOCK_STALE_AFTER,
 };
-pub use vault_indexing_api::{BacklinkEntry, ResolveWikiLinkRequest, ResolveWikiLinkResult};
 
-const TARGET_CHUNKING_VERSION: i64 = 1;
+const TARGET_CHUNKING_VERSION: i64 = 5;
 const SEGMENT_VEC_TABLE: &str = "segment_vec";
 const MIN_RELATED_NOTE_SCORE: f32 = 0.4;
 
@@ -54,18 +154,30 @@ pub struct IndexSummary {
     /// `doc` rows that were deleted because the file disappeared or
     /// a forced re-index was requested.
     pub docs_deleted: usize,
+    /// Inserted `doc` rows that took back the id of a recently deleted note.
+    pub docs_revived: usize,
     /// New `segment` rows created while chunking documents.
     pub segments_created: usize,
     /// Existing segments whose content hash changed.
     pub segments_updated: usize,
     /// Number of embeddings written or refreshed.
     pub embeddings_written: usize,
+    /// Embeddings reused from the cache instead of asking the provider.
+    pub embeddings_cached: usize,
     /// Links written or refreshed.
     pub links_written: usize,
     /// Links deleted before refresh.
     pub links_deleted: usize,
-    /// Detailed per-file errors that prevented indexing.
-    pub skipped_files: Vec<String>,
+    /// Documents queued for a later embedding pass because the provider
+    /// could not be reached or failed for that document.
+    pub embeddings_pending: usize,
+    /// Files that could not be indexed, with why and whether a retry may help.
+    pub skipped_files: Vec<SkippedFile>,
+    /// Files over the vault's size cap whose head alone was indexed.
+    pub truncated_files: Vec<String>,
+    /// The run was not started because another was active; that run repeats
+    /// once it finishes.
+    pub queued: bool,
 }
 
 /// Lightweight metadata returned for quick status checks.
@@ -73,15 +185,25 @@ pub struct IndexSummary {
 #[serde(rename_all = "camelCase")]
 pub struct IndexingMeta {
     pub indexed_doc_count: usize,
+    /// Always `Idle` here; callers holding an [`IndexRunGuard`] fill it in.
+    pub run_status: IndexRunStatus,
+    /// When unavailable, search falls back to keywords and no embeddings are
+    /// written.
+    pub vector_search: VectorSupport,
+    /// Another process writing this vault's index, which makes index writes
+    /// here fail until it finishes or its lock goes stale.
+    pub index_lock: Option<VaultLockInfo>,
 }
 
 pub(crate) struct EmbeddingContext {
     pub(crate) embedder: EmbeddingClient,
     pub(crate) target_dim: i32,
+    /// Limit of the shared embedding cache; zero disables it.
+    pub(crate) cache_max_entries: usize,
 }
 
 fn open_indexing_connection(db_path: &Path) -> Result<Connection> {
-    app_storage::sqlite_ext::register_auto_extension()?;
+    vector_support();
 
     let conn = Connection::open(db_path)
         .with_context(|| format!("Failed to open indexing database at {}", db_path.display()))?;
@@ -92,19 +214,56 @@ fn open_indexing_connection(db_path: &Path) -> Result<Connection> {
     Ok(conn)
 }
 
+/// Builds an embedding context from the first reachable profile.
+///
+/// Profiles are tried in order so a secondary provider can take over when the
+/// primary one is offline. The last error is returned when none of them work.
 fn create_embedding_context(
-    embedding_provider: &str,
-    embedding_model: &str,
+    db_path: &Path,
+    embedding_profiles: &[EmbeddingProfile],
 ) -> Result<Option<EmbeddingContext>> {
-    let has_embedding_config =
-        !embedding_provider.trim().is_empty() && !embedding_model.trim().is_empty();
-
-    if !has_embedding_config {
+    if !embedding_profiles
+        .iter()
+        .any(|profile| profile.is_configured())
+    {
+        return Ok(None);
+    }
+    // Without sqlite-vec the vectors could be neither stored nor searched, so
+    // documents are indexed for keyword search alone.
+    if !vector_support().available {
         return Ok(None);
     }
 
-    // Resolve embedding dimension by generating a test embedding.
-    let target_dim = resolve_embedding_dimension(embedding_provider, embedding_model)?;
+    let registry = EmbeddingProviderRegistry::load(db_path)?;
+    let cache_max_entries = app_storage::embedding_cache::get_embedding_cache_max_entries(db_path)?;
+    let mut last_error = None;
+
+    for profile in embedding_profiles
+        .iter()
+        .filter(|profile| profile.is_configured())
+    {
+        match create_embedding_context_for_profile(&registry, profile, cache_max_entries) {
+            Ok(context) => return Ok(Some(context)),
+            Err(error) => last_error = Some(error),
+        }
+    }
+
+    match last_error {
+        Some(error) => Err(error),
+        None => Ok(None),
+    }
+}
+
+fn create_embedding_context_for_profile(
+    registry: &EmbeddingProviderRegistry,
+    profile: &EmbeddingProfile,
+    cache_max_entries: usize,
+) -> Result<EmbeddingContext> {
+    // Embedder handles communication with the chosen vector backend.
+    let embedder = EmbeddingClient::new(registry, &profile.provider, &profile.model)?;
+
+    // Resolve embedding dimension, generating a test embedding if needed.
+    let target_dim = resolve_embedding_dimension(&embedder)?;
 
     if target_dim <= 0 {
         return Err(anyhow!(
@@ -113,12 +272,18 @@ fn create_embedding_context(
         ));
     }
 
-    // Embedder handles communication with the chosen vector backend.
-    let embedder = EmbeddingClient::new(embedding_provider, embedding_model)?;
-    Ok(Some(EmbeddingContext {
+    Ok(EmbeddingContext {
         embedder,
         target_dim,
-    }))
+        cache_max_entries,
+    })
+}
+
+fn single_embedding_profile(
+    embedding_provider: &str,
+    embedding_model: &str,
+) -> Vec<EmbeddingProfile> {
+    vec![EmbeddingProfile::new(embedding_provider, embedding_model)]
 }
 
 fn canonicalize_workspace_root(workspace_root: &Path) -> Result<PathBuf> {
@@ -137,19 +302,12 @@ fn canonicalize_workspace_root(workspace_root: &Path) -> Result<PathBuf> {
     })
 }
 
-fn is_markdown_or_mdx(path: &Path) -> bool {
-    matches!(
-        path.extension().and_then(OsStr::to_str),
-        Some(ext) if ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("mdx")
-    )
-}
-
 fn collect_workspace_rel_paths_for_wiki_resolution(workspace_root: &Path) -> Result<Vec<String>> {
     let mut rel_paths = Vec::new();
 
     for entry in WalkDir::new(workspace_root).follow_links(false) {
         let entry = entry.with_context(|| "Failed to traverse workspace for wiki resolution")?;
-        if entry.file_type().is_dir() || !is_markdown_or_mdx(entry.path()) {
+        if entry.file_type().is_dir() || !note::is_indexed_path(entry.path()) {
             continue;
         }
 
@@ -207,17 +365,39 @@ pub(super) fn find_vault_id(conn: &Connection, workspace_root: &Path) -> Result<
     app_storage::vault::find_workspace_id(conn, workspace_root)
 }
 
-#[derive(Debug, Default, Clone, Copy)]
-pub struct VaultIndexingRuntimeAdapter;
+/// Indexing runtime for the vault watcher. Writes go through `run_guard`, so
+/// sharing the guard with other callers keeps them from overlapping.
+#[derive(Debug, Default, Clone)]
+pub struct VaultIndexingRuntimeAdapter {
+    run_guard: IndexRunGuard,
+}
+
+impl VaultIndexingRuntimeAdapter {
+    pub fn new(run_guard: IndexRunGuard) -> Self {
+        Self { run_guard }
+    }
+}
 
 impl VaultIndexingRuntime for VaultIndexingRuntimeAdapter {
     fn index_vault_documents(&self, workspace_root: &Path, db_path: &Path) -> Result<()> {
-        crate::vault_indexing::index_vault_documents(workspace_root, db_path, "", "", false)
+        self.run_guard
+            .run_workspace(workspace_root, false, |force_reindex| {
+                crate::vault_indexing::index_vault_documents(
+                    workspace_root,
+                    db_path,
+                    "",
+                    "",
+                    force_reindex,
+                )
+            })
             .map(|_| ())
     }
 
     fn index_note(&self, workspace_root: &Path, db_path: &Path, note_path: &Path) -> Result<()> {
-        crate::vault_indexing::index_note(workspace_root, db_path, note_path, "", "").map(|_| ())
+        self.run_guard.run_exclusive(workspace_root, || {
+            crate::vault_indexing::index_note(workspace_root, db_path, note_path, "", "")
+                .map(|_| ())
+        })
     }
 
     fn delete_indexed_note(
@@ -226,7 +406,10 @@ impl VaultIndexingRuntime for VaultIndexingRuntimeAdapter {
         db_path: &Path,
         note_path: &Path,
     ) -> Result<()> {
-        crate::vault_indexing::delete_indexed_note(workspace_root, db_path, note_path).map(|_| ())
+        self.run_guard.run_exclusive(workspace_root, || {
+            crate::vault_indexing::delete_indexed_note(workspace_root, db_path, note_path)
+                .map(|_| ())
+        })
     }
 
     fn delete_indexed_notes_by_prefix(
@@ -235,8 +418,14 @@ impl VaultIndexingRuntime for VaultIndexingRuntimeAdapter {
         db_path: &Path,
         path_prefix: &Path,
     ) -> Result<()> {
-        crate::vault_indexing::delete_indexed_notes_by_prefix(workspace_root, db_path, path_prefix)
+        self.run_guard.run_exclusive(workspace_root, || {
+            crate::vault_indexing::delete_indexed_notes_by_prefix(
+                workspace_root,
+                db_path,
+                path_prefix,
+            )
             .map(|_| ())
+        })
     }
 
     fn rename_indexed_note(
@@ -246,13 +435,15 @@ impl VaultIndexingRuntime for VaultIndexingRuntimeAdapter {
         old_note_path: &Path,
         new_note_path: &Path,
     ) -> Result<()> {
-        crate::vault_indexing::rename_indexed_note(
-            workspace_root,
-            db_path,
-            old_note_path,
-            new_note_path,
-        )
-        .map(|_| ())
+        self.run_guard.run_exclusive(workspace_root, || {
+            crate::vault_indexing::rename_indexed_note(
+                workspace_root,
+                db_path,
+                old_note_path,
+                new_note_path,
+            )
+            .map(|_| ())
+        })
     }
 
     fn get_backlinks(
@@ -264,6 +455,23 @@ impl VaultIndexingRuntime for VaultIndexingRuntimeAdapter {
         crate::vault_indexing::get_backlinks(workspace_root, db_path, file_path)
     }
 
+    fn drain_pending_embeddings(&self, workspace_root: &Path, db_path: &Path) -> Result<()> {
+        let Some(config) = app_storage::vault::get_embedding_config(db_path, workspace_root)?
+        else {
+            return Ok(());
+        };
+
+        let profiles = config
+            .profiles()
+            .into_iter()
+            .map(|(provider, model)| EmbeddingProfile::new(provider, model))
+            .collect::<Vec<_>>();
+        self.run_guard.run_exclusive(workspace_root, || {
+            crate::vault_indexing::drain_pending_embeddings(workspace_root, db_path, &profiles)
+                .map(|_| ())
+        })
+    }
+
     fn resolve_wiki_link(&self, request: ResolveWikiLinkRequest) -> Result<ResolveWikiLinkResult> {
         crate::vault_indexing::resolve_wiki_link(request)
     }
@@ -275,14 +483,27 @@ pub fn index_vault_documents(
     embedding_provider: &str,
     embedding_model: &str,
     force_reindex: bool,
+) -> Result<IndexSummary> {
+    index_vault_documents_with_profiles(
+        workspace_root,
+        db_path,
+        &single_embedding_profile(embedding_provider, embedding_model),
+        force_reindex,
+    )
+}
+
+pub fn index_vault_documents_with_profiles(
+    workspace_root: &Path,
+    db_path: &Path,
+    embedding_profiles: &[EmbeddingProfile],
+    force_reindex: bool,
 ) -> Result<IndexSummary> {
     let _ = canonicalize_workspace_root(workspace_root)?;
     let markdown_files = collect_markdown_files(workspace_root)?;
     run_indexing_for_files(
         workspace_root,
         db_path,
-        embedding_provider,
-        embedding_model,
+        embedding_profiles,
         markdown_files,
         true,
         force_reindex,
@@ -295,31 +516,97 @@ pub fn refresh_workspace_embeddings(
     embedding_provider: &str,
     embedding_model: &str,
 ) -> Result<IndexSummary> {
-    let _ = canonicalize_workspace_root(workspace_root)?;
-    let files = collect_markdown_files(workspace_root)?;
-    run_embedding_refresh_for_files(
+    refresh_workspace_embeddings_with_profiles(
         workspace_root,
         db_path,
-        embedding_provider,
-        embedding_model,
-        files,
+        &single_embedding_profile(embedding_provider, embedding_model),
     )
 }
 
-fn is_markdown_path(path: &Path) -> bool {
-    matches!(
-        path.extension().and_then(|ext| ext.to_str()),
-        Some(ext) if ext.eq_ignore_ascii_case("md")
-    )
+pub fn refresh_workspace_embeddings_with_profiles(
+    workspace_root: &Path,
+    db_path: &Path,
+    embedding_profiles: &[EmbeddingProfile],
+) -> Result<IndexSummary> {
+    let _ = canonicalize_workspace_root(workspace_root)?;
+    let files = collect_markdown_files(workspace_root)?;
+    run_embedding_refresh_for_files(workspace_root, db_path, embedding_profiles, files)
+}
+
+/// Embeds documents that were queued while the embedding provider was unreachable.
+///
+/// Returns an error when no profile can be reached so callers can retry later;
+/// the queue is left untouched in that case.
+pub fn drain_pending_embeddings(
+    workspace_root: &Path,
+    db_path: &Path,
+    embedding_profiles: &[EmbeddingProfile],
+) -> Result<IndexSummary> {
+    let _ = canonicalize_workspace_root(workspace_root)?;
+    let _vault_lock = acquire_vault_lock(workspace_root)?;
+    let mut conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(IndexSummary::default());
+    };
+
+    let pending = load_pending_embedding_rel_paths(&conn, vault_id)?;
+    let mut summary = IndexSummary {
+        files_discovered: pending.len(),
+        embeddings_pending: pending.len(),
+        ..Default::default()
+    };
+    if pending.is_empty() {
+        return Ok(summary);
+    }
+
+    let Some(embedding_context) = create_embedding_context(db_path, embedding_profiles)? else {
+        return Ok(summary);
+    };
+
+    let options = load_indexing_options(&conn, vault_id)?;
+    let mut missing_doc_ids = Vec::new();
+    let mut prepared_documents = Vec::with_capacity(pending.len());
+    for (doc_id, rel_path) in pending {
+        let abs_path = disk_path(workspace_root, &rel_path);
+        if !abs_path.is_file() {
+            missing_doc_ids.push(doc_id);
+            continue;
+        }
+
+        let file = files::MarkdownFile::from_abs_and_rel(abs_path.clone(), rel_path);
+        match sync::PreparedDocument::load(file, &options) {
+            Ok(prepared) => prepared_documents.push(prepared),
+            Err(error) => {
+                summary
+                    .skipped_files
+                    .push(SkippedFile::read_failed(&abs_path, &error));
+            }
+        }
+    }
+
+    // Files removed since they were queued are pruned by the next full index run.
+    clear_pending_embeddings(&conn, &missing_doc_ids)?;
+
+    sync_embeddings_for_prepared(
+        &mut conn,
+        vault_id,
+        &prepared_documents,
+        &embedding_context,
+        &mut summary,
+        true,
+    )?;
+
+    summary.embeddings_pending = count_pending_embeddings(&conn, vault_id)?;
+    Ok(summary)
 }
 
 fn build_single_markdown_file(
     workspace_root: &Path,
     note_path: &Path,
 ) -> Result<files::MarkdownFile> {
-    if !is_markdown_path(note_path) {
+    if !note::is_indexed_path(note_path) {
         return Err(anyhow!(
-            "Note path must point to a markdown file (.md): {}",
+            "Note path must point to a markdown file (.md) or a drawing: {}",
             note_path.display()
         ));
     }
@@ -343,9 +630,9 @@ fn build_single_markdown_file(
 }
 
 fn to_workspace_rel_markdown_path(workspace_root: &Path, note_path: &Path) -> Result<String> {
-    if !is_markdown_path(note_path) {
+    if !note::is_indexed_path(note_path) {
         return Err(anyhow!(
-            "Note path must point to a markdown file (.md): {}",
+            "Note path must point to a markdown file (.md) or a drawing: {}",
             note_path.display()
         ));
     }
@@ -420,19 +707,36 @@ fn escape_sql_like_pattern(value: &str) -> String {
 fn run_indexing_for_files(
     workspace_root: &Path,
     db_path: &Path,
-    embedding_provider: &str,
-    embedding_model: &str,
+    embedding_profiles: &[EmbeddingProfile],
     files: Vec<files::MarkdownFile>,
     prune_deleted_docs: bool,
     force_reindex: bool,
 ) -> Result<IndexSummary> {
-    let embedding_context = create_embedding_context(embedding_provider, embedding_model)?;
+    let _span = tracing::info_span!(
+        "index_run",
+        vault = %workspace_root.display(),
+        files = files.len(),
+        force_reindex
+    )
+    .entered();
+    let _vault_lock = acquire_vault_lock(workspace_root)?;
+    // An unreachable provider must not block text, FTS, and link indexing; the
+    // affected documents are queued and embedded by a later retry instead.
+    let (embedding_context, embedding_error) =
+        match create_embedding_context(db_path, embedding_profiles) {
+            Ok(context) => (context, None),
+            Err(error) => {
+                tracing::warn!("embedding provider unavailable, queueing embeddings: {error:#}");
+                (None, Some(format!("{error:#}")))
+            }
+        };
     let mut conn = open_indexing_connection(db_path)?;
     let vault_id = app_storage::vault::ensure_workspace_exists(&conn, workspace_root)?;
+    fts_tokenizer::sync_fts_tokenizer(&mut conn, vault_id)?;
 
     // Force reindex wipes doc/segment tables so they can be recreated cleanly.
     let reset_deleted = if force_reindex {
-        if embedding_context.is_some() {
+        if embedding_context.is_some() || embedding_error.is_some() {
             clear_segment_vectors_for_vault(&conn, vault_id)?;
         }
         clear_index(&conn, vault_id)?
@@ -446,6 +750,27 @@ fn run_indexing_for_files(
         ..Default::default()
     };
 
+    // A full run over an unchanged tree is settled from the doc rows alone,
+    // skipping per-document link, alias, and segment queries.
+    let mut prune_deleted_docs = prune_deleted_docs;
+    let files = if prune_deleted_docs && !force_reindex {
+        match plan_warm_start(&conn, vault_id, files, embedding_context.as_ref())? {
+            WarmStart::Current => {
+                summary.files_processed = summary.files_discovered;
+                tracing::debug!("index is current");
+                return Ok(summary);
+            }
+            WarmStart::Changed(changed) => {
+                summary.files_processed = summary.files_discovered - changed.len();
+                prune_deleted_docs = false;
+                changed
+            }
+            WarmStart::Full(files) => files,
+        }
+    } else {
+        files
+    };
+
     let prepared_documents = sync_documents_with_prune(
         &mut conn,
         workspace_root,
@@ -455,6 +780,7 @@ fn run_indexing_for_files(
         &mut summary,
         prune_deleted_docs,
     )?;
+    metrics::record_documents_indexed(prepared_documents.len());
 
     if let Some(embedding_context) = embedding_context.as_ref() {
         sync_embeddings_for_prepared(
@@ -465,19 +791,40 @@ fn run_indexing_for_files(
             &mut summary,
             false,
         )?;
+    } else if let Some(error) = embedding_error.as_deref() {
+        queue_pending_embeddings_for_prepared(
+            &conn,
+            vault_id,
+            &prepared_documents,
+            error,
+            &mut summary,
+        )?;
     }
 
+    tracing::info!(
+        processed = summary.files_processed,
+        inserted = summary.docs_inserted,
+        deleted = summary.docs_deleted,
+        embeddings_written = summary.embeddings_written,
+        embeddings_pending = summary.embeddings_pending,
+        skipped = summary.skipped_files.len(),
+        truncated = summary.truncated_files.len(),
+        "index run finished"
+    );
+    for skipped in &summary.skipped_files {
+        tracing::warn!("skipped {skipped}");
+    }
     Ok(summary)
 }
 
 fn run_embedding_refresh_for_files(
     workspace_root: &Path,
     db_path: &Path,
-    embedding_provider: &str,
-    embedding_model: &str,
+    embedding_profiles: &[EmbeddingProfile],
     files: Vec<files::MarkdownFile>,
 ) -> Result<IndexSummary> {
-    let embedding_context = create_embedding_context(embedding_provider, embedding_model)?;
+    let _vault_lock = acquire_vault_lock(workspace_root)?;
+    let embedding_context = create_embedding_context(db_path, embedding_profiles)?;
     let mut summary = IndexSummary {
         files_discovered: files.len(),
         ..Default::default()
@@ -487,24 +834,25 @@ fn run_embedding_refresh_for_files(
         return Ok(summary);
     };
 
+    let mut conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(summary);
+    };
+
+    let options = load_indexing_options(&conn, vault_id)?;
     let mut prepared_documents = Vec::with_capacity(files.len());
     for file in files {
         let abs_path = file.abs_path.clone();
-        match sync::PreparedDocument::load(file) {
+        match sync::PreparedDocument::load(file, &options) {
             Ok(prepared) => prepared_documents.push(prepared),
             Err(error) => {
                 summary
                     .skipped_files
-                    .push(format!("{}: {}", abs_path.display(), error));
+                    .push(SkippedFile::read_failed(&abs_path, &error));
             }
         }
     }
 
-    let mut conn = open_indexing_connection(db_path)?;
-    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
-        return Ok(summary);
-    };
-
     sync_embeddings_for_prepared(
         &mut conn,
         vault_id,
@@ -523,14 +871,48 @@ pub fn index_note(
     note_path: &Path,
     embedding_provider: &str,
     embedding_model: &str,
+) -> Result<IndexSummary> {
+    index_note_with_profiles(
+        workspace_root,
+        db_path,
+        note_path,
+        &single_embedding_profile(embedding_provider, embedding_model),
+    )
+}
+
+pub fn index_note_with_profiles(
+    workspace_root: &Path,
+    db_path: &Path,
+    note_path: &Path,
+    embedding_profiles: &[EmbeddingProfile],
+) -> Result<IndexSummary> {
+    let _ = canonicalize_workspace_root(workspace_root)?;
+    let file = build_single_markdown_file(workspace_root, note_path)?;
+    run_indexing_for_files(
+        workspace_root,
+        db_path,
+        embedding_profiles,
+        vec![file],
+        false,
+        false,
+    )
+}
+
+/// Indexes one note from scratch, even if it has not changed since it was
+/// last indexed.
+pub fn reindex_note_with_profiles(
+    workspace_root: &Path,
+    db_path: &Path,
+    note_path: &Path,
+    embedding_profiles: &[EmbeddingProfile],
 ) -> Result<IndexSummary> {
     let _ = canonicalize_workspace_root(workspace_root)?;
     let file = build_single_markdown_file(workspace_root, note_path)?;
+    note_status::reset_note_index_state(workspace_root, db_path, &file.rel_path)?;
     run_indexing_for_files(
         workspace_root,
         db_path,
-        embedding_provider,
-        embedding_model,
+        embedding_profiles,
         vec![file],
         false,
         false,
@@ -545,16 +927,19 @@ pub fn delete_indexed_note(
     let _ = canonicalize_workspace_root(workspace_root)?;
     let rel_path = to_workspace_rel_markdown_path(workspace_root, note_path)?;
 
+    let _vault_lock = acquire_vault_lock(workspace_root)?;
     let conn = open_indexing_connection(db_path)?;
     let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
         return Ok(false);
     };
 
-    let deleted = conn
-        .execute(
-            "DELETE FROM doc WHERE vault_id = ?1 AND rel_path = ?2",
-            params![vault_id, &rel_path],
-        )
+    let doc_ids = load_doc_ids(
+        &conn,
+        "SELECT id FROM doc WHERE vault_id = ?1 AND rel_path = ?2",
+        params![vault_id, &rel_path],
+    )
+    .context("Failed to load indexed note document row")?;
+    let deleted = tombstones::delete_docs(&conn, vault_id, &doc_ids)
         .context("Failed to delete indexed note document row")?;
 
     Ok(deleted > 0)
@@ -570,21 +955,32 @@ pub fn delete_indexed_notes_by_prefix(
     let escaped_prefix = escape_sql_like_pattern(&rel_prefix);
     let like_pattern = format!("{escaped_prefix}/%");
 
+    let _vault_lock = acquire_vault_lock(workspace_root)?;
     let conn = open_indexing_connection(db_path)?;
     let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
         return Ok(0);
     };
 
-    let deleted = conn
-        .execute(
-            "DELETE FROM doc WHERE vault_id = ?1 AND rel_path LIKE ?2 ESCAPE '\\'",
-            params![vault_id, like_pattern],
-        )
+    let doc_ids = load_doc_ids(
+        &conn,
+        "SELECT id FROM doc WHERE vault_id = ?1 AND rel_path LIKE ?2 ESCAPE '\\'",
+        params![vault_id, like_pattern],
+    )
+    .context("Failed to load indexed notes by path prefix")?;
+    let deleted = tombstones::delete_docs(&conn, vault_id, &doc_ids)
         .context("Failed to delete indexed notes by path prefix")?;
 
     Ok(deleted)
 }
 
+fn load_doc_ids(conn: &Connection, sql: &str, params: impl rusqlite::Params) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare(sql)?;
+    let doc_ids = stmt
+        .query_map(params, |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<i64>>>()?;
+    Ok(doc_ids)
+}
+
 pub fn rename_indexed_note(
     workspace_root: &Path,
     db_path: &Path,
@@ -599,6 +995,7 @@ pub fn rename_indexed_note(
         return Ok(false);
     }
 
+    let _vault_lock = acquire_vault_lock(workspace_root)?;
     let mut conn = open_indexing_connection(db_path)?;
     let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
         return Ok(false);
@@ -655,14 +1052,16 @@ pub fn get_indexing_meta(workspace_root: &Path, db_path: &Path) -> Result<Indexi
     let _ = canonicalize_workspace_root(workspace_root)?;
     let conn = open_indexing_connection(db_path)?;
 
-    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
-        return Ok(IndexingMeta {
-            indexed_doc_count: 0,
-        });
+    let indexed_doc_count = match find_vault_id(&conn, workspace_root)? {
+        Some(vault_id) => count_indexed_docs(&conn, vault_id)?,
+        None => 0,
     };
 
     Ok(IndexingMeta {
-        indexed_doc_count: count_indexed_docs(&conn, vault_id)?,
+        indexed_doc_count,
+        vector_search: vector_support().clone(),
+        index_lock: live_foreign_vault_lock(workspace_root),
+        ..Default::default()
     })
 }
 
@@ -688,17 +1087,45 @@ fn count_indexed_docs(conn: &Connection, vault_id: i64) -> Result<usize> {
 
 pub fn resolve_wiki_link(request: ResolveWikiLinkRequest) -> Result<ResolveWikiLinkResult> {
     let workspace_root = canonicalize_workspace_root(Path::new(&request.workspace_path))?;
+    resolve_wiki_link_in_workspace(&workspace_root, request, &HashMap::new())
+}
+
+/// Like [`resolve_wiki_link`], but also resolves `[[Alias]]` through the
+/// frontmatter aliases stored in the index.
+pub fn resolve_wiki_link_with_index(
+    request: ResolveWikiLinkRequest,
+    db_path: &Path,
+) -> Result<ResolveWikiLinkResult> {
+    let workspace_root = canonicalize_workspace_root(Path::new(&request.workspace_path))?;
+    let note_aliases = if db_path.exists() {
+        let conn = open_indexing_connection(db_path)?;
+        match find_vault_id(&conn, &workspace_root)? {
+            Some(vault_id) => load_note_aliases(&conn, vault_id)?,
+            None => HashMap::new(),
+        }
+    } else {
+        HashMap::new()
+    };
+
+    resolve_wiki_link_in_workspace(&workspace_root, request, &note_aliases)
+}
 
+fn resolve_wiki_link_in_workspace(
+    workspace_root: &Path,
+    request: ResolveWikiLinkRequest,
+    note_aliases: &HashMap<String, Vec<String>>,
+) -> Result<ResolveWikiLinkResult> {
     let rel_paths = match request.workspace_rel_paths {
         Some(paths) if !paths.is_empty() => sanitize_workspace_rel_paths(paths),
-        _ => collect_workspace_rel_paths_for_wiki_resolution(&workspace_root)?,
+        _ => collect_workspace_rel_paths_for_wiki_resolution(workspace_root)?,
     };
 
     let resolved = resolve_wiki_link_target(
-        &workspace_root,
+        workspace_root,
         request.current_note_path.as_deref(),
         &request.raw_target,
         &rel_paths,
+        note_aliases,
     );
 
     Ok(ResolveWikiLinkResult {
@@ -707,6 +1134,7 @@ pub fn resolve_wiki_link(request: ResolveWikiLinkRequest) -> Result<ResolveWikiL
         match_count: resolved.match_count,
         disambiguated: resolved.disambiguated,
         unresolved: resolved.unresolved,
+        resolved_via_alias: resolved.resolved_via_alias,
     })
 }
 
@@ -718,6 +1146,12 @@ pub struct RelatedNoteEntry {
     pub rel_path: String,
     /// Filename without extension for display purposes.
     pub file_name: String,
+    /// Best cosine similarity between a segment of this note and one of the
+    /// source note.
+    pub similarity: f32,
+    /// Score the entries are ordered by: `similarity`, decayed by the note's
+    /// age when the vault's `relatedNotesRecency` setting is on.
+    pub score: f32,
 }
 
 #[derive(Debug, Default, Serialize)]
@@ -727,7 +1161,7 @@ pub struct GraphViewData {
     pub edges: Vec<GraphEdge>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GraphNode {
     pub id: String,
@@ -736,7 +1170,7 @@ pub struct GraphNode {
     pub unresolved: bool,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GraphEdge {
     pub source: String,
@@ -816,6 +1250,7 @@ pub fn get_backlinks(
             Ok(BacklinkEntry {
                 rel_path,
                 file_name,
+                mentions: Vec::new(),
             })
         })
         .context("Failed to query backlinks")?;
@@ -824,9 +1259,89 @@ pub fn get_backlinks(
         backlinks.push(row?);
     }
 
+    let mut mentions_by_path = load_backlink_mentions(&conn, vault_id, target_doc_id, &rel_path)?;
+    for backlink in &mut backlinks {
+        if let Some(mentions) = mentions_by_path.remove(&backlink.rel_path) {
+            backlink.mentions = mentions;
+        }
+    }
+
     Ok(backlinks)
 }
 
+fn load_backlink_mentions(
+    conn: &Connection,
+    vault_id: i64,
+    target_doc_id: Option<i64>,
+    target_rel_path: &str,
+) -> Result<HashMap<String, Vec<BacklinkMention>>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT d.rel_path, m.start_offset, m.end_offset, m.line, m.context, m.context_start \
+             FROM link l \
+             JOIN link_mention m ON m.link_id = l.id \
+             JOIN doc d ON d.id = l.source_doc_id \
+             WHERE d.vault_id = ?1 \
+               AND (l.target_doc_id = ?2 \
+                    OR (l.target_doc_id IS NULL AND l.target_path = ?3)) \
+             ORDER BY d.rel_path, m.start_offset",
+        )
+        .context("Failed to prepare backlink mention query")?;
+    let rows = stmt
+        .query_map(params![vault_id, target_doc_id, target_rel_path], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                BacklinkMention {
+                    start: row.get::<_, i64>(1)? as usize,
+                    end: row.get::<_, i64>(2)? as usize,
+                    line: row.get::<_, i64>(3)? as usize,
+                    context: row.get(4)?,
+                    context_start: row.get::<_, i64>(5)? as usize,
+                },
+            ))
+        })
+        .context("Failed to query backlink mentions")?;
+
+    let mut mentions_by_path: HashMap<String, Vec<BacklinkMention>> = HashMap::new();
+    for row in rows {
+        let (rel_path, mention) = row?;
+        mentions_by_path.entry(rel_path).or_default().push(mention);
+    }
+    Ok(mentions_by_path)
+}
+
+/// Number of notes linking to each of `rel_paths`, read from the count the
+/// index keeps on every note, for badges in the file tree. Paths that are not
+/// indexed are left out. Paths are looked up by their NFC key, as the writers
+/// store them, and returned as the caller spelled them.
+pub fn get_link_counts(
+    workspace_root: &Path,
+    db_path: &Path,
+    rel_paths: &[String],
+) -> Result<HashMap<String, usize>> {
+    let conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(HashMap::new());
+    };
+
+    let mut stmt = conn
+        .prepare("SELECT inbound_link_count FROM doc WHERE vault_id = ?1 AND rel_path = ?2")
+        .context("Failed to prepare link count query")?;
+    let mut counts = HashMap::with_capacity(rel_paths.len());
+    for rel_path in rel_paths {
+        let key = files::normalize_rel_path(Path::new(rel_path));
+        let count = stmt
+            .query_row(params![vault_id, key], |row| row.get::<_, i64>(0))
+            .optional()
+            .context("Failed to load link count")?;
+        if let Some(count) = count {
+            counts.insert(rel_path.clone(), count as usize);
+        }
+    }
+
+    Ok(counts)
+}
+
 /// Get semantically related documents using only existing indexed vectors.
 ///
 /// This reuses persisted segment vectors and does not generate new embeddings.
@@ -852,7 +1367,7 @@ pub fn get_related_notes(
         })?;
 
     let conn = open_indexing_connection(db_path)?;
-    if !segment_vec_table_exists(&conn)? {
+    if !vector_support().available || !segment_vec_table_exists(&conn)? {
         return Ok(Vec::new());
     }
 
@@ -885,7 +1400,14 @@ pub fn get_related_notes(
         return Ok(Vec::new());
     }
 
-    let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+    let ranking = RelatedNoteRanking::load(&conn, vault_id)?;
+    // A reranked list may promote notes from below the top `limit`, so every
+    // candidate above the threshold is scored.
+    let query_limit = if ranking.reorders() {
+        -1
+    } else {
+        i64::try_from(limit).unwrap_or(i64::MAX)
+    };
     let mut stmt = conn
         .prepare(
             "WITH source_segments AS ( \
@@ -897,6 +1419,7 @@ pub fn get_related_notes(
              ), \
              candidate_scores AS ( \
                  SELECT d.rel_path AS rel_path, \
+                        d.last_source_mtime_ns AS mtime_ns, \
                         MAX(1.0 - vec_distance_cosine(ss.embedding, cv.embedding)) AS score \
                  FROM doc d \
                  JOIN segment cs ON cs.doc_id = d.id \
@@ -910,7 +1433,7 @@ pub fn get_related_notes(
                    AND length(cv.embedding) = (?4 * 4) \
                  GROUP BY d.id, d.rel_path \
              ) \
-             SELECT rel_path \
+             SELECT rel_path, score, mtime_ns \
              FROM candidate_scores \
              WHERE score IS NOT NULL \
                AND score >= ?6 \
@@ -926,22 +1449,39 @@ pub fn get_related_notes(
                 source_doc_id,
                 embedding_model,
                 embedding_dim,
-                limit,
+                query_limit,
                 MIN_RELATED_NOTE_SCORE as f64
             ],
-            |row| row.get::<_, String>(0),
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, f64>(1)? as f32,
+                    row.get::<_, Option<i64>>(2)?,
+                ))
+            },
         )
         .context("Failed to query related notes")?;
 
     let mut related_notes = Vec::new();
     for row in rows {
-        let rel_path = row?;
+        let (rel_path, similarity, mtime_ns) = row?;
         let file_name = graph_node_name(&rel_path);
 
         related_notes.push(RelatedNoteEntry {
             rel_path,
             file_name,
+            similarity,
+            score: ranking.score(similarity, mtime_ns),
+        });
+    }
+    if ranking.reorders() {
+        related_notes.sort_by(|left, right| {
+            right
+                .score
+                .total_cmp(&left.score)
+                .then_with(|| left.rel_path.cmp(&right.rel_path))
         });
+        related_notes.truncate(limit);
     }
 
     Ok(related_notes)
@@ -0,0 +1,178 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use super::{
+    build_single_markdown_file, canonicalize_workspace_root, files::read_indexed_source,
+    find_vault_id, open_indexing_connection, segment_vec_table_exists, sync::load_indexing_options,
+    vault_lock::acquire_vault_lock, TARGET_CHUNKING_VERSION,
+};
+
+/// How one note stands in the index, for working out why search misses it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteIndexStatus {
+    pub rel_path: String,
+    /// The note has a row in the index, even if its content is not indexed yet.
+    pub indexed: bool,
+    /// Hash of the content as last indexed.
+    pub indexed_hash: Option<String>,
+    /// Hash of the file on disk now.
+    pub current_hash: Option<String>,
+    /// Why the file could not be read, such as a cloud placeholder.
+    pub read_error: Option<String>,
+    /// Indexed with the current content and chunking.
+    pub up_to_date: bool,
+    pub chunking_version: Option<i64>,
+    pub embedding_provider: Option<String>,
+    pub embedding_model: Option<String>,
+    pub embedding_dim: Option<i32>,
+    pub segment_count: usize,
+    /// Segments with a stored vector; fewer than `segment_count` means
+    /// semantic search only sees part of the note.
+    pub embedded_segment_count: usize,
+    /// Links out of the note, resolved or not.
+    pub link_count: usize,
+    pub unresolved_link_count: usize,
+    /// Why the last embedding attempt failed, while the note waits for a retry.
+    pub pending_embedding_error: Option<String>,
+}
+
+struct DocRow {
+    id: i64,
+    chunking_version: i64,
+    last_hash: Option<String>,
+    embedding_provider: Option<String>,
+    embedding_model: Option<String>,
+    embedding_dim: Option<i32>,
+}
+
+pub fn get_note_index_status(
+    workspace_root: &Path,
+    db_path: &Path,
+    note_path: &Path,
+) -> Result<NoteIndexStatus> {
+    let _ = canonicalize_workspace_root(workspace_root)?;
+    let file = build_single_markdown_file(workspace_root, note_path)?;
+    let conn = open_indexing_connection(db_path)?;
+    let vault_id = find_vault_id(&conn, workspace_root)?;
+
+    let options = match vault_id {
+        Some(vault_id) => load_indexing_options(&conn, vault_id)?,
+        None => Default::default(),
+    };
+    let (current_hash, read_error) =
+        match read_indexed_source(&file.abs_path, options.max_source_bytes) {
+            Ok(source) => (Some(source.hash), None),
+            Err(error) => (None, Some(format!("{error:#}"))),
+        };
+
+    let mut status = NoteIndexStatus {
+        rel_path: file.rel_path,
+        current_hash,
+        read_error,
+        ..Default::default()
+    };
+    let Some(vault_id) = vault_id else {
+        return Ok(status);
+    };
+    let Some(doc) = load_doc_row(&conn, vault_id, &status.rel_path)? else {
+        return Ok(status);
+    };
+
+    status.indexed = true;
+    status.up_to_date = doc.last_hash.is_some()
+        && doc.last_hash == status.current_hash
+        && doc.chunking_version == TARGET_CHUNKING_VERSION;
+    status.indexed_hash = doc.last_hash;
+    status.chunking_version = Some(doc.chunking_version);
+    status.embedding_provider = doc.embedding_provider;
+    status.embedding_model = doc.embedding_model;
+    status.embedding_dim = doc.embedding_dim;
+    status.segment_count = count(
+        &conn,
+        "SELECT COUNT(*) FROM segment WHERE doc_id = ?1",
+        doc.id,
+    )?;
+    if segment_vec_table_exists(&conn)? {
+        status.embedded_segment_count = count(
+            &conn,
+            "SELECT COUNT(*) FROM segment s JOIN segment_vec v ON v.rowid = s.id \
+             WHERE s.doc_id = ?1",
+            doc.id,
+        )?;
+    }
+    status.link_count = count(
+        &conn,
+        "SELECT COUNT(*) FROM link WHERE source_doc_id = ?1",
+        doc.id,
+    )?;
+    status.unresolved_link_count = count(
+        &conn,
+        "SELECT COUNT(*) FROM link WHERE source_doc_id = ?1 AND target_doc_id IS NULL",
+        doc.id,
+    )?;
+    status.pending_embedding_error = conn
+        .query_row(
+            "SELECT last_error FROM pending_embedding WHERE doc_id = ?1",
+            params![doc.id],
+            |row| row.get::<_, Option<String>>(0),
+        )
+        .optional()
+        .context("Failed to load pending embedding")?
+        .flatten();
+
+    Ok(status)
+}
+
+/// Forgets what the index knows about a note's content, so the next run
+/// re-chunks, re-embeds, and re-links it even though the file is unchanged.
+pub(crate) fn reset_note_index_state(
+    workspace_root: &Path,
+    db_path: &Path,
+    rel_path: &str,
+) -> Result<()> {
+    let _vault_lock = acquire_vault_lock(workspace_root)?;
+    let conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(());
+    };
+    conn.execute(
+        "UPDATE doc SET last_hash = NULL, last_source_size = NULL, last_source_mtime_ns = NULL, \
+                        last_embedding_provider = NULL, last_embedding_model = NULL, \
+                        last_embedding_dim = NULL \
+         WHERE vault_id = ?1 AND rel_path = ?2",
+        params![vault_id, rel_path],
+    )
+    .context("Failed to reset indexed note")?;
+    Ok(())
+}
+
+fn load_doc_row(conn: &Connection, vault_id: i64, rel_path: &str) -> Result<Option<DocRow>> {
+    conn.query_row(
+        "SELECT id, chunking_version, last_hash, last_embedding_provider, last_embedding_model, \
+                last_embedding_dim \
+         FROM doc WHERE vault_id = ?1 AND rel_path = ?2",
+        params![vault_id, rel_path],
+        |row| {
+            Ok(DocRow {
+                id: row.get(0)?,
+                chunking_version: row.get(1)?,
+                last_hash: row.get(2)?,
+                embedding_provider: row.get(3)?,
+                embedding_model: row.get(4)?,
+                embedding_dim: row.get(5)?,
+            })
+        },
+    )
+    .optional()
+    .context("Failed to load indexed note")
+}
+
+fn count(conn: &Connection, sql: &str, doc_id: i64) -> Result<usize> {
+    conn.query_row(sql, params![doc_id], |row| row.get::<_, i64>(0))
+        .map(|count| count as usize)
+        .with_context(|| format!("Failed to count rows of doc {doc_id}"))
+}
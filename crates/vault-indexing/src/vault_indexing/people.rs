@@ -0,0 +1,231 @@
+use std::{collections::HashMap, fs, ops::Range, path::Path};
+
+use anyhow::{Context, Result};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use rusqlite::params;
+use serde::Serialize;
+
+use super::{find_vault_id, links::wiki_link_spans, open_indexing_connection};
+
+/// Names shorter than this match too many ordinary words.
+const MIN_NAME_CHARS: usize = 2;
+
+/// A mention of a person that could become a wiki link to their page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersonLinkSuggestion {
+    /// Byte range of the mention in the note file.
+    pub start: usize,
+    pub end: usize,
+    /// Zero-based line of `start`.
+    pub line: usize,
+    /// Zero-based column of `start` in UTF-16 code units.
+    pub column: usize,
+    /// The mention as written.
+    pub text: String,
+    /// Rel path of the person's page.
+    pub person_rel_path: String,
+    /// Wiki link to put in place of the mention, keeping its text.
+    pub link: String,
+}
+
+struct PersonName {
+    name: String,
+    rel_path: String,
+    /// Link target that resolves to the page, its name unless another note
+    /// shares it.
+    target: String,
+}
+
+/// Capitalized mentions of the people whose pages are in `people_folder`,
+/// by page name or alias, outside code, links and frontmatter. Mentions on
+/// the person's own page are left out.
+pub fn suggest_person_links(
+    workspace_root: &Path,
+    db_path: &Path,
+    note_path: &Path,
+    people_folder: &str,
+) -> Result<Vec<PersonLinkSuggestion>> {
+    let rel_path = super::to_workspace_rel_markdown_path(workspace_root, note_path)?;
+    let contents =
+        fs::read_to_string(note_path).with_context(|| format!("Failed to read note {rel_path}"))?;
+
+    let conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+    let mut names = load_person_names(&conn, vault_id, people_folder)?;
+    names.retain(|person| person.rel_path != rel_path);
+    // Longer names win where they overlap, so "Ada Lovelace" beats "Ada".
+    names.sort_by_key(|person| std::cmp::Reverse(person.name.len()));
+
+    let mut claimed: Vec<Range<usize>> = Vec::new();
+    let mut suggestions = Vec::new();
+    for range in linkable_ranges(&contents) {
+        let text = &contents[range.clone()];
+        for person in &names {
+            for start in find_mentions(text, &person.name) {
+                let mention = range.start + start..range.start + start + person.name.len();
+                if claimed
+                    .iter()
+                    .any(|taken| taken.start < mention.end && mention.start < taken.end)
+                {
+                    continue;
+                }
+                claimed.push(mention.clone());
+                let written = &contents[mention.clone()];
+                let line_start = contents[..mention.start].rfind('\n').map_or(0, |i| i + 1);
+                suggestions.push(PersonLinkSuggestion {
+                    start: mention.start,
+                    end: mention.end,
+                    line: contents[..mention.start].matches('\n').count(),
+                    column: contents[line_start..mention.start].encode_utf16().count(),
+                    text: written.to_string(),
+                    person_rel_path: person.rel_path.clone(),
+                    link: if written == person.target {
+                        format!("[[{written}]]")
+                    } else {
+                        format!("[[{}|{written}]]", person.target)
+                    },
+                });
+            }
+        }
+    }
+    suggestions.sort_by_key(|suggestion| suggestion.start);
+    Ok(suggestions)
+}
+
+/// Page names and aliases of the indexed notes in `people_folder`.
+fn load_person_names(
+    conn: &rusqlite::Connection,
+    vault_id: i64,
+    people_folder: &str,
+) -> Result<Vec<PersonName>> {
+    let folder = people_folder.trim().trim_matches('/').replace('\\', "/");
+    let prefix = format!("{}/", folder.to_lowercase());
+
+    let mut stmt = conn
+        .prepare("SELECT rel_path FROM doc WHERE vault_id = ?1 AND last_hash IS NOT NULL")
+        .context("Failed to prepare note query")?;
+    let rel_paths = stmt
+        .query_map(params![vault_id], |row| row.get::<_, String>(0))
+        .context("Failed to load notes")?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let mut stem_counts: HashMap<String, usize> = HashMap::new();
+    for rel_path in &rel_paths {
+        *stem_counts
+            .entry(stem(rel_path).to_lowercase())
+            .or_default() += 1;
+    }
+
+    let mut alias_stmt = conn
+        .prepare(
+            "SELECT d.rel_path, da.alias \
+             FROM doc_alias da \
+             JOIN doc d ON d.id = da.doc_id \
+             WHERE d.vault_id = ?1",
+        )
+        .context("Failed to prepare note alias query")?;
+    let mut aliases: HashMap<String, Vec<String>> = HashMap::new();
+    for row in alias_stmt
+        .query_map(params![vault_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .context("Failed to load note aliases")?
+    {
+        let (rel_path, alias) = row?;
+        aliases.entry(rel_path).or_default().push(alias);
+    }
+
+    let mut names = Vec::new();
+    for rel_path in rel_paths
+        .iter()
+        .filter(|rel_path| rel_path.to_lowercase().starts_with(&prefix))
+    {
+        let page = stem(rel_path);
+        let target = if stem_counts.get(&page.to_lowercase()) == Some(&1) {
+            page.to_string()
+        } else {
+            note_path_without_extension(rel_path).to_string()
+        };
+        let person_aliases = aliases.get(rel_path).into_iter().flatten();
+        for name in std::iter::once(page).chain(person_aliases.map(String::as_str)) {
+            let name = name.trim();
+            if name.chars().count() >= MIN_NAME_CHARS {
+                names.push(PersonName {
+                    name: name.to_string(),
+                    rel_path: rel_path.clone(),
+                    target: target.clone(),
+                });
+            }
+        }
+    }
+    Ok(names)
+}
+
+fn stem(rel_path: &str) -> &str {
+    let file_name = rel_path.rsplit('/').next().unwrap_or(rel_path);
+    note_path_without_extension(file_name)
+}
+
+fn note_path_without_extension(rel_path: &str) -> &str {
+    rel_path
+        .rsplit_once('.')
+        .map_or(rel_path, |(without, _)| without)
+}
+
+/// Byte ranges of prose: text outside frontmatter, code, HTML, links and
+/// images.
+fn linkable_ranges(contents: &str) -> Vec<Range<usize>> {
+    let wiki_links = wiki_link_spans(contents);
+    let parser =
+        Parser::new_ext(contents, Options::ENABLE_YAML_STYLE_METADATA_BLOCKS).into_offset_iter();
+    let mut skipped_depth = 0usize;
+    let mut ranges = Vec::new();
+    for (event, range) in parser {
+        match event {
+            Event::Start(
+                Tag::Link { .. } | Tag::Image { .. } | Tag::CodeBlock(_) | Tag::MetadataBlock(_),
+            ) => skipped_depth += 1,
+            Event::End(
+                TagEnd::Link | TagEnd::Image | TagEnd::CodeBlock | TagEnd::MetadataBlock(_),
+            ) => skipped_depth = skipped_depth.saturating_sub(1),
+            Event::Text(_) if skipped_depth == 0 => {
+                let in_wiki_link = wiki_links
+                    .iter()
+                    .any(|link| link.start < range.end && range.start < link.end);
+                if !in_wiki_link {
+                    ranges.push(range);
+                }
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
+/// Starts of whole-word occurrences of `name` in `text` that begin with a
+/// capital letter. Letter case is otherwise ignored, so a lower-case alias
+/// still matches a capitalized mention.
+fn find_mentions(text: &str, name: &str) -> Vec<usize> {
+    let is_word = |ch: char| ch.is_alphanumeric() || ch == '_';
+    let mut starts = Vec::new();
+    for (start, first) in text.char_indices() {
+        if !first.is_uppercase() {
+            continue;
+        }
+        let end = start + name.len();
+        let Some(candidate) = text.get(start..end) else {
+            continue;
+        };
+        if !candidate.eq_ignore_ascii_case(name) {
+            continue;
+        }
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        if !before.is_some_and(is_word) && !after.is_some_and(is_word) {
+            starts.push(start);
+        }
+    }
+    starts
+}
@@ -0,0 +1,111 @@
+use note::FrontmatterValue;
+use rusqlite::types::Value as SqlValue;
+use serde_json::{Map, Value as JsonValue};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NoteProperty {
+    /// Lowercased, so queries match keys regardless of case.
+    pub(crate) key: String,
+    /// The value as JSON.
+    pub(crate) value: String,
+    /// Scalar values as SQL values for sorting; lists and objects sort last.
+    pub(crate) sort_value: SqlValue,
+}
+
+/// Top-level frontmatter properties of a note. Of keys differing only in
+/// case, the first one wins.
+pub(crate) fn extract_note_properties(source: &str) -> Vec<NoteProperty> {
+    let mut properties: Vec<NoteProperty> = Vec::new();
+    for property in note::parse_frontmatter(source).properties {
+        let key = property.key.trim().to_lowercase();
+        if key.is_empty() || properties.iter().any(|existing| existing.key == key) {
+            continue;
+        }
+        properties.push(NoteProperty {
+            key,
+            value: to_json(&property.value).to_string(),
+            sort_value: to_sort_value(&property.value),
+        });
+    }
+    properties
+}
+
+fn to_json(value: &FrontmatterValue) -> JsonValue {
+    match value {
+        FrontmatterValue::Null => JsonValue::Null,
+        FrontmatterValue::String(text) | FrontmatterValue::Date(text) => {
+            JsonValue::String(text.clone())
+        }
+        FrontmatterValue::Number(number) => JsonValue::Number(number.clone()),
+        FrontmatterValue::Boolean(flag) => JsonValue::Bool(*flag),
+        FrontmatterValue::List(items) => JsonValue::Array(items.iter().map(to_json).collect()),
+        FrontmatterValue::Object(properties) => JsonValue::Object(
+            properties
+                .iter()
+                .map(|property| (property.key.clone(), to_json(&property.value)))
+                .collect::<Map<_, _>>(),
+        ),
+    }
+}
+
+fn to_sort_value(value: &FrontmatterValue) -> SqlValue {
+    match value {
+        FrontmatterValue::String(text) | FrontmatterValue::Date(text) => {
+            SqlValue::Text(text.clone())
+        }
+        FrontmatterValue::Number(number) => match number.as_i64() {
+            Some(integer) => SqlValue::Integer(integer),
+            None => number.as_f64().map_or(SqlValue::Null, SqlValue::Real),
+        },
+        FrontmatterValue::Boolean(flag) => SqlValue::Integer(i64::from(*flag)),
+        FrontmatterValue::Null | FrontmatterValue::List(_) | FrontmatterValue::Object(_) => {
+            SqlValue::Null
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::types::Value as SqlValue;
+
+    use super::extract_note_properties;
+
+    #[test]
+    fn extracts_lowercased_keys_with_json_and_sort_values() {
+        let raw = [
+            "---",
+            "Status: Doing",
+            "status: ignored",
+            "priority: 2",
+            "tags: [a, b]",
+            "---",
+            "Body",
+        ]
+        .join("\n");
+
+        let properties = extract_note_properties(&raw)
+            .into_iter()
+            .map(|property| (property.key, property.value, property.sort_value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            properties,
+            vec![
+                (
+                    "status".to_string(),
+                    "\"Doing\"".to_string(),
+                    SqlValue::Text("Doing".to_string())
+                ),
+                (
+                    "priority".to_string(),
+                    "2".to_string(),
+                    SqlValue::Integer(2)
+                ),
+                (
+                    "tags".to_string(),
+                    "[\"a\",\"b\"]".to_string(),
+                    SqlValue::Null
+                ),
+            ]
+        );
+    }
+}
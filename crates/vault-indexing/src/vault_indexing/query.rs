@@ -0,0 +1,236 @@
+//! Table queries over indexed notes: a source filter, the fields to show and
+//! their order, evaluated in one SQL statement so dynamic tables stay cheap.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params_from_iter, types::Value as SqlValue};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use super::{
+    find_vault_id, open_indexing_connection, search::escape_like_pattern, tags::normalize_tag_query,
+};
+
+const DEFAULT_QUERY_LIMIT: usize = 100;
+const MAX_QUERY_LIMIT: usize = 1000;
+
+/// File name of the doc without its folder.
+const BASENAME_SQL: &str =
+    "substr(d.rel_path, length(rtrim(d.rel_path, replace(d.rel_path, '/', ''))) + 1)";
+
+/// Which notes a query reads.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum NoteQuerySource {
+    /// Notes under the folder, relative to the vault root, subfolders included.
+    Folder { path: String },
+    /// Notes with the tag or a tag nested under it.
+    Tag { tag: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum NoteQueryField {
+    /// File name without the extension.
+    Title,
+    Path,
+    /// Milliseconds since the epoch.
+    CreatedAt,
+    /// Milliseconds since the epoch.
+    ModifiedAt,
+    /// A frontmatter property, matched regardless of case.
+    Property {
+        key: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteQuerySort {
+    pub field: NoteQueryField,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NoteQuery {
+    /// Every indexed note when unset.
+    pub source: Option<NoteQuerySource>,
+    pub select: Vec<NoteQueryField>,
+    /// Applied in order; notes missing a value sort last. Ties fall back to
+    /// the path.
+    pub sort: Vec<NoteQuerySort>,
+    /// 100 when unset, at most 1000.
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteQueryResult {
+    /// The selected fields, in the order of each row's values.
+    pub columns: Vec<NoteQueryField>,
+    pub rows: Vec<NoteQueryRow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteQueryRow {
+    pub rel_path: String,
+    /// One value per selected field, `null` when the note has none.
+    pub values: Vec<JsonValue>,
+}
+
+pub fn query_notes(
+    workspace_root: &Path,
+    db_path: &Path,
+    query: &NoteQuery,
+) -> Result<NoteQueryResult> {
+    let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+    if !(1..=MAX_QUERY_LIMIT).contains(&limit) {
+        return Err(anyhow!(
+            "Query limit must be between 1 and {MAX_QUERY_LIMIT}: {limit}"
+        ));
+    }
+
+    let mut result = NoteQueryResult {
+        columns: query.select.clone(),
+        rows: Vec::new(),
+    };
+    let conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(result);
+    };
+
+    let mut sql = QueryBuilder::new(vault_id);
+    let select = query
+        .select
+        .iter()
+        .map(|field| sql.field_expr(field, false))
+        .collect::<Vec<_>>();
+    let filters = match &query.source {
+        Some(source) => match sql.source_filter(source) {
+            Some(filter) => filter,
+            // A tag that normalizes to nothing matches no note.
+            None => return Ok(result),
+        },
+        None => String::new(),
+    };
+    let mut order_by = Vec::new();
+    for sort in &query.sort {
+        let expr = sql.field_expr(&sort.field, true);
+        let direction = if sort.descending { "DESC" } else { "ASC" };
+        order_by.push(format!("({expr}) IS NULL, {expr} {direction}"));
+    }
+    order_by.push("d.rel_path".to_string());
+    let limit_param = sql.bind(SqlValue::Integer(limit as i64));
+
+    let statement = format!(
+        "SELECT d.rel_path{} FROM doc d WHERE d.vault_id = ?1{filters} ORDER BY {} LIMIT {limit_param}",
+        select
+            .iter()
+            .map(|expr| format!(", {expr}"))
+            .collect::<String>(),
+        order_by.join(", "),
+    );
+
+    let mut stmt = conn
+        .prepare(&statement)
+        .context("Failed to prepare note query")?;
+    let rows = stmt
+        .query_map(params_from_iter(sql.params.iter()), |row| {
+            let rel_path = row.get::<_, String>(0)?;
+            let mut values = Vec::with_capacity(query.select.len());
+            for (index, field) in query.select.iter().enumerate() {
+                let value = row.get::<_, SqlValue>(index + 1)?;
+                values.push(to_json(field, value));
+            }
+            Ok(NoteQueryRow { rel_path, values })
+        })
+        .context("Failed to run note query")?;
+    for row in rows {
+        result
+            .rows
+            .push(row.context("Failed to read note query row")?);
+    }
+
+    Ok(result)
+}
+
+struct QueryBuilder {
+    params: Vec<SqlValue>,
+}
+
+impl QueryBuilder {
+    fn new(vault_id: i64) -> Self {
+        Self {
+            params: vec![SqlValue::Integer(vault_id)],
+        }
+    }
+
+    /// Adds a parameter and returns its placeholder.
+    fn bind(&mut self, value: SqlValue) -> String {
+        self.params.push(value);
+        format!("?{}", self.params.len())
+    }
+
+    /// SQL for `field`. Properties read their JSON value for display and
+    /// their typed value for sorting.
+    fn field_expr(&mut self, field: &NoteQueryField, for_sort: bool) -> String {
+        match field {
+            NoteQueryField::Title => format!(
+                "substr({BASENAME_SQL}, 1, length(rtrim({BASENAME_SQL}, replace({BASENAME_SQL}, '.', ''))) - 1){}",
+                if for_sort { " COLLATE NOCASE" } else { "" }
+            ),
+            NoteQueryField::Path => "d.rel_path".to_string(),
+            NoteQueryField::CreatedAt => "d.source_created_ns / 1000000".to_string(),
+            NoteQueryField::ModifiedAt => "d.last_source_mtime_ns / 1000000".to_string(),
+            NoteQueryField::Property { key } => {
+                let key = self.bind(SqlValue::Text(key.trim().to_lowercase()));
+                let column = if for_sort { "sort_value" } else { "value" };
+                format!(
+                    "(SELECT p.{column} FROM doc_property p WHERE p.doc_id = d.id AND p.key = {key})"
+                )
+            }
+        }
+    }
+
+    fn source_filter(&mut self, source: &NoteQuerySource) -> Option<String> {
+        match source {
+            NoteQuerySource::Folder { path } => {
+                let folder = path.trim().replace('\\', "/");
+                let folder = folder.trim_matches('/');
+                if folder.is_empty() {
+                    return Some(String::new());
+                }
+                let pattern =
+                    self.bind(SqlValue::Text(format!("{}/%", escape_like_pattern(folder))));
+                Some(format!(" AND d.rel_path LIKE {pattern} ESCAPE '\\'"))
+            }
+            NoteQuerySource::Tag { tag } => {
+                let normalized_tag = normalize_tag_query(tag)?;
+                let descendant_pattern = format!("{}/%", escape_like_pattern(&normalized_tag));
+                let tag = self.bind(SqlValue::Text(normalized_tag));
+                let pattern = self.bind(SqlValue::Text(descendant_pattern));
+                Some(format!(
+                    " AND EXISTS (SELECT 1 FROM doc_tag dt WHERE dt.doc_id = d.id \
+                     AND (dt.normalized_tag = {tag} OR dt.normalized_tag LIKE {pattern} ESCAPE '\\'))"
+                ))
+            }
+        }
+    }
+}
+
+fn to_json(field: &NoteQueryField, value: SqlValue) -> JsonValue {
+    match (field, value) {
+        (_, SqlValue::Null) => JsonValue::Null,
+        (NoteQueryField::Property { .. }, SqlValue::Text(raw)) => {
+            serde_json::from_str(&raw).unwrap_or(JsonValue::String(raw))
+        }
+        (_, SqlValue::Integer(integer)) => JsonValue::from(integer),
+        (_, SqlValue::Real(real)) => JsonValue::from(real),
+        (_, SqlValue::Text(text)) => JsonValue::String(text),
+        (_, SqlValue::Blob(_)) => JsonValue::Null,
+    }
+}
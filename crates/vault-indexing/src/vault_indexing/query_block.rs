@@ -0,0 +1,350 @@
+//! ` ```mdit-query ` blocks: a small line-based language over [`NoteQuery`],
+//! one clause per line.
+//!
+//! ```text
+//! from folder ./Tasks      (or: from tag #work; the whole vault when left out)
+//! select title, status, due
+//! sort priority desc, title
+//! limit 20
+//! ```
+//!
+//! `title`, `path`, `created` and `modified` select note fields, any other
+//! name a frontmatter property. Relative folders start at the note's folder.
+
+use std::{
+    collections::BTreeSet,
+    path::{Component, Path},
+};
+
+use anyhow::{anyhow, Context, Result};
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use rusqlite::params;
+
+use super::{
+    find_vault_id, open_indexing_connection,
+    query::{
+        query_notes, NoteQuery, NoteQueryField, NoteQueryResult, NoteQuerySort, NoteQuerySource,
+    },
+    tags::normalize_tag_query,
+};
+
+/// Fence language of query blocks.
+pub const QUERY_BLOCK_LANGUAGE: &str = "mdit-query";
+
+/// What a query block in a note reads from, kept in the index so the note can
+/// be refreshed when one of those notes changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum QueryBlockSource {
+    Vault,
+    Folder(String),
+    Tag(String),
+}
+
+impl QueryBlockSource {
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::Vault => "vault",
+            Self::Folder(_) => "folder",
+            Self::Tag(_) => "tag",
+        }
+    }
+
+    pub(crate) fn value(&self) -> &str {
+        match self {
+            Self::Vault => "",
+            Self::Folder(folder) => folder,
+            Self::Tag(tag) => tag,
+        }
+    }
+}
+
+/// Parses the body of a query block found in the note at `note_rel_path`.
+pub fn parse_query_block(content: &str, note_rel_path: &str) -> Result<NoteQuery> {
+    let mut query = NoteQuery::default();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let clause = match keyword.to_lowercase().as_str() {
+            "from" => parse_source(rest, note_rel_path).map(|source| query.source = Some(source)),
+            "select" => parse_fields(rest).map(|fields| query.select = fields),
+            "sort" => parse_sorts(rest).map(|sorts| query.sort = sorts),
+            "limit" => rest
+                .parse::<usize>()
+                .map(|limit| query.limit = Some(limit))
+                .map_err(|_| anyhow!("limit must be a number: {rest}")),
+            other => Err(anyhow!("unknown clause '{other}'")),
+        };
+        clause.with_context(|| format!("Invalid query on line {}", index + 1))?;
+    }
+
+    if query.select.is_empty() {
+        query.select.push(NoteQueryField::Title);
+    }
+    Ok(query)
+}
+
+/// Runs the query block `content` written in `note_path`.
+pub fn render_query_block(
+    workspace_root: &Path,
+    db_path: &Path,
+    note_path: &Path,
+    content: &str,
+) -> Result<NoteQueryResult> {
+    let note_rel_path = note_path
+        .strip_prefix(workspace_root)
+        .map_err(|_| anyhow!("Note is outside the vault: {}", note_path.display()))?
+        .to_string_lossy()
+        .replace('\\', "/");
+    let query = parse_query_block(content, &note_rel_path)?;
+    query_notes(workspace_root, db_path, &query)
+}
+
+/// Notes whose query blocks read from any of `rel_paths`, by folder, by the
+/// tags the index holds for them, or over the whole vault. The changed notes
+/// themselves are left out.
+pub fn find_query_dependents(
+    workspace_root: &Path,
+    db_path: &Path,
+    rel_paths: &[String],
+) -> Result<Vec<String>> {
+    let conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT d.rel_path \
+             FROM doc_query_source q \
+             JOIN doc d ON d.id = q.doc_id \
+             WHERE d.vault_id = ?1 \
+               AND d.rel_path <> ?2 \
+               AND (q.source_kind = 'vault' \
+                 OR (q.source_kind = 'folder' \
+                   AND substr(?2, 1, length(q.source_value) + 1) = q.source_value || '/') \
+                 OR (q.source_kind = 'tag' AND EXISTS ( \
+                   SELECT 1 FROM doc_tag dt \
+                   JOIN doc changed ON changed.id = dt.doc_id \
+                   WHERE changed.vault_id = ?1 AND changed.rel_path = ?2 \
+                     AND (dt.normalized_tag = q.source_value \
+                       OR substr(dt.normalized_tag, 1, length(q.source_value) + 1) \
+                         = q.source_value || '/'))))",
+        )
+        .context("Failed to prepare query dependents lookup")?;
+
+    let mut dependents = BTreeSet::new();
+    for rel_path in rel_paths {
+        let rows = stmt
+            .query_map(params![vault_id, rel_path], |row| row.get::<_, String>(0))
+            .context("Failed to look up query dependents")?;
+        for row in rows {
+            dependents.insert(row?);
+        }
+    }
+    dependents.retain(|rel_path| !rel_paths.contains(rel_path));
+    Ok(dependents.into_iter().collect())
+}
+
+/// Sources of the valid query blocks in `source`. Blocks that do not parse
+/// are skipped; rendering reports their errors.
+pub(crate) fn extract_query_block_sources(
+    source: &str,
+    note_rel_path: &str,
+) -> Vec<QueryBlockSource> {
+    if !source.contains(QUERY_BLOCK_LANGUAGE) {
+        return Vec::new();
+    }
+
+    let mut sources = Vec::new();
+    let mut block: Option<String> = None;
+    for event in Parser::new_ext(source, Options::empty()) {
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info)))
+                if info.split_whitespace().next() == Some(QUERY_BLOCK_LANGUAGE) =>
+            {
+                block = Some(String::new());
+            }
+            Event::Text(text) => {
+                if let Some(block) = block.as_mut() {
+                    block.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                let Some(content) = block.take() else {
+                    continue;
+                };
+                let Ok(query) = parse_query_block(&content, note_rel_path) else {
+                    continue;
+                };
+                let source = match query.source {
+                    None => QueryBlockSource::Vault,
+                    Some(NoteQuerySource::Folder { path }) if path.is_empty() => {
+                        QueryBlockSource::Vault
+                    }
+                    Some(NoteQuerySource::Folder { path }) => QueryBlockSource::Folder(path),
+                    Some(NoteQuerySource::Tag { tag }) => QueryBlockSource::Tag(tag),
+                };
+                if !sources.contains(&source) {
+                    sources.push(source);
+                }
+            }
+            _ => {}
+        }
+    }
+    sources
+}
+
+fn parse_source(rest: &str, note_rel_path: &str) -> Result<NoteQuerySource> {
+    let (kind, value) = if rest.starts_with('#') {
+        ("tag", rest)
+    } else {
+        let (kind, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        (kind, value.trim())
+    };
+    let value = value.trim_matches('"');
+
+    match kind.to_lowercase().as_str() {
+        "folder" => Ok(NoteQuerySource::Folder {
+            path: resolve_folder(value, note_rel_path)?,
+        }),
+        "tag" => {
+            let tag = normalize_tag_query(value).ok_or_else(|| anyhow!("invalid tag: {value}"))?;
+            Ok(NoteQuerySource::Tag { tag })
+        }
+        _ => Err(anyhow!("expected 'from folder <path>' or 'from tag <tag>'")),
+    }
+}
+
+/// Vault-relative folder for `raw`; `.` and `./sub` start at the note's folder.
+fn resolve_folder(raw: &str, note_rel_path: &str) -> Result<String> {
+    let raw = raw.trim().replace('\\', "/");
+    let mut parts = Vec::new();
+    if raw == "." || raw.starts_with("./") || raw.starts_with("../") {
+        if let Some(parent) = Path::new(note_rel_path).parent() {
+            parts.extend(
+                parent
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy().into_owned()),
+            );
+        }
+    }
+    for component in Path::new(raw.trim_start_matches('/')).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                parts
+                    .pop()
+                    .ok_or_else(|| anyhow!("folder is outside the vault: {raw}"))?;
+            }
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            _ => return Err(anyhow!("folder is outside the vault: {raw}")),
+        }
+    }
+    Ok(parts.join("/"))
+}
+
+fn parse_fields(rest: &str) -> Result<Vec<NoteQueryField>> {
+    rest.split(',').map(parse_field).collect()
+}
+
+fn parse_field(raw: &str) -> Result<NoteQueryField> {
+    let name = raw.trim();
+    if name.is_empty() {
+        return Err(anyhow!("empty field name"));
+    }
+    Ok(match name.to_lowercase().as_str() {
+        "title" => NoteQueryField::Title,
+        "path" => NoteQueryField::Path,
+        "created" => NoteQueryField::CreatedAt,
+        "modified" => NoteQueryField::ModifiedAt,
+        _ => NoteQueryField::Property {
+            key: name.to_string(),
+        },
+    })
+}
+
+fn parse_sorts(rest: &str) -> Result<Vec<NoteQuerySort>> {
+    rest.split(',')
+        .map(|item| {
+            let item = item.trim();
+            let (field, direction) = match item.rsplit_once(char::is_whitespace) {
+                Some((field, direction))
+                    if matches!(direction.to_lowercase().as_str(), "asc" | "desc") =>
+                {
+                    (field, direction)
+                }
+                _ => (item, "asc"),
+            };
+            Ok(NoteQuerySort {
+                field: parse_field(field)?,
+                descending: direction.eq_ignore_ascii_case("desc"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_query_block_sources, parse_query_block, QueryBlockSource};
+    use crate::vault_indexing::query::{NoteQueryField, NoteQuerySource};
+
+    #[test]
+    fn parses_clauses_and_resolves_relative_folders() {
+        let query = parse_query_block(
+            "from folder ../Tasks\nselect title, Status\n\nsort priority desc, title\nlimit 5\n",
+            "Projects/Board.md",
+        )
+        .expect("query should parse");
+
+        assert_eq!(
+            query.source,
+            Some(NoteQuerySource::Folder {
+                path: "Tasks".to_string()
+            })
+        );
+        assert_eq!(
+            query.select,
+            vec![
+                NoteQueryField::Title,
+                NoteQueryField::Property {
+                    key: "Status".to_string()
+                }
+            ]
+        );
+        assert!(query.sort[0].descending);
+        assert!(!query.sort[1].descending);
+        assert_eq!(query.limit, Some(5));
+
+        let error = parse_query_block("select title\nwhere x", "a.md").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid query on line 2");
+        assert!(parse_query_block("from folder ../..", "a.md").is_err());
+    }
+
+    #[test]
+    fn extracts_sources_of_valid_query_blocks_only() {
+        let source = [
+            "```mdit-query",
+            "from tag #Work/Client",
+            "```",
+            "```mdit-query",
+            "from nowhere",
+            "```",
+            "```mdit-query",
+            "select title",
+            "```",
+        ]
+        .join("\n");
+
+        assert_eq!(
+            extract_query_block_sources(&source, "Board.md"),
+            vec![
+                QueryBlockSource::Tag("work/client".to_string()),
+                QueryBlockSource::Vault
+            ]
+        );
+    }
+}
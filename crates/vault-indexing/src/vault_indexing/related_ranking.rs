@@ -0,0 +1,92 @@
+//! How related notes are ordered once the vector search has scored them.
+//! Plain similarity by default; a vault can turn on recency decay in its
+//! `relatedNotesRecency` setting so older notes drift down the list.
+
+use std::time::SystemTime;
+
+use anyhow::Result;
+use app_storage::vault_settings::{load_vault_setting, VaultSettingKey};
+use rusqlite::Connection;
+
+use super::files::system_time_to_nanos;
+
+const NANOS_PER_DAY: f64 = 86_400.0 * 1_000_000_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RelatedNoteRanking {
+    Similarity,
+    /// Similarity halved for every `half_life_days` since the note was last
+    /// modified.
+    RecencyDecay {
+        half_life_days: f64,
+        now_ns: i64,
+    },
+}
+
+impl RelatedNoteRanking {
+    pub(crate) fn load(conn: &Connection, vault_id: i64) -> Result<Self> {
+        let setting = load_vault_setting(conn, vault_id, VaultSettingKey::RelatedNotesRecency)?;
+        let enabled = setting
+            .value
+            .get("enabled")
+            .and_then(|value| value.as_bool());
+        let half_life_days = setting
+            .value
+            .get("halfLifeDays")
+            .and_then(|value| value.as_f64());
+        Ok(match (enabled, half_life_days) {
+            (Some(true), Some(half_life_days)) if half_life_days > 0.0 => Self::RecencyDecay {
+                half_life_days,
+                now_ns: system_time_to_nanos(SystemTime::now()).unwrap_or_default(),
+            },
+            _ => Self::Similarity,
+        })
+    }
+
+    /// Whether the final order can differ from the similarity order, so the
+    /// search has to return every candidate rather than the top few.
+    pub(crate) fn reorders(self) -> bool {
+        !matches!(self, Self::Similarity)
+    }
+
+    /// Final score of a note with `similarity`, last modified at
+    /// `modified_ns`. Notes without a known modification time, or with one
+    /// in the future, are not decayed.
+    pub(crate) fn score(self, similarity: f32, modified_ns: Option<i64>) -> f32 {
+        match self {
+            Self::Similarity => similarity,
+            Self::RecencyDecay {
+                half_life_days,
+                now_ns,
+            } => {
+                let age_days = modified_ns
+                    .map(|modified_ns| now_ns.saturating_sub(modified_ns).max(0) as f64)
+                    .unwrap_or_default()
+                    / NANOS_PER_DAY;
+                (f64::from(similarity) * 0.5_f64.powf(age_days / half_life_days)) as f32
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RelatedNoteRanking, NANOS_PER_DAY};
+
+    #[test]
+    fn recency_decay_halves_similarity_each_half_life() {
+        let now_ns = 1_000 * NANOS_PER_DAY as i64;
+        let ranking = RelatedNoteRanking::RecencyDecay {
+            half_life_days: 30.0,
+            now_ns,
+        };
+        let days_ago = |days: i64| Some(now_ns - days * NANOS_PER_DAY as i64);
+
+        assert_eq!(ranking.score(0.8, days_ago(0)), 0.8);
+        assert!((ranking.score(0.8, days_ago(30)) - 0.4).abs() < 1e-6);
+        assert!((ranking.score(0.8, days_ago(60)) - 0.2).abs() < 1e-6);
+        assert_eq!(ranking.score(0.8, None), 0.8);
+        assert_eq!(ranking.score(0.8, Some(now_ns + 1)), 0.8);
+        assert_eq!(RelatedNoteRanking::Similarity.score(0.8, days_ago(60)), 0.8);
+    }
+}
@@ -0,0 +1,287 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use super::IndexSummary;
+
+/// What a workspace's index is doing right now.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IndexRunStatus {
+    #[default]
+    Idle,
+    Running,
+    /// A workspace run is active and another was requested while it ran.
+    RerunQueued,
+}
+
+#[derive(Debug)]
+enum ActiveRun {
+    /// A full workspace run. `rerun` holds the force flag of a request that
+    /// arrived while it was running.
+    Workspace { rerun: Option<bool> },
+    /// A single-note write, such as a watch event or a rename.
+    Note,
+}
+
+#[derive(Debug, Default)]
+struct GuardState {
+    active: Mutex<HashMap<PathBuf, ActiveRun>>,
+    released: Condvar,
+    /// Bumped each time a writer releases a workspace.
+    generations: Mutex<HashMap<PathBuf, u64>>,
+}
+
+/// Lets one writer at a time touch a workspace's index.
+///
+/// A workspace run requested while another is active is not started; the
+/// active run repeats once it finishes, so bursts of requests collapse into a
+/// single extra pass. Single-note writes wait for the active writer instead.
+#[derive(Debug, Default, Clone)]
+pub struct IndexRunGuard {
+    state: Arc<GuardState>,
+}
+
+impl IndexRunGuard {
+    pub fn status(&self, workspace_root: &Path) -> IndexRunStatus {
+        let Ok(active) = self.lock() else {
+            return IndexRunStatus::Idle;
+        };
+        match active.get(&run_key(workspace_root)) {
+            None | Some(ActiveRun::Note) => IndexRunStatus::Idle,
+            Some(ActiveRun::Workspace { rerun: None }) => IndexRunStatus::Running,
+            Some(ActiveRun::Workspace { rerun: Some(_) }) => IndexRunStatus::RerunQueued,
+        }
+    }
+
+    /// Counts finished writes to a workspace, so readers can tell whether the
+    /// index may have changed since they last looked.
+    pub fn write_generation(&self, workspace_root: &Path) -> u64 {
+        self.state
+            .generations
+            .lock()
+            .map(|generations| {
+                generations
+                    .get(&run_key(workspace_root))
+                    .copied()
+                    .unwrap_or(0)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Runs `run` with the requested force flag, then again for every request
+    /// queued meanwhile. A queued request returns right away with
+    /// `IndexSummary::queued` set.
+    pub fn run_workspace(
+        &self,
+        workspace_root: &Path,
+        force_reindex: bool,
+        mut run: impl FnMut(bool) -> Result<IndexSummary>,
+    ) -> Result<IndexSummary> {
+        let key = run_key(workspace_root);
+        {
+            let mut active = self.lock()?;
+            loop {
+                match active.get_mut(&key) {
+                    None => break,
+                    Some(ActiveRun::Workspace { rerun }) => {
+                        *rerun = Some(rerun.unwrap_or(false) || force_reindex);
+                        return Ok(IndexSummary {
+                            queued: true,
+                            ..Default::default()
+                        });
+                    }
+                    Some(ActiveRun::Note) => {
+                        active = self.wait(active)?;
+                    }
+                }
+            }
+            active.insert(key.clone(), ActiveRun::Workspace { rerun: None });
+        }
+
+        let _release = Release {
+            guard: self,
+            key: &key,
+        };
+        let mut force_reindex = force_reindex;
+        loop {
+            let summary = run(force_reindex)?;
+
+            let mut active = self.lock()?;
+            match active.get_mut(&key) {
+                Some(ActiveRun::Workspace { rerun }) => match rerun.take() {
+                    Some(force) => force_reindex = force,
+                    None => return Ok(summary),
+                },
+                _ => return Ok(summary),
+            }
+        }
+    }
+
+    /// Runs a single-note write once no other writer holds the workspace.
+    pub fn run_exclusive<T>(
+        &self,
+        workspace_root: &Path,
+        run: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        let key = run_key(workspace_root);
+        {
+            let mut active = self.lock()?;
+            while active.contains_key(&key) {
+                active = self.wait(active)?;
+            }
+            active.insert(key.clone(), ActiveRun::Note);
+        }
+
+        let _release = Release {
+            guard: self,
+            key: &key,
+        };
+        run()
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<PathBuf, ActiveRun>>> {
+        self.state
+            .active
+            .lock()
+            .map_err(|_| anyhow!("Index run guard is poisoned"))
+    }
+
+    fn wait<'a>(
+        &self,
+        active: MutexGuard<'a, HashMap<PathBuf, ActiveRun>>,
+    ) -> Result<MutexGuard<'a, HashMap<PathBuf, ActiveRun>>> {
+        self.state
+            .released
+            .wait(active)
+            .map_err(|_| anyhow!("Index run guard is poisoned"))
+    }
+}
+
+/// Frees the workspace even when the run fails or panics. A rerun still
+/// queued at that point is dropped along with it.
+struct Release<'a> {
+    guard: &'a IndexRunGuard,
+    key: &'a Path,
+}
+
+impl Drop for Release<'_> {
+    fn drop(&mut self) {
+        let mut active = match self.guard.state.active.lock() {
+            Ok(active) => active,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        active.remove(self.key);
+        if let Ok(mut generations) = self.guard.state.generations.lock() {
+            *generations.entry(self.key.to_path_buf()).or_default() += 1;
+        }
+        self.guard.state.released.notify_all();
+    }
+}
+
+// Commands pass the path the user picked while the watcher passes the
+// canonical one; both must map to the same slot.
+fn run_key(workspace_root: &Path) -> PathBuf {
+    std::fs::canonicalize(workspace_root).unwrap_or_else(|_| workspace_root.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::mpsc, thread, time::Duration};
+
+    use super::{IndexRunGuard, IndexRunStatus};
+    use crate::vault_indexing::IndexSummary;
+
+    const WORKSPACE: &str = "/mdit-run-guard-test-vault";
+
+    #[test]
+    fn given_active_run_when_more_runs_are_requested_then_one_rerun_picks_them_up() {
+        let guard = IndexRunGuard::default();
+        let (started_tx, started_rx) = mpsc::channel();
+        let (resume_tx, resume_rx) = mpsc::channel::<()>();
+
+        let runner = {
+            let guard = guard.clone();
+            thread::spawn(move || {
+                let mut forces = Vec::new();
+                let summary = guard
+                    .run_workspace(Path::new(WORKSPACE), false, |force| {
+                        forces.push(force);
+                        started_tx.send(()).unwrap();
+                        resume_rx.recv().unwrap();
+                        Ok(IndexSummary::default())
+                    })
+                    .unwrap();
+                (summary, forces)
+            })
+        };
+
+        started_rx.recv().unwrap();
+        assert_eq!(guard.status(Path::new(WORKSPACE)), IndexRunStatus::Running);
+
+        let queue = |force| {
+            guard
+                .run_workspace(Path::new(WORKSPACE), force, |_| {
+                    panic!("queued request must not start its own run")
+                })
+                .unwrap()
+        };
+        assert!(queue(true).queued);
+        assert!(queue(false).queued);
+        assert_eq!(
+            guard.status(Path::new(WORKSPACE)),
+            IndexRunStatus::RerunQueued
+        );
+
+        resume_tx.send(()).unwrap();
+        started_rx.recv().unwrap();
+        resume_tx.send(()).unwrap();
+
+        let (summary, forces) = runner.join().unwrap();
+        assert!(!summary.queued);
+        assert_eq!(forces, vec![false, true]);
+        assert_eq!(guard.status(Path::new(WORKSPACE)), IndexRunStatus::Idle);
+        assert_eq!(guard.write_generation(Path::new(WORKSPACE)), 1);
+    }
+
+    #[test]
+    fn given_active_run_when_a_note_write_arrives_then_it_waits_for_the_run() {
+        let guard = IndexRunGuard::default();
+        let (started_tx, started_rx) = mpsc::channel();
+        let (events_tx, events_rx) = mpsc::channel();
+
+        let runner = {
+            let guard = guard.clone();
+            let events_tx = events_tx.clone();
+            thread::spawn(move || {
+                guard
+                    .run_workspace(Path::new(WORKSPACE), false, |_| {
+                        started_tx.send(()).unwrap();
+                        thread::sleep(Duration::from_millis(50));
+                        events_tx.send("workspace").unwrap();
+                        Ok(IndexSummary::default())
+                    })
+                    .unwrap();
+            })
+        };
+
+        started_rx.recv().unwrap();
+        guard
+            .run_exclusive(Path::new(WORKSPACE), || {
+                events_tx.send("note").unwrap();
+                Ok(())
+            })
+            .unwrap();
+        runner.join().unwrap();
+
+        assert_eq!(
+            events_rx.try_iter().collect::<Vec<_>>(),
+            vec!["workspace", "note"]
+        );
+    }
+}
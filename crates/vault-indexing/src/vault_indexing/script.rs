@@ -0,0 +1,152 @@
+// Kana share of CJK ideographs above which Han-heavy text is treated as Japanese.
+// Chinese prose has no kana at all, while Japanese rarely drops below ~20%.
+const MIN_KANA_RATIO_FOR_JAPANESE: f32 = 0.1;
+
+/// Dominant language of a piece of text, inferred from its script only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TextLanguage {
+    Chinese,
+    Japanese,
+    Korean,
+    /// Non-CJK or empty text. Latin-script languages cannot be told apart by
+    /// script alone, so they all land here.
+    Undetermined,
+}
+
+impl TextLanguage {
+    /// BCP 47 primary language subtag stored on `doc.language`.
+    pub(crate) fn code(self) -> &'static str {
+        match self {
+            TextLanguage::Chinese => "zh",
+            TextLanguage::Japanese => "ja",
+            TextLanguage::Korean => "ko",
+            TextLanguage::Undetermined => "und",
+        }
+    }
+
+    pub(crate) fn is_cjk(self) -> bool {
+        !matches!(self, TextLanguage::Undetermined)
+    }
+}
+
+/// Count letters per script and return the dominant language. Digits,
+/// punctuation and whitespace are ignored so code and tables do not skew the result.
+pub(crate) fn detect_language(text: &str) -> TextLanguage {
+    let mut han = 0usize;
+    let mut kana = 0usize;
+    let mut hangul = 0usize;
+    let mut other_letters = 0usize;
+
+    for ch in text.chars() {
+        if is_han(ch) {
+            han += 1;
+        } else if is_kana(ch) {
+            kana += 1;
+        } else if is_hangul(ch) {
+            hangul += 1;
+        } else if ch.is_alphabetic() {
+            other_letters += 1;
+        }
+    }
+
+    let cjk = han + kana + hangul;
+    if cjk == 0 || cjk < other_letters {
+        return TextLanguage::Undetermined;
+    }
+
+    if hangul >= han + kana {
+        return TextLanguage::Korean;
+    }
+
+    if kana as f32 >= (han + kana) as f32 * MIN_KANA_RATIO_FOR_JAPANESE {
+        TextLanguage::Japanese
+    } else {
+        TextLanguage::Chinese
+    }
+}
+
+/// Words of `text` for counting: runs of letters and digits, with each Han
+/// or kana character on its own since those scripts do not space words.
+pub(crate) fn split_words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (index, ch) in text.char_indices() {
+        let in_word = ch.is_alphanumeric() || (ch == '\'' && start.is_some());
+        if !in_word || is_han(ch) || is_kana(ch) {
+            if let Some(word_start) = start.take() {
+                words.push(text[word_start..index].trim_end_matches('\''));
+            }
+        }
+        if is_han(ch) || is_kana(ch) {
+            words.push(&text[index..index + ch.len_utf8()]);
+        } else if in_word && start.is_none() {
+            start = Some(index);
+        }
+    }
+    if let Some(word_start) = start {
+        words.push(text[word_start..].trim_end_matches('\''));
+    }
+    words
+}
+
+fn is_han(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
+fn is_kana(ch: char) -> bool {
+    matches!(ch, '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' | '\u{FF66}'..='\u{FF9F}')
+}
+
+fn is_hangul(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_language, split_words, TextLanguage};
+
+    #[test]
+    fn detects_cjk_languages_by_script() {
+        assert_eq!(
+            detect_language("今日は良い天気ですね。"),
+            TextLanguage::Japanese
+        );
+        assert_eq!(
+            detect_language("今天天气很好，我们去公园吧。"),
+            TextLanguage::Chinese
+        );
+        assert_eq!(
+            detect_language("오늘은 날씨가 좋네요."),
+            TextLanguage::Korean
+        );
+    }
+
+    #[test]
+    fn latin_dominant_or_empty_text_is_undetermined() {
+        assert_eq!(
+            detect_language("Meeting notes about 東京 office"),
+            TextLanguage::Undetermined
+        );
+        assert_eq!(detect_language("1234 -- ..."), TextLanguage::Undetermined);
+        assert_eq!(detect_language(""), TextLanguage::Undetermined);
+    }
+
+    #[test]
+    fn splits_spaced_words_and_single_cjk_characters() {
+        assert_eq!(
+            split_words("It's a 2nd draft — 東京へ"),
+            vec!["It's", "a", "2nd", "draft", "東", "京", "へ"]
+        );
+        assert_eq!(split_words("안녕 세계"), vec!["안녕", "세계"]);
+        assert!(split_words(" -- ").is_empty());
+    }
+}
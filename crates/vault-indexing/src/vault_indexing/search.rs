@@ -2,25 +2,47 @@ use std::{
     cmp::Ordering,
     collections::HashMap,
     convert::TryFrom,
-    ffi::OsStr,
     fs,
     path::{Path, PathBuf},
     time::{SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::{anyhow, Context, Result};
-use rusqlite::{params, Connection};
-use serde::Serialize;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    embedding::{EmbeddingClient, EmbeddingProfile},
+    embedding_providers::EmbeddingProviderRegistry,
+    files::{disk_path, normalize_rel_path},
+    fts_tokenizer::vault_fts_table,
+    minhash::signature_similarity,
+    tags::normalize_tag_query,
+};
+
+mod query_terms;
+mod regex_scan;
+mod segments;
+mod spelling;
 
-use super::{embedding::EmbeddingClient, tags::normalize_tag_query};
+pub use segments::{search_segments, search_segments_where, SegmentMatch};
 
 const VECTOR_WEIGHT: f32 = 0.7;
 const BM25_WEIGHT: f32 = 0.3;
 const MIN_FINAL_SCORE: f32 = 0.05;
 const MIN_NOTE_BYTES: u64 = 256;
 const SEGMENT_VEC_TABLE: &str = "segment_vec";
-
-#[derive(Debug, Serialize)]
+/// Queries starting with this are run as [`SearchMode::Regex`].
+pub const REGEX_QUERY_PREFIX: &str = "re:";
+/// Content cap used when a caller asks for content without naming one.
+pub const DEFAULT_CONTENT_MAX_BYTES: usize = 16 * 1024;
+/// Only the best results carry content, which keeps large result lists small.
+const CONTENT_RESULT_LIMIT: usize = 10;
+/// Estimated share of word shingles two notes must have in common to be
+/// collapsed into one result.
+const NEAR_DUPLICATE_SIMILARITY: f32 = 0.8;
+
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SemanticNoteEntry {
     pub path: String,
@@ -28,6 +50,126 @@ pub struct SemanticNoteEntry {
     pub created_at: Option<i64>,
     pub modified_at: Option<i64>,
     pub similarity: f32,
+    /// Best-matching passage when the note was found through its vectors.
+    pub passage: Option<MatchedPassage>,
+    /// The note's indexed text, for the top results when asked for through
+    /// [`SearchOptions::content_max_bytes`].
+    pub content: Option<String>,
+    /// `content` was cut at the byte cap.
+    pub content_truncated: bool,
+    /// Near-duplicates of this note folded into it, best first, when asked
+    /// for through [`SearchOptions::collapse_similar`].
+    pub similar: Vec<SemanticNoteEntry>,
+}
+
+/// Location of a matched segment so the editor can scroll to it.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchedPassage {
+    pub start_offset: usize,
+    pub end_offset: usize,
+    pub heading_path: Vec<String>,
+    /// Fence language when the passage overlaps a code block; empty when the
+    /// fence has no info string.
+    pub code_language: Option<String>,
+}
+
+/// Which parts of the vault a query may match.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchScope {
+    #[default]
+    All,
+    /// Only notes whose matched segments overlap a fenced code block.
+    CodeOnly,
+}
+
+impl SearchScope {
+    fn segment_filter(self) -> &'static str {
+        match self {
+            SearchScope::All => "",
+            SearchScope::CodeOnly => "AND s.code_language IS NOT NULL",
+        }
+    }
+}
+
+/// How a query is matched against notes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchMode {
+    /// Keyword ranking blended with vector similarity when embeddings exist.
+    #[default]
+    Hybrid,
+    /// The query is a regular expression scanned over each note's indexed
+    /// text, for patterns full-text search cannot express. Notes are ranked
+    /// by match count.
+    Regex,
+}
+
+/// Order of search results. Dates put the newest first; ties keep score
+/// order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchSort {
+    #[default]
+    Score,
+    Modified,
+    Created,
+    Path,
+}
+
+/// How [`group_search_results`] buckets results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchGroupBy {
+    /// By the note's folder.
+    Folder,
+    /// By each of the note's tags, so a note with several tags is listed
+    /// under each of them.
+    Tag,
+}
+
+/// Filters for a query. Archived notes are left out unless asked for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    pub scope: SearchScope,
+    pub include_archived: bool,
+    pub mode: SearchMode,
+    pub sort: SearchSort,
+    /// Inlines up to this many bytes of each top result's indexed text,
+    /// read from the index rather than the file.
+    pub content_max_bytes: Option<usize>,
+    /// Folds each note whose text nearly matches a better-ranked result into
+    /// that result, so notes made from one template do not fill the list.
+    pub collapse_similar: bool,
+}
+
+impl SearchOptions {
+    fn doc_filter(self) -> &'static str {
+        // Notes with `search: false` are never returned.
+        if self.include_archived {
+            "AND d.searchable = 1"
+        } else {
+            "AND d.searchable = 1 AND d.archived = 0"
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResultGroup {
+    /// Folder relative to the vault root or tag, depending on the grouping.
+    /// Empty for notes at the vault root or without tags.
+    pub key: String,
+    pub entries: Vec<SemanticNoteEntry>,
+}
+
+/// Results of [`search_notes_with_correction`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrectedSearchResults {
+    pub entries: Vec<SemanticNoteEntry>,
+    /// The respelled query `entries` were found with, when the query as typed
+    /// found nothing.
+    pub did_you_mean: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -39,11 +181,36 @@ pub struct TagNoteEntry {
     pub modified_at: Option<i64>,
 }
 
+#[derive(Debug)]
+struct StoredEmbeddingProfile {
+    provider: Option<String>,
+    model: String,
+    doc_count: i64,
+}
+
+impl StoredEmbeddingProfile {
+    fn matches(&self, profile: &EmbeddingProfile) -> bool {
+        self.model == profile.model.trim()
+            && self
+                .provider
+                .as_deref()
+                .is_none_or(|provider| provider == profile.normalized_provider())
+    }
+}
+
+struct QueryEmbedding {
+    provider: String,
+    model: String,
+    dim: i32,
+    bytes: Vec<u8>,
+}
+
 #[derive(Debug, Default)]
 struct DocScore {
     rel_path: String,
     bm25: Option<f32>,
     vector: Option<f32>,
+    passage: Option<MatchedPassage>,
 }
 
 #[derive(Debug, Clone)]
@@ -51,12 +218,21 @@ pub(super) struct ScoreInput {
     pub(super) rel_path: String,
     pub(super) bm25: Option<f32>,
     pub(super) vector: Option<f32>,
+    pub(super) passage: Option<MatchedPassage>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub(super) struct RankedCandidate {
     pub(super) rel_path: String,
     pub(super) similarity: f32,
+    pub(super) passage: Option<MatchedPassage>,
+}
+
+struct VectorScore {
+    doc_id: i64,
+    rel_path: String,
+    score: f32,
+    passage: Option<MatchedPassage>,
 }
 
 pub fn search_notes_for_query(
@@ -65,6 +241,64 @@ pub fn search_notes_for_query(
     query: &str,
     embedding_provider: &str,
     embedding_model: &str,
+) -> Result<Vec<SemanticNoteEntry>> {
+    search_notes_for_query_with_profiles(
+        workspace_root,
+        db_path,
+        query,
+        &[EmbeddingProfile::new(embedding_provider, embedding_model)],
+    )
+}
+
+/// Hybrid search that embeds the query with the profile matching the stored vectors.
+///
+/// Profiles that produced more of the vault's vectors are tried first. When no
+/// profile can embed the query (e.g. the provider is offline), the search falls
+/// back to BM25 ranking instead of failing.
+pub fn search_notes_for_query_with_profiles(
+    workspace_root: &Path,
+    db_path: &Path,
+    query: &str,
+    embedding_profiles: &[EmbeddingProfile],
+) -> Result<Vec<SemanticNoteEntry>> {
+    search_notes_for_query_in_scope(
+        workspace_root,
+        db_path,
+        query,
+        embedding_profiles,
+        SearchScope::All,
+    )
+}
+
+/// Same as [`search_notes_for_query_with_profiles`], restricted to `scope`.
+pub fn search_notes_for_query_in_scope(
+    workspace_root: &Path,
+    db_path: &Path,
+    query: &str,
+    embedding_profiles: &[EmbeddingProfile],
+    scope: SearchScope,
+) -> Result<Vec<SemanticNoteEntry>> {
+    search_notes_for_query_with_options(
+        workspace_root,
+        db_path,
+        query,
+        embedding_profiles,
+        SearchOptions {
+            scope,
+            ..Default::default()
+        },
+    )
+}
+
+/// Same as [`search_notes_for_query_with_profiles`], filtered by `options`.
+/// A query starting with [`REGEX_QUERY_PREFIX`] is run in regex mode
+/// whatever `options.mode` says.
+pub fn search_notes_for_query_with_options(
+    workspace_root: &Path,
+    db_path: &Path,
+    query: &str,
+    embedding_profiles: &[EmbeddingProfile],
+    options: SearchOptions,
 ) -> Result<Vec<SemanticNoteEntry>> {
     if !workspace_root.exists() {
         return Err(anyhow!(
@@ -73,28 +307,14 @@ pub fn search_notes_for_query(
         ));
     }
 
-    let trimmed_query = query.trim();
+    let (trimmed_query, mode) = match query.trim().strip_prefix(REGEX_QUERY_PREFIX) {
+        Some(pattern) => (pattern.trim(), SearchMode::Regex),
+        None => (query.trim(), options.mode),
+    };
     if trimmed_query.is_empty() {
         return Ok(Vec::new());
     }
-
-    let vector_search_input =
-        if embedding_provider.trim().is_empty() || embedding_model.trim().is_empty() {
-            None
-        } else {
-            let embedder = EmbeddingClient::new(embedding_provider, embedding_model)?;
-            let query_embedding = embedder.generate(trimmed_query)?;
-            let query_vector = bytes_to_f32_vec(&query_embedding.bytes)?;
-            if query_vector.is_empty() || !query_vector.iter().all(|value| value.is_finite()) {
-                return Ok(Vec::new());
-            }
-
-            Some((
-                embedder.model_name().to_string(),
-                query_embedding.dim,
-                query_embedding.bytes,
-            ))
-        };
+    let _timer = super::metrics::SEARCH_LATENCY.start_timer();
 
     let conn = open_search_connection(db_path)?;
 
@@ -102,10 +322,37 @@ pub fn search_notes_for_query(
         return Ok(Vec::new());
     };
 
+    if mode == SearchMode::Regex {
+        let ranked_candidates =
+            regex_scan::load_regex_candidates(&conn, vault_id, trimmed_query, options)?;
+        let mut entries = materialize_ranked_entries(workspace_root, ranked_candidates)?;
+        if options.collapse_similar {
+            entries = collapse_similar_results(&conn, workspace_root, vault_id, entries)?;
+        }
+        sort_search_results(&mut entries, options.sort);
+        inline_indexed_content(&conn, workspace_root, vault_id, &mut entries, options)?;
+        return Ok(entries);
+    }
+
+    let parsed_query = query_terms::parse_query(&conn, vault_id, trimmed_query)?;
+    let vector_search_input = match embed_query_for_vault(
+        &conn,
+        db_path,
+        vault_id,
+        embedding_profiles,
+        &parsed_query.embedding_text,
+    )? {
+        QueryEmbeddingOutcome::Embedded(query_embedding) => Some(query_embedding),
+        QueryEmbeddingOutcome::Unavailable => None,
+        QueryEmbeddingOutcome::Invalid => return Ok(Vec::new()),
+    };
+
     let mut scores: HashMap<i64, DocScore> = HashMap::new();
 
-    for (doc_id, rel_path, bm25_score) in load_bm25_scores(&conn, vault_id, trimmed_query)? {
-        if !is_markdown(&rel_path) {
+    for (doc_id, rel_path, bm25_score) in
+        load_bm25_scores(&conn, vault_id, &parsed_query.fts, options)?
+    {
+        if !note::is_indexed_path(&rel_path) {
             continue;
         }
 
@@ -116,24 +363,26 @@ pub fn search_notes_for_query(
         entry.bm25 = Some(bm25_score);
     }
 
-    if let Some((embedding_model_name, embedding_dim, query_embedding_bytes)) = vector_search_input
-    {
-        for (doc_id, rel_path, vector_score) in load_vector_scores(
+    if let Some(query_embedding) = vector_search_input {
+        for vector_score in load_vector_scores(
             &conn,
             vault_id,
-            &embedding_model_name,
-            embedding_dim,
-            &query_embedding_bytes,
+            &query_embedding.provider,
+            &query_embedding.model,
+            query_embedding.dim,
+            &query_embedding.bytes,
+            options,
         )? {
-            if !is_markdown(&rel_path) {
+            if !note::is_indexed_path(&vector_score.rel_path) {
                 continue;
             }
 
-            let entry = scores.entry(doc_id).or_default();
+            let entry = scores.entry(vector_score.doc_id).or_default();
             if entry.rel_path.is_empty() {
-                entry.rel_path = rel_path;
+                entry.rel_path = vector_score.rel_path;
             }
-            entry.vector = Some(vector_score);
+            entry.vector = Some(vector_score.score);
+            entry.passage = vector_score.passage;
         }
     }
 
@@ -143,10 +392,214 @@ pub fn search_notes_for_query(
             rel_path: score.rel_path,
             bm25: score.bm25,
             vector: score.vector,
+            passage: score.passage,
         })
         .collect::<Vec<_>>();
     let ranked_candidates = rank_score_inputs(candidates);
-    materialize_ranked_entries(workspace_root, ranked_candidates)
+    let mut entries = materialize_ranked_entries(workspace_root, ranked_candidates)?;
+    if options.collapse_similar {
+        entries = collapse_similar_results(&conn, workspace_root, vault_id, entries)?;
+    }
+    sort_search_results(&mut entries, options.sort);
+    inline_indexed_content(&conn, workspace_root, vault_id, &mut entries, options)?;
+    Ok(entries)
+}
+
+/// Same as [`search_notes_for_query_with_options`], retried with misspelled
+/// words replaced by indexed terms when the query finds nothing. Regex
+/// queries are never respelled.
+pub fn search_notes_with_correction(
+    workspace_root: &Path,
+    db_path: &Path,
+    query: &str,
+    embedding_profiles: &[EmbeddingProfile],
+    options: SearchOptions,
+) -> Result<CorrectedSearchResults> {
+    let entries = search_notes_for_query_with_options(
+        workspace_root,
+        db_path,
+        query,
+        embedding_profiles,
+        options,
+    )?;
+    let uncorrected = |entries| CorrectedSearchResults {
+        entries,
+        did_you_mean: None,
+    };
+    let trimmed_query = query.trim();
+    if !entries.is_empty()
+        || options.mode == SearchMode::Regex
+        || trimmed_query.starts_with(REGEX_QUERY_PREFIX)
+    {
+        return Ok(uncorrected(entries));
+    }
+
+    let conn = open_search_connection(db_path)?;
+    let Some(vault_id) = super::find_vault_id(&conn, workspace_root)? else {
+        return Ok(uncorrected(entries));
+    };
+    let Some(corrected) = spelling::correct_query(&conn, vault_id, trimmed_query)? else {
+        return Ok(uncorrected(entries));
+    };
+
+    let corrected_entries = search_notes_for_query_with_options(
+        workspace_root,
+        db_path,
+        &corrected,
+        embedding_profiles,
+        options,
+    )?;
+    if corrected_entries.is_empty() {
+        return Ok(uncorrected(entries));
+    }
+    Ok(CorrectedSearchResults {
+        entries: corrected_entries,
+        did_you_mean: Some(corrected),
+    })
+}
+
+/// Folds each entry into the first better-ranked entry whose stored MinHash
+/// signature it nearly matches. `entries` must be in score order.
+fn collapse_similar_results(
+    conn: &Connection,
+    workspace_root: &Path,
+    vault_id: i64,
+    entries: Vec<SemanticNoteEntry>,
+) -> Result<Vec<SemanticNoteEntry>> {
+    let mut stmt = conn
+        .prepare("SELECT minhash FROM doc WHERE vault_id = ?1 AND rel_path = ?2")
+        .context("Failed to prepare MinHash lookup")?;
+
+    let mut collapsed: Vec<(SemanticNoteEntry, Option<Vec<u8>>)> = Vec::new();
+    for entry in entries {
+        let path = Path::new(&entry.path);
+        let rel_path = normalize_rel_path(path.strip_prefix(workspace_root).unwrap_or(path));
+        let signature = stmt
+            .query_row(params![vault_id, rel_path], |row| {
+                row.get::<_, Option<Vec<u8>>>(0)
+            })
+            .optional()
+            .context("Failed to load MinHash signature")?
+            .flatten();
+
+        let representative = signature.as_deref().and_then(|signature| {
+            collapsed.iter_mut().find(|(_, kept)| {
+                kept.as_deref().is_some_and(|kept| {
+                    signature_similarity(kept, signature) >= NEAR_DUPLICATE_SIMILARITY
+                })
+            })
+        });
+        match representative {
+            Some((kept, _)) => kept.similar.push(entry),
+            None => collapsed.push((entry, signature)),
+        }
+    }
+
+    Ok(collapsed.into_iter().map(|(entry, _)| entry).collect())
+}
+
+fn inline_indexed_content(
+    conn: &Connection,
+    workspace_root: &Path,
+    vault_id: i64,
+    entries: &mut [SemanticNoteEntry],
+    options: SearchOptions,
+) -> Result<()> {
+    let Some(max_bytes) = options.content_max_bytes else {
+        return Ok(());
+    };
+
+    let mut stmt = conn
+        .prepare("SELECT content FROM doc WHERE vault_id = ?1 AND rel_path = ?2")
+        .context("Failed to prepare content lookup")?;
+    for entry in entries.iter_mut().take(CONTENT_RESULT_LIMIT) {
+        let path = Path::new(&entry.path);
+        let rel_path = normalize_rel_path(path.strip_prefix(workspace_root).unwrap_or(path));
+        let content = stmt
+            .query_row(params![vault_id, rel_path], |row| {
+                row.get::<_, Option<String>>(0)
+            })
+            .optional()
+            .context("Failed to load indexed content")?
+            .flatten();
+        let Some(mut content) = content else {
+            continue;
+        };
+
+        if content.len() > max_bytes {
+            let mut end = max_bytes;
+            while !content.is_char_boundary(end) {
+                end -= 1;
+            }
+            content.truncate(end);
+            entry.content_truncated = true;
+        }
+        entry.content = Some(content);
+    }
+    Ok(())
+}
+
+/// Buckets `entries` by folder or tag. Groups come in the order of their
+/// first entry and each keeps the order of `entries`, so score-ordered
+/// results give score-ordered groups.
+pub fn group_search_results(
+    workspace_root: &Path,
+    db_path: &Path,
+    entries: Vec<SemanticNoteEntry>,
+    group_by: SearchGroupBy,
+) -> Result<Vec<SearchResultGroup>> {
+    let rel_paths = entries
+        .iter()
+        .map(|entry| {
+            let path = Path::new(&entry.path);
+            normalize_rel_path(path.strip_prefix(workspace_root).unwrap_or(path))
+        })
+        .collect::<Vec<_>>();
+    let tags_by_path = match group_by {
+        SearchGroupBy::Folder => HashMap::new(),
+        SearchGroupBy::Tag => get_note_tags(workspace_root, db_path, &rel_paths)?,
+    };
+
+    let mut groups: Vec<SearchResultGroup> = Vec::new();
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+    for (entry, rel_path) in entries.into_iter().zip(rel_paths) {
+        let keys = match group_by {
+            SearchGroupBy::Folder => vec![rel_path
+                .rsplit_once('/')
+                .map(|(folder, _)| folder.to_string())
+                .unwrap_or_default()],
+            SearchGroupBy::Tag => tags_by_path
+                .get(&rel_path)
+                .cloned()
+                .unwrap_or_else(|| vec![String::new()]),
+        };
+        for key in keys {
+            let index = *group_index.entry(key.clone()).or_insert_with(|| {
+                groups.push(SearchResultGroup {
+                    key,
+                    entries: Vec::new(),
+                });
+                groups.len() - 1
+            });
+            groups[index].entries.push(entry.clone());
+        }
+    }
+
+    Ok(groups)
+}
+
+fn sort_search_results(entries: &mut [SemanticNoteEntry], sort: SearchSort) {
+    // Stable sorts, so equal keys stay in score order.
+    match sort {
+        SearchSort::Score => {}
+        SearchSort::Modified => {
+            entries.sort_by_key(|entry| std::cmp::Reverse(entry.modified_at));
+        }
+        SearchSort::Created => {
+            entries.sort_by_key(|entry| std::cmp::Reverse(entry.created_at));
+        }
+        SearchSort::Path => entries.sort_by_cached_key(|entry| entry.path.to_lowercase()),
+    }
 }
 
 pub fn search_notes_by_tag(
@@ -174,8 +627,84 @@ pub fn search_notes_by_tag(
     materialize_tag_entries(workspace_root, rel_paths)
 }
 
+/// Indexed tags of each note in `rel_paths`, keyed by rel path. Notes without
+/// tags or not yet indexed are left out.
+pub fn get_note_tags(
+    workspace_root: &Path,
+    db_path: &Path,
+    rel_paths: &[String],
+) -> Result<HashMap<String, Vec<String>>> {
+    if rel_paths.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let conn = open_search_connection(db_path)?;
+    let Some(vault_id) = super::find_vault_id(&conn, workspace_root)? else {
+        return Ok(HashMap::new());
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT dt.tag \
+             FROM doc_tag dt \
+             JOIN doc d ON d.id = dt.doc_id \
+             WHERE d.vault_id = ?1 AND d.rel_path = ?2 \
+             ORDER BY dt.normalized_tag",
+        )
+        .context("Failed to prepare note tag query")?;
+
+    let mut tags_by_path = HashMap::new();
+    for rel_path in rel_paths {
+        let tags = stmt
+            .query_map(params![vault_id, rel_path], |row| row.get::<_, String>(0))
+            .context("Failed to run note tag query")?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if !tags.is_empty() {
+            tags_by_path.insert(rel_path.clone(), tags);
+        }
+    }
+
+    Ok(tags_by_path)
+}
+
+/// Frontmatter aliases of every indexed note in the vault, keyed by relative
+/// path, for matching notes by alias in the quick switcher.
+pub fn get_note_aliases(
+    workspace_root: &Path,
+    db_path: &Path,
+) -> Result<HashMap<String, Vec<String>>> {
+    let conn = open_search_connection(db_path)?;
+    let Some(vault_id) = super::find_vault_id(&conn, workspace_root)? else {
+        return Ok(HashMap::new());
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT d.rel_path, da.alias \
+             FROM doc_alias da \
+             JOIN doc d ON d.id = da.doc_id \
+             WHERE d.vault_id = ?1 \
+             ORDER BY d.rel_path, da.rowid",
+        )
+        .context("Failed to prepare note alias query")?;
+
+    let rows = stmt
+        .query_map(params![vault_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .context("Failed to run note alias query")?;
+
+    let mut aliases_by_path: HashMap<String, Vec<String>> = HashMap::new();
+    for row in rows {
+        let (rel_path, alias) = row?;
+        aliases_by_path.entry(rel_path).or_default().push(alias);
+    }
+
+    Ok(aliases_by_path)
+}
+
 fn open_search_connection(db_path: &Path) -> Result<Connection> {
-    app_storage::sqlite_ext::register_auto_extension()?;
+    app_storage::sqlite_ext::vector_support();
 
     let conn = Connection::open(db_path)
         .with_context(|| format!("Failed to open indexing database at {}", db_path.display()))?;
@@ -186,20 +715,138 @@ fn open_search_connection(db_path: &Path) -> Result<Connection> {
     Ok(conn)
 }
 
-fn load_bm25_scores(
+enum QueryEmbeddingOutcome {
+    Embedded(QueryEmbedding),
+    /// No profile could embed the query; ranking falls back to BM25.
+    Unavailable,
+    /// The provider returned an empty or non-finite vector.
+    Invalid,
+}
+
+/// Embeds the query with the first profile that can, trying the profiles
+/// that produced more of the vault's vectors first.
+fn embed_query_for_vault(
     conn: &Connection,
+    db_path: &Path,
     vault_id: i64,
+    embedding_profiles: &[EmbeddingProfile],
     query: &str,
-) -> Result<Vec<(i64, String, f32)>> {
-    let fts_query = build_fts_query(query);
+) -> Result<QueryEmbeddingOutcome> {
+    let stored_profiles = load_stored_embedding_profiles(conn, vault_id)?;
+    let registry = EmbeddingProviderRegistry::load(db_path)?;
+    // Without sqlite-vec the query is not embedded and ranking is BM25 only.
+    let vector_profiles = if app_storage::sqlite_ext::vector_support().available {
+        order_profiles_by_stored_vectors(embedding_profiles, &stored_profiles)
+    } else {
+        Vec::new()
+    };
+    for profile in vector_profiles {
+        let Ok(query_embedding) = embed_query(&registry, profile, query) else {
+            continue;
+        };
+
+        let query_vector = bytes_to_f32_vec(&query_embedding.bytes)?;
+        if query_vector.is_empty() || !query_vector.iter().all(|value| value.is_finite()) {
+            return Ok(QueryEmbeddingOutcome::Invalid);
+        }
+
+        return Ok(QueryEmbeddingOutcome::Embedded(query_embedding));
+    }
+
+    Ok(QueryEmbeddingOutcome::Unavailable)
+}
 
+fn embed_query(
+    registry: &EmbeddingProviderRegistry,
+    profile: &EmbeddingProfile,
+    query: &str,
+) -> Result<QueryEmbedding> {
+    let embedder = EmbeddingClient::new(registry, &profile.provider, &profile.model)?;
+    let embedding = embedder.generate(query)?;
+
+    Ok(QueryEmbedding {
+        provider: embedder.provider_name().to_string(),
+        model: embedder.model_name().to_string(),
+        dim: embedding.dim,
+        bytes: embedding.bytes,
+    })
+}
+
+fn load_stored_embedding_profiles(
+    conn: &Connection,
+    vault_id: i64,
+) -> Result<Vec<StoredEmbeddingProfile>> {
     let mut stmt = conn
         .prepare(
-            "SELECT d.id, d.rel_path, bm25(doc_fts) \
-             FROM doc_fts \
-             JOIN doc d ON d.id = doc_fts.rowid \
-             WHERE d.vault_id = ?1 AND doc_fts MATCH ?2",
+            "SELECT last_embedding_provider, last_embedding_model, COUNT(*) \
+             FROM doc \
+             WHERE vault_id = ?1 AND last_embedding_model IS NOT NULL \
+             GROUP BY last_embedding_provider, last_embedding_model",
         )
+        .context("Failed to prepare stored embedding profile query")?;
+
+    let rows = stmt
+        .query_map(params![vault_id], |row| {
+            Ok(StoredEmbeddingProfile {
+                provider: row.get(0)?,
+                model: row.get(1)?,
+                doc_count: row.get(2)?,
+            })
+        })
+        .context("Failed to load stored embedding profiles")?;
+
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read stored embedding profiles")
+}
+
+fn order_profiles_by_stored_vectors<'a>(
+    profiles: &'a [EmbeddingProfile],
+    stored_profiles: &[StoredEmbeddingProfile],
+) -> Vec<&'a EmbeddingProfile> {
+    let mut ordered = profiles
+        .iter()
+        .filter(|profile| profile.is_configured())
+        .map(|profile| {
+            let stored_doc_count: i64 = stored_profiles
+                .iter()
+                .filter(|stored| stored.matches(profile))
+                .map(|stored| stored.doc_count)
+                .sum();
+            (profile, stored_doc_count)
+        })
+        .collect::<Vec<_>>();
+
+    // Stable sort keeps the configured priority among equally covered profiles.
+    ordered.sort_by_key(|(_, stored_doc_count)| std::cmp::Reverse(*stored_doc_count));
+    ordered.into_iter().map(|(profile, _)| profile).collect()
+}
+
+fn load_bm25_scores(
+    conn: &Connection,
+    vault_id: i64,
+    fts_query: &str,
+    options: SearchOptions,
+) -> Result<Vec<(i64, String, f32)>> {
+    let scope = options.scope;
+    // FTS rows cover whole notes, so scoped keyword hits only require a matching segment.
+    let scope_filter = match scope {
+        SearchScope::All => String::new(),
+        SearchScope::CodeOnly => format!(
+            "AND EXISTS (SELECT 1 FROM segment s WHERE s.doc_id = d.id {})",
+            scope.segment_filter()
+        ),
+    };
+
+    let fts_table = vault_fts_table(conn, vault_id)?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT d.id, d.rel_path, bm25({fts_table}) \
+             FROM {fts_table} \
+             JOIN doc d ON d.id = {fts_table}.rowid \
+             WHERE d.vault_id = ?1 AND {fts_table} MATCH ?2 {scope_filter} {}",
+            options.doc_filter()
+        ))
         .context("Failed to prepare BM25 query")?;
 
     let rows = stmt
@@ -228,31 +875,40 @@ fn load_bm25_scores(
 fn load_vector_scores(
     conn: &Connection,
     vault_id: i64,
+    embedding_provider: &str,
     embedding_model: &str,
     embedding_dim: i32,
     query_embedding_bytes: &[u8],
-) -> Result<Vec<(i64, String, f32)>> {
+    options: SearchOptions,
+) -> Result<Vec<VectorScore>> {
     if !segment_vec_table_exists(conn)? {
         return Ok(Vec::new());
     }
 
+    // SQLite fills bare columns from the row that produced MAX(), which gives us
+    // the location of the best-matching segment for free.
     let mut stmt = conn
-        .prepare(
+        .prepare(&format!(
             "SELECT d.id, d.rel_path, \
                     MAX( \
                         CASE \
                             WHEN length(sv.embedding) = (?3 * 4) \
                             THEN 1.0 - vec_distance_cosine(sv.embedding, vec_f32(?4)) \
                         END \
-                    ) AS vector_score \
+                    ) AS vector_score, \
+                    s.start_offset, s.end_offset, s.heading_path, s.code_language \
              FROM doc d \
              JOIN segment s ON s.doc_id = d.id \
              JOIN segment_vec sv ON sv.rowid = s.id \
              WHERE d.vault_id = ?1 \
                AND d.last_embedding_model = ?2 \
                AND d.last_embedding_dim = ?3 \
+               AND (d.last_embedding_provider IS NULL OR d.last_embedding_provider = ?5) \
+               {} {} \
              GROUP BY d.id, d.rel_path",
-        )
+            options.scope.segment_filter(),
+            options.doc_filter()
+        ))
         .context("Failed to prepare vector similarity query")?;
 
     let rows = stmt
@@ -261,20 +917,22 @@ fn load_vector_scores(
                 vault_id,
                 embedding_model,
                 embedding_dim,
-                query_embedding_bytes
+                query_embedding_bytes,
+                embedding_provider
             ],
             |row| {
                 let doc_id: i64 = row.get(0)?;
                 let rel_path: String = row.get(1)?;
                 let vector_score: Option<f64> = row.get(2)?;
-                Ok((doc_id, rel_path, vector_score))
+                let passage = matched_passage(row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?);
+                Ok((doc_id, rel_path, vector_score, passage))
             },
         )
         .context("Failed to run vector similarity query")?;
 
     let mut output = Vec::new();
     for row in rows {
-        let (doc_id, rel_path, vector_score) = row?;
+        let (doc_id, rel_path, vector_score, passage) = row?;
         let Some(score) = vector_score else {
             continue;
         };
@@ -284,12 +942,38 @@ fn load_vector_scores(
             continue;
         }
 
-        output.push((doc_id, rel_path, score));
+        output.push(VectorScore {
+            doc_id,
+            rel_path,
+            score,
+            passage,
+        });
     }
 
     Ok(output)
 }
 
+fn matched_passage(
+    start_offset: Option<i64>,
+    end_offset: Option<i64>,
+    heading_path: Option<String>,
+    code_language: Option<String>,
+) -> Option<MatchedPassage> {
+    // Segments written before offsets were tracked have no location to report.
+    let start_offset = usize::try_from(start_offset?).ok()?;
+    let end_offset = usize::try_from(end_offset?).ok()?;
+    let heading_path = heading_path
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default();
+
+    Some(MatchedPassage {
+        start_offset,
+        end_offset,
+        heading_path,
+        code_language,
+    })
+}
+
 fn load_tag_scores(conn: &Connection, vault_id: i64, normalized_tag: &str) -> Result<Vec<String>> {
     let descendant_pattern = format!("{}/%", escape_like_pattern(normalized_tag));
 
@@ -313,7 +997,7 @@ fn load_tag_scores(conn: &Connection, vault_id: i64, normalized_tag: &str) -> Re
     let mut output = Vec::new();
     for row in rows {
         let rel_path = row?;
-        if is_markdown(&rel_path) {
+        if note::is_indexed_path(&rel_path) {
             output.push(rel_path);
         }
     }
@@ -390,7 +1074,7 @@ pub(super) fn rank_score_inputs(inputs: Vec<ScoreInput>) -> Vec<RankedCandidate>
 
     let mut ranked = Vec::new();
     for input in inputs {
-        if input.rel_path.is_empty() || !is_markdown(&input.rel_path) {
+        if input.rel_path.is_empty() || !note::is_indexed_path(&input.rel_path) {
             continue;
         }
 
@@ -408,6 +1092,7 @@ pub(super) fn rank_score_inputs(inputs: Vec<ScoreInput>) -> Vec<RankedCandidate>
         ranked.push(RankedCandidate {
             rel_path: input.rel_path,
             similarity: final_score,
+            passage: input.passage,
         });
     }
 
@@ -426,8 +1111,10 @@ pub(super) fn materialize_ranked_entries(
 ) -> Result<Vec<SemanticNoteEntry>> {
     let mut entries = Vec::new();
     for candidate in ranked_candidates {
-        let absolute_path = workspace_root.join(&candidate.rel_path);
-        if let Some(entry) = build_semantic_entry(absolute_path, candidate.similarity)? {
+        let absolute_path = disk_path(workspace_root, &candidate.rel_path);
+        if let Some(entry) =
+            build_semantic_entry(absolute_path, candidate.similarity, candidate.passage)?
+        {
             entries.push(entry);
         }
     }
@@ -440,7 +1127,7 @@ pub(super) fn materialize_tag_entries(
 ) -> Result<Vec<TagNoteEntry>> {
     let mut entries = Vec::new();
     for rel_path in rel_paths {
-        let absolute_path = workspace_root.join(rel_path);
+        let absolute_path = disk_path(workspace_root, &rel_path);
         if let Some(entry) = build_tag_entry(absolute_path)? {
             entries.push(entry);
         }
@@ -473,15 +1160,11 @@ fn bytes_to_f32_vec(bytes: &[u8]) -> Result<Vec<f32>> {
     Ok(values)
 }
 
-fn is_markdown(path: &str) -> bool {
-    Path::new(path)
-        .extension()
-        .and_then(OsStr::to_str)
-        .map(|ext| ext.eq_ignore_ascii_case("md"))
-        .unwrap_or(false)
-}
-
-fn build_semantic_entry(path: PathBuf, similarity: f32) -> Result<Option<SemanticNoteEntry>> {
+fn build_semantic_entry(
+    path: PathBuf,
+    similarity: f32,
+    passage: Option<MatchedPassage>,
+) -> Result<Option<SemanticNoteEntry>> {
     let Some(entry) = build_fs_entry(path, Some(MIN_NOTE_BYTES))? else {
         return Ok(None);
     };
@@ -492,6 +1175,10 @@ fn build_semantic_entry(path: PathBuf, similarity: f32) -> Result<Option<Semanti
         created_at: entry.created_at,
         modified_at: entry.modified_at,
         similarity,
+        passage,
+        content: None,
+        content_truncated: false,
+        similar: Vec::new(),
     }))
 }
 
@@ -547,7 +1234,7 @@ fn build_fs_entry(path: PathBuf, min_bytes: Option<u64>) -> Result<Option<FsNote
     }))
 }
 
-fn escape_like_pattern(input: &str) -> String {
+pub(super) fn escape_like_pattern(input: &str) -> String {
     let mut output = String::with_capacity(input.len());
     for ch in input.chars() {
         match ch {
@@ -571,7 +1258,7 @@ fn system_time_to_millis(time: SystemTime) -> Option<i64> {
 mod tests {
     use rusqlite::{params, Connection};
 
-    use super::{escape_like_pattern, load_tag_scores, load_vector_scores};
+    use super::{escape_like_pattern, load_tag_scores, load_vector_scores, SearchOptions};
 
     fn embedding_bytes(dim: usize) -> Vec<u8> {
         let mut bytes = Vec::with_capacity(dim * 4);
@@ -592,8 +1279,11 @@ mod tests {
                  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL, \
                  vault_id INTEGER NOT NULL, \
                  rel_path TEXT NOT NULL, \
+                 last_embedding_provider TEXT, \
                  last_embedding_model TEXT, \
-                 last_embedding_dim INTEGER \
+                 last_embedding_dim INTEGER, \
+                 archived INTEGER NOT NULL DEFAULT 0, \
+                 searchable INTEGER NOT NULL DEFAULT 1 \
              ); \
              CREATE TABLE doc_tag ( \
                  doc_id INTEGER NOT NULL, \
@@ -603,6 +1293,10 @@ mod tests {
              CREATE TABLE segment ( \
                  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL, \
                  doc_id INTEGER NOT NULL, \
+                 start_offset INTEGER, \
+                 end_offset INTEGER, \
+                 heading_path TEXT, \
+                 code_language TEXT, \
                  FOREIGN KEY (doc_id) REFERENCES doc(id) ON DELETE CASCADE \
              ); \
              CREATE TABLE segment_vec ( \
@@ -638,8 +1332,16 @@ mod tests {
         .expect("failed to insert mismatched embedding");
 
         let query_embedding = embedding_bytes(3);
-        let results = load_vector_scores(&conn, 10, "model-a", 3, &query_embedding)
-            .expect("vector score loading should not fail");
+        let results = load_vector_scores(
+            &conn,
+            10,
+            "test",
+            "model-a",
+            3,
+            &query_embedding,
+            SearchOptions::default(),
+        )
+        .expect("vector score loading should not fail");
 
         assert!(results.is_empty());
     }
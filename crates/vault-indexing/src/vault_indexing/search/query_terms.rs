@@ -0,0 +1,164 @@
+//! Applies a vault's stopwords and synonyms to a query before it reaches the
+//! keyword index and the embedding provider.
+
+use anyhow::Result;
+use app_storage::search_vocabulary::{load_search_vocabulary, SearchVocabulary};
+use rusqlite::Connection;
+
+use super::build_fts_query;
+
+// Each synonym substitution adds a phrase to the MATCH expression; a query
+// full of paired terms stops here rather than growing without bound.
+const MAX_QUERY_VARIANTS: usize = 8;
+
+/// A query as each side of hybrid search should see it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ParsedQuery {
+    /// FTS5 MATCH expression.
+    pub fts: String,
+    /// Text to embed: the query without stopwords, followed by any synonyms.
+    pub embedding_text: String,
+}
+
+/// Without stopwords or synonyms in play, `query` is searched as one phrase
+/// and embedded as typed.
+pub(super) fn parse_query(conn: &Connection, vault_id: i64, query: &str) -> Result<ParsedQuery> {
+    let vocabulary = load_search_vocabulary(conn, vault_id)?;
+    Ok(apply_vocabulary(&vocabulary, query))
+}
+
+fn apply_vocabulary(vocabulary: &SearchVocabulary, query: &str) -> ParsedQuery {
+    let unchanged = || ParsedQuery {
+        fts: build_fts_query(query),
+        embedding_text: query.to_string(),
+    };
+    if vocabulary.is_empty() {
+        return unchanged();
+    }
+
+    let words = query.split_whitespace().collect::<Vec<_>>();
+    let mut kept = words
+        .iter()
+        .copied()
+        .filter(|word| !vocabulary.stopwords.contains(&word.to_lowercase()))
+        .collect::<Vec<_>>();
+    // A query made only of stopwords is searched as typed.
+    if kept.is_empty() {
+        kept = words.clone();
+    }
+    let dropped_stopwords = kept.len() < words.len();
+    let terms = kept
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>();
+
+    let mut variants = vec![terms.clone()];
+    let mut added_synonyms: Vec<&str> = Vec::new();
+    'spans: for start in 0..terms.len() {
+        for end in start + 1..=terms.len() {
+            let Some(synonyms) = vocabulary.synonyms.get(&terms[start..end].join(" ")) else {
+                continue;
+            };
+            for synonym in synonyms {
+                if variants.len() == MAX_QUERY_VARIANTS {
+                    break 'spans;
+                }
+                let mut variant = terms[..start].to_vec();
+                variant.extend(synonym.split(' ').map(str::to_string));
+                variant.extend_from_slice(&terms[end..]);
+                variants.push(variant);
+                if !added_synonyms.contains(&synonym.as_str()) {
+                    added_synonyms.push(synonym);
+                }
+            }
+        }
+    }
+    if variants.len() == 1 && !dropped_stopwords {
+        return unchanged();
+    }
+
+    // With stopwords gone the remaining words need not be adjacent, so they
+    // are matched separately rather than as a phrase.
+    let fts = variants
+        .iter()
+        .map(|variant| {
+            if dropped_stopwords {
+                let words = variant
+                    .iter()
+                    .map(|word| build_fts_query(word))
+                    .collect::<Vec<_>>();
+                format!("({})", words.join(" AND "))
+            } else {
+                build_fts_query(&variant.join(" "))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" OR ");
+
+    let mut embedding_words = kept;
+    embedding_words.extend(added_synonyms);
+    ParsedQuery {
+        fts,
+        embedding_text: embedding_words.join(" "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use app_storage::search_vocabulary::SearchVocabulary;
+
+    use super::{apply_vocabulary, ParsedQuery};
+
+    fn vocabulary(pairs: &[(&str, &str)], stopwords: &[&str]) -> SearchVocabulary {
+        let mut vocabulary = SearchVocabulary::default();
+        for (term, synonym) in pairs {
+            vocabulary
+                .synonyms
+                .entry(term.to_string())
+                .or_default()
+                .push(synonym.to_string());
+            vocabulary
+                .synonyms
+                .entry(synonym.to_string())
+                .or_default()
+                .push(term.to_string());
+        }
+        vocabulary.stopwords = stopwords.iter().map(|word| word.to_string()).collect();
+        vocabulary
+    }
+
+    #[test]
+    fn query_without_vocabulary_is_one_phrase() {
+        let parsed = apply_vocabulary(&vocabulary(&[("k8s", "kubernetes")], &[]), "Deploy \"app\"");
+        assert_eq!(
+            parsed,
+            ParsedQuery {
+                fts: "\"Deploy \"\"app\"\"\"".to_string(),
+                embedding_text: "Deploy \"app\"".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn synonyms_add_phrases_and_embedding_terms() {
+        let parsed = apply_vocabulary(
+            &vocabulary(&[("k8s", "kubernetes"), ("ml", "machine learning")], &[]),
+            "K8s ML",
+        );
+        assert_eq!(
+            parsed.fts,
+            "\"k8s ml\" OR \"kubernetes ml\" OR \"k8s machine learning\""
+        );
+        assert_eq!(parsed.embedding_text, "K8s ML kubernetes machine learning");
+    }
+
+    #[test]
+    fn stopwords_are_dropped_and_the_rest_matched_apart() {
+        let parsed = apply_vocabulary(&vocabulary(&[], &["the", "of"]), "state of the art");
+        assert_eq!(parsed.fts, "(\"state\" AND \"art\")");
+        assert_eq!(parsed.embedding_text, "state art");
+
+        let only_stopwords = apply_vocabulary(&vocabulary(&[], &["the"]), "The");
+        assert_eq!(only_stopwords.fts, "\"The\"");
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+use rusqlite::{params, Connection};
+
+use super::{RankedCandidate, SearchOptions, SearchScope};
+
+// Scanning stops here and returns what matched so far, so a slow pattern
+// over a large vault cannot stall the search box.
+const REGEX_TIME_BUDGET: Duration = Duration::from_secs(2);
+const MAX_REGEX_SIZE: usize = 1 << 20;
+
+/// Notes whose indexed text matches `pattern`, ranked by match count.
+pub(super) fn load_regex_candidates(
+    conn: &Connection,
+    vault_id: i64,
+    pattern: &str,
+    options: SearchOptions,
+) -> Result<Vec<RankedCandidate>> {
+    let regex = RegexBuilder::new(pattern)
+        .multi_line(true)
+        .size_limit(MAX_REGEX_SIZE)
+        .build()
+        .with_context(|| format!("Invalid regex query: {pattern}"))?;
+    let docs = load_doc_contents(conn, vault_id, options)?;
+    let counts = count_matches(&regex, &docs, Instant::now() + REGEX_TIME_BUDGET);
+
+    let max_count = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    let mut ranked = counts
+        .into_iter()
+        .map(|(index, count)| RankedCandidate {
+            rel_path: docs[index].0.clone(),
+            similarity: count as f32 / max_count as f32,
+            passage: None,
+        })
+        .collect::<Vec<_>>();
+    ranked.sort_by(|left, right| {
+        right
+            .similarity
+            .total_cmp(&left.similarity)
+            .then_with(|| left.rel_path.cmp(&right.rel_path))
+    });
+    Ok(ranked)
+}
+
+fn load_doc_contents(
+    conn: &Connection,
+    vault_id: i64,
+    options: SearchOptions,
+) -> Result<Vec<(String, String)>> {
+    let scope_filter = match options.scope {
+        SearchScope::All => String::new(),
+        SearchScope::CodeOnly => format!(
+            "AND EXISTS (SELECT 1 FROM segment s WHERE s.doc_id = d.id {})",
+            options.scope.segment_filter()
+        ),
+    };
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT d.rel_path, d.content FROM doc d \
+             WHERE d.vault_id = ?1 {scope_filter} {}",
+            options.doc_filter()
+        ))
+        .context("Failed to prepare regex search query")?;
+    let docs = stmt
+        .query_map(params![vault_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .context("Failed to load notes for regex search")?
+        .collect::<rusqlite::Result<Vec<(String, String)>>>()
+        .context("Failed to read notes for regex search")?;
+
+    Ok(docs)
+}
+
+/// Match counts by index into `docs`, for the notes with any match. Notes
+/// are handed out to one worker per core until `deadline` passes.
+fn count_matches(
+    regex: &Regex,
+    docs: &[(String, String)],
+    deadline: Instant,
+) -> Vec<(usize, usize)> {
+    let workers = thread::available_parallelism()
+        .map_or(1, usize::from)
+        .min(docs.len());
+    let next = AtomicUsize::new(0);
+    let counts = Mutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                let mut local = Vec::new();
+                while Instant::now() < deadline {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some((_, content)) = docs.get(index) else {
+                        break;
+                    };
+                    let count = regex
+                        .find_iter(content)
+                        .filter(|found| !found.is_empty())
+                        .count();
+                    if count > 0 {
+                        local.push((index, count));
+                    }
+                }
+                counts
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .extend(local);
+            });
+        }
+    });
+
+    counts
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
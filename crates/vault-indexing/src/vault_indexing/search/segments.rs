@@ -0,0 +1,301 @@
+use std::{cmp::Ordering, collections::HashMap, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use super::super::files::disk_path;
+use super::{
+    embed_query_for_vault, matched_passage, metric_bounds, normalize_metric,
+    open_search_connection, segment_vec_table_exists, EmbeddingProfile, MatchedPassage,
+    QueryEmbedding, QueryEmbeddingOutcome, BM25_WEIGHT, MIN_FINAL_SCORE, VECTOR_WEIGHT,
+};
+
+// Each side ranks more segments than asked for, so a segment strong on only
+// one of them can still make the blended cut.
+const CANDIDATE_MULTIPLIER: usize = 4;
+
+/// One chunk of a note matched on its own, for callers that feed passages
+/// rather than whole notes into a prompt.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentMatch {
+    pub path: String,
+    pub rel_path: String,
+    pub ordinal: i64,
+    /// The segment as it was embedded, headed by the note title and its
+    /// headings.
+    pub text: String,
+    #[serde(flatten)]
+    pub passage: MatchedPassage,
+    /// Cosine similarity to the query; missing when the query could not be
+    /// embedded or the segment ranked too low.
+    pub vector_score: Option<f32>,
+    /// Inverted BM25 over segment text, so larger is better; missing when the
+    /// segment does not contain the query.
+    pub bm25_score: Option<f32>,
+    /// Both scores normalized over the candidates and blended as note search
+    /// does.
+    pub score: f32,
+}
+
+#[derive(Debug, Default)]
+struct SegmentScore {
+    bm25: Option<f32>,
+    vector: Option<f32>,
+}
+
+/// Ranks individual segments against `query`, without folding them into
+/// their notes. Only notes indexed with embeddings have segments, so a vault
+/// without them returns nothing.
+pub fn search_segments(
+    workspace_root: &Path,
+    db_path: &Path,
+    query: &str,
+    embedding_profiles: &[EmbeddingProfile],
+    limit: usize,
+) -> Result<Vec<SegmentMatch>> {
+    search_segments_where(
+        workspace_root,
+        db_path,
+        query,
+        embedding_profiles,
+        limit,
+        |_| true,
+    )
+}
+
+/// [`search_segments`] keeping only the segments `keep` accepts. The filter
+/// runs before the limit, so segments it drops do not use up result slots.
+pub fn search_segments_where(
+    workspace_root: &Path,
+    db_path: &Path,
+    query: &str,
+    embedding_profiles: &[EmbeddingProfile],
+    limit: usize,
+    mut keep: impl FnMut(&SegmentMatch) -> bool,
+) -> Result<Vec<SegmentMatch>> {
+    if !workspace_root.exists() {
+        return Err(anyhow!(
+            "Workspace path does not exist: {}",
+            workspace_root.display()
+        ));
+    }
+
+    let trimmed_query = query.trim();
+    if trimmed_query.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+    let _timer = super::super::metrics::SEARCH_LATENCY.start_timer();
+
+    let conn = open_search_connection(db_path)?;
+    let Some(vault_id) = super::super::find_vault_id(&conn, workspace_root)? else {
+        return Ok(Vec::new());
+    };
+
+    let parsed_query = super::query_terms::parse_query(&conn, vault_id, trimmed_query)?;
+    let candidate_limit = limit.saturating_mul(CANDIDATE_MULTIPLIER);
+    let mut scores: HashMap<i64, SegmentScore> = HashMap::new();
+    for (segment_id, bm25) in
+        load_segment_bm25_scores(&conn, vault_id, &parsed_query.fts, candidate_limit)?
+    {
+        scores.entry(segment_id).or_default().bm25 = Some(bm25);
+    }
+
+    let query_embedding = match embed_query_for_vault(
+        &conn,
+        db_path,
+        vault_id,
+        embedding_profiles,
+        &parsed_query.embedding_text,
+    )? {
+        QueryEmbeddingOutcome::Embedded(query_embedding) => Some(query_embedding),
+        QueryEmbeddingOutcome::Unavailable => None,
+        QueryEmbeddingOutcome::Invalid => return Ok(Vec::new()),
+    };
+    if let Some(query_embedding) = query_embedding {
+        for (segment_id, vector) in
+            load_segment_vector_scores(&conn, vault_id, &query_embedding, candidate_limit)?
+        {
+            scores.entry(segment_id).or_default().vector = Some(vector);
+        }
+    }
+
+    let bm25_bounds = metric_bounds(scores.values().filter_map(|score| score.bm25));
+    let vector_bounds = metric_bounds(scores.values().filter_map(|score| score.vector));
+    let mut ranked = scores
+        .into_iter()
+        .filter_map(|(segment_id, score)| {
+            let bm25_norm = normalize_metric(score.bm25, bm25_bounds);
+            let final_score = if vector_bounds.is_some() {
+                normalize_metric(score.vector, vector_bounds) * VECTOR_WEIGHT
+                    + bm25_norm * BM25_WEIGHT
+            } else {
+                bm25_norm
+            };
+            (final_score.is_finite() && final_score >= MIN_FINAL_SCORE).then_some((
+                segment_id,
+                score,
+                final_score,
+            ))
+        })
+        .collect::<Vec<_>>();
+    ranked.sort_by(|left, right| right.2.partial_cmp(&left.2).unwrap_or(Ordering::Equal));
+
+    let mut matches = Vec::new();
+    for (segment_id, score, final_score) in ranked {
+        if matches.len() == limit {
+            break;
+        }
+        if let Some(segment) =
+            load_segment_match(&conn, workspace_root, segment_id, score, final_score)?
+        {
+            if keep(&segment) {
+                matches.push(segment);
+            }
+        }
+    }
+    Ok(matches)
+}
+
+fn load_segment_bm25_scores(
+    conn: &Connection,
+    vault_id: i64,
+    fts_query: &str,
+    limit: usize,
+) -> Result<Vec<(i64, f32)>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, bm25(segment_fts) \
+             FROM segment_fts \
+             JOIN segment s ON s.id = segment_fts.rowid \
+             JOIN doc d ON d.id = s.doc_id \
+             WHERE d.vault_id = ?1 AND d.archived = 0 AND d.searchable = 1 \
+               AND segment_fts MATCH ?2 \
+             ORDER BY bm25(segment_fts) \
+             LIMIT ?3",
+        )
+        .context("Failed to prepare segment BM25 query")?;
+
+    let rows = stmt
+        .query_map(params![vault_id, fts_query, limit as i64], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)? as f32))
+        })
+        .context("Failed to run segment BM25 query")?;
+
+    let mut output = Vec::new();
+    for row in rows {
+        let (segment_id, bm25_raw) = row?;
+        if bm25_raw.is_finite() {
+            // Lower BM25 values are better; invert so larger means more relevant.
+            output.push((segment_id, -bm25_raw));
+        }
+    }
+    Ok(output)
+}
+
+fn load_segment_vector_scores(
+    conn: &Connection,
+    vault_id: i64,
+    query_embedding: &QueryEmbedding,
+    limit: usize,
+) -> Result<Vec<(i64, f32)>> {
+    if !segment_vec_table_exists(conn)? {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, 1.0 - vec_distance_cosine(sv.embedding, vec_f32(?4)) AS vector_score \
+             FROM doc d \
+             JOIN segment s ON s.doc_id = d.id \
+             JOIN segment_vec sv ON sv.rowid = s.id \
+             WHERE d.vault_id = ?1 \
+               AND d.archived = 0 AND d.searchable = 1 \
+               AND d.last_embedding_model = ?2 \
+               AND d.last_embedding_dim = ?3 \
+               AND (d.last_embedding_provider IS NULL OR d.last_embedding_provider = ?5) \
+               AND length(sv.embedding) = (?3 * 4) \
+             ORDER BY vector_score DESC \
+             LIMIT ?6",
+        )
+        .context("Failed to prepare segment similarity query")?;
+
+    let rows = stmt
+        .query_map(
+            params![
+                vault_id,
+                query_embedding.model,
+                query_embedding.dim,
+                query_embedding.bytes,
+                query_embedding.provider,
+                limit as i64
+            ],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Option<f64>>(1)?)),
+        )
+        .context("Failed to run segment similarity query")?;
+
+    let mut output = Vec::new();
+    for row in rows {
+        let (segment_id, score) = row?;
+        if let Some(score) = score
+            .map(|score| score as f32)
+            .filter(|score| score.is_finite())
+        {
+            output.push((segment_id, score));
+        }
+    }
+    Ok(output)
+}
+
+fn load_segment_match(
+    conn: &Connection,
+    workspace_root: &Path,
+    segment_id: i64,
+    score: SegmentScore,
+    final_score: f32,
+) -> Result<Option<SegmentMatch>> {
+    let row = conn
+        .query_row(
+            "SELECT d.rel_path, s.ordinal, s.text, \
+                    s.start_offset, s.end_offset, s.heading_path, s.code_language \
+             FROM segment s \
+             JOIN doc d ON d.id = s.doc_id \
+             WHERE s.id = ?1",
+            params![segment_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    matched_passage(row.get(3)?, row.get(4)?, row.get(5)?, row.get(6)?),
+                ))
+            },
+        )
+        .optional()
+        .with_context(|| format!("Failed to load segment {segment_id}"))?;
+
+    let Some((rel_path, ordinal, text, passage)) = row else {
+        return Ok(None);
+    };
+    // Segments written before offsets were tracked cannot be located.
+    let Some(passage) = passage else {
+        return Ok(None);
+    };
+    if !note::is_indexed_path(&rel_path) {
+        return Ok(None);
+    }
+
+    Ok(Some(SegmentMatch {
+        path: disk_path(workspace_root, &rel_path)
+            .to_string_lossy()
+            .into_owned(),
+        rel_path,
+        ordinal,
+        text,
+        passage,
+        vector_score: score.vector,
+        bm25_score: score.bm25,
+        score: final_score,
+    }))
+}
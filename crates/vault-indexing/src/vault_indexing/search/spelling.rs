@@ -0,0 +1,146 @@
+//! Spelling correction for keyword queries that match nothing, drawn from the
+//! terms of the full-text index.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+use super::super::fts_tokenizer::{built_tokenizer, vault_fts_table};
+
+// Shorter words have too many neighbours one edit away to guess from.
+const MIN_CORRECTED_CHARS: usize = 3;
+// Words up to this long may be one edit off; longer ones two.
+const ONE_EDIT_MAX_CHARS: usize = 4;
+
+/// `query` with each word the index does not know replaced by the closest
+/// indexed term, or `None` when no word could be corrected.
+///
+/// Terms come from the vault's FTS table; the shared table also holds other
+/// vaults' terms, so callers rerun the search to confirm the correction.
+pub(super) fn correct_query(
+    conn: &Connection,
+    vault_id: i64,
+    query: &str,
+) -> Result<Option<String>> {
+    // Trigram terms are fragments of words, not words to suggest.
+    if built_tokenizer(conn, vault_id)? == "trigram" {
+        return Ok(None);
+    }
+    let terms = load_term_doc_counts(conn, &vault_fts_table(conn, vault_id)?)?;
+
+    let mut corrected_any = false;
+    let words = query
+        .split_whitespace()
+        .map(|word| match correct_word(&terms, word) {
+            Some(correction) => {
+                corrected_any = true;
+                correction
+            }
+            None => word.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(corrected_any.then(|| words.join(" ")))
+}
+
+fn load_term_doc_counts(conn: &Connection, fts_table: &str) -> Result<HashMap<String, i64>> {
+    conn.execute_batch(&format!(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS temp.{fts_table}_vocab \
+         USING fts5vocab(main, {fts_table}, row)"
+    ))
+    .with_context(|| format!("Failed to open the term list of {fts_table}"))?;
+
+    let mut stmt = conn
+        .prepare(&format!("SELECT term, doc FROM temp.{fts_table}_vocab"))
+        .context("Failed to prepare term query")?;
+    let terms = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .context("Failed to load index terms")?
+        .collect::<rusqlite::Result<HashMap<String, i64>>>()
+        .context("Failed to read index terms")?;
+    Ok(terms)
+}
+
+/// The indexed term closest to `word`, preferring the one in more notes.
+fn correct_word(terms: &HashMap<String, i64>, word: &str) -> Option<String> {
+    if !word.chars().all(char::is_alphanumeric) {
+        return None;
+    }
+    let word = word.to_lowercase();
+    let chars = word.chars().collect::<Vec<_>>();
+    if chars.len() < MIN_CORRECTED_CHARS || terms.contains_key(&word) {
+        return None;
+    }
+    let max_edits = if chars.len() <= ONE_EDIT_MAX_CHARS {
+        1
+    } else {
+        2
+    };
+
+    terms
+        .iter()
+        .filter(|(term, _)| term.chars().count().abs_diff(chars.len()) <= max_edits)
+        .filter_map(|(term, doc_count)| {
+            let distance = edit_distance(&chars, &term.chars().collect::<Vec<_>>());
+            (distance <= max_edits).then_some((distance, -doc_count, term))
+        })
+        .min()
+        .map(|(_, _, term)| term.clone())
+}
+
+/// Levenshtein distance counting a swap of adjacent characters as one edit.
+fn edit_distance(left: &[char], right: &[char]) -> usize {
+    let mut rows = vec![vec![0; right.len() + 1]; left.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=left.len() {
+        for j in 1..=right.len() {
+            let substitution = usize::from(left[i - 1] != right[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + substitution);
+            if i > 1 && j > 1 && left[i - 1] == right[j - 2] && left[i - 2] == right[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[left.len()][right.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{correct_word, edit_distance};
+
+    #[test]
+    fn edit_distance_counts_swaps_as_one_edit() {
+        let chars = |word: &str| word.chars().collect::<Vec<_>>();
+        assert_eq!(edit_distance(&chars("garden"), &chars("garden")), 0);
+        assert_eq!(edit_distance(&chars("gardne"), &chars("garden")), 1);
+        assert_eq!(edit_distance(&chars("gaden"), &chars("garden")), 1);
+        assert_eq!(edit_distance(&chars("kitten"), &chars("sitting")), 3);
+    }
+
+    #[test]
+    fn misspelled_words_take_the_closest_and_most_common_term() {
+        let terms = HashMap::from([
+            ("compost".to_string(), 4),
+            ("compose".to_string(), 9),
+            ("garden".to_string(), 2),
+        ]);
+
+        assert_eq!(correct_word(&terms, "Gardne").as_deref(), Some("garden"));
+        assert_eq!(correct_word(&terms, "composd").as_deref(), Some("compose"));
+        assert_eq!(correct_word(&terms, "garden"), None, "known words stay");
+        assert_eq!(correct_word(&terms, "xyzzy"), None);
+        assert_eq!(correct_word(&terms, "gd"), None);
+    }
+}
@@ -0,0 +1,142 @@
+use std::{fmt, io, path::Path};
+
+use serde::Serialize;
+
+use super::cloud_files::CLOUD_PLACEHOLDER_SKIP_REASON;
+
+/// Why a file was left out of an index run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipErrorCode {
+    PermissionDenied,
+    /// The file went away between discovery and reading.
+    NotFound,
+    /// A cloud placeholder whose contents are not on this device yet.
+    CloudPlaceholder,
+    /// Not valid UTF-8, or a drawing that could not be parsed.
+    InvalidContent,
+    /// A drawing over the vault's size cap, which cannot be read in part.
+    TooLarge,
+    /// Any other read failure, such as a network volume dropping out.
+    Io,
+    /// The embedding provider failed; the note waits in the pending queue.
+    Embedding,
+    /// Writing the note to the index failed.
+    Index,
+}
+
+impl SkipErrorCode {
+    /// Whether indexing the file again may succeed without anyone editing it
+    /// or changing its permissions.
+    pub fn is_retryable(self) -> bool {
+        match self {
+            Self::CloudPlaceholder | Self::Io | Self::Embedding | Self::Index => true,
+            Self::PermissionDenied | Self::NotFound | Self::InvalidContent | Self::TooLarge => {
+                false
+            }
+        }
+    }
+
+    fn from_read_error(error: &anyhow::Error) -> Self {
+        if error.downcast_ref::<&str>() == Some(&CLOUD_PLACEHOLDER_SKIP_REASON) {
+            return Self::CloudPlaceholder;
+        }
+        let Some(io_error) = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<io::Error>())
+        else {
+            return Self::InvalidContent;
+        };
+        match io_error.kind() {
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            io::ErrorKind::NotFound => Self::NotFound,
+            io::ErrorKind::InvalidData => Self::InvalidContent,
+            io::ErrorKind::FileTooLarge => Self::TooLarge,
+            _ => Self::Io,
+        }
+    }
+}
+
+/// A file an index run could not index, with its previous index entry kept.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedFile {
+    /// Absolute path, as accepted by the single-note indexing entry points.
+    pub path: String,
+    pub error_code: SkipErrorCode,
+    pub message: String,
+    pub retryable: bool,
+}
+
+impl SkippedFile {
+    pub(crate) fn new(path: &Path, error_code: SkipErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            path: path.display().to_string(),
+            error_code,
+            message: message.into(),
+            retryable: error_code.is_retryable(),
+        }
+    }
+
+    /// A file whose source could not be read.
+    pub(crate) fn read_failed(path: &Path, error: &anyhow::Error) -> Self {
+        Self::new(
+            path,
+            SkipErrorCode::from_read_error(error),
+            error.to_string(),
+        )
+    }
+
+    pub(crate) fn index_failed(path: &Path, error: &anyhow::Error) -> Self {
+        Self::new(path, SkipErrorCode::Index, error.to_string())
+    }
+
+    pub(crate) fn embedding_failed(path: &Path, error: &anyhow::Error) -> Self {
+        Self::new(path, SkipErrorCode::Embedding, error.to_string())
+    }
+}
+
+impl fmt::Display for SkippedFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, path::Path};
+
+    use anyhow::{anyhow, Context};
+
+    use super::{SkipErrorCode, SkippedFile, CLOUD_PLACEHOLDER_SKIP_REASON};
+
+    #[test]
+    fn read_errors_are_classified_by_cause() {
+        let path = Path::new("/vault/a.md");
+        let denied = Err::<(), _>(io::Error::from(io::ErrorKind::PermissionDenied))
+            .context("Failed to read file /vault/a.md")
+            .unwrap_err();
+        let skipped = SkippedFile::read_failed(path, &denied);
+        assert_eq!(skipped.error_code, SkipErrorCode::PermissionDenied);
+        assert!(!skipped.retryable);
+        assert_eq!(
+            skipped.to_string(),
+            "/vault/a.md: Failed to read file /vault/a.md"
+        );
+
+        let placeholder = SkippedFile::read_failed(path, &anyhow!(CLOUD_PLACEHOLDER_SKIP_REASON));
+        assert_eq!(placeholder.error_code, SkipErrorCode::CloudPlaceholder);
+        assert!(placeholder.retryable);
+
+        let timed_out = anyhow::Error::new(io::Error::from(io::ErrorKind::TimedOut));
+        assert_eq!(
+            SkippedFile::read_failed(path, &timed_out).error_code,
+            SkipErrorCode::Io
+        );
+
+        let invalid = anyhow!("Drawing is not valid JSON");
+        assert_eq!(
+            SkippedFile::read_failed(path, &invalid).error_code,
+            SkipErrorCode::InvalidContent
+        );
+    }
+}
@@ -1,75 +1,285 @@
 use std::{
     collections::{HashMap, HashSet},
-    fs,
     path::Path,
 };
 
-use anyhow::{Context, Result};
+use anyhow::Result;
+use app_storage::vault_settings::{load_vault_setting, VaultSettingKey};
+use note::{FrontmatterValue, IndexingTextOptions};
 use rusqlite::Connection;
 
 use super::{
-    chunking::{chunk_document, hash_content},
-    files::MarkdownFile,
+    aliases::NoteAlias,
+    chunking::{chunk_note, DocumentChunk},
+    dates::NoteDate,
+    files::{disk_path, read_indexed_source, MarkdownFile, DEFAULT_MAX_SOURCE_BYTES},
+    geo::NoteLocation,
     links::LinkResolver,
+    properties::NoteProperty,
+    query_block::QueryBlockSource,
+    script::{detect_language, TextLanguage},
+    skipped_files::{SkipErrorCode, SkippedFile},
     tags::NoteTag,
     EmbeddingContext, IndexSummary, TARGET_CHUNKING_VERSION,
 };
 
+mod activity_log;
+mod alias_refresh;
+mod citation_refresh;
+mod date_refresh;
 mod doc_repo;
+mod embedding_cache;
 mod link_refresh;
+mod location_refresh;
+mod pending_embedding;
 mod policy;
+mod property_refresh;
+mod query_source_refresh;
 mod segment_sync;
 mod tag_refresh;
+mod warm_start;
 
+use activity_log::record_doc_activity;
+pub(crate) use alias_refresh::load_note_aliases;
+use alias_refresh::replace_aliases_for_doc;
+use citation_refresh::replace_citations_for_doc;
+use date_refresh::replace_dates_for_doc;
 use doc_repo::{
-    ensure_docs_for_files, load_docs, remove_deleted_docs, update_embedding_metadata,
-    update_hash_and_content, update_source_stat, DocRecord,
+    ensure_docs_for_files, load_docs, record_embedded_hash, remove_deleted_docs,
+    update_embedding_metadata, update_hash_and_content, update_source_stat, DocRecord,
 };
+use embedding_cache::prune_embedding_cache;
 use link_refresh::{
     bind_unresolved_links_for_inserted_docs, collect_query_keys_for_paths,
     load_forced_link_refresh_doc_ids, rel_path_query_keys, replace_links_for_doc,
 };
+use location_refresh::replace_location_for_doc;
+use pending_embedding::{clear_pending_embedding, enqueue_pending_embeddings};
 use policy::{
     can_skip_file_without_loading, decide_document_sync_action, embedding_target_changed,
     FileSyncAction,
 };
+use property_refresh::replace_properties_for_doc;
+use query_source_refresh::replace_query_sources_for_doc;
 use segment_sync::{rebuild_doc_chunks, segments_match_current_chunks, sync_segments_for_doc};
 use tag_refresh::replace_tags_for_doc;
+pub(crate) use warm_start::{count_current_files, plan_warm_start, WarmStart};
 
 pub(crate) struct PreparedDocument {
     pub(crate) file: MarkdownFile,
     contents: String,
+    /// Only the head of an oversized file was read into `contents`.
+    pub(crate) truncated: bool,
+    // `contents` with indexing noise blanked out; byte offsets still match `contents`.
+    chunk_source: String,
     doc_hash: String,
     indexed_content: String,
+    language: TextLanguage,
+    flags: IndexingFlags,
     note_tags: Vec<NoteTag>,
+    note_aliases: Vec<NoteAlias>,
+    note_dates: Vec<NoteDate>,
+    note_location: Option<NoteLocation>,
+    note_properties: Vec<NoteProperty>,
+    query_sources: Vec<QueryBlockSource>,
+    citation_keys: Vec<String>,
 }
 
 impl PreparedDocument {
-    pub(crate) fn load(file: MarkdownFile) -> Result<Self> {
-        let contents = fs::read_to_string(&file.abs_path)
-            .with_context(|| format!("Failed to read file {}", file.abs_path.display()))?;
-        let doc_hash = hash_content(&contents);
-        let indexed_content = note::format_indexing_text(&contents);
+    pub(crate) fn load(file: MarkdownFile, options: &IndexingOptions) -> Result<Self> {
+        let source = read_indexed_source(&file.abs_path, options.max_source_bytes)?;
+        let (contents, doc_hash, truncated) = (source.contents, source.hash, source.truncated);
+        let indexed_content = note::format_indexing_text_with_options(&contents, &options.text);
+        let chunk_source = note::mask_indexing_noise(&contents, &options.text);
+        let language = detect_language(&indexed_content);
+        let flags = IndexingFlags::from_source(&contents);
         let note_tags = super::tags::extract_note_tags(&contents);
+        let note_aliases = super::aliases::extract_note_aliases(&contents);
+        let note_dates = super::dates::extract_note_dates(&contents);
+        let note_location = super::geo::extract_note_location(&contents);
+        let note_properties = super::properties::extract_note_properties(&contents);
+        let query_sources =
+            super::query_block::extract_query_block_sources(&contents, &file.rel_path);
+        let citation_keys = super::citations::extract_citation_keys(&contents);
 
         Ok(Self {
             file,
             contents,
+            truncated,
+            chunk_source,
             doc_hash,
             indexed_content,
+            language,
+            flags,
             note_tags,
+            note_aliases,
+            note_dates,
+            note_location,
+            note_properties,
+            query_sources,
+            citation_keys,
         })
     }
 
-    fn chunks(&self) -> Vec<String> {
-        chunk_document(&self.contents, TARGET_CHUNKING_VERSION)
+    pub(crate) fn chunks(&self) -> Vec<DocumentChunk> {
+        // The note title leads every chunk breadcrumb.
+        let title = super::graph_node_name(&self.file.rel_path);
+        let mut chunks = chunk_note(&self.chunk_source, &title, TARGET_CHUNKING_VERSION);
+        for chunk in &mut chunks {
+            chunk.text = collapse_blanked_runs(&chunk.text);
+        }
+        chunks
+    }
+
+    /// Raw text of `chunk` with indexing noise removed and no breadcrumb line.
+    pub(crate) fn chunk_body(&self, chunk: &DocumentChunk) -> String {
+        let body = self
+            .chunk_source
+            .get(chunk.start_offset..chunk.end_offset)
+            .unwrap_or_default();
+        collapse_blanked_runs(body).trim().to_string()
+    }
+}
+
+/// Frontmatter property that keeps a note out of search when `false`.
+const SEARCH_PROPERTY: &str = "search";
+/// Frontmatter property that keeps a note from being embedded when `false`.
+const EMBED_PROPERTY: &str = "embed";
+
+/// Frontmatter switches for how much of a note is indexed. Every note keeps
+/// its doc row, so links, tags and the graph still see it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct IndexingFlags {
+    pub(crate) archived: bool,
+    /// Off with `search: false`: left out of the full-text index and of
+    /// search results.
+    pub(crate) searchable: bool,
+    /// Off with `embed: false`, or when the note is not searchable.
+    pub(crate) embeddable: bool,
+}
+
+impl IndexingFlags {
+    fn from_source(contents: &str) -> Self {
+        let properties = note::parse_frontmatter(contents).properties;
+        let value_of = |key: &str| {
+            properties
+                .iter()
+                .find(|property| property.key == key)
+                .map(|property| &property.value)
+        };
+        let disabled = |key: &str| value_of(key) == Some(&FrontmatterValue::Boolean(false));
+
+        let searchable = !disabled(SEARCH_PROPERTY);
+        Self {
+            archived: value_of(note::ARCHIVED_PROPERTY) == Some(&FrontmatterValue::Boolean(true)),
+            searchable,
+            embeddable: searchable && !disabled(EMBED_PROPERTY),
+        }
     }
 }
 
+// Masked noise leaves long runs of spaces behind. Shorter runs are kept so
+// indentation inside code blocks survives.
+const MIN_BLANKED_RUN_LEN: usize = 32;
+
+fn collapse_blanked_runs(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut run = 0usize;
+    for ch in text.chars() {
+        if ch == ' ' {
+            run += 1;
+            continue;
+        }
+        flush_space_run(&mut output, run);
+        run = 0;
+        output.push(ch);
+    }
+    flush_space_run(&mut output, run);
+    output
+}
+
+fn flush_space_run(output: &mut String, run: usize) {
+    let kept = if run >= MIN_BLANKED_RUN_LEN { 1 } else { run };
+    output.extend(std::iter::repeat_n(' ', kept));
+}
+
+/// Per-vault indexing text settings, such as frontmatter keys to leave out.
+/// How a vault's source files are read and turned into indexed text.
+#[derive(Debug, Clone)]
+pub(crate) struct IndexingOptions {
+    pub(crate) text: IndexingTextOptions,
+    /// Files above this size are indexed by their head only.
+    pub(crate) max_source_bytes: u64,
+}
+
+impl Default for IndexingOptions {
+    fn default() -> Self {
+        Self {
+            text: IndexingTextOptions::default(),
+            max_source_bytes: DEFAULT_MAX_SOURCE_BYTES,
+        }
+    }
+}
+
+pub(crate) fn load_indexing_options(conn: &Connection, vault_id: i64) -> Result<IndexingOptions> {
+    let max_source_megabytes =
+        load_vault_setting(conn, vault_id, VaultSettingKey::MaxIndexedFileMegabytes)?
+            .value
+            .as_u64();
+    Ok(IndexingOptions {
+        text: IndexingTextOptions {
+            excluded_frontmatter_keys: app_storage::vault::load_excluded_frontmatter_keys(
+                conn, vault_id,
+            )?,
+        },
+        max_source_bytes: max_source_megabytes.map_or(DEFAULT_MAX_SOURCE_BYTES, |megabytes| {
+            megabytes * 1024 * 1024
+        }),
+    })
+}
+
 pub(super) fn clear_segment_vectors_for_vault(conn: &Connection, vault_id: i64) -> Result<()> {
     segment_sync::clear_segment_vectors_for_vault(conn, vault_id)
 }
 
+pub(super) fn load_pending_embedding_rel_paths(
+    conn: &Connection,
+    vault_id: i64,
+) -> Result<Vec<(i64, String)>> {
+    pending_embedding::load_pending_embedding_rel_paths(conn, vault_id)
+}
+
+pub(super) fn count_pending_embeddings(conn: &Connection, vault_id: i64) -> Result<usize> {
+    pending_embedding::count_pending_embeddings(conn, vault_id)
+}
+
+pub(super) fn clear_pending_embeddings(conn: &Connection, doc_ids: &[i64]) -> Result<()> {
+    for doc_id in doc_ids {
+        clear_pending_embedding(conn, *doc_id)?;
+    }
+    Ok(())
+}
+
+/// Queue every prepared document for a later embedding pass. Used when no
+/// embedding provider could be reached so text indexing can still complete.
+pub(crate) fn queue_pending_embeddings_for_prepared(
+    conn: &Connection,
+    vault_id: i64,
+    prepared_documents: &[PreparedDocument],
+    error: &str,
+    summary: &mut IndexSummary,
+) -> Result<()> {
+    let existing_docs = load_docs(conn, vault_id)?;
+    let doc_ids = prepared_documents
+        .iter()
+        .filter_map(|prepared| existing_docs.get(&prepared.file.rel_path))
+        .map(|doc| doc.id)
+        .collect::<Vec<_>>();
+
+    summary.embeddings_pending += enqueue_pending_embeddings(conn, &doc_ids, error)?;
+    Ok(())
+}
+
 pub(crate) fn sync_documents_with_prune(
     conn: &mut Connection,
     workspace_root: &Path,
@@ -80,10 +290,12 @@ pub(crate) fn sync_documents_with_prune(
     prune_deleted_docs: bool,
 ) -> Result<Vec<PreparedDocument>> {
     let mut existing_docs = load_docs(conn, vault_id)?;
+    let options = load_indexing_options(conn, vault_id)?;
     let discovered: HashSet<String> = files.iter().map(|file| file.rel_path.clone()).collect();
+    let note_aliases = load_note_aliases(conn, vault_id)?;
 
     let deleted_rel_paths = if prune_deleted_docs {
-        remove_deleted_docs(conn, &mut existing_docs, &discovered, summary)?
+        remove_deleted_docs(conn, vault_id, &mut existing_docs, &discovered, summary)?
     } else {
         Vec::new()
     };
@@ -92,6 +304,15 @@ pub(crate) fn sync_documents_with_prune(
     bind_unresolved_links_for_inserted_docs(conn, &inserted_docs)?;
 
     let mut affected_query_keys = collect_query_keys_for_paths(&deleted_rel_paths);
+    // Aliases of deleted docs went with them, so links through those aliases are stale too.
+    for (alias, rel_paths) in &note_aliases {
+        if rel_paths
+            .iter()
+            .any(|rel_path| deleted_rel_paths.contains(rel_path))
+        {
+            affected_query_keys.insert(alias.clone());
+        }
+    }
     for (rel_path, _doc_id) in &inserted_docs {
         for key in rel_path_query_keys(rel_path) {
             affected_query_keys.insert(key);
@@ -105,9 +326,10 @@ pub(crate) fn sync_documents_with_prune(
         .iter()
         .map(|(rel_path, doc)| (rel_path.clone(), doc.id))
         .collect::<HashMap<_, _>>();
-    let link_resolver = LinkResolver::new(workspace_root, docs_by_path);
+    let link_resolver = LinkResolver::new(workspace_root, docs_by_path).with_aliases(&note_aliases);
 
     let mut prepared_documents = Vec::with_capacity(files.len());
+    let mut changed_aliases = HashSet::new();
     for file in files {
         let force_link_refresh_for_doc = existing_docs
             .get(&file.rel_path)
@@ -115,9 +337,10 @@ pub(crate) fn sync_documents_with_prune(
             .unwrap_or(false);
 
         let Some(doc_record) = existing_docs.get(&file.rel_path) else {
-            summary.skipped_files.push(format!(
-                "{}: Missing document row during indexing",
-                file.abs_path.display()
+            summary.skipped_files.push(SkippedFile::new(
+                &file.abs_path,
+                SkipErrorCode::Index,
+                "Missing document row during indexing",
             ));
             continue;
         };
@@ -128,20 +351,24 @@ pub(crate) fn sync_documents_with_prune(
         }
 
         let abs_path = file.abs_path.clone();
-        let prepared = match PreparedDocument::load(file) {
+        let prepared = match PreparedDocument::load(file, &options) {
             Ok(prepared) => prepared,
             Err(error) => {
                 summary
                     .skipped_files
-                    .push(format!("{}: {}", abs_path.display(), error));
+                    .push(SkippedFile::read_failed(&abs_path, &error));
                 continue;
             }
         };
+        if prepared.truncated {
+            summary.truncated_files.push(prepared.file.rel_path.clone());
+        }
 
         let Some(doc_record) = existing_docs.get_mut(&prepared.file.rel_path) else {
-            summary.skipped_files.push(format!(
-                "{}: Missing document row during indexing",
-                prepared.file.abs_path.display()
+            summary.skipped_files.push(SkippedFile::new(
+                &prepared.file.abs_path,
+                SkipErrorCode::Index,
+                "Missing document row during indexing",
             ));
             continue;
         };
@@ -152,11 +379,12 @@ pub(crate) fn sync_documents_with_prune(
             &prepared,
             &link_resolver,
             force_link_refresh_for_doc,
+            &mut changed_aliases,
             summary,
         ) {
             summary
                 .skipped_files
-                .push(format!("{}: {}", prepared.file.abs_path.display(), error));
+                .push(SkippedFile::index_failed(&prepared.file.abs_path, &error));
             continue;
         }
 
@@ -164,9 +392,57 @@ pub(crate) fn sync_documents_with_prune(
         prepared_documents.push(prepared);
     }
 
+    refresh_links_for_changed_aliases(conn, workspace_root, vault_id, &changed_aliases, summary)?;
+
     Ok(prepared_documents)
 }
 
+/// Links are resolved against the aliases stored before this run, so notes
+/// linking to an alias that was just added or removed are resolved again.
+fn refresh_links_for_changed_aliases(
+    conn: &mut Connection,
+    workspace_root: &Path,
+    vault_id: i64,
+    changed_aliases: &HashSet<String>,
+    summary: &mut IndexSummary,
+) -> Result<()> {
+    let doc_ids = load_forced_link_refresh_doc_ids(conn, vault_id, changed_aliases)?;
+    if doc_ids.is_empty() {
+        return Ok(());
+    }
+
+    let docs = load_docs(conn, vault_id)?;
+    let note_aliases = load_note_aliases(conn, vault_id)?;
+    let docs_by_path = docs
+        .iter()
+        .map(|(rel_path, doc)| (rel_path.clone(), doc.id))
+        .collect::<HashMap<_, _>>();
+    let link_resolver = LinkResolver::new(workspace_root, docs_by_path).with_aliases(&note_aliases);
+
+    for (rel_path, doc) in &docs {
+        if !doc_ids.contains(&doc.id) {
+            continue;
+        }
+
+        let file =
+            MarkdownFile::from_abs_and_rel(disk_path(workspace_root, rel_path), rel_path.clone());
+        let contents = match read_indexed_source(&file.abs_path, DEFAULT_MAX_SOURCE_BYTES) {
+            Ok(source) => source.contents,
+            Err(error) => {
+                summary
+                    .skipped_files
+                    .push(SkippedFile::read_failed(&file.abs_path, &error));
+                continue;
+            }
+        };
+
+        let resolution = link_resolver.resolve_links_with_dependencies(&file, &contents);
+        replace_links_for_doc(conn, doc.id, &resolution, summary)?;
+    }
+
+    Ok(())
+}
+
 pub(crate) fn sync_embeddings_for_prepared(
     conn: &mut Connection,
     vault_id: i64,
@@ -182,19 +458,25 @@ pub(crate) fn sync_embeddings_for_prepared(
             continue;
         };
 
-        if let Err(error) = sync_embedding_phase(conn, doc_record, &prepared, embedding, summary) {
-            summary
-                .skipped_files
-                .push(format!("{}: {}", prepared.file.abs_path.display(), error));
+        if let Err(error) = sync_embedding_phase(conn, doc_record, prepared, embedding, summary) {
+            summary.embeddings_pending +=
+                enqueue_pending_embeddings(conn, &[doc_record.id], &format!("{error:#}"))?;
+            summary.skipped_files.push(SkippedFile::embedding_failed(
+                &prepared.file.abs_path,
+                &error,
+            ));
             continue;
         }
 
+        clear_pending_embedding(conn, doc_record.id)?;
+        record_embedded_hash(conn, doc_record)?;
+
         if count_processed_files {
             summary.files_processed += 1;
         }
     }
 
-    Ok(())
+    prune_embedding_cache(conn, embedding)
 }
 
 fn sync_document_phase(
@@ -203,6 +485,7 @@ fn sync_document_phase(
     prepared: &PreparedDocument,
     link_resolver: &LinkResolver,
     force_link_refresh_for_doc: bool,
+    changed_aliases: &mut HashSet<String>,
     summary: &mut IndexSummary,
 ) -> Result<()> {
     let source_stat_changed =
@@ -228,11 +511,24 @@ fn sync_document_phase(
     }
 
     replace_tags_for_doc(conn, doc_record.id, &prepared.note_tags)?;
+    replace_dates_for_doc(conn, doc_record.id, &prepared.note_dates)?;
+    replace_location_for_doc(conn, doc_record.id, prepared.note_location.as_ref())?;
+    replace_properties_for_doc(conn, doc_record.id, &prepared.note_properties)?;
+    replace_query_sources_for_doc(conn, doc_record.id, &prepared.query_sources)?;
+    replace_citations_for_doc(conn, doc_record.id, &prepared.citation_keys)?;
+    changed_aliases.extend(replace_aliases_for_doc(
+        conn,
+        doc_record.id,
+        &prepared.note_aliases,
+    )?);
+    record_doc_activity(conn, doc_record, &prepared.file, &prepared.indexed_content)?;
     update_hash_and_content(
         conn,
         doc_record,
         &prepared.doc_hash,
         &prepared.indexed_content,
+        prepared.language.code(),
+        prepared.flags,
         &prepared.file,
     )
 }
@@ -250,10 +546,17 @@ fn sync_embedding_phase(
 
     let embedding_target_changed = embedding_target_changed(
         doc_record,
+        embedding.embedder.provider_name(),
         embedding.embedder.model_name(),
         embedding.target_dim,
     );
-    let chunks = prepared.chunks();
+    // A note kept out of embedding syncs to no segments, which drops any it
+    // had and still records the target so it is not reloaded every run.
+    let chunks = if prepared.flags.embeddable {
+        prepared.chunks()
+    } else {
+        Vec::new()
+    };
 
     if doc_record.chunking_version == TARGET_CHUNKING_VERSION
         && !embedding_target_changed
@@ -263,10 +566,11 @@ fn sync_embedding_phase(
     }
 
     if doc_record.chunking_version != TARGET_CHUNKING_VERSION {
-        rebuild_doc_chunks(conn, doc_record.id, &chunks, &embedding.embedder, summary)?;
+        rebuild_doc_chunks(conn, doc_record.id, &chunks, embedding, summary)?;
         return update_embedding_metadata(
             conn,
             doc_record,
+            embedding.embedder.provider_name(),
             embedding.embedder.model_name(),
             embedding.target_dim,
         );
@@ -276,7 +580,7 @@ fn sync_embedding_phase(
         conn,
         doc_record.id,
         &chunks,
-        &embedding.embedder,
+        embedding,
         embedding_target_changed,
         summary,
     )?;
@@ -285,6 +589,7 @@ fn sync_embedding_phase(
         update_embedding_metadata(
             conn,
             doc_record,
+            embedding.embedder.provider_name(),
             embedding.embedder.model_name(),
             embedding.target_dim,
         )?;
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+use super::super::{files::MarkdownFile, script::split_words};
+use super::doc_repo::DocRecord;
+
+/// Adds a content change of `file` to the activity of the local day it was
+/// written on, and logs the revision's word delta against the previously
+/// indexed content. Must run before the doc row takes the new source stat
+/// and content, since both are measured against the stored ones.
+pub(super) fn record_doc_activity(
+    conn: &Connection,
+    doc_record: &DocRecord,
+    file: &MarkdownFile,
+    indexed_content: &str,
+) -> Result<()> {
+    let bytes_added = file
+        .last_source_size
+        .map_or(0, |size| size - doc_record.last_source_size.unwrap_or(0))
+        .max(0);
+
+    let day: String = conn
+        .query_row(
+            "SELECT date(COALESCE(?1 / 1000000000, strftime('%s', 'now')), 'unixepoch', 'localtime')",
+            params![file.last_source_mtime_ns],
+            |row| row.get(0),
+        )
+        .context("Failed to compute activity day")?;
+
+    // A note counts once per day however often it is saved.
+    let first_touch = conn
+        .execute(
+            "UPDATE doc SET last_activity_day = ?1 \
+             WHERE id = ?2 AND last_activity_day IS NOT ?1",
+            params![day, doc_record.id],
+        )
+        .with_context(|| format!("Failed to update activity day of doc {}", doc_record.id))?;
+
+    conn.execute(
+        "INSERT INTO vault_activity_day (vault_id, day, notes_touched, bytes_added) \
+         SELECT vault_id, ?1, ?2, ?3 FROM doc WHERE id = ?4 \
+         ON CONFLICT(vault_id, day) DO UPDATE SET \
+             notes_touched = notes_touched + excluded.notes_touched, \
+             bytes_added = bytes_added + excluded.bytes_added",
+        params![day, first_touch as i64, bytes_added, doc_record.id],
+    )
+    .with_context(|| format!("Failed to record activity of doc {}", doc_record.id))?;
+
+    let previous_content: String = conn
+        .query_row(
+            "SELECT content FROM doc WHERE id = ?1",
+            params![doc_record.id],
+            |row| row.get(0),
+        )
+        .with_context(|| format!("Failed to load previous content of doc {}", doc_record.id))?;
+    let delta = word_delta(&previous_content, indexed_content);
+
+    conn.execute(
+        "INSERT INTO doc_activity \
+             (vault_id, doc_id, rel_path, day, words_added, words_removed, word_count) \
+         SELECT vault_id, id, rel_path, ?1, ?2, ?3, ?4 FROM doc WHERE id = ?5",
+        params![
+            day,
+            delta.added as i64,
+            delta.removed as i64,
+            delta.word_count as i64,
+            doc_record.id
+        ],
+    )
+    .with_context(|| format!("Failed to record word delta of doc {}", doc_record.id))?;
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct WordDelta {
+    added: usize,
+    removed: usize,
+    /// Words in the new content.
+    word_count: usize,
+}
+
+/// Words gained and lost between two revisions, compared as multisets so
+/// moving a paragraph around counts as neither.
+fn word_delta(previous: &str, current: &str) -> WordDelta {
+    let mut counts: HashMap<String, i64> = HashMap::new();
+    let mut word_count = 0;
+    for word in split_words(current) {
+        *counts.entry(word.to_lowercase()).or_default() += 1;
+        word_count += 1;
+    }
+    for word in split_words(previous) {
+        *counts.entry(word.to_lowercase()).or_default() -= 1;
+    }
+
+    let mut delta = WordDelta {
+        added: 0,
+        removed: 0,
+        word_count,
+    };
+    for count in counts.into_values() {
+        if count > 0 {
+            delta.added += count as usize;
+        } else {
+            delta.removed += count.unsigned_abs() as usize;
+        }
+    }
+    delta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{word_delta, WordDelta};
+
+    #[test]
+    fn counts_words_gained_and_lost_regardless_of_order() {
+        assert_eq!(
+            word_delta("The quick brown fox", "brown fox, the QUICK red fox"),
+            WordDelta {
+                added: 2,
+                removed: 0,
+                word_count: 6
+            }
+        );
+        assert_eq!(
+            word_delta("one two three", "one four"),
+            WordDelta {
+                added: 1,
+                removed: 2,
+                word_count: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+use crate::vault_indexing::aliases::NoteAlias;
+
+/// Rewrites the aliases of a document and returns the normalized aliases that
+/// were added or removed, so links written against them can be refreshed.
+pub(super) fn replace_aliases_for_doc(
+    conn: &mut Connection,
+    doc_id: i64,
+    aliases: &[NoteAlias],
+) -> Result<HashSet<String>> {
+    let tx = conn
+        .transaction()
+        .with_context(|| format!("Failed to start alias transaction for doc {}", doc_id))?;
+
+    let previous = {
+        let mut stmt = tx
+            .prepare("SELECT normalized_alias FROM doc_alias WHERE doc_id = ?1")
+            .with_context(|| format!("Failed to prepare alias query for doc {}", doc_id))?;
+        let rows = stmt
+            .query_map(params![doc_id], |row| row.get::<_, String>(0))
+            .with_context(|| format!("Failed to load aliases for doc {}", doc_id))?;
+        rows.collect::<rusqlite::Result<HashSet<_>>>()?
+    };
+
+    tx.execute("DELETE FROM doc_alias WHERE doc_id = ?1", params![doc_id])
+        .with_context(|| format!("Failed to clear aliases for doc {}", doc_id))?;
+
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO doc_alias (doc_id, alias, normalized_alias) \
+                 VALUES (?1, ?2, ?3)",
+            )
+            .with_context(|| format!("Failed to prepare alias insert for doc {}", doc_id))?;
+
+        for alias in aliases {
+            stmt.execute(params![
+                doc_id,
+                alias.alias.as_str(),
+                alias.normalized_alias.as_str()
+            ])
+            .with_context(|| {
+                format!(
+                    "Failed to insert alias '{}' for doc {}",
+                    alias.normalized_alias, doc_id
+                )
+            })?;
+        }
+    }
+
+    tx.commit()
+        .with_context(|| format!("Failed to commit aliases for doc {}", doc_id))?;
+
+    let current = aliases
+        .iter()
+        .map(|alias| alias.normalized_alias.clone())
+        .collect::<HashSet<_>>();
+    Ok(previous.symmetric_difference(&current).cloned().collect())
+}
+
+/// Relative paths of the documents in a vault, keyed by normalized alias.
+pub(crate) fn load_note_aliases(
+    conn: &Connection,
+    vault_id: i64,
+) -> Result<HashMap<String, Vec<String>>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT da.normalized_alias, d.rel_path \
+             FROM doc_alias da \
+             JOIN doc d ON d.id = da.doc_id \
+             WHERE d.vault_id = ?1",
+        )
+        .context("Failed to prepare note alias query")?;
+
+    let rows = stmt
+        .query_map(params![vault_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .context("Failed to load note aliases")?;
+
+    let mut aliases: HashMap<String, Vec<String>> = HashMap::new();
+    for row in rows {
+        let (normalized_alias, rel_path) = row?;
+        aliases.entry(normalized_alias).or_default().push(rel_path);
+    }
+
+    Ok(aliases)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rusqlite::{params, Connection};
+
+    use super::{load_note_aliases, replace_aliases_for_doc};
+    use crate::vault_indexing::aliases::NoteAlias;
+
+    fn open_connection() -> Connection {
+        let conn = Connection::open_in_memory().expect("failed to open in-memory db");
+        conn.pragma_update(None, "foreign_keys", 1)
+            .expect("failed to enable foreign keys");
+        conn.execute_batch(
+            "CREATE TABLE doc (
+                 id INTEGER PRIMARY KEY,
+                 vault_id INTEGER NOT NULL,
+                 rel_path TEXT NOT NULL
+             );
+             CREATE TABLE doc_alias (
+                 doc_id INTEGER NOT NULL,
+                 alias TEXT NOT NULL,
+                 normalized_alias TEXT NOT NULL,
+                 FOREIGN KEY (doc_id) REFERENCES doc(id) ON DELETE CASCADE
+             );",
+        )
+        .expect("failed to create alias tables");
+        conn
+    }
+
+    fn alias(alias: &str) -> NoteAlias {
+        NoteAlias {
+            alias: alias.to_string(),
+            normalized_alias: alias.to_lowercase(),
+        }
+    }
+
+    #[test]
+    fn replace_aliases_for_doc_reports_changed_aliases() {
+        let mut conn = open_connection();
+        conn.execute(
+            "INSERT INTO doc (id, vault_id, rel_path) VALUES (?1, ?2, ?3)",
+            params![1, 7, "notes/alpha.md"],
+        )
+        .expect("failed to insert doc");
+
+        let changed = replace_aliases_for_doc(&mut conn, 1, &[alias("Alpha"), alias("Old")])
+            .expect("alias refresh should succeed");
+        assert_eq!(
+            changed,
+            HashSet::from(["alpha".to_string(), "old".to_string()])
+        );
+
+        let changed = replace_aliases_for_doc(&mut conn, 1, &[alias("Alpha"), alias("New")])
+            .expect("alias refresh should succeed");
+        assert_eq!(
+            changed,
+            HashSet::from(["old".to_string(), "new".to_string()])
+        );
+
+        let aliases = load_note_aliases(&conn, 7).expect("aliases should load");
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases["new"], vec!["notes/alpha.md".to_string()]);
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+pub(super) fn replace_citations_for_doc(
+    conn: &mut Connection,
+    doc_id: i64,
+    citation_keys: &[String],
+) -> Result<()> {
+    let tx = conn
+        .transaction()
+        .with_context(|| format!("Failed to start citation transaction for doc {}", doc_id))?;
+
+    tx.execute(
+        "DELETE FROM doc_citation WHERE doc_id = ?1",
+        params![doc_id],
+    )
+    .with_context(|| format!("Failed to clear citations for doc {}", doc_id))?;
+
+    {
+        let mut stmt = tx
+            .prepare("INSERT INTO doc_citation (doc_id, citation_key) VALUES (?1, ?2)")
+            .with_context(|| format!("Failed to prepare citation insert for doc {}", doc_id))?;
+
+        for key in citation_keys {
+            stmt.execute(params![doc_id, key]).with_context(|| {
+                format!("Failed to insert citation '{}' for doc {}", key, doc_id)
+            })?;
+        }
+    }
+
+    tx.commit()
+        .with_context(|| format!("Failed to commit citations for doc {}", doc_id))?;
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+use crate::vault_indexing::dates::NoteDate;
+
+pub(super) fn replace_dates_for_doc(
+    conn: &mut Connection,
+    doc_id: i64,
+    dates: &[NoteDate],
+) -> Result<()> {
+    let tx = conn
+        .transaction()
+        .with_context(|| format!("Failed to start date transaction for doc {}", doc_id))?;
+
+    tx.execute("DELETE FROM doc_date WHERE doc_id = ?1", params![doc_id])
+        .with_context(|| format!("Failed to clear dates for doc {}", doc_id))?;
+
+    {
+        let mut stmt = tx
+            .prepare("INSERT INTO doc_date (doc_id, property, date) VALUES (?1, ?2, ?3)")
+            .with_context(|| format!("Failed to prepare date insert for doc {}", doc_id))?;
+
+        for date in dates {
+            stmt.execute(params![doc_id, date.property.as_str(), date.date.as_str()])
+                .with_context(|| {
+                    format!(
+                        "Failed to insert date '{}' for doc {}",
+                        date.property, doc_id
+                    )
+                })?;
+        }
+    }
+
+    tx.commit()
+        .with_context(|| format!("Failed to commit dates for doc {}", doc_id))?;
+
+    Ok(())
+}
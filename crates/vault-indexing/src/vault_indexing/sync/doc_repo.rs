@@ -3,7 +3,12 @@ use std::collections::{HashMap, HashSet};
 use anyhow::{Context, Result};
 use rusqlite::{params, Connection, Row};
 
-use super::super::{files::MarkdownFile, IndexSummary, TARGET_CHUNKING_VERSION};
+use super::super::{
+    files::{read_indexed_source, MarkdownFile, DEFAULT_MAX_SOURCE_BYTES},
+    minhash::minhash_signature,
+    tombstones, IndexSummary, TARGET_CHUNKING_VERSION,
+};
+use super::IndexingFlags;
 
 #[derive(Debug, Clone)]
 pub(super) struct DocRecord {
@@ -12,6 +17,7 @@ pub(super) struct DocRecord {
     pub(super) last_hash: Option<String>,
     pub(super) last_source_size: Option<i64>,
     pub(super) last_source_mtime_ns: Option<i64>,
+    pub(super) last_embedding_provider: Option<String>,
     pub(super) last_embedding_model: Option<String>,
     pub(super) last_embedding_dim: Option<i32>,
 }
@@ -27,6 +33,7 @@ impl DocRecord {
             last_source_mtime_ns: row.get::<_, Option<i64>>(5)?,
             last_embedding_model: row.get::<_, Option<String>>(6)?,
             last_embedding_dim: row.get::<_, Option<i32>>(7)?,
+            last_embedding_provider: row.get::<_, Option<String>>(8)?,
         };
         Ok((rel_path, record))
     }
@@ -35,8 +42,21 @@ impl DocRecord {
         self.last_hash.as_deref() == Some(doc_hash)
     }
 
-    pub(super) fn embedding_target_matches(&self, model: &str, target_dim: i32) -> bool {
-        self.last_embedding_model.as_deref() == Some(model)
+    pub(super) fn embedding_target_matches(
+        &self,
+        provider: &str,
+        model: &str,
+        target_dim: i32,
+    ) -> bool {
+        // Rows embedded before profiles were tracked have no provider recorded;
+        // treat them as belonging to whichever profile uses the same model.
+        let provider_matches = self
+            .last_embedding_provider
+            .as_deref()
+            .is_none_or(|stored| stored == provider);
+
+        provider_matches
+            && self.last_embedding_model.as_deref() == Some(model)
             && self.last_embedding_dim == Some(target_dim)
     }
 
@@ -66,19 +86,23 @@ enum DocUpdate<'a> {
     HashAndContent {
         doc_hash: &'a str,
         indexed_content: &'a str,
+        language: &'a str,
+        flags: IndexingFlags,
         file: &'a MarkdownFile,
     },
     EmbeddingMetadata {
+        provider: &'a str,
         model: &'a str,
         target_dim: i32,
     },
+    EmbeddedHash,
 }
 
 pub(super) fn load_docs(conn: &Connection, vault_id: i64) -> Result<HashMap<String, DocRecord>> {
     let mut stmt = conn
         .prepare(
             "SELECT id, rel_path, chunking_version, last_hash, last_source_size, last_source_mtime_ns, \
-                    last_embedding_model, last_embedding_dim \
+                    last_embedding_model, last_embedding_dim, last_embedding_provider \
              FROM doc WHERE vault_id = ?1",
         )
         .context("Failed to prepare statement to load documents")?;
@@ -98,6 +122,7 @@ pub(super) fn load_docs(conn: &Connection, vault_id: i64) -> Result<HashMap<Stri
 
 pub(super) fn remove_deleted_docs(
     conn: &Connection,
+    vault_id: i64,
     docs: &mut HashMap<String, DocRecord>,
     discovered: &HashSet<String>,
     summary: &mut IndexSummary,
@@ -108,13 +133,12 @@ pub(super) fn remove_deleted_docs(
         .cloned()
         .collect();
 
-    for rel_path in &to_delete {
-        if let Some(doc) = docs.remove(rel_path) {
-            conn.execute("DELETE FROM doc WHERE id = ?1", params![doc.id])
-                .with_context(|| format!("Failed to delete doc for rel_path {}", rel_path))?;
-            summary.docs_deleted += 1;
-        }
-    }
+    let doc_ids = to_delete
+        .iter()
+        .filter_map(|rel_path| docs.remove(rel_path))
+        .map(|doc| doc.id)
+        .collect::<Vec<_>>();
+    summary.docs_deleted += tombstones::delete_docs(conn, vault_id, &doc_ids)?;
 
     Ok(to_delete)
 }
@@ -127,15 +151,23 @@ pub(super) fn ensure_docs_for_files(
     summary: &mut IndexSummary,
 ) -> Result<Vec<(String, i64)>> {
     let mut inserted = Vec::new();
+    let mut tombstones = tombstones::load_tombstones(conn, vault_id)?;
     for file in files {
         if docs.contains_key(&file.rel_path) {
             continue;
         }
 
+        // A NULL id lets SQLite pick a fresh one.
+        let revived_id = tombstones::take_tombstone(&mut tombstones, &file.rel_path, || {
+            read_indexed_source(&file.abs_path, DEFAULT_MAX_SOURCE_BYTES)
+                .ok()
+                .map(|source| source.hash)
+        });
         conn.execute(
             "INSERT INTO doc (vault_id, rel_path, chunking_version, last_hash, last_source_size, \
-                              last_source_mtime_ns, last_embedding_model, last_embedding_dim, content) \
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                              last_source_mtime_ns, last_embedding_model, last_embedding_dim, content, \
+                              id) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 vault_id,
                 file.rel_path,
@@ -145,13 +177,18 @@ pub(super) fn ensure_docs_for_files(
                 Option::<i64>::None,
                 Option::<String>::None,
                 Option::<i32>::None,
-                ""
+                "",
+                revived_id
             ],
         )
         .with_context(|| format!("Failed to insert doc for {}", file.rel_path))?;
 
         let doc_id = conn.last_insert_rowid();
         summary.docs_inserted += 1;
+        if revived_id.is_some() {
+            tombstones::revive_doc(conn, doc_id)?;
+            summary.docs_revived += 1;
+        }
         inserted.push((file.rel_path.clone(), doc_id));
         docs.insert(
             file.rel_path.clone(),
@@ -161,6 +198,7 @@ pub(super) fn ensure_docs_for_files(
                 last_hash: None,
                 last_source_size: None,
                 last_source_mtime_ns: None,
+                last_embedding_provider: None,
                 last_embedding_model: None,
                 last_embedding_dim: None,
             },
@@ -183,6 +221,8 @@ pub(super) fn update_hash_and_content(
     doc_record: &mut DocRecord,
     doc_hash: &str,
     indexed_content: &str,
+    language: &str,
+    flags: IndexingFlags,
     file: &MarkdownFile,
 ) -> Result<()> {
     apply_doc_update(
@@ -191,6 +231,8 @@ pub(super) fn update_hash_and_content(
         DocUpdate::HashAndContent {
             doc_hash,
             indexed_content,
+            language,
+            flags,
             file,
         },
     )
@@ -199,16 +241,27 @@ pub(super) fn update_hash_and_content(
 pub(super) fn update_embedding_metadata(
     conn: &Connection,
     doc_record: &mut DocRecord,
+    provider: &str,
     model: &str,
     target_dim: i32,
 ) -> Result<()> {
     apply_doc_update(
         conn,
         doc_record,
-        DocUpdate::EmbeddingMetadata { model, target_dim },
+        DocUpdate::EmbeddingMetadata {
+            provider,
+            model,
+            target_dim,
+        },
     )
 }
 
+/// Records that the doc's vectors were made from its content as last
+/// indexed. An edit whose embedding fails leaves the two hashes apart.
+pub(super) fn record_embedded_hash(conn: &Connection, doc_record: &mut DocRecord) -> Result<()> {
+    apply_doc_update(conn, doc_record, DocUpdate::EmbeddedHash)
+}
+
 fn apply_doc_update(
     conn: &Connection,
     doc_record: &mut DocRecord,
@@ -217,10 +270,13 @@ fn apply_doc_update(
     match update {
         DocUpdate::SourceStat { file } => {
             conn.execute(
-                "UPDATE doc SET last_source_size = ?1, last_source_mtime_ns = ?2 WHERE id = ?3",
+                "UPDATE doc \
+                 SET last_source_size = ?1, last_source_mtime_ns = ?2, source_created_ns = ?3 \
+                 WHERE id = ?4",
                 params![
                     file.last_source_size,
                     file.last_source_mtime_ns,
+                    file.source_created_ns,
                     doc_record.id
                 ],
             )
@@ -231,17 +287,27 @@ fn apply_doc_update(
         DocUpdate::HashAndContent {
             doc_hash,
             indexed_content,
+            language,
+            flags,
             file,
         } => {
             conn.execute(
                 "UPDATE doc \
-                 SET last_hash = ?1, last_source_size = ?2, last_source_mtime_ns = ?3, content = ?4 \
-                 WHERE id = ?5",
+                 SET last_hash = ?1, last_source_size = ?2, last_source_mtime_ns = ?3, \
+                     source_created_ns = ?4, content = ?5, language = ?6, archived = ?7, \
+                     minhash = ?8, searchable = ?9, embeddable = ?10 \
+                 WHERE id = ?11",
                 params![
                     doc_hash,
                     file.last_source_size,
                     file.last_source_mtime_ns,
+                    file.source_created_ns,
                     indexed_content,
+                    language,
+                    flags.archived,
+                    minhash_signature(indexed_content),
+                    flags.searchable,
+                    flags.embeddable,
                     doc_record.id
                 ],
             )
@@ -250,19 +316,39 @@ fn apply_doc_update(
             doc_record.last_hash = Some(doc_hash.to_string());
             doc_record.update_source_stat(file);
         }
-        DocUpdate::EmbeddingMetadata { model, target_dim } => {
+        DocUpdate::EmbeddingMetadata {
+            provider,
+            model,
+            target_dim,
+        } => {
             conn.execute(
                 "UPDATE doc \
-                 SET chunking_version = ?1, last_embedding_model = ?2, last_embedding_dim = ?3 \
-                 WHERE id = ?4",
-                params![TARGET_CHUNKING_VERSION, model, target_dim, doc_record.id],
+                 SET chunking_version = ?1, last_embedding_provider = ?2, \
+                     last_embedding_model = ?3, last_embedding_dim = ?4 \
+                 WHERE id = ?5",
+                params![
+                    TARGET_CHUNKING_VERSION,
+                    provider,
+                    model,
+                    target_dim,
+                    doc_record.id
+                ],
             )
             .with_context(|| format!("Failed to update embedding metadata {}", doc_record.id))?;
 
             doc_record.chunking_version = TARGET_CHUNKING_VERSION;
+            doc_record.last_embedding_provider = Some(provider.to_string());
             doc_record.last_embedding_model = Some(model.to_string());
             doc_record.last_embedding_dim = Some(target_dim);
         }
+        DocUpdate::EmbeddedHash => {
+            conn.execute(
+                "UPDATE doc SET last_embedded_hash = last_hash \
+                 WHERE id = ?1 AND last_embedded_hash IS NOT last_hash",
+                params![doc_record.id],
+            )
+            .with_context(|| format!("Failed to record embedded hash {}", doc_record.id))?;
+        }
     }
 
     Ok(())
@@ -274,7 +360,7 @@ mod tests {
 
     use rusqlite::{params, Connection};
 
-    use super::{update_embedding_metadata, update_hash_and_content, DocRecord};
+    use super::{update_embedding_metadata, update_hash_and_content, DocRecord, IndexingFlags};
     use crate::vault_indexing::files::MarkdownFile;
 
     fn make_doc(model: Option<&str>, dim: Option<i32>) -> DocRecord {
@@ -284,6 +370,7 @@ mod tests {
             last_hash: Some("hash".to_string()),
             last_source_size: Some(10),
             last_source_mtime_ns: Some(20),
+            last_embedding_provider: None,
             last_embedding_model: model.map(|value| value.to_string()),
             last_embedding_dim: dim,
         }
@@ -295,6 +382,7 @@ mod tests {
             rel_path: "test.md".to_string(),
             last_source_size: Some(size),
             last_source_mtime_ns: Some(mtime_ns),
+            source_created_ns: None,
         }
     }
 
@@ -307,9 +395,16 @@ mod tests {
                  last_hash TEXT,
                  last_source_size INTEGER,
                  last_source_mtime_ns INTEGER,
+                 source_created_ns INTEGER,
+                 last_embedding_provider TEXT,
                  last_embedding_model TEXT,
                  last_embedding_dim INTEGER,
-                 content TEXT NOT NULL
+                 content TEXT NOT NULL,
+                 language TEXT,
+                 archived INTEGER NOT NULL DEFAULT 0,
+                 minhash BLOB,
+                 searchable INTEGER NOT NULL DEFAULT 1,
+                 embeddable INTEGER NOT NULL DEFAULT 1
              );
              CREATE TABLE content_update_audit (
                  id INTEGER PRIMARY KEY AUTOINCREMENT
@@ -335,7 +430,7 @@ mod tests {
 
         let mut doc = make_doc(Some("nomic-embed-text"), Some(768));
 
-        update_embedding_metadata(&conn, &mut doc, "nomic-embed-text", 768)
+        update_embedding_metadata(&conn, &mut doc, "ollama", "nomic-embed-text", 768)
             .expect("failed to update embedding metadata");
 
         let audit_count: i64 = conn
@@ -365,8 +460,20 @@ mod tests {
         let mut doc = make_doc(Some("nomic-embed-text"), Some(768));
         let file = make_file(10, 20);
 
-        update_hash_and_content(&conn, &mut doc, "next-hash", "changed content", &file)
-            .expect("failed to update hash and content");
+        update_hash_and_content(
+            &conn,
+            &mut doc,
+            "next-hash",
+            "changed content",
+            "und",
+            IndexingFlags {
+                archived: false,
+                searchable: true,
+                embeddable: true,
+            },
+            &file,
+        )
+        .expect("failed to update hash and content");
 
         let audit_count: i64 = conn
             .query_row("SELECT COUNT(*) FROM content_update_audit", [], |row| {
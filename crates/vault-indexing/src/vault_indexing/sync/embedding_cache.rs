@@ -0,0 +1,80 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::super::{embedding::EmbeddingVector, EmbeddingContext, IndexSummary};
+
+/// Returns the vector for a chunk, reusing one produced earlier for the same
+/// text by the same provider, model and dimension.
+///
+/// Re-indexing after a chunking or schema change rebuilds segments whose text
+/// did not change; the cache keeps those runs from calling the provider again.
+pub(super) fn generate_cached_embedding(
+    conn: &Connection,
+    embedding: &EmbeddingContext,
+    content_hash: &str,
+    text: &str,
+    summary: &mut IndexSummary,
+) -> Result<EmbeddingVector> {
+    if embedding.cache_max_entries == 0 {
+        return embedding.embedder.generate(text);
+    }
+
+    let provider = embedding.embedder.provider_name();
+    let model = embedding.embedder.model_name();
+    let cached = conn
+        .query_row(
+            "UPDATE embedding_cache SET last_used_at = ?5 \
+             WHERE content_hash = ?1 AND provider = ?2 AND model = ?3 AND dim = ?4 \
+             RETURNING vector",
+            params![
+                content_hash,
+                provider,
+                model,
+                embedding.target_dim,
+                now_ms()
+            ],
+            |row| row.get::<_, Vec<u8>>(0),
+        )
+        .optional()
+        .context("Failed to look up cached embedding")?;
+
+    if let Some(bytes) = cached {
+        summary.embeddings_cached += 1;
+        return Ok(EmbeddingVector {
+            dim: embedding.target_dim,
+            bytes,
+        });
+    }
+
+    let vector = embedding.embedder.generate(text)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO embedding_cache \
+             (content_hash, provider, model, dim, vector, last_used_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            content_hash,
+            provider,
+            model,
+            vector.dim,
+            vector.bytes,
+            now_ms()
+        ],
+    )
+    .context("Failed to cache embedding")?;
+
+    Ok(vector)
+}
+
+/// Evicts least recently used vectors once per run instead of on every insert.
+pub(super) fn prune_embedding_cache(conn: &Connection, embedding: &EmbeddingContext) -> Result<()> {
+    app_storage::embedding_cache::prune_embedding_cache(conn, embedding.cache_max_entries)?;
+    Ok(())
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as i64)
+}
@@ -148,6 +148,13 @@ pub(super) fn replace_links_for_doc(
              VALUES (?1, ?2, ?3)",
             )
             .with_context(|| format!("Failed to prepare link insert for doc {}", doc_id))?;
+        let mut mention_stmt = tx
+            .prepare(
+                "INSERT INTO link_mention \
+                 (link_id, start_offset, end_offset, line, context, context_start) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )
+            .with_context(|| format!("Failed to prepare link mention insert for doc {}", doc_id))?;
         for link in &resolution.links {
             stmt.execute(params![
                 doc_id,
@@ -155,6 +162,19 @@ pub(super) fn replace_links_for_doc(
                 link.target_path.as_str(),
             ])
             .with_context(|| format!("Failed to insert link for doc {}", doc_id))?;
+            let link_id = tx.last_insert_rowid();
+            for mention in &link.mentions {
+                mention_stmt
+                    .execute(params![
+                        link_id,
+                        mention.start as i64,
+                        mention.end as i64,
+                        mention.line as i64,
+                        mention.context.as_str(),
+                        mention.context_start as i64,
+                    ])
+                    .with_context(|| format!("Failed to insert link mention for doc {}", doc_id))?;
+            }
             summary.links_written += 1;
         }
     }
@@ -214,6 +234,14 @@ mod tests {
                  target_doc_id INTEGER,
                  target_path TEXT NOT NULL
              );
+             CREATE TABLE link_mention (
+                 link_id INTEGER NOT NULL REFERENCES link(id) ON DELETE CASCADE,
+                 start_offset INTEGER NOT NULL,
+                 end_offset INTEGER NOT NULL,
+                 line INTEGER NOT NULL,
+                 context TEXT NOT NULL,
+                 context_start INTEGER NOT NULL
+             );
              CREATE TABLE wiki_link_ref (
                  source_doc_id INTEGER NOT NULL,
                  query_key TEXT NOT NULL
@@ -383,10 +411,12 @@ mod tests {
                 ResolvedLink {
                     target_doc_id: Some(2),
                     target_path: "target.md".to_string(),
+                    mentions: Vec::new(),
                 },
                 ResolvedLink {
                     target_doc_id: None,
                     target_path: "missing.md".to_string(),
+                    mentions: Vec::new(),
                 },
             ],
             wiki_query_keys: string_set(&["target", "missing"]),
@@ -0,0 +1,33 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+use crate::vault_indexing::geo::NoteLocation;
+
+pub(super) fn replace_location_for_doc(
+    conn: &mut Connection,
+    doc_id: i64,
+    location: Option<&NoteLocation>,
+) -> Result<()> {
+    let tx = conn
+        .transaction()
+        .with_context(|| format!("Failed to start location transaction for doc {}", doc_id))?;
+
+    tx.execute(
+        "DELETE FROM doc_location WHERE doc_id = ?1",
+        params![doc_id],
+    )
+    .with_context(|| format!("Failed to clear location for doc {}", doc_id))?;
+
+    if let Some(location) = location {
+        tx.execute(
+            "INSERT INTO doc_location (doc_id, lat, lng, name) VALUES (?1, ?2, ?3, ?4)",
+            params![doc_id, location.lat, location.lng, location.name.as_deref()],
+        )
+        .with_context(|| format!("Failed to insert location for doc {}", doc_id))?;
+    }
+
+    tx.commit()
+        .with_context(|| format!("Failed to commit location for doc {}", doc_id))?;
+
+    Ok(())
+}
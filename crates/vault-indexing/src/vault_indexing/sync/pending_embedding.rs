@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+/// Queue documents whose embeddings could not be written so a later retry can
+/// pick them up once the provider is reachable again.
+pub(super) fn enqueue_pending_embeddings(
+    conn: &Connection,
+    doc_ids: &[i64],
+    error: &str,
+) -> Result<usize> {
+    let mut stmt = conn
+        .prepare(
+            "INSERT INTO pending_embedding (doc_id, attempts, last_error) VALUES (?1, 1, ?2) \
+             ON CONFLICT(doc_id) DO UPDATE SET \
+                 attempts = pending_embedding.attempts + 1, \
+                 last_error = excluded.last_error",
+        )
+        .context("Failed to prepare pending embedding insert")?;
+
+    for doc_id in doc_ids {
+        stmt.execute(params![doc_id, error])
+            .with_context(|| format!("Failed to queue pending embedding for doc {}", doc_id))?;
+    }
+
+    Ok(doc_ids.len())
+}
+
+pub(super) fn clear_pending_embedding(conn: &Connection, doc_id: i64) -> Result<()> {
+    conn.execute(
+        "DELETE FROM pending_embedding WHERE doc_id = ?1",
+        params![doc_id],
+    )
+    .with_context(|| format!("Failed to clear pending embedding for doc {}", doc_id))?;
+
+    Ok(())
+}
+
+pub(super) fn load_pending_embedding_rel_paths(
+    conn: &Connection,
+    vault_id: i64,
+) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT d.id, d.rel_path \
+             FROM pending_embedding pe \
+             JOIN doc d ON d.id = pe.doc_id \
+             WHERE d.vault_id = ?1 \
+             ORDER BY pe.queued_at ASC, d.id ASC",
+        )
+        .context("Failed to prepare pending embedding query")?;
+
+    let rows = stmt
+        .query_map(params![vault_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .context("Failed to load pending embeddings")?;
+
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read pending embedding rows")
+}
+
+pub(super) fn count_pending_embeddings(conn: &Connection, vault_id: i64) -> Result<usize> {
+    let count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) \
+             FROM pending_embedding pe \
+             JOIN doc d ON d.id = pe.doc_id \
+             WHERE d.vault_id = ?1",
+            params![vault_id],
+            |row| row.get(0),
+        )
+        .context("Failed to count pending embeddings")?;
+
+    Ok(usize::try_from(count).unwrap_or_default())
+}
@@ -42,16 +42,20 @@ pub(super) fn can_skip_file_without_loading(
     };
 
     doc_record.chunking_version == TARGET_CHUNKING_VERSION
-        && doc_record
-            .embedding_target_matches(embedding.embedder.model_name(), embedding.target_dim)
+        && doc_record.embedding_target_matches(
+            embedding.embedder.provider_name(),
+            embedding.embedder.model_name(),
+            embedding.target_dim,
+        )
 }
 
 pub(super) fn embedding_target_changed(
     doc_record: &DocRecord,
+    provider: &str,
     model: &str,
     target_dim: i32,
 ) -> bool {
-    !doc_record.embedding_target_matches(model, target_dim)
+    !doc_record.embedding_target_matches(provider, model, target_dim)
 }
 
 #[cfg(test)]
@@ -63,7 +67,8 @@ mod tests {
         FileSyncAction,
     };
     use crate::vault_indexing::{
-        embedding::EmbeddingClient, files::MarkdownFile, EmbeddingContext, TARGET_CHUNKING_VERSION,
+        embedding::EmbeddingClient, embedding_providers::EmbeddingProviderRegistry,
+        files::MarkdownFile, EmbeddingContext, TARGET_CHUNKING_VERSION,
     };
 
     use super::super::doc_repo::DocRecord;
@@ -75,6 +80,7 @@ mod tests {
             last_hash: Some("hash".to_string()),
             last_source_size: Some(10),
             last_source_mtime_ns: Some(20),
+            last_embedding_provider: None,
             last_embedding_model: model.map(|value| value.to_string()),
             last_embedding_dim: dim,
         }
@@ -86,34 +92,70 @@ mod tests {
             rel_path: "test.md".to_string(),
             last_source_size: Some(size),
             last_source_mtime_ns: Some(mtime_ns),
+            source_created_ns: None,
         }
     }
 
     fn make_embedding_context(model: &str, target_dim: i32) -> EmbeddingContext {
         EmbeddingContext {
-            embedder: EmbeddingClient::new("test", model)
+            embedder: EmbeddingClient::new(&EmbeddingProviderRegistry::default(), "test", model)
                 .expect("test embedding client should build"),
             target_dim,
+            cache_max_entries: 0,
         }
     }
 
     #[test]
     fn embedding_target_unchanged_returns_false() {
         let doc = make_doc(Some("nomic-embed-text"), Some(768));
-        assert!(!embedding_target_changed(&doc, "nomic-embed-text", 768));
+        assert!(!embedding_target_changed(
+            &doc,
+            "ollama",
+            "nomic-embed-text",
+            768
+        ));
     }
 
     #[test]
     fn embedding_target_changed_returns_true_for_model_or_dim_drift() {
         let doc = make_doc(Some("nomic-embed-text"), Some(768));
-        assert!(embedding_target_changed(&doc, "other-model", 768));
-        assert!(embedding_target_changed(&doc, "nomic-embed-text", 1024));
+        assert!(embedding_target_changed(&doc, "ollama", "other-model", 768));
+        assert!(embedding_target_changed(
+            &doc,
+            "ollama",
+            "nomic-embed-text",
+            1024
+        ));
+    }
+
+    #[test]
+    fn embedding_target_changed_returns_true_for_provider_drift() {
+        let mut doc = make_doc(Some("nomic-embed-text"), Some(768));
+        doc.last_embedding_provider = Some("ollama".to_string());
+
+        assert!(!embedding_target_changed(
+            &doc,
+            "ollama",
+            "nomic-embed-text",
+            768
+        ));
+        assert!(embedding_target_changed(
+            &doc,
+            "openai",
+            "nomic-embed-text",
+            768
+        ));
     }
 
     #[test]
     fn embedding_target_changed_returns_true_when_metadata_missing() {
         let doc = make_doc(None, None);
-        assert!(embedding_target_changed(&doc, "nomic-embed-text", 768));
+        assert!(embedding_target_changed(
+            &doc,
+            "ollama",
+            "nomic-embed-text",
+            768
+        ));
     }
 
     #[test]
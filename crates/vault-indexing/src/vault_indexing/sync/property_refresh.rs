@@ -0,0 +1,49 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+use crate::vault_indexing::properties::NoteProperty;
+
+pub(super) fn replace_properties_for_doc(
+    conn: &mut Connection,
+    doc_id: i64,
+    properties: &[NoteProperty],
+) -> Result<()> {
+    let tx = conn
+        .transaction()
+        .with_context(|| format!("Failed to start property transaction for doc {}", doc_id))?;
+
+    tx.execute(
+        "DELETE FROM doc_property WHERE doc_id = ?1",
+        params![doc_id],
+    )
+    .with_context(|| format!("Failed to clear properties for doc {}", doc_id))?;
+
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO doc_property (doc_id, key, value, sort_value) \
+                 VALUES (?1, ?2, ?3, ?4)",
+            )
+            .with_context(|| format!("Failed to prepare property insert for doc {}", doc_id))?;
+
+        for property in properties {
+            stmt.execute(params![
+                doc_id,
+                property.key.as_str(),
+                property.value.as_str(),
+                property.sort_value
+            ])
+            .with_context(|| {
+                format!(
+                    "Failed to insert property '{}' for doc {}",
+                    property.key, doc_id
+                )
+            })?;
+        }
+    }
+
+    tx.commit()
+        .with_context(|| format!("Failed to commit properties for doc {}", doc_id))?;
+
+    Ok(())
+}
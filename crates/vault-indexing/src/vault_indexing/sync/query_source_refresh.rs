@@ -0,0 +1,48 @@
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+
+use crate::vault_indexing::query_block::QueryBlockSource;
+
+pub(super) fn replace_query_sources_for_doc(
+    conn: &mut Connection,
+    doc_id: i64,
+    sources: &[QueryBlockSource],
+) -> Result<()> {
+    let tx = conn.transaction().with_context(|| {
+        format!(
+            "Failed to start query source transaction for doc {}",
+            doc_id
+        )
+    })?;
+
+    tx.execute(
+        "DELETE FROM doc_query_source WHERE doc_id = ?1",
+        params![doc_id],
+    )
+    .with_context(|| format!("Failed to clear query sources for doc {}", doc_id))?;
+
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO doc_query_source (doc_id, source_kind, source_value) \
+                 VALUES (?1, ?2, ?3)",
+            )
+            .with_context(|| format!("Failed to prepare query source insert for doc {}", doc_id))?;
+
+        for source in sources {
+            stmt.execute(params![doc_id, source.kind(), source.value()])
+                .with_context(|| {
+                    format!(
+                        "Failed to insert query source '{}' for doc {}",
+                        source.value(),
+                        doc_id
+                    )
+                })?;
+        }
+    }
+
+    tx.commit()
+        .with_context(|| format!("Failed to commit query sources for doc {}", doc_id))?;
+
+    Ok(())
+}
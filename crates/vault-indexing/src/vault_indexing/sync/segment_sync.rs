@@ -4,10 +4,11 @@ use anyhow::{anyhow, Context, Result};
 use rusqlite::{params, Connection};
 
 use super::super::{
-    chunking::hash_content,
-    embedding::{EmbeddingClient, EmbeddingVector},
-    IndexSummary,
+    chunking::{hash_content, DocumentChunk},
+    embedding::EmbeddingVector,
+    EmbeddingContext, IndexSummary,
 };
+use super::embedding_cache::generate_cached_embedding;
 
 const SEGMENT_VEC_TABLE: &str = "segment_vec";
 
@@ -16,6 +17,32 @@ struct SegmentRecord {
     id: i64,
     last_hash: String,
     has_embedding: bool,
+    metadata: SegmentMetadata,
+    text: String,
+}
+
+/// Where a segment's raw text sits in the note and what kind of text it is, as
+/// persisted on `segment`.
+#[derive(Debug, PartialEq, Eq)]
+struct SegmentMetadata {
+    start_offset: Option<i64>,
+    end_offset: Option<i64>,
+    heading_path: Option<String>,
+    code_language: Option<String>,
+}
+
+impl SegmentMetadata {
+    fn from_chunk(chunk: &DocumentChunk) -> Result<Self> {
+        let heading_path = serde_json::to_string(&chunk.heading_path)
+            .context("Failed to encode segment heading path")?;
+
+        Ok(Self {
+            start_offset: Some(chunk.start_offset as i64),
+            end_offset: Some(chunk.end_offset as i64),
+            heading_path: Some(heading_path),
+            code_language: chunk.code_language.clone(),
+        })
+    }
 }
 
 pub(super) fn clear_segment_vectors_for_vault(conn: &Connection, vault_id: i64) -> Result<()> {
@@ -41,24 +68,28 @@ pub(super) fn clear_segment_vectors_for_vault(conn: &Connection, vault_id: i64)
 pub(super) fn rebuild_doc_chunks(
     conn: &mut Connection,
     doc_id: i64,
-    chunks: &[String],
-    embedder: &EmbeddingClient,
+    chunks: &[DocumentChunk],
+    embedding: &EmbeddingContext,
     summary: &mut IndexSummary,
 ) -> Result<()> {
-    struct PreparedSegmentEmbedding {
+    struct PreparedSegmentEmbedding<'a> {
         ordinal: i64,
         hash: String,
+        metadata: SegmentMetadata,
+        text: &'a str,
         vector: EmbeddingVector,
     }
 
     // Generate all embeddings before taking the SQLite write lock so readers are not blocked.
     let mut prepared_segments = Vec::with_capacity(chunks.len());
     for (ordinal, chunk) in chunks.iter().enumerate() {
-        let hash = hash_content(chunk);
-        let vector = embedder.generate(chunk)?;
+        let hash = hash_content(&chunk.text);
+        let vector = generate_cached_embedding(conn, embedding, &hash, &chunk.text, summary)?;
         prepared_segments.push(PreparedSegmentEmbedding {
             ordinal: ordinal as i64,
             hash,
+            metadata: SegmentMetadata::from_chunk(chunk)?,
+            text: &chunk.text,
             vector,
         });
     }
@@ -75,7 +106,14 @@ pub(super) fn rebuild_doc_chunks(
         .with_context(|| format!("Failed to clear segments for doc {}", doc_id))?;
 
     for prepared in &prepared_segments {
-        let segment_id = insert_segment(&tx, doc_id, prepared.ordinal, &prepared.hash)?;
+        let segment_id = insert_segment(
+            &tx,
+            doc_id,
+            prepared.ordinal,
+            &prepared.hash,
+            &prepared.metadata,
+            prepared.text,
+        )?;
         summary.segments_created += 1;
         upsert_embedding(&tx, segment_id, &prepared.vector.bytes, summary)?;
     }
@@ -94,17 +132,27 @@ pub(super) fn rebuild_doc_chunks(
 pub(super) fn sync_segments_for_doc(
     conn: &Connection,
     doc_id: i64,
-    chunks: &[String],
-    embedder: &EmbeddingClient,
+    chunks: &[DocumentChunk],
+    embedding: &EmbeddingContext,
     force_reembed_all: bool,
     summary: &mut IndexSummary,
 ) -> Result<()> {
     let existing = load_segments_for_doc(conn, doc_id)?;
 
     for (ordinal, chunk) in chunks.iter().enumerate() {
-        let hash = hash_content(chunk);
+        let hash = hash_content(&chunk.text);
+        let metadata = SegmentMetadata::from_chunk(chunk)?;
         let ordinal_key = ordinal as i64;
         if let Some(segment) = existing.get(&ordinal_key) {
+            // Edits earlier in the note shift offsets without changing the chunk text.
+            if segment.metadata != metadata {
+                update_segment_metadata(conn, segment.id, &metadata)?;
+            }
+            // Segments indexed before their text was stored get it on the next run.
+            if segment.text != chunk.text {
+                update_segment_text(conn, segment.id, &chunk.text)?;
+            }
+
             let hash_changed = segment.last_hash != hash;
             let mut needs_embedding = force_reembed_all || hash_changed;
             if !needs_embedding {
@@ -113,7 +161,14 @@ pub(super) fn sync_segments_for_doc(
             }
 
             if needs_embedding {
-                write_embedding_for_segment(conn, segment.id, chunk, embedder, summary)?;
+                write_embedding_for_segment(
+                    conn,
+                    segment.id,
+                    &hash,
+                    &chunk.text,
+                    embedding,
+                    summary,
+                )?;
                 if hash_changed {
                     conn.execute(
                         "UPDATE segment SET last_hash = ?1 WHERE id = ?2",
@@ -126,11 +181,17 @@ pub(super) fn sync_segments_for_doc(
                 }
             }
         } else {
-            let segment_id = insert_segment(conn, doc_id, ordinal_key, &hash)?;
+            let segment_id =
+                insert_segment(conn, doc_id, ordinal_key, &hash, &metadata, &chunk.text)?;
             summary.segments_created += 1;
-            if let Err(error) =
-                write_embedding_for_segment(conn, segment_id, chunk, embedder, summary)
-            {
+            if let Err(error) = write_embedding_for_segment(
+                conn,
+                segment_id,
+                &hash,
+                &chunk.text,
+                embedding,
+                summary,
+            ) {
                 // Best-effort cleanup keeps the database consistent if embedding generation fails.
                 let cleanup_result: Result<()> = (|| {
                     delete_vector_for_segment(conn, segment_id)?;
@@ -159,7 +220,7 @@ pub(super) fn sync_segments_for_doc(
 pub(super) fn segments_match_current_chunks(
     conn: &Connection,
     doc_id: i64,
-    chunks: &[String],
+    chunks: &[DocumentChunk],
 ) -> Result<bool> {
     let existing = load_segments_for_doc(conn, doc_id)?;
     if existing.len() != chunks.len() {
@@ -171,7 +232,11 @@ pub(super) fn segments_match_current_chunks(
             return Ok(false);
         };
 
-        if segment.last_hash != hash_content(chunk) || !segment.has_embedding {
+        if segment.last_hash != hash_content(&chunk.text)
+            || !segment.has_embedding
+            || segment.metadata != SegmentMetadata::from_chunk(chunk)?
+            || segment.text != chunk.text
+        {
             return Ok(false);
         }
     }
@@ -182,7 +247,8 @@ pub(super) fn segments_match_current_chunks(
 fn load_segments_for_doc(conn: &Connection, doc_id: i64) -> Result<HashMap<i64, SegmentRecord>> {
     let mut stmt = conn
         .prepare(
-            "SELECT s.id, s.ordinal, s.last_hash, sv.rowid \
+            "SELECT s.id, s.ordinal, s.last_hash, sv.rowid, \
+                    s.start_offset, s.end_offset, s.heading_path, s.code_language, s.text \
              FROM segment s \
              LEFT JOIN segment_vec sv ON sv.rowid = s.id \
              WHERE s.doc_id = ?1",
@@ -196,19 +262,28 @@ fn load_segments_for_doc(conn: &Connection, doc_id: i64) -> Result<HashMap<i64,
                 row.get::<_, i64>(1)?,
                 row.get::<_, String>(2)?,
                 row.get::<_, Option<i64>>(3)?.is_some(),
+                SegmentMetadata {
+                    start_offset: row.get(4)?,
+                    end_offset: row.get(5)?,
+                    heading_path: row.get(6)?,
+                    code_language: row.get(7)?,
+                },
+                row.get::<_, String>(8)?,
             ))
         })
         .with_context(|| format!("Failed to load segments for doc {}", doc_id))?;
 
     let mut segments = HashMap::new();
     for row in rows {
-        let (id, ordinal, last_hash, has_embedding) = row?;
+        let (id, ordinal, last_hash, has_embedding, metadata, text) = row?;
         segments.insert(
             ordinal,
             SegmentRecord {
                 id,
                 last_hash,
                 has_embedding,
+                metadata,
+                text,
             },
         );
     }
@@ -216,16 +291,67 @@ fn load_segments_for_doc(conn: &Connection, doc_id: i64) -> Result<HashMap<i64,
     Ok(segments)
 }
 
-fn insert_segment(conn: &Connection, doc_id: i64, ordinal: i64, last_hash: &str) -> Result<i64> {
+fn insert_segment(
+    conn: &Connection,
+    doc_id: i64,
+    ordinal: i64,
+    last_hash: &str,
+    metadata: &SegmentMetadata,
+    text: &str,
+) -> Result<i64> {
     conn.execute(
-        "INSERT INTO segment (doc_id, ordinal, last_hash) VALUES (?1, ?2, ?3)",
-        params![doc_id, ordinal, last_hash],
+        "INSERT INTO segment ( \
+             doc_id, ordinal, last_hash, start_offset, end_offset, heading_path, code_language, \
+             text \
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            doc_id,
+            ordinal,
+            last_hash,
+            metadata.start_offset,
+            metadata.end_offset,
+            metadata.heading_path,
+            metadata.code_language,
+            text
+        ],
     )
     .with_context(|| format!("Failed to insert segment {} for doc {}", ordinal, doc_id))?;
 
     Ok(conn.last_insert_rowid())
 }
 
+fn update_segment_metadata(
+    conn: &Connection,
+    segment_id: i64,
+    metadata: &SegmentMetadata,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE segment \
+         SET start_offset = ?1, end_offset = ?2, heading_path = ?3, code_language = ?4 \
+         WHERE id = ?5",
+        params![
+            metadata.start_offset,
+            metadata.end_offset,
+            metadata.heading_path,
+            metadata.code_language,
+            segment_id
+        ],
+    )
+    .with_context(|| format!("Failed to update metadata for segment {}", segment_id))?;
+
+    Ok(())
+}
+
+fn update_segment_text(conn: &Connection, segment_id: i64, text: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE segment SET text = ?1 WHERE id = ?2",
+        params![text, segment_id],
+    )
+    .with_context(|| format!("Failed to update text for segment {}", segment_id))?;
+
+    Ok(())
+}
+
 fn prune_extra_segments(conn: &Connection, doc_id: i64, desired_segments: usize) -> Result<()> {
     conn.execute(
         "DELETE FROM segment WHERE doc_id = ?1 AND ordinal >= ?2",
@@ -239,13 +365,14 @@ fn prune_extra_segments(conn: &Connection, doc_id: i64, desired_segments: usize)
 fn write_embedding_for_segment(
     conn: &Connection,
     segment_id: i64,
+    content_hash: &str,
     contents: &str,
-    embedder: &EmbeddingClient,
+    embedding: &EmbeddingContext,
     summary: &mut IndexSummary,
 ) -> Result<()> {
     // Embedding is computed outside SQL so we only persist the binary payload.
-    let embedding = embedder.generate(contents)?;
-    upsert_embedding(conn, segment_id, &embedding.bytes, summary)
+    let vector = generate_cached_embedding(conn, embedding, content_hash, contents, summary)?;
+    upsert_embedding(conn, segment_id, &vector.bytes, summary)
 }
 
 fn upsert_embedding(
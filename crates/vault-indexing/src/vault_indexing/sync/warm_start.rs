@@ -0,0 +1,61 @@
+use anyhow::Result;
+use rusqlite::Connection;
+
+use super::super::{files::MarkdownFile, EmbeddingContext};
+use super::doc_repo::load_docs;
+use super::policy::can_skip_file_without_loading;
+
+/// What a workspace run has to look at, decided from one query over the doc
+/// rows and the stats the file walk already gathered.
+#[derive(Debug)]
+pub(crate) enum WarmStart {
+    /// Every file matches its row; the run has nothing to do.
+    Current,
+    /// Only these files changed and no file was added or removed, so the
+    /// untouched rows need no link, alias, or prune work.
+    Changed(Vec<MarkdownFile>),
+    /// Files were added or removed; the whole tree goes through the full run.
+    Full(Vec<MarkdownFile>),
+}
+
+pub(crate) fn plan_warm_start(
+    conn: &Connection,
+    vault_id: i64,
+    files: Vec<MarkdownFile>,
+    embedding: Option<&EmbeddingContext>,
+) -> Result<WarmStart> {
+    let docs = load_docs(conn, vault_id)?;
+    // Same count and every file has a row, so no row lost its file either.
+    if docs.len() != files.len() || !files.iter().all(|file| docs.contains_key(&file.rel_path)) {
+        return Ok(WarmStart::Full(files));
+    }
+
+    let changed = files
+        .into_iter()
+        .filter(|file| {
+            !can_skip_file_without_loading(&docs[&file.rel_path], file, false, embedding)
+        })
+        .collect::<Vec<_>>();
+    if changed.is_empty() {
+        Ok(WarmStart::Current)
+    } else {
+        Ok(WarmStart::Changed(changed))
+    }
+}
+
+/// How many of `files` match their doc row closely enough for a run to skip
+/// them.
+pub(crate) fn count_current_files(
+    conn: &Connection,
+    vault_id: i64,
+    files: &[MarkdownFile],
+) -> Result<usize> {
+    let docs = load_docs(conn, vault_id)?;
+    Ok(files
+        .iter()
+        .filter(|file| {
+            docs.get(&file.rel_path)
+                .is_some_and(|doc| can_skip_file_without_loading(doc, file, false, None))
+        })
+        .count())
+}
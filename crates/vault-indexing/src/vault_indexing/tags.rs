@@ -35,13 +35,13 @@ pub(crate) fn normalize_tag_query(raw: &str) -> Option<String> {
     normalize_tag_value(raw).map(|(_, normalized)| normalized)
 }
 
-fn strip_hidden_chars(raw: &str) -> String {
+pub(super) fn strip_hidden_chars(raw: &str) -> String {
     raw.chars()
         .filter(|ch| *ch != BOM && *ch != ZERO_WIDTH_SPACE)
         .collect()
 }
 
-fn split_frontmatter(raw: &str) -> (Option<&str>, &str) {
+pub(super) fn split_frontmatter(raw: &str) -> (Option<&str>, &str) {
     let trimmed = raw.trim_start();
     if !trimmed.starts_with("---") {
         return (None, raw);
@@ -127,7 +127,7 @@ fn collect_frontmatter_tags(
     }
 }
 
-fn frontmatter_payload(frontmatter: &str) -> String {
+pub(super) fn frontmatter_payload(frontmatter: &str) -> String {
     let lines: Vec<&str> = frontmatter.lines().collect();
     if lines.len() >= 2 && lines[0].trim() == "---" {
         let last = lines.len() - 1;
@@ -139,7 +139,7 @@ fn frontmatter_payload(frontmatter: &str) -> String {
     frontmatter.to_string()
 }
 
-fn lookup_mapping_value<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+pub(super) fn lookup_mapping_value<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
     let Value::Mapping(map) = value else {
         return None;
     };
@@ -0,0 +1,101 @@
+use rusqlite::Connection;
+
+use super::super::{get_activity_heatmap, get_writing_stats};
+use super::test_support::IndexingHarness;
+
+fn local_today() -> String {
+    Connection::open_in_memory()
+        .expect("failed to open in-memory db")
+        .query_row("SELECT date('now', 'localtime')", [], |row| row.get(0))
+        .expect("failed to read local date")
+}
+
+#[test]
+fn given_note_edited_twice_in_a_day_when_loading_heatmap_then_it_counts_once_with_all_growth() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-activity");
+    harness.write_note("journal.md", "Morning pages go here");
+    harness.run_workspace_index();
+
+    let grown = format!("Morning pages go here\n{}", "word ".repeat(60));
+    harness.write_note("journal.md", &grown);
+    harness.run_workspace_index();
+
+    let today = local_today();
+    let heatmap = get_activity_heatmap(harness.root(), harness.db_path(), &today, &today)
+        .expect("activity heatmap should load");
+
+    assert_eq!(heatmap.days.len(), 1);
+    assert_eq!(heatmap.days[0].date, today);
+    assert_eq!(heatmap.days[0].notes_touched, 1);
+    assert_eq!(heatmap.days[0].words_added, grown.len() / 6);
+    assert_eq!(heatmap.current_streak, 1);
+    assert_eq!(heatmap.longest_streak, 1);
+}
+
+#[test]
+fn given_unchanged_note_when_reindexing_then_no_activity_is_added() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-activity-unchanged");
+    harness.write_note("journal.md", "Same text");
+    harness.run_workspace_index();
+    harness.run_workspace_index();
+
+    let today = local_today();
+    let heatmap = get_activity_heatmap(harness.root(), harness.db_path(), &today, &today)
+        .expect("activity heatmap should load");
+
+    assert_eq!(heatmap.days[0].notes_touched, 1);
+    assert_eq!(heatmap.days[0].words_added, "Same text".len() / 6);
+}
+
+#[test]
+fn given_revisions_when_loading_writing_stats_then_word_deltas_add_up_per_note_and_folder() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-writing-stats");
+    harness.write_note("Projects/Novel/chapter.md", "It was a dark night");
+    harness.write_note("journal.md", "Slept well");
+    harness.run_workspace_index();
+
+    harness.write_note(
+        "Projects/Novel/chapter.md",
+        "It was a dark and stormy night",
+    );
+    harness.write_note("journal.md", "Slept");
+    harness.run_workspace_index();
+    harness.remove_note("journal.md");
+    harness.run_workspace_index();
+
+    let today = local_today();
+    let stats = get_writing_stats(harness.root(), harness.db_path(), &today, &today, None)
+        .expect("writing stats should load");
+    assert_eq!(stats.words_added, 5 + 2 + 2);
+    assert_eq!(stats.words_removed, 1);
+    let notes = stats
+        .notes
+        .iter()
+        .map(|note| {
+            (
+                note.rel_path.as_str(),
+                note.words_added,
+                note.words_removed,
+                note.word_count,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        notes,
+        vec![
+            ("Projects/Novel/chapter.md", 7, 0, 7),
+            ("journal.md", 2, 1, 1),
+        ]
+    );
+
+    let project = get_writing_stats(
+        harness.root(),
+        harness.db_path(),
+        &today,
+        &today,
+        Some("Projects/"),
+    )
+    .expect("project writing stats should load");
+    assert_eq!(project.words_added, 7);
+    assert_eq!(project.notes.len(), 1);
+}
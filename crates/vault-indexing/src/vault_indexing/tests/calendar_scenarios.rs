@@ -0,0 +1,74 @@
+use super::super::{get_calendar_data, CalendarNote};
+use super::test_support::IndexingHarness;
+
+// 2024-05-07T12:00:00Z, the same local day in nearly every time zone.
+const MAY_7_NOON_NS: i64 = 1_715_083_200_000_000_000;
+
+#[test]
+fn given_daily_and_dated_notes_when_loading_calendar_then_each_day_lists_them() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-calendar");
+    harness.write_note("Daily/2024-05-07.md", "Standup notes");
+    harness.write_note(
+        "release.md",
+        &[
+            "---",
+            "date: 2024-05-07",
+            "due: 2024-05-08T17:00",
+            "---",
+            "Ship it",
+        ]
+        .join("\n"),
+    );
+    harness.write_note("undated.md", "No dates here");
+
+    harness.run_workspace_index();
+    harness.set_doc_source_stat("release.md", Some(1), Some(MAY_7_NOON_NS));
+
+    let days = get_calendar_data(
+        harness.root(),
+        harness.db_path(),
+        "2024-05-06",
+        "2024-05-08",
+        "[Daily]/YYYY-MM-DD",
+    )
+    .expect("calendar data should load");
+
+    let dates = days.iter().map(|day| day.date.as_str()).collect::<Vec<_>>();
+    assert_eq!(dates, vec!["2024-05-06", "2024-05-07", "2024-05-08"]);
+
+    assert_eq!(days[0].daily_note, None);
+    assert!(days[0].dated_notes.is_empty());
+
+    assert_eq!(days[1].daily_note.as_deref(), Some("Daily/2024-05-07.md"));
+    assert_eq!(days[1].modified_count, 1);
+    assert_eq!(
+        days[1].dated_notes,
+        vec![CalendarNote {
+            rel_path: "release.md".to_string(),
+            property: "date".to_string(),
+        }]
+    );
+
+    assert_eq!(
+        days[2].dated_notes,
+        vec![CalendarNote {
+            rel_path: "release.md".to_string(),
+            property: "due".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn given_reversed_range_when_loading_calendar_then_it_is_rejected() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-calendar-reversed");
+
+    let result = get_calendar_data(
+        harness.root(),
+        harness.db_path(),
+        "2024-05-08",
+        "2024-05-06",
+        "YYYY-MM-DD",
+    );
+
+    assert!(result.is_err());
+}
@@ -1,4 +1,4 @@
-use super::super::chunking::{chunk_document, hash_content};
+use super::super::chunking::{chunk_document, chunk_note, hash_content};
 
 const GFM_MARKDOWN: &str = r#"---
 title: Sample Doc
@@ -58,6 +58,104 @@ fn given_oversized_input_when_chunking_then_multiple_chunks_are_created() {
     );
 }
 
+#[test]
+fn given_nested_headings_when_chunking_v3_then_breadcrumb_prefixes_chunk() {
+    let filler = "Body text that keeps the section above the minimum chunk size. ".repeat(20);
+    let content = format!("# Guide\n\n{filler}\n\n## Setup\n\n### Install\n\n{filler}\n");
+    let chunks = chunk_note(&content, "Handbook", 3);
+
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].heading_path, vec!["Guide".to_string()]);
+    assert!(chunks[0].text.starts_with("Handbook > Guide\n\n# Guide"));
+    assert_eq!(
+        chunks[1].heading_path,
+        vec!["Guide".to_string(), "Setup".to_string()]
+    );
+    assert!(chunks[1]
+        .text
+        .starts_with("Handbook > Guide > Setup\n\n## Setup"));
+}
+
+#[test]
+fn given_v3_chunks_when_chunking_then_offsets_point_at_raw_text() {
+    let filler = "Body text that keeps the section above the minimum chunk size. ".repeat(20);
+    let content = format!("Intro line\n\n# Alpha\n\n{filler}\n\n# Beta\n\n{filler}\n");
+    let chunks = chunk_note(&content, "", 3);
+    let raw_chunks = chunk_document(&content, 1);
+
+    assert_eq!(chunks.len(), raw_chunks.len());
+    for chunk in &chunks {
+        let raw = &content[chunk.start_offset..chunk.end_offset];
+        assert!(
+            chunk.text.ends_with(raw),
+            "offsets should cover the raw chunk"
+        );
+    }
+    assert_eq!(chunks[0].start_offset, 0);
+    assert!(chunks[0].heading_path.is_empty());
+}
+
+#[test]
+fn given_oversized_code_fence_when_chunking_v4_then_pieces_keep_language_and_functions() {
+    let function = |name: &str| {
+        format!(
+            "def {name}(items):\n{}    return items\n",
+            "    items = [item * 2 for item in items if item is not None]\n".repeat(40)
+        )
+    };
+    let content = format!(
+        "# Scripts\n\n```python\n{}\n{}\n{}```\n",
+        function("alpha"),
+        function("beta"),
+        function("gamma")
+    );
+    let chunks = chunk_note(&content, "", 4);
+
+    assert!(chunks.len() > 1, "oversized fence should be split");
+    assert!(chunks
+        .iter()
+        .all(|chunk| chunk.code_language.as_deref() == Some("python")));
+    for chunk in &chunks[1..] {
+        assert!(
+            chunk.text.starts_with("Scripts\n\ndef "),
+            "pieces should start at a function boundary"
+        );
+    }
+}
+
+#[test]
+fn given_prose_only_note_when_chunking_then_no_code_language_is_recorded() {
+    let chunks = chunk_note("# Notes\n\nPlain prose with `inline code`.", "", 4);
+
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(chunks[0].code_language, None);
+}
+
+#[test]
+fn given_cjk_note_when_chunking_then_chunks_respect_character_budget() {
+    let paragraph = "日本語の文章を分割するためのテストです。".repeat(12);
+    let content = (0..6)
+        .map(|_| paragraph.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let chunks = chunk_note(&content, "", 5);
+
+    assert!(chunks.len() > 1, "long CJK notes should be split");
+    assert!(chunks.iter().all(|chunk| chunk.text.chars().count() <= 512));
+}
+
+#[test]
+fn given_cjk_note_when_chunking_v4_then_chunks_keep_the_token_budget() {
+    let paragraph = "日本語の文章を分割するためのテストです。".repeat(12);
+    let content = (0..4)
+        .map(|_| paragraph.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    assert_eq!(chunk_note(&content, "", 4).len(), 1);
+    assert!(chunk_note(&content, "", 5).len() > 1);
+}
+
 #[test]
 fn given_identical_and_different_inputs_when_hashing_then_hash_is_stable() {
     let left = "same content";
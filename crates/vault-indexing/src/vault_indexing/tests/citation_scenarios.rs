@@ -0,0 +1,128 @@
+use super::super::{format_citation, get_citing_notes, search_citations, CitationStyle};
+use super::test_support::IndexingHarness;
+
+const BIBLIOGRAPHY: &str = r#"
+@article{doe2021,
+  author = {Doe, Jane and Roe, Max},
+  title = {Notes on Notes},
+  journal = {Journal of Notes},
+  year = {2021},
+}
+
+@book{smith2019,
+  author = {John Smith},
+  title = {Linking Thoughts},
+  year = 2019,
+}
+"#;
+
+#[test]
+fn given_bibliography_when_searching_and_formatting_then_entries_are_read_from_the_file() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-citations-search");
+    harness.write_note("refs/library.bib", BIBLIOGRAPHY);
+    harness.write_note("note.md", "Body");
+    harness.run_workspace_index();
+
+    let by_author = search_citations(
+        harness.root(),
+        harness.db_path(),
+        "refs/library.bib",
+        "roe",
+        10,
+    )
+    .expect("citation search should succeed");
+    assert_eq!(
+        by_author
+            .iter()
+            .map(|entry| entry.key.as_str())
+            .collect::<Vec<_>>(),
+        vec!["doe2021"]
+    );
+
+    let all = search_citations(
+        harness.root(),
+        harness.db_path(),
+        "refs/library.bib",
+        "",
+        10,
+    )
+    .expect("citation search should succeed");
+    assert_eq!(all.len(), 2);
+
+    let format = |key: &str, style| {
+        format_citation(
+            harness.root(),
+            harness.db_path(),
+            "refs/library.bib",
+            key,
+            style,
+        )
+        .expect("citation should format")
+    };
+    assert_eq!(format("doe2021", CitationStyle::Pandoc), "[@doe2021]");
+    assert_eq!(
+        format("doe2021", CitationStyle::AuthorYear),
+        "(Doe & Roe, 2021)"
+    );
+    assert_eq!(
+        format("smith2019", CitationStyle::Reference),
+        "Smith, J. (2019). Linking Thoughts."
+    );
+    assert!(format_citation(
+        harness.root(),
+        harness.db_path(),
+        "refs/library.bib",
+        "missing",
+        CitationStyle::Pandoc,
+    )
+    .is_err());
+}
+
+#[test]
+fn given_changed_bibliography_when_searching_then_entries_are_reloaded() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-citations-reload");
+    harness.write_note("library.bib", BIBLIOGRAPHY);
+    harness.write_note("note.md", "Body");
+    harness.run_workspace_index();
+    search_citations(harness.root(), harness.db_path(), "library.bib", "", 10)
+        .expect("citation search should succeed");
+
+    harness.write_note(
+        "library.bib",
+        "@misc{fresh2024, title = {Fresh Entry}, year = 2024}",
+    );
+
+    let entries = search_citations(harness.root(), harness.db_path(), "library.bib", "", 10)
+        .expect("citation search should succeed");
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| entry.key.as_str())
+            .collect::<Vec<_>>(),
+        vec!["fresh2024"]
+    );
+}
+
+#[test]
+fn given_notes_citing_a_key_when_querying_then_citing_notes_are_listed() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-citations-citing");
+    harness.write_note("a.md", "As argued [see @doe2021, p. 4].");
+    harness.write_note("b.md", "Compare [@smith2019; @doe2021].");
+    harness.write_note("c.md", "Only `[@doe2021]` in code.");
+    harness.run_workspace_index();
+
+    assert_eq!(
+        get_citing_notes(harness.root(), harness.db_path(), "doe2021")
+            .expect("citing notes should load"),
+        vec!["a.md".to_string(), "b.md".to_string()]
+    );
+
+    harness.write_note("a.md", "No citations any more.");
+    harness.run_workspace_index();
+
+    assert_eq!(
+        get_citing_notes(harness.root(), harness.db_path(), "doe2021")
+            .expect("citing notes should load"),
+        vec!["b.md".to_string()]
+    );
+}
@@ -0,0 +1,35 @@
+use super::super::run_consistency_sweep;
+use super::test_support::IndexingHarness;
+
+#[test]
+fn given_changes_the_watcher_missed_when_sweeping_then_divergent_files_reindex_and_ghosts_go() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-consistency-sweep");
+    harness.write_note("a.md", "# A\n\nsteady");
+    harness.write_note("b.md", "# B\n\nbefore");
+    harness.write_note("c.md", "# C\n\ndoomed");
+    harness.run_workspace_index();
+
+    harness.write_note("b.md", "# B\n\nafter the edit nobody saw");
+    harness.write_note("d.md", "# D\n\ncreated offline");
+    harness.remove_note("c.md");
+
+    let mut sweep = run_consistency_sweep(harness.root(), harness.db_path(), &[])
+        .expect("consistency sweep should succeed");
+    sweep.reindexed.sort();
+    assert_eq!(sweep.files_checked, 3);
+    assert_eq!(sweep.reindexed, ["b.md", "d.md"]);
+    assert_eq!(sweep.pruned, ["c.md"]);
+
+    assert!(harness
+        .doc_content("b.md")
+        .expect("b.md content")
+        .contains("after the edit nobody saw"));
+    assert!(harness.doc_id("d.md").is_some());
+    assert!(harness.doc_id("c.md").is_none());
+    assert_eq!(harness.meta().indexed_doc_count, 3);
+
+    let again = run_consistency_sweep(harness.root(), harness.db_path(), &[])
+        .expect("second sweep should succeed");
+    assert!(again.reindexed.is_empty());
+    assert!(again.pruned.is_empty());
+}
@@ -0,0 +1,121 @@
+use std::path::PathBuf;
+
+use super::super::{build_context, build_context_where, ContextRequest, EmbeddingProfile};
+use super::test_support::IndexingHarness;
+
+fn indexed_harness(prefix: &str) -> (IndexingHarness, Vec<EmbeddingProfile>) {
+    let harness = IndexingHarness::new(prefix);
+    harness.write_note(
+        "garden.md",
+        &format!("# Garden\n\n{}", "tomato basil compost ".repeat(20)),
+    );
+    harness.write_note(
+        "kitchen.md",
+        &format!("# Kitchen\n\n{}", "basil pesto pasta ".repeat(20)),
+    );
+    let profiles = vec![EmbeddingProfile::new("test", "model-b")];
+    harness.run_workspace_index_with_profiles(&profiles);
+    (harness, profiles)
+}
+
+#[test]
+fn given_query_and_note_paths_when_building_context_then_passages_are_cited_once_per_note() {
+    let (harness, profiles) = indexed_harness("mdit-vault-indexing-context-cited");
+
+    let context = build_context(
+        harness.root(),
+        harness.db_path(),
+        &ContextRequest {
+            query: Some("compost".to_string()),
+            note_paths: vec![PathBuf::from("kitchen.md"), PathBuf::from("kitchen.md")],
+            max_tokens: 1_000,
+        },
+        &profiles,
+    )
+    .expect("context should build");
+
+    let rel_paths = context
+        .sources
+        .iter()
+        .map(|source| source.rel_path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(rel_paths, vec!["kitchen.md", "garden.md"]);
+    assert_eq!(context.sources[0].passages.len(), 1);
+    assert!(context
+        .context
+        .starts_with("[1] kitchen.md > Kitchen\n# Kitchen"));
+    assert!(context.context.contains("\n\n[2] garden.md > Garden\n"));
+    assert!(context.token_count <= 1_000);
+    assert!(!context.truncated);
+}
+
+#[test]
+fn given_small_budget_when_building_context_then_lower_priority_passages_are_dropped() {
+    let (harness, profiles) = indexed_harness("mdit-vault-indexing-context-budget");
+    let kitchen_only = build_context(
+        harness.root(),
+        harness.db_path(),
+        &ContextRequest {
+            query: None,
+            note_paths: vec![PathBuf::from("kitchen.md")],
+            max_tokens: 1_000,
+        },
+        &profiles,
+    )
+    .expect("context should build");
+
+    let context = build_context(
+        harness.root(),
+        harness.db_path(),
+        &ContextRequest {
+            query: Some("compost".to_string()),
+            note_paths: vec![PathBuf::from("kitchen.md")],
+            max_tokens: kitchen_only.token_count,
+        },
+        &profiles,
+    )
+    .expect("context should build");
+
+    assert_eq!(context.context, kitchen_only.context);
+    assert_eq!(context.sources.len(), 1);
+    assert!(context.truncated);
+
+    let empty = build_context(
+        harness.root(),
+        harness.db_path(),
+        &ContextRequest {
+            query: Some("  ".to_string()),
+            ..Default::default()
+        },
+        &profiles,
+    );
+    assert!(empty.is_err());
+}
+
+#[test]
+fn given_filter_and_redaction_when_building_context_then_left_out_notes_and_masked_text_stay_out() {
+    let (harness, profiles) = indexed_harness("mdit-vault-indexing-context-filtered");
+
+    let context = build_context_where(
+        harness.root(),
+        harness.db_path(),
+        &ContextRequest {
+            query: Some("basil".to_string()),
+            note_paths: Vec::new(),
+            max_tokens: 1_000,
+        },
+        &profiles,
+        |path| !path.ends_with("garden.md"),
+        |text| *text = text.replace("pesto", "*****"),
+    )
+    .expect("context should build");
+
+    let rel_paths = context
+        .sources
+        .iter()
+        .map(|source| source.rel_path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(rel_paths, vec!["kitchen.md"]);
+    assert!(!context.context.contains("pesto"));
+    assert!(context.context.contains("basil *****"));
+}
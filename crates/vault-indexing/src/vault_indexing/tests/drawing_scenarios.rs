@@ -0,0 +1,108 @@
+use serde_json::json;
+
+use super::super::{search::search_notes_for_query, SkipErrorCode};
+use super::test_support::IndexingHarness;
+
+fn excalidraw(texts: &[&str]) -> String {
+    let elements = texts
+        .iter()
+        .enumerate()
+        .map(|(index, text)| {
+            json!({
+                "id": format!("text-{index}"),
+                "type": "text",
+                "x": 120,
+                "y": 80 * index,
+                "fontSize": 20,
+                "text": text,
+                "isDeleted": false
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "type": "excalidraw",
+        "version": 2,
+        "source": "https://excalidraw.com",
+        "elements": elements,
+        "appState": { "viewBackgroundColor": "#ffffff", "gridSize": null }
+    })
+    .to_string()
+}
+
+#[test]
+fn given_a_drawing_with_wiki_links_when_indexing_then_it_links_and_is_searchable() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-drawing-links");
+    harness.write_note("Plan.md", "# Plan");
+    harness.write_note("Index.md", "Overview in [[Roadmap.excalidraw]]");
+    harness.write_note(
+        "boards/Roadmap.excalidraw",
+        &excalidraw(&["Quarterly milestones", "Launch per [[Plan]]"]),
+    );
+    let summary = harness.run_workspace_index();
+    assert!(
+        summary.skipped_files.is_empty(),
+        "{:?}",
+        summary.skipped_files
+    );
+
+    assert_eq!(
+        harness.link_targets_for("boards/Roadmap.excalidraw"),
+        vec!["Plan.md"]
+    );
+    assert_eq!(
+        harness.link_targets_for("Index.md"),
+        vec!["boards/Roadmap.excalidraw"]
+    );
+    assert_eq!(
+        harness
+            .backlinks("Plan.md")
+            .into_iter()
+            .map(|entry| entry.rel_path)
+            .collect::<Vec<_>>(),
+        vec!["boards/Roadmap.excalidraw"]
+    );
+    let content = harness
+        .doc_content("boards/Roadmap.excalidraw")
+        .expect("drawing should have indexed content");
+    assert!(content.contains("Quarterly milestones"), "{content}");
+    assert!(!content.contains("excalidraw"), "{content}");
+
+    let results = search_notes_for_query(harness.root(), harness.db_path(), "milestones", "", "")
+        .expect("search should run");
+    assert_eq!(results.len(), 1);
+    assert!(results[0].path.ends_with("Roadmap.excalidraw"));
+}
+
+#[test]
+fn given_an_edited_drawing_when_reindexing_the_file_then_links_follow_its_text() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-drawing-edit");
+    harness.write_note("Ada.md", "# Ada");
+    harness.write_note("Grace.md", "# Grace");
+    harness.write_note("flow.tldraw", &tldraw("Ask [[Ada]]"));
+    harness.run_workspace_index();
+    assert_eq!(harness.link_targets_for("flow.tldraw"), vec!["Ada.md"]);
+
+    harness.write_note("flow.tldraw", &tldraw("Ask [[Grace]]"));
+    harness
+        .run_note_index("flow.tldraw")
+        .expect("drawing should index on its own");
+    assert_eq!(harness.link_targets_for("flow.tldraw"), vec!["Grace.md"]);
+
+    harness.write_note("flow.tldraw", "{ not json");
+    let summary = harness.run_workspace_index();
+    assert_eq!(summary.skipped_files.len(), 1);
+    assert!(summary.skipped_files[0].path.ends_with("flow.tldraw"));
+    assert_eq!(
+        summary.skipped_files[0].error_code,
+        SkipErrorCode::InvalidContent
+    );
+    assert!(!summary.skipped_files[0].retryable);
+}
+
+fn tldraw(text: &str) -> String {
+    json!({
+        "tldrawFileFormatVersion": 1,
+        "records": [{ "typeName": "shape", "type": "text", "props": { "text": text } }]
+    })
+    .to_string()
+}
@@ -0,0 +1,161 @@
+use std::fs;
+
+use super::super::{
+    find_in_vault, replace_in_vault, search_in_note, undo_last_replace, FindOptions,
+};
+use super::test_support::IndexingHarness;
+
+#[test]
+fn given_vault_notes_when_finding_then_matches_carry_line_context_and_respect_filters() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-find");
+    harness.write_note("a.md", "# Alpha\nThe cat sat.\nConcatenate Cat\n");
+    harness.write_note("projects/b.md", "cat\n");
+    harness.write_note(".hidden/c.md", "cat\n");
+
+    let all =
+        find_in_vault(harness.root(), "cat", &FindOptions::default()).expect("find should succeed");
+    assert_eq!(
+        all.matches
+            .iter()
+            .map(|found| (found.rel_path.as_str(), found.line, found.context.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("a.md", 1, "The cat sat."),
+            ("a.md", 2, "Concatenate Cat"),
+            ("a.md", 2, "Concatenate Cat"),
+            ("projects/b.md", 0, "cat"),
+        ]
+    );
+    let first = &all.matches[0];
+    assert_eq!(first.start - first.context_start, 4);
+
+    let whole_word = FindOptions {
+        case_sensitive: true,
+        whole_word: true,
+        path_filter: Some("*.md".to_string()),
+        ..FindOptions::default()
+    };
+    let filtered = find_in_vault(harness.root(), "cat", &whole_word).expect("find should succeed");
+    assert_eq!(filtered.matches.len(), 1);
+    assert_eq!(filtered.matches[0].line, 1);
+
+    let limited = FindOptions {
+        max_results: Some(2),
+        ..FindOptions::default()
+    };
+    let limited = find_in_vault(harness.root(), "cat", &limited).expect("find should succeed");
+    assert_eq!(limited.matches.len(), 2);
+    assert!(limited.truncated);
+
+    assert!(find_in_vault(harness.root(), "", &FindOptions::default()).is_err());
+    let invalid = FindOptions {
+        regex: true,
+        ..FindOptions::default()
+    };
+    assert!(find_in_vault(harness.root(), "(", &invalid).is_err());
+}
+
+#[test]
+fn given_replace_when_undoing_then_untouched_notes_are_restored_and_edited_ones_kept() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-replace");
+    harness.write_note("a.md", "Meet on 2024-01-05 and 2024-02-07.\n");
+    harness.write_note("b.md", "Due 2024-03-09\n");
+    harness.write_note("c.md", "No dates here\n");
+    harness.write_note("d.md", "---\nlocked: true\n---\nSigned 2024-04-01\n");
+    harness.run_workspace_index();
+
+    let options = FindOptions {
+        regex: true,
+        ..FindOptions::default()
+    };
+    let replaced = replace_in_vault(
+        harness.root(),
+        harness.db_path(),
+        r"(\d{4})-(\d{2})-(\d{2})",
+        "$3.$2.$1",
+        &options,
+    )
+    .expect("replace should succeed");
+
+    assert!(replaced.journal_id.is_some());
+    assert!(replaced.skipped.is_empty());
+    assert_eq!(replaced.locked, vec!["d.md"]);
+    assert_eq!(
+        replaced
+            .files
+            .iter()
+            .map(|file| (file.rel_path.as_str(), file.replacements))
+            .collect::<Vec<_>>(),
+        vec![("a.md", 2), ("b.md", 1)]
+    );
+    let read = |rel_path: &str| fs::read_to_string(harness.root().join(rel_path)).expect("read");
+    assert_eq!(read("a.md"), "Meet on 05.01.2024 and 07.02.2024.\n");
+    assert_eq!(read("b.md"), "Due 09.03.2024\n");
+    assert_eq!(read("d.md"), "---\nlocked: true\n---\nSigned 2024-04-01\n");
+
+    harness.write_note("b.md", "Due 09.03.2024, moved\n");
+    let undone = undo_last_replace(harness.root(), harness.db_path()).expect("undo should succeed");
+
+    assert_eq!(undone.journal_id, replaced.journal_id);
+    assert_eq!(undone.restored, vec!["a.md"]);
+    assert_eq!(undone.skipped, vec!["b.md"]);
+    assert_eq!(read("a.md"), "Meet on 2024-01-05 and 2024-02-07.\n");
+    assert_eq!(read("b.md"), "Due 09.03.2024, moved\n");
+
+    let nothing_left =
+        undo_last_replace(harness.root(), harness.db_path()).expect("undo should succeed");
+    assert_eq!(nothing_left.journal_id, None);
+}
+
+#[test]
+fn given_no_matches_when_replacing_then_no_journal_entry_is_recorded() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-replace-none");
+    harness.write_note("a.md", "Nothing to see\n");
+
+    let replaced = replace_in_vault(
+        harness.root(),
+        harness.db_path(),
+        "missing",
+        "found",
+        &FindOptions::default(),
+    )
+    .expect("replace should succeed");
+
+    assert_eq!(replaced.journal_id, None);
+    assert!(replaced.files.is_empty());
+    assert_eq!(
+        undo_last_replace(harness.root(), harness.db_path())
+            .expect("undo should succeed")
+            .journal_id,
+        None
+    );
+}
+
+#[test]
+fn given_note_when_searching_within_it_then_ranges_carry_utf16_columns() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-note-search");
+    harness.write_note("a.md", "# Café notes\nSee café, CAFÉ and café.\n");
+    let note_path = harness.root().join("a.md");
+
+    let found = search_in_note(&note_path, "café", &FindOptions::default())
+        .expect("note search should succeed");
+    assert_eq!(
+        found
+            .matches
+            .iter()
+            .map(|found| (found.line, found.column, found.end - found.start))
+            .collect::<Vec<_>>(),
+        vec![(0, 2, 5), (1, 4, 5), (1, 10, 5), (1, 19, 5)]
+    );
+    assert!(!found.truncated);
+
+    let limited = FindOptions {
+        case_sensitive: true,
+        max_results: Some(1),
+        ..FindOptions::default()
+    };
+    let limited = search_in_note(&note_path, "café", &limited).expect("note search should succeed");
+    assert_eq!(limited.matches.len(), 1);
+    assert_eq!((limited.matches[0].line, limited.matches[0].column), (1, 4));
+    assert!(limited.truncated);
+}
@@ -0,0 +1,64 @@
+use super::super::{get_notes_in_bounds, get_notes_near, GeoBounds};
+use super::test_support::IndexingHarness;
+
+fn located_note(name: &str, lat: f64, lng: f64) -> String {
+    format!("---\nlocation: {name}\nlat: {lat}\nlng: {lng}\n---\nField notes")
+}
+
+#[test]
+fn given_located_notes_when_querying_near_a_point_then_closest_come_first() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-geo-near");
+    harness.write_note("trips/louvre.md", &located_note("Louvre", 48.8606, 2.3376));
+    harness.write_note(
+        "trips/eiffel.md",
+        &located_note("Eiffel Tower", 48.8584, 2.2945),
+    );
+    harness.write_note("trips/london.md", &located_note("London", 51.5074, -0.1278));
+    harness.write_note("trips/plain.md", "No location");
+    harness.run_workspace_index();
+
+    let near = get_notes_near(harness.root(), harness.db_path(), 48.8566, 2.3522, 10.0)
+        .expect("nearby notes should load");
+    assert_eq!(
+        near.iter()
+            .map(|note| (note.rel_path.as_str(), note.name.as_deref()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("trips/louvre.md", Some("Louvre")),
+            ("trips/eiffel.md", Some("Eiffel Tower")),
+        ]
+    );
+    assert!(near[0].distance_km.expect("distance") < near[1].distance_km.expect("distance"));
+
+    harness.write_note("trips/louvre.md", "Moved out of the index of places");
+    harness.run_workspace_index();
+    let near = get_notes_near(harness.root(), harness.db_path(), 48.8566, 2.3522, 10.0)
+        .expect("nearby notes should load");
+    assert_eq!(near.len(), 1);
+}
+
+#[test]
+fn given_a_box_across_the_antimeridian_when_querying_then_both_sides_match() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-geo-bounds");
+    harness.write_note("fiji.md", &located_note("Suva", -18.1248, 178.4501));
+    harness.write_note("samoa.md", &located_note("Apia", -13.8333, -171.7667));
+    harness.write_note("sydney.md", &located_note("Sydney", -33.8688, 151.2093));
+    harness.run_workspace_index();
+
+    let pacific = GeoBounds {
+        south: -25.0,
+        west: 170.0,
+        north: -10.0,
+        east: -165.0,
+    };
+    let notes = get_notes_in_bounds(harness.root(), harness.db_path(), pacific)
+        .expect("notes in bounds should load");
+    assert_eq!(
+        notes
+            .iter()
+            .map(|note| note.rel_path.as_str())
+            .collect::<Vec<_>>(),
+        vec!["fiji.md", "samoa.md"]
+    );
+    assert!(notes.iter().all(|note| note.distance_km.is_none()));
+}
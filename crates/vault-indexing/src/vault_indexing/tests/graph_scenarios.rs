@@ -1,4 +1,6 @@
-use super::super::get_graph_view_data;
+use super::super::{
+    export_graph, get_graph_view_data, GraphExportFormat, GraphExportOptions, GraphTracker,
+};
 use super::test_support::IndexingHarness;
 
 #[test]
@@ -75,3 +77,133 @@ fn given_duplicate_edges_when_loading_graph_then_each_pair_is_returned_once() {
         .count();
     assert_eq!(matching_edges, 1);
 }
+
+#[test]
+fn given_tracked_graph_when_a_link_changes_then_only_the_difference_is_returned() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-graph-delta");
+    harness.write_note("source.md", "[[target]]\n");
+    harness.write_note("target.md", "# Target\n");
+    harness.run_workspace_index();
+
+    let tracker = GraphTracker::default();
+    let initial = tracker
+        .delta_since(harness.root(), harness.db_path(), None)
+        .expect("initial graph should load");
+    assert!(initial.reset);
+    assert_eq!(initial.upserted_nodes.len(), 2);
+    assert_eq!(initial.upserted_edges.len(), 1);
+
+    let unchanged = tracker
+        .delta_since(harness.root(), harness.db_path(), Some(initial.version))
+        .expect("unchanged graph should load");
+    assert!(unchanged.is_empty());
+    assert_eq!(unchanged.version, initial.version);
+
+    harness.write_note("source.md", "[[missing]]\n");
+    harness.run_workspace_index();
+
+    let delta = tracker
+        .delta_since(harness.root(), harness.db_path(), Some(initial.version))
+        .expect("changed graph should load");
+    let source_id = format!("doc:{}", harness.doc_id("source.md").unwrap());
+    let target_id = format!("doc:{}", harness.doc_id("target.md").unwrap());
+    assert!(!delta.reset);
+    assert_eq!(delta.base_version, initial.version);
+    assert!(delta.version > initial.version);
+    assert_eq!(
+        delta
+            .upserted_nodes
+            .iter()
+            .map(|node| node.id.as_str())
+            .collect::<Vec<_>>(),
+        vec!["unresolved:missing.md"]
+    );
+    assert!(delta.removed_nodes.is_empty());
+    assert_eq!(delta.upserted_edges.len(), 1);
+    assert_eq!(delta.upserted_edges[0].target, "unresolved:missing.md");
+    assert_eq!(delta.removed_edges.len(), 1);
+    assert_eq!(delta.removed_edges[0].source, source_id);
+    assert_eq!(delta.removed_edges[0].target, target_id);
+}
+
+#[test]
+fn given_several_changes_when_client_is_behind_then_deltas_are_merged() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-graph-delta-merge");
+    harness.write_note("a.md", "# A\n");
+    harness.run_workspace_index();
+
+    let tracker = GraphTracker::default();
+    let initial = tracker
+        .delta_since(harness.root(), harness.db_path(), None)
+        .expect("initial graph should load");
+
+    harness.write_note("b.md", "# B\n");
+    harness.run_workspace_index();
+    tracker
+        .refresh(harness.root(), harness.db_path())
+        .expect("graph should refresh")
+        .expect("adding a note should produce a delta");
+    let b_id = format!("doc:{}", harness.doc_id("b.md").unwrap());
+
+    harness.remove_note("b.md");
+    harness.run_workspace_index();
+
+    let merged = tracker
+        .delta_since(harness.root(), harness.db_path(), Some(initial.version))
+        .expect("merged delta should load");
+    assert!(!merged.reset);
+    assert!(merged.upserted_nodes.is_empty());
+    assert_eq!(merged.removed_nodes, vec![b_id]);
+
+    let stale = tracker
+        .delta_since(harness.root(), harness.db_path(), Some(initial.version - 1))
+        .expect("unknown version should still load");
+    assert!(stale.reset);
+    assert_eq!(stale.upserted_nodes.len(), 1);
+}
+
+#[test]
+fn given_tags_and_unresolved_links_when_exporting_graph_json_then_options_pick_nodes() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-graph-export");
+    harness.write_note("source.md", "#idea\n[[target]]\n[[missing]]\n");
+    harness.write_note("target.md", "#idea #draft\n");
+    harness.run_workspace_index();
+    let output_path = harness.root().join("graph.json");
+
+    let export = |options: &GraphExportOptions| {
+        let report = export_graph(
+            harness.root(),
+            harness.db_path(),
+            &output_path,
+            GraphExportFormat::Json,
+            options,
+        )
+        .expect("graph export should succeed");
+        let written = std::fs::read_to_string(&output_path).expect("export file");
+        let graph: serde_json::Value = serde_json::from_str(&written).expect("valid json");
+        let kinds = |key: &str| {
+            let mut kinds = graph[key]
+                .as_array()
+                .expect("array")
+                .iter()
+                .map(|item| item["kind"].as_str().expect("kind").to_string())
+                .collect::<Vec<_>>();
+            kinds.sort();
+            kinds
+        };
+        assert_eq!(report.node_count, graph["nodes"].as_array().unwrap().len());
+        assert_eq!(report.edge_count, graph["links"].as_array().unwrap().len());
+        (kinds("nodes"), kinds("links"))
+    };
+
+    let (nodes, links) = export(&GraphExportOptions::default());
+    assert_eq!(nodes, vec!["note", "note", "unresolved"]);
+    assert_eq!(links, vec!["link", "link"]);
+
+    let (nodes, links) = export(&GraphExportOptions {
+        include_tags: true,
+        include_unresolved: false,
+    });
+    assert_eq!(nodes, vec!["note", "note", "tag", "tag"]);
+    assert_eq!(links, vec!["link", "tag", "tag", "tag"]);
+}
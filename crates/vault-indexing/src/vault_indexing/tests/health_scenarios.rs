@@ -0,0 +1,70 @@
+use super::super::{get_embedding_coverage, get_vault_health};
+use super::test_support::IndexingHarness;
+
+#[test]
+fn given_partly_stale_vault_when_reading_health_then_each_gap_is_counted() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-health");
+    harness.write_note(
+        "a.md",
+        "See [[b]] and [[missing]]\n\n![](img/used%20photo.png)\n",
+    );
+    harness.write_note("b.md", "# B\n");
+    harness.write_note("c.md", "# C\n");
+    harness.write_note("img/used photo.png", "png");
+    harness.write_note("img/unused.png", "png");
+    harness.run_workspace_index();
+    harness.set_doc_embedding_metadata("a.md", Some("nomic-embed-text"), Some(768));
+    harness.set_doc_source_stat("c.md", Some(1), Some(1));
+
+    let health = get_vault_health(harness.root(), harness.db_path()).expect("health should load");
+
+    assert_eq!(health.source_file_count, 3);
+    assert_eq!(health.fresh_file_count, 2);
+    assert_eq!(health.freshness_percent, 66.7);
+    assert_eq!(health.indexed_doc_count, 3);
+    assert_eq!(health.embedded_doc_count, 1);
+    assert_eq!(health.embedding_coverage_percent, 33.3);
+    assert_eq!(health.unresolved_link_count, 1);
+    assert_eq!(health.orphan_attachments, vec!["img/unused.png"]);
+    assert!(health.oversized_notes.is_empty());
+}
+
+#[test]
+fn given_unindexed_vault_when_reading_health_then_nothing_is_fresh() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-health-empty");
+    harness.write_note("a.md", "# A\n");
+
+    let health = get_vault_health(harness.root(), harness.db_path()).expect("health should load");
+
+    assert_eq!(health.source_file_count, 1);
+    assert_eq!(health.fresh_file_count, 0);
+    assert_eq!(health.freshness_percent, 0.0);
+    assert_eq!(health.embedding_coverage_percent, 100.0);
+}
+
+#[test]
+fn given_embedded_vault_when_reading_coverage_then_model_and_staleness_are_counted() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-embedding-coverage");
+    harness.write_note("a.md", "# A\n\nAlpha notes.\n");
+    harness.write_note("b.md", "# B\n\nBeta notes.\n");
+    harness.write_note("c.md", "---\nembed: false\n---\n# C\n");
+    harness.run_workspace_index_with_embeddings("test", "model-a");
+    app_storage::vault::set_embedding_config(harness.db_path(), harness.root(), "test", "model-a")
+        .expect("save embedding config");
+    harness.set_doc_embedding_metadata("b.md", Some("model-b"), Some(768));
+    harness.write_note("a.md", "# A\n\nAlpha notes, edited.\n");
+    harness.run_workspace_index();
+
+    let coverage =
+        get_embedding_coverage(harness.root(), harness.db_path()).expect("coverage should load");
+
+    assert_eq!(coverage.provider.as_deref(), Some("test"));
+    assert_eq!(coverage.model.as_deref(), Some("model-a"));
+    assert_eq!(coverage.model_context_tokens, None);
+    assert_eq!(coverage.indexed_doc_count, 2);
+    assert_eq!(coverage.current_model_doc_count, 1);
+    assert_eq!(coverage.other_model_doc_count, 1);
+    assert_eq!(coverage.stale_doc_count, 1);
+    assert_eq!(coverage.embedded_segment_count, 2);
+    assert_eq!(coverage.oversized_segment_count, 0);
+}
@@ -26,6 +26,7 @@ fn make_file(root: &Path, rel_path: &str) -> MarkdownFile {
         rel_path: rel_path.replace('\\', "/"),
         last_source_size: None,
         last_source_mtime_ns: None,
+        source_created_ns: None,
     }
 }
 
@@ -67,6 +68,7 @@ fn resolve_wiki_target(
         current_note_path.and_then(|path| path.to_str()),
         raw_target,
         &workspace_rel_paths,
+        &HashMap::new(),
     )
 }
 
@@ -248,3 +250,85 @@ fn resolves_wiki_target_with_shortest_unique_suffix_canonical() {
     assert!(!resolved.disambiguated);
     assert!(!resolved.unresolved);
 }
+
+#[test]
+fn resolves_wiki_links_through_aliases_after_filenames() {
+    let root = temp_root();
+    let mut docs = HashMap::new();
+    docs.insert("projects/apollo.md".to_string(), 1);
+    docs.insert("moon.md".to_string(), 2);
+    let aliases = HashMap::from([
+        (
+            "moonshot".to_string(),
+            vec!["projects/apollo.md".to_string()],
+        ),
+        ("moon".to_string(), vec!["projects/apollo.md".to_string()]),
+    ]);
+
+    let resolver = LinkResolver::new(&root, docs).with_aliases(&aliases);
+    let source = make_file(&root, "source.md");
+    let links = resolver.resolve_links(&source, "[[Moonshot#Goals]] [[Moon]]");
+
+    assert_eq!(
+        find_link(&links, "projects/apollo.md").target_doc_id,
+        Some(1)
+    );
+    assert_eq!(find_link(&links, "moon.md").target_doc_id, Some(2));
+}
+
+#[test]
+fn marks_wiki_targets_resolved_via_alias() {
+    let root = temp_root();
+    let workspace_rel_paths = vec!["projects/apollo.md".to_string(), "moon.md".to_string()];
+    let aliases = HashMap::from([(
+        "moonshot".to_string(),
+        vec!["projects/apollo.md".to_string()],
+    )]);
+
+    let resolved = resolve_wiki_link_target(
+        &root,
+        None,
+        "Moonshot#Goals",
+        &workspace_rel_paths,
+        &aliases,
+    );
+    assert_eq!(
+        resolved.resolved_rel_path.as_deref(),
+        Some("projects/apollo.md")
+    );
+    assert_eq!(resolved.canonical_target, "Moonshot#Goals");
+    assert!(resolved.resolved_via_alias);
+
+    let resolved = resolve_wiki_link_target(&root, None, "moon", &workspace_rel_paths, &aliases);
+    assert_eq!(resolved.resolved_rel_path.as_deref(), Some("moon.md"));
+    assert!(!resolved.resolved_via_alias);
+}
+
+#[test]
+fn records_each_mention_with_offsets_and_surrounding_lines() {
+    let root = temp_root();
+    let mut docs = HashMap::new();
+    docs.insert("target.md".to_string(), 1);
+
+    let contents = "intro\nsee [[target]] here\noutro\n\nlast [again](target.md)";
+    let links = resolve_links(&root, docs, "source.md", contents);
+
+    assert_eq!(links.len(), 1);
+    let mentions = &find_link(&links, "target.md").mentions;
+    assert_eq!(mentions.len(), 2);
+
+    let wiki = &mentions[0];
+    assert_eq!(&contents[wiki.start..wiki.end], "[[target]]");
+    assert_eq!(wiki.line, 1);
+    assert_eq!(wiki.context, "intro\nsee [[target]] here\noutro");
+    assert_eq!(wiki.context_start, 0);
+
+    let markdown = &mentions[1];
+    assert_eq!(
+        &contents[markdown.start..markdown.end],
+        "[again](target.md)"
+    );
+    assert_eq!(markdown.line, 4);
+    assert_eq!(markdown.context, "\nlast [again](target.md)");
+    assert_eq!(&contents[markdown.context_start..], markdown.context);
+}
@@ -0,0 +1,83 @@
+use super::super::{run_maintenance_batch, EmbeddingProfile, MaintenanceBatch, MaintenanceTask};
+use super::test_support::IndexingHarness;
+
+fn run_batch(
+    harness: &IndexingHarness,
+    task: MaintenanceTask,
+    profiles: &[EmbeddingProfile],
+    batch_size: usize,
+) -> MaintenanceBatch {
+    run_maintenance_batch(
+        harness.root(),
+        harness.db_path(),
+        task,
+        profiles,
+        batch_size,
+    )
+    .expect("maintenance batch should succeed")
+}
+
+#[test]
+fn given_notes_deleted_while_unwatched_when_pruning_then_their_rows_go_in_batches() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-maintenance-prune");
+    for name in ["a.md", "b.md", "c.md"] {
+        harness.write_note(name, "# Note\n\nbody");
+    }
+    harness.run_workspace_index();
+    harness.remove_note("a.md");
+    harness.remove_note("b.md");
+
+    let first = run_batch(&harness, MaintenanceTask::PruneOrphans, &[], 1);
+    assert_eq!(
+        first,
+        MaintenanceBatch {
+            processed: 1,
+            done: false
+        }
+    );
+    let second = run_batch(&harness, MaintenanceTask::PruneOrphans, &[], 1);
+    assert_eq!(second.processed, 1);
+    let last = run_batch(&harness, MaintenanceTask::PruneOrphans, &[], 1);
+    assert_eq!(
+        last,
+        MaintenanceBatch {
+            processed: 0,
+            done: true
+        }
+    );
+
+    assert_eq!(harness.meta().indexed_doc_count, 1);
+    assert!(harness.doc_id("c.md").is_some());
+}
+
+#[test]
+fn given_notes_embedded_with_another_model_when_reembedding_drift_then_they_move_to_the_current_model(
+) {
+    let harness = IndexingHarness::new("mdit-vault-indexing-maintenance-drift");
+    harness.write_note("garden.md", "# Garden\n\ntomato basil compost");
+    harness.write_note("kitchen.md", "# Kitchen\n\nbasil pesto pasta");
+    harness.run_workspace_index_with_profiles(&[EmbeddingProfile::new("test", "model-a")]);
+
+    let profiles = vec![EmbeddingProfile::new("test", "model-b")];
+    let batch = run_batch(&harness, MaintenanceTask::ReembedDrift, &profiles, 10);
+    assert_eq!(
+        batch,
+        MaintenanceBatch {
+            processed: 2,
+            done: true
+        }
+    );
+    assert_eq!(
+        harness.doc_embedding_metadata("garden.md"),
+        Some((Some("model-b".to_string()), Some(3)))
+    );
+
+    let again = run_batch(&harness, MaintenanceTask::ReembedDrift, &profiles, 10);
+    assert_eq!(
+        again,
+        MaintenanceBatch {
+            processed: 0,
+            done: true
+        }
+    );
+}
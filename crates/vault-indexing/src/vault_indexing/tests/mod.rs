@@ -1,7 +1,19 @@
+mod activity_scenarios;
+mod calendar_scenarios;
 mod chunking_scenarios;
+mod citation_scenarios;
+mod consistency_scenarios;
+mod context_scenarios;
+mod drawing_scenarios;
+mod find_replace_scenarios;
+mod geo_scenarios;
 mod graph_scenarios;
+mod health_scenarios;
 mod link_scenarios;
+mod maintenance_scenarios;
 mod note_scenarios;
+mod people_scenarios;
+mod query_scenarios;
 mod search_scenarios;
 mod sync_scenarios;
 mod tag_scenarios;
@@ -3,7 +3,8 @@ use std::path::PathBuf;
 use rusqlite::{params, Connection};
 
 use super::super::{
-    delete_indexed_note, delete_indexed_notes_by_prefix, get_related_notes, rename_indexed_note,
+    delete_indexed_note, delete_indexed_notes_by_prefix, get_note_index_status, get_related_notes,
+    reindex_note_with_profiles, rename_indexed_note,
 };
 use super::test_support::IndexingHarness;
 
@@ -27,6 +28,46 @@ fn given_deleted_note_when_indexing_single_note_then_other_docs_are_not_pruned()
     assert_eq!(harness.meta().indexed_doc_count, 2);
 }
 
+#[test]
+fn given_edited_note_when_reading_index_status_then_stale_hash_is_reported_until_reindexed() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-note-status");
+    harness.write_note("a.md", "[[b]] and [[missing]]\n");
+    harness.write_note("b.md", "# B\n");
+    harness.run_workspace_index();
+    let note_path = harness.root().join("a.md");
+
+    let status = get_note_index_status(harness.root(), harness.db_path(), &note_path)
+        .expect("status should load");
+    assert!(status.indexed);
+    assert!(status.up_to_date);
+    assert_eq!(status.rel_path, "a.md");
+    assert_eq!(status.link_count, 2);
+    assert_eq!(status.unresolved_link_count, 1);
+    assert_eq!(status.embedding_model, None);
+
+    harness.write_note("a.md", "[[b]] only\n");
+    let status = get_note_index_status(harness.root(), harness.db_path(), &note_path)
+        .expect("status should load");
+    assert!(!status.up_to_date);
+    assert_ne!(status.indexed_hash, status.current_hash);
+
+    harness
+        .run_note_index("a.md")
+        .expect("single-note indexing should succeed");
+    let unchanged = harness
+        .run_note_index("a.md")
+        .expect("single-note indexing should succeed");
+    assert_eq!(unchanged.links_written, 0);
+
+    let forced = reindex_note_with_profiles(harness.root(), harness.db_path(), &note_path, &[])
+        .expect("forced reindex should succeed");
+    assert_eq!(forced.links_written, 1);
+    let status = get_note_index_status(harness.root(), harness.db_path(), &note_path)
+        .expect("status should load");
+    assert!(status.up_to_date);
+    assert_eq!(status.link_count, 1);
+}
+
 #[test]
 fn given_note_path_outside_workspace_when_indexing_single_note_then_it_is_rejected() {
     let harness = IndexingHarness::new("mdit-vault-indexing-note-outside");
@@ -56,6 +97,37 @@ fn given_non_markdown_note_path_when_indexing_single_note_then_it_is_rejected()
     assert!(error.to_string().contains("markdown"));
 }
 
+#[test]
+fn given_linking_sources_when_loading_backlinks_then_each_mention_carries_its_context() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-backlink-mentions");
+    harness.write_note("target.md", "# Target\n");
+    harness.write_note(
+        "source.md",
+        "# Source\nFirst [[target]] mention\n\nSecond [link](target.md)\n",
+    );
+
+    harness.run_workspace_index();
+
+    let backlinks = harness.backlinks("target.md");
+    assert_eq!(backlinks.len(), 1);
+    let mentions = &backlinks[0].mentions;
+    assert_eq!(
+        mentions
+            .iter()
+            .map(|mention| (mention.line, mention.context.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, "# Source\nFirst [[target]] mention"),
+            (3, "\nSecond [link](target.md)"),
+        ]
+    );
+    let first = &mentions[0];
+    assert_eq!(
+        &first.context[first.start - first.context_start..first.end - first.context_start],
+        "[[target]]"
+    );
+}
+
 #[test]
 fn given_multiple_links_from_same_source_when_loading_backlinks_then_each_source_appears_once() {
     let harness = IndexingHarness::new("mdit-vault-indexing-backlinks-dedupe");
@@ -166,6 +238,53 @@ fn given_limit_when_loading_related_notes_then_it_caps_results() {
     assert_eq!(related[0].rel_path, "near-1.md");
 }
 
+#[test]
+fn given_recency_decay_when_loading_related_notes_then_recent_notes_rank_higher() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-related-recency");
+    harness.write_note("source.md", &("source ".repeat(64)));
+    harness.write_note("old.md", &("old ".repeat(64)));
+    harness.write_note("recent.md", &("recent ".repeat(64)));
+    harness.run_workspace_index();
+
+    set_doc_embedding(&harness, "source.md", "model-a", 2, &[1.0, 0.0]);
+    set_doc_embedding(&harness, "old.md", "model-a", 2, &[0.95, 0.05]);
+    set_doc_embedding(&harness, "recent.md", "model-a", 2, &[0.8, 0.2]);
+    let conn = Connection::open(harness.db_path()).expect("failed to open test sqlite db");
+    conn.execute(
+        "UPDATE doc SET last_source_mtime_ns = last_source_mtime_ns - ?1 WHERE rel_path = 'old.md'",
+        params![400_i64 * 86_400 * 1_000_000_000],
+    )
+    .expect("failed to age old note");
+
+    let load = || {
+        get_related_notes(
+            harness.root(),
+            harness.db_path(),
+            &harness.root().join("source.md"),
+            1,
+        )
+        .expect("related note lookup should succeed")
+    };
+
+    let related = load();
+    assert_eq!(related[0].rel_path, "old.md");
+    assert_eq!(related[0].score, related[0].similarity);
+
+    app_storage::vault_settings::set_vault_setting(
+        harness.db_path(),
+        harness.root(),
+        "relatedNotesRecency",
+        &serde_json::json!({ "enabled": true, "halfLifeDays": 30 }),
+    )
+    .expect("enable recency decay");
+
+    let related = load();
+    assert_eq!(related.len(), 1);
+    assert_eq!(related[0].rel_path, "recent.md");
+    assert!(related[0].score <= related[0].similarity);
+    assert!(related[0].similarity > 0.9);
+}
+
 #[test]
 fn given_source_without_embedding_metadata_when_loading_related_notes_then_it_returns_empty() {
     let harness = IndexingHarness::new("mdit-vault-indexing-related-empty");
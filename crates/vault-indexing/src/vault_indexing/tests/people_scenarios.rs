@@ -0,0 +1,69 @@
+use super::super::suggest_person_links;
+use super::test_support::IndexingHarness;
+
+#[test]
+fn given_people_pages_when_suggesting_links_then_capitalized_unlinked_mentions_are_returned() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-people");
+    harness.write_note(
+        "People/Ada Lovelace.md",
+        "---\naliases:\n  - ada\n  - Countess\n---\nMathematician",
+    );
+    harness.write_note("People/Bo.md", "Engineer");
+    harness.write_note("Projects/Bo.md", "Another note named Bo");
+    let meeting = [
+        "---",
+        "attendees: Ada Lovelace",
+        "---",
+        "Met Ada Lovelace and Bo; the Countess agreed.",
+        "Then ada left, [[Ada Lovelace]] noted, and `Bo` wrote [Ada](https://ada.example).",
+        "```",
+        "Ada",
+        "```",
+        "Abo and Adams stay unlinked, Ada does not.",
+    ]
+    .join("\n");
+    harness.write_note("Meetings/Kickoff.md", &meeting);
+    harness.run_workspace_index();
+
+    let suggestions = suggest_person_links(
+        harness.root(),
+        harness.db_path(),
+        &harness.root().join("Meetings/Kickoff.md"),
+        "People",
+    )
+    .expect("suggestions should load");
+
+    assert_eq!(
+        suggestions
+            .iter()
+            .map(|suggestion| (
+                suggestion.text.as_str(),
+                suggestion.person_rel_path.as_str(),
+                suggestion.link.as_str()
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("Ada Lovelace", "People/Ada Lovelace.md", "[[Ada Lovelace]]"),
+            ("Bo", "People/Bo.md", "[[People/Bo|Bo]]"),
+            (
+                "Countess",
+                "People/Ada Lovelace.md",
+                "[[Ada Lovelace|Countess]]"
+            ),
+            ("Ada", "People/Ada Lovelace.md", "[[Ada Lovelace|Ada]]"),
+        ]
+    );
+    let first = &suggestions[0];
+    assert_eq!(&meeting[first.start..first.end], "Ada Lovelace");
+    assert_eq!((first.line, first.column), (3, 4));
+    assert_eq!(suggestions[3].line, 8);
+
+    let own_page = suggest_person_links(
+        harness.root(),
+        harness.db_path(),
+        &harness.root().join("People/Ada Lovelace.md"),
+        "People",
+    )
+    .expect("suggestions should load");
+    assert!(own_page.is_empty());
+}
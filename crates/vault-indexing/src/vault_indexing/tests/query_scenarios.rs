@@ -0,0 +1,131 @@
+use serde_json::json;
+
+use super::super::{
+    find_query_dependents, query_notes, render_query_block, NoteQuery, NoteQueryField,
+    NoteQuerySort, NoteQuerySource,
+};
+use super::test_support::IndexingHarness;
+
+fn property(key: &str) -> NoteQueryField {
+    NoteQueryField::Property {
+        key: key.to_string(),
+    }
+}
+
+#[test]
+fn given_tasks_with_properties_when_querying_a_folder_then_rows_follow_the_sort() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-query");
+    harness.write_note(
+        "Tasks/write.md",
+        "---\nStatus: doing\npriority: 2\n---\n#work Draft",
+    );
+    harness.write_note("Tasks/ship.md", "---\nstatus: todo\npriority: 1\n---\n");
+    harness.write_note("Tasks/idea.md", "---\ntags: [later]\n---\n#work");
+    harness.write_note("Notes/other.md", "---\nstatus: done\npriority: 3\n---\n");
+
+    harness.run_workspace_index();
+
+    let result = query_notes(
+        harness.root(),
+        harness.db_path(),
+        &NoteQuery {
+            source: Some(NoteQuerySource::Folder {
+                path: "Tasks/".to_string(),
+            }),
+            select: vec![NoteQueryField::Title, property("status"), property("tags")],
+            sort: vec![NoteQuerySort {
+                field: property("priority"),
+                descending: true,
+            }],
+            limit: None,
+        },
+    )
+    .expect("query should run");
+
+    let rows = result
+        .rows
+        .iter()
+        .map(|row| (row.rel_path.as_str(), row.values.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        vec![
+            (
+                "Tasks/write.md",
+                vec![json!("write"), json!("doing"), json!(null)]
+            ),
+            (
+                "Tasks/ship.md",
+                vec![json!("ship"), json!("todo"), json!(null)]
+            ),
+            (
+                "Tasks/idea.md",
+                vec![json!("idea"), json!(null), json!(["later"])]
+            ),
+        ]
+    );
+
+    let tagged = query_notes(
+        harness.root(),
+        harness.db_path(),
+        &NoteQuery {
+            source: Some(NoteQuerySource::Tag {
+                tag: "#work".to_string(),
+            }),
+            select: vec![NoteQueryField::Path],
+            limit: Some(1),
+            ..Default::default()
+        },
+    )
+    .expect("tag query should run");
+    assert_eq!(tagged.rows.len(), 1);
+    assert_eq!(tagged.rows[0].values, vec![json!("Tasks/idea.md")]);
+
+    let too_many = NoteQuery {
+        limit: Some(5000),
+        ..Default::default()
+    };
+    assert!(query_notes(harness.root(), harness.db_path(), &too_many).is_err());
+}
+
+#[test]
+fn given_query_blocks_when_their_sources_change_then_the_notes_are_dependents() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-query-blocks");
+    harness.write_note(
+        "Projects/Board.md",
+        "```mdit-query\nfrom folder ./Tasks\nselect title, status\n```",
+    );
+    harness.write_note(
+        "Work.md",
+        "```mdit-query\nfrom tag #work\n```\n\n```mdit-query\nfrom nowhere\n```",
+    );
+    harness.write_note("Everything.md", "```mdit-query\nlimit 3\n```");
+    harness.write_note(
+        "Projects/Tasks/ship.md",
+        "---\nstatus: todo\n---\n#work/client",
+    );
+    harness.write_note("Projects/plan.md", "Plan");
+
+    harness.run_workspace_index();
+
+    let rendered = render_query_block(
+        harness.root(),
+        harness.db_path(),
+        &harness.root().join("Projects/Board.md"),
+        "from folder ./Tasks\nselect title, status",
+    )
+    .expect("query block should render");
+    assert_eq!(rendered.rows.len(), 1);
+    assert_eq!(rendered.rows[0].values, vec![json!("ship"), json!("todo")]);
+
+    let dependents = |rel_path: &str| {
+        find_query_dependents(harness.root(), harness.db_path(), &[rel_path.to_string()])
+            .expect("dependents should load")
+    };
+    assert_eq!(
+        dependents("Projects/Tasks/ship.md"),
+        vec!["Everything.md", "Projects/Board.md", "Work.md"]
+    );
+    assert_eq!(dependents("Projects/plan.md"), vec!["Everything.md"]);
+    assert_eq!(dependents("Everything.md"), Vec::<String>::new());
+}
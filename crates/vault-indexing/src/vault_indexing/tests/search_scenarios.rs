@@ -1,9 +1,13 @@
 use std::path::Path;
 
 use super::super::search::{
-    materialize_ranked_entries, rank_score_inputs, search_notes_for_query, RankedCandidate,
-    ScoreInput,
+    group_search_results, materialize_ranked_entries, rank_score_inputs, search_notes_for_query,
+    search_notes_for_query_in_scope, search_notes_for_query_with_options,
+    search_notes_for_query_with_profiles, search_notes_with_correction, search_segments,
+    search_segments_where, RankedCandidate, ScoreInput, SearchGroupBy, SearchMode, SearchOptions,
+    SearchResultGroup, SearchScope, SearchSort, SemanticNoteEntry,
 };
+use super::super::{apply_fts_tokenizer, EmbeddingProfile};
 use super::test_support::IndexingHarness;
 
 #[test]
@@ -13,11 +17,13 @@ fn given_vector_and_bm25_scores_when_ranking_then_semantic_weight_dominates() {
             rel_path: "semantic.md".to_string(),
             bm25: Some(0.1),
             vector: Some(0.9),
+            passage: None,
         },
         ScoreInput {
             rel_path: "keyword.md".to_string(),
             bm25: Some(1.3),
             vector: Some(0.2),
+            passage: None,
         },
     ]);
 
@@ -33,11 +39,13 @@ fn given_bm25_only_scores_when_ranking_then_bm25_only_ranking_is_used() {
             rel_path: "high.md".to_string(),
             bm25: Some(0.9),
             vector: None,
+            passage: None,
         },
         ScoreInput {
             rel_path: "low.md".to_string(),
             bm25: Some(0.1),
             vector: None,
+            passage: None,
         },
     ]);
 
@@ -57,14 +65,17 @@ fn given_small_or_missing_files_when_materializing_ranked_candidates_then_only_r
             RankedCandidate {
                 rel_path: "tiny.md".to_string(),
                 similarity: 0.9,
+                passage: None,
             },
             RankedCandidate {
                 rel_path: "missing.md".to_string(),
                 similarity: 0.8,
+                passage: None,
             },
             RankedCandidate {
                 rel_path: "full.md".to_string(),
                 similarity: 0.7,
+                passage: None,
             },
         ],
     )
@@ -98,3 +109,680 @@ fn given_empty_query_or_missing_embedding_inputs_when_searching_then_it_returns_
             .expect("missing model should fall back to BM25-only search");
     assert!(missing_model.is_empty());
 }
+
+#[test]
+fn given_unavailable_primary_profile_when_indexing_and_searching_then_fallback_profile_is_used() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-profile-fallback");
+    harness.write_note(
+        "garden.md",
+        &format!("# Garden\n\n{}", "tomato basil compost ".repeat(20)),
+    );
+    let profiles = vec![
+        EmbeddingProfile::new("offline", "model-a"),
+        EmbeddingProfile::new("test", "model-b"),
+    ];
+
+    let summary = harness.run_workspace_index_with_profiles(&profiles);
+
+    assert!(summary.embeddings_written > 0);
+    assert_eq!(
+        harness.doc_embedding_provider("garden.md").as_deref(),
+        Some("test")
+    );
+    assert_eq!(
+        harness.doc_embedding_metadata("garden.md"),
+        Some((Some("model-b".to_string()), Some(3)))
+    );
+
+    let results = search_notes_for_query_with_profiles(
+        harness.root(),
+        harness.db_path(),
+        "compost",
+        &profiles,
+    )
+    .expect("search should fall back to the profile that produced stored vectors");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].name, "garden.md");
+}
+
+#[test]
+fn given_no_reachable_profile_when_searching_then_bm25_results_are_returned() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-profile-offline");
+    harness.write_note(
+        "garden.md",
+        &format!("# Garden\n\n{}", "tomato basil compost ".repeat(20)),
+    );
+    harness.run_workspace_index();
+
+    let results = search_notes_for_query_with_profiles(
+        harness.root(),
+        harness.db_path(),
+        "compost",
+        &[EmbeddingProfile::new("offline", "model-a")],
+    )
+    .expect("search should not fail when the provider is unavailable");
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].name, "garden.md");
+    assert!(results[0].passage.is_none());
+}
+
+#[test]
+fn given_embedded_note_when_searching_then_matched_passage_points_into_the_note() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-passage");
+    let contents = format!(
+        "Preface line\n\n# Garden\n\n## Beds\n\n{}",
+        "tomato basil compost ".repeat(20)
+    );
+    harness.write_note("garden.md", &contents);
+    let profiles = vec![EmbeddingProfile::new("test", "model-b")];
+    harness.run_workspace_index_with_profiles(&profiles);
+
+    let results = search_notes_for_query_with_profiles(
+        harness.root(),
+        harness.db_path(),
+        "compost",
+        &profiles,
+    )
+    .expect("search should succeed");
+
+    assert_eq!(results.len(), 1);
+    let passage = results[0]
+        .passage
+        .as_ref()
+        .expect("vector match should carry its passage");
+    assert_eq!(passage.start_offset, 0);
+    assert_eq!(passage.end_offset, contents.trim_end().len());
+    assert!(passage.heading_path.is_empty());
+}
+
+#[test]
+fn given_code_only_scope_when_searching_then_only_notes_with_code_matches_are_returned() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-code-scope");
+    harness.write_note(
+        "prose.md",
+        &format!("# Parser\n\n{}", "the parser reads tokens ".repeat(20)),
+    );
+    harness.write_note(
+        "code.md",
+        &format!(
+            "# Parser\n\n```rust\nfn parser() {{\n{}}}\n```\n",
+            "    // the parser reads tokens\n".repeat(20)
+        ),
+    );
+    let profiles = vec![EmbeddingProfile::new("test", "model-b")];
+    harness.run_workspace_index_with_profiles(&profiles);
+
+    let all = search_notes_for_query_in_scope(
+        harness.root(),
+        harness.db_path(),
+        "parser",
+        &profiles,
+        SearchScope::All,
+    )
+    .expect("unscoped search should succeed");
+    assert_eq!(all.len(), 2);
+
+    let code_only = search_notes_for_query_in_scope(
+        harness.root(),
+        harness.db_path(),
+        "parser",
+        &profiles,
+        SearchScope::CodeOnly,
+    )
+    .expect("code-only search should succeed");
+    assert_eq!(code_only.len(), 1);
+    assert_eq!(code_only[0].name, "code.md");
+    assert_eq!(
+        code_only[0]
+            .passage
+            .as_ref()
+            .and_then(|passage| passage.code_language.as_deref()),
+        Some("rust")
+    );
+}
+
+#[test]
+fn given_archived_note_when_searching_then_it_is_returned_only_when_requested() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-archived");
+    harness.write_note(
+        "current.md",
+        &format!("# Roadmap\n\n{}", "roadmap milestones ".repeat(20)),
+    );
+    harness.write_note(
+        "Archive/old.md",
+        &format!(
+            "---\narchived: true\n---\n# Roadmap\n\n{}",
+            "roadmap milestones ".repeat(20)
+        ),
+    );
+    let profiles = vec![EmbeddingProfile::new("test", "model-b")];
+    harness.run_workspace_index_with_profiles(&profiles);
+
+    let search = |options| {
+        search_notes_for_query_with_options(
+            harness.root(),
+            harness.db_path(),
+            "roadmap",
+            &profiles,
+            options,
+        )
+        .expect("search should succeed")
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(search(SearchOptions::default()), vec!["current.md"]);
+    let mut with_archived = search(SearchOptions {
+        include_archived: true,
+        ..SearchOptions::default()
+    });
+    with_archived.sort();
+    assert_eq!(with_archived, vec!["current.md", "old.md"]);
+}
+
+#[test]
+fn given_search_and_embed_flags_when_indexing_then_notes_are_kept_out_of_search_or_embedding() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-exclusion");
+    let body = "roadmap milestones ".repeat(20);
+    harness.write_note("public.md", &format!("# Roadmap\n\n{body}\n[[journal]]"));
+    harness.write_note(
+        "journal.md",
+        &format!("---\nsearch: false\n---\n# Roadmap\n\n{body}"),
+    );
+    harness.write_note(
+        "draft.md",
+        &format!("---\nembed: false\n---\n# Roadmap\n\n{body}"),
+    );
+    let profiles = vec![EmbeddingProfile::new("test", "model-b")];
+    harness.run_workspace_index_with_profiles(&profiles);
+
+    let search = |mode| {
+        let mut names = search_notes_for_query_with_options(
+            harness.root(),
+            harness.db_path(),
+            "roadmap",
+            &profiles,
+            SearchOptions {
+                mode,
+                ..SearchOptions::default()
+            },
+        )
+        .expect("search should succeed")
+        .into_iter()
+        .map(|entry| entry.name)
+        .collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+    assert_eq!(search(SearchMode::Hybrid), vec!["draft.md", "public.md"]);
+    assert_eq!(search(SearchMode::Regex), vec!["draft.md", "public.md"]);
+
+    assert!(harness.doc_id("journal.md").is_some(), "doc row is kept");
+    assert_eq!(harness.backlinks("journal.md").len(), 1);
+    assert_eq!(harness.doc_segment_stats("journal.md"), Some((0, 0)));
+    assert_eq!(harness.doc_segment_stats("draft.md"), Some((0, 0)));
+    assert!(harness
+        .doc_segment_stats("public.md")
+        .is_some_and(|(segments, _)| segments > 0));
+
+    harness.write_note("journal.md", &format!("# Roadmap\n\n{body}"));
+    harness.run_workspace_index_with_profiles(&profiles);
+    assert_eq!(
+        search(SearchMode::Hybrid),
+        vec!["draft.md", "journal.md", "public.md"],
+        "dropping the flag brings the note back"
+    );
+}
+
+#[test]
+fn given_regex_query_when_searching_then_notes_are_ranked_by_match_count() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-regex");
+    let filler = "Plain prose about the project schedule. ".repeat(8);
+    harness.write_note(
+        "two.md",
+        &format!("# Tasks\n\nTODO(alice): ship it\nTODO(bob): test it\n\n{filler}"),
+    );
+    harness.write_note(
+        "one.md",
+        &format!("# Tasks\n\nTODO(carol): write docs\n\n{filler}"),
+    );
+    harness.write_note("none.md", &format!("# Tasks\n\nTODO: nobody\n\n{filler}"));
+    harness.run_workspace_index();
+
+    let search = |query: &str, mode| {
+        search_notes_for_query_with_options(
+            harness.root(),
+            harness.db_path(),
+            query,
+            &[],
+            SearchOptions {
+                mode,
+                ..SearchOptions::default()
+            },
+        )
+    };
+
+    let entries = search(r"TODO\(\w+\):", SearchMode::Regex).expect("regex search succeeds");
+    assert_eq!(
+        entries
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.similarity))
+            .collect::<Vec<_>>(),
+        vec![("two.md", 1.0), ("one.md", 0.5)]
+    );
+
+    let prefixed = search(r"re: TODO\(bob\)", SearchMode::Hybrid).expect("prefix selects regex");
+    assert_eq!(
+        prefixed
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["two.md"]
+    );
+
+    assert!(search("re: TODO(", SearchMode::Hybrid).is_err());
+}
+
+#[test]
+fn given_sort_and_grouping_when_searching_then_groups_keep_score_order() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-groups");
+    let filler = "Plain prose about the project schedule. ".repeat(8);
+    harness.write_note(
+        "work/b.md",
+        &format!("---\ntags: [client, urgent]\n---\n# B\n\nTODO TODO TODO\n\n{filler}"),
+    );
+    harness.write_note(
+        "home/a.md",
+        &format!("---\ntags: [urgent]\n---\n# A\n\nTODO TODO\n\n{filler}"),
+    );
+    harness.write_note("work/c.md", &format!("# C\n\nTODO\n\n{filler}"));
+    harness.run_workspace_index();
+
+    let search = |sort| {
+        search_notes_for_query_with_options(
+            harness.root(),
+            harness.db_path(),
+            "TODO",
+            &[],
+            SearchOptions {
+                mode: SearchMode::Regex,
+                sort,
+                ..SearchOptions::default()
+            },
+        )
+        .expect("search should succeed")
+    };
+    let names = |entries: &[SemanticNoteEntry]| {
+        entries
+            .iter()
+            .map(|entry| entry.name.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let by_score = search(SearchSort::Score);
+    assert_eq!(names(&by_score), vec!["b.md", "a.md", "c.md"]);
+    assert_eq!(
+        names(&search(SearchSort::Path)),
+        vec!["a.md", "b.md", "c.md"]
+    );
+
+    let summarize = |groups: Vec<SearchResultGroup>| {
+        groups
+            .into_iter()
+            .map(|group| (group.key, names(&group.entries)))
+            .collect::<Vec<_>>()
+    };
+    let by_folder = group_search_results(
+        harness.root(),
+        harness.db_path(),
+        by_score.clone(),
+        SearchGroupBy::Folder,
+    )
+    .expect("grouping should succeed");
+    assert_eq!(
+        summarize(by_folder),
+        vec![
+            (
+                "work".to_string(),
+                vec!["b.md".to_string(), "c.md".to_string()]
+            ),
+            ("home".to_string(), vec!["a.md".to_string()]),
+        ]
+    );
+
+    let by_tag = group_search_results(
+        harness.root(),
+        harness.db_path(),
+        by_score,
+        SearchGroupBy::Tag,
+    )
+    .expect("grouping should succeed");
+    assert_eq!(
+        summarize(by_tag),
+        vec![
+            ("client".to_string(), vec!["b.md".to_string()]),
+            (
+                "urgent".to_string(),
+                vec!["b.md".to_string(), "a.md".to_string()]
+            ),
+            (String::new(), vec!["c.md".to_string()]),
+        ]
+    );
+}
+
+#[test]
+fn given_mdx_and_uppercase_extensions_when_searching_then_notes_are_returned() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-extensions");
+    let body = format!("# Widgets\n\n{}", "widget assembly notes ".repeat(20));
+    harness.write_note("lower.md", &body);
+    harness.write_note("Upper.MD", &body);
+    harness.write_note("component.mdx", &body);
+    harness.write_note("ignored.txt", &body);
+    harness.run_workspace_index();
+
+    let mut names = search_notes_for_query_with_options(
+        harness.root(),
+        harness.db_path(),
+        "widget",
+        &[],
+        SearchOptions::default(),
+    )
+    .expect("search should succeed")
+    .into_iter()
+    .map(|entry| entry.name)
+    .collect::<Vec<_>>();
+    names.sort();
+
+    assert_eq!(names, vec!["Upper.MD", "component.mdx", "lower.md"]);
+}
+
+#[test]
+fn given_content_cap_when_searching_then_indexed_text_is_inlined_up_to_the_cap() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-content");
+    let body = "Quarterly roadmap planning notes. ".repeat(12);
+    harness.write_note("roadmap.md", &format!("# Roadmap\n\n{body}"));
+    harness.run_workspace_index();
+
+    let search = |content_max_bytes| {
+        search_notes_for_query_with_options(
+            harness.root(),
+            harness.db_path(),
+            "roadmap",
+            &[],
+            SearchOptions {
+                content_max_bytes,
+                ..SearchOptions::default()
+            },
+        )
+        .expect("search should succeed")
+        .remove(0)
+    };
+
+    let without = search(None);
+    assert_eq!(without.content, None);
+
+    let full = search(Some(64 * 1024));
+    let content = full.content.expect("content should be inlined");
+    assert_eq!(Some(content.clone()), harness.doc_content("roadmap.md"));
+    assert!(!full.content_truncated);
+
+    let capped = search(Some(20));
+    assert_eq!(capped.content.as_deref(), Some(&content[..20]));
+    assert!(capped.content_truncated);
+}
+
+#[test]
+fn given_note_with_several_segments_when_searching_segments_then_each_is_ranked_on_its_own() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-segments");
+    let beds = "Tomato beds need compost and water every morning. ".repeat(20);
+    let tools = "The shovel and rake hang by the shed door. ".repeat(20);
+    let contents = format!("# Garden\n\n{beds}\n\n## Tools\n\n{tools}\n");
+    harness.write_note("garden.md", &contents);
+    let profiles = vec![EmbeddingProfile::new("test", "model-b")];
+    harness.run_workspace_index_with_profiles(&profiles);
+
+    let segments = search_segments(harness.root(), harness.db_path(), "shovel", &profiles, 10)
+        .expect("segment search should succeed");
+
+    assert_eq!(segments.len(), 2, "both segments of the note are returned");
+    assert!(segments
+        .iter()
+        .all(|segment| segment.rel_path == "garden.md"));
+    assert!(segments
+        .iter()
+        .all(|segment| segment.vector_score.is_some()));
+    let keyword_matches = segments
+        .iter()
+        .filter(|segment| segment.bm25_score.is_some())
+        .collect::<Vec<_>>();
+    assert_eq!(keyword_matches.len(), 1);
+    let tools_segment = keyword_matches[0];
+    assert_eq!(tools_segment.ordinal, 1);
+    assert_eq!(
+        tools_segment.passage.heading_path,
+        vec!["Garden".to_string(), "Tools".to_string()]
+    );
+    assert!(tools_segment.text.contains("shovel"));
+    assert!(
+        contents[tools_segment.passage.start_offset..tools_segment.passage.end_offset]
+            .starts_with("## Tools")
+    );
+
+    assert!(
+        search_segments(harness.root(), harness.db_path(), "shovel", &profiles, 1)
+            .expect("segment search should succeed")
+            .len()
+            == 1
+    );
+}
+
+#[test]
+fn given_filtered_out_segments_when_searching_segments_then_they_do_not_use_up_the_limit() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-segments-filter");
+    harness.write_note(
+        "private/shed.md",
+        &format!(
+            "# Shed
+
+{}",
+            "The shovel shovel shovel is here. ".repeat(20)
+        ),
+    );
+    harness.write_note(
+        "garden.md",
+        &format!(
+            "# Garden
+
+{}",
+            "Borrow the shovel for the beds. ".repeat(20)
+        ),
+    );
+    let profiles = vec![EmbeddingProfile::new("test", "model-b")];
+    harness.run_workspace_index_with_profiles(&profiles);
+
+    let segments = search_segments_where(
+        harness.root(),
+        harness.db_path(),
+        "shovel",
+        &profiles,
+        1,
+        |segment| !segment.rel_path.starts_with("private/"),
+    )
+    .expect("segment search should succeed");
+
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].rel_path, "garden.md");
+}
+
+#[test]
+fn given_tokenizer_setting_when_indexing_then_keyword_search_uses_it_until_changed_back() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-tokenizer");
+    harness.write_note(
+        "garden.md",
+        &format!("# Garden\n\n{}", "Gardens need compost. ".repeat(20)),
+    );
+    harness.run_workspace_index();
+    let search = |query: &str| {
+        search_notes_for_query(harness.root(), harness.db_path(), query, "", "")
+            .expect("search should succeed")
+            .len()
+    };
+    let set_tokenizer = |value: serde_json::Value| {
+        app_storage::vault_settings::set_vault_setting(
+            harness.db_path(),
+            harness.root(),
+            "searchTokenizer",
+            &value,
+        )
+        .expect("tokenizer setting should save");
+    };
+    assert_eq!(search("ompos"), 0);
+    assert_eq!(search("gardening"), 0);
+
+    set_tokenizer(serde_json::json!({ "tokenizer": "trigram" }));
+    harness.run_workspace_index();
+    assert_eq!(search("ompos"), 1, "trigram matches inside words");
+
+    set_tokenizer(serde_json::json!({ "tokenizer": "porter" }));
+    assert!(apply_fts_tokenizer(harness.root(), harness.db_path()).expect("tokenizer should apply"));
+    assert!(!apply_fts_tokenizer(harness.root(), harness.db_path())
+        .expect("unchanged tokenizer should be left alone"));
+    assert_eq!(search("ompos"), 0);
+    assert_eq!(search("gardening"), 1, "porter matches word stems");
+
+    harness.write_note(
+        "beds.md",
+        &format!("# Beds\n\n{}", "Raised beds. ".repeat(30)),
+    );
+    harness.run_workspace_index();
+    assert_eq!(search("bed"), 1, "new notes reach the vault's own table");
+
+    set_tokenizer(serde_json::json!({ "tokenizer": "unicode61", "tokenChars": "" }));
+    harness.run_workspace_index();
+    assert_eq!(search("gardening"), 0);
+    assert_eq!(search("compost"), 1);
+}
+
+#[test]
+fn given_synonyms_and_stopwords_when_searching_then_query_is_expanded_and_trimmed() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-vocabulary");
+    harness.write_note(
+        "cluster.md",
+        &format!(
+            "# Cluster\n\n{}",
+            "Kubernetes runs the cluster. ".repeat(20)
+        ),
+    );
+    harness.run_workspace_index();
+    let search = |query: &str| {
+        search_notes_for_query(harness.root(), harness.db_path(), query, "", "")
+            .expect("search should succeed")
+            .len()
+    };
+    assert_eq!(search("k8s"), 0);
+    assert_eq!(search("kubernetes a cluster"), 0);
+
+    app_storage::search_vocabulary::add_search_synonym(
+        harness.db_path(),
+        harness.root(),
+        "k8s",
+        "kubernetes",
+    )
+    .expect("synonym should save");
+    app_storage::search_vocabulary::add_search_stopword(harness.db_path(), harness.root(), "a")
+        .expect("stopword should save");
+
+    assert_eq!(search("k8s"), 1, "synonym stands in for the indexed term");
+    assert_eq!(search("kubernetes a cluster"), 1, "stopword is dropped");
+}
+
+#[test]
+fn given_templated_notes_when_collapsing_similar_then_near_duplicates_fold_into_one_result() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-collapse");
+    // Same length and one "roadmap" each, so all three rank alike.
+    let agenda = |item: &str| {
+        let items = (1..=120)
+            .map(|index| format!("{item}{index}"))
+            .collect::<Vec<_>>();
+        format!("Agenda: roadmap {}.", items.join(" "))
+    };
+    let template = agenda("point");
+    let other = agenda("topic");
+    harness.write_note(
+        "sync-a.md",
+        &format!("# Sync\n\nAttendees: Ana, Ben.\n\n{template}"),
+    );
+    harness.write_note(
+        "sync-b.md",
+        &format!("# Sync\n\nAttendees: Ana, Cleo.\n\n{template}"),
+    );
+    harness.write_note(
+        "sync-c.md",
+        &format!("# Sync\n\nAttendees: Dan, Eve.\n\n{other}"),
+    );
+    harness.run_workspace_index();
+
+    let search = |collapse_similar| {
+        search_notes_for_query_with_options(
+            harness.root(),
+            harness.db_path(),
+            "roadmap",
+            &[],
+            SearchOptions {
+                collapse_similar,
+                ..SearchOptions::default()
+            },
+        )
+        .expect("search should succeed")
+    };
+    let name = |entry: &SemanticNoteEntry| entry.name.clone();
+
+    let all = search(false);
+    assert_eq!(all.len(), 3);
+    assert!(all.iter().all(|entry| entry.similar.is_empty()));
+
+    let collapsed = search(true);
+    assert_eq!(collapsed.len(), 2);
+    let template_note = collapsed
+        .iter()
+        .find(|entry| entry.name != "sync-c.md")
+        .expect("one templated note stays in the results");
+    let mut members = vec![name(template_note)];
+    members.extend(template_note.similar.iter().map(name));
+    members.sort();
+    assert_eq!(members, vec!["sync-a.md", "sync-b.md"]);
+}
+
+#[test]
+fn given_misspelled_query_when_nothing_matches_then_search_is_retried_with_a_correction() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-search-correction");
+    harness.write_note(
+        "garden.md",
+        &format!("# Garden\n\n{}", "Tomato beds need compost. ".repeat(20)),
+    );
+    harness.run_workspace_index();
+    let search = |query: &str| {
+        search_notes_with_correction(
+            harness.root(),
+            harness.db_path(),
+            query,
+            &[],
+            SearchOptions::default(),
+        )
+        .expect("search should succeed")
+    };
+
+    let typo = search("tomatoe bdes");
+    assert_eq!(typo.did_you_mean.as_deref(), Some("tomato beds"));
+    assert_eq!(typo.entries.len(), 1);
+
+    let exact = search("compost");
+    assert_eq!(exact.did_you_mean, None);
+    assert_eq!(exact.entries.len(), 1);
+
+    let unknown = search("zeppelin");
+    assert_eq!(unknown.did_you_mean, None);
+    assert!(unknown.entries.is_empty());
+}
@@ -1,3 +1,4 @@
+use super::super::{get_link_counts, EmbeddingProfile};
 use super::test_support::IndexingHarness;
 
 #[test]
@@ -153,6 +154,29 @@ fn given_embedding_model_drift_when_reindexing_with_embeddings_then_doc_rows_and
     assert_eq!(harness.link_targets_for("source.md"), vec!["target.md"]);
 }
 
+#[test]
+fn given_chunking_version_drift_when_reindexing_with_embeddings_then_unchanged_chunks_reuse_cached_vectors(
+) {
+    let harness = IndexingHarness::new("mdit-vault-indexing-sync-embedding-cache");
+    harness.write_note("note.md", "# Note\n\nBody text.\n");
+
+    let first = harness.run_workspace_index_with_embeddings("test", "model-a");
+    assert!(first.embeddings_written > 0);
+    assert_eq!(first.embeddings_cached, 0);
+
+    harness.set_doc_chunking_version("note.md", 0);
+    let rebuilt = harness.run_workspace_index_with_embeddings("test", "model-a");
+    assert_eq!(rebuilt.embeddings_written, first.embeddings_written);
+    assert_eq!(rebuilt.embeddings_cached, first.embeddings_written);
+
+    app_storage::embedding_cache::set_embedding_cache_max_entries(harness.db_path(), 0)
+        .expect("cache limit should be saved");
+    harness.set_doc_chunking_version("note.md", 0);
+    let uncached = harness.run_workspace_index_with_embeddings("test", "model-a");
+    assert_eq!(uncached.embeddings_written, first.embeddings_written);
+    assert_eq!(uncached.embeddings_cached, 0);
+}
+
 #[test]
 fn given_note_reindexed_without_embeddings_then_document_state_updates_without_touching_vectors() {
     let harness = IndexingHarness::new("mdit-vault-indexing-sync-note-document-only");
@@ -378,3 +402,256 @@ fn given_source_doc_deleted_then_wiki_link_ref_rows_are_cascaded() {
     assert_eq!(summary.docs_deleted, 1);
     assert!(harness.wiki_ref_keys_for("source.md").is_empty());
 }
+
+#[test]
+fn given_unreachable_embedding_provider_when_indexing_then_text_index_completes_and_docs_are_queued(
+) {
+    let harness = IndexingHarness::new("mdit-vault-indexing-sync-offline-provider");
+    harness.write_note("a.md", "# Alpha\n\nOffline body sentence. [[b]]");
+    harness.write_note("b.md", "# Beta\n\nTarget note.");
+
+    let summary =
+        harness.run_workspace_index_with_profiles(&[EmbeddingProfile::new("offline", "model-a")]);
+
+    assert_eq!(summary.files_processed, 2);
+    assert_eq!(summary.embeddings_written, 0);
+    assert_eq!(summary.embeddings_pending, 2);
+    assert!(harness
+        .doc_content("a.md")
+        .expect("indexed doc content should exist")
+        .contains("Offline body sentence."));
+    assert_eq!(harness.link_targets_for("a.md"), vec!["b.md"]);
+    assert_eq!(harness.pending_embedding_count(), 2);
+}
+
+#[test]
+fn given_queued_docs_when_provider_recovers_then_draining_writes_embeddings_and_clears_queue() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-sync-drain-pending");
+    harness.write_note("a.md", "# Alpha\n\nQueued body sentence.");
+    harness.run_workspace_index_with_profiles(&[EmbeddingProfile::new("offline", "model-a")]);
+
+    let still_offline =
+        harness.drain_pending_embeddings(&[EmbeddingProfile::new("offline", "model-a")]);
+    assert!(still_offline.is_err());
+    assert_eq!(harness.pending_embedding_count(), 1);
+
+    let summary = harness
+        .drain_pending_embeddings(&[EmbeddingProfile::new("test", "model-a")])
+        .expect("draining with a reachable provider should succeed");
+
+    assert!(summary.embeddings_written > 0);
+    assert_eq!(summary.embeddings_pending, 0);
+    assert_eq!(harness.pending_embedding_count(), 0);
+    assert_eq!(
+        harness.doc_embedding_metadata("a.md"),
+        Some((Some("model-a".to_string()), Some(3)))
+    );
+}
+
+#[test]
+fn given_wiki_link_to_alias_when_alias_is_added_and_removed_then_source_link_follows() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-sync-alias-dependency");
+    harness.write_note("source.md", "[[Moonshot]]\n");
+    harness.write_note(
+        "projects/apollo.md",
+        "---\naliases: [Moonshot]\n---\n# Apollo\n",
+    );
+
+    harness.run_workspace_index();
+    assert_eq!(
+        harness.link_rows_for("source.md"),
+        vec![(
+            "projects/apollo.md".to_string(),
+            harness.doc_id("projects/apollo.md")
+        )]
+    );
+
+    harness.write_note("projects/apollo.md", "# Apollo\n");
+    harness.run_workspace_index();
+    assert_eq!(
+        harness.link_rows_for("source.md"),
+        vec![("Moonshot.md".to_string(), None)]
+    );
+}
+
+#[test]
+fn given_deleted_note_when_restored_then_doc_id_and_backlinks_come_back() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-sync-tombstone-restore");
+    harness.write_note("source.md", "[Go](target.md)\n");
+    harness.write_note("target.md", "# Target\n");
+    harness.run_workspace_index();
+    let original_id = harness.doc_id("target.md").expect("target doc id");
+
+    harness.remove_note("target.md");
+    harness.run_workspace_index();
+    assert_eq!(
+        harness.link_rows_for("source.md"),
+        vec![("target.md".to_string(), None)]
+    );
+
+    harness.write_note("target.md", "# Target\n");
+    let summary = harness.run_workspace_index();
+
+    assert_eq!(summary.docs_revived, 1);
+    assert_eq!(harness.doc_id("target.md"), Some(original_id));
+    assert_eq!(
+        harness.link_rows_for("source.md"),
+        vec![("target.md".to_string(), Some(original_id))]
+    );
+}
+
+#[test]
+fn given_deleted_note_when_same_contents_reappear_elsewhere_then_doc_id_is_revived() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-sync-tombstone-moved");
+    harness.write_note("draft.md", "# Contract\n\nSigned terms.\n");
+    harness.run_workspace_index();
+    let original_id = harness.doc_id("draft.md").expect("draft doc id");
+
+    harness.remove_note("draft.md");
+    harness.write_note("restored/draft.md", "# Contract\n\nSigned terms.\n");
+    let summary = harness.run_workspace_index();
+
+    assert_eq!(summary.docs_revived, 1);
+    assert_eq!(harness.doc_id("restored/draft.md"), Some(original_id));
+}
+
+#[test]
+fn given_zero_retention_when_deleted_note_returns_then_it_gets_a_new_doc_id() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-sync-tombstone-disabled");
+    harness.write_note("note.md", "# Note\n");
+    harness.run_workspace_index();
+    let original_id = harness.doc_id("note.md").expect("note doc id");
+    app_storage::vault_settings::set_vault_setting(
+        harness.db_path(),
+        harness.root(),
+        "deletedNoteRetentionDays",
+        &serde_json::json!(0),
+    )
+    .expect("disable tombstones");
+
+    harness.remove_note("note.md");
+    harness.run_workspace_index();
+    harness.write_note("note.md", "# Note\n");
+    let summary = harness.run_workspace_index();
+
+    assert_eq!(summary.docs_revived, 0);
+    assert_ne!(harness.doc_id("note.md"), Some(original_id));
+}
+
+#[test]
+fn given_only_edited_notes_when_reindexing_workspace_then_untouched_notes_count_as_processed() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-sync-warm-start");
+    harness.write_note("a.md", "[[b]]\n");
+    harness.write_note("b.md", "# B\n");
+    harness.write_note("c.md", "# C\n\nfirst draft");
+    harness.run_workspace_index();
+
+    harness.write_note("c.md", "# C\n\nsecond draft, a little longer");
+    let summary = harness.run_workspace_index();
+
+    assert_eq!(summary.files_discovered, 3);
+    assert_eq!(summary.files_processed, 3);
+    assert_eq!(summary.docs_deleted, 0);
+    assert_eq!(summary.links_written, 0);
+    assert!(harness
+        .doc_content("c.md")
+        .expect("c.md content")
+        .contains("second draft"));
+    assert_eq!(harness.link_targets_for("a.md"), vec!["b.md"]);
+
+    let unchanged = harness.run_workspace_index();
+    assert_eq!(unchanged.files_discovered, 3);
+    assert_eq!(unchanged.files_processed, 3);
+    assert_eq!(unchanged.segments_created, 0);
+    assert_eq!(unchanged.segments_updated, 0);
+    assert!(unchanged.skipped_files.is_empty());
+}
+
+#[test]
+fn given_note_over_the_size_cap_when_reindexing_workspace_then_only_its_head_is_indexed() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-sync-size-cap");
+    app_storage::vault_settings::set_vault_setting(
+        harness.db_path(),
+        harness.root(),
+        "maxIndexedFileMegabytes",
+        &serde_json::json!(1),
+    )
+    .expect("set size cap");
+    let log = format!(
+        "# Build log\n\nheadmarker\n{}tailmarker\n",
+        "compiling crate 0123456789\n".repeat(60_000)
+    );
+    harness.write_note("build.md", &log);
+    harness.write_note("small.md", "# Small\n");
+
+    let summary = harness.run_workspace_index();
+
+    assert_eq!(summary.truncated_files, vec!["build.md"]);
+    assert!(summary.skipped_files.is_empty());
+    let content = harness.doc_content("build.md").expect("build.md content");
+    assert!(content.contains("headmarker"));
+    assert!(!content.contains("tailmarker"));
+    assert_eq!(
+        harness.doc_hash("build.md"),
+        Some(blake3::hash(log.as_bytes()).to_hex().to_string())
+    );
+}
+
+#[test]
+fn given_links_changing_when_reindexing_workspace_then_inbound_link_counts_follow() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-sync-link-counts");
+    harness.write_note("a.md", "[[target]] and [[target.md]]\n");
+    harness.write_note("b.md", "[[target]] and [[later]]\n");
+    harness.write_note("target.md", "# Target\n");
+    harness.run_workspace_index();
+
+    let counts = |paths: &[&str]| {
+        let paths = paths
+            .iter()
+            .map(|path| path.to_string())
+            .collect::<Vec<_>>();
+        let mut counts = get_link_counts(harness.root(), harness.db_path(), &paths)
+            .expect("link counts should load")
+            .into_iter()
+            .collect::<Vec<_>>();
+        counts.sort();
+        counts
+    };
+    assert_eq!(
+        counts(&["a.md", "target.md", "missing.md"]),
+        vec![("a.md".to_string(), 0), ("target.md".to_string(), 2)]
+    );
+
+    harness.write_note("later.md", "# Later\n");
+    harness.remove_note("a.md");
+    harness.run_workspace_index();
+    assert_eq!(
+        counts(&["later.md", "target.md"]),
+        vec![("later.md".to_string(), 1), ("target.md".to_string(), 1)]
+    );
+
+    harness.write_note("b.md", "No links left\n");
+    harness.run_workspace_index();
+    assert_eq!(
+        counts(&["later.md", "target.md"]),
+        vec![("later.md".to_string(), 0), ("target.md".to_string(), 0)]
+    );
+}
+
+#[test]
+fn given_decomposed_path_when_reading_link_counts_then_it_matches_the_nfc_key() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-sync-link-counts-nfc");
+    harness.write_note("caf\u{e9}.md", "# Caf\u{e9}\n");
+    harness.write_note("menu.md", "[[caf\u{e9}]]\n");
+    harness.run_workspace_index();
+
+    let decomposed = "cafe\u{301}.md".to_string();
+    let counts = get_link_counts(
+        harness.root(),
+        harness.db_path(),
+        std::slice::from_ref(&decomposed),
+    )
+    .expect("link counts should load");
+
+    assert_eq!(counts.get(&decomposed), Some(&1));
+}
@@ -1,6 +1,6 @@
 use std::{thread, time::Duration};
 
-use super::super::{delete_indexed_note, rename_indexed_note};
+use super::super::{delete_indexed_note, get_note_tags, rename_indexed_note};
 use super::test_support::IndexingHarness;
 
 #[test]
@@ -210,3 +210,19 @@ fn given_embedding_only_refresh_when_model_changes_then_doc_tags_are_preserved()
     );
     assert!(harness.doc_tag_audit_events().is_empty());
 }
+
+#[test]
+fn given_listed_paths_when_loading_note_tags_then_only_tagged_indexed_notes_are_returned() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-tags-by-path");
+    harness.write_note("a.md", "Body #Work and #home");
+    harness.write_note("b.md", "No tags here");
+
+    harness.run_workspace_index();
+
+    let rel_paths = ["a.md", "b.md", "missing.md"].map(str::to_string);
+    let tags = get_note_tags(harness.root(), harness.db_path(), &rel_paths)
+        .expect("note tag lookup should succeed");
+
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags["a.md"], vec!["home".to_string(), "Work".to_string()]);
+}
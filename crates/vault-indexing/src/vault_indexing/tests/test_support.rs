@@ -6,8 +6,9 @@ use rusqlite::{params, Connection, OptionalExtension};
 use app_storage::migrations;
 
 use super::super::{
-    find_vault_id, get_backlinks, get_indexing_meta, index_note, index_vault_documents,
-    refresh_workspace_embeddings, search_notes_by_tag, BacklinkEntry, IndexSummary, IndexingMeta,
+    drain_pending_embeddings, find_vault_id, get_backlinks, get_indexing_meta, index_note,
+    index_vault_documents, index_vault_documents_with_profiles, refresh_workspace_embeddings,
+    search_notes_by_tag, BacklinkEntry, EmbeddingProfile, IndexSummary, IndexingMeta,
 };
 
 pub(super) struct IndexingHarness {
@@ -76,6 +77,35 @@ impl IndexingHarness {
         .expect("workspace indexing with embeddings should succeed")
     }
 
+    pub(super) fn run_workspace_index_with_profiles(
+        &self,
+        embedding_profiles: &[EmbeddingProfile],
+    ) -> IndexSummary {
+        index_vault_documents_with_profiles(&self.root, &self.db_path, embedding_profiles, false)
+            .expect("workspace indexing with embedding profiles should succeed")
+    }
+
+    pub(super) fn drain_pending_embeddings(
+        &self,
+        embedding_profiles: &[EmbeddingProfile],
+    ) -> Result<IndexSummary> {
+        drain_pending_embeddings(&self.root, &self.db_path, embedding_profiles)
+    }
+
+    pub(super) fn pending_embedding_count(&self) -> usize {
+        let Some((conn, vault_id)) = self.open_vault_connection() else {
+            return 0;
+        };
+        conn.query_row(
+            "SELECT COUNT(*) FROM pending_embedding pe \
+             JOIN doc d ON d.id = pe.doc_id \
+             WHERE d.vault_id = ?1",
+            params![vault_id],
+            |row| row.get::<_, i64>(0),
+        )
+        .expect("failed to count pending embeddings") as usize
+    }
+
     pub(super) fn refresh_workspace_embeddings(
         &self,
         embedding_provider: &str,
@@ -299,6 +329,14 @@ impl IndexingHarness {
         self.doc_queries().embedding_metadata(rel_path)
     }
 
+    pub(super) fn doc_embedding_provider(&self, rel_path: &str) -> Option<String> {
+        self.doc_queries().embedding_provider(rel_path)
+    }
+
+    pub(super) fn doc_language(&self, rel_path: &str) -> Option<String> {
+        self.doc_queries().language(rel_path)
+    }
+
     pub(super) fn doc_segment_stats(&self, rel_path: &str) -> Option<(usize, usize)> {
         self.doc_queries().segment_stats(rel_path)
     }
@@ -408,6 +446,30 @@ impl DocQueries<'_> {
         .expect("failed to query doc embedding metadata")
     }
 
+    fn embedding_provider(&self, rel_path: &str) -> Option<String> {
+        let (conn, vault_id) = self.harness.open_vault_connection()?;
+        conn.query_row(
+            "SELECT last_embedding_provider FROM doc WHERE vault_id = ?1 AND rel_path = ?2",
+            params![vault_id, rel_path],
+            |row| row.get::<_, Option<String>>(0),
+        )
+        .optional()
+        .expect("failed to query doc embedding provider")
+        .flatten()
+    }
+
+    fn language(&self, rel_path: &str) -> Option<String> {
+        let (conn, vault_id) = self.harness.open_vault_connection()?;
+        conn.query_row(
+            "SELECT language FROM doc WHERE vault_id = ?1 AND rel_path = ?2",
+            params![vault_id, rel_path],
+            |row| row.get::<_, Option<String>>(0),
+        )
+        .optional()
+        .expect("failed to query doc language")
+        .flatten()
+    }
+
     fn segment_stats(&self, rel_path: &str) -> Option<(usize, usize)> {
         let (conn, vault_id) = self.harness.open_vault_connection()?;
         conn.query_row(
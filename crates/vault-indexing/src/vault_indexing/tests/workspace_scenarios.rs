@@ -1,3 +1,7 @@
+use super::super::{
+    compare_vaults, copy_vault_files, find_vault_id, index_vault_documents_with_profiles,
+    open_indexing_connection, VaultFileStatus,
+};
 use super::test_support::IndexingHarness;
 
 #[test]
@@ -60,3 +64,135 @@ fn given_unresolved_wiki_link_when_target_note_is_added_then_links_are_refreshed
     assert_eq!(summary.links_written, 1);
     assert_eq!(harness.link_targets_for("a.md"), vec!["new-doc.md"]);
 }
+
+#[test]
+fn given_notes_in_different_scripts_when_indexing_then_doc_language_is_recorded() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-workspace-language");
+    harness.write_note("ja.md", "# メモ\n\n今日は会議の議事録をまとめました。\n");
+    harness.write_note("zh.md", "# 笔记\n\n今天我们整理了会议记录。\n");
+    harness.write_note("en.md", "# Notes\n\nMeeting minutes for today.\n");
+
+    harness.run_workspace_index();
+
+    assert_eq!(harness.doc_language("ja.md").as_deref(), Some("ja"));
+    assert_eq!(harness.doc_language("zh.md").as_deref(), Some("zh"));
+    assert_eq!(harness.doc_language("en.md").as_deref(), Some("und"));
+}
+
+#[test]
+fn given_excluded_keys_and_data_uris_when_indexing_then_indexed_content_drops_them() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-workspace-noise");
+    app_storage::vault::set_excluded_frontmatter_keys(
+        harness.db_path(),
+        harness.root(),
+        &["cssclasses".to_string()],
+    )
+    .expect("failed to save excluded frontmatter keys");
+    harness.write_note(
+        "a.md",
+        "---\ncssclasses: wide-page\nstatus: draft\n---\n# Chart\n\n\
+         ![plot](data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAAB) Quarterly revenue\n",
+    );
+
+    harness.run_workspace_index();
+
+    let content = harness
+        .doc_content("a.md")
+        .expect("doc content should exist");
+    assert!(content.contains("draft"));
+    assert!(content.contains("Quarterly revenue"));
+    assert!(!content.contains("wide-page"));
+    assert!(!content.contains("base64"));
+}
+
+#[test]
+fn given_forked_vault_copies_when_comparing_and_copying_then_files_are_classified_and_reindexed() {
+    let harness = IndexingHarness::new("mdit-vault-indexing-workspace-compare");
+    let laptop = harness.root().join("laptop");
+    let desktop = harness.root().join("desktop");
+    for (vault, changed, only) in [
+        (&laptop, "# Draft A\n", "laptop-only.md"),
+        (&desktop, "# Draft B\n", "assets/desktop-only.png"),
+    ] {
+        std::fs::create_dir_all(vault.join("assets")).expect("create vault");
+        std::fs::write(vault.join("same.md"), "# Same\n").expect("write note");
+        std::fs::write(vault.join("changed.md"), changed).expect("write note");
+        std::fs::write(vault.join(only), "only here\n").expect("write file");
+        index_vault_documents_with_profiles(vault, harness.db_path(), &[], false)
+            .expect("index vault");
+    }
+
+    let statuses = |comparison: super::super::VaultComparison| {
+        comparison
+            .files
+            .into_iter()
+            .map(|file| (file.rel_path, file.status))
+            .collect::<Vec<_>>()
+    };
+    let comparison = compare_vaults(harness.db_path(), &laptop, &desktop).expect("compare");
+    assert_eq!(
+        (
+            comparison.identical,
+            comparison.changed,
+            comparison.only_in_a,
+            comparison.only_in_b
+        ),
+        (1, 1, 1, 1)
+    );
+    assert_eq!(
+        statuses(comparison),
+        vec![
+            (
+                "assets/desktop-only.png".to_string(),
+                VaultFileStatus::OnlyInB
+            ),
+            ("changed.md".to_string(), VaultFileStatus::Changed),
+            ("laptop-only.md".to_string(), VaultFileStatus::OnlyInA),
+            ("same.md".to_string(), VaultFileStatus::Identical),
+        ]
+    );
+
+    let report = copy_vault_files(
+        harness.db_path(),
+        &laptop,
+        &desktop,
+        &["changed.md".to_string(), "laptop-only.md".to_string()],
+        &[],
+    )
+    .expect("copy files");
+    assert_eq!(report.copied, vec!["changed.md", "laptop-only.md"]);
+    assert_eq!(report.indexed, 2);
+
+    let comparison = compare_vaults(harness.db_path(), &laptop, &desktop).expect("compare");
+    assert_eq!(
+        (
+            comparison.identical,
+            comparison.changed,
+            comparison.only_in_a,
+            comparison.only_in_b
+        ),
+        (3, 0, 0, 1)
+    );
+    let conn = open_indexing_connection(harness.db_path()).expect("open db");
+    let desktop_id = find_vault_id(&conn, &desktop)
+        .expect("find vault")
+        .expect("desktop vault");
+    let indexed: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM doc WHERE vault_id = ?1 AND rel_path = 'laptop-only.md' \
+             AND last_hash IS NOT NULL",
+            [desktop_id],
+            |row| row.get(0),
+        )
+        .expect("count docs");
+    assert_eq!(indexed, 1);
+
+    assert!(copy_vault_files(
+        harness.db_path(),
+        &laptop,
+        &desktop,
+        &["../escape.md".to_string()],
+        &[],
+    )
+    .is_err());
+}
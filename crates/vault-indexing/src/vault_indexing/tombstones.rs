@@ -0,0 +1,124 @@
+//! Deleted `doc` rows leave a tombstone behind for the vault's
+//! `deletedNoteRetentionDays`, so a note restored from the trash, at its old
+//! path or elsewhere with the same contents, takes back its doc id and the
+//! links that pointed at it.
+
+use anyhow::{Context, Result};
+use app_storage::vault_settings::{load_vault_setting, VaultSettingKey};
+use rusqlite::{params, Connection};
+
+#[derive(Debug, Clone)]
+pub(super) struct Tombstone {
+    doc_id: i64,
+    rel_path: String,
+    last_hash: Option<String>,
+}
+
+/// Deletes `doc_ids`, burying each first unless the vault keeps no
+/// tombstones. Returns how many rows were deleted.
+pub(super) fn delete_docs(conn: &Connection, vault_id: i64, doc_ids: &[i64]) -> Result<usize> {
+    let keep_tombstones = retention_days(conn, vault_id)? > 0;
+    let mut deleted = 0;
+    for doc_id in doc_ids {
+        if keep_tombstones {
+            bury_doc(conn, *doc_id)?;
+        }
+        deleted += conn
+            .execute("DELETE FROM doc WHERE id = ?1", params![doc_id])
+            .with_context(|| format!("Failed to delete doc {doc_id}"))?;
+    }
+    Ok(deleted)
+}
+
+/// Tombstones of the vault still within the retention window, newest first.
+/// Expired ones are dropped on the way.
+pub(super) fn load_tombstones(conn: &Connection, vault_id: i64) -> Result<Vec<Tombstone>> {
+    let retention_days = retention_days(conn, vault_id)?;
+    conn.execute(
+        "DELETE FROM doc_tombstone
+         WHERE vault_id = ?1
+           AND deleted_at < strftime('%Y-%m-%dT%H:%M:%fZ', 'now', ?2)",
+        params![vault_id, format!("-{retention_days} days")],
+    )
+    .context("Failed to prune expired doc tombstones")?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT doc_id, rel_path, last_hash FROM doc_tombstone
+             WHERE vault_id = ?1
+             ORDER BY deleted_at DESC, doc_id DESC",
+        )
+        .context("Failed to prepare doc tombstone query")?;
+    let tombstones = stmt
+        .query_map(params![vault_id], |row| {
+            Ok(Tombstone {
+                doc_id: row.get(0)?,
+                rel_path: row.get(1)?,
+                last_hash: row.get(2)?,
+            })
+        })
+        .context("Failed to load doc tombstones")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read doc tombstone rows")?;
+    Ok(tombstones)
+}
+
+/// Takes the tombstone a new note at `rel_path` revives: the newest one at
+/// the same path, else one with the note's content hash. `content_hash` is
+/// only computed when no path matches.
+pub(super) fn take_tombstone(
+    tombstones: &mut Vec<Tombstone>,
+    rel_path: &str,
+    content_hash: impl FnOnce() -> Option<String>,
+) -> Option<i64> {
+    let position = tombstones
+        .iter()
+        .position(|tombstone| tombstone.rel_path == rel_path)
+        .or_else(|| {
+            let content_hash = content_hash()?;
+            tombstones
+                .iter()
+                .position(|tombstone| tombstone.last_hash.as_deref() == Some(&content_hash))
+        })?;
+    Some(tombstones.remove(position).doc_id)
+}
+
+/// Rebinds the links that pointed at the revived doc before it was deleted
+/// and drops its tombstone. The doc row must already be back under its id.
+pub(super) fn revive_doc(conn: &Connection, doc_id: i64) -> Result<()> {
+    conn.execute(
+        "UPDATE link SET target_doc_id = ?1
+         WHERE target_doc_id IS NULL
+           AND id IN (SELECT link_id FROM doc_tombstone_link WHERE doc_id = ?1)",
+        params![doc_id],
+    )
+    .with_context(|| format!("Failed to rebind links of revived doc {doc_id}"))?;
+    conn.execute(
+        "DELETE FROM doc_tombstone WHERE doc_id = ?1",
+        params![doc_id],
+    )
+    .with_context(|| format!("Failed to drop tombstone of revived doc {doc_id}"))?;
+    Ok(())
+}
+
+fn bury_doc(conn: &Connection, doc_id: i64) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO doc_tombstone (doc_id, vault_id, rel_path, last_hash)
+         SELECT id, vault_id, rel_path, last_hash FROM doc WHERE id = ?1",
+        params![doc_id],
+    )
+    .with_context(|| format!("Failed to record tombstone of doc {doc_id}"))?;
+    // The doc's own links go with it, so only other notes' links are kept.
+    conn.execute(
+        "INSERT OR IGNORE INTO doc_tombstone_link (doc_id, link_id)
+         SELECT ?1, id FROM link WHERE target_doc_id = ?1 AND source_doc_id <> ?1",
+        params![doc_id],
+    )
+    .with_context(|| format!("Failed to record links of doc {doc_id}"))?;
+    Ok(())
+}
+
+fn retention_days(conn: &Connection, vault_id: i64) -> Result<i64> {
+    let setting = load_vault_setting(conn, vault_id, VaultSettingKey::DeletedNoteRetentionDays)?;
+    Ok(setting.value.as_i64().unwrap_or_default())
+}
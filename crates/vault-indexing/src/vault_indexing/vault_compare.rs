@@ -0,0 +1,247 @@
+//! Side-by-side comparison of two copies of a vault, such as a laptop and a
+//! desktop copy that drifted apart after a failed sync, and copying chosen
+//! files from one to the other.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::params;
+use serde::Serialize;
+
+use super::{
+    chunking::hash_reader,
+    files::{collect_workspace_entries, is_hidden_component, SourceFileStat},
+    find_vault_id, index_note_with_profiles, open_indexing_connection, EmbeddingProfile,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VaultFileStatus {
+    Identical,
+    Changed,
+    OnlyInA,
+    OnlyInB,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultFileComparison {
+    pub rel_path: String,
+    pub status: VaultFileStatus,
+    /// Modification times, to tell which side of a changed file is newer.
+    pub modified_ns_a: Option<i64>,
+    pub modified_ns_b: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultComparison {
+    /// Every visible file of either vault, by relative path.
+    pub files: Vec<VaultFileComparison>,
+    pub identical: usize,
+    pub changed: usize,
+    pub only_in_a: usize,
+    pub only_in_b: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultCopyReport {
+    /// Relative paths written in the target vault.
+    pub copied: Vec<String>,
+    /// Copied notes and drawings indexed in the target vault.
+    pub indexed: usize,
+}
+
+/// Hash the index recorded for each note, keyed by relative path, with the
+/// size and modification time the file had then.
+type IndexHashes = HashMap<String, (Option<i64>, Option<i64>, String)>;
+
+/// A file found while walking one of the vaults.
+struct ComparedFile {
+    abs_path: PathBuf,
+    stat: SourceFileStat,
+}
+
+/// Compares the visible files of two vaults by relative path and content.
+/// Files of equal size are hashed, or matched by the hash the index keeps
+/// when the file has not changed since it was indexed.
+pub fn compare_vaults(db_path: &Path, path_a: &Path, path_b: &Path) -> Result<VaultComparison> {
+    let files_a = collect_compared_files(path_a)?;
+    let files_b = collect_compared_files(path_b)?;
+    let hashes_a = load_index_hashes(db_path, path_a)?;
+    let hashes_b = load_index_hashes(db_path, path_b)?;
+
+    let mut rel_paths = files_a.keys().chain(files_b.keys()).collect::<Vec<_>>();
+    rel_paths.sort();
+    rel_paths.dedup();
+
+    let mut comparison = VaultComparison::default();
+    for rel_path in rel_paths {
+        let file_a = files_a.get(rel_path);
+        let file_b = files_b.get(rel_path);
+        let status = match (file_a, file_b) {
+            (Some(file_a), Some(file_b)) => {
+                if file_a.stat.last_source_size != file_b.stat.last_source_size {
+                    VaultFileStatus::Changed
+                } else if content_hash(rel_path, file_a, &hashes_a)?
+                    == content_hash(rel_path, file_b, &hashes_b)?
+                {
+                    VaultFileStatus::Identical
+                } else {
+                    VaultFileStatus::Changed
+                }
+            }
+            (Some(_), None) => VaultFileStatus::OnlyInA,
+            (None, _) => VaultFileStatus::OnlyInB,
+        };
+        match status {
+            VaultFileStatus::Identical => comparison.identical += 1,
+            VaultFileStatus::Changed => comparison.changed += 1,
+            VaultFileStatus::OnlyInA => comparison.only_in_a += 1,
+            VaultFileStatus::OnlyInB => comparison.only_in_b += 1,
+        }
+        comparison.files.push(VaultFileComparison {
+            rel_path: rel_path.clone(),
+            status,
+            modified_ns_a: file_a.and_then(|file| file.stat.last_source_mtime_ns),
+            modified_ns_b: file_b.and_then(|file| file.stat.last_source_mtime_ns),
+        });
+    }
+
+    Ok(comparison)
+}
+
+/// Copies `rel_paths` from `source_root` to `target_root`, replacing files
+/// already there, and indexes the copied notes in the target vault.
+pub fn copy_vault_files(
+    db_path: &Path,
+    source_root: &Path,
+    target_root: &Path,
+    rel_paths: &[String],
+    embedding_profiles: &[EmbeddingProfile],
+) -> Result<VaultCopyReport> {
+    let mut report = VaultCopyReport::default();
+    for rel_path in rel_paths {
+        let relative = checked_rel_path(rel_path)?;
+        let source = source_root.join(&relative);
+        let target = target_root.join(&relative);
+
+        let metadata = fs::symlink_metadata(&source)
+            .with_context(|| format!("Failed to read {}", source.display()))?;
+        if !metadata.is_file() {
+            return Err(anyhow!("Not a file: {}", source.display()));
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::copy(&source, &target).with_context(|| {
+            format!(
+                "Failed to copy {} to {}",
+                source.display(),
+                target.display()
+            )
+        })?;
+        report.copied.push(rel_path.clone());
+
+        if note::is_indexed_path(&target) {
+            index_note_with_profiles(target_root, db_path, &target, embedding_profiles)?;
+            report.indexed += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+fn collect_compared_files(workspace_root: &Path) -> Result<HashMap<String, ComparedFile>> {
+    Ok(collect_workspace_entries(workspace_root)?
+        .into_iter()
+        .filter(|entry| !entry.is_dir)
+        .map(|entry| {
+            let stat = SourceFileStat::from_path(&entry.abs_path);
+            let abs_path = entry.abs_path;
+            (entry.rel_path, ComparedFile { abs_path, stat })
+        })
+        .collect())
+}
+
+fn load_index_hashes(db_path: &Path, workspace_root: &Path) -> Result<IndexHashes> {
+    let conn = open_indexing_connection(db_path)?;
+    let Some(vault_id) = find_vault_id(&conn, workspace_root)? else {
+        return Ok(HashMap::new());
+    };
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT rel_path, last_source_size, last_source_mtime_ns, last_hash \
+             FROM doc \
+             WHERE vault_id = ?1 AND last_hash IS NOT NULL",
+        )
+        .context("Failed to prepare index hash query")?;
+    let rows = stmt
+        .query_map(params![vault_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                (row.get(1)?, row.get(2)?, row.get::<_, String>(3)?),
+            ))
+        })
+        .context("Failed to load index hashes")?;
+
+    let mut hashes = HashMap::new();
+    for row in rows {
+        let (rel_path, hash) = row?;
+        hashes.insert(rel_path, hash);
+    }
+    Ok(hashes)
+}
+
+fn content_hash(rel_path: &str, file: &ComparedFile, index_hashes: &IndexHashes) -> Result<String> {
+    // Drawings are indexed by their text, not by the file's bytes.
+    if !note::is_drawing_path(rel_path) {
+        if let Some((size, mtime_ns, hash)) = index_hashes.get(rel_path) {
+            if file.stat.last_source_mtime_ns.is_some()
+                && *size == file.stat.last_source_size
+                && *mtime_ns == file.stat.last_source_mtime_ns
+            {
+                return Ok(hash.clone());
+            }
+        }
+    }
+
+    File::open(&file.abs_path)
+        .and_then(hash_reader)
+        .with_context(|| format!("Failed to hash {}", file.abs_path.display()))
+}
+
+/// `rel_path` as a path below a vault root, refusing absolute paths, parent
+/// segments and hidden entries.
+fn checked_rel_path(rel_path: &str) -> Result<PathBuf> {
+    let path = Path::new(rel_path);
+    let valid = path.components().next().is_some()
+        && path.components().all(|component| {
+            matches!(component, Component::Normal(_)) && !is_hidden_component(component)
+        });
+    if !valid {
+        return Err(anyhow!("Invalid vault-relative path: {rel_path}"));
+    }
+    Ok(path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::checked_rel_path;
+
+    #[test]
+    fn copied_paths_stay_inside_the_vault() {
+        assert!(checked_rel_path("notes/a.md").is_ok());
+        assert!(checked_rel_path("../a.md").is_err());
+        assert!(checked_rel_path("/etc/passwd").is_err());
+        assert!(checked_rel_path(".git/config").is_err());
+        assert!(checked_rel_path("").is_err());
+    }
+}
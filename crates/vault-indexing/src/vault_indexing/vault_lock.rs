@@ -0,0 +1,481 @@
+//! Advisory lock in the vault's `.mdit` folder that keeps two processes, the
+//! desktop app and the CLI or two machines syncing one vault, from writing
+//! its index at the same time.
+//!
+//! The holder rewrites the lock's heartbeat while it works. A lock whose
+//! heartbeat stopped is stale, left by a process that crashed, and is taken
+//! over by the next writer.
+
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub const VAULT_LOCK_PATH: &str = ".mdit/index.lock";
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+/// Several missed heartbeats, so a busy machine is not mistaken for a dead one.
+pub const VAULT_LOCK_STALE_AFTER: Duration = Duration::from_secs(60);
+
+static OWNER_NAME: OnceLock<String> = OnceLock::new();
+// Writers in this process share one lock per vault.
+static HELD_LOCKS: Mutex<Option<HashMap<PathBuf, HeldLock>>> = Mutex::new(None);
+
+/// Who holds a vault's lock, as written to the lock file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultLockInfo {
+    /// Unique per process, so a takeover is noticed by the old holder.
+    pub owner_id: String,
+    /// The program holding the lock, such as the desktop app or the CLI.
+    pub owner: String,
+    pub host: String,
+    pub pid: u32,
+    /// Unix seconds.
+    pub acquired_at: u64,
+    /// Unix seconds of the holder's last sign of life.
+    pub heartbeat_at: u64,
+}
+
+impl VaultLockInfo {
+    fn is_stale(&self, now: u64) -> bool {
+        now.saturating_sub(self.heartbeat_at) > VAULT_LOCK_STALE_AFTER.as_secs()
+    }
+}
+
+/// Returned by index writes while another process holds the vault's lock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultLockedError {
+    pub holder: VaultLockInfo,
+}
+
+impl fmt::Display for VaultLockedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let idle = unix_now().saturating_sub(self.holder.heartbeat_at);
+        write!(
+            f,
+            "Vault index is locked by {} (pid {} on {}, last active {idle}s ago). \
+             Try again when it finishes; the lock is released automatically if it \
+             stops responding for {}s.",
+            self.holder.owner,
+            self.holder.pid,
+            self.holder.host,
+            VAULT_LOCK_STALE_AFTER.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for VaultLockedError {}
+
+/// Names this process in locks it takes, for other writers' error messages.
+/// Set once at startup; later calls are ignored.
+pub fn set_vault_lock_owner(name: &str) {
+    let _ = OWNER_NAME.set(name.to_string());
+}
+
+struct HeldLock {
+    writers: usize,
+    stop: Arc<AtomicBool>,
+    heartbeat: thread::JoinHandle<()>,
+}
+
+/// Held while writing a vault's index; releases the lock when the last
+/// writer in this process drops it.
+#[derive(Debug)]
+pub(crate) struct VaultLockGuard {
+    lock_path: PathBuf,
+}
+
+impl Drop for VaultLockGuard {
+    fn drop(&mut self) {
+        let mut held = HELD_LOCKS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Some(locks) = held.as_mut() else {
+            return;
+        };
+        let Some(lock) = locks.get_mut(&self.lock_path) else {
+            return;
+        };
+        lock.writers -= 1;
+        if lock.writers > 0 {
+            return;
+        }
+
+        if let Some(lock) = locks.remove(&self.lock_path) {
+            lock.stop.store(true, Ordering::Relaxed);
+            lock.heartbeat.thread().unpark();
+            let _ = lock.heartbeat.join();
+        }
+        if read_lock(&self.lock_path).is_some_and(|info| info.owner_id == process_owner_id()) {
+            let _ = fs::remove_file(&self.lock_path);
+        }
+    }
+}
+
+/// Takes the lock of the vault at `workspace_root`, or fails with
+/// [`VaultLockedError`] while a live writer in another process holds it.
+pub(crate) fn acquire_vault_lock(workspace_root: &Path) -> Result<VaultLockGuard> {
+    let lock_path = workspace_root.join(VAULT_LOCK_PATH);
+    let mut held = HELD_LOCKS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let locks = held.get_or_insert_with(HashMap::new);
+
+    if let Some(lock) = locks.get_mut(&lock_path) {
+        lock.writers += 1;
+        return Ok(VaultLockGuard { lock_path });
+    }
+
+    if let Err(error) = create_lock_file(&lock_path) {
+        // A read-only vault can still be indexed, just without protection.
+        let read_only = error
+            .root_cause()
+            .downcast_ref::<io::Error>()
+            .is_some_and(|error| {
+                matches!(
+                    error.kind(),
+                    io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem
+                )
+            });
+        if !read_only {
+            return Err(error);
+        }
+        tracing::warn!("indexing without a vault lock: {error:#}");
+        return Ok(VaultLockGuard { lock_path });
+    }
+    let stop = Arc::new(AtomicBool::new(false));
+    let heartbeat = spawn_heartbeat(lock_path.clone(), Arc::clone(&stop))?;
+    locks.insert(
+        lock_path.clone(),
+        HeldLock {
+            writers: 1,
+            stop,
+            heartbeat,
+        },
+    );
+    Ok(VaultLockGuard { lock_path })
+}
+
+/// The current holder of the vault's lock, if any, stale or not.
+pub fn get_vault_lock(workspace_root: &Path) -> Option<VaultLockInfo> {
+    read_lock(&workspace_root.join(VAULT_LOCK_PATH))
+}
+
+/// The vault's lock when another process holds it and is still alive.
+pub(crate) fn live_foreign_vault_lock(workspace_root: &Path) -> Option<VaultLockInfo> {
+    get_vault_lock(workspace_root)
+        .filter(|info| info.owner_id != process_owner_id() && !info.is_stale(unix_now()))
+}
+
+/// Removes the vault's lock whoever holds it, for a user who knows the
+/// holder is gone without waiting for the lock to go stale. The old holder
+/// stops its heartbeat once it sees the lock is no longer its own.
+pub fn break_vault_lock(workspace_root: &Path) -> Result<Option<VaultLockInfo>> {
+    let lock_path = workspace_root.join(VAULT_LOCK_PATH);
+    let holder = read_lock(&lock_path);
+    match fs::remove_file(&lock_path) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(error) => {
+            return Err(error)
+                .with_context(|| format!("Failed to remove lock {}", lock_path.display()))
+        }
+    }
+    if let Some(holder) = &holder {
+        tracing::warn!(
+            owner = %holder.owner,
+            host = %holder.host,
+            pid = holder.pid,
+            "vault lock broken by user"
+        );
+    }
+    Ok(holder)
+}
+
+fn create_lock_file(lock_path: &Path) -> Result<()> {
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    let now = unix_now();
+    let info = VaultLockInfo {
+        owner_id: process_owner_id().to_string(),
+        owner: OWNER_NAME
+            .get()
+            .cloned()
+            .unwrap_or_else(|| "mdit".to_string()),
+        host: host_name(),
+        pid: std::process::id(),
+        acquired_at: now,
+        heartbeat_at: now,
+    };
+    let contents = serde_json::to_vec_pretty(&info)?;
+
+    // Later attempts follow the takeover of a stale lock.
+    for _ in 0..3 {
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(lock_path)
+        {
+            Ok(mut file) => {
+                file.write_all(&contents)
+                    .with_context(|| format!("Failed to write lock {}", lock_path.display()))?;
+                return Ok(());
+            }
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {
+                let holder = read_lock(lock_path);
+                match &holder {
+                    Some(holder) if !holder.is_stale(now) => {
+                        return Err(VaultLockedError {
+                            holder: holder.clone(),
+                        }
+                        .into());
+                    }
+                    Some(holder) => {
+                        tracing::warn!(
+                            owner = %holder.owner,
+                            host = %holder.host,
+                            pid = holder.pid,
+                            "taking over stale vault lock"
+                        );
+                    }
+                    // Unreadable while its holder is still writing it.
+                    None if modified_recently(lock_path) => break,
+                    None => {}
+                }
+                remove_stale_lock(lock_path, holder.as_ref())?;
+            }
+            Err(error) => {
+                return Err(error)
+                    .with_context(|| format!("Failed to create lock {}", lock_path.display()))
+            }
+        }
+    }
+
+    match read_lock(lock_path) {
+        Some(holder) => Err(VaultLockedError { holder }.into()),
+        None => Err(anyhow::anyhow!(
+            "Vault index lock {} is being taken by another process",
+            lock_path.display()
+        )),
+    }
+}
+
+/// Removes the lock at `lock_path` if it is still the stale `expected` one.
+/// The lock is first renamed aside, which only one process can do, so two
+/// writers taking over the same stale lock cannot remove each other's fresh
+/// one. A lock that turns out to have been replaced is put back.
+fn remove_stale_lock(lock_path: &Path, expected: Option<&VaultLockInfo>) -> Result<()> {
+    let claim_path = sibling_path(lock_path, "claim");
+    match fs::rename(lock_path, &claim_path) {
+        Ok(()) => {}
+        // Another writer claimed it first.
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => {
+            return Err(error)
+                .with_context(|| format!("Failed to take over lock {}", lock_path.display()))
+        }
+    }
+
+    if read_lock(&claim_path).as_ref() != expected {
+        // A fresh lock only goes back if nobody created one meanwhile.
+        if let Err(error) = fs::hard_link(&claim_path, lock_path) {
+            tracing::warn!(
+                "failed to restore vault lock {}: {error}",
+                lock_path.display()
+            );
+        }
+    }
+    let _ = fs::remove_file(&claim_path);
+    Ok(())
+}
+
+/// Replaces the lock's contents in one step, so readers never see it half
+/// written and mistake it for a stale or missing lock.
+fn write_lock_atomically(lock_path: &Path, info: &VaultLockInfo) -> Result<()> {
+    let temp_path = sibling_path(lock_path, "tmp");
+    let contents = serde_json::to_vec_pretty(info)?;
+    fs::write(&temp_path, contents)
+        .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    if let Err(error) = fs::rename(&temp_path, lock_path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(error)
+            .with_context(|| format!("Failed to replace lock {}", lock_path.display()));
+    }
+    Ok(())
+}
+
+/// A file next to the lock that belongs to this process alone.
+fn sibling_path(lock_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = lock_path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.{suffix}", std::process::id()));
+    lock_path.with_file_name(name)
+}
+
+fn spawn_heartbeat(lock_path: PathBuf, stop: Arc<AtomicBool>) -> Result<thread::JoinHandle<()>> {
+    thread::Builder::new()
+        .name("mdit-vault-lock".to_string())
+        .spawn(move || loop {
+            thread::park_timeout(HEARTBEAT_INTERVAL);
+            if stop.load(Ordering::Relaxed) {
+                return;
+            }
+            let Some(mut info) = read_lock(&lock_path) else {
+                tracing::warn!("vault lock {} was removed", lock_path.display());
+                return;
+            };
+            if info.owner_id != process_owner_id() {
+                tracing::warn!(
+                    owner = %info.owner,
+                    "vault lock {} was taken over",
+                    lock_path.display()
+                );
+                return;
+            }
+            info.heartbeat_at = unix_now();
+            if let Err(error) = write_lock_atomically(&lock_path, &info) {
+                tracing::warn!("failed to refresh vault lock: {error:#}");
+            }
+        })
+        .context("Failed to start vault lock heartbeat")
+}
+
+fn modified_recently(lock_path: &Path) -> bool {
+    fs::metadata(lock_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|elapsed| elapsed < VAULT_LOCK_STALE_AFTER)
+}
+
+fn read_lock(lock_path: &Path) -> Option<VaultLockInfo> {
+    let contents = fs::read(lock_path).ok()?;
+    serde_json::from_slice(&contents).ok()
+}
+
+fn process_owner_id() -> &'static str {
+    static OWNER_ID: OnceLock<String> = OnceLock::new();
+    OWNER_ID.get_or_init(|| {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        format!("{}-{}-{started}", host_name(), std::process::id())
+    })
+}
+
+fn host_name() -> String {
+    ["HOSTNAME", "COMPUTERNAME", "HOST"]
+        .into_iter()
+        .find_map(|key| std::env::var(key).ok().filter(|value| !value.is_empty()))
+        .or_else(|| {
+            fs::read_to_string("/etc/hostname")
+                .ok()
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+        })
+        .unwrap_or_else(|| "unknown host".to_string())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::{
+        acquire_vault_lock, get_vault_lock, read_lock, remove_stale_lock, unix_now, VaultLockInfo,
+        VaultLockedError, VAULT_LOCK_PATH, VAULT_LOCK_STALE_AFTER,
+    };
+
+    fn write_foreign_lock(root: &std::path::Path, heartbeat_at: u64) {
+        let info = VaultLockInfo {
+            owner_id: "other-machine-1".to_string(),
+            owner: "mdit cli".to_string(),
+            host: "other-machine".to_string(),
+            pid: 1,
+            acquired_at: heartbeat_at,
+            heartbeat_at,
+        };
+        fs::create_dir_all(root.join(".mdit")).expect("state dir should be created");
+        fs::write(
+            root.join(VAULT_LOCK_PATH),
+            serde_json::to_vec(&info).expect("lock should serialize"),
+        )
+        .expect("lock should be written");
+    }
+
+    #[test]
+    fn live_foreign_lock_blocks_and_stale_lock_is_taken_over() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should move forward")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-vault-lock-{nanos}"));
+
+        write_foreign_lock(&root, unix_now());
+        let error = acquire_vault_lock(&root).expect_err("live lock should block");
+        let locked = error
+            .downcast_ref::<VaultLockedError>()
+            .expect("error should name the holder");
+        assert_eq!(locked.holder.owner, "mdit cli");
+
+        write_foreign_lock(&root, unix_now() - VAULT_LOCK_STALE_AFTER.as_secs() - 1);
+        {
+            let _first = acquire_vault_lock(&root).expect("stale lock should be taken over");
+            let _second = acquire_vault_lock(&root).expect("lock is shared within a process");
+            let holder = get_vault_lock(&root).expect("lock should be held");
+            assert_eq!(holder.pid, std::process::id());
+        }
+        assert_eq!(get_vault_lock(&root), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn late_takeover_of_a_stale_lock_keeps_the_fresh_one() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should move forward")
+            .as_nanos();
+        let root = std::env::temp_dir().join(format!("mdit-vault-lock-race-{nanos}"));
+        let lock_path = root.join(VAULT_LOCK_PATH);
+
+        write_foreign_lock(&root, unix_now() - VAULT_LOCK_STALE_AFTER.as_secs() - 1);
+        let stale = read_lock(&lock_path).expect("stale lock should be readable");
+        // Another writer took the stale lock over first.
+        write_foreign_lock(&root, unix_now());
+        let fresh = read_lock(&lock_path).expect("fresh lock should be readable");
+
+        remove_stale_lock(&lock_path, Some(&stale)).expect("takeover should not fail");
+        assert_eq!(read_lock(&lock_path), Some(fresh));
+        assert!(acquire_vault_lock(&root).is_err());
+
+        remove_stale_lock(&lock_path, read_lock(&lock_path).as_ref())
+            .expect("matching lock should be removed");
+        assert_eq!(read_lock(&lock_path), None);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -63,6 +63,8 @@ fn normalize_rel_path(path: &Path) -> Option<String> {
         return None;
     }
 
+    // Kept as named on disk, since callers join it back onto the vault for
+    // file access. The index composes it to NFC for its keys.
     Some(parts.join("/"))
 }
 
@@ -106,6 +108,16 @@ mod tests {
         assert_eq!(to_vault_rel_path(root, path).as_deref(), Some("a/b.md"));
     }
 
+    #[test]
+    fn keeps_decomposed_file_names_as_on_disk() {
+        let root = Path::new("/vault");
+        let path = Path::new("/vault/Cafe\u{301}/Re\u{301}sume\u{301}.md");
+        assert_eq!(
+            to_vault_rel_path(root, path).as_deref(),
+            Some("Cafe\u{301}/Re\u{301}sume\u{301}.md")
+        );
+    }
+
     #[test]
     fn rejects_parent_traversal() {
         let root = Path::new("/vault");
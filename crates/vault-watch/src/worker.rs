@@ -41,8 +41,8 @@ pub(crate) fn spawn_worker(
             match collect_entry_index(&vault_root) {
                 Ok(index) => (index, false),
                 Err(error) => {
-                    eprintln!(
-                        "vault-watch: failed to bootstrap entry index for {}: {error}",
+                    tracing::error!(
+                        "failed to bootstrap entry index for {}: {error}",
                         vault_root.display()
                     );
                     (Default::default(), true)
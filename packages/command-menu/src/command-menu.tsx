@@ -17,11 +17,15 @@ import { getTagOnlySearchQuery } from "./tag-query"
 import type {
 	CommandMenuContentSearch,
 	CommandMenuEntry,
+	CommandMenuNoteAliasLoader,
+	CommandMenuPinnedNoteLoader,
 	CommandMenuSemanticSearch,
 	CommandMenuTagSearch,
 } from "./types"
+import { useNoteAliases } from "./use-note-aliases"
 import { useNoteContentSearch } from "./use-note-content-search"
 import { useNoteNameSearch } from "./use-note-name-search"
+import { usePinnedNotes } from "./use-pinned-notes"
 import { useSemanticSearch } from "./use-semantic-search"
 import { useTagSearch } from "./use-tag-search"
 
@@ -35,6 +39,8 @@ export type CommandMenuProps = {
 	searchContent?: CommandMenuContentSearch
 	searchSemantic?: CommandMenuSemanticSearch
 	searchTags?: CommandMenuTagSearch
+	loadNoteAliases?: CommandMenuNoteAliasLoader
+	loadPinnedNotes?: CommandMenuPinnedNoteLoader
 }
 
 export function CommandMenu({
@@ -47,6 +53,8 @@ export function CommandMenu({
 	searchContent,
 	searchSemantic,
 	searchTags,
+	loadNoteAliases,
+	loadPinnedNotes,
 }: CommandMenuProps) {
 	const [query, setQuery] = useState(initialQuery ?? "")
 	const [isInitialMeasureDebounced, setIsInitialMeasureDebounced] =
@@ -55,10 +63,14 @@ export function CommandMenu({
 	const debouncedQuery = useDebounce(query, 250)
 	const activeTagQuery = getTagOnlySearchQuery(query)
 	const debouncedTagQuery = getTagOnlySearchQuery(debouncedQuery)
+	const noteAliases = useNoteAliases(open, workspacePath, loadNoteAliases)
+	const pinnedNotes = usePinnedNotes(open, workspacePath, loadPinnedNotes)
 	const { filteredNoteResults, noteResultsByPath } = useNoteNameSearch(
 		entries,
 		workspacePath,
 		activeTagQuery ? "" : deferredQuery,
+		noteAliases,
+		pinnedNotes,
 	)
 	const { trimmedSearchTerm, contentMatchesByNote } = useNoteContentSearch(
 		activeTagQuery ? "" : debouncedQuery,
@@ -4,6 +4,11 @@ export type {
 	CommandMenuContentMatch,
 	CommandMenuContentSearch,
 	CommandMenuEntry,
+	CommandMenuMatchedPassage,
+	CommandMenuNoteAliases,
+	CommandMenuNoteAliasLoader,
+	CommandMenuPinnedNoteLoader,
+	CommandMenuPinnedNotes,
 	CommandMenuSemanticResult,
 	CommandMenuSemanticSearch,
 	CommandMenuTagResult,
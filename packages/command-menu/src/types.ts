@@ -12,12 +12,20 @@ export type CommandMenuContentMatch = {
 	lineText: string
 }
 
+export type CommandMenuMatchedPassage = {
+	startOffset: number
+	endOffset: number
+	headingPath: string[]
+	codeLanguage?: string | null
+}
+
 export type CommandMenuSemanticResult = {
 	path: string
 	name: string
 	similarity: number
 	createdAt?: Date
 	modifiedAt?: Date
+	passage?: CommandMenuMatchedPassage
 }
 
 export type CommandMenuTagResult = {
@@ -40,3 +48,17 @@ export type CommandMenuTagSearch = (
 	query: string,
 	workspacePath: string,
 ) => Promise<CommandMenuTagResult[]>
+
+/** Frontmatter aliases keyed by workspace-relative note path. */
+export type CommandMenuNoteAliases = Record<string, string[]>
+
+export type CommandMenuNoteAliasLoader = (
+	workspacePath: string,
+) => Promise<CommandMenuNoteAliases>
+
+/** Workspace-relative paths of pinned notes, in pin order. */
+export type CommandMenuPinnedNotes = string[]
+
+export type CommandMenuPinnedNoteLoader = (
+	workspacePath: string,
+) => Promise<CommandMenuPinnedNotes>
@@ -0,0 +1,47 @@
+import { useEffect, useRef, useState } from "react"
+import type {
+	CommandMenuNoteAliases,
+	CommandMenuNoteAliasLoader,
+} from "./types"
+
+const EMPTY_NOTE_ALIASES: CommandMenuNoteAliases = {}
+
+export const useNoteAliases = (
+	open: boolean,
+	workspacePath: string | null,
+	loadNoteAliases?: CommandMenuNoteAliasLoader,
+) => {
+	const [noteAliases, setNoteAliases] =
+		useState<CommandMenuNoteAliases>(EMPTY_NOTE_ALIASES)
+	const requestIdRef = useRef(0)
+
+	useEffect(() => {
+		const requestId = requestIdRef.current + 1
+		requestIdRef.current = requestId
+
+		if (!loadNoteAliases || !workspacePath) {
+			setNoteAliases(EMPTY_NOTE_ALIASES)
+			return
+		}
+
+		// Aliases change with note edits, so they are reloaded on every open.
+		if (!open) {
+			return
+		}
+
+		loadNoteAliases(workspacePath)
+			.then((nextAliases) => {
+				if (requestIdRef.current === requestId) {
+					setNoteAliases(nextAliases)
+				}
+			})
+			.catch((error) => {
+				if (requestIdRef.current === requestId) {
+					console.error("Failed to load note aliases:", error)
+					setNoteAliases(EMPTY_NOTE_ALIASES)
+				}
+			})
+	}, [loadNoteAliases, open, workspacePath])
+
+	return noteAliases
+}
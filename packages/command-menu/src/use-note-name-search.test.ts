@@ -86,4 +86,58 @@ describe("filterNoteResults", () => {
 			filterNoteResults(noteResults, "alp").map((note) => note.path),
 		).toEqual(["/ws/Alpha.md"])
 	})
+
+	it("matches notes by frontmatter alias", () => {
+		const noteResults = collectMarkdownNotes(
+			[
+				{
+					path: "/ws/projects/alpha.md",
+					name: "alpha.md",
+					isDirectory: false,
+				},
+				{ path: "/ws/beta.md", name: "beta.md", isDirectory: false },
+			],
+			"/ws",
+			{ "projects/alpha.md": ["Project Apollo"] },
+		)
+
+		expect(
+			filterNoteResults(noteResults, "apollo").map((note) => note.path),
+		).toEqual(["/ws/projects/alpha.md"])
+	})
+
+	it("ranks pinned notes first in pin order", () => {
+		const noteResults = collectMarkdownNotes(
+			[
+				{
+					path: "/ws/plan-a.md",
+					name: "plan-a.md",
+					isDirectory: false,
+					modifiedAt: new Date(2026, 0, 3),
+				},
+				{
+					path: "/ws/plan-b.md",
+					name: "plan-b.md",
+					isDirectory: false,
+					modifiedAt: new Date(2026, 0, 2),
+				},
+				{
+					path: "/ws/old/plan-c.md",
+					name: "plan-c.md",
+					isDirectory: false,
+					modifiedAt: new Date(2026, 0, 1),
+				},
+			],
+			"/ws",
+			undefined,
+			["plan-b.md", "old/plan-c.md"],
+		)
+
+		expect(
+			filterNoteResults(noteResults, "plan").map((note) => note.path),
+		).toEqual(["/ws/plan-b.md", "/ws/old/plan-c.md", "/ws/plan-a.md"])
+		expect(filterNoteResults(noteResults, "").map((note) => note.path)).toEqual(
+			["/ws/plan-b.md", "/ws/old/plan-c.md", "/ws/plan-a.md"],
+		)
+	})
 })
@@ -1,6 +1,10 @@
 import { useMemo } from "react"
 import { stripMarkdownExtension } from "./path-utils"
-import type { CommandMenuEntry } from "./types"
+import type {
+	CommandMenuEntry,
+	CommandMenuNoteAliases,
+	CommandMenuPinnedNotes,
+} from "./types"
 
 export type NoteResult = {
 	path: string
@@ -8,7 +12,10 @@ export type NoteResult = {
 	normalizedLabel: string
 	relativePath: string
 	keywords: string[]
+	aliases?: string[]
 	modifiedAt?: Date
+	// Position among the vault's pinned notes; unset for unpinned notes.
+	pinnedRank?: number
 }
 
 const MARKDOWN_EXTENSION_REGEX = /\.md$/i
@@ -44,26 +51,38 @@ export const toRelativePath = (
 const createNoteResult = (
 	entry: CommandMenuEntry,
 	workspacePath: string | null,
+	noteAliases?: CommandMenuNoteAliases,
+	pinnedRanks?: Map<string, number>,
 ): NoteResult => {
 	const label = stripMarkdownExtension(entry.name).trim() || entry.name
 	const relativePath = toRelativePath(entry.path, workspacePath)
+	// The index keys aliases and pins by forward-slash paths on every platform.
+	const storageKey = relativePath.replace(/\\/g, "/")
+	const aliases = noteAliases?.[storageKey] ?? []
 
 	return {
 		path: entry.path,
 		label,
 		normalizedLabel: label.toLowerCase(),
 		relativePath,
-		keywords: [label],
+		keywords: [label, ...aliases],
+		aliases: aliases.length > 0 ? aliases : undefined,
 		modifiedAt: entry.modifiedAt,
+		pinnedRank: pinnedRanks?.get(storageKey),
 	}
 }
 
 export const collectMarkdownNotes = (
 	entries: CommandMenuEntry[],
 	workspacePath: string | null,
+	noteAliases?: CommandMenuNoteAliases,
+	pinnedNotes?: CommandMenuPinnedNotes,
 ) => {
 	const results: NoteResult[] = []
 	const stack = [...entries]
+	const pinnedRanks = new Map(
+		pinnedNotes?.map((relPath, index) => [relPath, index]),
+	)
 
 	while (stack.length > 0) {
 		const node = stack.pop()
@@ -72,7 +91,9 @@ export const collectMarkdownNotes = (
 		}
 
 		if (isMarkdownFile(node)) {
-			results.push(createNoteResult(node, workspacePath))
+			results.push(
+				createNoteResult(node, workspacePath, noteAliases, pinnedRanks),
+			)
 		}
 
 		if (node.children?.length) {
@@ -83,12 +104,28 @@ export const collectMarkdownNotes = (
 	return results
 }
 
+const comparePinnedRank = (a: NoteResult, b: NoteResult) => {
+	if (a.pinnedRank === undefined || b.pinnedRank === undefined) {
+		if (a.pinnedRank === b.pinnedRank) {
+			return 0
+		}
+		return a.pinnedRank === undefined ? 1 : -1
+	}
+	return a.pinnedRank - b.pinnedRank
+}
+
+// Pinned notes come first in pin order, followed by the most recent others.
 const takeRecentNotes = (noteResults: NoteResult[]) => {
-	return [...noteResults]
+	const pinnedNotes = noteResults
+		.filter((note) => note.pinnedRank !== undefined)
+		.sort(comparePinnedRank)
+	const recentNotes = noteResults
+		.filter((note) => note.pinnedRank === undefined)
 		.sort(
 			(a, b) => (b.modifiedAt?.getTime() ?? 0) - (a.modifiedAt?.getTime() ?? 0),
 		)
 		.slice(0, RECENT_NOTES_LIMIT)
+	return [...pinnedNotes, ...recentNotes]
 }
 
 export const filterNoteResults = (noteResults: NoteResult[], query: string) => {
@@ -98,19 +135,29 @@ export const filterNoteResults = (noteResults: NoteResult[], query: string) => {
 		return takeRecentNotes(noteResults)
 	}
 
-	return noteResults.filter((note) =>
-		note.normalizedLabel.includes(normalizedQuery),
-	)
+	// Sorting is stable, so matches keep their order within pinned and unpinned.
+	return noteResults
+		.filter(
+			(note) =>
+				note.normalizedLabel.includes(normalizedQuery) ||
+				note.aliases?.some((alias) =>
+					alias.toLowerCase().includes(normalizedQuery),
+				),
+		)
+		.sort(comparePinnedRank)
 }
 
 export const useNoteNameSearch = (
 	entries: CommandMenuEntry[],
 	workspacePath: string | null,
 	query: string,
+	noteAliases?: CommandMenuNoteAliases,
+	pinnedNotes?: CommandMenuPinnedNotes,
 ) => {
 	const noteResults = useMemo(
-		() => collectMarkdownNotes(entries, workspacePath),
-		[entries, workspacePath],
+		() =>
+			collectMarkdownNotes(entries, workspacePath, noteAliases, pinnedNotes),
+		[entries, workspacePath, noteAliases, pinnedNotes],
 	)
 
 	const noteResultsByPath = useMemo(() => {
@@ -0,0 +1,47 @@
+import { useEffect, useRef, useState } from "react"
+import type {
+	CommandMenuPinnedNoteLoader,
+	CommandMenuPinnedNotes,
+} from "./types"
+
+const EMPTY_PINNED_NOTES: CommandMenuPinnedNotes = []
+
+export const usePinnedNotes = (
+	open: boolean,
+	workspacePath: string | null,
+	loadPinnedNotes?: CommandMenuPinnedNoteLoader,
+) => {
+	const [pinnedNotes, setPinnedNotes] =
+		useState<CommandMenuPinnedNotes>(EMPTY_PINNED_NOTES)
+	const requestIdRef = useRef(0)
+
+	useEffect(() => {
+		const requestId = requestIdRef.current + 1
+		requestIdRef.current = requestId
+
+		if (!loadPinnedNotes || !workspacePath) {
+			setPinnedNotes(EMPTY_PINNED_NOTES)
+			return
+		}
+
+		// Pins can change in any window, so they are reloaded on every open.
+		if (!open) {
+			return
+		}
+
+		loadPinnedNotes(workspacePath)
+			.then((nextPinnedNotes) => {
+				if (requestIdRef.current === requestId) {
+					setPinnedNotes(nextPinnedNotes)
+				}
+			})
+			.catch((error) => {
+				if (requestIdRef.current === requestId) {
+					console.error("Failed to load pinned notes:", error)
+					setPinnedNotes(EMPTY_PINNED_NOTES)
+				}
+			})
+	}, [loadPinnedNotes, open, workspacePath])
+
+	return pinnedNotes
+}
@@ -31,6 +31,7 @@ export type ResolveWikiLinkResult = {
 	matchCount: number
 	disambiguated: boolean
 	unresolved: boolean
+	resolvedViaAlias?: boolean
 }
 
 export type ResolveWikiLinkParams = {
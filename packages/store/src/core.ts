@@ -35,6 +35,7 @@ export type {
 	AppHotkeyCategory,
 	AppHotkeyDefinition,
 	AppHotkeyMap,
+	GlobalAppHotkeyActionId,
 } from "./hotkeys/hotkey-utils"
 export {
 	APP_HOTKEY_CATEGORY_LABELS,
@@ -45,6 +46,7 @@ export {
 	hotkeyToDisplayTokens,
 	hotkeyToMenuAccelerator,
 	isAppHotkeyActionId,
+	isGlobalAppHotkeyActionId,
 	isReservedAppHotkeyBinding,
 	mergeWithDefaultHotkeys,
 	normalizeHotkeyBinding,
@@ -69,6 +71,12 @@ export { prepareIndexingSlice } from "./indexing/indexing-slice"
 export type {
 	IndexingConfig,
 	IndexingMeta,
+	IndexRunStatus,
+	NoteIndexStatus,
+	SkipErrorCode,
+	SkippedFile,
+	VaultLockInfo,
+	VectorSupport,
 	WorkspaceIndexSummary,
 } from "./indexing/indexing-types"
 export type {
@@ -101,6 +109,7 @@ export type {
 export { VAULT_WATCH_BATCH_EVENT } from "./workspace/watch/types"
 export type {
 	BacklinkEntry,
+	BacklinkMention,
 	FileSystemDirectoryEntry,
 	FileSystemInfo,
 	FrontmatterUtils,
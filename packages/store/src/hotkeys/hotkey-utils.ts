@@ -18,6 +18,10 @@ export type AppHotkeyActionId =
 	| "toggle-settings"
 	| "toggle-note-info"
 	| "toggle-chat-panel"
+	| "quick-capture"
+
+// Registered with the OS so they work while Mdit is in the background.
+export type GlobalAppHotkeyActionId = "quick-capture"
 
 export type AppHotkeyCategory = "file" | "view" | "history" | "app"
 
@@ -26,6 +30,7 @@ export type AppHotkeyDefinition = {
 	label: string
 	category: AppHotkeyCategory
 	defaultBinding: string
+	global?: boolean
 }
 
 export type AppHotkeyMap = Record<AppHotkeyActionId, string>
@@ -135,6 +140,13 @@ export const APP_HOTKEY_DEFINITIONS: readonly AppHotkeyDefinition[] = [
 		category: "view",
 		defaultBinding: "Mod+Shift+L",
 	},
+	{
+		id: "quick-capture",
+		label: "Quick Capture",
+		category: "app",
+		defaultBinding: "Mod+Alt+Space",
+		global: true,
+	},
 ] as const
 
 const APP_HOTKEY_ACTION_ID_SET = new Set<AppHotkeyActionId>(
@@ -182,6 +194,14 @@ export function isAppHotkeyActionId(
 	)
 }
 
+export function isGlobalAppHotkeyActionId(
+	value: AppHotkeyActionId,
+): value is GlobalAppHotkeyActionId {
+	return APP_HOTKEY_DEFINITIONS.some(
+		(definition) => definition.id === value && definition.global === true,
+	)
+}
+
 export function normalizeHotkeyBinding(binding: string): string {
 	const trimmed = binding.trim()
 	if (!trimmed) {
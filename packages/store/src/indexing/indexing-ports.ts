@@ -13,4 +13,5 @@ export type IndexingPort = {
 	) => Promise<void>
 	indexVaultDocuments: (forceReindex: boolean) => Promise<WorkspaceIndexSummary>
 	refreshWorkspaceEmbeddings: () => Promise<WorkspaceIndexSummary>
+	indexNote: (notePath: string) => Promise<WorkspaceIndexSummary>
 }
@@ -25,6 +25,7 @@ function createIndexingStore({
 				setIndexingConfig: vi.fn().mockResolvedValue(undefined),
 				indexVaultDocuments: vi.fn().mockResolvedValue({}),
 				refreshWorkspaceEmbeddings: vi.fn().mockResolvedValue({}),
+				indexNote: vi.fn().mockResolvedValue({}),
 			})),
 	})
 
@@ -50,6 +51,7 @@ describe("indexing-slice config", () => {
 					setIndexingConfig: vi.fn().mockResolvedValue(undefined),
 					indexVaultDocuments: vi.fn().mockResolvedValue({}),
 					refreshWorkspaceEmbeddings: vi.fn().mockResolvedValue({}),
+					indexNote: vi.fn().mockResolvedValue({}),
 				}) satisfies IndexingPort,
 		})
 
@@ -76,6 +78,7 @@ describe("indexing-slice config", () => {
 					setIndexingConfig,
 					indexVaultDocuments: vi.fn().mockResolvedValue({}),
 					refreshWorkspaceEmbeddings: vi.fn().mockResolvedValue({}),
+					indexNote: vi.fn().mockResolvedValue({}),
 				}) satisfies IndexingPort,
 		})
 
@@ -111,6 +114,7 @@ describe("indexing-slice config", () => {
 					setIndexingConfig: vi.fn().mockResolvedValue(undefined),
 					indexVaultDocuments: vi.fn().mockResolvedValue({}),
 					refreshWorkspaceEmbeddings: vi.fn().mockResolvedValue({}),
+					indexNote: vi.fn().mockResolvedValue({}),
 				}) satisfies IndexingPort,
 		})
 
@@ -140,6 +144,7 @@ describe("indexing-slice indexVaultDocuments", () => {
 					setIndexingConfig: vi.fn().mockResolvedValue(undefined),
 					indexVaultDocuments,
 					refreshWorkspaceEmbeddings: vi.fn().mockResolvedValue({}),
+					indexNote: vi.fn().mockResolvedValue({}),
 				}) satisfies IndexingPort,
 		})
 
@@ -159,6 +164,7 @@ describe("indexing-slice indexVaultDocuments", () => {
 					setIndexingConfig: vi.fn().mockResolvedValue(undefined),
 					indexVaultDocuments: vi.fn().mockResolvedValue({}),
 					refreshWorkspaceEmbeddings,
+					indexNote: vi.fn().mockResolvedValue({}),
 				}) satisfies IndexingPort,
 		})
 
@@ -168,6 +174,54 @@ describe("indexing-slice indexVaultDocuments", () => {
 		expect(store.getState().isIndexing).toBe(false)
 	})
 
+	it("retries only retryable skipped files and keeps what still fails", async () => {
+		const placeholder = {
+			path: "/ws/cloud.md",
+			error_code: "cloud_placeholder" as const,
+			message: "Cloud placeholder not downloaded yet; indexing deferred",
+			retryable: true,
+		}
+		const denied = {
+			path: "/ws/private.md",
+			error_code: "permission_denied" as const,
+			message: "Failed to read file /ws/private.md",
+			retryable: false,
+		}
+		const flaky = {
+			path: "/ws/flaky.md",
+			error_code: "io" as const,
+			message: "Failed to read file /ws/flaky.md",
+			retryable: true,
+		}
+		const indexNote = vi.fn(async (notePath: string) => ({
+			skipped_files: notePath === flaky.path ? [flaky] : [],
+		}))
+		const { store } = createIndexingStore({
+			createIndexingPort: () =>
+				({
+					getIndexingMeta: vi.fn().mockResolvedValue({ indexedDocCount: 0 }),
+					getIndexingConfig: vi.fn().mockResolvedValue(null),
+					setIndexingConfig: vi.fn().mockResolvedValue(undefined),
+					indexVaultDocuments: vi.fn().mockResolvedValue({
+						skipped_files: [placeholder, denied, flaky],
+					}),
+					refreshWorkspaceEmbeddings: vi.fn().mockResolvedValue({}),
+					indexNote,
+				}) as unknown as IndexingPort,
+		})
+
+		await store.getState().indexVaultDocuments("/ws", false)
+		expect(store.getState().skippedFiles).toHaveLength(3)
+
+		const remaining = await store.getState().retrySkippedFiles("/ws")
+
+		expect(indexNote).toHaveBeenCalledTimes(2)
+		expect(indexNote).not.toHaveBeenCalledWith(denied.path)
+		expect(remaining).toEqual([denied, flaky])
+		expect(store.getState().skippedFiles).toEqual([denied, flaky])
+		expect(store.getState().isIndexing).toBe(false)
+	})
+
 	it("resetIndexingState clears current workspace state", () => {
 		const { store } = createIndexingStore()
 
@@ -1,6 +1,11 @@
 import type { StateCreator } from "zustand"
 import type { IndexingPort } from "./indexing-ports"
-import type { IndexingConfig, WorkspaceIndexSummary } from "./indexing-types"
+import type {
+	IndexingConfig,
+	SkippedFile,
+	VaultLockInfo,
+	WorkspaceIndexSummary,
+} from "./indexing-types"
 
 type EmbeddingModelsState = {
 	ollamaEmbeddingModels: string[]
@@ -12,6 +17,12 @@ export type IndexingSlice = {
 	isIndexing: boolean
 	indexedDocCount: number
 	isMetaLoading: boolean
+	// Set when vector search could not load and search fell back to keywords.
+	vectorSearchError: string | null
+	// Another process holds the vault's index lock, so indexing here fails.
+	indexLock: VaultLockInfo | null
+	// Files the last run could not index.
+	skippedFiles: SkippedFile[]
 
 	// Existing actions
 	resetIndexingState: () => void
@@ -30,6 +41,8 @@ export type IndexingSlice = {
 	refreshWorkspaceEmbeddings: (
 		workspacePath: string,
 	) => Promise<WorkspaceIndexSummary>
+	// Indexes the retryable skipped files again, one note at a time.
+	retrySkippedFiles: (workspacePath: string) => Promise<SkippedFile[]>
 
 	loadIndexingMeta: (workspacePath: string) => Promise<void>
 }
@@ -59,6 +72,9 @@ const buildInitialIndexingState = () => ({
 	isIndexing: false,
 	indexedDocCount: 0,
 	isMetaLoading: false,
+	vectorSearchError: null,
+	indexLock: null,
+	skippedFiles: [],
 })
 
 export const prepareIndexingSlice = ({
@@ -148,14 +164,49 @@ export const prepareIndexingSlice = ({
 			},
 
 			indexVaultDocuments: (workspacePath: string, forceReindex: boolean) =>
-				runExclusiveIndexingTask(workspacePath, (indexingPort) =>
-					indexingPort.indexVaultDocuments(forceReindex),
-				),
+				runExclusiveIndexingTask(workspacePath, async (indexingPort) => {
+					const sessionId = workspaceSessionId
+					const summary = await indexingPort.indexVaultDocuments(forceReindex)
+					if (isSessionActive(sessionId)) {
+						set({ skippedFiles: summary.skipped_files ?? [] })
+					}
+					return summary
+				}),
 
 			refreshWorkspaceEmbeddings: (workspacePath: string) =>
-				runExclusiveIndexingTask(workspacePath, (indexingPort) =>
-					indexingPort.refreshWorkspaceEmbeddings(),
-				),
+				runExclusiveIndexingTask(workspacePath, async (indexingPort) => {
+					const sessionId = workspaceSessionId
+					const summary = await indexingPort.refreshWorkspaceEmbeddings()
+					if (isSessionActive(sessionId)) {
+						set({ skippedFiles: summary.skipped_files ?? [] })
+					}
+					return summary
+				}),
+
+			retrySkippedFiles: (workspacePath: string) =>
+				runExclusiveIndexingTask(workspacePath, async (indexingPort) => {
+					const sessionId = workspaceSessionId
+					const remaining: SkippedFile[] = []
+					for (const file of get().skippedFiles) {
+						if (!file.retryable) {
+							remaining.push(file)
+							continue
+						}
+						try {
+							const summary = await indexingPort.indexNote(file.path)
+							remaining.push(...(summary.skipped_files ?? []))
+						} catch (error) {
+							remaining.push({
+								...file,
+								message: error instanceof Error ? error.message : String(error),
+							})
+						}
+					}
+					if (isSessionActive(sessionId)) {
+						set({ skippedFiles: remaining })
+					}
+					return remaining
+				}),
 
 			loadIndexingMeta: async (workspacePath: string) => {
 				const sessionId = workspaceSessionId
@@ -168,7 +219,14 @@ export const prepareIndexingSlice = ({
 					const meta = await indexingPort.getIndexingMeta()
 
 					if (workspaceSessionId === sessionId) {
-						set({ indexedDocCount: meta.indexedDocCount ?? 0 })
+						set({
+							indexedDocCount: meta.indexedDocCount ?? 0,
+							vectorSearchError:
+								meta.vectorSearch?.available === false
+									? (meta.vectorSearch.error ?? "Vector search is unavailable")
+									: null,
+							indexLock: meta.indexLock ?? null,
+						})
 					}
 				} catch {
 					if (workspaceSessionId === sessionId) {
@@ -1,6 +1,27 @@
 export type IndexingConfig = {
 	embeddingProvider: string
 	embeddingModel: string
+	fallbackEmbeddingProvider?: string | null
+	fallbackEmbeddingModel?: string | null
+}
+
+export type SkipErrorCode =
+	| "permission_denied"
+	| "not_found"
+	| "cloud_placeholder"
+	| "invalid_content"
+	| "too_large"
+	| "io"
+	| "embedding"
+	| "index"
+
+// A file an index run left out; `retryable` files may index on another try
+// without being edited.
+export type SkippedFile = {
+	path: string
+	error_code: SkipErrorCode
+	message: string
+	retryable: boolean
 }
 
 export type WorkspaceIndexSummary = {
@@ -8,14 +29,61 @@ export type WorkspaceIndexSummary = {
 	files_processed: number
 	docs_inserted: number
 	docs_deleted: number
+	docs_revived: number
 	segments_created: number
 	segments_updated: number
 	embeddings_written: number
+	embeddings_cached: number
 	links_written: number
 	links_deleted: number
-	skipped_files: string[]
+	embeddings_pending: number
+	skipped_files: SkippedFile[]
+	// Oversized files whose head alone was indexed.
+	truncated_files: string[]
+	// Another run was active; it repeats once it finishes.
+	queued: boolean
+}
+
+export type IndexRunStatus = "idle" | "running" | "rerunQueued"
+
+// Whether sqlite-vec loaded; without it search uses keywords only.
+export type VectorSupport = {
+	available: boolean
+	version: string | null
+	error: string | null
+}
+
+// Another process writing the vault's index; times are Unix seconds.
+export type VaultLockInfo = {
+	ownerId: string
+	owner: string
+	host: string
+	pid: number
+	acquiredAt: number
+	heartbeatAt: number
+}
+
+export type NoteIndexStatus = {
+	relPath: string
+	indexed: boolean
+	indexedHash: string | null
+	currentHash: string | null
+	readError: string | null
+	upToDate: boolean
+	chunkingVersion: number | null
+	embeddingProvider: string | null
+	embeddingModel: string | null
+	embeddingDim: number | null
+	segmentCount: number
+	embeddedSegmentCount: number
+	linkCount: number
+	unresolvedLinkCount: number
+	pendingEmbeddingError: string | null
 }
 
 export type IndexingMeta = {
 	indexedDocCount: number
+	runStatus: IndexRunStatus
+	vectorSearch?: VectorSupport
+	indexLock?: VaultLockInfo | null
 }
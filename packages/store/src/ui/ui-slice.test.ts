@@ -87,6 +87,14 @@ describe("ui-slice local api settings", () => {
 		store.getState().setLocalApiError(null)
 		expect(store.getState().localApiError).toBeNull()
 	})
+
+	it("records the port the local API server bound", () => {
+		const { store } = createUISliceStore()
+		expect(store.getState().localApiPort).toBeNull()
+
+		store.getState().setLocalApiPort(39124)
+		expect(store.getState().localApiPort).toBe(39124)
+	})
 })
 
 describe("ui-slice note info panel", () => {
@@ -64,6 +64,8 @@ export type UISlice = {
 	setLocalApiEnabled: (enabled: boolean) => void
 	localApiError: string | null
 	setLocalApiError: (message: string | null) => void
+	localApiPort: number | null
+	setLocalApiPort: (port: number | null) => void
 	chatPanelBetaEnabled: boolean
 	setChatPanelBetaEnabled: (enabled: boolean) => void
 	isChatPanelOpen: boolean
@@ -184,6 +186,8 @@ export const prepareUISlice =
 		},
 		localApiError: null,
 		setLocalApiError: (message) => set({ localApiError: message }),
+		localApiPort: null,
+		setLocalApiPort: (port) => set({ localApiPort: port }),
 		chatPanelBetaEnabled: preferences.getChatPanelBetaEnabled(),
 		setChatPanelBetaEnabled: (enabled) => {
 			const nextEnabled = preferences.setChatPanelBetaEnabled(enabled)
@@ -78,9 +78,22 @@ export type ToastLike = {
 	error?: (...args: any[]) => any
 }
 
+export type BacklinkMention = {
+	// Byte range of the link in the source file.
+	start: number
+	end: number
+	// Zero-based line of the link.
+	line: number
+	// The link's line with one line on either side.
+	context: string
+	// Byte offset of context in the source file.
+	contextStart: number
+}
+
 export type BacklinkEntry = {
 	relPath: string
 	fileName: string
+	mentions: BacklinkMention[]
 }
 
 export type ResolveWikiLinkResult = {
@@ -89,6 +102,7 @@ export type ResolveWikiLinkResult = {
 	matchCount: number
 	disambiguated: boolean
 	unresolved: boolean
+	resolvedViaAlias?: boolean
 }
 
 export type LinkIndexingDependencies = {